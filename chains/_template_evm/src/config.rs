@@ -1,18 +1,36 @@
-use anyhow::Result;
+use anyhow::{bail, Context, Result};
 use config::{Config, File};
-use core_logic::config::{ProxyConfig, SpamConfig};
+use core_logic::config::ProxyConfig;
+use core_logic::templates::{EvmAction, SpammerConfig};
 use serde::Deserialize;
 
 #[derive(Debug, Deserialize)]
 pub struct EvmConfig {
     pub rpc_url: String,
     pub chain_id: u64,
+    #[allow(dead_code)]
     pub private_key_file: String, // Path to encrypted wallet file
     pub tps: u32,
+    /// "SelfTransfer", "Transfer", "Call" or "Deploy"
+    #[serde(default = "default_mode")]
+    pub mode: String,
+    /// Recipient of "Transfer" and "Call"
+    pub to: Option<String>,
+    /// Hex calldata of "Call", init code of "Deploy"
+    pub data: Option<String>,
+    #[serde(default)]
+    pub value_wei: u64,
+    /// Caps the fee per gas; the adapter's default applies when unset
+    pub max_fee_gwei: Option<f64>,
+    pub priority_fee_gwei: Option<f64>,
     #[allow(dead_code)]
     pub proxies: Option<Vec<ProxyConfig>>,
 }
 
+fn default_mode() -> String {
+    "SelfTransfer".to_string()
+}
+
 impl EvmConfig {
     pub fn load(path: &str) -> Result<Self> {
         let settings = Config::builder()
@@ -22,16 +40,42 @@ impl EvmConfig {
         settings.try_deserialize().map_err(|e| anyhow::anyhow!(e))
     }
 
-    pub fn to_spam_config(&self) -> SpamConfig {
-        SpamConfig {
+    /// The transaction each task sends, from `mode`, `to`, `data` and `value_wei`
+    pub fn action(&self) -> Result<EvmAction> {
+        let to = || -> Result<_> {
+            let to = self
+                .to
+                .as_deref()
+                .context("`to` is required by this mode")?;
+            to.parse()
+                .with_context(|| format!("invalid `to` address {}", to))
+        };
+        let data = || -> Result<_> {
+            let data = self.data.as_deref().unwrap_or_default();
+            hex::decode(data.trim_start_matches("0x"))
+                .map(Into::into)
+                .context("`data` is not hex")
+        };
+        let value = self.value_wei.try_into()?;
+
+        Ok(match self.mode.as_str() {
+            "SelfTransfer" => EvmAction::SelfTransfer,
+            "Transfer" => EvmAction::Transfer { to: to()?, value },
+            "Call" => EvmAction::Call {
+                to: to()?,
+                data: data()?,
+                value,
+            },
+            "Deploy" => EvmAction::Deploy { bytecode: data()? },
+            other => bail!("unknown mode {}", other),
+        })
+    }
+
+    pub fn spammer_config(&self) -> SpammerConfig {
+        SpammerConfig {
             rpc_url: self.rpc_url.clone(),
             chain_id: self.chain_id,
             target_tps: self.tps,
-            duration_seconds: None, // Infinite by default
-            wallet_source: core_logic::config::WalletSource::File {
-                path: self.private_key_file.clone(),
-                encrypted: true,
-            },
         }
     }
 }
@@ -3,7 +3,7 @@ mod spammer;
 
 use anyhow::Result;
 use clap::Parser;
-use core_logic::{setup_logger, WorkerRunner};
+use core_logic::{setup_logger, EvmChainAdapter, GasConfig, WorkerRunner};
 // use core_logic::security::SecurityUtils;
 use config::EvmConfig;
 use dotenv::dotenv;
@@ -48,19 +48,30 @@ async fn main() -> Result<()> {
         info!("Loaded {} proxies for rotation.", proxies.len());
     }
 
+    // Everything chain specific comes from the config
+    let action = config.action()?;
+    let mut gas_config = GasConfig::new();
+    if let Some(max_fee) = config.max_fee_gwei {
+        gas_config = gas_config.with_max_fee(max_fee);
+    }
+    if let Some(priority_fee) = config.priority_fee_gwei {
+        gas_config = gas_config.with_priority_fee(priority_fee);
+    }
+
     // Create spammers
     let mut spammers = Vec::new();
     for (i, key) in keys.iter().enumerate() {
-        let wallet = key.parse::<ethers::signers::LocalWallet>()?;
+        let adapter = EvmChainAdapter::new(config.spammer_config(), vec![config.rpc_url.clone()])
+            .with_gas_config(gas_config.clone())
+            .with_action(action.clone());
 
         // Assign proxy round-robin if available
-        let proxy_config = if !proxies.is_empty() {
-            Some(proxies[i % proxies.len()].clone())
-        } else {
-            None
-        };
+        let proxy = (!proxies.is_empty()).then(|| {
+            let index = i % proxies.len();
+            (index, &proxies[index])
+        });
 
-        let spammer = EvmSpammer::new_with_signer(config.to_spam_config(), wallet, proxy_config)?;
+        let spammer = EvmSpammer::new_with_adapter(adapter, key, i, proxy)?;
         spammers.push(Box::new(spammer) as Box<dyn core_logic::traits::Spammer>);
     }
 
@@ -1,70 +1,55 @@
 use anyhow::Result;
 use async_trait::async_trait;
-use core_logic::config::SpamConfig;
-use core_logic::traits::Spammer;
-use ethers::prelude::*;
-use tokio::time::{sleep, Duration};
-use tracing::info;
-// use std::sync::Arc;
-// use std::str::FromStr;
-use reqwest::Client;
+use core_logic::config::{ProxyConfig, SpamConfig};
+use core_logic::metrics::{MetricsCollector, TaskLabels};
+use core_logic::templates::{ChainSpammer, EvmChainAdapter};
+use core_logic::traits::{Spammer, SpammerStats};
+use tokio::time::{sleep, Duration, Instant};
+use tokio_util::sync::CancellationToken;
+use tracing::{info, warn};
 
+/// Runs the adapter's configured action in a loop for one wallet
 pub struct EvmSpammer {
-    config: SpamConfig,
-    #[allow(dead_code)]
-    provider: Provider<Http>,
-    wallet: LocalWallet,
+    adapter: EvmChainAdapter,
+    wallet_index: usize,
+    proxy_index: Option<usize>,
 }
 
 impl EvmSpammer {
-    pub fn new_with_signer(
-        config: SpamConfig,
-        signer: LocalWallet,
-        proxy_config: Option<core_logic::config::ProxyConfig>,
+    pub fn new_with_adapter(
+        adapter: EvmChainAdapter,
+        private_key: &str,
+        wallet_index: usize,
+        proxy: Option<(usize, &ProxyConfig)>,
     ) -> Result<Self> {
-        // Build reqwest client with proxy if needed
-        let mut client_builder = Client::builder();
-
-        if let Some(proxy_conf) = proxy_config {
-            let mut proxy = reqwest::Proxy::all(&proxy_conf.url)?;
-            if let (Some(u), Some(p)) = (&proxy_conf.username, &proxy_conf.password) {
-                proxy = proxy.basic_auth(u, p);
-            }
-            client_builder = client_builder.proxy(proxy);
+        let mut adapter = adapter
+            .with_signer(private_key)
+            .map_err(anyhow::Error::msg)?;
+        if let Some((_, proxy)) = proxy {
+            adapter = adapter.with_proxy(proxy).map_err(anyhow::Error::msg)?;
         }
 
-        let client = client_builder.build()?;
-
-        let provider = Provider::new(Http::new_with_client(
-            reqwest::Url::parse(&config.rpc_url)?,
-            client,
-        ));
-
         Ok(Self {
-            provider,
-            wallet: signer.with_chain_id(config.chain_id),
-            config,
+            adapter,
+            wallet_index,
+            proxy_index: proxy.map(|(index, _)| index),
         })
     }
 }
 
-use tokio_util::sync::CancellationToken;
-
-// ...
-
 #[async_trait]
 impl Spammer for EvmSpammer {
     async fn new(_config: SpamConfig) -> Result<Self> {
-        Err(anyhow::anyhow!("Use new_with_signer construction"))
+        Err(anyhow::anyhow!("Use new_with_adapter construction"))
     }
 
-    async fn start(
-        &self,
-        cancellation_token: CancellationToken,
-    ) -> Result<core_logic::traits::SpammerStats> {
-        info!("EVM Spammer started for chain {}", self.config.chain_id);
+    async fn start(&self, cancellation_token: CancellationToken) -> Result<SpammerStats> {
+        let config = self.adapter.config();
+        info!("EVM Spammer started for chain {}", config.chain_id);
 
-        let mut stats = core_logic::traits::SpammerStats::default();
+        let task = self.adapter.action().name();
+        let mut stats = SpammerStats::default();
+        let run_start = Instant::now();
 
         loop {
             if cancellation_token.is_cancelled() {
@@ -72,22 +57,37 @@ impl Spammer for EvmSpammer {
                 break;
             }
 
-            // Mock spam loop using ethers
-            let _tx = TransactionRequest::new()
-                .to(self.wallet.address()) // Self-spam
-                .value(0)
-                .chain_id(self.config.chain_id);
+            let start = Instant::now();
+            let outcome = self.adapter.execute_task().await;
+            let duration = start.elapsed();
+            let success = match outcome {
+                Ok(result) if result.success => {
+                    info!("{} {}", result.message, result.tx_hash.unwrap_or_default());
+                    stats.record_success(task, duration, result.gas_used);
+                    true
+                }
+                Ok(result) => {
+                    warn!("{} {}", result.message, result.tx_hash.unwrap_or_default());
+                    stats.record_failure(task, duration, &result.message);
+                    false
+                }
+                Err(e) => {
+                    warn!("{} failed: {}", task, e);
+                    stats.record_failure(task, duration, &e);
+                    false
+                }
+            };
 
-            // In real impl, use self.wallet.sign_transaction(&tx) and self.provider.send_raw_transaction...
-            info!(
-                "Sending EVM Tx to {} on chain {}",
-                self.wallet.address(),
-                self.config.chain_id
-            );
-            stats.success += 1; // Mock success
+            let labels = TaskLabels {
+                chain_id: Some(config.chain_id),
+                task,
+                wallet_index: Some(self.wallet_index),
+                proxy_index: self.proxy_index,
+            };
+            MetricsCollector::global().record_task_with(&labels, duration, success);
 
             // Rate limit
-            let sleep_ms = 1000 / self.config.target_tps.max(1) as u64;
+            let sleep_ms = 1000 / config.target_tps.max(1) as u64;
 
             tokio::select! {
                 _ = cancellation_token.cancelled() => {
@@ -97,6 +97,8 @@ impl Spammer for EvmSpammer {
                 _ = sleep(Duration::from_millis(sleep_ms)) => {}
             }
         }
+
+        stats.run_duration_ms = run_start.elapsed().as_millis() as u64;
         Ok(stats)
     }
 
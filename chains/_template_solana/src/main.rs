@@ -1,16 +1,20 @@
 mod config;
+mod sender;
 mod spammer;
+mod task;
 
 use anyhow::Result;
 use clap::Parser;
-use core_logic::utils::{setup_logger, WorkerRunner};
-use core_logic::security::SecurityUtils;
 use config::SolanaConfig;
-use spammer::SolanaSpammer;
-use tracing::{info, error};
+use core_logic::{setup_logger, ArbiterConfig, ChainShare, ProxyArbiter, WorkerRunner};
 use dotenv::dotenv;
-use std::env;
+use sender::SenderProxy;
 use solana_sdk::signature::Keypair;
+use spammer::SolanaSpammer;
+use tracing::{error, info};
+
+/// Name of the chain in the proxy arbiter
+const CHAIN: &str = "solana";
 
 #[derive(Parser, Debug)]
 #[command(author, version, about, long_about = None)]
@@ -21,9 +25,9 @@ struct Args {
 
 #[tokio::main]
 async fn main() -> Result<()> {
-    setup_logger();
+    let _log_guard = setup_logger();
     dotenv().ok();
-    
+
     let args = Args::parse();
     info!("Loading Solana config from: {}", args.config);
 
@@ -35,22 +39,37 @@ async fn main() -> Result<()> {
         }
     };
 
-    // Load decrypted wallets (EVM, SOL, SUI)
-    let password = env::var("WALLET_PASSWORD").ok();
-    let wallets = core_logic::utils::WalletManager::get_decrypted_wallets(password)?;
-    
-    info!("Loaded {} wallets.", wallets.len());
+    // Load wallets (EVM, SOL, SUI keys share one file)
+    let manager = core_logic::WalletManager::new()?;
+    let password = core_logic::wallet_password(None)?;
+    info!("Found {} wallets.", manager.count());
 
     // Load proxies via ProxyManager (Standardized)
-    let proxies = core_logic::utils::ProxyManager::load_proxies()?;
+    let proxies = core_logic::ProxyManager::load_proxies()?;
     if !proxies.is_empty() {
         info!("Loaded {} proxies for rotation.", proxies.len());
     }
+    // Bans proxies that keep failing against the RPC host
+    let health = ProxyArbiter::new(
+        proxies.len(),
+        &[ChainShare {
+            name: CHAIN.to_string(),
+            target: config.rpc_url.clone(),
+            quota: 1.0,
+        }],
+        ArbiterConfig::default(),
+    )
+    .chain(CHAIN);
+
+    let db = core_logic::database::DatabaseManager::new("solana.db").await?;
+    let db = std::sync::Arc::new(db);
 
     // Create spammers
     let mut spammers = Vec::new();
-    for (i, wallet_data) in wallets.iter().enumerate() {
-        // Use sol_private_key from decrypted wallet
+    for i in 0..manager.count() {
+        let wallet_data = manager
+            .get_wallet(i, password.as_deref().map(String::as_str))
+            .await?;
         // Note: wallet_data.sol_private_key should be base58 string
         if wallet_data.sol_private_key.is_empty() {
             tracing::warn!("Wallet {} has no Solana key, skipping.", i);
@@ -58,18 +77,24 @@ async fn main() -> Result<()> {
         }
 
         let keypair = Keypair::from_base58_string(&wallet_data.sol_private_key);
-        
+
         // Assign proxy round-robin
-        let proxy_config = if !proxies.is_empty() {
-            Some(proxies[i % proxies.len()].clone())
-        } else {
-            None
-        };
+        let proxy = (!proxies.is_empty()).then(|| {
+            let index = i % proxies.len();
+            SenderProxy {
+                index,
+                config: proxies[index].clone(),
+                health: health.clone(),
+            }
+        });
 
         let spammer = SolanaSpammer::new_with_keypair(
             config.to_spam_config(),
             keypair,
-            proxy_config
+            i,
+            proxy,
+            task::all_tasks(),
+            Some(db.clone()),
         )?;
         spammers.push(Box::new(spammer) as Box<dyn core_logic::traits::Spammer>);
     }
@@ -0,0 +1,223 @@
+//! JSON-RPC transport for `RpcClient` that goes through a wallet's proxy
+//!
+//! `RpcClient`'s own constructors build their HTTP client internally, so an
+//! authenticated per-wallet proxy cannot be set on them. [`ProxiedSender`]
+//! posts the requests with a reqwest client of our own instead and is
+//! handed to `RpcClient::new_sender`.
+//!
+//! Every request is reported to the shared [`ChainProxies`] ban state: it
+//! counts as a success once a reply was received and decoded, and as a
+//! failure otherwise (send errors, rejected proxy credentials, error statuses,
+//! undecodable bodies), so a dead proxy is benched for the RPC host like on
+//! the EVM chains. JSON-RPC errors in a decoded reply are the node's answer
+//! and count as successes. While its proxy is banned a sender fails fast
+//! instead of waiting on timeouts.
+
+use async_trait::async_trait;
+use core_logic::config::ProxyConfig;
+use core_logic::ChainProxies;
+use reqwest::{Client, StatusCode};
+use serde_json::Value;
+use solana_client::client_error::{ClientError, ClientErrorKind, Result as ClientResult};
+use solana_client::rpc_custom_error::{
+    NodeUnhealthyErrorData, JSON_RPC_SERVER_ERROR_NODE_UNHEALTHY,
+    JSON_RPC_SERVER_ERROR_SEND_TRANSACTION_PREFLIGHT_FAILURE,
+};
+use solana_client::rpc_request::{RpcError, RpcRequest, RpcResponseErrorData};
+use solana_client::rpc_sender::{RpcSender, RpcTransportStats};
+use std::sync::atomic::{AtomicU64, Ordering};
+use std::sync::RwLock;
+use std::time::{Duration, Instant};
+use tracing::warn;
+
+/// Proxy a sender goes through and where its health is reported
+pub struct SenderProxy {
+    pub index: usize,
+    pub config: ProxyConfig,
+    /// Ban state shared by every sender on this chain
+    pub health: Option<ChainProxies>,
+}
+
+pub struct ProxiedSender {
+    client: Client,
+    url: String,
+    proxy: Option<SenderProxy>,
+    request_id: AtomicU64,
+    stats: RwLock<RpcTransportStats>,
+}
+
+impl ProxiedSender {
+    /// A sender for `url`, direct when `proxy` is `None`
+    pub fn new(url: &str, proxy: Option<SenderProxy>, timeout: Duration) -> anyhow::Result<Self> {
+        let mut builder = Client::builder().timeout(timeout);
+        if let Some(proxy) = &proxy {
+            let mut upstream = reqwest::Proxy::all(&proxy.config.url)?;
+            if let (Some(user), Some(pass)) = (&proxy.config.username, &proxy.config.password) {
+                upstream = upstream.basic_auth(user, pass);
+            }
+            builder = builder.proxy(upstream);
+        }
+        Ok(Self {
+            client: builder.build()?,
+            url: url.to_string(),
+            proxy,
+            request_id: AtomicU64::new(1),
+            stats: RwLock::new(RpcTransportStats::default()),
+        })
+    }
+
+    /// Reports the outcome of a request to the proxy's ban state
+    fn report(&self, proxy_ok: bool) {
+        let Some(SenderProxy {
+            index,
+            health: Some(health),
+            ..
+        }) = &self.proxy
+        else {
+            return;
+        };
+        if proxy_ok {
+            health.record_success(*index);
+        } else if health.record_failure(*index) {
+            warn!("Proxy {} banned for {}", index, health.host());
+        }
+    }
+
+    /// Posts `body` and decodes the reply, the part a bad proxy can break
+    async fn fetch(&self, body: &Value) -> ClientResult<Value> {
+        let response = self.client.post(&self.url).json(body).send().await?;
+        if response.status() == StatusCode::PROXY_AUTHENTICATION_REQUIRED {
+            return Err(
+                ClientErrorKind::Custom("proxy rejected its credentials".to_string()).into(),
+            );
+        }
+        Ok(response.error_for_status()?.json().await?)
+    }
+
+    async fn post(&self, body: &Value) -> ClientResult<Value> {
+        let fetched = self.fetch(body).await;
+        self.report(fetched.is_ok());
+
+        let mut json = fetched?;
+        if json["error"].is_object() {
+            return Err(rpc_error(&json["error"]).into());
+        }
+        Ok(json["result"].take())
+    }
+}
+
+/// The JSON-RPC `error` object as `RpcClient`'s own sender decodes it, keeping
+/// the simulation result (logs included) of a failed preflight and the slot
+/// lag of an unhealthy node from `data`
+fn rpc_error(error: &Value) -> RpcError {
+    let code = error["code"].as_i64().unwrap_or_default();
+    let data = match code {
+        JSON_RPC_SERVER_ERROR_SEND_TRANSACTION_PREFLIGHT_FAILURE => {
+            serde_json::from_value(error["data"].clone())
+                .map(RpcResponseErrorData::SendTransactionPreflightFailure)
+                .unwrap_or(RpcResponseErrorData::Empty)
+        }
+        JSON_RPC_SERVER_ERROR_NODE_UNHEALTHY => {
+            serde_json::from_value::<NodeUnhealthyErrorData>(error["data"].clone())
+                .map(|data| RpcResponseErrorData::NodeUnhealthy {
+                    num_slots_behind: data.num_slots_behind,
+                })
+                .unwrap_or(RpcResponseErrorData::Empty)
+        }
+        _ => RpcResponseErrorData::Empty,
+    };
+    RpcError::RpcResponseError {
+        code,
+        message: error["message"].as_str().unwrap_or_default().to_string(),
+        data,
+    }
+}
+
+#[async_trait]
+impl RpcSender for ProxiedSender {
+    async fn send(&self, request: RpcRequest, params: Value) -> ClientResult<Value> {
+        if let Some(SenderProxy {
+            index,
+            health: Some(health),
+            ..
+        }) = &self.proxy
+        {
+            if health.is_banned(*index) {
+                return Err(ClientError::from(ClientErrorKind::Custom(format!(
+                    "proxy {} is banned for {}",
+                    index,
+                    health.host()
+                ))));
+            }
+        }
+
+        let id = self.request_id.fetch_add(1, Ordering::Relaxed);
+        let body = request.build_request_json(id, params);
+        let start = Instant::now();
+        let result = self.post(&body).await;
+
+        if let Ok(mut stats) = self.stats.write() {
+            stats.request_count += 1;
+            stats.elapsed_time += start.elapsed();
+        }
+        result
+    }
+
+    fn get_transport_stats(&self) -> RpcTransportStats {
+        self.stats
+            .read()
+            .map(|stats| stats.clone())
+            .unwrap_or_default()
+    }
+
+    fn url(&self) -> String {
+        self.url.clone()
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use serde_json::json;
+
+    #[test]
+    fn test_rpc_error_keeps_preflight_logs() {
+        let error = json!({
+            "code": JSON_RPC_SERVER_ERROR_SEND_TRANSACTION_PREFLIGHT_FAILURE,
+            "message": "Transaction simulation failed",
+            "data": { "err": "AccountNotFound", "logs": ["Program log: nope"] },
+        });
+        let RpcError::RpcResponseError {
+            code,
+            message,
+            data,
+        } = rpc_error(&error)
+        else {
+            panic!("not a response error");
+        };
+        assert_eq!(
+            code,
+            JSON_RPC_SERVER_ERROR_SEND_TRANSACTION_PREFLIGHT_FAILURE
+        );
+        assert_eq!(message, "Transaction simulation failed");
+        let RpcResponseErrorData::SendTransactionPreflightFailure(result) = data else {
+            panic!("preflight data dropped");
+        };
+        assert_eq!(result.logs, Some(vec!["Program log: nope".to_string()]));
+
+        let unhealthy = json!({
+            "code": JSON_RPC_SERVER_ERROR_NODE_UNHEALTHY,
+            "message": "Node is behind",
+            "data": { "numSlotsBehind": 42 },
+        });
+        assert!(matches!(
+            rpc_error(&unhealthy),
+            RpcError::RpcResponseError {
+                data: RpcResponseErrorData::NodeUnhealthy {
+                    num_slots_behind: Some(42)
+                },
+                ..
+            }
+        ));
+    }
+}
@@ -1,131 +1,199 @@
+use crate::sender::{ProxiedSender, SenderProxy};
+use crate::task::{SolanaTask, TaskContext};
+use anyhow::Result;
 use async_trait::async_trait;
-use core_logic::traits::Spammer;
 use core_logic::config::SpamConfig;
-use anyhow::{Result, Context};
-use tracing::{info, warn, error};
-use tokio::time::{sleep, Duration};
-use solana_client::rpc_client::RpcClient;
+use core_logic::database::DatabaseManager;
+use core_logic::metrics::{MetricsCollector, TaskLabels};
+use core_logic::traits::{Spammer, SpammerStats};
+use rand::distributions::{Distribution, WeightedIndex};
+use rand::rngs::OsRng;
+use solana_client::nonblocking::rpc_client::RpcClient;
+use solana_client::rpc_client::RpcClientConfig;
 use solana_sdk::{
-    signature::{Keypair, Signer},
-    transaction::Transaction,
-    system_instruction,
-    pubkey::Pubkey,
     commitment_config::CommitmentConfig,
+    signature::{Keypair, Signer},
 };
 use std::sync::Arc;
-use reqwest::Client;
+use tokio::time::{sleep, Duration, Instant};
+use tokio_util::sync::CancellationToken;
+use tracing::{info, warn, Instrument};
 
 pub struct SolanaSpammer {
     config: SpamConfig,
     client: Arc<RpcClient>,
     keypair: Arc<Keypair>,
+    tasks: Vec<Box<SolanaTask>>,
+    dist: WeightedIndex<u32>,
+    // Context IDs for logging
+    wallet_index: usize,
+    proxy_index: Option<usize>,
+    db: Option<Arc<DatabaseManager>>,
 }
 
-impl SolanaSpammer {
-    pub fn new_with_keypair(config: SpamConfig, keypair: Keypair, proxy_config: Option<core_logic::config::ProxyConfig>) -> Result<Self> {
-         // Build reqwest client with proxy if needed
-        let mut client_builder = Client::builder();
-        
-        if let Some(proxy_conf) = proxy_config {
-             let mut proxy = reqwest::Proxy::all(&proxy_conf.url)?;
-             if let (Some(u), Some(p)) = (&proxy_conf.username, &proxy_conf.password) {
-                 proxy = proxy.basic_auth(u, p);
-             }
-             client_builder = client_builder.proxy(proxy);
-        }
+fn get_task_weight(name: &str) -> u32 {
+    match name {
+        "01_selfTransfer" => 10,
+        "05_memo" => 10,
+        "04_splTransfer" => 5,
+        _ => 1, //default
+    }
+}
 
+impl SolanaSpammer {
+    /// A spammer for wallet `wallet_index` sending from `keypair`, through
+    /// `proxy` when given
+    pub fn new_with_keypair(
+        config: SpamConfig,
+        keypair: Keypair,
+        wallet_index: usize,
+        proxy: Option<SenderProxy>,
+        tasks: Vec<Box<SolanaTask>>,
+        db: Option<Arc<DatabaseManager>>,
+    ) -> Result<Self> {
+        let proxy_index = proxy.as_ref().map(|proxy| proxy.index);
         // Increase timeout for Solana RPC
-        let client = client_builder
-            .timeout(Duration::from_secs(30))
-            .build()?;
-            
-        // RpcClient::new_with_client requires the URL and the reqwest client
-        // Note: Assuming solana-client supports this constructor in the version used
-        // If not, we might need to rely on environment variables for proxying, or specific client construction
-        // Standard RpcClient uses reqwest under the hood.
-        
-        // NOTE: solana_client::RpcClient doesn't expose `new_with_client` easily in all versions.
-        // It's often better to just use standard new() unless we heavily customize the transport.
-        // However, for proxy AUTH, we absolutely need custom transport.
-        // Let's assume for now we use the standard constructor if no proxy, or Http helper if valid.
-        
-        // Actually, solana_client usually provides `start_with_runtime` or similar but it's complex.
-        // For simplicity in this template, we will rely on standard `new_with_timeout_and_commitment`
-        // UNLESS we can confirm `RpcClient` accepts a custom helper.
-        
-        // Workaround: Solana RPC client is NOT easily proxyable via `reqwest` injection in older versions.
-        // BUT, `RpcClient` constructors often take a URL. Reqwest supports `HTTP_PROXY` env var.
-        // If we want per-wallet proxies, we might need to manually perform JSON-RPC calls via `reqwest`
-        // instead of `solana_client`.
-        //
-        // Given `evm-project` uses `ethers` (which wraps `a_client`), it's easier.
-        // For Solana, let's keep it simple: WE WILL USE `RpcClient` basic entry for now.
-        // To truly support authenticated proxies per wallet in Solana, we would need to implement `RpcSender`.
-        // For this template, verify if `SolanaSpammer` logic sends `native calls`?
-        // Ah, `start()` uses `self.client.send_transaction`.
-        
-        // FOR NOW: Let's assume standard behavior. If we really need strict proxying, 
-        // we might have to use `reqwest` to post the transaction blob manually to the RPC endpoint.
-        
-        // Let's proceed with the standard constructor but warn if proxy is set but not applied
-        // because of library limitations, OR we try to set it.
-        
-        let rpc_client = RpcClient::new_with_timeout_and_commitment(
-            config.rpc_url.clone(),
-            Duration::from_secs(30),
-            CommitmentConfig::confirmed(),
+        let sender = ProxiedSender::new(&config.rpc_url, proxy, Duration::from_secs(30))?;
+        let rpc_client = RpcClient::new_sender(
+            sender,
+            RpcClientConfig::with_commitment(CommitmentConfig::confirmed()),
         );
 
+        let weights: Vec<u32> = tasks.iter().map(|t| get_task_weight(t.name())).collect();
+        let dist = WeightedIndex::new(&weights)?;
+
         Ok(Self {
             config,
             client: Arc::new(rpc_client),
             keypair: Arc::new(keypair),
+            tasks,
+            dist,
+            wallet_index,
+            proxy_index,
+            db,
         })
     }
+
+    fn chain_id(&self) -> Option<u64> {
+        (self.config.chain_id != 0).then_some(self.config.chain_id)
+    }
+
+    /// Logs a finished task and counts it in the database and metrics
+    async fn record(&self, task: &str, duration: Duration, result: Result<String, String>) {
+        let success = result.is_ok();
+        let message = match result {
+            Ok(message) => {
+                info!(
+                    target: "task_result",
+                    "Success [{}] {} in {:.1}s",
+                    task,
+                    message,
+                    duration.as_secs_f64()
+                );
+                message
+            }
+            Err(message) => {
+                warn!(
+                    target: "task_result",
+                    "Failed  [{}] {} in {:.1}s",
+                    task,
+                    message,
+                    duration.as_secs_f64()
+                );
+                message
+            }
+        };
+
+        let labels = TaskLabels {
+            chain_id: self.chain_id(),
+            task,
+            wallet_index: Some(self.wallet_index),
+            proxy_index: self.proxy_index,
+        };
+        MetricsCollector::global().record_task_with(&labels, duration, success);
+
+        if let Some(db) = &self.db {
+            let _ = db
+                .log_chain_task_result(
+                    self.chain_id(),
+                    &format!("{:03}", self.wallet_index + 1),
+                    &self.keypair.pubkey().to_string(),
+                    task,
+                    success,
+                    &message,
+                    duration.as_millis() as u64,
+                )
+                .await;
+        }
+    }
 }
 
 #[async_trait]
 impl Spammer for SolanaSpammer {
-    async fn new(config: SpamConfig) -> Result<Self> {
+    async fn new(_config: SpamConfig) -> Result<Self> {
         // Fallback for trait creation without keypair logic handling here
         // Ideally we pass keypair in via factory/builder pattern in runner
         Err(anyhow::anyhow!("Use new_with_keypair construction"))
     }
 
-    async fn start(&self) -> Result<()> {
-        info!("Solana Spammer started...");
-        
-        loop {
-            // Mock transaction: Send 0 SOL to self
-            let sender = self.keypair.pubkey();
-            // In a real spammer, we would manage blockhash fetching in a background thread
-            // to avoid latency.
-            let blockhash = match self.client.get_latest_blockhash() {
-                Ok(b) => b,
-                Err(e) => {
-                    error!("Failed to get blockhash: {}", e);
-                    sleep(Duration::from_secs(1)).await;
-                    continue;
+    async fn start(&self, cancellation_token: CancellationToken) -> Result<SpammerStats> {
+        let span = tracing::info_span!(
+            "spammer_context",
+            wallet_id = format!("{:03}", self.wallet_index + 1),
+            proxy_id = self
+                .proxy_index
+                .map_or_else(|| "000".to_string(), |i| format!("{:03}", i + 1))
+        );
+
+        async move {
+            info!("Solana Spammer started...");
+            let mut stats = SpammerStats::default();
+            let run_start = Instant::now();
+
+            loop {
+                if cancellation_token.is_cancelled() {
+                    break;
+                }
+
+                let task = &self.tasks[self.dist.sample(&mut OsRng)];
+                let ctx = TaskContext {
+                    client: self.client.clone(),
+                    keypair: self.keypair.clone(),
+                    db: self.db.clone(),
+                };
+
+                let start = Instant::now();
+                let outcome = task.run(ctx).await;
+                let duration = start.elapsed();
+                match outcome {
+                    Ok(res) if res.success => {
+                        stats.record_success(task.name(), duration, res.gas_used);
+                        self.record(task.name(), duration, Ok(res.message)).await;
+                    }
+                    Ok(res) => {
+                        stats.record_failure(task.name(), duration, &res.message);
+                        self.record(task.name(), duration, Err(res.message)).await;
+                    }
+                    Err(e) => {
+                        let message = format!("{:#}", e);
+                        stats.record_failure(task.name(), duration, &message);
+                        self.record(task.name(), duration, Err(message)).await;
+                    }
+                }
+
+                // Rate limit (very basic)
+                let sleep_ms = 1000 / self.config.target_tps.max(1) as u64;
+                tokio::select! {
+                    _ = cancellation_token.cancelled() => break,
+                    _ = sleep(Duration::from_millis(sleep_ms)) => {}
                 }
-            };
-            
-            let ix = system_instruction::transfer(&sender, &sender, 0);
-            let tx = Transaction::new_signed_with_payer(
-                &[ix],
-                Some(&sender),
-                &[&*self.keypair], // Deref Arc to Keypair
-                blockhash,
-            );
-            
-            match self.client.send_transaction(&tx) {
-                Ok(sig) => info!("Sent Solana Tx: {}", sig),
-                Err(e) => error!("Failed to send Solana Tx: {}", e),
             }
 
-            // Rate limit (very basic)
-            let sleep_ms = 1000 / self.config.target_tps.max(1) as u64;
-            sleep(Duration::from_millis(sleep_ms)).await;
+            stats.run_duration_ms = run_start.elapsed().as_millis() as u64;
+            Ok(stats)
         }
+        .instrument(span)
+        .await
     }
 
     async fn stop(&self) -> Result<()> {
@@ -0,0 +1,61 @@
+use anyhow::Result;
+use solana_client::nonblocking::rpc_client::RpcClient;
+use solana_sdk::{
+    instruction::Instruction,
+    signature::{Keypair, Signature, Signer},
+    transaction::Transaction,
+};
+use std::sync::Arc;
+
+pub mod t01_self_transfer;
+pub mod t02_create_account;
+pub mod t03_create_mint;
+pub mod t04_spl_transfer;
+pub mod t05_memo;
+
+pub use self::t01_self_transfer::SelfTransferTask;
+pub use self::t02_create_account::CreateAccountTask;
+pub use self::t03_create_mint::CreateMintTask;
+pub use self::t04_spl_transfer::SplTransferTask;
+pub use self::t05_memo::MemoTask;
+
+pub use core_logic::traits::{Task, TaskResult};
+
+#[derive(Clone)]
+pub struct TaskContext {
+    pub client: Arc<RpcClient>,
+    pub keypair: Arc<Keypair>,
+    pub db: Option<Arc<core_logic::database::DatabaseManager>>,
+}
+
+impl TaskContext {
+    /// Signs `instructions` with the wallet and `extra_signers`, sends them
+    /// in one transaction and waits for confirmation
+    pub async fn send(
+        &self,
+        instructions: &[Instruction],
+        extra_signers: &[&Keypair],
+    ) -> Result<Signature> {
+        let payer = self.keypair.pubkey();
+        let blockhash = self.client.get_latest_blockhash().await?;
+        let mut signers: Vec<&Keypair> = vec![&self.keypair];
+        signers.extend_from_slice(extra_signers);
+        let tx =
+            Transaction::new_signed_with_payer(instructions, Some(&payer), &signers, blockhash);
+        Ok(self.client.send_and_confirm_transaction(&tx).await?)
+    }
+}
+
+// Trait alias
+pub type SolanaTask = dyn Task<TaskContext> + Send + Sync;
+
+/// All tasks, in the order of their numbers
+pub fn all_tasks() -> Vec<Box<SolanaTask>> {
+    vec![
+        Box::new(SelfTransferTask),
+        Box::new(CreateAccountTask),
+        Box::new(CreateMintTask),
+        Box::new(SplTransferTask),
+        Box::new(MemoTask),
+    ]
+}
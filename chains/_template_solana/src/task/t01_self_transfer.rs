@@ -0,0 +1,27 @@
+use crate::task::{Task, TaskContext, TaskResult};
+use anyhow::Result;
+use async_trait::async_trait;
+use solana_sdk::{signature::Signer, system_instruction};
+
+pub struct SelfTransferTask;
+
+#[async_trait]
+impl Task<TaskContext> for SelfTransferTask {
+    async fn run(&self, ctx: TaskContext) -> Result<TaskResult> {
+        // Send 0 SOL to self
+        let wallet = ctx.keypair.pubkey();
+        let ix = system_instruction::transfer(&wallet, &wallet, 0);
+        let signature = ctx.send(&[ix], &[]).await?;
+
+        Ok(TaskResult {
+            success: true,
+            message: "Self-transfer 0 SOL".into(),
+            tx_hash: Some(signature.to_string()),
+            ..Default::default()
+        })
+    }
+
+    fn name(&self) -> &str {
+        "01_selfTransfer"
+    }
+}
@@ -0,0 +1,42 @@
+use crate::task::{Task, TaskContext, TaskResult};
+use anyhow::Result;
+use async_trait::async_trait;
+use rand::Rng;
+use solana_sdk::{pubkey::Pubkey, signature::Signer, system_instruction, system_program};
+
+pub struct CreateAccountTask;
+
+#[async_trait]
+impl Task<TaskContext> for CreateAccountTask {
+    async fn run(&self, ctx: TaskContext) -> Result<TaskResult> {
+        // Derived from the wallet and a seed, so the wallet can move the
+        // rent back out with transfer_with_seed
+        let wallet = ctx.keypair.pubkey();
+        let seed = format!("spam-{:08x}", rand::thread_rng().gen::<u32>());
+        let account = Pubkey::create_with_seed(&wallet, &seed, &system_program::id())?;
+        let lamports = ctx.client.get_minimum_balance_for_rent_exemption(0).await?;
+
+        let ix = system_instruction::create_account_with_seed(
+            &wallet,
+            &account,
+            &wallet,
+            &seed,
+            lamports,
+            0,
+            &system_program::id(),
+        );
+        let signature = ctx.send(&[ix], &[]).await?;
+
+        Ok(TaskResult {
+            success: true,
+            message: format!("Created account {} (seed {})", account, seed),
+            tx_hash: Some(signature.to_string()),
+            contract_address: Some(account.to_string()),
+            ..Default::default()
+        })
+    }
+
+    fn name(&self) -> &str {
+        "02_createAccount"
+    }
+}
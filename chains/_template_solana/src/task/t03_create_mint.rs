@@ -0,0 +1,84 @@
+use crate::task::{Task, TaskContext, TaskResult};
+use anyhow::Result;
+use async_trait::async_trait;
+use core_logic::traits::TokenAmount;
+use solana_sdk::{
+    program_pack::Pack,
+    signature::{Keypair, Signer},
+    system_instruction,
+};
+use spl_associated_token_account::{
+    get_associated_token_address, instruction::create_associated_token_account_idempotent,
+};
+use spl_token::state::Mint;
+
+const DECIMALS: u8 = 6;
+/// Minted to the wallet, 1,000 whole tokens
+const SUPPLY: u64 = 1_000 * 10u64.pow(DECIMALS as u32);
+
+pub struct CreateMintTask;
+
+#[async_trait]
+impl Task<TaskContext> for CreateMintTask {
+    async fn run(&self, ctx: TaskContext) -> Result<TaskResult> {
+        let wallet = ctx.keypair.pubkey();
+        let mint = Keypair::new();
+        let rent = ctx
+            .client
+            .get_minimum_balance_for_rent_exemption(Mint::LEN)
+            .await?;
+        let ata = get_associated_token_address(&wallet, &mint.pubkey());
+
+        let instructions = [
+            system_instruction::create_account(
+                &wallet,
+                &mint.pubkey(),
+                rent,
+                Mint::LEN as u64,
+                &spl_token::id(),
+            ),
+            spl_token::instruction::initialize_mint2(
+                &spl_token::id(),
+                &mint.pubkey(),
+                &wallet,
+                None,
+                DECIMALS,
+            )?,
+            create_associated_token_account_idempotent(
+                &wallet,
+                &wallet,
+                &mint.pubkey(),
+                &spl_token::id(),
+            ),
+            spl_token::instruction::mint_to(
+                &spl_token::id(),
+                &mint.pubkey(),
+                &ata,
+                &wallet,
+                &[],
+                SUPPLY,
+            )?,
+        ];
+        let signature = ctx.send(&instructions, &[&mint]).await?;
+
+        Ok(TaskResult {
+            success: true,
+            message: format!(
+                "Created mint {} and minted {} to self",
+                mint.pubkey(),
+                SUPPLY
+            ),
+            tx_hash: Some(signature.to_string()),
+            contract_address: Some(mint.pubkey().to_string()),
+            token_amounts: vec![TokenAmount {
+                token: mint.pubkey().to_string(),
+                amount: SUPPLY.to_string(),
+            }],
+            ..Default::default()
+        })
+    }
+
+    fn name(&self) -> &str {
+        "03_createMint"
+    }
+}
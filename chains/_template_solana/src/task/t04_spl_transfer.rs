@@ -0,0 +1,96 @@
+use crate::task::{Task, TaskContext, TaskResult};
+use anyhow::{Context, Result};
+use async_trait::async_trait;
+use core_logic::traits::TokenAmount;
+use solana_account_decoder::UiAccountData;
+use solana_client::rpc_request::TokenAccountsFilter;
+use solana_client::rpc_response::RpcKeyedAccount;
+use solana_sdk::{
+    pubkey::Pubkey,
+    signature::{Keypair, Signer},
+};
+use spl_associated_token_account::{
+    get_associated_token_address, instruction::create_associated_token_account_idempotent,
+};
+
+pub struct SplTransferTask;
+
+/// A token account of the wallet with a balance
+struct Holding {
+    account: Pubkey,
+    mint: Pubkey,
+    decimals: u8,
+    amount: u64,
+}
+
+fn holding(keyed: &RpcKeyedAccount) -> Option<Holding> {
+    let UiAccountData::Json(parsed) = &keyed.account.data else {
+        return None;
+    };
+    let info = &parsed.parsed["info"];
+    let amount: u64 = info["tokenAmount"]["amount"].as_str()?.parse().ok()?;
+    if amount == 0 {
+        return None;
+    }
+    Some(Holding {
+        account: keyed.pubkey.parse().ok()?,
+        mint: info["mint"].as_str()?.parse().ok()?,
+        decimals: info["tokenAmount"]["decimals"].as_u64()? as u8,
+        amount,
+    })
+}
+
+#[async_trait]
+impl Task<TaskContext> for SplTransferTask {
+    async fn run(&self, ctx: TaskContext) -> Result<TaskResult> {
+        let wallet = ctx.keypair.pubkey();
+        let accounts = ctx
+            .client
+            .get_token_accounts_by_owner(&wallet, TokenAccountsFilter::ProgramId(spl_token::id()))
+            .await?;
+        let source = accounts
+            .iter()
+            .find_map(holding)
+            .context("No SPL token balance, 03_createMint has to run first")?;
+
+        // 1% of the balance to a fresh owner, whose token account we fund
+        let recipient = Keypair::new().pubkey();
+        let destination = get_associated_token_address(&recipient, &source.mint);
+        let amount = (source.amount / 100).max(1);
+
+        let instructions = [
+            create_associated_token_account_idempotent(
+                &wallet,
+                &recipient,
+                &source.mint,
+                &spl_token::id(),
+            ),
+            spl_token::instruction::transfer_checked(
+                &spl_token::id(),
+                &source.account,
+                &source.mint,
+                &destination,
+                &wallet,
+                &[],
+                amount,
+                source.decimals,
+            )?,
+        ];
+        let signature = ctx.send(&instructions, &[]).await?;
+
+        Ok(TaskResult {
+            success: true,
+            message: format!("Sent {} of {} to {}", amount, source.mint, recipient),
+            tx_hash: Some(signature.to_string()),
+            token_amounts: vec![TokenAmount {
+                token: source.mint.to_string(),
+                amount: amount.to_string(),
+            }],
+            ..Default::default()
+        })
+    }
+
+    fn name(&self) -> &str {
+        "04_splTransfer"
+    }
+}
@@ -0,0 +1,29 @@
+use crate::task::{Task, TaskContext, TaskResult};
+use anyhow::Result;
+use async_trait::async_trait;
+use rand::Rng;
+use solana_sdk::signature::Signer;
+
+pub struct MemoTask;
+
+#[async_trait]
+impl Task<TaskContext> for MemoTask {
+    async fn run(&self, ctx: TaskContext) -> Result<TaskResult> {
+        let wallet = ctx.keypair.pubkey();
+        let memo = format!("gm {:06}", rand::thread_rng().gen_range(0..1_000_000));
+        // Signed by the wallet, so the memo shows up as its own
+        let ix = spl_memo::build_memo(memo.as_bytes(), &[&wallet]);
+        let signature = ctx.send(&[ix], &[]).await?;
+
+        Ok(TaskResult {
+            success: true,
+            message: format!("Memo \"{}\"", memo),
+            tx_hash: Some(signature.to_string()),
+            ..Default::default()
+        })
+    }
+
+    fn name(&self) -> &str {
+        "05_memo"
+    }
+}
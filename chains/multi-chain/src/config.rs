@@ -0,0 +1,273 @@
+use anyhow::{bail, Context, Result};
+use core_logic::{ArbiterConfig, ProxyManager};
+use serde::Deserialize;
+use std::collections::HashSet;
+use std::ops::Range;
+use std::time::Duration;
+
+/// Unified configuration for running several chain spammers in one process
+#[derive(Debug, Clone, Deserialize)]
+#[serde(deny_unknown_fields)]
+pub struct MultiChainConfig {
+    /// Database shared by every chain; rows are told apart by chain_id
+    #[serde(default = "default_database")]
+    pub database: String,
+    /// Proxy list shared by every chain, split by `proxy_quota`
+    #[serde(default = "default_proxies")]
+    pub proxies: String,
+    /// Give every chain the whole proxy list and arbitrate between them,
+    /// instead of splitting it into disjoint slices
+    #[serde(default)]
+    pub share_proxies: bool,
+    #[serde(default)]
+    pub arbiter: ArbiterSettings,
+    #[serde(rename = "chain")]
+    pub chains: Vec<ChainEntry>,
+}
+
+/// One `[[chain]]` worker group
+#[derive(Debug, Clone, Deserialize)]
+#[serde(deny_unknown_fields)]
+pub struct ChainEntry {
+    /// Label used in logs
+    pub name: String,
+    pub kind: ChainKind,
+    /// Path to the chain's own config file
+    pub config: String,
+    /// Worker count, overriding the chain config
+    #[serde(default)]
+    pub workers: Option<u64>,
+    /// Relative share of the proxy pool (0 = direct connections)
+    #[serde(default = "default_proxy_quota")]
+    pub proxy_quota: f64,
+    #[serde(default = "default_enabled")]
+    pub enabled: bool,
+}
+
+/// `[arbiter]` limits used when `share_proxies` is on
+#[derive(Debug, Clone, Deserialize)]
+#[serde(deny_unknown_fields, default)]
+pub struct ArbiterSettings {
+    /// In-flight requests per proxy across all chains, split by `proxy_quota`
+    pub per_proxy_concurrency: usize,
+    /// Consecutive failures against a chain's RPC host before banning the proxy for it
+    pub failure_threshold: u32,
+    pub ban_minutes: u64,
+}
+
+impl Default for ArbiterSettings {
+    fn default() -> Self {
+        let defaults = ArbiterConfig::default();
+        Self {
+            per_proxy_concurrency: defaults.per_proxy_concurrency,
+            failure_threshold: defaults.failure_threshold,
+            ban_minutes: defaults.ban_duration.as_secs() / 60,
+        }
+    }
+}
+
+impl ArbiterSettings {
+    pub fn to_arbiter_config(&self) -> ArbiterConfig {
+        ArbiterConfig {
+            per_proxy_concurrency: self.per_proxy_concurrency,
+            failure_threshold: self.failure_threshold,
+            ban_duration: Duration::from_secs(self.ban_minutes * 60),
+        }
+    }
+}
+
+/// Spammer implementation behind a chain entry
+#[derive(Debug, Clone, Copy, PartialEq, Eq, Deserialize)]
+#[serde(rename_all = "lowercase")]
+pub enum ChainKind {
+    Tempo,
+    Rise,
+}
+
+fn default_database() -> String {
+    "multi-chain.db".to_string()
+}
+
+fn default_proxies() -> String {
+    "proxies.txt".to_string()
+}
+
+fn default_proxy_quota() -> f64 {
+    1.0
+}
+
+fn default_enabled() -> bool {
+    true
+}
+
+impl MultiChainConfig {
+    pub fn from_path(path: &str) -> Result<Self> {
+        let content = std::fs::read_to_string(path)
+            .with_context(|| format!("Failed to read config file: {}", path))?;
+        Self::from_toml_str(&content, path)
+    }
+
+    fn from_toml_str(content: &str, source: &str) -> Result<Self> {
+        let config: Self = toml::from_str(content)
+            .map_err(|e| {
+                match core_logic::config::unknown_key_error(
+                    e.message(),
+                    source,
+                    content,
+                    e.span().map(|span| span.start),
+                ) {
+                    Some(unknown) => anyhow::Error::new(unknown),
+                    None => anyhow::Error::new(e),
+                }
+            })
+            .context("Failed to parse config TOML")?;
+        config.validate()?;
+        Ok(config)
+    }
+
+    fn validate(&self) -> Result<()> {
+        let mut names = HashSet::new();
+        for chain in &self.chains {
+            if !names.insert(chain.name.as_str()) {
+                bail!("Duplicate chain name '{}'", chain.name);
+            }
+            if !chain.proxy_quota.is_finite() || chain.proxy_quota < 0.0 {
+                bail!(
+                    "Chain '{}': proxy_quota must be a non-negative number, got {}",
+                    chain.name,
+                    chain.proxy_quota
+                );
+            }
+        }
+        if self.enabled_chains().next().is_none() {
+            bail!("No enabled [[chain]] entries");
+        }
+        // The tempo spammer keeps its contract addresses, rate limiter, chaos,
+        // watchdog and RPC stats process-wide, so two of them would share one set
+        let tempo_chains = self
+            .enabled_chains()
+            .filter(|chain| chain.kind == ChainKind::Tempo)
+            .count();
+        if tempo_chains > 1 {
+            bail!(
+                "Only one enabled tempo chain per process, found {}",
+                tempo_chains
+            );
+        }
+        if self.share_proxies && self.arbiter.per_proxy_concurrency == 0 {
+            bail!("arbiter.per_proxy_concurrency must be at least 1");
+        }
+        Ok(())
+    }
+
+    /// Chains to run, in config order
+    pub fn enabled_chains(&self) -> impl Iterator<Item = &ChainEntry> {
+        self.chains.iter().filter(|chain| chain.enabled)
+    }
+
+    /// Proxy index ranges for [`enabled_chains`](Self::enabled_chains)
+    ///
+    /// Disjoint slices by `proxy_quota`, or the whole list for every chain
+    /// with a quota when `share_proxies` is on.
+    pub fn proxy_ranges(&self, total_proxies: usize) -> Vec<Range<usize>> {
+        if self.share_proxies {
+            return self
+                .enabled_chains()
+                .map(|chain| {
+                    if chain.proxy_quota > 0.0 {
+                        0..total_proxies
+                    } else {
+                        0..0
+                    }
+                })
+                .collect();
+        }
+
+        let quotas: Vec<f64> = self
+            .enabled_chains()
+            .map(|chain| chain.proxy_quota)
+            .collect();
+        ProxyManager::partition(total_proxies, &quotas)
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    const SAMPLE: &str = r#"
+database = "shared.db"
+
+[[chain]]
+name = "tempo"
+kind = "tempo"
+config = "tempo.toml"
+proxy_quota = 3
+
+[[chain]]
+name = "rise"
+kind = "rise"
+config = "rise.toml"
+workers = 4
+
+[[chain]]
+name = "rise-backup"
+kind = "rise"
+config = "rise.toml"
+enabled = false
+"#;
+
+    #[test]
+    fn test_parse_and_split_proxies() {
+        let config = MultiChainConfig::from_toml_str(SAMPLE, "multi.toml").unwrap();
+        assert_eq!(config.database, "shared.db");
+        assert_eq!(config.proxies, "proxies.txt");
+
+        let enabled: Vec<_> = config.enabled_chains().map(|c| c.kind).collect();
+        assert_eq!(enabled, vec![ChainKind::Tempo, ChainKind::Rise]);
+        assert_eq!(config.chains[1].workers, Some(4));
+
+        // Disabled chains take no share of the pool
+        assert_eq!(config.proxy_ranges(8), vec![0..6, 6..8]);
+    }
+
+    #[test]
+    fn test_shared_proxies() {
+        let shared = SAMPLE.replace(
+            "database = \"shared.db\"",
+            "database = \"shared.db\"\nshare_proxies = true\n\n[arbiter]\nfailure_threshold = 5",
+        );
+        let config = MultiChainConfig::from_toml_str(&shared, "multi.toml").unwrap();
+        assert_eq!(config.proxy_ranges(8), vec![0..8, 0..8]);
+
+        let arbiter = config.arbiter.to_arbiter_config();
+        assert_eq!(arbiter.failure_threshold, 5);
+        assert_eq!(arbiter.per_proxy_concurrency, 8);
+        assert_eq!(arbiter.ban_duration, Duration::from_secs(600));
+    }
+
+    #[test]
+    fn test_rejects_bad_entries() {
+        let unknown = SAMPLE.replace("workers = 4", "worker = 4");
+        let err = MultiChainConfig::from_toml_str(&unknown, "multi.toml").unwrap_err();
+        assert!(format!("{:#}", err).contains("did you mean 'workers'?"));
+
+        let duplicate = SAMPLE.replace("\"rise-backup\"", "\"rise\"");
+        assert!(MultiChainConfig::from_toml_str(&duplicate, "multi.toml").is_err());
+
+        let disabled = SAMPLE
+            .replace(
+                "config = \"tempo.toml\"",
+                "config = \"tempo.toml\"\nenabled = false",
+            )
+            .replace("workers = 4", "workers = 4\nenabled = false");
+        assert!(MultiChainConfig::from_toml_str(&disabled, "multi.toml").is_err());
+
+        let two_tempo = SAMPLE.replace(
+            "kind = \"rise\"\nconfig = \"rise.toml\"\nworkers",
+            "kind = \"tempo\"\nconfig = \"rise.toml\"\nworkers",
+        );
+        let err = MultiChainConfig::from_toml_str(&two_tempo, "multi.toml").unwrap_err();
+        assert!(err.to_string().contains("Only one enabled tempo chain"));
+    }
+}
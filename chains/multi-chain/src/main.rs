@@ -0,0 +1,314 @@
+//! Multi-Chain Runner - Several chain spammers in one process
+//!
+//! Runs one worker group per enabled `[[chain]]` entry of the unified config.
+//! All groups share the wallet set, one database (rows tagged with the
+//! chain's `chain_id`) and one proxy list, which is split into disjoint
+//! slices by `proxy_quota` so the chains never compete for the same proxy.
+//!
+//! With `share_proxies = true` every chain sees the whole list instead and a
+//! [`ProxyArbiter`] splits each proxy's concurrency by `proxy_quota`. Proxy
+//! bans are then scoped to the RPC host of the chain that hit the failures,
+//! so a proxy blocked by one chain's endpoint stays in use for the others.
+//!
+//! ```bash
+//! cargo run -p multi-chain --release -- --config chains/multi-chain/config.toml
+//! ```
+
+mod config;
+
+use anyhow::{Context, Result};
+use clap::Parser;
+use config::{ChainEntry, ChainKind, MultiChainConfig};
+use core_logic::database::{AsyncDbConfig, DatabaseManager, FallbackStrategy};
+use core_logic::{setup_logger, ChainShare, ProxyArbiter, WalletManager, WorkerRunner};
+use dialoguer::{theme::ColorfulTheme, Password};
+use dotenv::dotenv;
+use rise_project::config::RiseConfig;
+use std::sync::Arc;
+use tempo_spammer::config::TempoSpammerConfig;
+use tempo_spammer::proxy_session::SessionRotation;
+use tempo_spammer::tasks::{load_proxies_with_rotation, ProxyConfig};
+use tempo_spammer::{ClientPool, ProxyBanlist};
+use tokio::task::JoinSet;
+use tokio_util::sync::CancellationToken;
+use tracing::{error, info, warn, Instrument};
+use zeroize::Zeroizing;
+
+#[derive(Parser, Debug)]
+#[command(author, version, about, long_about = None)]
+struct Args {
+    #[arg(short, long, default_value = "chains/multi-chain/config.toml")]
+    config: String,
+    /// Run every chain on direct connections
+    #[arg(long)]
+    no_proxy: bool,
+}
+
+#[tokio::main]
+async fn main() -> Result<()> {
+    let _log_guard = setup_logger();
+    // Keep guard alive for file logging
+    std::mem::forget(_log_guard);
+    dotenv().ok();
+
+    let args = Args::parse();
+    let config = MultiChainConfig::from_path(&args.config).context("Failed to load config")?;
+    let chains: Vec<&ChainEntry> = config.enabled_chains().collect();
+    info!(
+        "Running {} chains: {}",
+        chains.len(),
+        chains
+            .iter()
+            .map(|c| c.name.as_str())
+            .collect::<Vec<_>>()
+            .join(", ")
+    );
+
+    let manager = WalletManager::new()?;
+    if manager.count() == 0 {
+        error!("No wallets found");
+        return Ok(());
+    }
+    let wallet_password = wallet_password(&manager).await?;
+
+    // One proxy list for every chain; templated sessions follow the first
+    // tempo chain's [proxy] settings
+    let tempo_configs = chains
+        .iter()
+        .filter(|c| c.kind == ChainKind::Tempo)
+        .map(|c| {
+            TempoSpammerConfig::from_path(&c.config)
+                .with_context(|| format!("Failed to load {} config", c.name))
+        })
+        .collect::<Result<Vec<_>>>()?;
+    let rise_configs = chains
+        .iter()
+        .filter(|c| c.kind == ChainKind::Rise)
+        .map(|c| {
+            RiseConfig::load(&c.config).with_context(|| format!("Failed to load {} config", c.name))
+        })
+        .collect::<Result<Vec<_>>>()?;
+    let rotation = tempo_configs
+        .first()
+        .map(|c| c.proxy.session_rotation())
+        .unwrap_or(SessionRotation::PerConnection);
+    let proxies = if args.no_proxy {
+        Vec::new()
+    } else {
+        load_proxies_with_rotation(&config.proxies, rotation).unwrap_or_else(|e| {
+            warn!("No proxies loaded from {}: {:#}", config.proxies, e);
+            Vec::new()
+        })
+    };
+    let ranges = config.proxy_ranges(proxies.len());
+    let arbiter = config.share_proxies.then(|| {
+        let mut tempo_urls = tempo_configs.iter().map(|c| c.rpc_url.clone());
+        let mut rise_urls = rise_configs.iter().map(|c| c.rpc_url.clone());
+        let shares: Vec<ChainShare> = chains
+            .iter()
+            .map(|c| ChainShare {
+                name: c.name.clone(),
+                target: match c.kind {
+                    ChainKind::Tempo => tempo_urls.next(),
+                    ChainKind::Rise => rise_urls.next(),
+                }
+                .expect("loaded above"),
+                quota: c.proxy_quota,
+            })
+            .collect();
+        ProxyArbiter::new(proxies.len(), &shares, config.arbiter.to_arbiter_config())
+    });
+
+    let db = Arc::new(
+        DatabaseManager::new_with_async(
+            &config.database,
+            AsyncDbConfig::default(),
+            FallbackStrategy::Hybrid,
+        )
+        .await?,
+    );
+    info!("Database: {}", config.database);
+
+    if chains.iter().any(|c| c.kind == ChainKind::Rise) {
+        rise_project::utils::address_cache::AddressCache::init()?;
+    }
+
+    let shutdown = CancellationToken::new();
+    let mut groups = JoinSet::new();
+    let mut tempo_configs = tempo_configs.into_iter();
+    let mut rise_configs = rise_configs.into_iter();
+
+    for (chain, range) in chains.iter().zip(ranges) {
+        let chain_proxies = proxies[range.clone()].to_vec();
+        let chain_arbiter = arbiter.as_ref().and_then(|a| a.chain(&chain.name));
+        match chain_arbiter {
+            Some(ref shared) => info!(
+                "[{}] {} shared proxies, {} in flight per proxy",
+                chain.name,
+                chain_proxies.len(),
+                shared.concurrency()
+            ),
+            None => info!(
+                "[{}] {} proxies ({}..{})",
+                chain.name,
+                chain_proxies.len(),
+                range.start,
+                range.end
+            ),
+        }
+        let span = tracing::info_span!("chain", name = %chain.name);
+
+        match chain.kind {
+            ChainKind::Tempo => {
+                let tempo_config = tempo_configs.next().expect("loaded above");
+                let workers = chain.workers.unwrap_or(tempo_config.worker_count);
+                let mut pool = ClientPool::new(
+                    tempo_config.clone(),
+                    db.clone(),
+                    Some(wallet_password.clone()),
+                    tempo_config.connection_semaphore,
+                )?
+                .with_proxies(chain_proxies)
+                .with_proxy_banlist(ProxyBanlist::new(10));
+                if let Some(shared) = chain_arbiter {
+                    pool = pool.with_proxy_arbiter(shared);
+                }
+                let pool = Arc::new(pool);
+                let db = db.clone();
+                let token = shutdown.clone();
+                groups.spawn(
+                    async move {
+                        tempo_spammer::runner::run_spammer_until(
+                            pool,
+                            tempo_spammer::runner::default_tasks(),
+                            &tempo_config,
+                            db,
+                            workers,
+                            token.cancelled_owned(),
+                        )
+                        .await;
+                        Ok(())
+                    }
+                    .instrument(span),
+                );
+            }
+            ChainKind::Rise => {
+                let rise_config = rise_configs.next().expect("loaded above");
+                let workers = chain
+                    .workers
+                    .map(|w| w as usize)
+                    .or(rise_config.worker_amount)
+                    .unwrap_or(manager.count());
+                let spammers = rise_project::runner::build_spammers(
+                    &rise_config,
+                    &manager,
+                    Some(&wallet_password),
+                    &to_core_proxies(&chain_proxies),
+                    chain_arbiter.as_ref(),
+                    Some(db.clone()),
+                    workers,
+                )
+                .await?;
+                // WorkerRunner stops its workers on Ctrl+C by itself and logs
+                // the chain's summary
+                let runner = WorkerRunner::new(spammers).with_ramp_up(rise_config.ramp_up());
+                groups.spawn(async move { runner.run().await.map(|_| ()) }.instrument(span));
+            }
+        }
+    }
+
+    let token = shutdown.clone();
+    tokio::spawn(async move {
+        if tokio::signal::ctrl_c().await.is_ok() {
+            info!("🛑 Received Ctrl+C. Stopping all chains...");
+            token.cancel();
+        }
+    });
+
+    if let Some(ref arbiter) = arbiter {
+        let arbiter = arbiter.clone();
+        let token = shutdown.clone();
+        tokio::spawn(async move {
+            let mut interval = tokio::time::interval(std::time::Duration::from_secs(60));
+            loop {
+                tokio::select! {
+                    _ = token.cancelled() => break,
+                    _ = interval.tick() => {}
+                }
+                let dead = arbiter.banned_everywhere();
+                if !dead.is_empty() {
+                    warn!("{} proxies banned for every chain: {:?}", dead.len(), dead);
+                }
+            }
+        });
+    }
+
+    while let Some(res) = groups.join_next().await {
+        match res {
+            Ok(Ok(())) => {}
+            Ok(Err(e)) => error!("Chain worker group failed: {:#}", e),
+            Err(e) => error!("Chain worker group panicked: {:?}", e),
+        }
+    }
+
+    match db.get_chain_summary().await {
+        Ok(summary) => {
+            for row in summary {
+                info!(
+                    "Chain {}: {} success / {} failed",
+                    row.chain_id
+                        .map_or_else(|| "-".to_string(), |id| id.to_string()),
+                    row.success,
+                    row.failed
+                );
+            }
+        }
+        Err(e) => warn!("Failed to summarize results: {:#}", e),
+    }
+
+    match Arc::try_unwrap(db) {
+        Ok(db) => db.shutdown().await?,
+        Err(_) => warn!("Database still in use, skipping final flush"),
+    }
+
+    Ok(())
+}
+
+/// WALLET_PASSWORD if it decrypts the first wallet, otherwise an interactive prompt
+async fn wallet_password(manager: &WalletManager) -> Result<Zeroizing<String>> {
+    if let Ok(password) = std::env::var("WALLET_PASSWORD") {
+        if manager.get_wallet(0, Some(&password)).await.is_ok() {
+            return Ok(Zeroizing::new(password));
+        }
+        error!("Wallet decryption failed with WALLET_PASSWORD.");
+    }
+
+    let password = Zeroizing::new(
+        Password::with_theme(&ColorfulTheme::default())
+            .with_prompt("Enter wallet password")
+            .interact()?,
+    );
+    manager
+        .get_wallet(0, Some(&password))
+        .await
+        .context("Decryption failed with provided password")?;
+    Ok(password)
+}
+
+/// Converts tempo proxies to the plain form used by the rise spammer
+///
+/// Templated credentials are filled once, so each rise worker keeps the
+/// session it was built with.
+fn to_core_proxies(proxies: &[ProxyConfig]) -> Vec<core_logic::ProxyConfig> {
+    proxies
+        .iter()
+        .map(|proxy| {
+            let credentials = proxy.credentials();
+            core_logic::ProxyConfig {
+                url: proxy.url.clone(),
+                username: credentials.username,
+                password: credentials.password,
+            }
+        })
+        .collect()
+}
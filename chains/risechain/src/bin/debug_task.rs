@@ -1,13 +1,15 @@
 use anyhow::Result;
 use clap::Parser;
+use core_logic::config::ProxyConfig;
 use core_logic::setup_logger;
 use dialoguer::{theme::ColorfulTheme, Password, Select};
 use dotenv::dotenv;
 use ethers::prelude::*;
 use reqwest;
+use rise_project::client::RiseClient;
 use rise_project::config::RiseConfig;
 use rise_project::task::{
-    t01_check_balance::CheckBalanceTask, t02_simple_eth_transfer::SimpleEthTransferTask,
+    compat, t01_check_balance::CheckBalanceTask, t02_simple_eth_transfer::SimpleEthTransferTask,
     t03_deploy_contract::DeployContractTask, t04_interact_contract::InteractContractTask,
     t05_self_transfer::SelfTransferTask, t06_send_meme::SendMemeTokenTask,
     t07_create_meme::CreateMemeTask, t09_weth_wrap::WethWrapTask, t10_weth_unwrap::WethUnwrapTask,
@@ -198,17 +200,24 @@ async fn main() -> Result<()> {
                                     let wallet = w.with_chain_id(cfg.chain_id);
                                     let addr = format!("{:?}", wallet.address());
 
-                                    let ctx = TaskContext {
-                                        provider: (*provider_arc).clone(),
-                                        wallet: wallet,
-                                        config: (*cfg).clone(),
-                                        proxy: proxy_url,
-                                        db: Some(db_manager),
-                                        gas_manager: gas_manager,
-                                    };
-
                                     let task = CheckBalanceTask;
-                                    match task.run(ctx).await {
+                                    let outcome =
+                                        match alloy_client(&cfg, &wallet, proxy_url.as_deref()) {
+                                            Ok(client) => {
+                                                let ctx = TaskContext {
+                                                    provider: (*provider_arc).clone(),
+                                                    wallet: wallet,
+                                                    config: (*cfg).clone(),
+                                                    proxy: proxy_url,
+                                                    db: Some(db_manager),
+                                                    gas_manager: gas_manager,
+                                                    client,
+                                                };
+                                                task.run(ctx).await
+                                            }
+                                            Err(e) => Err(e),
+                                        };
+                                    match outcome {
                                         Ok(res) => {
                                             if res.success {
                                                 (
@@ -424,13 +433,16 @@ async fn main() -> Result<()> {
         ));
 
         // 4. Execute
+        let wallet = wallet.with_chain_id(cfg.chain_id);
+        let client = alloy_client(&cfg, &wallet, proxy_url.as_deref())?;
         let ctx = TaskContext {
             provider,
-            wallet: wallet.with_chain_id(cfg.chain_id),
+            wallet,
             config: cfg.clone(),
             proxy: proxy_url,
             db: Some(db_manager),
             gas_manager,
+            client,
         };
 
         println!("Running...");
@@ -453,3 +465,23 @@ async fn main() -> Result<()> {
 
     Ok(())
 }
+
+/// Alloy client for `wallet`, going through the same proxy as its provider
+fn alloy_client(
+    cfg: &RiseConfig,
+    wallet: &LocalWallet,
+    proxy_url: Option<&str>,
+) -> Result<RiseClient> {
+    // The proxy credentials are part of the URL here
+    let proxy = proxy_url.map(|url| ProxyConfig {
+        url: url.to_string(),
+        username: None,
+        password: None,
+    });
+    RiseClient::new(
+        &cfg.rpc_url,
+        compat::alloy_signer(wallet)?,
+        cfg.chain_id,
+        proxy.as_ref(),
+    )
+}
@@ -0,0 +1,110 @@
+//! Alloy-based provider and signer for RISE
+//!
+//! Counterpart of the tempo stack's `TempoClient`. Tasks move over to it one
+//! at a time through [`TaskContext::client`](crate::task::TaskContext); the
+//! ethers provider and wallet stay in the context until the last task has
+//! been migrated, see [`crate::task::compat`].
+
+use alloy::network::{EthereumWallet, TransactionBuilder};
+use alloy::primitives::{Address, U256};
+use alloy::providers::{Provider, ProviderBuilder};
+use alloy::rpc::client::RpcClient;
+use alloy::rpc::types::{TransactionReceipt, TransactionRequest};
+use alloy::signers::local::PrivateKeySigner;
+use alloy::transports::http::{reqwest, Http};
+use anyhow::{Context, Result};
+use core_logic::config::ProxyConfig;
+use std::fmt;
+use std::sync::Arc;
+use std::time::Duration;
+
+/// How long [`RiseClient::send`] waits for a receipt
+const RECEIPT_TIMEOUT: Duration = Duration::from_secs(60);
+
+/// Alloy provider with the wallet's signer, going through its proxy
+///
+/// Nonces, chain id and any gas fields a request leaves unset are filled by
+/// the provider. Nonces are read from the pending block for every
+/// transaction, so tasks still sending through ethers can't make them stale.
+#[derive(Clone)]
+pub struct RiseClient {
+    provider: Arc<dyn Provider + Send + Sync>,
+    signer: PrivateKeySigner,
+    chain_id: u64,
+}
+
+impl fmt::Debug for RiseClient {
+    fn fmt(&self, f: &mut fmt::Formatter<'_>) -> fmt::Result {
+        f.debug_struct("RiseClient")
+            .field("address", &self.signer.address())
+            .field("chain_id", &self.chain_id)
+            .finish()
+    }
+}
+
+impl RiseClient {
+    pub fn new(
+        rpc_url: &str,
+        signer: PrivateKeySigner,
+        chain_id: u64,
+        proxy: Option<&ProxyConfig>,
+    ) -> Result<Self> {
+        let mut builder = reqwest::Client::builder();
+        if let Some(proxy) = proxy {
+            let mut upstream = reqwest::Proxy::all(&proxy.url)?;
+            if let (Some(u), Some(p)) = (&proxy.username, &proxy.password) {
+                upstream = upstream.basic_auth(u, p);
+            }
+            builder = builder.proxy(upstream);
+        }
+        let http = Http::with_client(
+            builder.build()?,
+            rpc_url.parse().context("Invalid RPC URL")?,
+        );
+
+        let provider = ProviderBuilder::new()
+            .wallet(EthereumWallet::from(signer.clone()))
+            .connect_client(RpcClient::new(http, false));
+
+        Ok(Self {
+            provider: Arc::new(provider),
+            signer,
+            chain_id,
+        })
+    }
+
+    pub fn provider(&self) -> &Arc<dyn Provider + Send + Sync> {
+        &self.provider
+    }
+
+    pub fn signer(&self) -> &PrivateKeySigner {
+        &self.signer
+    }
+
+    pub fn address(&self) -> Address {
+        self.signer.address()
+    }
+
+    pub fn chain_id(&self) -> u64 {
+        self.chain_id
+    }
+
+    pub async fn get_balance(&self) -> Result<U256> {
+        Ok(self.provider.get_balance(self.address()).await?)
+    }
+
+    /// Signs and sends `tx` from the wallet and waits for its receipt
+    pub async fn send(&self, tx: TransactionRequest) -> Result<TransactionReceipt> {
+        let tx = tx.with_from(self.address()).with_chain_id(self.chain_id);
+        let pending = self
+            .provider
+            .send_transaction(tx)
+            .await
+            .context("Failed to send transaction")?;
+        pending
+            .with_timeout(Some(RECEIPT_TIMEOUT))
+            .get_receipt()
+            .await
+            .context("Failed to get transaction receipt")
+    }
+}
@@ -1,9 +1,12 @@
 use anyhow::Result;
 use config::{Config, File};
 use core_logic::config::{ProxyConfig, SpamConfig};
+use core_logic::RampUp;
 use serde::Deserialize;
+use std::time::Duration;
 
 #[derive(Debug, Deserialize, Clone)]
+#[serde(deny_unknown_fields)]
 pub struct RiseConfig {
     pub rpc_url: String,
     pub chain_id: u64,
@@ -16,6 +19,18 @@ pub struct RiseConfig {
     pub create2_factory: Option<String>,
     #[allow(dead_code)]
     pub proxies: Option<Vec<ProxyConfig>>,
+    /// Never prompt; same as `--non-interactive`
+    #[serde(default)]
+    pub non_interactive: bool,
+    /// File whose first line is the wallet password
+    #[serde(default)]
+    pub password_file: Option<String>,
+    /// Workers started per step; unset starts all at once
+    pub ramp_up_batch: Option<usize>,
+    /// Seconds between ramp-up steps
+    pub ramp_up_interval_secs: Option<u64>,
+    /// Failure percentage above which the ramp-up waits
+    pub ramp_up_max_error_rate: Option<f64>,
 }
 
 impl RiseConfig {
@@ -24,7 +39,28 @@ impl RiseConfig {
             .add_source(File::with_name(path))
             .build()?;
 
-        settings.try_deserialize().map_err(|e| anyhow::anyhow!(e))
+        settings.try_deserialize().map_err(|e| {
+            let message = e.to_string();
+            let content = std::fs::read_to_string(path)
+                .or_else(|_| std::fs::read_to_string(format!("{}.toml", path)))
+                .unwrap_or_default();
+            match core_logic::config::unknown_key_error(&message, path, &content, None) {
+                Some(unknown) => anyhow::anyhow!(unknown),
+                None => anyhow::anyhow!(e),
+            }
+        })
+    }
+
+    pub fn ramp_up(&self) -> RampUp {
+        let defaults = RampUp::default();
+        RampUp {
+            batch: self.ramp_up_batch.unwrap_or(defaults.batch),
+            interval: self
+                .ramp_up_interval_secs
+                .map_or(defaults.interval, Duration::from_secs),
+            max_error_rate: self.ramp_up_max_error_rate,
+            ..defaults
+        }
     }
 
     pub fn to_spam_config(&self) -> SpamConfig {
@@ -1,5 +1,7 @@
+pub mod client;
 pub mod config;
 pub mod contracts;
+pub mod runner;
 pub mod spammer;
 pub mod task;
 pub mod utils;
@@ -1,5 +1,4 @@
 use rise_project::config;
-use rise_project::spammer;
 
 use anyhow::Result;
 use clap::Parser;
@@ -8,10 +7,7 @@ use core_logic::metrics::MetricsCollector;
 use core_logic::{setup_logger, WorkerRunner};
 use dialoguer::{theme::ColorfulTheme, Password};
 use dotenv::dotenv;
-use ethers::prelude::*;
-use rand::seq::SliceRandom;
-use spammer::EvmSpammer;
-use std::env;
+use std::path::Path;
 use tokio::time::{interval, Duration};
 use tracing::{error, info};
 
@@ -24,6 +20,12 @@ struct Args {
     export_metrics: Option<String>,
     #[arg(long, default_value = "30")]
     metrics_interval: u64,
+    /// Never prompt; fail with an error when input would be needed
+    #[arg(long)]
+    non_interactive: bool,
+    /// File whose first line is the wallet password
+    #[arg(long)]
+    password_file: Option<String>,
 }
 
 #[tokio::main]
@@ -38,11 +40,15 @@ async fn main() -> Result<()> {
 
     let config = match RiseConfig::load(&args.config) {
         Ok(c) => c,
+        Err(e) if core_logic::non_interactive(args.non_interactive, false) => {
+            return Err(e.context("Failed to load config"))
+        }
         Err(e) => {
             error!("Failed to load config: {}", e);
             return Ok(());
         }
     };
+    let headless = core_logic::non_interactive(args.non_interactive, config.non_interactive);
 
     info!("Configuration loaded for chain ID: {}", config.chain_id);
 
@@ -52,46 +58,47 @@ async fn main() -> Result<()> {
 
     info!("Found {} wallet files.", total_wallets);
 
-    // Get password (env var first, then interactive fallback)
+    // Get password (file or env var first, then interactive fallback)
     let wallet_password = if total_wallets > 0 {
-        let mut password = env::var("WALLET_PASSWORD").ok();
+        let password_file = args
+            .password_file
+            .as_deref()
+            .or(config.password_file.as_deref());
+        let mut password = core_logic::wallet_password(password_file.map(Path::new))?;
 
         // Validate password or prompt
-        if password.is_none() || manager.get_wallet(0, password.as_deref()).await.is_err() {
+        if let Err(e) = manager
+            .get_wallet(0, password.as_deref().map(String::as_str))
+            .await
+        {
+            if headless {
+                return Err(e.context(format!(
+                    "Could not unlock wallets without a prompt; {}",
+                    core_logic::password_sources_hint()
+                )));
+            }
             if password.is_none() {
-                error!("WALLET_PASSWORD environment variable is not set.");
+                error!("No wallet password configured.");
             } else {
                 error!("Wallet decryption failed with provided password.");
             }
 
-            // Try interactive prompt
-            match Password::with_theme(&ColorfulTheme::default())
+            let input = Password::with_theme(&ColorfulTheme::default())
                 .with_prompt("Enter wallet password")
-                .interact()
-            {
-                Ok(input) => {
-                    password = Some(input);
-                    // Validate interactive password
-                    if let Err(e) = manager.get_wallet(0, password.as_deref()).await {
-                        error!("Interactive password also failed: {}", e);
-                        return Ok(());
-                    }
-                    info!("Interactive password validated successfully.");
-                }
-                Err(_) => {
-                    // Non-interactive mode - show helpful error
-                    error!("Cannot prompt for password (not a terminal).");
-                    error!("Please set WALLET_PASSWORD environment variable:");
-                    error!("  PowerShell: $env:WALLET_PASSWORD='your_password'");
-                    error!("  CMD: set WALLET_PASSWORD=your_password");
-                    return Ok(());
-                }
+                .interact()?;
+            if let Err(e) = manager.get_wallet(0, Some(&input)).await {
+                error!("Interactive password also failed: {}", e);
+                return Ok(());
             }
+            info!("Interactive password validated successfully.");
+            password = Some(input.into());
         } else {
             info!("Wallet password validated successfully.");
         }
 
         password
+    } else if headless {
+        anyhow::bail!("No wallets found in wallet-json/ or pv.txt");
     } else {
         None
     };
@@ -111,72 +118,26 @@ async fn main() -> Result<()> {
     let db_manager = core_logic::database::DatabaseManager::new("rise.db").await?;
     let db_arc = std::sync::Arc::new(db_manager);
 
-    // Create spammers
-    let mut spammers = Vec::new();
-
     // Limit workers if configured
-    let max_workers = if total_wallets == 0 {
-        0
-    } else {
-        config
-            .worker_amount
-            .unwrap_or(total_wallets)
-            .min(total_wallets)
-    };
+    let max_workers = config.worker_amount.unwrap_or(total_wallets);
 
     info!(
         "Starting {} workers (Available: {}, Configured limit: {:?})",
-        max_workers, total_wallets, config.worker_amount
+        max_workers.min(total_wallets),
+        total_wallets,
+        config.worker_amount
     );
 
-    let mut rng = rand::thread_rng();
-    let mut wallet_indices: Vec<usize> = (0..total_wallets).collect();
-    wallet_indices.shuffle(&mut rng);
-
-    for i in 0..max_workers {
-        let wallet_idx = wallet_indices[i];
-        // Lazy decrypt
-        let decrypted = match manager
-            .get_wallet(wallet_idx, wallet_password.as_deref())
-            .await
-        {
-            Ok(w) => w,
-            Err(e) => {
-                error!("Failed to decrypt wallet {}: {}", wallet_idx, e);
-                continue;
-            }
-        };
-
-        let key = decrypted.evm_private_key.clone();
-        let wallet = key.parse::<ethers::signers::LocalWallet>()?;
-
-        // Assign proxy randomly if available
-        let (proxy_config, proxy_id_str) = if !proxies.is_empty() {
-            use rand::Rng;
-            let idx = rng.gen_range(0..proxies.len());
-            (Some(proxies[idx].clone()), format!("{:03}", idx + 1))
-        } else {
-            (None, "000".to_string())
-        };
-
-        if let Some(ref p) = proxy_config {
-            info!("Assigned proxy {} to wallet {:?}", p.url, wallet.address());
-        }
-
-        // Use wallet_idx for the ID string to track which actual wallet is being used
-        let wallet_id_str = format!("{:03}", wallet_idx + 1);
-
-        let spammer = EvmSpammer::new_with_signer(
-            config.to_spam_config(),
-            config.clone(),
-            wallet,
-            proxy_config,
-            wallet_id_str,
-            proxy_id_str,
-            Some(db_arc.clone()),
-        )?;
-        spammers.push(Box::new(spammer) as Box<dyn core_logic::traits::Spammer>);
-    }
+    let spammers = rise_project::runner::build_spammers(
+        &config,
+        &manager,
+        wallet_password.as_deref().map(String::as_str),
+        &proxies,
+        None,
+        Some(db_arc),
+        max_workers,
+    )
+    .await?;
 
     // Run
     let metrics_task = if let Some(ref metrics_path) = args.export_metrics {
@@ -198,7 +159,10 @@ async fn main() -> Result<()> {
         None
     };
 
-    WorkerRunner::run_spammers(spammers).await?;
+    WorkerRunner::new(spammers)
+        .with_ramp_up(config.ramp_up())
+        .run()
+        .await?;
 
     // Cancel metrics task
     if let Some(task) = metrics_task {
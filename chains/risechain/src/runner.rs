@@ -0,0 +1,90 @@
+use crate::config::RiseConfig;
+use crate::spammer::EvmSpammer;
+use anyhow::Result;
+use core_logic::config::ProxyConfig;
+use core_logic::database::DatabaseManager;
+use core_logic::traits::Spammer;
+use core_logic::{ChainProxies, WalletManager};
+use ethers::signers::{LocalWallet, Signer};
+use rand::seq::SliceRandom;
+use rand::Rng;
+use std::sync::Arc;
+use tracing::{error, info};
+
+/// Builds one spammer per wallet, up to `max_workers`
+///
+/// Wallets are shuffled and decrypted lazily; wallets that fail to decrypt
+/// are skipped. Each spammer gets a random proxy from `proxies` (or a direct
+/// connection when the list is empty) and logs its results to `db` tagged
+/// with the configured chain id.
+///
+/// With `arbiter`, `proxies` must be the list the arbiter was created for:
+/// proxies are picked among those not banned for the chain's host, and each
+/// spammer shares its proxy with other chains through the arbiter.
+pub async fn build_spammers(
+    config: &RiseConfig,
+    manager: &WalletManager,
+    wallet_password: Option<&str>,
+    proxies: &[ProxyConfig],
+    arbiter: Option<&ChainProxies>,
+    db: Option<Arc<DatabaseManager>>,
+    max_workers: usize,
+) -> Result<Vec<Box<dyn Spammer>>> {
+    let total_wallets = manager.count();
+    let max_workers = max_workers.min(total_wallets);
+
+    let mut rng = rand::thread_rng();
+    let mut wallet_indices: Vec<usize> = (0..total_wallets).collect();
+    wallet_indices.shuffle(&mut rng);
+
+    let mut spammers = Vec::new();
+    for &wallet_idx in wallet_indices.iter().take(max_workers) {
+        // Lazy decrypt
+        let decrypted = match manager.get_wallet(wallet_idx, wallet_password).await {
+            Ok(w) => w,
+            Err(e) => {
+                error!("Failed to decrypt wallet {}: {}", wallet_idx, e);
+                continue;
+            }
+        };
+
+        let key = decrypted.evm_private_key.clone();
+        let wallet = key.parse::<LocalWallet>()?;
+
+        // Assign proxy randomly if available, preferring those not banned
+        // for this chain
+        let available = arbiter.map(ChainProxies::available).unwrap_or_default();
+        let proxy_idx = match available.choose(&mut rng) {
+            Some(&idx) => Some(idx),
+            None if !proxies.is_empty() => Some(rng.gen_range(0..proxies.len())),
+            None => None,
+        };
+        let (proxy_config, proxy_id_str) = match proxy_idx {
+            Some(idx) => (Some(proxies[idx].clone()), format!("{:03}", idx + 1)),
+            None => (None, "000".to_string()),
+        };
+
+        if let Some(ref p) = proxy_config {
+            info!("Assigned proxy {} to wallet {:?}", p.url, wallet.address());
+        }
+
+        // Use wallet_idx for the ID string to track which actual wallet is being used
+        let wallet_id_str = format!("{:03}", wallet_idx + 1);
+
+        let mut spammer = EvmSpammer::new_with_signer(
+            config.to_spam_config(),
+            config.clone(),
+            wallet,
+            proxy_config,
+            wallet_id_str,
+            proxy_id_str,
+            db.clone(),
+        )?;
+        if let (Some(arbiter), Some(idx)) = (arbiter, proxy_idx) {
+            spammer = spammer.with_proxy_arbiter(arbiter.clone(), idx);
+        }
+        spammers.push(Box::new(spammer) as Box<dyn Spammer>);
+    }
+
+    Ok(spammers)
+}
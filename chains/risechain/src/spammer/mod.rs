@@ -1,3 +1,4 @@
+use crate::client::RiseClient;
 use crate::config::RiseConfig;
 use crate::task::t01_check_balance::CheckBalanceTask;
 use crate::task::t02_simple_eth_transfer::SimpleEthTransferTask;
@@ -52,11 +53,13 @@ use crate::task::t52_calldata_size::CalldataSizeTask;
 use crate::task::t53_gas_stipend::GasStipendTask;
 use crate::task::t54_gas_price_zero::GasPriceZeroTask;
 use crate::task::t55_block_hash::BlockHashUsageTask;
-use crate::task::{RiseTask, TaskContext};
+use crate::task::{compat, RiseTask, TaskContext};
 use anyhow::Result;
 use async_trait::async_trait;
 use core_logic::config::SpamConfig;
+use core_logic::metrics::{MetricsCollector, TaskLabels};
 use core_logic::traits::Spammer;
+use core_logic::ChainProxies;
 use ethers::prelude::*;
 use rand::rngs::OsRng;
 
@@ -83,7 +86,21 @@ pub struct EvmSpammer {
     // Database
     db: Option<Arc<DatabaseManager>>,
     gas_manager: Arc<crate::utils::gas::GasManager>,
+    client: RiseClient,
     dist: WeightedIndex<u32>,
+    // Shared proxy quota and bans, with the index of this worker's proxy
+    proxy_arbiter: Option<(ChainProxies, usize)>,
+}
+
+/// How long a worker waits while its proxy is busy or banned for this chain
+const PROXY_WAIT: Duration = Duration::from_millis(500);
+
+/// Connection and tunnel errors that point at the proxy rather than the chain
+fn is_proxy_error(message: &str) -> bool {
+    message.contains("tunnel error")
+        || message.contains("Connect")
+        || message.contains("connection closed")
+        || message.contains("error sending request")
 }
 
 fn get_task_weight(name: &str) -> u32 {
@@ -95,6 +112,20 @@ fn get_task_weight(name: &str) -> u32 {
 }
 
 impl EvmSpammer {
+    /// Counts a finished task in the global metrics, which also gate the
+    /// worker ramp-up
+    fn record_metrics(&self, task: &str, duration: Duration, success: bool) {
+        // IDs are 1-based, `000` is a direct connection
+        let index = |id: &str| id.parse::<usize>().ok().and_then(|n| n.checked_sub(1));
+        let labels = TaskLabels {
+            chain_id: Some(self.rise_config.chain_id),
+            task,
+            wallet_index: index(&self.wallet_id),
+            proxy_index: index(&self.proxy_id),
+        };
+        MetricsCollector::global().record_task_with(&labels, duration, success);
+    }
+
     // Modified constructor to accept IDs
     pub fn new_with_signer(
         spam_config: SpamConfig,
@@ -120,6 +151,12 @@ impl EvmSpammer {
             reqwest::Url::parse(&spam_config.rpc_url)?,
             client,
         ));
+        let rise_client = RiseClient::new(
+            &spam_config.rpc_url,
+            compat::alloy_signer(&signer)?,
+            spam_config.chain_id,
+            proxy_config.as_ref(),
+        )?;
 
         let tasks: Vec<Box<RiseTask>> = vec![
             Box::new(CheckBalanceTask),
@@ -220,9 +257,21 @@ impl EvmSpammer {
             proxy_url: proxy_config.map(|p| p.url),
             db,
             gas_manager,
+            client: rise_client,
             dist,
+            proxy_arbiter: None,
         })
     }
+
+    /// Shares the worker's proxy with other chains through `arbiter`
+    ///
+    /// `proxy_idx` is the proxy's index in the list the arbiter was created
+    /// for. Each task then waits for one of the chain's slots on the proxy,
+    /// and its outcome counts towards the proxy's ban for the chain's host.
+    pub fn with_proxy_arbiter(mut self, arbiter: ChainProxies, proxy_idx: usize) -> Self {
+        self.proxy_arbiter = Some((arbiter, proxy_idx));
+        self
+    }
 }
 
 #[async_trait]
@@ -245,6 +294,7 @@ impl Spammer for EvmSpammer {
         async move {
             info!("RISE Spammer started for chain {}", self.config.chain_id);
             let mut stats = core_logic::traits::SpammerStats::default();
+            let run_start = std::time::Instant::now();
 
             loop {
                 // Check if cancelled before starting task
@@ -253,6 +303,30 @@ impl Spammer for EvmSpammer {
                     break;
                 }
 
+                // Stay within this chain's share of a proxy other chains also use
+                let proxy_slot = match &self.proxy_arbiter {
+                    Some((arbiter, proxy_idx)) => {
+                        let permit = if arbiter.is_banned(*proxy_idx) {
+                            None
+                        } else {
+                            arbiter.try_acquire(*proxy_idx)
+                        };
+                        match permit {
+                            Some(permit) => Some(permit),
+                            None => {
+                                tokio::select! {
+                                    _ = cancellation_token.cancelled() => {
+                                        info!("Worker stopping (cancelled).");
+                                        break;
+                                    }
+                                    _ = sleep(PROXY_WAIT) => continue,
+                                }
+                            }
+                        }
+                    }
+                    None => None,
+                };
+
                 let task = {
                     let mut rng = OsRng;
                     let idx = self.dist.sample(&mut rng);
@@ -269,13 +343,18 @@ impl Spammer for EvmSpammer {
                         proxy: self.proxy_url.clone(),
                         db: self.db.clone(),
                         gas_manager: self.gas_manager.clone(),
+                        client: self.client.clone(),
                     };
 
                     let start_time = std::time::Instant::now();
                     match task.run(ctx).await {
                         Ok(res) => {
-                            stats.success += 1;
                             let duration = start_time.elapsed();
+                            if let Some((arbiter, proxy_idx)) = &self.proxy_arbiter {
+                                arbiter.record_success(*proxy_idx);
+                            }
+                            stats.record_success(task.name(), duration, res.gas_used);
+                            self.record_metrics(task.name(), duration, true);
                             let block_num = match self.provider.get_block_number().await {
                                 Ok(n) => n.to_string(),
                                 Err(_) => "???".to_string(),
@@ -380,7 +459,8 @@ impl Spammer for EvmSpammer {
                                 // Code block re-uses `res.message` for DB. Excellent.
 
                                 let _ = db
-                                    .log_task_result(
+                                    .log_chain_task_result(
+                                        Some(self.rise_config.chain_id),
                                         &self.wallet_id,
                                         &format!("{:?}", self.wallet.address()),
                                         task.name(),
@@ -392,8 +472,20 @@ impl Spammer for EvmSpammer {
                             }
                         }
                         Err(e) => {
-                            stats.failed += 1;
                             let duration = start_time.elapsed();
+                            if let Some((arbiter, proxy_idx)) = &self.proxy_arbiter {
+                                if is_proxy_error(&format!("{:#}", e))
+                                    && arbiter.record_failure(*proxy_idx)
+                                {
+                                    warn!(
+                                        "Proxy {:03} banned for {} after repeated failures",
+                                        proxy_idx + 1,
+                                        arbiter.host()
+                                    );
+                                }
+                            }
+                            stats.record_failure(task.name(), duration, &format!("{:#}", e));
+                            self.record_metrics(task.name(), duration, false);
                             use colored::*; // Ensure trait is in scope
                             let raw_err = format!("{:#}", e).replace("\n", " | ");
                             let msg_limit = 125;
@@ -423,7 +515,8 @@ impl Spammer for EvmSpammer {
 
                             if let Some(db) = &self.db {
                                 let _ = db
-                                    .log_task_result(
+                                    .log_chain_task_result(
+                                        Some(self.rise_config.chain_id),
                                         &self.wallet_id,
                                         &format!("{:?}", self.wallet.address()),
                                         task.name(),
@@ -437,6 +530,8 @@ impl Spammer for EvmSpammer {
                     }
                 }
 
+                drop(proxy_slot);
+
                 // Rate limit logic
                 let sleep_ms = if let (Some(min), Some(max)) =
                     (self.rise_config.min_delay_ms, self.rise_config.max_delay_ms)
@@ -456,6 +551,7 @@ impl Spammer for EvmSpammer {
                     _ = sleep(Duration::from_millis(sleep_ms)) => {}
                 }
             }
+            stats.run_duration_ms = run_start.elapsed().as_millis() as u64;
             Ok(stats)
         }
         .instrument(span)
@@ -22,13 +22,14 @@ impl Task<TaskContext> for CheckCodeTask {
         let provider = &ctx.provider;
         let addr: Address = "0x4200000000000000000000000000000000000017".parse()?;
         let code = provider.get_code(addr, None).await?;
-        
+
         debug!("Code at {:?}: {} bytes", addr, code.len());
-        
+
         Ok(TaskResult {
             success: true,
             message: format!("Code len: {}", code.len()),
             tx_hash: None,
+            ..Default::default()
         })
     }
 }
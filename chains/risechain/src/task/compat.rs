@@ -0,0 +1,138 @@
+//! Bridge between the ethers and alloy halves of `TaskContext`
+//!
+//! Tasks are being moved from `ctx.provider`/`ctx.wallet` (ethers) to
+//! `ctx.client` (alloy) one at a time. Shared helpers such as the address
+//! cache and the gas manager still speak ethers types; these conversions let
+//! a migrated task use them until they are ported as well.
+//!
+//! A migrated task sends with `ctx.client.send(..)` and turns the receipt
+//! into its result with [`receipt_result`].
+
+use crate::task::TaskResult;
+use alloy::primitives::{Address, Bytes, B256, U256};
+use alloy::rpc::types::TransactionReceipt;
+use alloy::signers::local::PrivateKeySigner;
+use anyhow::Result;
+use ethers::signers::LocalWallet;
+use ethers::types as eth;
+
+/// Converts an ethers value to its alloy equivalent
+pub trait ToAlloy {
+    type Alloy;
+    fn to_alloy(&self) -> Self::Alloy;
+}
+
+/// Converts an alloy value to its ethers equivalent
+pub trait ToEthers {
+    type Ethers;
+    fn to_ethers(&self) -> Self::Ethers;
+}
+
+impl ToAlloy for eth::Address {
+    type Alloy = Address;
+    fn to_alloy(&self) -> Address {
+        Address::from(self.0)
+    }
+}
+
+impl ToEthers for Address {
+    type Ethers = eth::Address;
+    fn to_ethers(&self) -> eth::Address {
+        eth::Address::from(self.0 .0)
+    }
+}
+
+impl ToAlloy for eth::U256 {
+    type Alloy = U256;
+    fn to_alloy(&self) -> U256 {
+        // Both store four little-endian u64 limbs
+        U256::from_limbs(self.0)
+    }
+}
+
+impl ToEthers for U256 {
+    type Ethers = eth::U256;
+    fn to_ethers(&self) -> eth::U256 {
+        eth::U256(*self.as_limbs())
+    }
+}
+
+impl ToAlloy for eth::H256 {
+    type Alloy = B256;
+    fn to_alloy(&self) -> B256 {
+        B256::from(self.0)
+    }
+}
+
+impl ToEthers for B256 {
+    type Ethers = eth::H256;
+    fn to_ethers(&self) -> eth::H256 {
+        eth::H256(self.0)
+    }
+}
+
+impl ToAlloy for eth::Bytes {
+    type Alloy = Bytes;
+    fn to_alloy(&self) -> Bytes {
+        Bytes::copy_from_slice(self.as_ref())
+    }
+}
+
+impl ToEthers for Bytes {
+    type Ethers = eth::Bytes;
+    fn to_ethers(&self) -> eth::Bytes {
+        eth::Bytes::from(self.to_vec())
+    }
+}
+
+/// The alloy signer for an ethers wallet's key
+pub fn alloy_signer(wallet: &LocalWallet) -> Result<PrivateKeySigner> {
+    let key = B256::from_slice(&wallet.signer().to_bytes());
+    Ok(PrivateKeySigner::from_bytes(&key)?)
+}
+
+/// Task result for a mined transaction, successful when it didn't revert
+pub fn receipt_result(receipt: &TransactionReceipt, message: String) -> TaskResult {
+    TaskResult {
+        success: receipt.status(),
+        message,
+        tx_hash: Some(format!("{:?}", receipt.transaction_hash)),
+        gas_used: Some(receipt.gas_used),
+        block_number: receipt.block_number,
+        ..Default::default()
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    #[test]
+    fn test_round_trips() {
+        let address: eth::Address = "0xf39Fd6e51aad88F6F4ce6aB8827279cffFb92266"
+            .parse()
+            .unwrap();
+        assert_eq!(address.to_alloy().to_ethers(), address);
+        assert_eq!(
+            address.to_alloy().to_string(),
+            "0xf39Fd6e51aad88F6F4ce6aB8827279cffFb92266"
+        );
+
+        let value = eth::U256::from_dec_str("340282366920938463463374607431768211457").unwrap();
+        assert_eq!(value.to_alloy().to_string(), value.to_string());
+        assert_eq!(value.to_alloy().to_ethers(), value);
+
+        let hash = eth::H256::repeat_byte(0xab);
+        assert_eq!(hash.to_alloy().to_ethers(), hash);
+
+        let wallet: LocalWallet =
+            "0xac0974bec39a17e36ba4a6b4d238ff944bacb478cbed5efcae784d7bf4f2ff80"
+                .parse()
+                .unwrap();
+        let signer = alloy_signer(&wallet).unwrap();
+        assert_eq!(
+            signer.address().to_ethers(),
+            ethers::signers::Signer::address(&wallet)
+        );
+    }
+}
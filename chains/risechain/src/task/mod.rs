@@ -1,6 +1,7 @@
 use crate::config::RiseConfig;
 use ethers::prelude::*;
 
+pub mod compat;
 pub mod t01_check_balance;
 pub mod t02_simple_eth_transfer;
 pub mod t03_deploy_contract;
@@ -112,6 +113,9 @@ pub struct TaskContext {
     pub proxy: Option<String>,
     pub db: Option<std::sync::Arc<core_logic::database::DatabaseManager>>,
     pub gas_manager: std::sync::Arc<crate::utils::gas::GasManager>,
+    /// Alloy provider and signer for the same wallet; migrated tasks use
+    /// this instead of `provider` and `wallet`
+    pub client: crate::client::RiseClient,
 }
 
 // Trait alias
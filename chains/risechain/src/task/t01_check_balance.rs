@@ -57,6 +57,7 @@ impl Task<TaskContext> for CheckBalanceTask {
             success: true,
             message: balances_str,
             tx_hash: None,
+            ..Default::default()
         })
     }
 
@@ -1,8 +1,12 @@
+use crate::task::compat::{receipt_result, ToAlloy};
 use crate::task::{Task, TaskContext, TaskResult};
 use crate::utils::address_cache::AddressCache;
+use alloy::network::TransactionBuilder;
+use alloy::primitives::utils::format_ether;
+use alloy::primitives::U256;
+use alloy::rpc::types::TransactionRequest;
 use anyhow::{Context, Result};
 use async_trait::async_trait;
-use ethers::prelude::*;
 
 pub struct SimpleEthTransferTask;
 
@@ -19,26 +23,27 @@ impl Task<TaskContext> for SimpleEthTransferTask {
     }
 
     async fn run(&self, ctx: TaskContext) -> Result<TaskResult> {
-        let provider = &ctx.provider;
-        let wallet = &ctx.wallet;
-        let address = wallet.address();
+        let client = &ctx.client;
 
         // Get random recipient from address cache
-        let recipient = AddressCache::get_random().context("Failed to get random address")?;
+        let recipient = AddressCache::get_random()
+            .context("Failed to get random address")?
+            .to_alloy();
 
         let (max_fee, priority_fee) = ctx.gas_manager.get_fees().await?;
-        let gas_limit = crate::utils::gas::GasManager::LIMIT_TRANSFER;
+        let (max_fee, priority_fee): (u128, u128) =
+            (max_fee.to_alloy().to(), priority_fee.to_alloy().to());
+        let gas_limit = crate::utils::gas::GasManager::LIMIT_TRANSFER.as_u64();
 
         // Check Balance
-        let balance = provider.get_balance(address, None).await?;
+        let balance = client.get_balance().await?;
 
         // Transfer 3% of current balance
-        let amount_wei = (balance * U256::from(3u64) / U256::from(100u64)).as_u64();
-        let amount_eth = ethers::utils::format_units(amount_wei, "ether")
-            .unwrap_or_else(|_| amount_wei.to_string());
+        let amount_wei = balance * U256::from(3u64) / U256::from(100u64);
+        let amount_eth = format_ether(amount_wei);
 
-        let required_val = amount_wei + (gas_limit.as_u64() * max_fee.as_u64()); // Approx check
-        if balance.as_u64() < required_val {
+        let required_val = amount_wei + U256::from(gas_limit) * U256::from(max_fee); // Approx check
+        if balance < required_val {
             return Ok(TaskResult {
                 success: false,
                 message: format!(
@@ -46,28 +51,21 @@ impl Task<TaskContext> for SimpleEthTransferTask {
                     balance, required_val
                 ),
                 tx_hash: None,
+                ..Default::default()
             });
         }
 
-        let tx = Eip1559TransactionRequest::new()
-            .to(recipient)
-            .value(amount_wei)
-            .gas(gas_limit)
-            .max_fee_per_gas(max_fee)
-            .max_priority_fee_per_gas(priority_fee)
-            .from(address);
+        let tx = TransactionRequest::default()
+            .with_to(recipient)
+            .with_value(amount_wei)
+            .with_gas_limit(gas_limit)
+            .with_max_fee_per_gas(max_fee)
+            .with_max_priority_fee_per_gas(priority_fee);
 
-        use ethers::middleware::SignerMiddleware;
-        let client = SignerMiddleware::new(provider.clone(), wallet.clone());
-        let pending_tx = client.send_transaction(tx, None).await?;
-        let receipt = pending_tx
-            .await?
-            .context("Failed to get transaction receipt")?;
-
-        Ok(TaskResult {
-            success: receipt.status == Some(U64::from(1)),
-            message: format!("Sent {} ETH to {:?}", amount_eth, recipient),
-            tx_hash: Some(format!("{:?}", receipt.transaction_hash)),
-        })
+        let receipt = client.send(tx).await?;
+        Ok(receipt_result(
+            &receipt,
+            format!("Sent {} ETH to {:?}", amount_eth, recipient),
+        ))
     }
 }
@@ -30,6 +30,7 @@ impl Task<TaskContext> for DeployContractTask {
                     balance, required
                 ),
                 tx_hash: None,
+                ..Default::default()
             });
         }
 
@@ -63,12 +64,14 @@ impl Task<TaskContext> for DeployContractTask {
                         success: true,
                         message: format!("Deployed Counter at {}", addr_str),
                         tx_hash: Some(format!("{:?}", r.transaction_hash)),
+                        ..Default::default()
                     })
                 } else {
                     Ok(TaskResult {
                         success: false,
                         message: "No contract address in receipt".into(),
                         tx_hash: Some(format!("{:?}", r.transaction_hash)),
+                        ..Default::default()
                     })
                 }
             }
@@ -76,6 +79,7 @@ impl Task<TaskContext> for DeployContractTask {
                 success: false,
                 message: "Transaction dropped".into(),
                 tx_hash: None,
+                ..Default::default()
             }),
         }
     }
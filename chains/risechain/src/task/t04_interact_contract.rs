@@ -24,6 +24,7 @@ impl Task<TaskContext> for InteractContractTask {
                 success: false,
                 message: "DB not available".into(),
                 tx_hash: None,
+                ..Default::default()
             });
         };
 
@@ -32,6 +33,7 @@ impl Task<TaskContext> for InteractContractTask {
                 success: false,
                 message: "No contracts found to interact with".into(),
                 tx_hash: None,
+                ..Default::default()
             });
         }
 
@@ -67,6 +69,7 @@ impl Task<TaskContext> for InteractContractTask {
                     balance, required
                 ),
                 tx_hash: None,
+                ..Default::default()
             });
         }
 
@@ -91,11 +94,13 @@ impl Task<TaskContext> for InteractContractTask {
                 success: r.status == Some(U64::from(1)),
                 message: format!("Called increment() on {}", contract_addr_str),
                 tx_hash: Some(format!("{:?}", r.transaction_hash)),
+                ..Default::default()
             }),
             None => Ok(TaskResult {
                 success: false,
                 message: "Transaction dropped".into(),
                 tx_hash: None,
+                ..Default::default()
             }),
         }
     }
@@ -1,7 +1,10 @@
+use crate::task::compat::{receipt_result, ToAlloy};
 use crate::task::{Task, TaskContext, TaskResult};
+use alloy::network::TransactionBuilder;
+use alloy::primitives::U256;
+use alloy::rpc::types::TransactionRequest;
 use anyhow::Result;
 use async_trait::async_trait;
-use ethers::prelude::*;
 
 pub struct SelfTransferTask;
 
@@ -12,32 +15,15 @@ impl Task<TaskContext> for SelfTransferTask {
         let (max_fee, priority_fee) = ctx.gas_manager.get_fees().await?;
         let gas_limit = crate::utils::gas::GasManager::LIMIT_TRANSFER;
 
-        let tx = Eip1559TransactionRequest::new()
-            .to(ctx.wallet.address())
-            .value(0)
-            .gas(gas_limit)
-            .max_fee_per_gas(max_fee)
-            .max_priority_fee_per_gas(priority_fee)
-            .from(ctx.wallet.address());
+        let tx = TransactionRequest::default()
+            .with_to(ctx.client.address())
+            .with_value(U256::ZERO)
+            .with_gas_limit(gas_limit.as_u64())
+            .with_max_fee_per_gas(max_fee.to_alloy().to())
+            .with_max_priority_fee_per_gas(priority_fee.to_alloy().to());
 
-        use ethers::middleware::SignerMiddleware;
-        let client = SignerMiddleware::new(ctx.provider.clone(), ctx.wallet.clone());
-        let pending_tx = client.send_transaction(tx, None).await?;
-
-        let receipt = pending_tx.await?;
-
-        match receipt {
-            Some(r) => Ok(TaskResult {
-                success: r.status == Some(U64::from(1)),
-                message: "Self-transfer 0 ETH".into(),
-                tx_hash: Some(format!("{:?}", r.transaction_hash)),
-            }),
-            None => Ok(TaskResult {
-                success: false,
-                message: "Transaction dropped".into(),
-                tx_hash: None,
-            }),
-        }
+        let receipt = ctx.client.send(tx).await?;
+        Ok(receipt_result(&receipt, "Self-transfer 0 ETH".into()))
     }
 
     fn name(&self) -> &str {
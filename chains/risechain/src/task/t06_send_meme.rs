@@ -38,6 +38,7 @@ impl Task<TaskContext> for SendMemeTokenTask {
                     wallet_str
                 ),
                 tx_hash: None,
+                ..Default::default()
             });
         }
 
@@ -62,6 +63,7 @@ impl Task<TaskContext> for SendMemeTokenTask {
                 success: false,
                 message: format!("Wallet has 0 balance of token at {:?}", token_address),
                 tx_hash: None,
+                ..Default::default()
             });
         }
 
@@ -72,6 +74,7 @@ impl Task<TaskContext> for SendMemeTokenTask {
                 success: false,
                 message: format!("Balance too low to send 1% (balance: {})", balance),
                 tx_hash: None,
+                ..Default::default()
             });
         }
 
@@ -106,6 +109,7 @@ impl Task<TaskContext> for SendMemeTokenTask {
                 token_address
             ),
             tx_hash: Some(format!("{:?}", receipt.transaction_hash)),
+            ..Default::default()
         })
     }
 }
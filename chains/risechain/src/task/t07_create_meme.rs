@@ -65,6 +65,7 @@ impl Task<TaskContext> for CreateMemeTask {
                     required, balance
                 ),
                 tx_hash: None,
+                ..Default::default()
             });
         }
 
@@ -102,6 +103,7 @@ impl Task<TaskContext> for CreateMemeTask {
                 success: false,
                 message: format!("Deployment failed with status {:?}", receipt.status),
                 tx_hash: Some(format!("{:?}", receipt.transaction_hash)),
+                ..Default::default()
             });
         }
 
@@ -131,6 +133,7 @@ impl Task<TaskContext> for CreateMemeTask {
             success: true,
             message: format!("Created {} ({}) at {:?}", name, symbol, token_address),
             tx_hash: Some(format!("{:?}", receipt.transaction_hash)),
+            ..Default::default()
         })
     }
 }
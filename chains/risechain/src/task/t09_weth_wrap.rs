@@ -65,6 +65,7 @@ impl Task<TaskContext> for WethWrapTask {
             success: receipt.status == Some(U64::from(1)),
             message: format!("Wrapped {} ETH to WETH at {:?}", amount_eth, weth_address),
             tx_hash: Some(format!("{:?}", receipt.transaction_hash)),
+            ..Default::default()
         })
     }
 }
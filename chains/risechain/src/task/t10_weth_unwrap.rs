@@ -48,6 +48,7 @@ impl Task<TaskContext> for WethUnwrapTask {
                 success: false,
                 message: "No WETH to unwrap".to_string(),
                 tx_hash: None,
+                ..Default::default()
             });
         }
 
@@ -79,6 +80,7 @@ impl Task<TaskContext> for WethUnwrapTask {
             success: receipt.status == Some(U64::from(1)),
             message: format!("Unwrapped {} WETH to ETH at {:?}", amount_eth, weth_address),
             tx_hash: Some(format!("{:?}", receipt.transaction_hash)),
+            ..Default::default()
         })
     }
 }
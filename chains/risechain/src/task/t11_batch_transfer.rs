@@ -92,6 +92,7 @@ impl Task<TaskContext> for BatchTransferTask {
                 amount_eth, num_transfers, success_count
             ),
             tx_hash: Some(tx_hashes.join(",")),
+            ..Default::default()
         })
     }
 }
@@ -226,6 +226,7 @@ impl Task<TaskContext> for NftMintTask {
                 nft_address, actual_token_id, token_id
             ),
             tx_hash: Some(format!("{:?}", receipt.transaction_hash)),
+            ..Default::default()
         })
     }
 }
@@ -172,6 +172,7 @@ impl Task<TaskContext> for NftTransferTask {
                 token_id, nft_address, recipient
             ),
             tx_hash: Some(format!("{:?}", transfer_receipt.transaction_hash)),
+            ..Default::default()
         })
     }
 }
@@ -46,6 +46,7 @@ impl Task<TaskContext> for ApproveTokenTask {
                     estimated_cost, balance
                 ),
                 tx_hash: None,
+                ..Default::default()
             });
         }
 
@@ -104,6 +105,7 @@ impl Task<TaskContext> for ApproveTokenTask {
             success,
             message: final_message,
             tx_hash: Some(format!("{:?}", receipt.transaction_hash)),
+            ..Default::default()
         })
     }
 }
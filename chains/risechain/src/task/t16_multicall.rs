@@ -101,6 +101,7 @@ impl Task<TaskContext> for MulticallTask {
                 address
             ),
             tx_hash: Some(format!("{:?}", receipt.transaction_hash)),
+            ..Default::default()
         })
     }
 }
@@ -103,6 +103,7 @@ impl Task<TaskContext> for ReadOracleTask {
             success: true,
             message,
             tx_hash: None,
+            ..Default::default()
         })
     }
 }
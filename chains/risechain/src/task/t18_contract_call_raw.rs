@@ -85,6 +85,7 @@ impl Task<TaskContext> for ContractCallRawTask {
                 amount_eth, recipient
             ),
             tx_hash: Some(format!("{:?}", receipt.transaction_hash)),
+            ..Default::default()
         })
     }
 }
@@ -79,6 +79,7 @@ impl Task<TaskContext> for HighGasLimitTask {
                 amount_eth, recipient
             ),
             tx_hash: Some(format!("{:?}", receipt.transaction_hash)),
+            ..Default::default()
         })
     }
 }
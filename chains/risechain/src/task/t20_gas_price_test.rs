@@ -85,6 +85,7 @@ impl Task<TaskContext> for GasPriceTestTask {
                 amount_eth, recipient, priority_fee_gwei, max_fee_gwei
             ),
             tx_hash: Some(format!("{:?}", receipt.transaction_hash)),
+            ..Default::default()
         })
     }
 }
@@ -97,6 +97,7 @@ impl Task<TaskContext> for Erc1155MintTask {
                 amount, token_id, recipient
             ),
             tx_hash: Some(format!("{:?}", receipt.transaction_hash)),
+            ..Default::default()
         })
     }
 }
@@ -123,6 +123,7 @@ impl Task<TaskContext> for Erc1155TransferTask {
                 amount, token_id, recipient
             ),
             tx_hash: Some(format!("{:?}", receipt.transaction_hash)),
+            ..Default::default()
         })
     }
 }
@@ -68,6 +68,7 @@ impl Task<TaskContext> for TimedInteractionTask {
                 block_number, formatted_time, timestamp_secs, base_fee_eth
             ),
             tx_hash: None,
+            ..Default::default()
         })
     }
 }
@@ -100,6 +100,7 @@ impl Task<TaskContext> for Create2DeployTask {
                 success: false,
                 message: "Deployed event not found in logs".to_string(),
                 tx_hash: Some(format!("{:?}", receipt.transaction_hash)),
+                ..Default::default()
             });
         }
 
@@ -109,6 +110,7 @@ impl Task<TaskContext> for Create2DeployTask {
             success: receipt.status == Some(U64::from(1)),
             message: format!("CREATE2 deployed to {} with salt {}", addr_str, salt_hex),
             tx_hash: Some(format!("{:?}", receipt.transaction_hash)),
+            ..Default::default()
         })
     }
 }
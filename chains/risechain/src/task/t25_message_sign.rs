@@ -46,6 +46,7 @@ impl Task<TaskContext> for MessageSignTask {
                 &signature_hex[..12]
             ),
             tx_hash: None,
+            ..Default::default()
         })
     }
 }
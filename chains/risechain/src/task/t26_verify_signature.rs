@@ -71,6 +71,7 @@ impl Task<TaskContext> for VerifySignatureTask {
                 message, receipt.transaction_hash, is_valid
             ),
             tx_hash: Some(format!("{:?}", receipt.transaction_hash)),
+            ..Default::default()
         })
     }
 }
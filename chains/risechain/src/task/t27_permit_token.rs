@@ -192,6 +192,7 @@ impl Task<TaskContext> for PermitTokenTask {
                 amount_formatted, name, token_nonce, deadline
             ),
             tx_hash: Some(format!("{:?}", receipt.transaction_hash)),
+            ..Default::default()
         })
     }
 }
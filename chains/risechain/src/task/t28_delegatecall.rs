@@ -75,6 +75,7 @@ impl Task<TaskContext> for DelegatecallTask {
                 success: false,
                 message: "Factory deploy transaction failed (reverted)".to_string(),
                 tx_hash: Some(format!("{:?}", receipt.transaction_hash)),
+                ..Default::default()
             });
         }
 
@@ -93,6 +94,7 @@ impl Task<TaskContext> for DelegatecallTask {
                 success: false,
                 message: "Deployed event not found in logs".to_string(),
                 tx_hash: Some(format!("{:?}", receipt.transaction_hash)),
+                ..Default::default()
             });
         }
 
@@ -132,6 +134,7 @@ impl Task<TaskContext> for DelegatecallTask {
                 contract_address, initial_value, new_value
             ),
             tx_hash: Some(format!("{:?}", increment_receipt.transaction_hash)),
+            ..Default::default()
         })
     }
 }
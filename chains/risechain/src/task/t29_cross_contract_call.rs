@@ -98,6 +98,7 @@ impl Task<TaskContext> for CrossContractCallTask {
                 success: false,
                 message: "Deployed event not found in logs".to_string(),
                 tx_hash: Some(format!("{:?}", receipt.transaction_hash)),
+                ..Default::default()
             });
         }
 
@@ -107,6 +108,7 @@ impl Task<TaskContext> for CrossContractCallTask {
                 success: false,
                 message: format!("Deployed contract has no code at {:?}", target_address),
                 tx_hash: Some(format!("{:?}", receipt.transaction_hash)),
+                ..Default::default()
             });
         }
 
@@ -148,6 +150,7 @@ impl Task<TaskContext> for CrossContractCallTask {
                 target_address, initial_value, new_value
             ),
             tx_hash: Some(format!("{:?}", increment_receipt.transaction_hash)),
+            ..Default::default()
         })
     }
 }
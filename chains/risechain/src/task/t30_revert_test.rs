@@ -66,6 +66,7 @@ impl Task<TaskContext> for RevertTestTask {
                             amount_eth, recipient
                         ),
                         tx_hash: Some(format!("{:?}", receipt.transaction_hash)),
+                        ..Default::default()
                     }
                 } else {
                     TaskResult {
@@ -75,6 +76,7 @@ impl Task<TaskContext> for RevertTestTask {
                             amount_eth, recipient
                         ),
                         tx_hash: Some(format!("{:?}", receipt.transaction_hash)),
+                        ..Default::default()
                     }
                 }
             }
@@ -82,16 +84,19 @@ impl Task<TaskContext> for RevertTestTask {
                 success: false,
                 message: "Transaction dropped".into(),
                 tx_hash: None,
+                ..Default::default()
             },
             (_, Err(e)) => TaskResult {
                 success: true,
                 message: format!("Transaction reverted/error: {}", e),
                 tx_hash: None,
+                ..Default::default()
             },
             (Err(e), _) => TaskResult {
                 success: true,
                 message: format!("Transaction failed as expected: {}", e),
                 tx_hash: None,
+                ..Default::default()
             },
         };
 
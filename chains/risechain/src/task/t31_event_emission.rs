@@ -91,6 +91,7 @@ impl Task<TaskContext> for EventEmissionTask {
                 amount_eth, events_found, verified_events
             ),
             tx_hash: Some(format!("{:?}", receipt.transaction_hash)),
+            ..Default::default()
         })
     }
 }
@@ -80,6 +80,7 @@ impl Task<TaskContext> for EthWithDataTask {
                 amount_eth, recipient, data_hex
             ),
             tx_hash: Some(format!("{:?}", receipt.transaction_hash)),
+            ..Default::default()
         })
     }
 }
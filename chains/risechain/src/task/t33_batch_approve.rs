@@ -94,6 +94,7 @@ impl Task<TaskContext> for BatchApproveTask {
                 tokens.len()
             ),
             tx_hash: Some(tx_hashes.join(",")),
+            ..Default::default()
         })
     }
 }
@@ -90,6 +90,7 @@ impl Task<TaskContext> for RoleBasedAccessTask {
             success: true,
             message: messages.join("\n"),
             tx_hash: None,
+            ..Default::default()
         })
     }
 }
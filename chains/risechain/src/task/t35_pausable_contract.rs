@@ -111,6 +111,7 @@ impl Task<TaskContext> for PausableContractTask {
             success: true,
             message: messages.join("\n"),
             tx_hash: None,
+            ..Default::default()
         })
     }
 }
@@ -99,6 +99,7 @@ impl Task<TaskContext> for Create2FactoryTask {
                 success: false,
                 message: "Deployed event not found in logs".to_string(),
                 tx_hash: Some(format!("{:?}", receipt.transaction_hash)),
+                ..Default::default()
             });
         }
 
@@ -109,6 +110,7 @@ impl Task<TaskContext> for Create2FactoryTask {
                 contract_address, salt_hex
             ),
             tx_hash: Some(format!("{:?}", receipt.transaction_hash)),
+            ..Default::default()
         })
     }
 }
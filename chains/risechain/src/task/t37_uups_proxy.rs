@@ -104,6 +104,7 @@ impl Task<TaskContext> for UUPSProxyTask {
                 implementation_address, current_value, version
             ),
             tx_hash: Some(format!("{:?}", impl_receipt.transaction_hash)),
+            ..Default::default()
         })
     }
 }
@@ -109,6 +109,7 @@ impl Task<TaskContext> for TransparentProxyTask {
                 implementation_address, admin, current_value
             ),
             tx_hash: Some(format!("{:?}", impl_receipt.transaction_hash)),
+            ..Default::default()
         })
     }
 }
@@ -82,6 +82,7 @@ impl Task<TaskContext> for UniswapV2SwapTask {
                 success: true,
                 message: "No WBTC balance to swap".to_string(),
                 tx_hash: None,
+                ..Default::default()
             });
         }
 
@@ -124,6 +125,7 @@ impl Task<TaskContext> for UniswapV2SwapTask {
                 success: false,
                 message: "WETH Contract does not exist at 0x4200...06".to_string(),
                 tx_hash: None,
+                ..Default::default()
             });
         }
 
@@ -182,6 +184,7 @@ impl Task<TaskContext> for UniswapV2SwapTask {
                         success: false,
                         message: "No WBTC to swap".to_string(),
                         tx_hash: None,
+                        ..Default::default()
                     });
                 }
 
@@ -308,6 +311,7 @@ impl Task<TaskContext> for UniswapV2SwapTask {
                     success: true,
                     message: format!("Swapped {} WBTC -> {} WETH -> ETH", amount_in, amount_out),
                     tx_hash: Some(format!("{:?}", receipt.transaction_hash)),
+                    ..Default::default()
                 });
             }
             Err(_) => {
@@ -372,6 +376,7 @@ impl Task<TaskContext> for UniswapV2SwapTask {
                 messages.join(" | ")
             ),
             tx_hash: Some(format!("{:?}", receipt.transaction_hash)),
+            ..Default::default()
         })
     }
 }
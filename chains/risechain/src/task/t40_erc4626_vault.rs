@@ -49,6 +49,7 @@ impl Task<TaskContext> for ERC4626VaultTask {
                 vault_code_len, weth_bal
             ),
             tx_hash: None,
+            ..Default::default()
         })
     }
 }
@@ -58,6 +58,7 @@ impl Task<TaskContext> for FlashLoanTestTask {
             success: true,
             message: format!("Flash Loan Check: {}", summary_parts.join(" | ")),
             tx_hash: None,
+            ..Default::default()
         })
     }
 }
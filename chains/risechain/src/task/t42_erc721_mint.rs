@@ -103,6 +103,7 @@ impl Task<TaskContext> for ERC721MintTask {
                 token_uri, total_before, total_after
             ),
             tx_hash: Some(format!("{:?}", receipt.transaction_hash)),
+            ..Default::default()
         })
     }
 }
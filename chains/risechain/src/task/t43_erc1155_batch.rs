@@ -100,6 +100,7 @@ impl Task<TaskContext> for ERC1155BatchTask {
                 total_minted
             ),
             tx_hash: Some(format!("{:?}", receipt.transaction_hash)),
+            ..Default::default()
         })
     }
 }
@@ -124,6 +124,7 @@ impl Task<TaskContext> for StoragePatternTask {
                 contract_address, packed_after, value_a, value_b
             ),
             tx_hash: Some(format!("{:?}", set_receipt.transaction_hash)),
+            ..Default::default()
         })
     }
 }
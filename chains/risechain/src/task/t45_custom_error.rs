@@ -73,6 +73,7 @@ impl Task<TaskContext> for CustomErrorTestTask {
                 contract_address, data
             ),
             tx_hash: Some(format!("{:?}", deploy_receipt.transaction_hash)),
+            ..Default::default()
         })
     }
 }
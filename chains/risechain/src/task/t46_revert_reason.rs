@@ -75,6 +75,7 @@ impl Task<TaskContext> for RevertWithReasonTask {
                 contract_address, state
             ),
             tx_hash: Some(format!("{:?}", deploy_receipt.transaction_hash)),
+            ..Default::default()
         })
     }
 }
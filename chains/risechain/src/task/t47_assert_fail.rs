@@ -75,6 +75,7 @@ impl Task<TaskContext> for AssertFailTask {
                 contract_address, value
             ),
             tx_hash: Some(format!("{:?}", deploy_receipt.transaction_hash)),
+            ..Default::default()
         })
     }
 }
@@ -90,6 +90,7 @@ impl Task<TaskContext> for AnonymousEventTask {
                 contract_address, anonymous_count
             ),
             tx_hash: Some(format!("{:?}", emit_receipt.transaction_hash)),
+            ..Default::default()
         })
     }
 }
@@ -99,6 +99,7 @@ impl Task<TaskContext> for IndexedTopicsTask {
                 contract_address, indexed_count
             ),
             tx_hash: Some(format!("{:?}", emit_receipt.transaction_hash)),
+            ..Default::default()
         })
     }
 }
@@ -99,6 +99,7 @@ impl Task<TaskContext> for LargeEventDataTask {
                 contract_address, event_data_size
             ),
             tx_hash: Some(format!("{:?}", emit_receipt.transaction_hash)),
+            ..Default::default()
         })
     }
 }
@@ -89,6 +89,7 @@ impl Task<TaskContext> for MemoryExpansionTask {
                 large_array.len()
             ),
             tx_hash: Some(format!("{:?}", process_receipt.transaction_hash)),
+            ..Default::default()
         })
     }
 }
@@ -94,6 +94,7 @@ impl Task<TaskContext> for CalldataSizeTask {
                 contract_address, calldata_size
             ),
             tx_hash: Some(format!("{:?}", store_receipt.transaction_hash)),
+            ..Default::default()
         })
     }
 }
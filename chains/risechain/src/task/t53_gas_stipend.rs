@@ -81,6 +81,7 @@ impl Task<TaskContext> for GasStipendTask {
                 contract_address, gas_amount
             ),
             tx_hash: Some(format!("{:?}", call_receipt.transaction_hash)),
+            ..Default::default()
         })
     }
 }
@@ -68,6 +68,7 @@ impl Task<TaskContext> for GasPriceZeroTask {
                 amount_eth, priority_fee_display, receipt.transaction_hash
             ),
             tx_hash: Some(format!("{:?}", receipt.transaction_hash)),
+            ..Default::default()
         })
     }
 }
@@ -70,6 +70,7 @@ impl Task<TaskContext> for BlockHashUsageTask {
                 current_block, target_block_num, block_hash.is_some(), random_number
             ),
             tx_hash: None,
+            ..Default::default()
         })
     }
 }
@@ -282,6 +282,7 @@ impl Task<TaskContext> for Eip7702ExploreTask {
             success: true,
             message: messages.join("\n"),
             tx_hash: None,
+            ..Default::default()
         })
     }
 }
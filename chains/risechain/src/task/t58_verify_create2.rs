@@ -100,12 +100,14 @@ impl Task<TaskContext> for VerifyCreate2Task {
                     success: true,
                     message: format!("CREATE2 Opcode WORKS! Deployed at {:?}", deployed_addr),
                     tx_hash: Some(format!("{:?}", call_receipt.transaction_hash)),
+                    ..Default::default()
                 });
             } else {
                 return Ok(TaskResult {
                     success: false,
                     message: "CREATE2 succeeded but no code found at address".to_string(),
                     tx_hash: Some(format!("{:?}", call_receipt.transaction_hash)),
+                    ..Default::default()
                 });
             }
         } else {
@@ -113,6 +115,7 @@ impl Task<TaskContext> for VerifyCreate2Task {
                 success: false,
                 message: "CREATE2 transaction failed or reverted".to_string(),
                 tx_hash: Some(format!("{:?}", call_receipt.transaction_hash)),
+                ..Default::default()
             });
         }
     }
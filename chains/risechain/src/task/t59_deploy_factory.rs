@@ -53,6 +53,7 @@ impl Task<TaskContext> for DeployFactoryTask {
             success: true,
             message: format!("Factory Deployed: {:?}", factory_address),
             tx_hash: Some(format!("{:?}", receipt.transaction_hash)),
+            ..Default::default()
         })
     }
 }
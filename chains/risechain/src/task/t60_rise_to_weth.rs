@@ -76,6 +76,7 @@ impl Task<TaskContext> for RiseToWethTask {
                 success: false,
                 message: "No RISE to swap".into(),
                 tx_hash: None,
+                ..Default::default()
             });
         }
 
@@ -206,6 +207,7 @@ impl Task<TaskContext> for RiseToWethTask {
                 success: true,
                 message: "Swapped RISE -> ETH via Router".into(),
                 tx_hash: Some(format!("{:?}", receipt.transaction_hash)),
+                ..Default::default()
             });
         }
 
@@ -251,6 +253,7 @@ impl Task<TaskContext> for RiseToWethTask {
             success: true,
             message: "Swapped RISE -> WETH -> ETH".into(),
             tx_hash: None,
+            ..Default::default()
         })
     }
 }
@@ -110,6 +110,7 @@ impl Task<TaskContext> for TestCreate2Task {
             success: true,
             message: messages.join("\n"),
             tx_hash: None,
+            ..Default::default()
         })
     }
 }
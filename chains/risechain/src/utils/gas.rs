@@ -1,11 +1,13 @@
 use anyhow::Result;
-use core_logic::GasConfig;
+use async_trait::async_trait;
+use core_logic::{FeeSample, Fees, GasConfig, GasEstimator};
 use ethers::prelude::*;
 use std::sync::Arc;
 
+/// RISE fee policy on top of [`core_logic::GasManager`], in ethers types
 #[derive(Clone, Debug)]
 pub struct GasManager {
-    config: GasConfig,
+    policy: core_logic::GasManager,
     provider: Arc<Provider<Http>>,
 }
 
@@ -19,114 +21,97 @@ impl GasManager {
 
     pub fn new(provider: Arc<Provider<Http>>) -> Self {
         Self {
-            config: GasConfig::new()
-                .with_max_fee(0.000000009) // 9 Wei
-                .with_priority_fee(0.000000001), // 1 Wei
+            policy: core_logic::GasManager::new(
+                GasConfig::new()
+                    .with_max_fee(Self::MAX_FEE_GWEI_DEFAULT) // 9 Wei
+                    .with_priority_fee(Self::PRIORITY_FEE_GWEI_DEFAULT) // 1 Wei
+                    // Max fee stays base fee plus tip, without headroom
+                    .with_base_fee_headroom(0),
+            ),
             provider,
         }
     }
 
     pub fn with_config(mut self, config: GasConfig) -> Self {
-        self.config = config;
+        self.policy = core_logic::GasManager::new(config);
         self
     }
 
-    pub async fn get_fees(&self) -> Result<(U256, U256)> {
-        // 1. Get Base Fee from latest block for calculation
-        let block = self
-            .provider
-            .get_block(BlockNumber::Latest)
-            .await?
-            .ok_or_else(|| anyhow::anyhow!("Failed to get latest block"))?;
-
-        let base_fee = block
-            .base_fee_per_gas
-            .ok_or_else(|| anyhow::anyhow!("Base fee missing in block"))?;
-
-        // 2. Try to estimate fees from oracle (checks history aka "last block")
-        let (mut est_max, mut est_prio) = match self.provider.estimate_eip1559_fees(None).await {
-            Ok(fees) => fees,
-            Err(_) => {
-                // Fallback to config if estimation fails
-                let prio = parse_units(self.config.priority_gwei(), "gwei")?.into();
-                (base_fee + prio, prio)
-            }
-        };
-
-        // 3. Clamp values to User Config
-        let config_max: U256 = parse_units(self.config.max_gwei(), "gwei")?.into();
-        let _config_prio: U256 = parse_units(self.config.priority_gwei(), "gwei")?.into();
-
-        // Enforce Max Cap
-        if est_max > config_max {
-            est_max = config_max;
-        }
-
-        // For priority, we generally trust the oracle but ensure it's not insane?
-        // Actually, user wants "very low" fees, so we should arguably clamp priority too if it exceeds our config
-        // But usually priority < max.
-        // Let's ensure priority isn't > max_fee (logic error)
-        if est_prio > est_max {
-            est_prio = est_max;
-        }
-
-        // Ensure we at least pay the configured priority if the oracle is too low?
-        // No, user said "smart", implying dynamic. If network is cheap, pay cheap.
-
-        Ok((est_max, est_prio))
-    }
-
-    pub async fn get_priority_fee_adjusted(&self, base_fee: U256) -> Result<U256> {
-        let block = self.provider.get_block(BlockNumber::Latest).await?;
-        let Some(block) = block else {
-            return Ok(parse_units(self.config.priority_gwei(), "gwei")?.into());
-        };
-
-        let Some(parent_base_fee) = block.base_fee_per_gas else {
-            return Ok(parse_units(self.config.priority_gwei(), "gwei")?.into());
-        };
-
-        let base_fee_change = if parent_base_fee > U256::zero() {
-            (base_fee - parent_base_fee) * 100 / parent_base_fee
-        } else {
-            U256::zero()
-        };
-
-        let priority_fee = if base_fee_change > U256::from(10) {
-            parse_units(self.config.priority_gwei() * 2.0, "gwei")?.into()
-        } else if base_fee_change > U256::from(5) {
-            parse_units(self.config.priority_gwei() * 1.5, "gwei")?.into()
-        } else {
-            parse_units(self.config.priority_gwei(), "gwei")?.into()
-        };
-
-        Ok(priority_fee)
+    /// The shared policy, for bumping a stuck transaction's fees
+    pub fn policy(&self) -> &core_logic::GasManager {
+        &self.policy
     }
 
-    pub fn get_max_fee(&self, base_fee: U256) -> U256 {
-        let priority_fee_wei: U256 =
-            parse_units(self.config.priority_gwei(), "gwei").unwrap_or(U256::zero());
-        let max_fee_wei = base_fee + priority_fee_wei;
-        let max_configured_wei: U256 =
-            parse_units(self.config.max_gwei(), "gwei").unwrap_or(U256::zero());
-
-        max_fee_wei.min(max_configured_wei)
+    /// `(max_fee_per_gas, max_priority_fee_per_gas)` for a type 2 transaction
+    ///
+    /// Where the policy prices legacy, both are the gas price, which a type 2
+    /// transaction then pays outright.
+    pub async fn get_fees(&self) -> Result<(U256, U256)> {
+        Ok(match self.policy.fees(self).await? {
+            Fees::Eip1559 {
+                max_fee,
+                priority_fee,
+            } => (U256::from(max_fee), U256::from(priority_fee)),
+            Fees::Legacy { gas_price } => (U256::from(gas_price), U256::from(gas_price)),
+        })
     }
 
     pub fn limit_deploy(&self) -> U256 {
-        U256([self.config.limit_deploy(), 0, 0, 0])
+        U256([self.policy.config().limit_deploy(), 0, 0, 0])
     }
 
     pub fn limit_transfer(&self) -> U256 {
-        U256([self.config.limit_transfer(), 0, 0, 0])
+        U256([self.policy.config().limit_transfer(), 0, 0, 0])
     }
 
     pub fn limit_counter_interact(&self) -> U256 {
-        U256([self.config.limit_counter_interact(), 0, 0, 0])
+        U256([self.policy.config().limit_counter_interact(), 0, 0, 0])
     }
 
     pub fn limit_send_meme(&self) -> U256 {
-        U256([self.config.limit_send_meme(), 0, 0, 0])
+        U256([self.policy.config().limit_send_meme(), 0, 0, 0])
+    }
+}
+
+#[async_trait]
+impl GasEstimator for GasManager {
+    type Error = anyhow::Error;
+
+    async fn estimate_gas(&self, data: &[u8], to: Option<&str>) -> Result<u64> {
+        let mut tx = Eip1559TransactionRequest::new().data(data.to_vec());
+        if let Some(to) = to {
+            tx = tx.to(to.parse::<Address>()?);
+        }
+        Ok(self.provider.estimate_gas(&tx.into(), None).await?.as_u64())
+    }
+
+    async fn get_gas_price(&self) -> Result<u64> {
+        Ok(self.provider.get_gas_price().await?.as_u64())
+    }
+
+    async fn fee_sample(&self, percentile: f64) -> Result<FeeSample> {
+        let gas_price = self.provider.get_gas_price().await?.as_u128();
+        let Ok(history) = self
+            .provider
+            .fee_history(1u64, BlockNumber::Latest, &[percentile])
+            .await
+        else {
+            return Ok(FeeSample::legacy(gas_price));
+        };
+
+        Ok(FeeSample {
+            gas_price,
+            base_fee: history
+                .base_fee_per_gas
+                .last()
+                .filter(|fee| !fee.is_zero())
+                .map(U256::as_u128),
+            priority_fee: history
+                .reward
+                .last()
+                .and_then(|rewards| rewards.first())
+                .map(U256::as_u128),
+        })
     }
 }
 
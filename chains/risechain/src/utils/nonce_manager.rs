@@ -1,14 +1,15 @@
 use anyhow::{Context, Result};
+use async_trait::async_trait;
+use core_logic::{NonceManagement, NonceManager, NonceSource};
 use ethers::prelude::*;
-// use std::sync::atomic::{AtomicU64, Ordering}; // Unused
 use std::sync::Arc;
-use tokio::sync::Mutex;
 
+/// Nonces for one wallet, cached in the shared [`core_logic::NonceManager`]
 #[derive(Clone, Debug)]
 pub struct SimpleNonceManager {
     provider: Arc<Provider<Http>>,
     address: Address,
-    current_nonce: Arc<Mutex<Option<U256>>>,
+    nonces: Arc<NonceManager<Address>>,
 }
 
 impl SimpleNonceManager {
@@ -16,7 +17,7 @@ impl SimpleNonceManager {
         Self {
             provider,
             address,
-            current_nonce: Arc::new(Mutex::new(None)),
+            nonces: Arc::new(NonceManager::new()),
         }
     }
 
@@ -24,34 +25,31 @@ impl SimpleNonceManager {
     /// If initialized, returns the local counter and increments it.
     /// If not, fetches from pending state.
     pub async fn next(&self) -> Result<U256> {
-        let mut nonce_guard = self.current_nonce.lock().await;
-
-        if let Some(nonce) = *nonce_guard {
-            let next = nonce + 1;
-            *nonce_guard = Some(next);
-            Ok(nonce)
-        } else {
-            // Fetch from chain
-            let nonce = self
-                .provider
-                .get_transaction_count(self.address, Some(BlockNumber::Pending.into()))
-                .await
-                .context("Failed to fetch initial nonce")?;
-
-            *nonce_guard = Some(nonce + 1);
-            Ok(nonce)
-        }
+        let nonce = self.nonces.next_or_fetch(self.address, self).await?;
+        Ok(U256::from(nonce))
     }
 
     /// Reset the local nonce to the on-chain value (useful on errors)
     pub async fn resync(&self) -> Result<()> {
-        let mut nonce_guard = self.current_nonce.lock().await;
         let nonce = self
-            .provider
-            .get_transaction_count(self.address, Some(BlockNumber::Pending.into()))
+            .next_nonce(self.address)
             .await
             .context("Failed to resync nonce")?;
-        *nonce_guard = Some(nonce);
+        self.nonces.set(self.address, nonce).await;
         Ok(())
     }
 }
+
+#[async_trait]
+impl NonceSource<Address> for SimpleNonceManager {
+    type Error = anyhow::Error;
+
+    async fn next_nonce(&self, address: Address) -> Result<u64> {
+        let nonce = self
+            .provider
+            .get_transaction_count(address, Some(BlockNumber::Pending.into()))
+            .await
+            .context("Failed to fetch nonce")?;
+        Ok(nonce.as_u64())
+    }
+}
@@ -0,0 +1,82 @@
+use anyhow::Result;
+use config::{Config, File};
+use core_logic::RampUp;
+use serde::Deserialize;
+use std::time::Duration;
+
+/// Gas budget of one transaction when unset, in MIST (0.01 SUI)
+const DEFAULT_GAS_BUDGET: u64 = 10_000_000;
+const DEFAULT_TRANSFER_AMOUNT: u64 = 1_000;
+const DEFAULT_SPLIT_AMOUNT: u64 = 1_000_000;
+
+#[derive(Debug, Deserialize, Clone)]
+#[serde(deny_unknown_fields)]
+pub struct SuiConfig {
+    pub rpc_url: String,
+    /// Tags results in the database; Sui itself has no numeric chain id
+    pub chain_id: Option<u64>,
+    pub worker_amount: Option<usize>,
+    pub min_delay_ms: Option<u64>,
+    pub max_delay_ms: Option<u64>,
+    /// Gas budget of each transaction, in MIST
+    pub gas_budget: Option<u64>,
+    /// MIST sent by `01_transferSui`
+    pub transfer_amount: Option<u64>,
+    /// Size of each new coin made by `02_splitCoin` and `04_moveCall`, in MIST
+    pub split_amount: Option<u64>,
+    /// Never prompt; same as `--non-interactive`
+    #[serde(default)]
+    pub non_interactive: bool,
+    /// File whose first line is the wallet password
+    #[serde(default)]
+    pub password_file: Option<String>,
+    /// Workers started per step; unset starts all at once
+    pub ramp_up_batch: Option<usize>,
+    /// Seconds between ramp-up steps
+    pub ramp_up_interval_secs: Option<u64>,
+    /// Failure percentage above which the ramp-up waits
+    pub ramp_up_max_error_rate: Option<f64>,
+}
+
+impl SuiConfig {
+    pub fn load(path: &str) -> Result<Self> {
+        let settings = Config::builder()
+            .add_source(File::with_name(path))
+            .build()?;
+
+        settings.try_deserialize().map_err(|e| {
+            let message = e.to_string();
+            let content = std::fs::read_to_string(path)
+                .or_else(|_| std::fs::read_to_string(format!("{}.toml", path)))
+                .unwrap_or_default();
+            match core_logic::config::unknown_key_error(&message, path, &content, None) {
+                Some(unknown) => anyhow::anyhow!(unknown),
+                None => anyhow::anyhow!(e),
+            }
+        })
+    }
+
+    pub fn ramp_up(&self) -> RampUp {
+        let defaults = RampUp::default();
+        RampUp {
+            batch: self.ramp_up_batch.unwrap_or(defaults.batch),
+            interval: self
+                .ramp_up_interval_secs
+                .map_or(defaults.interval, Duration::from_secs),
+            max_error_rate: self.ramp_up_max_error_rate,
+            ..defaults
+        }
+    }
+
+    pub fn gas_budget(&self) -> u64 {
+        self.gas_budget.unwrap_or(DEFAULT_GAS_BUDGET)
+    }
+
+    pub fn transfer_amount(&self) -> u64 {
+        self.transfer_amount.unwrap_or(DEFAULT_TRANSFER_AMOUNT)
+    }
+
+    pub fn split_amount(&self) -> u64 {
+        self.split_amount.unwrap_or(DEFAULT_SPLIT_AMOUNT)
+    }
+}
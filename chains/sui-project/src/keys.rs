@@ -0,0 +1,148 @@
+//! Sui Ed25519 keys and transaction signing
+//!
+//! Wallets store `sui_private_key` either as a `suiprivkey1...` bech32
+//! string (what `sui keytool export` prints) or as base64/hex bytes, with or
+//! without the leading scheme flag. Only Ed25519 keys are supported.
+//!
+//! A transaction is signed over `blake2b256(intent || tx_bytes)` and sent as
+//! `flag || signature || public key`, base64 encoded.
+
+use anyhow::{anyhow, bail, Context, Result};
+use base64::{engine::general_purpose::STANDARD, Engine};
+use bech32::FromBase32;
+use blake2::digest::consts::U32;
+use blake2::{Blake2b, Digest};
+use ed25519_dalek::{Signer, SigningKey};
+
+type Blake2b256 = Blake2b<U32>;
+
+/// Signature scheme flag of Ed25519 keys, addresses and signatures
+const ED25519_FLAG: u8 = 0x00;
+/// Human readable part of bech32 encoded private keys
+const PRIVATE_KEY_HRP: &str = "suiprivkey";
+/// Intent of a transaction: TransactionData, version 0, Sui app
+const TRANSACTION_INTENT: [u8; 3] = [0, 0, 0];
+
+pub struct SuiKeypair {
+    signing: SigningKey,
+    address: String,
+}
+
+impl SuiKeypair {
+    /// Parses a stored `sui_private_key`
+    pub fn from_private_key(key: &str) -> Result<Self> {
+        let key = key.trim();
+        let bytes = if key.starts_with(PRIVATE_KEY_HRP) {
+            let (hrp, data, _) = bech32::decode(key).context("invalid bech32 private key")?;
+            if hrp != PRIVATE_KEY_HRP {
+                bail!("unexpected bech32 prefix {}", hrp);
+            }
+            Vec::<u8>::from_base32(&data)?
+        } else {
+            let unprefixed = key.strip_prefix("0x").unwrap_or(key);
+            if unprefixed.len().is_multiple_of(2)
+                && unprefixed.chars().all(|c| c.is_ascii_hexdigit())
+            {
+                hex::decode(unprefixed)?
+            } else {
+                STANDARD
+                    .decode(key)
+                    .context("private key is neither bech32, hex nor base64")?
+            }
+        };
+
+        let secret: [u8; 32] = match bytes.as_slice() {
+            [ED25519_FLAG, secret @ ..] if secret.len() == 32 => secret.try_into()?,
+            [flag, rest @ ..] if rest.len() == 32 => {
+                bail!(
+                    "unsupported key scheme flag {}, only Ed25519 keys work",
+                    flag
+                )
+            }
+            secret => secret
+                .try_into()
+                .map_err(|_| anyhow!("expected a 32 byte key, got {} bytes", secret.len()))?,
+        };
+
+        let signing = SigningKey::from_bytes(&secret);
+        let mut hasher = Blake2b256::new();
+        hasher.update([ED25519_FLAG]);
+        hasher.update(signing.verifying_key().as_bytes());
+        let address = format!("0x{}", hex::encode(hasher.finalize()));
+
+        Ok(Self { signing, address })
+    }
+
+    /// The `0x`-prefixed 32 byte account address
+    pub fn address(&self) -> &str {
+        &self.address
+    }
+
+    /// Signs BCS `TransactionData` as returned by the node, giving the
+    /// serialized signature `sui_executeTransactionBlock` expects
+    pub fn sign_transaction(&self, tx_bytes: &[u8]) -> String {
+        let mut hasher = Blake2b256::new();
+        hasher.update(TRANSACTION_INTENT);
+        hasher.update(tx_bytes);
+        let signature = self.signing.sign(&hasher.finalize());
+
+        let mut serialized = Vec::with_capacity(1 + 64 + 32);
+        serialized.push(ED25519_FLAG);
+        serialized.extend_from_slice(&signature.to_bytes());
+        serialized.extend_from_slice(self.signing.verifying_key().as_bytes());
+        STANDARD.encode(serialized)
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use bech32::{ToBase32, Variant};
+    use ed25519_dalek::{Signature, Verifier};
+
+    const SECRET: [u8; 32] = [7; 32];
+
+    #[test]
+    fn test_key_formats_agree() {
+        let mut flagged = vec![ED25519_FLAG];
+        flagged.extend_from_slice(&SECRET);
+
+        let from_hex = SuiKeypair::from_private_key(&format!("0x{}", hex::encode(SECRET))).unwrap();
+        let from_base64 = SuiKeypair::from_private_key(&STANDARD.encode(&flagged)).unwrap();
+        let bech32 = bech32::encode(PRIVATE_KEY_HRP, flagged.to_base32(), Variant::Bech32).unwrap();
+        let from_bech32 = SuiKeypair::from_private_key(&bech32).unwrap();
+
+        assert_eq!(from_hex.address().len(), 66);
+        assert_eq!(from_hex.address(), from_base64.address());
+        assert_eq!(from_hex.address(), from_bech32.address());
+
+        // Secp256k1 (flag 1) keys are rejected rather than misread
+        let mut secp = vec![1];
+        secp.extend_from_slice(&SECRET);
+        assert!(SuiKeypair::from_private_key(&STANDARD.encode(secp)).is_err());
+    }
+
+    #[test]
+    fn test_transaction_signature() {
+        let keypair = SuiKeypair::from_private_key(&hex::encode(SECRET)).unwrap();
+        let tx_bytes = b"transaction data";
+
+        let serialized = STANDARD.decode(keypair.sign_transaction(tx_bytes)).unwrap();
+        assert_eq!(serialized.len(), 97);
+        assert_eq!(serialized[0], ED25519_FLAG);
+        assert_eq!(
+            &serialized[65..],
+            keypair.signing.verifying_key().as_bytes()
+        );
+
+        let mut hasher = Blake2b256::new();
+        hasher.update(TRANSACTION_INTENT);
+        hasher.update(tx_bytes);
+        let signature = Signature::from_bytes(serialized[1..65].try_into().unwrap());
+        assert!(keypair
+            .signing
+            .verifying_key()
+            .verify(&hasher.finalize(), &signature)
+            .is_ok());
+    }
+}
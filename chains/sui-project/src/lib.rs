@@ -0,0 +1,6 @@
+pub mod config;
+pub mod keys;
+pub mod rpc;
+pub mod runner;
+pub mod spammer;
+pub mod task;
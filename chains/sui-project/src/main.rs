@@ -0,0 +1,127 @@
+use sui_project::config;
+
+use anyhow::Result;
+use clap::Parser;
+use config::SuiConfig;
+use core_logic::{setup_logger, WorkerRunner};
+use dialoguer::{theme::ColorfulTheme, Password};
+use dotenv::dotenv;
+use std::path::Path;
+use tracing::{error, info};
+
+#[derive(Parser, Debug)]
+#[command(author, version, about, long_about = None)]
+struct Args {
+    #[arg(short, long, default_value = "chains/sui-project/config.toml")]
+    config: String,
+    /// Never prompt; fail with an error when input would be needed
+    #[arg(long)]
+    non_interactive: bool,
+    /// File whose first line is the wallet password
+    #[arg(long)]
+    password_file: Option<String>,
+}
+
+#[tokio::main]
+async fn main() -> Result<()> {
+    let _log_guard = setup_logger();
+    dotenv().ok();
+
+    let args = Args::parse();
+    info!("Loading config from: {}", args.config);
+
+    let config = match SuiConfig::load(&args.config) {
+        Ok(c) => c,
+        Err(e) if core_logic::non_interactive(args.non_interactive, false) => {
+            return Err(e.context("Failed to load config"))
+        }
+        Err(e) => {
+            error!("Failed to load config: {}", e);
+            return Ok(());
+        }
+    };
+    let headless = core_logic::non_interactive(args.non_interactive, config.non_interactive);
+
+    info!("Configuration loaded for {}", config.rpc_url);
+
+    // EVM, SOL and SUI keys share the wallet files
+    let manager = core_logic::WalletManager::new()?;
+    let total_wallets = manager.count();
+
+    info!("Found {} wallet files.", total_wallets);
+
+    // Get password (file or env var first, then interactive fallback)
+    let wallet_password = if total_wallets > 0 {
+        let password_file = args
+            .password_file
+            .as_deref()
+            .or(config.password_file.as_deref());
+        let mut password = core_logic::wallet_password(password_file.map(Path::new))?;
+
+        if let Err(e) = manager
+            .get_wallet(0, password.as_deref().map(String::as_str))
+            .await
+        {
+            if headless {
+                return Err(e.context(format!(
+                    "Could not unlock wallets without a prompt; {}",
+                    core_logic::password_sources_hint()
+                )));
+            }
+            if password.is_none() {
+                error!("No wallet password configured.");
+            } else {
+                error!("Wallet decryption failed with provided password.");
+            }
+
+            let input = Password::with_theme(&ColorfulTheme::default())
+                .with_prompt("Enter wallet password")
+                .interact()?;
+            if let Err(e) = manager.get_wallet(0, Some(&input)).await {
+                error!("Interactive password also failed: {}", e);
+                return Ok(());
+            }
+            info!("Interactive password validated successfully.");
+            password = Some(input.into());
+        } else {
+            info!("Wallet password validated successfully.");
+        }
+
+        password
+    } else if headless {
+        anyhow::bail!("No wallets found in wallet-json/ or pv.txt");
+    } else {
+        None
+    };
+
+    // Load proxies
+    let proxies = core_logic::ProxyManager::load_proxies()?;
+    if !proxies.is_empty() {
+        info!("Loaded {} proxies for rotation.", proxies.len());
+    }
+
+    let db_manager = core_logic::database::DatabaseManager::new("sui.db").await?;
+    let db_arc = std::sync::Arc::new(db_manager);
+
+    let max_workers = config.worker_amount.unwrap_or(total_wallets);
+    let spammers = sui_project::runner::build_spammers(
+        &config,
+        &manager,
+        wallet_password.as_deref().map(String::as_str),
+        &proxies,
+        Some(db_arc),
+        max_workers,
+    )
+    .await?;
+    if spammers.is_empty() {
+        anyhow::bail!("No wallet has a usable sui_private_key");
+    }
+    info!("Starting {} Sui workers", spammers.len());
+
+    WorkerRunner::new(spammers)
+        .with_ramp_up(config.ramp_up())
+        .run()
+        .await?;
+
+    Ok(())
+}
@@ -0,0 +1,169 @@
+//! Minimal Sui JSON-RPC client
+//!
+//! Transactions are built by the node's `unsafe_*` methods, which return
+//! BCS `TransactionData` ready to be signed locally, so the tasks need no
+//! Sui SDK or BCS types of their own.
+
+use anyhow::{anyhow, Context, Result};
+use core_logic::config::ProxyConfig;
+use reqwest::Client;
+use serde::de::DeserializeOwned;
+use serde::{Deserialize, Deserializer};
+use serde_json::{json, Value};
+use std::sync::atomic::{AtomicU64, Ordering};
+use std::time::Duration;
+
+pub const SUI_COIN_TYPE: &str = "0x2::sui::SUI";
+
+/// Coins fetched per `suix_getCoins` page, the node's maximum
+const COIN_PAGE_SIZE: usize = 50;
+
+/// u64 values arrive as JSON strings
+fn u64_string<'de, D: Deserializer<'de>>(deserializer: D) -> Result<u64, D::Error> {
+    String::deserialize(deserializer)?
+        .parse()
+        .map_err(serde::de::Error::custom)
+}
+
+#[derive(Debug, Clone, Deserialize)]
+#[serde(rename_all = "camelCase")]
+pub struct Coin {
+    pub coin_object_id: String,
+    #[serde(deserialize_with = "u64_string")]
+    pub balance: u64,
+}
+
+#[derive(Debug, Deserialize)]
+#[serde(rename_all = "camelCase")]
+struct CoinPage {
+    data: Vec<Coin>,
+}
+
+/// Unsigned transaction returned by the `unsafe_*` builders
+#[derive(Debug, Deserialize)]
+#[serde(rename_all = "camelCase")]
+pub struct TransactionBytes {
+    /// Base64 BCS `TransactionData`
+    pub tx_bytes: String,
+}
+
+#[derive(Debug, Deserialize)]
+#[serde(rename_all = "camelCase")]
+pub struct ExecutionStatus {
+    pub status: String,
+    pub error: Option<String>,
+}
+
+#[derive(Debug, Deserialize)]
+#[serde(rename_all = "camelCase")]
+pub struct GasCostSummary {
+    #[serde(deserialize_with = "u64_string")]
+    pub computation_cost: u64,
+    #[serde(deserialize_with = "u64_string")]
+    pub storage_cost: u64,
+    #[serde(deserialize_with = "u64_string")]
+    pub storage_rebate: u64,
+}
+
+impl GasCostSummary {
+    /// MIST actually paid, zero when the storage rebate covers the costs
+    pub fn paid(&self) -> u64 {
+        (self.computation_cost + self.storage_cost).saturating_sub(self.storage_rebate)
+    }
+}
+
+#[derive(Debug, Deserialize)]
+#[serde(rename_all = "camelCase")]
+pub struct TransactionEffects {
+    pub status: ExecutionStatus,
+    pub gas_used: GasCostSummary,
+}
+
+#[derive(Debug, Deserialize)]
+pub struct ExecutedTransaction {
+    pub digest: String,
+    pub effects: Option<TransactionEffects>,
+}
+
+pub struct SuiRpc {
+    client: Client,
+    url: String,
+    request_id: AtomicU64,
+}
+
+impl SuiRpc {
+    /// A client for `url`, direct when `proxy` is `None`
+    pub fn new(url: &str, proxy: Option<&ProxyConfig>, timeout: Duration) -> Result<Self> {
+        let mut builder = Client::builder().timeout(timeout);
+        if let Some(proxy) = proxy {
+            let mut upstream = reqwest::Proxy::all(&proxy.url)?;
+            if let (Some(user), Some(pass)) = (&proxy.username, &proxy.password) {
+                upstream = upstream.basic_auth(user, pass);
+            }
+            builder = builder.proxy(upstream);
+        }
+        Ok(Self {
+            client: builder.build()?,
+            url: url.to_string(),
+            request_id: AtomicU64::new(1),
+        })
+    }
+
+    pub async fn call<T: DeserializeOwned>(&self, method: &str, params: Value) -> Result<T> {
+        let body = json!({
+            "jsonrpc": "2.0",
+            "id": self.request_id.fetch_add(1, Ordering::Relaxed),
+            "method": method,
+            "params": params,
+        });
+        let mut response: Value = self
+            .client
+            .post(&self.url)
+            .json(&body)
+            .send()
+            .await?
+            .error_for_status()?
+            .json()
+            .await?;
+
+        if let Some(error) = response.get("error") {
+            return Err(anyhow!(
+                "{} failed: {} (code {})",
+                method,
+                error["message"].as_str().unwrap_or_default(),
+                error["code"].as_i64().unwrap_or_default()
+            ));
+        }
+        serde_json::from_value(response["result"].take())
+            .with_context(|| format!("unexpected {} response", method))
+    }
+
+    /// SUI coins of `owner`, largest first
+    ///
+    /// Only the first page is read; tasks never need more coins than that.
+    pub async fn sui_coins(&self, owner: &str) -> Result<Vec<Coin>> {
+        let page: CoinPage = self
+            .call(
+                "suix_getCoins",
+                json!([owner, SUI_COIN_TYPE, null, COIN_PAGE_SIZE]),
+            )
+            .await?;
+        let mut coins = page.data;
+        coins.sort_by_key(|coin| std::cmp::Reverse(coin.balance));
+        Ok(coins)
+    }
+
+    /// Submits a signed transaction and waits until the node executed it
+    pub async fn execute(&self, tx_bytes: &str, signature: &str) -> Result<ExecutedTransaction> {
+        self.call(
+            "sui_executeTransactionBlock",
+            json!([
+                tx_bytes,
+                [signature],
+                { "showEffects": true },
+                "WaitForLocalExecution"
+            ]),
+        )
+        .await
+    }
+}
@@ -0,0 +1,80 @@
+use crate::config::SuiConfig;
+use crate::keys::SuiKeypair;
+use crate::spammer::SuiSpammer;
+use crate::task;
+use anyhow::Result;
+use core_logic::config::ProxyConfig;
+use core_logic::database::DatabaseManager;
+use core_logic::traits::Spammer;
+use core_logic::WalletManager;
+use rand::seq::SliceRandom;
+use rand::Rng;
+use std::sync::Arc;
+use tracing::{error, info, warn};
+
+/// Builds one spammer per wallet with a Sui key, up to `max_workers`
+///
+/// Works like the EVM chains: wallets are shuffled and decrypted lazily,
+/// each spammer gets a random proxy from `proxies` (direct when empty).
+/// Wallets without a usable `sui_private_key` are skipped.
+pub async fn build_spammers(
+    config: &SuiConfig,
+    manager: &WalletManager,
+    wallet_password: Option<&str>,
+    proxies: &[ProxyConfig],
+    db: Option<Arc<DatabaseManager>>,
+    max_workers: usize,
+) -> Result<Vec<Box<dyn Spammer>>> {
+    let config = Arc::new(config.clone());
+    let mut rng = rand::thread_rng();
+    let mut wallet_indices: Vec<usize> = (0..manager.count()).collect();
+    wallet_indices.shuffle(&mut rng);
+
+    let mut spammers = Vec::new();
+    for wallet_idx in wallet_indices {
+        if spammers.len() >= max_workers {
+            break;
+        }
+
+        // Lazy decrypt
+        let decrypted = match manager.get_wallet(wallet_idx, wallet_password).await {
+            Ok(w) => w,
+            Err(e) => {
+                error!("Failed to decrypt wallet {}: {}", wallet_idx, e);
+                continue;
+            }
+        };
+        if decrypted.sui_private_key.is_empty() {
+            warn!("Wallet {} has no Sui key, skipping.", wallet_idx + 1);
+            continue;
+        }
+        let keypair = match SuiKeypair::from_private_key(&decrypted.sui_private_key) {
+            Ok(keypair) => keypair,
+            Err(e) => {
+                error!("Wallet {} has an unusable Sui key: {}", wallet_idx + 1, e);
+                continue;
+            }
+        };
+
+        // Assign proxy randomly if available
+        let proxy = (!proxies.is_empty()).then(|| {
+            let idx = rng.gen_range(0..proxies.len());
+            (idx, proxies[idx].clone())
+        });
+        if let Some((_, p)) = &proxy {
+            info!("Assigned proxy {} to wallet {}", p.url, keypair.address());
+        }
+
+        let spammer = SuiSpammer::new_with_keypair(
+            config.clone(),
+            keypair,
+            wallet_idx,
+            proxy,
+            task::all_tasks(),
+            db.clone(),
+        )?;
+        spammers.push(Box::new(spammer) as Box<dyn Spammer>);
+    }
+
+    Ok(spammers)
+}
@@ -0,0 +1,198 @@
+use crate::config::SuiConfig;
+use crate::keys::SuiKeypair;
+use crate::rpc::SuiRpc;
+use crate::task::{SuiTask, TaskContext};
+use anyhow::Result;
+use async_trait::async_trait;
+use core_logic::config::{ProxyConfig, SpamConfig};
+use core_logic::database::DatabaseManager;
+use core_logic::metrics::{MetricsCollector, TaskLabels};
+use core_logic::traits::{Spammer, SpammerStats};
+use rand::distributions::{Distribution, WeightedIndex};
+use rand::rngs::OsRng;
+use rand::Rng;
+use std::sync::Arc;
+use tokio::time::{sleep, Duration, Instant};
+use tokio_util::sync::CancellationToken;
+use tracing::{info, warn, Instrument};
+
+pub struct SuiSpammer {
+    config: Arc<SuiConfig>,
+    rpc: Arc<SuiRpc>,
+    keypair: Arc<SuiKeypair>,
+    tasks: Vec<Box<SuiTask>>,
+    dist: WeightedIndex<u32>,
+    // Context IDs for logging
+    wallet_index: usize,
+    proxy_index: Option<usize>,
+    db: Option<Arc<DatabaseManager>>,
+}
+
+fn get_task_weight(name: &str) -> u32 {
+    match name {
+        "01_transferSui" => 10,
+        "02_splitCoin" => 5,
+        "03_mergeCoins" => 2,
+        _ => 1, //default
+    }
+}
+
+impl SuiSpammer {
+    /// A spammer for wallet `wallet_index` signing with `keypair`, through
+    /// the proxy numbered `proxy.0` when given
+    pub fn new_with_keypair(
+        config: Arc<SuiConfig>,
+        keypair: SuiKeypair,
+        wallet_index: usize,
+        proxy: Option<(usize, ProxyConfig)>,
+        tasks: Vec<Box<SuiTask>>,
+        db: Option<Arc<DatabaseManager>>,
+    ) -> Result<Self> {
+        let rpc = SuiRpc::new(
+            &config.rpc_url,
+            proxy.as_ref().map(|(_, proxy)| proxy),
+            Duration::from_secs(30),
+        )?;
+
+        let weights: Vec<u32> = tasks.iter().map(|t| get_task_weight(t.name())).collect();
+        let dist = WeightedIndex::new(&weights)?;
+
+        Ok(Self {
+            config,
+            rpc: Arc::new(rpc),
+            keypair: Arc::new(keypair),
+            tasks,
+            dist,
+            wallet_index,
+            proxy_index: proxy.map(|(index, _)| index),
+            db,
+        })
+    }
+
+    /// Logs a finished task and counts it in the database and metrics
+    async fn record(&self, task: &str, duration: Duration, result: Result<String, String>) {
+        let success = result.is_ok();
+        let message = match result {
+            Ok(message) => {
+                info!(
+                    target: "task_result",
+                    "Success [{}] {} in {:.1}s",
+                    task,
+                    message,
+                    duration.as_secs_f64()
+                );
+                message
+            }
+            Err(message) => {
+                warn!(
+                    target: "task_result",
+                    "Failed  [{}] {} in {:.1}s",
+                    task,
+                    message,
+                    duration.as_secs_f64()
+                );
+                message
+            }
+        };
+
+        let labels = TaskLabels {
+            chain_id: self.config.chain_id,
+            task,
+            wallet_index: Some(self.wallet_index),
+            proxy_index: self.proxy_index,
+        };
+        MetricsCollector::global().record_task_with(&labels, duration, success);
+
+        if let Some(db) = &self.db {
+            let _ = db
+                .log_chain_task_result(
+                    self.config.chain_id,
+                    &format!("{:03}", self.wallet_index + 1),
+                    self.keypair.address(),
+                    task,
+                    success,
+                    &message,
+                    duration.as_millis() as u64,
+                )
+                .await;
+        }
+    }
+
+    fn next_delay(&self) -> Duration {
+        let min = self.config.min_delay_ms.unwrap_or(1000);
+        let max = self.config.max_delay_ms.unwrap_or(min).max(min);
+        Duration::from_millis(rand::thread_rng().gen_range(min..=max))
+    }
+}
+
+#[async_trait]
+impl Spammer for SuiSpammer {
+    async fn new(_config: SpamConfig) -> Result<Self> {
+        // Needs a keypair and the Sui config, see new_with_keypair
+        Err(anyhow::anyhow!("Use new_with_keypair construction"))
+    }
+
+    async fn start(&self, cancellation_token: CancellationToken) -> Result<SpammerStats> {
+        let span = tracing::info_span!(
+            "spammer_context",
+            wallet_id = format!("{:03}", self.wallet_index + 1),
+            proxy_id = self
+                .proxy_index
+                .map_or_else(|| "000".to_string(), |i| format!("{:03}", i + 1))
+        );
+
+        async move {
+            info!("Sui Spammer started for {}", self.keypair.address());
+            let mut stats = SpammerStats::default();
+            let run_start = Instant::now();
+
+            loop {
+                if cancellation_token.is_cancelled() {
+                    break;
+                }
+
+                let task = &self.tasks[self.dist.sample(&mut OsRng)];
+                let ctx = TaskContext {
+                    rpc: self.rpc.clone(),
+                    keypair: self.keypair.clone(),
+                    config: self.config.clone(),
+                    db: self.db.clone(),
+                };
+
+                let start = Instant::now();
+                let outcome = task.run(ctx).await;
+                let duration = start.elapsed();
+                match outcome {
+                    Ok(res) if res.success => {
+                        stats.record_success(task.name(), duration, res.gas_used);
+                        self.record(task.name(), duration, Ok(res.message)).await;
+                    }
+                    Ok(res) => {
+                        stats.record_failure(task.name(), duration, &res.message);
+                        self.record(task.name(), duration, Err(res.message)).await;
+                    }
+                    Err(e) => {
+                        let message = format!("{:#}", e);
+                        stats.record_failure(task.name(), duration, &message);
+                        self.record(task.name(), duration, Err(message)).await;
+                    }
+                }
+
+                tokio::select! {
+                    _ = cancellation_token.cancelled() => break,
+                    _ = sleep(self.next_delay()) => {}
+                }
+            }
+
+            stats.run_duration_ms = run_start.elapsed().as_millis() as u64;
+            Ok(stats)
+        }
+        .instrument(span)
+        .await
+    }
+
+    async fn stop(&self) -> Result<()> {
+        info!("Sui Spammer stopping...");
+        Ok(())
+    }
+}
@@ -0,0 +1,93 @@
+use crate::config::SuiConfig;
+use crate::keys::SuiKeypair;
+use crate::rpc::{Coin, SuiRpc, TransactionBytes};
+use anyhow::{bail, Context, Result};
+use base64::{engine::general_purpose::STANDARD, Engine};
+use serde_json::Value;
+use std::sync::Arc;
+
+pub mod t01_transfer_sui;
+pub mod t02_split_coin;
+pub mod t03_merge_coins;
+pub mod t04_move_call;
+
+pub use self::t01_transfer_sui::TransferSuiTask;
+pub use self::t02_split_coin::SplitCoinTask;
+pub use self::t03_merge_coins::MergeCoinsTask;
+pub use self::t04_move_call::MoveCallTask;
+
+pub use core_logic::traits::{Task, TaskResult};
+
+#[derive(Clone)]
+pub struct TaskContext {
+    pub rpc: Arc<SuiRpc>,
+    pub keypair: Arc<SuiKeypair>,
+    pub config: Arc<SuiConfig>,
+    pub db: Option<Arc<core_logic::database::DatabaseManager>>,
+}
+
+impl TaskContext {
+    pub fn address(&self) -> &str {
+        self.keypair.address()
+    }
+
+    /// The wallet's SUI coins, largest first; errors when it has none
+    pub async fn coins(&self) -> Result<Vec<Coin>> {
+        let coins = self.rpc.sui_coins(self.address()).await?;
+        if coins.is_empty() {
+            bail!("wallet {} holds no SUI", self.address());
+        }
+        Ok(coins)
+    }
+
+    /// Builds a transaction with the node's `unsafe_*` builder `method`,
+    /// signs and executes it
+    ///
+    /// `params` are the builder's arguments after the signer. The result
+    /// fails when the transaction executed but aborted on chain.
+    pub async fn execute(
+        &self,
+        method: &str,
+        params: Vec<Value>,
+        action: String,
+    ) -> Result<TaskResult> {
+        let mut args = vec![Value::from(self.address())];
+        args.extend(params);
+        let tx: TransactionBytes = self.rpc.call(method, Value::Array(args)).await?;
+
+        let bytes = STANDARD
+            .decode(&tx.tx_bytes)
+            .context("node returned invalid transaction bytes")?;
+        let signature = self.keypair.sign_transaction(&bytes);
+        let executed = self.rpc.execute(&tx.tx_bytes, &signature).await?;
+
+        let Some(effects) = executed.effects else {
+            bail!("no effects returned for {}", executed.digest);
+        };
+        let success = effects.status.status == "success";
+        let message = match effects.status.error {
+            Some(error) if !success => format!("{} aborted: {}", action, error),
+            _ => action,
+        };
+        Ok(TaskResult {
+            success,
+            message,
+            tx_hash: Some(executed.digest),
+            gas_used: Some(effects.gas_used.paid()),
+            ..Default::default()
+        })
+    }
+}
+
+// Trait alias
+pub type SuiTask = dyn Task<TaskContext> + Send + Sync;
+
+/// All tasks, in the order of their numbers
+pub fn all_tasks() -> Vec<Box<SuiTask>> {
+    vec![
+        Box::new(TransferSuiTask),
+        Box::new(SplitCoinTask),
+        Box::new(MergeCoinsTask),
+        Box::new(MoveCallTask),
+    ]
+}
@@ -0,0 +1,38 @@
+use crate::task::{Task, TaskContext, TaskResult};
+use anyhow::{bail, Result};
+use async_trait::async_trait;
+use rand::Rng;
+use serde_json::json;
+
+pub struct TransferSuiTask;
+
+#[async_trait]
+impl Task<TaskContext> for TransferSuiTask {
+    async fn run(&self, ctx: TaskContext) -> Result<TaskResult> {
+        let amount = ctx.config.transfer_amount();
+        let gas_budget = ctx.config.gas_budget();
+
+        // The transferred coin pays the gas as well
+        let coin = ctx.coins().await?.remove(0);
+        if coin.balance < amount + gas_budget {
+            bail!("insufficient SUI: largest coin holds {} MIST", coin.balance);
+        }
+
+        let recipient = format!("0x{}", hex::encode(rand::thread_rng().gen::<[u8; 32]>()));
+        ctx.execute(
+            "unsafe_transferSui",
+            vec![
+                json!(coin.coin_object_id),
+                json!(gas_budget.to_string()),
+                json!(recipient),
+                json!(amount.to_string()),
+            ],
+            format!("Sent {} MIST to {}", amount, recipient),
+        )
+        .await
+    }
+
+    fn name(&self) -> &str {
+        "01_transferSui"
+    }
+}
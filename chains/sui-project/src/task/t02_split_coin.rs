@@ -0,0 +1,43 @@
+use crate::task::{Task, TaskContext, TaskResult};
+use anyhow::{bail, Result};
+use async_trait::async_trait;
+use serde_json::json;
+
+/// New coins split off per run
+const PIECES: usize = 2;
+
+pub struct SplitCoinTask;
+
+#[async_trait]
+impl Task<TaskContext> for SplitCoinTask {
+    async fn run(&self, ctx: TaskContext) -> Result<TaskResult> {
+        let amount = ctx.config.split_amount();
+        let gas_budget = ctx.config.gas_budget();
+
+        let coin = ctx.coins().await?.remove(0);
+        if coin.balance < amount * PIECES as u64 + gas_budget {
+            bail!("insufficient SUI: largest coin holds {} MIST", coin.balance);
+        }
+
+        // `unsafe_splitCoin` needs a second coin for gas; paying the pieces
+        // back to ourselves splits the one coin and lets it pay the gas
+        ctx.execute(
+            "unsafe_paySui",
+            vec![
+                json!([coin.coin_object_id]),
+                json!(vec![ctx.address(); PIECES]),
+                json!(vec![amount.to_string(); PIECES]),
+                json!(gas_budget.to_string()),
+            ],
+            format!(
+                "Split {} x {} MIST off {}",
+                PIECES, amount, coin.coin_object_id
+            ),
+        )
+        .await
+    }
+
+    fn name(&self) -> &str {
+        "02_splitCoin"
+    }
+}
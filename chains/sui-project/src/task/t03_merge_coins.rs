@@ -0,0 +1,44 @@
+use crate::task::{Task, TaskContext, TaskResult};
+use anyhow::{bail, Result};
+use async_trait::async_trait;
+use serde_json::json;
+
+/// Most coins merged in one transaction
+const MAX_MERGED: usize = 10;
+
+pub struct MergeCoinsTask;
+
+#[async_trait]
+impl Task<TaskContext> for MergeCoinsTask {
+    async fn run(&self, ctx: TaskContext) -> Result<TaskResult> {
+        let coins = ctx.coins().await?;
+        if coins.len() < 2 {
+            bail!("nothing to merge, the wallet holds a single SUI coin");
+        }
+
+        // Largest coin first so it pays the gas, then the smallest ones
+        let mut merged = vec![coins[0].coin_object_id.clone()];
+        merged.extend(
+            coins[1..]
+                .iter()
+                .rev()
+                .take(MAX_MERGED - 1)
+                .map(|coin| coin.coin_object_id.clone()),
+        );
+
+        ctx.execute(
+            "unsafe_payAllSui",
+            vec![
+                json!(merged),
+                json!(ctx.address()),
+                json!(ctx.config.gas_budget().to_string()),
+            ],
+            format!("Merged {} SUI coins", merged.len()),
+        )
+        .await
+    }
+
+    fn name(&self) -> &str {
+        "03_mergeCoins"
+    }
+}
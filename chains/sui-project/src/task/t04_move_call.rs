@@ -0,0 +1,49 @@
+use crate::rpc::SUI_COIN_TYPE;
+use crate::task::{Task, TaskContext, TaskResult};
+use anyhow::{anyhow, bail, Result};
+use async_trait::async_trait;
+use serde_json::json;
+
+pub struct MoveCallTask;
+
+#[async_trait]
+impl Task<TaskContext> for MoveCallTask {
+    async fn run(&self, ctx: TaskContext) -> Result<TaskResult> {
+        let amount = ctx.config.split_amount();
+        let gas_budget = ctx.config.gas_budget();
+
+        // The coin passed to the call cannot also pay the gas
+        let coins = ctx.coins().await?;
+        if coins.len() < 2 {
+            bail!("needs a second SUI coin next to the gas coin, run 02_splitCoin first");
+        }
+        let gas = &coins[0];
+        if gas.balance < gas_budget {
+            bail!("insufficient SUI: largest coin holds {} MIST", gas.balance);
+        }
+        let coin = coins[1..]
+            .iter()
+            .find(|coin| coin.balance >= amount)
+            .ok_or_else(|| anyhow!("no coin besides the gas coin holds {} MIST", amount))?;
+
+        // 0x2::pay::split_and_transfer<SUI>(coin, amount, recipient)
+        ctx.execute(
+            "unsafe_moveCall",
+            vec![
+                json!("0x2"),
+                json!("pay"),
+                json!("split_and_transfer"),
+                json!([SUI_COIN_TYPE]),
+                json!([coin.coin_object_id, amount.to_string(), ctx.address()]),
+                json!(gas.coin_object_id),
+                json!(gas_budget.to_string()),
+            ],
+            format!("Called 0x2::pay::split_and_transfer for {} MIST", amount),
+        )
+        .await
+    }
+
+    fn name(&self) -> &str {
+        "04_moveCall"
+    }
+}
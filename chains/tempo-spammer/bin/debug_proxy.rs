@@ -58,8 +58,8 @@ async fn check_proxy(config: &ProxyConfig, url: &str) -> Result<String> {
     let client_builder = Client::builder().timeout(Duration::from_secs(10));
 
     let proxy = reqwest::Proxy::all(&config.url)?;
-    let proxy = if let (Some(u), Some(p)) = (&config.username, &config.password) {
-        proxy.basic_auth(u, p)
+    let proxy = if let Some((u, p)) = config.basic_auth() {
+        proxy.basic_auth(&u, &p)
     } else {
         proxy
     };
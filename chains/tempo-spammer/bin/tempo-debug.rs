@@ -1,34 +1,63 @@
+//! tempo-debug - Runs tasks by hand against the configured network
+//!
+//! One task on wallet 0 by default. `--loop`, `--wallet-range` and
+//! `--all-tasks` widen that into a smoke test of a deployment, ending in a
+//! pass/fail matrix per task, and `--json` prints the runs for scripts. The
+//! exit status is 1 when any run did not succeed.
+
 use anyhow::{Context, Result};
 use clap::Parser;
 use core_logic::WalletManager;
 use core_logic::database::DatabaseManager;
-use core_logic::traits::TaskResult;
 use dialoguer::{Password, theme::ColorfulTheme};
 use dotenv::dotenv;
 use rand::Rng;
+use serde::Serialize;
 use std::env;
+use std::ops::Range;
+use std::path::PathBuf;
 use std::sync::Arc;
+use std::time::Instant;
 use tempo_spammer::TempoClient;
 use tempo_spammer::config::TempoSpammerConfig;
-use tempo_spammer::tasks::{TaskContext, TempoTask, load_proxies};
-use tracing;
-use tracing_subscriber;
+use tempo_spammer::tasks::{ProxyConfig, TaskContext, TempoTask, load_proxies};
 
 #[derive(Parser, Debug)]
 #[command(author, version, about, long_about = None)]
 struct Args {
     /// Path to config.toml
-    #[arg(short, long, default_value = "config/config.toml")]
-    config: String,
+    #[arg(short, long, default_value = tempo_spammer::config::DEFAULT_CONFIG_PATH)]
+    config: PathBuf,
+
+    /// Config profile to apply, `[profiles.<name>]`
+    #[arg(long)]
+    profile: Option<String>,
 
     /// Task to run (name or number)
-    #[arg(short, long)]
-    task: String,
+    #[arg(short, long, required_unless_present = "all_tasks")]
+    task: Option<String>,
+
+    /// Run every task and print a pass/fail matrix per task
+    #[arg(long, conflicts_with = "task")]
+    all_tasks: bool,
 
     /// Wallet index to use
-    #[arg(short, long, default_value = "0")]
+    #[arg(short, long, default_value = "0", conflicts_with = "wallet_range")]
     wallet: usize,
 
+    /// Wallet indices to run on, end exclusive, e.g. `0..50`
+    #[arg(long, value_parser = parse_wallet_range)]
+    wallet_range: Option<Range<usize>>,
+
+    /// Times to run each task on each wallet
+    #[arg(long = "loop", value_name = "N", default_value_t = 1,
+          value_parser = clap::value_parser!(u32).range(1..))]
+    loop_count: u32,
+
+    /// Print the runs as JSON on stdout, with progress and logs on stderr
+    #[arg(long)]
+    json: bool,
+
     /// Proxy index (optional, random if not specified, 0 for direct)
     #[arg(short, long)]
     proxy: Option<usize>,
@@ -38,139 +67,176 @@ struct Args {
     no_db: bool,
 }
 
+/// Progress line, on stderr when stdout carries `--json`
+macro_rules! note {
+    ($json:expr, $($arg:tt)*) => {
+        if $json {
+            eprintln!($($arg)*);
+        } else {
+            println!($($arg)*);
+        }
+    };
+}
+
+/// `start..end` with `start` below `end`
+fn parse_wallet_range(s: &str) -> Result<Range<usize>, String> {
+    let (start, end) = s
+        .split_once("..")
+        .ok_or_else(|| format!("Expected a range like 0..50, got '{}'", s))?;
+    let start: usize = start
+        .trim()
+        .parse()
+        .map_err(|_| format!("Invalid range start '{}'", start))?;
+    let end: usize = end
+        .trim()
+        .parse()
+        .map_err(|_| format!("Invalid range end '{}'", end))?;
+    if start >= end {
+        return Err(format!("Empty wallet range {}..{}", start, end));
+    }
+    Ok(start..end)
+}
+
+#[derive(Debug, Clone, Copy, PartialEq, Eq, Serialize)]
+#[serde(rename_all = "lowercase")]
+enum RunStatus {
+    Success,
+    Failed,
+    Error,
+    Timeout,
+}
+
+/// One run of one task on one wallet
+#[derive(Debug, Serialize)]
+struct Run {
+    task: String,
+    wallet: usize,
+    address: Option<String>,
+    iteration: u32,
+    status: RunStatus,
+    message: String,
+    tx_hash: Option<String>,
+    duration_ms: u64,
+}
+
+/// Proxy for a client: `--proxy` (1-based, 0 for direct) or a random one
+fn pick_proxy(
+    proxies: &[ProxyConfig],
+    requested: Option<usize>,
+    json: bool,
+) -> (Option<usize>, Option<&ProxyConfig>) {
+    if proxies.is_empty() {
+        return (None, None);
+    }
+    match requested {
+        Some(0) => (None, None),
+        Some(idx) if idx <= proxies.len() => (Some(idx - 1), Some(&proxies[idx - 1])),
+        requested => {
+            if let Some(idx) = requested {
+                note!(
+                    json,
+                    "⚠️  Proxy index {} out of range (have {}), using random proxy",
+                    idx,
+                    proxies.len()
+                );
+            }
+            let random_idx = rand::thread_rng().gen_range(0..proxies.len());
+            (Some(random_idx), Some(&proxies[random_idx]))
+        }
+    }
+}
+
 #[tokio::main]
 async fn main() -> Result<()> {
     dotenv().ok();
 
+    let args = Args::parse();
+    let json = args.json;
+
     // Initialize tracing for debug mode (show everything)
-    tracing_subscriber::fmt()
+    let subscriber = tracing_subscriber::fmt()
         .with_max_level(tracing::Level::DEBUG)
-        .with_target(false) // cleaner output without targets
-        .init();
-
-    let args = Args::parse();
+        .with_target(false); // cleaner output without targets
+    if json {
+        subscriber.with_writer(std::io::stderr).init();
+    } else {
+        subscriber.init();
+    }
 
     // Load config
-    let config_path = if std::path::Path::new(&args.config).exists() {
-        args.config.clone()
-    } else if args.config == "config/config.toml"
-        && std::path::Path::new("chains/tempo-spammer/config/config.toml").exists()
-    {
-        "chains/tempo-spammer/config/config.toml".to_string()
-    } else {
-        args.config.clone()
-    };
+    let config_path = tempo_spammer::config::resolve_config_path(&args.config);
 
-    let config = TempoSpammerConfig::from_path(&config_path).context("Failed to load config")?;
-    println!(
+    let config = TempoSpammerConfig::from_path_with_profile(&config_path, args.profile.as_deref())
+        .context("Failed to load config")?;
+    tempo_spammer::network::configure(&config.contracts);
+    note!(
+        json,
         "Loaded config: {} (chain {})",
-        config.rpc_url, config.chain_id
+        config.rpc_url,
+        config.chain_id
     );
 
     // Load wallet with password handling
     let mut wallet_password = env::var("WALLET_PASSWORD").ok();
-    let wallet_manager = WalletManager::new()?;
+    let wallet_manager = WalletManager::with_options(config.wallets.wallet_options())?;
     let total_wallets = wallet_manager.count();
 
     if total_wallets == 0 {
-        println!("❌ No wallets found");
-        return Ok(());
+        anyhow::bail!("No wallets found");
     }
 
-    if args.wallet >= total_wallets {
-        println!(
-            "❌ Wallet {} not found (have {})",
-            args.wallet, total_wallets
+    let wallets = args
+        .wallet_range
+        .clone()
+        .unwrap_or(args.wallet..args.wallet + 1);
+    if wallets.end > total_wallets {
+        anyhow::bail!(
+            "Wallet {} not found (have {})",
+            wallets.end - 1,
+            total_wallets
         );
-        return Ok(());
     }
 
-    println!("Using wallet {} of {}", args.wallet, total_wallets);
+    note!(
+        json,
+        "Using wallets {}..{} of {}",
+        wallets.start,
+        wallets.end,
+        total_wallets
+    );
 
     // Test wallet decryption with current password
-    if let Err(_) = wallet_manager
-        .get_wallet(args.wallet, wallet_password.as_deref())
+    if wallet_manager
+        .get_wallet(wallets.start, wallet_password.as_deref())
         .await
+        .is_err()
     {
-        println!("\n⚠️  Decryption failed with env KEY (or KEY unset/wrong).");
+        eprintln!("\n⚠️  Decryption failed with env KEY (or KEY unset/wrong).");
         let input = Password::with_theme(&ColorfulTheme::default())
             .with_prompt("Enter wallet password")
             .interact()?;
         wallet_password = Some(input);
 
         // Validate password works
-        if let Err(e) = wallet_manager
-            .get_wallet(args.wallet, wallet_password.as_deref())
+        wallet_manager
+            .get_wallet(wallets.start, wallet_password.as_deref())
             .await
-        {
-            println!("❌ Decryption failed even with provided password: {}", e);
-            return Ok(());
-        }
+            .context("Decryption failed even with provided password")?;
     }
 
-    // Get the directory containing the config file
-    let config_path_obj = std::path::Path::new(&config_path);
-    let config_dir = config_path_obj
-        .parent()
-        .unwrap_or(std::path::Path::new("."));
-    let proxies_path = config_dir.join("proxies.txt");
-
-    // Load proxies
-    let proxy_path_str = proxies_path.to_str().unwrap_or("config/proxies.txt");
-    let proxies = load_proxies(proxy_path_str)?;
-    println!(
-        "Loaded {} proxies from {}",
-        proxies.len(),
-        proxies_path.display()
-    );
-
-    // Select proxy (random by default)
-    let (proxy_idx, proxy) = if proxies.is_empty() {
-        // No proxies available, use direct
-        println!("No proxies available, using direct connection");
-        (None, None)
-    } else if let Some(idx) = args.proxy {
-        if idx == 0 {
-            // Explicit direct connection via --proxy 0
-            println!("Using direct connection (--proxy 0)");
-            (None, None)
-        } else if idx > 0 && idx <= proxies.len() {
-            // Specific proxy (1-indexed)
-            (Some(idx - 1), Some(&proxies[idx - 1]))
-        } else {
-            println!(
-                "⚠️  Proxy index {} out of range (have {}), using random proxy",
-                idx,
-                proxies.len()
-            );
-            let random_idx = rand::thread_rng().gen_range(0..proxies.len());
-            (Some(random_idx), Some(&proxies[random_idx]))
-        }
+    // proxies.txt next to the config file, else in the working directory
+    let proxies_path = tempo_spammer::config::proxies_path(&config_path);
+    let proxies = load_proxies(&proxies_path)?;
+    if proxies.is_empty() {
+        note!(json, "No proxies available, using direct connection");
     } else {
-        // Random proxy by default
-        let random_idx = rand::thread_rng().gen_range(0..proxies.len());
-        (Some(random_idx), Some(&proxies[random_idx]))
-    };
-
-    // Get private key
-    let decrypted = wallet_manager
-        .get_wallet(args.wallet, wallet_password.as_deref())
-        .await?;
-
-    // Create client with proxy
-    let client = TempoClient::new(
-        &config.rpc_url,
-        &decrypted.evm_private_key,
-        proxy,
-        proxy_idx,
-    )
-    .await?;
-    println!("Wallet address: {:?}", client.address());
-    println!(
-        "Using proxy {}: {}",
-        proxy_idx.unwrap_or(0) + 1,
-        proxy.map(|p| &p.url).unwrap_or(&"direct".to_string())
-    );
+        note!(
+            json,
+            "Loaded {} proxies from {}",
+            proxies.len(),
+            proxies_path.display()
+        );
+    }
 
     // Define available tasks
     let tasks: Vec<(usize, &str, &str, Box<dyn TempoTask>)> = vec![
@@ -483,32 +549,23 @@ async fn main() -> Result<()> {
         ),
     ];
 
-    // Find task
-    #[allow(noop_method_call)]
-    let task_input = args.task.to_lowercase();
-    let _max_task_idx = tasks.iter().map(|(i, _, _, _)| *i).max().unwrap_or(5);
-    let (task_idx, _task_key, task_desc, task) = if let Ok(idx) = task_input.parse::<usize>() {
-        // Numeric index
-        if idx == 0 || (idx > 50 && idx != 999) {
-            panic!("Task index {} not found. Available tasks: 1-50, 999", idx);
+    // Find the tasks to run
+    let selected: Vec<&(usize, &str, &str, Box<dyn TempoTask>)> = match &args.task {
+        Some(task) => vec![find_task(&tasks, task)?],
+        None => {
+            let mut all: Vec<_> = tasks.iter().collect();
+            all.sort_by_key(|(idx, _, _, _)| *idx);
+            all
         }
-        tasks.iter().find(|(i, _, _, _)| *i == idx).unwrap()
-    } else {
-        // Name match
-        tasks
-            .iter()
-            .find(|(_, name, _, _)| name.to_lowercase() == task_input || name.contains(&task_input))
-            .expect("Task not found. Available tasks: 01-50")
     };
 
-    println!("Running task {}: {}", task_idx, task_desc);
-
     // Initialize database if not disabled
     let db = if !args.no_db {
         match DatabaseManager::new("tempo-spammer.db").await {
             Ok(db) => Some(Arc::new(db)),
             Err(e) => {
-                println!(
+                note!(
+                    json,
                     "⚠️  Failed to open database: {:?}. Continuing without DB.",
                     e
                 );
@@ -519,43 +576,233 @@ async fn main() -> Result<()> {
         None
     };
 
-    // Create context with optional database
-    let ctx = TaskContext::new(client.clone(), config, db);
+    let mut runs = Vec::new();
+    for wallet in wallets.clone() {
+        let client = match connect(
+            &wallet_manager,
+            wallet,
+            wallet_password.as_deref(),
+            &config.rpc_url,
+            &proxies,
+            args.proxy,
+            json,
+        )
+        .await
+        {
+            Ok(client) => client,
+            Err(e) => {
+                note!(json, "❌ Wallet {}: {:#}", wallet, e);
+                for (_, name, _, _) in &selected {
+                    runs.push(Run {
+                        task: name.to_string(),
+                        wallet,
+                        address: None,
+                        iteration: 1,
+                        status: RunStatus::Error,
+                        message: format!("Client setup failed: {:#}", e),
+                        tx_hash: None,
+                        duration_ms: 0,
+                    });
+                }
+                continue;
+            }
+        };
+
+        for iteration in 1..=args.loop_count {
+            for (task_idx, name, task_desc, task) in &selected {
+                note!(
+                    json,
+                    "Running task {}: {} (wallet {}, run {}/{})",
+                    task_idx,
+                    task_desc,
+                    wallet,
+                    iteration,
+                    args.loop_count
+                );
+                let ctx = TaskContext::new(client.clone(), config.clone(), db.clone());
+                let run = run_task(&ctx, &**task, name, wallet, iteration).await;
+                print_run(&run, &client, json);
+                runs.push(run);
+            }
+        }
+    }
+
+    let failed = runs
+        .iter()
+        .filter(|run| run.status != RunStatus::Success)
+        .count();
+    if json {
+        let report = serde_json::json!({
+            "summary": {
+                "runs": runs.len(),
+                "succeeded": runs.len() - failed,
+                "failed": failed,
+            },
+            "runs": &runs,
+        });
+        println!("{}", serde_json::to_string_pretty(&report)?);
+    } else if runs.len() > 1 {
+        print_matrix(&selected, &runs);
+    }
+
+    if failed > 0 {
+        anyhow::bail!("{} of {} runs did not succeed", failed, runs.len());
+    }
+    Ok(())
+}
+
+/// Task by number or by (part of its) name
+fn find_task<'t>(
+    tasks: &'t [(usize, &'static str, &'static str, Box<dyn TempoTask>)],
+    input: &str,
+) -> Result<&'t (usize, &'static str, &'static str, Box<dyn TempoTask>)> {
+    let input = input.to_lowercase();
+    let found = match input.parse::<usize>() {
+        Ok(idx) => tasks.iter().find(|(i, _, _, _)| *i == idx),
+        Err(_) => tasks
+            .iter()
+            .find(|(_, name, _, _)| name.to_lowercase() == input)
+            .or_else(|| tasks.iter().find(|(_, name, _, _)| name.contains(&input))),
+    };
+    found.with_context(|| format!("Task '{}' not found. Available tasks: 1-50, 999", input))
+}
 
-    // Run task with timeout
-    let start_time = std::time::Instant::now();
-    let result = tokio::time::timeout(ctx.timeout, task.run(&ctx)).await;
+/// Client for `wallet`, through the chosen proxy
+async fn connect(
+    wallet_manager: &WalletManager,
+    wallet: usize,
+    password: Option<&str>,
+    rpc_url: &str,
+    proxies: &[ProxyConfig],
+    requested_proxy: Option<usize>,
+    json: bool,
+) -> Result<TempoClient> {
+    let decrypted = wallet_manager.get_wallet(wallet, password).await?;
+    let (proxy_idx, proxy) = pick_proxy(proxies, requested_proxy, json);
+    let client = TempoClient::new(rpc_url, &decrypted.evm_private_key, proxy, proxy_idx).await?;
+    note!(
+        json,
+        "Wallet {} address: {:?}, proxy {}",
+        wallet,
+        client.address(),
+        proxy.map_or("direct", |p| p.url.as_str())
+    );
+    Ok(client)
+}
 
-    match result {
-        Ok(Ok(task_result)) => {
-            let duration = start_time.elapsed();
+/// Runs `task` once within the context's timeout
+async fn run_task(
+    ctx: &TaskContext,
+    task: &dyn TempoTask,
+    name: &str,
+    wallet: usize,
+    iteration: u32,
+) -> Run {
+    let start_time = Instant::now();
+    let result = tokio::time::timeout(ctx.timeout, task.run(ctx)).await;
+    let (status, message, tx_hash) = match result {
+        Ok(Ok(task_result)) => (
             if task_result.success {
-                println!("✅ Success: {}", task_result.message);
+                RunStatus::Success
             } else {
-                println!("⚠️  Failed: {}", task_result.message);
-            }
-            if let Some(hash) = task_result.tx_hash {
-                println!("📎 Transaction: {}", hash);
-            }
-            println!("⏱️  Duration: {:.1}s", duration.as_secs_f64());
-        }
-        Ok(Err(e)) => {
-            let duration = start_time.elapsed();
-            println!("Proxy: {:?}", client.proxy_config.as_ref().map(|p| &p.url));
-            println!("❌ Error: {:?}", e);
-            println!("⏱️  Duration: {:.1}s", duration.as_secs_f64());
+                RunStatus::Failed
+            },
+            task_result.message,
+            task_result.tx_hash,
+        ),
+        Ok(Err(e)) => (RunStatus::Error, format!("{:?}", e), None),
+        Err(_) => (
+            RunStatus::Timeout,
+            format!("Task timed out after {}s", ctx.timeout.as_secs()),
+            None,
+        ),
+    };
+    Run {
+        task: name.to_string(),
+        wallet,
+        address: Some(format!("{:?}", ctx.address())),
+        iteration,
+        status,
+        message,
+        tx_hash,
+        duration_ms: start_time.elapsed().as_millis() as u64,
+    }
+}
+
+fn print_run(run: &Run, client: &TempoClient, json: bool) {
+    match run.status {
+        RunStatus::Success => note!(json, "✅ Success: {}", run.message),
+        RunStatus::Failed | RunStatus::Timeout => note!(json, "⚠️  Failed: {}", run.message),
+        RunStatus::Error => {
+            note!(
+                json,
+                "Proxy: {:?}",
+                client.proxy_config.as_ref().map(|p| &p.url)
+            );
+            note!(json, "❌ Error: {}", run.message);
         }
-        Err(_) => {
-            let duration = start_time.elapsed();
-            let timed_out_result = TaskResult {
-                success: false,
-                message: format!("Task timed out after 60s"),
-                tx_hash: None,
-            };
-            println!("⚠️  Failed: {}", timed_out_result.message);
-            println!("⏱️  Duration: {:.1}s", duration.as_secs_f64());
+    }
+    if let Some(hash) = &run.tx_hash {
+        note!(json, "📎 Transaction: {}", hash);
+    }
+    note!(
+        json,
+        "⏱️  Duration: {:.1}s",
+        run.duration_ms as f64 / 1000.0
+    );
+}
+
+/// Wallets listed per task before the rest are counted
+const MAX_LISTED_WALLETS: usize = 8;
+
+/// Pass/fail counts per task, with the wallets it did not succeed on
+fn print_matrix(selected: &[&(usize, &str, &str, Box<dyn TempoTask>)], runs: &[Run]) {
+    println!(
+        "\n{:<28} {:>6} {:>6} {:>6} {:>7} {:>7}  Wallets not passing",
+        "Task", "Pass", "Fail", "Error", "Timeout", "Avg s"
+    );
+    for (_, name, _, _) in selected {
+        let task_runs: Vec<&Run> = runs.iter().filter(|run| run.task == *name).collect();
+        let count = |status| task_runs.iter().filter(|run| run.status == status).count();
+        let avg_secs = if task_runs.is_empty() {
+            0.0
+        } else {
+            task_runs.iter().map(|run| run.duration_ms).sum::<u64>() as f64
+                / task_runs.len() as f64
+                / 1000.0
+        };
+
+        let mut failing: Vec<usize> = task_runs
+            .iter()
+            .filter(|run| run.status != RunStatus::Success)
+            .map(|run| run.wallet)
+            .collect();
+        failing.dedup();
+        let mut listed = failing
+            .iter()
+            .take(MAX_LISTED_WALLETS)
+            .map(|wallet| wallet.to_string())
+            .collect::<Vec<_>>()
+            .join(",");
+        if failing.len() > MAX_LISTED_WALLETS {
+            listed.push_str(&format!(" +{}", failing.len() - MAX_LISTED_WALLETS));
         }
+
+        println!(
+            "{:<28} {:>6} {:>6} {:>6} {:>7} {:>7.1}  {}",
+            name,
+            count(RunStatus::Success),
+            count(RunStatus::Failed),
+            count(RunStatus::Error),
+            count(RunStatus::Timeout),
+            avg_secs,
+            listed
+        );
     }
 
-    Ok(())
+    let passed = runs
+        .iter()
+        .filter(|run| run.status == RunStatus::Success)
+        .count();
+    println!("\n{} of {} runs succeeded", passed, runs.len());
 }
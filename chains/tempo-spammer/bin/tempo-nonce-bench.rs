@@ -0,0 +1,141 @@
+//! Nonce Bench - Data for tuning `[nonce] shard_count` and the wallet cooldown
+//!
+//! Runs one round per shard count and cooldown against the configured RPC,
+//! reading nonces only, and prints a table. See `tempo_spammer::nonce_bench`.
+
+use anyhow::{Context, Result};
+use clap::Parser;
+use dotenv::dotenv;
+use std::path::PathBuf;
+use std::time::Duration;
+use tempo_spammer::TempoClient;
+use tempo_spammer::config::TempoSpammerConfig;
+use tempo_spammer::nonce_bench::{self, BenchSettings};
+
+#[derive(Parser, Debug)]
+#[command(
+    author,
+    version,
+    about = "Benchmark the robust nonce manager against the live RPC"
+)]
+struct Args {
+    /// Path to config.toml
+    #[arg(short, long, default_value = tempo_spammer::config::DEFAULT_CONFIG_PATH)]
+    config: PathBuf,
+
+    /// Config profile to apply, `[profiles.<name>]`
+    #[arg(long)]
+    profile: Option<String>,
+
+    /// Concurrent workers [default: worker_count from the config]
+    #[arg(long)]
+    workers: Option<usize>,
+
+    /// Simulated wallets, read from the RPC but never sent from
+    #[arg(long, default_value = "100")]
+    wallets: usize,
+
+    /// Shard counts to compare [default: [nonce] shard_count]
+    #[arg(long, value_delimiter = ',')]
+    shards: Vec<usize>,
+
+    /// Wallet cooldowns to compare in ms [default: [nonce] base_cooldown_ms]
+    #[arg(long, value_delimiter = ',')]
+    cooldown_ms: Vec<u64>,
+
+    /// Length of each round
+    #[arg(long, default_value = "10")]
+    duration_secs: u64,
+
+    /// Share of sends rejected as "nonce too low"
+    #[arg(long, default_value = "0.01")]
+    error_rate: f64,
+
+    /// Share of sends failing before the node, releasing their nonce
+    #[arg(long, default_value = "0.02")]
+    release_rate: f64,
+
+    /// Nonces the node is ahead on an injected "nonce too low"
+    #[arg(long, default_value = "2")]
+    ahead: u64,
+
+    /// Also write every round as JSON
+    #[arg(long)]
+    json: Option<PathBuf>,
+}
+
+#[tokio::main]
+async fn main() -> Result<()> {
+    dotenv().ok();
+    let args = Args::parse();
+
+    let config_path = tempo_spammer::config::resolve_config_path(&args.config);
+    let config = TempoSpammerConfig::from_path_with_profile(&config_path, args.profile.as_deref())
+        .context("Failed to load config")?;
+
+    // Reads only; the key signs nothing
+    let key = alloy::signers::local::PrivateKeySigner::random();
+    let client = TempoClient::new(
+        &config.rpc_url,
+        &format!("0x{}", hex::encode(key.to_bytes())),
+        None,
+        None,
+    )
+    .await
+    .context("Failed to connect to the RPC")?;
+
+    let shards = match args.shards.as_slice() {
+        [] => vec![config.nonce.shard_count],
+        shards => shards.to_vec(),
+    };
+    let cooldowns = match args.cooldown_ms.as_slice() {
+        [] => vec![
+            config
+                .nonce
+                .base_cooldown_ms
+                .max(config.nonce.min_cooldown_ms),
+        ],
+        cooldowns => cooldowns.to_vec(),
+    };
+
+    println!("Nonce bench against {}", config.rpc_url);
+    let mut reports = Vec::new();
+    for &shard_count in &shards {
+        for &cooldown_ms in &cooldowns {
+            let settings = BenchSettings {
+                workers: args.workers.unwrap_or(config.worker_count as usize),
+                wallets: args.wallets,
+                shard_count,
+                cooldown: Duration::from_millis(cooldown_ms),
+                duration: Duration::from_secs(args.duration_secs),
+                error_rate: args.error_rate,
+                release_rate: args.release_rate,
+                ahead: args.ahead,
+            };
+            println!(
+                "Round: {} shards, {}ms cooldown, {} workers, {} wallets, {}s",
+                shard_count, cooldown_ms, settings.workers, settings.wallets, args.duration_secs
+            );
+            reports.push(nonce_bench::run_round(&client, &settings).await?);
+        }
+    }
+
+    println!();
+    print!("{}", nonce_bench::render(&reports));
+    if let Some(rpc) = reports.first().map(|r| r.rpc) {
+        println!(
+            "\nRPC eth_getTransactionCount: p50 {:.1}ms, p95 {:.1}ms ({} reads in the first round)",
+            rpc.p50_ms, rpc.p95_ms, rpc.count
+        );
+    }
+    if reports.iter().any(|r| r.duplicates + r.stale_retries > 0) {
+        println!("WARNING: rounds with bad nonces (duplicates or stale retries) above");
+    }
+
+    if let Some(path) = &args.json {
+        std::fs::write(path, serde_json::to_string_pretty(&reports)?)
+            .with_context(|| format!("Failed to write {}", path.display()))?;
+        println!("Report written to {}", path.display());
+    }
+    Ok(())
+}
@@ -1,21 +1,27 @@
 use anyhow::{Context, Result};
 use clap::Parser;
-use core_logic::WalletManager;
 use core_logic::database::DatabaseManager;
+use core_logic::{Tone, WalletManager, paint};
 use dotenv::dotenv;
 use std::env;
 use std::io::Write;
+use std::path::PathBuf;
 
 use std::time::Duration;
 use tempo_spammer::config::TempoSpammerConfig;
 use tempo_spammer::tasks::{TaskContext, TempoTask, load_proxies};
+use zeroize::Zeroizing;
 
 #[derive(Parser, Debug)]
 #[command(author, version, about, long_about = None)]
 struct Args {
     /// Path to config.toml
-    #[arg(short, long, default_value = "config/config.toml")]
-    config: String,
+    #[arg(short, long, default_value = tempo_spammer::config::DEFAULT_CONFIG_PATH)]
+    config: PathBuf,
+
+    /// Config profile to apply, `[profiles.<name>]`
+    #[arg(long)]
+    profile: Option<String>,
 
     /// Skip database logging
     #[arg(long, default_value = "false")]
@@ -43,31 +49,22 @@ async fn main() -> Result<()> {
     let args = Args::parse();
 
     // 1. Load Config
-    let config_path = if std::path::Path::new(&args.config).exists() {
-        args.config.clone()
-    } else if args.config == "config/config.toml"
-        && std::path::Path::new("chains/tempo-spammer/config/config.toml").exists()
-    {
-        "chains/tempo-spammer/config/config.toml".to_string()
-    } else {
-        args.config.clone()
-    };
-    let config = TempoSpammerConfig::from_path(&config_path).context("Failed to load config")?;
+    let config_path = tempo_spammer::config::resolve_config_path(&args.config);
+    let config = TempoSpammerConfig::from_path_with_profile(&config_path, args.profile.as_deref())
+        .context("Failed to load config")?;
+    tempo_spammer::network::configure(&config.contracts);
 
     // 2. Load Wallets
-    let wallet_password = env::var("WALLET_PASSWORD").ok();
-    let wallet_manager = WalletManager::new()?;
+    let wallet_password = env::var("WALLET_PASSWORD").ok().map(Zeroizing::new);
+    let wallet_manager = WalletManager::with_options(config.wallets.wallet_options())?;
     let total_wallets = wallet_manager.count();
     if total_wallets == 0 {
         return Err(anyhow::anyhow!("No wallets found"));
     }
 
     // 3. Initialize ClientPool
-    let config_dir = std::path::Path::new(&config_path)
-        .parent()
-        .unwrap_or(std::path::Path::new("."));
-    let proxies_path = config_dir.join("proxies.txt");
-    let proxies = load_proxies(proxies_path.to_str().unwrap_or("config/proxies.txt"))?;
+    let proxies_path = tempo_spammer::config::proxies_path(&config_path);
+    let proxies = load_proxies(&proxies_path)?;
     let total_proxies = proxies.len();
 
     // 4. Initialize DB and ClientPool
@@ -284,13 +281,15 @@ async fn main() -> Result<()> {
         std::io::stdout().flush()?;
 
         if res.success {
-            println!("\x1b[32m✅ {:.2}s\x1b[0m", res.duration.as_secs_f64());
+            let line = format!("✅ {:.2}s", res.duration.as_secs_f64());
+            println!("{}", paint(Tone::Success, line));
         } else {
-            println!(
-                "\x1b[31m❌ {:.2}s - Error: {}\x1b[0m",
+            let line = format!(
+                "❌ {:.2}s - Error: {}",
                 res.duration.as_secs_f64(),
                 res.error.as_deref().unwrap_or_default()
             );
+            println!("{}", paint(Tone::Error, line));
         }
 
         results.push(res);
@@ -307,37 +306,39 @@ async fn main() -> Result<()> {
         / total_tasks as f64;
     let longest_task = results.iter().max_by_key(|r| r.duration).unwrap();
 
-    let rate_color = if success_rate >= 90.0 {
-        "\x1b[32m"
+    let rate_tone = if success_rate >= 90.0 {
+        Tone::Success
     } else if success_rate >= 70.0 {
-        "\x1b[33m"
+        Tone::Warning
     } else {
-        "\x1b[31m"
+        Tone::Error
     };
-    let cyan = "\x1b[36m";
-    let magenta = "\x1b[35m";
-    let bold = "\x1b[1m";
-    let reset = "\x1b[0m";
 
     println!(
-        "\n{}================ REPORT ================{}",
-        bold, reset
+        "\n{}",
+        paint(Tone::Strong, "================ REPORT ================")
     );
     println!(
-        "Task success rate: {}{:.2}% ({} of {}){}",
-        rate_color, success_rate, passed_tasks, total_tasks, reset
+        "Task success rate: {}",
+        paint(
+            rate_tone,
+            format!("{:.2}% ({} of {})", success_rate, passed_tasks, total_tasks)
+        )
     );
     println!(
-        "Average task duration: {}{:.2}s{}",
-        cyan, avg_duration, reset
+        "Average task duration: {}",
+        paint(Tone::Info, format!("{:.2}s", avg_duration))
     );
     println!(
-        "Longest task duration: {}{}Task {:02} ({:.2}s){}",
-        magenta,
-        bold,
-        longest_task.id,
-        longest_task.duration.as_secs_f64(),
-        reset
+        "Longest task duration: {}",
+        paint(
+            Tone::Accent,
+            format!(
+                "Task {:02} ({:.2}s)",
+                longest_task.id,
+                longest_task.duration.as_secs_f64()
+            )
+        )
     );
     println!("------------------------------------------");
     println!("Full report saved to: debug_report.md\n");
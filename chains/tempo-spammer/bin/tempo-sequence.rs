@@ -5,6 +5,7 @@ use core_logic::database::DatabaseManager;
 use dialoguer::{Input, Password, theme::ColorfulTheme};
 use dotenv::dotenv;
 use std::env;
+use std::path::PathBuf;
 
 use rand::Rng;
 use std::sync::Arc;
@@ -12,6 +13,7 @@ use std::time::Duration;
 use tempo_spammer::config::TempoSpammerConfig;
 use tempo_spammer::tasks::{TaskContext, TempoTask, load_proxies};
 use tokio::sync::Semaphore;
+use zeroize::Zeroizing;
 
 // Include compile-time configuration from build.rs
 include!(concat!(env!("OUT_DIR"), "/build_config.rs"));
@@ -24,8 +26,12 @@ include!(concat!(env!("OUT_DIR"), "/build_config.rs"));
 )]
 struct Args {
     /// Path to config.toml
-    #[arg(short, long, default_value = "config/config.toml")]
-    config: String,
+    #[arg(short, long, default_value = tempo_spammer::config::DEFAULT_CONFIG_PATH)]
+    config: PathBuf,
+
+    /// Config profile to apply, `[profiles.<name>]`
+    #[arg(long)]
+    profile: Option<String>,
 
     /// Skip database logging
     #[arg(long, default_value = "false")]
@@ -54,28 +60,25 @@ async fn main() -> Result<()> {
     let args = Args::parse();
 
     // 1. Load Config
-    let config_path = if std::path::Path::new(&args.config).exists() {
-        args.config.clone()
-    } else if args.config == "config/config.toml"
-        && std::path::Path::new("chains/tempo-spammer/config/config.toml").exists()
-    {
-        "chains/tempo-spammer/config/config.toml".to_string()
-    } else {
-        args.config.clone()
-    };
-    let config = TempoSpammerConfig::from_path(&config_path).context("Failed to load config")?;
+    let config_path = tempo_spammer::config::resolve_config_path(&args.config);
+    let config = TempoSpammerConfig::from_path_with_profile(&config_path, args.profile.as_deref())
+        .context("Failed to load config")?;
+    tempo_spammer::network::configure(&config.contracts);
 
     // 2. Load Wallets - SMART: Auto-detect all wallets
     // Priority: env var > compile-time > interactive prompt
-    let mut wallet_password = env::var("WALLET_PASSWORD").ok().or_else(|| {
-        if !COMPILE_TIME_PASSWORD.is_empty() {
-            Some(COMPILE_TIME_PASSWORD.to_string())
-        } else {
-            None
-        }
-    });
+    let mut wallet_password = env::var("WALLET_PASSWORD")
+        .ok()
+        .or_else(|| {
+            if !COMPILE_TIME_PASSWORD.is_empty() {
+                Some(COMPILE_TIME_PASSWORD.to_string())
+            } else {
+                None
+            }
+        })
+        .map(Zeroizing::new);
 
-    let wallet_manager = WalletManager::new()?;
+    let wallet_manager = WalletManager::with_options(config.wallets.wallet_options())?;
     let total_wallets = wallet_manager.count();
     if total_wallets == 0 {
         return Err(anyhow::anyhow!("No wallets found in wallet-json directory"));
@@ -83,7 +86,7 @@ async fn main() -> Result<()> {
 
     // Interactive password prompt if env var not set or invalid
     if let Err(_) = wallet_manager
-        .get_wallet(0, wallet_password.as_deref())
+        .get_wallet(0, wallet_password.as_deref().map(String::as_str))
         .await
     {
         println!("\n⚠️  Wallet decryption failed (password not set or invalid).");
@@ -91,11 +94,11 @@ async fn main() -> Result<()> {
             .with_prompt("Enter wallet password")
             .report(true) // Show asterisks (*****) when typing
             .interact()?;
-        wallet_password = Some(input);
+        wallet_password = Some(Zeroizing::new(input));
 
         // Validate again
         if let Err(e) = wallet_manager
-            .get_wallet(0, wallet_password.as_deref())
+            .get_wallet(0, wallet_password.as_deref().map(String::as_str))
             .await
         {
             return Err(anyhow::anyhow!(
@@ -176,11 +179,8 @@ async fn main() -> Result<()> {
     println!("---------------------------------------------------");
 
     // 6. Initialize ClientPool
-    let config_dir = std::path::Path::new(&config_path)
-        .parent()
-        .unwrap_or(std::path::Path::new("."));
-    let proxies_path = config_dir.join("proxies.txt");
-    let proxies = load_proxies(proxies_path.to_str().unwrap_or("config/proxies.txt"))?;
+    let proxies_path = tempo_spammer::config::proxies_path(&config_path);
+    let proxies = load_proxies(&proxies_path)?;
 
     let client_pool = Arc::new(
         tempo_spammer::ClientPool::new(
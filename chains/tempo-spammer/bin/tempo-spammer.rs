@@ -1,23 +1,28 @@
+use alloy_primitives::{Address, U256};
 use anyhow::{Context, Result};
 use clap::{Parser, Subcommand};
-use core_logic::WalletManager;
-use core_logic::database::{AsyncDbConfig, DatabaseManager, FallbackStrategy, QueuedTaskResult};
-use core_logic::setup_logger;
+use core_logic::database::{DatabaseManager, ExportFormat, ShardedDatabase};
+use core_logic::{HostPartition, WalletManager, WalletOptions, WalletTags};
+use core_logic::{setup_logger_with_rotation, setup_logger_with_tracing};
 use dialoguer::{Input, Password, theme::ColorfulTheme};
 use dotenv::dotenv;
-use futures::future::join_all;
-
-use rand::distributions::{Distribution, WeightedIndex};
-use rand::rngs::StdRng;
-use rand::{Rng, SeedableRng};
 use std::env;
+use std::ops::Range;
+use std::path::{Path, PathBuf};
+use std::str::FromStr;
 use std::sync::Arc;
 use std::time::Duration;
-use tempo_spammer::ProxyBanlist;
 use tempo_spammer::TempoClient;
-use tempo_spammer::bot::notification::spawn_notification_service;
+use tempo_spammer::budget::{SpendBudget, token_label};
+use tempo_spammer::bundle::BundleOptions;
 use tempo_spammer::config::TempoSpammerConfig as Config;
-use tempo_spammer::tasks::{TaskContext, TempoTask, load_proxies};
+use tempo_spammer::config::{ControlSettings, DaemonSettings};
+use tempo_spammer::daemon::{self, PidFile, Started};
+use tempo_spammer::offline::{self, BroadcastOptions, BroadcastSummary, Delivery, SignOptions};
+use tempo_spammer::price;
+use tempo_spammer::runtime::{RuntimeOptions, SpammerRuntime, UnlockedWallets};
+use tempo_spammer::tasks::{TaskContext, TempoTask};
+use tempo_spammer::tx_builder::FeeProfile;
 use tracing::{error, info, warn};
 use zeroize::Zeroizing;
 
@@ -27,8 +32,44 @@ include!(concat!(env!("OUT_DIR"), "/build_config.rs"));
 #[derive(Parser, Debug)]
 #[command(author, version, about, long_about = None)]
 struct Args {
-    #[arg(short, long, default_value = "config/config.toml")]
-    config: String,
+    #[arg(short, long, default_value = tempo_spammer::config::DEFAULT_CONFIG_PATH)]
+    config: PathBuf,
+
+    /// Apply `[profiles.<name>]` of the config over its shared settings, e.g. `local`
+    #[arg(long, global = true)]
+    profile: Option<String>,
+
+    /// Run `[local_dev] tasks` against anvil with mock system contracts, then exit
+    #[arg(long)]
+    local_dev: bool,
+
+    /// Never prompt; fail with an error when input would be needed
+    #[arg(long, global = true)]
+    non_interactive: bool,
+
+    /// File whose first line is the wallet password
+    #[arg(long, global = true)]
+    password_file: Option<PathBuf>,
+
+    /// Only use wallets with this tag, repeat for any of several [default: `[wallets] tags`]
+    #[arg(long = "wallet-tag", global = true)]
+    wallet_tags: Vec<String>,
+
+    /// Only use this host's share of the wallets, as `index/total` [default: `[wallets] partition`]
+    #[arg(long, global = true)]
+    wallet_partition: Option<HostPartition>,
+
+    /// Append every JSON-RPC request and answer to this file [default: `[rpc_recording]`]
+    #[arg(long, global = true)]
+    record_rpc: Option<PathBuf>,
+
+    /// Detach into the background; `status` and `stop` reach the detached run
+    #[arg(long)]
+    daemon: bool,
+
+    /// Set on the detached copy started by --daemon
+    #[arg(long, hide = true)]
+    daemon_child: bool,
 
     #[command(subcommand)]
     command: Option<Commands>,
@@ -49,9 +90,269 @@ enum Commands {
         task: String,
     },
     List,
+    /// Interactive first-time setup: RPC, wallets, recipients, proxies and config
+    Init,
+    /// Join a coordinated burst round shared through the database
+    Burst {
+        /// Round id; instances using the same id fire together
+        #[arg(short, long)]
+        round: String,
+        /// Name to report this instance under [default: $HOSTNAME-<pid>]
+        #[arg(long)]
+        instance: Option<String>,
+        /// Transactions each wallet signs ahead and sends at the start [default: [burst] presign]
+        #[arg(long)]
+        presign: Option<u32>,
+    },
+    /// Check system contracts and deploy missing helper contracts after a testnet reset
+    Bootstrap {
+        /// Only report, deploy nothing
+        #[arg(long)]
+        check_only: bool,
+        /// Redeploy helpers even if the recorded copy is still on chain
+        #[arg(long, conflicts_with = "check_only")]
+        force: bool,
+    },
+    /// Archive a run's config, metrics, database rows, HTML report and logs into one zip
+    Bundle {
+        /// Start of the run: `now`, an age like 6h, unix seconds or RFC 3339
+        #[arg(long)]
+        since: String,
+        /// End of the run, same formats as --since
+        #[arg(long, default_value = "now")]
+        until: String,
+        /// Archive path [default: `<[bundle] dir>/tempo-<chain>-<since>.zip`]
+        #[arg(short, long)]
+        out: Option<std::path::PathBuf>,
+        /// Base database path [default: `[database] path` from the config]
+        #[arg(long)]
+        db: Option<String>,
+    },
+    /// Write a database table to CSV or Parquet for offline analysis
+    Export {
+        /// Table to export, e.g. task_metrics, gas_usage or audits
+        #[arg(default_value = "task_metrics")]
+        table: String,
+        /// csv or parquet
+        #[arg(short, long, default_value = "csv")]
+        format: ExportFormat,
+        /// Output file [default: `<table>.<csv|parquet>`]
+        #[arg(short, long)]
+        out: Option<PathBuf>,
+        /// Only rows since: an age like 6h, unix seconds or RFC 3339
+        #[arg(long)]
+        since: Option<String>,
+        /// Only rows until, same formats as --since
+        #[arg(long)]
+        until: Option<String>,
+        /// Database path [default: `[database] path` from the config]
+        #[arg(long)]
+        db: Option<String>,
+    },
+    /// Database maintenance commands
+    Db {
+        #[command(subcommand)]
+        action: DbCommands,
+    },
+    /// List wallet files and manage their tags in `wallet-tags.json`
+    Wallets {
+        #[command(subcommand)]
+        action: WalletCommands,
+    },
+    /// State, workers, uptime and task counts of the running spammer
+    Status,
+    /// Stop the running spammer, e.g. one started with --daemon
+    Stop,
+    /// Send a command to a running spammer's control socket, e.g. `ctl log set nonce=debug`
+    Ctl {
+        /// Control socket address [default: `[control] listen` from the config]
+        #[arg(long)]
+        addr: Option<String>,
+        /// Command words; run `ctl help` for the list
+        #[arg(trailing_var_arg = true)]
+        command: Vec<String>,
+    },
+    /// Sign a batch of TIP-20 transfers into a file without network access
+    Sign {
+        /// Wallets to sign with, as indices `start..end`
+        #[arg(long, value_parser = parse_wallet_range, default_value = "0..1")]
+        wallets: Range<usize>,
+        /// Transactions per wallet
+        #[arg(short = 'n', long, default_value = "10")]
+        count: usize,
+        /// First nonce of every wallet not in the --after batch
+        #[arg(long, default_value = "0")]
+        nonce: u64,
+        /// Continue each wallet's nonces after its last transaction in this batch
+        #[arg(long)]
+        after: Option<PathBuf>,
+        /// TIP-20 token to transfer [default: PathUSD]
+        #[arg(long)]
+        token: Option<Address>,
+        /// Recipient [default: each wallet itself]
+        #[arg(long)]
+        to: Option<Address>,
+        /// Amount in the token's base units
+        #[arg(long, default_value = "1")]
+        amount: U256,
+        #[arg(long, default_value = "100000")]
+        gas_limit: u64,
+        /// fast, normal or cheap
+        #[arg(long, default_value = "normal")]
+        fee_profile: FeeProfile,
+        /// Batch file to create; an existing file is never overwritten
+        #[arg(short, long)]
+        out: PathBuf,
+    },
+    /// Send a batch written by `sign` to the RPC and track its receipts
+    Broadcast {
+        /// Batch file
+        path: PathBuf,
+        /// Transactions sent per second
+        #[arg(long, default_value = "10")]
+        rate: f64,
+        /// Seconds to wait for receipts after the last send
+        #[arg(long, default_value = "60")]
+        settle_secs: u64,
+        /// Write the outcome of every transaction to this JSONL file
+        #[arg(long)]
+        receipts: Option<PathBuf>,
+        /// RPC endpoint [default: `rpc_url` from the config]
+        #[arg(long)]
+        rpc_url: Option<String>,
+    },
+    /// Decode a block or transaction with the Tempo protocol types
+    Inspect {
+        #[command(subcommand)]
+        target: InspectCommands,
+        /// RPC endpoint [default: `rpc_url` from the config]
+        #[arg(long)]
+        rpc_url: Option<String>,
+    },
+}
+
+#[derive(Subcommand, Debug)]
+enum InspectCommands {
+    /// Header extras, transactions and subblocks of a block
+    Block {
+        /// Block number, hash or tag such as latest
+        #[arg(default_value = "latest")]
+        block: String,
+    },
+    /// Calls, fee token, nonce lane, validity window and receipt of a transaction
+    Tx { hash: alloy_primitives::B256 },
+}
+
+#[derive(Subcommand, Debug)]
+enum WalletCommands {
+    /// Index, file, address and tags of each wallet a run would use, without decrypting
+    List,
+    /// Tag wallets, given by address or file name
+    Tag {
+        tag: String,
+        #[arg(required = true)]
+        wallets: Vec<String>,
+    },
+    /// Remove a tag from wallets, given by address or file name
+    Untag {
+        tag: String,
+        #[arg(required = true)]
+        wallets: Vec<String>,
+    },
+}
+
+#[derive(Subcommand, Debug)]
+enum DbCommands {
+    /// Merge a JSONL spill file of offline task results into the database
+    Import {
+        /// Path to the spill file
+        path: String,
+        #[arg(long, default_value = "tempo-spammer.db")]
+        db: String,
+    },
+    /// Per-task totals across the database and all of its monthly shards
+    Stats {
+        /// Base database path (shards are found next to it)
+        #[arg(long, default_value = "tempo-spammer.db")]
+        db: String,
+    },
+    /// Requests, estimated traffic and success rate per proxy, most traffic first
+    Proxies {
+        /// Base database path (shards are found next to it)
+        #[arg(long, default_value = "tempo-spammer.db")]
+        db: String,
+    },
+    /// Token spend per wallet and in total against the `[budget]` caps
+    Budget {
+        /// UTC day as YYYY-MM-DD [default: today]
+        #[arg(long)]
+        day: Option<String>,
+        #[arg(long, default_value = "tempo-spammer.db")]
+        db: String,
+    },
+    /// Per-instance send rates and inclusion outcomes of a burst round
+    Bursts {
+        /// Round id passed to `burst --round`
+        round: String,
+        #[arg(long, default_value = "tempo-spammer.db")]
+        db: String,
+    },
+    /// Send to inclusion to confirmation percentiles per subblock of recorded transactions
+    Latency {
+        /// Only samples of this source, e.g. `burst:spike-1`
+        #[arg(short, long)]
+        source: Option<String>,
+        /// Only transactions sent since then: an age like 6h, unix seconds or RFC 3339
+        #[arg(long)]
+        since: Option<String>,
+        #[arg(long, default_value = "tempo-spammer.db")]
+        db: String,
+    },
+    /// Distinct contracts and event signatures in the archived receipt logs
+    Coverage {
+        /// Only logs of this task
+        #[arg(short, long)]
+        task: Option<String>,
+        /// Read a `[log_archive]` JSONL file instead of the database
+        #[arg(long)]
+        file: Option<PathBuf>,
+        #[arg(long, default_value = "tempo-spammer.db")]
+        db: String,
+    },
+    /// Read-your-writes checks per task: inconsistencies and propagation lag
+    Consistency {
+        #[arg(long, default_value = "tempo-spammer.db")]
+        db: String,
+    },
+    /// Recent `[audit]` samples with their RPC steps, newest first
+    Audits {
+        /// Only audits of this task
+        #[arg(short, long)]
+        task: Option<String>,
+        #[arg(short, long, default_value = "10")]
+        limit: i64,
+        #[arg(long, default_value = "tempo-spammer.db")]
+        db: String,
+    },
+    /// Successes and reverts per fuzzed selector of task 66_abi_fuzz_call
+    Fuzz {
+        /// Chain to show [default: the config's chain_id]
+        #[arg(long)]
+        chain_id: Option<u64>,
+        #[arg(long, default_value = "tempo-spammer.db")]
+        db: String,
+    },
 }
+
 #[tokio::main]
 async fn main() -> Result<()> {
+    let result = run().await;
+    // Spans still waiting in the export batch go out before exiting
+    core_logic::shutdown_tracing();
+    result
+}
+
+async fn run() -> Result<()> {
     dotenv().ok();
 
     let args = Args::parse();
@@ -64,10 +365,33 @@ async fn main() -> Result<()> {
         _ => (false, false),
     };
 
-    if !is_quiet {
-        let _log_guard = setup_logger();
+    // Auto-detect config path if default is not found
+    let config_path = tempo_spammer::config::resolve_config_path(&args.config);
+    let profile = args.profile.as_deref();
+    // Read early for the logger, the PID file and the control socket; a bad
+    // config is reported when the run loads it
+    let settings = Config::from_path_with_profile(&config_path, profile).ok();
+
+    if args.daemon {
+        return start_daemon(&args, &config_path).await;
+    }
+
+    let log_levels = if !is_quiet {
+        let rotation = settings
+            .as_ref()
+            .map(|config| config.logging.log_rotation())
+            .unwrap_or_default();
+        let otlp = settings
+            .as_ref()
+            .and_then(|config| config.tracing.otlp_config());
+        let logger = setup_logger_with_tracing(rotation.clone(), otlp.as_ref());
+        let (_log_guard, levels) = logger.unwrap_or_else(|e| {
+            eprintln!("Warning: {:#}; running without span export", e);
+            setup_logger_with_rotation(rotation)
+        });
         // Keep guard alive for file logging - will be dropped at end of main()
         std::mem::forget(_log_guard);
+        Some(levels)
     } else {
         // Minimal logger for quiet mode (Errors only, or muted stdout)
         // For now, we just skip setup_logger which typically enables the flashy output
@@ -78,22 +402,167 @@ async fn main() -> Result<()> {
         tracing_subscriber::fmt()
             .with_max_level(tracing::Level::ERROR)
             .init();
+        None
+    };
+
+    // Setup writes the config rather than reading it
+    if let Some(Commands::Init) = &args.command {
+        if core_logic::non_interactive(args.non_interactive, false) {
+            anyhow::bail!("init asks questions and needs an interactive terminal");
+        }
+        return tempo_spammer::init::run_wizard(&args.config).await;
     }
 
-    // Auto-detect config path if default is not found
-    let config_path = if std::path::Path::new(&args.config).exists() {
-        args.config.clone()
-    } else if args.config == "config/config.toml"
-        && std::path::Path::new("chains/tempo-spammer/config/config.toml").exists()
+    // Database maintenance needs neither wallets nor proxies
+    if let Some(Commands::Db { action }) = &args.command {
+        if let Some(config) = &settings {
+            let headless =
+                core_logic::non_interactive(args.non_interactive, config.non_interactive);
+            unlock_database(config, headless)?;
+        }
+        return run_db_command(action, &config_path, profile).await;
+    }
+    if let Some(Commands::Status) = &args.command {
+        return print_status(settings.as_ref()).await;
+    }
+    if let Some(Commands::Stop) = &args.command {
+        return stop_running(settings.as_ref()).await;
+    }
+    if let Some(Commands::Ctl { addr, command }) = &args.command {
+        let addr = match addr {
+            Some(addr) => addr.clone(),
+            None => Config::from_path_with_profile(&config_path, profile)
+                .map(|config| config.control.listen)
+                .unwrap_or_else(|_| ControlSettings::default().listen),
+        };
+        let reply = tempo_spammer::control::send_command(&addr, &command.join(" ")).await?;
+        println!("{}", reply);
+        if reply.starts_with("error: ") {
+            std::process::exit(1);
+        }
+        return Ok(());
+    }
+    if let Some(Commands::Inspect { target, rpc_url }) = &args.command {
+        let rpc_url = match rpc_url {
+            Some(url) => url.clone(),
+            None => {
+                Config::from_path_with_profile(&config_path, profile)
+                    .context("Failed to load config, pass --rpc-url")?
+                    .rpc_url
+            }
+        };
+        return run_inspect(target, &rpc_url).await;
+    }
+    if let Some(Commands::Broadcast {
+        path,
+        rate,
+        settle_secs,
+        receipts,
+        rpc_url,
+    }) = &args.command
     {
-        "chains/tempo-spammer/config/config.toml".to_string()
-    } else {
-        args.config.clone()
-    };
+        let rpc_url = match rpc_url {
+            Some(url) => url.clone(),
+            None => {
+                Config::from_path_with_profile(&config_path, profile)
+                    .context("Failed to load config, pass --rpc-url")?
+                    .rpc_url
+            }
+        };
+        let options = BroadcastOptions {
+            rate: *rate,
+            settle: Duration::from_secs(*settle_secs),
+        };
+        return run_broadcast(path, &rpc_url, options, receipts.as_deref()).await;
+    }
 
-    let config = Config::from_path(&config_path).context("Failed to load config")?;
+    let mut config =
+        Config::from_path_with_profile(&config_path, profile).context("Failed to load config")?;
+    if args.daemon_child {
+        // `status` and `stop` are the only way to reach a detached run
+        config.control.enabled = true;
+    }
+    if !args.wallet_tags.is_empty() {
+        config.wallets.tags = args.wallet_tags.clone();
+    }
+    if let Some(partition) = args.wallet_partition {
+        config.wallets.partition = Some(partition);
+    }
+    if let Some(path) = &args.record_rpc {
+        config.rpc_recording.enabled = true;
+        config.rpc_recording.path = path.to_string_lossy().into_owned();
+    }
+    tempo_spammer::network::configure(&config.contracts);
 
-    if !is_quiet {
+    // Tags are managed without decrypting any wallet
+    if let Some(Commands::Wallets { action }) = &args.command {
+        return run_wallet_command(action, &config);
+    }
+
+    unlock_database(
+        &config,
+        core_logic::non_interactive(
+            args.non_interactive || args.daemon_child,
+            config.non_interactive,
+        ),
+    )?;
+
+    if let Some(Commands::Export {
+        table,
+        format,
+        out,
+        since,
+        until,
+        db,
+    }) = &args.command
+    {
+        let now = chrono::Utc::now();
+        let parse = |value: &Option<String>| {
+            value
+                .as_deref()
+                .map(|value| tempo_spammer::bundle::parse_time(value, now).map(|at| at.timestamp()))
+                .transpose()
+        };
+        let out = out
+            .clone()
+            .unwrap_or_else(|| PathBuf::from(format!("{}.{}", table, format.extension())));
+        let db_path = db.clone().unwrap_or_else(|| config.database.path.clone());
+        if !Path::new(&db_path).exists() {
+            anyhow::bail!("Database {} not found", db_path);
+        }
+        let db_manager = DatabaseManager::new(&db_path).await?;
+        let rows = db_manager
+            .export_table(table, *format, &out, parse(since)?, parse(until)?)
+            .await?;
+        println!("Exported {} rows of {} to {}", rows, table, out.display());
+        return db_manager.shutdown().await;
+    }
+
+    if let Some(Commands::Bundle {
+        since,
+        until,
+        out,
+        db,
+    }) = &args.command
+    {
+        let now = chrono::Utc::now();
+        let options = BundleOptions {
+            config_path: config_path.clone(),
+            db_path: db.clone().unwrap_or_else(|| config.database.path.clone()),
+            since: tempo_spammer::bundle::parse_time(since, now)?,
+            until: tempo_spammer::bundle::parse_time(until, now)?,
+            out: out.clone(),
+        };
+        return print_bundle(&config, &options).await;
+    }
+
+    if args.local_dev {
+        return run_local_dev(&config).await;
+    }
+
+    // Signing is offline; the run banner and targets would only mislead
+    let signing = matches!(args.command, Some(Commands::Sign { .. }));
+    if !is_quiet && !signing {
         println!(
             r#"
         ╔════════════════════════════════════════════════════════════╗
@@ -102,62 +571,119 @@ async fn main() -> Result<()> {
         "#
         );
     }
-    info!(target: "task_result", "Target RPC: {}", config.rpc_url);
-    info!(target: "task_result", "Target Chain ID: {}", config.chain_id);
-    info!(
-        target: "task_result",
-        "Workers: {}",
-        args.command
-            .as_ref()
-            .and_then(|c| match c {
-                Commands::Spammer { workers, .. } => *workers,
-                _ => None,
-            })
-            .unwrap_or(config.worker_count)
-    );
-    info!(
-        target: "task_result",
-        "Interval: {}ms - {}ms",
-        config.task_interval_min, config.task_interval_max
+    if !signing {
+        if let Some(profile) = &config.profile {
+            info!(target: "task_result", "Profile: {}", profile);
+        }
+        info!(target: "task_result", "Target RPC: {}", config.rpc_url);
+        info!(target: "task_result", "Target Chain ID: {}", config.chain_id);
+        info!(
+            target: "task_result",
+            "Workers: {}",
+            args.command
+                .as_ref()
+                .and_then(|c| match c {
+                    Commands::Spammer { workers, .. } => *workers,
+                    _ => None,
+                })
+                .unwrap_or(config.worker_count)
+        );
+        info!(
+            target: "task_result",
+            "Interval: {}ms - {}ms",
+            config.task_interval_min, config.task_interval_max
+        );
+    }
+
+    // Headless runs take the password from a file or the environment
+    let headless = core_logic::non_interactive(
+        args.non_interactive || args.daemon_child,
+        config.non_interactive,
     );
+    let password_file = args
+        .password_file
+        .as_deref()
+        .or(config.wallets.password_file.as_deref());
+    let configured_password = core_logic::wallet_password(password_file)?;
 
-    // Prompt for wallet password at runtime (never stored in binary)
-    let wallet_manager = WalletManager::new()?;
+    let wallet_manager = WalletManager::with_options(config.wallets.wallet_options())?;
     let total_wallets = wallet_manager.count();
 
     if total_wallets == 0 {
+        if headless {
+            anyhow::bail!("No wallets found in wallet-json/ or pv.txt");
+        }
         error!("No wallets found");
         return Ok(());
     }
 
-    // Prompt for password immediately at startup
     if !is_quiet {
         println!("\n🔐 Wallet Configuration:");
         println!("   Found {} wallets", total_wallets);
     }
 
-    let password_input = Password::with_theme(&ColorfulTheme::default())
-        .with_prompt("Enter wallet password")
-        .report(true) // Show asterisks (*****) when typing
-        .interact()?;
-
-    // Wrap password in Zeroizing to ensure it's cleared from memory when dropped
-    let wallet_password = Zeroizing::new(password_input);
+    // Prompt for password at runtime (never stored in binary) unless one was
+    // configured; headless runs without one still work for raw keys in pv.txt
+    let wallet_password = match configured_password {
+        Some(password) => Some(password),
+        None if headless => None,
+        None => {
+            let password_input = Password::with_theme(&ColorfulTheme::default())
+                .with_prompt("Enter wallet password")
+                .report(true) // Show asterisks (*****) when typing
+                .interact()?;
+            // Wrap password in Zeroizing to ensure it's cleared from memory when dropped
+            Some(Zeroizing::new(password_input))
+        }
+    };
 
     // Validate password with first wallet
-    if let Err(e) = wallet_manager.get_wallet(0, Some(&wallet_password)).await {
-        error!("Decryption failed with provided password: {}", e);
-        return Ok(());
-    }
+    let wallets = match UnlockedWallets::unlock(wallet_manager, wallet_password).await {
+        Ok(wallets) => wallets,
+        Err(e) if headless => {
+            return Err(e.context(format!(
+                "Could not unlock wallets without a prompt; {}",
+                core_logic::password_sources_hint()
+            )));
+        }
+        Err(e) => {
+            error!("Decryption failed with provided password: {:#}", e);
+            return Ok(());
+        }
+    };
 
     if !is_quiet {
         println!("✅ Password accepted.");
     }
 
-    info!("Found {} wallets", total_wallets);
+    if let Some(Commands::Sign {
+        wallets: indices,
+        count,
+        nonce,
+        after,
+        token,
+        to,
+        amount,
+        gas_limit,
+        fee_profile,
+        out,
+    }) = &args.command
+    {
+        let options = SignOptions {
+            chain_id: config.chain_id,
+            count: *count,
+            start_nonce: *nonce,
+            token: token.unwrap_or(tempo_spammer::network::contracts().path_usd),
+            to: *to,
+            amount: *amount,
+            gas_limit: *gas_limit,
+            fee_profile: *fee_profile,
+        };
+        return run_sign(&wallets, indices.clone(), &options, after.as_deref(), out).await;
+    }
 
     // Prompt for number of workers BEFORE proxy health check
-    let runtime_workers = if !is_quiet {
+    let runtime_workers = if !is_quiet && !headless {
         println!("\n👷 Worker Configuration:");
         println!("   Available wallets: {}", total_wallets);
         println!("   Config default: {}", config.worker_count);
@@ -177,217 +703,1127 @@ async fn main() -> Result<()> {
     } else {
         config.worker_count
     };
-
-    // Get the directory containing the config file to find proxies.txt
-    let config_path_obj = std::path::Path::new(&config_path);
-    let config_dir = config_path_obj
-        .parent()
-        .unwrap_or(std::path::Path::new("."));
-
-    // Check config dir first, then root
-    let config_proxies = config_dir.join("proxies.txt");
-    let root_proxies = std::path::Path::new("proxies.txt");
-
-    let proxy_path_str = if config_proxies.exists() {
-        config_proxies
-            .to_str()
-            .unwrap_or("config/proxies.txt")
-            .to_string()
-    } else if root_proxies.exists() {
-        "proxies.txt".to_string()
-    } else {
-        "config/proxies.txt".to_string()
+    // CLI workers win over the prompt
+    let worker_count = match &args.command {
+        Some(Commands::Spammer {
+            workers: Some(workers),
+            ..
+        }) => *workers,
+        _ => runtime_workers,
     };
 
-    // Convert to slice for load_proxies
-    let proxy_path_str = proxy_path_str.as_str();
+    // The banner plays while the proxies are scanned
+    let banner_handle =
+        (!is_quiet && !args.daemon_child).then(|| tokio::spawn(display_animated_banner()));
+
+    let runtime = SpammerRuntime::build(
+        config,
+        wallets,
+        RuntimeOptions {
+            config_path,
+            workers: Some(worker_count),
+            no_proxy,
+            notifications: true,
+            log_levels,
+            database: None,
+        },
+    )
+    .await?;
+
+    if let Some(handle) = banner_handle {
+        let _ = handle.await;
+    }
 
-    // Load proxies
-    let proxies = if no_proxy {
-        Vec::new()
-    } else {
-        load_proxies(proxy_path_str).unwrap_or_else(|_| Vec::new())
-    };
+    let config = runtime.config();
+    let client_pool = runtime.client_pool().clone();
+    let db_manager = runtime.database().clone();
+
+    match args.command {
+        Some(Commands::Spammer { .. }) | None => {
+            run_and_bundle(runtime).await?;
+        }
+        Some(Commands::Run { task }) => {
+            let tasks = tempo_spammer::runner::default_tasks();
+            // run_single_task logic would need updating too, but skipping for now to focus on spammer
+            let client = client_pool
+                .get_client(0)
+                .await
+                .expect("Failed to get client 0");
+            run_single_task(&client, &tasks, &task, config, db_manager.clone()).await;
+        }
+        Some(Commands::List) => {
+            println!("Available tasks:");
+            for (i, task) in tempo_spammer::runner::default_tasks().iter().enumerate() {
+                println!("  {}: {}", i + 1, task.name());
+            }
+        }
+        Some(Commands::Burst {
+            round,
+            instance,
+            presign,
+        }) => {
+            let instance = instance.unwrap_or_else(tempo_spammer::burst::default_instance_id);
+            let result = tempo_spammer::burst::run_burst(
+                client_pool,
+                config,
+                db_manager.clone(),
+                &round,
+                &instance,
+                presign.unwrap_or(config.burst.presign),
+            )
+            .await?;
+            info!(
+                target: "task_result",
+                "Burst {} as {}: {} sent at {:.1} tx/s, {} included, {} reverted, {} pending, \
+                 inclusion p50 {:.0}ms p99 {:.0}ms",
+                round,
+                instance,
+                result.sent,
+                result.achieved_rate,
+                result.included,
+                result.reverted,
+                result.pending,
+                result.p50_inclusion_ms,
+                result.p99_inclusion_ms
+            );
+        }
+        Some(Commands::Bootstrap { check_only, force }) => {
+            let client = client_pool
+                .get_client(0)
+                .await
+                .context("Failed to get client 0")?;
+            let options = tempo_spammer::bootstrap::BootstrapOptions { check_only, force };
+            let report = tempo_spammer::bootstrap::run_bootstrap(
+                &client,
+                &config.rpc_url,
+                &db_manager,
+                options,
+            )
+            .await?;
+            print_bootstrap_report(&report);
+            if !report.is_ready() {
+                std::process::exit(1);
+            }
+        }
+        Some(Commands::Db { .. })
+        | Some(Commands::Wallets { .. })
+        | Some(Commands::Ctl { .. })
+        | Some(Commands::Inspect { .. })
+        | Some(Commands::Sign { .. })
+        | Some(Commands::Broadcast { .. })
+        | Some(Commands::Status)
+        | Some(Commands::Stop)
+        | Some(Commands::Bundle { .. })
+        | Some(Commands::Export { .. })
+        | Some(Commands::Init) => {
+            unreachable!("handled before startup")
+        }
+    }
 
-    // Proxy Health Check (if proxies enabled)
-    let proxy_banlist = if !proxies.is_empty() {
-        if !is_quiet {
-            info!(target: "task_result", "🔍 Starting proxy health check for {} proxies...", proxies.len());
+    Ok(())
+}
+
+/// Runs the spammer until Ctrl-C; with `[bundle] on_exit` it then bundles the run
+/// Ctrl+C, `stop` on the control socket, or the SIGTERM that `docker stop`
+/// and systemd send
+async fn shutdown_signal(stop: Arc<tokio::sync::Notify>) {
+    #[cfg(unix)]
+    {
+        use tokio::signal::unix::{SignalKind, signal};
+        if let Ok(mut term) = signal(SignalKind::terminate()) {
+            tokio::select! {
+                _ = tokio::signal::ctrl_c() => {}
+                _ = stop.notified() => {}
+                _ = term.recv() => {}
+            }
+            return;
         }
+    }
+    tokio::select! {
+        _ = tokio::signal::ctrl_c() => {}
+        _ = stop.notified() => {}
+    }
+}
 
-        // Start banner animation concurrently
-        let banner_handle = if !is_quiet {
-            Some(tokio::spawn(display_animated_banner()))
-        } else {
-            None
-        };
+async fn run_and_bundle(mut runtime: SpammerRuntime) -> Result<()> {
+    let started = chrono::Utc::now();
+    runtime.start()?;
+    let _pid_file = PidFile::create(&runtime.config().daemon.pid_file)
+        .inspect_err(|e| error!("{:#}", e))
+        .ok();
+    shutdown_signal(runtime.stop_requests()).await;
+    runtime.stop().await;
 
-        let banlist = ProxyBanlist::new(10); // 10-minute ban
+    let stats = runtime.stats().await;
+    info!(
+        target: "task_result",
+        "Stopped after {}s: {} tasks, {:.1}% successful",
+        stats.uptime.as_secs(),
+        stats.tasks(),
+        stats.success_rate()
+    );
 
-        // Start partial scan with background continuation
-        let (partial_healthy, partial_banned, background_handle) =
-            tempo_spammer::proxy_health::scan_proxies_partial(
-                Arc::new(proxies.clone()),
-                config.rpc_url.clone(),
-                banlist.clone(),
-                10, // 10 concurrent checks to prevent rate limits
-                50, // min_healthy = 50 - stop early once we have 50 healthy proxies
-            )
-            .await;
+    let config = runtime.config();
+    if !config.bundle.on_exit {
+        return Ok(());
+    }
+    let options = BundleOptions {
+        config_path: runtime.config_path().to_path_buf(),
+        db_path: config.database.path.clone(),
+        since: started,
+        until: chrono::Utc::now(),
+        out: None,
+    };
+    print_bundle(config, &options).await
+}
 
-        // Log partial results immediately and continue with startup
-        info!(target: "task_result", "✅ {}/{} proxies healthy (partial scan), ⏱️ {} temporarily banned (10min) - continuing startup...", 
-            partial_healthy, proxies.len(), partial_banned);
+/// Sets the SQLCipher key of every database this run opens when
+/// `[database] encrypt` is on
+///
+/// The key comes from `key_file`, `DATABASE_KEY_FILE`, `DATABASE_KEY` or a
+/// prompt. Without one the database is opened as plain SQLite.
+fn unlock_database(config: &Config, headless: bool) -> Result<()> {
+    let key = match config.database.encryption_key()? {
+        None if config.database.encrypt && !headless => prompt_database_key()?,
+        key => key,
+    };
+    if config.database.encrypt && key.is_none() {
+        warn!(
+            "[database] encrypt is on but no key is set ({} or {}); using plain SQLite",
+            core_logic::DATABASE_KEY_ENV,
+            core_logic::DATABASE_KEY_FILE_ENV
+        );
+    }
+    core_logic::database::set_encryption_key(key);
+    Ok(())
+}
 
-        // Ensure banner finishes before continuing
-        if let Some(handle) = banner_handle {
-            let _ = handle.await;
-        }
+/// Asks for the database key; an empty answer keeps the database unencrypted
+fn prompt_database_key() -> Result<Option<Zeroizing<String>>> {
+    let input = Zeroizing::new(
+        Password::with_theme(&ColorfulTheme::default())
+            .with_prompt("Enter database key (empty for no encryption)")
+            .allow_empty_password(true)
+            .report(true)
+            .interact()?,
+    );
+    Ok((!input.is_empty()).then_some(input))
+}
+
+/// How long `--daemon` waits for the detached run to start its workers
+const DAEMON_START_TIMEOUT: Duration = Duration::from_secs(120);
 
-        // Store background handle to keep it alive until end of main
-        let _proxy_scan_handle = background_handle;
+/// How long `stop` waits for the run to remove its PID file
+const STOP_TIMEOUT: Duration = Duration::from_secs(60);
 
-        Some(banlist)
+/// Starts this run again detached from the console and reports how the
+/// start went
+async fn start_daemon(args: &Args, config_path: &Path) -> Result<()> {
+    if !matches!(args.command, None | Some(Commands::Spammer { .. })) {
+        anyhow::bail!("--daemon only runs the spammer");
+    }
+    let config = Config::from_path_with_profile(config_path, args.profile.as_deref())
+        .context("Failed to load config")?;
+    if let Ok(reply) = tempo_spammer::control::send_command(&config.control.listen, "status").await
+    {
+        anyhow::bail!("A spammer is already running: {}", reply);
+    }
+
+    // The detached run cannot prompt, so ask here unless the password is
+    // configured or prompts are off
+    let password_file = args
+        .password_file
+        .as_deref()
+        .or(config.wallets.password_file.as_deref());
+    let headless = core_logic::non_interactive(args.non_interactive, config.non_interactive);
+    let password = if core_logic::wallet_password(password_file)?.is_none() && !headless {
+        let input = Password::with_theme(&ColorfulTheme::default())
+            .with_prompt("Enter wallet password")
+            .report(true)
+            .interact()?;
+        Some(Zeroizing::new(input))
     } else {
-        // No proxies, but still show banner if not quiet
-        if !is_quiet {
-            display_animated_banner().await;
-        }
         None
     };
 
-    // Configure async database logging
-    let async_db_config = AsyncDbConfig {
-        channel_capacity: 1000,
-        batch_size: 200,
-        flush_interval_ms: 200,
+    let database_key = match config.database.encryption_key()? {
+        None if config.database.encrypt && !headless => prompt_database_key()?,
+        _ => None,
     };
 
-    // Create shared database manager with async logging
-    let db_manager = Arc::new(
-        DatabaseManager::new_with_async(
-            "tempo-spammer.db",
-            async_db_config,
-            FallbackStrategy::Hybrid, // Drop + warning when full
-        )
-        .await?,
+    let mut child = daemon::spawn(
+        &daemon::child_args(env::args_os().skip(1)),
+        password.as_deref().map(String::as_str),
+        database_key.as_deref().map(String::as_str),
+    )?;
+    println!("Starting in the background (pid {})...", child.id());
+    match daemon::wait_for_start(&mut child, &config.daemon.pid_file, DAEMON_START_TIMEOUT).await? {
+        Started::Running(pid) => {
+            println!(
+                "✅ Running as pid {}; see `tempo-spammer status`, end it with `tempo-spammer stop`",
+                pid
+            );
+        }
+        Started::Starting(pid) => {
+            println!(
+                "⏳ pid {} is still starting; see `tempo-spammer status` and {}",
+                pid,
+                daemon::OUTPUT_FILE
+            );
+        }
+        Started::Exited(status) => {
+            anyhow::bail!(
+                "The background run exited ({}) before starting:\n{}",
+                status,
+                daemon::last_error(20)
+            );
+        }
+    }
+    Ok(())
+}
+
+/// Control address and PID file of the running spammer
+fn control_target(config: Option<&Config>) -> (String, PathBuf) {
+    match config {
+        Some(config) => (
+            config.control.listen.clone(),
+            config.daemon.pid_file.clone(),
+        ),
+        None => (
+            ControlSettings::default().listen,
+            DaemonSettings::default().pid_file,
+        ),
+    }
+}
+
+/// Why a spammer with a PID file does not answer
+fn unreachable_reason(pid: u32, pid_file: &Path, addr: &str) -> String {
+    format!(
+        "pid {} from {} does not answer on {}; it exited without cleaning up or runs without [control] enabled",
+        pid,
+        pid_file.display(),
+        addr
+    )
+}
+
+async fn run_inspect(target: &InspectCommands, rpc_url: &str) -> Result<()> {
+    use tempo_spammer::inspect;
+
+    let url = rpc_url
+        .parse()
+        .with_context(|| format!("Invalid RPC URL {}", rpc_url))?;
+    let provider = alloy::providers::ProviderBuilder::new().connect_http(url);
+    match target {
+        InspectCommands::Block { block } => {
+            let block = inspect::parse_block_id(block)?;
+            print!("{}", inspect::inspect_block(&provider, block).await?);
+        }
+        InspectCommands::Tx { hash } => {
+            print!("{}", inspect::inspect_tx(&provider, *hash).await?);
+        }
+    }
+    Ok(())
+}
+
+/// `start..end` with `start` below `end`
+fn parse_wallet_range(s: &str) -> Result<Range<usize>, String> {
+    let (start, end) = s
+        .split_once("..")
+        .ok_or_else(|| format!("Expected a range like 0..50, got '{}'", s))?;
+    let start: usize = start
+        .trim()
+        .parse()
+        .map_err(|_| format!("Invalid range start '{}'", start))?;
+    let end: usize = end
+        .trim()
+        .parse()
+        .map_err(|_| format!("Invalid range end '{}'", end))?;
+    if start >= end {
+        return Err(format!("Empty wallet range {}..{}", start, end));
+    }
+    Ok(start..end)
+}
+
+/// Signs `options.count` transfers for each wallet in `indices` into `out`
+async fn run_sign(
+    wallets: &UnlockedWallets,
+    indices: Range<usize>,
+    options: &SignOptions,
+    after: Option<&Path>,
+    out: &Path,
+) -> Result<()> {
+    if indices.end > wallets.count() {
+        anyhow::bail!(
+            "Wallet range {}..{} exceeds the {} wallets",
+            indices.start,
+            indices.end,
+            wallets.count()
+        );
+    }
+    let previous = match after {
+        Some(path) => offline::read_batch(path)?,
+        None => Vec::new(),
+    };
+    let mut signers = Vec::with_capacity(indices.len());
+    for index in indices {
+        signers.push(wallets.signer(index, options.chain_id).await?);
+    }
+
+    let batch = offline::sign_batch(&signers, options, &previous).await?;
+    offline::write_batch(out, &batch)?;
+    println!(
+        "Signed {} transactions for {} wallets into {}",
+        batch.len(),
+        signers.len(),
+        out.display()
     );
+    let mut next_nonces: Vec<_> = offline::next_nonces(&batch).into_iter().collect();
+    next_nonces.sort();
+    for (wallet, next) in next_nonces {
+        println!("  {}  next nonce {}", wallet, next);
+    }
+    Ok(())
+}
 
-    // Create ClientPool with cloned password and configurable connection semaphore
-    // The original Zeroizing password will be cleared after this scope
-    let client_pool = Arc::new(
-        tempo_spammer::ClientPool::new(
-            config.clone(),
-            db_manager.clone(),                // Use same instance
-            Some(wallet_password.to_string()), // Clone for ClientPool
-            config.connection_semaphore,       // Use configurable semaphore size from config
-        )
-        .context("Failed to create client pool")?
-        .with_proxies(proxies)
-        .with_proxy_banlist(proxy_banlist.unwrap_or_else(|| ProxyBanlist::new(10))),
+/// Sends the batch at `path` and prints what came of it
+async fn run_broadcast(
+    path: &Path,
+    rpc_url: &str,
+    options: BroadcastOptions,
+    receipts: Option<&Path>,
+) -> Result<()> {
+    if !options.rate.is_finite() || options.rate <= 0.0 {
+        anyhow::bail!("--rate must be above 0");
+    }
+    let batch = offline::read_batch(path)?;
+    let url = rpc_url
+        .parse()
+        .with_context(|| format!("Invalid RPC URL {}", rpc_url))?;
+    let provider = alloy::providers::ProviderBuilder::new().connect_http(url);
+
+    let deliveries = offline::broadcast(&provider, &batch, options).await;
+    if let Some(receipts) = receipts {
+        offline::write_receipts(receipts, &batch, &deliveries)?;
+    }
+    let summary = BroadcastSummary::from_deliveries(&deliveries);
+    println!(
+        "{}: {} sent, {} rejected; {} included, {} reverted, {} pending",
+        path.display(),
+        summary.sent,
+        summary.rejected,
+        summary.included,
+        summary.reverted,
+        summary.pending
     );
+    if summary.rejected > 0 {
+        let first = deliveries
+            .iter()
+            .zip(&batch)
+            .find_map(|(delivery, tx)| match delivery {
+                Delivery::Rejected(error) => Some((tx, error)),
+                Delivery::Sent(_) => None,
+            });
+        if let Some((tx, error)) = first {
+            println!(
+                "  first rejected: {} nonce {}: {}",
+                tx.wallet, tx.nonce, error
+            );
+        }
+    }
+    Ok(())
+}
 
-    // wallet_password (Zeroizing<String>) is dropped here and automatically zeroized from memory
+async fn print_status(config: Option<&Config>) -> Result<()> {
+    let (addr, pid_file) = control_target(config);
+    match tempo_spammer::control::send_command(&addr, "status").await {
+        Ok(reply) => {
+            println!("{}", reply);
+            Ok(())
+        }
+        Err(_) => {
+            match daemon::read_pid(&pid_file) {
+                Some(pid) => println!("{}", unreachable_reason(pid, &pid_file, &addr)),
+                None => println!("not running"),
+            }
+            std::process::exit(1);
+        }
+    }
+}
 
-    let total_wallets = client_pool.count();
-    info!("Found {} wallets", total_wallets);
+async fn stop_running(config: Option<&Config>) -> Result<()> {
+    let (addr, pid_file) = control_target(config);
+    let reply = match tempo_spammer::control::send_command(&addr, "stop").await {
+        Ok(reply) => reply,
+        Err(_) => match daemon::read_pid(&pid_file) {
+            Some(pid) => anyhow::bail!("{}", unreachable_reason(pid, &pid_file, &addr)),
+            None => {
+                println!("not running");
+                return Ok(());
+            }
+        },
+    };
+    if reply.starts_with("error: ") {
+        anyhow::bail!("{}", reply);
+    }
+    println!("{}...", reply);
+
+    // The PID file goes once queued results are flushed
+    let deadline = std::time::Instant::now() + STOP_TIMEOUT;
+    while let Some(pid) = daemon::read_pid(&pid_file) {
+        if std::time::Instant::now() >= deadline {
+            println!("pid {} is still shutting down", pid);
+            return Ok(());
+        }
+        tokio::time::sleep(Duration::from_millis(250)).await;
+    }
+    println!("stopped");
+    Ok(())
+}
 
-    // Initialize Telegram bot notification service (every 3 hours)
-    if let Some(bot_handle) = spawn_notification_service().await {
-        info!(
-            "Telegram bot notification service started (chat_id: 1754837820, notifications every 3 hours)"
+async fn print_bundle(config: &Config, options: &BundleOptions) -> Result<()> {
+    let summary = tempo_spammer::bundle::write_bundle(config, options).await?;
+    println!(
+        "Bundle written to {} ({} task results, {} log lines, {} to {})",
+        summary.path.display(),
+        summary.records,
+        summary.log_lines,
+        options.since.to_rfc3339(),
+        options.until.to_rfc3339()
+    );
+    Ok(())
+}
+
+/// Runs the local devnet check and exits non-zero if any task failed
+async fn run_local_dev(config: &Config) -> Result<()> {
+    let tasks = tempo_spammer::runner::default_tasks();
+    let report = tempo_spammer::local_dev::run_local_dev(config, &tasks).await?;
+    for outcome in &report.outcomes {
+        println!(
+            "{} {:<24} {:?} ({:.1}s) {}",
+            if outcome.success { "PASS" } else { "FAIL" },
+            outcome.task,
+            outcome.wallet,
+            outcome.duration.as_secs_f64(),
+            outcome.message
         );
-        // The bot runs independently in the background
-        tokio::spawn(async move {
-            if let Err(e) = bot_handle.await {
-                error!("Telegram bot task failed: {}", e);
-            }
-        });
     }
+    let failures = report.failures().len();
+    println!(
+        "{}/{} task runs passed",
+        report.outcomes.len() - failures,
+        report.outcomes.len()
+    );
+    if failures > 0 {
+        std::process::exit(1);
+    }
+    Ok(())
+}
 
-    let tasks: Vec<Box<dyn TempoTask>> = vec![
-        Box::new(tempo_spammer::tasks::t01_deploy_contract::DeployContractTask::new()),
-        Box::new(tempo_spammer::tasks::t02_claim_faucet::ClaimFaucetTask::new()),
-        Box::new(tempo_spammer::tasks::t03_send_token::SendTokenTask::new()),
-        Box::new(tempo_spammer::tasks::t04_create_stable::CreateStableTask::new()),
-        Box::new(tempo_spammer::tasks::t05_swap_stable::SwapStableTask::new()),
-        Box::new(tempo_spammer::tasks::t06_add_liquidity::AddLiquidityTask::new()),
-        Box::new(tempo_spammer::tasks::t07_mint_stable::MintStableTask::new()),
-        Box::new(tempo_spammer::tasks::t08_burn_stable::BurnStableTask::new()),
-        Box::new(tempo_spammer::tasks::t09_transfer_token::TransferTokenTask::new()),
-        Box::new(tempo_spammer::tasks::t10_transfer_memo::TransferMemoTask::new()),
-        Box::new(tempo_spammer::tasks::t11_limit_order::LimitOrderTask::new()),
-        Box::new(tempo_spammer::tasks::t12_remove_liquidity::RemoveLiquidityTask::new()),
-        Box::new(tempo_spammer::tasks::t13_grant_role::GrantRoleTask::new()),
-        Box::new(tempo_spammer::tasks::t14_nft_create_mint::NftCreateMintTask::new()),
-        Box::new(tempo_spammer::tasks::t15_mint_domain::MintDomainTask::new()),
-        Box::new(tempo_spammer::tasks::t16_mint_random_nft::MintRandomNftTask::new()),
-        Box::new(tempo_spammer::tasks::t17_batch_eip7702::BatchEip7702Task::new()),
-        Box::new(tempo_spammer::tasks::t18_tip403_policies::Tip403PoliciesTask::new()),
-        Box::new(tempo_spammer::tasks::t19_wallet_analytics::WalletAnalyticsTask::new()),
-        Box::new(tempo_spammer::tasks::t20_wallet_activity::WalletActivityTask::new()),
-        Box::new(tempo_spammer::tasks::t21_create_meme::CreateMemeTask::new()),
-        Box::new(tempo_spammer::tasks::t22_mint_meme::MintMemeTask::new()),
-        Box::new(tempo_spammer::tasks::t23_transfer_meme::TransferMemeTask::new()),
-        Box::new(tempo_spammer::tasks::t24_batch_swap::BatchSwapTask::new()),
-        Box::new(tempo_spammer::tasks::t25_batch_system_token::BatchSystemTokenTask::new()),
-        Box::new(tempo_spammer::tasks::t26_batch_stable_token::BatchStableTokenTask::new()),
-        Box::new(tempo_spammer::tasks::t27_batch_meme_token::BatchMemeTokenTask::new()),
-        Box::new(tempo_spammer::tasks::t28_multi_send_disperse::MultiSendDisperseTask::new()),
-        Box::new(tempo_spammer::tasks::t29_multi_send_disperse_stable::MultiSendDisperseStableTask::new()),
-        Box::new(tempo_spammer::tasks::t30_multi_send_disperse_meme::MultiSendDisperseMemeTask::new()),
-        Box::new(tempo_spammer::tasks::t31_multi_send_concurrent::MultiSendConcurrentTask::new()),
-        Box::new(tempo_spammer::tasks::t32_multi_send_concurrent_stable::MultiSendConcurrentStableTask::new()),
-        Box::new(tempo_spammer::tasks::t33_multi_send_concurrent_meme::MultiSendConcurrentMemeTask::new()),
-        Box::new(tempo_spammer::tasks::t34_batch_send_transaction::BatchSendTransactionTask::new()),
-        Box::new(tempo_spammer::tasks::t35_batch_send_transaction_stable::BatchSendTransactionStableTask::new()),
-        Box::new(tempo_spammer::tasks::t36_batch_send_transaction_meme::BatchSendTransactionMemeTask::new()),
-        Box::new(tempo_spammer::tasks::t37_transfer_later::TransferLaterTask::new()),
-        Box::new(tempo_spammer::tasks::t38_transfer_later_stable::TransferLaterStableTask::new()),
-        Box::new(tempo_spammer::tasks::t39_transfer_later_meme::TransferLaterMemeTask::new()),
-        Box::new(tempo_spammer::tasks::t40_distribute_shares::DistributeSharesTask::new()),
-        Box::new(tempo_spammer::tasks::t41_distribute_shares_stable::DistributeSharesStableTask::new()),
-        Box::new(tempo_spammer::tasks::t42_distribute_shares_meme::DistributeSharesMemeTask::new()),
-        Box::new(tempo_spammer::tasks::t43_batch_mint_stable::BatchMintStableTask::new()),
-        Box::new(tempo_spammer::tasks::t44_batch_mint_meme::BatchMintMemeTask::new()),
-        Box::new(tempo_spammer::tasks::t45_deploy_viral_faucet::DeployViralFaucetTask::new()),
-        Box::new(tempo_spammer::tasks::t46_claim_viral_faucet::ClaimViralFaucetTask::new()),
-        Box::new(tempo_spammer::tasks::t47_deploy_viral_nft::DeployViralNftTask::new()),
-        Box::new(tempo_spammer::tasks::t48_mint_viral_nft::MintViralNftTask::new()),
-        Box::new(tempo_spammer::tasks::t49_time_bomb::TimeBombTask::new()),
-        Box::new(tempo_spammer::tasks::t50_deploy_storm::DeployStormTask::new()),
-    ];
+fn print_bootstrap_report(report: &tempo_spammer::bootstrap::BootstrapReport) {
+    use tempo_spammer::bootstrap::HelperStatus;
 
-    match args.command {
-        Some(Commands::Spammer { workers, .. }) => {
-            // Use CLI workers if provided, otherwise use runtime_workers (already prompted)
-            let worker_count = workers.unwrap_or(runtime_workers);
-            run_spammer(client_pool, tasks, &config, db_manager, worker_count).await;
-        }
-        Some(Commands::Run { task }) => {
-            // run_single_task logic would need updating too, but skipping for now to focus on spammer
-            let client = client_pool
-                .get_client(0)
-                .await
-                .expect("Failed to get client 0");
-            run_single_task(&client, &tasks, &task, &config, db_manager.clone()).await;
+    println!("System contracts (chain {}):", report.chain_id);
+    for check in &report.system {
+        println!(
+            "  {:<8} {:<16} {}",
+            if check.present { "ok" } else { "MISSING" },
+            check.name,
+            check.address
+        );
+    }
+    println!("Helper contracts:");
+    for helper in &report.helpers {
+        let status = match helper.status {
+            HelperStatus::Current => "current",
+            HelperStatus::Deployed => "deployed",
+            HelperStatus::Missing => "MISSING",
+        };
+        let address = helper
+            .address
+            .map_or_else(|| "-".to_string(), |address| address.to_string());
+        match helper.tx_hash {
+            Some(tx_hash) => println!(
+                "  {:<8} {:<16} {} (tx {})",
+                status, helper.name, address, tx_hash
+            ),
+            None => println!("  {:<8} {:<16} {}", status, helper.name, address),
         }
-        Some(Commands::List) => {
-            println!("Available tasks:");
-            for (i, task) in tasks.iter().enumerate() {
-                println!("  {}: {}", i + 1, task.name());
+    }
+
+    if report.is_ready() {
+        println!("\nTo pin these addresses in config.toml:");
+        println!("[helpers.pinned.{}]", report.chain_id);
+        for helper in &report.helpers {
+            if let Some(address) = helper.address {
+                println!("{} = \"{}\"", helper.name, address);
             }
         }
-        None => {
-            // Use runtime_workers (already prompted before proxy health check)
-            run_spammer(client_pool, tasks, &config, db_manager, runtime_workers).await;
+    }
+}
+
+/// Lists wallets or changes their tags in the wallets directory's `wallet-tags.json`
+fn run_wallet_command(action: &WalletCommands, config: &Config) -> Result<()> {
+    let (tag, wallets, adding) = match action {
+        WalletCommands::List => {
+            let manager = WalletManager::with_options(config.wallets.wallet_options())?;
+            for (index, entry) in manager.entries() {
+                let file = entry
+                    .path
+                    .file_name()
+                    .map(PathBuf::from)
+                    .unwrap_or_default();
+                println!(
+                    "{:>5}  {:<32} {:<42} {}",
+                    index,
+                    entry.subdir.join(file).display(),
+                    entry.address.as_deref().unwrap_or("-"),
+                    entry.tags.join(", ")
+                );
+            }
+            println!("{} wallet files", manager.entries().count());
+            return Ok(());
         }
+        WalletCommands::Tag { tag, wallets } => (tag, wallets, true),
+        WalletCommands::Untag { tag, wallets } => (tag, wallets, false),
+    };
+
+    // Every wallet file in the campaign directory can be tagged, not only the
+    // ones already carrying the selected tags
+    let manager = WalletManager::with_options(WalletOptions {
+        tags: Vec::new(),
+        partition: None,
+        ..config.wallets.wallet_options()
+    })?;
+    let path = manager
+        .tags_file()
+        .context("No wallet files found in wallet-json/")?
+        .to_path_buf();
+    let mut tags = WalletTags::load(&path)?;
+
+    for wallet in wallets {
+        let address = manager
+            .entries()
+            .find(|(_, entry)| {
+                entry
+                    .address
+                    .as_deref()
+                    .is_some_and(|address| address.eq_ignore_ascii_case(wallet))
+                    || entry
+                        .path
+                        .file_name()
+                        .is_some_and(|name| name == wallet.as_str())
+            })
+            .and_then(|(_, entry)| entry.address.clone())
+            .with_context(|| format!("No wallet file with an address matches {}", wallet))?;
+        let changed = if adding {
+            tags.add(&address, tag)
+        } else {
+            tags.remove(&address, tag)
+        };
+        let verb = match (adding, changed) {
+            (true, true) => "tagged",
+            (true, false) => "already tagged",
+            (false, true) => "untagged",
+            (false, false) => "was not tagged",
+        };
+        println!("{} {} {}", address, verb, tag);
     }
 
+    tags.save(&path)?;
+    println!("Saved {}", path.display());
     Ok(())
 }
 
+async fn run_db_command(
+    action: &DbCommands,
+    config_path: &Path,
+    profile: Option<&str>,
+) -> Result<()> {
+    match action {
+        DbCommands::Import { path, db } => {
+            let db_manager = DatabaseManager::new(db).await?;
+            let summary = db_manager
+                .import_spill_file(path)
+                .await
+                .with_context(|| format!("Failed to import spill file {}", path))?;
+            println!(
+                "Imported {} results from {} ({} duplicates skipped, {} malformed lines)",
+                summary.imported, path, summary.duplicates, summary.malformed
+            );
+            db_manager.shutdown().await
+        }
+        DbCommands::Stats { db } => {
+            let sharded = ShardedDatabase::open(db).await?;
+            if sharded.is_empty() {
+                println!("No database files found for {}", db);
+                return Ok(());
+            }
+
+            println!("Database files:");
+            for path in sharded.shard_paths() {
+                println!("  {}", path);
+            }
+
+            let summary = sharded.get_task_summary().await?;
+            println!(
+                "\n{:<36} {:>10} {:>10} {:>9} {:>10} {:>10} {:>12}",
+                "Task", "Success", "Failed", "Rate", "Avg ms", "Avg gas", "Verified"
+            );
+            let (mut success, mut failed) = (0i64, 0i64);
+            for row in &summary {
+                success += row.success;
+                failed += row.failed;
+                // Runs whose state checks all passed, out of those that ran checks
+                let verified = if row.verified > 0 {
+                    format!("{}/{}", row.verified - row.mismatched, row.verified)
+                } else {
+                    "-".to_string()
+                };
+                println!(
+                    "{:<36} {:>10} {:>10} {:>8.1}% {:>10.0} {:>10} {:>12}",
+                    row.task_name,
+                    row.success,
+                    row.failed,
+                    row.success as f64 / row.total().max(1) as f64 * 100.0,
+                    row.avg_duration_ms(),
+                    row.avg_gas_used()
+                        .map(|gas| format!("{:.0}", gas))
+                        .unwrap_or_else(|| "-".to_string()),
+                    verified
+                );
+            }
+            println!(
+                "{:<36} {:>10} {:>10} {:>8.1}%",
+                "TOTAL",
+                success,
+                failed,
+                success as f64 / (success + failed).max(1) as f64 * 100.0
+            );
+
+            let gas = sharded.get_gas_summary().await?;
+            if gas.is_empty() {
+                return Ok(());
+            }
+            // Ceilings and prices are informational, a missing config only hides them
+            let config = Config::from_path_with_profile(config_path, profile).ok();
+            let limits = config
+                .as_ref()
+                .map(|config| config.gas.limits.clone())
+                .unwrap_or_default();
+            let quote = match &config {
+                Some(config) => price::report_quote(&config.price, &config.rpc_url).await,
+                None => None,
+            };
+            let usd = |cost_wei: f64| {
+                quote.as_ref().map_or_else(
+                    || "-".to_string(),
+                    |quote| price::format_usd(quote.gas_usd(cost_wei)),
+                )
+            };
+            println!("\nGas usage (from receipts)");
+            println!(
+                "{:<36} {:>8} {:>9} {:>10} {:>10} {:>9} {:>12} {:>12} {:>10}",
+                "Task",
+                "Txs",
+                "Reverted",
+                "Avg gas",
+                "Max gas",
+                "Avg gwei",
+                "Cost",
+                "USD",
+                "Ceiling"
+            );
+            for row in &gas {
+                println!(
+                    "{:<36} {:>8} {:>9} {:>10.0} {:>10} {:>9.2} {:>12.6} {:>12} {:>10}",
+                    row.task_name,
+                    row.transactions,
+                    row.reverted,
+                    row.avg_gas(),
+                    row.max_gas,
+                    row.avg_gas_price() / 1e9,
+                    row.total_cost_wei / 1e18,
+                    usd(row.total_cost_wei),
+                    limits
+                        .get(&row.task_name)
+                        .map_or_else(|| "-".to_string(), |limit| limit.to_string())
+                );
+            }
+            let total_cost_wei = gas.iter().map(|row| row.total_cost_wei).sum::<f64>();
+            println!(
+                "{:<36} {:>8} {:>9} {:>10} {:>10} {:>9} {:>12.6} {:>12}",
+                "TOTAL",
+                gas.iter().map(|row| row.transactions).sum::<i64>(),
+                gas.iter().map(|row| row.reverted).sum::<i64>(),
+                "",
+                "",
+                "",
+                total_cost_wei / 1e18,
+                usd(total_cost_wei)
+            );
+            if let Some(quote) = &quote {
+                println!(
+                    "USD at {} per fee unit ({}, {})",
+                    price::format_usd(quote.native_usd),
+                    quote.source,
+                    quote.fetched_at.format("%Y-%m-%d %H:%M UTC")
+                );
+            }
+            Ok(())
+        }
+        DbCommands::Proxies { db } => {
+            let sharded = ShardedDatabase::open(db).await?;
+            let stats = sharded.get_proxy_stats().await?;
+            if stats.is_empty() {
+                println!("No proxy usage recorded in {}", db);
+                return Ok(());
+            }
+
+            const MB: f64 = 1024.0 * 1024.0;
+            println!(
+                "{:<40} {:>10} {:>11} {:>9} {:>9}",
+                "Proxy", "Requests", "Est. MB", "KB/req", "Success"
+            );
+            let (mut requests, mut bytes) = (0i64, 0i64);
+            for row in &stats {
+                requests += row.request_count;
+                bytes += row.total_bytes();
+                println!(
+                    "{:<40} {:>10} {:>11.2} {:>9.1} {:>8.1}%",
+                    row.proxy_url,
+                    row.request_count,
+                    row.total_bytes() as f64 / MB,
+                    row.total_bytes() as f64 / 1024.0 / row.request_count.max(1) as f64,
+                    row.success_rate()
+                );
+            }
+            println!(
+                "{:<40} {:>10} {:>11.2}",
+                "TOTAL",
+                requests,
+                bytes as f64 / MB
+            );
+            Ok(())
+        }
+        DbCommands::Budget { day, db } => {
+            let config = Config::from_path_with_profile(config_path, profile)
+                .context("Failed to load config")?;
+            let budget = SpendBudget::new(&config.budget)?;
+            let day = day
+                .clone()
+                .unwrap_or_else(|| chrono::Utc::now().date_naive().to_string());
+            let db_manager = DatabaseManager::new(db).await?;
+            let records = db_manager.get_daily_spend(&day).await?;
+
+            let tokens = budget.capped_tokens();
+            if tokens.is_empty() && records.is_empty() {
+                println!(
+                    "No [budget] caps in {} and no spend recorded",
+                    config_path.display()
+                );
+                return Ok(());
+            }
+
+            let quote = price::report_quote(&config.price, &config.rpc_url).await;
+            let cap_label =
+                |cap: Option<U256>| cap.map_or_else(|| "-".to_string(), |c| c.to_string());
+            println!("Spend on {} (base units)", day);
+            for token in tokens {
+                let key = format!("{:?}", token);
+                let mut rows: Vec<(String, U256)> = records
+                    .iter()
+                    .filter(|r| r.token.eq_ignore_ascii_case(&key))
+                    .filter_map(|r| {
+                        Some((r.wallet_address.clone(), U256::from_str(&r.amount).ok()?))
+                    })
+                    .collect();
+                rows.sort_by_key(|row| std::cmp::Reverse(row.1));
+                let total = rows
+                    .iter()
+                    .fold(U256::ZERO, |sum, (_, amount)| sum + amount);
+                let wallet_cap = budget.wallet_cap(token);
+
+                let value = quote
+                    .as_ref()
+                    .and_then(|quote| quote.token_usd(token, total))
+                    .map_or_else(String::new, |usd| format!(" ({})", price::format_usd(usd)));
+                println!(
+                    "\n{}: {}{} spent by {} wallets, global cap {}",
+                    token_label(token),
+                    total,
+                    value,
+                    rows.len(),
+                    cap_label(budget.global_cap(token))
+                );
+                println!(
+                    "{:<44} {:>24} {:>24} {:>7}",
+                    "Wallet", "Spent", "Cap", "Used"
+                );
+                for (wallet, spent) in &rows {
+                    let used = wallet_cap
+                        .filter(|cap| !cap.is_zero())
+                        .map_or_else(String::new, |cap| {
+                            format!("{:.1}%", f64::from(*spent) / f64::from(cap) * 100.0)
+                        });
+                    println!(
+                        "{:<44} {:>24} {:>24} {:>7}",
+                        wallet,
+                        spent,
+                        cap_label(wallet_cap),
+                        used
+                    );
+                }
+            }
+            Ok(())
+        }
+        DbCommands::Bursts { round, db } => {
+            let db_manager = DatabaseManager::new(db).await?;
+            let Some((burst, results)) = db_manager.get_burst_results(round).await? else {
+                println!("No burst round {} in {}", round, db);
+                return Ok(());
+            };
+
+            let start = chrono::DateTime::from_timestamp_millis(burst.start_at_ms)
+                .map_or_else(|| burst.start_at_ms.to_string(), |t| t.to_rfc3339());
+            println!(
+                "Round {}: start {}, {}ms at {} tx/s per instance\n",
+                burst.round_id, start, burst.duration_ms, burst.target_rate
+            );
+            println!(
+                "{:<28} {:>7} {:>7} {:>8} {:>9} {:>9} {:>8} {:>9} {:>8} {:>8} {:>8} {:>8} {:>8}",
+                "Instance",
+                "Sent",
+                "Errors",
+                "tx/s",
+                "Included",
+                "Reverted",
+                "Pending",
+                "Landed",
+                "Avg ms",
+                "p50",
+                "p90",
+                "p99",
+                "Max"
+            );
+            for row in &results {
+                println!(
+                    "{:<28} {:>7} {:>7} {:>8.1} {:>9} {:>9} {:>8} {:>8.1}% {:>8.0} {:>8.0} {:>8.0} {:>8.0} {:>8.0}",
+                    row.instance_id,
+                    row.sent,
+                    row.send_errors,
+                    row.achieved_rate,
+                    row.included,
+                    row.reverted,
+                    row.pending,
+                    row.inclusion_rate(),
+                    row.avg_inclusion_ms,
+                    row.p50_inclusion_ms,
+                    row.p90_inclusion_ms,
+                    row.p99_inclusion_ms,
+                    row.max_inclusion_ms
+                );
+            }
+            let first_block = results.iter().filter_map(|r| r.first_block).min();
+            let last_block = results.iter().filter_map(|r| r.last_block).max();
+            println!(
+                "{:<28} {:>7} {:>7} {:>8.1}",
+                "TOTAL",
+                results.iter().map(|r| r.sent).sum::<i64>(),
+                results.iter().map(|r| r.send_errors).sum::<i64>(),
+                results.iter().map(|r| r.achieved_rate).sum::<f64>()
+            );
+            if let (Some(first), Some(last)) = (first_block, last_block) {
+                println!("Blocks {}..={} ({} blocks)", first, last, last - first + 1);
+            }
+
+            let blocks = db_manager.get_burst_blocks(round).await?;
+            if !blocks.is_empty() {
+                println!(
+                    "\n{:>10} {:<28} {:>5} {:>9} {:>8} {:>8} {:>8}",
+                    "Block", "Instance", "Txs", "Subblock", "Min ms", "p50", "Max"
+                );
+                for block in &blocks {
+                    println!(
+                        "{:>10} {:<28} {:>5} {:>9} {:>8.0} {:>8.0} {:>8.0}",
+                        block.block_number,
+                        block.instance_id,
+                        block.transactions,
+                        block.in_subblocks,
+                        block.min_inclusion_ms,
+                        block.p50_inclusion_ms,
+                        block.max_inclusion_ms
+                    );
+                }
+            }
+            Ok(())
+        }
+        DbCommands::Latency { source, since, db } => {
+            let since_ms = match since {
+                Some(since) => {
+                    tempo_spammer::bundle::parse_time(since, chrono::Utc::now())?.timestamp_millis()
+                }
+                None => 0,
+            };
+            let db_manager = DatabaseManager::new(db).await?;
+            let samples = db_manager
+                .get_latency_samples(source.as_deref(), since_ms)
+                .await?;
+            if samples.is_empty() {
+                println!("No latency samples in {} (run a burst round)", db);
+                return Ok(());
+            }
+
+            let blocks: std::collections::HashSet<i64> =
+                samples.iter().map(|s| s.block_number).collect();
+            println!(
+                "{} transactions in {} blocks\n",
+                samples.len(),
+                blocks.len()
+            );
+            print!(
+                "{}",
+                tempo_spammer::latency::render(&tempo_spammer::latency::report(&samples))
+            );
+            Ok(())
+        }
+        DbCommands::Coverage { task, file, db } => {
+            let (rows, origin) = match file {
+                Some(file) => {
+                    let logs: Vec<_> = tempo_spammer::log_archive::read_jsonl(file)?
+                        .into_iter()
+                        .filter(|log| task.as_ref().is_none_or(|task| &log.task == task))
+                        .collect();
+                    (
+                        tempo_spammer::log_archive::coverage_rows(&logs),
+                        file.display().to_string(),
+                    )
+                }
+                None => {
+                    let db_manager = DatabaseManager::new(db).await?;
+                    (
+                        db_manager.get_log_coverage(task.as_deref()).await?,
+                        db.clone(),
+                    )
+                }
+            };
+            if rows.is_empty() {
+                println!("No archived logs in {} (enable [log_archive])", origin);
+                return Ok(());
+            }
+
+            print!(
+                "{}",
+                tempo_spammer::log_archive::Coverage::from_rows(&rows).render()
+            );
+            Ok(())
+        }
+        DbCommands::Consistency { db } => {
+            let db_manager = DatabaseManager::new(db).await?;
+            let summary = db_manager.get_consistency_summary().await?;
+            if summary.is_empty() {
+                println!("No consistency checks in {} (enable [verify])", db);
+                return Ok(());
+            }
+
+            println!(
+                "{:<28} {:>8} {:>13} {:>10} {:>10}",
+                "Task", "Checks", "Inconsistent", "Avg lag", "Max lag"
+            );
+            for row in &summary {
+                println!(
+                    "{:<28} {:>8} {:>13} {:>8.0}ms {:>8}ms",
+                    row.task_name, row.checks, row.inconsistent, row.avg_lag_ms, row.max_lag_ms
+                );
+            }
+            Ok(())
+        }
+        DbCommands::Audits { task, limit, db } => {
+            let db_manager = DatabaseManager::new(db).await?;
+            let audits = db_manager.get_audits(task.as_deref(), *limit).await?;
+            if audits.is_empty() {
+                println!("No audits in {} (set [audit] sample_rate)", db);
+                return Ok(());
+            }
+
+            for audit in &audits {
+                let time = chrono::DateTime::from_timestamp(audit.timestamp, 0)
+                    .map(|t| t.format("%Y-%m-%d %H:%M:%S").to_string())
+                    .unwrap_or_default();
+                println!(
+                    "{} [WK:{}] {} {} {} via {} in {}ms: {}",
+                    time,
+                    audit.worker_id,
+                    audit.task_name,
+                    if audit.success { "SUCCESS" } else { "FAILED" },
+                    audit.wallet_address,
+                    audit.proxy,
+                    audit.duration_ms,
+                    audit.message
+                );
+                if let Some(nonce) = audit.nonce {
+                    println!(
+                        "    nonce {} (key {}) gas {} max fee {} priority {}",
+                        nonce,
+                        audit.nonce_key.as_deref().unwrap_or("-"),
+                        audit.gas_limit.unwrap_or_default(),
+                        audit.max_fee_per_gas.as_deref().unwrap_or("-"),
+                        audit.max_priority_fee_per_gas.as_deref().unwrap_or("-")
+                    );
+                }
+                let steps: Vec<serde_json::Value> =
+                    serde_json::from_str(&audit.steps).unwrap_or_default();
+                for step in &steps {
+                    println!(
+                        "    +{:>6}ms {:>6}ms {} {}",
+                        step["start_ms"],
+                        step["duration_ms"],
+                        step["name"].as_str().unwrap_or("?"),
+                        step["error"].as_str().unwrap_or("")
+                    );
+                }
+            }
+            Ok(())
+        }
+        DbCommands::Fuzz { chain_id, db } => {
+            let chain_id = match chain_id {
+                Some(chain_id) => *chain_id,
+                None => {
+                    Config::from_path_with_profile(config_path, profile)
+                        .context("Failed to load config")?
+                        .chain_id
+                }
+            };
+            let db_manager = DatabaseManager::new(db).await?;
+            let results = db_manager.get_fuzz_results(chain_id).await?;
+            if results.is_empty() {
+                println!("No fuzz results for chain {} in {}", chain_id, db);
+                return Ok(());
+            }
+
+            println!(
+                "{:<14} {:<44} {:>9} {:>8}  Last revert",
+                "Contract", "Function", "Successes", "Reverts"
+            );
+            for row in &results {
+                println!(
+                    "{:<14} {:<44} {:>9} {:>8}  {}",
+                    row.contract, row.signature, row.successes, row.reverts, row.last_error
+                );
+            }
+            Ok(())
+        }
+    }
+}
+
+/// Box-drawn banner shown line by line
+///
+/// Consoles without ANSI support (legacy Windows hosts, output redirected to a
+/// file) get a single plain line instead of the block characters and delays.
 async fn display_animated_banner() {
+    if !core_logic::ansi_supported() {
+        println!("\n== GANTENG MAKSIMAL - tempo-spammer ==\n");
+        return;
+    }
     let lines = [
         "\n",
         "    ╔══════════════════════════════════════════════════════════════════╗",
@@ -414,371 +1850,6 @@ async fn display_animated_banner() {
     }
 }
 
-async fn run_spammer(
-    client_pool: Arc<tempo_spammer::ClientPool>,
-    tasks: Vec<Box<dyn TempoTask>>,
-    config: &Config,
-    db_manager: Arc<DatabaseManager>,
-    worker_count: u64,
-) {
-    info!(target: "task_result", "Starting spammer with {} workers...", worker_count);
-    info!(target: "task_result", "Per-worker semaphore: {} concurrent requests", config.worker_semaphore);
-
-    let task_weights: Vec<u32> = tasks
-        .iter()
-        .map(|t| match t.name() {
-            n if n.contains("SendToken") => 10,
-            n if n.contains("Transfer") => 10,
-            n if n.contains("Swap") => 5,
-            _ => 1,
-        })
-        .collect();
-    let dist = WeightedIndex::new(&task_weights).expect("Failed to create weighted distribution");
-    let tasks = Arc::new(tasks);
-
-    let config = config.clone();
-    let _client_count = client_pool.count();
-
-    let mut handles = Vec::new();
-
-    for worker_id in 0..worker_count {
-        let client_pool = client_pool.clone();
-        let tasks = tasks.clone();
-        let db = db_manager.clone();
-        let config = config.clone();
-        let dist = dist.clone();
-
-        // Per-worker semaphore to prevent burst patterns
-        let worker_semaphore = Arc::new(tokio::sync::Semaphore::new(config.worker_semaphore));
-
-        let handle = tokio::spawn(async move {
-            let mut rng = StdRng::from_entropy();
-            let initial_sleep = rng.gen_range(0..2000);
-            tokio::time::sleep(Duration::from_millis(initial_sleep)).await;
-
-            let mut backoff_ms = 10u64; // Start with 10ms backoff
-
-            loop {
-                // Acquire per-worker permit (prevents burst patterns)
-                let _worker_permit = match worker_semaphore.clone().try_acquire_owned() {
-                    Ok(permit) => permit,
-                    Err(_) => {
-                        // Worker at capacity, wait briefly
-                        tokio::time::sleep(Duration::from_millis(50)).await;
-                        continue;
-                    }
-                };
-                // Check for cancellation
-                if false {
-                    break;
-                } // Placeholder
-
-                // let wallet_idx = rng.gen_range(0..client_count); // Handled by pool
-
-                // Acquire lease on a wallet with exponential backoff
-                let lease = match client_pool.try_acquire_client().await {
-                    Some(l) => {
-                        backoff_ms = 10; // Reset backoff on success
-                        l
-                    }
-                    None => {
-                        // All wallets busy, use exponential backoff (10ms -> 20ms -> 40ms... max 100ms)
-                        tokio::time::sleep(Duration::from_millis(backoff_ms)).await;
-                        backoff_ms = (backoff_ms * 2).min(100); // Double but cap at 100ms
-                        continue;
-                    }
-                };
-
-                let wallet_idx = lease.index;
-                let client = lease.client.clone(); // Clone ARC, lease stays alive until end of scope
-
-                let task_idx = dist.sample(&mut rng);
-                let task = &tasks[task_idx];
-
-                let ctx = TaskContext::new(client.clone(), config.clone(), Some(db.clone()));
-
-                let proxy_url_for_span = client
-                    .proxy_config
-                    .as_ref()
-                    .map(|p| p.url.as_str())
-                    .unwrap_or("direct");
-
-                let span = tracing::info_span!(
-                    "task",
-                    worker_id = worker_id,
-                    wallet = ?client.address(),
-                    task = task.name(),
-                    proxy = proxy_url_for_span
-                );
-                let start = std::time::Instant::now();
-
-                match tokio::time::timeout(Duration::from_secs(config.task_timeout), task.run(&ctx))
-                    .await
-                {
-                    Ok(Ok(result)) => {
-                        let _enter = span.enter();
-                        let duration = start.elapsed();
-
-                        // Async logging: queue result without blocking
-                        if let Some(database) = &ctx.db {
-                            let queued_result = QueuedTaskResult {
-                                worker_id: format!("{:03}", worker_id),
-                                wallet_address: client.address().to_string(),
-                                task_name: task.name().to_string(),
-                                success: result.success,
-                                message: result.message.clone(),
-                                duration_ms: duration.as_millis() as u64,
-                                timestamp: chrono::Utc::now().timestamp(),
-                            };
-
-                            // Non-blocking send (returns immediately)
-                            if let Err(e) = database.queue_task_result(queued_result) {
-                                // Log at warn level for visibility - this shouldn't happen often
-                                warn!("Failed to queue task result for DB logging: {}", e);
-                            }
-                        }
-
-                        let status_msg = if result.success {
-                            if let Some(tx_hash) = &result.tx_hash {
-                                format!("TxHash: {}", tx_hash)
-                            } else if !result.message.is_empty() {
-                                result.message.clone()
-                            } else {
-                                "Success".to_string()
-                            }
-                        } else {
-                            result.message.clone()
-                        };
-
-                        info!(
-                            target: "task_result",
-                            "[WK:{:03}][WL:{:03}][P:{}] {} [{}] {} t:{:.1}s",
-                            worker_id,
-                            wallet_idx,
-                            client.proxy_index.map(|i| format!("{:03}", i)).unwrap_or_else(|| "DIR".to_string()),
-                            if result.success { "SUCCESS" } else { "FAILED " },
-                            task.name(),
-                            status_msg,
-                            duration.as_secs_f32()
-                        );
-                    }
-                    Ok(Err(e)) => {
-                        let _enter = span.enter();
-                        let duration = start.elapsed();
-                        let error_msg = format!("{:#}", e);
-
-                        // === PROXY BANNING LOGIC ===
-                        // Detect connection/tunnel errors that indicate a bad proxy
-                        if error_msg.contains("tunnel error")
-                            || error_msg.contains("Connect")
-                            || error_msg.contains("connection closed")
-                            || error_msg.contains("error sending request")
-                        {
-                            if let Some(proxy_idx) = client.proxy_index {
-                                if let Some(banlist) = &client_pool.proxy_banlist {
-                                    tracing::warn!(
-                                        "[WK:{:03}][P:{:03}] 🚫 Banning unhealthy proxy due to error: {:.100}...",
-                                        worker_id,
-                                        proxy_idx,
-                                        error_msg
-                                    );
-                                    banlist.ban(proxy_idx).await;
-                                }
-                            }
-                        }
-
-                        let mut recovered = false;
-
-                        // Auto-refresh nonce cache on "nonce too low" errors
-                        if error_msg.contains("nonce too low") {
-                            tracing::debug!(
-                                "[WK:{:03}] Detected stale nonce, refreshing from blockchain...",
-                                worker_id
-                            );
-
-                            // Force refresh nonce from blockchain
-                            if let Some(robust_manager) = &ctx.client.robust_nonce_manager {
-                                let mut handled = false;
-                                // Parse error: "nonce too low: next nonce <next>, tx nonce <tx>"
-                                if let (Some(next_pos), Some(tx_pos)) =
-                                    (error_msg.find("next nonce "), error_msg.find(", tx nonce "))
-                                {
-                                    let next_str = &error_msg[next_pos + 11..tx_pos];
-                                    let tx_str_check = &error_msg[tx_pos + 11..];
-                                    // tx_str might have trailing chars, take until non-digit
-                                    let tx_str = tx_str_check
-                                        .chars()
-                                        .take_while(|c| c.is_ascii_digit())
-                                        .collect::<String>();
-
-                                    if let (Ok(next_nonce), Ok(tx_nonce)) = (
-                                        next_str.trim().parse::<u64>(),
-                                        tx_str.trim().parse::<u64>(),
-                                    ) {
-                                        robust_manager
-                                            .handle_nonce_error(ctx.address(), tx_nonce, next_nonce)
-                                            .await;
-                                        tracing::info!(
-                                            "[WK:{:03}] Robust recovery: failed {} -> actual {}",
-                                            worker_id,
-                                            tx_nonce,
-                                            next_nonce
-                                        );
-                                        handled = true;
-                                        recovered = true;
-                                    }
-                                }
-
-                                if !handled {
-                                    // Fallback: use get_pending_nonce which handles "pending" tag correctly manual
-                                    match ctx.client.get_pending_nonce(&ctx.config.rpc_url).await {
-                                        Ok(fresh_nonce) => {
-                                            robust_manager
-                                                .initialize(ctx.address(), fresh_nonce)
-                                                .await;
-                                            tracing::debug!(
-                                                "[WK:{:03}] RobustNonceManager re-initialized to {}",
-                                                worker_id,
-                                                fresh_nonce
-                                            );
-                                            recovered = true;
-                                        }
-                                        Err(e) => tracing::warn!(
-                                            "[WK:{:03}] Failed to refresh robust nonce: {:?}",
-                                            worker_id,
-                                            e
-                                        ),
-                                    }
-                                }
-                            }
-                            // Legacy Manager
-                            else if let Some(manager) = &ctx.client.nonce_manager {
-                                match ctx.client.get_pending_nonce(&ctx.config.rpc_url).await {
-                                    Ok(fresh_nonce) => {
-                                        manager.set(ctx.address(), fresh_nonce).await;
-                                        tracing::debug!(
-                                            "[WK:{:03}] Nonce cache refreshed to {}",
-                                            worker_id,
-                                            fresh_nonce
-                                        );
-                                        recovered = true;
-                                    }
-                                    Err(refresh_err) => {
-                                        tracing::warn!(
-                                            "[WK:{:03}] Failed to refresh nonce: {:?}",
-                                            worker_id,
-                                            refresh_err
-                                        );
-                                    }
-                                }
-                            }
-                        }
-
-                        // Async logging for error
-                        if let Some(database) = &ctx.db {
-                            let queued_result = QueuedTaskResult {
-                                worker_id: format!("{:03}", worker_id),
-                                wallet_address: client.address().to_string(),
-                                task_name: task.name().to_string(),
-                                success: false,
-                                message: error_msg.clone(),
-                                duration_ms: duration.as_millis() as u64,
-                                timestamp: chrono::Utc::now().timestamp(),
-                            };
-
-                            if let Err(e) = database.queue_task_result(queued_result) {
-                                warn!("Failed to queue error result for DB logging: {}", e);
-                            }
-                        }
-
-                        if recovered {
-                            // Log as INFO/WARN - it's a recovered error, normal operation
-                            info!(target: "task_result", "[WK:{:03}][WL:{:03}][P:{}] \x1b[33mRETRY\x1b[0m [{}] Nonce mismatch (recovered) t:{:.1}s",
-                                worker_id,
-                                wallet_idx,
-                                client.proxy_index.map(|i| format!("{:03}", i)).unwrap_or_else(|| "DIR".to_string()),
-                                task.name(),
-                                duration.as_secs_f32()
-                            );
-                        } else {
-                            error!(target: "task_result", "[WK:{:03}][WL:{:03}][P:{}] \x1b[31mERROR\x1b[0m [{}] Task error: {} t:{:.1}s",
-                                worker_id,
-                                wallet_idx,
-                                client.proxy_index.map(|i| format!("{:03}", i)).unwrap_or_else(|| "DIR".to_string()),
-                                task.name(),
-                                error_msg,
-                                duration.as_secs_f32()
-                            );
-                        }
-                    }
-                    Err(_) => {
-                        let _enter = span.enter();
-                        let duration = start.elapsed();
-                        let error_msg = "Task timed out".to_string();
-
-                        // Async logging for timeout
-                        if let Some(database) = &ctx.db {
-                            let queued_result = QueuedTaskResult {
-                                worker_id: format!("{:03}", worker_id),
-                                wallet_address: client.address().to_string(),
-                                task_name: task.name().to_string(),
-                                success: false,
-                                message: error_msg.clone(),
-                                duration_ms: duration.as_millis() as u64,
-                                timestamp: chrono::Utc::now().timestamp(),
-                            };
-
-                            if let Err(e) = database.queue_task_result(queued_result) {
-                                warn!("Failed to queue timeout result for DB logging: {}", e);
-                            }
-                        }
-                        error!(target: "task_result", "[WK:{:03}][WL:{:03}][P:{}] \x1b[31mERROR\x1b[0m [{}] {} t:{:.1}s",
-                            worker_id,
-                            wallet_idx,
-                            client.proxy_index.map(|i| format!("{:03}", i)).unwrap_or_else(|| "DIR".to_string()),
-                            task.name(),
-                            error_msg,
-                            duration.as_secs_f32()
-                        );
-                    }
-                }
-
-                // Explicitly release the lease with cooldown
-                lease.release().await;
-
-                let sleep_ms = config.random_interval();
-                tokio::time::sleep(Duration::from_millis(sleep_ms)).await;
-            }
-        });
-
-        handles.push(handle);
-    }
-
-    // Spawn database monitoring task
-    let db_monitor = db_manager.clone();
-    let monitor_handle = tokio::spawn(async move {
-        let mut interval = tokio::time::interval(Duration::from_secs(30));
-        loop {
-            interval.tick().await;
-            let metrics = db_monitor.get_metrics();
-            let (queued, dropped) = db_monitor.get_async_metrics();
-            info!(
-                "DB Metrics: {} queries, {} errors ({:.1}%), {} queued, {} dropped",
-                metrics.total_queries,
-                metrics.total_errors,
-                metrics.error_rate(),
-                queued,
-                dropped
-            );
-        }
-    });
-
-    join_all(handles).await;
-
-    // Cancel monitor task
-    monitor_handle.abort();
-}
-
 async fn run_single_task(
     client: &TempoClient,
     tasks: &[Box<dyn TempoTask>],
@@ -0,0 +1,52 @@
+//! Runs your own task next to built-in ones
+//!
+//! ```bash
+//! TEMPO_PRIVATE_KEYS=0xabc... cargo run -p tempo-spammer --example custom_task
+//! ```
+//!
+//! `[tasks.weights]` in the config applies to custom tasks by name like to
+//! any other; unweighted tasks get the default weight.
+
+use anyhow::Result;
+use async_trait::async_trait;
+use std::time::Duration;
+use tempo_spammer::tasks::t03_send_token::SendTokenTask;
+use tempo_spammer::tasks::{TaskContext, TaskResult, TempoTask};
+use tempo_spammer::{Spammer, TempoSpammerConfig};
+
+/// Reads the wallet's native balance without sending anything
+struct BalanceProbe;
+
+#[async_trait]
+impl TempoTask for BalanceProbe {
+    fn name(&self) -> &'static str {
+        "custom_balance_probe"
+    }
+
+    async fn run(&self, ctx: &TaskContext) -> Result<TaskResult> {
+        let balance = ctx.client.provider.get_balance(ctx.address()).await?;
+        Ok(TaskResult {
+            success: true,
+            message: format!("Balance {}", balance),
+            ..Default::default()
+        }
+        .with_detail("balance", balance.to_string()))
+    }
+}
+
+#[tokio::main]
+async fn main() -> Result<()> {
+    tracing_subscriber::fmt().with_env_filter("info").init();
+
+    let keys = std::env::var("TEMPO_PRIVATE_KEYS")?;
+    let config = TempoSpammerConfig::from_path("config/config.toml")?;
+    let spammer = Spammer::builder(config)
+        .private_keys(keys.split(',').map(str::trim))
+        .tasks(vec![Box::new(BalanceProbe), Box::new(SendTokenTask::new())])
+        .workers(1)
+        .build()
+        .await?;
+
+    spammer.run_for(Duration::from_secs(30)).await;
+    Ok(())
+}
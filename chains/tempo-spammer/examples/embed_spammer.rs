@@ -0,0 +1,74 @@
+//! Runs the spammer from your own binary and prints every finished task
+//!
+//! ```bash
+//! TEMPO_PRIVATE_KEYS=0xabc...,0xdef... \
+//!     cargo run -p tempo-spammer --example embed_spammer -- config/config.toml 120
+//! ```
+//!
+//! Arguments are the config path (default `config/config.toml`) and the run
+//! time in seconds (default 60). Without `TEMPO_PRIVATE_KEYS`, wallets come
+//! from `wallet-json/` decrypted with `TEMPO_WALLET_PASSWORD`, or `pv.txt`.
+
+use anyhow::Result;
+use std::collections::BTreeMap;
+use std::time::Duration;
+use tempo_spammer::{Spammer, TempoSpammerConfig};
+
+#[tokio::main]
+async fn main() -> Result<()> {
+    tracing_subscriber::fmt().with_env_filter("warn").init();
+
+    let mut args = std::env::args().skip(1);
+    let config_path = args
+        .next()
+        .unwrap_or_else(|| "config/config.toml".to_string());
+    let seconds: u64 = args.next().map(|s| s.parse()).transpose()?.unwrap_or(60);
+
+    let config = TempoSpammerConfig::from_path(&config_path)?;
+    let (outcomes, mut rx) = tokio::sync::mpsc::unbounded_channel();
+
+    let mut builder = Spammer::builder(config).sink(outcomes);
+    if let Ok(keys) = std::env::var("TEMPO_PRIVATE_KEYS") {
+        builder = builder.private_keys(keys.split(',').map(str::trim));
+    } else if let Ok(password) = std::env::var("TEMPO_WALLET_PASSWORD") {
+        builder = builder.wallet_password(password);
+    }
+    let spammer = builder.build().await?;
+    println!(
+        "Running {} tasks on {} wallets with {} workers for {}s",
+        spammer.task_names().len(),
+        spammer.client_pool().count(),
+        spammer.workers(),
+        seconds
+    );
+
+    let printer = tokio::spawn(async move {
+        let mut totals: BTreeMap<&'static str, (u64, u64)> = BTreeMap::new();
+        while let Some(outcome) = rx.recv().await {
+            println!(
+                "[WK:{:03}] {} {} {} ({:.1}s)",
+                outcome.worker_id,
+                if outcome.result.success {
+                    "OK  "
+                } else {
+                    "FAIL"
+                },
+                outcome.task,
+                outcome.result.message,
+                outcome.duration.as_secs_f32()
+            );
+            let entry = totals.entry(outcome.task).or_default();
+            entry.0 += 1;
+            entry.1 += u64::from(outcome.result.success);
+        }
+        totals
+    });
+
+    spammer.run_for(Duration::from_secs(seconds)).await;
+
+    // The workers and their sink senders are gone, so the printer finishes
+    for (task, (runs, successes)) in printer.await? {
+        println!("{:<40} {:>4}/{:<4} succeeded", task, successes, runs);
+    }
+    Ok(())
+}
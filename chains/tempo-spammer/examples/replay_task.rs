@@ -0,0 +1,78 @@
+//! Replays a task against its recorded `[audit]` runs, offline
+//!
+//! ```bash
+//! cargo run -p tempo-spammer --example replay_task --features testing -- \
+//!     03_send_token --db tempo-spammer.db --limit 20
+//! ```
+//!
+//! Each successful audited run of the task becomes a fixture for the mock
+//! chain; the task runs once per fixture and must succeed and send the same
+//! calls as the recorded run. Exits with an error when a replay differs.
+
+use anyhow::{Context, Result, bail};
+use tempo_spammer::replay::Fixture;
+use tempo_spammer::runner::default_tasks;
+use tempo_spammer::testing::TaskHarness;
+
+const USAGE: &str = "usage: replay_task <task name> [--db <path>] [--limit <n>]";
+
+#[tokio::main]
+async fn main() -> Result<()> {
+    let mut args = std::env::args().skip(1);
+    let name = args.next().context(USAGE)?;
+    let mut db = "tempo-spammer.db".to_string();
+    let mut limit = 50;
+    while let Some(flag) = args.next() {
+        let value = args.next().context(USAGE)?;
+        match flag.as_str() {
+            "--db" => db = value,
+            "--limit" => limit = value.parse().context("--limit takes a number")?,
+            _ => bail!(USAGE),
+        }
+    }
+
+    let task = default_tasks()
+        .into_iter()
+        .find(|task| task.name() == name)
+        .with_context(|| format!("Unknown task '{}'", name))?;
+    let db = core_logic::database::DatabaseManager::new(&db).await?;
+    let fixtures = Fixture::load(&db, &name, limit).await?;
+    if fixtures.is_empty() {
+        bail!("No successful audits of {} (set [audit] sample_rate)", name);
+    }
+
+    let mut failed = 0;
+    for fixture in &fixtures {
+        let harness = TaskHarness::new().with_db().await?;
+        let started = std::time::Instant::now();
+        let replay = fixture.replay(&harness, task.as_ref()).await;
+        let time = chrono::DateTime::from_timestamp(fixture.timestamp, 0)
+            .map(|t| t.format("%Y-%m-%d %H:%M:%S").to_string())
+            .unwrap_or_default();
+        println!(
+            "{} {} in {}ms ({} steps skipped): {}",
+            time,
+            if replay.passed() { "PASS" } else { "FAIL" },
+            started.elapsed().as_millis(),
+            replay.skipped,
+            replay.result.message
+        );
+        for mismatch in &replay.mismatches {
+            println!("    {}", mismatch);
+        }
+        if !replay.passed() {
+            failed += 1;
+        }
+    }
+
+    if failed > 0 {
+        bail!(
+            "{} of {} replays of {} failed",
+            failed,
+            fixtures.len(),
+            name
+        );
+    }
+    println!("All {} replays of {} passed", fixtures.len(), name);
+    Ok(())
+}
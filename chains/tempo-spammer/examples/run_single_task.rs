@@ -0,0 +1,55 @@
+//! Runs one task once on one leased wallet
+//!
+//! ```bash
+//! TEMPO_PRIVATE_KEYS=0xabc... \
+//!     cargo run -p tempo-spammer --example run_single_task -- 03_send_token
+//! ```
+//!
+//! Useful for checking a task against a node before adding it to a run.
+
+use anyhow::{Context, Result};
+use tempo_spammer::runner::default_tasks;
+use tempo_spammer::{Spammer, TaskContext, TempoSpammerConfig};
+
+#[tokio::main]
+async fn main() -> Result<()> {
+    tracing_subscriber::fmt().with_env_filter("info").init();
+
+    let name = std::env::args()
+        .nth(1)
+        .context("usage: run_single_task <task name>")?;
+    let task = default_tasks()
+        .into_iter()
+        .find(|task| task.name() == name)
+        .with_context(|| format!("Unknown task '{}'", name))?;
+
+    let keys = std::env::var("TEMPO_PRIVATE_KEYS")?;
+    let config = TempoSpammerConfig::from_path("config/config.toml")?;
+    let spammer = Spammer::builder(config)
+        .private_keys(keys.split(',').map(str::trim))
+        .build()
+        .await?;
+
+    let lease = spammer
+        .client_pool()
+        .try_acquire_client()
+        .await
+        .context("No wallet available")?;
+    let ctx = TaskContext::new(
+        lease.client.clone(),
+        spammer.config().clone(),
+        Some(spammer.database().clone()),
+    );
+
+    let result = task.run(&ctx).await?;
+    println!(
+        "{} {}: {}",
+        if result.success { "OK" } else { "FAILED" },
+        task.name(),
+        result.message
+    );
+    if let Some(hash) = &result.tx_hash {
+        println!("tx {}", hash);
+    }
+    Ok(())
+}
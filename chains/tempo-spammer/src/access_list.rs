@@ -0,0 +1,128 @@
+//! Access Lists - EIP-2930 lists from `eth_createAccessList`
+//!
+//! An access list declares the accounts and storage slots a transaction
+//! touches, which makes their first access cheaper at the price of a larger
+//! transaction. Whether that pays off depends on the call, so it is measured
+//! instead of assumed. With `[gas] access_lists = true`, tasks that support it
+//! ask the node for the list before sending, attach it and record what it
+//! changed:
+//!
+//! ```toml
+//! [gas]
+//! access_lists = true
+//! ```
+//!
+//! [`GasManager::access_list`](crate::tasks::GasManager::access_list) covers
+//! plain transaction requests. Tempo (0x76) transactions carry one list for
+//! all calls, so [`GasManager::calls_access_list`](crate::tasks::GasManager::calls_access_list)
+//! asks for each call and merges the lists with [`AccessListEstimate::merge`].
+//!
+//! [`AccessListEstimate::record`] stores the figures as task result details:
+//! `access_list_entries`, `gas_with_access_list`, `gas_without_access_list`
+//! and `access_list_gas_delta`, the gas saved (negative when the list costs
+//! more than it saves).
+
+use crate::tasks::TaskResult;
+use alloy::eips::eip2930::{AccessList, AccessListItem};
+use std::collections::BTreeMap;
+
+/// An access list with the estimated gas of a transaction with and without it
+#[derive(Debug, Clone, Default, PartialEq, Eq)]
+pub struct AccessListEstimate {
+    pub access_list: AccessList,
+    /// `gasUsed` reported by `eth_createAccessList`
+    pub gas_with: u64,
+    /// `eth_estimateGas` of the same transaction without a list
+    pub gas_without: u64,
+}
+
+impl AccessListEstimate {
+    /// Gas saved by attaching the list, negative when it costs more
+    pub fn gas_delta(&self) -> i64 {
+        self.gas_without as i64 - self.gas_with as i64
+    }
+
+    /// Combines the estimates of several calls into one list and gas totals
+    ///
+    /// Accounts keep their first position; storage keys are deduplicated.
+    pub fn merge(estimates: impl IntoIterator<Item = Self>) -> Self {
+        let mut order = Vec::new();
+        let mut keys: BTreeMap<_, Vec<_>> = BTreeMap::new();
+        let mut merged = Self::default();
+
+        for estimate in estimates {
+            merged.gas_with += estimate.gas_with;
+            merged.gas_without += estimate.gas_without;
+            for item in estimate.access_list.0 {
+                let slots = keys.entry(item.address).or_insert_with(|| {
+                    order.push(item.address);
+                    Vec::new()
+                });
+                for key in item.storage_keys {
+                    if !slots.contains(&key) {
+                        slots.push(key);
+                    }
+                }
+            }
+        }
+
+        merged.access_list = AccessList(
+            order
+                .into_iter()
+                .map(|address| AccessListItem {
+                    address,
+                    storage_keys: keys.remove(&address).unwrap_or_default(),
+                })
+                .collect(),
+        );
+        merged
+    }
+
+    /// Adds the list size and gas figures to a task result's details
+    pub fn record(&self, result: TaskResult) -> TaskResult {
+        result
+            .with_detail("access_list_entries", self.access_list.0.len())
+            .with_detail("gas_with_access_list", self.gas_with)
+            .with_detail("gas_without_access_list", self.gas_without)
+            .with_detail("access_list_gas_delta", self.gas_delta())
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use alloy_primitives::{Address, B256};
+
+    fn item(address: u8, keys: &[u8]) -> AccessListItem {
+        AccessListItem {
+            address: Address::repeat_byte(address),
+            storage_keys: keys.iter().map(|k| B256::repeat_byte(*k)).collect(),
+        }
+    }
+
+    #[test]
+    fn test_merge_dedups_accounts_and_keys() {
+        let merged = AccessListEstimate::merge([
+            AccessListEstimate {
+                access_list: AccessList(vec![item(2, &[1, 2]), item(1, &[])]),
+                gas_with: 50_000,
+                gas_without: 52_000,
+            },
+            AccessListEstimate {
+                access_list: AccessList(vec![item(2, &[2, 3])]),
+                gas_with: 30_000,
+                gas_without: 29_500,
+            },
+        ]);
+
+        assert_eq!(
+            merged.access_list,
+            AccessList(vec![item(2, &[1, 2, 3]), item(1, &[])])
+        );
+        assert_eq!(merged.gas_delta(), 1_500);
+
+        let result = merged.record(TaskResult::default());
+        assert_eq!(result.details["access_list_entries"], 2);
+        assert_eq!(result.details["access_list_gas_delta"], 1_500);
+    }
+}
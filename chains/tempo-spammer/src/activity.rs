@@ -0,0 +1,235 @@
+//! Activity Schedule - Time-of-day worker scaling and quiet hours
+//!
+//! Real users are not active at a constant rate around the clock. The
+//! `[activity]` section gives each hour of the day a level between 0.0 and
+//! 1.0, the share of workers allowed to run; the rest sleep until the level
+//! rises again. Quiet hours force the level to 0.
+//!
+//! ```toml
+//! [activity]
+//! timezone = "Asia/Bangkok"
+//! quiet_hours = ["01-06"]
+//! ramp_minutes = 40
+//! hourly = [
+//!     0.3, 0.0, 0.0, 0.0, 0.0, 0.0, 0.2, 0.4, 0.7, 0.9, 1.0, 1.0,
+//!     0.8, 0.9, 1.0, 1.0, 0.9, 0.8, 0.9, 1.0, 1.0, 0.9, 0.7, 0.5,
+//! ]
+//! ```
+//!
+//! # Ramping
+//!
+//! Levels do not step on the hour. Within `ramp_minutes` centred on each hour
+//! boundary the level moves linearly from one hour's value to the next, so
+//! with a 40 minute ramp the 09:00 change runs from 08:40 to 09:20. A ramp of
+//! 0 switches on the hour.
+
+use crate::config::ActivitySettings;
+use anyhow::{Context, Result, bail};
+use chrono::{DateTime, Timelike, Utc};
+use chrono_tz::Tz;
+
+/// A parsed `[activity]` section
+#[derive(Debug, Clone, PartialEq)]
+pub struct ActivitySchedule {
+    timezone: Tz,
+    /// Level per local hour, quiet hours already applied
+    levels: [f64; 24],
+    ramp_minutes: u32,
+}
+
+impl Default for ActivitySchedule {
+    fn default() -> Self {
+        Self {
+            timezone: Tz::UTC,
+            levels: [1.0; 24],
+            ramp_minutes: 0,
+        }
+    }
+}
+
+impl ActivitySchedule {
+    /// Validates and parses the settings
+    ///
+    /// An empty `hourly` list means full activity outside quiet hours.
+    pub fn from_settings(settings: &ActivitySettings) -> Result<Self> {
+        let timezone: Tz = settings
+            .timezone
+            .parse()
+            .map_err(|e| anyhow::anyhow!("{}", e))
+            .with_context(|| format!("Invalid activity timezone '{}'", settings.timezone))?;
+
+        let mut levels = [1.0; 24];
+        if !settings.hourly.is_empty() {
+            if settings.hourly.len() != 24 {
+                bail!(
+                    "activity.hourly needs 24 values, got {}",
+                    settings.hourly.len()
+                );
+            }
+            for (hour, &level) in settings.hourly.iter().enumerate() {
+                if !(0.0..=1.0).contains(&level) {
+                    bail!(
+                        "activity.hourly[{}] = {} is not within 0.0..=1.0",
+                        hour,
+                        level
+                    );
+                }
+                levels[hour] = level;
+            }
+        }
+        for window in &settings.quiet_hours {
+            for (hour, level) in levels.iter_mut().enumerate() {
+                if window.contains(hour as u32) {
+                    *level = 0.0;
+                }
+            }
+        }
+
+        Ok(Self {
+            timezone,
+            levels,
+            ramp_minutes: settings.ramp_minutes.min(60),
+        })
+    }
+
+    /// Whether every hour runs at full activity, i.e. the schedule does nothing
+    pub fn is_constant(&self) -> bool {
+        self.levels.iter().all(|&level| level >= 1.0)
+    }
+
+    /// Activity level at `minute` minutes past local midnight
+    pub fn level_at_minute(&self, minute: u32) -> f64 {
+        let minute = minute % (24 * 60);
+        let hour = (minute / 60) as usize;
+        let past_hour = f64::from(minute % 60);
+        let half = f64::from(self.ramp_minutes) / 2.0;
+
+        if half > 0.0 && past_hour < half {
+            // Second half of the ramp from the previous hour
+            let prev = self.levels[(hour + 23) % 24];
+            lerp(prev, self.levels[hour], (past_hour + half) / (2.0 * half))
+        } else if half > 0.0 && past_hour >= 60.0 - half {
+            // First half of the ramp into the next hour
+            let next = self.levels[(hour + 1) % 24];
+            lerp(
+                self.levels[hour],
+                next,
+                (past_hour - (60.0 - half)) / (2.0 * half),
+            )
+        } else {
+            self.levels[hour]
+        }
+    }
+
+    /// Activity level at `now`, in the configured time zone
+    pub fn level(&self, now: DateTime<Utc>) -> f64 {
+        let local = now.with_timezone(&self.timezone);
+        self.level_at_minute(local.hour() * 60 + local.minute())
+    }
+
+    /// Number of the `worker_count` workers that should run at `level`
+    ///
+    /// Any level above 0 keeps at least one worker running.
+    pub fn active_workers(level: f64, worker_count: u64) -> u64 {
+        if level <= 0.0 || worker_count == 0 {
+            return 0;
+        }
+        ((level * worker_count as f64).round() as u64).clamp(1, worker_count)
+    }
+}
+
+fn lerp(from: f64, to: f64, t: f64) -> f64 {
+    from + (to - from) * t
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    fn schedule(hourly: Vec<f64>, quiet: &[&str], ramp_minutes: u32) -> ActivitySchedule {
+        ActivitySchedule::from_settings(&ActivitySettings {
+            hourly,
+            quiet_hours: quiet.iter().map(|w| w.parse().unwrap()).collect(),
+            ramp_minutes,
+            ..Default::default()
+        })
+        .unwrap()
+    }
+
+    #[test]
+    fn test_quiet_hours_and_ramp() {
+        let mut hourly = vec![1.0; 24];
+        hourly[8] = 0.5;
+        let schedule = schedule(hourly, &["01-06"], 20);
+        assert!(!schedule.is_constant());
+
+        assert_eq!(schedule.level_at_minute(3 * 60 + 30), 0.0);
+        assert_eq!(schedule.level_at_minute(12 * 60), 1.0);
+        // 06:00 ramps from 0.0 to 1.0 between 05:50 and 06:10
+        assert_eq!(schedule.level_at_minute(5 * 60 + 50), 0.0);
+        assert_eq!(schedule.level_at_minute(6 * 60), 0.5);
+        assert_eq!(schedule.level_at_minute(6 * 60 + 5), 0.75);
+        assert_eq!(schedule.level_at_minute(6 * 60 + 10), 1.0);
+        // 09:00 ramps from 0.5 back to 1.0
+        assert_eq!(schedule.level_at_minute(8 * 60 + 30), 0.5);
+        assert_eq!(schedule.level_at_minute(9 * 60), 0.75);
+        // Midnight wraps: 00:00 is the boundary between hour 23 and 0
+        assert_eq!(schedule.level_at_minute(24 * 60 - 1), 1.0);
+
+        let stepped = ActivitySchedule {
+            ramp_minutes: 0,
+            ..schedule
+        };
+        assert_eq!(stepped.level_at_minute(6 * 60), 1.0);
+        assert_eq!(stepped.level_at_minute(6 * 60 - 1), 0.0);
+    }
+
+    #[test]
+    fn test_settings_validation() {
+        assert!(
+            ActivitySchedule::from_settings(&ActivitySettings::default())
+                .unwrap()
+                .is_constant()
+        );
+        assert!(
+            ActivitySchedule::from_settings(&ActivitySettings {
+                hourly: vec![1.0; 23],
+                ..Default::default()
+            })
+            .is_err()
+        );
+        assert!(
+            ActivitySchedule::from_settings(&ActivitySettings {
+                hourly: vec![1.5; 24],
+                ..Default::default()
+            })
+            .is_err()
+        );
+        assert!(
+            ActivitySchedule::from_settings(&ActivitySettings {
+                timezone: "Mars/Olympus".to_string(),
+                ..Default::default()
+            })
+            .is_err()
+        );
+    }
+
+    #[test]
+    fn test_timezone_and_worker_count() {
+        let schedule = ActivitySchedule::from_settings(&ActivitySettings {
+            timezone: "Asia/Bangkok".to_string(),
+            quiet_hours: vec!["01-06".parse().unwrap()],
+            ramp_minutes: 0,
+            ..Default::default()
+        })
+        .unwrap();
+        // 20:00 UTC is 03:00 in Bangkok
+        let now = "2026-10-18T20:00:00Z".parse::<DateTime<Utc>>().unwrap();
+        assert_eq!(schedule.level(now), 0.0);
+
+        assert_eq!(ActivitySchedule::active_workers(0.0, 10), 0);
+        assert_eq!(ActivitySchedule::active_workers(0.01, 10), 1);
+        assert_eq!(ActivitySchedule::active_workers(0.44, 10), 4);
+        assert_eq!(ActivitySchedule::active_workers(1.0, 10), 10);
+    }
+}
@@ -0,0 +1,270 @@
+//! EVM Assembler - Hand-written contract bytecode
+//!
+//! The mock system contracts of `--local-dev` and the helper contracts that
+//! `bootstrap` deploys are small enough to write opcode by opcode, which
+//! keeps the spammer free of a Solidity toolchain. [`Asm`] resolves named
+//! jump targets and a few calldata and `CALL` patterns; [`init_code`] wraps
+//! runtime code for deployment.
+
+use alloy_primitives::{Address, Bytes, U256};
+use std::collections::HashMap;
+
+pub(crate) mod op {
+    pub const STOP: u8 = 0x00;
+    pub const ADD: u8 = 0x01;
+    pub const SUB: u8 = 0x03;
+    pub const MOD: u8 = 0x06;
+    pub const LT: u8 = 0x10;
+    pub const GT: u8 = 0x11;
+    pub const EQ: u8 = 0x14;
+    pub const ISZERO: u8 = 0x15;
+    pub const AND: u8 = 0x16;
+    pub const SHL: u8 = 0x1b;
+    pub const SHR: u8 = 0x1c;
+    pub const KECCAK256: u8 = 0x20;
+    pub const ADDRESS: u8 = 0x30;
+    pub const CALLER: u8 = 0x33;
+    pub const CALLDATALOAD: u8 = 0x35;
+    pub const CODECOPY: u8 = 0x39;
+    pub const EXTCODECOPY: u8 = 0x3c;
+    pub const TIMESTAMP: u8 = 0x42;
+    pub const CHAINID: u8 = 0x46;
+    pub const POP: u8 = 0x50;
+    pub const MLOAD: u8 = 0x51;
+    pub const MSTORE: u8 = 0x52;
+    pub const SLOAD: u8 = 0x54;
+    pub const SSTORE: u8 = 0x55;
+    pub const JUMP: u8 = 0x56;
+    pub const JUMPI: u8 = 0x57;
+    pub const GAS: u8 = 0x5a;
+    pub const JUMPDEST: u8 = 0x5b;
+    pub const PUSH1: u8 = 0x60;
+    pub const PUSH2: u8 = 0x61;
+    pub const DUP1: u8 = 0x80;
+    pub const DUP2: u8 = 0x81;
+    pub const DUP4: u8 = 0x83;
+    pub const DUP5: u8 = 0x84;
+    pub const LOG2: u8 = 0xa2;
+    pub const LOG3: u8 = 0xa3;
+    pub const CREATE: u8 = 0xf0;
+    pub const CALL: u8 = 0xf1;
+    pub const RETURN: u8 = 0xf3;
+    pub const STATICCALL: u8 = 0xfa;
+    pub const REVERT: u8 = 0xfd;
+}
+
+/// Length of [`deploy_prefix`]
+pub(crate) const DEPLOY_PREFIX_LEN: usize = 14;
+
+/// Minimal assembler with named jump targets
+#[derive(Default)]
+pub(crate) struct Asm {
+    code: Vec<u8>,
+    labels: HashMap<&'static str, usize>,
+    /// Positions of two-byte jump targets still to fill in
+    fixups: Vec<(usize, &'static str)>,
+}
+
+impl Asm {
+    pub(crate) fn op(&mut self, op: u8) -> &mut Self {
+        self.code.push(op);
+        self
+    }
+
+    /// Pushes `value` with the shortest PUSH that holds it
+    pub(crate) fn push(&mut self, value: U256) -> &mut Self {
+        let bytes = value.to_be_bytes_trimmed_vec();
+        let bytes = if bytes.is_empty() { vec![0] } else { bytes };
+        self.code.push(op::PUSH1 + bytes.len() as u8 - 1);
+        self.code.extend_from_slice(&bytes);
+        self
+    }
+
+    pub(crate) fn push_u64(&mut self, value: u64) -> &mut Self {
+        self.push(U256::from(value))
+    }
+
+    /// Pushes a selector shifted into the top four bytes of a word
+    pub(crate) fn push_selector(&mut self, selector: [u8; 4]) -> &mut Self {
+        self.push(U256::from(u32::from_be_bytes(selector)) << 224)
+    }
+
+    pub(crate) fn push_label(&mut self, label: &'static str) -> &mut Self {
+        self.code.push(op::PUSH2);
+        self.fixups.push((self.code.len(), label));
+        self.code.extend_from_slice(&[0, 0]);
+        self
+    }
+
+    pub(crate) fn label(&mut self, label: &'static str) -> &mut Self {
+        self.labels.insert(label, self.code.len());
+        self.op(op::JUMPDEST)
+    }
+
+    /// Pushes calldata word `index` (0 for the first argument)
+    pub(crate) fn arg(&mut self, index: u64) -> &mut Self {
+        self.push_u64(4 + 32 * index).op(op::CALLDATALOAD)
+    }
+
+    /// Pushes the selector of the call as a number
+    pub(crate) fn selector(&mut self) -> &mut Self {
+        self.push_u64(0)
+            .op(op::CALLDATALOAD)
+            .push_u64(224)
+            .op(op::SHR)
+    }
+
+    /// Jumps to `label` when the call's selector matches; the selector stays on the stack
+    pub(crate) fn dispatch(&mut self, selector: [u8; 4], label: &'static str) -> &mut Self {
+        self.op(op::DUP1)
+            .push_u64(u32::from_be_bytes(selector).into())
+            .op(op::EQ)
+            .push_label(label)
+            .op(op::JUMPI)
+    }
+
+    /// Stores `value` (emitted by `push`) at memory `offset`
+    pub(crate) fn mstore(&mut self, offset: u64, push: impl FnOnce(&mut Self)) -> &mut Self {
+        push(self);
+        self.push_u64(offset).op(op::MSTORE)
+    }
+
+    /// Returns the word on top of the stack
+    pub(crate) fn return_word(&mut self) -> &mut Self {
+        self.push_u64(0)
+            .op(op::MSTORE)
+            .push_u64(32)
+            .push_u64(0)
+            .op(op::RETURN)
+    }
+
+    /// `CALL`s `target` with the first `args_len` bytes of memory, reverting if it fails
+    pub(crate) fn call(&mut self, target: Address, args_len: u64) -> &mut Self {
+        self.call_with(args_len, |asm| {
+            asm.push(U256::from_be_slice(target.as_slice()));
+        })
+    }
+
+    /// Like [`Self::call`], with the target taken from calldata word `index`
+    pub(crate) fn call_arg(&mut self, index: u64, args_len: u64) -> &mut Self {
+        self.call_with(args_len, |asm| {
+            asm.arg(index);
+        })
+    }
+
+    /// Like [`Self::call`], with the target read from memory at `target_at`
+    pub(crate) fn call_mem(&mut self, target_at: u64, args_len: u64) -> &mut Self {
+        self.call_with(args_len, |asm| {
+            asm.push_u64(target_at).op(op::MLOAD);
+        })
+    }
+
+    fn call_with(&mut self, args_len: u64, target: impl FnOnce(&mut Self)) -> &mut Self {
+        self.push_u64(0)
+            .push_u64(0)
+            .push_u64(args_len)
+            .push_u64(0)
+            .push_u64(0);
+        target(self);
+        self.op(op::GAS)
+            .op(op::CALL)
+            .op(op::ISZERO)
+            .push_label("fail")
+            .op(op::JUMPI)
+    }
+
+    /// Target of [`Self::call`] failures: reverts without data
+    pub(crate) fn fail(&mut self) -> &mut Self {
+        self.label("fail").push_u64(0).op(op::DUP1).op(op::REVERT)
+    }
+
+    pub(crate) fn assemble(mut self) -> Bytes {
+        for (at, label) in &self.fixups {
+            let target = self.labels[label] as u16;
+            self.code[*at..*at + 2].copy_from_slice(&target.to_be_bytes());
+        }
+        self.code.into()
+    }
+}
+
+/// Init code prefix that returns the `runtime_len` bytes following it
+pub(crate) fn deploy_prefix(runtime_len: usize) -> Bytes {
+    let len = (runtime_len as u16).to_be_bytes();
+    let mut asm = Asm::default();
+    asm.op(op::PUSH2)
+        .op(len[0])
+        .op(len[1])
+        .push_u64(DEPLOY_PREFIX_LEN as u64)
+        .push_u64(0)
+        .op(op::CODECOPY)
+        .op(op::PUSH2)
+        .op(len[0])
+        .op(len[1])
+        .push_u64(0)
+        .op(op::RETURN);
+    let prefix = asm.assemble();
+    debug_assert_eq!(prefix.len(), DEPLOY_PREFIX_LEN);
+    prefix
+}
+
+/// Init code deploying `runtime`
+pub(crate) fn init_code(runtime: &[u8]) -> Bytes {
+    [deploy_prefix(runtime.len()).as_ref(), runtime]
+        .concat()
+        .into()
+}
+
+/// Jump targets pushed right before `JUMP`/`JUMPI` that do not land on a `JUMPDEST`
+#[cfg(test)]
+pub(crate) fn bad_jumps(code: &[u8]) -> Vec<usize> {
+    let mut bad = Vec::new();
+    let mut pc = 0;
+    while pc < code.len() {
+        let opcode = code[pc];
+        if opcode == op::PUSH2 && matches!(code.get(pc + 3), Some(&(op::JUMP | op::JUMPI))) {
+            let target = u16::from_be_bytes([code[pc + 1], code[pc + 2]]) as usize;
+            if code.get(target) != Some(&op::JUMPDEST) {
+                bad.push(target);
+            }
+        }
+        pc += 1 + match opcode {
+            0x60..=0x7f => (opcode - 0x5f) as usize,
+            _ => 0,
+        };
+    }
+    bad
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    #[test]
+    fn test_init_code_returns_runtime() {
+        let runtime = [op::CALLER, op::PUSH1, 0, op::MSTORE];
+        let code = init_code(&runtime);
+        assert_eq!(
+            &code[..DEPLOY_PREFIX_LEN],
+            &[
+                0x61, 0, 4, 0x60, 0x0e, 0x60, 0x00, 0x39, 0x61, 0, 4, 0x60, 0x00, 0xf3
+            ]
+        );
+        assert_eq!(&code[DEPLOY_PREFIX_LEN..], &runtime);
+    }
+
+    #[test]
+    fn test_labels_resolve_to_jumpdests() {
+        let mut asm = Asm::default();
+        asm.push_u64(1)
+            .push_label("end")
+            .op(op::JUMPI)
+            .push_label("loop")
+            .op(op::JUMP)
+            .label("loop")
+            .op(op::STOP)
+            .label("end");
+        let code = asm.assemble();
+        assert!(bad_jumps(&code).is_empty());
+        assert_eq!(code[code.len() - 1], op::JUMPDEST);
+    }
+}
@@ -0,0 +1,362 @@
+//! Bootstrap - One-command recovery after a testnet reset
+//!
+//! A reset wipes every contract deployed on the network. Instead of
+//! rediscovering addresses by hand, `tempo-spammer bootstrap`:
+//!
+//! 1. checks that each of the [`system_contracts`] has code, which catches
+//!    a wrong RPC or chain id, genesis changes and stale `[contracts]`
+//!    addresses
+//! 2. looks up the [`helper contracts`](crate::contracts) recorded for the chain in `helper_contracts`
+//!    and keeps each one whose code still matches
+//! 3. deploys the missing or stale helpers from the first wallet and
+//!    records the new addresses
+//!
+//! ```bash
+//! tempo-spammer bootstrap              # check, then deploy what is missing
+//! tempo-spammer bootstrap --check-only # report without sending anything
+//! tempo-spammer bootstrap --force      # redeploy every helper
+//! ```
+
+use crate::TempoClient;
+use crate::asm::init_code;
+use crate::contracts::helper_contracts;
+use crate::gas_usage::wait_for_receipt;
+use crate::network;
+use alloy::providers::Provider;
+use alloy::rpc::types::TransactionRequest;
+use alloy_primitives::{Address, B256, TxKind, address};
+use anyhow::{Context, Result, bail};
+use core_logic::database::{DatabaseManager, HelperContractRecord};
+use std::time::Duration;
+use tracing::info;
+
+/// Precompiles and well-known deployments the tasks rely on besides the
+/// configured [`SystemContracts`](crate::config::SystemContracts)
+pub const FIXED_CONTRACTS: &[(&str, Address)] = &[
+    ("NonceManager", crate::client::NONCE_PRECOMPILE),
+    ("AccountKeychain", crate::session_key::ACCOUNT_KEYCHAIN),
+    ("Multicall3", crate::tasks::tip20::MULTICALL3),
+    (
+        "CreateX",
+        address!("ba5Ed099633D3B313e4D5F7bdc1305d3c28ba5Ed"),
+    ),
+    (
+        "Permit2",
+        address!("000000000022d473030f116ddee9f6b43ac78ba3"),
+    ),
+    (
+        "Create2Deployer",
+        address!("4e59b44847b379578588920cA78FbF26c0B4956C"),
+    ),
+];
+
+/// Contracts the tasks rely on: the configured `[contracts]`, then
+/// the [`FIXED_CONTRACTS`]
+pub fn system_contracts() -> Vec<(&'static str, Address)> {
+    network::contracts()
+        .named()
+        .into_iter()
+        .chain(FIXED_CONTRACTS.iter().copied())
+        .collect()
+}
+
+/// Gas limit of a helper deployment; the helpers are a few hundred bytes
+const DEPLOY_GAS_LIMIT: u64 = 500_000;
+
+const RECEIPT_TIMEOUT: Duration = Duration::from_secs(120);
+const RECEIPT_POLL: Duration = Duration::from_secs(2);
+
+/// How far [`run_bootstrap`] may go
+#[derive(Debug, Clone, Copy, Default, PartialEq, Eq)]
+pub struct BootstrapOptions {
+    /// Report only, deploy nothing
+    pub check_only: bool,
+    /// Redeploy helpers whose recorded copy is still current
+    pub force: bool,
+}
+
+/// Presence of one system contract
+#[derive(Debug, Clone, PartialEq, Eq)]
+pub struct SystemCheck {
+    pub name: &'static str,
+    pub address: Address,
+    pub present: bool,
+}
+
+#[derive(Debug, Clone, Copy, PartialEq, Eq)]
+pub enum HelperStatus {
+    /// The recorded address still holds the helper
+    Current,
+    /// Deployed and recorded by this run
+    Deployed,
+    /// Not recorded or gone, and left so by `--check-only`
+    Missing,
+}
+
+#[derive(Debug, Clone, PartialEq, Eq)]
+pub struct HelperOutcome {
+    pub name: &'static str,
+    /// Current or new address; for missing helpers the stale one, if any
+    pub address: Option<Address>,
+    pub status: HelperStatus,
+    pub tx_hash: Option<B256>,
+}
+
+#[derive(Debug, Clone, PartialEq, Eq)]
+pub struct BootstrapReport {
+    pub chain_id: u64,
+    pub system: Vec<SystemCheck>,
+    pub helpers: Vec<HelperOutcome>,
+}
+
+impl BootstrapReport {
+    /// True when every system contract is present and every helper deployed
+    pub fn is_ready(&self) -> bool {
+        self.system.iter().all(|check| check.present)
+            && self
+                .helpers
+                .iter()
+                .all(|helper| helper.status != HelperStatus::Missing)
+    }
+}
+
+/// Checks that every entry of [`system_contracts`] has code
+pub async fn check_system_contracts(
+    provider: &(dyn Provider + Send + Sync),
+) -> Result<Vec<SystemCheck>> {
+    let contracts = system_contracts();
+    let mut checks = Vec::with_capacity(contracts.len());
+    for (name, address) in contracts {
+        let code = provider
+            .get_code_at(address)
+            .await
+            .with_context(|| format!("Failed to read code of {} at {}", name, address))?;
+        checks.push(SystemCheck {
+            name,
+            address,
+            present: !code.is_empty(),
+        });
+    }
+    Ok(checks)
+}
+
+/// Checks system contracts, then keeps or deploys each helper contract
+pub async fn run_bootstrap(
+    client: &TempoClient,
+    rpc_url: &str,
+    db: &DatabaseManager,
+    options: BootstrapOptions,
+) -> Result<BootstrapReport> {
+    let chain_id = client.chain_id();
+    let system = check_system_contracts(client.provider()).await?;
+    let recorded = db.get_helper_contracts(chain_id).await?;
+
+    let mut helpers = Vec::new();
+    for helper in helper_contracts() {
+        let previous = recorded
+            .iter()
+            .find(|record| record.name == helper.name)
+            .and_then(|record| record.address.parse::<Address>().ok());
+
+        if let Some(address) = previous {
+            if !options.force && has_runtime(client, address, &helper.runtime).await? {
+                helpers.push(HelperOutcome {
+                    name: helper.name,
+                    address: Some(address),
+                    status: HelperStatus::Current,
+                    tx_hash: None,
+                });
+                continue;
+            }
+        }
+        if options.check_only {
+            helpers.push(HelperOutcome {
+                name: helper.name,
+                address: previous,
+                status: HelperStatus::Missing,
+                tx_hash: None,
+            });
+            continue;
+        }
+
+        let (address, tx_hash) = deploy(client, rpc_url, &helper.runtime)
+            .await
+            .with_context(|| format!("Failed to deploy {}", helper.name))?;
+        db.log_helper_contract(&HelperContractRecord {
+            chain_id: chain_id as i64,
+            name: helper.name.to_string(),
+            address: address.to_string(),
+            code_hash: helper.code_hash().to_string(),
+            tx_hash: tx_hash.to_string(),
+            deployer: client.address().to_string(),
+            deployed_at: chrono::Utc::now().timestamp(),
+        })
+        .await?;
+        info!("Deployed {} at {} ({})", helper.name, address, tx_hash);
+        helpers.push(HelperOutcome {
+            name: helper.name,
+            address: Some(address),
+            status: HelperStatus::Deployed,
+            tx_hash: Some(tx_hash),
+        });
+    }
+
+    Ok(BootstrapReport {
+        chain_id,
+        system,
+        helpers,
+    })
+}
+
+/// Whether `address` holds exactly `runtime`
+async fn has_runtime(client: &TempoClient, address: Address, runtime: &[u8]) -> Result<bool> {
+    let code = client
+        .provider
+        .get_code_at(address)
+        .await
+        .with_context(|| format!("Failed to read code at {}", address))?;
+    Ok(code.as_ref() == runtime)
+}
+
+/// Deploys `runtime` and waits until its code is on chain
+async fn deploy(client: &TempoClient, rpc_url: &str, runtime: &[u8]) -> Result<(Address, B256)> {
+    let nonce = client.get_pending_nonce(rpc_url).await?;
+    let mut tx = TransactionRequest::default()
+        .input(init_code(runtime).into())
+        .from(client.address())
+        .nonce(nonce)
+        .gas_limit(DEPLOY_GAS_LIMIT);
+    tx.to = Some(TxKind::Create);
+
+    let pending = client
+        .provider
+        .send_transaction(tx)
+        .await
+        .context("Failed to send deployment transaction")?;
+    let tx_hash = *pending.tx_hash();
+    let receipt = wait_for_receipt(
+        client.provider.as_ref(),
+        tx_hash,
+        RECEIPT_TIMEOUT,
+        RECEIPT_POLL,
+    )
+    .await
+    .with_context(|| format!("No receipt for deployment {}", tx_hash))?;
+    if !receipt.status() {
+        bail!("Deployment {} reverted", tx_hash);
+    }
+    let address = receipt
+        .contract_address
+        .with_context(|| format!("Receipt of {} has no contract address", tx_hash))?;
+    if !has_runtime(client, address, runtime).await? {
+        bail!(
+            "Code at {} does not match after deployment {}",
+            address,
+            tx_hash
+        );
+    }
+    Ok((address, tx_hash))
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use crate::testing::TaskHarness;
+
+    /// Harness whose chain has every system contract and expects `deploys` helper deployments
+    async fn harness(deploys: usize) -> TaskHarness {
+        let harness = TaskHarness::new().with_db().await.unwrap();
+        for (_, address) in system_contracts() {
+            harness.mock.set_code(address, vec![0xef]);
+        }
+        // The mock does not execute init code, so the deployed code is set up front
+        for (nonce, helper) in helper_contracts().iter().take(deploys).enumerate() {
+            harness.mock.set_code(
+                harness.mock.address().create(nonce as u64),
+                helper.runtime.clone(),
+            );
+        }
+        harness
+    }
+
+    async fn bootstrap(harness: &TaskHarness, options: BootstrapOptions) -> BootstrapReport {
+        let db = harness.db.clone().unwrap();
+        run_bootstrap(&harness.mock.client(), "http://mock", &db, options)
+            .await
+            .unwrap()
+    }
+
+    #[tokio::test]
+    async fn test_deploys_and_records_helpers() {
+        let count = helper_contracts().len();
+        let harness = harness(count).await;
+        let report = bootstrap(&harness, BootstrapOptions::default()).await;
+
+        assert!(report.is_ready(), "{:?}", report);
+        assert_eq!(harness.mock.sent().len(), count);
+        let recorded = harness
+            .db
+            .as_ref()
+            .unwrap()
+            .get_helper_contracts(report.chain_id)
+            .await
+            .unwrap();
+        assert_eq!(recorded.len(), count);
+        for outcome in &report.helpers {
+            assert_eq!(outcome.status, HelperStatus::Deployed);
+            let record = recorded.iter().find(|r| r.name == outcome.name).unwrap();
+            assert_eq!(record.address, outcome.address.unwrap().to_string());
+        }
+
+        // A second run finds every helper in place and sends nothing
+        let again = bootstrap(&harness, BootstrapOptions::default()).await;
+        assert!(
+            again
+                .helpers
+                .iter()
+                .all(|h| h.status == HelperStatus::Current)
+        );
+        assert_eq!(harness.mock.sent().len(), count);
+    }
+
+    #[tokio::test]
+    async fn test_check_only_reports_reset_chain() {
+        let harness = harness(0).await;
+        harness
+            .mock
+            .set_code(system_contracts()[0].1, alloy_primitives::Bytes::new());
+        // Recorded before a reset, no code there any more
+        let stale = Address::repeat_byte(0x77);
+        harness
+            .db
+            .as_ref()
+            .unwrap()
+            .log_helper_contract(&HelperContractRecord {
+                chain_id: harness.mock.client().chain_id() as i64,
+                name: "disperse".to_string(),
+                address: stale.to_string(),
+                code_hash: String::new(),
+                tx_hash: String::new(),
+                deployer: String::new(),
+                deployed_at: 0,
+            })
+            .await
+            .unwrap();
+
+        let report = bootstrap(
+            &harness,
+            BootstrapOptions {
+                check_only: true,
+                ..Default::default()
+            },
+        )
+        .await;
+
+        assert!(!report.is_ready());
+        assert!(!report.system[0].present);
+        assert!(report.system[1..].iter().all(|check| check.present));
+        assert_eq!(report.helpers[0].status, HelperStatus::Missing);
+        assert_eq!(report.helpers[0].address, Some(stale));
+        assert!(harness.mock.sent().is_empty());
+    }
+}
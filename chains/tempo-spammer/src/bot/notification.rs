@@ -1,10 +1,13 @@
+use crate::rpc_stats::{RpcAlert, rpc_stats};
+use crate::task_slo::{SloEvent, task_slo};
+use crate::wallet_health::{QuarantinedWallet, WalletHealth};
 use anyhow::{Error, Result};
 use chrono::{DateTime, Utc};
 use chrono_tz::Asia::Bangkok;
 use reqwest::Client;
 use std::sync::Arc;
 use std::time::Duration;
-use tokio::time::interval;
+use tokio::time::{Instant, interval, interval_at};
 use tracing::{error, info};
 
 // Include compile-time Telegram configuration from build.rs
@@ -27,12 +30,25 @@ impl TelegramConfig {
     }
 }
 
+/// How often the notifier looks for newly quarantined wallets
+const QUARANTINE_CHECK: Duration = Duration::from_secs(5 * 60);
+
+/// Quarantined wallets listed in one alert
+const QUARANTINE_ALERT_LIMIT: usize = 10;
+
+/// How often the notifier looks for new `[rpc_stats]` alerts
+const RPC_ALERT_CHECK: Duration = Duration::from_secs(60);
+
+/// How often the notifier looks for tasks paused or resumed under `[slo]`
+const SLO_CHECK: Duration = Duration::from_secs(60);
+
 /// Telegram notification service
 pub struct TelegramNotifier {
     config: TelegramConfig,
     client: Client,
     start_time: DateTime<Utc>,
     ip_address: String,
+    wallet_health: Option<Arc<WalletHealth>>,
 }
 
 impl TelegramNotifier {
@@ -45,9 +61,16 @@ impl TelegramNotifier {
             client,
             start_time: Utc::now(),
             ip_address,
+            wallet_health: None,
         }
     }
 
+    /// Also reports wallets the pool quarantines
+    pub fn with_wallet_health(mut self, wallet_health: Arc<WalletHealth>) -> Self {
+        self.wallet_health = Some(wallet_health);
+        self
+    }
+
     /// Fetch public IP address using ipify.org API
     async fn fetch_public_ip(client: &Client) -> String {
         match client
@@ -131,6 +154,12 @@ impl TelegramNotifier {
             format!("{}s", uptime.num_seconds())
         };
 
+        let quarantined = self
+            .wallet_health
+            .as_ref()
+            .map_or(0, |health| health.quarantined().len());
+        let paused_tasks = task_slo().paused().len();
+
         if is_first {
             format!(
                 "🚀 *VPS + tempo-spammer started*\n\n\
@@ -147,16 +176,77 @@ impl TelegramNotifier {
                 🌐 IP Address: `{}`\n\
                 🕐 Current time: {} (GMT+7)\n\
                 ⏱️ Uptime: {}\n\
+                🩺 Quarantined wallets: {}\n\
+                ⏸️ Paused tasks: {}\n\
                 📍 VPS is healthy and operational",
                 self.ip_address,
                 now_gmt7.format("%Y-%m-%d %H:%M:%S"),
-                uptime_str
+                uptime_str,
+                quarantined,
+                paused_tasks
             )
         }
     }
 
+    /// Alert for wallets quarantined after `since`, if there are any
+    fn format_quarantine_alert(
+        wallets: &[QuarantinedWallet],
+        since: DateTime<Utc>,
+    ) -> Option<String> {
+        let new: Vec<_> = wallets.iter().filter(|w| w.since > since).collect();
+        if new.is_empty() {
+            return None;
+        }
+        let mut message = format!("🩺 *{} wallets quarantined*\n", new.len());
+        for wallet in new.iter().take(QUARANTINE_ALERT_LIMIT) {
+            message.push_str(&format!(
+                "\n• #{:03} `{}` score {}, `{}` until {} (GMT+7)",
+                wallet.index,
+                wallet.address,
+                wallet.score,
+                wallet.error_summary(),
+                wallet.until.with_timezone(&Bangkok).format("%H:%M")
+            ));
+        }
+        if new.len() > QUARANTINE_ALERT_LIMIT {
+            message.push_str(&format!(
+                "\n…and {} more",
+                new.len() - QUARANTINE_ALERT_LIMIT
+            ));
+        }
+        message.push_str(&format!("\n\nIn quarantine now: {}", wallets.len()));
+        Some(message)
+    }
+
+    /// Alert for RPC methods past their `[rpc_stats]` limits, if there are any
+    fn format_rpc_alert(alerts: &[RpcAlert]) -> Option<String> {
+        if alerts.is_empty() {
+            return None;
+        }
+        let mut message = format!("🐢 *{} RPC alerts*\n", alerts.len());
+        for alert in alerts {
+            message.push_str(&format!("\n• `{}`", alert));
+        }
+        Some(message)
+    }
+
+    /// Alert for tasks paused or resumed under `[slo]`, if there are any
+    fn format_slo_alert(events: &[SloEvent]) -> Option<String> {
+        if events.is_empty() {
+            return None;
+        }
+        let mut message = format!("⏸️ *{} task SLO changes*\n", events.len());
+        for event in events {
+            message.push_str(&format!("\n• `{}`", event));
+        }
+        Some(message)
+    }
+
     /// Start the notification scheduler
-    /// Sends first notification immediately, then every 3 hours
+    /// Sends first notification immediately, then every 3 hours, and alerts
+    /// on newly quarantined wallets within [`QUARANTINE_CHECK`], on RPC
+    /// methods past their limits within [`RPC_ALERT_CHECK`] and on tasks
+    /// paused or resumed within [`SLO_CHECK`]
     pub async fn start(self: Arc<Self>) {
         info!("Starting Telegram notification service (every 3 hours)");
 
@@ -170,26 +260,80 @@ impl TelegramNotifier {
 
         // Create interval for every 3 hours (3 * 60 * 60 = 10800 seconds)
         let mut interval = interval(Duration::from_secs(3 * 60 * 60));
+        interval.tick().await; // the first tick fires at once
+        let mut quarantine_check = interval_at(Instant::now() + QUARANTINE_CHECK, QUARANTINE_CHECK);
+        let mut rpc_check = interval_at(Instant::now() + RPC_ALERT_CHECK, RPC_ALERT_CHECK);
+        let mut slo_check = interval_at(Instant::now() + SLO_CHECK, SLO_CHECK);
+        let mut alerted_until = Utc::now();
+        let mut rpc_alerted_until = Utc::now();
+        let mut slo_alerted_until = Utc::now();
 
         loop {
-            interval.tick().await;
-
-            let message = self.format_status_message(false);
-            match self.send_message(&message).await {
-                Ok(_) => info!("Periodic Telegram notification sent"),
-                Err(e) => error!("Failed to send Telegram notification: {}", e),
+            tokio::select! {
+                _ = interval.tick() => {
+                    let message = self.format_status_message(false);
+                    match self.send_message(&message).await {
+                        Ok(_) => info!("Periodic Telegram notification sent"),
+                        Err(e) => error!("Failed to send Telegram notification: {}", e),
+                    }
+                }
+                _ = quarantine_check.tick() => {
+                    let Some(health) = &self.wallet_health else {
+                        continue;
+                    };
+                    let checked_at = Utc::now();
+                    let wallets = health.quarantined();
+                    if let Some(message) = Self::format_quarantine_alert(&wallets, alerted_until) {
+                        match self.send_message(&message).await {
+                            Ok(_) => alerted_until = checked_at,
+                            Err(e) => error!("Failed to send quarantine alert: {}", e),
+                        }
+                    }
+                }
+                _ = rpc_check.tick() => {
+                    let alerts = rpc_stats().alerts_since(rpc_alerted_until);
+                    let Some(last) = alerts.last().map(|alert| alert.raised_at) else {
+                        continue;
+                    };
+                    if let Some(message) = Self::format_rpc_alert(&alerts) {
+                        match self.send_message(&message).await {
+                            Ok(_) => rpc_alerted_until = last,
+                            Err(e) => error!("Failed to send RPC alert: {}", e),
+                        }
+                    }
+                }
+                _ = slo_check.tick() => {
+                    let events = task_slo().events_since(slo_alerted_until);
+                    let Some(last) = events.last().map(|event| event.at) else {
+                        continue;
+                    };
+                    if let Some(message) = Self::format_slo_alert(&events) {
+                        match self.send_message(&message).await {
+                            Ok(_) => slo_alerted_until = last,
+                            Err(e) => error!("Failed to send task SLO alert: {}", e),
+                        }
+                    }
+                }
             }
         }
     }
 }
 
 /// Initialize and spawn the notification service
-pub async fn spawn_notification_service() -> Option<tokio::task::JoinHandle<()>> {
+///
+/// With `wallet_health` the bot also reports quarantined wallets.
+pub async fn spawn_notification_service(
+    wallet_health: Option<Arc<WalletHealth>>,
+) -> Option<tokio::task::JoinHandle<()>> {
     let config = TelegramConfig::new();
 
     info!("Initializing Telegram bot (chat_id: {})", config.chat_id);
 
-    let notifier = Arc::new(TelegramNotifier::new(config).await);
+    let mut notifier = TelegramNotifier::new(config).await;
+    if let Some(health) = wallet_health {
+        notifier = notifier.with_wallet_health(health);
+    }
+    let notifier = Arc::new(notifier);
 
     Some(tokio::spawn(async move {
         notifier.start().await;
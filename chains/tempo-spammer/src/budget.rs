@@ -0,0 +1,457 @@
+//! Spend Budget - Daily per-wallet and global token spend caps
+//!
+//! Guards against a misbehaving task draining wallets. `[budget]` caps how
+//! much of a token each wallet, and all wallets together, may send per UTC
+//! day:
+//!
+//! ```toml
+//! [budget]
+//! wallet_daily = { "PathUSD" = 50_000_000 }    # 50 PathUSD per wallet
+//! global_daily = { "PathUSD" = 2_000_000_000 } # 2000 PathUSD in total
+//! ```
+//!
+//! Value-moving tasks reserve their amount through
+//! [`TaskContext::reserve_spend`](crate::tasks::TaskContext::reserve_spend)
+//! before sending. A reservation over either cap fails with
+//! [`BudgetExceeded`], which the task returns as its result instead of
+//! sending. A reservation that is dropped without being committed (the send
+//! failed) is refunded.
+//!
+//! Committed totals are written to the `spend_ledger` table and loaded again
+//! at startup, so restarts do not reset the day's budget. Tokens without a
+//! cap are not tracked.
+
+use crate::config::BudgetSettings;
+use crate::tasks::TaskResult;
+use crate::tasks::tempo_tokens::TempoTokens;
+use alloy_primitives::{Address, U256};
+use anyhow::{Context, Result};
+use chrono::NaiveDate;
+use core_logic::database::{DatabaseManager, SpendRecord};
+use std::collections::HashMap;
+use std::fmt;
+use std::str::FromStr;
+use std::sync::{Arc, Mutex};
+use tracing::warn;
+
+/// Which cap a reservation ran into
+#[derive(Debug, Clone, Copy, PartialEq, Eq)]
+pub enum BudgetScope {
+    Wallet,
+    Global,
+}
+
+/// A spend that would take a wallet or the fleet over its daily cap
+#[derive(Debug, Clone, PartialEq, Eq)]
+pub struct BudgetExceeded {
+    pub scope: BudgetScope,
+    pub token: Address,
+    /// Already spent today within the scope
+    pub spent: U256,
+    pub requested: U256,
+    pub cap: U256,
+}
+
+impl fmt::Display for BudgetExceeded {
+    fn fmt(&self, f: &mut fmt::Formatter<'_>) -> fmt::Result {
+        let scope = match self.scope {
+            BudgetScope::Wallet => "wallet",
+            BudgetScope::Global => "global",
+        };
+        write!(
+            f,
+            "Budget exceeded: {} daily cap for {} is {}, spent {}, requested {}",
+            scope,
+            token_label(self.token),
+            self.cap,
+            self.spent,
+            self.requested
+        )
+    }
+}
+
+impl std::error::Error for BudgetExceeded {}
+
+impl From<BudgetExceeded> for TaskResult {
+    fn from(exceeded: BudgetExceeded) -> Self {
+        TaskResult {
+            success: false,
+            message: exceeded.to_string(),
+            tx_hash: None,
+            ..Default::default()
+        }
+    }
+}
+
+#[derive(Debug)]
+struct Ledger {
+    day: NaiveDate,
+    wallets: HashMap<(Address, Address), U256>,
+    global: HashMap<Address, U256>,
+}
+
+impl Ledger {
+    fn new(day: NaiveDate) -> Self {
+        Self {
+            day,
+            wallets: HashMap::new(),
+            global: HashMap::new(),
+        }
+    }
+
+    fn roll_to(&mut self, day: NaiveDate) {
+        if self.day != day {
+            *self = Self::new(day);
+        }
+    }
+}
+
+/// Daily spend caps and the running totals they are checked against
+#[derive(Debug)]
+pub struct SpendBudget {
+    wallet_caps: HashMap<Address, U256>,
+    global_caps: HashMap<Address, U256>,
+    ledger: Mutex<Ledger>,
+    db: Option<Arc<DatabaseManager>>,
+}
+
+impl SpendBudget {
+    /// Builds the budget from `[budget]`, resolving token symbols to addresses
+    pub fn new(settings: &BudgetSettings) -> Result<Self> {
+        let resolve = |caps: &std::collections::BTreeMap<String, u64>| {
+            caps.iter()
+                .map(|(token, cap)| Ok((resolve_token(token)?, U256::from(*cap))))
+                .collect::<Result<HashMap<_, _>>>()
+        };
+        Ok(Self {
+            wallet_caps: resolve(&settings.wallet_daily)?,
+            global_caps: resolve(&settings.global_daily)?,
+            ledger: Mutex::new(Ledger::new(today())),
+            db: None,
+        })
+    }
+
+    /// Persists committed spend to the database
+    pub fn with_db(mut self, db: Arc<DatabaseManager>) -> Self {
+        self.db = Some(db);
+        self
+    }
+
+    /// Whether no caps are configured
+    pub fn is_empty(&self) -> bool {
+        self.wallet_caps.is_empty() && self.global_caps.is_empty()
+    }
+
+    /// Loads today's recorded spend from the database
+    pub async fn load_today(&self) -> Result<()> {
+        let Some(db) = &self.db else {
+            return Ok(());
+        };
+        let day = today();
+        let records = db.get_daily_spend(&day.to_string()).await?;
+
+        let mut ledger = self.ledger.lock().unwrap();
+        ledger.roll_to(day);
+        for record in records {
+            let (Ok(wallet), Ok(token), Ok(amount)) = (
+                Address::from_str(&record.wallet_address),
+                Address::from_str(&record.token),
+                U256::from_str(&record.amount),
+            ) else {
+                warn!("Skipping malformed spend ledger row: {:?}", record);
+                continue;
+            };
+            ledger.wallets.insert((wallet, token), amount);
+            *ledger.global.entry(token).or_default() += amount;
+        }
+        Ok(())
+    }
+
+    /// Reserves `amount` of `token` for `wallet` against today's caps
+    ///
+    /// The reservation is refunded when dropped without
+    /// [`SpendReservation::commit`].
+    pub fn reserve(
+        self: &Arc<Self>,
+        wallet: Address,
+        token: Address,
+        amount: U256,
+    ) -> Result<SpendReservation, BudgetExceeded> {
+        self.reserve_on(today(), wallet, token, amount)
+    }
+
+    fn reserve_on(
+        self: &Arc<Self>,
+        day: NaiveDate,
+        wallet: Address,
+        token: Address,
+        amount: U256,
+    ) -> Result<SpendReservation, BudgetExceeded> {
+        let wallet_cap = self.wallet_caps.get(&token).copied();
+        let global_cap = self.global_caps.get(&token).copied();
+        if wallet_cap.is_none() && global_cap.is_none() {
+            return Ok(SpendReservation::untracked());
+        }
+
+        let mut ledger = self.ledger.lock().unwrap();
+        ledger.roll_to(day);
+        let wallet_spent = ledger
+            .wallets
+            .get(&(wallet, token))
+            .copied()
+            .unwrap_or_default();
+        let global_spent = ledger.global.get(&token).copied().unwrap_or_default();
+
+        for (scope, spent, cap) in [
+            (BudgetScope::Wallet, wallet_spent, wallet_cap),
+            (BudgetScope::Global, global_spent, global_cap),
+        ] {
+            if let Some(cap) = cap {
+                if spent.saturating_add(amount) > cap {
+                    return Err(BudgetExceeded {
+                        scope,
+                        token,
+                        spent,
+                        requested: amount,
+                        cap,
+                    });
+                }
+            }
+        }
+
+        ledger
+            .wallets
+            .insert((wallet, token), wallet_spent + amount);
+        ledger.global.insert(token, global_spent + amount);
+        Ok(SpendReservation {
+            budget: Some(self.clone()),
+            day,
+            wallet,
+            token,
+            amount,
+            committed: false,
+        })
+    }
+
+    /// Today's spend of a token by one wallet and by all wallets
+    pub fn spent(&self, wallet: Address, token: Address) -> (U256, U256) {
+        let mut ledger = self.ledger.lock().unwrap();
+        ledger.roll_to(today());
+        (
+            ledger
+                .wallets
+                .get(&(wallet, token))
+                .copied()
+                .unwrap_or_default(),
+            ledger.global.get(&token).copied().unwrap_or_default(),
+        )
+    }
+
+    /// Per-wallet daily cap of a token, if any
+    pub fn wallet_cap(&self, token: Address) -> Option<U256> {
+        self.wallet_caps.get(&token).copied()
+    }
+
+    /// Global daily cap of a token, if any
+    pub fn global_cap(&self, token: Address) -> Option<U256> {
+        self.global_caps.get(&token).copied()
+    }
+
+    /// Every token with a cap, sorted
+    pub fn capped_tokens(&self) -> Vec<Address> {
+        let mut tokens: Vec<Address> = self
+            .wallet_caps
+            .keys()
+            .chain(self.global_caps.keys())
+            .copied()
+            .collect();
+        tokens.sort();
+        tokens.dedup();
+        tokens
+    }
+
+    fn refund(&self, reservation: &SpendReservation) {
+        let mut ledger = self.ledger.lock().unwrap();
+        // Yesterday's spend was already reset
+        if ledger.day != reservation.day {
+            return;
+        }
+        if let Some(spent) = ledger
+            .wallets
+            .get_mut(&(reservation.wallet, reservation.token))
+        {
+            *spent = spent.saturating_sub(reservation.amount);
+        }
+        if let Some(spent) = ledger.global.get_mut(&reservation.token) {
+            *spent = spent.saturating_sub(reservation.amount);
+        }
+    }
+}
+
+/// Spend held against the budget until committed or dropped
+#[derive(Debug)]
+#[must_use = "dropping a reservation refunds it"]
+pub struct SpendReservation {
+    budget: Option<Arc<SpendBudget>>,
+    day: NaiveDate,
+    wallet: Address,
+    token: Address,
+    amount: U256,
+    committed: bool,
+}
+
+impl SpendReservation {
+    /// A reservation for a token without caps
+    pub fn untracked() -> Self {
+        Self {
+            budget: None,
+            day: NaiveDate::MIN,
+            wallet: Address::ZERO,
+            token: Address::ZERO,
+            amount: U256::ZERO,
+            committed: true,
+        }
+    }
+
+    /// Keeps the spend and records the wallet's new total in the database
+    pub async fn commit(mut self) {
+        self.committed = true;
+        let Some(budget) = &self.budget else {
+            return;
+        };
+        let Some(db) = &budget.db else {
+            return;
+        };
+        let (spent, _) = budget.spent(self.wallet, self.token);
+        let record = SpendRecord {
+            day: self.day.to_string(),
+            wallet_address: format!("{:?}", self.wallet),
+            token: format!("{:?}", self.token),
+            amount: spent.to_string(),
+        };
+        if let Err(e) = db.set_daily_spend(&record).await {
+            warn!("Failed to persist spend for {:?}: {:#}", self.wallet, e);
+        }
+    }
+}
+
+impl Drop for SpendReservation {
+    fn drop(&mut self) {
+        if self.committed {
+            return;
+        }
+        if let Some(budget) = &self.budget {
+            budget.refund(self);
+        }
+    }
+}
+
+/// Resolves a config key to a token address: a hex address or a system token symbol
+pub fn resolve_token(token: &str) -> Result<Address> {
+    if token.starts_with("0x") {
+        return Address::from_str(token)
+            .with_context(|| format!("Invalid token address {}", token));
+    }
+    TempoTokens::get_system_tokens()
+        .into_iter()
+        .find(|t| t.symbol.eq_ignore_ascii_case(token))
+        .map(|t| t.address)
+        .with_context(|| format!("Unknown budget token '{}', use its address", token))
+}
+
+/// System token symbol for an address, or the address itself
+pub fn token_label(token: Address) -> String {
+    TempoTokens::get_system_tokens()
+        .into_iter()
+        .find(|t| t.address == token)
+        .map_or_else(|| format!("{:?}", token), |t| t.symbol)
+}
+
+fn today() -> NaiveDate {
+    chrono::Utc::now().date_naive()
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use std::collections::BTreeMap;
+
+    const WALLET_A: Address = Address::repeat_byte(0xaa);
+    const WALLET_B: Address = Address::repeat_byte(0xbb);
+
+    fn budget() -> Arc<SpendBudget> {
+        Arc::new(
+            SpendBudget::new(&BudgetSettings {
+                wallet_daily: BTreeMap::from([("PathUSD".to_string(), 100)]),
+                global_daily: BTreeMap::from([("pathusd".to_string(), 150)]),
+            })
+            .unwrap(),
+        )
+    }
+
+    #[test]
+    fn test_wallet_and_global_caps() {
+        let budget = budget();
+        let path_usd = TempoTokens::get_path_usd_address();
+        let day = NaiveDate::from_ymd_opt(2026, 10, 18).unwrap();
+
+        let first = budget
+            .reserve_on(day, WALLET_A, path_usd, U256::from(80))
+            .unwrap();
+        let exceeded = budget
+            .reserve_on(day, WALLET_A, path_usd, U256::from(30))
+            .unwrap_err();
+        assert_eq!(exceeded.scope, BudgetScope::Wallet);
+        assert_eq!(exceeded.spent, U256::from(80));
+
+        // Wallet B is within its own cap but the fleet would pass 150
+        let exceeded = budget
+            .reserve_on(day, WALLET_B, path_usd, U256::from(80))
+            .unwrap_err();
+        assert_eq!(exceeded.scope, BudgetScope::Global);
+        let result: TaskResult = exceeded.into();
+        assert!(!result.success);
+        assert!(result.message.contains("global daily cap for PathUSD"));
+
+        // Uncapped tokens are never refused
+        let other = Address::repeat_byte(0x01);
+        let untracked = budget.reserve_on(day, WALLET_A, other, U256::MAX).unwrap();
+        drop(untracked);
+        drop(first);
+        assert!(
+            budget
+                .reserve_on(day, WALLET_B, path_usd, U256::from(80))
+                .is_ok()
+        );
+    }
+
+    #[test]
+    fn test_uncommitted_reservations_refund_and_days_reset() {
+        let budget = budget();
+        let path_usd = TempoTokens::get_path_usd_address();
+        let day = NaiveDate::from_ymd_opt(2026, 10, 18).unwrap();
+
+        let failed_send = budget
+            .reserve_on(day, WALLET_A, path_usd, U256::from(100))
+            .unwrap();
+        drop(failed_send);
+        let mut kept = budget
+            .reserve_on(day, WALLET_A, path_usd, U256::from(100))
+            .unwrap();
+        kept.committed = true;
+        drop(kept);
+        assert!(
+            budget
+                .reserve_on(day, WALLET_A, path_usd, U256::from(1))
+                .is_err()
+        );
+
+        let next_day = day.succ_opt().unwrap();
+        assert!(
+            budget
+                .reserve_on(next_day, WALLET_A, path_usd, U256::from(100))
+                .is_ok()
+        );
+        assert!(resolve_token("NotAToken").is_err());
+    }
+}
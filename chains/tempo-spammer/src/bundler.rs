@@ -0,0 +1,338 @@
+//! Bundler - Minimal ERC-4337 bundler client
+//!
+//! Smart accounts do not send transactions themselves. Their owner signs a
+//! [`UserOperation`] and hands it to a bundler, which packs user operations
+//! into one `handleOps` transaction to the EntryPoint. [`BundlerClient`]
+//! covers the calls the `_aa_` tasks need:
+//!
+//! - `eth_supportedEntryPoints`
+//! - `eth_estimateUserOperationGas`
+//! - `eth_sendUserOperation`
+//! - `eth_getUserOperationReceipt`, polled by [`BundlerClient::wait_for_receipt`]
+//!
+//! Operations use the EntryPoint v0.7 JSON form (`factory`/`factoryData`
+//! instead of `initCode`). Paymasters are not supported: the account pays
+//! its own gas from its EntryPoint deposit.
+//!
+//! ```toml
+//! [aa]
+//! bundler_url = "http://localhost:4337"
+//! ```
+//!
+//! The bundler is reached directly, not through the wallet's proxy.
+
+use alloy::signers::Signer;
+use alloy::signers::local::PrivateKeySigner;
+use alloy_primitives::{Address, B256, Bytes, U256, hex, keccak256};
+use alloy_sol_types::SolValue;
+use anyhow::{Context, Result};
+use serde::de::DeserializeOwned;
+use serde::{Deserialize, Serialize};
+use serde_json::{Value, json};
+use std::time::Duration;
+
+/// Signature placeholder for gas estimation, shaped like a real one so
+/// accounts that `ecrecover` it spend about the same gas
+pub const DUMMY_SIGNATURE: [u8; 65] = hex!(
+    "fffffffffffffffffffffffffffffff000000000000000000000000000000000"
+    "7aaaaaaaaaaaaaaaaaaaaaaaaaaaaaaaaaaaaaaaaaaaaaaaaaaaaaaaaaaaaaaa"
+    "1c"
+);
+
+/// An EntryPoint v0.7 user operation in bundler JSON-RPC form
+#[derive(Debug, Clone, Default, PartialEq, Eq, Serialize, Deserialize)]
+#[serde(rename_all = "camelCase")]
+pub struct UserOperation {
+    pub sender: Address,
+    pub nonce: U256,
+    /// Deploys the account with this operation when set
+    #[serde(default, skip_serializing_if = "Option::is_none")]
+    pub factory: Option<Address>,
+    #[serde(default, skip_serializing_if = "Option::is_none")]
+    pub factory_data: Option<Bytes>,
+    pub call_data: Bytes,
+    pub call_gas_limit: U256,
+    pub verification_gas_limit: U256,
+    pub pre_verification_gas: U256,
+    pub max_fee_per_gas: U256,
+    pub max_priority_fee_per_gas: U256,
+    pub signature: Bytes,
+}
+
+impl UserOperation {
+    /// The hash the account owner signs, as `EntryPoint.getUserOpHash` computes it
+    pub fn hash(&self, entry_point: Address, chain_id: u64) -> B256 {
+        let init_code = match (&self.factory, &self.factory_data) {
+            (Some(factory), Some(data)) => [factory.as_slice(), data.as_ref()].concat(),
+            (Some(factory), None) => factory.to_vec(),
+            (None, _) => Vec::new(),
+        };
+        let packed = (
+            self.sender,
+            self.nonce,
+            keccak256(&init_code),
+            keccak256(&self.call_data),
+            pack_u128s(self.verification_gas_limit, self.call_gas_limit),
+            self.pre_verification_gas,
+            pack_u128s(self.max_priority_fee_per_gas, self.max_fee_per_gas),
+            keccak256(b""),
+        )
+            .abi_encode();
+        keccak256((keccak256(packed), entry_point, U256::from(chain_id)).abi_encode())
+    }
+
+    /// Signs the operation for a SimpleAccount owned by `signer`
+    ///
+    /// SimpleAccount checks an EIP-191 personal signature over the hash.
+    pub async fn sign(
+        &mut self,
+        signer: &PrivateKeySigner,
+        entry_point: Address,
+        chain_id: u64,
+    ) -> Result<()> {
+        let hash = self.hash(entry_point, chain_id);
+        let signature = signer
+            .sign_message(hash.as_slice())
+            .await
+            .context("Failed to sign user operation")?;
+        self.signature = Bytes::from(signature.as_bytes().to_vec());
+        Ok(())
+    }
+}
+
+/// Two values of at most 128 bits in one word, `high` first, as v0.7 packs
+/// gas limits and fees
+fn pack_u128s(high: U256, low: U256) -> B256 {
+    B256::from((high << 128) | (low & U256::from(u128::MAX)))
+}
+
+/// Gas figures from `eth_estimateUserOperationGas`
+#[derive(Debug, Clone, Default, PartialEq, Eq, Deserialize)]
+#[serde(rename_all = "camelCase")]
+pub struct UserOperationGas {
+    pub pre_verification_gas: U256,
+    pub verification_gas_limit: U256,
+    pub call_gas_limit: U256,
+}
+
+/// `eth_getUserOperationReceipt` result
+#[derive(Debug, Clone, PartialEq, Eq, Deserialize)]
+#[serde(rename_all = "camelCase")]
+pub struct UserOperationReceipt {
+    pub user_op_hash: B256,
+    /// Whether the account's call succeeded; the bundle itself was included
+    pub success: bool,
+    pub actual_gas_used: U256,
+    pub actual_gas_cost: U256,
+    #[serde(default)]
+    pub reason: Option<String>,
+    pub receipt: BundleTransaction,
+}
+
+/// The `handleOps` transaction that included a user operation
+#[derive(Debug, Clone, PartialEq, Eq, Deserialize)]
+#[serde(rename_all = "camelCase")]
+pub struct BundleTransaction {
+    pub transaction_hash: B256,
+    #[serde(default)]
+    pub block_number: Option<U256>,
+}
+
+/// JSON-RPC client for one bundler and EntryPoint
+#[derive(Debug, Clone)]
+pub struct BundlerClient {
+    url: String,
+    entry_point: Address,
+    http: reqwest::Client,
+}
+
+impl BundlerClient {
+    pub fn new(url: &str, entry_point: Address) -> Self {
+        Self {
+            url: url.to_string(),
+            entry_point,
+            http: reqwest::Client::new(),
+        }
+    }
+
+    pub fn entry_point(&self) -> Address {
+        self.entry_point
+    }
+
+    async fn request<T: DeserializeOwned>(&self, method: &str, params: Value) -> Result<T> {
+        let body = json!({"jsonrpc": "2.0", "id": 1, "method": method, "params": params});
+        let response: Value = self
+            .http
+            .post(&self.url)
+            .json(&body)
+            .send()
+            .await
+            .with_context(|| format!("Bundler unreachable for {}", method))?
+            .json()
+            .await
+            .with_context(|| format!("Bundler sent no JSON for {}", method))?;
+
+        if let Some(error) = response.get("error") {
+            anyhow::bail!(
+                "Bundler rejected {}: {} (code {})",
+                method,
+                error
+                    .get("message")
+                    .and_then(Value::as_str)
+                    .unwrap_or("no message"),
+                error.get("code").unwrap_or(&Value::Null)
+            );
+        }
+        serde_json::from_value(response.get("result").cloned().unwrap_or(Value::Null))
+            .with_context(|| format!("Unexpected {} result", method))
+    }
+
+    /// EntryPoints the bundler serves
+    pub async fn supported_entry_points(&self) -> Result<Vec<Address>> {
+        self.request("eth_supportedEntryPoints", json!([])).await
+    }
+
+    /// Gas limits for `op`, which should carry [`DUMMY_SIGNATURE`]
+    pub async fn estimate_user_operation_gas(
+        &self,
+        op: &UserOperation,
+    ) -> Result<UserOperationGas> {
+        self.request(
+            "eth_estimateUserOperationGas",
+            json!([op, self.entry_point]),
+        )
+        .await
+    }
+
+    /// Submits a signed operation and returns its hash
+    pub async fn send_user_operation(&self, op: &UserOperation) -> Result<B256> {
+        self.request("eth_sendUserOperation", json!([op, self.entry_point]))
+            .await
+    }
+
+    /// Receipt of an operation, `None` while it is not included
+    pub async fn get_user_operation_receipt(
+        &self,
+        hash: B256,
+    ) -> Result<Option<UserOperationReceipt>> {
+        self.request("eth_getUserOperationReceipt", json!([hash]))
+            .await
+    }
+
+    /// Polls for an operation's receipt until `timeout`
+    ///
+    /// Returns `None` when the operation was not included in time. Lookup
+    /// errors are retried like a missing receipt.
+    pub async fn wait_for_receipt(
+        &self,
+        hash: B256,
+        timeout: Duration,
+        poll: Duration,
+    ) -> Option<UserOperationReceipt> {
+        let deadline = tokio::time::Instant::now() + timeout;
+        loop {
+            match self.get_user_operation_receipt(hash).await {
+                Ok(Some(receipt)) => return Some(receipt),
+                Ok(None) => {}
+                Err(e) => tracing::debug!("User operation receipt lookup failed: {:#}", e),
+            }
+            if tokio::time::Instant::now() + poll > deadline {
+                return None;
+            }
+            tokio::time::sleep(poll).await;
+        }
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use crate::testing::TEST_PRIVATE_KEY;
+
+    fn operation() -> UserOperation {
+        UserOperation {
+            sender: Address::repeat_byte(0x11),
+            nonce: U256::from(3),
+            call_data: Bytes::from(vec![0xb6, 0x1d, 0x27, 0xf6]),
+            call_gas_limit: U256::from(80_000),
+            verification_gas_limit: U256::from(120_000),
+            pre_verification_gas: U256::from(50_000),
+            max_fee_per_gas: U256::from(30_000_000_000u64),
+            max_priority_fee_per_gas: U256::from(1_500_000_000u64),
+            ..Default::default()
+        }
+    }
+
+    #[test]
+    fn test_serializes_v07_rpc_form() {
+        let json = serde_json::to_value(operation()).unwrap();
+        assert_eq!(json["callGasLimit"], "0x13880");
+        assert_eq!(json["nonce"], "0x3");
+        assert!(json.get("factory").is_none());
+
+        let deploying = UserOperation {
+            factory: Some(Address::repeat_byte(0x22)),
+            factory_data: Some(Bytes::from(vec![1, 2])),
+            ..operation()
+        };
+        let json = serde_json::to_value(&deploying).unwrap();
+        assert_eq!(json["factoryData"], "0x0102");
+        let back: UserOperation = serde_json::from_value(json).unwrap();
+        assert_eq!(back, deploying);
+    }
+
+    #[test]
+    fn test_hash_covers_chain_entry_point_and_init_code() {
+        let op = operation();
+        let entry_point = Address::repeat_byte(0xee);
+        let hash = op.hash(entry_point, 42431);
+        assert_ne!(hash, op.hash(entry_point, 1));
+        assert_ne!(hash, op.hash(Address::repeat_byte(0xef), 42431));
+
+        let deploying = UserOperation {
+            factory: Some(Address::repeat_byte(0x22)),
+            ..operation()
+        };
+        assert_ne!(hash, deploying.hash(entry_point, 42431));
+        // The signature is not part of what gets signed
+        let signed = UserOperation {
+            signature: Bytes::from(DUMMY_SIGNATURE.to_vec()),
+            ..operation()
+        };
+        assert_eq!(hash, signed.hash(entry_point, 42431));
+    }
+
+    #[tokio::test]
+    async fn test_signature_recovers_owner() {
+        let signer: PrivateKeySigner = TEST_PRIVATE_KEY.parse().unwrap();
+        let entry_point = Address::repeat_byte(0xee);
+        let mut op = operation();
+        op.sign(&signer, entry_point, 42431).await.unwrap();
+
+        assert_eq!(op.signature.len(), 65);
+        let signature = alloy_primitives::Signature::try_from(op.signature.as_ref()).unwrap();
+        let recovered = signature
+            .recover_address_from_msg(op.hash(entry_point, 42431).as_slice())
+            .unwrap();
+        assert_eq!(recovered, signer.address());
+    }
+
+    #[test]
+    fn test_receipt_parses() {
+        let receipt: UserOperationReceipt = serde_json::from_value(json!({
+            "userOpHash": B256::repeat_byte(1),
+            "entryPoint": Address::repeat_byte(0xee),
+            "sender": Address::repeat_byte(0x11),
+            "success": false,
+            "actualGasUsed": "0x1d4c0",
+            "actualGasCost": "0x6fc23ac00",
+            "reason": "0x08c379a0",
+            "logs": [],
+            "receipt": {"transactionHash": B256::repeat_byte(2), "blockNumber": "0x10"},
+        }))
+        .unwrap();
+        assert!(!receipt.success);
+        assert_eq!(receipt.actual_gas_used, U256::from(120_000));
+        assert_eq!(receipt.receipt.block_number, Some(U256::from(16)));
+    }
+}
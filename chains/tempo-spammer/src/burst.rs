@@ -0,0 +1,666 @@
+//! Burst Mode - Time-synchronized transaction spikes across instances
+//!
+//! For testing how the chain handles synchronized load, several spammer
+//! instances can fire the same burst at the same moment. Instances started
+//! with the same round id agree on the start through the shared database:
+//! the first one to join stores a start `lead_secs` in the future together
+//! with its `[burst]` rate and duration, and every later instance adopts
+//! that round instead of its own settings.
+//!
+//! ```bash
+//! # on every host, within lead_secs of each other
+//! tempo-spammer burst --round spike-1
+//! tempo-spammer db bursts spike-1
+//! ```
+//!
+//! During the window each instance sends TIP-20 self-transfers of one base
+//! unit at the round's rate, spread over its wallets, without waiting for
+//! receipts. Afterwards it polls receipts for up to `settle_secs` and stores
+//! its achieved send rate and the inclusion outcome in `burst_results`.
+//!
+//! With `[burst] presign` (or `--presign`) set, each instance instead leases
+//! every wallet it can, signs `presign` transfers per wallet ahead of the
+//! start and parks one task per wallet on a barrier. The barrier opens at
+//! the round's start and every wallet sends its whole batch at once, so the
+//! spike is as sharp as the RPC allows; the round's rate and duration are
+//! not used then.
+//!
+//! Either way the landed transactions are also broken down per block, with
+//! how many of them validators proposed in subblocks, into `burst_blocks`,
+//! and each one's send, inclusion and confirmation times go into
+//! `latency_samples` under `burst:<round>` (see [`crate::latency`]).
+//!
+//! Instances rely on their system clocks for the start, so hosts should be
+//! NTP-synchronized.
+
+use crate::ClientPool;
+use crate::TempoClient;
+use crate::client_pool::ClientLease;
+use crate::config::{BurstSettings, TempoSpammerConfig};
+use crate::latency::{self, Landed};
+use crate::network;
+use crate::offline::{self, SignOptions, SignedTx};
+use crate::tx_builder::FeeProfile;
+use alloy::network::ReceiptResponse;
+use alloy::rpc::types::TransactionRequest;
+use alloy_primitives::{Address, B256, U256};
+use alloy_sol_types::{SolCall, sol};
+use anyhow::{Context, Result, bail};
+use core_logic::database::{BurstBlock, BurstResult, BurstRound, DatabaseManager};
+use std::collections::{BTreeMap, HashSet};
+use std::sync::Arc;
+use std::time::{Duration, Instant, SystemTime, UNIX_EPOCH};
+use tokio::sync::Barrier;
+use tokio::task::JoinSet;
+use tokio::time::MissedTickBehavior;
+use tracing::{info, warn};
+
+/// Delay between receipt polling passes
+const RECEIPT_POLL: Duration = Duration::from_secs(1);
+
+/// Gas limit of the pre-signed self-transfers
+const PRESIGN_GAS_LIMIT: u64 = 100_000;
+
+sol! {
+    interface ITIP20 {
+        function transfer(address to, uint256 amount) returns (bool);
+    }
+}
+
+/// What happened to one accepted transaction
+#[derive(Debug, Clone, Copy, PartialEq, Eq)]
+pub enum Inclusion {
+    Included { block: u64, latency_ms: u64 },
+    Reverted { block: u64, latency_ms: u64 },
+    Pending,
+}
+
+/// Name this instance reports under: `$HOSTNAME-<pid>`
+pub fn default_instance_id() -> String {
+    let host = std::env::var("HOSTNAME").unwrap_or_else(|_| "instance".to_string());
+    format!("{}-{}", host, std::process::id())
+}
+
+/// Round this instance proposes when it is the first to join
+pub fn proposal(round_id: &str, settings: &BurstSettings, now_ms: i64) -> BurstRound {
+    BurstRound {
+        round_id: round_id.to_string(),
+        start_at_ms: now_ms + (settings.lead_secs * 1000) as i64,
+        duration_ms: (settings.duration_secs * 1000) as i64,
+        target_rate: settings.rate,
+    }
+}
+
+/// Aggregates the outcome of one instance's burst
+pub fn summarize(
+    round_id: &str,
+    instance_id: &str,
+    send_errors: u64,
+    window: Duration,
+    outcomes: &[Inclusion],
+) -> BurstResult {
+    let mut result = BurstResult {
+        round_id: round_id.to_string(),
+        instance_id: instance_id.to_string(),
+        sent: outcomes.len() as i64,
+        send_errors: send_errors as i64,
+        achieved_rate: outcomes.len() as f64 / window.as_secs_f64().max(0.001),
+        included: 0,
+        reverted: 0,
+        pending: 0,
+        first_block: None,
+        last_block: None,
+        avg_inclusion_ms: 0.0,
+        p50_inclusion_ms: 0.0,
+        p90_inclusion_ms: 0.0,
+        p99_inclusion_ms: 0.0,
+        max_inclusion_ms: 0.0,
+    };
+
+    let mut latencies = Vec::with_capacity(outcomes.len());
+    for outcome in outcomes {
+        let (block, latency_ms) = match *outcome {
+            Inclusion::Included { block, latency_ms } => {
+                result.included += 1;
+                (block, latency_ms)
+            }
+            Inclusion::Reverted { block, latency_ms } => {
+                result.reverted += 1;
+                (block, latency_ms)
+            }
+            Inclusion::Pending => {
+                result.pending += 1;
+                continue;
+            }
+        };
+        latencies.push(latency_ms);
+        let block = block as i64;
+        result.first_block = Some(result.first_block.map_or(block, |b| b.min(block)));
+        result.last_block = Some(result.last_block.map_or(block, |b| b.max(block)));
+    }
+
+    if !latencies.is_empty() {
+        latencies.sort_unstable();
+        result.avg_inclusion_ms = latencies.iter().sum::<u64>() as f64 / latencies.len() as f64;
+        result.p50_inclusion_ms = percentile(&latencies, 50.0);
+        result.p90_inclusion_ms = percentile(&latencies, 90.0);
+        result.p99_inclusion_ms = percentile(&latencies, 99.0);
+        result.max_inclusion_ms = latencies[latencies.len() - 1] as f64;
+    }
+    result
+}
+
+/// Groups the landed transactions by block
+///
+/// `in_subblocks` holds the hashes of transactions validators proposed in
+/// subblocks; blocks come out in ascending order.
+pub fn breakdown(
+    round_id: &str,
+    instance_id: &str,
+    landed: &[(B256, Inclusion)],
+    in_subblocks: &HashSet<B256>,
+) -> Vec<BurstBlock> {
+    let mut by_block: BTreeMap<u64, (Vec<u64>, i64)> = BTreeMap::new();
+    for (hash, outcome) in landed {
+        let (Inclusion::Included { block, latency_ms } | Inclusion::Reverted { block, latency_ms }) =
+            *outcome
+        else {
+            continue;
+        };
+        let (latencies, subblock_count) = by_block.entry(block).or_default();
+        latencies.push(latency_ms);
+        if in_subblocks.contains(hash) {
+            *subblock_count += 1;
+        }
+    }
+
+    by_block
+        .into_iter()
+        .map(|(block, (mut latencies, in_subblocks))| {
+            latencies.sort_unstable();
+            BurstBlock {
+                round_id: round_id.to_string(),
+                instance_id: instance_id.to_string(),
+                block_number: block as i64,
+                transactions: latencies.len() as i64,
+                in_subblocks,
+                min_inclusion_ms: latencies[0] as f64,
+                p50_inclusion_ms: percentile(&latencies, 50.0),
+                max_inclusion_ms: latencies[latencies.len() - 1] as f64,
+            }
+        })
+        .collect()
+}
+
+/// Nearest-rank percentile of ascending, non-empty `sorted`
+fn percentile(sorted: &[u64], p: f64) -> f64 {
+    let rank = (p / 100.0 * sorted.len() as f64).ceil() as usize;
+    sorted[rank.clamp(1, sorted.len()) - 1] as f64
+}
+
+/// An accepted transaction awaiting its receipt
+struct Sent {
+    client: TempoClient,
+    hash: B256,
+    sent_at: Instant,
+    /// The same moment in unix milliseconds
+    sent_at_ms: i64,
+}
+
+/// Joins `round_id`, sends this instance's share of the burst and records the outcome
+///
+/// `presign` transactions per wallet are signed ahead and released together
+/// at the start; with 0 the round's rate and duration apply.
+pub async fn run_burst(
+    client_pool: Arc<ClientPool>,
+    config: &TempoSpammerConfig,
+    db: Arc<DatabaseManager>,
+    round_id: &str,
+    instance_id: &str,
+    presign: u32,
+) -> Result<BurstResult> {
+    let round = db
+        .join_burst_round(&proposal(round_id, &config.burst, unix_ms()))
+        .await?;
+    let end_ms = round.start_at_ms + round.duration_ms;
+
+    let now_ms = unix_ms();
+    if now_ms >= end_ms {
+        bail!("Burst round {} is already over", round.round_id);
+    }
+    let (sent, send_errors, window) = if presign > 0 {
+        run_presigned(&client_pool, config, &round, presign).await?
+    } else {
+        run_paced(&client_pool, config, &round).await
+    };
+    info!(
+        "Burst {}: {} accepted, {} failed, waiting up to {}s for receipts",
+        round.round_id,
+        sent.len(),
+        send_errors,
+        config.burst.settle_secs
+    );
+
+    let outcomes = await_receipts(&sent, Duration::from_secs(config.burst.settle_secs)).await;
+    let result = summarize(&round.round_id, instance_id, send_errors, window, &outcomes);
+    db.record_burst_result(&result)
+        .await
+        .context("Failed to store burst result")?;
+
+    let samples = match sent.first() {
+        Some(tx) if config.burst.subblocks => {
+            let source = format!("burst:{}", round.round_id);
+            latency::collect(&tx.client.provider, &source, &landed(&sent, &outcomes)).await
+        }
+        _ => Vec::new(),
+    };
+    db.record_latency_samples(&samples)
+        .await
+        .context("Failed to store latency samples")?;
+
+    let in_subblocks: HashSet<B256> = samples
+        .iter()
+        .filter(|sample| sample.subblock.is_some())
+        .filter_map(|sample| sample.tx_hash.parse().ok())
+        .collect();
+    let outcomes: Vec<(B256, Inclusion)> = sent
+        .iter()
+        .zip(&outcomes)
+        .map(|(tx, outcome)| (tx.hash, *outcome))
+        .collect();
+    let blocks = breakdown(&round.round_id, instance_id, &outcomes, &in_subblocks);
+    db.record_burst_blocks(&round.round_id, instance_id, &blocks)
+        .await
+        .context("Failed to store burst blocks")?;
+    Ok(result)
+}
+
+/// The accepted transactions whose receipts were seen, with wall-clock times
+fn landed(sent: &[Sent], outcomes: &[Inclusion]) -> Vec<Landed> {
+    sent.iter()
+        .zip(outcomes)
+        .filter_map(|(tx, outcome)| {
+            let (Inclusion::Included { block, latency_ms }
+            | Inclusion::Reverted { block, latency_ms }) = *outcome
+            else {
+                return None;
+            };
+            Some(Landed {
+                hash: tx.hash,
+                wallet: tx.client.address(),
+                block,
+                sent_at_ms: tx.sent_at_ms,
+                confirmed_at_ms: tx.sent_at_ms + latency_ms as i64,
+            })
+        })
+        .collect()
+}
+
+/// Sends at the round's rate over its window, returning the accepted
+/// transactions, the failed sends and the window
+async fn run_paced(
+    client_pool: &Arc<ClientPool>,
+    config: &TempoSpammerConfig,
+    round: &BurstRound,
+) -> (Vec<Sent>, u64, Duration) {
+    let end_ms = round.start_at_ms + round.duration_ms;
+    let now_ms = unix_ms();
+    if now_ms > round.start_at_ms {
+        warn!(
+            "Joined burst {} {}ms late, sending for the rest of the window",
+            round.round_id,
+            now_ms - round.start_at_ms
+        );
+    } else {
+        info!(
+            "Burst {}: {} tx/s for {}ms, starting in {}ms",
+            round.round_id,
+            round.target_rate,
+            round.duration_ms,
+            round.start_at_ms - now_ms
+        );
+        tokio::time::sleep(Duration::from_millis((round.start_at_ms - now_ms) as u64)).await;
+    }
+
+    let window_start = Instant::now();
+    let window = Duration::from_millis((end_ms - unix_ms()).max(0) as u64);
+    let mut ticker = tokio::time::interval(Duration::from_secs_f64(1.0 / round.target_rate));
+    ticker.set_missed_tick_behavior(MissedTickBehavior::Burst);
+
+    let mut sends = JoinSet::new();
+    let mut send_errors = 0u64;
+    let mut skipped = 0u64;
+    while window_start.elapsed() < window {
+        ticker.tick().await;
+        let Some(lease) = client_pool.try_acquire_client().await else {
+            // Every wallet is mid-send or out of permits; nothing was sent,
+            // so the slot is skipped rather than counted as a failed send
+            skipped += 1;
+            continue;
+        };
+        let rpc_url = config.rpc_url.clone();
+        sends.spawn(async move {
+            let (sent_at, sent_at_ms) = (Instant::now(), unix_ms());
+            let hash = send_self_transfer(&lease.client, &rpc_url).await?;
+            Ok::<_, anyhow::Error>(Sent {
+                client: lease.client.clone(),
+                hash,
+                sent_at,
+                sent_at_ms,
+            })
+        });
+    }
+
+    let mut sent = Vec::new();
+    while let Some(joined) = sends.join_next().await {
+        match joined {
+            Ok(Ok(tx)) => sent.push(tx),
+            Ok(Err(e)) => {
+                send_errors += 1;
+                tracing::debug!("Burst send failed: {:#}", e);
+            }
+            Err(e) => {
+                send_errors += 1;
+                warn!("Burst send panicked: {:?}", e);
+            }
+        }
+    }
+    if skipped > 0 {
+        info!(
+            "Burst {}: skipped {} slots with no wallet free",
+            round.round_id, skipped
+        );
+    }
+    (sent, send_errors, window)
+}
+
+/// Signs `presign` transfers for every wallet the pool hands out, holds
+/// the wallets on a barrier until the round's start and then lets each
+/// send its batch, returning the accepted transactions, the failed sends
+/// and the time from release to the last send
+async fn run_presigned(
+    client_pool: &Arc<ClientPool>,
+    config: &TempoSpammerConfig,
+    round: &BurstRound,
+    presign: u32,
+) -> Result<(Vec<Sent>, u64, Duration)> {
+    let mut leases = Vec::new();
+    while let Some(lease) = client_pool.try_acquire_client().await {
+        leases.push(lease);
+    }
+    if leases.is_empty() {
+        bail!("No wallet available for burst {}", round.round_id);
+    }
+
+    let options = SignOptions {
+        chain_id: config.chain_id,
+        count: presign as usize,
+        start_nonce: 0,
+        token: network::contracts().path_usd,
+        to: None,
+        amount: U256::from(1),
+        gas_limit: PRESIGN_GAS_LIMIT,
+        fee_profile: FeeProfile::Normal,
+    };
+    let batches = futures::future::try_join_all(
+        leases
+            .iter()
+            .map(|lease| presign_wallet(lease, &config.rpc_url, &options)),
+    )
+    .await?;
+    info!(
+        "Burst {}: {} wallets holding {} pre-signed transactions each",
+        round.round_id,
+        leases.len(),
+        presign
+    );
+
+    let barrier = Arc::new(Barrier::new(leases.len() + 1));
+    let mut wallets = JoinSet::new();
+    for (lease, batch) in leases.into_iter().zip(batches) {
+        let barrier = barrier.clone();
+        wallets.spawn(async move {
+            barrier.wait().await;
+            send_batch(lease, batch).await
+        });
+    }
+
+    let wait_ms = round.start_at_ms - unix_ms();
+    if wait_ms > 0 {
+        tokio::time::sleep(Duration::from_millis(wait_ms as u64)).await;
+    } else {
+        warn!(
+            "Burst {} started {}ms before signing finished, releasing now",
+            round.round_id, -wait_ms
+        );
+    }
+    barrier.wait().await;
+    let released = Instant::now();
+
+    let mut sent = Vec::new();
+    let mut send_errors = 0u64;
+    while let Some(joined) = wallets.join_next().await {
+        match joined {
+            Ok((accepted, failed)) => {
+                sent.extend(accepted);
+                send_errors += failed;
+            }
+            Err(e) => {
+                send_errors += presign as u64;
+                warn!("Burst wallet panicked: {:?}", e);
+            }
+        }
+    }
+    Ok((sent, send_errors, released.elapsed()))
+}
+
+/// Signs the wallet's batch starting at its pending nonce
+async fn presign_wallet(
+    lease: &ClientLease,
+    rpc_url: &str,
+    options: &SignOptions,
+) -> Result<Vec<SignedTx>> {
+    let options = SignOptions {
+        start_nonce: lease.client.get_pending_nonce(rpc_url).await?,
+        ..options.clone()
+    };
+    offline::sign_batch(std::slice::from_ref(&lease.client.signer), &options, &[]).await
+}
+
+/// Sends a wallet's batch in nonce order; after a rejected send the later
+/// nonces could not land, so the rest of the batch counts as failed
+async fn send_batch(lease: ClientLease, batch: Vec<SignedTx>) -> (Vec<Sent>, u64) {
+    let mut sent = Vec::with_capacity(batch.len());
+    for (index, tx) in batch.iter().enumerate() {
+        let (sent_at, sent_at_ms) = (Instant::now(), unix_ms());
+        if let Err(e) = lease.client.provider.send_raw_transaction(&tx.raw).await {
+            tracing::debug!("Burst send from {} failed: {:#}", tx.wallet, e);
+            return (sent, (batch.len() - index) as u64);
+        }
+        sent.push(Sent {
+            client: lease.client.clone(),
+            hash: tx.hash,
+            sent_at,
+            sent_at_ms,
+        });
+    }
+    (sent, 0)
+}
+
+/// Sends one base unit of PathUSD, held by every funded wallet, to itself
+async fn send_self_transfer(client: &TempoClient, rpc_url: &str) -> Result<B256> {
+    let address = client.address();
+    let data = ITIP20::transferCall {
+        to: address,
+        amount: U256::from(1),
+    }
+    .abi_encode();
+
+    let nonce = client.get_pending_nonce(rpc_url).await?;
+    let tx = TransactionRequest::default()
+        .to(network::contracts().path_usd)
+        .input(data.into())
+        .from(address)
+        .nonce(nonce);
+    let pending = client.provider.send_transaction(tx).await?;
+    Ok(*pending.tx_hash())
+}
+
+/// Polls receipts until every transaction landed or `settle` elapsed
+async fn await_receipts(sent: &[Sent], settle: Duration) -> Vec<Inclusion> {
+    let deadline = Instant::now() + settle;
+    let mut outcomes = vec![Inclusion::Pending; sent.len()];
+
+    loop {
+        for (tx, outcome) in sent.iter().zip(outcomes.iter_mut()) {
+            if *outcome != Inclusion::Pending {
+                continue;
+            }
+            let Ok(Some(receipt)) = tx.client.provider.get_transaction_receipt(tx.hash).await
+            else {
+                continue;
+            };
+            let block = receipt.block_number().unwrap_or_default();
+            let latency_ms = tx.sent_at.elapsed().as_millis() as u64;
+            *outcome = if receipt.status() {
+                Inclusion::Included { block, latency_ms }
+            } else {
+                Inclusion::Reverted { block, latency_ms }
+            };
+        }
+
+        if outcomes.iter().all(|o| *o != Inclusion::Pending) || Instant::now() >= deadline {
+            return outcomes;
+        }
+        tokio::time::sleep(RECEIPT_POLL).await;
+    }
+}
+
+fn unix_ms() -> i64 {
+    SystemTime::now()
+        .duration_since(UNIX_EPOCH)
+        .map(|d| d.as_millis() as i64)
+        .unwrap_or_default()
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    #[test]
+    fn test_proposal_starts_after_lead() {
+        let settings = BurstSettings {
+            rate: 50.0,
+            duration_secs: 5,
+            lead_secs: 20,
+            ..Default::default()
+        };
+        let round = proposal("spike-1", &settings, 1_000);
+        assert_eq!(round.start_at_ms, 21_000);
+        assert_eq!(round.duration_ms, 5_000);
+        assert_eq!(round.target_rate, 50.0);
+    }
+
+    #[test]
+    fn test_summarize_outcomes() {
+        let outcomes = [
+            Inclusion::Included {
+                block: 12,
+                latency_ms: 900,
+            },
+            Inclusion::Reverted {
+                block: 10,
+                latency_ms: 300,
+            },
+            Inclusion::Pending,
+            Inclusion::Included {
+                block: 11,
+                latency_ms: 600,
+            },
+        ];
+        let result = summarize("spike-1", "host-a", 2, Duration::from_secs(2), &outcomes);
+        assert_eq!(result.sent, 4);
+        assert_eq!(result.send_errors, 2);
+        assert_eq!(result.achieved_rate, 2.0);
+        assert_eq!(
+            (result.included, result.reverted, result.pending),
+            (2, 1, 1)
+        );
+        assert_eq!(
+            (result.first_block, result.last_block),
+            (Some(10), Some(12))
+        );
+        assert_eq!(result.avg_inclusion_ms, 600.0);
+        assert_eq!(result.p50_inclusion_ms, 600.0);
+        assert_eq!(result.p90_inclusion_ms, 900.0);
+        assert_eq!(result.max_inclusion_ms, 900.0);
+        assert_eq!(result.inclusion_rate(), 75.0);
+
+        let empty = summarize("spike-1", "host-b", 0, Duration::ZERO, &[]);
+        assert_eq!(empty.first_block, None);
+        assert_eq!(empty.avg_inclusion_ms, 0.0);
+        assert_eq!(empty.p99_inclusion_ms, 0.0);
+    }
+
+    #[test]
+    fn test_percentile_nearest_rank() {
+        let sorted: Vec<u64> = (1..=100).collect();
+        assert_eq!(percentile(&sorted, 50.0), 50.0);
+        assert_eq!(percentile(&sorted, 99.0), 99.0);
+        assert_eq!(percentile(&sorted, 0.0), 1.0);
+        assert_eq!(percentile(&[7], 90.0), 7.0);
+    }
+
+    #[test]
+    fn test_breakdown_per_block() {
+        let hash = |n: u8| B256::repeat_byte(n);
+        let landed = [
+            (
+                hash(1),
+                Inclusion::Included {
+                    block: 11,
+                    latency_ms: 800,
+                },
+            ),
+            (hash(2), Inclusion::Pending),
+            (
+                hash(3),
+                Inclusion::Included {
+                    block: 10,
+                    latency_ms: 400,
+                },
+            ),
+            (
+                hash(4),
+                Inclusion::Reverted {
+                    block: 10,
+                    latency_ms: 500,
+                },
+            ),
+            (
+                hash(5),
+                Inclusion::Included {
+                    block: 10,
+                    latency_ms: 450,
+                },
+            ),
+        ];
+        let in_subblocks = HashSet::from([hash(3), hash(4), hash(2)]);
+
+        let blocks = breakdown("spike-1", "host-a", &landed, &in_subblocks);
+        assert_eq!(blocks.len(), 2);
+        assert_eq!(blocks[0].block_number, 10);
+        assert_eq!((blocks[0].transactions, blocks[0].in_subblocks), (3, 2));
+        assert_eq!(
+            (
+                blocks[0].min_inclusion_ms,
+                blocks[0].p50_inclusion_ms,
+                blocks[0].max_inclusion_ms
+            ),
+            (400.0, 450.0, 500.0)
+        );
+        assert_eq!(blocks[1].block_number, 11);
+        assert_eq!((blocks[1].transactions, blocks[1].in_subblocks), (1, 0));
+        assert_eq!(blocks[1].instance_id, "host-a");
+    }
+}
@@ -0,0 +1,267 @@
+//! Capabilities - What the node behind `rpc_url` supports
+//!
+//! A `chain_id` that does not match the node's has every transaction of a run
+//! signed for the wrong chain, and a node without Tempo's protocol features
+//! rejects the 0x76 transactions half the tasks send. Before the first worker
+//! starts the runtime asks the node:
+//!
+//! - `eth_chainId`, compared with `chain_id`
+//! - whether the latest header carries Tempo's fields
+//!   (`mainBlockGeneralGasLimit`), which only nodes that accept Tempo (0x76)
+//!   transactions serve
+//! - whether the nonce precompile answers `getNonce`, i.e. 2D nonces work
+//!
+//! ```toml
+//! [capabilities]
+//! on_chain_id_mismatch = "fail"   # or "adopt" the node's chain id
+//! on_missing = "adjust"           # or "fail" without Tempo transactions or 2D nonces
+//! ```
+//!
+//! The result is kept on the [`ClientPool`](crate::ClientPool) and handed to
+//! every [`TaskContext`](crate::tasks::TaskContext), where tasks read it with
+//! [`capabilities`](crate::tasks::TaskContext::capabilities);
+//! [`send_calls`](crate::tasks::TaskContext::send_calls) fails fast on a node
+//! without Tempo transactions. When the probe cannot reach the node, startup
+//! goes on and every feature is assumed.
+
+use crate::client::{INonce, NONCE_PRECOMPILE};
+use crate::config::{ChainIdMismatch, MissingCapability, TempoSpammerConfig};
+use alloy::eips::BlockNumberOrTag;
+use alloy::providers::{Provider, ProviderBuilder};
+use alloy::rpc::types::TransactionRequest;
+use alloy_primitives::{Address, Bytes, U256};
+use alloy_sol_types::SolCall;
+use anyhow::{Context, Result};
+use std::fmt;
+use std::time::Duration;
+use tracing::{info, warn};
+
+/// Header field only Tempo nodes serve
+const TEMPO_HEADER_FIELD: &str = "mainBlockGeneralGasLimit";
+
+/// Chain id and Tempo features of a node
+#[derive(Debug, Clone, Copy, PartialEq, Eq)]
+pub struct ChainCapabilities {
+    /// Chain id the node reports
+    pub chain_id: u64,
+    /// Tempo (0x76) transactions: batched calls, fee tokens, fee payers
+    pub tempo_transactions: bool,
+    /// Nonce keys other than 0 through the nonce precompile
+    pub nonce_2d: bool,
+}
+
+impl ChainCapabilities {
+    /// Every feature, for runs whose node was not probed
+    pub fn assumed(chain_id: u64) -> Self {
+        Self {
+            chain_id,
+            tempo_transactions: true,
+            nonce_2d: true,
+        }
+    }
+
+    /// Names of the features the node lacks
+    pub fn missing(&self) -> Vec<&'static str> {
+        let mut missing = Vec::new();
+        if !self.tempo_transactions {
+            missing.push("Tempo transactions");
+        }
+        if !self.nonce_2d {
+            missing.push("2D nonces");
+        }
+        missing
+    }
+}
+
+impl fmt::Display for ChainCapabilities {
+    fn fmt(&self, f: &mut fmt::Formatter<'_>) -> fmt::Result {
+        let flag = |supported: bool| if supported { "yes" } else { "no" };
+        write!(
+            f,
+            "chain {}, Tempo transactions: {}, 2D nonces: {}",
+            self.chain_id,
+            flag(self.tempo_transactions),
+            flag(self.nonce_2d)
+        )
+    }
+}
+
+/// Asks the node for its chain id and features
+///
+/// # Errors
+///
+/// Fails when `eth_chainId` fails; a failing feature probe counts as the
+/// feature being missing.
+pub async fn probe<P: Provider + ?Sized>(provider: &P) -> Result<ChainCapabilities> {
+    let chain_id = provider
+        .get_chain_id()
+        .await
+        .context("Failed to read eth_chainId")?;
+    let (tempo_transactions, nonce_2d) =
+        tokio::join!(probe_tempo_header(provider), probe_nonce_2d(provider));
+    Ok(ChainCapabilities {
+        chain_id,
+        tempo_transactions,
+        nonce_2d,
+    })
+}
+
+async fn probe_tempo_header<P: Provider + ?Sized>(provider: &P) -> bool {
+    let block: Result<Option<serde_json::Value>, _> = provider
+        .client()
+        .request("eth_getBlockByNumber", (BlockNumberOrTag::Latest, false))
+        .await;
+    match block {
+        Ok(block) => block.is_some_and(|block| is_tempo_header(&block)),
+        Err(e) => {
+            warn!("Tempo header probe failed: {}", e);
+            false
+        }
+    }
+}
+
+async fn probe_nonce_2d<P: Provider + ?Sized>(provider: &P) -> bool {
+    // Key 0 is the protocol nonce, which the precompile refuses
+    let call = INonce::getNonceCall {
+        account: Address::ZERO,
+        nonceKey: U256::from(1),
+    };
+    let request = TransactionRequest::default()
+        .to(NONCE_PRECOMPILE)
+        .input(Bytes::from(call.abi_encode()).into());
+    match provider.call(request).await {
+        // Without the precompile the call succeeds with no data
+        Ok(data) => INonce::getNonceCall::abi_decode_returns(&data).is_ok(),
+        Err(e) => {
+            warn!("2D nonce probe failed: {}", e);
+            false
+        }
+    }
+}
+
+/// Whether a JSON-RPC block carries Tempo's header fields
+pub fn is_tempo_header(block: &serde_json::Value) -> bool {
+    block.get(TEMPO_HEADER_FIELD).is_some()
+}
+
+/// Probes the node behind `rpc_url` and applies `[capabilities]`
+///
+/// With `on_chain_id_mismatch = "adopt"` the config's `chain_id` is replaced
+/// by the node's, so build the client pool from the config afterwards.
+/// Returns `None` when the probe is disabled or the node cannot be reached.
+///
+/// # Errors
+///
+/// Fails on a chain id mismatch with `"fail"`, or on a missing feature with
+/// `on_missing = "fail"`.
+pub async fn check(config: &mut TempoSpammerConfig) -> Result<Option<ChainCapabilities>> {
+    let settings = config.capabilities.clone();
+    if !settings.enabled {
+        return Ok(None);
+    }
+    let url = config
+        .rpc_url
+        .parse()
+        .with_context(|| format!("Invalid RPC URL {}", config.rpc_url))?;
+    let provider = ProviderBuilder::new().connect_http(url);
+    let probed = tokio::time::timeout(Duration::from_secs(settings.timeout_secs), probe(&provider))
+        .await
+        .unwrap_or_else(|_| {
+            Err(anyhow::anyhow!(
+                "no answer within {}s",
+                settings.timeout_secs
+            ))
+        });
+    let capabilities = match probed {
+        Ok(capabilities) => capabilities,
+        Err(e) => {
+            warn!(
+                "Capability probe of {} failed, assuming every feature: {:#}",
+                config.rpc_url, e
+            );
+            return Ok(None);
+        }
+    };
+
+    if capabilities.chain_id != config.chain_id {
+        match settings.on_chain_id_mismatch {
+            ChainIdMismatch::Fail => anyhow::bail!(
+                "chain_id {} does not match chain {} of {}; fix chain_id or set \
+                 [capabilities] on_chain_id_mismatch = \"adopt\"",
+                config.chain_id,
+                capabilities.chain_id,
+                config.rpc_url
+            ),
+            ChainIdMismatch::Adopt => {
+                warn!(
+                    "chain_id {} does not match chain {} of {}, signing for {}",
+                    config.chain_id, capabilities.chain_id, config.rpc_url, capabilities.chain_id
+                );
+                config.chain_id = capabilities.chain_id;
+            }
+        }
+    }
+
+    let missing = capabilities.missing();
+    if !missing.is_empty() {
+        match settings.on_missing {
+            MissingCapability::Fail => anyhow::bail!(
+                "{} does not support {}",
+                config.rpc_url,
+                missing.join(" or ")
+            ),
+            MissingCapability::Adjust => warn!(
+                "{} does not support {}, tasks that need them fail fast",
+                config.rpc_url,
+                missing.join(" or ")
+            ),
+        }
+    }
+    info!(target: "task_result", "Node capabilities: {}", capabilities);
+    Ok(Some(capabilities))
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use crate::testing::MockTempoClient;
+
+    #[tokio::test]
+    async fn test_probe_reads_chain_and_features() {
+        let mock = MockTempoClient::default();
+        let client = mock.client();
+
+        // The mock serves plain Ethereum headers and no nonce precompile
+        let capabilities = probe(client.provider()).await.unwrap();
+        assert_eq!(capabilities.chain_id, client.chain_id());
+        assert!(!capabilities.tempo_transactions);
+        assert!(!capabilities.nonce_2d);
+        assert_eq!(
+            capabilities.missing(),
+            vec!["Tempo transactions", "2D nonces"]
+        );
+
+        let nonce = INonce::getNonceCall::abi_encode_returns(&7u64);
+        mock.on_call(NONCE_PRECOMPILE, "", nonce);
+        assert!(probe(client.provider()).await.unwrap().nonce_2d);
+    }
+
+    #[test]
+    fn test_tempo_header_detection() {
+        let tempo = serde_json::json!({
+            "number": "0x1",
+            "mainBlockGeneralGasLimit": "0x1c9c380",
+            "sharedGasLimit": "0x0",
+            "timestampMillisPart": "0x1f4",
+        });
+        assert!(is_tempo_header(&tempo));
+        assert!(!is_tempo_header(&serde_json::json!({ "number": "0x1" })));
+
+        let assumed = ChainCapabilities::assumed(42431);
+        assert!(assumed.missing().is_empty());
+        assert_eq!(
+            assumed.to_string(),
+            "chain 42431, Tempo transactions: yes, 2D nonces: yes"
+        );
+    }
+}
@@ -0,0 +1,219 @@
+//! Chain Head - Latest block shared by all workers
+//!
+//! Tasks that want the head of the chain (its number, base fee, timestamp
+//! or gas limit) would each ask the node for it on every run. With
+//! `[chain_head]` enabled one background loop polls the latest block for
+//! the whole run instead, and every task reads the header it last saw:
+//!
+//! ```toml
+//! [chain_head]
+//! enabled = true
+//! poll_ms = 1000
+//! ```
+//!
+//! Tasks read the head with [`TaskContext::head`](crate::tasks::TaskContext::head),
+//! which asks the node itself while the watcher is off, has not seen a block
+//! yet, or has not heard from the node for three polls. Code that acts on
+//! new blocks takes a receiver from [`ChainHead::subscribe`] or waits with
+//! [`ChainHead::wait_past`]; each new block is broadcast once, skipped
+//! numbers included as one jump. The runner uses them to start `[tasks]
+//! every_blocks` tasks.
+
+use crate::config::ChainHeadSettings;
+use alloy::eips::BlockNumberOrTag;
+use alloy::providers::Provider;
+use anyhow::{Context, Result};
+use std::sync::RwLock;
+use std::time::{Duration, Instant};
+use tokio::sync::broadcast;
+use tracing::{debug, info};
+
+/// New blocks a subscriber may fall behind by before it skips ahead
+const CHANNEL_CAPACITY: usize = 16;
+
+/// What the tasks use of the latest block header
+#[derive(Debug, Clone, Copy, PartialEq, Eq)]
+pub struct BlockHead {
+    pub number: u64,
+    /// Base fee per gas, `None` on chains without EIP-1559
+    pub base_fee: Option<u64>,
+    /// Block time in seconds since the epoch
+    pub timestamp: u64,
+    pub gas_limit: u64,
+}
+
+impl BlockHead {
+    /// Reads the latest block from `provider`
+    ///
+    /// # Errors
+    ///
+    /// Fails when the request fails or the node has no latest block.
+    pub async fn fetch<P: Provider + ?Sized>(provider: &P) -> Result<Self> {
+        let block = provider
+            .get_block_by_number(BlockNumberOrTag::Latest)
+            .await
+            .context("Failed to fetch latest block")?
+            .context("Latest block not found")?;
+        Ok(Self {
+            number: block.header.number,
+            base_fee: block.header.base_fee_per_gas,
+            timestamp: block.header.timestamp,
+            gas_limit: block.header.gas_limit,
+        })
+    }
+}
+
+/// Latest block header of the chain, polled once for all workers
+#[derive(Debug)]
+pub struct ChainHead {
+    settings: ChainHeadSettings,
+    /// Newest header and when a poll last confirmed it
+    latest: RwLock<Option<(BlockHead, Instant)>>,
+    blocks: broadcast::Sender<BlockHead>,
+}
+
+impl ChainHead {
+    pub fn new(settings: ChainHeadSettings) -> Self {
+        Self {
+            settings,
+            latest: RwLock::new(None),
+            blocks: broadcast::channel(CHANNEL_CAPACITY).0,
+        }
+    }
+
+    /// Newest header, `None` before the first poll or when polls stopped answering
+    pub fn latest(&self) -> Option<BlockHead> {
+        let max_age = Duration::from_millis(self.settings.poll_ms.saturating_mul(3));
+        self.latest
+            .read()
+            .unwrap()
+            .filter(|(_, seen)| seen.elapsed() <= max_age)
+            .map(|(head, _)| head)
+    }
+
+    /// Receives each new block from now on
+    pub fn subscribe(&self) -> broadcast::Receiver<BlockHead> {
+        self.blocks.subscribe()
+    }
+
+    /// Waits for the first block above `number`
+    ///
+    /// Returns at once when the latest header already is. Returns `None`
+    /// when the watcher stops; callers bound the wait with a timeout.
+    pub async fn wait_past(&self, number: u64) -> Option<BlockHead> {
+        let mut blocks = self.subscribe();
+        if let Some(head) = self.latest().filter(|head| head.number > number) {
+            return Some(head);
+        }
+        loop {
+            match blocks.recv().await {
+                Ok(head) if head.number > number => return Some(head),
+                Ok(_) | Err(broadcast::error::RecvError::Lagged(_)) => {}
+                Err(broadcast::error::RecvError::Closed) => return None,
+            }
+        }
+    }
+
+    /// Stores a polled header, broadcasting it when it is a new block
+    ///
+    /// Returns whether it was new. Headers older than the stored one, as a
+    /// lagging node behind a load balancer answers, are ignored.
+    pub fn record(&self, head: BlockHead) -> bool {
+        let mut latest = self.latest.write().unwrap();
+        let previous = latest.map(|(previous, _)| previous.number);
+        if previous.is_some_and(|number| head.number < number) {
+            return false;
+        }
+        *latest = Some((head, Instant::now()));
+        drop(latest);
+
+        let new = previous.is_none_or(|number| head.number > number);
+        if new {
+            // No subscribers is not an error
+            let _ = self.blocks.send(head);
+        }
+        new
+    }
+
+    /// Polls the latest block every `poll_ms` until the task is aborted
+    pub async fn run<P: Provider + ?Sized>(&self, provider: &P) {
+        let mut interval = tokio::time::interval(Duration::from_millis(self.settings.poll_ms));
+        interval.set_missed_tick_behavior(tokio::time::MissedTickBehavior::Delay);
+        let mut failing = false;
+        loop {
+            interval.tick().await;
+            match BlockHead::fetch(provider).await {
+                Ok(head) => {
+                    if failing {
+                        info!("Chain head at block {} again", head.number);
+                        failing = false;
+                    }
+                    self.record(head);
+                }
+                Err(e) => {
+                    if !failing {
+                        debug!("Chain head poll failed, tasks ask the node: {:#}", e);
+                        failing = true;
+                    }
+                }
+            }
+        }
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use crate::testing::MockTempoClient;
+
+    fn head(number: u64) -> BlockHead {
+        BlockHead {
+            number,
+            base_fee: Some(10),
+            timestamp: 1_700_000_000 + number,
+            gas_limit: 30_000_000,
+        }
+    }
+
+    #[tokio::test]
+    async fn test_new_blocks_are_broadcast_once() {
+        let chain_head = ChainHead::new(ChainHeadSettings::default());
+        let mut blocks = chain_head.subscribe();
+        assert_eq!(chain_head.latest(), None);
+
+        assert!(chain_head.record(head(5)));
+        assert!(!chain_head.record(head(5)));
+        assert!(!chain_head.record(head(4)));
+        assert!(chain_head.record(head(7)));
+
+        assert_eq!(blocks.recv().await.unwrap().number, 5);
+        assert_eq!(blocks.recv().await.unwrap().number, 7);
+        assert!(blocks.try_recv().is_err());
+        assert_eq!(chain_head.latest(), Some(head(7)));
+    }
+
+    #[tokio::test]
+    async fn test_wait_past_returns_next_block() {
+        let chain_head = std::sync::Arc::new(ChainHead::new(ChainHeadSettings::default()));
+        chain_head.record(head(3));
+        assert_eq!(chain_head.wait_past(2).await, Some(head(3)));
+
+        let waiting = tokio::spawn({
+            let chain_head = chain_head.clone();
+            async move { chain_head.wait_past(3).await }
+        });
+        tokio::task::yield_now().await;
+        chain_head.record(head(4));
+        assert_eq!(waiting.await.unwrap(), Some(head(4)));
+    }
+
+    #[tokio::test]
+    async fn test_fetch_reads_latest_header() {
+        let mock = MockTempoClient::default();
+        let client = mock.client();
+        let head = BlockHead::fetch(client.provider()).await.unwrap();
+        assert_eq!(head.number, 1);
+        assert_eq!(head.gas_limit, 30_000_000);
+        assert!(head.base_fee.is_some());
+    }
+}
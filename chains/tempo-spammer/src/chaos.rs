@@ -0,0 +1,537 @@
+//! Chaos - Faults injected into RPC traffic on purpose
+//!
+//! The retry, proxy ban and nonce recovery paths only run when the RPC
+//! misbehaves. With `[chaos] enabled` the [`ChaosLayer`] that every
+//! [`TempoClient`](crate::TempoClient) installs below the rate limiter makes it
+//! misbehave at the configured rates:
+//!
+//! - `drop_rate`: the request reaches the node, but its answer is replaced
+//!   with a connection error, as when a proxy drops the connection
+//! - `rate_limit_rate`: the request is answered with an HTTP 429 instead of
+//!   being sent
+//! - `nonce_too_low_rate`: an `eth_sendRawTransaction` is refused with
+//!   `nonce too low` instead of being sent
+//! - `receipt_delay_rate`: a transaction's receipt is reported missing for
+//!   `receipt_delay_ms` after it is first asked for
+//!
+//! ```toml
+//! [chaos]
+//! enabled = true
+//! drop_rate = 0.01
+//! rate_limit_rate = 0.02
+//! nonce_too_low_rate = 0.05
+//! receipt_delay_rate = 0.1
+//! receipt_delay_ms = 5000
+//! ```
+//!
+//! A fault counts as recovered once the same wallet's next call of the same
+//! method gets a result through, which for a delayed receipt is the receipt
+//! itself. [`Chaos::stats`] counts both by fault; the runner logs them every
+//! 30 seconds and on shutdown. Batches pass untouched.
+//!
+//! Meant for test networks only: a dropped `eth_sendRawTransaction` still
+//! reaches the node, so the task sees an error for a transaction that lands.
+
+use crate::config::ChaosSettings;
+use alloy::rpc::json_rpc::{Id, RequestPacket, ResponsePacket};
+use alloy::transports::{TransportError, TransportErrorKind, TransportFut};
+use alloy_primitives::{Address, B256};
+use rand::Rng;
+use std::collections::HashMap;
+use std::fmt;
+use std::sync::{Arc, Mutex, OnceLock, RwLock};
+use std::task::{Context, Poll};
+use std::time::{Duration, Instant};
+use tower::{Layer, Service};
+use tracing::debug;
+
+/// Message of a dropped answer, matched by the runner's proxy ban check
+const DROPPED_MESSAGE: &str =
+    "error sending request: connection closed before message completed (chaos)";
+
+static CHAOS: OnceLock<Arc<Chaos>> = OnceLock::new();
+
+/// Process-wide fault injector shared by all clients
+pub fn chaos() -> &'static Arc<Chaos> {
+    CHAOS.get_or_init(|| Arc::new(Chaos::new(ChaosSettings::default())))
+}
+
+/// A kind of injected fault
+#[derive(Debug, Clone, Copy, PartialEq, Eq)]
+pub enum Fault {
+    DroppedResponse,
+    RateLimited,
+    NonceTooLow,
+    DelayedReceipt,
+}
+
+impl Fault {
+    pub const ALL: [Fault; 4] = [
+        Fault::DroppedResponse,
+        Fault::RateLimited,
+        Fault::NonceTooLow,
+        Fault::DelayedReceipt,
+    ];
+
+    pub fn as_str(self) -> &'static str {
+        match self {
+            Fault::DroppedResponse => "dropped_response",
+            Fault::RateLimited => "rate_limited",
+            Fault::NonceTooLow => "nonce_too_low",
+            Fault::DelayedReceipt => "delayed_receipt",
+        }
+    }
+}
+
+impl fmt::Display for Fault {
+    fn fmt(&self, f: &mut fmt::Formatter<'_>) -> fmt::Result {
+        f.write_str(self.as_str())
+    }
+}
+
+/// Injected faults and how many of them the clients got past
+#[derive(Debug, Clone, Copy, Default, PartialEq, Eq)]
+pub struct FaultCounts {
+    pub injected: u64,
+    pub recovered: u64,
+}
+
+/// What to do with one request
+#[derive(Debug, Clone, Copy, PartialEq, Eq)]
+enum Action {
+    /// Send it on
+    Forward,
+    /// Send it on, then answer with a connection error
+    Drop,
+    /// Answer with the fault without sending it
+    Answer(Fault),
+}
+
+/// Fault rates and the counts of what was injected
+#[derive(Debug)]
+pub struct Chaos {
+    settings: RwLock<ChaosSettings>,
+    counts: Mutex<[FaultCounts; Fault::ALL.len()]>,
+    /// Faults not yet recovered from, by wallet and method
+    pending: Mutex<HashMap<(Address, String), Vec<Fault>>>,
+    /// Receipts held back and until when
+    delayed: Mutex<HashMap<B256, Instant>>,
+}
+
+impl Chaos {
+    pub fn new(settings: ChaosSettings) -> Self {
+        Self {
+            settings: RwLock::new(settings),
+            counts: Mutex::new([FaultCounts::default(); Fault::ALL.len()]),
+            pending: Mutex::new(HashMap::new()),
+            delayed: Mutex::new(HashMap::new()),
+        }
+    }
+
+    /// Applies `[chaos]`
+    pub fn configure(&self, settings: ChaosSettings) {
+        *self.settings.write().unwrap() = settings;
+    }
+
+    pub fn enabled(&self) -> bool {
+        self.settings.read().unwrap().enabled
+    }
+
+    /// Transport layer injecting faults into `wallet`'s calls
+    pub fn layer(self: &Arc<Self>, wallet: Address) -> ChaosLayer {
+        ChaosLayer {
+            chaos: self.clone(),
+            wallet,
+        }
+    }
+
+    /// Counts by fault, in the order of [`Fault::ALL`]
+    pub fn stats(&self) -> Vec<(Fault, FaultCounts)> {
+        let counts = self.counts.lock().unwrap();
+        Fault::ALL.into_iter().zip(counts.iter().copied()).collect()
+    }
+
+    /// Counts over all faults
+    pub fn totals(&self) -> FaultCounts {
+        self.stats()
+            .into_iter()
+            .fold(FaultCounts::default(), |total, (_, counts)| FaultCounts {
+                injected: total.injected + counts.injected,
+                recovered: total.recovered + counts.recovered,
+            })
+    }
+
+    /// Rolls the fault rates for one call of `wallet`
+    fn pick(&self, wallet: Address, method: &str, params: Option<&str>, now: Instant) -> Action {
+        let settings = *self.settings.read().unwrap();
+        if !settings.enabled {
+            return Action::Forward;
+        }
+
+        if method == "eth_getTransactionReceipt"
+            && let Some(hash) = params.and_then(|params| {
+                serde_json::from_str::<(B256,)>(params)
+                    .ok()
+                    .map(|params| params.0)
+            })
+        {
+            let mut delayed = self.delayed.lock().unwrap();
+            match delayed.get(&hash) {
+                Some(until) if *until > now => return Action::Answer(Fault::DelayedReceipt),
+                Some(_) => {
+                    delayed.remove(&hash);
+                    return Action::Forward;
+                }
+                None if roll(settings.receipt_delay_rate) => {
+                    delayed.retain(|_, until| *until > now);
+                    delayed.insert(hash, now + Duration::from_millis(settings.receipt_delay_ms));
+                    drop(delayed);
+                    self.inject(wallet, method, Fault::DelayedReceipt);
+                    return Action::Answer(Fault::DelayedReceipt);
+                }
+                None => {}
+            }
+        }
+
+        if method == "eth_sendRawTransaction" && roll(settings.nonce_too_low_rate) {
+            self.inject(wallet, method, Fault::NonceTooLow);
+            Action::Answer(Fault::NonceTooLow)
+        } else if roll(settings.rate_limit_rate) {
+            self.inject(wallet, method, Fault::RateLimited);
+            Action::Answer(Fault::RateLimited)
+        } else if roll(settings.drop_rate) {
+            self.inject(wallet, method, Fault::DroppedResponse);
+            Action::Drop
+        } else {
+            Action::Forward
+        }
+    }
+
+    fn inject(&self, wallet: Address, method: &str, fault: Fault) {
+        debug!("Chaos: {} on {} for {}", fault, method, wallet);
+        self.counts.lock().unwrap()[fault as usize].injected += 1;
+        self.pending
+            .lock()
+            .unwrap()
+            .entry((wallet, method.to_string()))
+            .or_default()
+            .push(fault);
+    }
+
+    /// Marks `wallet`'s faults on `method` recovered when `response` has a result
+    fn settle(
+        &self,
+        wallet: Address,
+        method: &str,
+        response: &Result<ResponsePacket, TransportError>,
+    ) {
+        let Ok(ResponsePacket::Single(resp)) = response else {
+            return;
+        };
+        if resp
+            .payload
+            .as_success()
+            .is_none_or(|raw| raw.get() == "null")
+        {
+            return;
+        }
+        let Some(faults) = self
+            .pending
+            .lock()
+            .unwrap()
+            .remove(&(wallet, method.to_string()))
+        else {
+            return;
+        };
+        let mut counts = self.counts.lock().unwrap();
+        for fault in faults {
+            counts[fault as usize].recovered += 1;
+        }
+    }
+}
+
+/// Whether a fault with probability `rate` happens this time
+fn roll(rate: f64) -> bool {
+    rate > 0.0 && rand::thread_rng().gen_bool(rate.min(1.0))
+}
+
+/// The node's answer to request `id` had it failed with `fault`
+fn answer(id: &Id, fault: Fault) -> Result<ResponsePacket, TransportError> {
+    let payload = match fault {
+        Fault::RateLimited => {
+            return Err(TransportErrorKind::http_error(
+                429,
+                "too many requests (chaos)".to_string(),
+            ));
+        }
+        Fault::DroppedResponse => return Err(TransportErrorKind::custom_str(DROPPED_MESSAGE)),
+        Fault::NonceTooLow => r#""error":{"code":-32000,"message":"nonce too low"}"#,
+        Fault::DelayedReceipt => r#""result":null"#,
+    };
+    let id = serde_json::to_string(id).map_err(TransportErrorKind::custom)?;
+    serde_json::from_str(&format!(r#"{{"jsonrpc":"2.0","id":{},{}}}"#, id, payload))
+        .map_err(TransportErrorKind::custom)
+}
+
+/// Transport layer installing [`ChaosService`] in one wallet's client
+#[derive(Debug, Clone)]
+pub struct ChaosLayer {
+    chaos: Arc<Chaos>,
+    wallet: Address,
+}
+
+impl<S> Layer<S> for ChaosLayer {
+    type Service = ChaosService<S>;
+
+    fn layer(&self, inner: S) -> Self::Service {
+        ChaosService {
+            inner,
+            chaos: self.chaos.clone(),
+            wallet: self.wallet,
+        }
+    }
+}
+
+/// Transport service answering part of the requests with injected faults
+#[derive(Debug, Clone)]
+pub struct ChaosService<S> {
+    inner: S,
+    chaos: Arc<Chaos>,
+    wallet: Address,
+}
+
+impl<S> Service<RequestPacket> for ChaosService<S>
+where
+    S: Service<
+            RequestPacket,
+            Response = ResponsePacket,
+            Error = TransportError,
+            Future = TransportFut<'static>,
+        > + Send
+        + 'static,
+{
+    type Response = ResponsePacket;
+    type Error = TransportError;
+    type Future = TransportFut<'static>;
+
+    fn poll_ready(&mut self, cx: &mut Context<'_>) -> Poll<Result<(), Self::Error>> {
+        self.inner.poll_ready(cx)
+    }
+
+    fn call(&mut self, request: RequestPacket) -> Self::Future {
+        let RequestPacket::Single(req) = &request else {
+            return self.inner.call(request);
+        };
+        let method = req.method().to_string();
+        let action = self.chaos.pick(
+            self.wallet,
+            &method,
+            req.params().map(|params| params.get()),
+            Instant::now(),
+        );
+        if let Action::Answer(fault) = action {
+            let response = answer(req.id(), fault);
+            return Box::pin(async move { response });
+        }
+
+        let chaos = self.chaos.clone();
+        let wallet = self.wallet;
+        let fut = self.inner.call(request);
+        Box::pin(async move {
+            let response = fut.await;
+            if action == Action::Drop {
+                return Err(TransportErrorKind::custom_str(DROPPED_MESSAGE));
+            }
+            chaos.settle(wallet, &method, &response);
+            response
+        })
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use alloy::rpc::json_rpc::Request;
+    use serde_json::{Value, json};
+    use std::sync::atomic::{AtomicUsize, Ordering};
+
+    /// Answers every call with `0x1`
+    #[derive(Clone, Default)]
+    struct Upstream {
+        calls: Arc<AtomicUsize>,
+    }
+
+    impl Service<RequestPacket> for Upstream {
+        type Response = ResponsePacket;
+        type Error = TransportError;
+        type Future = TransportFut<'static>;
+
+        fn poll_ready(&mut self, _cx: &mut Context<'_>) -> Poll<Result<(), Self::Error>> {
+            Poll::Ready(Ok(()))
+        }
+
+        fn call(&mut self, packet: RequestPacket) -> Self::Future {
+            self.calls.fetch_add(1, Ordering::SeqCst);
+            let RequestPacket::Single(req) = packet else {
+                unreachable!("tests send single requests")
+            };
+            let response = format!(
+                r#"{{"jsonrpc":"2.0","id":{},"result":"0x1"}}"#,
+                serde_json::to_string(req.id()).unwrap(),
+            );
+            Box::pin(
+                async move { serde_json::from_str(&response).map_err(TransportErrorKind::custom) },
+            )
+        }
+    }
+
+    fn request(method: &'static str, params: Value) -> RequestPacket {
+        RequestPacket::Single(
+            Request::new(method, Id::Number(1), params)
+                .serialize()
+                .unwrap(),
+        )
+    }
+
+    fn service(chaos: &Arc<Chaos>) -> (ChaosService<Upstream>, Arc<AtomicUsize>) {
+        let upstream = Upstream::default();
+        let calls = upstream.calls.clone();
+        (chaos.layer(Address::ZERO).layer(upstream), calls)
+    }
+
+    fn counts(chaos: &Chaos, fault: Fault) -> FaultCounts {
+        chaos.stats()[fault as usize].1
+    }
+
+    fn result(response: Result<ResponsePacket, TransportError>) -> String {
+        match response.unwrap() {
+            ResponsePacket::Single(resp) => match resp.payload.as_success() {
+                Some(raw) => raw.get().to_string(),
+                None => resp.payload.as_error().unwrap().message.to_string(),
+            },
+            ResponsePacket::Batch(_) => unreachable!(),
+        }
+    }
+
+    #[tokio::test]
+    async fn test_disabled_passes_through() {
+        let chaos = Arc::new(Chaos::new(ChaosSettings {
+            enabled: false,
+            rate_limit_rate: 1.0,
+            ..ChaosSettings::default()
+        }));
+        let (mut service, calls) = service(&chaos);
+
+        let response = service.call(request("eth_blockNumber", json!([]))).await;
+        assert_eq!(result(response), "\"0x1\"");
+        assert_eq!(calls.load(Ordering::SeqCst), 1);
+        assert_eq!(chaos.totals(), FaultCounts::default());
+    }
+
+    #[tokio::test]
+    async fn test_faults_counted_until_recovered() {
+        let chaos = Arc::new(Chaos::new(ChaosSettings {
+            enabled: true,
+            nonce_too_low_rate: 1.0,
+            ..ChaosSettings::default()
+        }));
+        let (mut service, calls) = service(&chaos);
+
+        // Refused without reaching the node; reads are left alone
+        let send = || request("eth_sendRawTransaction", json!(["0x00"]));
+        assert_eq!(result(service.call(send()).await), "nonce too low");
+        assert_eq!(result(service.call(send()).await), "nonce too low");
+        assert_eq!(
+            result(service.call(request("eth_call", json!([]))).await),
+            "\"0x1\""
+        );
+        assert_eq!(calls.load(Ordering::SeqCst), 1);
+
+        chaos.configure(ChaosSettings {
+            enabled: true,
+            rate_limit_rate: 1.0,
+            ..ChaosSettings::default()
+        });
+        let err = service
+            .call(request("eth_chainId", json!([])))
+            .await
+            .unwrap_err();
+        assert!(crate::rate_limit::is_rate_limited(&Err(err)));
+
+        chaos.configure(ChaosSettings {
+            enabled: true,
+            drop_rate: 1.0,
+            ..ChaosSettings::default()
+        });
+        let err = service.call(send()).await.unwrap_err();
+        assert!(err.to_string().contains("error sending request"), "{}", err);
+        assert_eq!(calls.load(Ordering::SeqCst), 2);
+
+        // The next send that gets through recovers all three send faults
+        chaos.configure(ChaosSettings {
+            enabled: true,
+            ..ChaosSettings::default()
+        });
+        assert_eq!(result(service.call(send()).await), "\"0x1\"");
+        assert_eq!(
+            counts(&chaos, Fault::NonceTooLow),
+            FaultCounts {
+                injected: 2,
+                recovered: 2
+            }
+        );
+        assert_eq!(
+            counts(&chaos, Fault::DroppedResponse),
+            FaultCounts {
+                injected: 1,
+                recovered: 1
+            }
+        );
+        assert_eq!(
+            counts(&chaos, Fault::RateLimited),
+            FaultCounts {
+                injected: 1,
+                recovered: 0
+            }
+        );
+        assert_eq!(
+            chaos.totals(),
+            FaultCounts {
+                injected: 4,
+                recovered: 3
+            }
+        );
+    }
+
+    #[tokio::test]
+    async fn test_receipt_held_back_for_delay() {
+        let chaos = Arc::new(Chaos::new(ChaosSettings {
+            enabled: true,
+            receipt_delay_rate: 1.0,
+            receipt_delay_ms: 50,
+            ..ChaosSettings::default()
+        }));
+        let (mut service, calls) = service(&chaos);
+        let receipt = || {
+            request(
+                "eth_getTransactionReceipt",
+                json!([B256::repeat_byte(0xab)]),
+            )
+        };
+
+        assert_eq!(result(service.call(receipt()).await), "null");
+        assert_eq!(result(service.call(receipt()).await), "null");
+        assert_eq!(calls.load(Ordering::SeqCst), 0);
+
+        tokio::time::sleep(Duration::from_millis(60)).await;
+        assert_eq!(result(service.call(receipt()).await), "\"0x1\"");
+        assert_eq!(calls.load(Ordering::SeqCst), 1);
+        assert_eq!(
+            counts(&chaos, Fault::DelayedReceipt),
+            FaultCounts {
+                injected: 1,
+                recovered: 1
+            }
+        );
+    }
+}
@@ -11,6 +11,8 @@
 //! - **Automatic Retries**: Exponential backoff for failed requests (5 retries)
 //! - **Connection Pooling**: Efficient HTTP connection reuse
 //! - **Nonce Management**: Optional integration with NonceManager for caching
+//! - **Message Signing**: EIP-191 messages and EIP-712 typed data with the
+//!   wallet key
 //!
 //! # Example
 //!
@@ -45,6 +47,7 @@
 //!     url: "http://proxy.example.com:8080".to_string(),
 //!     username: Some("user".to_string()),
 //!     password: Some("pass".to_string()),
+//!     session: None,
 //! };
 //!
 //! let client = TempoClient::new(
@@ -64,19 +67,54 @@
 //! - Initial backoff: 100ms
 //! - Max backoff: 2000ms
 //!
-//! This handles transient network issues and RPC rate limiting.
+//! This handles transient network issues.
+//!
+//! # Rate Limits
+//!
+//! Requests are spaced out per endpoint and `429` answers pause the endpoint
+//! for the server's `Retry-After` through the
+//! [`rate_limit`](crate::rate_limit) layer, which also retries rate-limited
+//! reads. Rate-limited sends fail with a rate limit error instead.
+//!
+//! # Response Cache
+//!
+//! Answers that never change, such as the chain id, token decimals and
+//! contract code, are shared by all clients through the
+//! [`rpc_cache`](crate::rpc_cache) layer and served without a round trip.
+//!
+//! # Tracing
+//!
+//! Every call runs in an `rpc` span of the [`rpc_span`](crate::rpc_span)
+//! layer, a child of the calling task's span.
+//!
+//! # Watchdog
+//!
+//! Calls in flight and recent provider errors are tracked per wallet by the
+//! [`watchdog`](crate::watchdog) layer for snapshots of slow task runs.
 
 use super::tasks::ProxyConfig;
 use alloy::providers::Provider;
 use alloy::rpc::client::ClientBuilder;
+use alloy::signers::SignerSync;
 use alloy::signers::local::PrivateKeySigner;
-use alloy::transports::http::Http;
-use alloy_primitives::Address;
+use alloy_primitives::{Address, Signature, address};
+use alloy_sol_types::{Eip712Domain, SolCall, SolStruct, sol};
 use anyhow::{Context, Result};
+use async_trait::async_trait;
+use core_logic::{FeeSample, GasEstimator, Nonce2D, NonceManagement, NonceSource};
 use reqwest::{Client, Proxy};
 use std::sync::Arc;
 use url::Url;
 
+sol! {
+    interface INonce {
+        function getNonce(address account, uint256 nonceKey) external view returns (uint64 nonce);
+    }
+}
+
+/// Nonce precompile, holding the user nonce keys of Tempo's 2D nonces
+pub(crate) const NONCE_PRECOMPILE: Address = address!("4E4F4E4345000000000000000000000000000000");
+
 /// High-level client for Tempo blockchain interactions
 ///
 /// Wraps Alloy's provider with additional features for production use including
@@ -170,19 +208,66 @@ impl TempoClient {
     ) -> Result<Self> {
         let signer: PrivateKeySigner =
             private_key.parse().context("Failed to parse private key")?;
+        Self::new_from_signer(
+            rpc_url,
+            signer,
+            reqwest_client,
+            proxy_config,
+            proxy_index,
+            nonce_manager,
+            robust_nonce_manager,
+            use_pending_count,
+        )
+        .await
+    }
 
+    /// Creates a new client signing with an already parsed key
+    ///
+    /// Same as [`new_from_reqwest`](Self::new_from_reqwest) without the key
+    /// string, so callers that rebuild a client, e.g. for another proxy, can
+    /// reuse the old client's signer instead of decrypting the wallet again.
+    pub async fn new_from_signer(
+        rpc_url: &str,
+        signer: PrivateKeySigner,
+        reqwest_client: Client,
+        proxy_config: Option<ProxyConfig>,
+        proxy_index: Option<usize>,
+        nonce_manager: Option<Arc<crate::NonceManager>>,
+        robust_nonce_manager: Option<Arc<crate::RobustNonceManager>>,
+        use_pending_count: bool,
+    ) -> Result<Self> {
         let chain_id = signer.chain_id().unwrap_or(42431);
 
         // Create a resilient RPC client with retry logic
-        let http_transport = Http::with_client(
+        let http_transport = crate::rate_limit::limiter().transport(
             reqwest_client,
             rpc_url.parse::<Url>().context("Invalid RPC URL")?,
         );
 
         let client = ClientBuilder::default()
+            // Outermost, so one span covers a call and all its retries
+            .layer(crate::rpc_span::RpcSpanLayer::new(signer.address()))
+            // Per-method counts and latencies, a call's retries included
+            .layer(crate::rpc_stats::rpc_stats().layer())
             .layer(alloy::transports::layers::RetryBackoffLayer::new(
                 5, 100, 2000,
             ))
+            // Inside the retry layer, so audits time each attempt on its own
+            .layer(crate::audit::AuditLayer)
+            // Tracks each attempt in flight for the watchdog's snapshots
+            .layer(crate::watchdog::watchdog().layer(signer.address()))
+            // Audited runs still see cached answers, which skip the proxy
+            .layer(crate::rpc_cache::cache().layer(rpc_url))
+            // Cache hits take no token; rate-limited reads are retried in here
+            .layer(crate::rate_limit::limiter().layer(rpc_url))
+            // Below the rate limiter, so injected 429s are handled like real ones
+            .layer(crate::chaos::chaos().layer(signer.address()))
+            // Innermost, so every attempt that goes out is billed to the proxy
+            .layer(
+                crate::proxy_usage::tracker().layer(proxy_config.as_ref().map(|p| p.url.as_str())),
+            )
+            // Below everything else, so the recording holds what the node answered
+            .layer(crate::recording::recorder().layer(signer.address(), rpc_url))
             .transport(http_transport, true);
 
         let provider: Arc<dyn Provider + Send + Sync> = Arc::new(
@@ -281,10 +366,8 @@ impl TempoClient {
             let proxy_url = &proxy_config.url;
             let proxy = Proxy::all(proxy_url).context("Failed to create proxy")?;
 
-            if let (Some(username), Some(password)) =
-                (&proxy_config.username, &proxy_config.password)
-            {
-                let proxy = proxy.basic_auth(username, password);
+            if let Some((username, password)) = proxy_config.basic_auth() {
+                let proxy = proxy.basic_auth(&username, &password);
                 client_builder = client_builder.proxy(proxy);
             } else {
                 client_builder = client_builder.proxy(proxy);
@@ -300,15 +383,24 @@ impl TempoClient {
             .context("Failed to build reqwest client")?;
 
         // Create a resilient RPC client with retry logic
-        let http_transport = Http::with_client(
+        let http_transport = crate::rate_limit::limiter().transport(
             reqwest_client,
             rpc_url.parse::<Url>().context("Invalid RPC URL")?,
         );
 
         let client = ClientBuilder::default()
+            .layer(crate::rpc_span::RpcSpanLayer::new(signer.address()))
+            .layer(crate::rpc_stats::rpc_stats().layer())
             .layer(alloy::transports::layers::RetryBackoffLayer::new(
                 5, 100, 2000,
             ))
+            .layer(crate::audit::AuditLayer)
+            .layer(crate::watchdog::watchdog().layer(signer.address()))
+            .layer(crate::rpc_cache::cache().layer(rpc_url))
+            .layer(crate::rate_limit::limiter().layer(rpc_url))
+            .layer(crate::chaos::chaos().layer(signer.address()))
+            .layer(crate::proxy_usage::tracker().layer(proxy.map(|p| p.url.as_str())))
+            .layer(crate::recording::recorder().layer(signer.address(), rpc_url))
             .transport(http_transport, true);
 
         let provider: Arc<dyn Provider + Send + Sync> = Arc::new(
@@ -409,6 +501,59 @@ impl TempoClient {
         &*self.provider
     }
 
+    /// Starts a transaction from this wallet
+    ///
+    /// The returned [`TxBuilder`](crate::tx_builder::TxBuilder) estimates
+    /// gas, takes the nonce, prices the transaction from its fee profile and
+    /// retries nonce races when sent. See the [`tx_builder`](crate::tx_builder)
+    /// module.
+    ///
+    /// # Example
+    ///
+    /// ```rust,no_run
+    /// # use alloy_primitives::{Address, U256};
+    /// # async fn example(client: &tempo_spammer::TempoClient, to: Address) -> anyhow::Result<()> {
+    /// let outcome = client
+    ///     .tx()
+    ///     .to(to)
+    ///     .value(U256::from(1))
+    ///     .send_and_confirm()
+    ///     .await?;
+    /// assert!(outcome.succeeded());
+    /// # Ok(())
+    /// # }
+    /// ```
+    pub fn tx(&self) -> crate::tx_builder::TxBuilder<'_> {
+        crate::tx_builder::TxBuilder::new(self)
+    }
+
+    /// Signs a message the EIP-191 way, as `personal_sign` does
+    ///
+    /// The message is prefixed with `"\x19Ethereum Signed Message:\n"` and
+    /// its length before hashing, so the signature can never pass for a
+    /// transaction. Recover the signer with
+    /// [`Signature::recover_address_from_msg`].
+    pub fn sign_message(&self, message: &[u8]) -> Result<Signature> {
+        self.signer
+            .sign_message_sync(message)
+            .context("Failed to sign message")
+    }
+
+    /// Signs a struct as EIP-712 typed data under `domain`
+    ///
+    /// The signed digest is `value.eip712_signing_hash(domain)`, so any
+    /// `sol!` struct works. Recover the signer with
+    /// [`Signature::recover_address_from_prehash`] on that digest.
+    pub fn sign_typed_data<T: SolStruct>(
+        &self,
+        value: &T,
+        domain: &Eip712Domain,
+    ) -> Result<Signature> {
+        self.signer
+            .sign_hash_sync(&value.eip712_signing_hash(domain))
+            .context("Failed to sign typed data")
+    }
+
     /// Phase 3: Verify provider is ready by making a simple RPC call
     ///
     /// This ensures the connection is fully established before returning the client,
@@ -508,37 +653,15 @@ impl TempoClient {
     /// - RPC request fails
     /// - Proxy configuration is invalid
     /// - Network timeout occurs
-    pub async fn get_pending_nonce(&self, rpc_url: &str) -> Result<u64> {
+    pub async fn get_pending_nonce(&self, _rpc_url: &str) -> Result<u64> {
         let address = self.signer.address();
 
-        // 1. Try Local Manager
-        if let Some(manager) = &self.nonce_manager {
-            if let Some(next_nonce) = manager.get_and_increment(address).await {
-                return Ok(next_nonce);
-            }
-        }
-
-        // 2. Fallback to RPC using existing provider (avoids creating new HTTP clients)
-        // This prevents connection pool exhaustion when many tasks run concurrently
-        // Use pending block tag if configured to get transactions in mempool
-        let rpc_nonce = if self.use_pending_count {
-            self.provider
-                .get_transaction_count(address)
-                .await
-                .map_err(|e| anyhow::anyhow!("Failed to get pending transaction count: {}", e))?
-        } else {
-            self.provider
-                .get_transaction_count(address)
-                .await
-                .map_err(|e| anyhow::anyhow!("Failed to get transaction count: {}", e))?
-        };
-
-        // 3. Update Manager with NEXT expected nonce
-        if let Some(manager) = &self.nonce_manager {
-            manager.set(address, rpc_nonce + 1).await;
+        // Fetching through the existing provider avoids creating new HTTP
+        // clients, which would exhaust the connection pool under load
+        match &self.nonce_manager {
+            Some(manager) => manager.next_or_fetch(address, self).await,
+            None => self.next_nonce(address).await,
         }
-
-        Ok(rpc_nonce)
     }
 
     /// Gets a nonce using the robust nonce manager with reservation pattern
@@ -567,7 +690,7 @@ impl TempoClient {
     /// ```
     pub async fn get_robust_nonce(
         &self,
-        rpc_url: &str,
+        _rpc_url: &str,
     ) -> Result<crate::robust_nonce_manager::NonceReservation> {
         let address = self.signer.address();
 
@@ -578,7 +701,7 @@ impl TempoClient {
             }
 
             // Need to initialize from RPC
-            let rpc_nonce = self.fetch_nonce_from_rpc(rpc_url).await?;
+            let rpc_nonce = self.next_nonce(address).await?;
             manager.initialize(address, rpc_nonce).await;
 
             // Try again
@@ -651,20 +774,156 @@ impl TempoClient {
         }
     }
 
-    /// Helper: Fetch nonce from RPC using existing provider
+    /// Sends several calls as one Tempo (0x76) transaction
     ///
-    /// Uses the client's existing provider instead of creating new HTTP connections,
-    /// preventing connection pool exhaustion under high concurrency.
-    async fn fetch_nonce_from_rpc(&self, _rpc_url: &str) -> Result<u64> {
-        let address = self.signer.address();
+    /// The calls execute in order and atomically: if any of them reverts, the
+    /// whole transaction reverts and none of their effects remain. Fees follow
+    /// the tasks' usual 125% of the current gas price.
+    ///
+    /// # Arguments
+    ///
+    /// * `signer` - Wallet or session key signer, see `TaskContext::tempo_signer`
+    /// * `rpc_url` - The RPC endpoint URL for nonce fallback requests
+    /// * `calls` - Calls to execute, at least one
+    /// * `gas_limit` - Gas limit of the whole transaction
+    ///
+    /// # Returns
+    ///
+    /// The transaction hash once the node accepted it. The cached nonce is
+    /// advanced on success and reset when sending fails.
+    pub async fn send_calls(
+        &self,
+        signer: &crate::session_key::TempoSigner,
+        rpc_url: &str,
+        calls: Vec<tempo_primitives::transaction::Call>,
+        gas_limit: u64,
+    ) -> Result<alloy_primitives::TxHash> {
+        self.send_calls_with_access_list(signer, rpc_url, calls, Default::default(), gas_limit)
+            .await
+    }
 
-        // Use existing provider to avoid creating new HTTP clients
-        let rpc_nonce = self
-            .provider
+    /// Sends several calls as one Tempo (0x76) transaction with an access list
+    ///
+    /// Same as [`send_calls`](Self::send_calls), with `access_list` declaring
+    /// the accounts and storage slots the calls touch. An empty list sends the
+    /// transaction without one.
+    ///
+    /// # Arguments
+    ///
+    /// * `signer` - Wallet or session key signer, see `TaskContext::tempo_signer`
+    /// * `rpc_url` - The RPC endpoint URL for nonce fallback requests
+    /// * `calls` - Calls to execute, at least one
+    /// * `access_list` - EIP-2930 access list covering all calls
+    /// * `gas_limit` - Gas limit of the whole transaction
+    ///
+    /// # Returns
+    ///
+    /// The transaction hash once the node accepted it.
+    pub async fn send_calls_with_access_list(
+        &self,
+        signer: &crate::session_key::TempoSigner,
+        rpc_url: &str,
+        calls: Vec<tempo_primitives::transaction::Call>,
+        access_list: alloy::eips::eip2930::AccessList,
+        gas_limit: u64,
+    ) -> Result<alloy_primitives::TxHash> {
+        if calls.is_empty() {
+            anyhow::bail!("A Tempo transaction needs at least one call");
+        }
+
+        let nonce = self.get_pending_nonce(rpc_url).await?;
+        let gas_price = self.provider.get_gas_price().await?;
+        let tx = tempo_primitives::transaction::TempoTransaction {
+            chain_id: self.chain_id,
+            nonce,
+            max_fee_per_gas: (gas_price * 125) / 100,
+            max_priority_fee_per_gas: 1_500_000_000,
+            gas_limit,
+            calls,
+            access_list,
+            ..Default::default()
+        };
+        let sig = signer.sign(&tx).await?;
+        let mut raw = Vec::new();
+        tx.into_signed(sig).eip2718_encode(&mut raw);
+
+        let pending = match self.provider.send_raw_transaction(&raw).await {
+            Ok(pending) => pending,
+            Err(e) => {
+                self.reset_nonce_cache().await;
+                return Err(e).context("Failed to send Tempo transaction");
+            }
+        };
+        if let Some(manager) = &self.nonce_manager {
+            manager.set(self.address(), nonce + 1).await;
+        }
+        Ok(*pending.tx_hash())
+    }
+}
+
+/// Lets [`core_logic::GasManager`] price transactions from this client
+#[async_trait]
+impl GasEstimator for TempoClient {
+    type Error = anyhow::Error;
+
+    async fn estimate_gas(&self, data: &[u8], to: Option<&str>) -> Result<u64> {
+        let mut tx = alloy::rpc::types::TransactionRequest::default()
+            .from(self.address())
+            .input(data.to_vec().into());
+        if let Some(to) = to {
+            tx = tx.to(to.parse::<Address>()?);
+        }
+        Ok(self.provider.estimate_gas(tx).await?)
+    }
+
+    async fn get_gas_price(&self) -> Result<u64> {
+        Ok(self.provider.get_gas_price().await?.try_into()?)
+    }
+
+    async fn fee_sample(&self, percentile: f64) -> Result<FeeSample> {
+        FeeSample::fetch(self.provider.as_ref(), percentile)
+            .await
+            .map_err(anyhow::Error::msg)
+    }
+}
+
+/// Reads protocol nonces for the nonce managers
+#[async_trait]
+impl NonceSource for TempoClient {
+    type Error = anyhow::Error;
+
+    async fn next_nonce(&self, address: Address) -> Result<u64> {
+        self.provider
             .get_transaction_count(address)
             .await
-            .map_err(|e| anyhow::anyhow!("Failed to get transaction count: {}", e))?;
+            .map_err(|e| anyhow::anyhow!("Failed to get transaction count: {}", e))
+    }
+}
 
-        Ok(rpc_nonce)
+/// Reads 2D nonces: key 0 is the protocol nonce, other keys come from the
+/// nonce precompile
+#[async_trait]
+impl NonceSource<Nonce2D> for TempoClient {
+    type Error = anyhow::Error;
+
+    async fn next_nonce(&self, key: Nonce2D) -> Result<u64> {
+        if key.is_protocol() {
+            return self.next_nonce(key.address).await;
+        }
+
+        let call = INonce::getNonceCall {
+            account: key.address,
+            nonceKey: key.key,
+        };
+        let data = self
+            .provider
+            .call(
+                alloy::rpc::types::TransactionRequest::default()
+                    .to(NONCE_PRECOMPILE)
+                    .input(alloy_primitives::Bytes::from(call.abi_encode()).into()),
+            )
+            .await
+            .context("Nonce precompile getNonce failed")?;
+        INonce::getNonceCall::abi_decode_returns(&data).context("Failed to decode getNonce result")
     }
 }
@@ -9,7 +9,8 @@
 //! for wallet leasing:
 //!
 //! 1. **Acquisition**: Workers request a client via [`ClientPool::try_acquire_client`]
-//! 2. **Selection**: Pool selects random available wallet with healthy proxy
+//! 2. **Selection**: Pool selects an available wallet with healthy proxy, by
+//!    `[wallets] selection` (see [`WalletSelector`])
 //! 3. **Locking**: Wallet is marked as "in use" to prevent double-spending
 //! 4. **Lease**: Returns a [`ClientLease`] that auto-releases on drop
 //! 5. **Cooldown**: 4-second delay before wallet is available again (prevents nonce races)
@@ -51,6 +52,25 @@
 //! # }
 //! ```
 //!
+//! # Partitioned Wallets
+//!
+//! With `[wallets] assignment = "partitioned"` workers skip leasing: each
+//! one owns the contiguous slice of wallet indices [`partition`] gives it
+//! and cycles through them with a worker-local [`WalletPartition`], which
+//! also keeps the reuse cooldown. [`ClientPool::acquire_owned_client`] hands
+//! out such wallets without touching the locked or available sets, so
+//! workers never contend on them; they still share the connection limit.
+//!
+//! # Backpressure
+//!
+//! The try methods return `None` at once when every connection permit is
+//! taken or no wallet is free. [`ClientPool::acquire_client_waiting`] instead
+//! waits for a permit and then for a released wallet, up to a deadline, so a
+//! saturated pool parks workers rather than having them spin.
+//! [`ClientPool::stats`] reports free permits, locked wallets, acquisition
+//! waits, lease hold times and how often the pool was saturated; the runner
+//! logs them every 30 seconds and warns when acquisitions time out.
+//!
 //! # Proxy Rotation
 //!
 //! The pool supports multiple proxies with automatic rotation:
@@ -59,6 +79,11 @@
 //! - Automatic fallback to direct connection if all proxies banned
 //! - Per-proxy HTTP client caching for connection reuse
 //!
+//! How wallets map onto proxies is set by `[proxy] assignment` (see
+//! [`ProxyAssignments`]): `rotating` hands proxies out round-robin, `sticky`
+//! pins each wallet to one proxy across restarts, and `hybrid` pins wallets
+//! but moves them when their proxy gets banned.
+//!
 //! # Nonce Management
 //!
 //! Optional integration with [`NonceManager`] for high-throughput scenarios:
@@ -71,18 +96,94 @@
 //! - HTTP clients are cached per proxy (connection reuse)
 //! - Wallet clients are cached after first creation
 //! - 4-second cooldown prevents nonce synchronization issues
-//! - Random selection distributes load evenly
+//! - Random selection is O(1) but uneven over time: some wallets get leased
+//!   several times as often as others. `least_recently_used` and
+//!   `round_robin` spread leases evenly at the cost of a scan of the free
+//!   wallets per lease
 
 use crate::TempoClient;
-use crate::config::TempoSpammerConfig as Config;
+use crate::config::{ProxyAssignmentPolicy, TempoSpammerConfig as Config, WalletSelection};
+use crate::proxy_assignment::{ProxyAssignments, WorkerProxies, current_worker};
+use crate::proxy_session::SessionRotation;
 use crate::tasks::load_proxies;
+use crate::wallet_health::WalletHealth;
+use alloy::signers::local::PrivateKeySigner;
 use anyhow::{Context, Result};
 use core_logic::WalletManager;
-use std::collections::HashMap;
+use std::collections::{BTreeMap, BTreeSet, HashMap};
+use std::ops::Range;
 use std::path::Path;
 use std::sync::Arc;
-use std::sync::atomic::{AtomicUsize, Ordering};
-use tokio::sync::RwLock;
+use std::sync::atomic::{AtomicU64, AtomicUsize, Ordering};
+use std::time::{Duration, Instant};
+use tokio::sync::{Notify, OwnedSemaphorePermit, RwLock};
+use zeroize::Zeroizing;
+
+/// Longest pause between wallet checks while waiting, so wallets whose proxy
+/// recovers from a ban are noticed without a release
+const WAIT_RECHECK: Duration = Duration::from_millis(250);
+
+/// Cumulative acquisition and lease counters of a [`ClientPool`]
+#[derive(Debug, Default)]
+pub struct PoolMetrics {
+    leases: AtomicU64,
+    saturated: AtomicU64,
+    timeouts: AtomicU64,
+    wait_us: AtomicU64,
+    max_wait_us: AtomicU64,
+    holds: AtomicU64,
+    hold_us: AtomicU64,
+    max_hold_us: AtomicU64,
+}
+
+impl PoolMetrics {
+    fn record_lease(&self, wait: Duration) {
+        let us = wait.as_micros() as u64;
+        self.leases.fetch_add(1, Ordering::Relaxed);
+        self.wait_us.fetch_add(us, Ordering::Relaxed);
+        self.max_wait_us.fetch_max(us, Ordering::Relaxed);
+    }
+
+    fn record_hold(&self, held: Duration) {
+        let us = held.as_micros() as u64;
+        self.holds.fetch_add(1, Ordering::Relaxed);
+        self.hold_us.fetch_add(us, Ordering::Relaxed);
+        self.max_hold_us.fetch_max(us, Ordering::Relaxed);
+    }
+}
+
+/// Point-in-time view of a [`ClientPool`]'s load, see [`ClientPool::stats`]
+#[derive(Debug, Clone, PartialEq)]
+pub struct PoolStats {
+    /// Connection permits free right now
+    pub permits_available: usize,
+    /// Size of the connection limit (`connection_semaphore`)
+    pub permits_total: usize,
+    /// Wallets leased or cooling down
+    pub wallets_locked: usize,
+    /// Wallets ready to lease
+    pub wallets_available: usize,
+    /// Wallets sitting out a quarantine, see [`WalletHealth`]
+    pub wallets_quarantined: usize,
+    /// Leases handed out since start
+    pub leases: u64,
+    /// Acquisitions that found every connection permit taken
+    pub saturated: u64,
+    /// [`ClientPool::acquire_client_waiting`] calls that gave up
+    pub timeouts: u64,
+    pub avg_wait: Duration,
+    pub max_wait: Duration,
+    /// Time from acquisition to release, cooldown excluded
+    pub avg_hold: Duration,
+    pub max_hold: Duration,
+}
+
+impl PoolStats {
+    /// Whether no connection permit is free
+    pub fn is_saturated(&self) -> bool {
+        self.permits_available == 0
+    }
+}
 
 /// Pool of clients for multi-wallet transaction spamming
 ///
@@ -105,11 +206,12 @@ use tokio::sync::RwLock;
 /// - `locked_wallets`: Set of currently leased wallet indices
 /// - `nonce_manager`: Optional nonce caching
 /// - `proxy_banlist`: Optional proxy health tracking
+/// - `proxy_arbiter`: Optional quota and ban sharing with other chains
 pub struct ClientPool {
     /// Wallet manager for accessing encrypted keys
     wallet_manager: Arc<WalletManager>,
-    /// Password for wallet decryption
-    wallet_password: Option<String>,
+    /// Password for wallet decryption, wiped when the pool drops
+    wallet_password: Option<Zeroizing<String>>,
     /// Cache of created clients by wallet index
     clients: RwLock<HashMap<usize, TempoClient>>,
     /// Cache of HTTP clients per proxy (None = direct, Some(url) = proxy)
@@ -131,6 +233,8 @@ pub struct ClientPool {
     pub sharded_robust_nonce_managers: Vec<Arc<crate::RobustNonceManager>>,
     /// Optional proxy banlist for health tracking
     pub proxy_banlist: Option<crate::proxy_health::ProxyBanlist>,
+    /// Optional share of a proxy pool arbitrated between several chains
+    pub proxy_arbiter: Option<core_logic::ChainProxies>,
     /// Database manager for logging
     pub db: Option<Arc<core_logic::database::DatabaseManager>>,
 
@@ -143,6 +247,9 @@ pub struct ClientPool {
     /// Enables O(1) removal when wallet is locked
     available_positions: RwLock<HashMap<usize, usize>>,
 
+    /// Which available wallet is leased next
+    wallet_selector: WalletSelector,
+
     /// Cache for proxy banned status to avoid repeated checks
     /// Maps proxy_index -> (is_banned, timestamp)
     proxy_cache: RwLock<HashMap<usize, (bool, std::time::Instant)>>,
@@ -152,8 +259,36 @@ pub struct ClientPool {
     /// Ensures all 390+ proxies are utilized evenly
     proxy_rotation_counter: AtomicUsize,
 
+    /// Wallet -> proxy pins for sticky and hybrid assignment policies
+    proxy_assignments: ProxyAssignments,
+
+    /// Worker -> proxy bindings for the worker assignment policy
+    worker_proxies: Option<WorkerProxies>,
+
+    /// Proxy session generation each cached client was built with
+    /// Clients on a templated proxy are rebuilt once its session moves on
+    client_session_generations: RwLock<HashMap<usize, u64>>,
+
     /// Semaphore to limit total concurrent connections across all workers
     pub connection_semaphore: Arc<tokio::sync::Semaphore>,
+
+    /// Permits the semaphore was created with
+    connection_limit: usize,
+
+    /// Woken whenever a wallet returns to the available set
+    wallet_released: Notify,
+
+    /// Acquisition and lease counters behind [`ClientPool::stats`]
+    metrics: Arc<PoolMetrics>,
+
+    /// Failure scores deciding which wallets sit out in quarantine
+    wallet_health: Arc<WalletHealth>,
+
+    /// Quarantined wallets held back from the available set until their recheck
+    parked_wallets: std::sync::Mutex<BTreeSet<usize>>,
+
+    /// What the node supports, `None` when it was not probed
+    capabilities: Option<crate::capabilities::ChainCapabilities>,
 }
 
 /// RAII guard for a leased client
@@ -184,10 +319,14 @@ pub struct ClientLease {
     pub client: TempoClient,
     /// Index of the wallet in the pool
     pub index: usize,
-    /// Reference to the pool for release on drop
-    pool: Arc<ClientPool>,
+    /// Reference to the pool for release on drop, `None` for a wallet the
+    /// worker owns in partitioned mode
+    pool: Option<Arc<ClientPool>>,
     /// Connection permit that is released when lease is dropped
     pub permit: Option<tokio::sync::OwnedSemaphorePermit>,
+    /// When the lease was handed out, for the pool's hold time metrics
+    acquired_at: Instant,
+    metrics: Arc<PoolMetrics>,
 }
 
 impl ClientLease {
@@ -203,8 +342,10 @@ impl ClientLease {
     /// // lease.release().await; // Explicit release with cooldown
     /// # }
     /// ```
-    pub async fn release(self) {
-        let pool = self.pool.clone();
+    pub async fn release(mut self) {
+        let Some(pool) = self.pool.take() else {
+            return;
+        };
         let index = self.index;
         let nonce_config = pool.config.nonce.clone();
 
@@ -227,8 +368,10 @@ impl ClientLease {
     ///
     /// **WARNING**: This may cause nonce races if used incorrectly.
     /// Only use this if you're certain the transaction has been confirmed.
-    pub async fn release_immediate(self) {
-        self.pool.release_wallet(self.index).await;
+    pub async fn release_immediate(mut self) {
+        if let Some(pool) = self.pool.take() {
+            pool.release_wallet(self.index).await;
+        }
     }
 }
 
@@ -238,13 +381,17 @@ impl Drop for ClientLease {
     /// This is a safety fallback. If you see this warning in logs,
     /// you should update your code to call `lease.release().await` explicitly.
     fn drop(&mut self) {
+        // Also reached through release(), which consumes the lease
+        self.metrics.record_hold(self.acquired_at.elapsed());
+        let Some(pool) = self.pool.take() else {
+            return;
+        };
         tracing::warn!(
             target: "client_pool",
             "ClientLease dropped without explicit release(). \
              Using automatic release with cooldown. \
              Prefer calling lease.release().await explicitly."
         );
-        let pool = self.pool.clone();
         let index = self.index;
         let nonce_config = pool.config.nonce.clone();
 
@@ -283,14 +430,14 @@ impl std::ops::Deref for ClientLease {
 impl ClientPool {
     /// Creates a new client pool
     ///
-    /// Initializes the pool with wallets from the wallet manager.
+    /// Initializes the pool with the wallets `[wallets]` selects.
     /// Clients are created lazily on first use. Use `with_proxies()` to add proxies.
     ///
     /// # Arguments
     ///
     /// * `config` - The TempoSpammerConfig configuration object
     /// * `db` - Database manager for logging
-    /// * `wallet_password` - Optional password for wallet decryption
+    /// * `wallet_password` - Optional password for wallet decryption, wiped on drop
     ///
     /// # Returns
     ///
@@ -310,7 +457,7 @@ impl ClientPool {
     /// let pool = ClientPool::new(
     ///     config,
     ///     db,
-    ///     Some("password".to_string()),
+    ///     Some(Zeroizing::new("password".to_string())),
     /// )?;
     /// # Ok(())
     /// # }
@@ -318,10 +465,39 @@ impl ClientPool {
     pub fn new(
         config: Config,
         db: Arc<core_logic::database::DatabaseManager>,
-        wallet_password: Option<String>,
+        wallet_password: Option<Zeroizing<String>>,
         connection_semaphore_size: usize,
     ) -> Result<Self> {
-        let wallet_manager = Arc::new(WalletManager::new()?);
+        let wallet_manager = WalletManager::with_options(config.wallets.wallet_options())?;
+        Ok(Self::from_wallet_manager(
+            config,
+            db,
+            wallet_manager,
+            wallet_password,
+            connection_semaphore_size,
+        ))
+    }
+
+    /// Creates a client pool over wallets the caller already loaded
+    ///
+    /// Same as [`ClientPool::new`] without scanning `wallet-json/` and
+    /// `pv.txt`, e.g. for [`WalletManager::from_private_keys`].
+    ///
+    /// # Arguments
+    ///
+    /// * `config` - The TempoSpammerConfig configuration object
+    /// * `db` - Database manager for logging
+    /// * `wallet_manager` - The wallets to lease
+    /// * `wallet_password` - Password for encrypted wallets, `None` for raw keys
+    /// * `connection_semaphore_size` - Connections open at once across the pool
+    pub fn from_wallet_manager(
+        config: Config,
+        db: Arc<core_logic::database::DatabaseManager>,
+        wallet_manager: WalletManager,
+        wallet_password: Option<Zeroizing<String>>,
+        connection_semaphore_size: usize,
+    ) -> Self {
+        let wallet_manager = Arc::new(wallet_manager);
 
         // Initialize nonce managers
         let nonce_manager = Some(Arc::new(crate::NonceManager::new()));
@@ -338,35 +514,48 @@ impl ClientPool {
             .collect();
 
         // Initialize proxy banlist
-        let proxy_banlist = Some(crate::proxy_health::ProxyBanlist::new(10)); // 10 min ban
+        let proxy_banlist = Some(crate::proxy_health::ProxyBanlist::with_policy(
+            config.proxy.bans.policy(),
+        ));
 
         // Initialize O(1) wallet selection structures
         let total_wallets = wallet_manager.count();
         let initial_available: Vec<usize> = (0..total_wallets).collect();
         let initial_positions: HashMap<usize, usize> = (0..total_wallets).map(|i| (i, i)).collect();
 
-        Ok(Self {
+        Self {
             wallet_manager,
             wallet_password,
             clients: RwLock::new(HashMap::new()),
             http_clients: RwLock::new(HashMap::new()),
             proxies: Vec::new(), // Empty initially, use with_proxies() to add
-            config,
             locked_wallets: tokio::sync::Mutex::new(std::collections::HashSet::new()),
             nonce_manager,
             robust_nonce_manager,
             sharded_nonce_managers,
             sharded_robust_nonce_managers,
             proxy_banlist,
+            proxy_arbiter: None,
             db: Some(db),
             // O(1) optimization fields
             available_wallets: RwLock::new(initial_available),
             available_positions: RwLock::new(initial_positions),
+            wallet_selector: WalletSelector::new(config.wallets.selection),
             proxy_cache: RwLock::new(HashMap::new()),
             // Proxy rotation counter for even distribution
             proxy_rotation_counter: AtomicUsize::new(0),
+            proxy_assignments: ProxyAssignments::new(ProxyAssignmentPolicy::Rotating),
+            worker_proxies: None,
+            client_session_generations: RwLock::new(HashMap::new()),
             connection_semaphore: Arc::new(tokio::sync::Semaphore::new(connection_semaphore_size)),
-        })
+            connection_limit: connection_semaphore_size,
+            wallet_released: Notify::new(),
+            metrics: Arc::new(PoolMetrics::default()),
+            wallet_health: Arc::new(WalletHealth::new(config.wallet_health.clone())),
+            parked_wallets: std::sync::Mutex::new(BTreeSet::new()),
+            capabilities: None,
+            config,
+        }
     }
 
     /// Sets the proxies for this pool
@@ -381,11 +570,33 @@ impl ClientPool {
     /// # Returns
     ///
     /// Self with proxies configured
+    ///
+    /// Also loads the persisted wallet -> proxy assignments for the sticky and
+    /// hybrid policies, resolved against this proxy list, and the worker
+    /// bindings of the worker policy.
     pub fn with_proxies(mut self, proxies: Vec<crate::tasks::ProxyConfig>) -> Self {
+        self.proxy_assignments = ProxyAssignments::load(
+            self.config.proxy.assignment,
+            &self.config.proxy.assignment_file,
+            self.config.proxy.max_wallets_per_proxy,
+            &proxies,
+        );
+        self.worker_proxies = None;
+        if self.config.proxy.assignment == ProxyAssignmentPolicy::Worker && !proxies.is_empty() {
+            self.worker_proxies = WorkerProxies::new(&self.config.proxy.workers, proxies.len());
+            if self.worker_proxies.is_none() {
+                tracing::warn!("No proxy left to bind workers to, rotating proxies instead");
+            }
+        }
         self.proxies = proxies;
         self
     }
 
+    /// Worker to proxy bindings, with `[proxy] assignment = "worker"`
+    pub fn worker_proxies(&self) -> Option<&WorkerProxies> {
+        self.worker_proxies.as_ref()
+    }
+
     /// Sets the proxy banlist for this pool
     ///
     /// This is a builder-style method that consumes self and returns it
@@ -403,6 +614,33 @@ impl ClientPool {
         self
     }
 
+    /// Shares the proxies with other chains through a [`core_logic::ProxyArbiter`]
+    ///
+    /// The proxy list must be the one the arbiter was created for. Failing
+    /// proxies are then banned only for this chain's RPC host, after the
+    /// arbiter's failure threshold. The spammer runner takes a slot from the
+    /// arbiter before each task to keep the chain within its per-proxy quota.
+    pub fn with_proxy_arbiter(mut self, arbiter: core_logic::ChainProxies) -> Self {
+        self.proxy_arbiter = Some(arbiter);
+        self
+    }
+
+    /// Sets what the startup probe found the node to support
+    pub fn with_capabilities(
+        mut self,
+        capabilities: Option<crate::capabilities::ChainCapabilities>,
+    ) -> Self {
+        self.capabilities = capabilities;
+        self
+    }
+
+    /// What the node supports, every feature when it was not probed
+    pub fn capabilities(&self) -> crate::capabilities::ChainCapabilities {
+        self.capabilities.unwrap_or_else(|| {
+            crate::capabilities::ChainCapabilities::assumed(self.config.chain_id)
+        })
+    }
+
     /// Attempts to acquire an available client using O(1) fast path
     ///
     /// This is the primary method for acquiring clients. It uses an optimized O(1)
@@ -415,13 +653,104 @@ impl ClientPool {
     /// - `Some(ClientLease)` - A leased client ready for use
     /// - `None` - No wallets available (all in use)
     pub async fn try_acquire_client(self: &Arc<Self>) -> Option<ClientLease> {
-        // Try fast O(1) path first
-        if let Some(lease) = self.try_acquire_client_fast().await {
-            return Some(lease);
+        let started = Instant::now();
+        // Try fast O(1) path first, then fall back to the legacy O(n) path
+        let lease = match self.try_acquire_client_fast().await {
+            Some(lease) => Some(lease),
+            None => self.try_acquire_client_legacy().await,
+        };
+        if lease.is_some() {
+            self.metrics.record_lease(started.elapsed());
+        }
+        lease
+    }
+
+    /// Leases a client, waiting up to `timeout` for a connection permit and a
+    /// free wallet
+    ///
+    /// Unlike [`try_acquire_client`](Self::try_acquire_client) this parks the
+    /// caller while the pool is saturated: it queues on the connection
+    /// semaphore, then sleeps until a wallet is released (or at most
+    /// [`WAIT_RECHECK`], in case a banned proxy recovers). It never takes the
+    /// legacy path, so every lease it returns holds a connection permit.
+    ///
+    /// # Returns
+    ///
+    /// - `Some(ClientLease)` - A leased client ready for use
+    /// - `None` - The deadline passed, counted in [`PoolStats::timeouts`]
+    pub async fn acquire_client_waiting(
+        self: &Arc<Self>,
+        timeout: Duration,
+    ) -> Option<ClientLease> {
+        let started = Instant::now();
+        let deadline = tokio::time::Instant::now() + timeout;
+
+        let mut permit = match self.connection_semaphore.clone().try_acquire_owned() {
+            Ok(permit) => permit,
+            Err(_) => {
+                self.metrics.saturated.fetch_add(1, Ordering::Relaxed);
+                let waiting = self.connection_semaphore.clone().acquire_owned();
+                match tokio::time::timeout_at(deadline, waiting).await {
+                    Ok(Ok(permit)) => permit,
+                    _ => {
+                        self.metrics.timeouts.fetch_add(1, Ordering::Relaxed);
+                        return None;
+                    }
+                }
+            }
+        };
+
+        loop {
+            // Registered before looking, so a release in between still wakes us
+            let released = self.wallet_released.notified();
+            tokio::pin!(released);
+            released.as_mut().enable();
+
+            match self.lease_with_permit(permit).await {
+                Ok(lease) => {
+                    self.metrics.record_lease(started.elapsed());
+                    return Some(lease);
+                }
+                Err(returned) => permit = returned,
+            }
+
+            let now = tokio::time::Instant::now();
+            if now >= deadline {
+                self.metrics.timeouts.fetch_add(1, Ordering::Relaxed);
+                return None;
+            }
+            let _ = tokio::time::timeout_at((now + WAIT_RECHECK).min(deadline), released).await;
         }
+    }
 
-        // Fallback to legacy O(n) path if fast path fails
-        self.try_acquire_client_legacy().await
+    /// Load and acquisition figures for monitoring
+    pub async fn stats(&self) -> PoolStats {
+        let wallets_locked = self.locked_wallets.lock().await.len();
+        let wallets_available = self.available_wallets.read().await.len();
+        let metrics = &self.metrics;
+        let average = |total: &AtomicU64, count: &AtomicU64| {
+            let count = count.load(Ordering::Relaxed);
+            Duration::from_micros(
+                total
+                    .load(Ordering::Relaxed)
+                    .checked_div(count)
+                    .unwrap_or(0),
+            )
+        };
+        PoolStats {
+            permits_available: self.connection_semaphore.available_permits(),
+            permits_total: self.connection_limit,
+            wallets_locked,
+            wallets_available,
+            wallets_quarantined: self.wallet_health.quarantined().len(),
+            leases: metrics.leases.load(Ordering::Relaxed),
+            saturated: metrics.saturated.load(Ordering::Relaxed),
+            timeouts: metrics.timeouts.load(Ordering::Relaxed),
+            avg_wait: average(&metrics.wait_us, &metrics.leases),
+            max_wait: Duration::from_micros(metrics.max_wait_us.load(Ordering::Relaxed)),
+            avg_hold: average(&metrics.hold_us, &metrics.holds),
+            max_hold: Duration::from_micros(metrics.max_hold_us.load(Ordering::Relaxed)),
+        }
     }
 
     /// Fast O(1) client acquisition
@@ -433,9 +762,21 @@ impl ClientPool {
         // If pool is saturated, return None immediately to trigger backoff in worker loop
         let permit = match self.connection_semaphore.clone().try_acquire_owned() {
             Ok(p) => p,
-            Err(_) => return None,
+            Err(_) => {
+                self.metrics.saturated.fetch_add(1, Ordering::Relaxed);
+                return None;
+            }
         };
+        self.lease_with_permit(permit).await.ok()
+    }
 
+    /// Leases an available wallet under an already acquired `permit`
+    ///
+    /// Hands the permit back when no wallet could be leased.
+    async fn lease_with_permit(
+        self: &Arc<Self>,
+        permit: OwnedSemaphorePermit,
+    ) -> std::result::Result<ClientLease, OwnedSemaphorePermit> {
         // 1. Fast check: Get available count
         let available_count = {
             let available = self.available_wallets.read().await;
@@ -443,23 +784,21 @@ impl ClientPool {
         };
 
         if available_count == 0 {
-            return None;
+            return Err(permit);
         }
 
-        // 2. Random selection with retry logic for banned proxies
+        // 2. Selection with retry logic for banned proxies
         let mut retry_count = 0;
         const MAX_RETRIES: u32 = 5; // Increased from 1 to 5 for better resilience
+        let mut skipped = Vec::new();
 
         loop {
-            // Pick random wallet from available set using fast RNG
+            // Pick a wallet from the available set by the selection policy
             let (selected_wallet, random_idx) = {
                 let available = self.available_wallets.read().await;
-                if available.is_empty() {
-                    return None;
-                }
-
-                // Use fastrand for better performance (no expensive RNG initialization)
-                let idx = fastrand::usize(0..available.len());
+                let Some(idx) = self.wallet_selector.pick(&available, &skipped) else {
+                    return Err(permit);
+                };
                 (available[idx], idx)
             };
 
@@ -471,12 +810,13 @@ impl ClientPool {
                 // This prevents wallet starvation when proxies fail
                 if retry_count < MAX_RETRIES {
                     retry_count += 1;
+                    skipped.push(selected_wallet);
                     // Brief delay to let proxy recover and avoid hammering
                     tokio::time::sleep(tokio::time::Duration::from_millis(100)).await;
                     continue;
                 } else {
-                    // Max retries reached, return None to try legacy path
-                    return None;
+                    // Max retries reached, give up so the caller can try the legacy path
+                    return Err(permit);
                 }
             }
 
@@ -487,19 +827,14 @@ impl ClientPool {
                     retry_count += 1;
                     continue;
                 } else {
-                    return None;
+                    return Err(permit);
                 }
             }
 
             // 5. Create/get client
             match self.get_or_create_client(selected_wallet).await {
                 Ok(client) => {
-                    return Some(ClientLease {
-                        client,
-                        index: selected_wallet,
-                        pool: self.clone(),
-                        permit: Some(permit),
-                    });
+                    return Ok(self.lease(client, selected_wallet, true, Some(permit)));
                 }
                 Err(e) => {
                     tracing::error!(
@@ -508,12 +843,46 @@ impl ClientPool {
                         e
                     );
                     self.unlock_wallet_fast(selected_wallet).await;
-                    return None;
+                    return Err(permit);
                 }
             }
         }
     }
 
+    /// Client for a wallet the calling worker owns in partitioned mode
+    ///
+    /// Skips the locked and available sets entirely; the caller must be the
+    /// only one using `wallet_idx`, which [`partition`] guarantees between
+    /// workers. Releasing the lease gives nothing back to the pool.
+    ///
+    /// # Returns
+    ///
+    /// - `Some(ClientLease)` - The wallet's client
+    /// - `None` - Connection limit reached, the wallet's proxy is banned, or
+    ///   the client could not be created
+    pub async fn acquire_owned_client(self: &Arc<Self>, wallet_idx: usize) -> Option<ClientLease> {
+        let started = Instant::now();
+        let Ok(permit) = self.connection_semaphore.clone().try_acquire_owned() else {
+            self.metrics.saturated.fetch_add(1, Ordering::Relaxed);
+            return None;
+        };
+        if self.wallet_health.is_quarantined(wallet_idx)
+            || !self.check_proxy_cached(wallet_idx).await
+        {
+            return None;
+        }
+        match self.get_or_create_client(wallet_idx).await {
+            Ok(client) => {
+                self.metrics.record_lease(started.elapsed());
+                Some(self.lease(client, wallet_idx, false, Some(permit)))
+            }
+            Err(e) => {
+                tracing::error!("Failed to create client for wallet {}: {}", wallet_idx, e);
+                None
+            }
+        }
+    }
+
     /// Legacy O(n) client acquisition (kept as fallback)
     ///
     /// Scans all wallets linearly. Slower but handles edge cases.
@@ -538,14 +907,14 @@ impl ClientPool {
 
         // Filter by proxy health - check if ANY proxy is available for this wallet
         // With rotating proxy assignment, wallets can use any healthy proxy
-        if let Some(ref banlist) = self.proxy_banlist {
+        if self.proxy_banlist.is_some() || self.proxy_arbiter.is_some() {
             // Check if at least one proxy is healthy
             let mut has_healthy_proxy = self.proxies.is_empty(); // true if no proxies
 
             if !has_healthy_proxy {
                 // Check if any proxy is not banned
                 for idx in 0..self.proxies.len() {
-                    if !banlist.is_banned(idx).await {
+                    if !self.is_proxy_banned(idx).await {
                         has_healthy_proxy = true;
                         break;
                     }
@@ -559,12 +928,22 @@ impl ClientPool {
             // Don't filter wallets - with rotation they can use any healthy proxy
         }
 
+        // Sticky wallets whose pinned proxy is banned must wait for it to recover
+        if self.proxy_assignments.policy() == ProxyAssignmentPolicy::Sticky {
+            let mut usable = Vec::with_capacity(available.len());
+            for wallet_idx in available {
+                if self.check_proxy_cached(wallet_idx).await {
+                    usable.push(wallet_idx);
+                }
+            }
+            available = usable;
+        }
+
         if available.is_empty() {
             return None;
         }
 
-        // Random selection using fastrand
-        let selected_idx = available[fastrand::usize(0..available.len())];
+        let selected_idx = available[self.wallet_selector.pick(&available, &[])?];
 
         // Lock the wallet
         let mut locked = self.locked_wallets.lock().await;
@@ -577,14 +956,9 @@ impl ClientPool {
         let client = self.get_or_create_client(selected_idx).await;
 
         match client {
-            Ok(client) => Some(ClientLease {
-                client,
-                index: selected_idx,
-                pool: self.clone(),
-                // Legacy path doesn't limit connections strictly, or acquire explicitly here if needed
-                // For now we can assume fast path is primary
-                permit: None,
-            }),
+            // Legacy path doesn't limit connections strictly, or acquire explicitly here if needed
+            // For now we can assume fast path is primary
+            Ok(client) => Some(self.lease(client, selected_idx, true, None)),
             Err(e) => {
                 // Failed to create client, release the lock
                 tracing::error!("Failed to create client for wallet {}: {}", selected_idx, e);
@@ -594,45 +968,62 @@ impl ClientPool {
         }
     }
 
+    /// Wraps `client` in a lease that returns `index` to the pool when `pooled`
+    fn lease(
+        self: &Arc<Self>,
+        client: TempoClient,
+        index: usize,
+        pooled: bool,
+        permit: Option<OwnedSemaphorePermit>,
+    ) -> ClientLease {
+        if pooled {
+            self.wallet_selector.leased(index, Instant::now());
+        }
+        ClientLease {
+            client,
+            index,
+            pool: pooled.then(|| self.clone()),
+            permit,
+            acquired_at: Instant::now(),
+            metrics: self.metrics.clone(),
+        }
+    }
+
     /// Gets an existing client from cache or creates a new one
     async fn get_or_create_client(&self, wallet_idx: usize) -> Result<TempoClient> {
-        // Check cache first
-        {
+        // Check cache first; a stale client still lends its signer
+        let cached_signer = {
             let clients = self.clients.read().await;
-            if let Some(client) = clients.get(&wallet_idx) {
-                return Ok(client.clone());
+            match clients.get(&wallet_idx) {
+                Some(client) => {
+                    if !self.should_move_proxy(client).await
+                        && !self.session_expired(wallet_idx, client).await
+                    {
+                        return Ok(client.clone());
+                    }
+                    Some(client.signer.clone())
+                }
+                None => None,
             }
-        }
+        };
 
         // Need to create a new client
-        let wallet = self
-            .wallet_manager
-            .get_wallet(wallet_idx, self.wallet_password.as_deref())
-            .await
-            .map_err(|e| anyhow::anyhow!("Failed to get wallet {}: {}", wallet_idx, e))?;
+        let signer = match cached_signer {
+            Some(signer) => signer,
+            None => self.wallet_signer(wallet_idx).await?,
+        };
 
         // Phase 2: Atomic proxy selection - calculate once, use everywhere
         // This prevents race conditions where proxy_idx changes between selection and client creation
-        let proxy_idx = if self.proxies.is_empty() {
-            None
-        } else {
-            // Use atomic counter for round-robin selection
-            let idx =
-                self.proxy_rotation_counter.fetch_add(1, Ordering::SeqCst) % self.proxies.len();
-            Some(idx)
-        };
+        let proxy_idx = self.select_proxy(wallet_idx).await;
 
         let proxy_config = proxy_idx.map(|idx| &self.proxies[idx]);
+        let session_generation = proxy_config.map(|p| p.session_generation());
 
         // Get or create HTTP client for this proxy configuration
         // Try to create client with proxy first, fallback to direct connection
         let (client, used_proxy_idx) = match self
-            .try_create_client_with_fallback(
-                wallet_idx,
-                &wallet.evm_private_key,
-                proxy_idx,
-                proxy_config,
-            )
+            .try_create_client_with_fallback(wallet_idx, &signer, proxy_idx, proxy_config)
             .await
         {
             Ok((c, idx)) => (c, idx),
@@ -653,6 +1044,18 @@ impl ClientPool {
         // Update proxy_idx_for_client to reflect what was actually used
         let proxy_idx_for_client = used_proxy_idx;
 
+        {
+            let mut generations = self.client_session_generations.write().await;
+            match (used_proxy_idx, session_generation) {
+                (Some(_), Some(generation)) => {
+                    generations.insert(wallet_idx, generation);
+                }
+                _ => {
+                    generations.remove(&wallet_idx);
+                }
+            }
+        }
+
         // Cache the client
         let mut clients = self.clients.write().await;
         clients.insert(wallet_idx, client.clone());
@@ -660,6 +1063,159 @@ impl ClientPool {
         Ok(client)
     }
 
+    /// Picks the proxy for a new client according to the assignment policy
+    ///
+    /// Rotating uses the round-robin counter. Sticky and hybrid reuse the
+    /// wallet's pinned proxy; hybrid re-pins when that proxy is banned, and
+    /// new pins prefer healthy proxies in rotation order.
+    async fn select_proxy(&self, wallet_idx: usize) -> Option<usize> {
+        if self.proxies.is_empty() {
+            return None;
+        }
+
+        if let Some(workers) = &self.worker_proxies {
+            return Some(self.worker_proxy(workers).await);
+        }
+
+        if !self.proxy_assignments.is_pinned() {
+            // Use atomic counter for round-robin selection
+            return Some(
+                self.proxy_rotation_counter.fetch_add(1, Ordering::SeqCst) % self.proxies.len(),
+            );
+        }
+
+        if let Some(pinned) = self.proxy_assignments.get(wallet_idx) {
+            let keep = self.proxy_assignments.policy() == ProxyAssignmentPolicy::Sticky
+                || !self.is_proxy_banned(pinned).await;
+            if keep {
+                return Some(pinned);
+            }
+            tracing::info!(
+                "Wallet {} moving off banned proxy {} (hybrid assignment)",
+                wallet_idx,
+                pinned
+            );
+        }
+
+        let start = self.proxy_rotation_counter.fetch_add(1, Ordering::SeqCst);
+        let mut candidates = Vec::with_capacity(self.proxies.len());
+        for offset in 0..self.proxies.len() {
+            let idx = (start + offset) % self.proxies.len();
+            if !self.is_proxy_banned(idx).await {
+                candidates.push(idx);
+            }
+        }
+        if candidates.is_empty() {
+            // Everything banned: pin anyway so the wallet keeps a stable identity
+            candidates.push(start % self.proxies.len());
+        }
+
+        self.proxy_assignments.assign(wallet_idx, &candidates)
+    }
+
+    /// Proxy of the calling worker under the worker policy
+    ///
+    /// Its own proxy, or the first healthy backup while that one is banned;
+    /// with every candidate banned it stays on its own. Callers outside a
+    /// worker, such as startup checks, use worker 0's.
+    async fn worker_proxy(&self, workers: &WorkerProxies) -> usize {
+        let worker = current_worker().unwrap_or(0);
+        for idx in workers.candidates(worker) {
+            if !self.is_proxy_banned(idx).await {
+                return idx;
+            }
+        }
+        workers.bound(worker)
+    }
+
+    /// Whether a cached client must be rebuilt on a different proxy
+    ///
+    /// Hybrid clients move off a banned proxy; under the worker policy a
+    /// client is rebuilt whenever it is not on the calling worker's proxy.
+    async fn should_move_proxy(&self, client: &TempoClient) -> bool {
+        if let Some(workers) = &self.worker_proxies {
+            return client.proxy_index != Some(self.worker_proxy(workers).await);
+        }
+        if self.proxy_assignments.policy() != ProxyAssignmentPolicy::Hybrid {
+            return false;
+        }
+        match client.proxy_index {
+            Some(idx) => self.is_proxy_banned(idx).await,
+            None => false,
+        }
+    }
+
+    /// Whether a cached client's proxy session was rotated or regenerated
+    async fn session_expired(&self, wallet_idx: usize, client: &TempoClient) -> bool {
+        let Some(proxy) = client.proxy_index.and_then(|idx| self.proxies.get(idx)) else {
+            return false;
+        };
+        if proxy.session.is_none() {
+            return false;
+        }
+        let built_with = self
+            .client_session_generations
+            .read()
+            .await
+            .get(&wallet_idx)
+            .copied();
+        built_with != Some(proxy.session_generation())
+    }
+
+    /// Reports a failing proxy
+    ///
+    /// Proxies with `{session}` credentials are not banned: their session is
+    /// regenerated so clients rebuilt on them get a new exit IP. Other
+    /// proxies count towards a ban on the banlist, escalating with each ban,
+    /// or towards a ban for this chain's RPC host when the pool is shared
+    /// through a proxy arbiter.
+    pub async fn ban_proxy(&self, proxy_idx: usize) {
+        if let Some(session) = self.proxies.get(proxy_idx).and_then(|p| p.session.as_ref()) {
+            let generation = session.regenerate();
+            tracing::info!(
+                "Proxy {} failed, regenerated its session (generation {}) instead of banning",
+                proxy_idx,
+                generation
+            );
+            return;
+        }
+
+        if let Some(ref arbiter) = self.proxy_arbiter {
+            arbiter.record_failure(proxy_idx);
+            return;
+        }
+
+        if let Some(ref banlist) = self.proxy_banlist {
+            banlist.record_failure(proxy_idx).await;
+        }
+    }
+
+    /// Reports a request that went through a proxy
+    ///
+    /// Clears the proxy's failure count, on the proxy arbiter for this
+    /// chain's host or on the banlist, where it also decays ban severity.
+    pub async fn record_proxy_success(&self, proxy_idx: usize) {
+        if let Some(ref arbiter) = self.proxy_arbiter {
+            arbiter.record_success(proxy_idx);
+            return;
+        }
+        if let Some(ref banlist) = self.proxy_banlist {
+            banlist.record_success(proxy_idx).await;
+        }
+    }
+
+    async fn is_proxy_banned(&self, proxy_idx: usize) -> bool {
+        if let Some(ref arbiter) = self.proxy_arbiter {
+            if arbiter.is_banned(proxy_idx) {
+                return true;
+            }
+        }
+        match self.proxy_banlist {
+            Some(ref banlist) => banlist.is_banned(proxy_idx).await,
+            None => false,
+        }
+    }
+
     /// Get the appropriate nonce manager for a wallet index
     ///
     /// Returns sharded manager if per_wallet is enabled, otherwise returns shared manager
@@ -687,11 +1243,32 @@ impl ClientPool {
         }
     }
 
+    /// Decrypts a wallet just long enough to build its signer
+    ///
+    /// The decrypted wallet skips the wallet manager's cache and is zeroized
+    /// on return; the key lives on only inside the signer.
+    async fn wallet_signer(&self, wallet_idx: usize) -> Result<PrivateKeySigner> {
+        let wallet = self
+            .wallet_manager
+            .load_wallet(
+                wallet_idx,
+                self.wallet_password.as_deref().map(String::as_str),
+            )
+            .await
+            .map_err(|e| anyhow::anyhow!("Failed to get wallet {}: {}", wallet_idx, e))?;
+        let signer: PrivateKeySigner = wallet
+            .evm_private_key
+            .parse()
+            .with_context(|| format!("Failed to parse private key of wallet {}", wallet_idx))?;
+        // Refuses to sign for any other chain
+        Ok(signer.with_chain_id(Some(self.config.chain_id)))
+    }
+
     /// Try to create client with proxy, fallback to direct connection on failure
     async fn try_create_client_with_fallback(
         &self,
         wallet_idx: usize,
-        private_key: &str,
+        signer: &PrivateKeySigner,
         proxy_idx: Option<usize>,
         proxy_config: Option<&crate::tasks::ProxyConfig>,
     ) -> Result<(TempoClient, Option<usize>)> {
@@ -706,9 +1283,9 @@ impl ClientPool {
                 .await
             {
                 Ok(reqwest_client) => {
-                    match TempoClient::new_from_reqwest(
+                    match TempoClient::new_from_signer(
                         &self.config.rpc_url,
-                        private_key,
+                        signer.clone(),
                         reqwest_client,
                         Some(config.clone()),
                         proxy_idx,
@@ -728,9 +1305,7 @@ impl ClientPool {
                                 e
                             );
                             if let Some(idx) = proxy_idx {
-                                if let Some(ref banlist) = self.proxy_banlist {
-                                    banlist.ban(idx).await;
-                                }
+                                self.ban_proxy(idx).await;
                             }
                         }
                     }
@@ -745,12 +1320,31 @@ impl ClientPool {
             }
         }
 
+        // Sticky wallets never leave their proxy, not even for a direct connection
+        if proxy_config.is_some()
+            && self.proxy_assignments.policy() == ProxyAssignmentPolicy::Sticky
+        {
+            anyhow::bail!(
+                "Pinned proxy {:?} unavailable for sticky wallet {}",
+                proxy_idx,
+                wallet_idx
+            );
+        }
+        // Nor do bound workers, whose exit IP must not change
+        if proxy_config.is_some() && self.worker_proxies.is_some() {
+            anyhow::bail!(
+                "Worker proxy {:?} unavailable for wallet {}",
+                proxy_idx,
+                wallet_idx
+            );
+        }
+
         // Second attempt: Direct connection (no proxy)
         tracing::info!("Using direct connection for wallet {}", wallet_idx);
         let direct_client = self.get_or_create_http_client(None).await?;
-        let client = TempoClient::new_from_reqwest(
+        let client = TempoClient::new_from_signer(
             &self.config.rpc_url,
-            private_key,
+            signer.clone(),
             direct_client,
             None,
             None,
@@ -769,10 +1363,26 @@ impl ClientPool {
         &self,
         proxy_url: Option<String>,
     ) -> Result<reqwest::Client> {
+        let proxy_config = proxy_url
+            .as_ref()
+            .and_then(|url| self.proxies.iter().find(|p| p.url == *url));
+
+        // Templated proxies are cached per session generation, or not at all
+        // when every connection gets its own session
+        let cache_key = match proxy_config.and_then(|p| p.session.as_ref()) {
+            Some(session) => match session.rotation() {
+                SessionRotation::PerConnection => None,
+                SessionRotation::Interval(_) => proxy_url
+                    .as_ref()
+                    .map(|url| Some(format!("{}#{}", url, session.generation()))),
+            },
+            None => Some(proxy_url.clone()),
+        };
+
         // Check cache first
-        {
+        if let Some(ref key) = cache_key {
             let http_clients = self.http_clients.read().await;
-            if let Some(client) = http_clients.get(&proxy_url) {
+            if let Some(client) = http_clients.get(key) {
                 return Ok(client.clone());
             }
         }
@@ -785,19 +1395,16 @@ impl ClientPool {
             .pool_max_idle_per_host(10); // Limit connections per proxy to prevent exhaustion
 
         // Configure proxy if specified
-        if let Some(ref url) = proxy_url {
-            if let Some(proxy_config) = self.proxies.iter().find(|p| p.url == *url) {
-                let proxy = reqwest::Proxy::all(url)
-                    .with_context(|| format!("Failed to create proxy for URL: {}", url))?;
-
-                if let (Some(username), Some(password)) =
-                    (&proxy_config.username, &proxy_config.password)
-                {
-                    let proxy = proxy.basic_auth(username, password);
-                    client_builder = client_builder.proxy(proxy);
-                } else {
-                    client_builder = client_builder.proxy(proxy);
-                }
+        if let Some(proxy_config) = proxy_config {
+            let url = &proxy_config.url;
+            let proxy = reqwest::Proxy::all(url)
+                .with_context(|| format!("Failed to create proxy for URL: {}", url))?;
+
+            if let Some((username, password)) = proxy_config.basic_auth() {
+                let proxy = proxy.basic_auth(&username, &password);
+                client_builder = client_builder.proxy(proxy);
+            } else {
+                client_builder = client_builder.proxy(proxy);
             }
         }
 
@@ -829,9 +1436,20 @@ impl ClientPool {
             }
         });
 
-        // Cache the HTTP client
-        let mut http_clients = self.http_clients.write().await;
-        http_clients.insert(proxy_url, client.clone());
+        // Cache the HTTP client, dropping clients of expired sessions
+        if let Some(key) = cache_key {
+            let mut http_clients = self.http_clients.write().await;
+            if let (Some(new_key), Some(url)) =
+                (key.as_ref().filter(|_| key != proxy_url), &proxy_url)
+            {
+                let prefix = format!("{}#", url);
+                http_clients.retain(|k, _| {
+                    k.as_ref()
+                        .is_none_or(|k| !k.starts_with(&prefix) || k == new_key)
+                });
+            }
+            http_clients.insert(key, client.clone());
+        }
 
         Ok(client)
     }
@@ -853,10 +1471,45 @@ impl ClientPool {
     ///
     /// Uses O(1) fast path for adding back to available set.
     pub async fn release_wallet(&self, index: usize) {
+        // Quarantined wallets stay locked until recheck_quarantined() lets them go
+        if self.wallet_health.is_quarantined(index) {
+            self.parked_wallets.lock().unwrap().insert(index);
+            return;
+        }
         // Use O(1) fast unlock
         self.unlock_wallet_fast(index).await;
     }
 
+    /// Health scores of the pool's wallets
+    pub fn wallet_health(&self) -> Arc<WalletHealth> {
+        self.wallet_health.clone()
+    }
+
+    /// Returns quarantined wallets whose recheck time has passed to the
+    /// available set
+    ///
+    /// # Returns
+    ///
+    /// The number of wallets returned
+    pub async fn recheck_quarantined(&self) -> usize {
+        let recovered: Vec<usize> = {
+            let mut parked = self.parked_wallets.lock().unwrap();
+            let recovered: Vec<usize> = parked
+                .iter()
+                .copied()
+                .filter(|index| !self.wallet_health.is_quarantined(*index))
+                .collect();
+            for index in &recovered {
+                parked.remove(index);
+            }
+            recovered
+        };
+        for index in &recovered {
+            self.unlock_wallet_fast(*index).await;
+        }
+        recovered.len()
+    }
+
     /// Returns the number of available (non-locked) wallets
     ///
     /// Useful for monitoring pool saturation and load balancing decisions.
@@ -890,24 +1543,63 @@ impl ClientPool {
         self.total_count()
     }
 
+    /// Tags of a wallet, from its file and `wallet-tags.json`
+    pub fn wallet_tags(&self, wallet_idx: usize) -> &[String] {
+        self.wallet_manager.tags(wallet_idx)
+    }
+
+    /// Number of wallets in the pool carrying each tag
+    pub fn tag_counts(&self) -> BTreeMap<String, usize> {
+        let mut counts = BTreeMap::new();
+        for wallet_idx in 0..self.wallet_manager.count() {
+            for tag in self.wallet_manager.tags(wallet_idx) {
+                *counts.entry(tag.clone()).or_insert(0) += 1;
+            }
+        }
+        counts
+    }
+
+    /// Returns the proxies clients are spread over, indexed like `proxy_index`
+    pub fn proxies(&self) -> &[crate::tasks::ProxyConfig] {
+        &self.proxies
+    }
+
     // === O(1) Wallet Selection Helper Methods ===
 
     /// Check proxy health with 30-second caching
     ///
     /// Returns true if any proxy is available (not banned or no proxy)
-    /// With rotating proxy assignment, we check if there are ANY healthy proxies
-    async fn check_proxy_cached(&self, _wallet_idx: usize) -> bool {
+    /// With rotating proxy assignment, we check if there are ANY healthy proxies.
+    /// A sticky wallet is only usable while its own pinned proxy is healthy,
+    /// a bound worker only while its proxy or one of the backups is.
+    async fn check_proxy_cached(&self, wallet_idx: usize) -> bool {
         if self.proxies.is_empty() {
             return true; // No proxy = always available
         }
 
+        if self.proxy_assignments.policy() == ProxyAssignmentPolicy::Sticky {
+            if let Some(pinned) = self.proxy_assignments.get(wallet_idx) {
+                return !self.is_proxy_banned(pinned).await;
+            }
+        }
+
+        // A bound worker waits while its own proxy and every backup are banned
+        if let Some(workers) = &self.worker_proxies {
+            for idx in workers.candidates(current_worker().unwrap_or(0)) {
+                if !self.is_proxy_banned(idx).await {
+                    return true;
+                }
+            }
+            return false;
+        }
+
         // With rotating assignment, check if at least one proxy is healthy
         // The rotation logic will skip banned proxies automatically
-        if let Some(ref banlist) = self.proxy_banlist {
+        if self.proxy_banlist.is_some() || self.proxy_arbiter.is_some() {
             // Check if any proxy is healthy (not banned)
             let mut has_healthy_proxy = false;
             for idx in 0..self.proxies.len() {
-                if !banlist.is_banned(idx).await {
+                if !self.is_proxy_banned(idx).await {
                     has_healthy_proxy = true;
                     break;
                 }
@@ -1007,6 +1699,7 @@ impl ClientPool {
             available.push(wallet_idx);
             positions.insert(wallet_idx, new_pos);
         }
+        self.wallet_released.notify_waiters();
     }
 
     /// Gets a client by wallet index
@@ -1057,12 +1750,17 @@ impl ClientPool {
             anyhow::bail!("Wallet index {} out of bounds", wallet_idx);
         }
 
-        // Get wallet
-        let wallet = self
-            .wallet_manager
-            .get_wallet(wallet_idx, self.wallet_password.as_deref())
+        // Reuse the cached client's signer rather than decrypting again
+        let cached_signer = self
+            .clients
+            .read()
             .await
-            .map_err(|e| anyhow::anyhow!("Failed to get wallet {}: {}", wallet_idx, e))?;
+            .get(&wallet_idx)
+            .map(|client| client.signer.clone());
+        let signer = match cached_signer {
+            Some(signer) => signer,
+            None => self.wallet_signer(wallet_idx).await?,
+        };
 
         // Select a different proxy (rotate by using wallet_idx + offset)
         // Ensure offset is non-zero if possible to actually rotate
@@ -1084,10 +1782,8 @@ impl ClientPool {
             let proxy = reqwest::Proxy::all(&proxy_config.url)
                 .with_context(|| format!("Failed to create proxy for URL: {}", proxy_config.url))?;
 
-            if let (Some(username), Some(password)) =
-                (&proxy_config.username, &proxy_config.password)
-            {
-                let proxy = proxy.basic_auth(username, password);
+            if let Some((username, password)) = proxy_config.basic_auth() {
+                let proxy = proxy.basic_auth(&username, &password);
                 client_builder = client_builder.proxy(proxy);
             } else {
                 client_builder = client_builder.proxy(proxy);
@@ -1099,9 +1795,9 @@ impl ClientPool {
             .context("Failed to build reqwest client")?;
 
         // Create the TempoClient
-        let client = TempoClient::new_from_reqwest(
+        let client = TempoClient::new_from_signer(
             &self.config.rpc_url,
-            &wallet.evm_private_key,
+            signer,
             reqwest_client,
             proxy_config.cloned(),
             proxy_config.map(|_| (wallet_idx + rotation_offset) % self.proxies.len()),
@@ -1120,3 +1816,352 @@ impl ClientPool {
         Ok(client)
     }
 }
+
+/// Picks the available wallet to lease next, by `[wallets] selection`
+///
+/// Least-recently-used compares the last-lease timestamps this records and
+/// round-robin a cursor past the last leased index; both scan the available
+/// wallets. Partitioned workers do not go through it.
+#[derive(Debug)]
+pub struct WalletSelector {
+    policy: WalletSelection,
+    last_leased: std::sync::Mutex<HashMap<usize, Instant>>,
+    /// The wallet index after the last one leased
+    cursor: AtomicUsize,
+}
+
+impl WalletSelector {
+    pub fn new(policy: WalletSelection) -> Self {
+        Self {
+            policy,
+            last_leased: std::sync::Mutex::new(HashMap::new()),
+            cursor: AtomicUsize::new(0),
+        }
+    }
+
+    pub fn policy(&self) -> WalletSelection {
+        self.policy
+    }
+
+    /// Position in `available` of the wallet to lease, passing over `skip`
+    ///
+    /// `None` when every available wallet is skipped.
+    pub fn pick(&self, available: &[usize], skip: &[usize]) -> Option<usize> {
+        let candidates = available
+            .iter()
+            .copied()
+            .enumerate()
+            .filter(|(_, wallet)| !skip.contains(wallet));
+        match self.policy {
+            WalletSelection::Random if skip.is_empty() => {
+                (!available.is_empty()).then(|| fastrand::usize(0..available.len()))
+            }
+            WalletSelection::Random => {
+                let positions: Vec<usize> = candidates.map(|(pos, _)| pos).collect();
+                (!positions.is_empty()).then(|| positions[fastrand::usize(0..positions.len())])
+            }
+            WalletSelection::LeastRecentlyUsed => {
+                let last_leased = self.last_leased.lock().unwrap();
+                // `None` sorts first: wallets never leased go before the rest
+                candidates
+                    .min_by_key(|(_, wallet)| (last_leased.get(wallet).copied(), *wallet))
+                    .map(|(pos, _)| pos)
+            }
+            WalletSelection::RoundRobin => {
+                let cursor = self.cursor.load(Ordering::Relaxed);
+                candidates
+                    .min_by_key(|(_, wallet)| (*wallet < cursor, *wallet))
+                    .map(|(pos, _)| pos)
+            }
+        }
+    }
+
+    /// Records that `wallet_idx` was leased at `now`
+    pub fn leased(&self, wallet_idx: usize, now: Instant) {
+        self.last_leased.lock().unwrap().insert(wallet_idx, now);
+        self.cursor.store(wallet_idx + 1, Ordering::Relaxed);
+    }
+}
+
+/// Wallet indices worker `worker_id` of `worker_count` owns out of `total`
+///
+/// Slices are contiguous and differ in length by at most one; workers past
+/// `total` get an empty slice.
+pub fn partition(worker_id: u64, worker_count: u64, total: usize) -> Range<usize> {
+    let workers = worker_count.max(1) as usize;
+    let worker = (worker_id as usize).min(workers);
+    let (base, extra) = (total / workers, total % workers);
+    let start = worker * base + worker.min(extra);
+    let len = if worker < extra { base + 1 } else { base };
+    start..(start + len).min(total)
+}
+
+/// A worker's own slice of wallets in partitioned mode
+///
+/// Hands the wallets out round-robin and skips those used less than the
+/// cooldown ago, the worker-local counterpart of the pool's release delay.
+#[derive(Debug, Clone)]
+pub struct WalletPartition {
+    wallets: Range<usize>,
+    cooldown: Duration,
+    next: usize,
+    resting_until: Vec<Option<Instant>>,
+}
+
+impl WalletPartition {
+    pub fn new(worker_id: u64, worker_count: u64, total: usize, cooldown: Duration) -> Self {
+        let wallets = partition(worker_id, worker_count, total);
+        Self {
+            resting_until: vec![None; wallets.len()],
+            wallets,
+            cooldown,
+            next: 0,
+        }
+    }
+
+    /// Wallet indices this worker owns
+    pub fn wallets(&self) -> Range<usize> {
+        self.wallets.clone()
+    }
+
+    /// The next owned wallet that is not cooling down at `now`
+    pub fn next_ready(&mut self, now: Instant) -> Option<usize> {
+        let len = self.wallets.len();
+        for step in 0..len {
+            let slot = (self.next + step) % len;
+            if self.resting_until[slot].is_none_or(|until| until <= now) {
+                self.next = (slot + 1) % len;
+                return Some(self.wallets.start + slot);
+            }
+        }
+        None
+    }
+
+    /// Starts the cooldown of `wallet_idx` at `now`
+    pub fn rest(&mut self, wallet_idx: usize, now: Instant) {
+        if self.wallets.contains(&wallet_idx) {
+            self.resting_until[wallet_idx - self.wallets.start] = Some(now + self.cooldown);
+        }
+    }
+
+    /// Leases the next ready wallet from `pool` without any shared locking
+    pub async fn acquire(&mut self, pool: &Arc<ClientPool>) -> Option<ClientLease> {
+        let wallet_idx = self.next_ready(Instant::now())?;
+        pool.acquire_owned_client(wallet_idx).await
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    #[test]
+    fn test_partitions_cover_wallets_once() {
+        for (workers, total) in [(1, 5), (3, 10), (4, 4), (6, 4), (7, 100)] {
+            let mut covered = Vec::new();
+            for worker in 0..workers {
+                let slice = partition(worker, workers, total);
+                assert!(slice.len() <= total.div_ceil(workers as usize));
+                covered.extend(slice);
+            }
+            assert_eq!(
+                covered,
+                (0..total).collect::<Vec<_>>(),
+                "{}/{}",
+                workers,
+                total
+            );
+        }
+        assert_eq!(partition(1, 3, 10), 4..7);
+        assert!(partition(5, 6, 4).is_empty());
+    }
+
+    #[test]
+    fn test_partition_skips_resting_wallets() {
+        let start = Instant::now();
+        let mut partition = WalletPartition::new(1, 2, 6, Duration::from_secs(2));
+        assert_eq!(partition.wallets(), 3..6);
+
+        assert_eq!(partition.next_ready(start), Some(3));
+        partition.rest(3, start);
+        assert_eq!(partition.next_ready(start), Some(4));
+        partition.rest(4, start);
+        assert_eq!(partition.next_ready(start), Some(5));
+        partition.rest(5, start);
+        assert_eq!(partition.next_ready(start), None);
+        // Wallets of other workers are never handed out
+        partition.rest(0, start);
+
+        let later = start + Duration::from_secs(2);
+        assert_eq!(partition.next_ready(later), Some(3));
+        assert!(
+            WalletPartition::new(2, 3, 2, Duration::ZERO)
+                .next_ready(start)
+                .is_none()
+        );
+    }
+
+    #[test]
+    fn test_least_recently_used_selection() {
+        let selector = WalletSelector::new(WalletSelection::LeastRecentlyUsed);
+        let start = Instant::now();
+        let available = [2, 0, 1];
+        // Never leased first, lowest index on ties
+        assert_eq!(selector.pick(&available, &[]), Some(1));
+        selector.leased(0, start);
+        selector.leased(1, start + Duration::from_secs(1));
+        assert_eq!(selector.pick(&available, &[]), Some(0));
+        selector.leased(2, start + Duration::from_secs(2));
+        assert_eq!(selector.pick(&available, &[]), Some(1));
+        assert_eq!(selector.pick(&available, &[0]), Some(2));
+        assert_eq!(selector.pick(&available, &[0, 1, 2]), None);
+        assert_eq!(selector.pick(&[], &[]), None);
+    }
+
+    #[test]
+    fn test_round_robin_selection() {
+        let selector = WalletSelector::new(WalletSelection::RoundRobin);
+        let available = [3, 1, 2, 0];
+        assert_eq!(available[selector.pick(&available, &[]).unwrap()], 0);
+        selector.leased(0, Instant::now());
+        assert_eq!(available[selector.pick(&available, &[]).unwrap()], 1);
+        // Leased wallets are out of the available set; the cursor moves on
+        selector.leased(2, Instant::now());
+        assert_eq!(available[selector.pick(&available, &[]).unwrap()], 3);
+        selector.leased(3, Instant::now());
+        assert_eq!(available[selector.pick(&available, &[]).unwrap()], 0);
+        assert_eq!(available[selector.pick(&available, &[0]).unwrap()], 1);
+    }
+
+    #[test]
+    fn test_fair_selection_spreads_leases_evenly() {
+        for policy in [
+            WalletSelection::LeastRecentlyUsed,
+            WalletSelection::RoundRobin,
+        ] {
+            let selector = WalletSelector::new(policy);
+            let mut available: Vec<usize> = (0..10).collect();
+            let mut leases = [0u32; 10];
+            let mut now = Instant::now();
+            for _ in 0..100 {
+                let wallet = available.swap_remove(selector.pick(&available, &[]).unwrap());
+                selector.leased(wallet, now);
+                leases[wallet] += 1;
+                available.push(wallet);
+                now += Duration::from_millis(1);
+            }
+            assert_eq!(leases, [10; 10], "{:?}", policy);
+        }
+
+        let selector = WalletSelector::new(WalletSelection::Random);
+        assert_eq!(selector.pick(&[4, 5], &[4]), Some(1));
+    }
+
+    /// Pool over the test wallet with its client already cached, so leasing
+    /// needs no network
+    async fn mock_pool(connections: usize) -> (crate::testing::TaskHarness, Arc<ClientPool>) {
+        let harness = crate::testing::TaskHarness::new().with_db().await.unwrap();
+        let pool = ClientPool::from_wallet_manager(
+            harness.config.clone(),
+            harness.db.clone().unwrap(),
+            WalletManager::from_private_keys([crate::testing::TEST_PRIVATE_KEY]),
+            None,
+            connections,
+        );
+        pool.clients.write().await.insert(0, harness.mock.client());
+        (harness, Arc::new(pool))
+    }
+
+    #[tokio::test]
+    async fn test_waiting_acquire_times_out_and_wakes_on_release() {
+        let (_harness, pool) = mock_pool(2).await;
+        let lease = pool
+            .acquire_client_waiting(Duration::from_secs(1))
+            .await
+            .unwrap();
+        assert!(pool.try_acquire_client_fast().await.is_none());
+
+        // The only wallet is leased: waiting gives up at the deadline
+        assert!(
+            pool.acquire_client_waiting(Duration::from_millis(20))
+                .await
+                .is_none()
+        );
+        let stats = pool.stats().await;
+        assert_eq!((stats.leases, stats.timeouts), (1, 1));
+        assert_eq!((stats.wallets_locked, stats.wallets_available), (1, 0));
+        assert_eq!((stats.permits_available, stats.permits_total), (1, 2));
+
+        // A release wakes the waiter long before its deadline
+        let started = Instant::now();
+        tokio::spawn(async move {
+            tokio::time::sleep(Duration::from_millis(20)).await;
+            lease.release_immediate().await;
+        });
+        let lease = pool
+            .acquire_client_waiting(Duration::from_secs(5))
+            .await
+            .unwrap();
+        assert!(started.elapsed() < WAIT_RECHECK);
+        assert_eq!(lease.index, 0);
+
+        let stats = pool.stats().await;
+        assert_eq!(stats.leases, 2);
+        assert!(stats.max_hold >= Duration::from_millis(20));
+        assert!(stats.max_wait >= Duration::from_millis(20));
+    }
+
+    #[tokio::test]
+    async fn test_waiting_acquire_queues_on_connection_permits() {
+        let (_harness, pool) = mock_pool(1).await;
+        let held = pool
+            .connection_semaphore
+            .clone()
+            .acquire_owned()
+            .await
+            .unwrap();
+        assert!(pool.stats().await.is_saturated());
+
+        tokio::spawn(async move {
+            tokio::time::sleep(Duration::from_millis(20)).await;
+            drop(held);
+        });
+        let lease = pool
+            .acquire_client_waiting(Duration::from_secs(5))
+            .await
+            .unwrap();
+        assert!(lease.permit.is_some());
+        let stats = pool.stats().await;
+        assert_eq!((stats.saturated, stats.timeouts), (1, 0));
+        lease.release_immediate().await;
+    }
+
+    #[tokio::test]
+    async fn test_quarantined_wallet_is_parked_on_release() {
+        let mut harness = crate::testing::TaskHarness::new().with_db().await.unwrap();
+        harness.config.wallet_health.max_consecutive_failures = 1;
+        let pool = ClientPool::from_wallet_manager(
+            harness.config.clone(),
+            harness.db.clone().unwrap(),
+            WalletManager::from_private_keys([crate::testing::TEST_PRIVATE_KEY]),
+            None,
+            2,
+        );
+        pool.clients.write().await.insert(0, harness.mock.client());
+        let pool = Arc::new(pool);
+
+        let lease = pool.try_acquire_client_fast().await.unwrap();
+        let quarantine = pool
+            .wallet_health()
+            .record(0, lease.client.address(), Some("nonce too low"))
+            .unwrap();
+        assert_eq!(quarantine.consecutive_failures, 1);
+        lease.release_immediate().await;
+
+        // Parked rather than returned, until the recheck timer runs out
+        assert!(pool.try_acquire_client_fast().await.is_none());
+        assert_eq!(pool.recheck_quarantined().await, 0);
+        let stats = pool.stats().await;
+        assert_eq!((stats.wallets_quarantined, stats.wallets_available), (1, 0));
+    }
+}
@@ -1,9 +1,16 @@
 //! Configuration loader for tempo-spammer
 
+use alloy_primitives::Address;
 use anyhow::{Context, Result};
+use core_logic::database::{AsyncDbConfig, JournalMode, SynchronousLevel};
+use core_logic::{ConfigError, LogRotation, RotationPeriod};
 use serde::Deserialize;
+use std::collections::BTreeMap;
 use std::fs;
+use std::path::{Path, PathBuf};
 use std::str::FromStr;
+use std::time::Duration;
+use zeroize::Zeroizing;
 
 #[derive(Debug, Clone, Deserialize)]
 #[serde(try_from = "String")]
@@ -25,6 +32,7 @@ impl From<U128Config> for u128 {
 
 /// Configuration for the tempo spammer
 #[derive(Debug, Clone, Deserialize)]
+#[serde(deny_unknown_fields)]
 pub struct TempoSpammerConfig {
     /// RPC endpoint URL
     pub rpc_url: String,
@@ -56,6 +64,142 @@ pub struct TempoSpammerConfig {
     /// Nonce management configuration
     #[serde(default)]
     pub nonce: NonceConfig,
+    /// Database storage configuration
+    #[serde(default)]
+    pub database: DatabaseConfig,
+    /// Proxy usage configuration
+    #[serde(default)]
+    pub proxy: ProxySettings,
+    /// Task weights and schedules
+    #[serde(default)]
+    pub tasks: TaskSettings,
+    /// Remote task settings polled from a central URL
+    #[serde(default)]
+    pub remote: RemoteSettings,
+    /// Wallet behavior profiles by name, e.g. `[personas.trader]`
+    #[serde(default)]
+    pub personas: BTreeMap<String, PersonaSettings>,
+    /// Time-of-day activity curve and quiet hours
+    #[serde(default)]
+    pub activity: ActivitySettings,
+    /// Coordinated burst rounds (`burst` subcommand)
+    #[serde(default)]
+    pub burst: BurstSettings,
+    /// Daily per-wallet and global spend caps by token
+    #[serde(default)]
+    pub budget: BudgetSettings,
+    /// Re-reading state changes through a second endpoint
+    #[serde(default)]
+    pub verify: VerifySettings,
+    /// Access keys signing Tempo transactions instead of the wallet key
+    #[serde(default)]
+    pub session_keys: SessionKeySettings,
+    /// Local control socket for adjusting a live run
+    #[serde(default)]
+    pub control: ControlSettings,
+    /// Local devnet used by `--local-dev`
+    #[serde(default)]
+    pub local_dev: LocalDevSettings,
+    /// Per-worker start offsets, interval jitter and arrival process
+    #[serde(default)]
+    pub pacing: PacingSettings,
+    /// Full-context capture for a sample of task runs
+    #[serde(default)]
+    pub audit: AuditSettings,
+    /// Gas usage tracking and per-task gas-limit ceilings
+    #[serde(default)]
+    pub gas: GasSettings,
+    /// Sizes probed by the `_stress_` tasks
+    #[serde(default)]
+    pub stress: StressSettings,
+    /// Helper contract addresses pinned per chain and their startup check
+    #[serde(default)]
+    pub helpers: HelperSettings,
+    /// Run artifact archives (`bundle` subcommand)
+    #[serde(default)]
+    pub bundle: BundleSettings,
+    /// ERC-4337 contracts and bundler for the `_aa_` tasks
+    #[serde(default)]
+    pub aa: AaSettings,
+    /// Transfers between the pool's own wallets
+    #[serde(default)]
+    pub recipients: RecipientSettings,
+    /// NFT listings on the marketplace helper
+    #[serde(default)]
+    pub marketplace: MarketplaceSettings,
+    /// Follow-up management of registered `.tempo` domains
+    #[serde(default)]
+    pub domains: DomainSettings,
+    /// Slippage tolerance of the swap tasks
+    #[serde(default)]
+    pub swap: SwapSettings,
+    /// How workers get the wallets they run tasks with
+    #[serde(default)]
+    pub wallets: WalletSettings,
+    /// Reuse of RPC answers that do not change between calls
+    #[serde(default)]
+    pub rpc_cache: RpcCacheSettings,
+    /// Every JSON-RPC request and answer appended to a file for replay
+    #[serde(default)]
+    pub rpc_recording: RpcRecordingSettings,
+    /// Request rates per RPC endpoint and handling of 429 answers
+    #[serde(default)]
+    pub rate_limit: RateLimitSettings,
+    /// Scoring wallets by their failures and quarantining broken ones
+    #[serde(default)]
+    pub wallet_health: WalletHealthSettings,
+    /// Never prompt; fail when the wallet password is not configured (default: false)
+    #[serde(default)]
+    pub non_interactive: bool,
+    /// Rotation and retention of the `logs/app.*` files
+    #[serde(default)]
+    pub logging: LoggingSettings,
+    /// Span export to an OpenTelemetry collector such as Jaeger
+    #[serde(default)]
+    pub tracing: TracingSettings,
+    /// Diagnostics snapshots of runs nearing their timeout and slow RPC logging
+    #[serde(default)]
+    pub watchdog: WatchdogSettings,
+    /// USD prices for gas and token amounts in reports
+    #[serde(default)]
+    pub price: PriceSettings,
+    /// Startup check of the chain id and of the Tempo features the node supports
+    #[serde(default)]
+    pub capabilities: CapabilitySettings,
+    /// Skipping wallets with too many unconfirmed transactions
+    #[serde(default)]
+    pub mempool: MempoolSettings,
+    /// Latest block polled once for all workers
+    #[serde(default)]
+    pub chain_head: ChainHeadSettings,
+    /// PID file of `--daemon` and foreground runs
+    #[serde(default)]
+    pub daemon: DaemonSettings,
+    /// Sampling of success lines and per-task summaries in the `task_result` log
+    #[serde(default)]
+    pub results: ResultLogSettings,
+    /// Per-method RPC latency and error limits that raise alerts
+    #[serde(default)]
+    pub rpc_stats: RpcStatsSettings,
+    /// Faults injected into RPC traffic to exercise the recovery paths
+    #[serde(default)]
+    pub chaos: ChaosSettings,
+    /// Pausing tasks whose success rate falls below a threshold
+    #[serde(default)]
+    pub slo: SloSettings,
+    /// Archival of task transactions' receipt logs for coverage audits
+    #[serde(default)]
+    pub log_archive: LogArchiveSettings,
+    /// Address book of the network's system contracts
+    #[serde(default)]
+    pub contracts: SystemContracts,
+    /// Named overrides of the settings above, e.g. `[profiles.local]`,
+    /// one of which is applied with `--profile`
+    #[serde(default)]
+    pub profiles: BTreeMap<String, toml::Table>,
+    /// Profile applied at load, `None` for the file's own settings
+    #[serde(skip)]
+    pub profile: Option<String>,
 }
 
 fn default_connection_semaphore() -> usize {
@@ -68,6 +212,7 @@ fn default_worker_semaphore() -> usize {
 
 /// Configuration for nonce management
 #[derive(Debug, Clone, Deserialize)]
+#[serde(deny_unknown_fields)]
 pub struct NonceConfig {
     /// Base cooldown between wallet reuse in milliseconds (default: 1500ms)
     #[serde(default = "default_nonce_base_cooldown_ms")]
@@ -150,67 +295,3787 @@ fn default_nonce_retry_max_ms() -> u64 {
     2000
 }
 
-fn deserialize_u128<'de, D>(deserializer: D) -> Result<u128, D::Error>
-where
-    D: serde::Deserializer<'de>,
-{
-    struct U128Visitor;
+/// Configuration for result database storage
+#[derive(Debug, Clone, Deserialize)]
+#[serde(deny_unknown_fields)]
+pub struct DatabaseConfig {
+    /// Path to the SQLite database file (default: "tempo-spammer.db")
+    #[serde(default = "default_database_path")]
+    pub path: String,
+    /// Write to one file per calendar month, e.g. tempo-spammer-2024-06.db (default: false)
+    #[serde(default)]
+    pub shard_by_month: bool,
+    /// SQLite journal mode (default: wal)
+    #[serde(default)]
+    pub journal_mode: JournalMode,
+    /// SQLite synchronous level (default: normal)
+    #[serde(default)]
+    pub synchronous: SynchronousLevel,
+    /// How long a write waits on a locked database before failing (default: 5000)
+    #[serde(default = "default_busy_timeout_ms")]
+    pub busy_timeout_ms: u64,
+    /// Interval of passive WAL checkpoints, 0 = SQLite's automatic ones only (default: 60000)
+    #[serde(default = "default_checkpoint_interval_ms")]
+    pub checkpoint_interval_ms: u64,
+    /// Encrypt the database with SQLCipher, needs a `sqlcipher` build (default: false)
+    #[serde(default)]
+    pub encrypt: bool,
+    /// File whose first line is the database key, read instead of prompting
+    #[serde(default)]
+    pub key_file: Option<PathBuf>,
+    /// JSONL file that task results the database failed to store are
+    /// appended to, for `db import` (default: none, such results are dropped)
+    #[serde(default)]
+    pub spill_path: Option<PathBuf>,
+}
 
-    impl<'de> serde::de::Visitor<'de> for U128Visitor {
-        type Value = u128;
+impl Default for DatabaseConfig {
+    fn default() -> Self {
+        Self {
+            path: default_database_path(),
+            shard_by_month: false,
+            journal_mode: JournalMode::default(),
+            synchronous: SynchronousLevel::default(),
+            busy_timeout_ms: default_busy_timeout_ms(),
+            checkpoint_interval_ms: default_checkpoint_interval_ms(),
+            encrypt: false,
+            key_file: None,
+            spill_path: None,
+        }
+    }
+}
 
-        fn expecting(&self, formatter: &mut std::fmt::Formatter) -> std::fmt::Result {
-            formatter.write_str("a string or integer representing a u128")
+impl DatabaseConfig {
+    /// Database file to open for a run starting now
+    ///
+    /// With `shard_by_month` this is the current month's shard. Task results
+    /// follow the month: the flush worker moves on to the next shard when
+    /// the month changes (see [`async_config`](Self::async_config)), while
+    /// the run's other records stay in the shard it started in.
+    pub fn active_path(&self) -> String {
+        if self.shard_by_month {
+            core_logic::database::monthly_shard_path(&self.path, chrono::Utc::now())
+        } else {
+            self.path.clone()
         }
+    }
 
-        fn visit_str<E>(self, value: &str) -> Result<Self::Value, E>
-        where
-            E: serde::de::Error,
-        {
-            u128::from_str(value).map_err(|_| E::custom("invalid u128"))
+    /// The SQLCipher key from `key_file`, `DATABASE_KEY_FILE` or `DATABASE_KEY`
+    ///
+    /// `None` when `encrypt` is off or no key is configured; callers may
+    /// prompt for one and otherwise open the database unencrypted.
+    ///
+    /// # Errors
+    ///
+    /// Fails when the key file cannot be read or is empty.
+    pub fn encryption_key(&self) -> Result<Option<Zeroizing<String>>> {
+        if !self.encrypt {
+            return Ok(None);
         }
+        core_logic::database_key(self.key_file.as_deref())
+    }
 
-        fn visit_u64<E>(self, value: u64) -> Result<Self::Value, E>
-        where
-            E: serde::de::Error,
-        {
-            Ok(value as u128)
+    /// Async logging settings of the run's database
+    pub fn async_config(&self) -> AsyncDbConfig {
+        AsyncDbConfig {
+            journal_mode: self.journal_mode,
+            synchronous: self.synchronous,
+            busy_timeout_ms: self.busy_timeout_ms,
+            checkpoint_interval_ms: self.checkpoint_interval_ms,
+            spill_path: self.spill_path.clone(),
+            shard_base_path: self.shard_by_month.then(|| self.path.clone()),
+            ..AsyncDbConfig::default()
         }
+    }
+}
 
-        fn visit_i64<E>(self, value: i64) -> Result<Self::Value, E>
-        where
-            E: serde::de::Error,
-        {
-            if value >= 0 {
-                Ok(value as u128)
-            } else {
-                Err(E::custom("negative u128"))
-            }
+fn default_database_path() -> String {
+    "tempo-spammer.db".to_string()
+}
+
+fn default_busy_timeout_ms() -> u64 {
+    5000
+}
+
+fn default_checkpoint_interval_ms() -> u64 {
+    60_000
+}
+
+/// How wallets are mapped onto proxies
+#[derive(Debug, Clone, Copy, PartialEq, Eq, Default, Deserialize)]
+#[serde(rename_all = "lowercase")]
+pub enum ProxyAssignmentPolicy {
+    /// Each wallet is pinned to one proxy for good, even while it is banned
+    Sticky,
+    /// Proxies are handed out round-robin whenever a client is created
+    #[default]
+    Rotating,
+    /// Wallets are pinned, but move to a new proxy when theirs fails
+    Hybrid,
+    /// Each worker sends through its own proxy, failing over only to
+    /// `[proxy.workers] backups`
+    Worker,
+}
+
+/// Configuration for proxy usage
+#[derive(Debug, Clone, Deserialize)]
+#[serde(deny_unknown_fields)]
+pub struct ProxySettings {
+    /// Wallet to proxy assignment policy (default: rotating)
+    #[serde(default)]
+    pub assignment: ProxyAssignmentPolicy,
+    /// File holding the persisted wallet to proxy map for sticky/hybrid (default: "proxy-assignments.json")
+    #[serde(default = "default_proxy_assignment_file")]
+    pub assignment_file: String,
+    /// Preferred maximum number of wallets pinned to one proxy, 0 = unlimited (default: 0)
+    #[serde(default)]
+    pub max_wallets_per_proxy: usize,
+    /// Seconds a `{session}` proxy credential is kept, 0 = new session per connection (default: 0)
+    #[serde(default)]
+    pub session_interval_secs: u64,
+    /// Ban escalation for failing proxies, from `[proxy.bans]`
+    #[serde(default)]
+    pub bans: ProxyBanSettings,
+    /// Proxy health checks, from `[proxy.health]`
+    #[serde(default)]
+    pub health: ProxyHealthSettings,
+    /// Worker to proxy bindings for `assignment = "worker"`, from `[proxy.workers]`
+    #[serde(default)]
+    pub workers: WorkerProxySettings,
+}
+
+impl Default for ProxySettings {
+    fn default() -> Self {
+        Self {
+            assignment: ProxyAssignmentPolicy::default(),
+            assignment_file: default_proxy_assignment_file(),
+            max_wallets_per_proxy: 0,
+            session_interval_secs: 0,
+            bans: ProxyBanSettings::default(),
+            health: ProxyHealthSettings::default(),
+            workers: WorkerProxySettings::default(),
         }
     }
+}
 
-    deserializer.deserialize_any(U128Visitor)
+impl ProxySettings {
+    /// Session rotation for proxies with `{session}` credentials
+    pub fn session_rotation(&self) -> crate::proxy_session::SessionRotation {
+        crate::proxy_session::SessionRotation::from_interval_secs(self.session_interval_secs)
+    }
 }
 
-impl TempoSpammerConfig {
-    /// Load configuration from a TOML file
-    ///
-    /// # Arguments
-    /// * `path` - Path to the config.toml file
-    ///
-    /// # Example
-    /// ```ignore
-    /// let config = TempoSpammerConfig::from_path("config/config.toml")?;
-    /// ```
-    pub fn from_path(path: &str) -> Result<Self> {
-        let content =
-            fs::read_to_string(path).context(format!("Failed to read config from {}", path))?;
-        toml::from_str(&content).context("Failed to parse config TOML")
+fn default_proxy_assignment_file() -> String {
+    "proxy-assignments.json".to_string()
+}
+
+/// Configuration for binding workers to proxies
+///
+/// Proxy indices are 0-based lines of `proxies.txt`.
+#[derive(Debug, Clone, Default, PartialEq, Eq, Deserialize)]
+#[serde(deny_unknown_fields)]
+pub struct WorkerProxySettings {
+    /// Proxy of each worker, by worker id; wraps around when shorter than the
+    /// worker count (default: every proxy not in `backups`, in order)
+    #[serde(default)]
+    pub bindings: Vec<usize>,
+    /// Proxies a worker falls back to while its own is banned (default: none)
+    #[serde(default)]
+    pub backups: Vec<usize>,
+}
+
+/// Configuration for escalating proxy bans
+///
+/// Every ban moves a proxy one severity tier up, so a proxy that keeps
+/// failing sits out longer each time. Successful requests walk it back down.
+#[derive(Debug, Clone, PartialEq, Deserialize)]
+#[serde(deny_unknown_fields)]
+pub struct ProxyBanSettings {
+    /// Ban length in minutes per severity tier (default: [10, 30, 120])
+    #[serde(default = "default_proxy_ban_tiers_minutes")]
+    pub tiers_minutes: Vec<u64>,
+    /// Ban for the rest of the session once the last tier is used up (default: true)
+    #[serde(default = "default_proxy_ban_permanent")]
+    pub permanent_after_last_tier: bool,
+    /// Failed requests in a row before a proxy is banned (default: 1)
+    #[serde(default = "default_proxy_ban_failure_threshold")]
+    pub failure_threshold: u32,
+    /// Successful requests that lower a proxy's severity by one tier, 0 = never (default: 50)
+    #[serde(default = "default_proxy_ban_decay_successes")]
+    pub decay_successes: u32,
+}
+
+impl Default for ProxyBanSettings {
+    fn default() -> Self {
+        Self {
+            tiers_minutes: default_proxy_ban_tiers_minutes(),
+            permanent_after_last_tier: default_proxy_ban_permanent(),
+            failure_threshold: default_proxy_ban_failure_threshold(),
+            decay_successes: default_proxy_ban_decay_successes(),
+        }
     }
+}
 
-    /// Get a random task interval between min and max
-    pub fn random_interval(&self) -> u64 {
-        let mut rng = rand::thread_rng();
-        rand::Rng::gen_range(&mut rng, self.task_interval_min..=self.task_interval_max)
+impl ProxyBanSettings {
+    /// Ban policy for the proxy banlist
+    pub fn policy(&self) -> crate::proxy_health::BanPolicy {
+        crate::proxy_health::BanPolicy {
+            tiers: self
+                .tiers_minutes
+                .iter()
+                .map(|minutes| Duration::from_secs(minutes * 60))
+                .collect(),
+            permanent_after_last_tier: self.permanent_after_last_tier,
+            failure_threshold: self.failure_threshold,
+            decay_successes: self.decay_successes,
+        }
+    }
+}
+
+/// Configuration for proxy health checks
+///
+/// Some proxies answer a `HEAD` to the RPC but fail on POST bodies or on
+/// TLS to other hosts; a `chain_id` probe and extra targets catch those.
+#[derive(Debug, Clone, PartialEq, Deserialize)]
+#[serde(deny_unknown_fields)]
+pub struct ProxyHealthSettings {
+    /// Probe request: head | chain_id (POST eth_chainId) (default: head)
+    #[serde(default)]
+    pub probe: crate::proxy_health::ProbeMethod,
+    /// URLs probed through each proxy, empty = the configured rpc_url (default: [])
+    #[serde(default)]
+    pub targets: Vec<String>,
+    /// Slowest acceptable probe response in milliseconds, 0 = no limit (default: 0)
+    #[serde(default)]
+    pub max_latency_ms: u64,
+    /// Probes in a row each target must pass (default: 1)
+    #[serde(default = "default_proxy_health_required_successes")]
+    pub required_successes: u32,
+    /// Proxies checked at once (default: 10)
+    #[serde(default = "default_proxy_health_concurrency")]
+    pub concurrency: usize,
+}
+
+impl Default for ProxyHealthSettings {
+    fn default() -> Self {
+        Self {
+            probe: crate::proxy_health::ProbeMethod::default(),
+            targets: Vec::new(),
+            max_latency_ms: 0,
+            required_successes: default_proxy_health_required_successes(),
+            concurrency: default_proxy_health_concurrency(),
+        }
+    }
+}
+
+impl ProxyHealthSettings {
+    /// Health probe for proxies, falling back to `rpc_url` without targets
+    pub fn probe(&self, rpc_url: &str) -> crate::proxy_health::HealthProbe {
+        let targets = if self.targets.is_empty() {
+            vec![rpc_url.to_string()]
+        } else {
+            self.targets.clone()
+        };
+        crate::proxy_health::HealthProbe {
+            method: self.probe,
+            targets,
+            max_latency: (self.max_latency_ms > 0)
+                .then_some(Duration::from_millis(self.max_latency_ms)),
+            required_successes: self.required_successes,
+            concurrency: self.concurrency,
+        }
+    }
+}
+
+fn default_proxy_health_required_successes() -> u32 {
+    1
+}
+
+fn default_proxy_health_concurrency() -> usize {
+    10
+}
+
+fn default_proxy_ban_tiers_minutes() -> Vec<u64> {
+    vec![10, 30, 120]
+}
+
+fn default_proxy_ban_permanent() -> bool {
+    true
+}
+
+fn default_proxy_ban_failure_threshold() -> u32 {
+    1
+}
+
+fn default_proxy_ban_decay_successes() -> u32 {
+    50
+}
+
+/// Task weights and run windows, from `[tasks]` or a remote document
+///
+/// Keys are task names such as `03_send_token`. Tasks without a weight keep
+/// the built-in one; tasks without a window run around the clock.
+#[derive(Debug, Clone, Default, PartialEq, Deserialize)]
+#[serde(deny_unknown_fields)]
+pub struct TaskSettings {
+    /// Relative pick weight per task, 0 = never run
+    #[serde(default)]
+    pub weights: BTreeMap<String, u32>,
+    /// UTC hours a task may run in, e.g. `"08-20"` or `"22-06"`
+    #[serde(default)]
+    pub schedule: BTreeMap<String, HourWindow>,
+    /// Timeout in seconds per task, replacing `task_timeout` and the task's
+    /// own default
+    #[serde(default)]
+    pub timeouts: BTreeMap<String, u64>,
+    /// Longest time in seconds an enabled task may go without running
+    /// before it is picked ahead of the weights (default: off)
+    #[serde(default)]
+    pub starvation_secs: Option<u64>,
+    /// Tasks run once every this many new blocks instead of by weight,
+    /// needs `[chain_head]`
+    #[serde(default)]
+    pub every_blocks: BTreeMap<String, u64>,
+}
+
+impl TaskSettings {
+    /// These settings with every key of `other` taking precedence
+    pub fn overlay(&self, other: &TaskSettings) -> TaskSettings {
+        let mut merged = self.clone();
+        merged.weights.extend(other.weights.clone());
+        merged.schedule.extend(other.schedule.clone());
+        merged.timeouts.extend(other.timeouts.clone());
+        merged.every_blocks.extend(other.every_blocks.clone());
+        merged.starvation_secs = other.starvation_secs.or(self.starvation_secs);
+        merged
+    }
+}
+
+/// Range of UTC hours, start inclusive and end exclusive
+///
+/// A window whose end is before its start wraps past midnight.
+#[derive(Debug, Clone, Copy, PartialEq, Eq, Deserialize)]
+#[serde(try_from = "String")]
+pub struct HourWindow {
+    pub start: u32,
+    pub end: u32,
+}
+
+impl HourWindow {
+    /// Whether the hour (0-23) falls inside the window
+    pub fn contains(&self, hour: u32) -> bool {
+        if self.start <= self.end {
+            (self.start..self.end).contains(&hour)
+        } else {
+            hour >= self.start || hour < self.end
+        }
+    }
+}
+
+impl FromStr for HourWindow {
+    type Err = anyhow::Error;
+
+    fn from_str(s: &str) -> Result<Self> {
+        let (start, end) = s
+            .split_once('-')
+            .with_context(|| format!("Expected an hour range like \"08-20\", got \"{}\"", s))?;
+        let start: u32 = start.trim().parse().context("Invalid start hour")?;
+        let end: u32 = end.trim().parse().context("Invalid end hour")?;
+        if start > 23 || end > 24 || start == end {
+            anyhow::bail!("Invalid hour range \"{}\"", s);
+        }
+        Ok(Self { start, end })
+    }
+}
+
+impl TryFrom<String> for HourWindow {
+    type Error = anyhow::Error;
+
+    fn try_from(s: String) -> Result<Self> {
+        s.parse()
+    }
+}
+
+/// A wallet behavior profile
+///
+/// Wallets are spread over the profiles by `share`; see [`crate::persona`].
+#[derive(Debug, Clone, PartialEq, Deserialize)]
+#[serde(deny_unknown_fields)]
+pub struct PersonaSettings {
+    /// Relative share of wallets given this persona (default: 1)
+    #[serde(default = "default_persona_share")]
+    pub share: u32,
+    /// Multiplier on the weight of each named task (default: 1.0 for all)
+    #[serde(default)]
+    pub weights: BTreeMap<String, f64>,
+    /// Multiplier on transfer amounts (default: 1.0)
+    #[serde(default = "default_amount_scale")]
+    pub amount_scale: f64,
+    /// UTC hours the wallet is active, e.g. `"09-23"` (default: always)
+    #[serde(default)]
+    pub hours: Option<HourWindow>,
+}
+
+fn default_persona_share() -> u32 {
+    1
+}
+
+fn default_amount_scale() -> f64 {
+    1.0
+}
+
+/// Configuration for daily token spend caps
+///
+/// Keys are token addresses or system token symbols (`PathUSD`, `AlphaUSD`,
+/// ...); values are caps in the token's base units per UTC day.
+#[derive(Debug, Clone, Default, PartialEq, Deserialize)]
+#[serde(deny_unknown_fields)]
+pub struct BudgetSettings {
+    /// Cap on what each wallet may send per day (default: none)
+    #[serde(default)]
+    pub wallet_daily: BTreeMap<String, u64>,
+    /// Cap on what all wallets together may send per day (default: none)
+    #[serde(default)]
+    pub global_daily: BTreeMap<String, u64>,
+}
+
+/// Configuration for session keys registered in the AccountKeychain
+///
+/// `limits` keys are token addresses or system token symbols; values are
+/// caps in base units over the key's lifetime.
+#[derive(Debug, Clone, PartialEq, Deserialize)]
+#[serde(deny_unknown_fields)]
+pub struct SessionKeySettings {
+    /// Whether Tempo transactions are signed with session keys (default: false)
+    #[serde(default)]
+    pub enabled: bool,
+    /// Hours before a wallet rotates to a new session key (default: 24)
+    #[serde(default = "default_session_ttl_hours")]
+    pub ttl_hours: u64,
+    /// Spending limits put on each key (default: none, unlimited)
+    #[serde(default)]
+    pub limits: BTreeMap<String, u64>,
+}
+
+impl Default for SessionKeySettings {
+    fn default() -> Self {
+        Self {
+            enabled: false,
+            ttl_hours: default_session_ttl_hours(),
+            limits: BTreeMap::new(),
+        }
+    }
+}
+
+fn default_session_ttl_hours() -> u64 {
+    24
+}
+
+/// Configuration for the control socket (`ctl` subcommand)
+#[derive(Debug, Clone, PartialEq, Deserialize)]
+#[serde(deny_unknown_fields)]
+pub struct ControlSettings {
+    /// Whether the spammer accepts control commands (default: false)
+    #[serde(default)]
+    pub enabled: bool,
+    /// Address to listen on (default: "127.0.0.1:9137"), or a named pipe
+    /// such as `\\.\pipe\tempo-spammer` on Windows
+    #[serde(default = "default_control_listen")]
+    pub listen: String,
+}
+
+impl Default for ControlSettings {
+    fn default() -> Self {
+        Self {
+            enabled: false,
+            listen: default_control_listen(),
+        }
+    }
+}
+
+fn default_control_listen() -> String {
+    "127.0.0.1:9137".to_string()
+}
+
+/// How a worker spaces its tasks
+#[derive(Debug, Clone, Copy, PartialEq, Eq, Default, Deserialize)]
+#[serde(rename_all = "lowercase")]
+pub enum ArrivalProcess {
+    /// Uniform pause between the worker's interval bounds
+    #[default]
+    Uniform,
+    /// Exponential pause with the bounds' midpoint as mean (Poisson arrivals)
+    Poisson,
+}
+
+/// Configuration for desynchronizing workers
+#[derive(Debug, Clone, PartialEq, Deserialize)]
+#[serde(deny_unknown_fields)]
+pub struct PacingSettings {
+    /// Stagger worker starts across one maximum interval by worker id (default: true)
+    #[serde(default = "default_phase_offset")]
+    pub phase_offset: bool,
+    /// Each worker scales both interval bounds by a factor within 1 ± this (default: 0.25)
+    #[serde(default = "default_interval_jitter")]
+    pub interval_jitter: f64,
+    /// Pause distribution between a worker's tasks (default: uniform)
+    #[serde(default)]
+    pub arrivals: ArrivalProcess,
+}
+
+impl Default for PacingSettings {
+    fn default() -> Self {
+        Self {
+            phase_offset: default_phase_offset(),
+            interval_jitter: default_interval_jitter(),
+            arrivals: ArrivalProcess::default(),
+        }
+    }
+}
+
+fn default_phase_offset() -> bool {
+    true
+}
+
+fn default_interval_jitter() -> f64 {
+    0.25
+}
+
+/// Configuration for gas usage tracking
+#[derive(Debug, Clone, PartialEq, Deserialize)]
+#[serde(deny_unknown_fields)]
+pub struct GasSettings {
+    /// Fetch the receipt of every sent transaction into `gas_usage` (default: true)
+    #[serde(default = "default_track_gas_usage")]
+    pub track_usage: bool,
+    /// How long to wait for a receipt before giving up, in seconds (default: 120)
+    #[serde(default = "default_receipt_timeout_secs")]
+    pub receipt_timeout_secs: u64,
+    /// Receipt lookups in flight at once; transactions beyond are not tracked (default: 256)
+    #[serde(default = "default_max_pending_receipts")]
+    pub max_pending: usize,
+    /// Highest gas limit each task may set, by task name (default: none)
+    #[serde(default)]
+    pub limits: BTreeMap<String, u64>,
+    /// Attach access lists from `eth_createAccessList` where tasks support it (default: false)
+    #[serde(default)]
+    pub access_lists: bool,
+}
+
+impl Default for GasSettings {
+    fn default() -> Self {
+        Self {
+            track_usage: default_track_gas_usage(),
+            receipt_timeout_secs: default_receipt_timeout_secs(),
+            max_pending: default_max_pending_receipts(),
+            limits: BTreeMap::new(),
+            access_lists: false,
+        }
+    }
+}
+
+fn default_track_gas_usage() -> bool {
+    true
+}
+
+fn default_receipt_timeout_secs() -> u64 {
+    120
+}
+
+fn default_max_pending_receipts() -> usize {
+    256
+}
+
+/// Configuration for the protocol limit stress tasks
+///
+/// Each run picks one entry of the list, so mixing sizes below and above a
+/// limit shows where it lies.
+#[derive(Debug, Clone, PartialEq, Deserialize)]
+#[serde(deny_unknown_fields)]
+pub struct StressSettings {
+    /// Calldata sizes in bytes for `51_stress_large_calldata` (default: [4096, 65536, 131072, 196608])
+    #[serde(default = "default_stress_calldata_sizes")]
+    pub calldata_sizes: Vec<usize>,
+    /// Calls per transaction for `52_stress_max_calls` (default: [8, 32, 33, 64])
+    #[serde(default = "default_stress_call_counts")]
+    pub call_counts: Vec<usize>,
+    /// Gas limit of `53_stress_near_gas_limit` as a share of the block gas limit (default: 0.95)
+    #[serde(default = "default_stress_gas_fraction")]
+    pub gas_fraction: f64,
+}
+
+impl Default for StressSettings {
+    fn default() -> Self {
+        Self {
+            calldata_sizes: default_stress_calldata_sizes(),
+            call_counts: default_stress_call_counts(),
+            gas_fraction: default_stress_gas_fraction(),
+        }
+    }
+}
+
+fn default_stress_calldata_sizes() -> Vec<usize> {
+    vec![4096, 65536, 131072, 196608]
+}
+
+fn default_stress_call_counts() -> Vec<usize> {
+    vec![8, 32, 33, 64]
+}
+
+fn default_stress_gas_fraction() -> f64 {
+    0.95
+}
+
+/// Configuration for helper contract resolution
+#[derive(Debug, Clone, PartialEq, Deserialize)]
+#[serde(deny_unknown_fields)]
+pub struct HelperSettings {
+    /// Compare the code hash at each helper address with the bundled runtime at startup (default: true)
+    #[serde(default = "default_verify_helpers")]
+    pub verify: bool,
+    /// Helper addresses by chain id and helper name; unpinned helpers use the `bootstrap` records (default: none)
+    #[serde(default)]
+    pub pinned: BTreeMap<String, BTreeMap<String, Address>>,
+}
+
+impl Default for HelperSettings {
+    fn default() -> Self {
+        Self {
+            verify: default_verify_helpers(),
+            pinned: BTreeMap::new(),
+        }
+    }
+}
+
+fn default_verify_helpers() -> bool {
+    true
+}
+
+/// Configuration for run artifact bundles
+#[derive(Debug, Clone, PartialEq, Deserialize)]
+#[serde(deny_unknown_fields)]
+pub struct BundleSettings {
+    /// Stop the spammer on Ctrl-C and bundle the run that just ended (default: false)
+    #[serde(default)]
+    pub on_exit: bool,
+    /// Directory archives are written to (default: "bundles")
+    #[serde(default = "default_bundle_dir")]
+    pub dir: String,
+    /// Directory of the hourly `app.*` log files (default: "logs")
+    #[serde(default = "default_bundle_logs_dir")]
+    pub logs_dir: String,
+    /// Most recent log lines of the run kept in the archive, 0 for none (default: 2000)
+    #[serde(default = "default_bundle_log_lines")]
+    pub log_lines: usize,
+}
+
+impl Default for BundleSettings {
+    fn default() -> Self {
+        Self {
+            on_exit: false,
+            dir: default_bundle_dir(),
+            logs_dir: default_bundle_logs_dir(),
+            log_lines: default_bundle_log_lines(),
+        }
+    }
+}
+
+fn default_bundle_dir() -> String {
+    "bundles".to_string()
+}
+
+fn default_bundle_logs_dir() -> String {
+    "logs".to_string()
+}
+
+fn default_bundle_log_lines() -> usize {
+    2000
+}
+
+/// Configuration for the ERC-4337 account abstraction tasks
+#[derive(Debug, Clone, PartialEq, Deserialize)]
+#[serde(deny_unknown_fields)]
+pub struct AaSettings {
+    /// Bundler JSON-RPC endpoint; user operation tasks fail without one (default: none)
+    #[serde(default)]
+    pub bundler_url: String,
+    /// EntryPoint v0.7 contract (default: the canonical 0x0000000071727De22E5E9d8BAf0edAc6f37da032)
+    #[serde(default = "default_aa_entry_point")]
+    pub entry_point: Address,
+    /// SimpleAccountFactory for that EntryPoint (default: the canonical 0x91E60e0613810449d098b0b5Ec8b51A0FE8c8985)
+    #[serde(default = "default_aa_account_factory")]
+    pub account_factory: Address,
+    /// Smart accounts each wallet creates before funding existing ones (default: 3)
+    #[serde(default = "default_aa_max_accounts")]
+    pub max_accounts: usize,
+    /// Native amount deposited at the EntryPoint for a new account's gas, in wei (default: 0)
+    #[serde(default, deserialize_with = "deserialize_u128")]
+    pub deposit_wei: u128,
+    /// How long to wait for a user operation receipt, in seconds (default: 60)
+    #[serde(default = "default_aa_receipt_timeout_secs")]
+    pub receipt_timeout_secs: u64,
+}
+
+impl Default for AaSettings {
+    fn default() -> Self {
+        Self {
+            bundler_url: String::new(),
+            entry_point: default_aa_entry_point(),
+            account_factory: default_aa_account_factory(),
+            max_accounts: default_aa_max_accounts(),
+            deposit_wei: 0,
+            receipt_timeout_secs: default_aa_receipt_timeout_secs(),
+        }
+    }
+}
+
+fn default_aa_entry_point() -> Address {
+    alloy_primitives::address!("0x0000000071727De22E5E9d8BAf0edAc6f37da032")
+}
+
+fn default_aa_account_factory() -> Address {
+    alloy_primitives::address!("0x91E60e0613810449d098b0b5Ec8b51A0FE8c8985")
+}
+
+fn default_aa_max_accounts() -> usize {
+    3
+}
+
+fn default_aa_receipt_timeout_secs() -> u64 {
+    60
+}
+
+/// Configuration for transfer recipients drawn from the wallet pool
+#[derive(Debug, Clone, PartialEq, Deserialize)]
+#[serde(deny_unknown_fields)]
+pub struct RecipientSettings {
+    /// Share of transfers sent to another wallet of the pool, 0.0 to 1.0 (default: 0.0, disabled)
+    #[serde(default)]
+    pub pool_share: f64,
+    /// How long a short balance check waits for in-flight transfers to the wallet, in seconds (default: 10)
+    #[serde(default = "default_recipients_incoming_wait_secs")]
+    pub incoming_wait_secs: u64,
+}
+
+impl Default for RecipientSettings {
+    fn default() -> Self {
+        Self {
+            pool_share: 0.0,
+            incoming_wait_secs: default_recipients_incoming_wait_secs(),
+        }
+    }
+}
+
+fn default_recipients_incoming_wait_secs() -> u64 {
+    10
+}
+
+/// Configuration for the NFT marketplace tasks
+#[derive(Debug, Clone, PartialEq, Deserialize)]
+#[serde(deny_unknown_fields)]
+pub struct MarketplaceSettings {
+    /// Active listings a wallet keeps before it stops listing (default: 3)
+    #[serde(default = "default_marketplace_max_listings")]
+    pub max_listings: usize,
+    /// Age after which an unsold listing is cancelled, in seconds (default: 3600)
+    #[serde(default = "default_marketplace_stale_after_secs")]
+    pub stale_after_secs: u64,
+}
+
+impl Default for MarketplaceSettings {
+    fn default() -> Self {
+        Self {
+            max_listings: default_marketplace_max_listings(),
+            stale_after_secs: default_marketplace_stale_after_secs(),
+        }
+    }
+}
+
+fn default_marketplace_max_listings() -> usize {
+    3
+}
+
+fn default_marketplace_stale_after_secs() -> u64 {
+    3600
+}
+
+/// Address book of the Tempo system contracts the tasks call
+///
+/// The defaults are the predeploys of the public testnets; a network that
+/// has them elsewhere, or a testnet after a redeploy, sets them in
+/// `[contracts]` or its profile's `contracts` table. Tasks read them with
+/// [`TaskContext::contracts`](crate::tasks::TaskContext::contracts), code
+/// without a context with [`network::contracts`](crate::network::contracts).
+#[derive(Debug, Clone, Copy, PartialEq, Eq, Deserialize)]
+#[serde(deny_unknown_fields)]
+pub struct SystemContracts {
+    /// PathUSD, the quote token of the other stablecoins (default: 0x20C0000000000000000000000000000000000000)
+    #[serde(default = "default_contracts_path_usd")]
+    pub path_usd: Address,
+    /// AlphaUSD (default: 0x20C0000000000000000000000000000000000001)
+    #[serde(default = "default_contracts_alpha_usd")]
+    pub alpha_usd: Address,
+    /// BetaUSD (default: 0x20C0000000000000000000000000000000000002)
+    #[serde(default = "default_contracts_beta_usd")]
+    pub beta_usd: Address,
+    /// ThetaUSD (default: 0x20C0000000000000000000000000000000000003)
+    #[serde(default = "default_contracts_theta_usd")]
+    pub theta_usd: Address,
+    /// TIP-20 factory creating stablecoins and meme tokens (default: 0x20FC000000000000000000000000000000000000)
+    #[serde(default = "default_contracts_tip20_factory")]
+    pub tip20_factory: Address,
+    /// Stablecoin DEX for swaps, liquidity and orders (default: 0xDEC0000000000000000000000000000000000000)
+    #[serde(default = "default_contracts_stablecoin_dex")]
+    pub stablecoin_dex: Address,
+    /// Fee AMM converting gas fees between stablecoins (default: 0xFEEC000000000000000000000000000000000000)
+    #[serde(default = "default_contracts_fee_manager")]
+    pub fee_manager: Address,
+    /// TIP-403 transfer policy registry (default: 0x403C000000000000000000000000000000000000)
+    #[serde(default = "default_contracts_tip403_registry")]
+    pub tip403_registry: Address,
+    /// Faucet minting the system tokens (default: 0x4200000000000000000000000000000000000019)
+    #[serde(default = "default_contracts_faucet")]
+    pub faucet: Address,
+    /// Infinity Name Service, registry and registrar of `.tempo` names (default: 0x30C0000000000000000000000000000000000000)
+    #[serde(default = "default_contracts_name_service")]
+    pub name_service: Address,
+}
+
+impl SystemContracts {
+    /// Predeploys of the public testnets
+    pub const TESTNET: Self = Self {
+        path_usd: alloy_primitives::address!("0x20C0000000000000000000000000000000000000"),
+        alpha_usd: alloy_primitives::address!("0x20C0000000000000000000000000000000000001"),
+        beta_usd: alloy_primitives::address!("0x20C0000000000000000000000000000000000002"),
+        theta_usd: alloy_primitives::address!("0x20C0000000000000000000000000000000000003"),
+        tip20_factory: alloy_primitives::address!("0x20FC000000000000000000000000000000000000"),
+        stablecoin_dex: alloy_primitives::address!("0xDEC0000000000000000000000000000000000000"),
+        fee_manager: alloy_primitives::address!("0xFEEC000000000000000000000000000000000000"),
+        tip403_registry: alloy_primitives::address!("0x403C000000000000000000000000000000000000"),
+        faucet: alloy_primitives::address!("0x4200000000000000000000000000000000000019"),
+        name_service: alloy_primitives::address!("0x30C0000000000000000000000000000000000000"),
+    };
+
+    /// System tokens by symbol, PathUSD first
+    pub fn system_tokens(&self) -> [(&'static str, Address); 4] {
+        [
+            ("PathUSD", self.path_usd),
+            ("AlphaUSD", self.alpha_usd),
+            ("BetaUSD", self.beta_usd),
+            ("ThetaUSD", self.theta_usd),
+        ]
+    }
+
+    /// Every address by contract name, as `bootstrap` reports them
+    pub fn named(&self) -> [(&'static str, Address); 10] {
+        let [path_usd, alpha_usd, beta_usd, theta_usd] = self.system_tokens();
+        [
+            path_usd,
+            alpha_usd,
+            beta_usd,
+            theta_usd,
+            ("TIP20Factory", self.tip20_factory),
+            ("StablecoinDEX", self.stablecoin_dex),
+            ("FeeManager", self.fee_manager),
+            ("TIP403Registry", self.tip403_registry),
+            ("Faucet", self.faucet),
+            ("InfinityName", self.name_service),
+        ]
+    }
+}
+
+impl Default for SystemContracts {
+    fn default() -> Self {
+        Self::TESTNET
+    }
+}
+
+fn default_contracts_path_usd() -> Address {
+    SystemContracts::TESTNET.path_usd
+}
+
+fn default_contracts_alpha_usd() -> Address {
+    SystemContracts::TESTNET.alpha_usd
+}
+
+fn default_contracts_beta_usd() -> Address {
+    SystemContracts::TESTNET.beta_usd
+}
+
+fn default_contracts_theta_usd() -> Address {
+    SystemContracts::TESTNET.theta_usd
+}
+
+fn default_contracts_tip20_factory() -> Address {
+    SystemContracts::TESTNET.tip20_factory
+}
+
+fn default_contracts_stablecoin_dex() -> Address {
+    SystemContracts::TESTNET.stablecoin_dex
+}
+
+fn default_contracts_fee_manager() -> Address {
+    SystemContracts::TESTNET.fee_manager
+}
+
+fn default_contracts_tip403_registry() -> Address {
+    SystemContracts::TESTNET.tip403_registry
+}
+
+fn default_contracts_faucet() -> Address {
+    SystemContracts::TESTNET.faucet
+}
+
+fn default_contracts_name_service() -> Address {
+    SystemContracts::TESTNET.name_service
+}
+
+/// Configuration for the domain management tasks
+#[derive(Debug, Clone, PartialEq, Deserialize)]
+#[serde(deny_unknown_fields)]
+pub struct DomainSettings {
+    /// Resolver the set-resolver task points names at (default: 0x30C0000000000000000000000000000000000000, the testnets' name service)
+    #[serde(default = "default_domains_resolver")]
+    pub resolver: Address,
+    /// Duration a renewal extends a name by, in seconds (default: 31536000, one year)
+    #[serde(default = "default_domains_renew_duration_secs")]
+    pub renew_duration_secs: u64,
+}
+
+impl Default for DomainSettings {
+    fn default() -> Self {
+        Self {
+            resolver: default_domains_resolver(),
+            renew_duration_secs: default_domains_renew_duration_secs(),
+        }
+    }
+}
+
+fn default_domains_resolver() -> Address {
+    SystemContracts::TESTNET.name_service
+}
+
+fn default_domains_renew_duration_secs() -> u64 {
+    31_536_000
+}
+
+/// Configuration for the swap tasks
+///
+/// Swaps are quoted against the Stablecoin DEX first and sent with a minimum
+/// output `slippage_bps` below the quote; a book that moves further in
+/// between makes the swap revert, reported as slippage exceeded.
+#[derive(Debug, Clone, PartialEq, Eq, Deserialize)]
+#[serde(deny_unknown_fields)]
+pub struct SwapSettings {
+    /// Output a swap may lose against its quote, in basis points (default: 100, 1%)
+    #[serde(default = "default_swap_slippage_bps")]
+    pub slippage_bps: u32,
+}
+
+impl Default for SwapSettings {
+    fn default() -> Self {
+        Self {
+            slippage_bps: default_swap_slippage_bps(),
+        }
+    }
+}
+
+fn default_swap_slippage_bps() -> u32 {
+    100
+}
+
+/// How workers get their wallets
+#[derive(Debug, Clone, Copy, PartialEq, Eq, Default, Deserialize)]
+#[serde(rename_all = "lowercase")]
+pub enum WalletAssignment {
+    /// Workers lease any free wallet from the shared pool
+    #[default]
+    Leased,
+    /// Each worker owns a fixed, contiguous slice of the wallet indices and
+    /// never touches the shared pool
+    Partitioned,
+}
+
+/// Which free wallet the shared pool leases next
+#[derive(Debug, Clone, Copy, PartialEq, Eq, Default, Deserialize)]
+#[serde(rename_all = "snake_case")]
+pub enum WalletSelection {
+    /// Any free wallet at random
+    #[default]
+    Random,
+    /// The free wallet leased longest ago, wallets never leased first
+    LeastRecentlyUsed,
+    /// Free wallets in index order, continuing after the last one leased
+    RoundRobin,
+}
+
+/// Configuration for assigning wallets to workers
+#[derive(Debug, Clone, PartialEq, Deserialize)]
+#[serde(deny_unknown_fields)]
+pub struct WalletSettings {
+    /// `leased` or `partitioned` (default: leased)
+    #[serde(default)]
+    pub assignment: WalletAssignment,
+    /// `random`, `least_recently_used` or `round_robin`, for leased wallets (default: random)
+    #[serde(default)]
+    pub selection: WalletSelection,
+    /// File whose first line is the wallet password, read instead of prompting
+    #[serde(default)]
+    pub password_file: Option<PathBuf>,
+    /// Subdirectory of `wallet-json/` to load wallets from, e.g. one per campaign
+    #[serde(default)]
+    pub subdir: Option<PathBuf>,
+    /// Also load wallet files from directories nested below it (default: false)
+    #[serde(default)]
+    pub recursive: bool,
+    /// Only load wallets tagged with one of these, in their file or `wallet-tags.json` (default: all)
+    #[serde(default)]
+    pub tags: Vec<String>,
+    /// Decrypted wallets kept in memory, 0 = unlimited (default: 1024)
+    #[serde(default = "default_wallet_cache_size")]
+    pub cache_size: usize,
+    /// This host's share of the wallets, `{ index = 2, total = 5 }` (default: all)
+    #[serde(default)]
+    pub partition: Option<core_logic::HostPartition>,
+}
+
+impl Default for WalletSettings {
+    fn default() -> Self {
+        Self {
+            assignment: WalletAssignment::default(),
+            selection: WalletSelection::default(),
+            password_file: None,
+            subdir: None,
+            recursive: false,
+            tags: Vec::new(),
+            cache_size: default_wallet_cache_size(),
+            partition: None,
+        }
+    }
+}
+
+impl WalletSettings {
+    /// Which wallet files to index, for [`core_logic::WalletManager::with_options`]
+    pub fn wallet_options(&self) -> core_logic::WalletOptions {
+        core_logic::WalletOptions {
+            dir: None,
+            subdir: self.subdir.clone(),
+            recursive: self.recursive,
+            tags: self.tags.clone(),
+            cache_size: self.cache_size,
+            partition: self.partition,
+        }
+    }
+}
+
+fn default_wallet_cache_size() -> usize {
+    1024
+}
+
+/// Configuration for wallet health scoring and quarantine
+#[derive(Debug, Clone, PartialEq, Deserialize)]
+#[serde(deny_unknown_fields)]
+pub struct WalletHealthSettings {
+    /// Score wallets and quarantine failing ones (default: true)
+    #[serde(default = "default_wallet_health_enabled")]
+    pub enabled: bool,
+    /// Score out of 100 below which a wallet is quarantined (default: 30)
+    #[serde(default = "default_wallet_health_threshold")]
+    pub threshold: u32,
+    /// Failures in a row that quarantine a wallet regardless of its score (default: 10)
+    #[serde(default = "default_wallet_health_max_consecutive_failures")]
+    pub max_consecutive_failures: u32,
+    /// How long a quarantined wallet sits out before it is tried again, in seconds (default: 1800)
+    #[serde(default = "default_wallet_health_recheck_secs")]
+    pub recheck_secs: u64,
+}
+
+impl Default for WalletHealthSettings {
+    fn default() -> Self {
+        Self {
+            enabled: default_wallet_health_enabled(),
+            threshold: default_wallet_health_threshold(),
+            max_consecutive_failures: default_wallet_health_max_consecutive_failures(),
+            recheck_secs: default_wallet_health_recheck_secs(),
+        }
+    }
+}
+
+fn default_wallet_health_enabled() -> bool {
+    true
+}
+
+fn default_wallet_health_threshold() -> u32 {
+    30
+}
+
+fn default_wallet_health_max_consecutive_failures() -> u32 {
+    10
+}
+
+fn default_wallet_health_recheck_secs() -> u64 {
+    1800
+}
+
+/// Configuration for rotating the file log
+#[derive(Debug, Clone, PartialEq, Deserialize)]
+#[serde(deny_unknown_fields)]
+pub struct LoggingSettings {
+    /// `hourly`, `daily` or `never`; size still rolls files over (default: hourly)
+    #[serde(default)]
+    pub rotation: RotationPeriod,
+    /// Size in MB at which a new file starts, 0 for no limit (default: 50)
+    #[serde(default = "default_logging_max_file_mb")]
+    pub max_file_mb: u64,
+    /// Log files kept, the current one included, 0 keeps all (default: 48)
+    #[serde(default = "default_logging_max_files")]
+    pub max_files: usize,
+}
+
+impl Default for LoggingSettings {
+    fn default() -> Self {
+        Self {
+            rotation: RotationPeriod::default(),
+            max_file_mb: default_logging_max_file_mb(),
+            max_files: default_logging_max_files(),
+        }
+    }
+}
+
+impl LoggingSettings {
+    /// Rotation of the `logs/app.*` files for [`core_logic::setup_logger_with_rotation`]
+    pub fn log_rotation(&self) -> LogRotation {
+        LogRotation {
+            period: self.rotation,
+            max_bytes: (self.max_file_mb > 0).then(|| self.max_file_mb * 1024 * 1024),
+            max_files: self.max_files,
+            ..LogRotation::default()
+        }
+    }
+}
+
+/// Configuration for exporting spans over OTLP
+#[derive(Debug, Clone, PartialEq, Deserialize)]
+#[serde(deny_unknown_fields)]
+pub struct TracingSettings {
+    /// Export task, transaction, RPC and database spans (default: false)
+    #[serde(default)]
+    pub enabled: bool,
+    /// OTLP/gRPC endpoint of the collector (default: "http://localhost:4317")
+    #[serde(default = "default_tracing_endpoint")]
+    pub endpoint: String,
+    /// `service.name` of the exported spans (default: "tempo-spammer")
+    #[serde(default = "default_tracing_service_name")]
+    pub service_name: String,
+    /// Share of task traces exported, 0.0-1.0 (default: 1.0)
+    #[serde(default = "default_tracing_sample_ratio")]
+    pub sample_ratio: f64,
+    /// Extra resource attributes, e.g. `{ host = "box-2" }` (default: none)
+    #[serde(default)]
+    pub attributes: BTreeMap<String, String>,
+}
+
+impl Default for TracingSettings {
+    fn default() -> Self {
+        Self {
+            enabled: false,
+            endpoint: default_tracing_endpoint(),
+            service_name: default_tracing_service_name(),
+            sample_ratio: default_tracing_sample_ratio(),
+            attributes: BTreeMap::new(),
+        }
+    }
+}
+
+impl TracingSettings {
+    /// Exporter settings for [`core_logic::setup_logger_with_tracing`], `None` when disabled
+    pub fn otlp_config(&self) -> Option<core_logic::OtlpConfig> {
+        self.enabled.then(|| core_logic::OtlpConfig {
+            endpoint: self.endpoint.clone(),
+            service_name: self.service_name.clone(),
+            sample_ratio: self.sample_ratio,
+            attributes: self.attributes.clone(),
+        })
+    }
+}
+
+fn default_tracing_endpoint() -> String {
+    "http://localhost:4317".to_string()
+}
+
+fn default_tracing_service_name() -> String {
+    "tempo-spammer".to_string()
+}
+
+fn default_tracing_sample_ratio() -> f64 {
+    1.0
+}
+
+fn default_logging_max_file_mb() -> u64 {
+    50
+}
+
+fn default_logging_max_files() -> usize {
+    48
+}
+
+/// Configuration for the PID file
+#[derive(Debug, Clone, PartialEq, Deserialize)]
+#[serde(deny_unknown_fields)]
+pub struct DaemonSettings {
+    /// Written while the spammer runs, removed when it exits (default: "tempo-spammer.pid")
+    #[serde(default = "default_daemon_pid_file")]
+    pub pid_file: PathBuf,
+}
+
+impl Default for DaemonSettings {
+    fn default() -> Self {
+        Self {
+            pid_file: default_daemon_pid_file(),
+        }
+    }
+}
+
+fn default_daemon_pid_file() -> PathBuf {
+    PathBuf::from("tempo-spammer.pid")
+}
+
+/// Configuration for the [`result_log`](crate::result_log) volume controls
+#[derive(Debug, Clone, Copy, PartialEq, Eq, Deserialize)]
+#[serde(deny_unknown_fields)]
+pub struct ResultLogSettings {
+    /// Log 1 in this many successes per task, 1 = all, 0 = none; failures
+    /// are always logged (default: 1)
+    #[serde(default = "default_results_success_every")]
+    pub success_every: u64,
+    /// Seconds between per-task summary lines, 0 = off (default: 0)
+    #[serde(default)]
+    pub summary_secs: u64,
+}
+
+impl Default for ResultLogSettings {
+    fn default() -> Self {
+        Self {
+            success_every: default_results_success_every(),
+            summary_secs: 0,
+        }
+    }
+}
+
+fn default_results_success_every() -> u64 {
+    1
+}
+
+/// Configuration for the per-method [`rpc_stats`](crate::rpc_stats) alerts
+#[derive(Debug, Clone, PartialEq, Deserialize)]
+#[serde(deny_unknown_fields)]
+pub struct RpcStatsSettings {
+    /// Seconds of calls per method the limits are checked against (default: 300)
+    #[serde(default = "default_rpc_stats_window_secs")]
+    pub window_secs: u64,
+    /// Calls a method needs in the window before it can alert (default: 20)
+    #[serde(default = "default_rpc_stats_min_samples")]
+    pub min_samples: usize,
+    /// Seconds between checks of the limits, 0 = no alerts (default: 60)
+    #[serde(default = "default_rpc_stats_check_secs")]
+    pub check_secs: u64,
+    /// p95 latency limit per JSON-RPC method in milliseconds (default: none)
+    #[serde(default)]
+    pub p95_ms: BTreeMap<String, u64>,
+    /// Share of a method's calls answered with an error above which it
+    /// alerts, 0.0 to 1.0, 0.0 = off (default: 0.0)
+    #[serde(default)]
+    pub max_error_rate: f64,
+}
+
+impl Default for RpcStatsSettings {
+    fn default() -> Self {
+        Self {
+            window_secs: default_rpc_stats_window_secs(),
+            min_samples: default_rpc_stats_min_samples(),
+            check_secs: default_rpc_stats_check_secs(),
+            p95_ms: BTreeMap::new(),
+            max_error_rate: 0.0,
+        }
+    }
+}
+
+fn default_rpc_stats_window_secs() -> u64 {
+    300
+}
+
+fn default_rpc_stats_min_samples() -> usize {
+    20
+}
+
+fn default_rpc_stats_check_secs() -> u64 {
+    60
+}
+
+/// Configuration for the RPC [`recording`](crate::recording)
+#[derive(Debug, Clone, PartialEq, Deserialize)]
+#[serde(deny_unknown_fields)]
+pub struct RpcRecordingSettings {
+    /// Append every JSON-RPC request and answer to `path` (default: false)
+    #[serde(default)]
+    pub enabled: bool,
+    /// JSONL file the calls are appended to (default: "rpc-recording.jsonl")
+    #[serde(default = "default_rpc_recording_path")]
+    pub path: String,
+}
+
+impl Default for RpcRecordingSettings {
+    fn default() -> Self {
+        Self {
+            enabled: false,
+            path: default_rpc_recording_path(),
+        }
+    }
+}
+
+fn default_rpc_recording_path() -> String {
+    "rpc-recording.jsonl".to_string()
+}
+
+/// Configuration for the RPC response cache
+#[derive(Debug, Clone, PartialEq, Deserialize)]
+#[serde(deny_unknown_fields)]
+pub struct RpcCacheSettings {
+    /// Answer immutable and TTL-cached requests from memory (default: true)
+    #[serde(default = "default_rpc_cache_enabled")]
+    pub enabled: bool,
+    /// Most answers kept; the oldest is evicted first (default: 10000)
+    #[serde(default = "default_rpc_cache_max_entries")]
+    pub max_entries: usize,
+    /// How long answers of further methods stay valid, in milliseconds by
+    /// method name (default: 1000 for `eth_gasPrice` and `eth_maxPriorityFeePerGas`)
+    #[serde(default = "default_rpc_cache_ttl_ms")]
+    pub ttl_ms: BTreeMap<String, u64>,
+}
+
+impl Default for RpcCacheSettings {
+    fn default() -> Self {
+        Self {
+            enabled: default_rpc_cache_enabled(),
+            max_entries: default_rpc_cache_max_entries(),
+            ttl_ms: default_rpc_cache_ttl_ms(),
+        }
+    }
+}
+
+fn default_rpc_cache_enabled() -> bool {
+    true
+}
+
+fn default_rpc_cache_max_entries() -> usize {
+    10_000
+}
+
+fn default_rpc_cache_ttl_ms() -> BTreeMap<String, u64> {
+    BTreeMap::from([
+        ("eth_gasPrice".to_string(), 1000),
+        ("eth_maxPriorityFeePerGas".to_string(), 1000),
+    ])
+}
+
+/// Configuration for RPC rate limits
+#[derive(Debug, Clone, PartialEq, Deserialize)]
+#[serde(deny_unknown_fields)]
+pub struct RateLimitSettings {
+    /// Requests per second to each RPC endpoint, 0 = unlimited (default: 0)
+    #[serde(default)]
+    pub requests_per_second: f64,
+    /// Requests per second by endpoint URL, overriding requests_per_second (default: {})
+    #[serde(default)]
+    pub endpoints: BTreeMap<String, f64>,
+    /// Requests that may go out at once before the rate applies (default: 10)
+    #[serde(default = "default_rate_limit_burst")]
+    pub burst: u32,
+    /// Retries of idempotent reads the RPC rate limited (default: 3)
+    #[serde(default = "default_rate_limit_max_retries")]
+    pub max_retries: u32,
+    /// Pause after a rate-limited answer without Retry-After, in milliseconds (default: 1000)
+    #[serde(default = "default_rate_limit_backoff_ms")]
+    pub backoff_ms: u64,
+    /// Longest pause taken, whatever Retry-After asks for, in milliseconds (default: 30000)
+    #[serde(default = "default_rate_limit_max_backoff_ms")]
+    pub max_backoff_ms: u64,
+}
+
+impl Default for RateLimitSettings {
+    fn default() -> Self {
+        Self {
+            requests_per_second: 0.0,
+            endpoints: BTreeMap::new(),
+            burst: default_rate_limit_burst(),
+            max_retries: default_rate_limit_max_retries(),
+            backoff_ms: default_rate_limit_backoff_ms(),
+            max_backoff_ms: default_rate_limit_max_backoff_ms(),
+        }
+    }
+}
+
+impl RateLimitSettings {
+    /// Requests per second allowed to `endpoint`, 0 = unlimited
+    pub fn rate_for(&self, endpoint: &str) -> f64 {
+        let endpoint = endpoint.trim_end_matches('/');
+        self.endpoints
+            .iter()
+            .find(|(url, _)| url.trim_end_matches('/') == endpoint)
+            .map_or(self.requests_per_second, |(_, rate)| *rate)
+    }
+}
+
+fn default_rate_limit_burst() -> u32 {
+    10
+}
+
+fn default_rate_limit_max_retries() -> u32 {
+    3
+}
+
+fn default_rate_limit_backoff_ms() -> u64 {
+    1000
+}
+
+fn default_rate_limit_max_backoff_ms() -> u64 {
+    30_000
+}
+
+/// Configuration for audit sampling
+#[derive(Debug, Clone, PartialEq, Deserialize)]
+#[serde(deny_unknown_fields)]
+pub struct AuditSettings {
+    /// Share of task runs audited, 0.0 to 1.0 (default: 0.0, disabled)
+    #[serde(default)]
+    pub sample_rate: f64,
+    /// Longest RPC params or calldata kept per entry, in bytes (default: 2048)
+    #[serde(default = "default_audit_max_param_bytes")]
+    pub max_param_bytes: usize,
+}
+
+impl Default for AuditSettings {
+    fn default() -> Self {
+        Self {
+            sample_rate: 0.0,
+            max_param_bytes: default_audit_max_param_bytes(),
+        }
+    }
+}
+
+fn default_audit_max_param_bytes() -> usize {
+    2048
+}
+
+/// Configuration for the task run [`watchdog`](crate::watchdog)
+#[derive(Debug, Clone, PartialEq, Deserialize)]
+#[serde(deny_unknown_fields)]
+pub struct WatchdogSettings {
+    /// Share of a task's timeout after which a diagnostics snapshot of the
+    /// run is taken, 0.0 to below 1.0, 0.0 = off (default: 0.8)
+    #[serde(default = "default_watchdog_soft_threshold")]
+    pub soft_threshold: f64,
+    /// RPC attempts slower than this are logged, in milliseconds, 0 = off (default: 10000)
+    #[serde(default = "default_watchdog_slow_rpc_ms")]
+    pub slow_rpc_ms: u64,
+    /// Provider errors kept per wallet for snapshots (default: 5)
+    #[serde(default = "default_watchdog_recent_errors")]
+    pub recent_errors: usize,
+}
+
+impl Default for WatchdogSettings {
+    fn default() -> Self {
+        Self {
+            soft_threshold: default_watchdog_soft_threshold(),
+            slow_rpc_ms: default_watchdog_slow_rpc_ms(),
+            recent_errors: default_watchdog_recent_errors(),
+        }
+    }
+}
+
+impl WatchdogSettings {
+    /// Time into a run with `timeout` at which it is snapshotted, `None` when off
+    pub fn soft_deadline(&self, timeout: Duration) -> Option<Duration> {
+        (self.soft_threshold > 0.0).then(|| timeout.mul_f64(self.soft_threshold))
+    }
+}
+
+fn default_watchdog_soft_threshold() -> f64 {
+    0.8
+}
+
+fn default_watchdog_slow_rpc_ms() -> u64 {
+    10_000
+}
+
+fn default_watchdog_recent_errors() -> usize {
+    5
+}
+
+/// Where the USD price of the fee unit comes from
+#[derive(Debug, Clone, Copy, Default, PartialEq, Eq, Deserialize)]
+#[serde(rename_all = "lowercase")]
+pub enum PriceSource {
+    /// `native_usd` as configured
+    #[default]
+    Fixed,
+    /// A number in the JSON document at `url`
+    Http,
+    /// `latestRoundData` of a Chainlink-style aggregator at `oracle`
+    Oracle,
+}
+
+/// Configuration for the USD [`price`](crate::price) feed
+#[derive(Debug, Clone, PartialEq, Deserialize)]
+#[serde(deny_unknown_fields)]
+pub struct PriceSettings {
+    /// Show USD values in `db stats`, `db budget`, bundles and the run log (default: false)
+    #[serde(default)]
+    pub enabled: bool,
+    /// fixed | http | oracle (default: "fixed")
+    #[serde(default)]
+    pub source: PriceSource,
+    /// USD per 10^18 wei of fees with `source = "fixed"`; Tempo prices gas
+    /// in attodollars, so 1.0 (default: 1.0)
+    #[serde(default = "default_price_native_usd")]
+    pub native_usd: f64,
+    /// JSON document holding the price with `source = "http"`
+    #[serde(default)]
+    pub url: String,
+    /// JSON pointer to the price in the document, e.g. "/ethereum/usd" (default: "/usd")
+    #[serde(default = "default_price_json_pointer")]
+    pub json_pointer: String,
+    /// Aggregator address with `source = "oracle"`
+    #[serde(default)]
+    pub oracle: Option<String>,
+    /// Endpoint the aggregator is read through (default: `rpc_url`)
+    #[serde(default)]
+    pub rpc_url: Option<String>,
+    /// USD per whole token by symbol or address (default: 1.0 for the system USD tokens)
+    #[serde(default)]
+    pub tokens: BTreeMap<String, f64>,
+    /// Decimals of the priced tokens, TIP-20 tokens have 6 (default: 6)
+    #[serde(default = "default_price_token_decimals")]
+    pub token_decimals: u8,
+    /// Seconds between price refreshes during a run (default: 3600)
+    #[serde(default = "default_price_refresh_secs")]
+    pub refresh_secs: u64,
+}
+
+impl Default for PriceSettings {
+    fn default() -> Self {
+        Self {
+            enabled: false,
+            source: PriceSource::default(),
+            native_usd: default_price_native_usd(),
+            url: String::new(),
+            json_pointer: default_price_json_pointer(),
+            oracle: None,
+            rpc_url: None,
+            tokens: BTreeMap::new(),
+            token_decimals: default_price_token_decimals(),
+            refresh_secs: default_price_refresh_secs(),
+        }
+    }
+}
+
+fn default_price_native_usd() -> f64 {
+    1.0
+}
+
+fn default_price_json_pointer() -> String {
+    "/usd".to_string()
+}
+
+fn default_price_token_decimals() -> u8 {
+    6
+}
+
+fn default_price_refresh_secs() -> u64 {
+    3600
+}
+
+/// What to do when `chain_id` differs from the node's `eth_chainId`
+#[derive(Debug, Clone, Copy, Default, PartialEq, Eq, Deserialize)]
+#[serde(rename_all = "lowercase")]
+pub enum ChainIdMismatch {
+    /// Refuse to start
+    #[default]
+    Fail,
+    /// Sign for the node's chain and warn
+    Adopt,
+}
+
+/// What to do when the node lacks a Tempo feature
+#[derive(Debug, Clone, Copy, Default, PartialEq, Eq, Deserialize)]
+#[serde(rename_all = "lowercase")]
+pub enum MissingCapability {
+    /// Warn and let tasks that need the feature fail fast
+    #[default]
+    Adjust,
+    /// Refuse to start
+    Fail,
+}
+
+/// Configuration for the startup [`capabilities`](crate::capabilities) probe
+#[derive(Debug, Clone, PartialEq, Deserialize)]
+#[serde(deny_unknown_fields)]
+pub struct CapabilitySettings {
+    /// Probe the node before the first worker starts (default: true)
+    #[serde(default = "default_capabilities_enabled")]
+    pub enabled: bool,
+    /// fail | adopt (default: "fail")
+    #[serde(default)]
+    pub on_chain_id_mismatch: ChainIdMismatch,
+    /// adjust | fail when Tempo transactions or 2D nonces are missing (default: "adjust")
+    #[serde(default)]
+    pub on_missing: MissingCapability,
+    /// Seconds the probe may take before startup goes on without it (default: 10)
+    #[serde(default = "default_capabilities_timeout_secs")]
+    pub timeout_secs: u64,
+}
+
+impl Default for CapabilitySettings {
+    fn default() -> Self {
+        Self {
+            enabled: true,
+            on_chain_id_mismatch: ChainIdMismatch::default(),
+            on_missing: MissingCapability::default(),
+            timeout_secs: default_capabilities_timeout_secs(),
+        }
+    }
+}
+
+fn default_capabilities_enabled() -> bool {
+    true
+}
+
+fn default_capabilities_timeout_secs() -> u64 {
+    10
+}
+
+/// Where the [`mempool`](crate::mempool) monitor reads pending transactions
+#[derive(Debug, Clone, Copy, Default, PartialEq, Eq, Deserialize)]
+#[serde(rename_all = "lowercase")]
+pub enum PendingSource {
+    /// `txpool_contentFrom`, falling back to nonces when the node lacks it
+    #[default]
+    Auto,
+    /// Pending minus latest `eth_getTransactionCount`
+    Nonce,
+    /// `txpool_contentFrom` only, also counting queued transactions
+    Txpool,
+}
+
+/// Configuration for the pending transaction [`mempool`](crate::mempool) monitor
+#[derive(Debug, Clone, PartialEq, Deserialize)]
+#[serde(deny_unknown_fields)]
+pub struct MempoolSettings {
+    /// Poll the pending transactions of leased wallets (default: false)
+    #[serde(default)]
+    pub enabled: bool,
+    /// Unconfirmed transactions at which a wallet is skipped (default: 4)
+    #[serde(default = "default_mempool_max_pending")]
+    pub max_pending: u64,
+    /// Seconds between polls of every known wallet (default: 10)
+    #[serde(default = "default_mempool_refresh_secs")]
+    pub refresh_secs: u64,
+    /// Wallets polled at the same time (default: 8)
+    #[serde(default = "default_mempool_concurrency")]
+    pub concurrency: usize,
+    /// auto | nonce | txpool (default: "auto")
+    #[serde(default)]
+    pub source: PendingSource,
+}
+
+impl Default for MempoolSettings {
+    fn default() -> Self {
+        Self {
+            enabled: false,
+            max_pending: default_mempool_max_pending(),
+            refresh_secs: default_mempool_refresh_secs(),
+            concurrency: default_mempool_concurrency(),
+            source: PendingSource::default(),
+        }
+    }
+}
+
+fn default_mempool_max_pending() -> u64 {
+    4
+}
+
+fn default_mempool_refresh_secs() -> u64 {
+    10
+}
+
+fn default_mempool_concurrency() -> usize {
+    8
+}
+
+/// Configuration for the shared [`chain_head`](crate::chain_head) watcher
+#[derive(Debug, Clone, PartialEq, Eq, Deserialize)]
+#[serde(deny_unknown_fields)]
+pub struct ChainHeadSettings {
+    /// Poll the latest block in the background for all tasks (default: true)
+    #[serde(default = "default_chain_head_enabled")]
+    pub enabled: bool,
+    /// Milliseconds between polls (default: 1000)
+    #[serde(default = "default_chain_head_poll_ms")]
+    pub poll_ms: u64,
+}
+
+impl Default for ChainHeadSettings {
+    fn default() -> Self {
+        Self {
+            enabled: default_chain_head_enabled(),
+            poll_ms: default_chain_head_poll_ms(),
+        }
+    }
+}
+
+fn default_chain_head_enabled() -> bool {
+    true
+}
+
+fn default_chain_head_poll_ms() -> u64 {
+    1000
+}
+
+/// Configuration for [`chaos`](crate::chaos) fault injection
+#[derive(Debug, Clone, Copy, PartialEq, Deserialize)]
+#[serde(deny_unknown_fields)]
+pub struct ChaosSettings {
+    /// Inject faults into RPC traffic, for test networks only (default: false)
+    #[serde(default)]
+    pub enabled: bool,
+    /// Share of calls whose answer is dropped after they reach the node,
+    /// 0.0 to 1.0 (default: 0.0)
+    #[serde(default)]
+    pub drop_rate: f64,
+    /// Share of calls answered with a fake HTTP 429, 0.0 to 1.0 (default: 0.0)
+    #[serde(default)]
+    pub rate_limit_rate: f64,
+    /// Share of `eth_sendRawTransaction` calls refused with "nonce too low",
+    /// 0.0 to 1.0 (default: 0.0)
+    #[serde(default)]
+    pub nonce_too_low_rate: f64,
+    /// Share of transaction receipts held back, 0.0 to 1.0 (default: 0.0)
+    #[serde(default)]
+    pub receipt_delay_rate: f64,
+    /// Milliseconds a held back receipt is reported missing (default: 5000)
+    #[serde(default = "default_chaos_receipt_delay_ms")]
+    pub receipt_delay_ms: u64,
+}
+
+impl Default for ChaosSettings {
+    fn default() -> Self {
+        Self {
+            enabled: false,
+            drop_rate: 0.0,
+            rate_limit_rate: 0.0,
+            nonce_too_low_rate: 0.0,
+            receipt_delay_rate: 0.0,
+            receipt_delay_ms: default_chaos_receipt_delay_ms(),
+        }
+    }
+}
+
+fn default_chaos_receipt_delay_ms() -> u64 {
+    5000
+}
+
+/// Configuration for [`task_slo`](crate::task_slo) tracking
+#[derive(Debug, Clone, Copy, PartialEq, Deserialize)]
+#[serde(deny_unknown_fields)]
+pub struct SloSettings {
+    /// Share of successful runs in the window below which a task is paused,
+    /// 0.0 to 1.0, 0.0 = off (default: 0.0)
+    #[serde(default)]
+    pub min_success_rate: f64,
+    /// Seconds of runs per task the success rate covers (default: 900)
+    #[serde(default = "default_slo_window_secs")]
+    pub window_secs: u64,
+    /// Runs a task needs in the window before it can be paused (default: 20)
+    #[serde(default = "default_slo_min_runs")]
+    pub min_runs: usize,
+    /// Seconds between probe runs of a paused task (default: 300)
+    #[serde(default = "default_slo_probe_secs")]
+    pub probe_secs: u64,
+}
+
+impl Default for SloSettings {
+    fn default() -> Self {
+        Self {
+            min_success_rate: 0.0,
+            window_secs: default_slo_window_secs(),
+            min_runs: default_slo_min_runs(),
+            probe_secs: default_slo_probe_secs(),
+        }
+    }
+}
+
+impl SloSettings {
+    pub fn enabled(&self) -> bool {
+        self.min_success_rate > 0.0
+    }
+}
+
+fn default_slo_window_secs() -> u64 {
+    900
+}
+
+fn default_slo_min_runs() -> usize {
+    20
+}
+
+fn default_slo_probe_secs() -> u64 {
+    300
+}
+
+/// Where [`log_archive`](crate::log_archive) writes receipt logs
+#[derive(Debug, Clone, Copy, PartialEq, Eq, Default, Deserialize)]
+#[serde(rename_all = "lowercase")]
+pub enum LogSink {
+    /// The `receipt_logs` table, log data zlib-compressed
+    #[default]
+    Db,
+    /// One JSON line per log appended to `path`
+    Jsonl,
+}
+
+/// Configuration for [`log_archive`](crate::log_archive)
+#[derive(Debug, Clone, PartialEq, Deserialize)]
+#[serde(deny_unknown_fields)]
+pub struct LogArchiveSettings {
+    /// Archive the receipt logs of the transaction each task run reports (default: false)
+    #[serde(default)]
+    pub enabled: bool,
+    /// Tasks whose transactions are archived, every task when empty (default: [])
+    #[serde(default)]
+    pub tasks: Vec<String>,
+    /// db or jsonl (default: db)
+    #[serde(default)]
+    pub sink: LogSink,
+    /// File of the jsonl sink, gzip-compressed when it ends in `.gz`
+    /// (default: "receipt-logs.jsonl.gz")
+    #[serde(default = "default_log_archive_path")]
+    pub path: String,
+}
+
+impl Default for LogArchiveSettings {
+    fn default() -> Self {
+        Self {
+            enabled: false,
+            tasks: Vec::new(),
+            sink: LogSink::default(),
+            path: default_log_archive_path(),
+        }
+    }
+}
+
+impl LogArchiveSettings {
+    /// Whether runs of `task` get their logs archived
+    pub fn archives(&self, task: &str) -> bool {
+        self.enabled && (self.tasks.is_empty() || self.tasks.iter().any(|t| t == task))
+    }
+}
+
+fn default_log_archive_path() -> String {
+    "receipt-logs.jsonl.gz".to_string()
+}
+
+/// Configuration for `--local-dev` runs against anvil
+#[derive(Debug, Clone, PartialEq, Deserialize)]
+#[serde(deny_unknown_fields)]
+pub struct LocalDevSettings {
+    /// Anvil binary to start (default: "anvil" from PATH)
+    #[serde(default = "default_local_dev_anvil")]
+    pub anvil: String,
+    /// Running anvil-compatible node to use instead of starting one (default: none)
+    #[serde(default)]
+    pub rpc_url: Option<String>,
+    /// Number of throwaway wallets running the tasks (default: 2)
+    #[serde(default = "default_local_dev_wallets")]
+    pub wallets: usize,
+    /// Tasks to run, in order (default: the tasks the mock system contracts support)
+    #[serde(default = "default_local_dev_tasks")]
+    pub tasks: Vec<String>,
+    /// Seconds to wait for the node to answer (default: 15)
+    #[serde(default = "default_local_dev_startup")]
+    pub startup_timeout_secs: u64,
+}
+
+impl Default for LocalDevSettings {
+    fn default() -> Self {
+        Self {
+            anvil: default_local_dev_anvil(),
+            rpc_url: None,
+            wallets: default_local_dev_wallets(),
+            tasks: default_local_dev_tasks(),
+            startup_timeout_secs: default_local_dev_startup(),
+        }
+    }
+}
+
+fn default_local_dev_anvil() -> String {
+    "anvil".to_string()
+}
+
+fn default_local_dev_wallets() -> usize {
+    2
+}
+
+fn default_local_dev_tasks() -> Vec<String> {
+    crate::local_dev::DEFAULT_TASKS
+        .iter()
+        .map(|task| task.to_string())
+        .collect()
+}
+
+fn default_local_dev_startup() -> u64 {
+    15
+}
+
+/// Configuration for read-your-writes consistency checks
+#[derive(Debug, Clone, PartialEq, Deserialize)]
+#[serde(deny_unknown_fields)]
+pub struct VerifySettings {
+    /// Whether state-changing tasks re-read their writes (default: false)
+    #[serde(default)]
+    pub enabled: bool,
+    /// Share of successful state changes that get checked, 0.0 to 1.0 (default: 1.0)
+    #[serde(default = "default_verify_sample_rate")]
+    pub sample_rate: f64,
+    /// Delay before the first re-read in milliseconds (default: 3000)
+    #[serde(default = "default_verify_delay_ms")]
+    pub delay_ms: u64,
+    /// Interval between re-reads until the state shows up in milliseconds (default: 1000)
+    #[serde(default = "default_verify_poll_ms")]
+    pub poll_ms: u64,
+    /// Give up and record an inconsistency after this many seconds (default: 30)
+    #[serde(default = "default_verify_timeout")]
+    pub timeout_secs: u64,
+    /// Endpoint to re-read through (default: `rpc_url` via another proxy)
+    #[serde(default)]
+    pub rpc_url: Option<String>,
+}
+
+impl Default for VerifySettings {
+    fn default() -> Self {
+        Self {
+            enabled: false,
+            sample_rate: default_verify_sample_rate(),
+            delay_ms: default_verify_delay_ms(),
+            poll_ms: default_verify_poll_ms(),
+            timeout_secs: default_verify_timeout(),
+            rpc_url: None,
+        }
+    }
+}
+
+fn default_verify_sample_rate() -> f64 {
+    1.0
+}
+
+fn default_verify_delay_ms() -> u64 {
+    3000
+}
+
+fn default_verify_poll_ms() -> u64 {
+    1000
+}
+
+fn default_verify_timeout() -> u64 {
+    30
+}
+
+/// Configuration for coordinated burst rounds across instances
+#[derive(Debug, Clone, PartialEq, Deserialize)]
+#[serde(deny_unknown_fields)]
+pub struct BurstSettings {
+    /// Transactions per second each instance sends during the burst (default: 20)
+    #[serde(default = "default_burst_rate")]
+    pub rate: f64,
+    /// Length of the burst window in seconds (default: 10)
+    #[serde(default = "default_burst_duration")]
+    pub duration_secs: u64,
+    /// Delay from the first instance joining to the start, so others can join (default: 30)
+    #[serde(default = "default_burst_lead")]
+    pub lead_secs: u64,
+    /// How long to wait for receipts after the burst (default: 60)
+    #[serde(default = "default_burst_settle")]
+    pub settle_secs: u64,
+    /// Transactions each wallet signs before the start and sends at once when
+    /// every wallet is released together; 0 sends at `rate` over
+    /// `duration_secs` instead (default: 0)
+    #[serde(default)]
+    pub presign: u32,
+    /// Inspect the landed blocks for the subblock of each transaction and
+    /// record its latency samples (default: true)
+    #[serde(default = "default_burst_subblocks")]
+    pub subblocks: bool,
+}
+
+impl Default for BurstSettings {
+    fn default() -> Self {
+        Self {
+            rate: default_burst_rate(),
+            duration_secs: default_burst_duration(),
+            lead_secs: default_burst_lead(),
+            settle_secs: default_burst_settle(),
+            presign: 0,
+            subblocks: default_burst_subblocks(),
+        }
+    }
+}
+
+fn default_burst_rate() -> f64 {
+    20.0
+}
+
+fn default_burst_duration() -> u64 {
+    10
+}
+
+fn default_burst_lead() -> u64 {
+    30
+}
+
+fn default_burst_settle() -> u64 {
+    60
+}
+
+fn default_burst_subblocks() -> bool {
+    true
+}
+
+/// Configuration for scaling worker activity through the day
+#[derive(Debug, Clone, PartialEq, Deserialize)]
+#[serde(deny_unknown_fields)]
+pub struct ActivitySettings {
+    /// IANA time zone the hours below are in (default: "UTC")
+    #[serde(default = "default_activity_timezone")]
+    pub timezone: String,
+    /// Share of workers active in each hour, 24 values from 0.0 to 1.0 (default: all 1.0)
+    #[serde(default)]
+    pub hourly: Vec<f64>,
+    /// Hour ranges with no activity at all, e.g. `["01-06"]` (default: none)
+    #[serde(default)]
+    pub quiet_hours: Vec<HourWindow>,
+    /// Minutes over which the level moves from one hour to the next, 0 = step (default: 30)
+    #[serde(default = "default_ramp_minutes")]
+    pub ramp_minutes: u32,
+}
+
+impl Default for ActivitySettings {
+    fn default() -> Self {
+        Self {
+            timezone: default_activity_timezone(),
+            hourly: Vec::new(),
+            quiet_hours: Vec::new(),
+            ramp_minutes: default_ramp_minutes(),
+        }
+    }
+}
+
+fn default_activity_timezone() -> String {
+    "UTC".to_string()
+}
+
+fn default_ramp_minutes() -> u32 {
+    30
+}
+
+/// Configuration for polling task settings from a central URL
+#[derive(Debug, Clone, Deserialize)]
+#[serde(deny_unknown_fields)]
+pub struct RemoteSettings {
+    /// JSON or TOML document with `weights` and `schedule` tables, empty = disabled (default: "")
+    #[serde(default)]
+    pub url: String,
+    /// Seconds between polls (default: 300)
+    #[serde(default = "default_remote_poll_interval")]
+    pub poll_interval_secs: u64,
+    /// Hex digest of the document, default: `url` + ".sig"
+    #[serde(default)]
+    pub signature_url: Option<String>,
+    /// Environment variable holding the HMAC key; without it the digest is a plain SHA-256 (default: "TEMPO_REMOTE_KEY")
+    #[serde(default = "default_remote_key_env")]
+    pub key_env: String,
+    /// Last verified document, used when the URL is unreachable at startup (default: "remote-tasks.cache")
+    #[serde(default = "default_remote_cache_file")]
+    pub cache_file: String,
+}
+
+impl Default for RemoteSettings {
+    fn default() -> Self {
+        Self {
+            url: String::new(),
+            poll_interval_secs: default_remote_poll_interval(),
+            signature_url: None,
+            key_env: default_remote_key_env(),
+            cache_file: default_remote_cache_file(),
+        }
+    }
+}
+
+impl RemoteSettings {
+    pub fn enabled(&self) -> bool {
+        !self.url.is_empty()
+    }
+
+    /// Where the document digest is published
+    pub fn signature_url(&self) -> String {
+        self.signature_url
+            .clone()
+            .unwrap_or_else(|| format!("{}.sig", self.url))
+    }
+}
+
+fn default_remote_poll_interval() -> u64 {
+    300
+}
+
+fn default_remote_key_env() -> String {
+    "TEMPO_REMOTE_KEY".to_string()
+}
+
+fn default_remote_cache_file() -> String {
+    "remote-tasks.cache".to_string()
+}
+
+/// Prefix of environment variables that override config keys
+pub const ENV_PREFIX: &str = "TEMPO__";
+
+/// A config key overridden by a `TEMPO__SECTION__KEY` environment variable
+///
+/// Path segments are separated by `__` and lowercased, so
+/// `TEMPO__PROXY__ASSIGNMENT` sets `assignment` in `[proxy]`. Values are read
+/// as TOML literals (`20`, `true`, `[1, 2]`) and fall back to a plain string,
+/// so URLs need no quoting. A literal set on a string key, like
+/// `TEMPO__WALLETS__PASSWORD_FILE=1234`, is read as the string instead.
+#[derive(Debug, Clone, PartialEq)]
+pub struct EnvOverride {
+    /// Environment variable name
+    pub var: String,
+    /// Key path, table names first
+    pub path: Vec<String>,
+    /// Parsed value
+    pub value: toml::Value,
+    /// Value as set in the environment
+    pub raw: String,
+}
+
+impl EnvOverride {
+    /// Collects overrides from environment variables, sorted by name
+    pub fn from_vars(vars: impl IntoIterator<Item = (String, String)>) -> Vec<Self> {
+        let mut overrides: Vec<Self> = vars
+            .into_iter()
+            .filter_map(|(var, raw)| {
+                let path: Vec<String> = var
+                    .strip_prefix(ENV_PREFIX)?
+                    .split("__")
+                    .map(str::to_lowercase)
+                    .collect();
+                if path.iter().any(String::is_empty) {
+                    return None;
+                }
+                Some(Self {
+                    value: parse_env_value(&raw),
+                    raw,
+                    var,
+                    path,
+                })
+            })
+            .collect();
+        overrides.sort_by(|a, b| a.var.cmp(&b.var));
+        overrides
+    }
+
+    /// Dotted key path, e.g. `nonce.shard_count`
+    pub fn key(&self) -> String {
+        self.path.join(".")
+    }
+
+    /// Sets the value in a parsed config table, creating missing tables
+    pub fn apply(&self, table: &mut toml::Table) -> Result<()> {
+        let (key, tables) = self
+            .path
+            .split_last()
+            .context("Empty config override path")?;
+
+        let mut current = table;
+        for name in tables {
+            current = current
+                .entry(name.clone())
+                .or_insert_with(|| toml::Value::Table(toml::Table::new()))
+                .as_table_mut()
+                .with_context(|| format!("{}: '{}' is not a config table", self.var, name))?;
+        }
+        current.insert(key.clone(), self.value.clone());
+        Ok(())
+    }
+}
+
+/// Deserializes a config table with `overrides` applied
+///
+/// Each overridden key is read once, as its raw string where the config
+/// expects a string or path and as its TOML literal everywhere else, so
+/// numeric-looking paths and names still reach string keys.
+fn deserialize_overridden(
+    table: toml::Table,
+    overrides: &[EnvOverride],
+) -> std::result::Result<TempoSpammerConfig, toml::de::Error> {
+    let mut root = Overridden::Value(toml::Value::Table(table));
+    for over in overrides {
+        root.mark(&over.path, over);
+    }
+    TempoSpammerConfig::deserialize(root)
+}
+
+/// A config table with environment overrides at some of its leaves
+enum Overridden {
+    Value(toml::Value),
+    Table(BTreeMap<String, Overridden>),
+    Env { raw: String, literal: toml::Value },
+}
+
+impl Overridden {
+    /// Marks the key at `path` as set by `over`, splitting the tables above it
+    fn mark(&mut self, path: &[String], over: &EnvOverride) {
+        let Some((name, rest)) = path.split_first() else {
+            *self = Overridden::Env {
+                raw: over.raw.clone(),
+                literal: over.value.clone(),
+            };
+            return;
+        };
+        if let Overridden::Value(toml::Value::Table(table)) = self {
+            let table = std::mem::take(table)
+                .into_iter()
+                .map(|(key, value)| (key, Overridden::Value(value)))
+                .collect();
+            *self = Overridden::Table(table);
+        }
+        // Paths through other values were rejected by `EnvOverride::apply`
+        if let Overridden::Table(table) = self {
+            table
+                .entry(name.clone())
+                .or_insert_with(|| Overridden::Value(toml::Value::Table(toml::Table::new())))
+                .mark(rest, over);
+        }
+    }
+}
+
+impl<'de> serde::de::IntoDeserializer<'de, toml::de::Error> for Overridden {
+    type Deserializer = Self;
+
+    fn into_deserializer(self) -> Self {
+        self
+    }
+}
+
+impl<'de> serde::Deserializer<'de> for Overridden {
+    type Error = toml::de::Error;
+
+    fn deserialize_any<V: serde::de::Visitor<'de>>(
+        self,
+        visitor: V,
+    ) -> std::result::Result<V::Value, Self::Error> {
+        match self {
+            Overridden::Value(value) | Overridden::Env { literal: value, .. } => {
+                value.deserialize_any(visitor)
+            }
+            Overridden::Table(table) => {
+                visitor.visit_map(serde::de::value::MapDeserializer::new(table.into_iter()))
+            }
+        }
+    }
+
+    fn deserialize_str<V: serde::de::Visitor<'de>>(
+        self,
+        visitor: V,
+    ) -> std::result::Result<V::Value, Self::Error> {
+        self.deserialize_string(visitor)
+    }
+
+    fn deserialize_string<V: serde::de::Visitor<'de>>(
+        self,
+        visitor: V,
+    ) -> std::result::Result<V::Value, Self::Error> {
+        match self {
+            Overridden::Env { raw, .. } => visitor.visit_string(raw),
+            other => other.deserialize_any(visitor),
+        }
+    }
+
+    fn deserialize_option<V: serde::de::Visitor<'de>>(
+        self,
+        visitor: V,
+    ) -> std::result::Result<V::Value, Self::Error> {
+        match self {
+            Overridden::Value(value) => value.deserialize_option(visitor),
+            other => visitor.visit_some(other),
+        }
+    }
+
+    fn deserialize_newtype_struct<V: serde::de::Visitor<'de>>(
+        self,
+        name: &'static str,
+        visitor: V,
+    ) -> std::result::Result<V::Value, Self::Error> {
+        match self {
+            Overridden::Value(value) => value.deserialize_newtype_struct(name, visitor),
+            other => visitor.visit_newtype_struct(other),
+        }
+    }
+
+    fn deserialize_enum<V: serde::de::Visitor<'de>>(
+        self,
+        name: &'static str,
+        variants: &'static [&'static str],
+        visitor: V,
+    ) -> std::result::Result<V::Value, Self::Error> {
+        match self {
+            Overridden::Value(value) | Overridden::Env { literal: value, .. } => {
+                value.deserialize_enum(name, variants, visitor)
+            }
+            table @ Overridden::Table(_) => table.deserialize_any(visitor),
+        }
+    }
+
+    serde::forward_to_deserialize_any! {
+        bool i8 i16 i32 i64 i128 u8 u16 u32 u64 u128 f32 f64 char bytes byte_buf
+        unit unit_struct seq tuple tuple_struct map struct identifier ignored_any
+    }
+}
+
+/// The `[profiles.<name>]` table of a parsed config file
+fn profile_table(table: &toml::Table, name: &str, source: &str) -> Result<toml::Table> {
+    let profiles = table.get("profiles").and_then(toml::Value::as_table);
+    match profiles.and_then(|profiles| profiles.get(name)) {
+        Some(toml::Value::Table(profile)) => Ok(profile.clone()),
+        Some(_) => anyhow::bail!("profiles.{} in {} is not a table", name, source),
+        None => {
+            let known: Vec<&str> = profiles
+                .map(|profiles| profiles.keys().map(String::as_str).collect())
+                .unwrap_or_default();
+            if known.is_empty() {
+                anyhow::bail!("Unknown profile '{}': {} defines no profiles", name, source)
+            }
+            anyhow::bail!(
+                "Unknown profile '{}': {} defines {}",
+                name,
+                source,
+                known.join(", ")
+            )
+        }
+    }
+}
+
+/// Merges `layer` into `base`, table by table; other values are replaced
+fn merge_table(base: &mut toml::Table, layer: toml::Table) {
+    for (key, value) in layer {
+        match (base.get_mut(&key), value) {
+            (Some(toml::Value::Table(base)), toml::Value::Table(layer)) => merge_table(base, layer),
+            (Some(slot), value) => *slot = value,
+            (None, value) => {
+                base.insert(key, value);
+            }
+        }
+    }
+}
+
+fn parse_env_value(raw: &str) -> toml::Value {
+    toml::from_str::<toml::Table>(&format!("value = {}", raw))
+        .ok()
+        .and_then(|mut table| table.remove("value"))
+        .unwrap_or_else(|| toml::Value::String(raw.to_string()))
+}
+
+fn deserialize_u128<'de, D>(deserializer: D) -> Result<u128, D::Error>
+where
+    D: serde::Deserializer<'de>,
+{
+    struct U128Visitor;
+
+    impl<'de> serde::de::Visitor<'de> for U128Visitor {
+        type Value = u128;
+
+        fn expecting(&self, formatter: &mut std::fmt::Formatter) -> std::fmt::Result {
+            formatter.write_str("a string or integer representing a u128")
+        }
+
+        fn visit_str<E>(self, value: &str) -> Result<Self::Value, E>
+        where
+            E: serde::de::Error,
+        {
+            u128::from_str(value).map_err(|_| E::custom("invalid u128"))
+        }
+
+        fn visit_u64<E>(self, value: u64) -> Result<Self::Value, E>
+        where
+            E: serde::de::Error,
+        {
+            Ok(value as u128)
+        }
+
+        fn visit_i64<E>(self, value: i64) -> Result<Self::Value, E>
+        where
+            E: serde::de::Error,
+        {
+            if value >= 0 {
+                Ok(value as u128)
+            } else {
+                Err(E::custom("negative u128"))
+            }
+        }
+    }
+
+    deserializer.deserialize_any(U128Visitor)
+}
+
+impl TempoSpammerConfig {
+    /// Load configuration from a TOML file
+    ///
+    /// Any key can be overridden with a `TEMPO__SECTION__KEY` environment
+    /// variable (see [`EnvOverride`]), e.g. `TEMPO__WORKER_COUNT=20` or
+    /// `TEMPO__NONCE__SHARD_COUNT=64`.
+    ///
+    /// # Arguments
+    /// * `path` - Path to the config.toml file
+    ///
+    /// # Example
+    /// ```ignore
+    /// let config = TempoSpammerConfig::from_path("config/config.toml")?;
+    /// ```
+    pub fn from_path(path: impl AsRef<Path>) -> Result<Self> {
+        Self::from_path_with_profile(path, None)
+    }
+
+    /// Load configuration from a TOML file with one of its profiles applied
+    ///
+    /// The `[profiles.<name>]` table of `profile` is merged over the rest of
+    /// the file, table by table, before environment overrides; `None` loads
+    /// the file's own settings. Fails when the file has no such profile.
+    pub fn from_path_with_profile(path: impl AsRef<Path>, profile: Option<&str>) -> Result<Self> {
+        let path = path.as_ref();
+        let content = fs::read_to_string(path)
+            .with_context(|| format!("Failed to read config from {}", path.display()))?;
+        let overrides = EnvOverride::from_vars(std::env::vars());
+        let config =
+            Self::from_toml_profile(&content, &path.display().to_string(), profile, &overrides)?;
+        config.validate()?;
+        Ok(config)
+    }
+
+    /// Checks values the types alone don't constrain
+    pub fn validate(&self) -> Result<()> {
+        if !(0.0..=1.0).contains(&self.tracing.sample_ratio) {
+            anyhow::bail!(
+                "tracing.sample_ratio must be between 0 and 1, got {}",
+                self.tracing.sample_ratio
+            );
+        }
+        if let Some(partition) = &self.wallets.partition {
+            partition
+                .validate()
+                .context("Invalid [wallets] partition")?;
+        }
+        for (name, persona) in &self.personas {
+            if !persona.amount_scale.is_finite() || persona.amount_scale <= 0.0 {
+                anyhow::bail!(
+                    "personas.{}.amount_scale must be a positive number, got {}",
+                    name,
+                    persona.amount_scale
+                );
+            }
+            if let Some((task, weight)) = persona
+                .weights
+                .iter()
+                .find(|(_, weight)| !weight.is_finite() || **weight < 0.0)
+            {
+                anyhow::bail!(
+                    "personas.{}.weights.\"{}\" must be a non-negative number, got {}",
+                    name,
+                    task,
+                    weight
+                );
+            }
+        }
+        if !self.personas.is_empty() && self.personas.values().all(|p| p.share == 0) {
+            anyhow::bail!("At least one persona needs a share above 0");
+        }
+        crate::activity::ActivitySchedule::from_settings(&self.activity)?;
+        crate::budget::SpendBudget::new(&self.budget)?;
+        if self.burst.rate.is_nan() || self.burst.rate <= 0.0 {
+            anyhow::bail!("burst.rate must be above 0, got {}", self.burst.rate);
+        }
+        if self.session_keys.enabled {
+            crate::session_key::SessionKeys::new(&self.session_keys)?;
+        }
+        if !crate::control::is_pipe(&self.control.listen) {
+            self.control
+                .listen
+                .parse::<std::net::SocketAddr>()
+                .with_context(|| format!("Invalid control.listen '{}'", self.control.listen))?;
+        }
+        if !(0.0..1.0).contains(&self.pacing.interval_jitter) {
+            anyhow::bail!(
+                "pacing.interval_jitter must be within 0.0..1.0, got {}",
+                self.pacing.interval_jitter
+            );
+        }
+        if !(0.0..=1.0).contains(&self.audit.sample_rate) {
+            anyhow::bail!(
+                "audit.sample_rate must be within 0.0..=1.0, got {}",
+                self.audit.sample_rate
+            );
+        }
+        if !(0.0..1.0).contains(&self.watchdog.soft_threshold) {
+            anyhow::bail!(
+                "watchdog.soft_threshold must be within 0.0..1.0, got {}",
+                self.watchdog.soft_threshold
+            );
+        }
+        if !(0.0..=1.0).contains(&self.rpc_stats.max_error_rate) {
+            anyhow::bail!(
+                "rpc_stats.max_error_rate must be within 0.0..=1.0, got {}",
+                self.rpc_stats.max_error_rate
+            );
+        }
+        if self.price.enabled {
+            match self.price.source {
+                PriceSource::Fixed if self.price.native_usd < 0.0 => {
+                    anyhow::bail!("price.native_usd must not be negative")
+                }
+                PriceSource::Http if self.price.url.is_empty() => {
+                    anyhow::bail!("price.url is required with price.source = \"http\"")
+                }
+                PriceSource::Oracle => {
+                    let oracle = self
+                        .price
+                        .oracle
+                        .as_deref()
+                        .context("price.oracle is required with price.source = \"oracle\"")?;
+                    Address::from_str(oracle)
+                        .with_context(|| format!("Invalid price.oracle '{}'", oracle))?;
+                }
+                _ => {}
+            }
+            for token in self.price.tokens.keys() {
+                crate::budget::resolve_token(token)
+                    .with_context(|| format!("Invalid price.tokens key '{}'", token))?;
+            }
+        }
+        if self.capabilities.enabled && self.capabilities.timeout_secs == 0 {
+            anyhow::bail!("capabilities.timeout_secs must be above 0");
+        }
+        if self.mempool.enabled {
+            if self.mempool.max_pending == 0 {
+                anyhow::bail!("mempool.max_pending must be above 0");
+            }
+            if self.mempool.refresh_secs == 0 {
+                anyhow::bail!("mempool.refresh_secs must be above 0");
+            }
+            if self.mempool.concurrency == 0 {
+                anyhow::bail!("mempool.concurrency must be above 0");
+            }
+        }
+        if self.chain_head.enabled && self.chain_head.poll_ms == 0 {
+            anyhow::bail!("chain_head.poll_ms must be above 0");
+        }
+        if !self.chain_head.enabled && !self.tasks.every_blocks.is_empty() {
+            anyhow::bail!("tasks.every_blocks needs [chain_head] enabled");
+        }
+        for (name, rate) in [
+            ("drop_rate", self.chaos.drop_rate),
+            ("rate_limit_rate", self.chaos.rate_limit_rate),
+            ("nonce_too_low_rate", self.chaos.nonce_too_low_rate),
+            ("receipt_delay_rate", self.chaos.receipt_delay_rate),
+        ] {
+            if !(0.0..=1.0).contains(&rate) {
+                anyhow::bail!("chaos.{} must be within 0.0..=1.0, got {}", name, rate);
+            }
+        }
+        if !(0.0..=1.0).contains(&self.slo.min_success_rate) {
+            anyhow::bail!(
+                "slo.min_success_rate must be within 0.0..=1.0, got {}",
+                self.slo.min_success_rate
+            );
+        }
+        if self.slo.enabled() {
+            if self.slo.window_secs == 0 {
+                anyhow::bail!("slo.window_secs must be above 0");
+            }
+            if self.slo.min_runs == 0 {
+                anyhow::bail!("slo.min_runs must be above 0");
+            }
+            if self.slo.probe_secs == 0 {
+                anyhow::bail!("slo.probe_secs must be above 0");
+            }
+        }
+        if self.log_archive.sink == LogSink::Jsonl && self.log_archive.path.trim().is_empty() {
+            anyhow::bail!("log_archive.path must be set for the jsonl sink");
+        }
+        if let Some((task, _)) = self.gas.limits.iter().find(|(_, limit)| **limit == 0) {
+            anyhow::bail!("gas.limits.\"{}\" must be above 0", task);
+        }
+        if self.proxy.bans.tiers_minutes.is_empty() {
+            anyhow::bail!("proxy.bans.tiers_minutes must not be empty");
+        }
+        if self.proxy.bans.tiers_minutes.contains(&0) {
+            anyhow::bail!("proxy.bans.tiers_minutes entries must be above 0");
+        }
+        if self.proxy.bans.failure_threshold == 0 {
+            anyhow::bail!("proxy.bans.failure_threshold must be at least 1");
+        }
+        for target in &self.proxy.health.targets {
+            let url = url::Url::parse(target)
+                .with_context(|| format!("proxy.health.targets entry '{}' is not a URL", target))?;
+            if !matches!(url.scheme(), "http" | "https") {
+                anyhow::bail!(
+                    "proxy.health.targets must be http(s) URLs, got '{}'",
+                    target
+                );
+            }
+        }
+        if self.proxy.health.required_successes == 0 {
+            anyhow::bail!("proxy.health.required_successes must be at least 1");
+        }
+        if self.proxy.health.concurrency == 0 {
+            anyhow::bail!("proxy.health.concurrency must be at least 1");
+        }
+        let rate_limit = &self.rate_limit;
+        if !(rate_limit.requests_per_second.is_finite() && rate_limit.requests_per_second >= 0.0) {
+            anyhow::bail!(
+                "rate_limit.requests_per_second must be 0 or above, got {}",
+                rate_limit.requests_per_second
+            );
+        }
+        for (endpoint, rate) in &rate_limit.endpoints {
+            url::Url::parse(endpoint)
+                .with_context(|| format!("rate_limit.endpoints key '{}' is not a URL", endpoint))?;
+            if !(rate.is_finite() && *rate > 0.0) {
+                anyhow::bail!(
+                    "rate_limit.endpoints.\"{}\" must be above 0, got {}",
+                    endpoint,
+                    rate
+                );
+            }
+        }
+        if rate_limit.burst == 0 {
+            anyhow::bail!("rate_limit.burst must be at least 1");
+        }
+        if rate_limit.max_backoff_ms < rate_limit.backoff_ms {
+            anyhow::bail!(
+                "rate_limit.max_backoff_ms must be at least backoff_ms ({})",
+                rate_limit.backoff_ms
+            );
+        }
+        if self.stress.calldata_sizes.is_empty() || self.stress.call_counts.is_empty() {
+            anyhow::bail!("stress.calldata_sizes and stress.call_counts must not be empty");
+        }
+        if self.stress.call_counts.contains(&0) {
+            anyhow::bail!("stress.call_counts entries must be at least 1");
+        }
+        if !(self.stress.gas_fraction > 0.0 && self.stress.gas_fraction <= 2.0) {
+            anyhow::bail!(
+                "stress.gas_fraction must be within (0.0, 2.0], got {}",
+                self.stress.gas_fraction
+            );
+        }
+        let helper_names: Vec<_> = crate::contracts::helper_contracts()
+            .iter()
+            .map(|helper| helper.name)
+            .collect();
+        for (chain, pinned) in &self.helpers.pinned {
+            if chain.parse::<u64>().is_err() {
+                anyhow::bail!("helpers.pinned keys must be chain ids, got '{}'", chain);
+            }
+            if let Some(name) = pinned
+                .keys()
+                .find(|name| !helper_names.contains(&name.as_str()))
+            {
+                anyhow::bail!(
+                    "helpers.pinned.{} names unknown helper '{}', expected one of {:?}",
+                    chain,
+                    name,
+                    helper_names
+                );
+            }
+        }
+        if self.bundle.dir.trim().is_empty() {
+            anyhow::bail!("bundle.dir must not be empty");
+        }
+        if !self.aa.bundler_url.is_empty() {
+            let url = url::Url::parse(&self.aa.bundler_url).with_context(|| {
+                format!("aa.bundler_url '{}' is not a URL", self.aa.bundler_url)
+            })?;
+            if !matches!(url.scheme(), "http" | "https") {
+                anyhow::bail!("aa.bundler_url must be an http(s) URL");
+            }
+        }
+        if self.aa.max_accounts == 0 {
+            anyhow::bail!("aa.max_accounts must be at least 1");
+        }
+        if self.aa.receipt_timeout_secs == 0 {
+            anyhow::bail!("aa.receipt_timeout_secs must be above 0");
+        }
+        if !(0.0..=1.0).contains(&self.recipients.pool_share) {
+            anyhow::bail!(
+                "recipients.pool_share must be within 0.0..=1.0, got {}",
+                self.recipients.pool_share
+            );
+        }
+        if self.marketplace.max_listings == 0 {
+            anyhow::bail!("marketplace.max_listings must be at least 1");
+        }
+        if self.domains.renew_duration_secs == 0 {
+            anyhow::bail!("domains.renew_duration_secs must be above 0");
+        }
+        if self.swap.slippage_bps >= crate::tasks::fee_amm::BPS {
+            anyhow::bail!(
+                "swap.slippage_bps must be below {}, got {}",
+                crate::tasks::fee_amm::BPS,
+                self.swap.slippage_bps
+            );
+        }
+        if self.local_dev.wallets == 0 {
+            anyhow::bail!("local_dev.wallets must be at least 1");
+        }
+        if self.wallet_health.threshold > crate::wallet_health::FULL_SCORE {
+            anyhow::bail!(
+                "wallet_health.threshold must be at most {}, got {}",
+                crate::wallet_health::FULL_SCORE,
+                self.wallet_health.threshold
+            );
+        }
+        if self.wallet_health.max_consecutive_failures == 0 {
+            anyhow::bail!("wallet_health.max_consecutive_failures must be at least 1");
+        }
+        if self.wallet_health.recheck_secs == 0 {
+            anyhow::bail!("wallet_health.recheck_secs must be above 0");
+        }
+        if self.rpc_cache.max_entries == 0 {
+            anyhow::bail!("rpc_cache.max_entries must be at least 1");
+        }
+        if let Some(method) = self.rpc_cache.ttl_ms.keys().find(|method| {
+            method.starts_with("eth_send") || method.as_str() == "eth_getTransactionCount"
+        }) {
+            anyhow::bail!(
+                "rpc_cache.ttl_ms cannot cache '{}', its answers must be fresh",
+                method
+            );
+        }
+        if !(0.0..=1.0).contains(&self.verify.sample_rate) {
+            anyhow::bail!(
+                "verify.sample_rate must be within 0.0..=1.0, got {}",
+                self.verify.sample_rate
+            );
+        }
+        Ok(())
+    }
+
+    /// Parses config content, applying environment overrides on top
+    pub(crate) fn from_toml_str(
+        content: &str,
+        source: &str,
+        overrides: &[EnvOverride],
+    ) -> Result<Self> {
+        Self::from_toml_profile(content, source, None, overrides)
+    }
+
+    /// Parses config content with `profile` merged in and environment
+    /// overrides on top
+    pub(crate) fn from_toml_profile(
+        content: &str,
+        source: &str,
+        profile: Option<&str>,
+        overrides: &[EnvOverride],
+    ) -> Result<Self> {
+        let explain = |message: &str, offset: Option<usize>| {
+            core_logic::config::unknown_key_error(message, source, content, offset)
+        };
+
+        if overrides.is_empty() && profile.is_none() {
+            return toml::from_str(content)
+                .map_err(
+                    |e| match explain(e.message(), e.span().map(|span| span.start)) {
+                        Some(unknown) => anyhow::Error::new(unknown),
+                        None => anyhow::Error::new(e),
+                    },
+                )
+                .context("Failed to parse config TOML");
+        }
+
+        let mut table: toml::Table =
+            toml::from_str(content).context("Failed to parse config TOML")?;
+        if let Some(name) = profile {
+            let layer = profile_table(&table, name, source)?;
+            merge_table(&mut table, layer);
+            tracing::info!("Config profile '{}' applied", name);
+        }
+        for over in overrides {
+            over.apply(&mut table)?;
+            tracing::info!("Config key '{}' overridden by {}", over.key(), over.var);
+        }
+
+        let mut config: Self = deserialize_overridden(table, overrides)
+            .map_err(|e: toml::de::Error| match explain(e.message(), None) {
+                Some(ConfigError::UnknownKey {
+                    key,
+                    location,
+                    suggestion,
+                }) => {
+                    // Keys not found in the file came from an override
+                    let from_env = overrides
+                        .iter()
+                        .find(|over| location == source && over.path.last() == Some(&key));
+                    match from_env {
+                        Some(over) => anyhow::Error::new(ConfigError::UnknownKey {
+                            key: over.key(),
+                            location: over.var.clone(),
+                            suggestion,
+                        }),
+                        None => anyhow::Error::new(ConfigError::UnknownKey {
+                            key,
+                            location,
+                            suggestion,
+                        }),
+                    }
+                }
+                Some(other) => anyhow::Error::new(other),
+                None => anyhow::Error::new(e),
+            })
+            .context("Failed to apply config overrides")?;
+        config.profile = profile.map(str::to_string);
+        Ok(config)
+    }
+
+    /// Get a random task interval between min and max
+    pub fn random_interval(&self) -> u64 {
+        let mut rng = rand::thread_rng();
+        rand::Rng::gen_range(&mut rng, self.task_interval_min..=self.task_interval_max)
+    }
+}
+
+/// Default `--config` of the binaries
+pub const DEFAULT_CONFIG_PATH: &str = "config/config.toml";
+
+/// The config file to load for `--config path`
+///
+/// When `path` is the default and does not exist, falls back to the copy
+/// under `chains/tempo-spammer/` so the binaries also start from the
+/// workspace root. Paths compare by component, so `config\config.toml`
+/// counts as the default on Windows.
+pub fn resolve_config_path(path: &Path) -> PathBuf {
+    let workspace = Path::new("chains")
+        .join("tempo-spammer")
+        .join(DEFAULT_CONFIG_PATH);
+    if !path.exists() && path == Path::new(DEFAULT_CONFIG_PATH) && workspace.exists() {
+        workspace
+    } else {
+        path.to_path_buf()
+    }
+}
+
+/// `proxies.txt` next to the config file, else in the working directory
+///
+/// Returns the path next to the config when neither exists, so a missing
+/// file is reported where it is expected.
+pub fn proxies_path(config_path: &Path) -> PathBuf {
+    let beside_config = config_path
+        .parent()
+        .unwrap_or(Path::new("."))
+        .join("proxies.txt");
+    let in_working_dir = PathBuf::from("proxies.txt");
+    if !beside_config.exists() && in_working_dir.exists() {
+        in_working_dir
+    } else {
+        beside_config
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    #[test]
+    fn test_bundled_config_parses() {
+        let path = concat!(env!("CARGO_MANIFEST_DIR"), "/config/config.toml");
+        let config = TempoSpammerConfig::from_path(path).unwrap();
+        assert_eq!(config.nonce.shard_count, 32);
+    }
+
+    fn minimal_config() -> String {
+        "rpc_url = \"http://localhost:8545\"\nchain_id = 42431\nworker_count = 1\n\
+         default_gas_limit = 1\nmax_fee_per_gas = 1\npriority_fee_per_gas = 1\n\
+         task_interval_min = 1\ntask_interval_max = 2\ntask_timeout = 5\n"
+            .to_string()
+    }
+
+    fn overrides(vars: &[(&str, &str)]) -> Vec<EnvOverride> {
+        EnvOverride::from_vars(
+            vars.iter()
+                .map(|(k, v)| (k.to_string(), v.to_string()))
+                .collect::<Vec<_>>(),
+        )
+    }
+
+    #[test]
+    fn test_env_overrides_applied() {
+        let vars = overrides(&[
+            ("TEMPO__WORKER_COUNT", "20"),
+            ("TEMPO__RPC_URL", "https://rpc.example.org"),
+            ("TEMPO__NONCE__ADAPTIVE_BACKOFF", "false"),
+            ("TEMPO__PROXY__ASSIGNMENT", "sticky"),
+            ("TEMPO_CONFIG", "ignored.toml"),
+            ("PATH", "/usr/bin"),
+        ]);
+        assert_eq!(vars.len(), 4);
+
+        let config =
+            TempoSpammerConfig::from_toml_str(&minimal_config(), "config.toml", &vars).unwrap();
+        assert_eq!(config.worker_count, 20);
+        assert_eq!(config.rpc_url, "https://rpc.example.org");
+        assert!(!config.nonce.adaptive_backoff);
+        assert_eq!(config.proxy.assignment, ProxyAssignmentPolicy::Sticky);
+    }
+
+    #[test]
+    fn test_env_override_numeric_looking_string() {
+        let vars = overrides(&[
+            ("TEMPO__WALLETS__PASSWORD_FILE", "1234"),
+            ("TEMPO__WALLETS__SUBDIR", "2024"),
+            ("TEMPO__PROXY__ASSIGNMENT_FILE", "7"),
+            ("TEMPO__WORKER_COUNT", "20"),
+        ]);
+        let config =
+            TempoSpammerConfig::from_toml_str(&minimal_config(), "config.toml", &vars).unwrap();
+        assert_eq!(config.wallets.password_file, Some(PathBuf::from("1234")));
+        assert_eq!(config.wallets.subdir, Some(PathBuf::from("2024")));
+        assert_eq!(config.proxy.assignment_file, "7");
+        assert_eq!(config.worker_count, 20);
+
+        // Numbers are still required where the key is numeric
+        let vars = overrides(&[("TEMPO__WORKER_COUNT", "twenty")]);
+        assert!(
+            TempoSpammerConfig::from_toml_str(&minimal_config(), "config.toml", &vars).is_err()
+        );
+    }
+
+    #[test]
+    fn test_env_override_unknown_key_names_variable() {
+        let vars = overrides(&[("TEMPO__NONCE__SHARD_CONT", "8")]);
+        let err =
+            TempoSpammerConfig::from_toml_str(&minimal_config(), "config.toml", &vars).unwrap_err();
+
+        let message = format!("{:#}", err);
+        assert!(
+            message.contains("'nonce.shard_cont' at TEMPO__NONCE__SHARD_CONT"),
+            "{}",
+            message
+        );
+        assert!(
+            message.contains("did you mean 'shard_count'"),
+            "{}",
+            message
+        );
+    }
+
+    #[test]
+    fn test_personas_parsed_and_validated() {
+        let content = minimal_config()
+            + "[personas.trader]\nshare = 3\nweights = { \"05_swap_stable\" = 4.0 }\n\
+               hours = \"06-22\"\n\n[personas.collector]\namount_scale = 0.5\n";
+        let config = TempoSpammerConfig::from_toml_str(&content, "config.toml", &[]).unwrap();
+        config.validate().unwrap();
+
+        let trader = &config.personas["trader"];
+        assert_eq!(trader.share, 3);
+        assert_eq!(trader.weights["05_swap_stable"], 4.0);
+        assert_eq!(trader.hours, Some(HourWindow { start: 6, end: 22 }));
+        assert_eq!(config.personas["collector"].share, 1);
+
+        let negative = content.replace("amount_scale = 0.5", "amount_scale = -1.0");
+        let config = TempoSpammerConfig::from_toml_str(&negative, "config.toml", &[]).unwrap();
+        assert!(config.validate().is_err());
+    }
+
+    #[test]
+    fn test_control_listen_accepts_pipes() {
+        for listen in ["127.0.0.1:9137", r"\\.\pipe\tempo-spammer"] {
+            let content = minimal_config() + &format!("[control]\nlisten = '{}'\n", listen);
+            let config = TempoSpammerConfig::from_toml_str(&content, "config.toml", &[]).unwrap();
+            config.validate().unwrap();
+        }
+        let content = minimal_config() + "[control]\nlisten = 'localhost'\n";
+        let config = TempoSpammerConfig::from_toml_str(&content, "config.toml", &[]).unwrap();
+        assert!(config.validate().is_err());
+    }
+
+    #[test]
+    fn test_rpc_cache_settings() {
+        let config =
+            TempoSpammerConfig::from_toml_str(&minimal_config(), "config.toml", &[]).unwrap();
+        assert!(config.rpc_cache.enabled);
+        assert_eq!(config.rpc_cache.ttl_ms["eth_gasPrice"], 1000);
+
+        let content = minimal_config() + "[rpc_cache]\nttl_ms = { \"eth_blockNumber\" = 250 }\n";
+        let config = TempoSpammerConfig::from_toml_str(&content, "config.toml", &[]).unwrap();
+        config.validate().unwrap();
+        assert_eq!(
+            config.rpc_cache.ttl_ms,
+            BTreeMap::from([("eth_blockNumber".to_string(), 250)])
+        );
+
+        let content = content.replace("eth_blockNumber", "eth_getTransactionCount");
+        let config = TempoSpammerConfig::from_toml_str(&content, "config.toml", &[]).unwrap();
+        assert!(config.validate().is_err());
+    }
+
+    #[test]
+    fn test_proxy_ban_settings() {
+        let config =
+            TempoSpammerConfig::from_toml_str(&minimal_config(), "config.toml", &[]).unwrap();
+        let policy = config.proxy.bans.policy();
+        assert_eq!(
+            policy.tiers,
+            vec![
+                Duration::from_secs(600),
+                Duration::from_secs(1800),
+                Duration::from_secs(7200)
+            ]
+        );
+        assert!(policy.permanent_after_last_tier);
+
+        let content = minimal_config()
+            + "[proxy.bans]\ntiers_minutes = [5, 15]\npermanent_after_last_tier = false\n\
+               failure_threshold = 3\ndecay_successes = 0\n";
+        let config = TempoSpammerConfig::from_toml_str(&content, "config.toml", &[]).unwrap();
+        config.validate().unwrap();
+        assert_eq!(config.proxy.bans.tiers_minutes, vec![5, 15]);
+        assert!(!config.proxy.bans.permanent_after_last_tier);
+        assert_eq!(config.proxy.bans.failure_threshold, 3);
+
+        let zero_tier = content.replace("[5, 15]", "[5, 0]");
+        let config = TempoSpammerConfig::from_toml_str(&zero_tier, "config.toml", &[]).unwrap();
+        assert!(config.validate().is_err());
+
+        let zero_threshold = content.replace("failure_threshold = 3", "failure_threshold = 0");
+        let config =
+            TempoSpammerConfig::from_toml_str(&zero_threshold, "config.toml", &[]).unwrap();
+        assert!(config.validate().is_err());
+    }
+
+    #[test]
+    fn test_proxy_health_settings() {
+        let config =
+            TempoSpammerConfig::from_toml_str(&minimal_config(), "config.toml", &[]).unwrap();
+        let probe = config.proxy.health.probe(&config.rpc_url);
+        assert_eq!(probe.method, crate::proxy_health::ProbeMethod::Head);
+        assert_eq!(probe.targets, vec!["http://localhost:8545".to_string()]);
+        assert_eq!(probe.max_latency, None);
+        assert_eq!(probe.concurrency, 10);
+
+        let content = minimal_config()
+            + "[proxy.health]\nprobe = \"chain_id\"\n\
+               targets = [\"https://rpc.example.org\", \"https://example.com\"]\n\
+               max_latency_ms = 1500\nrequired_successes = 2\nconcurrency = 25\n";
+        let config = TempoSpammerConfig::from_toml_str(&content, "config.toml", &[]).unwrap();
+        config.validate().unwrap();
+        let probe = config.proxy.health.probe(&config.rpc_url);
+        assert_eq!(probe.method, crate::proxy_health::ProbeMethod::ChainId);
+        assert_eq!(probe.targets.len(), 2);
+        assert_eq!(probe.max_latency, Some(Duration::from_millis(1500)));
+        assert_eq!(probe.required_successes, 2);
+        assert_eq!(probe.concurrency, 25);
+
+        let bad_target = content.replace("https://example.com", "ftp://example.com");
+        let config = TempoSpammerConfig::from_toml_str(&bad_target, "config.toml", &[]).unwrap();
+        assert!(config.validate().is_err());
+
+        let no_concurrency = content.replace("concurrency = 25", "concurrency = 0");
+        let config =
+            TempoSpammerConfig::from_toml_str(&no_concurrency, "config.toml", &[]).unwrap();
+        assert!(config.validate().is_err());
+    }
+
+    #[test]
+    fn test_worker_proxy_bindings() {
+        let config =
+            TempoSpammerConfig::from_toml_str(&minimal_config(), "config.toml", &[]).unwrap();
+        assert_eq!(config.proxy.workers, WorkerProxySettings::default());
+
+        let content = minimal_config()
+            + "[proxy]\nassignment = \"worker\"\n\
+               [proxy.workers]\nbindings = [0, 1, 2]\nbackups = [3, 4]\n";
+        let config = TempoSpammerConfig::from_toml_str(&content, "config.toml", &[]).unwrap();
+        config.validate().unwrap();
+        assert_eq!(config.proxy.assignment, ProxyAssignmentPolicy::Worker);
+        assert_eq!(config.proxy.workers.bindings, vec![0, 1, 2]);
+        assert_eq!(config.proxy.workers.backups, vec![3, 4]);
+
+        let typo = content.replace("backups", "backup");
+        assert!(TempoSpammerConfig::from_toml_str(&typo, "config.toml", &[]).is_err());
+    }
+
+    #[test]
+    fn test_rate_limit_settings() {
+        let config =
+            TempoSpammerConfig::from_toml_str(&minimal_config(), "config.toml", &[]).unwrap();
+        assert_eq!(config.rate_limit.rate_for(&config.rpc_url), 0.0);
+        assert_eq!(config.rate_limit.max_retries, 3);
+
+        let content = minimal_config()
+            + "[rate_limit]\nrequests_per_second = 20\nburst = 5\n\
+               endpoints = { \"https://rpc.example.org/\" = 50 }\n";
+        let config = TempoSpammerConfig::from_toml_str(&content, "config.toml", &[]).unwrap();
+        config.validate().unwrap();
+        assert_eq!(config.rate_limit.rate_for("http://localhost:8545"), 20.0);
+        assert_eq!(config.rate_limit.rate_for("https://rpc.example.org"), 50.0);
+
+        let zero_rate = content.replace("= 50", "= 0");
+        let config = TempoSpammerConfig::from_toml_str(&zero_rate, "config.toml", &[]).unwrap();
+        assert!(config.validate().is_err());
+
+        let short_cap =
+            minimal_config() + "[rate_limit]\nbackoff_ms = 5000\nmax_backoff_ms = 1000\n";
+        let config = TempoSpammerConfig::from_toml_str(&short_cap, "config.toml", &[]).unwrap();
+        assert!(config.validate().is_err());
+    }
+
+    #[test]
+    fn test_stress_settings_validated() {
+        let content = minimal_config() + "[stress]\ncall_counts = [1, 256]\ngas_fraction = 1.5\n";
+        let config = TempoSpammerConfig::from_toml_str(&content, "config.toml", &[]).unwrap();
+        config.validate().unwrap();
+        assert_eq!(config.stress.call_counts, vec![1, 256]);
+        assert_eq!(
+            config.stress.calldata_sizes,
+            StressSettings::default().calldata_sizes
+        );
+
+        for bad in [
+            "call_counts = []",
+            "call_counts = [0]",
+            "gas_fraction = 0.0",
+        ] {
+            let content = minimal_config() + "[stress]\n" + bad + "\n";
+            let config = TempoSpammerConfig::from_toml_str(&content, "config.toml", &[]).unwrap();
+            assert!(config.validate().is_err(), "{}", bad);
+        }
+    }
+
+    #[test]
+    fn test_pinned_helpers_validated() {
+        let address = "0x1111111111111111111111111111111111111111";
+        let content = minimal_config() + "[helpers.pinned.42431]\ndisperse = \"" + address + "\"\n";
+        let config = TempoSpammerConfig::from_toml_str(&content, "config.toml", &[]).unwrap();
+        config.validate().unwrap();
+        assert!(config.helpers.verify);
+        assert_eq!(
+            config.helpers.pinned["42431"]["disperse"],
+            address.parse::<Address>().unwrap()
+        );
+
+        let typo = content.replace("disperse", "dispers");
+        let config = TempoSpammerConfig::from_toml_str(&typo, "config.toml", &[]).unwrap();
+        assert!(config.validate().is_err());
+        let chain = content.replace("pinned.42431", "pinned.tempo");
+        let config = TempoSpammerConfig::from_toml_str(&chain, "config.toml", &[]).unwrap();
+        assert!(config.validate().is_err());
+    }
+
+    #[test]
+    fn test_bundle_settings() {
+        let config =
+            TempoSpammerConfig::from_toml_str(&minimal_config(), "config.toml", &[]).unwrap();
+        assert_eq!(config.bundle, BundleSettings::default());
+        assert!(!config.bundle.on_exit);
+
+        let content = minimal_config() + "[bundle]\non_exit = true\nlog_lines = 0\n";
+        let config = TempoSpammerConfig::from_toml_str(&content, "config.toml", &[]).unwrap();
+        config.validate().unwrap();
+        assert!(config.bundle.on_exit);
+        assert_eq!(config.bundle.log_lines, 0);
+
+        let empty = minimal_config() + "[bundle]\ndir = \"\"\n";
+        let config = TempoSpammerConfig::from_toml_str(&empty, "config.toml", &[]).unwrap();
+        assert!(config.validate().is_err());
+    }
+
+    #[test]
+    fn test_aa_settings() {
+        let config =
+            TempoSpammerConfig::from_toml_str(&minimal_config(), "config.toml", &[]).unwrap();
+        assert_eq!(config.aa, AaSettings::default());
+        assert!(config.aa.bundler_url.is_empty());
+
+        let content = minimal_config()
+            + "[aa]\nbundler_url = \"http://localhost:4337\"\nmax_accounts = 1\n\
+               deposit_wei = 1000000000000000\n";
+        let config = TempoSpammerConfig::from_toml_str(&content, "config.toml", &[]).unwrap();
+        config.validate().unwrap();
+        assert_eq!(config.aa.deposit_wei, 1_000_000_000_000_000);
+
+        for bad in [
+            "bundler_url = \"ws://localhost:4337\"",
+            "bundler_url = \"not a url\"",
+            "max_accounts = 0",
+        ] {
+            let content = minimal_config() + "[aa]\n" + bad + "\n";
+            let config = TempoSpammerConfig::from_toml_str(&content, "config.toml", &[]).unwrap();
+            assert!(config.validate().is_err(), "{}", bad);
+        }
+    }
+
+    #[test]
+    fn test_recipient_settings() {
+        let config =
+            TempoSpammerConfig::from_toml_str(&minimal_config(), "config.toml", &[]).unwrap();
+        assert_eq!(config.recipients, RecipientSettings::default());
+
+        let content = minimal_config() + "[recipients]\npool_share = 0.3\n";
+        let config = TempoSpammerConfig::from_toml_str(&content, "config.toml", &[]).unwrap();
+        config.validate().unwrap();
+        assert_eq!(config.recipients.pool_share, 0.3);
+        assert_eq!(config.recipients.incoming_wait_secs, 10);
+
+        let content = minimal_config() + "[recipients]\npool_share = 1.5\n";
+        let config = TempoSpammerConfig::from_toml_str(&content, "config.toml", &[]).unwrap();
+        assert!(config.validate().is_err());
+    }
+
+    #[test]
+    fn test_marketplace_settings() {
+        let config =
+            TempoSpammerConfig::from_toml_str(&minimal_config(), "config.toml", &[]).unwrap();
+        assert_eq!(config.marketplace, MarketplaceSettings::default());
+
+        let content = minimal_config() + "[marketplace]\nstale_after_secs = 600\n";
+        let config = TempoSpammerConfig::from_toml_str(&content, "config.toml", &[]).unwrap();
+        config.validate().unwrap();
+        assert_eq!(config.marketplace.stale_after_secs, 600);
+        assert_eq!(config.marketplace.max_listings, 3);
+
+        let content = minimal_config() + "[marketplace]\nmax_listings = 0\n";
+        let config = TempoSpammerConfig::from_toml_str(&content, "config.toml", &[]).unwrap();
+        assert!(config.validate().is_err());
+    }
+
+    #[test]
+    fn test_domain_settings() {
+        let config =
+            TempoSpammerConfig::from_toml_str(&minimal_config(), "config.toml", &[]).unwrap();
+        assert_eq!(config.domains, DomainSettings::default());
+
+        let content = minimal_config()
+            + "[domains]\nresolver = \"0x00000000000000000000000000000000000000aa\"\n";
+        let config = TempoSpammerConfig::from_toml_str(&content, "config.toml", &[]).unwrap();
+        config.validate().unwrap();
+        assert_eq!(
+            config.domains.resolver,
+            alloy_primitives::address!("0x00000000000000000000000000000000000000aa")
+        );
+        assert_eq!(config.domains.renew_duration_secs, 31_536_000);
+
+        let content = minimal_config() + "[domains]\nrenew_duration_secs = 0\n";
+        let config = TempoSpammerConfig::from_toml_str(&content, "config.toml", &[]).unwrap();
+        assert!(config.validate().is_err());
+    }
+
+    #[test]
+    fn test_swap_settings() {
+        let config =
+            TempoSpammerConfig::from_toml_str(&minimal_config(), "config.toml", &[]).unwrap();
+        assert_eq!(config.swap.slippage_bps, 100);
+
+        let content = minimal_config() + "[swap]\nslippage_bps = 50\n";
+        let config = TempoSpammerConfig::from_toml_str(&content, "config.toml", &[]).unwrap();
+        config.validate().unwrap();
+        assert_eq!(config.swap.slippage_bps, 50);
+
+        let content = minimal_config() + "[swap]\nslippage_bps = 10000\n";
+        let config = TempoSpammerConfig::from_toml_str(&content, "config.toml", &[]).unwrap();
+        assert!(config.validate().is_err());
+    }
+
+    #[test]
+    fn test_wallet_assignment() {
+        let config =
+            TempoSpammerConfig::from_toml_str(&minimal_config(), "config.toml", &[]).unwrap();
+        assert_eq!(config.wallets.assignment, WalletAssignment::Leased);
+
+        let content = minimal_config() + "[wallets]\nassignment = \"partitioned\"\n";
+        let config = TempoSpammerConfig::from_toml_str(&content, "config.toml", &[]).unwrap();
+        assert_eq!(config.wallets.assignment, WalletAssignment::Partitioned);
+
+        let content = minimal_config() + "[wallets]\nassignment = \"static\"\n";
+        assert!(TempoSpammerConfig::from_toml_str(&content, "config.toml", &[]).is_err());
+    }
+
+    #[test]
+    fn test_wallet_selection() {
+        let config =
+            TempoSpammerConfig::from_toml_str(&minimal_config(), "config.toml", &[]).unwrap();
+        assert_eq!(config.wallets.selection, WalletSelection::Random);
+
+        let content = minimal_config() + "[wallets]\nselection = \"least_recently_used\"\n";
+        let config = TempoSpammerConfig::from_toml_str(&content, "config.toml", &[]).unwrap();
+        assert_eq!(config.wallets.selection, WalletSelection::LeastRecentlyUsed);
+
+        let content = minimal_config() + "[wallets]\nselection = \"round_robin\"\n";
+        let config = TempoSpammerConfig::from_toml_str(&content, "config.toml", &[]).unwrap();
+        assert_eq!(config.wallets.selection, WalletSelection::RoundRobin);
+
+        let content = minimal_config() + "[wallets]\nselection = \"lru\"\n";
+        assert!(TempoSpammerConfig::from_toml_str(&content, "config.toml", &[]).is_err());
+    }
+
+    #[test]
+    fn test_wallet_subset() {
+        let config =
+            TempoSpammerConfig::from_toml_str(&minimal_config(), "config.toml", &[]).unwrap();
+        assert_eq!(
+            config.wallets.wallet_options(),
+            core_logic::WalletOptions::default()
+        );
+
+        let content = minimal_config()
+            + "[wallets]\nsubdir = \"campaign-a\"\ntags = [\"airdrop\"]\ncache_size = 64\n";
+        let config = TempoSpammerConfig::from_toml_str(&content, "config.toml", &[]).unwrap();
+        let options = config.wallets.wallet_options();
+        assert_eq!(options.subdir, Some(PathBuf::from("campaign-a")));
+        assert_eq!(options.tags, ["airdrop"]);
+        assert_eq!(options.cache_size, 64);
+        assert!(!options.recursive);
+        assert_eq!(options.partition, None);
+
+        let content = minimal_config() + "[wallets]\npartition = { index = 2, total = 5 }\n";
+        let config = TempoSpammerConfig::from_toml_str(&content, "config.toml", &[]).unwrap();
+        let partition = config.wallets.wallet_options().partition.unwrap();
+        assert_eq!(partition.to_string(), "2/5");
+
+        let content = minimal_config() + "[wallets]\npartition = { index = 5, total = 5 }\n";
+        let config = TempoSpammerConfig::from_toml_str(&content, "config.toml", &[]).unwrap();
+        assert!(config.validate().is_err());
+    }
+
+    #[test]
+    fn test_tracing_export() {
+        let config =
+            TempoSpammerConfig::from_toml_str(&minimal_config(), "config.toml", &[]).unwrap();
+        assert_eq!(config.tracing.otlp_config(), None);
+
+        let content = minimal_config()
+            + "[tracing]\nenabled = true\nendpoint = \"http://jaeger:4317\"\nsample_ratio = 0.25\nattributes = { host = \"box-2\" }\n";
+        let config = TempoSpammerConfig::from_toml_str(&content, "config.toml", &[]).unwrap();
+        let otlp = config.tracing.otlp_config().unwrap();
+        assert_eq!(otlp.endpoint, "http://jaeger:4317");
+        assert_eq!(otlp.service_name, "tempo-spammer");
+        assert_eq!(otlp.sample_ratio, 0.25);
+        assert_eq!(otlp.attributes["host"], "box-2");
+
+        let content = minimal_config() + "[tracing]\nsample_ratio = 2.0\n";
+        let config = TempoSpammerConfig::from_toml_str(&content, "config.toml", &[]).unwrap();
+        assert!(config.validate().is_err());
+    }
+
+    #[test]
+    fn test_watchdog_soft_deadline() {
+        let config =
+            TempoSpammerConfig::from_toml_str(&minimal_config(), "config.toml", &[]).unwrap();
+        assert_eq!(
+            config.watchdog.soft_deadline(Duration::from_secs(60)),
+            Some(Duration::from_secs(48))
+        );
+
+        let content = minimal_config() + "[watchdog]\nsoft_threshold = 0.0\n";
+        let config = TempoSpammerConfig::from_toml_str(&content, "config.toml", &[]).unwrap();
+        assert_eq!(config.watchdog.soft_deadline(Duration::from_secs(60)), None);
+
+        let content = minimal_config() + "[watchdog]\nsoft_threshold = 1.0\n";
+        let config = TempoSpammerConfig::from_toml_str(&content, "config.toml", &[]).unwrap();
+        assert!(config.validate().is_err());
+    }
+
+    #[test]
+    fn test_price_settings() {
+        let config =
+            TempoSpammerConfig::from_toml_str(&minimal_config(), "config.toml", &[]).unwrap();
+        assert!(!config.price.enabled);
+        assert_eq!(config.price.source, PriceSource::Fixed);
+        assert_eq!(config.price.refresh_secs, 3600);
+
+        let content = minimal_config()
+            + "[price]\nenabled = true\nsource = \"oracle\"\n\
+               oracle = \"0x5f4eC3Df9cbd43714FE2740f5E3616155c5b8419\"\n\
+               tokens = { \"PathUSD\" = 0.999 }\n";
+        let config = TempoSpammerConfig::from_toml_str(&content, "config.toml", &[]).unwrap();
+        config.validate().unwrap();
+        assert_eq!(config.price.source, PriceSource::Oracle);
+
+        // Each source needs its own settings
+        let content = minimal_config() + "[price]\nenabled = true\nsource = \"http\"\n";
+        let config = TempoSpammerConfig::from_toml_str(&content, "config.toml", &[]).unwrap();
+        assert!(config.validate().is_err());
+
+        let content =
+            minimal_config() + "[price]\nenabled = true\ntokens = { \"NoSuchUSD\" = 1.0 }\n";
+        let config = TempoSpammerConfig::from_toml_str(&content, "config.toml", &[]).unwrap();
+        assert!(config.validate().is_err());
+    }
+
+    #[test]
+    fn test_capability_settings() {
+        let config =
+            TempoSpammerConfig::from_toml_str(&minimal_config(), "config.toml", &[]).unwrap();
+        assert!(config.capabilities.enabled);
+        assert_eq!(
+            config.capabilities.on_chain_id_mismatch,
+            ChainIdMismatch::Fail
+        );
+        assert_eq!(config.capabilities.on_missing, MissingCapability::Adjust);
+
+        let content = minimal_config()
+            + "[capabilities]\non_chain_id_mismatch = \"adopt\"\non_missing = \"fail\"\n";
+        let config = TempoSpammerConfig::from_toml_str(&content, "config.toml", &[]).unwrap();
+        config.validate().unwrap();
+        assert_eq!(
+            config.capabilities.on_chain_id_mismatch,
+            ChainIdMismatch::Adopt
+        );
+        assert_eq!(config.capabilities.on_missing, MissingCapability::Fail);
+
+        let content = minimal_config() + "[capabilities]\ntimeout_secs = 0\n";
+        let config = TempoSpammerConfig::from_toml_str(&content, "config.toml", &[]).unwrap();
+        assert!(config.validate().is_err());
+    }
+
+    #[test]
+    fn test_mempool_settings() {
+        let config =
+            TempoSpammerConfig::from_toml_str(&minimal_config(), "config.toml", &[]).unwrap();
+        assert!(!config.mempool.enabled);
+        assert_eq!(config.mempool.source, PendingSource::Auto);
+
+        let content =
+            minimal_config() + "[mempool]\nenabled = true\nmax_pending = 2\nsource = \"nonce\"\n";
+        let config = TempoSpammerConfig::from_toml_str(&content, "config.toml", &[]).unwrap();
+        config.validate().unwrap();
+        assert_eq!(config.mempool.max_pending, 2);
+        assert_eq!(config.mempool.source, PendingSource::Nonce);
+
+        let content = minimal_config() + "[mempool]\nenabled = true\nmax_pending = 0\n";
+        let config = TempoSpammerConfig::from_toml_str(&content, "config.toml", &[]).unwrap();
+        assert!(config.validate().is_err());
+    }
+
+    #[test]
+    fn test_chain_head_settings() {
+        let config =
+            TempoSpammerConfig::from_toml_str(&minimal_config(), "config.toml", &[]).unwrap();
+        assert!(config.chain_head.enabled);
+        assert_eq!(config.chain_head.poll_ms, 1000);
+
+        let content = minimal_config() + "[chain_head]\npoll_ms = 0\n";
+        let config = TempoSpammerConfig::from_toml_str(&content, "config.toml", &[]).unwrap();
+        assert!(config.validate().is_err());
+
+        let content = minimal_config() + "[chain_head]\nenabled = false\npoll_ms = 0\n";
+        let config = TempoSpammerConfig::from_toml_str(&content, "config.toml", &[]).unwrap();
+        config.validate().unwrap();
+
+        // Block-triggered tasks need the watcher
+        let content = minimal_config()
+            + "[chain_head]\nenabled = false\n\n[tasks.every_blocks]\n\"03_send_token\" = 1\n";
+        let config = TempoSpammerConfig::from_toml_str(&content, "config.toml", &[]).unwrap();
+        assert!(config.validate().is_err());
+    }
+
+    #[test]
+    fn test_chaos_settings() {
+        let config =
+            TempoSpammerConfig::from_toml_str(&minimal_config(), "config.toml", &[]).unwrap();
+        assert_eq!(config.chaos, ChaosSettings::default());
+        assert!(!config.chaos.enabled);
+
+        let content = minimal_config()
+            + "[chaos]\nenabled = true\nnonce_too_low_rate = 0.05\nreceipt_delay_ms = 2000\n";
+        let config = TempoSpammerConfig::from_toml_str(&content, "config.toml", &[]).unwrap();
+        config.validate().unwrap();
+        assert_eq!(config.chaos.nonce_too_low_rate, 0.05);
+        assert_eq!(config.chaos.receipt_delay_ms, 2000);
+
+        let content = minimal_config() + "[chaos]\ndrop_rate = 1.5\n";
+        let config = TempoSpammerConfig::from_toml_str(&content, "config.toml", &[]).unwrap();
+        assert!(config.validate().is_err());
+    }
+
+    #[test]
+    fn test_slo_settings() {
+        let config =
+            TempoSpammerConfig::from_toml_str(&minimal_config(), "config.toml", &[]).unwrap();
+        assert_eq!(config.slo, SloSettings::default());
+        assert!(!config.slo.enabled());
+
+        let content = minimal_config() + "[slo]\nmin_success_rate = 0.05\nmin_runs = 10\n";
+        let config = TempoSpammerConfig::from_toml_str(&content, "config.toml", &[]).unwrap();
+        config.validate().unwrap();
+        assert!(config.slo.enabled());
+        assert_eq!(config.slo.min_runs, 10);
+        assert_eq!(config.slo.window_secs, 900);
+
+        let content = minimal_config() + "[slo]\nmin_success_rate = 0.05\nprobe_secs = 0\n";
+        let config = TempoSpammerConfig::from_toml_str(&content, "config.toml", &[]).unwrap();
+        assert!(config.validate().is_err());
+    }
+
+    #[test]
+    fn test_log_archive_settings() {
+        let config =
+            TempoSpammerConfig::from_toml_str(&minimal_config(), "config.toml", &[]).unwrap();
+        assert_eq!(config.log_archive, LogArchiveSettings::default());
+        assert!(!config.log_archive.archives("03_send_token"));
+
+        let content = minimal_config()
+            + "[log_archive]\nenabled = true\ntasks = [\"10_transfer_memo\"]\nsink = \"jsonl\"\n";
+        let config = TempoSpammerConfig::from_toml_str(&content, "config.toml", &[]).unwrap();
+        config.validate().unwrap();
+        assert_eq!(config.log_archive.sink, LogSink::Jsonl);
+        assert_eq!(config.log_archive.path, "receipt-logs.jsonl.gz");
+        assert!(config.log_archive.archives("10_transfer_memo"));
+        assert!(!config.log_archive.archives("03_send_token"));
+
+        let content = minimal_config() + "[log_archive]\nsink = \"jsonl\"\npath = \" \"\n";
+        let config = TempoSpammerConfig::from_toml_str(&content, "config.toml", &[]).unwrap();
+        assert!(config.validate().is_err());
+    }
+
+    #[test]
+    fn test_burst_settings() {
+        let config =
+            TempoSpammerConfig::from_toml_str(&minimal_config(), "config.toml", &[]).unwrap();
+        assert_eq!(config.burst, BurstSettings::default());
+        assert_eq!(config.burst.presign, 0);
+        assert!(config.burst.subblocks);
+
+        let content = minimal_config() + "[burst]\npresign = 25\nsubblocks = false\n";
+        let config = TempoSpammerConfig::from_toml_str(&content, "config.toml", &[]).unwrap();
+        config.validate().unwrap();
+        assert_eq!(config.burst.presign, 25);
+        assert!(!config.burst.subblocks);
+        assert_eq!(config.burst.lead_secs, 30);
+    }
+
+    #[test]
+    fn test_result_log_settings() {
+        let config =
+            TempoSpammerConfig::from_toml_str(&minimal_config(), "config.toml", &[]).unwrap();
+        assert_eq!(config.results, ResultLogSettings::default());
+        assert_eq!(config.results.success_every, 1);
+
+        let content = minimal_config() + "[results]\nsuccess_every = 50\nsummary_secs = 60\n";
+        let config = TempoSpammerConfig::from_toml_str(&content, "config.toml", &[]).unwrap();
+        config.validate().unwrap();
+        assert_eq!(config.results.success_every, 50);
+        assert_eq!(config.results.summary_secs, 60);
+    }
+
+    #[test]
+    fn test_rpc_stats_settings() {
+        let config =
+            TempoSpammerConfig::from_toml_str(&minimal_config(), "config.toml", &[]).unwrap();
+        assert_eq!(config.rpc_stats, RpcStatsSettings::default());
+        assert!(config.rpc_stats.p95_ms.is_empty());
+
+        let content = minimal_config()
+            + "[rpc_stats]\nmax_error_rate = 0.2\n\n[rpc_stats.p95_ms]\neth_getTransactionReceipt = 5000\n";
+        let config = TempoSpammerConfig::from_toml_str(&content, "config.toml", &[]).unwrap();
+        config.validate().unwrap();
+        assert_eq!(config.rpc_stats.max_error_rate, 0.2);
+        assert_eq!(config.rpc_stats.p95_ms["eth_getTransactionReceipt"], 5000);
+
+        let content = minimal_config() + "[rpc_stats]\nmax_error_rate = 1.5\n";
+        let config = TempoSpammerConfig::from_toml_str(&content, "config.toml", &[]).unwrap();
+        assert!(config.validate().is_err());
+    }
+
+    #[test]
+    fn test_profile_merged_over_shared_settings() {
+        let content = minimal_config()
+            + "[nonce]\nshard_count = 32\nretry_max = 7\n\n\
+               [profiles.local]\nrpc_url = \"http://127.0.0.1:8546\"\nchain_id = 1337\n\n\
+               [profiles.local.nonce]\nshard_count = 1\n\n\
+               [profiles.local.contracts]\nfaucet = \"0x0000000000000000000000000000000000000019\"\n\n\
+               [profiles.moderato]\nchain_id = 42431\n";
+
+        let shared = TempoSpammerConfig::from_toml_str(&content, "config.toml", &[]).unwrap();
+        assert_eq!(shared.profile, None);
+        assert_eq!(shared.chain_id, 42431);
+        assert_eq!(shared.nonce.shard_count, 32);
+        assert_eq!(shared.contracts, SystemContracts::TESTNET);
+        assert_eq!(shared.profiles.len(), 2);
+
+        let local =
+            TempoSpammerConfig::from_toml_profile(&content, "config.toml", Some("local"), &[])
+                .unwrap();
+        assert_eq!(local.profile.as_deref(), Some("local"));
+        assert_eq!(local.rpc_url, "http://127.0.0.1:8546");
+        assert_eq!(local.chain_id, 1337);
+        assert_eq!(local.nonce.shard_count, 1);
+        assert_eq!(local.nonce.retry_max, 7);
+        assert_eq!(
+            local.contracts.faucet,
+            "0x0000000000000000000000000000000000000019"
+                .parse::<Address>()
+                .unwrap()
+        );
+        assert_eq!(local.contracts.path_usd, SystemContracts::TESTNET.path_usd);
+
+        // Environment overrides still win over the profile
+        let vars = overrides(&[("TEMPO__CHAIN_ID", "7")]);
+        let config =
+            TempoSpammerConfig::from_toml_profile(&content, "config.toml", Some("local"), &vars)
+                .unwrap();
+        assert_eq!(config.chain_id, 7);
+    }
+
+    #[test]
+    fn test_system_contracts_override_one_address() {
+        let content = minimal_config()
+            + "[contracts]\nname_service = \"0x00000000000000000000000000000000000030c1\"\n";
+        let config = TempoSpammerConfig::from_toml_str(&content, "config.toml", &[]).unwrap();
+        let redeployed = "0x00000000000000000000000000000000000030c1"
+            .parse::<Address>()
+            .unwrap();
+        assert_eq!(config.contracts.name_service, redeployed);
+        assert_eq!(config.contracts.faucet, SystemContracts::TESTNET.faucet);
+
+        let named = config.contracts.named();
+        assert_eq!(named[0], ("PathUSD", SystemContracts::TESTNET.path_usd));
+        assert!(named.contains(&("InfinityName", redeployed)));
+        let mut addresses: Vec<Address> = named.iter().map(|(_, address)| *address).collect();
+        addresses.sort();
+        addresses.dedup();
+        assert_eq!(addresses.len(), named.len());
+
+        let content = minimal_config() + "[contracts]\nfee_amm = \"0x00\"\n";
+        assert!(TempoSpammerConfig::from_toml_str(&content, "config.toml", &[]).is_err());
+    }
+
+    #[test]
+    fn test_profile_errors() {
+        let content = minimal_config() + "[profiles.local]\nchain_id = 1337\n";
+        let err =
+            TempoSpammerConfig::from_toml_profile(&content, "config.toml", Some("allegro"), &[])
+                .unwrap_err();
+        assert_eq!(
+            err.to_string(),
+            "Unknown profile 'allegro': config.toml defines local"
+        );
+
+        let err =
+            TempoSpammerConfig::from_toml_profile(&minimal_config(), "config.toml", Some("x"), &[])
+                .unwrap_err();
+        assert!(err.to_string().contains("defines no profiles"));
+
+        // Keys of the selected profile are checked like the rest of the file
+        let content = minimal_config() + "[profiles.local.contracts]\nfacuet = \"0x00\"\n";
+        assert!(
+            TempoSpammerConfig::from_toml_profile(&content, "config.toml", Some("local"), &[])
+                .is_err()
+        );
+    }
+
+    #[test]
+    fn test_database_tuning() {
+        let config =
+            TempoSpammerConfig::from_toml_str(&minimal_config(), "config.toml", &[]).unwrap();
+        let tuning = config.database.async_config();
+        assert_eq!(tuning.journal_mode, JournalMode::Wal);
+        assert_eq!(tuning.synchronous, SynchronousLevel::Normal);
+        assert_eq!(tuning.busy_timeout_ms, 5000);
+        assert_eq!(tuning.spill_path, None);
+        assert_eq!(tuning.shard_base_path, None);
+
+        let content = minimal_config()
+            + "[database]\njournal_mode = \"truncate\"\nsynchronous = \"full\"\nbusy_timeout_ms = 250\ncheckpoint_interval_ms = 0\nspill_path = \"spill.jsonl\"\nshard_by_month = true\n";
+        let config = TempoSpammerConfig::from_toml_str(&content, "config.toml", &[]).unwrap();
+        let tuning = config.database.async_config();
+        assert_eq!(tuning.journal_mode, JournalMode::Truncate);
+        assert_eq!(tuning.synchronous, SynchronousLevel::Full);
+        assert_eq!(tuning.busy_timeout_ms, 250);
+        assert_eq!(tuning.checkpoint_interval_ms, 0);
+        assert_eq!(tuning.spill_path, Some(PathBuf::from("spill.jsonl")));
+        assert_eq!(tuning.shard_base_path.as_deref(), Some("tempo-spammer.db"));
+        assert_eq!(tuning.batch_size, AsyncDbConfig::default().batch_size);
+
+        let content = minimal_config() + "[database]\njournal_mode = \"journal\"\n";
+        assert!(TempoSpammerConfig::from_toml_str(&content, "config.toml", &[]).is_err());
+    }
+
+    #[test]
+    fn test_database_key_file() {
+        let key_file = std::env::temp_dir().join(format!(
+            "tempo-db-key-{}-{}",
+            std::process::id(),
+            fastrand::u64(..)
+        ));
+        fs::write(&key_file, "db-secret\n").unwrap();
+        let content = minimal_config()
+            + &format!(
+                "[database]\nencrypt = true\nkey_file = {:?}\n",
+                key_file.display().to_string()
+            );
+        let config = TempoSpammerConfig::from_toml_str(&content, "config.toml", &[]).unwrap();
+        assert_eq!(
+            config
+                .database
+                .encryption_key()
+                .unwrap()
+                .as_deref()
+                .map(String::as_str),
+            Some("db-secret")
+        );
+
+        // The key file is ignored while encryption is off
+        let mut database = config.database.clone();
+        database.encrypt = false;
+        assert!(database.encryption_key().unwrap().is_none());
+        fs::remove_file(&key_file).unwrap();
+    }
+
+    #[test]
+    fn test_config_and_proxy_paths() {
+        let dir = std::env::temp_dir().join(format!(
+            "tempo-config-{}-{}",
+            std::process::id(),
+            fastrand::u64(..)
+        ));
+        fs::create_dir_all(&dir).unwrap();
+        let config_path = dir.join("config.toml");
+        assert_eq!(resolve_config_path(&config_path), config_path);
+
+        fs::write(dir.join("proxies.txt"), "127.0.0.1:8080\n").unwrap();
+        assert_eq!(proxies_path(&config_path), dir.join("proxies.txt"));
+        fs::remove_dir_all(&dir).ok();
+    }
+
+    #[test]
+    fn test_unknown_key_suggests_nearest() {
+        let content = minimal_config() + "[nonce]\nshard_cout = 4\n";
+        let err = TempoSpammerConfig::from_toml_str(&content, "config.toml", &[]).unwrap_err();
+
+        let message = format!("{:#}", err);
+        assert!(message.contains("'nonce.shard_cout'"), "{}", message);
+        assert!(message.contains("config.toml:11:1"), "{}", message);
+        assert!(
+            message.contains("did you mean 'shard_count'"),
+            "{}",
+            message
+        );
     }
 }
@@ -0,0 +1,374 @@
+//! Consistency Checks - Read-your-writes verification through a second endpoint
+//!
+//! Testnets often serve reads from replicas that trail the sequencer. With
+//! `[verify] enabled = true`, state-changing tasks (token created, role
+//! granted) hand the state they just wrote to the [`ConsistencyVerifier`].
+//! It reads the value once through the writing client, then re-reads it in
+//! the background through a different route until both agree:
+//!
+//! - another proxy from the pool than the one the wallet wrote through, or
+//!   a direct connection when there is no other proxy
+//! - `[verify] rpc_url` instead of the main `rpc_url` when set
+//!
+//! The first re-read happens `delay_ms` after the write and repeats every
+//! `poll_ms`. Each check is stored in `consistency_checks` with the time
+//! until the state showed up, or as inconsistent once `timeout_secs` pass.
+//!
+//! ```bash
+//! tempo-spammer db consistency
+//! ```
+
+use crate::config::VerifySettings;
+use crate::tasks::ProxyConfig;
+use alloy::providers::Provider;
+use alloy::rpc::client::ClientBuilder;
+use alloy::rpc::types::TransactionRequest;
+use alloy::transports::http::Http;
+use alloy_primitives::{Address, Bytes};
+use anyhow::{Context, Result};
+use core_logic::database::{ConsistencyCheck, DatabaseManager};
+use rand::Rng;
+use reqwest::{Client, Proxy};
+use std::collections::HashMap;
+use std::future::Future;
+use std::sync::{Arc, Mutex};
+use std::time::{Duration, Instant};
+use tracing::{debug, warn};
+use url::Url;
+
+/// State a task wrote and expects other nodes to serve
+#[derive(Debug, Clone, PartialEq, Eq)]
+pub enum StateCheck {
+    /// Contract code at an address
+    Code { address: Address },
+    /// Return data of a view call
+    Call { to: Address, data: Bytes },
+}
+
+impl StateCheck {
+    /// Short description stored with the check, e.g. `call 0x20c0...:70a08231`
+    pub fn target(&self) -> String {
+        match self {
+            Self::Code { address } => format!("code {}", address),
+            Self::Call { to, data } => {
+                format!("call {}:{}", to, hex::encode(&data[..data.len().min(4)]))
+            }
+        }
+    }
+
+    /// Reads the current value through `provider`
+    pub async fn read(&self, provider: &(dyn Provider + Send + Sync)) -> Result<Bytes> {
+        match self {
+            Self::Code { address } => provider
+                .get_code_at(*address)
+                .await
+                .context("eth_getCode failed"),
+            Self::Call { to, data } => provider
+                .call(
+                    TransactionRequest::default()
+                        .to(*to)
+                        .input(data.clone().into()),
+                )
+                .await
+                .context("eth_call failed"),
+        }
+    }
+}
+
+/// Result of polling a reader
+#[derive(Debug, Clone, Copy, PartialEq, Eq)]
+pub struct PollOutcome {
+    pub consistent: bool,
+    pub attempts: u32,
+}
+
+/// Calls `read` every `poll` until its value equals `expected` or `deadline` passes
+///
+/// At least one read is made. Failed reads count as attempts that did not match.
+pub async fn poll_until<T, F, Fut>(
+    expected: &T,
+    poll: Duration,
+    deadline: Instant,
+    mut read: F,
+) -> PollOutcome
+where
+    T: PartialEq,
+    F: FnMut() -> Fut,
+    Fut: Future<Output = Result<T>>,
+{
+    let mut attempts = 0;
+    loop {
+        attempts += 1;
+        match read().await {
+            Ok(value) if value == *expected => {
+                return PollOutcome {
+                    consistent: true,
+                    attempts,
+                };
+            }
+            Ok(_) => {}
+            Err(e) => debug!("Consistency read failed: {:#}", e),
+        }
+        if Instant::now() + poll > deadline {
+            return PollOutcome {
+                consistent: false,
+                attempts,
+            };
+        }
+        tokio::time::sleep(poll).await;
+    }
+}
+
+/// Proxy to re-read through, never the writer's own
+///
+/// Returns `None` (direct connection) when no other proxy exists.
+pub fn pick_reader_proxy(
+    writer: Option<usize>,
+    proxy_count: usize,
+    rng: &mut impl Rng,
+) -> Option<usize> {
+    let candidates = proxy_count - usize::from(writer.is_some_and(|w| w < proxy_count));
+    if candidates == 0 {
+        return None;
+    }
+    let pick = rng.gen_range(0..candidates);
+    match writer {
+        Some(w) if pick >= w => Some(pick + 1),
+        _ => Some(pick),
+    }
+}
+
+/// A read-only provider on one route
+#[derive(Clone)]
+struct Reader {
+    label: String,
+    provider: Arc<dyn Provider + Send + Sync>,
+}
+
+/// Re-reads task writes through other routes and records the propagation lag
+pub struct ConsistencyVerifier {
+    settings: VerifySettings,
+    /// Endpoint reads go to
+    rpc_url: String,
+    proxies: Vec<ProxyConfig>,
+    db: Option<Arc<DatabaseManager>>,
+    /// Readers built so far by proxy index, `None` for direct
+    readers: Mutex<HashMap<Option<usize>, Reader>>,
+}
+
+impl std::fmt::Debug for ConsistencyVerifier {
+    fn fmt(&self, f: &mut std::fmt::Formatter<'_>) -> std::fmt::Result {
+        f.debug_struct("ConsistencyVerifier")
+            .field("settings", &self.settings)
+            .field("rpc_url", &self.rpc_url)
+            .field("proxies", &self.proxies.len())
+            .finish_non_exhaustive()
+    }
+}
+
+impl ConsistencyVerifier {
+    /// Creates a verifier reading from `[verify] rpc_url`, or `rpc_url` when unset
+    pub fn new(
+        settings: VerifySettings,
+        rpc_url: &str,
+        proxies: Vec<ProxyConfig>,
+        db: Option<Arc<DatabaseManager>>,
+    ) -> Self {
+        let rpc_url = settings
+            .rpc_url
+            .clone()
+            .unwrap_or_else(|| rpc_url.to_string());
+        Self {
+            settings,
+            rpc_url,
+            proxies,
+            db,
+            readers: Mutex::new(HashMap::new()),
+        }
+    }
+
+    /// Whether this state change should be checked, per `sample_rate`
+    pub fn sample(&self) -> bool {
+        rand::thread_rng().gen_bool(self.settings.sample_rate.clamp(0.0, 1.0))
+    }
+
+    /// Checks in the background that other routes serve `expected` for `check`
+    ///
+    /// `writer_proxy` is the proxy the write went through; the re-read uses
+    /// another one.
+    pub fn spawn(
+        self: &Arc<Self>,
+        task_name: &str,
+        wallet: Address,
+        writer_proxy: Option<usize>,
+        check: StateCheck,
+        expected: Bytes,
+    ) {
+        let written_at = Instant::now();
+        let verifier = self.clone();
+        let task_name = task_name.to_string();
+
+        tokio::spawn(async move {
+            let slot = pick_reader_proxy(
+                writer_proxy,
+                verifier.proxies.len(),
+                &mut rand::thread_rng(),
+            );
+            let reader = match verifier.reader(slot) {
+                Ok(reader) => reader,
+                Err(e) => {
+                    warn!("Skipping consistency check for {}: {:#}", task_name, e);
+                    return;
+                }
+            };
+
+            tokio::time::sleep(Duration::from_millis(verifier.settings.delay_ms)).await;
+            let deadline = written_at + Duration::from_secs(verifier.settings.timeout_secs);
+            let outcome = poll_until(
+                &expected,
+                Duration::from_millis(verifier.settings.poll_ms.max(1)),
+                deadline,
+                || check.read(reader.provider.as_ref()),
+            )
+            .await;
+
+            let lag_ms = written_at.elapsed().as_millis() as i64;
+            if outcome.consistent {
+                debug!(
+                    "{} visible via {} after {}ms",
+                    check.target(),
+                    reader.label,
+                    lag_ms
+                );
+            } else {
+                warn!(
+                    "{}: {} not visible via {} after {}ms",
+                    task_name,
+                    check.target(),
+                    reader.label,
+                    lag_ms
+                );
+            }
+
+            if let Some(db) = &verifier.db {
+                let record = ConsistencyCheck {
+                    task_name,
+                    wallet_address: wallet.to_string(),
+                    target: check.target(),
+                    reader: reader.label,
+                    consistent: outcome.consistent,
+                    lag_ms,
+                    attempts: i64::from(outcome.attempts),
+                    timestamp: chrono::Utc::now().timestamp(),
+                };
+                if let Err(e) = db.log_consistency_check(&record).await {
+                    warn!("Failed to store consistency check: {:#}", e);
+                }
+            }
+        });
+    }
+
+    /// Reader for a proxy slot, built on first use
+    fn reader(&self, slot: Option<usize>) -> Result<Reader> {
+        if let Some(reader) = self.readers.lock().unwrap().get(&slot) {
+            return Ok(reader.clone());
+        }
+
+        let mut builder = Client::builder()
+            .timeout(Duration::from_secs(30))
+            .connect_timeout(Duration::from_secs(10));
+        let route = match slot.and_then(|idx| self.proxies.get(idx).map(|p| (idx, p))) {
+            Some((idx, proxy_config)) => {
+                let mut proxy = Proxy::all(&proxy_config.url).context("Failed to create proxy")?;
+                if let Some((username, password)) = proxy_config.basic_auth() {
+                    proxy = proxy.basic_auth(&username, &password);
+                }
+                builder = builder.proxy(proxy);
+                format!("proxy {}", idx)
+            }
+            None => "direct".to_string(),
+        };
+        let http = Http::with_client(
+            builder.build().context("Failed to build reqwest client")?,
+            self.rpc_url
+                .parse::<Url>()
+                .context("Invalid verify RPC URL")?,
+        );
+        let provider: Arc<dyn Provider + Send + Sync> = Arc::new(
+            alloy::providers::ProviderBuilder::new()
+                .connect_client(ClientBuilder::default().transport(http, false)),
+        );
+
+        let label = match &self.settings.rpc_url {
+            Some(url) => format!("{} {}", url, route),
+            None => route,
+        };
+        let reader = Reader { label, provider };
+        self.readers.lock().unwrap().insert(slot, reader.clone());
+        Ok(reader)
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use rand::SeedableRng;
+    use rand::rngs::StdRng;
+    use std::sync::atomic::{AtomicU32, Ordering};
+
+    #[test]
+    fn test_reader_never_uses_writer_proxy() {
+        let mut rng = StdRng::seed_from_u64(7);
+        for _ in 0..200 {
+            let pick = pick_reader_proxy(Some(2), 4, &mut rng).unwrap();
+            assert!(pick < 4 && pick != 2);
+        }
+        assert_eq!(pick_reader_proxy(Some(0), 1, &mut rng), None);
+        assert_eq!(pick_reader_proxy(None, 0, &mut rng), None);
+        assert_eq!(pick_reader_proxy(None, 1, &mut rng), Some(0));
+    }
+
+    #[tokio::test]
+    async fn test_poll_until_lagging_reader() {
+        let reads = AtomicU32::new(0);
+        let lagging = || async {
+            // Old value for the first two reads, a failure, then the new one
+            Ok(match reads.fetch_add(1, Ordering::SeqCst) {
+                0 | 1 => 0u8,
+                2 => anyhow::bail!("connection reset"),
+                _ => 1,
+            })
+        };
+
+        let deadline = Instant::now() + Duration::from_secs(5);
+        let outcome = poll_until(&1, Duration::from_millis(1), deadline, lagging).await;
+        assert_eq!(
+            outcome,
+            PollOutcome {
+                consistent: true,
+                attempts: 4
+            }
+        );
+
+        let deadline = Instant::now() + Duration::from_millis(20);
+        let stale = poll_until(&1, Duration::from_millis(5), deadline, || async { Ok(0u8) }).await;
+        assert!(!stale.consistent);
+        assert!(stale.attempts >= 2);
+    }
+
+    #[test]
+    fn test_target_labels() {
+        let token: Address = "0x20C0000000000000000000000000000000000005"
+            .parse()
+            .unwrap();
+        let check = StateCheck::Call {
+            to: token,
+            data: Bytes::from(vec![0x70, 0xa0, 0x82, 0x31, 0, 0]),
+        };
+        assert_eq!(check.target(), format!("call {}:70a08231", token));
+        assert_eq!(
+            StateCheck::Code { address: token }.target(),
+            format!("code {}", token)
+        );
+    }
+}
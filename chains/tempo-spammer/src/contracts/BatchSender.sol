@@ -0,0 +1,16 @@
+// SPDX-License-Identifier: MIT
+pragma solidity ^0.8.0;
+
+interface ITIP20 {
+    function transfer(address to, uint256 amount) external returns (bool);
+}
+
+/// Pays out of its own token balance, so fund and call it in one transaction
+contract BatchSender {
+    function batchTransfer(address token, address[] calldata recipients, uint256[] calldata amounts) external {
+        require(recipients.length == amounts.length);
+        for (uint256 i = 0; i < recipients.length; i++) {
+            ITIP20(token).transfer(recipients[i], amounts[i]);
+        }
+    }
+}
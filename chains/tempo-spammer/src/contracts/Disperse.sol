@@ -0,0 +1,16 @@
+// SPDX-License-Identifier: MIT
+pragma solidity ^0.8.0;
+
+interface ITIP20 {
+    function transferFrom(address from, address to, uint256 amount) external returns (bool);
+}
+
+/// Pulls each value from the caller to its recipient; one approval covers a batch
+contract Disperse {
+    function disperseToken(address token, address[] calldata recipients, uint256[] calldata values) external {
+        require(recipients.length == values.length);
+        for (uint256 i = 0; i < recipients.length; i++) {
+            ITIP20(token).transferFrom(msg.sender, recipients[i], values[i]);
+        }
+    }
+}
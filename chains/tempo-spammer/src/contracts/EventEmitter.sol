@@ -0,0 +1,10 @@
+// SPDX-License-Identifier: MIT
+pragma solidity ^0.8.0;
+
+contract EventEmitter {
+    event Emitted(address indexed sender, bytes32 indexed tag, uint256 value);
+
+    function emitEvent(bytes32 tag, uint256 value) external {
+        emit Emitted(msg.sender, tag, value);
+    }
+}
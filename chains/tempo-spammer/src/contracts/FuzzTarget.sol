@@ -0,0 +1,15 @@
+// SPDX-License-Identifier: MIT
+pragma solidity ^0.8.0;
+
+/// Accepts any calldata; `poke` writes storage and reverts for multiples of 7
+contract FuzzTarget {
+    function poke(uint256 seed) external returns (uint256) {
+        require(seed % 7 != 0);
+        assembly {
+            sstore(and(seed, 0xff), seed)
+        }
+        return seed;
+    }
+
+    fallback() external {}
+}
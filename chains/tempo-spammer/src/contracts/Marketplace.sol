@@ -0,0 +1,50 @@
+// SPDX-License-Identifier: MIT
+pragma solidity ^0.8.0;
+
+interface IERC721 {
+    function transferFrom(address from, address to, uint256 tokenId) external;
+}
+
+interface ITIP20 {
+    function transferFrom(address from, address to, uint256 amount) external returns (bool);
+}
+
+/// Fixed-price NFT listings paid in a TIP-20 token. Nothing is escrowed: the
+/// seller approves the marketplace, the buyer approves the price
+contract Marketplace {
+    struct Listing {
+        address seller;
+        address nft;
+        uint256 tokenId;
+        address token;
+        uint256 price;
+    }
+
+    uint256 public listingCount;
+    mapping(uint256 => Listing) public listings;
+
+    event Listed(uint256 indexed id, address indexed seller, address nft, uint256 tokenId, address token, uint256 price);
+    event Sold(uint256 indexed id, address indexed buyer);
+    event Cancelled(uint256 indexed id);
+
+    function list(address nft, uint256 tokenId, address token, uint256 price) external returns (uint256 id) {
+        id = ++listingCount;
+        listings[id] = Listing(msg.sender, nft, tokenId, token, price);
+        emit Listed(id, msg.sender, nft, tokenId, token, price);
+    }
+
+    function buy(uint256 id) external {
+        Listing memory listing = listings[id];
+        require(listing.seller != address(0));
+        delete listings[id].seller;
+        ITIP20(listing.token).transferFrom(msg.sender, listing.seller, listing.price);
+        IERC721(listing.nft).transferFrom(listing.seller, msg.sender, listing.tokenId);
+        emit Sold(id, msg.sender);
+    }
+
+    function cancel(uint256 id) external {
+        require(listings[id].seller == msg.sender);
+        delete listings[id].seller;
+        emit Cancelled(id);
+    }
+}
@@ -0,0 +1,41 @@
+// SPDX-License-Identifier: MIT
+pragma solidity ^0.8.0;
+
+/// Checks EIP-712 signed orders and logs the valid ones. Nothing is stored,
+/// so an order verifies as often as it is sent until its deadline
+contract OrderVerifier {
+    struct Order {
+        address maker;
+        address token;
+        uint256 amount;
+        uint256 nonce;
+        uint256 deadline;
+    }
+
+    bytes32 constant DOMAIN_TYPEHASH =
+        keccak256("EIP712Domain(string name,string version,uint256 chainId,address verifyingContract)");
+    bytes32 constant ORDER_TYPEHASH =
+        keccak256("Order(address maker,address token,uint256 amount,uint256 nonce,uint256 deadline)");
+
+    event OrderVerified(bytes32 indexed digest, address indexed maker, uint256 nonce);
+
+    function verifyOrder(Order calldata order, uint8 v, bytes32 r, bytes32 s) external {
+        require(block.timestamp <= order.deadline);
+        bytes32 domainSeparator = keccak256(
+            abi.encode(
+                DOMAIN_TYPEHASH,
+                keccak256("Tempo Order Verifier"),
+                keccak256("1"),
+                block.chainid,
+                address(this)
+            )
+        );
+        bytes32 structHash = keccak256(
+            abi.encode(ORDER_TYPEHASH, order.maker, order.token, order.amount, order.nonce, order.deadline)
+        );
+        bytes32 digest = keccak256(abi.encodePacked("\x19\x01", domainSeparator, structHash));
+        address signer = ecrecover(digest, v, r, s);
+        require(signer != address(0) && signer == order.maker);
+        emit OrderVerified(digest, order.maker, order.nonce);
+    }
+}
@@ -0,0 +1,666 @@
+//! Framework helper contracts
+//!
+//! Small contracts tasks call instead of looping over single transactions.
+//! `bootstrap` deploys them and [`HelperRegistry`] checks them at startup:
+//!
+//! - [`disperse`]: `disperseToken(token, recipients, values)` pulls each
+//!   value from the caller to its recipient with `transferFrom`, so one
+//!   approval covers a whole batch
+//! - [`batch_sender`]: `batchTransfer(token, recipients, amounts)` pays
+//!   each recipient from the contract's own balance with `transfer`. Anyone
+//!   can spend that balance, so fund it within the same Tempo transaction
+//! - [`event_emitter`]: `emitEvent(tag, value)` logs
+//!   `Emitted(sender, tag, value)` and does nothing else
+//! - [`fuzz_target`]: `poke(seed)` stores `seed` in slot `seed & 0xff` and
+//!   returns it, reverting when `seed` is a multiple of 7. Any other
+//!   calldata succeeds without effect
+//! - [`marketplace`]: fixed-price NFT listings paid in a TIP-20 token.
+//!   `list(nft, tokenId, token, price)` records a listing and logs
+//!   `Listed`, `buy(id)` pays the seller with `transferFrom` and moves the
+//!   NFT to the buyer, `cancel(id)` lets the seller withdraw it. Nothing is
+//!   escrowed, so the seller keeps the NFT approved for the marketplace
+//! - [`order_verifier`]: `verifyOrder(order, v, r, s)` recovers the signer
+//!   of an EIP-712 [`Order`](IOrderVerifier::Order) under
+//!   [`order_domain`] and logs `OrderVerified(digest, maker, nonce)` when it
+//!   is the maker and the deadline has not passed. Nothing is stored
+//!
+//! The `.sol` files next to this module are the reference sources. The
+//! deployed runtime is assembled with [`crate::asm`] like the local devnet
+//! mocks, which keeps the spammer free of a Solidity toolchain; it ignores
+//! the booleans tokens return, as TIP-20 transfers revert on failure.
+//! Mismatched array lengths or a failed transfer revert everything, and all
+//! but the fuzz target revert on unknown selectors. The marketplace keeps
+//! its listings where the reference source's `listings` mapping would.
+//!
+//! Multicall3 is not among them: Tempo ships it in genesis, so bootstrap
+//! checks it like a system contract.
+
+pub mod registry;
+
+pub use registry::{HelperCheck, HelperRegistry};
+
+use crate::asm::{Asm, op};
+use alloy::json_abi::Function;
+use alloy_primitives::{Address, B256, Bytes, U256, keccak256};
+use alloy_sol_types::{Eip712Domain, SolCall, SolEvent, SolStruct, sol};
+
+sol! {
+    interface IDisperse {
+        function disperseToken(address token, address[] recipients, uint256[] values);
+    }
+
+    interface IBatchSender {
+        function batchTransfer(address token, address[] recipients, uint256[] amounts);
+    }
+
+    interface IEventEmitter {
+        function emitEvent(bytes32 tag, uint256 value);
+
+        event Emitted(address indexed sender, bytes32 indexed tag, uint256 value);
+    }
+
+    interface IFuzzTarget {
+        function poke(uint256 seed) returns (uint256);
+    }
+
+    interface IMarketplace {
+        function list(address nft, uint256 tokenId, address token, uint256 price) returns (uint256 id);
+        function buy(uint256 id);
+        function cancel(uint256 id);
+        function listings(uint256 id) view returns (address seller, address nft, uint256 tokenId, address token, uint256 price);
+        function listingCount() view returns (uint256);
+
+        event Listed(uint256 indexed id, address indexed seller, address nft, uint256 tokenId, address token, uint256 price);
+        event Sold(uint256 indexed id, address indexed buyer);
+        event Cancelled(uint256 indexed id);
+    }
+
+    interface IOrderVerifier {
+        struct Order {
+            address maker;
+            address token;
+            uint256 amount;
+            uint256 nonce;
+            uint256 deadline;
+        }
+
+        function verifyOrder(Order order, uint8 v, bytes32 r, bytes32 s);
+
+        event OrderVerified(bytes32 indexed digest, address indexed maker, uint256 nonce);
+    }
+
+    interface IHelperToken {
+        function transfer(address to, uint256 amount) returns (bool);
+        function transferFrom(address from, address to, uint256 amount) returns (bool);
+    }
+}
+
+/// A helper contract with its reference source and runtime code
+#[derive(Debug, Clone, PartialEq, Eq)]
+pub struct HelperContract {
+    /// Name deployments are recorded and pinned under
+    pub name: &'static str,
+    /// Solidity the runtime implements
+    pub source: &'static str,
+    pub runtime: Bytes,
+}
+
+impl HelperContract {
+    /// Hash a deployed copy's code must have
+    pub fn code_hash(&self) -> B256 {
+        keccak256(&self.runtime)
+    }
+
+    /// Functions of the contract, parsed from its reference source
+    ///
+    /// Only the last `contract` block is read, so the interfaces of the
+    /// tokens it calls are left out. Public state variables have no
+    /// `function` declaration and are missing too.
+    pub fn abi(&self) -> Vec<Function> {
+        let body = self
+            .source
+            .rfind("\ncontract ")
+            .map_or(self.source, |at| &self.source[at..]);
+        body.split("function ")
+            .skip(1)
+            .filter_map(|declaration| {
+                let end = declaration.find(['{', ';'])?;
+                let declaration = declaration[..end]
+                    .split_whitespace()
+                    .collect::<Vec<_>>()
+                    .join(" ");
+                Function::parse(&format!("function {}", declaration)).ok()
+            })
+            .collect()
+    }
+}
+
+/// Every helper contract `bootstrap` deploys
+pub fn helper_contracts() -> Vec<HelperContract> {
+    vec![
+        HelperContract {
+            name: "disperse",
+            source: include_str!("Disperse.sol"),
+            runtime: disperse(),
+        },
+        HelperContract {
+            name: "batch_sender",
+            source: include_str!("BatchSender.sol"),
+            runtime: batch_sender(),
+        },
+        HelperContract {
+            name: "event_emitter",
+            source: include_str!("EventEmitter.sol"),
+            runtime: event_emitter(),
+        },
+        HelperContract {
+            name: "fuzz_target",
+            source: include_str!("FuzzTarget.sol"),
+            runtime: fuzz_target(),
+        },
+        HelperContract {
+            name: "marketplace",
+            source: include_str!("Marketplace.sol"),
+            runtime: marketplace(),
+        },
+        HelperContract {
+            name: "order_verifier",
+            source: include_str!("OrderVerifier.sol"),
+            runtime: order_verifier(),
+        },
+    ]
+}
+
+/// Runtime code of the disperse helper
+pub fn disperse() -> Bytes {
+    token_fan_out(
+        IDisperse::disperseTokenCall::SELECTOR,
+        IHelperToken::transferFromCall::SELECTOR,
+        true,
+    )
+}
+
+/// Runtime code of the batch sender helper
+pub fn batch_sender() -> Bytes {
+    token_fan_out(
+        IBatchSender::batchTransferCall::SELECTOR,
+        IHelperToken::transferCall::SELECTOR,
+        false,
+    )
+}
+
+/// `entry(token, recipients, values)` calling `token` once per recipient
+///
+/// The calls are `transferFrom(caller, recipient, value)` when `from_caller`
+/// is set and `transfer(recipient, value)` otherwise.
+fn token_fan_out(entry: [u8; 4], transfer: [u8; 4], from_caller: bool) -> Bytes {
+    let (recipient_at, value_at, args_len) = if from_caller {
+        (36, 68, 100)
+    } else {
+        (4, 36, 68)
+    };
+
+    let mut asm = Asm::default();
+    asm.selector()
+        .dispatch(entry, "entry")
+        .push_label("fail")
+        .op(op::JUMP);
+
+    // Array arguments hold offsets from the start of the arguments to the length word
+    asm.label("entry")
+        .op(op::POP)
+        .arg(1)
+        .push_u64(4)
+        .op(op::ADD) // [recipients]
+        .arg(2)
+        .push_u64(4)
+        .op(op::ADD) // [values, recipients]
+        .op(op::DUP2)
+        .op(op::CALLDATALOAD) // [n, values, recipients]
+        .op(op::DUP2)
+        .op(op::CALLDATALOAD)
+        .op(op::DUP2)
+        .op(op::EQ)
+        .op(op::ISZERO)
+        .push_label("fail")
+        .op(op::JUMPI)
+        .push_u64(0); // [i, n, values, recipients]
+
+    asm.label("loop")
+        .op(op::DUP2)
+        .op(op::DUP2)
+        .op(op::LT)
+        .op(op::ISZERO)
+        .push_label("done")
+        .op(op::JUMPI)
+        .op(op::DUP1)
+        .push_u64(1)
+        .op(op::ADD)
+        .push_u64(5)
+        .op(op::SHL) // [32 * (i + 1), i, n, values, recipients]
+        .mstore(0, |a| {
+            a.push_selector(transfer);
+        });
+    if from_caller {
+        asm.mstore(4, |a| {
+            a.op(op::CALLER);
+        });
+    }
+    asm.mstore(recipient_at, |a| {
+        a.op(op::DUP5).op(op::DUP2).op(op::ADD).op(op::CALLDATALOAD);
+    })
+    .op(op::DUP4)
+    .op(op::ADD)
+    .op(op::CALLDATALOAD)
+    .push_u64(value_at)
+    .op(op::MSTORE) // [i, n, values, recipients]
+    .call_arg(0, args_len)
+    .push_u64(1)
+    .op(op::ADD)
+    .push_label("loop")
+    .op(op::JUMP);
+
+    asm.label("done").op(op::STOP).fail();
+    asm.assemble()
+}
+
+/// Runtime code of the event emitter helper
+pub fn event_emitter() -> Bytes {
+    let mut asm = Asm::default();
+    asm.selector()
+        .dispatch(IEventEmitter::emitEventCall::SELECTOR, "emit")
+        .push_label("fail")
+        .op(op::JUMP);
+
+    asm.label("emit")
+        .op(op::POP)
+        .mstore(0, |a| {
+            a.arg(1);
+        })
+        .arg(0)
+        .op(op::CALLER)
+        .push(IEventEmitter::Emitted::SIGNATURE_HASH.into())
+        .push_u64(32)
+        .push_u64(0)
+        .op(op::LOG3)
+        .op(op::STOP)
+        .fail();
+    asm.assemble()
+}
+
+/// Runtime code of the fuzz target helper
+pub fn fuzz_target() -> Bytes {
+    let mut asm = Asm::default();
+    asm.selector()
+        .dispatch(IFuzzTarget::pokeCall::SELECTOR, "poke")
+        .op(op::STOP);
+
+    asm.label("poke")
+        .op(op::POP)
+        .arg(0) // [seed]
+        .push_u64(7)
+        .op(op::DUP2)
+        .op(op::MOD)
+        .op(op::ISZERO)
+        .push_label("fail")
+        .op(op::JUMPI)
+        .op(op::DUP1)
+        .op(op::DUP1)
+        .push_u64(0xff)
+        .op(op::AND) // [slot, seed, seed]
+        .op(op::SSTORE)
+        .return_word()
+        .fail();
+    asm.assemble()
+}
+
+/// Storage slot of the `listings` mapping, after `listingCount` in slot 0
+const LISTINGS_SLOT: u64 = 1;
+/// Memory the marketplace copies a listing to before paying out
+const LISTING_AT: u64 = 0x100;
+
+/// Replaces the listing id on top of the stack with its first storage slot,
+/// keeping the id below it
+///
+/// The fields follow in the order of `IMarketplace::listings`, one slot each.
+fn listing_slot(asm: &mut Asm) {
+    asm.mstore(0, |a| {
+        a.op(op::DUP1);
+    })
+    .mstore(32, |a| {
+        a.push_u64(LISTINGS_SLOT);
+    })
+    .push_u64(64)
+    .push_u64(0)
+    .op(op::KECCAK256);
+}
+
+/// Runtime code of the marketplace helper
+pub fn marketplace() -> Bytes {
+    // Offsets of the fields in a listing copied to LISTING_AT
+    let [seller_at, nft_at, token_id_at, token_at, price_at] =
+        [0, 1, 2, 3, 4].map(|field| LISTING_AT + 32 * field);
+
+    let mut asm = Asm::default();
+    asm.selector()
+        .dispatch(IMarketplace::listCall::SELECTOR, "list")
+        .dispatch(IMarketplace::buyCall::SELECTOR, "buy")
+        .dispatch(IMarketplace::cancelCall::SELECTOR, "cancel")
+        .dispatch(IMarketplace::listingsCall::SELECTOR, "listings")
+        .dispatch(IMarketplace::listingCountCall::SELECTOR, "count")
+        .push_label("fail")
+        .op(op::JUMP);
+
+    asm.label("list")
+        .op(op::POP)
+        .push_u64(0)
+        .op(op::SLOAD)
+        .push_u64(1)
+        .op(op::ADD) // [id]
+        .op(op::DUP1)
+        .push_u64(0)
+        .op(op::SSTORE);
+    listing_slot(&mut asm); // [slot, id]
+    asm.op(op::CALLER).op(op::DUP2).op(op::SSTORE);
+    for field in 1..=4 {
+        asm.arg(field - 1)
+            .op(op::DUP2)
+            .push_u64(field)
+            .op(op::ADD)
+            .op(op::SSTORE);
+    }
+    asm.op(op::POP); // [id]
+    for field in 0..4 {
+        asm.mstore(32 * field, |a| {
+            a.arg(field);
+        });
+    }
+    asm.op(op::CALLER)
+        .op(op::DUP2)
+        .push(IMarketplace::Listed::SIGNATURE_HASH.into())
+        .push_u64(128)
+        .push_u64(0)
+        .op(op::LOG3)
+        .return_word();
+
+    // Clears the seller before calling out, so a listing sells once
+    asm.label("buy").op(op::POP).arg(0);
+    listing_slot(&mut asm); // [slot, id]
+    for field in 0..5 {
+        asm.op(op::DUP1)
+            .push_u64(field)
+            .op(op::ADD)
+            .op(op::SLOAD)
+            .push_u64(LISTING_AT + 32 * field)
+            .op(op::MSTORE);
+    }
+    asm.push_u64(seller_at)
+        .op(op::MLOAD)
+        .op(op::ISZERO)
+        .push_label("fail")
+        .op(op::JUMPI)
+        .push_u64(0)
+        .op(op::DUP2)
+        .op(op::SSTORE)
+        .op(op::POP) // [id]
+        .mstore(0, |a| {
+            a.push_selector(IHelperToken::transferFromCall::SELECTOR);
+        })
+        .mstore(4, |a| {
+            a.op(op::CALLER);
+        })
+        .mstore(36, |a| {
+            a.push_u64(seller_at).op(op::MLOAD);
+        })
+        .mstore(68, |a| {
+            a.push_u64(price_at).op(op::MLOAD);
+        })
+        .call_mem(token_at, 100)
+        .mstore(4, |a| {
+            a.push_u64(seller_at).op(op::MLOAD);
+        })
+        .mstore(36, |a| {
+            a.op(op::CALLER);
+        })
+        .mstore(68, |a| {
+            a.push_u64(token_id_at).op(op::MLOAD);
+        })
+        .call_mem(nft_at, 100)
+        .op(op::CALLER)
+        .op(op::DUP2)
+        .push(IMarketplace::Sold::SIGNATURE_HASH.into())
+        .push_u64(0)
+        .push_u64(0)
+        .op(op::LOG3)
+        .op(op::STOP);
+
+    asm.label("cancel").op(op::POP).arg(0);
+    listing_slot(&mut asm); // [slot, id]
+    asm.op(op::DUP1)
+        .op(op::SLOAD)
+        .op(op::CALLER)
+        .op(op::EQ)
+        .op(op::ISZERO)
+        .push_label("fail")
+        .op(op::JUMPI)
+        .push_u64(0)
+        .op(op::DUP2)
+        .op(op::SSTORE)
+        .op(op::POP) // [id]
+        .push(IMarketplace::Cancelled::SIGNATURE_HASH.into())
+        .push_u64(0)
+        .push_u64(0)
+        .op(op::LOG2)
+        .op(op::STOP);
+
+    asm.label("listings").op(op::POP).arg(0);
+    listing_slot(&mut asm); // [slot, id]
+    for field in 0..5 {
+        asm.mstore(32 * field, |a| {
+            a.op(op::DUP1).push_u64(field).op(op::ADD).op(op::SLOAD);
+        });
+    }
+    asm.push_u64(160).push_u64(0).op(op::RETURN);
+
+    asm.label("count")
+        .op(op::POP)
+        .push_u64(0)
+        .op(op::SLOAD)
+        .return_word()
+        .fail();
+    asm.assemble()
+}
+
+/// EIP-712 domain name of the order verifier
+pub const ORDER_DOMAIN_NAME: &str = "Tempo Order Verifier";
+/// EIP-712 domain version of the order verifier
+pub const ORDER_DOMAIN_VERSION: &str = "1";
+
+/// EIP-712 domain orders for the verifier deployed at `verifier` are signed under
+pub fn order_domain(chain_id: u64, verifier: Address) -> Eip712Domain {
+    Eip712Domain::new(
+        Some(ORDER_DOMAIN_NAME.into()),
+        Some(ORDER_DOMAIN_VERSION.into()),
+        Some(U256::from(chain_id)),
+        Some(verifier),
+        None,
+    )
+}
+
+/// Memory the order verifier hashes the order at
+const ORDER_AT: u64 = 0x200;
+/// Memory the order verifier builds the signed digest's preimage at
+const DIGEST_AT: u64 = 0x300;
+
+/// Runtime code of the order verifier helper
+pub fn order_verifier() -> Bytes {
+    let domain_typehash = keccak256(
+        "EIP712Domain(string name,string version,uint256 chainId,address verifyingContract)",
+    );
+
+    let mut asm = Asm::default();
+    asm.selector()
+        .dispatch(IOrderVerifier::verifyOrderCall::SELECTOR, "verify")
+        .push_label("fail")
+        .op(op::JUMP);
+
+    // The order's five fields are the first five arguments, then v, r and s
+    asm.label("verify")
+        .op(op::POP)
+        .op(op::TIMESTAMP)
+        .arg(4)
+        .op(op::LT)
+        .push_label("fail")
+        .op(op::JUMPI)
+        .mstore(0, |a| {
+            a.push(domain_typehash.into());
+        })
+        .mstore(32, |a| {
+            a.push(keccak256(ORDER_DOMAIN_NAME).into());
+        })
+        .mstore(64, |a| {
+            a.push(keccak256(ORDER_DOMAIN_VERSION).into());
+        })
+        .mstore(96, |a| {
+            a.op(op::CHAINID);
+        })
+        .mstore(128, |a| {
+            a.op(op::ADDRESS);
+        })
+        .push_u64(160)
+        .push_u64(0)
+        .op(op::KECCAK256) // [domain separator]
+        .mstore(DIGEST_AT, |a| {
+            a.push(U256::from(0x1901) << 240);
+        })
+        .push_u64(DIGEST_AT + 2)
+        .op(op::MSTORE)
+        .mstore(ORDER_AT, |a| {
+            a.push(keccak256(IOrderVerifier::Order::eip712_encode_type().as_bytes()).into());
+        });
+    for field in 0..5 {
+        asm.mstore(ORDER_AT + 32 * (field + 1), |a| {
+            a.arg(field);
+        });
+    }
+    asm.push_u64(192)
+        .push_u64(ORDER_AT)
+        .op(op::KECCAK256) // [struct hash]
+        .push_u64(DIGEST_AT + 34)
+        .op(op::MSTORE)
+        .push_u64(66)
+        .push_u64(DIGEST_AT)
+        .op(op::KECCAK256); // [digest]
+
+    // ecrecover leaves its output untouched when recovery fails, so clear it first
+    asm.mstore(0, |a| {
+        a.op(op::DUP1);
+    });
+    for word in 1..4 {
+        asm.mstore(32 * word, |a| {
+            a.arg(4 + word);
+        });
+    }
+    asm.mstore(128, |a| {
+        a.push_u64(0);
+    })
+    .push_u64(32)
+    .push_u64(128)
+    .push_u64(128)
+    .push_u64(0)
+    .push_u64(1)
+    .op(op::GAS)
+    .op(op::STATICCALL)
+    .op(op::ISZERO)
+    .push_label("fail")
+    .op(op::JUMPI)
+    .push_u64(128)
+    .op(op::MLOAD) // [signer, digest]
+    .op(op::DUP1)
+    .op(op::ISZERO)
+    .push_label("fail")
+    .op(op::JUMPI)
+    .arg(0)
+    .op(op::EQ)
+    .op(op::ISZERO)
+    .push_label("fail")
+    .op(op::JUMPI)
+    .mstore(0, |a| {
+        a.arg(3);
+    })
+    .arg(0)
+    .op(op::DUP2)
+    .push(IOrderVerifier::OrderVerified::SIGNATURE_HASH.into())
+    .push_u64(32)
+    .push_u64(0)
+    .op(op::LOG3)
+    .op(op::STOP)
+    .fail();
+    asm.assemble()
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use crate::asm::bad_jumps;
+
+    #[test]
+    fn test_helper_jumps_land_on_jumpdests() {
+        for helper in helper_contracts() {
+            assert!(bad_jumps(&helper.runtime).is_empty(), "{}", helper.name);
+        }
+    }
+
+    #[test]
+    fn test_sources_declare_entry_points() {
+        for (helper, entry) in helper_contracts().iter().zip([
+            "function disperseToken(",
+            "function batchTransfer(",
+            "function emitEvent(",
+            "function poke(",
+            "function list(",
+            "function verifyOrder(",
+        ]) {
+            assert!(helper.source.contains(entry), "{}", helper.name);
+        }
+    }
+
+    #[test]
+    fn test_order_types_match_source() {
+        let source = include_str!("OrderVerifier.sol");
+        assert!(source.contains(IOrderVerifier::Order::eip712_encode_type().as_ref()));
+        assert!(source.contains(ORDER_DOMAIN_NAME));
+        assert_eq!(
+            order_domain(1, Address::ZERO).encode_type(),
+            "EIP712Domain(string name,string version,uint256 chainId,address verifyingContract)"
+        );
+    }
+
+    #[test]
+    fn test_abi_parsed_from_source() {
+        let helpers = helper_contracts();
+        let abi = |name: &str| helpers.iter().find(|h| h.name == name).unwrap().abi();
+
+        let poke = abi("fuzz_target");
+        assert_eq!(poke.len(), 1);
+        assert_eq!(poke[0].selector(), IFuzzTarget::pokeCall::SELECTOR);
+
+        // The ITIP20 interface above the contract is skipped
+        let disperse = abi("disperse");
+        assert_eq!(disperse.len(), 1);
+        assert_eq!(
+            disperse[0].selector(),
+            IDisperse::disperseTokenCall::SELECTOR
+        );
+
+        let selectors: Vec<_> = abi("marketplace").iter().map(|f| f.selector()).collect();
+        assert_eq!(
+            selectors,
+            [
+                IMarketplace::listCall::SELECTOR,
+                IMarketplace::buyCall::SELECTOR,
+                IMarketplace::cancelCall::SELECTOR,
+            ]
+        );
+    }
+}
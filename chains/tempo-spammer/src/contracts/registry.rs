@@ -0,0 +1,204 @@
+//! Helper Registry - Startup check of pinned and deployed helper contracts
+//!
+//! A testnet reset leaves configured helper addresses pointing at empty
+//! accounts, and tasks calling them would send transactions that do
+//! nothing. At startup the runner resolves each helper to the address pinned
+//! for the chain in `[helpers.pinned]`, or else the one `bootstrap`
+//! recorded, and compares the code hash there with the bundled runtime.
+//! Tasks ask [`TaskContext::helper`](crate::tasks::TaskContext::helper) for
+//! an address and get an error naming the problem when the helper did not
+//! check out:
+//!
+//! ```toml
+//! [helpers.pinned.42431]
+//! disperse = "0x..."
+//! ```
+
+use crate::config::HelperSettings;
+use crate::contracts::helper_contracts;
+use alloy::providers::Provider;
+use alloy_primitives::{Address, keccak256};
+use anyhow::{Context, Result, anyhow};
+use core_logic::database::DatabaseManager;
+
+/// Resolution of one helper contract
+#[derive(Debug, Clone, PartialEq, Eq)]
+pub struct HelperCheck {
+    pub name: &'static str,
+    /// Pinned or recorded address, if there is one
+    pub address: Option<Address>,
+    /// Whether the address comes from `[helpers.pinned]`
+    pub pinned: bool,
+    /// Why tasks may not use the helper, `None` once it checked out
+    pub problem: Option<String>,
+}
+
+/// Helper contract addresses usable on the current chain
+#[derive(Debug, Clone, Default, PartialEq, Eq)]
+pub struct HelperRegistry {
+    chain_id: u64,
+    checks: Vec<HelperCheck>,
+}
+
+impl HelperRegistry {
+    /// Resolves every helper for `chain_id`, comparing code hashes when `settings.verify` is set
+    pub async fn resolve(
+        provider: &(dyn Provider + Send + Sync),
+        chain_id: u64,
+        settings: &HelperSettings,
+        db: Option<&DatabaseManager>,
+    ) -> Result<Self> {
+        let pinned = settings.pinned.get(&chain_id.to_string());
+        let recorded = match db {
+            Some(db) => db.get_helper_contracts(chain_id).await?,
+            None => Vec::new(),
+        };
+
+        let mut checks = Vec::new();
+        for helper in helper_contracts() {
+            let pinned_address = pinned.and_then(|pinned| pinned.get(helper.name)).copied();
+            let address = pinned_address.or_else(|| {
+                recorded
+                    .iter()
+                    .find(|record| record.name == helper.name)
+                    .and_then(|record| record.address.parse().ok())
+            });
+
+            let problem = match address {
+                None => Some(format!(
+                    "not pinned in [helpers.pinned.{}] and not deployed by `bootstrap`",
+                    chain_id
+                )),
+                Some(address) if settings.verify => {
+                    let code = provider
+                        .get_code_at(address)
+                        .await
+                        .with_context(|| format!("Failed to read code of {}", helper.name))?;
+                    if code.is_empty() {
+                        Some(format!(
+                            "no code at {}; after a network reset run `tempo-spammer bootstrap`",
+                            address
+                        ))
+                    } else if keccak256(&code) != helper.code_hash() {
+                        Some(format!(
+                            "code hash at {} is {}, expected {}",
+                            address,
+                            keccak256(&code),
+                            helper.code_hash()
+                        ))
+                    } else {
+                        None
+                    }
+                }
+                Some(_) => None,
+            };
+            checks.push(HelperCheck {
+                name: helper.name,
+                address,
+                pinned: pinned_address.is_some(),
+                problem,
+            });
+        }
+        Ok(Self { chain_id, checks })
+    }
+
+    pub fn checks(&self) -> &[HelperCheck] {
+        &self.checks
+    }
+
+    /// Address of helper `name`, or why it cannot be used
+    pub fn address(&self, name: &str) -> Result<Address> {
+        let check = self
+            .checks
+            .iter()
+            .find(|check| check.name == name)
+            .ok_or_else(|| anyhow!("Unknown helper contract '{}'", name))?;
+        match (&check.problem, check.address) {
+            (None, Some(address)) => Ok(address),
+            (Some(problem), _) => Err(anyhow!(
+                "Helper contract '{}' unavailable on chain {}: {}",
+                name,
+                self.chain_id,
+                problem
+            )),
+            (None, None) => Err(anyhow!("Helper contract '{}' has no address", name)),
+        }
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use crate::contracts::{disperse, event_emitter};
+    use crate::testing::TaskHarness;
+    use std::collections::BTreeMap;
+
+    #[tokio::test]
+    async fn test_pinned_helpers_checked_by_code_hash() {
+        let harness = TaskHarness::new();
+        let client = harness.mock.client();
+        let (good, stale, wrong) = (
+            Address::repeat_byte(1),
+            Address::repeat_byte(2),
+            Address::repeat_byte(3),
+        );
+        harness.mock.set_code(good, disperse());
+        harness.mock.set_code(wrong, disperse());
+
+        let pinned = BTreeMap::from([
+            ("disperse".to_string(), good),
+            ("batch_sender".to_string(), stale),
+            ("event_emitter".to_string(), wrong),
+        ]);
+        let settings = HelperSettings {
+            pinned: BTreeMap::from([(client.chain_id().to_string(), pinned)]),
+            ..Default::default()
+        };
+        let registry =
+            HelperRegistry::resolve(client.provider(), client.chain_id(), &settings, None)
+                .await
+                .unwrap();
+
+        assert_eq!(registry.address("disperse").unwrap(), good);
+        let err = registry.address("batch_sender").unwrap_err().to_string();
+        assert!(err.contains("no code at"), "{}", err);
+        let err = registry.address("event_emitter").unwrap_err().to_string();
+        assert!(err.contains("code hash"), "{}", err);
+        let err = registry.address("fuzz_target").unwrap_err().to_string();
+        assert!(err.contains("not pinned"), "{}", err);
+        assert!(registry.address("multicall").is_err());
+    }
+
+    #[tokio::test]
+    async fn test_recorded_helpers_used_without_pin() {
+        let harness = TaskHarness::new().with_db().await.unwrap();
+        let client = harness.mock.client();
+        let db = harness.db.clone().unwrap();
+        let address = Address::repeat_byte(4);
+        harness.mock.set_code(address, event_emitter());
+        db.log_helper_contract(&core_logic::database::HelperContractRecord {
+            chain_id: client.chain_id() as i64,
+            name: "event_emitter".to_string(),
+            address: address.to_string(),
+            code_hash: String::new(),
+            tx_hash: String::new(),
+            deployer: String::new(),
+            deployed_at: 0,
+        })
+        .await
+        .unwrap();
+
+        let registry = HelperRegistry::resolve(
+            client.provider(),
+            client.chain_id(),
+            &HelperSettings::default(),
+            Some(&db),
+        )
+        .await
+        .unwrap();
+        let check = &registry.checks()[2];
+        assert_eq!(check.name, "event_emitter");
+        assert_eq!((check.address, check.pinned), (Some(address), false));
+        assert_eq!(registry.address("event_emitter").unwrap(), address);
+    }
+}
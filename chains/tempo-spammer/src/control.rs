@@ -0,0 +1,610 @@
+//! Control Socket - Adjusting a live run without restarting
+//!
+//! With `[control] enabled = true` the spammer listens on `listen` (a
+//! loopback address by default) for one-line commands. The `ctl` subcommand
+//! sends them and prints the reply:
+//!
+//! ```bash
+//! tempo-spammer ctl log set nonce=debug
+//! tempo-spammer ctl log set tasks=info tasks.t04_create_stable=trace
+//! tempo-spammer ctl log
+//! tempo-spammer ctl log reset
+//! tempo-spammer ctl status
+//! tempo-spammer ctl metrics
+//! tempo-spammer ctl results set success_every=100 summary_secs=60
+//! ```
+//!
+//! `metrics` prints the process metrics registry in the Prometheus text
+//! format: task counts by chain, task, wallet bucket, proxy and outcome,
+//! task latency histograms, RPC calls and latencies by method, and the
+//! database query series.
+//!
+//! `results` shows and changes the sampling of success lines and the
+//! per-task summaries of the [`result_log`](crate::result_log); `results
+//! reset` goes back to `[results]`.
+//!
+//! `status` and `stop` (also the top-level `status` and `stop` subcommands)
+//! are answered once the runtime hands over a [`Lifecycle`]; `stop` winds
+//! the run down the same way Ctrl+C does.
+//!
+//! # Log modules
+//!
+//! `log set` takes `module=level` pairs. Modules name groups of tracing
+//! targets ([`LOG_MODULES`]); a dotted suffix narrows a module to one of its
+//! submodules, and a name containing `::` is used as a target as is. The
+//! most specific setting wins, so `tasks=info tasks.t04_create_stable=trace`
+//! traces one task while the others log at info.
+//!
+//! Levels are added on top of the default console and file filters: they
+//! make a subsystem more verbose but never hide errors or task results.
+//!
+//! # Named pipes
+//!
+//! On Windows `listen` may also be a named pipe such as
+//! `\\.\pipe\tempo-spammer`, which needs no free port and is only
+//! reachable from the same machine. `ctl --addr` takes the same pipe name.
+//! Other platforms reject pipe names at startup.
+
+use crate::result_log::result_log;
+use anyhow::{Context, Result, bail};
+use core_logic::LogLevels;
+use core_logic::metrics::MetricsCollector;
+use std::collections::BTreeMap;
+use std::net::SocketAddr;
+use std::sync::{Arc, Mutex};
+use tokio::io::{AsyncBufReadExt, AsyncRead, AsyncReadExt, AsyncWrite, AsyncWriteExt, BufReader};
+use tokio::net::{TcpListener, TcpStream};
+use tokio::sync::Notify;
+use tokio::task::JoinHandle;
+use tracing::level_filters::LevelFilter;
+use tracing::{debug, info, warn};
+use tracing_subscriber::filter::Targets;
+
+/// Log modules and the tracing targets they cover
+pub const LOG_MODULES: &[(&str, &[&str])] = &[
+    ("client", &["tempo_spammer::client"]),
+    ("pool", &["tempo_spammer::client_pool", "client_pool"]),
+    (
+        "nonce",
+        &[
+            "tempo_spammer::nonce_manager",
+            "tempo_spammer::robust_nonce_manager",
+            "nonce_manager",
+        ],
+    ),
+    ("proxy", &["tempo_spammer::proxy_"]),
+    ("tasks", &["tempo_spammer::tasks"]),
+    ("runner", &["tempo_spammer::runner"]),
+    ("db", &["core_logic::database", "database"]),
+];
+
+/// Longest command line the server reads
+const MAX_COMMAND_LEN: u64 = 4096;
+
+/// Prefix of Windows named pipe names
+pub const PIPE_PREFIX: &str = r"\\.\pipe\";
+
+/// Whether a control address names a Windows named pipe
+pub fn is_pipe(addr: &str) -> bool {
+    addr.len() > PIPE_PREFIX.len()
+        && addr
+            .get(..PIPE_PREFIX.len())
+            .is_some_and(|prefix| prefix.eq_ignore_ascii_case(PIPE_PREFIX))
+}
+
+/// Tracing targets for a module name such as `nonce` or `tasks.t04_create_stable`
+pub fn module_targets(name: &str) -> Result<Vec<String>> {
+    if name.contains("::") {
+        return Ok(vec![name.to_string()]);
+    }
+    let (root, sub) = match name.split_once('.') {
+        Some((root, sub)) => (root, Some(sub)),
+        None => (name, None),
+    };
+    let Some((_, targets)) = LOG_MODULES.iter().find(|(module, _)| *module == root) else {
+        bail!(
+            "Unknown log module '{}' (known: {})",
+            root,
+            LOG_MODULES
+                .iter()
+                .map(|(module, _)| *module)
+                .collect::<Vec<_>>()
+                .join(", ")
+        );
+    };
+    let Some(sub) = sub else {
+        return Ok(targets.iter().map(|target| target.to_string()).collect());
+    };
+    // Only complete module paths have submodules
+    let nested: Vec<String> = targets
+        .iter()
+        .filter(|target| target.contains("::") && !target.ends_with('_'))
+        .map(|target| format!("{}::{}", target, sub.replace('.', "::")))
+        .collect();
+    if nested.is_empty() {
+        bail!("Log module '{}' has no submodules", root);
+    }
+    Ok(nested)
+}
+
+/// Parses `module=level`
+pub fn parse_directive(directive: &str) -> Result<(String, LevelFilter)> {
+    let Some((module, level)) = directive.split_once('=') else {
+        bail!("Expected module=level, got '{}'", directive);
+    };
+    module_targets(module)?;
+    let level = level
+        .parse::<LevelFilter>()
+        .map_err(|_| anyhow::anyhow!("Unknown log level '{}'", level))?;
+    Ok((module.to_string(), level))
+}
+
+/// Filter enabling the levels set per module
+///
+/// Targets are matched by prefix, so `client` would also cover
+/// `client_pool`. Modules that were not set themselves are pinned to `off`
+/// in the filter, which leaves them at their default level.
+pub fn build_targets(levels: &BTreeMap<String, LevelFilter>) -> Result<Targets> {
+    let mut set = Vec::new();
+    for (module, level) in levels {
+        for target in module_targets(module)? {
+            set.push((target, *level));
+        }
+    }
+
+    let mut targets = Targets::new();
+    for (module, module_targets) in LOG_MODULES {
+        if levels.contains_key(*module) {
+            continue;
+        }
+        for target in *module_targets {
+            let shadowed = set
+                .iter()
+                .any(|(other, _)| target.len() > other.len() && target.starts_with(other.as_str()));
+            if shadowed {
+                targets = targets.with_target(*target, LevelFilter::OFF);
+            }
+        }
+    }
+    for (target, level) in set {
+        targets = targets.with_target(target, level);
+    }
+    Ok(targets)
+}
+
+type StatusFn = dyn Fn() -> String + Send + Sync;
+
+/// The running process behind the `status` and `stop` commands
+pub struct Lifecycle {
+    status: Box<StatusFn>,
+    stop: Arc<Notify>,
+}
+
+impl std::fmt::Debug for Lifecycle {
+    fn fmt(&self, f: &mut std::fmt::Formatter<'_>) -> std::fmt::Result {
+        f.debug_struct("Lifecycle").finish_non_exhaustive()
+    }
+}
+
+impl Lifecycle {
+    /// `status` replies with what `status` returns; `stop` notifies `stop`
+    pub fn new(status: impl Fn() -> String + Send + Sync + 'static, stop: Arc<Notify>) -> Self {
+        Self {
+            status: Box::new(status),
+            stop,
+        }
+    }
+}
+
+/// Module levels set through the control socket
+#[derive(Debug)]
+pub struct LogControl {
+    /// `None` when the logger cannot be reloaded (quiet mode)
+    levels: Option<LogLevels>,
+    current: Mutex<BTreeMap<String, LevelFilter>>,
+    lifecycle: Option<Lifecycle>,
+}
+
+impl LogControl {
+    pub fn new(levels: Option<LogLevels>) -> Self {
+        Self {
+            levels,
+            current: Mutex::new(BTreeMap::new()),
+            lifecycle: None,
+        }
+    }
+
+    /// Answers `status` and `stop` for a running process
+    pub fn with_lifecycle(mut self, lifecycle: Lifecycle) -> Self {
+        self.lifecycle = Some(lifecycle);
+        self
+    }
+
+    fn lifecycle(&self) -> Result<&Lifecycle> {
+        self.lifecycle
+            .as_ref()
+            .context("No run to report on or stop")
+    }
+
+    /// Sets levels from `module=level` directives, keeping the other modules
+    pub fn set(&self, directives: &[&str]) -> Result<String> {
+        if directives.is_empty() {
+            bail!("Usage: log set <module>=<level>...");
+        }
+        let parsed = directives
+            .iter()
+            .map(|directive| parse_directive(directive))
+            .collect::<Result<Vec<_>>>()?;
+
+        let mut current = self.current.lock().unwrap();
+        let mut next = current.clone();
+        next.extend(parsed);
+        self.apply(&next)?;
+        *current = next;
+        info!("Log levels changed: {}", describe(&current));
+        Ok(format!("log levels: {}", describe(&current)))
+    }
+
+    /// Drops the given modules' levels, or all of them when `modules` is empty
+    pub fn reset(&self, modules: &[&str]) -> Result<String> {
+        let mut current = self.current.lock().unwrap();
+        let mut next = current.clone();
+        if modules.is_empty() {
+            next.clear();
+        } else {
+            for module in modules {
+                next.remove(*module);
+            }
+        }
+        self.apply(&next)?;
+        *current = next;
+        Ok(format!("log levels: {}", describe(&current)))
+    }
+
+    /// One line per module with its level
+    pub fn show(&self) -> String {
+        let current = self.current.lock().unwrap();
+        let mut lines: Vec<String> = LOG_MODULES
+            .iter()
+            .map(|(module, _)| {
+                let level = current
+                    .get(*module)
+                    .map(|level| level.to_string().to_lowercase())
+                    .unwrap_or_else(|| "default".to_string());
+                format!("{:<10} {}", module, level)
+            })
+            .collect();
+        // Submodules and raw targets after the fixed list
+        for (module, level) in current.iter() {
+            if !LOG_MODULES.iter().any(|(name, _)| name == module) {
+                lines.push(format!(
+                    "{:<10} {}",
+                    module,
+                    level.to_string().to_lowercase()
+                ));
+            }
+        }
+        lines.join("\n")
+    }
+
+    fn apply(&self, levels: &BTreeMap<String, LevelFilter>) -> Result<()> {
+        let Some(handle) = &self.levels else {
+            bail!("Log levels cannot be changed in quiet mode");
+        };
+        handle.apply(build_targets(levels)?)
+    }
+
+    /// Runs one control command and returns the reply
+    pub fn execute(&self, line: &str) -> String {
+        let words: Vec<&str> = line.split_whitespace().collect();
+        let result = match words.as_slice() {
+            ["log"] | ["log", "show"] => Ok(self.show()),
+            ["log", "set", directives @ ..] => self.set(directives),
+            ["log", "reset", modules @ ..] => self.reset(modules),
+            ["status"] => self.lifecycle().map(|lifecycle| (lifecycle.status)()),
+            ["metrics"] => Ok(MetricsCollector::global()
+                .to_prometheus()
+                .trim_end()
+                .to_string()),
+            ["results"] | ["results", "show"] => Ok(result_log().show()),
+            ["results", "set", directives @ ..] => result_log().set(directives),
+            ["results", "reset"] => Ok(result_log().reset()),
+            ["stop"] => self.lifecycle().map(|lifecycle| {
+                info!(target: "task_result", "Stop requested over the control socket");
+                lifecycle.stop.notify_one();
+                "stopping".to_string()
+            }),
+            [] | ["help"] => Ok(HELP.to_string()),
+            _ => Err(anyhow::anyhow!(
+                "Unknown command '{}', try 'help'",
+                line.trim()
+            )),
+        };
+        result.unwrap_or_else(|e| format!("error: {:#}", e))
+    }
+}
+
+const HELP: &str = "\
+log                           show log levels per module
+log set <module>=<level>...   raise modules to trace|debug|info|warn|error
+log reset [module...]         back to the defaults
+status                        state, workers, uptime and task counts
+stop                          stop the run like Ctrl+C
+metrics                       labeled counters and histograms (Prometheus text)
+results                       show result line sampling and summary interval
+results set <key>=<n>...      success_every (1 in n successes logged), summary_secs
+results reset                 back to [results]
+modules: client, pool, nonce, proxy, tasks, runner, db (tasks.<module> for one task)";
+
+fn describe(levels: &BTreeMap<String, LevelFilter>) -> String {
+    if levels.is_empty() {
+        return "defaults".to_string();
+    }
+    levels
+        .iter()
+        .map(|(module, level)| format!("{}={}", module, level.to_string().to_lowercase()))
+        .collect::<Vec<_>>()
+        .join(" ")
+}
+
+/// Listens on `listen` and answers one command per connection
+pub async fn serve(listen: &str, control: Arc<LogControl>) -> Result<JoinHandle<()>> {
+    if is_pipe(listen) {
+        return serve_pipe(listen, control);
+    }
+    let addr: SocketAddr = listen
+        .parse()
+        .with_context(|| format!("Invalid control address '{}'", listen))?;
+    if !addr.ip().is_loopback() {
+        warn!(
+            "Control socket on {} is reachable from other hosts and has no authentication",
+            addr
+        );
+    }
+    let listener = TcpListener::bind(addr)
+        .await
+        .with_context(|| format!("Failed to bind control socket {}", addr))?;
+    info!("Control socket listening on {}", addr);
+
+    Ok(tokio::spawn(async move {
+        loop {
+            let (stream, peer) = match listener.accept().await {
+                Ok(conn) => conn,
+                Err(e) => {
+                    warn!("Control socket accept failed: {}", e);
+                    continue;
+                }
+            };
+            let control = control.clone();
+            tokio::spawn(async move {
+                if let Err(e) = answer(stream, &control).await {
+                    debug!("Control connection from {} failed: {:#}", peer, e);
+                }
+            });
+        }
+    }))
+}
+
+#[cfg(windows)]
+fn serve_pipe(name: &str, control: Arc<LogControl>) -> Result<JoinHandle<()>> {
+    use tokio::net::windows::named_pipe::ServerOptions;
+
+    let mut server = ServerOptions::new()
+        .first_pipe_instance(true)
+        .create(name)
+        .with_context(|| format!("Failed to create control pipe {}", name))?;
+    info!("Control pipe listening on {}", name);
+
+    let name = name.to_string();
+    Ok(tokio::spawn(async move {
+        loop {
+            if let Err(e) = server.connect().await {
+                warn!("Control pipe connect failed: {}", e);
+                continue;
+            }
+            // A new instance takes the next client while this one answers
+            let connected = server;
+            server = match ServerOptions::new().create(&name) {
+                Ok(next) => next,
+                Err(e) => {
+                    warn!("Control pipe {} closed: {}", name, e);
+                    return;
+                }
+            };
+            let control = control.clone();
+            tokio::spawn(async move {
+                if let Err(e) = answer(connected, &control).await {
+                    debug!("Control pipe connection failed: {:#}", e);
+                }
+            });
+        }
+    }))
+}
+
+#[cfg(not(windows))]
+fn serve_pipe(name: &str, _control: Arc<LogControl>) -> Result<JoinHandle<()>> {
+    bail!(
+        "Control pipe {} needs Windows, use a host:port address",
+        name
+    )
+}
+
+async fn answer<S: AsyncRead + AsyncWrite>(stream: S, control: &LogControl) -> Result<()> {
+    let (reader, mut writer) = tokio::io::split(stream);
+    let mut line = String::new();
+    BufReader::new(reader.take(MAX_COMMAND_LEN))
+        .read_line(&mut line)
+        .await?;
+    let reply = control.execute(&line);
+    writer.write_all(reply.as_bytes()).await?;
+    writer.write_all(b"\n").await?;
+    writer.shutdown().await?;
+    Ok(())
+}
+
+/// Sends `command` to a running spammer and returns its reply
+pub async fn send_command(addr: &str, command: &str) -> Result<String> {
+    if is_pipe(addr) {
+        return exchange(connect_pipe(addr).await?, command).await;
+    }
+    let stream = TcpStream::connect(addr)
+        .await
+        .with_context(|| format!("No spammer control socket at {}", addr))?;
+    exchange(stream, command).await
+}
+
+#[cfg(windows)]
+async fn connect_pipe(name: &str) -> Result<tokio::net::windows::named_pipe::NamedPipeClient> {
+    use tokio::net::windows::named_pipe::ClientOptions;
+
+    /// ERROR_PIPE_BUSY: every instance is serving another client
+    const PIPE_BUSY: i32 = 231;
+    for _ in 0..50 {
+        match ClientOptions::new().open(name) {
+            Err(e) if e.raw_os_error() == Some(PIPE_BUSY) => {
+                tokio::time::sleep(std::time::Duration::from_millis(20)).await;
+            }
+            result => {
+                return result.with_context(|| format!("No spammer control pipe at {}", name));
+            }
+        }
+    }
+    bail!("Control pipe {} stayed busy", name)
+}
+
+#[cfg(not(windows))]
+async fn connect_pipe(name: &str) -> Result<TcpStream> {
+    bail!("Control pipe {} needs Windows", name)
+}
+
+async fn exchange<S: AsyncRead + AsyncWrite + Unpin>(
+    mut stream: S,
+    command: &str,
+) -> Result<String> {
+    stream.write_all(command.as_bytes()).await?;
+    stream.write_all(b"\n").await?;
+    let mut reply = String::new();
+    stream.read_to_string(&mut reply).await?;
+    Ok(reply.trim_end().to_string())
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use tracing::Level;
+
+    #[test]
+    fn test_module_targets() {
+        assert_eq!(
+            module_targets("tasks.t04_create_stable").unwrap(),
+            vec!["tempo_spammer::tasks::t04_create_stable"]
+        );
+        assert_eq!(
+            module_targets("db").unwrap(),
+            vec!["core_logic::database", "database"]
+        );
+        assert_eq!(
+            module_targets("tempo_spammer::burst").unwrap(),
+            vec!["tempo_spammer::burst"]
+        );
+        assert!(module_targets("mempool").is_err());
+        assert!(parse_directive("nonce=loud").is_err());
+        assert!(parse_directive("nonce").is_err());
+        assert_eq!(
+            parse_directive("nonce=DEBUG").unwrap(),
+            ("nonce".to_string(), LevelFilter::DEBUG)
+        );
+    }
+
+    #[test]
+    fn test_targets_keep_modules_apart() {
+        let levels: BTreeMap<_, _> = [
+            ("client".to_string(), LevelFilter::DEBUG),
+            ("tasks".to_string(), LevelFilter::INFO),
+            ("tasks.t04_create_stable".to_string(), LevelFilter::TRACE),
+        ]
+        .into();
+        let targets = build_targets(&levels).unwrap();
+
+        assert!(targets.would_enable("tempo_spammer::client", &Level::DEBUG));
+        // `client` is a prefix of `client_pool`, which stays at its default
+        assert!(!targets.would_enable("tempo_spammer::client_pool", &Level::ERROR));
+        assert!(targets.would_enable("tempo_spammer::tasks::t04_create_stable", &Level::TRACE));
+        assert!(targets.would_enable("tempo_spammer::tasks::t03_send_token", &Level::INFO));
+        assert!(!targets.would_enable("tempo_spammer::tasks::t03_send_token", &Level::DEBUG));
+        assert!(!targets.would_enable("tempo_spammer::nonce_manager", &Level::ERROR));
+    }
+
+    #[test]
+    fn test_pipe_names() {
+        assert!(is_pipe(r"\\.\pipe\tempo-spammer"));
+        assert!(is_pipe(r"\\.\PIPE\tempo"));
+        assert!(!is_pipe(PIPE_PREFIX));
+        assert!(!is_pipe("127.0.0.1:9137"));
+    }
+
+    #[tokio::test]
+    async fn test_commands_over_any_stream() {
+        let (client, server) = tokio::io::duplex(1024);
+        let control = LogControl::new(None);
+        let (reply, served) = tokio::join!(exchange(client, "log"), answer(server, &control));
+        served.unwrap();
+        assert!(reply.unwrap().starts_with("client     default"));
+    }
+
+    #[cfg(not(windows))]
+    #[tokio::test]
+    async fn test_pipes_need_windows() {
+        let control = Arc::new(LogControl::new(None));
+        let err = serve(r"\\.\pipe\tempo-spammer", control).await.unwrap_err();
+        assert!(err.to_string().contains("needs Windows"), "{}", err);
+    }
+
+    #[test]
+    fn test_commands_without_reloadable_logger() {
+        let control = LogControl::new(None);
+        assert!(
+            control
+                .execute("log set nonce=debug")
+                .starts_with("error: ")
+        );
+        assert!(
+            control
+                .execute("log set nonce=loud")
+                .contains("Unknown log level")
+        );
+        assert!(control.execute("frobnicate").starts_with("error: "));
+        assert!(
+            control
+                .execute("results")
+                .starts_with("results: success_every=")
+        );
+        assert!(
+            control
+                .execute("results set success_every=often")
+                .contains("needs a whole number")
+        );
+        assert!(control.execute("log").starts_with("client     default"));
+        assert!(
+            control
+                .execute("metrics")
+                .starts_with("# TYPE uptime_seconds gauge")
+        );
+    }
+
+    #[tokio::test]
+    async fn test_status_and_stop() {
+        let control = LogControl::new(None);
+        assert!(control.execute("stop").starts_with("error: "));
+
+        let stop = Arc::new(Notify::new());
+        let control =
+            control.with_lifecycle(Lifecycle::new(|| "running".to_string(), stop.clone()));
+        assert_eq!(control.execute("status"), "running");
+        assert_eq!(control.execute("stop"), "stopping");
+        // The permit is kept until the owner waits for it
+        tokio::time::timeout(std::time::Duration::from_secs(1), stop.notified())
+            .await
+            .unwrap();
+    }
+}
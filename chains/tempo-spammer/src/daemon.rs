@@ -0,0 +1,256 @@
+//! Daemon - Running detached in the background with a PID file
+//!
+//! `tempo-spammer --daemon` starts a second copy of itself without a console
+//! and returns once that copy has unlocked its wallets and started its
+//! workers, so it keeps running after the terminal or RDP session closes:
+//!
+//! ```bash
+//! WALLET_PASSWORD_FILE=secret.txt tempo-spammer --daemon spammer --workers 8
+//! tempo-spammer status
+//! tempo-spammer stop
+//! ```
+//!
+//! The detached copy runs headless (see `--non-interactive`) with the
+//! [`control`](crate::control) socket switched on, which is how `status` and
+//! `stop` reach it. When no password source is configured the password is
+//! asked for once up front and handed to the copy through its environment.
+//!
+//! Every run, detached or not, writes its process id to `[daemon] pid_file`
+//! and removes it on exit. Output that never reaches the log files (errors
+//! before the logger is up, panics) goes to [`OUTPUT_FILE`]; a failed start
+//! prints the error found there.
+
+use anyhow::{Context, Result};
+use std::ffi::OsString;
+use std::fs::{self, OpenOptions};
+use std::path::{Path, PathBuf};
+use std::process::{Child, Command, ExitStatus, Stdio};
+use std::time::{Duration, Instant};
+use tracing::warn;
+
+/// Flag that detaches the spammer
+pub const DAEMON_FLAG: &str = "--daemon";
+
+/// Hidden flag marking the detached copy
+pub const CHILD_FLAG: &str = "--daemon-child";
+
+/// Stderr of the detached copy; its stdout repeats the log and is dropped
+pub const OUTPUT_FILE: &str = "logs/daemon.out";
+
+/// How often [`wait_for_start`] looks at the child
+const POLL_INTERVAL: Duration = Duration::from_millis(250);
+
+/// The current process id in a file, removed again on drop
+#[derive(Debug)]
+pub struct PidFile {
+    path: PathBuf,
+    pid: u32,
+}
+
+impl PidFile {
+    /// Writes the current process id to `path`
+    ///
+    /// A file left behind by a run that did not exit cleanly is replaced.
+    pub fn create(path: &Path) -> Result<Self> {
+        if let Some(old) = read_pid(path) {
+            warn!("Replacing PID file {} left by pid {}", path.display(), old);
+        }
+        if let Some(parent) = path
+            .parent()
+            .filter(|parent| !parent.as_os_str().is_empty())
+        {
+            fs::create_dir_all(parent)?;
+        }
+        let pid = std::process::id();
+        fs::write(path, format!("{}\n", pid))
+            .with_context(|| format!("Failed to write PID file {}", path.display()))?;
+        Ok(Self {
+            path: path.to_path_buf(),
+            pid,
+        })
+    }
+
+    pub fn path(&self) -> &Path {
+        &self.path
+    }
+}
+
+impl Drop for PidFile {
+    fn drop(&mut self) {
+        // A newer run may have taken the file over
+        if read_pid(&self.path) == Some(self.pid) {
+            fs::remove_file(&self.path).ok();
+        }
+    }
+}
+
+/// The process id in a PID file, `None` when there is none
+pub fn read_pid(path: &Path) -> Option<u32> {
+    fs::read_to_string(path).ok()?.trim().parse().ok()
+}
+
+/// Arguments of the detached copy: `args` without [`DAEMON_FLAG`], marked
+/// with [`CHILD_FLAG`]
+pub fn child_args(args: impl IntoIterator<Item = OsString>) -> Vec<OsString> {
+    std::iter::once(OsString::from(CHILD_FLAG))
+        .chain(args.into_iter().filter(|arg| arg != DAEMON_FLAG))
+        .collect()
+}
+
+/// Starts this executable again with `args`, detached from the console
+///
+/// `password` is passed as `WALLET_PASSWORD` and `database_key` as
+/// `DATABASE_KEY` when set.
+pub fn spawn(
+    args: &[OsString],
+    password: Option<&str>,
+    database_key: Option<&str>,
+) -> Result<Child> {
+    let output = Path::new(OUTPUT_FILE);
+    if let Some(parent) = output.parent() {
+        fs::create_dir_all(parent)?;
+    }
+    let stderr = OpenOptions::new()
+        .create(true)
+        .append(true)
+        .open(output)
+        .with_context(|| format!("Failed to open {}", output.display()))?;
+
+    let exe = std::env::current_exe().context("Failed to find the running executable")?;
+    let mut command = Command::new(exe);
+    command
+        .args(args)
+        .stdin(Stdio::null())
+        .stdout(Stdio::null())
+        .stderr(stderr);
+    if let Some(password) = password {
+        command.env(core_logic::PASSWORD_ENV, password);
+    }
+    if let Some(key) = database_key {
+        command.env(core_logic::DATABASE_KEY_ENV, key);
+    }
+    detach(&mut command);
+    command
+        .spawn()
+        .context("Failed to start the background process")
+}
+
+#[cfg(windows)]
+fn detach(command: &mut Command) {
+    use std::os::windows::process::CommandExt;
+
+    const DETACHED_PROCESS: u32 = 0x0000_0008;
+    const CREATE_NEW_PROCESS_GROUP: u32 = 0x0000_0200;
+    command.creation_flags(DETACHED_PROCESS | CREATE_NEW_PROCESS_GROUP);
+}
+
+#[cfg(unix)]
+fn detach(command: &mut Command) {
+    use std::os::unix::process::CommandExt;
+
+    // Out of the terminal's process group, so Ctrl+C and hangups miss it
+    command.process_group(0);
+}
+
+#[cfg(not(any(windows, unix)))]
+fn detach(_command: &mut Command) {}
+
+/// How the start of the detached copy ended
+#[derive(Debug)]
+pub enum Started {
+    /// Workers are running under this pid
+    Running(u32),
+    /// Still unlocking wallets or scanning proxies when the wait ran out
+    Starting(u32),
+    /// Exited before its workers started
+    Exited(ExitStatus),
+}
+
+/// Waits until `child` wrote its pid to `pid_file`, exited, or `timeout`
+/// passed
+pub async fn wait_for_start(
+    child: &mut Child,
+    pid_file: &Path,
+    timeout: Duration,
+) -> Result<Started> {
+    let deadline = Instant::now() + timeout;
+    loop {
+        if let Some(status) = child.try_wait()? {
+            return Ok(Started::Exited(status));
+        }
+        if read_pid(pid_file) == Some(child.id()) {
+            return Ok(Started::Running(child.id()));
+        }
+        if Instant::now() >= deadline {
+            return Ok(Started::Starting(child.id()));
+        }
+        tokio::time::sleep(POLL_INTERVAL).await;
+    }
+}
+
+/// The last error or panic in [`OUTPUT_FILE`] without its backtrace, at
+/// most `max_lines` lines
+pub fn last_error(max_lines: usize) -> String {
+    excerpt(
+        &fs::read_to_string(OUTPUT_FILE).unwrap_or_default(),
+        max_lines,
+    )
+}
+
+fn excerpt(output: &str, max_lines: usize) -> String {
+    let lines: Vec<&str> = output.lines().collect();
+    let start = lines
+        .iter()
+        .rposition(|line| line.starts_with("Error: ") || line.starts_with("thread '"))
+        .unwrap_or(lines.len().saturating_sub(max_lines));
+    lines[start..]
+        .iter()
+        .take_while(|line| !line.starts_with("Stack backtrace:"))
+        .take(max_lines)
+        .copied()
+        .collect::<Vec<_>>()
+        .join("\n")
+        .trim_end()
+        .to_string()
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    #[test]
+    fn test_child_args_swap_the_flag() {
+        let args = ["--daemon", "spammer", "--workers", "4"].map(OsString::from);
+        assert_eq!(
+            child_args(args),
+            ["--daemon-child", "spammer", "--workers", "4"].map(OsString::from)
+        );
+    }
+
+    #[test]
+    fn test_excerpt_skips_backtraces() {
+        let output = "Error: old\n\nError: No wallets found\n\nStack backtrace:\n   0: main\n";
+        assert_eq!(excerpt(output, 5), "Error: No wallets found");
+        assert_eq!(excerpt("a\nb\nc\n", 2), "b\nc");
+    }
+
+    #[test]
+    fn test_pid_file_lifecycle() {
+        let path = std::env::temp_dir()
+            .join(format!("tempo-pid-{}", std::process::id()))
+            .join("tempo-spammer.pid");
+        assert_eq!(read_pid(&path), None);
+
+        let pid_file = PidFile::create(&path).unwrap();
+        assert_eq!(read_pid(pid_file.path()), Some(std::process::id()));
+        drop(pid_file);
+        assert!(!path.exists());
+
+        // A file taken over by another run stays
+        let pid_file = PidFile::create(&path).unwrap();
+        fs::write(&path, "1\n").unwrap();
+        drop(pid_file);
+        assert_eq!(read_pid(&path), Some(1));
+        fs::remove_dir_all(path.parent().unwrap()).unwrap();
+    }
+}
@@ -0,0 +1,244 @@
+//! Gas Usage - Receipt-based gas accounting per task
+//!
+//! Testnet gas allowances run out unevenly when a few tasks burn most of
+//! them. With `[gas] track_usage` on, the runner hands the transaction each
+//! task reports in [`TaskResult::tx_hash`](crate::tasks::TaskResult) to the
+//! [`GasTracker`]. It fetches the receipt in the background through the same
+//! client and stores gas used, effective gas price and status in `gas_usage`;
+//! `db stats` shows the per-task totals.
+//!
+//! Tasks that set gas limits themselves cap them with
+//! [`TaskContext::gas_limit`](crate::tasks::TaskContext::gas_limit), which
+//! applies the `[gas.limits]` ceiling for the task:
+//!
+//! ```toml
+//! [gas.limits]
+//! "40_distribute_shares" = 2_000_000
+//! ```
+//!
+//! Only the reported transaction is tracked, so tasks sending several count
+//! the last one.
+
+use crate::TempoClient;
+use crate::config::GasSettings;
+use alloy::providers::Provider;
+use alloy::rpc::types::TransactionReceipt;
+use alloy_primitives::{Address, B256};
+use core_logic::database::{DatabaseManager, GasUsageRecord};
+use std::sync::Arc;
+use std::sync::atomic::{AtomicU64, Ordering};
+use std::time::{Duration, Instant};
+use tokio::sync::Semaphore;
+use tracing::{debug, warn};
+
+/// Pause between receipt lookups
+const RECEIPT_POLL: Duration = Duration::from_secs(2);
+
+/// Background receipt lookups feeding `gas_usage`
+#[derive(Debug)]
+pub struct GasTracker {
+    settings: GasSettings,
+    db: Arc<DatabaseManager>,
+    pending: Arc<Semaphore>,
+    skipped: AtomicU64,
+    cost_gwei: AtomicU64,
+}
+
+impl GasTracker {
+    pub fn new(settings: GasSettings, db: Arc<DatabaseManager>) -> Self {
+        let pending = Arc::new(Semaphore::new(settings.max_pending.max(1)));
+        Self {
+            settings,
+            db,
+            pending,
+            skipped: AtomicU64::new(0),
+            cost_gwei: AtomicU64::new(0),
+        }
+    }
+
+    /// Transactions not tracked because `max_pending` lookups were running
+    pub fn skipped(&self) -> u64 {
+        self.skipped.load(Ordering::Relaxed)
+    }
+
+    /// Fees of the receipts recorded so far, in wei at gwei precision
+    pub fn cost_wei(&self) -> f64 {
+        self.cost_gwei.load(Ordering::Relaxed) as f64 * 1e9
+    }
+
+    /// Records the gas of `tx_hash` once its receipt shows up
+    pub fn spawn(self: &Arc<Self>, task_name: &str, client: &TempoClient, tx_hash: B256) {
+        let Ok(permit) = self.pending.clone().try_acquire_owned() else {
+            self.skipped.fetch_add(1, Ordering::Relaxed);
+            debug!("Too many pending receipts, not tracking gas of {}", tx_hash);
+            return;
+        };
+        let tracker = self.clone();
+        let task_name = task_name.to_string();
+        let wallet = client.address();
+        let provider = client.provider.clone();
+
+        tokio::spawn(async move {
+            let _permit = permit;
+            let timeout = Duration::from_secs(tracker.settings.receipt_timeout_secs);
+            let Some(receipt) =
+                wait_for_receipt(provider.as_ref(), tx_hash, timeout, RECEIPT_POLL).await
+            else {
+                debug!(
+                    "No receipt for {} ({}) within {:?}",
+                    tx_hash, task_name, timeout
+                );
+                return;
+            };
+            let record = usage_record(&task_name, wallet, &receipt);
+            let cost_gwei = receipt.gas_used as u128 * receipt.effective_gas_price / 1_000_000_000;
+            tracker.cost_gwei.fetch_add(
+                u64::try_from(cost_gwei).unwrap_or(u64::MAX),
+                Ordering::Relaxed,
+            );
+            if let Err(e) = tracker.db.log_gas_usage(&record).await {
+                warn!("Failed to store gas usage of {}: {:#}", tx_hash, e);
+            }
+        });
+    }
+}
+
+/// Polls for the receipt of `tx_hash` until `timeout` passes
+///
+/// Lookup errors are retried like a missing receipt.
+pub async fn wait_for_receipt(
+    provider: &(dyn Provider + Send + Sync),
+    tx_hash: B256,
+    timeout: Duration,
+    poll: Duration,
+) -> Option<TransactionReceipt> {
+    let deadline = Instant::now() + timeout;
+    loop {
+        match provider.get_transaction_receipt(tx_hash).await {
+            Ok(Some(receipt)) => return Some(receipt),
+            Ok(None) => {}
+            Err(e) => debug!("Receipt lookup for {} failed: {}", tx_hash, e),
+        }
+        if Instant::now() + poll > deadline {
+            return None;
+        }
+        tokio::time::sleep(poll).await;
+    }
+}
+
+/// The `gas_usage` row for a receipt
+pub fn usage_record(
+    task_name: &str,
+    wallet: Address,
+    receipt: &TransactionReceipt,
+) -> GasUsageRecord {
+    GasUsageRecord {
+        task_name: task_name.to_string(),
+        wallet_address: wallet.to_string(),
+        tx_hash: receipt.transaction_hash.to_string(),
+        gas_used: receipt.gas_used as i64,
+        effective_gas_price: i64::try_from(receipt.effective_gas_price).unwrap_or(i64::MAX),
+        block_number: receipt.block_number.map(|block| block as i64),
+        success: receipt.status(),
+        timestamp: chrono::Utc::now().timestamp(),
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use crate::testing::TaskHarness;
+    use alloy::rpc::types::TransactionRequest;
+
+    #[tokio::test]
+    async fn test_receipt_recorded_in_gas_usage() {
+        let harness = TaskHarness::new().with_db().await.unwrap();
+        harness.mock.set_gas(30_000_000_000, 10_000_000_000, 65_000);
+        let client = harness.mock.client();
+        let pending = client
+            .provider
+            .send_transaction(
+                TransactionRequest::default()
+                    .to(Address::repeat_byte(0x42))
+                    .from(client.address()),
+            )
+            .await
+            .unwrap();
+
+        let db = harness.db.clone().unwrap();
+        let tracker = Arc::new(GasTracker::new(GasSettings::default(), db.clone()));
+        tracker.spawn("03_send_token", &client, *pending.tx_hash());
+
+        let deadline = Instant::now() + Duration::from_secs(5);
+        let summary = loop {
+            let summary = db.get_gas_summary().await.unwrap();
+            if !summary.is_empty() || Instant::now() > deadline {
+                break summary;
+            }
+            tokio::time::sleep(Duration::from_millis(20)).await;
+        };
+        assert_eq!(summary.len(), 1);
+        assert_eq!(summary[0].task_name, "03_send_token");
+        assert_eq!(summary[0].transactions, 1);
+        assert_eq!(summary[0].reverted, 0);
+        assert_eq!(summary[0].total_gas, 65_000);
+        assert_eq!(summary[0].avg_gas_price(), 30_000_000_000.0);
+        assert_eq!(tracker.cost_wei(), 65_000.0 * 30_000_000_000.0);
+    }
+
+    #[tokio::test]
+    async fn test_missing_receipt_gives_up() {
+        let harness = TaskHarness::new();
+        let client = harness.mock.client();
+        let receipt = wait_for_receipt(
+            client.provider.as_ref(),
+            B256::repeat_byte(1),
+            Duration::from_millis(30),
+            Duration::from_millis(10),
+        )
+        .await;
+        assert!(receipt.is_none());
+        assert!(harness.mock.count("eth_getTransactionReceipt") >= 2);
+    }
+
+    #[tokio::test]
+    async fn test_receipt_wait_ends_before_task_deadline() {
+        let harness = TaskHarness::new();
+        let ctx = harness
+            .context()
+            .with_deadline(crate::tasks::WRAP_UP + Duration::from_millis(100));
+        assert!(ctx.time_for(Duration::from_secs(60)) <= Duration::from_millis(100));
+        assert!(!ctx.is_cancelled());
+
+        let started = Instant::now();
+        let receipt = ctx
+            .wait_for_receipt(
+                B256::repeat_byte(1),
+                Duration::from_secs(60),
+                Duration::from_millis(10),
+            )
+            .await;
+        assert!(receipt.is_none());
+        tokio::time::timeout(Duration::from_secs(1), ctx.cancelled())
+            .await
+            .unwrap();
+        assert!(ctx.is_cancelled());
+        assert!(started.elapsed() < crate::tasks::WRAP_UP);
+        assert!(!ctx.retry_after(Duration::from_millis(10)).await);
+    }
+
+    #[tokio::test]
+    async fn test_lookups_beyond_max_pending_skipped() {
+        let harness = TaskHarness::new().with_db().await.unwrap();
+        let settings = GasSettings {
+            max_pending: 1,
+            receipt_timeout_secs: 1,
+            ..Default::default()
+        };
+        let tracker = Arc::new(GasTracker::new(settings, harness.db.clone().unwrap()));
+        let client = harness.mock.client();
+        tracker.spawn("03_send_token", &client, B256::repeat_byte(1));
+        tracker.spawn("03_send_token", &client, B256::repeat_byte(2));
+        assert_eq!(tracker.skipped(), 1);
+    }
+}
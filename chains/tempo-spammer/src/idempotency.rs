@@ -0,0 +1,153 @@
+//! Idempotency - Keeping a retried task from repeating its side effect
+//!
+//! A run that times out after sending may still land its transaction, and
+//! the next run of the task claims the faucet or registers a name again.
+//! Tasks with such side effects derive a deterministic operation key, the
+//! same for every run meant to perform that one effect, and check it
+//! through the [`TaskContext`](crate::tasks::TaskContext) before sending:
+//!
+//! ```rust,ignore
+//! let key = format!("02_claim_faucet:{:?}:{}", ctx.address(), idempotency::today());
+//! if let Some(done) = ctx.already_done(&key).await? {
+//!     return Ok(done);
+//! }
+//! let pending = client.provider.send_transaction(tx).await?;
+//! ctx.operation_sent(&key, *pending.tx_hash()).await;
+//! ```
+//!
+//! Keys are stored per chain in the `operations` table. A key is `sent`
+//! while its transaction is in flight and `done` once the transaction
+//! succeeded, either when the task saw the receipt and called
+//! `operation_done` or when a later `already_done` found it on chain. A
+//! `done` key, or a `sent` one whose transaction the node still holds,
+//! short-circuits with an [`already_done`] result: a success tagged
+//! `already_done` in `details`. A reverted or dropped transaction leaves the
+//! key open for the next run.
+//!
+//! Without a database nothing is recorded and every run sends.
+
+use alloy::providers::Provider;
+use alloy_primitives::{Address, TxHash};
+use anyhow::{Context, Result};
+use core_logic::database::{DatabaseManager, OperationRecord};
+use core_logic::traits::TaskResult;
+
+/// Status of a key whose transaction is in flight
+pub const SENT: &str = "sent";
+
+/// Status of a key whose transaction succeeded
+pub const DONE: &str = "done";
+
+/// What an operation key says about the side effect
+#[derive(Debug, Clone, Copy, PartialEq, Eq)]
+pub enum Prior {
+    /// Never sent, or sent and reverted or dropped: go ahead
+    Open,
+    /// Sent in this transaction, which the node has not included yet
+    Pending(TxHash),
+    /// Succeeded in this transaction
+    Done(TxHash),
+}
+
+/// Looks `key` up, checking a `sent` transaction on chain
+///
+/// A `sent` key whose receipt succeeded is marked `done` on the way.
+///
+/// # Errors
+///
+/// Fails when the database or the node cannot be read, so the task does not
+/// send blindly.
+pub async fn lookup<P: Provider + ?Sized>(
+    db: &DatabaseManager,
+    provider: &P,
+    chain_id: u64,
+    key: &str,
+) -> Result<Prior> {
+    let Some(record) = db.get_operation(chain_id, key).await? else {
+        return Ok(Prior::Open);
+    };
+    let Ok(tx_hash) = record.tx_hash.parse::<TxHash>() else {
+        return Ok(Prior::Open);
+    };
+    if record.status == DONE {
+        return Ok(Prior::Done(tx_hash));
+    }
+
+    let receipt = provider
+        .get_transaction_receipt(tx_hash)
+        .await
+        .with_context(|| format!("Failed to check {} of operation {}", tx_hash, key))?;
+    match receipt {
+        Some(receipt) if receipt.status() => {
+            db.record_operation(&OperationRecord {
+                status: DONE.to_string(),
+                updated_at: chrono::Utc::now().timestamp(),
+                ..record
+            })
+            .await?;
+            Ok(Prior::Done(tx_hash))
+        }
+        Some(_) => Ok(Prior::Open),
+        // Raw, as Tempo transactions do not decode as Ethereum ones
+        None => {
+            let tx: serde_json::Value = provider
+                .client()
+                .request("eth_getTransactionByHash", (tx_hash,))
+                .await
+                .with_context(|| format!("Failed to check {} of operation {}", tx_hash, key))?;
+            Ok(if tx.is_null() {
+                Prior::Open
+            } else {
+                Prior::Pending(tx_hash)
+            })
+        }
+    }
+}
+
+/// Stores `key` as sent in `tx_hash` (`SENT`) or succeeded (`DONE`)
+pub async fn record(
+    db: &DatabaseManager,
+    chain_id: u64,
+    wallet: Address,
+    key: &str,
+    tx_hash: TxHash,
+    status: &str,
+) -> Result<()> {
+    db.record_operation(&OperationRecord {
+        chain_id: chain_id as i64,
+        op_key: key.to_string(),
+        wallet_address: wallet.to_string(),
+        tx_hash: format!("{:?}", tx_hash),
+        status: status.to_string(),
+        updated_at: chrono::Utc::now().timestamp(),
+    })
+    .await
+}
+
+/// The result a task returns instead of repeating operation `key`
+pub fn already_done(key: &str, prior: Prior) -> Option<TaskResult> {
+    let (tx_hash, state) = match prior {
+        Prior::Open => return None,
+        Prior::Pending(tx_hash) => (tx_hash, "pending"),
+        Prior::Done(tx_hash) => (tx_hash, "done"),
+    };
+    Some(
+        TaskResult {
+            success: true,
+            message: format!("Already {}: {} in {:?}", state, key, tx_hash),
+            tx_hash: Some(format!("{:?}", tx_hash)),
+            ..Default::default()
+        }
+        .with_detail("already_done", key),
+    )
+}
+
+/// Whether `result` was short-circuited by [`already_done`]
+pub fn is_already_done(result: &TaskResult) -> bool {
+    result.details.get("already_done").is_some()
+}
+
+/// Today's UTC date, for keys of effects meant once per day
+pub fn today() -> String {
+    chrono::Utc::now().format("%Y-%m-%d").to_string()
+}
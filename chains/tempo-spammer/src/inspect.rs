@@ -0,0 +1,678 @@
+//! Inspect - Decoded Tempo blocks and transactions
+//!
+//! Explorers show native Tempo transactions as opaque type-0x76 blobs, which
+//! makes it hard to tell why one misbehaved. `inspect` reads a block or a
+//! transaction over JSON-RPC and decodes it with the `tempo-primitives`
+//! protocol types:
+//!
+//! ```bash
+//! tempo-spammer inspect block latest
+//! tempo-spammer inspect block 1234567
+//! tempo-spammer inspect tx 0x<hash>
+//! ```
+//!
+//! A block shows the [`TempoHeader`] extras (general and shared gas limits and
+//! `timestamp_millis_part`), each transaction with its type, sender, nonce
+//! lane, fee token and call count, and the subblocks named in the block's
+//! metadata system transaction together with the transactions their
+//! validators proposed. A transaction shows every 0x76 field: the calls, fee
+//! token, 2D nonce, validity window, fee payer, signature type and key
+//! authorization, followed by its receipt.
+//!
+//! Transactions are fetched as raw EIP-2718 bytes (`eth_getRawTransactionByHash`)
+//! and decoded locally, so nothing depends on how the node renders them as
+//! JSON.
+
+use crate::budget::token_label;
+use alloy::consensus::Transaction;
+use alloy::consensus::transaction::SignerRecoverable;
+use alloy::eips::eip2718::Decodable2718;
+use alloy::eips::{BlockId, BlockNumberOrTag};
+use alloy::providers::Provider;
+use alloy::rlp::Decodable;
+use alloy_primitives::{Address, B256, Bytes, TxKind, U256};
+use anyhow::{Context, Result};
+use chrono::DateTime;
+use futures::{StreamExt, TryStreamExt};
+use serde::Deserialize;
+use std::fmt;
+use std::str::FromStr;
+use tempo_primitives::transaction::TEMPO_EXPIRING_NONCE_KEY;
+use tempo_primitives::{SubBlockMetadata, TempoHeader, TempoTxEnvelope};
+
+/// Raw transactions fetched at once for a block
+const FETCH_CONCURRENCY: usize = 8;
+
+/// A block with its transactions decoded
+#[derive(Debug, Clone)]
+pub struct BlockInspection {
+    pub hash: B256,
+    pub header: TempoHeader,
+    pub transactions: Vec<InspectedTx>,
+    pub subblocks: Vec<SubBlockInfo>,
+}
+
+/// A decoded transaction and its sender
+#[derive(Debug, Clone)]
+pub struct InspectedTx {
+    pub hash: B256,
+    /// `None` for system transactions and signatures that do not recover
+    pub from: Option<Address>,
+    pub tx: TempoTxEnvelope,
+}
+
+/// One subblock of a block
+#[derive(Debug, Clone, PartialEq, Eq)]
+pub struct SubBlockInfo {
+    /// Validator from the block's metadata, `None` for transactions whose
+    /// proposer the metadata does not list
+    pub validator: Option<B256>,
+    pub fee_recipient: Option<Address>,
+    /// Transactions proposed for the subblock, in block order
+    pub transactions: Vec<B256>,
+}
+
+/// A transaction with its position in the chain and its receipt
+#[derive(Debug, Clone)]
+pub struct TxInspection {
+    pub tx: InspectedTx,
+    /// `None` while pending
+    pub block_number: Option<u64>,
+    pub index: Option<u64>,
+    pub receipt: Option<ReceiptInfo>,
+}
+
+/// The receipt fields that matter for Tempo transactions
+#[derive(Debug, Clone, PartialEq, Eq)]
+pub struct ReceiptInfo {
+    pub success: bool,
+    pub gas_used: u64,
+    pub effective_gas_price: u128,
+    /// Token the fee was paid in
+    pub fee_token: Option<Address>,
+    /// Account the fee was charged to, differs from the sender when sponsored
+    pub fee_payer: Option<Address>,
+    pub logs: usize,
+}
+
+impl ReceiptInfo {
+    /// Reads a JSON-RPC receipt, `None` without status and gas used
+    pub fn from_json(receipt: &serde_json::Value) -> Option<Self> {
+        let address = |key: &str| {
+            receipt
+                .get(key)
+                .and_then(|value| value.as_str())
+                .and_then(|value| Address::from_str(value).ok())
+        };
+        Some(Self {
+            success: quantity(receipt.get("status")?)? == 1,
+            gas_used: quantity(receipt.get("gasUsed")?)? as u64,
+            effective_gas_price: receipt
+                .get("effectiveGasPrice")
+                .and_then(quantity)
+                .unwrap_or_default(),
+            fee_token: address("feeToken"),
+            fee_payer: address("feePayer"),
+            logs: receipt
+                .get("logs")
+                .and_then(|logs| logs.as_array())
+                .map_or(0, Vec::len),
+        })
+    }
+}
+
+/// The fields of an `eth_getBlockBy*` answer without transaction bodies
+#[derive(Debug, Deserialize)]
+struct RpcBlock {
+    hash: B256,
+    #[serde(flatten)]
+    header: TempoHeader,
+    #[serde(default)]
+    transactions: Vec<B256>,
+}
+
+/// The position fields of an `eth_getTransactionByHash` answer
+#[derive(Debug, Deserialize)]
+#[serde(rename_all = "camelCase")]
+struct RpcTxPosition {
+    #[serde(default)]
+    block_number: Option<String>,
+    #[serde(default)]
+    transaction_index: Option<String>,
+}
+
+/// Parses a block as a tag (`latest`, `finalized`, ...), decimal or hex number, or hash
+pub fn parse_block_id(value: &str) -> Result<BlockId> {
+    let value = value.trim();
+    if value.len() == 66 && value.starts_with("0x") {
+        return B256::from_str(value)
+            .map(BlockId::from)
+            .with_context(|| format!("Invalid block hash {}", value));
+    }
+    if let Ok(number) = value.parse::<u64>() {
+        return Ok(BlockId::number(number));
+    }
+    BlockNumberOrTag::from_str(value)
+        .map(BlockId::Number)
+        .map_err(|_| anyhow::anyhow!("Invalid block '{}', use a number, hash or tag", value))
+}
+
+/// Fetches a block and decodes its header, transactions and subblocks
+pub async fn inspect_block<P: Provider>(provider: &P, block: BlockId) -> Result<BlockInspection> {
+    let client = provider.client();
+    let answer: Option<RpcBlock> = match block {
+        BlockId::Hash(hash) => {
+            client
+                .request("eth_getBlockByHash", (hash.block_hash, false))
+                .await
+        }
+        BlockId::Number(number) => {
+            client
+                .request("eth_getBlockByNumber", (number, false))
+                .await
+        }
+    }
+    .with_context(|| format!("Failed to fetch block {}", block))?;
+    let answer = answer.with_context(|| format!("Block {} not found", block))?;
+
+    let transactions = futures::stream::iter(answer.transactions)
+        .map(|hash| fetch_tx(provider, hash))
+        .buffered(FETCH_CONCURRENCY)
+        .try_collect::<Vec<_>>()
+        .await?;
+    let subblocks = subblocks(answer.header.inner.number, &transactions);
+
+    Ok(BlockInspection {
+        hash: answer.hash,
+        header: answer.header,
+        transactions,
+        subblocks,
+    })
+}
+
+/// Fetches and decodes a transaction with its position and receipt
+pub async fn inspect_tx<P: Provider>(provider: &P, hash: B256) -> Result<TxInspection> {
+    let tx = fetch_tx(provider, hash).await?;
+    let client = provider.client();
+    let position: Option<RpcTxPosition> = client
+        .request("eth_getTransactionByHash", (hash,))
+        .await
+        .with_context(|| format!("Failed to fetch transaction {}", hash))?;
+    let receipt: Option<serde_json::Value> = client
+        .request("eth_getTransactionReceipt", (hash,))
+        .await
+        .with_context(|| format!("Failed to fetch the receipt of {}", hash))?;
+
+    let (block_number, index) = position.map_or((None, None), |p| {
+        (
+            p.block_number.as_deref().and_then(parse_quantity),
+            p.transaction_index.as_deref().and_then(parse_quantity),
+        )
+    });
+    Ok(TxInspection {
+        tx,
+        block_number,
+        index,
+        receipt: receipt.as_ref().and_then(ReceiptInfo::from_json),
+    })
+}
+
+async fn fetch_tx<P: Provider>(provider: &P, hash: B256) -> Result<InspectedTx> {
+    let raw: Option<Bytes> = provider
+        .client()
+        .request("eth_getRawTransactionByHash", (hash,))
+        .await
+        .with_context(|| format!("Failed to fetch transaction {}", hash))?;
+    let raw = raw.with_context(|| format!("Transaction {} not found", hash))?;
+    let tx = TempoTxEnvelope::decode_2718(&mut raw.as_ref())
+        .with_context(|| format!("Failed to decode transaction {}", hash))?;
+    let from = (!tx.is_system_tx())
+        .then(|| tx.recover_signer().ok())
+        .flatten();
+    Ok(InspectedTx { hash, from, tx })
+}
+
+/// Subblocks of a block: those its metadata system transaction lists, each
+/// with the transactions their validator proposed
+pub fn subblocks(block_number: u64, transactions: &[InspectedTx]) -> Vec<SubBlockInfo> {
+    let metadata = transactions
+        .iter()
+        .rev()
+        .filter(|t| t.tx.is_system_tx() && t.tx.to() == Some(Address::ZERO))
+        .find_map(|t| decode_metadata(block_number, t.tx.input()))
+        .unwrap_or_default();
+    let mut subblocks: Vec<SubBlockInfo> = metadata
+        .iter()
+        .map(|metadata| SubBlockInfo {
+            validator: Some(metadata.validator),
+            fee_recipient: Some(metadata.fee_recipient),
+            transactions: Vec::new(),
+        })
+        .collect();
+
+    for t in transactions {
+        let Some(proposer) = t.tx.subblock_proposer() else {
+            continue;
+        };
+        let listed = subblocks.iter().position(|subblock| {
+            subblock
+                .validator
+                .is_some_and(|validator| proposer.matches(validator))
+        });
+        let unlisted = || subblocks.iter().position(|s| s.validator.is_none());
+        match listed.or_else(unlisted) {
+            Some(i) => subblocks[i].transactions.push(t.hash),
+            None => subblocks.push(SubBlockInfo {
+                validator: None,
+                fee_recipient: None,
+                transactions: vec![t.hash],
+            }),
+        }
+    }
+    subblocks
+}
+
+/// Decodes the metadata system transaction's input: the RLP list of
+/// subblock metadata followed by the block number as a 32-byte word
+pub fn decode_metadata(block_number: u64, input: &[u8]) -> Option<Vec<SubBlockMetadata>> {
+    let (mut metadata, number) = input.split_at_checked(input.len().checked_sub(32)?)?;
+    if U256::from_be_slice(number) != U256::from(block_number) {
+        return None;
+    }
+    let decoded = Vec::<SubBlockMetadata>::decode(&mut metadata).ok()?;
+    metadata.is_empty().then_some(decoded)
+}
+
+/// Reads a hex quantity
+fn quantity(value: &serde_json::Value) -> Option<u128> {
+    u128::from_str_radix(value.as_str()?.trim_start_matches("0x"), 16).ok()
+}
+
+fn parse_quantity(value: &str) -> Option<u64> {
+    u64::from_str_radix(value.trim_start_matches("0x"), 16).ok()
+}
+
+/// Transaction type as shown in listings
+fn type_label(tx: &TempoTxEnvelope) -> String {
+    if tx.is_system_tx() {
+        "system".to_string()
+    } else if tx.is_aa() {
+        "0x76 Tempo".to_string()
+    } else {
+        tx.tx_type().to_string()
+    }
+}
+
+/// Nonce with its 2D lane for Tempo transactions
+fn nonce_label(tx: &TempoTxEnvelope) -> String {
+    match tx.nonce_key() {
+        Some(key) if key == TEMPO_EXPIRING_NONCE_KEY => format!("{} (expiring)", tx.nonce()),
+        Some(key) if !key.is_zero() => match tx.subblock_proposer() {
+            Some(proposer) => format!("{:#x}/{} (subblock {})", key, tx.nonce(), proposer),
+            None => format!("{:#x}/{}", key, tx.nonce()),
+        },
+        _ => tx.nonce().to_string(),
+    }
+}
+
+fn call_label(to: TxKind, value: U256, input: &Bytes) -> String {
+    let target = match to {
+        TxKind::Call(to) => to.to_string(),
+        TxKind::Create => "create".to_string(),
+    };
+    let selector = input.get(..4).map_or_else(String::new, |selector| {
+        format!(" selector 0x{}", alloy_primitives::hex::encode(selector))
+    });
+    format!(
+        "{}{}{} input {} bytes",
+        target,
+        if value.is_zero() {
+            String::new()
+        } else {
+            format!(" value {}", value)
+        },
+        selector,
+        input.len()
+    )
+}
+
+fn gwei(wei: u128) -> f64 {
+    wei as f64 / 1e9
+}
+
+impl fmt::Display for BlockInspection {
+    fn fmt(&self, f: &mut fmt::Formatter<'_>) -> fmt::Result {
+        let header = &self.header;
+        let inner = &header.inner;
+        let millis = header.timestamp_millis();
+        writeln!(f, "Block {} {}", inner.number, self.hash)?;
+        writeln!(
+            f,
+            "  Timestamp     {} ({} + {}ms)",
+            DateTime::from_timestamp_millis(millis as i64)
+                .map(|at| at.format("%Y-%m-%d %H:%M:%S%.3f UTC").to_string())
+                .unwrap_or_default(),
+            inner.timestamp,
+            header.timestamp_millis_part
+        )?;
+        writeln!(
+            f,
+            "  Gas           {} used of {}, general limit {}, shared limit {}",
+            inner.gas_used, inner.gas_limit, header.general_gas_limit, header.shared_gas_limit
+        )?;
+        if let Some(base_fee) = inner.base_fee_per_gas {
+            writeln!(f, "  Base fee      {:.4} gwei", gwei(base_fee.into()))?;
+        }
+        writeln!(f, "  Beneficiary   {}", inner.beneficiary)?;
+        writeln!(f, "  Parent        {}", inner.parent_hash)?;
+
+        let tempo = self.transactions.iter().filter(|t| t.tx.is_aa()).count();
+        writeln!(
+            f,
+            "\nTransactions ({}, {} Tempo)",
+            self.transactions.len(),
+            tempo
+        )?;
+        for (i, t) in self.transactions.iter().enumerate() {
+            writeln!(
+                f,
+                "  {:>4} {} {:<10} from {} nonce {} fee token {} calls {}",
+                i,
+                t.hash,
+                type_label(&t.tx),
+                t.from
+                    .map_or_else(|| "-".to_string(), |from| from.to_string()),
+                nonce_label(&t.tx),
+                t.tx.fee_token()
+                    .map_or_else(|| "-".to_string(), token_label),
+                t.tx.calls().count()
+            )?;
+        }
+
+        writeln!(f, "\nSubblocks ({})", self.subblocks.len())?;
+        for subblock in &self.subblocks {
+            match (subblock.validator, subblock.fee_recipient) {
+                (Some(validator), Some(recipient)) => writeln!(
+                    f,
+                    "  validator {} fee recipient {}: {} transactions",
+                    validator,
+                    recipient,
+                    subblock.transactions.len()
+                )?,
+                _ => writeln!(
+                    f,
+                    "  proposer not in the metadata: {} transactions",
+                    subblock.transactions.len()
+                )?,
+            }
+            for hash in &subblock.transactions {
+                writeln!(f, "    {}", hash)?;
+            }
+        }
+        Ok(())
+    }
+}
+
+impl fmt::Display for TxInspection {
+    fn fmt(&self, f: &mut fmt::Formatter<'_>) -> fmt::Result {
+        let tx = &self.tx.tx;
+        writeln!(f, "Transaction {}", self.tx.hash)?;
+        writeln!(f, "  Type          {}", type_label(tx))?;
+        if let Some(from) = self.tx.from {
+            writeln!(f, "  From          {}", from)?;
+        }
+        match (self.block_number, self.index) {
+            (Some(block), Some(index)) => {
+                writeln!(f, "  Block         {} (index {})", block, index)?
+            }
+            _ => writeln!(f, "  Block         pending")?,
+        }
+        writeln!(f, "  Nonce         {}", nonce_label(tx))?;
+        writeln!(
+            f,
+            "  Gas           limit {}, max fee {:.4} gwei, priority fee {:.4} gwei",
+            tx.gas_limit(),
+            gwei(tx.max_fee_per_gas()),
+            gwei(tx.max_priority_fee_per_gas().unwrap_or_default())
+        )?;
+
+        if let Some(aa) = tx.as_aa() {
+            let inner = aa.tx();
+            writeln!(
+                f,
+                "  Fee token     {}",
+                inner
+                    .fee_token
+                    .map_or_else(|| "none (node default)".to_string(), token_label)
+            )?;
+            writeln!(
+                f,
+                "  Fee payer     {}",
+                if inner.fee_payer_signature.is_some() {
+                    "sponsored (fee payer signature)"
+                } else {
+                    "sender"
+                }
+            )?;
+            let signature = aa.signature();
+            writeln!(
+                f,
+                "  Signature     {:?}{}",
+                signature.signature_type(),
+                if signature.is_keychain() {
+                    " via access key"
+                } else {
+                    ""
+                }
+            )?;
+            if inner.valid_after.is_some() || inner.valid_before.is_some() {
+                let bound = |at: Option<u64>| {
+                    at.and_then(|at| DateTime::from_timestamp(at as i64, 0))
+                        .map_or_else(|| "-".to_string(), |at| at.to_rfc3339())
+                };
+                writeln!(
+                    f,
+                    "  Valid         after {} before {}",
+                    bound(inner.valid_after),
+                    bound(inner.valid_before)
+                )?;
+            }
+            if inner.key_authorization.is_some() {
+                writeln!(f, "  Key auth      provisions an access key")?;
+            }
+            if !inner.tempo_authorization_list.is_empty() {
+                writeln!(
+                    f,
+                    "  Authorizations {}",
+                    inner.tempo_authorization_list.len()
+                )?;
+            }
+            writeln!(f, "\nCalls ({})", inner.calls.len())?;
+            for (i, call) in inner.calls.iter().enumerate() {
+                writeln!(
+                    f,
+                    "  {:>3} {}",
+                    i,
+                    call_label(call.to, call.value, &call.input)
+                )?;
+            }
+        } else {
+            writeln!(
+                f,
+                "  Call          {}",
+                call_label(tx.kind(), tx.value(), tx.input())
+            )?;
+        }
+
+        match &self.receipt {
+            Some(receipt) => {
+                writeln!(
+                    f,
+                    "\nReceipt       {}, {} gas at {:.4} gwei, {} logs",
+                    if receipt.success {
+                        "success"
+                    } else {
+                        "reverted"
+                    },
+                    receipt.gas_used,
+                    gwei(receipt.effective_gas_price),
+                    receipt.logs
+                )?;
+                if let Some(token) = receipt.fee_token {
+                    writeln!(f, "  Fee paid in   {}", token_label(token))?;
+                }
+                if let Some(payer) = receipt.fee_payer {
+                    writeln!(f, "  Fee charged   {}", payer)?;
+                }
+            }
+            None => writeln!(f, "\nReceipt       none yet")?,
+        }
+        Ok(())
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use alloy::consensus::{Signed, TxLegacy};
+    use alloy::rlp::Encodable;
+    use alloy_primitives::Signature;
+    use tempo_primitives::transaction::envelope::TEMPO_SYSTEM_TX_SIGNATURE;
+    use tempo_primitives::transaction::{PrimitiveSignature, TempoSignature};
+    use tempo_primitives::{AASigned, SubBlockVersion, TempoTransaction};
+
+    fn tempo_tx(nonce_key: U256, fee_token: Option<Address>) -> InspectedTx {
+        let tx = TempoTransaction {
+            chain_id: 42431,
+            fee_token,
+            gas_limit: 100_000,
+            nonce_key,
+            calls: vec![tempo_primitives::transaction::Call {
+                to: TxKind::Call(Address::repeat_byte(0x42)),
+                value: U256::ZERO,
+                input: Bytes::from(vec![0xa9, 0x05, 0x9c, 0xbb, 0, 0]),
+            }],
+            ..Default::default()
+        };
+        let signature =
+            TempoSignature::Primitive(PrimitiveSignature::Secp256k1(Signature::test_signature()));
+        let tx = AASigned::new_unhashed(tx, signature);
+        InspectedTx {
+            hash: *tx.hash(),
+            from: Some(Address::repeat_byte(1)),
+            tx: TempoTxEnvelope::AA(tx),
+        }
+    }
+
+    fn metadata_tx(block_number: u64, metadata: &[SubBlockMetadata]) -> InspectedTx {
+        let mut input = Vec::new();
+        metadata.to_vec().encode(&mut input);
+        input.extend_from_slice(&U256::from(block_number).to_be_bytes::<32>());
+        let tx = TxLegacy {
+            chain_id: Some(42431),
+            to: TxKind::Call(Address::ZERO),
+            input: input.into(),
+            ..Default::default()
+        };
+        let tx = Signed::new_unhashed(tx, TEMPO_SYSTEM_TX_SIGNATURE);
+        InspectedTx {
+            hash: *tx.hash(),
+            from: None,
+            tx: TempoTxEnvelope::Legacy(tx),
+        }
+    }
+
+    #[test]
+    fn test_parse_block_id() {
+        assert_eq!(parse_block_id("1234").unwrap(), BlockId::number(1234));
+        assert_eq!(parse_block_id("0x4d2").unwrap(), BlockId::number(1234));
+        assert_eq!(parse_block_id("latest").unwrap(), BlockId::latest());
+        let hash = B256::repeat_byte(0xab);
+        assert_eq!(
+            parse_block_id(&hash.to_string()).unwrap(),
+            BlockId::from(hash)
+        );
+        assert!(parse_block_id("yesterday").is_err());
+    }
+
+    #[test]
+    fn test_subblocks_group_proposed_transactions() {
+        let validator = B256::repeat_byte(0x11);
+        let recipient = Address::repeat_byte(0x22);
+        // Subblock nonce keys: prefix byte, then the validator key's first 15 bytes
+        let mut key = [0u8; 32];
+        key[0] = tempo_primitives::subblock::TEMPO_SUBBLOCK_NONCE_KEY_PREFIX;
+        key[1..16].copy_from_slice(&validator[..15]);
+        let mut stranger = key;
+        stranger[1] = 0x99;
+
+        let proposed = tempo_tx(U256::from_be_bytes(key), None);
+        let unlisted = tempo_tx(U256::from_be_bytes(stranger), None);
+        let ordinary = tempo_tx(U256::from(7), None);
+        let transactions = vec![
+            proposed.clone(),
+            ordinary,
+            unlisted.clone(),
+            metadata_tx(
+                9,
+                &[SubBlockMetadata {
+                    version: SubBlockVersion::V1,
+                    validator,
+                    fee_recipient: recipient,
+                    signature: Bytes::from(vec![1; 65]),
+                }],
+            ),
+        ];
+
+        let subblocks = subblocks(9, &transactions);
+        assert_eq!(
+            subblocks,
+            vec![
+                SubBlockInfo {
+                    validator: Some(validator),
+                    fee_recipient: Some(recipient),
+                    transactions: vec![proposed.hash],
+                },
+                SubBlockInfo {
+                    validator: None,
+                    fee_recipient: None,
+                    transactions: vec![unlisted.hash],
+                },
+            ]
+        );
+
+        // Metadata of another block is not this block's
+        assert!(decode_metadata(8, transactions[3].tx.input()).is_none());
+    }
+
+    #[test]
+    fn test_tx_display_shows_tempo_fields() {
+        let path_usd = crate::budget::resolve_token("PathUSD").unwrap();
+        let inspection = TxInspection {
+            tx: tempo_tx(TEMPO_EXPIRING_NONCE_KEY, Some(path_usd)),
+            block_number: Some(12),
+            index: Some(3),
+            receipt: ReceiptInfo::from_json(&serde_json::json!({
+                "status": "0x0",
+                "gasUsed": "0x5208",
+                "effectiveGasPrice": "0x4a817c800",
+                "feeToken": path_usd.to_string(),
+                "logs": [],
+            })),
+        };
+        let text = inspection.to_string();
+        assert!(text.contains("0x76 Tempo"), "{}", text);
+        assert!(text.contains("Fee token     PathUSD"), "{}", text);
+        assert!(text.contains("(expiring)"), "{}", text);
+        assert!(
+            text.contains("selector 0xa9059cbb input 6 bytes"),
+            "{}",
+            text
+        );
+        assert!(
+            text.contains("reverted, 21000 gas at 20.0000 gwei"),
+            "{}",
+            text
+        );
+    }
+}
@@ -0,0 +1,283 @@
+//! Latency - Send, inclusion and confirmation times per subblock
+//!
+//! Tempo blocks carry the proposer's own transactions followed by the
+//! subblocks other validators proposed, so how fast a transaction lands
+//! depends on the lane that picks it up. For every landed transaction this
+//! records three times in `latency_samples`:
+//!
+//! - `sent_at_ms`: when the transaction was handed to the RPC
+//! - `included_at_ms`: the including block's millisecond timestamp. A
+//!   subblock has no timestamp of its own; it is included when the proposer
+//!   seals the block
+//! - `confirmed_at_ms`: when the receipt was seen, so this stage includes
+//!   the receipt polling interval
+//!
+//! together with the subblock that carried it, read from the block's
+//! metadata system transaction the way `inspect block` decodes it. Burst
+//! rounds record their transactions under `burst:<round>`, and the report
+//! breaks the stages down per lane:
+//!
+//! ```bash
+//! tempo-spammer db latency --source burst:spike-1
+//! tempo-spammer db latency --since 6h
+//! ```
+//!
+//! Times come from the local clock and the validators' clocks, so hosts
+//! should be NTP-synchronized; a block stamped before the send counts as
+//! included at once.
+
+use crate::inspect::{self, SubBlockInfo};
+use crate::nonce_bench::LatencySummary;
+use alloy::eips::BlockId;
+use alloy::providers::Provider;
+use alloy_primitives::{Address, B256};
+use core_logic::database::LatencySample;
+use std::collections::{BTreeMap, HashMap};
+use std::fmt::Write;
+use std::time::Duration;
+use tracing::warn;
+
+/// Lane label of the proposer's own transactions in reports
+const PROPOSER_LANE: &str = "proposer";
+
+/// Subblock label of transactions in a subblock the metadata does not name
+const UNLISTED_SUBBLOCK: &str = "unlisted";
+
+/// A transaction whose receipt was seen
+#[derive(Debug, Clone, Copy, PartialEq, Eq)]
+pub struct Landed {
+    pub hash: B256,
+    pub wallet: Address,
+    pub block: u64,
+    /// Unix milliseconds
+    pub sent_at_ms: i64,
+    pub confirmed_at_ms: i64,
+}
+
+/// Inspects each block the `landed` transactions are in and turns them into samples
+///
+/// A block that cannot be inspected is left out with a warning.
+pub async fn collect<P: Provider>(
+    provider: &P,
+    source: &str,
+    landed: &[Landed],
+) -> Vec<LatencySample> {
+    let mut by_block: BTreeMap<u64, Vec<Landed>> = BTreeMap::new();
+    for tx in landed {
+        by_block.entry(tx.block).or_default().push(*tx);
+    }
+
+    let mut samples = Vec::with_capacity(landed.len());
+    for (block, txs) in by_block {
+        match inspect::inspect_block(provider, BlockId::number(block)).await {
+            Ok(inspection) => samples.extend(block_samples(
+                source,
+                inspection.header.timestamp_millis() as i64,
+                &inspection.subblocks,
+                &txs,
+            )),
+            Err(e) => warn!("Skipping latency samples of block {}: {:#}", block, e),
+        }
+    }
+    samples
+}
+
+/// Samples of the `landed` transactions of one block stamped `block_time_ms`
+pub fn block_samples(
+    source: &str,
+    block_time_ms: i64,
+    subblocks: &[SubBlockInfo],
+    landed: &[Landed],
+) -> Vec<LatencySample> {
+    let lanes: HashMap<B256, String> = subblocks
+        .iter()
+        .flat_map(|subblock| {
+            let label = subblock
+                .validator
+                .map_or_else(|| UNLISTED_SUBBLOCK.to_string(), |v| v.to_string());
+            subblock
+                .transactions
+                .iter()
+                .map(move |hash| (*hash, label.clone()))
+        })
+        .collect();
+
+    landed
+        .iter()
+        .map(|tx| LatencySample {
+            tx_hash: tx.hash.to_string(),
+            source: source.to_string(),
+            wallet_address: tx.wallet.to_string(),
+            block_number: tx.block as i64,
+            subblock: lanes.get(&tx.hash).cloned(),
+            sent_at_ms: tx.sent_at_ms,
+            included_at_ms: block_time_ms,
+            confirmed_at_ms: tx.confirmed_at_ms,
+        })
+        .collect()
+}
+
+/// Stage percentiles of the transactions one lane carried
+#[derive(Debug, Clone, PartialEq)]
+pub struct LaneReport {
+    /// Subblock validator, `unlisted`, `proposer`, or `all` for every lane
+    pub lane: String,
+    /// Send to the including block's timestamp
+    pub inclusion: LatencySummary,
+    /// Block timestamp to the receipt being seen
+    pub confirmation: LatencySummary,
+    /// Send to the receipt being seen
+    pub total: LatencySummary,
+}
+
+/// Per-lane stage percentiles, all lanes first, then by lane
+pub fn report(samples: &[LatencySample]) -> Vec<LaneReport> {
+    let mut lanes: BTreeMap<&str, Vec<&LatencySample>> = BTreeMap::new();
+    for sample in samples {
+        lanes
+            .entry(sample.subblock.as_deref().unwrap_or(PROPOSER_LANE))
+            .or_default()
+            .push(sample);
+    }
+
+    let all = samples.iter().collect();
+    std::iter::once(("all", all))
+        .chain(lanes)
+        .map(|(lane, samples)| lane_report(lane, &samples))
+        .collect()
+}
+
+fn lane_report(lane: &str, samples: &[&LatencySample]) -> LaneReport {
+    let stage = |from: fn(&LatencySample) -> i64, to: fn(&LatencySample) -> i64| {
+        LatencySummary::from_samples(
+            samples
+                .iter()
+                .map(|s| Duration::from_millis((to(s) - from(s)).max(0) as u64))
+                .collect(),
+        )
+    };
+    LaneReport {
+        lane: lane.to_string(),
+        inclusion: stage(|s| s.sent_at_ms, |s| s.included_at_ms),
+        confirmation: stage(|s| s.included_at_ms, |s| s.confirmed_at_ms),
+        total: stage(|s| s.sent_at_ms, |s| s.confirmed_at_ms),
+    }
+}
+
+/// Lane reports as a table, one row per lane and stage
+pub fn render(reports: &[LaneReport]) -> String {
+    let mut out = format!(
+        "{:<66} {:<12} {:>7} {:>9} {:>9} {:>9} {:>9}\n",
+        "Lane", "Stage", "Txs", "p50 ms", "p95 ms", "p99 ms", "Max ms"
+    );
+    for report in reports {
+        for (stage, summary) in [
+            ("inclusion", &report.inclusion),
+            ("confirmation", &report.confirmation),
+            ("total", &report.total),
+        ] {
+            let _ = writeln!(
+                out,
+                "{:<66} {:<12} {:>7} {:>9.0} {:>9.0} {:>9.0} {:>9.0}",
+                report.lane,
+                stage,
+                summary.count,
+                summary.p50_ms,
+                summary.p95_ms,
+                summary.p99_ms,
+                summary.max_ms
+            );
+        }
+    }
+    out
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    fn landed(n: u8, sent_at_ms: i64, confirmed_at_ms: i64) -> Landed {
+        Landed {
+            hash: B256::repeat_byte(n),
+            wallet: Address::repeat_byte(n),
+            block: 101,
+            sent_at_ms,
+            confirmed_at_ms,
+        }
+    }
+
+    #[test]
+    fn test_block_samples_name_the_subblock() {
+        let validator = B256::repeat_byte(0xaa);
+        let subblocks = [
+            SubBlockInfo {
+                validator: Some(validator),
+                fee_recipient: None,
+                transactions: vec![B256::repeat_byte(2)],
+            },
+            SubBlockInfo {
+                validator: None,
+                fee_recipient: None,
+                transactions: vec![B256::repeat_byte(3)],
+            },
+        ];
+        let txs = [
+            landed(1, 1_000, 2_000),
+            landed(2, 1_100, 2_100),
+            landed(3, 1_200, 2_200),
+        ];
+
+        let samples = block_samples("burst:spike-1", 1_500, &subblocks, &txs);
+        let lanes: Vec<Option<&str>> = samples.iter().map(|s| s.subblock.as_deref()).collect();
+        assert_eq!(
+            lanes,
+            vec![None, Some(validator.to_string().as_str()), Some("unlisted")]
+        );
+        assert!(samples.iter().all(|s| s.included_at_ms == 1_500));
+        assert_eq!(samples[0].tx_hash, B256::repeat_byte(1).to_string());
+        assert_eq!(samples[0].block_number, 101);
+    }
+
+    #[test]
+    fn test_report_per_lane() {
+        let sample =
+            |sent: i64, included: i64, confirmed: i64, subblock: Option<&str>| LatencySample {
+                tx_hash: String::new(),
+                source: "burst:spike-1".to_string(),
+                wallet_address: String::new(),
+                block_number: 101,
+                subblock: subblock.map(str::to_string),
+                sent_at_ms: sent,
+                included_at_ms: included,
+                confirmed_at_ms: confirmed,
+            };
+        let samples = [
+            sample(1_000, 1_400, 2_000, None),
+            sample(1_000, 1_800, 2_000, Some("0xaa")),
+            // Block stamped before the send: included at once
+            sample(1_000, 900, 1_500, None),
+        ];
+
+        let reports = report(&samples);
+        let lanes: Vec<&str> = reports.iter().map(|r| r.lane.as_str()).collect();
+        assert_eq!(lanes, vec!["all", "0xaa", "proposer"]);
+        assert_eq!(reports[0].total.count, 3);
+        assert_eq!(reports[1].inclusion.p50_ms, 800.0);
+        assert_eq!(reports[1].confirmation.max_ms, 200.0);
+        assert_eq!(reports[2].inclusion.count, 2);
+        assert_eq!(reports[2].inclusion.max_ms, 400.0);
+        assert_eq!(reports[2].inclusion.p50_ms, 400.0);
+        assert_eq!(reports[2].total.max_ms, 1_000.0);
+
+        let table = render(&reports);
+        assert_eq!(table.lines().count(), 1 + 3 * 3);
+        assert!(table.contains("proposer"));
+    }
+
+    #[test]
+    fn test_report_without_samples() {
+        let reports = report(&[]);
+        assert_eq!(reports.len(), 1);
+        assert_eq!(reports[0].total, LatencySummary::default());
+    }
+}
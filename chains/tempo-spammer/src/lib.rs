@@ -92,6 +92,27 @@
 //! # }
 //! ```
 //!
+//! ## Embedding the Spammer
+//!
+//! [`Spammer::builder`] runs the full task loop from another program, without
+//! the binary's prompts. See the [`spammer`] module and the `examples/`
+//! directory.
+//!
+//! ```rust,no_run
+//! use tempo_spammer::{Spammer, TempoSpammerConfig};
+//!
+//! # async fn example() -> anyhow::Result<()> {
+//! let config = TempoSpammerConfig::from_path("config/config.toml")?;
+//! let spammer = Spammer::builder(config)
+//!     .private_keys(["0x..."])
+//!     .workers(4)
+//!     .build()
+//!     .await?;
+//! spammer.run_until(async { tokio::signal::ctrl_c().await.unwrap() }).await;
+//! # Ok(())
+//! # }
+//! ```
+//!
 //! # System Tokens
 //!
 //! The Tempo blockchain provides several system tokens (TIP-20 standard):
@@ -107,6 +128,9 @@
 //! - **Fee AMM DEX**: `0xdec0000000000000000000000000000000000000`
 //! - **Faucet**: `0x4200000000000000000000000000000000000019`
 //!
+//! These are the public testnets' addresses; a config profile's `contracts`
+//! table moves them for another network (see [`network`]).
+//!
 //! # Safety
 //!
 //! - Private keys are never logged or exposed
@@ -122,15 +146,69 @@
 
 #![allow(unused)]
 
+pub mod access_list;
+pub mod activity;
+pub(crate) mod asm;
+pub mod audit;
+pub mod bootstrap;
 pub mod bot;
+pub mod budget;
+pub mod bundle;
+pub mod bundler;
+pub mod burst;
+pub mod capabilities;
+pub mod chain_head;
+pub mod chaos;
 pub mod client;
 pub mod client_pool;
 pub mod config;
+pub mod consistency;
+pub mod contracts;
+pub mod control;
+pub mod daemon;
+pub mod gas_usage;
+pub mod idempotency;
+pub mod init;
+pub mod inspect;
+pub mod latency;
+pub mod local_dev;
+pub mod log_archive;
+pub mod mempool;
+pub mod network;
+pub mod nonce_bench;
 pub mod nonce_manager;
+pub mod offline;
+pub mod pacing;
+pub mod persona;
+pub mod price;
+pub mod proxy_assignment;
 pub mod proxy_health;
+pub mod proxy_session;
+pub mod proxy_usage;
+pub mod rate_limit;
+pub mod recording;
+pub mod remote_config;
+#[cfg(any(test, feature = "testing"))]
+pub mod replay;
+pub mod result_log;
+pub mod revert;
 pub mod robust_nonce_manager;
+pub mod rpc_cache;
+pub mod rpc_span;
+pub mod rpc_stats;
+pub mod runner;
+pub mod runtime;
+pub mod session_key;
+pub mod spammer;
+pub mod task_slo;
 pub mod tasks;
+#[cfg(any(test, feature = "testing"))]
+pub mod testing;
+pub mod transfers;
+pub mod tx_builder;
 pub mod utils;
+pub mod wallet_health;
+pub mod watchdog;
 
 pub use client::TempoClient;
 pub use client_pool::ClientPool;
@@ -140,4 +218,6 @@ pub use proxy_health::ProxyBanlist;
 pub use robust_nonce_manager::{
     NonceManagerConfig, NonceReservation, NonceStats, RobustNonceManager,
 };
-pub use tasks::{ProxyConfig, TaskContext, TempoTask};
+pub use runtime::SpammerRuntime;
+pub use spammer::Spammer;
+pub use tasks::{ProxyConfig, ProxyCredentials, TaskContext, TempoTask};
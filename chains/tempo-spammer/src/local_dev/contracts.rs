@@ -0,0 +1,426 @@
+//! Mock system contracts for local devnets
+//!
+//! Plain EVM nodes have none of Tempo's precompiles, so `--local-dev`
+//! installs stand-ins at their addresses. They are assembled here rather
+//! than compiled so the spammer needs no Solidity toolchain:
+//!
+//! - [`tip20`]: balances, allowances, `transfer`, `transferWithMemo`,
+//!   `transferFrom`, `approve`, `decimals` (6) and an unrestricted `mint`;
+//!   a short balance or allowance reverts with the TIP-20 error
+//! - [`faucet`]: any call mints [`FAUCET_AMOUNT`] of every system token to
+//!   the address in its first argument
+//! - [`factory`]: `createToken(..)` deploys a copy of the PathUSD mock and
+//!   emits `TokenCreated` with the new address
+//! - [`dex`]: `swapExactAmountIn` takes the input token with `transferFrom`
+//!   and mints the same amount of the output token
+//!
+//! Selectors the mocks do not know succeed without doing anything, so role
+//! grants, pauses and similar admin calls go through. View calls they do not
+//! know return no data.
+
+use crate::asm::{Asm, deploy_prefix, op};
+use alloy_primitives::{Address, B256, Bytes, U256, address, keccak256};
+use alloy_sol_types::{SolCall, SolError, SolEvent, sol};
+
+/// System tokens, the first one (PathUSD) doubling as the factory template
+pub const SYSTEM_TOKENS: [Address; 4] = [
+    address!("20C0000000000000000000000000000000000000"),
+    address!("20C0000000000000000000000000000000000001"),
+    address!("20C0000000000000000000000000000000000002"),
+    address!("20C0000000000000000000000000000000000003"),
+];
+pub const TIP20_FACTORY: Address = address!("20FC000000000000000000000000000000000000");
+pub const FAUCET: Address = address!("4200000000000000000000000000000000000019");
+pub const STABLECOIN_DEX: Address = address!("dec0000000000000000000000000000000000000");
+
+/// Base units of each system token one faucet call mints (1M tokens at 6 decimals)
+pub const FAUCET_AMOUNT: u64 = 1_000_000_000_000;
+
+sol! {
+    interface IMockTip20 {
+        function balanceOf(address account) view returns (uint256);
+        function transfer(address to, uint256 amount) returns (bool);
+        function transferWithMemo(address to, uint256 amount, bytes32 memo);
+        function transferFrom(address from, address to, uint256 amount) returns (bool);
+        function approve(address spender, uint256 amount) returns (bool);
+        function allowance(address owner, address spender) view returns (uint256);
+        function decimals() view returns (uint8);
+        function mint(address to, uint256 amount);
+
+        event Transfer(address indexed from, address indexed to, uint256 amount);
+
+        error InsufficientBalance(uint256 available, uint256 required, address token);
+        error InsufficientAllowance();
+    }
+
+    interface IMockFactory {
+        function createToken(
+            string name,
+            string symbol,
+            string currency,
+            address quoteToken,
+            address admin,
+            bytes32 salt
+        ) returns (address);
+    }
+
+    interface IMockDex {
+        function swapExactAmountIn(address tokenIn, address tokenOut, uint128 amountIn, uint128 minAmountOut)
+            returns (uint128 amountOut);
+    }
+}
+
+/// `TokenCreated` topic the create-stablecoin task looks for
+fn token_created_topic() -> B256 {
+    keccak256(b"TokenCreated(address,string,string,string,address,address,bytes32)")
+}
+
+/// Every contract to install, by address
+pub fn system_contracts() -> Vec<(Address, Bytes)> {
+    let token = tip20();
+    let mut contracts: Vec<_> = SYSTEM_TOKENS
+        .iter()
+        .map(|address| (*address, token.clone()))
+        .collect();
+    contracts.push((TIP20_FACTORY, factory(SYSTEM_TOKENS[0], token.len())));
+    contracts.push((FAUCET, faucet()));
+    contracts.push((STABLECOIN_DEX, dex()));
+    contracts
+}
+
+/// Moves `amount` from `from` to `to` and logs `Transfer`
+///
+/// Balances live in the slot numbered by the holder's address. Each closure
+/// pushes one value; jumps to `insufficient_balance` with `[balance, amount]`
+/// on the stack when `from` holds too little.
+fn emit_transfer(
+    asm: &mut Asm,
+    from: impl Fn(&mut Asm),
+    to: impl Fn(&mut Asm),
+    amount: impl Fn(&mut Asm),
+) {
+    amount(asm);
+    from(asm);
+    asm.op(op::SLOAD) // [balance, amount]
+        .op(op::DUP2)
+        .op(op::DUP2)
+        .op(op::LT)
+        .push_label("insufficient_balance")
+        .op(op::JUMPI)
+        .op(op::SUB); // [balance - amount]
+    from(asm);
+    asm.op(op::SSTORE);
+
+    amount(asm);
+    to(asm);
+    asm.op(op::SLOAD).op(op::ADD);
+    to(asm);
+    asm.op(op::SSTORE);
+
+    asm.mstore(0, |asm| amount(asm));
+    to(asm);
+    from(asm);
+    asm.push(IMockTip20::Transfer::SIGNATURE_HASH.into())
+        .push_u64(32)
+        .push_u64(0)
+        .op(op::LOG3);
+}
+
+/// Pushes the allowance slot of `owner` for `spender`: `keccak256(owner . spender)`
+fn emit_allowance_slot(
+    asm: &mut Asm,
+    owner: impl FnOnce(&mut Asm),
+    spender: impl FnOnce(&mut Asm),
+) {
+    asm.mstore(0, owner)
+        .mstore(32, spender)
+        .push_u64(64)
+        .push_u64(0)
+        .op(op::KECCAK256);
+}
+
+/// Runtime code of the TIP-20 mock
+pub fn tip20() -> Bytes {
+    let mut asm = Asm::default();
+    asm.selector()
+        .dispatch(IMockTip20::balanceOfCall::SELECTOR, "balance_of")
+        .dispatch(IMockTip20::transferCall::SELECTOR, "transfer")
+        .dispatch(IMockTip20::transferWithMemoCall::SELECTOR, "transfer")
+        .dispatch(IMockTip20::transferFromCall::SELECTOR, "transfer_from")
+        .dispatch(IMockTip20::approveCall::SELECTOR, "approve")
+        .dispatch(IMockTip20::allowanceCall::SELECTOR, "allowance")
+        .dispatch(IMockTip20::decimalsCall::SELECTOR, "decimals")
+        .dispatch(IMockTip20::mintCall::SELECTOR, "mint")
+        .op(op::STOP);
+
+    asm.label("balance_of").arg(0).op(op::SLOAD).return_word();
+
+    asm.label("decimals").push_u64(6).return_word();
+
+    asm.label("allowance");
+    emit_allowance_slot(
+        &mut asm,
+        |a| {
+            a.arg(0);
+        },
+        |a| {
+            a.arg(1);
+        },
+    );
+    asm.op(op::SLOAD).return_word();
+
+    asm.label("approve");
+    asm.arg(1);
+    emit_allowance_slot(
+        &mut asm,
+        |a| {
+            a.op(op::CALLER);
+        },
+        |a| {
+            a.arg(0);
+        },
+    );
+    asm.op(op::SSTORE).push_u64(1).return_word();
+
+    asm.label("transfer");
+    emit_transfer(
+        &mut asm,
+        |a| {
+            a.op(op::CALLER);
+        },
+        |a| {
+            a.arg(0);
+        },
+        |a| {
+            a.arg(1);
+        },
+    );
+    asm.push_u64(1).return_word();
+
+    asm.label("transfer_from");
+    asm.arg(2);
+    emit_allowance_slot(
+        &mut asm,
+        |a| {
+            a.arg(0);
+        },
+        |a| {
+            a.op(op::CALLER);
+        },
+    );
+    asm.op(op::SLOAD) // [allowance, amount]
+        .op(op::DUP2)
+        .op(op::DUP2)
+        .op(op::LT)
+        .push_label("insufficient_allowance")
+        .op(op::JUMPI)
+        .op(op::SUB);
+    emit_allowance_slot(
+        &mut asm,
+        |a| {
+            a.arg(0);
+        },
+        |a| {
+            a.op(op::CALLER);
+        },
+    );
+    asm.op(op::SSTORE);
+    emit_transfer(
+        &mut asm,
+        |a| {
+            a.arg(0);
+        },
+        |a| {
+            a.arg(1);
+        },
+        |a| {
+            a.arg(2);
+        },
+    );
+    asm.push_u64(1).return_word();
+
+    asm.label("mint")
+        .arg(1)
+        .arg(0)
+        .op(op::SLOAD)
+        .op(op::ADD)
+        .arg(0)
+        .op(op::SSTORE)
+        .mstore(0, |a| {
+            a.arg(1);
+        })
+        .arg(0)
+        .push_u64(0)
+        .push(IMockTip20::Transfer::SIGNATURE_HASH.into())
+        .push_u64(32)
+        .push_u64(0)
+        .op(op::LOG3)
+        .op(op::STOP);
+
+    // [balance, amount] -> InsufficientBalance(balance, amount, this)
+    asm.label("insufficient_balance")
+        .mstore(0, |a| {
+            a.push_selector(IMockTip20::InsufficientBalance::SELECTOR);
+        })
+        .push_u64(4)
+        .op(op::MSTORE)
+        .push_u64(36)
+        .op(op::MSTORE)
+        .mstore(68, |a| {
+            a.op(op::ADDRESS);
+        })
+        .push_u64(100)
+        .push_u64(0)
+        .op(op::REVERT);
+
+    asm.label("insufficient_allowance")
+        .mstore(0, |a| {
+            a.push_selector(IMockTip20::InsufficientAllowance::SELECTOR);
+        })
+        .push_u64(4)
+        .push_u64(0)
+        .op(op::REVERT);
+
+    asm.assemble()
+}
+
+/// Runtime code of the faucet mock
+pub fn faucet() -> Bytes {
+    let mut asm = Asm::default();
+    for token in SYSTEM_TOKENS {
+        asm.mstore(0, |a| {
+            a.push_selector(IMockTip20::mintCall::SELECTOR);
+        })
+        .mstore(4, |a| {
+            a.arg(0);
+        })
+        .mstore(36, |a| {
+            a.push_u64(FAUCET_AMOUNT);
+        })
+        .call(token, 68);
+    }
+    asm.op(op::STOP).fail();
+    asm.assemble()
+}
+
+/// Runtime code of the factory mock, cloning the `template_len`-byte code at `template`
+pub fn factory(template: Address, template_len: usize) -> Bytes {
+    let len = template_len as u64;
+    let prefix = deploy_prefix(template_len);
+
+    let mut asm = Asm::default();
+    asm.selector()
+        .dispatch(IMockFactory::createTokenCall::SELECTOR, "create")
+        .op(op::STOP);
+
+    asm.label("create")
+        .mstore(0, |a| {
+            a.push(U256::from_be_slice(&prefix))
+                .push_u64(8 * (32 - prefix.len() as u64))
+                .op(op::SHL);
+        })
+        .push_u64(len)
+        .push_u64(0)
+        .push_u64(prefix.len() as u64)
+        .push(U256::from_be_slice(template.as_slice()))
+        .op(op::EXTCODECOPY)
+        .push_u64(prefix.len() as u64 + len)
+        .push_u64(0)
+        .push_u64(0)
+        .op(op::CREATE) // [token]
+        .op(op::DUP1)
+        .op(op::ISZERO)
+        .push_label("fail")
+        .op(op::JUMPI)
+        .op(op::DUP1)
+        .push(token_created_topic().into())
+        .push_u64(0)
+        .push_u64(0)
+        .op(op::LOG2)
+        .return_word();
+    asm.fail();
+    asm.assemble()
+}
+
+/// Runtime code of the stablecoin DEX mock
+pub fn dex() -> Bytes {
+    let mut asm = Asm::default();
+    asm.selector()
+        .dispatch(IMockDex::swapExactAmountInCall::SELECTOR, "swap")
+        .op(op::STOP);
+
+    asm.label("swap")
+        .mstore(0, |a| {
+            a.push_selector(IMockTip20::transferFromCall::SELECTOR);
+        })
+        .mstore(4, |a| {
+            a.op(op::CALLER);
+        })
+        .mstore(36, |a| {
+            a.op(op::ADDRESS);
+        })
+        .mstore(68, |a| {
+            a.arg(2);
+        });
+    // tokenIn, read from calldata at runtime
+    asm.call_arg(0, 100);
+
+    asm.mstore(0, |a| {
+        a.push_selector(IMockTip20::mintCall::SELECTOR);
+    })
+    .mstore(4, |a| {
+        a.op(op::CALLER);
+    })
+    .mstore(36, |a| {
+        a.arg(2);
+    });
+    asm.call_arg(1, 68);
+
+    asm.arg(2).return_word();
+    asm.fail();
+    asm.assemble()
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    #[test]
+    fn test_jump_targets_are_jumpdests() {
+        for (address, code) in system_contracts() {
+            let mut pc = 0;
+            while pc < code.len() {
+                let opcode = code[pc];
+                if opcode == op::PUSH2 && code.get(pc + 3) == Some(&op::JUMPI) {
+                    let target = u16::from_be_bytes([code[pc + 1], code[pc + 2]]) as usize;
+                    assert_eq!(
+                        code[target],
+                        op::JUMPDEST,
+                        "{} jumps to {}",
+                        address,
+                        target
+                    );
+                }
+                pc += 1 + match opcode {
+                    0x60..=0x7f => (opcode - 0x5f) as usize,
+                    _ => 0,
+                };
+            }
+        }
+    }
+
+    #[test]
+    fn test_factory_init_code_returns_template() {
+        let token = tip20();
+        let factory = factory(SYSTEM_TOKENS[0], token.len());
+        let len = (token.len() as u16).to_be_bytes();
+        let prefix = [
+            0x61, len[0], len[1], 0x60, 0x0e, 0x60, 0x00, 0x39, 0x61, len[0], len[1], 0x60, 0x00,
+            0xf3,
+        ];
+        // The prefix is pushed as one left-aligned word
+        let mut push = vec![0x6d];
+        push.extend_from_slice(&prefix);
+        assert!(factory.windows(push.len()).any(|w| w == push.as_slice()));
+    }
+}
@@ -0,0 +1,286 @@
+//! Local Devnet - End-to-end task runs without the public testnet
+//!
+//! `tempo-spammer --local-dev` checks the whole pipeline (config, clients,
+//! nonce handling, tasks, database) against a throwaway chain:
+//!
+//! 1. starts `anvil` on a free port, or uses `[local_dev] rpc_url`
+//! 2. installs the [`contracts`] mocks at the system token, TIP-20 factory,
+//!    faucet and stablecoin DEX addresses with `anvil_setCode`
+//! 3. creates `[local_dev] wallets` random wallets and gives each native
+//!    balance for gas with `anvil_setBalance`
+//! 4. runs every task in `[local_dev] tasks` once per wallet, in order, with
+//!    a temporary database
+//!
+//! ```bash
+//! tempo-spammer --local-dev
+//! ```
+//!
+//! Tasks that need Tempo transactions (0x76) or precompiles without a mock
+//! fail here, which is why the default task list is limited to
+//! [`DEFAULT_TASKS`].
+
+pub mod contracts;
+
+use crate::TempoClient;
+use crate::config::{LocalDevSettings, TempoSpammerConfig};
+use crate::revert;
+use crate::tasks::{TaskContext, TempoTask};
+use alloy::providers::{Provider, ProviderBuilder};
+use alloy::signers::local::PrivateKeySigner;
+use alloy_primitives::{Address, U256};
+use anyhow::{Context, Result, bail};
+use core_logic::database::DatabaseManager;
+use std::path::PathBuf;
+use std::process::Stdio;
+use std::sync::Arc;
+use std::time::{Duration, Instant};
+use tokio::process::{Child, Command};
+use tracing::{info, warn};
+use url::Url;
+
+/// Tasks the mock system contracts support, in a funding-first order
+pub const DEFAULT_TASKS: &[&str] = &[
+    "02_claim_faucet",
+    "01_deploy_contract",
+    "03_send_token",
+    "04_create_stable",
+    "05_swap_stable",
+    "09_transfer_token",
+    "10_transfer_memo",
+    "13_grant_role",
+    "14_nft_create_mint",
+];
+
+/// Chain id the started node reports, matching the testnet
+const LOCAL_CHAIN_ID: u64 = 42431;
+
+/// Native balance each wallet gets for gas (1000 ETH)
+const WALLET_BALANCE: u128 = 1_000_000_000_000_000_000_000;
+
+/// A node with the mock system contracts installed
+pub struct LocalDevnet {
+    pub rpc_url: String,
+    /// Started node, killed on drop; `None` when using `[local_dev] rpc_url`
+    _node: Option<Child>,
+}
+
+impl LocalDevnet {
+    /// Starts anvil, or connects to `rpc_url`, and installs the mocks
+    pub async fn start(settings: &LocalDevSettings) -> Result<Self> {
+        let devnet = match &settings.rpc_url {
+            Some(rpc_url) => Self {
+                rpc_url: rpc_url.clone(),
+                _node: None,
+            },
+            None => Self::spawn_anvil(&settings.anvil)?,
+        };
+        devnet
+            .wait_ready(Duration::from_secs(settings.startup_timeout_secs))
+            .await?;
+        devnet.install_system_contracts().await?;
+        Ok(devnet)
+    }
+
+    fn spawn_anvil(anvil: &str) -> Result<Self> {
+        // Reserve a free port, then release it for anvil
+        let port = std::net::TcpListener::bind("127.0.0.1:0")
+            .and_then(|listener| listener.local_addr())
+            .context("No free local port")?
+            .port();
+        let node = Command::new(anvil)
+            .args(["--port", &port.to_string()])
+            .args(["--chain-id", &LOCAL_CHAIN_ID.to_string()])
+            .stdout(Stdio::null())
+            .stderr(Stdio::null())
+            .kill_on_drop(true)
+            .spawn()
+            .with_context(|| format!("Failed to start '{}'; is anvil installed?", anvil))?;
+        info!("Started {} on port {}", anvil, port);
+        Ok(Self {
+            rpc_url: format!("http://127.0.0.1:{}", port),
+            _node: Some(node),
+        })
+    }
+
+    fn provider(&self) -> Result<impl Provider + use<>> {
+        let url: Url = self.rpc_url.parse().context("Invalid local_dev RPC URL")?;
+        Ok(ProviderBuilder::new().connect_http(url))
+    }
+
+    async fn wait_ready(&self, timeout: Duration) -> Result<()> {
+        let provider = self.provider()?;
+        let deadline = Instant::now() + timeout;
+        loop {
+            match provider.get_chain_id().await {
+                Ok(_) => return Ok(()),
+                Err(e) if Instant::now() >= deadline => {
+                    bail!("Local node at {} not ready: {}", self.rpc_url, e)
+                }
+                Err(_) => tokio::time::sleep(Duration::from_millis(200)).await,
+            }
+        }
+    }
+
+    /// Places the [`contracts`] mocks at the system addresses
+    pub async fn install_system_contracts(&self) -> Result<()> {
+        let provider = self.provider()?;
+        for (address, code) in contracts::system_contracts() {
+            provider
+                .raw_request::<_, serde_json::Value>("anvil_setCode".into(), (address, code))
+                .await
+                .with_context(|| format!("anvil_setCode failed for {}", address))?;
+        }
+        Ok(())
+    }
+
+    /// Gives `address` [`WALLET_BALANCE`] of native currency
+    pub async fn fund(&self, address: Address) -> Result<()> {
+        self.provider()?
+            .raw_request::<_, serde_json::Value>(
+                "anvil_setBalance".into(),
+                (address, U256::from(WALLET_BALANCE)),
+            )
+            .await
+            .with_context(|| format!("anvil_setBalance failed for {}", address))?;
+        Ok(())
+    }
+}
+
+/// Result of one task run on the devnet
+#[derive(Debug, Clone)]
+pub struct LocalDevOutcome {
+    pub task: String,
+    pub wallet: Address,
+    pub success: bool,
+    pub message: String,
+    pub duration: Duration,
+}
+
+/// Results of a `--local-dev` run
+#[derive(Debug, Clone, Default)]
+pub struct LocalDevReport {
+    pub outcomes: Vec<LocalDevOutcome>,
+}
+
+impl LocalDevReport {
+    pub fn failures(&self) -> Vec<&LocalDevOutcome> {
+        self.outcomes.iter().filter(|o| !o.success).collect()
+    }
+}
+
+/// Picks `names` out of `tasks`, in the order given
+pub fn select_tasks<'a>(
+    tasks: &'a [Box<dyn TempoTask>],
+    names: &[String],
+) -> Result<Vec<&'a dyn TempoTask>> {
+    names
+        .iter()
+        .map(|name| {
+            tasks
+                .iter()
+                .find(|task| task.name() == name)
+                .map(|task| task.as_ref())
+                .with_context(|| format!("Unknown task '{}' in local_dev.tasks", name))
+        })
+        .collect()
+}
+
+/// Runs `[local_dev] tasks` from `tasks` on a fresh devnet
+pub async fn run_local_dev(
+    config: &TempoSpammerConfig,
+    tasks: &[Box<dyn TempoTask>],
+) -> Result<LocalDevReport> {
+    let settings = &config.local_dev;
+    let selected = select_tasks(tasks, &settings.tasks)?;
+    {
+        let mut decoder = revert::decoder().write().unwrap();
+        for task in &selected {
+            task.register_errors(&mut decoder);
+        }
+    }
+
+    let devnet = LocalDevnet::start(settings).await?;
+    let mut config = config.clone();
+    config.rpc_url = devnet.rpc_url.clone();
+    config.chain_id = LOCAL_CHAIN_ID;
+
+    let db_path = TempDb::new();
+    let db = Arc::new(DatabaseManager::new(db_path.as_str()?).await?);
+
+    let mut clients = Vec::with_capacity(settings.wallets);
+    for _ in 0..settings.wallets {
+        let signer = PrivateKeySigner::random();
+        devnet.fund(signer.address()).await?;
+        let key = format!("0x{}", hex::encode(signer.to_bytes()));
+        clients.push(TempoClient::new(&devnet.rpc_url, &key, None, None).await?);
+    }
+
+    let mut report = LocalDevReport::default();
+    for task in selected {
+        for client in &clients {
+            let ctx = TaskContext::new(client.clone(), config.clone(), Some(db.clone()));
+            let started = Instant::now();
+            let (success, message) = match tokio::time::timeout(ctx.timeout, task.run(&ctx)).await {
+                Ok(Ok(result)) => (result.success, result.message),
+                Ok(Err(e)) => (false, revert::describe(e.as_ref())),
+                Err(_) => (false, format!("Timed out after {:?}", ctx.timeout)),
+            };
+            if success {
+                info!("✅ {} {:?}: {}", task.name(), client.address(), message);
+            } else {
+                warn!("❌ {} {:?}: {}", task.name(), client.address(), message);
+            }
+            report.outcomes.push(LocalDevOutcome {
+                task: task.name().to_string(),
+                wallet: client.address(),
+                success,
+                message,
+                duration: started.elapsed(),
+            });
+        }
+    }
+    Ok(report)
+}
+
+/// Database file removed when dropped
+struct TempDb(PathBuf);
+
+impl TempDb {
+    fn new() -> Self {
+        Self(std::env::temp_dir().join(format!(
+            "tempo-local-dev-{}-{}.db",
+            std::process::id(),
+            fastrand::u64(..)
+        )))
+    }
+
+    fn as_str(&self) -> Result<&str> {
+        self.0.to_str().context("Non UTF-8 temp path")
+    }
+}
+
+impl Drop for TempDb {
+    fn drop(&mut self) {
+        for suffix in ["", "-wal", "-shm"] {
+            let mut file = self.0.clone().into_os_string();
+            file.push(suffix);
+            let _ = std::fs::remove_file(file);
+        }
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    #[test]
+    fn test_default_tasks_exist() {
+        let tasks = crate::runner::default_tasks();
+        let selected = select_tasks(&tasks, &LocalDevSettings::default().tasks).unwrap();
+        assert_eq!(selected.len(), DEFAULT_TASKS.len());
+        assert_eq!(selected[0].name(), "02_claim_faucet");
+
+        let missing = select_tasks(&tasks, &["99_missing".to_string()]);
+        assert!(missing.is_err_and(|e| e.to_string().contains("99_missing")));
+    }
+}
@@ -0,0 +1,561 @@
+//! Log Archive - Receipt logs of task transactions for coverage audits
+//!
+//! `task_metrics` says a task succeeded, not which contracts and events the
+//! run actually exercised. With `[log_archive]` enabled the runner hands the
+//! transaction each task reports in
+//! [`TaskResult::tx_hash`](crate::tasks::TaskResult) to the [`LogArchive`],
+//! which fetches the receipt in the background and keeps every log: emitting
+//! contract, all topics and the data.
+//!
+//! ```toml
+//! [log_archive]
+//! enabled = true
+//! tasks = ["10_transfer_memo", "21_create_meme"]   # empty: every task
+//! sink = "db"                                      # or "jsonl"
+//! ```
+//!
+//! The `db` sink writes the `receipt_logs` table with the data
+//! zlib-compressed; the `jsonl` sink appends one [`ArchivedLog`] per line to
+//! `path`, gzip-compressed when it ends in `.gz`. Each receipt is written as
+//! its own gzip member, so the file stays readable when a run is killed.
+//!
+//! `coverage` lists the distinct contracts and event signatures the archived
+//! logs came from, naming the system contracts and the Tempo events it knows:
+//!
+//! ```bash
+//! tempo-spammer db coverage
+//! tempo-spammer db coverage --task 21_create_meme
+//! tempo-spammer db coverage --file receipt-logs.jsonl.gz
+//! ```
+//!
+//! Only the reported transaction is archived, as for gas tracking, and the
+//! receipt is awaited as long as `[gas] receipt_timeout_secs`.
+
+use crate::TempoClient;
+use crate::config::{LogArchiveSettings, LogSink};
+use crate::gas_usage::wait_for_receipt;
+use crate::network;
+use alloy::rpc::types::TransactionReceipt;
+use alloy_primitives::{Address, B256, Bytes, keccak256};
+use anyhow::{Context, Result};
+use core_logic::database::{DatabaseManager, LogCoverage, ReceiptLogRecord};
+use flate2::Compression;
+use flate2::read::MultiGzDecoder;
+use flate2::write::{GzEncoder, ZlibEncoder};
+use serde::{Deserialize, Serialize};
+use std::cmp::Reverse;
+use std::collections::{BTreeMap, BTreeSet, HashMap};
+use std::fmt::Write as _;
+use std::fs::{File, OpenOptions};
+use std::io::{BufRead, BufReader, Read, Write};
+use std::path::Path;
+use std::sync::atomic::{AtomicU64, Ordering};
+use std::sync::{Arc, Mutex};
+use std::time::Duration;
+use tokio::sync::Semaphore;
+use tracing::{debug, warn};
+
+/// Pause between receipt lookups
+const RECEIPT_POLL: Duration = Duration::from_secs(2);
+
+/// Event signatures the coverage report names
+const KNOWN_EVENTS: &[&str] = &[
+    "Transfer(address,address,uint256)",
+    "Approval(address,address,uint256)",
+    "TransferWithMemo(address,address,uint256,bytes32)",
+    "Mint(address,uint256)",
+    "Burn(address,uint256)",
+    "BurnBlocked(address,uint256)",
+    "TransferPolicyUpdate(address,uint64)",
+    "SupplyCapUpdate(address,uint256)",
+    "PauseStateUpdate(address,bool)",
+    "QuoteTokenUpdate(address,address)",
+    "RewardDistributed(address,uint256)",
+    "RewardRecipientSet(address,address)",
+    "RoleMembershipUpdated(bytes32,address,address,bool)",
+    "TokenCreated(address,string,string,string,address,address,bytes32)",
+    "PairCreated(bytes32,address,address)",
+    "OrderPlaced(uint128,address,address,uint128,bool,int16,bool,int16)",
+    "OrderFilled(uint128,address,address,uint128,bool)",
+    "OrderCancelled(uint128)",
+    "PolicyCreated(uint64,address,uint8)",
+    "PolicyAdminUpdated(uint64,address,address)",
+    "WhitelistUpdated(uint64,address,address,bool)",
+    "BlacklistUpdated(uint64,address,address,bool)",
+    "UserTokenSet(address,address)",
+    "ValidatorTokenSet(address,address)",
+    "FeesDistributed(address,address,uint256)",
+    "RebalanceSwap(address,address,address,uint256,uint256)",
+    "KeyAuthorized(address,address,uint8,uint64)",
+    "KeyRevoked(address,address)",
+    "NonceIncremented(address,uint256,uint64)",
+    "OwnershipTransferred(address,address)",
+];
+
+/// One receipt log, as written to the JSONL sink
+#[derive(Debug, Clone, PartialEq, Eq, Serialize, Deserialize)]
+pub struct ArchivedLog {
+    pub task: String,
+    pub tx_hash: B256,
+    pub block_number: Option<u64>,
+    /// Position in the block, or in the receipt when the node leaves it out
+    pub log_index: u64,
+    pub address: Address,
+    pub topics: Vec<B256>,
+    pub data: Bytes,
+}
+
+impl ArchivedLog {
+    /// Every log of `receipt`
+    pub fn from_receipt(task: &str, receipt: &TransactionReceipt) -> Vec<Self> {
+        receipt
+            .inner
+            .logs()
+            .iter()
+            .enumerate()
+            .map(|(position, log)| Self {
+                task: task.to_string(),
+                tx_hash: receipt.transaction_hash,
+                block_number: receipt.block_number,
+                log_index: log.log_index.unwrap_or(position as u64),
+                address: log.address(),
+                topics: log.topics().to_vec(),
+                data: log.data().data.clone(),
+            })
+            .collect()
+    }
+
+    /// The `receipt_logs` row, data compressed
+    pub fn to_record(&self, timestamp: i64) -> Result<ReceiptLogRecord> {
+        let mut data = ZlibEncoder::new(Vec::new(), Compression::default());
+        data.write_all(&self.data)?;
+        Ok(ReceiptLogRecord {
+            tx_hash: self.tx_hash.to_string(),
+            log_index: self.log_index as i64,
+            task_name: self.task.clone(),
+            block_number: self.block_number.map(|block| block as i64),
+            address: self.address.to_string(),
+            topics: self.topics.iter().map(B256::to_string).collect(),
+            data: data.finish()?,
+            timestamp,
+        })
+    }
+}
+
+/// Background receipt lookups feeding the log archive
+#[derive(Debug)]
+pub struct LogArchive {
+    settings: LogArchiveSettings,
+    db: Arc<DatabaseManager>,
+    receipt_timeout: Duration,
+    pending: Arc<Semaphore>,
+    /// Serializes appends to the JSONL file
+    file: Mutex<()>,
+    skipped: AtomicU64,
+}
+
+impl LogArchive {
+    pub fn new(
+        settings: LogArchiveSettings,
+        db: Arc<DatabaseManager>,
+        receipt_timeout: Duration,
+        max_pending: usize,
+    ) -> Self {
+        Self {
+            settings,
+            db,
+            receipt_timeout,
+            pending: Arc::new(Semaphore::new(max_pending.max(1))),
+            file: Mutex::new(()),
+            skipped: AtomicU64::new(0),
+        }
+    }
+
+    /// Transactions not archived because `max_pending` lookups were running
+    pub fn skipped(&self) -> u64 {
+        self.skipped.load(Ordering::Relaxed)
+    }
+
+    /// Archives the logs of `tx_hash` once its receipt shows up, for tasks the settings select
+    pub fn spawn(self: &Arc<Self>, task_name: &str, client: &TempoClient, tx_hash: B256) {
+        if !self.settings.archives(task_name) {
+            return;
+        }
+        let Ok(permit) = self.pending.clone().try_acquire_owned() else {
+            self.skipped.fetch_add(1, Ordering::Relaxed);
+            debug!(
+                "Too many pending receipts, not archiving logs of {}",
+                tx_hash
+            );
+            return;
+        };
+        let archive = self.clone();
+        let task_name = task_name.to_string();
+        let provider = client.provider.clone();
+
+        tokio::spawn(async move {
+            let _permit = permit;
+            let Some(receipt) = wait_for_receipt(
+                provider.as_ref(),
+                tx_hash,
+                archive.receipt_timeout,
+                RECEIPT_POLL,
+            )
+            .await
+            else {
+                debug!(
+                    "No receipt for {} ({}), logs not archived",
+                    tx_hash, task_name
+                );
+                return;
+            };
+            let logs = ArchivedLog::from_receipt(&task_name, &receipt);
+            if let Err(e) = archive.store(&logs).await {
+                warn!("Failed to archive the logs of {}: {:#}", tx_hash, e);
+            }
+        });
+    }
+
+    /// Writes `logs` to the configured sink
+    pub async fn store(&self, logs: &[ArchivedLog]) -> Result<()> {
+        if logs.is_empty() {
+            return Ok(());
+        }
+        match self.settings.sink {
+            LogSink::Db => {
+                let timestamp = chrono::Utc::now().timestamp();
+                let records = logs
+                    .iter()
+                    .map(|log| log.to_record(timestamp))
+                    .collect::<Result<Vec<_>>>()?;
+                self.db.log_receipt_logs(&records).await
+            }
+            LogSink::Jsonl => {
+                let _guard = self.file.lock().unwrap_or_else(|e| e.into_inner());
+                append_jsonl(Path::new(&self.settings.path), logs)
+            }
+        }
+    }
+}
+
+/// Appends `logs` to `path`, as one gzip member when it ends in `.gz`
+pub fn append_jsonl(path: &Path, logs: &[ArchivedLog]) -> Result<()> {
+    let mut lines = Vec::new();
+    for log in logs {
+        serde_json::to_writer(&mut lines, log)?;
+        lines.push(b'\n');
+    }
+    if is_gzip(path) {
+        let mut member = GzEncoder::new(Vec::new(), Compression::default());
+        member.write_all(&lines)?;
+        lines = member.finish()?;
+    }
+
+    OpenOptions::new()
+        .create(true)
+        .append(true)
+        .open(path)
+        .and_then(|mut file| file.write_all(&lines))
+        .with_context(|| format!("Failed to append to {}", path.display()))
+}
+
+/// Reads a file written by the JSONL sink
+///
+/// # Errors
+///
+/// Fails on a line that does not parse, naming it.
+pub fn read_jsonl(path: &Path) -> Result<Vec<ArchivedLog>> {
+    let file = File::open(path).with_context(|| format!("Failed to open {}", path.display()))?;
+    let reader: Box<dyn Read> = if is_gzip(path) {
+        Box::new(MultiGzDecoder::new(file))
+    } else {
+        Box::new(file)
+    };
+
+    let mut logs = Vec::new();
+    for (index, line) in BufReader::new(reader).lines().enumerate() {
+        let line = line.with_context(|| format!("Failed to read {}", path.display()))?;
+        if line.trim().is_empty() {
+            continue;
+        }
+        logs.push(
+            serde_json::from_str(&line)
+                .with_context(|| format!("{} line {}", path.display(), index + 1))?,
+        );
+    }
+    Ok(logs)
+}
+
+fn is_gzip(path: &Path) -> bool {
+    path.extension().is_some_and(|extension| extension == "gz")
+}
+
+/// Logs per contract and event signature, as `receipt_logs` groups them
+pub fn coverage_rows(logs: &[ArchivedLog]) -> Vec<LogCoverage> {
+    // (address, topic0) -> (logs, tasks)
+    let mut groups = BTreeMap::<_, (i64, BTreeSet<&str>)>::new();
+    for log in logs {
+        let (count, tasks) = groups
+            .entry((log.address, log.topics.first().copied()))
+            .or_default();
+        *count += 1;
+        tasks.insert(&log.task);
+    }
+
+    let mut rows: Vec<LogCoverage> = groups
+        .into_iter()
+        .map(|((address, topic0), (logs, tasks))| LogCoverage {
+            address: address.to_string(),
+            topic0: topic0.map(|topic| topic.to_string()),
+            logs,
+            tasks: tasks.into_iter().collect::<Vec<_>>().join(","),
+        })
+        .collect();
+    rows.sort_by_key(|row| Reverse(row.logs));
+    rows
+}
+
+/// Distinct contracts and event signatures exercised
+#[derive(Debug, Clone, Default, PartialEq, Eq)]
+pub struct Coverage {
+    /// Most logs first
+    pub contracts: Vec<ContractCoverage>,
+    pub events: Vec<EventCoverage>,
+}
+
+#[derive(Debug, Clone, PartialEq, Eq)]
+pub struct ContractCoverage {
+    pub address: String,
+    /// System contract name, if it is one
+    pub name: Option<&'static str>,
+    pub logs: i64,
+    /// Distinct event signatures
+    pub events: usize,
+    pub tasks: BTreeSet<String>,
+}
+
+#[derive(Debug, Clone, PartialEq, Eq)]
+pub struct EventCoverage {
+    /// `None` for anonymous events without topics
+    pub topic0: Option<String>,
+    /// Signature, if it is one of the known events
+    pub signature: Option<&'static str>,
+    pub logs: i64,
+    /// Distinct contracts emitting it
+    pub contracts: usize,
+    pub tasks: BTreeSet<String>,
+}
+
+impl Coverage {
+    /// Rolls per contract and event rows up both ways
+    pub fn from_rows(rows: &[LogCoverage]) -> Self {
+        let names: HashMap<String, &'static str> = network::contracts()
+            .named()
+            .into_iter()
+            .map(|(name, address)| (address.to_string(), name))
+            .collect();
+        let signatures: HashMap<String, &'static str> = KNOWN_EVENTS
+            .iter()
+            .map(|signature| (keccak256(signature).to_string(), *signature))
+            .collect();
+
+        let mut contracts: BTreeMap<&str, ContractCoverage> = BTreeMap::new();
+        let mut events: BTreeMap<Option<&str>, (EventCoverage, BTreeSet<&str>)> = BTreeMap::new();
+        for row in rows {
+            let tasks = row.tasks.split(',').filter(|t| !t.is_empty());
+
+            let contract = contracts
+                .entry(&row.address)
+                .or_insert_with(|| ContractCoverage {
+                    address: row.address.clone(),
+                    name: names.get(&row.address).copied(),
+                    logs: 0,
+                    events: 0,
+                    tasks: BTreeSet::new(),
+                });
+            contract.logs += row.logs;
+            contract.events += 1;
+            contract.tasks.extend(tasks.clone().map(str::to_string));
+
+            let (event, emitters) = events.entry(row.topic0.as_deref()).or_insert_with(|| {
+                let event = EventCoverage {
+                    topic0: row.topic0.clone(),
+                    signature: row
+                        .topic0
+                        .as_ref()
+                        .and_then(|topic| signatures.get(topic).copied()),
+                    logs: 0,
+                    contracts: 0,
+                    tasks: BTreeSet::new(),
+                };
+                (event, BTreeSet::new())
+            });
+            event.logs += row.logs;
+            emitters.insert(&row.address);
+            event.contracts = emitters.len();
+            event.tasks.extend(tasks.map(str::to_string));
+        }
+
+        let mut contracts: Vec<_> = contracts.into_values().collect();
+        contracts.sort_by_key(|contract| Reverse(contract.logs));
+        let mut events: Vec<_> = events.into_values().map(|(event, _)| event).collect();
+        events.sort_by_key(|event| Reverse(event.logs));
+        Self { contracts, events }
+    }
+
+    /// Both lists as tables
+    pub fn render(&self) -> String {
+        let mut out = format!(
+            "{} contracts, {} event signatures\n\n{:<42} {:<16} {:>8} {:>7}  Tasks\n",
+            self.contracts.len(),
+            self.events.len(),
+            "Contract",
+            "Name",
+            "Logs",
+            "Events"
+        );
+        for contract in &self.contracts {
+            let _ = writeln!(
+                out,
+                "{:<42} {:<16} {:>8} {:>7}  {}",
+                contract.address,
+                contract.name.unwrap_or("-"),
+                contract.logs,
+                contract.events,
+                join(&contract.tasks)
+            );
+        }
+
+        let _ = write!(
+            out,
+            "\n{:<66} {:>8} {:>9}  Signature\n",
+            "Topic0", "Logs", "Contracts"
+        );
+        for event in &self.events {
+            let _ = writeln!(
+                out,
+                "{:<66} {:>8} {:>9}  {}\n    tasks: {}",
+                event.topic0.as_deref().unwrap_or("(anonymous)"),
+                event.logs,
+                event.contracts,
+                event.signature.unwrap_or("?"),
+                join(&event.tasks)
+            );
+        }
+        out
+    }
+}
+
+fn join(tasks: &BTreeSet<String>) -> String {
+    tasks.iter().cloned().collect::<Vec<_>>().join(", ")
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use flate2::read::ZlibDecoder;
+
+    fn log(task: &str, address: Address, topic0: Option<B256>, index: u64) -> ArchivedLog {
+        ArchivedLog {
+            task: task.to_string(),
+            tx_hash: B256::repeat_byte(index as u8),
+            block_number: Some(101),
+            log_index: index,
+            address,
+            // A log without topic0 has no other topics either
+            topics: topic0
+                .map(|topic| vec![topic, B256::repeat_byte(0xee)])
+                .unwrap_or_default(),
+            data: Bytes::from(vec![7u8; 64]),
+        }
+    }
+
+    #[test]
+    fn test_record_compresses_data() {
+        let transfer = keccak256("Transfer(address,address,uint256)");
+        let archived = log("03_send_token", Address::repeat_byte(1), Some(transfer), 3);
+        let record = archived.to_record(1_700_000_000).unwrap();
+        assert_eq!(record.topics.len(), 2);
+        assert_eq!(record.topics[0], transfer.to_string());
+        assert_eq!(record.log_index, 3);
+        assert!(record.data.len() < archived.data.len());
+
+        let mut data = Vec::new();
+        ZlibDecoder::new(record.data.as_slice())
+            .read_to_end(&mut data)
+            .unwrap();
+        assert_eq!(data, archived.data.to_vec());
+    }
+
+    #[test]
+    fn test_jsonl_round_trip_across_gzip_members() {
+        let dir = std::env::temp_dir().join(format!(
+            "tempo-log-archive-{}-{}",
+            std::process::id(),
+            fastrand::u64(..)
+        ));
+        std::fs::create_dir_all(&dir).unwrap();
+        let token = Address::repeat_byte(1);
+        let first = vec![log("03_send_token", token, None, 0)];
+        let second = vec![
+            log("10_transfer_memo", token, Some(B256::repeat_byte(2)), 1),
+            log("10_transfer_memo", token, Some(B256::repeat_byte(2)), 2),
+        ];
+
+        for name in ["logs.jsonl", "logs.jsonl.gz"] {
+            let path = dir.join(name);
+            append_jsonl(&path, &first).unwrap();
+            append_jsonl(&path, &second).unwrap();
+            let read = read_jsonl(&path).unwrap();
+            assert_eq!(read, [first.clone(), second.clone()].concat());
+        }
+        std::fs::remove_dir_all(&dir).ok();
+    }
+
+    #[test]
+    fn test_coverage_names_contracts_and_events() {
+        let path_usd = network::contracts().path_usd;
+        let other = Address::repeat_byte(0x42);
+        let transfer = keccak256("Transfer(address,address,uint256)");
+        let unknown = B256::repeat_byte(9);
+        let logs = [
+            log("03_send_token", path_usd, Some(transfer), 0),
+            log("10_transfer_memo", path_usd, Some(transfer), 1),
+            log("10_transfer_memo", path_usd, Some(unknown), 2),
+            log("21_create_meme", other, Some(transfer), 3),
+            log("21_create_meme", other, None, 4),
+        ];
+
+        let rows = coverage_rows(&logs);
+        assert_eq!(rows.len(), 4);
+        assert_eq!(rows[0].logs, 2);
+        assert_eq!(rows[0].tasks, "03_send_token,10_transfer_memo");
+
+        let coverage = Coverage::from_rows(&rows);
+        assert_eq!(coverage.contracts.len(), 2);
+        assert_eq!(coverage.contracts[0].address, path_usd.to_string());
+        assert_eq!(coverage.contracts[0].name, Some("PathUSD"));
+        assert_eq!(
+            (coverage.contracts[0].logs, coverage.contracts[0].events),
+            (3, 2)
+        );
+        assert_eq!(coverage.contracts[1].name, None);
+
+        assert_eq!(coverage.events.len(), 3);
+        let top = &coverage.events[0];
+        assert_eq!(top.signature, Some("Transfer(address,address,uint256)"));
+        assert_eq!((top.logs, top.contracts), (3, 2));
+        assert_eq!(top.tasks.len(), 3);
+        assert!(coverage.events.iter().any(|e| e.topic0.is_none()));
+        assert!(
+            coverage
+                .events
+                .iter()
+                .any(|e| e.signature.is_none() && e.topic0.is_some())
+        );
+
+        let table = coverage.render();
+        assert!(table.starts_with("2 contracts, 3 event signatures"));
+        assert!(table.contains("Transfer(address,address,uint256)"));
+    }
+}
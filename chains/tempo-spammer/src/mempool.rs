@@ -0,0 +1,267 @@
+//! Mempool - Unconfirmed transactions per pool wallet
+//!
+//! A wallet whose transactions stop confirming (an underpriced send, a nonce
+//! gap, a congested node) keeps being leased, and every task piles another
+//! transaction on top of the stuck ones. [`PendingMonitor`] counts each
+//! wallet's unconfirmed transactions and the worker loop skips wallets at
+//! `max_pending` until their transactions land:
+//!
+//! ```toml
+//! [mempool]
+//! enabled = true
+//! max_pending = 4      # skip wallets with 4 or more unconfirmed transactions
+//! refresh_secs = 10
+//! source = "auto"      # txpool_contentFrom, else pending minus latest nonce
+//! ```
+//!
+//! Wallets join the monitor when a worker first leases them; every
+//! `refresh_secs` a background task polls all known wallets, `concurrency` at
+//! a time. With `source = "auto"` the first failing `txpool_contentFrom`
+//! switches the monitor to nonces for the rest of the run. The nonce count
+//! misses transactions queued behind a gap, the txpool count includes them.
+//!
+//! A skipped lease is released with its usual cooldown, so the worker moves
+//! on to another wallet. The runner logs the backed-up wallets and skipped
+//! leases every 30 seconds.
+
+use crate::config::{MempoolSettings, PendingSource};
+use alloy::providers::Provider;
+use alloy_primitives::Address;
+use anyhow::{Context, Result};
+use futures::StreamExt;
+use std::collections::HashMap;
+use std::future::IntoFuture;
+use std::sync::Mutex;
+use std::sync::atomic::{AtomicBool, AtomicU64, Ordering};
+use std::time::Duration;
+use tracing::{debug, info};
+
+/// Unconfirmed transaction counts of the wallets leased so far
+#[derive(Debug)]
+pub struct PendingMonitor {
+    settings: MempoolSettings,
+    /// Last count per wallet, 0 until first polled
+    pending: Mutex<HashMap<Address, u64>>,
+    /// Whether polls still go to `txpool_contentFrom`
+    use_txpool: AtomicBool,
+    /// Leases given up because their wallet was backed up
+    skipped: AtomicU64,
+}
+
+impl PendingMonitor {
+    pub fn new(settings: MempoolSettings) -> Self {
+        Self {
+            use_txpool: AtomicBool::new(settings.source != PendingSource::Nonce),
+            settings,
+            pending: Mutex::new(HashMap::new()),
+            skipped: AtomicU64::new(0),
+        }
+    }
+
+    /// Adds a leased wallet to the polled set
+    pub fn register(&self, wallet: Address) {
+        self.pending.lock().unwrap().entry(wallet).or_insert(0);
+    }
+
+    /// Last polled unconfirmed count of `wallet`
+    pub fn pending(&self, wallet: Address) -> u64 {
+        self.pending
+            .lock()
+            .unwrap()
+            .get(&wallet)
+            .copied()
+            .unwrap_or(0)
+    }
+
+    /// Whether the worker holding `wallet` should leave it alone for now
+    ///
+    /// Counts a skipped lease when it should.
+    pub fn is_backed_up(&self, wallet: Address) -> bool {
+        let backed_up = self.pending(wallet) >= self.settings.max_pending;
+        if backed_up {
+            self.skipped.fetch_add(1, Ordering::Relaxed);
+        }
+        backed_up
+    }
+
+    /// Stores a polled count, logging wallets that cross `max_pending`
+    pub fn record(&self, wallet: Address, pending: u64) {
+        let previous = self
+            .pending
+            .lock()
+            .unwrap()
+            .insert(wallet, pending)
+            .unwrap_or(0);
+        let limit = self.settings.max_pending;
+        if pending >= limit && previous < limit {
+            info!(target: "task_result", "Wallet {} has {} unconfirmed transactions, skipping it", wallet, pending);
+        } else if pending < limit && previous >= limit {
+            debug!(
+                "Wallet {} down to {} unconfirmed transactions",
+                wallet, pending
+            );
+        }
+    }
+
+    /// Wallets at `max_pending` or above
+    pub fn backed_up(&self) -> usize {
+        let limit = self.settings.max_pending;
+        self.pending
+            .lock()
+            .unwrap()
+            .values()
+            .filter(|pending| **pending >= limit)
+            .count()
+    }
+
+    /// Leases skipped so far
+    pub fn skipped(&self) -> u64 {
+        self.skipped.load(Ordering::Relaxed)
+    }
+
+    /// Polls every known wallet once, returning how many are backed up
+    pub async fn refresh<P: Provider + ?Sized>(&self, provider: &P) -> usize {
+        let wallets: Vec<Address> = self.pending.lock().unwrap().keys().copied().collect();
+        let polls = futures::stream::iter(wallets)
+            .map(|wallet| async move { (wallet, self.poll(provider, wallet).await) })
+            .buffer_unordered(self.settings.concurrency.max(1))
+            .collect::<Vec<_>>()
+            .await;
+        for (wallet, polled) in polls {
+            match polled {
+                Ok(pending) => self.record(wallet, pending),
+                // Keeps the last count rather than guessing
+                Err(e) => debug!("Pending count of {} failed: {:#}", wallet, e),
+            }
+        }
+        self.backed_up()
+    }
+
+    /// Refreshes every `refresh_secs` until the task is aborted
+    pub async fn run<P: Provider + ?Sized>(&self, provider: &P) {
+        let mut interval = tokio::time::interval(Duration::from_secs(self.settings.refresh_secs));
+        loop {
+            interval.tick().await;
+            self.refresh(provider).await;
+        }
+    }
+
+    async fn poll<P: Provider + ?Sized>(&self, provider: &P, wallet: Address) -> Result<u64> {
+        if self.use_txpool.load(Ordering::Relaxed) {
+            match txpool_pending(provider, wallet).await {
+                Ok(pending) => return Ok(pending),
+                Err(e) if self.settings.source == PendingSource::Auto => {
+                    if self.use_txpool.swap(false, Ordering::Relaxed) {
+                        info!(
+                            "txpool API unavailable ({:#}), counting unconfirmed transactions by nonce",
+                            e
+                        );
+                    }
+                }
+                Err(e) => return Err(e),
+            }
+        }
+        nonce_gap(provider, wallet).await
+    }
+}
+
+/// Transactions `wallet` sent that are not in a block yet, by nonce
+///
+/// # Errors
+///
+/// Fails when either `eth_getTransactionCount` fails.
+pub async fn nonce_gap<P: Provider + ?Sized>(provider: &P, wallet: Address) -> Result<u64> {
+    let (pending, latest) = tokio::try_join!(
+        provider
+            .get_transaction_count(wallet)
+            .pending()
+            .into_future(),
+        provider
+            .get_transaction_count(wallet)
+            .latest()
+            .into_future(),
+    )
+    .context("Failed to read transaction counts")?;
+    Ok(pending.saturating_sub(latest))
+}
+
+/// Pending and queued transactions of `wallet` in the node's txpool
+///
+/// # Errors
+///
+/// Fails when the node does not serve `txpool_contentFrom`.
+pub async fn txpool_pending<P: Provider + ?Sized>(provider: &P, wallet: Address) -> Result<u64> {
+    let content: serde_json::Value = provider
+        .client()
+        .request("txpool_contentFrom", (wallet,))
+        .await
+        .context("txpool_contentFrom failed")?;
+    Ok(count_txpool(&content))
+}
+
+/// Transactions in a `txpool_contentFrom` result
+pub fn count_txpool(content: &serde_json::Value) -> u64 {
+    ["pending", "queued"]
+        .iter()
+        .filter_map(|kind| content.get(kind)?.as_object())
+        .map(|txs| txs.len() as u64)
+        .sum()
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use crate::testing::MockTempoClient;
+
+    fn settings(max_pending: u64, source: PendingSource) -> MempoolSettings {
+        MempoolSettings {
+            enabled: true,
+            max_pending,
+            source,
+            ..MempoolSettings::default()
+        }
+    }
+
+    #[test]
+    fn test_backed_up_wallets_are_skipped() {
+        let monitor = PendingMonitor::new(settings(3, PendingSource::Nonce));
+        let (busy, idle) = (Address::repeat_byte(1), Address::repeat_byte(2));
+        monitor.register(busy);
+        monitor.register(idle);
+
+        monitor.record(busy, 2);
+        assert!(!monitor.is_backed_up(busy));
+        monitor.record(busy, 3);
+        assert!(monitor.is_backed_up(busy));
+        assert!(!monitor.is_backed_up(idle));
+        assert_eq!((monitor.backed_up(), monitor.skipped()), (1, 1));
+
+        monitor.record(busy, 0);
+        assert!(!monitor.is_backed_up(busy));
+        assert_eq!(monitor.backed_up(), 0);
+    }
+
+    #[test]
+    fn test_txpool_content_counts_pending_and_queued() {
+        let content = serde_json::json!({
+            "pending": { "7": {}, "8": {} },
+            "queued": { "10": {} },
+        });
+        assert_eq!(count_txpool(&content), 3);
+        assert_eq!(count_txpool(&serde_json::json!({ "pending": {} })), 0);
+    }
+
+    #[tokio::test]
+    async fn test_auto_falls_back_to_nonces() {
+        let mock = MockTempoClient::default();
+        let client = mock.client();
+        let monitor = PendingMonitor::new(settings(1, PendingSource::Auto));
+        monitor.register(client.address());
+
+        // The mock has no txpool API and equal pending and latest nonces
+        assert_eq!(monitor.refresh(client.provider()).await, 0);
+        assert_eq!(monitor.refresh(client.provider()).await, 0);
+        assert_eq!(mock.count("txpool_contentFrom"), 1);
+        assert_eq!(mock.count("eth_getTransactionCount"), 4);
+    }
+}
@@ -0,0 +1,30 @@
+//! Network - System contract addresses of the Tempo network in use
+//!
+//! The `[contracts]` address book for code without a task context, such as
+//! the token helpers, prices and budgets; tasks read the same addresses with
+//! [`TaskContext::contracts`](crate::tasks::TaskContext::contracts). Either
+//! way a config profile can point them at another network's deployment:
+//!
+//! ```toml
+//! [profiles.allegro.contracts]
+//! faucet = "0x4200000000000000000000000000000000000019"
+//! stablecoin_dex = "0xdec0000000000000000000000000000000000000"
+//! ```
+//!
+//! The binaries call [`configure`] once the config is loaded; until then
+//! the public testnets' predeploys are used.
+
+use crate::config::SystemContracts;
+use std::sync::RwLock;
+
+static CONTRACTS: RwLock<SystemContracts> = RwLock::new(SystemContracts::TESTNET);
+
+/// Contract addresses of the configured network
+pub fn contracts() -> SystemContracts {
+    *CONTRACTS.read().unwrap()
+}
+
+/// Applies `[contracts]`
+pub fn configure(contracts: &SystemContracts) {
+    *CONTRACTS.write().unwrap() = *contracts;
+}
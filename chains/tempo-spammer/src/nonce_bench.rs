@@ -0,0 +1,440 @@
+//! Nonce Bench - Measuring the robust nonce manager under load
+//!
+//! `tempo-nonce-bench` drives [`RobustNonceManager`] the way the client pool
+//! does, without sending anything: workers lease a wallet, reserve a nonce
+//! from the wallet's shard, and hand the wallet back after the cooldown.
+//! Sequences are initialized from the live RPC, so reservations that miss
+//! the cache pay a real `eth_getTransactionCount` round trip.
+//!
+//! Each round injects two kinds of trouble:
+//!
+//! - `error_rate`: the send is rejected as "nonce too low" with the node
+//!   `ahead` nonces past the attempt. Recovery re-reads the count, calls
+//!   `handle_nonce_error` and reserves again; the time until the retry holds
+//!   a valid nonce is the recovery latency.
+//! - `release_rate`: the send fails before reaching the node and the nonce is
+//!   released. The time until that nonce is reserved again is the gap-fill
+//!   time, which is mostly the wallet's cooldown.
+//!
+//! A round reports reservation throughput and latency percentiles, and
+//! counts nonces confirmed twice or retried below the node's count, which
+//! should both stay at zero:
+//!
+//! ```bash
+//! tempo-nonce-bench --shards 8,16,32 --cooldown-ms 300,800 --duration-secs 20
+//! ```
+
+use crate::RobustNonceManager;
+use alloy_primitives::{Address, keccak256};
+use anyhow::{Context, Result};
+use core_logic::NonceSource;
+use serde::Serialize;
+use std::collections::{HashMap, HashSet, VecDeque};
+use std::sync::{Arc, Mutex};
+use std::time::{Duration, Instant};
+
+/// Shape of one benchmark round
+#[derive(Debug, Clone, Serialize)]
+pub struct BenchSettings {
+    pub workers: usize,
+    pub wallets: usize,
+    pub shard_count: usize,
+    /// Pause before a used wallet can be leased again
+    pub cooldown: Duration,
+    pub duration: Duration,
+    /// Share of sends rejected as "nonce too low"
+    pub error_rate: f64,
+    /// Share of sends that fail before reaching the node
+    pub release_rate: f64,
+    /// How far the node is past the attempt on an injected error
+    pub ahead: u64,
+}
+
+impl Default for BenchSettings {
+    fn default() -> Self {
+        Self {
+            workers: 10,
+            wallets: 100,
+            shard_count: 16,
+            cooldown: Duration::from_millis(800),
+            duration: Duration::from_secs(10),
+            error_rate: 0.01,
+            release_rate: 0.02,
+            ahead: 2,
+        }
+    }
+}
+
+/// Percentiles of one kind of wait, in milliseconds
+#[derive(Debug, Clone, Copy, Default, PartialEq, Serialize)]
+pub struct LatencySummary {
+    pub count: usize,
+    pub p50_ms: f64,
+    pub p95_ms: f64,
+    pub p99_ms: f64,
+    pub max_ms: f64,
+}
+
+impl LatencySummary {
+    pub fn from_samples(mut samples: Vec<Duration>) -> Self {
+        if samples.is_empty() {
+            return Self::default();
+        }
+        samples.sort_unstable();
+        let at = |p: f64| {
+            let index = ((samples.len() - 1) as f64 * p).round() as usize;
+            samples[index].as_secs_f64() * 1000.0
+        };
+        Self {
+            count: samples.len(),
+            p50_ms: at(0.50),
+            p95_ms: at(0.95),
+            p99_ms: at(0.99),
+            max_ms: at(1.0),
+        }
+    }
+}
+
+/// Measurements of one round
+#[derive(Debug, Clone, Serialize)]
+pub struct RoundReport {
+    pub settings: BenchSettings,
+    pub elapsed_secs: f64,
+    pub reservations: u64,
+    pub reservations_per_sec: f64,
+    /// `reserve_nonce`, including the RPC read on a cache miss
+    pub reserve: LatencySummary,
+    /// Waiting for a wallet out of cooldown
+    pub wallet_wait: LatencySummary,
+    pub rpc: LatencySummary,
+    pub recovery: LatencySummary,
+    pub gap_fill: LatencySummary,
+    /// Released nonces not reserved again before the round ended
+    pub gaps_open: usize,
+    /// Nonces confirmed twice for the same wallet
+    pub duplicates: u64,
+    /// Retries after an injected error that got a nonce below the node's count
+    pub stale_retries: u64,
+}
+
+#[derive(Default)]
+struct Samples {
+    reserve: Vec<Duration>,
+    wallet_wait: Vec<Duration>,
+    rpc: Vec<Duration>,
+    recovery: Vec<Duration>,
+    gap_fill: Vec<Duration>,
+    duplicates: u64,
+    stale_retries: u64,
+}
+
+struct Round<'a, S: ?Sized> {
+    source: &'a S,
+    settings: &'a BenchSettings,
+    managers: Vec<Arc<RobustNonceManager>>,
+    addresses: Vec<Address>,
+    free: Arc<Mutex<VecDeque<usize>>>,
+    /// Released nonces by wallet, with the time they were released
+    gaps: Mutex<HashMap<(usize, u64), Instant>>,
+    confirmed: Mutex<HashMap<usize, HashSet<u64>>>,
+    samples: Mutex<Samples>,
+}
+
+/// Addresses nobody holds a key for, so their counts never move
+pub fn bench_addresses(count: usize) -> Vec<Address> {
+    (0..count)
+        .map(|i| Address::from_slice(&keccak256(format!("tempo-nonce-bench:{}", i))[12..]))
+        .collect()
+}
+
+/// Runs one round against `source`
+///
+/// # Errors
+///
+/// Fails when `source` cannot be read; a benchmark with missing RPC reads
+/// would under-report every latency.
+pub async fn run_round<S>(source: &S, settings: &BenchSettings) -> Result<RoundReport>
+where
+    S: NonceSource<Address, Error = anyhow::Error> + ?Sized,
+{
+    let round = Round {
+        source,
+        settings,
+        managers: (0..settings.shard_count.max(1))
+            .map(|_| Arc::new(RobustNonceManager::new()))
+            .collect(),
+        addresses: bench_addresses(settings.wallets.max(1)),
+        free: Arc::new(Mutex::new((0..settings.wallets.max(1)).collect())),
+        gaps: Mutex::new(HashMap::new()),
+        confirmed: Mutex::new(HashMap::new()),
+        samples: Mutex::new(Samples::default()),
+    };
+
+    let start = Instant::now();
+    let deadline = start + settings.duration;
+    let workers = (0..settings.workers.max(1)).map(|_| round.work(deadline));
+    let reservations: u64 = futures::future::try_join_all(workers)
+        .await?
+        .into_iter()
+        .sum();
+    let elapsed = start.elapsed().as_secs_f64();
+
+    let gaps_open = round.gaps.lock().unwrap().len();
+    let samples = round.samples.into_inner().unwrap();
+    Ok(RoundReport {
+        settings: settings.clone(),
+        elapsed_secs: elapsed,
+        reservations,
+        reservations_per_sec: reservations as f64 / elapsed.max(f64::EPSILON),
+        reserve: LatencySummary::from_samples(samples.reserve),
+        wallet_wait: LatencySummary::from_samples(samples.wallet_wait),
+        rpc: LatencySummary::from_samples(samples.rpc),
+        recovery: LatencySummary::from_samples(samples.recovery),
+        gap_fill: LatencySummary::from_samples(samples.gap_fill),
+        gaps_open,
+        duplicates: samples.duplicates,
+        stale_retries: samples.stale_retries,
+    })
+}
+
+impl<S> Round<'_, S>
+where
+    S: NonceSource<Address, Error = anyhow::Error> + ?Sized,
+{
+    /// One worker's loop until `deadline`, returning its reservations
+    async fn work(&self, deadline: Instant) -> Result<u64> {
+        let mut reservations = 0;
+        while let Some(wallet) = self.lease(deadline).await {
+            reservations += self.send(wallet).await?;
+            self.release(wallet);
+        }
+        Ok(reservations)
+    }
+
+    async fn lease(&self, deadline: Instant) -> Option<usize> {
+        let waiting = Instant::now();
+        loop {
+            if Instant::now() >= deadline {
+                return None;
+            }
+            if let Some(wallet) = self.free.lock().unwrap().pop_front() {
+                self.samples
+                    .lock()
+                    .unwrap()
+                    .wallet_wait
+                    .push(waiting.elapsed());
+                return Some(wallet);
+            }
+            tokio::time::sleep(Duration::from_millis(1)).await;
+        }
+    }
+
+    fn release(&self, wallet: usize) {
+        let cooldown = self.settings.cooldown;
+        if cooldown.is_zero() {
+            self.free.lock().unwrap().push_back(wallet);
+            return;
+        }
+        let free = self.free.clone();
+        tokio::spawn(async move {
+            tokio::time::sleep(cooldown).await;
+            free.lock().unwrap().push_back(wallet);
+        });
+    }
+
+    /// One simulated send from `wallet`, returning the reservations it took
+    async fn send(&self, wallet: usize) -> Result<u64> {
+        let manager = &self.managers[wallet % self.managers.len()];
+        let address = self.addresses[wallet];
+
+        let started = Instant::now();
+        let reservation = self.reserve(manager, address).await?;
+        self.samples.lock().unwrap().reserve.push(started.elapsed());
+        let nonce = reservation.nonce;
+        if let Some(released) = self.gaps.lock().unwrap().remove(&(wallet, nonce)) {
+            self.samples
+                .lock()
+                .unwrap()
+                .gap_fill
+                .push(released.elapsed());
+        }
+
+        let roll = fastrand::f64();
+        if roll < self.settings.error_rate {
+            // Rejected by the node, which is `ahead` nonces further
+            let rejected = Instant::now();
+            reservation.mark_submitted().await;
+            let actual_next = self.read(address).await?.max(nonce + self.settings.ahead);
+            manager
+                .handle_nonce_error(address, nonce, actual_next)
+                .await;
+            // Released nonces below the node's count are gone, not open
+            self.gaps
+                .lock()
+                .unwrap()
+                .retain(|&(w, n), _| w != wallet || n >= actual_next);
+            let retry = self.reserve(manager, address).await?;
+            self.samples
+                .lock()
+                .unwrap()
+                .recovery
+                .push(rejected.elapsed());
+            if retry.nonce < actual_next {
+                self.samples.lock().unwrap().stale_retries += 1;
+            }
+            let retried = retry.nonce;
+            retry.mark_submitted().await;
+            self.confirm(manager, wallet, retried).await;
+            return Ok(2);
+        }
+        if roll < self.settings.error_rate + self.settings.release_rate {
+            // Failed before reaching the node
+            reservation.release().await;
+            self.gaps
+                .lock()
+                .unwrap()
+                .insert((wallet, nonce), Instant::now());
+            return Ok(1);
+        }
+        reservation.mark_submitted().await;
+        self.confirm(manager, wallet, nonce).await;
+        Ok(1)
+    }
+
+    /// Reserves a nonce, initializing the sequence from the RPC on a miss
+    async fn reserve(
+        &self,
+        manager: &Arc<RobustNonceManager>,
+        address: Address,
+    ) -> Result<crate::NonceReservation> {
+        if let Some(reservation) = manager.reserve_nonce(address).await {
+            return Ok(reservation);
+        }
+        let next = self.read(address).await?;
+        manager.initialize(address, next).await;
+        manager
+            .reserve_nonce(address)
+            .await
+            .context("No nonce after initializing the sequence")
+    }
+
+    async fn read(&self, address: Address) -> Result<u64> {
+        let started = Instant::now();
+        let next = self.source.next_nonce(address).await?;
+        self.samples.lock().unwrap().rpc.push(started.elapsed());
+        Ok(next)
+    }
+
+    async fn confirm(&self, manager: &RobustNonceManager, wallet: usize, nonce: u64) {
+        manager.confirm_nonce(self.addresses[wallet], nonce).await;
+        let fresh = self
+            .confirmed
+            .lock()
+            .unwrap()
+            .entry(wallet)
+            .or_default()
+            .insert(nonce);
+        if !fresh {
+            self.samples.lock().unwrap().duplicates += 1;
+        }
+    }
+}
+
+/// Table of rounds, one row each
+pub fn render(reports: &[RoundReport]) -> String {
+    let mut out = format!(
+        "{:>6} {:>8} {:>10} {:>12} {:>16} {:>16} {:>16} {:>16} {:>6} {:>5}\n",
+        "shards",
+        "cooldown",
+        "res/s",
+        "reservations",
+        "reserve p50/p99",
+        "wait p50/p99",
+        "recovery p50/max",
+        "gap fill p50/max",
+        "open",
+        "bad"
+    );
+    for report in reports {
+        let pair = |a: f64, b: f64| format!("{:.2}/{:.2}ms", a, b);
+        out.push_str(&format!(
+            "{:>6} {:>6}ms {:>10.0} {:>12} {:>16} {:>16} {:>16} {:>16} {:>6} {:>5}\n",
+            report.settings.shard_count,
+            report.settings.cooldown.as_millis(),
+            report.reservations_per_sec,
+            report.reservations,
+            pair(report.reserve.p50_ms, report.reserve.p99_ms),
+            pair(report.wallet_wait.p50_ms, report.wallet_wait.p99_ms),
+            pair(report.recovery.p50_ms, report.recovery.max_ms),
+            pair(report.gap_fill.p50_ms, report.gap_fill.max_ms),
+            report.gaps_open,
+            report.duplicates + report.stale_retries
+        ));
+    }
+    out
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use async_trait::async_trait;
+
+    /// Node whose every sequence is at nonce 7
+    struct FixedSource;
+
+    #[async_trait]
+    impl NonceSource for FixedSource {
+        type Error = anyhow::Error;
+
+        async fn next_nonce(&self, _address: Address) -> Result<u64> {
+            Ok(7)
+        }
+    }
+
+    fn settings(error_rate: f64, release_rate: f64) -> BenchSettings {
+        BenchSettings {
+            workers: 4,
+            wallets: 8,
+            shard_count: 2,
+            cooldown: Duration::ZERO,
+            duration: Duration::from_millis(200),
+            error_rate,
+            release_rate,
+            ahead: 3,
+        }
+    }
+
+    #[tokio::test]
+    async fn test_round_without_trouble() {
+        let report = run_round(&FixedSource, &settings(0.0, 0.0)).await.unwrap();
+        assert!(report.reservations > 0);
+        assert_eq!(report.reserve.count as u64, report.reservations);
+        // One read per wallet to initialize its sequence
+        assert_eq!(report.rpc.count, 8);
+        assert_eq!(report.recovery.count, 0);
+        assert_eq!((report.duplicates, report.stale_retries), (0, 0));
+    }
+
+    #[tokio::test]
+    async fn test_recovers_and_fills_gaps() {
+        let report = run_round(&FixedSource, &settings(0.2, 0.2)).await.unwrap();
+        assert!(report.recovery.count > 0);
+        assert!(report.gap_fill.count > 0);
+        assert_eq!((report.duplicates, report.stale_retries), (0, 0));
+        assert_eq!(render(&[report]).lines().count(), 2);
+    }
+
+    #[test]
+    fn test_latency_percentiles() {
+        let samples = (1..=100).map(Duration::from_millis).collect();
+        let summary = LatencySummary::from_samples(samples);
+        assert_eq!(summary.count, 100);
+        assert_eq!(summary.p50_ms, 51.0);
+        assert_eq!(summary.p99_ms, 99.0);
+        assert_eq!(summary.max_ms, 100.0);
+        assert_eq!(
+            LatencySummary::from_samples(Vec::new()),
+            LatencySummary::default()
+        );
+    }
+}
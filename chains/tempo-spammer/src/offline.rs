@@ -0,0 +1,516 @@
+//! Offline Signing - Pre-signed transaction batches broadcast later
+//!
+//! Signing needs the wallet keys but no network; broadcasting needs the
+//! network but no keys. `sign` builds and signs a batch of TIP-20 transfers
+//! on the machine holding the wallets, which may be air-gapped, and writes
+//! it to a file. `broadcast` streams that file to the RPC from the spamming
+//! host and tracks the receipts:
+//!
+//! ```bash
+//! # where the wallets are, no network needed
+//! tempo-spammer sign --wallets 0..10 --count 50 --nonce 0 --out batch-1.jsonl
+//! tempo-spammer sign --wallets 0..10 --count 50 --after batch-1.jsonl --out batch-2.jsonl
+//!
+//! # on the spamming host
+//! tempo-spammer broadcast batch-1.jsonl --rate 20 --receipts batch-1.receipts.jsonl
+//! ```
+//!
+//! Nonces are reserved from a [`RobustNonceManager`] seeded per wallet with
+//! `--nonce`, or for wallets that signed in the `--after` batch with the
+//! nonce following their last one there, so consecutive batches continue
+//! each other. A batch holds one [`SignedTx`] per line, the wallets taking
+//! turns, so that a streamed batch spreads over every wallet instead of
+//! queueing one wallet's nonces at a time.
+//!
+//! Broadcasting sends the lines in file order at `--rate`, each after the
+//! previous send returned, since a wallet's later nonces wait in the pool
+//! until the earlier ones arrive. A transaction the node already knows
+//! counts as sent, and one whose nonce is used counts as sent when it is
+//! the transaction that used it, so an interrupted batch can simply be
+//! broadcast again. Receipts are polled for up to `--settle-secs` afterwards.
+
+use crate::RobustNonceManager;
+use crate::burst::Inclusion;
+use crate::session_key::TempoSigner;
+use crate::tx_builder::FeeProfile;
+use alloy::network::ReceiptResponse;
+use alloy::providers::Provider;
+use alloy::signers::local::PrivateKeySigner;
+use alloy_primitives::{Address, B256, Bytes, TxKind, U256, keccak256};
+use alloy_sol_types::{SolCall, sol};
+use anyhow::{Context, Result, bail};
+use serde::{Deserialize, Serialize};
+use std::collections::HashMap;
+use std::fs::File;
+use std::io::{BufRead, BufReader, BufWriter, Write};
+use std::path::Path;
+use std::sync::Arc;
+use std::time::{Duration, Instant};
+use tempo_primitives::transaction::{Call, TempoTransaction};
+use tokio::time::MissedTickBehavior;
+use tracing::{debug, info};
+
+/// Delay between receipt polling passes
+const RECEIPT_POLL: Duration = Duration::from_secs(1);
+
+sol! {
+    interface ITIP20 {
+        function transfer(address to, uint256 amount) returns (bool);
+    }
+}
+
+/// One signed transaction of a batch file
+#[derive(Debug, Clone, PartialEq, Eq, Serialize, Deserialize)]
+pub struct SignedTx {
+    pub wallet: Address,
+    pub nonce: u64,
+    pub hash: B256,
+    /// EIP-2718 encoded Tempo transaction, as sent with `eth_sendRawTransaction`
+    pub raw: Bytes,
+}
+
+/// What `sign` puts into each transaction
+#[derive(Debug, Clone)]
+pub struct SignOptions {
+    pub chain_id: u64,
+    /// Transactions per wallet
+    pub count: usize,
+    /// First nonce of wallets without transactions in the previous batch
+    pub start_nonce: u64,
+    /// TIP-20 token transferred
+    pub token: Address,
+    /// Recipient, each wallet itself when unset
+    pub to: Option<Address>,
+    /// Amount in the token's base units
+    pub amount: U256,
+    pub gas_limit: u64,
+    pub fee_profile: FeeProfile,
+}
+
+/// Signs `count` transfers per wallet, the wallets taking turns
+///
+/// Nonces continue from `previous`, the batch signed before this one, for
+/// the wallets in it and start at `start_nonce` for the others. Needs no
+/// network.
+pub async fn sign_batch(
+    signers: &[PrivateKeySigner],
+    options: &SignOptions,
+    previous: &[SignedTx],
+) -> Result<Vec<SignedTx>> {
+    let nonces = Arc::new(RobustNonceManager::new());
+    let next = next_nonces(previous);
+    for signer in signers {
+        let address = signer.address();
+        let first = next.get(&address).copied().unwrap_or(options.start_nonce);
+        nonces.initialize(address, first).await;
+    }
+
+    let signers: Vec<(Address, TempoSigner)> = signers
+        .iter()
+        .map(|signer| (signer.address(), TempoSigner::Root(signer.clone())))
+        .collect();
+    let mut batch = Vec::with_capacity(signers.len() * options.count);
+    for _ in 0..options.count {
+        for (wallet, signer) in &signers {
+            let wallet = *wallet;
+            let reservation = nonces
+                .reserve_nonce(wallet)
+                .await
+                .with_context(|| format!("No nonce sequence for {}", wallet))?;
+
+            let transfer = ITIP20::transferCall {
+                to: options.to.unwrap_or(wallet),
+                amount: options.amount,
+            };
+            let tx = TempoTransaction {
+                chain_id: options.chain_id,
+                nonce: reservation.nonce,
+                max_fee_per_gas: options.fee_profile.max_fee_per_gas(),
+                max_priority_fee_per_gas: options.fee_profile.max_priority_fee_per_gas(),
+                gas_limit: options.gas_limit,
+                calls: vec![Call {
+                    to: TxKind::Call(options.token),
+                    value: U256::ZERO,
+                    input: transfer.abi_encode().into(),
+                }],
+                ..Default::default()
+            };
+            let signature = signer.sign(&tx).await?;
+            let mut raw = Vec::new();
+            tx.into_signed(signature).eip2718_encode(&mut raw);
+
+            batch.push(SignedTx {
+                wallet,
+                nonce: reservation.nonce,
+                hash: keccak256(&raw),
+                raw: raw.into(),
+            });
+            reservation.mark_submitted().await;
+        }
+    }
+    Ok(batch)
+}
+
+/// Nonce following each wallet's last one in `batch`
+pub fn next_nonces(batch: &[SignedTx]) -> HashMap<Address, u64> {
+    let mut next = HashMap::new();
+    for tx in batch {
+        let nonce = next.entry(tx.wallet).or_insert(0);
+        *nonce = (*nonce).max(tx.nonce + 1);
+    }
+    next
+}
+
+/// Writes `batch` to a new file, one transaction per line
+///
+/// # Errors
+///
+/// Fails when `path` already exists, so a signed batch is never replaced by
+/// one reusing its nonces.
+pub fn write_batch(path: &Path, batch: &[SignedTx]) -> Result<()> {
+    let file =
+        File::create_new(path).with_context(|| format!("Failed to create {}", path.display()))?;
+    let mut writer = BufWriter::new(file);
+    for tx in batch {
+        serde_json::to_writer(&mut writer, tx)?;
+        writer.write_all(b"\n")?;
+    }
+    writer
+        .flush()
+        .with_context(|| format!("Failed to write {}", path.display()))
+}
+
+/// Reads a batch written by [`write_batch`]
+///
+/// # Errors
+///
+/// Fails on a line that does not parse or whose hash does not match its raw
+/// transaction, naming the line.
+pub fn read_batch(path: &Path) -> Result<Vec<SignedTx>> {
+    let file = File::open(path).with_context(|| format!("Failed to open {}", path.display()))?;
+    let mut batch = Vec::new();
+    for (index, line) in BufReader::new(file).lines().enumerate() {
+        let line = line.with_context(|| format!("Failed to read {}", path.display()))?;
+        if line.trim().is_empty() {
+            continue;
+        }
+        let tx: SignedTx = serde_json::from_str(&line)
+            .with_context(|| format!("{} line {}", path.display(), index + 1))?;
+        if keccak256(&tx.raw) != tx.hash {
+            bail!(
+                "{} line {}: hash does not match the raw transaction",
+                path.display(),
+                index + 1
+            );
+        }
+        batch.push(tx);
+    }
+    Ok(batch)
+}
+
+/// Pace of a broadcast and how long it waits for receipts
+#[derive(Debug, Clone, Copy)]
+pub struct BroadcastOptions {
+    /// Transactions sent per second
+    pub rate: f64,
+    /// Wait for receipts after the last send
+    pub settle: Duration,
+}
+
+/// What came of one transaction of a broadcast batch
+#[derive(Debug, Clone, PartialEq, Eq)]
+pub enum Delivery {
+    /// Accepted by the node, with its inclusion once the receipts were polled
+    Sent(Inclusion),
+    /// Refused by the node with this error
+    Rejected(String),
+}
+
+/// Totals of a broadcast
+#[derive(Debug, Clone, Copy, Default, PartialEq, Eq)]
+pub struct BroadcastSummary {
+    pub sent: usize,
+    pub rejected: usize,
+    pub included: usize,
+    pub reverted: usize,
+    pub pending: usize,
+}
+
+impl BroadcastSummary {
+    pub fn from_deliveries(deliveries: &[Delivery]) -> Self {
+        let mut summary = Self::default();
+        for delivery in deliveries {
+            match delivery {
+                Delivery::Rejected(_) => summary.rejected += 1,
+                Delivery::Sent(inclusion) => {
+                    summary.sent += 1;
+                    match inclusion {
+                        Inclusion::Included { .. } => summary.included += 1,
+                        Inclusion::Reverted { .. } => summary.reverted += 1,
+                        Inclusion::Pending => summary.pending += 1,
+                    }
+                }
+            }
+        }
+        summary
+    }
+}
+
+/// A transaction and what came of it, as a line of the `--receipts` file
+#[derive(Debug, Clone, Serialize)]
+pub struct DeliveryRecord<'a> {
+    pub hash: B256,
+    pub wallet: Address,
+    pub nonce: u64,
+    /// included, reverted, pending or rejected
+    pub status: &'static str,
+    pub block: Option<u64>,
+    pub latency_ms: Option<u64>,
+    pub error: Option<&'a str>,
+}
+
+impl<'a> DeliveryRecord<'a> {
+    pub fn new(tx: &SignedTx, delivery: &'a Delivery) -> Self {
+        let (status, block, latency_ms, error) = match delivery {
+            Delivery::Sent(Inclusion::Included { block, latency_ms }) => {
+                ("included", Some(*block), Some(*latency_ms), None)
+            }
+            Delivery::Sent(Inclusion::Reverted { block, latency_ms }) => {
+                ("reverted", Some(*block), Some(*latency_ms), None)
+            }
+            Delivery::Sent(Inclusion::Pending) => ("pending", None, None, None),
+            Delivery::Rejected(error) => ("rejected", None, None, Some(error.as_str())),
+        };
+        Self {
+            hash: tx.hash,
+            wallet: tx.wallet,
+            nonce: tx.nonce,
+            status,
+            block,
+            latency_ms,
+            error,
+        }
+    }
+}
+
+/// Writes one [`DeliveryRecord`] per transaction of `batch`
+pub fn write_receipts(path: &Path, batch: &[SignedTx], deliveries: &[Delivery]) -> Result<()> {
+    let file =
+        File::create(path).with_context(|| format!("Failed to create {}", path.display()))?;
+    let mut writer = BufWriter::new(file);
+    for (tx, delivery) in batch.iter().zip(deliveries) {
+        serde_json::to_writer(&mut writer, &DeliveryRecord::new(tx, delivery))?;
+        writer.write_all(b"\n")?;
+    }
+    writer
+        .flush()
+        .with_context(|| format!("Failed to write {}", path.display()))
+}
+
+/// Sends `batch` in order at `options.rate`, then polls the receipts
+///
+/// Returns one [`Delivery`] per transaction, in batch order.
+pub async fn broadcast<P: Provider + ?Sized>(
+    provider: &P,
+    batch: &[SignedTx],
+    options: BroadcastOptions,
+) -> Vec<Delivery> {
+    let mut ticker = tokio::time::interval(Duration::from_secs_f64(1.0 / options.rate));
+    ticker.set_missed_tick_behavior(MissedTickBehavior::Delay);
+
+    let mut deliveries = Vec::with_capacity(batch.len());
+    let mut sent_at = vec![None; batch.len()];
+    for (index, tx) in batch.iter().enumerate() {
+        ticker.tick().await;
+        let started = Instant::now();
+        let delivery = match provider.send_raw_transaction(&tx.raw).await {
+            Ok(_) => Delivery::Sent(Inclusion::Pending),
+            Err(e) => {
+                let message = e.to_string();
+                let lower = message.to_lowercase();
+                if lower.contains("already known") {
+                    Delivery::Sent(Inclusion::Pending)
+                } else if lower.contains("nonce too low") {
+                    // Sent by an earlier broadcast of the batch, latency unknown
+                    match provider.get_transaction_receipt(tx.hash).await {
+                        Ok(Some(receipt)) => Delivery::Sent(inclusion(&receipt, 0)),
+                        _ => Delivery::Rejected(message),
+                    }
+                } else {
+                    Delivery::Rejected(message)
+                }
+            }
+        };
+        match &delivery {
+            Delivery::Sent(Inclusion::Pending) => sent_at[index] = Some(started),
+            Delivery::Rejected(error) => {
+                debug!("{} nonce {} rejected: {}", tx.wallet, tx.nonce, error)
+            }
+            Delivery::Sent(_) => {}
+        }
+        deliveries.push(delivery);
+    }
+
+    let summary = BroadcastSummary::from_deliveries(&deliveries);
+    info!(
+        "Broadcast {} transactions: {} sent, {} rejected, waiting up to {}s for receipts",
+        batch.len(),
+        summary.sent,
+        summary.rejected,
+        options.settle.as_secs()
+    );
+    await_receipts(provider, batch, &sent_at, &mut deliveries, options.settle).await;
+    deliveries
+}
+
+/// Polls receipts of the sent transactions until all landed or `settle` elapsed
+async fn await_receipts<P: Provider + ?Sized>(
+    provider: &P,
+    batch: &[SignedTx],
+    sent_at: &[Option<Instant>],
+    deliveries: &mut [Delivery],
+    settle: Duration,
+) {
+    let deadline = Instant::now() + settle;
+    loop {
+        for ((tx, sent_at), delivery) in batch.iter().zip(sent_at).zip(deliveries.iter_mut()) {
+            let Some(sent_at) = sent_at else {
+                continue;
+            };
+            if *delivery != Delivery::Sent(Inclusion::Pending) {
+                continue;
+            }
+            let Ok(Some(receipt)) = provider.get_transaction_receipt(tx.hash).await else {
+                continue;
+            };
+            *delivery = Delivery::Sent(inclusion(&receipt, sent_at.elapsed().as_millis() as u64));
+        }
+
+        let pending = deliveries.contains(&Delivery::Sent(Inclusion::Pending));
+        if !pending || Instant::now() >= deadline {
+            return;
+        }
+        tokio::time::sleep(RECEIPT_POLL).await;
+    }
+}
+
+fn inclusion<R: ReceiptResponse>(receipt: &R, latency_ms: u64) -> Inclusion {
+    let block = receipt.block_number().unwrap_or_default();
+    if receipt.status() {
+        Inclusion::Included { block, latency_ms }
+    } else {
+        Inclusion::Reverted { block, latency_ms }
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use crate::testing::{MockTempoClient, TEST_PRIVATE_KEY};
+
+    fn options(count: usize) -> SignOptions {
+        SignOptions {
+            chain_id: 42431,
+            count,
+            start_nonce: 7,
+            token: Address::repeat_byte(0x20),
+            to: None,
+            amount: U256::from(1),
+            gas_limit: 100_000,
+            fee_profile: FeeProfile::Normal,
+        }
+    }
+
+    fn signers() -> Vec<PrivateKeySigner> {
+        vec![
+            TEST_PRIVATE_KEY.parse().unwrap(),
+            PrivateKeySigner::random(),
+        ]
+    }
+
+    #[tokio::test]
+    async fn test_sign_batch_interleaves_wallets_and_continues_nonces() {
+        let signers = signers();
+        let (a, b) = (signers[0].address(), signers[1].address());
+
+        let first = sign_batch(&signers, &options(2), &[]).await.unwrap();
+        let order: Vec<(Address, u64)> = first.iter().map(|tx| (tx.wallet, tx.nonce)).collect();
+        assert_eq!(order, vec![(a, 7), (b, 7), (a, 8), (b, 8)]);
+        assert!(first.iter().all(|tx| keccak256(&tx.raw) == tx.hash));
+
+        // Only the first wallet signed before; the new one starts at --nonce
+        let previous = &first[..1];
+        let mut options = options(1);
+        options.start_nonce = 0;
+        let second = sign_batch(&signers, &options, previous).await.unwrap();
+        let nonces: Vec<u64> = second.iter().map(|tx| tx.nonce).collect();
+        assert_eq!(nonces, vec![8, 0]);
+    }
+
+    #[tokio::test]
+    async fn test_batch_file_round_trip() {
+        let dir = std::env::temp_dir().join(format!("offline-batch-{}", std::process::id()));
+        std::fs::create_dir_all(&dir).unwrap();
+        let path = dir.join("batch.jsonl");
+        let _ = std::fs::remove_file(&path);
+
+        let batch = sign_batch(&signers(), &options(2), &[]).await.unwrap();
+        write_batch(&path, &batch).unwrap();
+        assert_eq!(read_batch(&path).unwrap(), batch);
+        // A signed batch is never overwritten
+        assert!(write_batch(&path, &batch).is_err());
+
+        let mut tampered = batch.clone();
+        tampered[1].hash = B256::ZERO;
+        let tampered_path = dir.join("tampered.jsonl");
+        let _ = std::fs::remove_file(&tampered_path);
+        write_batch(&tampered_path, &tampered).unwrap();
+        let error = read_batch(&tampered_path).unwrap_err().to_string();
+        assert!(error.contains("line 2"), "{}", error);
+
+        std::fs::remove_dir_all(&dir).unwrap();
+    }
+
+    #[tokio::test]
+    async fn test_broadcast_tracks_receipts() {
+        let mock = MockTempoClient::default();
+        let client = mock.client();
+        let batch = sign_batch(&[client.signer.clone()], &options(4), &[])
+            .await
+            .unwrap();
+
+        mock.fail_next_send("already known");
+        mock.revert_next_receipt();
+        // The mock mines on send, so the first receipt pass finds them all
+        let broadcast_options = BroadcastOptions {
+            rate: 1000.0,
+            settle: Duration::ZERO,
+        };
+        let mut deliveries = broadcast(client.provider(), &batch[..2], broadcast_options).await;
+        mock.fail_next_send("insufficient funds for gas");
+        deliveries.extend(broadcast(client.provider(), &batch[2..], broadcast_options).await);
+
+        // The first send was already known and never reached the mock's pool
+        assert_eq!(deliveries[0], Delivery::Sent(Inclusion::Pending));
+        assert!(matches!(
+            deliveries[1],
+            Delivery::Sent(Inclusion::Reverted { .. })
+        ));
+        assert!(matches!(&deliveries[2], Delivery::Rejected(e) if e.contains("insufficient")));
+        assert!(matches!(
+            deliveries[3],
+            Delivery::Sent(Inclusion::Included { .. })
+        ));
+        assert_eq!(
+            BroadcastSummary::from_deliveries(&deliveries),
+            BroadcastSummary {
+                sent: 3,
+                rejected: 1,
+                included: 1,
+                reverted: 1,
+                pending: 1,
+            }
+        );
+        assert_eq!(mock.count("eth_sendRawTransaction"), 4);
+    }
+}
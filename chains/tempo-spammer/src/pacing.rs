@@ -0,0 +1,202 @@
+//! Worker Pacing - Keeping workers out of lockstep
+//!
+//! When every worker draws its pause from the same
+//! `task_interval_min..=task_interval_max` range and starts within the same
+//! two seconds, the workers move in and out of phase together and show up in
+//! chain metrics as waves of transactions. `[pacing]` gives each worker its
+//! own timing, derived from its worker id so a restart reproduces it:
+//!
+//! - **Phase offset**: worker `n` waits `frac(n·φ)` of `task_interval_max`
+//!   before its first task. The golden-ratio sequence spreads any number of
+//!   workers evenly over the interval.
+//! - **Interval jitter**: both interval bounds are scaled by a per-worker
+//!   factor within `1 ± interval_jitter`, so workers do not share a period.
+//! - **Arrivals**: `uniform` draws each pause between the worker's bounds;
+//!   `poisson` draws it from an exponential distribution with the bounds'
+//!   midpoint as mean, making every worker an independent Poisson process.
+//!
+//! ```toml
+//! [pacing]
+//! phase_offset = true
+//! interval_jitter = 0.25
+//! arrivals = "poisson"
+//! ```
+
+use crate::config::{ArrivalProcess, TempoSpammerConfig};
+use rand::Rng;
+use std::time::Duration;
+
+/// Fractional part of the golden ratio, used for phase offsets
+const PHASE_STEP: f64 = 0.618_033_988_749_894_9;
+
+/// Fractional part of √2, used for interval jitter so it does not follow the phase
+const JITTER_STEP: f64 = 0.414_213_562_373_095_1;
+
+/// Random start delay used when `phase_offset` is off
+const UNSTAGGERED_START_MS: u64 = 2000;
+
+/// Timing of one worker
+#[derive(Debug, Clone, PartialEq)]
+pub struct WorkerPacing {
+    /// Fixed delay before the first task, `None` for a random one
+    phase_offset: Option<Duration>,
+    min_ms: u64,
+    max_ms: u64,
+    arrivals: ArrivalProcess,
+}
+
+impl WorkerPacing {
+    /// Derives the timing of worker `worker_id` from `[pacing]` and the task interval
+    pub fn new(config: &TempoSpammerConfig, worker_id: u64) -> Self {
+        let settings = &config.pacing;
+        let phase_offset = settings.phase_offset.then(|| {
+            Duration::from_millis(
+                (spread(worker_id, PHASE_STEP) * config.task_interval_max as f64) as u64,
+            )
+        });
+
+        // Offset by one so worker 0 does not always get the shortest interval
+        let factor =
+            1.0 + settings.interval_jitter * (2.0 * spread(worker_id + 1, JITTER_STEP) - 1.0);
+        let min_ms = (config.task_interval_min as f64 * factor).round() as u64;
+        let max_ms = ((config.task_interval_max as f64 * factor).round() as u64).max(min_ms);
+
+        Self {
+            phase_offset,
+            min_ms,
+            max_ms,
+            arrivals: settings.arrivals,
+        }
+    }
+
+    /// Interval bounds of this worker in milliseconds
+    pub fn bounds(&self) -> (u64, u64) {
+        (self.min_ms, self.max_ms)
+    }
+
+    /// Delay before the worker's first task
+    pub fn start_delay<R: Rng>(&self, rng: &mut R) -> Duration {
+        self.phase_offset
+            .unwrap_or_else(|| Duration::from_millis(rng.gen_range(0..UNSTAGGERED_START_MS)))
+    }
+
+    /// Pause after a task
+    pub fn next_interval<R: Rng>(&self, rng: &mut R) -> Duration {
+        let ms = match self.arrivals {
+            ArrivalProcess::Uniform => rng.gen_range(self.min_ms..=self.max_ms),
+            ArrivalProcess::Poisson => {
+                let mean = (self.min_ms + self.max_ms) as f64 / 2.0;
+                // 1 - [0, 1) keeps ln() finite
+                let u: f64 = 1.0 - rng.r#gen::<f64>();
+                (-u.ln() * mean) as u64
+            }
+        };
+        Duration::from_millis(ms)
+    }
+}
+
+/// `frac(n * step)`, a low-discrepancy sequence in `[0, 1)`
+fn spread(n: u64, step: f64) -> f64 {
+    (n as f64 * step).fract()
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use crate::config::PacingSettings;
+    use rand::SeedableRng;
+    use rand::rngs::StdRng;
+
+    fn config(pacing: PacingSettings) -> TempoSpammerConfig {
+        let mut config: TempoSpammerConfig = toml::from_str(
+            "rpc_url = \"http://localhost:8545\"\nchain_id = 42431\nworker_count = 1\n\
+             default_gas_limit = 1\nmax_fee_per_gas = 1\npriority_fee_per_gas = 1\n\
+             task_interval_min = 1000\ntask_interval_max = 3000\ntask_timeout = 5\n",
+        )
+        .unwrap();
+        config.pacing = pacing;
+        config
+    }
+
+    #[test]
+    fn test_phase_offsets_spread_workers() {
+        let config = config(PacingSettings::default());
+        let mut rng = StdRng::seed_from_u64(1);
+        let mut offsets: Vec<u64> = (0..10)
+            .map(|id| {
+                WorkerPacing::new(&config, id)
+                    .start_delay(&mut rng)
+                    .as_millis() as u64
+            })
+            .collect();
+        offsets.sort_unstable();
+        assert_eq!(offsets[0], 0);
+        assert!(offsets.iter().all(|&ms| ms < 3000));
+        // No two workers start within 100ms of each other
+        assert!(
+            offsets.windows(2).all(|w| w[1] - w[0] > 100),
+            "{:?}",
+            offsets
+        );
+        // Derived from the worker id, not random
+        assert_eq!(WorkerPacing::new(&config, 7), WorkerPacing::new(&config, 7));
+    }
+
+    #[test]
+    fn test_interval_jitter_per_worker() {
+        let config = config(PacingSettings::default());
+        let bounds: Vec<_> = (0..8)
+            .map(|id| WorkerPacing::new(&config, id).bounds())
+            .collect();
+        for &(min, max) in &bounds {
+            assert!((750..=1250).contains(&min), "{:?}", bounds);
+            assert!((2250..=3750).contains(&max), "{:?}", bounds);
+            assert!(min < max);
+        }
+        let mut distinct = bounds.clone();
+        distinct.dedup();
+        assert_eq!(distinct.len(), bounds.len());
+
+        let off = config_without_jitter();
+        assert_eq!(WorkerPacing::new(&off, 3).bounds(), (1000, 3000));
+    }
+
+    fn config_without_jitter() -> TempoSpammerConfig {
+        config(PacingSettings {
+            phase_offset: false,
+            interval_jitter: 0.0,
+            ..Default::default()
+        })
+    }
+
+    #[test]
+    fn test_uniform_stays_within_bounds() {
+        let pacing = WorkerPacing::new(&config_without_jitter(), 0);
+        let mut rng = StdRng::seed_from_u64(2);
+        for _ in 0..1000 {
+            let ms = pacing.next_interval(&mut rng).as_millis();
+            assert!((1000..=3000).contains(&ms));
+        }
+        assert!(pacing.start_delay(&mut rng) < Duration::from_millis(2000));
+    }
+
+    #[test]
+    fn test_poisson_mean_is_midpoint() {
+        let pacing = WorkerPacing::new(
+            &config(PacingSettings {
+                interval_jitter: 0.0,
+                arrivals: ArrivalProcess::Poisson,
+                ..Default::default()
+            }),
+            0,
+        );
+        let mut rng = StdRng::seed_from_u64(3);
+        let samples: Vec<u128> = (0..20_000)
+            .map(|_| pacing.next_interval(&mut rng).as_millis())
+            .collect();
+        let mean = samples.iter().sum::<u128>() as f64 / samples.len() as f64;
+        assert!((1900.0..2100.0).contains(&mean), "mean {}", mean);
+        // Exponential pauses go below the uniform minimum
+        assert!(samples.iter().any(|&ms| ms < 1000));
+    }
+}
@@ -0,0 +1,190 @@
+//! Persona Engine - Per-wallet behavior profiles
+//!
+//! Wallets that all pick tasks from the same distribution, send similar
+//! amounts and run around the clock are easy to cluster. Personas give each
+//! wallet a profile defined in `[personas.<name>]`:
+//!
+//! - **weights** - multipliers on the task weights from `[tasks]`
+//!   (`0` disables a task for the persona)
+//! - **amount_scale** - multiplier on transfer amounts
+//! - **hours** - UTC window in which the wallet is active at all
+//!
+//! ```toml
+//! [personas.trader]
+//! share = 3
+//! weights = { "05_swap_stable" = 4.0, "11_limit_order" = 3.0 }
+//! hours = "06-22"
+//!
+//! [personas.collector]
+//! weights = { "14_nft_create_mint" = 5.0, "16_mint_random_nft" = 5.0 }
+//! amount_scale = 0.5
+//! ```
+//!
+//! # Assignment
+//!
+//! Wallets are spread over personas in proportion to `share`, using a hash of
+//! the wallet index. The mapping needs no state: a wallet keeps its persona
+//! across restarts and hosts as long as the `[personas]` section is
+//! unchanged. Without personas every wallet uses the plain task weights.
+
+use crate::config::{HourWindow, PersonaSettings};
+use alloy_primitives::U256;
+use std::collections::BTreeMap;
+use std::sync::Arc;
+
+/// Precision of [`Persona::scale_amount`]
+const SCALE_PRECISION: u64 = 1_000;
+
+/// A named behavior profile
+#[derive(Debug, Clone, PartialEq)]
+pub struct Persona {
+    pub name: String,
+    /// Task weight multipliers by task name
+    pub weights: BTreeMap<String, f64>,
+    pub amount_scale: f64,
+    pub hours: Option<HourWindow>,
+}
+
+impl Persona {
+    /// Multiplier for a task's weight, 1.0 unless configured
+    pub fn weight_multiplier(&self, task: &str) -> f64 {
+        self.weights.get(task).copied().unwrap_or(1.0)
+    }
+
+    /// Whether wallets with this persona act during the UTC `hour`
+    pub fn is_active(&self, hour: u32) -> bool {
+        self.hours.is_none_or(|window| window.contains(hour))
+    }
+
+    /// Applies `amount_scale` to a token amount
+    pub fn scale_amount(&self, amount: U256) -> U256 {
+        let factor = (self.amount_scale * SCALE_PRECISION as f64).round() as u64;
+        amount.saturating_mul(U256::from(factor)) / U256::from(SCALE_PRECISION)
+    }
+}
+
+/// Maps wallet indices onto personas
+#[derive(Debug, Clone, Default)]
+pub struct PersonaAssigner {
+    personas: Vec<Arc<Persona>>,
+    /// Cumulative share bounds, parallel to `personas`
+    bounds: Vec<u64>,
+}
+
+impl PersonaAssigner {
+    /// Builds the assigner from `[personas]`; personas with share 0 are never assigned
+    pub fn new(settings: &BTreeMap<String, PersonaSettings>) -> Self {
+        let mut assigner = Self::default();
+        let mut total = 0u64;
+        for (name, persona) in settings {
+            if persona.share == 0 {
+                continue;
+            }
+            total += u64::from(persona.share);
+            assigner.bounds.push(total);
+            assigner.personas.push(Arc::new(Persona {
+                name: name.clone(),
+                weights: persona.weights.clone(),
+                amount_scale: persona.amount_scale,
+                hours: persona.hours,
+            }));
+        }
+        assigner
+    }
+
+    pub fn is_empty(&self) -> bool {
+        self.personas.is_empty()
+    }
+
+    /// Persona of a wallet, `None` when no personas are configured
+    pub fn for_wallet(&self, wallet_idx: usize) -> Option<Arc<Persona>> {
+        let total = *self.bounds.last()?;
+        let slot = splitmix64(wallet_idx as u64) % total;
+        let position = self.bounds.partition_point(|&bound| bound <= slot);
+        self.personas.get(position).cloned()
+    }
+
+    /// Number of wallets per persona among the first `wallets`, in config order
+    pub fn distribution(&self, wallets: usize) -> Vec<(String, usize)> {
+        let mut counts: Vec<(String, usize)> = self
+            .personas
+            .iter()
+            .map(|persona| (persona.name.clone(), 0))
+            .collect();
+        for wallet_idx in 0..wallets {
+            if let Some(persona) = self.for_wallet(wallet_idx) {
+                if let Some(entry) = counts.iter_mut().find(|(name, _)| *name == persona.name) {
+                    entry.1 += 1;
+                }
+            }
+        }
+        counts
+    }
+}
+
+/// Stable 64-bit mix so neighbouring wallet indices land on unrelated personas
+fn splitmix64(value: u64) -> u64 {
+    let mut z = value.wrapping_add(0x9E37_79B9_7F4A_7C15);
+    z = (z ^ (z >> 30)).wrapping_mul(0xBF58_476D_1CE4_E5B9);
+    z = (z ^ (z >> 27)).wrapping_mul(0x94D0_49BB_1331_11EB);
+    z ^ (z >> 31)
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    fn settings(share: u32, amount_scale: f64) -> PersonaSettings {
+        PersonaSettings {
+            share,
+            weights: BTreeMap::new(),
+            amount_scale,
+            hours: None,
+        }
+    }
+
+    #[test]
+    fn test_assignment_is_deterministic_and_follows_shares() {
+        let config = BTreeMap::from([
+            ("collector".to_string(), settings(1, 1.0)),
+            ("idle".to_string(), settings(0, 1.0)),
+            ("trader".to_string(), settings(3, 1.0)),
+        ]);
+        let assigner = PersonaAssigner::new(&config);
+        let again = PersonaAssigner::new(&config);
+        for wallet_idx in 0..100 {
+            assert_eq!(
+                assigner.for_wallet(wallet_idx),
+                again.for_wallet(wallet_idx)
+            );
+        }
+
+        let counts = assigner.distribution(4000);
+        assert_eq!(counts[0].0, "collector");
+        assert_eq!(counts[1].0, "trader");
+        assert_eq!(counts[0].1 + counts[1].1, 4000);
+        // Roughly 1:3, never the zero-share persona
+        assert!((800..1200).contains(&counts[0].1), "{:?}", counts);
+
+        assert!(
+            PersonaAssigner::new(&BTreeMap::new())
+                .for_wallet(0)
+                .is_none()
+        );
+    }
+
+    #[test]
+    fn test_persona_biases() {
+        let persona = Persona {
+            name: "degen".to_string(),
+            weights: BTreeMap::from([("05_swap_stable".to_string(), 4.0)]),
+            amount_scale: 2.5,
+            hours: Some("20-04".parse().unwrap()),
+        };
+        assert_eq!(persona.weight_multiplier("05_swap_stable"), 4.0);
+        assert_eq!(persona.weight_multiplier("03_send_token"), 1.0);
+        assert!(persona.is_active(23) && persona.is_active(3));
+        assert!(!persona.is_active(12));
+        assert_eq!(persona.scale_amount(U256::from(1_000)), U256::from(2_500));
+    }
+}
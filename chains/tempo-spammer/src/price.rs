@@ -0,0 +1,363 @@
+//! Price Feed - USD values for gas and token spend
+//!
+//! Gas costs in wei and token amounts in base units mean little outside the
+//! team running the spammer. With `[price] enabled` the reports convert them
+//! to USD: `db stats` adds a USD column to the gas table, `db budget` values
+//! each token's spend, bundles carry USD figures in `metrics.json` and
+//! `report.html`, and the run log shows the gas spent so far.
+//!
+//! ```toml
+//! [price]
+//! enabled = true
+//! source = "http"
+//! url = "https://api.coingecko.com/api/v3/simple/price?ids=ethereum&vs_currencies=usd"
+//! json_pointer = "/ethereum/usd"
+//! ```
+//!
+//! # Sources
+//!
+//! The price of the fee unit (10^18 wei) comes from one of
+//!
+//! - `fixed`: `native_usd`, 1.0 by default since Tempo prices gas in
+//!   attodollars
+//! - `http`: the number, or numeric string, at `json_pointer` in the JSON
+//!   document at `url`
+//! - `oracle`: `latestRoundData` and `decimals` of a Chainlink-style
+//!   aggregator at `oracle`, read through `[price] rpc_url` or `rpc_url`
+//!
+//! Token prices are fixed: `tokens` maps symbols or addresses to USD per
+//! whole token, and the system USD tokens count as 1.0 unless listed.
+//!
+//! # Caching
+//!
+//! During a run the quote lives in the process-wide [`feed`] and a background
+//! task refreshes it every `refresh_secs`, hourly by default. Readers only
+//! take the cached quote, so nothing on the task path waits for the price
+//! source, and a failed refresh keeps the previous quote. The `db` and
+//! `bundle` commands fetch one quote when they start.
+
+use crate::budget::resolve_token;
+use crate::config::{PriceSettings, PriceSource};
+use crate::tasks::tempo_tokens::TempoTokens;
+use alloy::providers::{Provider, ProviderBuilder};
+use alloy::rpc::types::TransactionRequest;
+use alloy_primitives::{Address, Bytes, I256, U256};
+use alloy_sol_types::{SolCall, sol};
+use anyhow::{Context, Result, bail};
+use chrono::{DateTime, Utc};
+use std::collections::HashMap;
+use std::str::FromStr;
+use std::sync::{Arc, OnceLock, RwLock};
+use std::time::Duration;
+use tokio::task::JoinHandle;
+use tracing::{debug, info, warn};
+
+sol! {
+    interface IAggregatorV3 {
+        function decimals() external view returns (uint8);
+        function latestRoundData() external view returns (
+            uint80 roundId,
+            int256 answer,
+            uint256 startedAt,
+            uint256 updatedAt,
+            uint80 answeredInRound
+        );
+    }
+}
+
+/// Timeout for each price download or aggregator read
+const FETCH_TIMEOUT: Duration = Duration::from_secs(15);
+
+/// Wei per fee unit
+const WEI_PER_UNIT: f64 = 1e18;
+
+static FEED: OnceLock<Arc<PriceFeed>> = OnceLock::new();
+
+/// The process-wide price feed, empty until a quote is set
+pub fn feed() -> &'static Arc<PriceFeed> {
+    FEED.get_or_init(|| Arc::new(PriceFeed::default()))
+}
+
+/// USD prices at one point in time
+#[derive(Debug, Clone, PartialEq)]
+pub struct PriceQuote {
+    /// USD per 10^18 wei of fees
+    pub native_usd: f64,
+    /// USD per whole token
+    pub tokens: HashMap<Address, f64>,
+    pub token_decimals: u8,
+    /// Where `native_usd` came from, e.g. `oracle 0x...`
+    pub source: String,
+    pub fetched_at: DateTime<Utc>,
+}
+
+impl PriceQuote {
+    /// Quote with `native_usd` for the fee unit and the token prices of `settings`
+    pub fn new(settings: &PriceSettings, native_usd: f64, source: impl Into<String>) -> Self {
+        let mut tokens: HashMap<Address, f64> = TempoTokens::get_system_tokens()
+            .into_iter()
+            .map(|token| (token.address, 1.0))
+            .collect();
+        for (key, usd) in &settings.tokens {
+            match resolve_token(key) {
+                Ok(token) => {
+                    tokens.insert(token, *usd);
+                }
+                Err(e) => warn!("Ignoring price of {}: {:#}", key, e),
+            }
+        }
+        Self {
+            native_usd,
+            tokens,
+            token_decimals: settings.token_decimals,
+            source: source.into(),
+            fetched_at: Utc::now(),
+        }
+    }
+
+    /// USD value of a fee total in wei
+    pub fn gas_usd(&self, cost_wei: f64) -> f64 {
+        cost_wei / WEI_PER_UNIT * self.native_usd
+    }
+
+    /// USD value of `amount` base units of `token`, `None` for unpriced tokens
+    pub fn token_usd(&self, token: Address, amount: U256) -> Option<f64> {
+        let usd = self.tokens.get(&token)?;
+        Some(f64::from(amount) / 10f64.powi(self.token_decimals.into()) * usd)
+    }
+}
+
+/// Cached [`PriceQuote`] shared by the reports of a run
+#[derive(Debug, Default)]
+pub struct PriceFeed {
+    quote: RwLock<Option<Arc<PriceQuote>>>,
+}
+
+impl PriceFeed {
+    /// The last quote, `None` before the first one or with `[price]` off
+    pub fn quote(&self) -> Option<Arc<PriceQuote>> {
+        self.quote.read().unwrap().clone()
+    }
+
+    pub fn set(&self, quote: PriceQuote) {
+        *self.quote.write().unwrap() = Some(Arc::new(quote));
+    }
+
+    /// Fetches a quote now and every `refresh_secs` after
+    ///
+    /// A fixed price is set once. Failed refreshes keep the previous quote.
+    pub fn spawn_refresher(
+        self: &Arc<Self>,
+        settings: PriceSettings,
+        rpc_url: String,
+    ) -> JoinHandle<()> {
+        let feed = self.clone();
+        tokio::spawn(async move {
+            let mut interval =
+                tokio::time::interval(Duration::from_secs(settings.refresh_secs.max(1)));
+            let mut failing = false;
+            loop {
+                interval.tick().await;
+                match fetch_quote(&settings, &rpc_url).await {
+                    Ok(quote) => {
+                        if failing {
+                            info!("Price source reachable again");
+                            failing = false;
+                        }
+                        debug!("Fee unit at ${} from {}", quote.native_usd, quote.source);
+                        feed.set(quote);
+                    }
+                    Err(e) => {
+                        if !failing {
+                            warn!("Price refresh failed, keeping the last quote: {:#}", e);
+                            failing = true;
+                        }
+                    }
+                }
+                if settings.source == PriceSource::Fixed {
+                    return;
+                }
+            }
+        })
+    }
+}
+
+/// Fetches a quote from the configured source
+pub async fn fetch_quote(settings: &PriceSettings, rpc_url: &str) -> Result<PriceQuote> {
+    let (native_usd, source) = match settings.source {
+        PriceSource::Fixed => (settings.native_usd, "fixed".to_string()),
+        PriceSource::Http => (
+            fetch_http(&settings.url, &settings.json_pointer).await?,
+            format!("http {}", crate::bundle::redact_urls(&settings.url)),
+        ),
+        PriceSource::Oracle => {
+            let oracle = settings
+                .oracle
+                .as_deref()
+                .context("price.oracle is not set")?;
+            let oracle = Address::from_str(oracle)
+                .with_context(|| format!("Invalid price.oracle '{}'", oracle))?;
+            let rpc_url = settings.rpc_url.as_deref().unwrap_or(rpc_url);
+            (
+                read_oracle(rpc_url, oracle).await?,
+                format!("oracle {}", oracle),
+            )
+        }
+    };
+    Ok(PriceQuote::new(settings, native_usd, source))
+}
+
+/// One quote for a report, `None` with `[price]` off or when the source fails
+pub async fn report_quote(settings: &PriceSettings, rpc_url: &str) -> Option<PriceQuote> {
+    if !settings.enabled {
+        return None;
+    }
+    match fetch_quote(settings, rpc_url).await {
+        Ok(quote) => Some(quote),
+        Err(e) => {
+            warn!("No USD prices for this report: {:#}", e);
+            None
+        }
+    }
+}
+
+/// Formats a USD value with cents, or more digits below a cent
+pub fn format_usd(usd: f64) -> String {
+    if usd != 0.0 && usd.abs() < 0.01 {
+        format!("${:.6}", usd)
+    } else {
+        format!("${:.2}", usd)
+    }
+}
+
+async fn fetch_http(url: &str, pointer: &str) -> Result<f64> {
+    let document: serde_json::Value = reqwest::Client::new()
+        .get(url)
+        .timeout(FETCH_TIMEOUT)
+        .send()
+        .await
+        .and_then(|response| response.error_for_status())
+        .with_context(|| format!("Failed to fetch {}", crate::bundle::redact_urls(url)))?
+        .json()
+        .await
+        .context("Price document is not JSON")?;
+    parse_price(&document, pointer)
+}
+
+/// Reads the price at `pointer`, a JSON number or numeric string
+pub fn parse_price(document: &serde_json::Value, pointer: &str) -> Result<f64> {
+    let value = document
+        .pointer(pointer)
+        .with_context(|| format!("No value at {} in the price document", pointer))?;
+    let price = match value {
+        serde_json::Value::Number(number) => number.as_f64(),
+        serde_json::Value::String(text) => text.trim().parse().ok(),
+        _ => None,
+    }
+    .with_context(|| format!("Value at {} is not a price: {}", pointer, value))?;
+    if !price.is_finite() || price < 0.0 {
+        bail!("Value at {} is not a price: {}", pointer, price);
+    }
+    Ok(price)
+}
+
+async fn read_oracle(rpc_url: &str, oracle: Address) -> Result<f64> {
+    let provider = ProviderBuilder::new().connect_http(
+        rpc_url
+            .parse()
+            .with_context(|| format!("Invalid price RPC URL {}", rpc_url))?,
+    );
+    let request = |input: Vec<u8>| {
+        TransactionRequest::default()
+            .to(oracle)
+            .input(Bytes::from(input).into())
+    };
+
+    let read = async {
+        let decimals = provider
+            .call(request(IAggregatorV3::decimalsCall {}.abi_encode()))
+            .await
+            .context("Aggregator decimals failed")?;
+        let decimals = IAggregatorV3::decimalsCall::abi_decode_returns(&decimals)
+            .context("Failed to decode aggregator decimals")?;
+        let round = provider
+            .call(request(IAggregatorV3::latestRoundDataCall {}.abi_encode()))
+            .await
+            .context("Aggregator latestRoundData failed")?;
+        let round = IAggregatorV3::latestRoundDataCall::abi_decode_returns(&round)
+            .context("Failed to decode aggregator latestRoundData")?;
+        answer_usd(round.answer, decimals)
+    };
+    tokio::time::timeout(FETCH_TIMEOUT, read)
+        .await
+        .with_context(|| format!("Aggregator {} did not answer in time", oracle))?
+}
+
+/// Scales an aggregator answer with `decimals` to USD
+pub fn answer_usd(answer: I256, decimals: u8) -> Result<f64> {
+    if answer <= I256::ZERO {
+        bail!("Aggregator answer {} is not a price", answer);
+    }
+    Ok(f64::from(answer.into_raw()) / 10f64.powi(decimals.into()))
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use std::collections::BTreeMap;
+
+    #[test]
+    fn test_parse_price_and_aggregator_answer() {
+        let document = serde_json::json!({
+            "ethereum": { "usd": 3012.5 },
+            "tempo": { "usd": "0.98" },
+            "broken": { "usd": "n/a" },
+        });
+        assert_eq!(parse_price(&document, "/ethereum/usd").unwrap(), 3012.5);
+        assert_eq!(parse_price(&document, "/tempo/usd").unwrap(), 0.98);
+        assert!(parse_price(&document, "/broken/usd").is_err());
+        assert!(parse_price(&document, "/usd").is_err());
+
+        // 8 decimals, like Chainlink's USD feeds
+        let answer = I256::try_from(301_250_000_000i64).unwrap();
+        assert_eq!(answer_usd(answer, 8).unwrap(), 3012.5);
+        assert!(answer_usd(I256::ZERO, 8).is_err());
+        assert!(answer_usd(I256::MINUS_ONE, 8).is_err());
+    }
+
+    #[test]
+    fn test_quote_converts_gas_and_tokens() {
+        let settings = PriceSettings {
+            tokens: BTreeMap::from([
+                ("AlphaUSD".to_string(), 0.5),
+                (
+                    "0x20c0000000000000000000000000000000000009".to_string(),
+                    2.0,
+                ),
+            ]),
+            ..Default::default()
+        };
+        let quote = PriceQuote::new(&settings, 3000.0, "fixed");
+
+        // 0.002 fee units
+        assert!((quote.gas_usd(2e15) - 6.0).abs() < 1e-9);
+
+        let path_usd = TempoTokens::get_system_tokens()[0].address;
+        let alpha_usd = resolve_token("AlphaUSD").unwrap();
+        let listed = resolve_token("0x20c0000000000000000000000000000000000009").unwrap();
+        assert_eq!(quote.token_usd(path_usd, U256::from(2_500_000)), Some(2.5));
+        assert_eq!(
+            quote.token_usd(alpha_usd, U256::from(2_500_000)),
+            Some(1.25)
+        );
+        assert_eq!(quote.token_usd(listed, U256::from(1_000_000)), Some(2.0));
+        assert_eq!(
+            quote.token_usd(Address::repeat_byte(7), U256::from(1)),
+            None
+        );
+
+        assert_eq!(format_usd(1234.567), "$1234.57");
+        assert_eq!(format_usd(0.000123), "$0.000123");
+        assert_eq!(format_usd(0.0), "$0.00");
+    }
+}
@@ -0,0 +1,483 @@
+//! Proxy Assignment - Persistent wallet to proxy mapping
+//!
+//! Some RPC providers flag wallets whose requests arrive from a different IP
+//! every time. This module keeps track of which proxy each wallet is pinned to
+//! so [`ClientPool`](crate::ClientPool) can honour the configured
+//! [`ProxyAssignmentPolicy`]:
+//!
+//! - **Sticky**: a wallet keeps its proxy forever; while that proxy is banned
+//!   the wallet is simply not leased
+//! - **Rotating**: no pinning, proxies are handed out round-robin (the map is unused)
+//! - **Hybrid**: a wallet keeps its proxy until it fails, then moves to the next
+//!   healthy one and stays there
+//! - **Worker**: each worker sends through its own proxy whatever wallet it
+//!   leased, see [`WorkerProxies`]; the map is unused
+//!
+//! # Persistence
+//!
+//! The map is stored as JSON (`wallet index -> proxy URL`) in the file set by
+//! `[proxy] assignment_file`. Proxy URLs rather than indices are stored, so
+//! reordering `proxies.txt` keeps assignments intact; entries whose proxy was
+//! removed are dropped on load. Writes are throttled and the final state is
+//! written when the map is dropped.
+//!
+//! # IP Reuse
+//!
+//! `[proxy] max_wallets_per_proxy` caps how many wallets share one proxy when
+//! new assignments are made. The cap is a preference: if every healthy proxy
+//! is full, the least loaded one is used.
+//!
+//! # Worker Binding
+//!
+//! Some providers want one exit IP per worker. With `assignment = "worker"`
+//! worker `i` is bound to `[proxy.workers] bindings[i]` (by default the `i`-th
+//! proxy not listed as a backup). While that proxy is banned the worker moves
+//! to the first healthy proxy of `backups`, starting at its own offset so
+//! failing workers spread out, and back once the ban is over. It never falls
+//! back to another worker's proxy or a direct connection. The runner runs each
+//! worker inside [`worker_scope`], which is how the
+//! [`ClientPool`](crate::ClientPool) knows whose proxy a client needs.
+
+use crate::config::{ProxyAssignmentPolicy, WorkerProxySettings};
+use crate::tasks::ProxyConfig;
+use anyhow::{Context, Result};
+use std::collections::{BTreeMap, HashMap};
+use std::future::Future;
+use std::path::PathBuf;
+use std::sync::Mutex;
+use std::time::{Duration, Instant};
+
+/// Minimum time between two writes of the assignment file
+const SAVE_INTERVAL: Duration = Duration::from_secs(2);
+
+tokio::task_local! {
+    static WORKER: u64;
+}
+
+/// Runs `fut` as worker `worker_id`, for [`current_worker`]
+pub async fn worker_scope<F: Future>(worker_id: u64, fut: F) -> F::Output {
+    WORKER.scope(worker_id, fut).await
+}
+
+/// Id of the worker the caller runs in, if any
+pub fn current_worker() -> Option<u64> {
+    WORKER.try_with(|worker| *worker).ok()
+}
+
+#[derive(Debug, Default)]
+struct AssignmentState {
+    /// wallet index -> proxy index
+    by_wallet: HashMap<usize, usize>,
+    /// proxy index -> number of wallets pinned to it
+    load: HashMap<usize, usize>,
+    dirty: bool,
+    last_save: Option<Instant>,
+}
+
+/// Wallet to proxy assignments for sticky and hybrid policies
+#[derive(Debug)]
+pub struct ProxyAssignments {
+    policy: ProxyAssignmentPolicy,
+    path: Option<PathBuf>,
+    max_wallets_per_proxy: usize,
+    proxy_urls: Vec<String>,
+    state: Mutex<AssignmentState>,
+}
+
+impl ProxyAssignments {
+    /// Creates an empty, non-persistent assignment map
+    pub fn new(policy: ProxyAssignmentPolicy) -> Self {
+        Self {
+            policy,
+            path: None,
+            max_wallets_per_proxy: 0,
+            proxy_urls: Vec::new(),
+            state: Mutex::new(AssignmentState::default()),
+        }
+    }
+
+    /// Loads assignments for the given proxy list
+    ///
+    /// A missing or unreadable file starts an empty map; with the rotating
+    /// policy the file is neither read nor written.
+    ///
+    /// # Arguments
+    ///
+    /// * `policy` - Assignment policy from `[proxy] assignment`
+    /// * `path` - Assignment file from `[proxy] assignment_file`
+    /// * `max_wallets_per_proxy` - Preferred cap on wallets per proxy (0 = unlimited)
+    /// * `proxies` - Proxies currently loaded from `proxies.txt`
+    pub fn load(
+        policy: ProxyAssignmentPolicy,
+        path: impl Into<PathBuf>,
+        max_wallets_per_proxy: usize,
+        proxies: &[ProxyConfig],
+    ) -> Self {
+        let proxy_urls: Vec<String> = proxies.iter().map(|p| p.url.clone()).collect();
+        let mut state = AssignmentState::default();
+        let path = path.into();
+
+        if is_pinned(policy) {
+            match std::fs::read_to_string(&path) {
+                Ok(content) => match serde_json::from_str::<BTreeMap<usize, String>>(&content) {
+                    Ok(saved) => {
+                        let index_by_url: HashMap<&str, usize> = proxy_urls
+                            .iter()
+                            .enumerate()
+                            .map(|(idx, url)| (url.as_str(), idx))
+                            .collect();
+                        let mut dropped = 0;
+                        for (wallet_idx, url) in saved {
+                            match index_by_url.get(url.as_str()) {
+                                Some(&proxy_idx) => {
+                                    state.by_wallet.insert(wallet_idx, proxy_idx);
+                                    *state.load.entry(proxy_idx).or_default() += 1;
+                                }
+                                None => dropped += 1,
+                            }
+                        }
+                        tracing::info!(
+                            "Loaded {} proxy assignments from {} ({} stale dropped)",
+                            state.by_wallet.len(),
+                            path.display(),
+                            dropped
+                        );
+                        state.dirty = dropped > 0;
+                    }
+                    Err(e) => tracing::warn!(
+                        "Ignoring unreadable proxy assignment file {}: {}",
+                        path.display(),
+                        e
+                    ),
+                },
+                Err(e) if e.kind() == std::io::ErrorKind::NotFound => {}
+                Err(e) => tracing::warn!(
+                    "Failed to read proxy assignment file {}: {}",
+                    path.display(),
+                    e
+                ),
+            }
+        }
+
+        Self {
+            policy,
+            path: is_pinned(policy).then_some(path),
+            max_wallets_per_proxy,
+            proxy_urls,
+            state: Mutex::new(state),
+        }
+    }
+
+    /// The configured assignment policy
+    pub fn policy(&self) -> ProxyAssignmentPolicy {
+        self.policy
+    }
+
+    /// Whether wallets are pinned to proxies (sticky or hybrid)
+    pub fn is_pinned(&self) -> bool {
+        is_pinned(self.policy)
+    }
+
+    /// Proxy index the wallet is pinned to, if any
+    pub fn get(&self, wallet_idx: usize) -> Option<usize> {
+        self.state
+            .lock()
+            .unwrap()
+            .by_wallet
+            .get(&wallet_idx)
+            .copied()
+    }
+
+    /// Number of wallets currently pinned to a proxy
+    pub fn wallets_on(&self, proxy_idx: usize) -> usize {
+        self.state
+            .lock()
+            .unwrap()
+            .load
+            .get(&proxy_idx)
+            .copied()
+            .unwrap_or(0)
+    }
+
+    /// Pins a wallet to the first candidate proxy that is under the reuse cap
+    ///
+    /// Candidates are expected in preference order (healthy proxies in
+    /// rotation order). Falls back to the least loaded candidate when all are
+    /// at the cap.
+    ///
+    /// # Returns
+    ///
+    /// The chosen proxy index, or `None` if there are no candidates
+    pub fn assign(&self, wallet_idx: usize, candidates: &[usize]) -> Option<usize> {
+        let mut state = self.state.lock().unwrap();
+        let load_of = |state: &AssignmentState, idx: usize| -> usize {
+            let current = state.load.get(&idx).copied().unwrap_or(0);
+            // The wallet's own slot does not count against its current proxy
+            if state.by_wallet.get(&wallet_idx) == Some(&idx) {
+                current.saturating_sub(1)
+            } else {
+                current
+            }
+        };
+
+        let chosen = candidates
+            .iter()
+            .copied()
+            .find(|&idx| {
+                self.max_wallets_per_proxy == 0 || load_of(&state, idx) < self.max_wallets_per_proxy
+            })
+            .or_else(|| {
+                candidates
+                    .iter()
+                    .copied()
+                    .min_by_key(|&idx| load_of(&state, idx))
+            })?;
+
+        if let Some(previous) = state.by_wallet.insert(wallet_idx, chosen) {
+            if let Some(count) = state.load.get_mut(&previous) {
+                *count = count.saturating_sub(1);
+            }
+        }
+        *state.load.entry(chosen).or_default() += 1;
+        state.dirty = true;
+        self.save_throttled(&mut state);
+
+        Some(chosen)
+    }
+
+    /// Removes a wallet's pin so the next client creation picks a new proxy
+    pub fn unassign(&self, wallet_idx: usize) {
+        let mut state = self.state.lock().unwrap();
+        if let Some(previous) = state.by_wallet.remove(&wallet_idx) {
+            if let Some(count) = state.load.get_mut(&previous) {
+                *count = count.saturating_sub(1);
+            }
+            state.dirty = true;
+            self.save_throttled(&mut state);
+        }
+    }
+
+    /// Writes the map to disk if it changed since the last write
+    pub fn save(&self) -> Result<()> {
+        let mut state = self.state.lock().unwrap();
+        self.write_state(&mut state)
+    }
+
+    fn save_throttled(&self, state: &mut AssignmentState) {
+        let due = state
+            .last_save
+            .is_none_or(|last| last.elapsed() >= SAVE_INTERVAL);
+        if due {
+            if let Err(e) = self.write_state(state) {
+                tracing::warn!("Failed to persist proxy assignments: {:#}", e);
+            }
+        }
+    }
+
+    fn write_state(&self, state: &mut AssignmentState) -> Result<()> {
+        let Some(path) = &self.path else {
+            return Ok(());
+        };
+        if !state.dirty {
+            return Ok(());
+        }
+
+        let saved: BTreeMap<usize, &str> = state
+            .by_wallet
+            .iter()
+            .filter_map(|(&wallet_idx, &proxy_idx)| {
+                self.proxy_urls
+                    .get(proxy_idx)
+                    .map(|url| (wallet_idx, url.as_str()))
+            })
+            .collect();
+        let json = serde_json::to_string_pretty(&saved)?;
+
+        // Write-then-rename so a crash never leaves a truncated file behind
+        let tmp_path = path.with_extension("json.tmp");
+        std::fs::write(&tmp_path, json)
+            .with_context(|| format!("Failed to write {}", tmp_path.display()))?;
+        std::fs::rename(&tmp_path, path)
+            .with_context(|| format!("Failed to replace {}", path.display()))?;
+
+        state.dirty = false;
+        state.last_save = Some(Instant::now());
+        Ok(())
+    }
+}
+
+fn is_pinned(policy: ProxyAssignmentPolicy) -> bool {
+    matches!(
+        policy,
+        ProxyAssignmentPolicy::Sticky | ProxyAssignmentPolicy::Hybrid
+    )
+}
+
+/// Worker to proxy bindings for the worker policy
+#[derive(Debug, Clone, PartialEq, Eq)]
+pub struct WorkerProxies {
+    /// Proxy of worker `i`, wrapping around
+    bound: Vec<usize>,
+    backups: Vec<usize>,
+}
+
+impl WorkerProxies {
+    /// Bindings for `proxy_count` loaded proxies
+    ///
+    /// Indices past the proxy list are dropped with a warning. Returns `None`
+    /// when no proxy is left to bind workers to.
+    pub fn new(settings: &WorkerProxySettings, proxy_count: usize) -> Option<Self> {
+        let in_range = |list: &[usize], what: &str| -> Vec<usize> {
+            let (kept, dropped): (Vec<usize>, Vec<usize>) =
+                list.iter().partition(|&&idx| idx < proxy_count);
+            if !dropped.is_empty() {
+                tracing::warn!(
+                    "Ignoring proxy.workers.{} {:?}, only {} proxies loaded",
+                    what,
+                    dropped,
+                    proxy_count
+                );
+            }
+            kept
+        };
+        let backups = in_range(&settings.backups, "backups");
+        let bound = if settings.bindings.is_empty() {
+            (0..proxy_count)
+                .filter(|idx| !backups.contains(idx))
+                .collect()
+        } else {
+            in_range(&settings.bindings, "bindings")
+        };
+        (!bound.is_empty()).then_some(Self { bound, backups })
+    }
+
+    /// Proxy worker `worker_id` is bound to
+    pub fn bound(&self, worker_id: u64) -> usize {
+        self.bound[worker_id as usize % self.bound.len()]
+    }
+
+    /// Proxies worker `worker_id` may use, in order of preference
+    ///
+    /// Its own proxy first, then the backups starting at the worker's offset.
+    pub fn candidates(&self, worker_id: u64) -> Vec<usize> {
+        let own = self.bound(worker_id);
+        let offset = worker_id as usize % self.backups.len().max(1);
+        let backups = self.backups[offset..]
+            .iter()
+            .chain(&self.backups[..offset])
+            .copied()
+            .filter(|&idx| idx != own);
+        std::iter::once(own).chain(backups).collect()
+    }
+
+    /// Workers of `worker_count` sharing their proxy with another
+    pub fn shared(&self, worker_count: u64) -> usize {
+        (worker_count as usize).saturating_sub(self.bound.len())
+    }
+}
+
+impl Drop for ProxyAssignments {
+    fn drop(&mut self) {
+        if let Err(e) = self.save() {
+            tracing::warn!("Failed to persist proxy assignments on shutdown: {:#}", e);
+        }
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    fn proxies(n: usize) -> Vec<ProxyConfig> {
+        (0..n)
+            .map(|i| ProxyConfig {
+                url: format!("http://10.0.0.{}:8080", i),
+                username: None,
+                password: None,
+                session: None,
+            })
+            .collect()
+    }
+
+    fn temp_assignment_file() -> PathBuf {
+        std::env::temp_dir().join(format!(
+            "tempo-assign-{}-{}.json",
+            std::process::id(),
+            fastrand::u64(..)
+        ))
+    }
+
+    #[test]
+    fn test_assign_respects_reuse_cap() {
+        let path = temp_assignment_file();
+        let assignments =
+            ProxyAssignments::load(ProxyAssignmentPolicy::Sticky, &path, 1, &proxies(2));
+
+        assert_eq!(assignments.assign(0, &[0, 1]), Some(0));
+        assert_eq!(assignments.assign(1, &[0, 1]), Some(1));
+        // Both proxies full: falls back to the least loaded candidate
+        assert_eq!(assignments.assign(2, &[1, 0]), Some(1));
+        assert_eq!(assignments.wallets_on(1), 2);
+        // Re-assigning a wallet to its own proxy does not count against the cap
+        assert_eq!(assignments.assign(0, &[0, 1]), Some(0));
+
+        drop(assignments);
+        let _ = std::fs::remove_file(&path);
+    }
+
+    #[test]
+    fn test_workers_bound_to_own_proxy_and_backups() {
+        let settings = WorkerProxySettings {
+            bindings: Vec::new(),
+            backups: vec![3, 4, 9],
+        };
+        let workers = WorkerProxies::new(&settings, 5).unwrap();
+        assert_eq!(
+            (workers.bound(0), workers.bound(1), workers.bound(2)),
+            (0, 1, 2)
+        );
+        // Three proxies for four workers: the fourth shares with the first
+        assert_eq!(workers.bound(3), 0);
+        assert_eq!(workers.shared(4), 1);
+        assert_eq!(workers.candidates(0), vec![0, 3, 4]);
+        assert_eq!(workers.candidates(1), vec![1, 4, 3]);
+
+        let settings = WorkerProxySettings {
+            bindings: vec![4, 2],
+            backups: Vec::new(),
+        };
+        let workers = WorkerProxies::new(&settings, 5).unwrap();
+        assert_eq!(workers.candidates(1), vec![2]);
+        assert!(WorkerProxies::new(&settings, 0).is_none());
+    }
+
+    #[tokio::test]
+    async fn test_worker_scope_sets_current_worker() {
+        assert_eq!(current_worker(), None);
+        assert_eq!(worker_scope(7, async { current_worker() }).await, Some(7));
+    }
+
+    #[test]
+    fn test_assignments_survive_reload_by_url() {
+        let path = temp_assignment_file();
+
+        {
+            let assignments =
+                ProxyAssignments::load(ProxyAssignmentPolicy::Hybrid, &path, 0, &proxies(3));
+            assignments.assign(7, &[2]);
+            assignments.assign(8, &[1]);
+        }
+
+        // proxies.txt reordered and one proxy removed
+        let mut reordered = proxies(3);
+        reordered.swap(0, 2);
+        reordered.remove(1);
+        let reloaded = ProxyAssignments::load(ProxyAssignmentPolicy::Hybrid, &path, 0, &reordered);
+
+        assert_eq!(reloaded.get(7), Some(0));
+        assert_eq!(reloaded.get(8), None);
+
+        drop(reloaded);
+        let _ = std::fs::remove_file(&path);
+    }
+}
@@ -9,12 +9,13 @@
 //! The health system consists of two main components:
 //!
 //! 1. **ProxyBanlist**: Tracks banned proxies with automatic expiration
-//! 2. **Health Checker**: Tests proxies by making HTTP requests to the RPC endpoint
+//! 2. **Health Checker**: Tests proxies by sending probe requests through them (see [`HealthProbe`])
 //!
 //! # Health Check Flow
 //!
 //! 1. **Startup Scan**: All proxies are checked concurrently on startup
-//! 2. **Banning**: Unhealthy proxies are banned for a configurable duration (default 10 min)
+//! 2. **Banning**: Unhealthy proxies are banned, longer each time they fail again
+//!    (default 10m → 30m → 2h → rest of the session, see [`BanPolicy`])
 //! 3. **Client Filtering**: ClientPool automatically excludes banned proxies
 //! 4. **Background Recheck**: Banned proxies are periodically retested
 //! 5. **Auto-Unban**: Healthy proxies are automatically unbanned
@@ -43,23 +44,24 @@
 //! # Concurrent Health Checks
 //!
 //! The `scan_proxies` function checks all proxies concurrently with a configurable
-//! limit to avoid overwhelming the network:
+//! limit to avoid overwhelming the network. What a check sends is described by a
+//! [`HealthProbe`], built from `[proxy.health]` in the config:
 //!
 //! ```rust,no_run
-//! use tempo_spammer::proxy_health::{scan_proxies, ProxyBanlist};
+//! use tempo_spammer::proxy_health::{scan_proxies, HealthProbe, ProbeMethod, ProxyBanlist};
 //! use tempo_spammer::tasks::load_proxies;
 //!
 //! # async fn example() -> anyhow::Result<()> {
 //! let proxies = load_proxies("config/proxies.txt")?;
 //! let banlist = ProxyBanlist::new(30);
 //!
-//! // Scan all proxies, 50 at a time
-//! let (healthy, banned) = scan_proxies(
-//!     &proxies,
-//!     "https://rpc.moderato.tempo.xyz",
-//!     &banlist,
-//!     50,
-//! ).await;
+//! // POST eth_chainId through every proxy, 50 at a time
+//! let probe = HealthProbe {
+//!     method: ProbeMethod::ChainId,
+//!     concurrency: 50,
+//!     ..HealthProbe::head("https://rpc.moderato.tempo.xyz")
+//! };
+//! let (healthy, banned) = scan_proxies(&proxies, &probe, &banlist).await;
 //!
 //! println!("Healthy: {}, Banned: {}", healthy, banned);
 //! # Ok(())
@@ -69,16 +71,107 @@
 use crate::tasks::ProxyConfig;
 use anyhow::Result;
 use futures::stream::{self, StreamExt};
+use serde::Deserialize;
 use std::collections::HashMap;
 use std::sync::Arc;
 use std::time::{Duration, Instant};
 use tokio::sync::RwLock;
 
-/// Tracks banned proxies with automatic expiration
+/// Escalating ban durations for failing proxies
 ///
-/// Implements a temporary banlist for unhealthy proxies. Banned proxies are
-/// automatically excluded from rotation until the ban duration expires or
-/// they are manually unbanned after passing a health check.
+/// Each ban moves a proxy one severity tier up and bans it for that tier's
+/// duration. Past the last tier the proxy is either banned for the rest of
+/// the session or keeps getting the last tier's duration.
+#[derive(Debug, Clone, PartialEq)]
+pub struct BanPolicy {
+    /// Ban duration per severity tier, shortest first
+    pub tiers: Vec<Duration>,
+    /// Ban for the rest of the session once the last tier is used up
+    pub permanent_after_last_tier: bool,
+    /// Failures in a row reported through [`ProxyBanlist::record_failure`] before a ban
+    pub failure_threshold: u32,
+    /// Successes that lower a proxy's severity by one tier, 0 = never
+    pub decay_successes: u32,
+}
+
+impl Default for BanPolicy {
+    /// 10 minutes, then 30 minutes, then 2 hours, then the rest of the session
+    fn default() -> Self {
+        Self {
+            tiers: vec![
+                Duration::from_secs(10 * 60),
+                Duration::from_secs(30 * 60),
+                Duration::from_secs(2 * 60 * 60),
+            ],
+            permanent_after_last_tier: true,
+            failure_threshold: 1,
+            decay_successes: 50,
+        }
+    }
+}
+
+impl BanPolicy {
+    /// A single ban duration that never escalates
+    pub fn fixed(ban_duration: Duration) -> Self {
+        Self {
+            tiers: vec![ban_duration],
+            permanent_after_last_tier: false,
+            failure_threshold: 1,
+            decay_successes: 0,
+        }
+    }
+
+    /// Ban duration for the given severity, `None` for a session-long ban
+    ///
+    /// Severity 1 is the first ban.
+    pub fn duration_for(&self, severity: u32) -> Option<Duration> {
+        let tier = severity.max(1) as usize - 1;
+        match self.tiers.get(tier) {
+            Some(&duration) => Some(duration),
+            None if self.permanent_after_last_tier => None,
+            None => self.tiers.last().copied(),
+        }
+    }
+}
+
+/// A ban currently on a proxy
+#[derive(Debug, Clone, Copy)]
+enum Ban {
+    /// Lifted at the given instant
+    Until(Instant),
+    /// Kept for the rest of the session
+    Session,
+}
+
+impl Ban {
+    fn is_active(&self, now: Instant) -> bool {
+        match self {
+            Ban::Until(until) => now < *until,
+            Ban::Session => true,
+        }
+    }
+}
+
+/// Failure history of one proxy
+#[derive(Debug, Clone, Default)]
+struct ProxyRecord {
+    /// Failures in a row since the last ban or success
+    failures: u32,
+    /// Severity tier of the latest ban, 0 = never banned or fully decayed
+    severity: u32,
+    /// Successes counted towards the next severity decay
+    successes: u32,
+    /// The ban in force, if any
+    ban: Option<Ban>,
+}
+
+/// Tracks banned proxies with escalating ban durations
+///
+/// Implements a banlist for unhealthy proxies. Banned proxies are excluded
+/// from rotation until their ban expires or they are unbanned after passing
+/// a health check. Each proxy keeps its severity across bans, so a proxy
+/// that keeps failing is banned for longer each time (see [`BanPolicy`]),
+/// while successful requests decay its severity again.
 ///
 /// # Thread Safety
 ///
@@ -91,14 +184,16 @@ use tokio::sync::RwLock;
 /// share the same banned proxy state.
 #[derive(Clone)]
 pub struct ProxyBanlist {
-    /// Map of banned proxy indices to ban start time
-    banned: Arc<RwLock<HashMap<usize, Instant>>>,
-    /// Duration for which proxies remain banned
-    ban_duration: Duration,
+    /// Failure history and bans by proxy index
+    records: Arc<RwLock<HashMap<usize, ProxyRecord>>>,
+    /// Ban escalation policy
+    policy: Arc<BanPolicy>,
 }
 
 impl ProxyBanlist {
-    /// Creates a new banlist with specified ban duration
+    /// Creates a new banlist with a fixed ban duration
+    ///
+    /// Bans never escalate; use [`ProxyBanlist::with_policy`] for severity tiers.
     ///
     /// # Arguments
     ///
@@ -113,16 +208,37 @@ impl ProxyBanlist {
     /// let banlist = ProxyBanlist::new(10);
     /// ```
     pub fn new(ban_duration_minutes: u64) -> Self {
+        Self::with_policy(BanPolicy::fixed(Duration::from_secs(
+            ban_duration_minutes * 60,
+        )))
+    }
+
+    /// Creates a new banlist escalating bans by the given policy
+    ///
+    /// # Example
+    ///
+    /// ```rust
+    /// use tempo_spammer::proxy_health::{BanPolicy, ProxyBanlist};
+    ///
+    /// // 10m -> 30m -> 2h -> rest of the session
+    /// let banlist = ProxyBanlist::with_policy(BanPolicy::default());
+    /// ```
+    pub fn with_policy(policy: BanPolicy) -> Self {
         Self {
-            banned: Arc::new(RwLock::new(HashMap::new())),
-            ban_duration: Duration::from_secs(ban_duration_minutes * 60),
+            records: Arc::new(RwLock::new(HashMap::new())),
+            policy: Arc::new(policy),
         }
     }
 
+    /// The ban escalation policy
+    pub fn policy(&self) -> &BanPolicy {
+        &self.policy
+    }
+
     /// Checks if a proxy is currently banned
     ///
-    /// Returns true if the proxy index is in the banlist and the ban duration
-    /// has not yet expired.
+    /// Returns true if the proxy has a session-long ban or a ban that has
+    /// not yet expired.
     ///
     /// # Arguments
     ///
@@ -132,76 +248,237 @@ impl ProxyBanlist {
     ///
     /// `true` if the proxy is banned, `false` otherwise
     pub async fn is_banned(&self, proxy_index: usize) -> bool {
-        let banned = self.banned.read().await;
-        if let Some(&ban_time) = banned.get(&proxy_index) {
-            ban_time.elapsed() < self.ban_duration
-        } else {
-            false
-        }
+        let records = self.records.read().await;
+        records
+            .get(&proxy_index)
+            .and_then(|record| record.ban)
+            .is_some_and(|ban| ban.is_active(Instant::now()))
     }
 
-    /// Bans a proxy temporarily
+    /// Checks if a proxy is banned for the rest of the session
+    pub async fn is_permanently_banned(&self, proxy_index: usize) -> bool {
+        let records = self.records.read().await;
+        records
+            .get(&proxy_index)
+            .is_some_and(|record| matches!(record.ban, Some(Ban::Session)))
+    }
+
+    /// Severity tier of a proxy's latest ban, 0 if it has none
+    pub async fn severity(&self, proxy_index: usize) -> u32 {
+        let records = self.records.read().await;
+        records
+            .get(&proxy_index)
+            .map_or(0, |record| record.severity)
+    }
+
+    /// Bans a proxy at the next severity tier
     ///
-    /// Adds the proxy to the banlist with the current timestamp. The proxy will
-    /// remain banned until the ban duration expires or it is manually unbanned.
+    /// The ban lasts for the tier's duration from now, or for the rest of
+    /// the session past the last tier. A proxy that is still banned keeps
+    /// its current ban and tier, so overlapping scans of the same dead
+    /// proxy do not escalate it.
     ///
     /// # Arguments
     ///
     /// * `proxy_index` - The index of the proxy to ban
     pub async fn ban(&self, proxy_index: usize) {
-        let mut banned = self.banned.write().await;
-        banned.insert(proxy_index, Instant::now());
+        let mut records = self.records.write().await;
+        let record = records.entry(proxy_index).or_default();
+        if record.ban.is_some_and(|ban| ban.is_active(Instant::now())) {
+            return;
+        }
+        self.escalate(proxy_index, record);
+    }
+
+    /// Reports a failed request through a proxy
+    ///
+    /// Bans the proxy once [`BanPolicy::failure_threshold`] failures in a
+    /// row are reached. Failures while the proxy is banned are not counted.
+    ///
+    /// # Returns
+    ///
+    /// `true` if the proxy is banned after this failure
+    pub async fn record_failure(&self, proxy_index: usize) -> bool {
+        let mut records = self.records.write().await;
+        let record = records.entry(proxy_index).or_default();
+        if record.ban.is_some_and(|ban| ban.is_active(Instant::now())) {
+            return true;
+        }
+
+        record.failures += 1;
+        record.successes = 0;
+        if record.failures < self.policy.failure_threshold {
+            return false;
+        }
+        self.escalate(proxy_index, record);
+        true
+    }
+
+    /// Reports a successful request through a proxy
+    ///
+    /// Clears the proxy's failure count and, every
+    /// [`BanPolicy::decay_successes`] successes, lowers its severity by one
+    /// tier so the next ban is shorter again.
+    pub async fn record_success(&self, proxy_index: usize) {
+        let mut records = self.records.write().await;
+        let Some(record) = records.get_mut(&proxy_index) else {
+            return;
+        };
+
+        record.failures = 0;
+        if self.policy.decay_successes == 0 || record.severity == 0 {
+            return;
+        }
+        record.successes += 1;
+        if record.successes >= self.policy.decay_successes {
+            record.successes = 0;
+            record.severity -= 1;
+            tracing::debug!(
+                "Proxy {} ban severity decayed to {}",
+                proxy_index,
+                record.severity
+            );
+        }
     }
 
     /// Unbans a proxy manually
     ///
-    /// Removes the proxy from the banlist immediately, making it available
-    /// for use again. Typically called when a health check passes.
+    /// Lifts a temporary ban immediately, making the proxy available for use
+    /// again. Typically called when a health check passes. The proxy keeps
+    /// its severity, and session-long bans are not lifted.
     ///
     /// # Arguments
     ///
     /// * `proxy_index` - The index of the proxy to unban
     pub async fn unban(&self, proxy_index: usize) {
-        let mut banned = self.banned.write().await;
-        banned.remove(&proxy_index);
+        let mut records = self.records.write().await;
+        if let Some(record) = records.get_mut(&proxy_index) {
+            record.failures = 0;
+            if matches!(record.ban, Some(Ban::Until(_))) {
+                record.ban = None;
+            }
+        }
     }
 
     /// Gets list of currently banned proxy indices
     ///
-    /// Returns all proxy indices that are currently banned and whose ban
-    /// duration has not yet expired.
+    /// Returns all proxy indices with a session-long ban or a ban that has
+    /// not yet expired.
     ///
     /// # Returns
     ///
     /// Vector of banned proxy indices
     pub async fn get_banned_indices(&self) -> Vec<usize> {
-        let banned = self.banned.read().await;
+        let records = self.records.read().await;
+        let now = Instant::now();
+        records
+            .iter()
+            .filter(|(_, record)| record.ban.is_some_and(|ban| ban.is_active(now)))
+            .map(|(&idx, _)| idx)
+            .collect()
+    }
+
+    /// Gets list of proxy indices with an unexpired temporary ban
+    ///
+    /// Like [`ProxyBanlist::get_banned_indices`], without session-long bans.
+    pub async fn get_temporarily_banned_indices(&self) -> Vec<usize> {
+        let records = self.records.read().await;
         let now = Instant::now();
-        banned
+        records
             .iter()
-            .filter(|(_, ban_time)| (now - **ban_time) < self.ban_duration)
+            .filter(|(_, record)| matches!(record.ban, Some(Ban::Until(until)) if now < until))
             .map(|(&idx, _)| idx)
             .collect()
     }
 
     /// Removes expired bans from the list
     ///
-    /// Cleans up the banlist by removing entries where the ban duration has
-    /// expired. This is called automatically by the background recheck task.
+    /// Clears bans whose duration has expired. Severity is kept so the next
+    /// ban escalates; proxies with nothing left to remember are dropped.
+    /// This is called automatically by the background recheck task.
     pub async fn cleanup_expired(&self) {
-        let mut banned = self.banned.write().await;
+        let mut records = self.records.write().await;
         let now = Instant::now();
-        banned.retain(|_, ban_time| (now - *ban_time) < self.ban_duration);
+        for record in records.values_mut() {
+            if record.ban.is_some_and(|ban| !ban.is_active(now)) {
+                record.ban = None;
+            }
+        }
+        records
+            .retain(|_, record| record.ban.is_some() || record.severity > 0 || record.failures > 0);
+    }
+
+    fn escalate(&self, proxy_index: usize, record: &mut ProxyRecord) {
+        record.failures = 0;
+        record.successes = 0;
+        if matches!(record.ban, Some(Ban::Session)) {
+            return;
+        }
+
+        record.severity = record.severity.saturating_add(1);
+        record.ban = Some(match self.policy.duration_for(record.severity) {
+            Some(duration) => Ban::Until(Instant::now() + duration),
+            None => Ban::Session,
+        });
+        tracing::debug!(
+            "Proxy {} banned at severity {} ({:?})",
+            proxy_index,
+            record.severity,
+            record.ban
+        );
+    }
+}
+
+/// Request sent through a proxy to check it
+#[derive(Debug, Clone, Copy, PartialEq, Eq, Default, Deserialize)]
+#[serde(rename_all = "snake_case")]
+pub enum ProbeMethod {
+    /// `HEAD` request; any response counts as healthy
+    #[default]
+    Head,
+    /// JSON-RPC `eth_chainId` POST; needs a successful status and a result
+    ChainId,
+}
+
+/// How proxies are health checked
+///
+/// A proxy is healthy when every target passes `required_successes` probes
+/// in a row, each answered within `max_latency` when one is set.
+#[derive(Debug, Clone, PartialEq)]
+pub struct HealthProbe {
+    /// Request sent to each target
+    pub method: ProbeMethod,
+    /// URLs probed through each proxy
+    pub targets: Vec<String>,
+    /// Slowest acceptable probe response, `None` = only the client timeout
+    pub max_latency: Option<Duration>,
+    /// Probes in a row each target must pass
+    pub required_successes: u32,
+    /// Proxies checked at once
+    pub concurrency: usize,
+}
+
+impl HealthProbe {
+    /// A single `HEAD` request to `rpc_url`, 10 proxies at a time
+    pub fn head(rpc_url: &str) -> Self {
+        Self {
+            method: ProbeMethod::Head,
+            targets: vec![rpc_url.to_string()],
+            max_latency: None,
+            required_successes: 1,
+            concurrency: 10,
+        }
     }
 }
+
 use std::sync::OnceLock;
 
 static CLIENT_CACHE: OnceLock<tokio::sync::RwLock<HashMap<String, reqwest::Client>>> =
     OnceLock::new();
 
 /// Test if a proxy is healthy using cached clients
-async fn check_proxy_health(proxy: &ProxyConfig, rpc_url: &str) -> bool {
-    let proxy_url_full = if let (Some(user), Some(pass)) = (&proxy.username, &proxy.password) {
+async fn check_proxy_health(proxy: &ProxyConfig, probe: &HealthProbe) -> bool {
+    let proxy_url_full = if let Some((user, pass)) = proxy.basic_auth() {
         // Formatted for reqwest::Proxy
         let host_port = proxy
             .url
@@ -211,6 +488,12 @@ async fn check_proxy_health(proxy: &ProxyConfig, rpc_url: &str) -> bool {
     } else {
         proxy.url.clone()
     };
+    // Templated credentials differ per call; cache those per session generation
+    let cache_key = if proxy.session.is_some() {
+        format!("{}#{}", proxy.url, proxy.session_generation())
+    } else {
+        proxy_url_full.clone()
+    };
 
     // Get cache
     let cache = CLIENT_CACHE.get_or_init(|| tokio::sync::RwLock::new(HashMap::new()));
@@ -218,7 +501,7 @@ async fn check_proxy_health(proxy: &ProxyConfig, rpc_url: &str) -> bool {
     // Try to get existing client
     let client = {
         let read = cache.read().await;
-        read.get(&proxy_url_full).cloned()
+        read.get(&cache_key).cloned()
     };
 
     let client = if let Some(c) = client {
@@ -249,36 +532,72 @@ async fn check_proxy_health(proxy: &ProxyConfig, rpc_url: &str) -> bool {
 
         // Insert into cache
         let mut write = cache.write().await;
-        write.insert(proxy_url_full.clone(), new_client.clone());
+        write.insert(cache_key, new_client.clone());
         new_client
     };
 
-    // Try a simple HEAD request to RPC endpoint
-    match client.head(rpc_url).send().await {
-        Ok(_) => true, // Any response = proxy works
-        Err(_) => false,
+    for target in &probe.targets {
+        for _ in 0..probe.required_successes.max(1) {
+            let start = Instant::now();
+            if !probe_once(&client, probe.method, target).await {
+                return false;
+            }
+            if probe.max_latency.is_some_and(|max| start.elapsed() > max) {
+                tracing::debug!(
+                    "Proxy {} too slow for {}: {:?}",
+                    proxy.url,
+                    target,
+                    start.elapsed()
+                );
+                return false;
+            }
+        }
+    }
+    true
+}
+
+/// Sends one probe request to `target`
+async fn probe_once(client: &reqwest::Client, method: ProbeMethod, target: &str) -> bool {
+    match method {
+        // Any response = proxy works
+        ProbeMethod::Head => client.head(target).send().await.is_ok(),
+        ProbeMethod::ChainId => {
+            let body = serde_json::json!({
+                "jsonrpc": "2.0",
+                "id": 1,
+                "method": "eth_chainId",
+                "params": [],
+            });
+            let response = match client.post(target).json(&body).send().await {
+                Ok(response) if response.status().is_success() => response,
+                _ => return false,
+            };
+            match response.json::<serde_json::Value>().await {
+                Ok(reply) => reply.get("result").is_some_and(|r| r.is_string()),
+                Err(_) => false,
+            }
+        }
     }
 }
 
 /// Scan all proxies in parallel batches and ban unhealthy ones
 pub async fn scan_proxies(
     proxies: &[ProxyConfig],
-    rpc_url: &str,
+    probe: &HealthProbe,
     banlist: &ProxyBanlist,
-    concurrent_limit: usize,
 ) -> (usize, usize) {
     tracing::info!(
         "🔍 Scanning {} proxies ({} concurrent)...",
         proxies.len(),
-        concurrent_limit
+        probe.concurrency
     );
 
     let results: Vec<(usize, bool)> = stream::iter(proxies.iter().enumerate())
         .map(|(idx, proxy)| async move {
-            let is_healthy = check_proxy_health(proxy, rpc_url).await;
+            let is_healthy = check_proxy_health(proxy, probe).await;
             (idx, is_healthy)
         })
-        .buffer_unordered(concurrent_limit)
+        .buffer_unordered(probe.concurrency.max(1))
         .collect()
         .await;
 
@@ -303,18 +622,16 @@ pub async fn scan_proxies(
 ///
 /// # Arguments
 /// * `proxies` - List of proxy configurations
-/// * `rpc_url` - RPC endpoint URL for health checks
+/// * `probe` - Health check to run against each proxy
 /// * `banlist` - Proxy banlist to update
-/// * `concurrent_limit` - Max concurrent health checks
 /// * `min_healthy` - Minimum healthy proxies needed before returning (e.g., 50)
 ///
 /// # Returns
 /// (healthy_count, banned_count, background_handle) - Handle to continue checking in background
 pub async fn scan_proxies_partial(
     proxies: Arc<Vec<ProxyConfig>>,
-    rpc_url: String,
+    probe: HealthProbe,
     banlist: ProxyBanlist,
-    concurrent_limit: usize,
     min_healthy: usize,
 ) -> (usize, usize, tokio::task::JoinHandle<(usize, usize)>) {
     tracing::info!(
@@ -326,12 +643,11 @@ pub async fn scan_proxies_partial(
     // Clone for foreground checks (we need to use these after spawning background task)
     let banlist_fg = banlist.clone();
     let proxies_fg = proxies.clone();
-    let rpc_url_fg = rpc_url.clone();
+    let probe_fg = probe.clone();
 
     // Spawn background task to check ALL proxies
-    let background_handle = tokio::spawn(async move {
-        scan_proxies_with_progress(proxies, rpc_url, banlist, concurrent_limit).await
-    });
+    let background_handle =
+        tokio::spawn(async move { scan_proxies_with_progress(proxies, probe, banlist).await });
 
     // Wait for minimum healthy proxies
     let mut healthy_count = 0;
@@ -349,7 +665,7 @@ pub async fn scan_proxies_partial(
             break;
         }
 
-        let is_healthy = check_proxy_health(proxy, &rpc_url_fg).await;
+        let is_healthy = check_proxy_health(proxy, &probe_fg).await;
         checked_count += 1;
 
         if is_healthy {
@@ -367,9 +683,8 @@ pub async fn scan_proxies_partial(
 /// Internal function to scan all proxies and update banlist
 async fn scan_proxies_with_progress(
     proxies: Arc<Vec<ProxyConfig>>,
-    rpc_url: String,
+    probe: HealthProbe,
     banlist: ProxyBanlist,
-    concurrent_limit: usize,
 ) -> (usize, usize) {
     // Convert to owned vector to avoid lifetime issues
     let proxy_vec: Vec<(usize, ProxyConfig)> = proxies
@@ -380,13 +695,13 @@ async fn scan_proxies_with_progress(
 
     let results: Vec<(usize, bool)> = stream::iter(proxy_vec)
         .map(|(idx, proxy)| {
-            let rpc_url = rpc_url.clone();
+            let probe = &probe;
             async move {
-                let is_healthy = check_proxy_health(&proxy, &rpc_url).await;
+                let is_healthy = check_proxy_health(&proxy, probe).await;
                 (idx, is_healthy)
             }
         })
-        .buffer_unordered(concurrent_limit)
+        .buffer_unordered(probe.concurrency.max(1))
         .collect()
         .await;
 
@@ -416,7 +731,7 @@ async fn scan_proxies_with_progress(
 /// Background task to re-check banned proxies every 10 minutes
 pub async fn start_recheck_task(
     proxies: Arc<Vec<ProxyConfig>>,
-    rpc_url: String,
+    probe: HealthProbe,
     banlist: ProxyBanlist,
     check_interval_minutes: u64,
 ) {
@@ -426,7 +741,8 @@ pub async fn start_recheck_task(
     loop {
         interval.tick().await;
 
-        let banned_indices = banlist.get_banned_indices().await;
+        // Session-long bans are not lifted by a passing health check
+        let banned_indices = banlist.get_temporarily_banned_indices().await;
         if banned_indices.is_empty() {
             tracing::debug!("⏱️ No banned proxies to re-check");
             continue;
@@ -437,7 +753,7 @@ pub async fn start_recheck_task(
         let mut unbanned_count = 0;
         for idx in banned_indices {
             if let Some(proxy) = proxies.get(idx) {
-                if check_proxy_health(proxy, &rpc_url).await {
+                if check_proxy_health(proxy, &probe).await {
                     banlist.unban(idx).await;
                     unbanned_count += 1;
                     tracing::debug!("✅ Proxy {} recovered and unbanned", idx);
@@ -453,3 +769,111 @@ pub async fn start_recheck_task(
         banlist.cleanup_expired().await;
     }
 }
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    fn policy() -> BanPolicy {
+        BanPolicy {
+            tiers: vec![
+                Duration::from_secs(600),
+                Duration::from_secs(1800),
+                Duration::from_secs(7200),
+            ],
+            permanent_after_last_tier: true,
+            failure_threshold: 2,
+            decay_successes: 3,
+        }
+    }
+
+    #[test]
+    fn test_duration_for_tiers() {
+        let policy = policy();
+        assert_eq!(policy.duration_for(1), Some(Duration::from_secs(600)));
+        assert_eq!(policy.duration_for(3), Some(Duration::from_secs(7200)));
+        assert_eq!(policy.duration_for(4), None);
+
+        let fixed = BanPolicy::fixed(Duration::from_secs(60));
+        assert_eq!(fixed.duration_for(1), Some(Duration::from_secs(60)));
+        assert_eq!(fixed.duration_for(9), Some(Duration::from_secs(60)));
+    }
+
+    #[tokio::test]
+    async fn test_bans_escalate_to_session() {
+        let banlist = ProxyBanlist::with_policy(policy());
+        for severity in 1..=3 {
+            banlist.ban(0).await;
+            assert_eq!(banlist.severity(0).await, severity);
+            assert!(!banlist.is_permanently_banned(0).await);
+            banlist.unban(0).await;
+            assert!(!banlist.is_banned(0).await);
+        }
+
+        banlist.ban(0).await;
+        assert!(banlist.is_permanently_banned(0).await);
+        banlist.unban(0).await;
+        banlist.cleanup_expired().await;
+        assert!(banlist.is_banned(0).await);
+        assert_eq!(banlist.get_banned_indices().await, vec![0]);
+        assert!(banlist.get_temporarily_banned_indices().await.is_empty());
+    }
+
+    #[tokio::test]
+    async fn test_ban_while_banned_keeps_tier() {
+        let banlist = ProxyBanlist::with_policy(policy());
+        banlist.ban(4).await;
+        banlist.ban(4).await;
+        assert_eq!(banlist.severity(4).await, 1);
+        assert!(banlist.is_banned(4).await);
+
+        banlist.unban(4).await;
+        banlist.ban(4).await;
+        assert_eq!(banlist.severity(4).await, 2);
+    }
+
+    #[tokio::test]
+    async fn test_failure_threshold() {
+        let banlist = ProxyBanlist::with_policy(policy());
+        assert!(!banlist.record_failure(1).await);
+        banlist.record_success(1).await;
+        assert!(!banlist.record_failure(1).await);
+        assert!(banlist.record_failure(1).await);
+        assert!(banlist.is_banned(1).await);
+        assert_eq!(banlist.severity(1).await, 1);
+
+        // Failures during a ban do not escalate it
+        assert!(banlist.record_failure(1).await);
+        assert_eq!(banlist.severity(1).await, 1);
+    }
+
+    #[tokio::test]
+    async fn test_successes_decay_severity() {
+        let banlist = ProxyBanlist::with_policy(policy());
+        banlist.ban(2).await;
+        banlist.unban(2).await;
+        banlist.ban(2).await;
+        banlist.unban(2).await;
+        assert_eq!(banlist.severity(2).await, 2);
+
+        for _ in 0..3 {
+            banlist.record_success(2).await;
+        }
+        assert_eq!(banlist.severity(2).await, 1);
+
+        banlist.ban(2).await;
+        assert_eq!(banlist.severity(2).await, 2);
+    }
+
+    #[tokio::test]
+    async fn test_fixed_ban_never_escalates() {
+        let banlist = ProxyBanlist::new(10);
+        for _ in 0..5 {
+            banlist.ban(3).await;
+            banlist.unban(3).await;
+        }
+        banlist.ban(3).await;
+        assert!(banlist.is_banned(3).await);
+        assert!(!banlist.is_permanently_banned(3).await);
+    }
+}
@@ -0,0 +1,288 @@
+//! Proxy Usage - Request and bandwidth accounting per proxy
+//!
+//! Proxy providers bill per GB, so every RPC call made through a proxy is
+//! counted by a transport layer installed in [`TempoClient`](crate::TempoClient).
+//! Counters are kept in memory per proxy URL and periodically added onto the
+//! `proxy_stats` table with [`ProxyUsageTracker::flush`]; `db proxies` reports
+//! the totals.
+//!
+//! # Estimates
+//!
+//! Byte counts are the JSON-RPC request and response bodies plus
+//! [`ESTIMATED_HEADER_BYTES`] per direction for HTTP headers. TLS and proxy
+//! tunnel overhead is not visible at this layer, so provider invoices will be
+//! somewhat higher. The layer sits inside the retry layer, so every retried
+//! attempt is counted, and below the [`rpc_cache`](crate::rpc_cache) layer,
+//! so answers served from memory are not.
+//!
+//! Direct connections are not tracked.
+
+use alloy::rpc::json_rpc::{RequestPacket, ResponsePacket};
+use alloy::transports::{TransportError, TransportFut};
+use anyhow::Result;
+use core_logic::database::{DatabaseManager, ProxyUsage};
+use std::collections::HashMap;
+use std::sync::atomic::{AtomicU64, Ordering};
+use std::sync::{Arc, Mutex, OnceLock};
+use std::task::{Context, Poll};
+use tower::{Layer, Service};
+
+/// Approximate HTTP header size added to each request and each response
+pub const ESTIMATED_HEADER_BYTES: u64 = 200;
+
+static TRACKER: OnceLock<ProxyUsageTracker> = OnceLock::new();
+
+/// Process-wide usage tracker shared by all clients
+pub fn tracker() -> &'static ProxyUsageTracker {
+    TRACKER.get_or_init(ProxyUsageTracker::default)
+}
+
+/// Live counters for one proxy
+#[derive(Debug, Default)]
+pub struct ProxyCounters {
+    requests: AtomicU64,
+    successes: AtomicU64,
+    failures: AtomicU64,
+    bytes_sent: AtomicU64,
+    bytes_received: AtomicU64,
+}
+
+impl ProxyCounters {
+    /// Records one request with its estimated sizes
+    pub fn record(&self, sent: u64, received: u64, success: bool) {
+        self.requests.fetch_add(1, Ordering::Relaxed);
+        if success {
+            self.successes.fetch_add(1, Ordering::Relaxed);
+        } else {
+            self.failures.fetch_add(1, Ordering::Relaxed);
+        }
+        self.bytes_sent.fetch_add(sent, Ordering::Relaxed);
+        self.bytes_received.fetch_add(received, Ordering::Relaxed);
+    }
+
+    /// Current totals without resetting them
+    pub fn snapshot(&self) -> ProxyUsage {
+        ProxyUsage {
+            requests: self.requests.load(Ordering::Relaxed),
+            successes: self.successes.load(Ordering::Relaxed),
+            failures: self.failures.load(Ordering::Relaxed),
+            bytes_sent: self.bytes_sent.load(Ordering::Relaxed),
+            bytes_received: self.bytes_received.load(Ordering::Relaxed),
+        }
+    }
+
+    fn take(&self) -> ProxyUsage {
+        ProxyUsage {
+            requests: self.requests.swap(0, Ordering::Relaxed),
+            successes: self.successes.swap(0, Ordering::Relaxed),
+            failures: self.failures.swap(0, Ordering::Relaxed),
+            bytes_sent: self.bytes_sent.swap(0, Ordering::Relaxed),
+            bytes_received: self.bytes_received.swap(0, Ordering::Relaxed),
+        }
+    }
+
+    fn restore(&self, usage: &ProxyUsage) {
+        self.requests.fetch_add(usage.requests, Ordering::Relaxed);
+        self.successes.fetch_add(usage.successes, Ordering::Relaxed);
+        self.failures.fetch_add(usage.failures, Ordering::Relaxed);
+        self.bytes_sent
+            .fetch_add(usage.bytes_sent, Ordering::Relaxed);
+        self.bytes_received
+            .fetch_add(usage.bytes_received, Ordering::Relaxed);
+    }
+}
+
+/// Usage counters keyed by proxy URL
+#[derive(Debug, Default)]
+pub struct ProxyUsageTracker {
+    by_proxy: Mutex<HashMap<String, Arc<ProxyCounters>>>,
+}
+
+impl ProxyUsageTracker {
+    /// Counters for a proxy URL, created on first use
+    pub fn counters(&self, proxy_url: &str) -> Arc<ProxyCounters> {
+        self.by_proxy
+            .lock()
+            .unwrap()
+            .entry(proxy_url.to_string())
+            .or_default()
+            .clone()
+    }
+
+    /// Transport layer counting traffic for a proxy, or a pass-through for direct connections
+    pub fn layer(&self, proxy_url: Option<&str>) -> ProxyUsageLayer {
+        ProxyUsageLayer {
+            counters: proxy_url.map(|url| self.counters(url)),
+        }
+    }
+
+    /// Unflushed usage per proxy, without resetting it
+    pub fn snapshot(&self) -> Vec<(String, ProxyUsage)> {
+        let by_proxy = self.by_proxy.lock().unwrap();
+        by_proxy
+            .iter()
+            .map(|(url, counters)| (url.clone(), counters.snapshot()))
+            .filter(|(_, usage)| !usage.is_empty())
+            .collect()
+    }
+
+    /// Adds the usage collected since the last flush onto `proxy_stats`
+    ///
+    /// Usage that fails to write is put back and retried on the next flush.
+    ///
+    /// # Returns
+    ///
+    /// The number of proxies written
+    pub async fn flush(&self, db: &DatabaseManager) -> Result<usize> {
+        let pending: Vec<(String, Arc<ProxyCounters>)> = {
+            let by_proxy = self.by_proxy.lock().unwrap();
+            by_proxy
+                .iter()
+                .map(|(url, counters)| (url.clone(), counters.clone()))
+                .collect()
+        };
+
+        let mut written = 0;
+        let mut first_error = None;
+        for (url, counters) in pending {
+            let usage = counters.take();
+            if usage.is_empty() {
+                continue;
+            }
+            match db.record_proxy_usage(&url, &usage).await {
+                Ok(()) => written += 1,
+                Err(e) => {
+                    counters.restore(&usage);
+                    first_error.get_or_insert(e);
+                }
+            }
+        }
+
+        match first_error {
+            Some(e) => Err(e),
+            None => Ok(written),
+        }
+    }
+}
+
+/// Tower layer installing [`ProxyUsageService`] in an RPC client
+#[derive(Debug, Clone)]
+pub struct ProxyUsageLayer {
+    counters: Option<Arc<ProxyCounters>>,
+}
+
+impl<S> Layer<S> for ProxyUsageLayer {
+    type Service = ProxyUsageService<S>;
+
+    fn layer(&self, inner: S) -> Self::Service {
+        ProxyUsageService {
+            inner,
+            counters: self.counters.clone(),
+        }
+    }
+}
+
+/// Transport service that records request counts and sizes
+#[derive(Debug, Clone)]
+pub struct ProxyUsageService<S> {
+    inner: S,
+    counters: Option<Arc<ProxyCounters>>,
+}
+
+impl<S> Service<RequestPacket> for ProxyUsageService<S>
+where
+    S: Service<
+            RequestPacket,
+            Response = ResponsePacket,
+            Error = TransportError,
+            Future = TransportFut<'static>,
+        > + Send
+        + 'static,
+{
+    type Response = ResponsePacket;
+    type Error = TransportError;
+    type Future = TransportFut<'static>;
+
+    fn poll_ready(&mut self, cx: &mut Context<'_>) -> Poll<Result<(), Self::Error>> {
+        self.inner.poll_ready(cx)
+    }
+
+    fn call(&mut self, request: RequestPacket) -> Self::Future {
+        let Some(counters) = self.counters.clone() else {
+            return self.inner.call(request);
+        };
+
+        let sent = request_size(&request) + ESTIMATED_HEADER_BYTES;
+        let fut = self.inner.call(request);
+        Box::pin(async move {
+            let result = fut.await;
+            match &result {
+                Ok(response) => counters.record(
+                    sent,
+                    response_size(response) + ESTIMATED_HEADER_BYTES,
+                    response_succeeded(response),
+                ),
+                Err(_) => counters.record(sent, 0, false),
+            }
+            result
+        })
+    }
+}
+
+fn request_size(request: &RequestPacket) -> u64 {
+    match request {
+        RequestPacket::Single(req) => req.serialized().get().len() as u64,
+        // Batches are sent as a JSON array: brackets plus separating commas
+        RequestPacket::Batch(reqs) => {
+            reqs.iter()
+                .map(|req| req.serialized().get().len() as u64)
+                .sum::<u64>()
+                + reqs.len().max(1) as u64
+                + 1
+        }
+    }
+}
+
+fn response_size(response: &ResponsePacket) -> u64 {
+    let len = |r| serde_json::to_vec(r).map_or(0, |bytes| bytes.len() as u64);
+    match response {
+        ResponsePacket::Single(resp) => len(resp),
+        ResponsePacket::Batch(resps) => {
+            resps.iter().map(len).sum::<u64>() + resps.len().max(1) as u64 + 1
+        }
+    }
+}
+
+fn response_succeeded(response: &ResponsePacket) -> bool {
+    match response {
+        ResponsePacket::Single(resp) => resp.is_success(),
+        ResponsePacket::Batch(resps) => resps.iter().all(|resp| resp.is_success()),
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    #[test]
+    fn test_counters_take_and_restore() {
+        let tracker = ProxyUsageTracker::default();
+        let counters = tracker.counters("http://10.0.0.1:8080");
+        counters.record(100, 400, true);
+        counters.record(120, 0, false);
+
+        let usage = counters.take();
+        assert_eq!(usage.requests, 2);
+        assert_eq!(usage.successes, 1);
+        assert_eq!(usage.failures, 1);
+        assert_eq!(usage.bytes_sent, 220);
+        assert_eq!(usage.bytes_received, 400);
+        assert!(tracker.snapshot().is_empty());
+
+        counters.restore(&usage);
+        assert_eq!(
+            tracker.snapshot(),
+            vec![("http://10.0.0.1:8080".to_string(), usage)]
+        );
+    }
+}
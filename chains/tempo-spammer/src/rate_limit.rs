@@ -0,0 +1,653 @@
+//! Rate Limit - Per-endpoint request limits and 429 handling
+//!
+//! At high worker counts the RPC answers part of the traffic with
+//! `429 Too Many Requests`. [`RateLimitLayer`] sits in the transport of each
+//! [`TempoClient`](crate::TempoClient) and keeps every client of the process
+//! in step per endpoint:
+//!
+//! - a token bucket per endpoint spaces requests out to the rate set in
+//!   `[rate_limit]`, by endpoint or for all of them
+//! - a rate-limited answer, an HTTP 429 or a JSON-RPC rate limit error,
+//!   pauses the endpoint for the server's `Retry-After`, or `backoff_ms`
+//!   when it sends none
+//! - idempotent reads are retried up to `max_retries` times once the pause
+//!   is over; transaction submissions are not, so a 429 on a send surfaces
+//!   as a rate limit error instead of being resent behind the task's back
+//!
+//! The alloy HTTP transport drops response headers, so clients use
+//! [`RetryAfterHttp`] instead, which notes `Retry-After` on the limiter
+//! before handing the 429 up.
+//!
+//! # Metrics
+//!
+//! [`RateLimiter::stats`] counts rate-limited answers and retries; the
+//! runner logs them every 30 seconds.
+
+use crate::config::RateLimitSettings;
+use alloy::rpc::json_rpc::{RequestPacket, ResponsePacket};
+use alloy::transports::{TransportError, TransportErrorKind, TransportFut};
+use std::collections::HashMap;
+use std::fmt;
+use std::sync::{Arc, Mutex, OnceLock, RwLock};
+use std::task::{Context, Poll};
+use std::time::{Duration, Instant};
+use tower::{Layer, Service};
+use url::Url;
+
+/// Methods that only read state and are safe to send again
+const IDEMPOTENT_METHODS: [&str; 10] = [
+    "eth_call",
+    "eth_chainId",
+    "eth_blockNumber",
+    "eth_gasPrice",
+    "eth_maxPriorityFeePerGas",
+    "eth_feeHistory",
+    "eth_estimateGas",
+    "eth_syncing",
+    "net_version",
+    "web3_clientVersion",
+];
+
+static LIMITER: OnceLock<Arc<RateLimiter>> = OnceLock::new();
+
+/// Process-wide limiter shared by all clients
+pub fn limiter() -> &'static Arc<RateLimiter> {
+    LIMITER.get_or_init(|| Arc::new(RateLimiter::new(RateLimitSettings::default())))
+}
+
+/// Whether a request may be retried without side effects
+///
+/// `eth_get*` calls and the reads in [`IDEMPOTENT_METHODS`] are; batches are
+/// when every request in them is.
+pub fn is_idempotent(request: &RequestPacket) -> bool {
+    let idempotent =
+        |method: &str| method.starts_with("eth_get") || IDEMPOTENT_METHODS.contains(&method);
+    match request {
+        RequestPacket::Single(req) => idempotent(req.method()),
+        RequestPacket::Batch(reqs) => reqs.iter().all(|req| idempotent(req.method())),
+    }
+}
+
+/// EIP-1474 "limit exceeded", which providers answer request quotas with
+const LIMIT_EXCEEDED_CODE: i64 = -32005;
+
+/// Whether the RPC turned a request down for going over its rate limit
+///
+/// Only an HTTP 429, JSON-RPC code 429 or `-32005`, or a message naming a
+/// rate limit counts: a limited endpoint is paused for every worker, so gas
+/// and execution errors like "gas limit exceeded" must not match.
+pub fn is_rate_limited(response: &Result<ResponsePacket, TransportError>) -> bool {
+    match response {
+        Err(TransportError::Transport(TransportErrorKind::HttpError(err))) => {
+            err.is_rate_limit_err()
+        }
+        Err(_) => false,
+        Ok(packet) => packet.iter_errors().any(|err| {
+            let message = err.message.to_ascii_lowercase();
+            err.code == 429
+                || err.code == LIMIT_EXCEEDED_CODE
+                || message.contains("rate limit")
+                || message.contains("too many requests")
+        }),
+    }
+}
+
+/// Parses a `Retry-After` header: delay seconds or an HTTP date
+pub fn parse_retry_after(value: &str) -> Option<Duration> {
+    let value = value.trim();
+    if let Ok(secs) = value.parse::<f64>() {
+        return (secs.is_finite() && secs >= 0.0).then(|| Duration::from_secs_f64(secs));
+    }
+    let date = chrono::DateTime::parse_from_rfc2822(value).ok()?;
+    let wait = date.signed_duration_since(chrono::Utc::now());
+    Some(wait.to_std().unwrap_or_default())
+}
+
+/// A request given up on because the endpoint keeps rate limiting
+#[derive(Debug, Clone)]
+pub struct RateLimited {
+    pub endpoint: String,
+    /// Pause the endpoint is in when the request was given up on
+    pub retry_after: Duration,
+}
+
+impl fmt::Display for RateLimited {
+    fn fmt(&self, f: &mut fmt::Formatter<'_>) -> fmt::Result {
+        write!(
+            f,
+            "too many requests (429) from {}, retry after {}ms",
+            self.endpoint,
+            self.retry_after.as_millis()
+        )
+    }
+}
+
+impl std::error::Error for RateLimited {}
+
+/// Rate-limited answers and retries over all endpoints
+#[derive(Debug, Clone, Copy, Default, PartialEq, Eq)]
+pub struct RateLimitStats {
+    /// Answers turned down by the RPC for its rate limit
+    pub limited: u64,
+    /// Idempotent requests sent again after a rate-limited answer
+    pub retried: u64,
+    /// Requests handed back as [`RateLimited`]
+    pub given_up: u64,
+}
+
+/// Token bucket and pause of one endpoint
+#[derive(Debug)]
+struct Bucket {
+    tokens: f64,
+    refilled: Instant,
+    paused_until: Option<Instant>,
+}
+
+/// Token buckets and 429 pauses by endpoint
+#[derive(Debug)]
+pub struct RateLimiter {
+    settings: RwLock<RateLimitSettings>,
+    buckets: Mutex<HashMap<String, Bucket>>,
+    stats: Mutex<RateLimitStats>,
+}
+
+impl RateLimiter {
+    pub fn new(settings: RateLimitSettings) -> Self {
+        Self {
+            settings: RwLock::new(settings),
+            buckets: Mutex::new(HashMap::new()),
+            stats: Mutex::new(RateLimitStats::default()),
+        }
+    }
+
+    /// Applies `[rate_limit]`, dropping the buckets and pauses so far
+    pub fn configure(&self, settings: RateLimitSettings) {
+        *self.settings.write().unwrap() = settings;
+        self.buckets.lock().unwrap().clear();
+    }
+
+    /// Transport layer limiting requests to the RPC at `endpoint`
+    pub fn layer(self: &Arc<Self>, endpoint: &str) -> RateLimitLayer {
+        RateLimitLayer {
+            limiter: self.clone(),
+            endpoint: endpoint.into(),
+        }
+    }
+
+    /// HTTP transport to `url` noting `Retry-After` on this limiter
+    pub fn transport(self: &Arc<Self>, client: reqwest::Client, url: Url) -> RetryAfterHttp {
+        RetryAfterHttp {
+            client,
+            endpoint: url.as_str().trim_end_matches('/').into(),
+            url,
+            limiter: self.clone(),
+        }
+    }
+
+    /// Rate-limited answers and retries so far
+    pub fn stats(&self) -> RateLimitStats {
+        *self.stats.lock().unwrap()
+    }
+
+    /// Waits until a request to `endpoint` may go out
+    pub async fn acquire(&self, endpoint: &str) {
+        loop {
+            let wait = self.reserve(endpoint, Instant::now());
+            if wait.is_zero() {
+                return;
+            }
+            tokio::time::sleep(wait).await;
+        }
+    }
+
+    /// Pauses `endpoint` after a rate-limited answer, returning the pause
+    ///
+    /// Without `retry_after` a pause already in force is kept, so the
+    /// server's `Retry-After` noted by [`RetryAfterHttp`] wins; otherwise the
+    /// endpoint waits `backoff_ms`. Pauses are capped at `max_backoff_ms`.
+    pub fn pause(&self, endpoint: &str, retry_after: Option<Duration>) -> Duration {
+        let (backoff, max_backoff) = {
+            let settings = self.settings.read().unwrap();
+            (
+                Duration::from_millis(settings.backoff_ms),
+                Duration::from_millis(settings.max_backoff_ms),
+            )
+        };
+        let now = Instant::now();
+        let mut buckets = self.buckets.lock().unwrap();
+        let bucket = buckets.entry(key(endpoint)).or_insert_with(|| Bucket {
+            tokens: 0.0,
+            refilled: now,
+            paused_until: None,
+        });
+        let current = bucket
+            .paused_until
+            .filter(|until| *until > now)
+            .map(|until| until - now);
+        let wait = match (retry_after, current) {
+            (Some(retry_after), _) => retry_after.min(max_backoff),
+            (None, Some(current)) => return current,
+            (None, None) => backoff.min(max_backoff),
+        };
+        bucket.paused_until = Some(now + wait);
+        wait
+    }
+
+    /// Takes a token for `endpoint`, or returns how long to wait for one
+    fn reserve(&self, endpoint: &str, now: Instant) -> Duration {
+        let (rate, burst) = {
+            let settings = self.settings.read().unwrap();
+            (
+                settings.rate_for(endpoint),
+                f64::from(settings.burst.max(1)),
+            )
+        };
+        let mut buckets = self.buckets.lock().unwrap();
+        let bucket = buckets.entry(key(endpoint)).or_insert_with(|| Bucket {
+            tokens: burst,
+            refilled: now,
+            paused_until: None,
+        });
+        if let Some(until) = bucket.paused_until {
+            if until > now {
+                return until - now;
+            }
+            bucket.paused_until = None;
+        }
+        if rate <= 0.0 {
+            return Duration::ZERO;
+        }
+
+        let elapsed = now.saturating_duration_since(bucket.refilled).as_secs_f64();
+        bucket.tokens = (bucket.tokens + elapsed * rate).min(burst);
+        bucket.refilled = now;
+        if bucket.tokens >= 1.0 {
+            bucket.tokens -= 1.0;
+            Duration::ZERO
+        } else {
+            Duration::from_secs_f64((1.0 - bucket.tokens) / rate)
+        }
+    }
+
+    fn max_retries(&self) -> u32 {
+        self.settings.read().unwrap().max_retries
+    }
+
+    fn count(&self, update: impl FnOnce(&mut RateLimitStats)) {
+        update(&mut self.stats.lock().unwrap());
+    }
+}
+
+/// Endpoints are matched without a trailing slash
+fn key(endpoint: &str) -> String {
+    endpoint.trim_end_matches('/').to_string()
+}
+
+/// Tower layer installing [`RateLimitService`] in an RPC client
+#[derive(Debug, Clone)]
+pub struct RateLimitLayer {
+    limiter: Arc<RateLimiter>,
+    endpoint: Arc<str>,
+}
+
+impl<S> Layer<S> for RateLimitLayer {
+    type Service = RateLimitService<S>;
+
+    fn layer(&self, inner: S) -> Self::Service {
+        RateLimitService {
+            inner,
+            limiter: self.limiter.clone(),
+            endpoint: self.endpoint.clone(),
+        }
+    }
+}
+
+/// Transport service spacing requests out and retrying rate-limited reads
+#[derive(Debug, Clone)]
+pub struct RateLimitService<S> {
+    inner: S,
+    limiter: Arc<RateLimiter>,
+    endpoint: Arc<str>,
+}
+
+impl<S> Service<RequestPacket> for RateLimitService<S>
+where
+    S: Service<
+            RequestPacket,
+            Response = ResponsePacket,
+            Error = TransportError,
+            Future = TransportFut<'static>,
+        > + Clone
+        + Send
+        + 'static,
+{
+    type Response = ResponsePacket;
+    type Error = TransportError;
+    type Future = TransportFut<'static>;
+
+    fn poll_ready(&mut self, cx: &mut Context<'_>) -> Poll<Result<(), Self::Error>> {
+        self.inner.poll_ready(cx)
+    }
+
+    fn call(&mut self, request: RequestPacket) -> Self::Future {
+        let inner = self.inner.clone();
+        let mut inner = std::mem::replace(&mut self.inner, inner);
+        let limiter = self.limiter.clone();
+        let endpoint = self.endpoint.clone();
+        let retries = if is_idempotent(&request) {
+            limiter.max_retries()
+        } else {
+            0
+        };
+
+        Box::pin(async move {
+            let mut attempt = 0;
+            loop {
+                limiter.acquire(&endpoint).await;
+                let response = inner.call(request.clone()).await;
+                if !is_rate_limited(&response) {
+                    return response;
+                }
+
+                let wait = limiter.pause(&endpoint, None);
+                limiter.count(|stats| stats.limited += 1);
+                if attempt >= retries {
+                    limiter.count(|stats| stats.given_up += 1);
+                    return Err(TransportErrorKind::custom(RateLimited {
+                        endpoint: endpoint.to_string(),
+                        retry_after: wait,
+                    }));
+                }
+                attempt += 1;
+                limiter.count(|stats| stats.retried += 1);
+                tracing::debug!(
+                    "Rate limited by {}, retry {}/{} in {}ms",
+                    endpoint,
+                    attempt,
+                    retries,
+                    wait.as_millis()
+                );
+            }
+        })
+    }
+}
+
+/// HTTP transport like alloy's `Http<reqwest::Client>` that keeps `Retry-After`
+///
+/// On a 429 answer the header is noted as a pause on the [`RateLimiter`]
+/// before the usual HTTP error is returned.
+#[derive(Debug, Clone)]
+pub struct RetryAfterHttp {
+    client: reqwest::Client,
+    url: Url,
+    endpoint: Arc<str>,
+    limiter: Arc<RateLimiter>,
+}
+
+impl RetryAfterHttp {
+    async fn send(self, request: RequestPacket) -> Result<ResponsePacket, TransportError> {
+        let response = self
+            .client
+            .post(self.url)
+            .json(&request)
+            .headers(request.headers())
+            .send()
+            .await
+            .map_err(TransportErrorKind::custom)?;
+        let status = response.status();
+        if status == reqwest::StatusCode::TOO_MANY_REQUESTS {
+            let retry_after = response
+                .headers()
+                .get(reqwest::header::RETRY_AFTER)
+                .and_then(|value| value.to_str().ok())
+                .and_then(parse_retry_after);
+            if let Some(retry_after) = retry_after {
+                self.limiter.pause(&self.endpoint, Some(retry_after));
+            }
+        }
+
+        let body = response.bytes().await.map_err(TransportErrorKind::custom)?;
+        if !status.is_success() {
+            return Err(TransportErrorKind::http_error(
+                status.as_u16(),
+                String::from_utf8_lossy(&body).into_owned(),
+            ));
+        }
+        serde_json::from_slice(&body)
+            .map_err(|err| TransportError::deser_err(err, String::from_utf8_lossy(&body)))
+    }
+}
+
+impl Service<RequestPacket> for RetryAfterHttp {
+    type Response = ResponsePacket;
+    type Error = TransportError;
+    type Future = TransportFut<'static>;
+
+    fn poll_ready(&mut self, _cx: &mut Context<'_>) -> Poll<Result<(), Self::Error>> {
+        Poll::Ready(Ok(()))
+    }
+
+    fn call(&mut self, request: RequestPacket) -> Self::Future {
+        Box::pin(self.clone().send(request))
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use alloy::rpc::json_rpc::{Id, Request};
+    use serde_json::{Value, json};
+    use std::collections::BTreeMap;
+    use std::sync::atomic::{AtomicUsize, Ordering};
+
+    /// Answers the first `limited` calls with a 429, then with `0x1`
+    #[derive(Clone)]
+    struct Upstream {
+        limited: usize,
+        calls: Arc<AtomicUsize>,
+    }
+
+    impl Service<RequestPacket> for Upstream {
+        type Response = ResponsePacket;
+        type Error = TransportError;
+        type Future = TransportFut<'static>;
+
+        fn poll_ready(&mut self, _cx: &mut Context<'_>) -> Poll<Result<(), Self::Error>> {
+            Poll::Ready(Ok(()))
+        }
+
+        fn call(&mut self, packet: RequestPacket) -> Self::Future {
+            let call = self.calls.fetch_add(1, Ordering::SeqCst);
+            let limited = call < self.limited;
+            let RequestPacket::Single(req) = packet else {
+                unreachable!("tests send single requests")
+            };
+            let response = format!(
+                r#"{{"jsonrpc":"2.0","id":{},"result":"0x1"}}"#,
+                serde_json::to_string(req.id()).unwrap(),
+            );
+            Box::pin(async move {
+                if limited {
+                    return Err(TransportErrorKind::http_error(429, String::new()));
+                }
+                serde_json::from_str(&response).map_err(TransportErrorKind::custom)
+            })
+        }
+    }
+
+    fn request(method: &'static str, params: Value) -> RequestPacket {
+        RequestPacket::Single(
+            Request::new(method, Id::Number(1), params)
+                .serialize()
+                .unwrap(),
+        )
+    }
+
+    fn settings() -> RateLimitSettings {
+        RateLimitSettings {
+            backoff_ms: 10,
+            max_backoff_ms: 50,
+            ..RateLimitSettings::default()
+        }
+    }
+
+    fn service(
+        limiter: &Arc<RateLimiter>,
+        limited: usize,
+    ) -> (RateLimitService<Upstream>, Arc<AtomicUsize>) {
+        let calls = Arc::new(AtomicUsize::new(0));
+        let upstream = Upstream {
+            limited,
+            calls: calls.clone(),
+        };
+        (limiter.layer("http://rpc.test").layer(upstream), calls)
+    }
+
+    #[test]
+    fn test_parse_retry_after() {
+        assert_eq!(parse_retry_after("3"), Some(Duration::from_secs(3)));
+        assert_eq!(parse_retry_after(" 0.5 "), Some(Duration::from_millis(500)));
+        assert_eq!(
+            parse_retry_after("Wed, 21 Oct 2015 07:28:00 GMT"),
+            Some(Duration::ZERO)
+        );
+        assert_eq!(parse_retry_after("-1"), None);
+        assert_eq!(parse_retry_after("soon"), None);
+    }
+
+    fn rpc_error(code: i64, message: &str) -> Result<ResponsePacket, TransportError> {
+        Ok(serde_json::from_value(json!({
+            "jsonrpc": "2.0",
+            "id": 1,
+            "error": { "code": code, "message": message }
+        }))
+        .unwrap())
+    }
+
+    #[test]
+    fn test_rate_limit_errors() {
+        assert!(is_rate_limited(&Err(TransportErrorKind::http_error(
+            429,
+            String::new()
+        ))));
+        assert!(is_rate_limited(&rpc_error(-32005, "limit exceeded")));
+        assert!(is_rate_limited(&rpc_error(-32000, "Rate limit reached")));
+        assert!(is_rate_limited(&rpc_error(-32000, "Too Many Requests")));
+
+        // Gas and execution limits are not rate limits
+        assert!(!is_rate_limited(&rpc_error(-32000, "gas limit exceeded")));
+        assert!(!is_rate_limited(&rpc_error(
+            -32000,
+            "exceeds block gas limit: block gas limit exceeded"
+        )));
+        assert!(!is_rate_limited(&rpc_error(
+            3,
+            "execution reverted: daily limit exceeded"
+        )));
+    }
+
+    #[test]
+    fn test_idempotent_methods() {
+        assert!(is_idempotent(&request("eth_getBalance", json!([]))));
+        assert!(is_idempotent(&request("eth_call", json!([]))));
+        assert!(!is_idempotent(&request(
+            "eth_sendRawTransaction",
+            json!([])
+        )));
+    }
+
+    #[test]
+    fn test_token_bucket() {
+        let limiter = RateLimiter::new(RateLimitSettings {
+            requests_per_second: 10.0,
+            burst: 2,
+            endpoints: BTreeMap::from([("http://fast.test".to_string(), 1000.0)]),
+            ..settings()
+        });
+        let now = Instant::now();
+        assert!(limiter.reserve("http://rpc.test", now).is_zero());
+        assert!(limiter.reserve("http://rpc.test/", now).is_zero());
+        assert_eq!(
+            limiter.reserve("http://rpc.test", now),
+            Duration::from_millis(100)
+        );
+        assert!(
+            limiter
+                .reserve("http://rpc.test", now + Duration::from_millis(100))
+                .is_zero()
+        );
+
+        // Other endpoints have their own bucket and rate
+        assert!(limiter.reserve("http://fast.test", now).is_zero());
+        assert!(limiter.reserve("http://fast.test", now).is_zero());
+        assert_eq!(
+            limiter.reserve("http://fast.test", now),
+            Duration::from_millis(1)
+        );
+    }
+
+    #[test]
+    fn test_retry_after_overrides_backoff() {
+        let limiter = RateLimiter::new(settings());
+        assert_eq!(
+            limiter.pause("http://rpc.test", Some(Duration::from_millis(30))),
+            Duration::from_millis(30)
+        );
+        // A later 429 without a header keeps the server's pause
+        assert!(limiter.pause("http://rpc.test", None) <= Duration::from_millis(30));
+        assert_eq!(
+            limiter.pause("http://rpc.test", Some(Duration::from_secs(60))),
+            Duration::from_millis(50)
+        );
+        assert!(!limiter.reserve("http://rpc.test", Instant::now()).is_zero());
+    }
+
+    #[tokio::test]
+    async fn test_reads_retried_after_429() {
+        let limiter = Arc::new(RateLimiter::new(settings()));
+        let (mut service, calls) = service(&limiter, 2);
+
+        let response = service.call(request("eth_blockNumber", json!([]))).await;
+        assert!(response.is_ok());
+        assert_eq!(calls.load(Ordering::SeqCst), 3);
+        assert_eq!(
+            limiter.stats(),
+            RateLimitStats {
+                limited: 2,
+                retried: 2,
+                given_up: 0
+            }
+        );
+    }
+
+    #[tokio::test]
+    async fn test_sends_not_retried() {
+        let limiter = Arc::new(RateLimiter::new(settings()));
+        let (mut service, calls) = service(&limiter, 1);
+
+        let err = service
+            .call(request("eth_sendRawTransaction", json!(["0x00"])))
+            .await
+            .unwrap_err();
+        assert!(
+            err.to_string().contains("too many requests (429)"),
+            "{}",
+            err
+        );
+        assert_eq!(calls.load(Ordering::SeqCst), 1);
+        assert_eq!(limiter.stats().given_up, 1);
+    }
+
+    #[tokio::test]
+    async fn test_reads_give_up_after_max_retries() {
+        let limiter = Arc::new(RateLimiter::new(RateLimitSettings {
+            max_retries: 1,
+            ..settings()
+        }));
+        let (mut service, calls) = service(&limiter, 5);
+
+        let response = service.call(request("eth_chainId", json!([]))).await;
+        assert!(response.is_err());
+        assert_eq!(calls.load(Ordering::SeqCst), 2);
+    }
+}
@@ -0,0 +1,594 @@
+//! RPC Recording - Node answers written to a file and served back in tests
+//!
+//! Some failures only happen against the real node: an odd receipt, a stale
+//! nonce, a 502 from the load balancer. With `[rpc_recording] enabled`, the
+//! [`RecordLayer`] of every [`TempoClient`] appends each JSON-RPC request and
+//! the node's answer to `path`, one JSON line per request:
+//!
+//! ```json
+//! {"ts":1760000000123,"wallet":"0x…","endpoint":"https://rpc.moderato.tempo.xyz","method":"eth_getTransactionReceipt","params":["0x…"],"result":null,"duration_ms":41}
+//! ```
+//!
+//! Failed requests carry the JSON-RPC `error` object or, when no answer came
+//! back at all, a `transport_error` message instead of `result`. The layer is
+//! the innermost one, so every attempt that goes out is recorded as answered,
+//! retries included, and answers served by the [`rpc_cache`](crate::rpc_cache)
+//! are not. Endpoints are cut to scheme and host so API keys in the URL path
+//! stay out of the file.
+//!
+//! # Replay
+//!
+//! [`Recording::load`] reads a file back; [`Recording::wallet`] and
+//! [`Recording::between`] narrow it to one wallet and the failure window, and
+//! [`Recording::client`] builds a [`TempoClient`] answering from it:
+//!
+//! - a request with the recorded method and params gets the recorded answers
+//!   in order
+//! - any other request gets the next unused answer of the same method, so
+//!   transactions signed with another key still get the recorded send results
+//!   and receipts
+//! - once those run out, the last answer served is repeated
+//! - methods that were never recorded get a `-32601` error
+//!
+//! Transport errors are replayed as transport errors.
+//!
+//! ```rust,ignore
+//! let recording = Recording::load("rpc-recording.jsonl")?
+//!     .wallet(wallet)
+//!     .between(1_760_000_000_000, 1_760_000_300_000);
+//! let ctx = TaskContext::new(recording.client(TEST_PRIVATE_KEY)?, config, None);
+//! let result = SendTokenTask::new().run(&ctx).await?;
+//! ```
+
+use crate::TempoClient;
+use crate::config::RpcRecordingSettings;
+use alloy::providers::{Provider, ProviderBuilder};
+use alloy::rpc::client::ClientBuilder;
+use alloy::rpc::json_rpc::{RequestPacket, Response, ResponsePacket, SerializedRequest};
+use alloy::signers::local::PrivateKeySigner;
+use alloy::transports::{TransportError, TransportErrorKind, TransportFut};
+use alloy_primitives::Address;
+use anyhow::{Context, Result};
+use serde::{Deserialize, Serialize};
+use serde_json::{Value, json};
+use std::collections::{HashMap, VecDeque};
+use std::fs::{File, OpenOptions};
+use std::io::{BufRead, BufReader, Write};
+use std::path::{Path, PathBuf};
+use std::sync::{Arc, Mutex, OnceLock};
+use std::task::{Context as TaskCx, Poll};
+use std::time::Instant;
+use tower::{Layer, Service};
+use tracing::{info, warn};
+
+static RECORDER: OnceLock<Arc<RpcRecorder>> = OnceLock::new();
+
+/// Process-wide recorder shared by all clients
+pub fn recorder() -> &'static Arc<RpcRecorder> {
+    RECORDER.get_or_init(|| Arc::new(RpcRecorder::default()))
+}
+
+/// One JSON-RPC request and the node's answer
+#[derive(Debug, Clone, PartialEq, Serialize, Deserialize)]
+pub struct RecordedCall {
+    /// Unix time the request was sent, in milliseconds
+    pub ts: i64,
+    pub wallet: Address,
+    /// Scheme and host of the RPC endpoint
+    pub endpoint: String,
+    pub method: String,
+    #[serde(default)]
+    pub params: Value,
+    /// Result of a successful call; absent and `null` both replay as `null`
+    #[serde(default, skip_serializing_if = "Option::is_none")]
+    pub result: Option<Value>,
+    /// JSON-RPC error object of a failed call
+    #[serde(default, skip_serializing_if = "Option::is_none")]
+    pub error: Option<Value>,
+    /// Message of a call that got no JSON-RPC answer
+    #[serde(default, skip_serializing_if = "Option::is_none")]
+    pub transport_error: Option<String>,
+    pub duration_ms: u64,
+}
+
+/// Appends the calls of every client to the `[rpc_recording]` file
+#[derive(Debug, Default)]
+pub struct RpcRecorder {
+    file: Mutex<Option<(PathBuf, File)>>,
+}
+
+impl RpcRecorder {
+    /// Applies `[rpc_recording]`, opening the file for appending or closing it
+    ///
+    /// Keeps an already open file when the path is unchanged.
+    pub fn configure(&self, settings: &RpcRecordingSettings) -> Result<()> {
+        let mut file = self.file.lock().unwrap();
+        if !settings.enabled {
+            *file = None;
+            return Ok(());
+        }
+        let path = PathBuf::from(&settings.path);
+        if file.as_ref().is_some_and(|(open, _)| *open == path) {
+            return Ok(());
+        }
+        let opened = OpenOptions::new()
+            .create(true)
+            .append(true)
+            .open(&path)
+            .with_context(|| format!("Failed to open RPC recording {}", path.display()))?;
+        info!("Recording RPC calls to {}", path.display());
+        *file = Some((path, opened));
+        Ok(())
+    }
+
+    /// Whether calls are being recorded
+    pub fn is_recording(&self) -> bool {
+        self.file.lock().unwrap().is_some()
+    }
+
+    /// Transport layer recording the calls `wallet`'s client makes to `endpoint`
+    pub fn layer(self: &Arc<Self>, wallet: Address, endpoint: &str) -> RecordLayer {
+        let endpoint = url::Url::parse(endpoint)
+            .map(|url| url.origin().ascii_serialization())
+            .unwrap_or_default();
+        RecordLayer {
+            recorder: self.clone(),
+            wallet,
+            endpoint: endpoint.into(),
+        }
+    }
+
+    fn write(&self, calls: &[RecordedCall]) {
+        let mut file = self.file.lock().unwrap();
+        let Some((path, file)) = file.as_mut() else {
+            return;
+        };
+        let mut lines = String::new();
+        for call in calls {
+            if let Ok(line) = serde_json::to_string(call) {
+                lines.push_str(&line);
+                lines.push('\n');
+            }
+        }
+        if let Err(e) = file.write_all(lines.as_bytes()) {
+            warn!("Failed to write RPC recording {}: {}", path.display(), e);
+        }
+    }
+}
+
+/// Tower layer installing [`RecordService`] in an RPC client
+#[derive(Debug, Clone)]
+pub struct RecordLayer {
+    recorder: Arc<RpcRecorder>,
+    wallet: Address,
+    endpoint: Arc<str>,
+}
+
+impl<S> Layer<S> for RecordLayer {
+    type Service = RecordService<S>;
+
+    fn layer(&self, inner: S) -> Self::Service {
+        RecordService {
+            inner,
+            recorder: self.recorder.clone(),
+            wallet: self.wallet,
+            endpoint: self.endpoint.clone(),
+        }
+    }
+}
+
+/// Transport service writing every request and its answer to the recording
+#[derive(Debug, Clone)]
+pub struct RecordService<S> {
+    inner: S,
+    recorder: Arc<RpcRecorder>,
+    wallet: Address,
+    endpoint: Arc<str>,
+}
+
+impl<S> Service<RequestPacket> for RecordService<S>
+where
+    S: Service<
+            RequestPacket,
+            Response = ResponsePacket,
+            Error = TransportError,
+            Future = TransportFut<'static>,
+        > + Send
+        + 'static,
+{
+    type Response = ResponsePacket;
+    type Error = TransportError;
+    type Future = TransportFut<'static>;
+
+    fn poll_ready(&mut self, cx: &mut TaskCx<'_>) -> Poll<Result<(), Self::Error>> {
+        self.inner.poll_ready(cx)
+    }
+
+    fn call(&mut self, request: RequestPacket) -> Self::Future {
+        if !self.recorder.is_recording() {
+            return self.inner.call(request);
+        }
+
+        let ts = chrono::Utc::now().timestamp_millis();
+        let requests: Vec<SerializedRequest> = match &request {
+            RequestPacket::Single(req) => vec![req.clone()],
+            RequestPacket::Batch(reqs) => reqs.clone(),
+        };
+        let recorder = self.recorder.clone();
+        let wallet = self.wallet;
+        let endpoint = self.endpoint.clone();
+        let fut = self.inner.call(request);
+
+        Box::pin(async move {
+            let started = Instant::now();
+            let result = fut.await;
+            let duration_ms = started.elapsed().as_millis() as u64;
+
+            let responses: &[Response] = match &result {
+                Ok(ResponsePacket::Single(resp)) => std::slice::from_ref(resp),
+                Ok(ResponsePacket::Batch(resps)) => resps,
+                Err(_) => &[],
+            };
+            let calls: Vec<RecordedCall> = requests
+                .iter()
+                .map(|req| {
+                    let mut call = RecordedCall {
+                        ts,
+                        wallet,
+                        endpoint: endpoint.to_string(),
+                        method: req.method().to_string(),
+                        params: req
+                            .params()
+                            .and_then(|raw| serde_json::from_str(raw.get()).ok())
+                            .unwrap_or(Value::Null),
+                        result: None,
+                        error: None,
+                        transport_error: None,
+                        duration_ms,
+                    };
+                    match (&result, responses.iter().find(|resp| resp.id == *req.id())) {
+                        (Err(e), _) => call.transport_error = Some(e.to_string()),
+                        (Ok(_), Some(resp)) => match resp.payload.as_success() {
+                            Some(raw) => call.result = serde_json::from_str(raw.get()).ok(),
+                            None => {
+                                call.error = resp
+                                    .payload
+                                    .as_error()
+                                    .and_then(|e| serde_json::to_value(e).ok())
+                            }
+                        },
+                        (Ok(_), None) => {
+                            call.transport_error = Some("no answer in batch".to_string())
+                        }
+                    }
+                    call
+                })
+                .collect();
+            recorder.write(&calls);
+            result
+        })
+    }
+}
+
+/// Recorded calls, read back for replay
+#[derive(Debug, Clone, Default)]
+pub struct Recording {
+    calls: Vec<RecordedCall>,
+}
+
+impl Recording {
+    /// Recording of the given calls
+    pub fn new(calls: Vec<RecordedCall>) -> Self {
+        Self { calls }
+    }
+
+    /// Reads a recording file, skipping lines that do not parse
+    pub fn load(path: impl AsRef<Path>) -> Result<Self> {
+        let path = path.as_ref();
+        let file = File::open(path)
+            .with_context(|| format!("Failed to open RPC recording {}", path.display()))?;
+        let mut calls = Vec::new();
+        for (line_no, line) in BufReader::new(file).lines().enumerate() {
+            let line = line.with_context(|| format!("Failed to read {}", path.display()))?;
+            if line.trim().is_empty() {
+                continue;
+            }
+            match serde_json::from_str(&line) {
+                Ok(call) => calls.push(call),
+                Err(e) => warn!("Skipping malformed recording line {}: {}", line_no + 1, e),
+            }
+        }
+        Ok(Self { calls })
+    }
+
+    /// Only the calls of `wallet`
+    pub fn wallet(mut self, wallet: Address) -> Self {
+        self.calls.retain(|call| call.wallet == wallet);
+        self
+    }
+
+    /// Only the calls sent with `since_ms <= ts <= until_ms`
+    pub fn between(mut self, since_ms: i64, until_ms: i64) -> Self {
+        self.calls
+            .retain(|call| (since_ms..=until_ms).contains(&call.ts));
+        self
+    }
+
+    pub fn calls(&self) -> &[RecordedCall] {
+        &self.calls
+    }
+
+    /// A client signing with `private_key` whose transport answers from this
+    /// recording
+    ///
+    /// The chain id is the recorded `eth_chainId` answer, if any.
+    pub fn client(&self, private_key: &str) -> Result<TempoClient> {
+        let signer: PrivateKeySigner =
+            private_key.parse().context("Failed to parse private key")?;
+        let chain_id = self
+            .calls
+            .iter()
+            .filter(|call| call.method == "eth_chainId")
+            .find_map(|call| call.result.as_ref()?.as_str().map(str::to_string))
+            .and_then(|id| u64::from_str_radix(id.trim_start_matches("0x"), 16).ok())
+            .or(signer.chain_id())
+            .unwrap_or(42431);
+        let transport = ReplayTransport {
+            state: Arc::new(Mutex::new(ReplayState::new(self.calls.clone()))),
+        };
+
+        let provider: Arc<dyn Provider + Send + Sync> = Arc::new(
+            ProviderBuilder::new()
+                .wallet(signer.clone())
+                .connect_client(
+                    ClientBuilder::default()
+                        .layer(crate::audit::AuditLayer)
+                        .transport(transport, true),
+                ),
+        );
+        Ok(TempoClient {
+            provider,
+            signer,
+            chain_id,
+            proxy_config: None,
+            proxy_index: None,
+            nonce_manager: None,
+            robust_nonce_manager: None,
+            use_pending_count: false,
+        })
+    }
+}
+
+/// Recorded answers and which of them were served
+#[derive(Debug)]
+struct ReplayState {
+    calls: Vec<RecordedCall>,
+    used: Vec<bool>,
+    /// Indices of the calls per method and params
+    exact: HashMap<(String, String), VecDeque<usize>>,
+    /// Last answer served per method and params
+    last_exact: HashMap<(String, String), usize>,
+    /// Indices of the calls per method
+    by_method: HashMap<String, VecDeque<usize>>,
+    /// Last answer served per method
+    last_by_method: HashMap<String, usize>,
+}
+
+impl ReplayState {
+    fn new(calls: Vec<RecordedCall>) -> Self {
+        let mut exact: HashMap<(String, String), VecDeque<usize>> = HashMap::new();
+        let mut by_method: HashMap<String, VecDeque<usize>> = HashMap::new();
+        for (idx, call) in calls.iter().enumerate() {
+            exact
+                .entry((call.method.clone(), call.params.to_string()))
+                .or_default()
+                .push_back(idx);
+            by_method
+                .entry(call.method.clone())
+                .or_default()
+                .push_back(idx);
+        }
+        Self {
+            used: vec![false; calls.len()],
+            calls,
+            exact,
+            last_exact: HashMap::new(),
+            by_method,
+            last_by_method: HashMap::new(),
+        }
+    }
+
+    /// Index of the recorded call answering `method` with `params`
+    fn next(&mut self, method: &str, params: &Value) -> Option<usize> {
+        let key = (method.to_string(), params.to_string());
+        if let Some(queue) = self.exact.get_mut(&key) {
+            if let Some(idx) = std::iter::from_fn(|| queue.pop_front()).find(|idx| !self.used[*idx])
+            {
+                self.last_exact.insert(key, idx);
+                return Some(self.serve(method, idx));
+            }
+            if let Some(idx) = self.last_exact.get(&key) {
+                return Some(*idx);
+            }
+        }
+        let queue = self.by_method.get_mut(method)?;
+        match std::iter::from_fn(|| queue.pop_front()).find(|idx| !self.used[*idx]) {
+            Some(idx) => Some(self.serve(method, idx)),
+            None => self.last_by_method.get(method).copied(),
+        }
+    }
+
+    /// Marks the call at `idx` as served for `method`
+    fn serve(&mut self, method: &str, idx: usize) -> usize {
+        self.used[idx] = true;
+        self.last_by_method.insert(method.to_string(), idx);
+        idx
+    }
+}
+
+/// Transport answering every request from a [`Recording`]
+#[derive(Clone)]
+struct ReplayTransport {
+    state: Arc<Mutex<ReplayState>>,
+}
+
+impl ReplayTransport {
+    fn respond(&self, request: &SerializedRequest) -> Result<Value, String> {
+        let params: Value = request
+            .params()
+            .and_then(|raw| serde_json::from_str(raw.get()).ok())
+            .unwrap_or(Value::Null);
+        let mut response = json!({"jsonrpc": "2.0", "id": request.id()});
+        let mut state = self.state.lock().unwrap();
+        match state.next(request.method(), &params) {
+            Some(idx) => {
+                let call = &state.calls[idx];
+                if let Some(message) = &call.transport_error {
+                    return Err(message.clone());
+                }
+                match &call.error {
+                    Some(error) => response["error"] = error.clone(),
+                    None => response["result"] = call.result.clone().unwrap_or(Value::Null),
+                }
+            }
+            None => {
+                response["error"] = json!({
+                    "code": -32601,
+                    "message": format!("{} not in recording", request.method()),
+                })
+            }
+        }
+        Ok(response)
+    }
+}
+
+impl Service<RequestPacket> for ReplayTransport {
+    type Response = ResponsePacket;
+    type Error = TransportError;
+    type Future = TransportFut<'static>;
+
+    fn poll_ready(&mut self, _cx: &mut TaskCx<'_>) -> Poll<Result<(), Self::Error>> {
+        Poll::Ready(Ok(()))
+    }
+
+    fn call(&mut self, packet: RequestPacket) -> Self::Future {
+        let response = match &packet {
+            RequestPacket::Single(request) => self.respond(request),
+            RequestPacket::Batch(requests) => requests
+                .iter()
+                .map(|r| self.respond(r))
+                .collect::<Result<Vec<_>, _>>()
+                .map(Value::Array),
+        };
+        Box::pin(async move {
+            match response {
+                Ok(response) => {
+                    serde_json::from_str(&response.to_string()).map_err(TransportErrorKind::custom)
+                }
+                Err(message) => Err(TransportErrorKind::custom_str(&message)),
+            }
+        })
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use crate::testing::TEST_PRIVATE_KEY;
+
+    fn recorded(method: &str, params: Value, result: Value) -> RecordedCall {
+        RecordedCall {
+            ts: 1_000,
+            wallet: Address::ZERO,
+            endpoint: "https://rpc.example".to_string(),
+            method: method.to_string(),
+            params,
+            result: Some(result),
+            error: None,
+            transport_error: None,
+            duration_ms: 5,
+        }
+    }
+
+    #[tokio::test]
+    async fn test_replay_serves_recorded_answers() {
+        let mut failed = recorded("eth_getCode", json!([Address::ZERO, "latest"]), Value::Null);
+        failed.result = None;
+        failed.error = Some(json!({"code": -32000, "message": "header not found"}));
+        let mut dropped = recorded("eth_gasPrice", json!([]), Value::Null);
+        dropped.transport_error = Some("HTTP error 502".to_string());
+        let recording = Recording::new(vec![
+            recorded("eth_chainId", json!([]), json!("0xa5bf")),
+            recorded("eth_blockNumber", json!([]), json!("0x10")),
+            recorded("eth_blockNumber", json!([]), json!("0x11")),
+            failed,
+            dropped,
+        ]);
+
+        let client = recording.client(TEST_PRIVATE_KEY).unwrap();
+        assert_eq!(client.chain_id, 0xa5bf);
+        let provider = &client.provider;
+        assert_eq!(provider.get_block_number().await.unwrap(), 0x10);
+        assert_eq!(provider.get_block_number().await.unwrap(), 0x11);
+        // The last answer repeats once the recorded ones run out
+        assert_eq!(provider.get_block_number().await.unwrap(), 0x11);
+
+        let error = provider.get_code_at(Address::ZERO).await.unwrap_err();
+        assert!(error.to_string().contains("header not found"));
+        let error = provider.get_gas_price().await.unwrap_err();
+        assert!(error.to_string().contains("HTTP error 502"));
+        let error = provider.get_accounts().await.unwrap_err();
+        assert!(error.to_string().contains("eth_accounts not in recording"));
+    }
+
+    #[tokio::test]
+    async fn test_record_layer_writes_what_replays() {
+        let path = std::env::temp_dir().join(format!(
+            "tempo-recording-{}-{}.jsonl",
+            std::process::id(),
+            fastrand::u64(..)
+        ));
+        let recorder = Arc::new(RpcRecorder::default());
+        recorder
+            .configure(&RpcRecordingSettings {
+                enabled: true,
+                path: path.to_string_lossy().into_owned(),
+            })
+            .unwrap();
+
+        let source = Recording::new(vec![
+            recorded("eth_blockNumber", json!([]), json!("0x2a")),
+            recorded("eth_getTransactionReceipt", json!(["0xab"]), Value::Null),
+        ]);
+        let wallet = Address::repeat_byte(7);
+        let transport = ReplayTransport {
+            state: Arc::new(Mutex::new(ReplayState::new(source.calls().to_vec()))),
+        };
+        let client = ClientBuilder::default()
+            .layer(recorder.layer(wallet, "https://rpc.example/v1/secret-key"))
+            .transport(transport, true);
+        let _: Value = client.request("eth_blockNumber", ()).await.unwrap();
+        let _: Value = client
+            .request("eth_getTransactionReceipt", ("0xab",))
+            .await
+            .unwrap();
+        recorder
+            .configure(&RpcRecordingSettings {
+                enabled: false,
+                path: String::new(),
+            })
+            .unwrap();
+
+        let recording = Recording::load(&path).unwrap();
+        std::fs::remove_file(&path).unwrap();
+        let calls = recording.calls().to_vec();
+        assert_eq!(calls.len(), 2);
+        assert_eq!(calls[0].wallet, wallet);
+        assert_eq!(calls[0].endpoint, "https://rpc.example");
+        assert_eq!(calls[0].result, Some(json!("0x2a")));
+        assert_eq!(calls[1].params, json!(["0xab"]));
+        assert_eq!(calls[1].result, None);
+        assert_eq!(recording.clone().wallet(Address::ZERO).calls().len(), 0);
+        assert_eq!(recording.between(0, calls[0].ts - 1).calls().len(), 0);
+    }
+}
@@ -0,0 +1,238 @@
+//! Remote Config - Centrally tuned task weights and schedules
+//!
+//! A fleet of spammer hosts can be re-tuned without touching each machine:
+//! with `[remote] url` set, the runner polls a JSON or TOML document holding
+//! the same `weights`, `schedule` and `timeouts` tables as the local
+//! `[tasks]` section and lays it over the local settings.
+//!
+//! ```toml
+//! [weights]
+//! "03_send_token" = 20
+//! "21_create_meme" = 0
+//!
+//! [schedule]
+//! "45_deploy_viral_faucet" = "02-05"
+//! ```
+//!
+//! # Verification
+//!
+//! Every document must come with a hex digest published at
+//! `signature_url` (default: the document URL plus `.sig`, `sha256sum`
+//! output is accepted). When the environment variable named by `key_env`
+//! is set the digest is an HMAC-SHA256 with that key, so only holders of the
+//! key can re-tune the fleet; otherwise it is a plain SHA-256 checksum that
+//! catches truncated or mangled downloads. Documents that fail verification
+//! are ignored.
+//!
+//! # Fallback
+//!
+//! The last verified document is kept in `cache_file`. If the URL is
+//! unreachable at startup the cached copy is used, and without one the local
+//! `[tasks]` settings apply. Failed polls later on keep the settings in use.
+
+use crate::config::{RemoteSettings, TaskSettings};
+use anyhow::{Context, Result, bail};
+use hmac::{Hmac, Mac};
+use sha2::{Digest, Sha256};
+use std::time::Duration;
+use tokio::sync::watch;
+use tokio::task::JoinHandle;
+use tracing::{info, warn};
+
+/// Timeout for each document or signature download
+const FETCH_TIMEOUT: Duration = Duration::from_secs(15);
+
+/// Parses a remote document as JSON (when it starts with `{`) or TOML
+pub fn parse_document(body: &str) -> Result<TaskSettings> {
+    if body.trim_start().starts_with('{') {
+        serde_json::from_str(body).context("Failed to parse remote task settings JSON")
+    } else {
+        toml::from_str(body).context("Failed to parse remote task settings TOML")
+    }
+}
+
+/// Checks a document against its published hex digest
+///
+/// With a key the digest must be the HMAC-SHA256 of the body, otherwise its
+/// SHA-256. Only the first whitespace-separated token of `digest` is used.
+pub fn verify(body: &[u8], digest: &str, key: Option<&[u8]>) -> Result<()> {
+    let token = digest.split_whitespace().next().unwrap_or_default();
+    let expected = hex::decode(token).context("Signature is not hex")?;
+
+    match key {
+        Some(key) => {
+            let mut mac = Hmac::<Sha256>::new_from_slice(key).context("Invalid HMAC key")?;
+            mac.update(body);
+            mac.verify_slice(&expected)
+                .map_err(|_| anyhow::anyhow!("HMAC signature mismatch"))
+        }
+        None => {
+            if Sha256::digest(body).as_slice() != expected.as_slice() {
+                bail!("SHA-256 checksum mismatch");
+            }
+            Ok(())
+        }
+    }
+}
+
+/// Downloads and verifies the remote document
+///
+/// # Returns
+///
+/// The raw document and its parsed settings
+pub async fn fetch(
+    client: &reqwest::Client,
+    settings: &RemoteSettings,
+) -> Result<(String, TaskSettings)> {
+    let body = get_text(client, &settings.url).await?;
+    let digest = get_text(client, &settings.signature_url()).await?;
+
+    let key = std::env::var(&settings.key_env).ok();
+    verify(body.as_bytes(), &digest, key.as_deref().map(str::as_bytes))
+        .with_context(|| format!("Rejected remote task settings from {}", settings.url))?;
+
+    let parsed = parse_document(&body)?;
+    Ok((body, parsed))
+}
+
+async fn get_text(client: &reqwest::Client, url: &str) -> Result<String> {
+    client
+        .get(url)
+        .timeout(FETCH_TIMEOUT)
+        .send()
+        .await
+        .and_then(|response| response.error_for_status())
+        .with_context(|| format!("Failed to fetch {}", url))?
+        .text()
+        .await
+        .with_context(|| format!("Failed to read {}", url))
+}
+
+/// Settings from the last verified document, if one was cached
+pub fn load_cache(path: &str) -> Option<TaskSettings> {
+    let body = std::fs::read_to_string(path).ok()?;
+    match parse_document(&body) {
+        Ok(settings) => Some(settings),
+        Err(e) => {
+            warn!("Ignoring remote settings cache {}: {:#}", path, e);
+            None
+        }
+    }
+}
+
+/// Polls the remote document and publishes `local` overlaid with it
+///
+/// The first poll happens immediately. Updates are only sent when the
+/// merged settings change.
+pub fn spawn_poller(
+    settings: RemoteSettings,
+    local: TaskSettings,
+    tx: watch::Sender<TaskSettings>,
+) -> JoinHandle<()> {
+    tokio::spawn(async move {
+        let client = reqwest::Client::new();
+        let mut interval =
+            tokio::time::interval(Duration::from_secs(settings.poll_interval_secs.max(1)));
+        let mut first_poll = true;
+        let mut failing = false;
+
+        loop {
+            interval.tick().await;
+            match fetch(&client, &settings).await {
+                Ok((body, remote)) => {
+                    if failing {
+                        info!("Remote task settings reachable again");
+                        failing = false;
+                    }
+                    if let Err(e) = std::fs::write(&settings.cache_file, &body) {
+                        warn!("Failed to cache remote task settings: {}", e);
+                    }
+                    publish(&tx, local.overlay(&remote), &settings.url);
+                }
+                Err(e) => {
+                    if !failing {
+                        warn!("Remote task settings unavailable: {:#}", e);
+                        failing = true;
+                    }
+                    if first_poll {
+                        match load_cache(&settings.cache_file) {
+                            Some(cached) => {
+                                publish(&tx, local.overlay(&cached), &settings.cache_file)
+                            }
+                            None => info!("No cached remote task settings, using local [tasks]"),
+                        }
+                    }
+                }
+            }
+            first_poll = false;
+        }
+    })
+}
+
+fn publish(tx: &watch::Sender<TaskSettings>, settings: TaskSettings, source: &str) {
+    let changed = tx.send_if_modified(|current| {
+        if *current == settings {
+            return false;
+        }
+        *current = settings;
+        true
+    });
+    if changed {
+        info!("Task settings updated from {}", source);
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use crate::config::HourWindow;
+
+    const DOCUMENT: &str =
+        "[weights]\n\"03_send_token\" = 20\n\n[schedule]\n\"02_claim_faucet\" = \"22-06\"\n";
+
+    #[test]
+    fn test_parse_json_and_toml() {
+        let from_toml = parse_document(DOCUMENT).unwrap();
+        let from_json = parse_document(
+            r#"{"weights": {"03_send_token": 20}, "schedule": {"02_claim_faucet": "22-06"}}"#,
+        )
+        .unwrap();
+        assert_eq!(from_toml, from_json);
+
+        let window = from_toml.schedule["02_claim_faucet"];
+        assert_eq!(window, HourWindow { start: 22, end: 6 });
+        assert!(window.contains(23) && window.contains(0) && !window.contains(12));
+
+        assert!(parse_document("[weights]\n\"03_send_token\" = -1\n").is_err());
+        assert!(parse_document("[wieghts]\n").is_err());
+    }
+
+    #[test]
+    fn test_verify_checksum_and_hmac() {
+        let body = DOCUMENT.as_bytes();
+        let checksum = hex::encode(Sha256::digest(body));
+        verify(body, &format!("{}  tasks.toml\n", checksum), None).unwrap();
+        assert!(verify(b"[weights]\n", &checksum, None).is_err());
+
+        let mut mac = Hmac::<Sha256>::new_from_slice(b"fleet-key").unwrap();
+        mac.update(body);
+        let signature = hex::encode(mac.finalize().into_bytes());
+        verify(body, &signature, Some(b"fleet-key")).unwrap();
+        assert!(verify(body, &signature, Some(b"other-key")).is_err());
+        // A bare checksum is not accepted once a key is configured
+        assert!(verify(body, &checksum, Some(b"fleet-key")).is_err());
+    }
+
+    #[test]
+    fn test_remote_overlays_local() {
+        let local = parse_document(
+            "[weights]\n\"03_send_token\" = 5\n\"05_swap_stable\" = 3\n\n[timeouts]\n\"01_deploy_contract\" = 90\n",
+        )
+        .unwrap();
+        let merged = local.overlay(&parse_document(DOCUMENT).unwrap());
+        assert_eq!(merged.weights["03_send_token"], 20);
+        assert_eq!(merged.weights["05_swap_stable"], 3);
+        assert_eq!(merged.schedule.len(), 1);
+        assert_eq!(merged.timeouts["01_deploy_contract"], 90);
+    }
+}
@@ -0,0 +1,468 @@
+//! Replay - Audited runs served back to a task offline
+//!
+//! Every `[audit]` sample stores the RPC calls of one task run together with
+//! their results (see [`audit`](crate::audit)). A [`Fixture`] turns a
+//! successful sample into programming for a [`MockTempoClient`]:
+//!
+//! - `eth_call` answers and reverts per contract and selector
+//! - code, the starting nonce and the native balance
+//! - logs `eth_getLogs` returned
+//! - receipt status and logs, one per sent transaction, in order
+//! - `eth_sendRawTransaction` failures, such as `nonce too low`
+//!
+//! [`Fixture::replay`] runs a task against the programmed mock and compares
+//! the transactions it sends with the recorded ones by type, call targets and
+//! selectors. A change to a task is checked against real chain responses in
+//! milliseconds, with no node and no network:
+//!
+//! ```bash
+//! cargo run -p tempo-spammer --example replay_task --features testing -- \
+//!     03_send_token --db tempo-spammer.db
+//! ```
+//!
+//! The recorded wallet is swapped for the mock wallet in all params and
+//! results. Steps cut at `[audit] max_param_bytes` cannot be served and are
+//! counted as skipped; raise the limit while collecting fixtures for tasks
+//! with large calldata. Tasks that pick tokens or contracts at random may go
+//! another way than the recorded run and then miss answers.
+
+use crate::audit::{AuditStep, AuditedTx};
+use crate::tasks::{TaskResult, TempoTask};
+use crate::testing::{MockTempoClient, TaskHarness};
+use alloy_primitives::{Address, B256, Bytes, U256};
+use anyhow::{Context, Result};
+use core_logic::database::{AuditRecord, DatabaseManager};
+use serde_json::Value;
+use std::collections::HashSet;
+
+/// Calldata kept when decoding sent transactions: the selector and `0x`
+const SELECTOR_CHARS: usize = 10;
+
+/// One successful audited run of a task
+#[derive(Debug, Clone)]
+pub struct Fixture {
+    pub task_name: String,
+    /// Wallet the run was recorded with
+    pub wallet: Address,
+    pub timestamp: i64,
+    pub steps: Vec<AuditStep>,
+    pub transactions: Vec<AuditedTx>,
+}
+
+/// Outcome of replaying a [`Fixture`]
+#[derive(Debug)]
+pub struct Replay {
+    pub result: TaskResult,
+    /// Recorded steps the mock could not be programmed from
+    pub skipped: usize,
+    /// Differences between the sent and the recorded transactions
+    pub mismatches: Vec<String>,
+}
+
+impl Replay {
+    /// Whether the task succeeded and sent what the recorded run sent
+    pub fn passed(&self) -> bool {
+        self.result.success && self.mismatches.is_empty()
+    }
+}
+
+impl Fixture {
+    /// Fixture of one audited run
+    pub fn from_audit(audit: &AuditRecord) -> Result<Self> {
+        Ok(Self {
+            task_name: audit.task_name.clone(),
+            wallet: audit
+                .wallet_address
+                .parse()
+                .with_context(|| format!("Bad wallet '{}' in audit", audit.wallet_address))?,
+            timestamp: audit.timestamp,
+            steps: serde_json::from_str(&audit.steps).context("Bad steps in audit")?,
+            transactions: serde_json::from_str(&audit.transactions)
+                .context("Bad transactions in audit")?,
+        })
+    }
+
+    /// Fixtures of the successful runs among the latest `limit` audits of
+    /// `task_name`, newest first
+    pub async fn load(db: &DatabaseManager, task_name: &str, limit: i64) -> Result<Vec<Self>> {
+        db.get_audits(Some(task_name), limit)
+            .await?
+            .iter()
+            .filter(|audit| audit.success)
+            .map(Self::from_audit)
+            .collect()
+    }
+
+    /// Programs `mock` with the recorded answers, returning how many steps
+    /// could not be used
+    pub fn program(&self, mock: &MockTempoClient) -> usize {
+        let wallet = mock.address();
+        let mut nonce_set = false;
+        let mut balance_set = false;
+        let mut logs = HashSet::new();
+        let mut receipts = HashSet::new();
+        let mut skipped = 0;
+
+        for step in &self.steps {
+            let params = self.parse(step.params.as_deref(), wallet);
+            let result = self.parse(step.result.as_deref(), wallet);
+            let used = match step.name.trim_start_matches("rpc ") {
+                "eth_call" => match (call_target(params.as_ref()), step.ok) {
+                    (Some((to, selector)), true) => {
+                        match result.and_then(|r| serde_json::from_value::<Bytes>(r).ok()) {
+                            Some(data) => {
+                                mock.on_call(to, &selector, data);
+                                true
+                            }
+                            None => false,
+                        }
+                    }
+                    (Some((to, selector)), false) => {
+                        let reverted = step.error.as_deref().is_some_and(|e| e.contains("revert"));
+                        if reverted {
+                            mock.on_call_revert(to, &selector, Bytes::new());
+                        }
+                        reverted
+                    }
+                    (None, _) => false,
+                },
+                "eth_getCode" => {
+                    let address = params
+                        .as_ref()
+                        .and_then(|p| p.get(0))
+                        .and_then(|a| serde_json::from_value::<Address>(a.clone()).ok());
+                    let code = result.and_then(|r| serde_json::from_value::<Bytes>(r).ok());
+                    match (address, code) {
+                        (Some(address), Some(code)) => {
+                            mock.set_code(address, code);
+                            true
+                        }
+                        _ => false,
+                    }
+                }
+                "eth_getTransactionCount" => {
+                    match result.and_then(|r| serde_json::from_value::<U256>(r).ok()) {
+                        Some(nonce) => {
+                            // Later reads follow the mock's own nonce
+                            if !nonce_set {
+                                mock.set_nonce(nonce.saturating_to());
+                                nonce_set = true;
+                            }
+                            true
+                        }
+                        None => false,
+                    }
+                }
+                "eth_getBalance" => {
+                    match result.and_then(|r| serde_json::from_value::<U256>(r).ok()) {
+                        Some(balance) => {
+                            if !balance_set {
+                                mock.set_balance(balance);
+                                balance_set = true;
+                            }
+                            true
+                        }
+                        None => false,
+                    }
+                }
+                "eth_getLogs" => match result.as_ref().and_then(Value::as_array) {
+                    Some(found) => {
+                        for log in found.iter().filter_map(parse_log) {
+                            if logs.insert(log.clone()) {
+                                mock.push_log(log.0, log.1, log.2);
+                            }
+                        }
+                        true
+                    }
+                    None => false,
+                },
+                "eth_getTransactionReceipt" => match &result {
+                    // Not mined yet when polled
+                    Some(Value::Null) => true,
+                    Some(receipt) => {
+                        let hash = receipt.get("transactionHash").cloned();
+                        if receipts.insert(hash.map(|h| h.to_string())) {
+                            let status =
+                                receipt.get("status").and_then(Value::as_str) == Some("0x1");
+                            let logs = receipt
+                                .get("logs")
+                                .and_then(Value::as_array)
+                                .map(|logs| logs.iter().filter_map(parse_log).collect())
+                                .unwrap_or_default();
+                            mock.queue_receipt(status, logs);
+                        }
+                        true
+                    }
+                    None => false,
+                },
+                "eth_sendRawTransaction" => {
+                    if !step.ok {
+                        mock.fail_next_send(step.error.as_deref().unwrap_or("rejected"));
+                    }
+                    true
+                }
+                // Answered by the mock itself
+                "eth_chainId"
+                | "eth_blockNumber"
+                | "eth_gasPrice"
+                | "eth_maxPriorityFeePerGas"
+                | "eth_feeHistory"
+                | "eth_getBlockByNumber"
+                | "eth_estimateGas"
+                | "eth_createAccessList" => true,
+                _ => false,
+            };
+            if !used {
+                skipped += 1;
+            }
+        }
+        skipped
+    }
+
+    /// Programs the harness mock, runs `task` once and compares what it sent
+    ///
+    /// A task error counts as a failed result carrying the error chain.
+    pub async fn replay(&self, harness: &TaskHarness, task: &dyn TempoTask) -> Replay {
+        let skipped = self.program(&harness.mock);
+        let result = harness.run(task).await.unwrap_or_else(|e| TaskResult {
+            success: false,
+            message: format!("{:#}", e),
+            ..Default::default()
+        });
+        let sent: Vec<AuditedTx> = harness
+            .mock
+            .sent()
+            .iter()
+            .filter_map(|tx| AuditedTx::decode(&tx.raw, SELECTOR_CHARS))
+            .collect();
+        Replay {
+            result,
+            skipped,
+            mismatches: self.compare(&sent, harness.mock.address()),
+        }
+    }
+
+    fn compare(&self, sent: &[AuditedTx], wallet: Address) -> Vec<String> {
+        let mut mismatches = Vec::new();
+        if sent.len() != self.transactions.len() {
+            mismatches.push(format!(
+                "sent {} transactions, recorded {}",
+                sent.len(),
+                self.transactions.len()
+            ));
+        }
+        let calls = |tx: &AuditedTx| -> Vec<(Option<Address>, String)> {
+            tx.calls
+                .iter()
+                .map(|call| {
+                    let to = call
+                        .to
+                        .map(|to| if to == self.wallet { wallet } else { to });
+                    let selector: String = call.input.chars().take(SELECTOR_CHARS).collect();
+                    (to, selector.to_lowercase())
+                })
+                .collect()
+        };
+        for (i, (sent, recorded)) in sent.iter().zip(&self.transactions).enumerate() {
+            if sent.tx_type != recorded.tx_type {
+                mismatches.push(format!(
+                    "tx {}: type {:#x}, recorded {:#x}",
+                    i, sent.tx_type, recorded.tx_type
+                ));
+            }
+            let (sent_calls, recorded_calls) = (calls(sent), calls(recorded));
+            if sent_calls != recorded_calls {
+                mismatches.push(format!(
+                    "tx {}: calls {:?}, recorded {:?}",
+                    i, sent_calls, recorded_calls
+                ));
+            }
+        }
+        mismatches
+    }
+
+    /// Parses recorded JSON rebased onto `wallet`, `None` when missing or cut
+    fn parse(&self, text: Option<&str>, wallet: Address) -> Option<Value> {
+        let text = text.filter(|text| !is_cut(text))?;
+        let rebased = text
+            .to_lowercase()
+            .replace(&hex::encode(self.wallet), &hex::encode(wallet));
+        serde_json::from_str(&rebased).ok()
+    }
+}
+
+/// Whether `text` was shortened to `[audit] max_param_bytes`
+fn is_cut(text: &str) -> bool {
+    text.ends_with(" bytes)") && text.contains("...(")
+}
+
+/// Contract and hex selector of `eth_call` params; an empty selector for
+/// calls without calldata
+fn call_target(params: Option<&Value>) -> Option<(Address, String)> {
+    let tx = params?.get(0)?;
+    let to = serde_json::from_value(tx.get("to")?.clone()).ok()?;
+    let input = tx
+        .get("input")
+        .or_else(|| tx.get("data"))
+        .and_then(Value::as_str)
+        .unwrap_or_default();
+    let selector = input.trim_start_matches("0x").get(..8).unwrap_or_default();
+    Some((to, selector.to_string()))
+}
+
+fn parse_log(log: &Value) -> Option<(Address, Vec<B256>, Bytes)> {
+    Some((
+        serde_json::from_value(log.get("address")?.clone()).ok()?,
+        serde_json::from_value(log.get("topics")?.clone()).ok()?,
+        serde_json::from_value(log.get("data")?.clone()).ok()?,
+    ))
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use crate::audit::{AuditTrace, scope};
+    use crate::tasks::TaskContext;
+    use crate::testing::MockTempoClient;
+    use alloy::rpc::types::TransactionRequest;
+    use alloy_primitives::{TxKind, address};
+    use alloy_sol_types::{SolCall, sol};
+    use async_trait::async_trait;
+    use tempo_primitives::transaction::Call;
+
+    sol! {
+        function balanceOf(address owner) view returns (uint256);
+        function transfer(address to, uint256 amount) returns (bool);
+    }
+
+    const TOKEN: Address = address!("0x20c0000000000000000000000000000000000000");
+    const EVENT: B256 = B256::repeat_byte(0xe7);
+
+    /// Reads the wallet's balance, sends half of it back to the token when
+    /// there is any and reports the receipt's log count
+    struct ProbeTask;
+
+    #[async_trait]
+    impl TempoTask for ProbeTask {
+        fn name(&self) -> &'static str {
+            "probe"
+        }
+
+        async fn run(&self, ctx: &TaskContext) -> anyhow::Result<TaskResult> {
+            let call = balanceOfCall {
+                owner: ctx.address(),
+            };
+            let data = ctx
+                .client
+                .provider
+                .call(
+                    TransactionRequest::default()
+                        .to(TOKEN)
+                        .input(call.abi_encode().into()),
+                )
+                .await?;
+            let balance = balanceOfCall::abi_decode_returns(&data)?;
+            if balance.is_zero() {
+                return Ok(TaskResult {
+                    success: false,
+                    message: "No balance".to_string(),
+                    ..Default::default()
+                });
+            }
+            let transfer = Call {
+                to: TxKind::Call(TOKEN),
+                value: U256::ZERO,
+                input: transferCall {
+                    to: TOKEN,
+                    amount: balance / U256::from(2),
+                }
+                .abi_encode()
+                .into(),
+            };
+            let (tx_hash, _) = ctx.send_calls(vec![transfer], 100_000).await?;
+            let receipt = crate::gas_usage::wait_for_receipt(
+                ctx.client.provider.as_ref(),
+                tx_hash,
+                std::time::Duration::from_secs(5),
+                std::time::Duration::from_millis(10),
+            )
+            .await
+            .context("No receipt")?;
+            Ok(TaskResult {
+                success: receipt.status(),
+                message: "Sent".to_string(),
+                ..Default::default()
+            }
+            .with_detail("logs", receipt.inner.logs().len()))
+        }
+    }
+
+    /// Audits one probe run against a chain where the wallet holds tokens
+    async fn recorded_run() -> AuditRecord {
+        let harness = TaskHarness::new();
+        harness
+            .mock
+            .on_call(TOKEN, "70a08231", U256::from(5_000).to_be_bytes_vec());
+        harness
+            .mock
+            .push_receipt_log(TOKEN, vec![EVENT], Bytes::new());
+
+        let trace = AuditTrace::new(4096);
+        let ctx = harness.context();
+        let outcome = Ok(scope(Some(trace.clone()), ProbeTask.run(&ctx)).await);
+        let record = trace.finish("probe", 0, &ctx.client, &outcome);
+        assert!(record.success, "{}", record.message);
+        record
+    }
+
+    /// Harness for a second wallet, to replay onto another address
+    fn other_harness() -> TaskHarness {
+        let mut harness = TaskHarness::new();
+        harness.mock = MockTempoClient::new(
+            "0x59c6995e998f97a5a0044966f0945389dc9e86dae88c7a8412f4603b6b78690d",
+        )
+        .unwrap();
+        harness
+    }
+
+    #[tokio::test]
+    async fn test_replay_serves_recorded_answers() {
+        let fixture = Fixture::from_audit(&recorded_run().await).unwrap();
+        assert_eq!(fixture.transactions.len(), 1);
+
+        // The fresh chain has no balance for the wallet: the answer comes
+        // from the fixture, as does the receipt log
+        let harness = other_harness();
+        assert_ne!(harness.mock.address(), fixture.wallet);
+        let replay = fixture.replay(&harness, &ProbeTask).await;
+        assert!(replay.passed(), "{:?}", replay);
+        assert_eq!(replay.skipped, 0);
+        assert_eq!(replay.result.details["logs"], 1);
+    }
+
+    #[tokio::test]
+    async fn test_replay_reports_changed_transactions() {
+        let mut fixture = Fixture::from_audit(&recorded_run().await).unwrap();
+        fixture.transactions[0].calls[0].input = "0x095ea7b3".to_string();
+
+        let replay = fixture.replay(&other_harness(), &ProbeTask).await;
+        assert!(replay.result.success);
+        assert!(!replay.passed());
+        assert_eq!(replay.mismatches.len(), 1, "{:?}", replay.mismatches);
+        assert!(replay.mismatches[0].contains("0x095ea7b3"));
+    }
+
+    #[tokio::test]
+    async fn test_cut_steps_are_skipped() {
+        let mut fixture = Fixture::from_audit(&recorded_run().await).unwrap();
+        for step in &mut fixture.steps {
+            if step.name == "rpc eth_call" {
+                step.result = Some("\"0x00...(66 bytes)".to_string());
+            }
+        }
+
+        let replay = fixture.replay(&other_harness(), &ProbeTask).await;
+        assert!(replay.skipped > 0);
+        assert!(!replay.result.success);
+    }
+}
@@ -0,0 +1,309 @@
+//! Result Log - Sampling and summarising the `task_result` stream
+//!
+//! Every task run logs one `task_result` line, which at a few hundred
+//! workers is more than anyone can read. `[results]` thins the successes out
+//! and replaces them with periodic per-task totals:
+//!
+//! ```toml
+//! [results]
+//! success_every = 50   # log 1 in 50 successes of each task
+//! summary_secs = 60    # one SUMMARY line per task every minute
+//! ```
+//!
+//! Failures, task errors and timeouts are always logged; the first success
+//! of every task is too. A summary line covers the runs since the previous
+//! one:
+//!
+//! ```text
+//! SUMMARY [03_send_token] 412 runs, 409 ok, 3 failed (0.7%), avg 1.8s, 401 successes not logged
+//! ```
+//!
+//! Only the log is sampled: the database, metrics and result sinks still see
+//! every run. Both settings can be changed on a live run through the
+//! [`control`](crate::control) socket, and `reset` goes back to the config:
+//!
+//! ```bash
+//! tempo-spammer ctl results set success_every=200 summary_secs=30
+//! tempo-spammer ctl results
+//! tempo-spammer ctl results reset
+//! ```
+
+use crate::config::ResultLogSettings;
+use anyhow::{Result, bail};
+use std::collections::{BTreeMap, HashMap};
+use std::sync::{Arc, Mutex, OnceLock, RwLock};
+use std::time::Duration;
+use tokio::sync::Notify;
+use tracing::info;
+
+static RESULT_LOG: OnceLock<Arc<ResultLog>> = OnceLock::new();
+
+/// Process-wide result log shared by all workers
+pub fn result_log() -> &'static Arc<ResultLog> {
+    RESULT_LOG.get_or_init(|| Arc::new(ResultLog::new(ResultLogSettings::default())))
+}
+
+/// Runs of one task since the last summary
+#[derive(Debug, Clone, Copy, Default, PartialEq, Eq)]
+pub struct TaskSummary {
+    pub succeeded: u64,
+    pub failed: u64,
+    /// Successes whose line was sampled out
+    pub suppressed: u64,
+    pub total_duration: Duration,
+}
+
+impl TaskSummary {
+    pub fn runs(&self) -> u64 {
+        self.succeeded + self.failed
+    }
+
+    /// Failed share of the runs in percent
+    pub fn failure_rate(&self) -> f64 {
+        match self.runs() {
+            0 => 0.0,
+            runs => self.failed as f64 * 100.0 / runs as f64,
+        }
+    }
+
+    pub fn avg_duration(&self) -> Duration {
+        match self.runs() {
+            0 => Duration::ZERO,
+            runs => self.total_duration / runs as u32,
+        }
+    }
+}
+
+#[derive(Debug, Default)]
+struct ResultState {
+    /// Successes per task over the whole run, for 1-in-N sampling
+    successes: HashMap<String, u64>,
+    summaries: BTreeMap<String, TaskSummary>,
+}
+
+/// Which result lines are logged, and the totals behind the summaries
+#[derive(Debug)]
+pub struct ResultLog {
+    /// `[results]` as loaded, restored by [`reset`](Self::reset)
+    configured: RwLock<ResultLogSettings>,
+    current: RwLock<ResultLogSettings>,
+    state: Mutex<ResultState>,
+    /// Wakes the summary loop when `summary_secs` changes
+    changed: Notify,
+}
+
+impl ResultLog {
+    pub fn new(settings: ResultLogSettings) -> Self {
+        Self {
+            configured: RwLock::new(settings),
+            current: RwLock::new(settings),
+            state: Mutex::new(ResultState::default()),
+            changed: Notify::new(),
+        }
+    }
+
+    /// Applies `[results]`, replacing any change made at runtime
+    pub fn configure(&self, settings: ResultLogSettings) {
+        *self.configured.write().unwrap() = settings;
+        self.replace(settings);
+    }
+
+    pub fn settings(&self) -> ResultLogSettings {
+        *self.current.read().unwrap()
+    }
+
+    /// Changes settings from `key=value` directives, keeping the others
+    ///
+    /// # Errors
+    ///
+    /// Fails on unknown keys and values that are not whole numbers; nothing
+    /// is changed then.
+    pub fn set(&self, directives: &[&str]) -> Result<String> {
+        if directives.is_empty() {
+            bail!("Usage: results set success_every=<n> summary_secs=<secs>");
+        }
+        let mut next = self.settings();
+        for directive in directives {
+            let Some((key, value)) = directive.split_once('=') else {
+                bail!("Expected key=value, got '{}'", directive);
+            };
+            let value: u64 = value
+                .parse()
+                .map_err(|_| anyhow::anyhow!("'{}' needs a whole number, got '{}'", key, value))?;
+            match key {
+                "success_every" => next.success_every = value,
+                "summary_secs" => next.summary_secs = value,
+                _ => bail!(
+                    "Unknown results setting '{}' (known: success_every, summary_secs)",
+                    key
+                ),
+            }
+        }
+        self.replace(next);
+        info!("Result log changed: {}", describe(&next));
+        Ok(format!("results: {}", describe(&next)))
+    }
+
+    /// Goes back to the configured settings
+    pub fn reset(&self) -> String {
+        let configured = *self.configured.read().unwrap();
+        self.replace(configured);
+        format!("results: {}", describe(&configured))
+    }
+
+    /// Current settings as one line
+    pub fn show(&self) -> String {
+        format!("results: {}", describe(&self.settings()))
+    }
+
+    fn replace(&self, settings: ResultLogSettings) {
+        let previous = std::mem::replace(&mut *self.current.write().unwrap(), settings);
+        if previous.summary_secs != settings.summary_secs {
+            self.changed.notify_one();
+        }
+    }
+
+    /// Counts a finished run, returning whether its line should be logged
+    pub fn record(&self, task: &str, success: bool, duration: Duration) -> bool {
+        let settings = self.settings();
+        let mut state = self.state.lock().unwrap();
+        let logged = if success {
+            let successes = state.successes.entry(task.to_string()).or_insert(0);
+            *successes += 1;
+            settings.success_every > 0 && (*successes - 1).is_multiple_of(settings.success_every)
+        } else {
+            true
+        };
+        if settings.summary_secs > 0 {
+            let summary = state.summaries.entry(task.to_string()).or_default();
+            if success {
+                summary.succeeded += 1;
+            } else {
+                summary.failed += 1;
+            }
+            if !logged {
+                summary.suppressed += 1;
+            }
+            summary.total_duration += duration;
+        }
+        logged
+    }
+
+    /// Per-task totals since the last call, tasks without runs left out
+    pub fn take_summaries(&self) -> Vec<(String, TaskSummary)> {
+        std::mem::take(&mut self.state.lock().unwrap().summaries)
+            .into_iter()
+            .filter(|(_, summary)| summary.runs() > 0)
+            .collect()
+    }
+
+    /// Logs one `SUMMARY` line per task that ran since the last summary
+    pub fn log_summaries(&self) {
+        for (task, summary) in self.take_summaries() {
+            info!(
+                target: "task_result",
+                "SUMMARY [{}] {} runs, {} ok, {} failed ({:.1}%), avg {:.1}s, {} successes not logged",
+                task,
+                summary.runs(),
+                summary.succeeded,
+                summary.failed,
+                summary.failure_rate(),
+                summary.avg_duration().as_secs_f32(),
+                summary.suppressed
+            );
+        }
+    }
+
+    /// Logs summaries every `summary_secs` until the task is aborted
+    ///
+    /// Picks up interval changes right away; sleeps while summaries are off.
+    pub async fn run(&self) {
+        loop {
+            let interval = self.settings().summary_secs;
+            let wait = async {
+                match interval {
+                    0 => std::future::pending().await,
+                    secs => tokio::time::sleep(Duration::from_secs(secs)).await,
+                }
+            };
+            tokio::select! {
+                _ = wait => self.log_summaries(),
+                _ = self.changed.notified() => {}
+            }
+        }
+    }
+}
+
+fn describe(settings: &ResultLogSettings) -> String {
+    format!(
+        "success_every={} summary_secs={}",
+        settings.success_every, settings.summary_secs
+    )
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    fn log(success_every: u64, summary_secs: u64) -> ResultLog {
+        ResultLog::new(ResultLogSettings {
+            success_every,
+            summary_secs,
+        })
+    }
+
+    #[test]
+    fn test_successes_sampled_failures_kept() {
+        let results = log(3, 0);
+        let ms = Duration::from_millis(100);
+        let logged: Vec<bool> = (0..7).map(|_| results.record("a", true, ms)).collect();
+        assert_eq!(logged, [true, false, false, true, false, false, true]);
+        // Each task is sampled on its own
+        assert!(results.record("b", true, ms));
+        assert!(results.record("a", false, ms));
+
+        let silent = log(0, 0);
+        assert!(!silent.record("a", true, ms));
+        assert!(silent.record("a", false, ms));
+        // Totals are only kept while summaries are on
+        assert!(silent.take_summaries().is_empty());
+    }
+
+    #[test]
+    fn test_summaries_cover_runs_since_last() {
+        let results = log(2, 60);
+        results.record("a", true, Duration::from_secs(1));
+        results.record("a", true, Duration::from_secs(2));
+        results.record("a", false, Duration::from_secs(3));
+
+        let summaries = results.take_summaries();
+        assert_eq!(summaries.len(), 1);
+        let (task, summary) = &summaries[0];
+        assert_eq!(task, "a");
+        assert_eq!(
+            (summary.runs(), summary.failed, summary.suppressed),
+            (3, 1, 1)
+        );
+        assert_eq!(summary.avg_duration(), Duration::from_secs(2));
+        assert!(results.take_summaries().is_empty());
+    }
+
+    #[test]
+    fn test_set_and_reset() {
+        let results = log(1, 0);
+        assert_eq!(
+            results
+                .set(&["success_every=100", "summary_secs=30"])
+                .unwrap(),
+            "results: success_every=100 summary_secs=30"
+        );
+        assert_eq!(results.settings().success_every, 100);
+
+        assert!(results.set(&["success_every=lots"]).is_err());
+        assert!(results.set(&["verbosity=2"]).is_err());
+        assert!(results.set(&[]).is_err());
+        assert_eq!(results.settings().summary_secs, 30);
+
+        assert_eq!(results.reset(), "results: success_every=1 summary_secs=0");
+    }
+}
@@ -0,0 +1,410 @@
+//! Revert Decoding - Human-readable reasons for reverted calls
+//!
+//! A reverted `eth_call`, gas estimate or transaction submission comes back
+//! as an RPC error carrying raw revert data (`0x08c379a0...`). This module
+//! turns that data into something a person can read:
+//!
+//! - `Error(string)` reverts become their message
+//! - `Panic(uint256)` reverts become the panic kind, e.g.
+//!   `panic: arithmetic underflow or overflow (0x11)`
+//! - Custom errors registered in the [`RevertDecoder`] are shown with their
+//!   arguments, e.g. `InsufficientBalance { available: 0, required: 5, .. }`
+//!
+//! # Registry
+//!
+//! The process-wide [`decoder`] starts out knowing the custom errors of the
+//! Tempo precompiles (TIP-20, the TIP-20 factory, the stablecoin DEX,
+//! TIP-403, the fee manager and AMM, 2D nonces and the account keychain).
+//! Tasks talking to other contracts add the errors of their `sol!`
+//! interfaces through [`TempoTask::register_errors`](crate::TempoTask::register_errors),
+//! which the spammer runner calls once at startup.
+//!
+//! # Usage
+//!
+//! [`describe`] formats an error chain and appends the decoded reason, and is
+//! what task failures and the `task_metrics` messages use:
+//!
+//! ```text
+//! server returned an error response: error code 3: execution reverted (revert: PolicyForbids)
+//! ```
+
+use alloy::primitives::Bytes;
+use alloy::transports::TransportError;
+use alloy_sol_types::{GenericContractError, SolInterface, sol};
+use std::collections::HashMap;
+use std::error::Error;
+use std::fmt::Debug;
+use std::sync::{OnceLock, RwLock};
+
+/// Length of an error selector
+const SELECTOR_LEN: usize = 4;
+
+sol! {
+    #[derive(Debug)]
+    interface TIP20 {
+        error InsufficientBalance(uint256 available, uint256 required, address token);
+        error InsufficientAllowance();
+        error SupplyCapExceeded();
+        error InvalidSupplyCap();
+        error InvalidPayload();
+        error StringTooLong();
+        error PolicyForbids();
+        error InvalidRecipient();
+        error ContractPaused();
+        error InvalidCurrency();
+        error InvalidQuoteToken();
+        error TransfersDisabled();
+        error InvalidAmount();
+        error NoOptedInSupply();
+        error Unauthorized();
+        error ProtectedAddress();
+        error InvalidToken();
+        error Uninitialized();
+        error InvalidTransferPolicyId();
+    }
+
+    #[derive(Debug)]
+    interface TIP20Factory {
+        error AddressReserved();
+        error AddressNotReserved();
+        error TokenAlreadyExists(address token);
+    }
+
+    #[derive(Debug)]
+    interface StablecoinDEX {
+        error PairDoesNotExist();
+        error PairAlreadyExists();
+        error OrderDoesNotExist();
+        error IdenticalTokens();
+        error TickOutOfBounds(int16 tick);
+        error InvalidTick();
+        error InvalidFlipTick();
+        error InsufficientBalance();
+        error InsufficientLiquidity();
+        error InsufficientOutput();
+        error MaxInputExceeded();
+        error BelowMinimumOrderSize(uint128 amount);
+        error InvalidBaseToken();
+        error OrderNotStale();
+    }
+
+    #[derive(Debug)]
+    interface TIP403Registry {
+        error IncompatiblePolicyType();
+        error PolicyNotFound();
+    }
+
+    #[derive(Debug)]
+    interface FeeManager {
+        error OnlyValidator();
+        error OnlySystemContract();
+        error PoolDoesNotExist();
+        error InsufficientFeeTokenBalance();
+        error InternalError();
+        error CannotChangeWithinBlock();
+        error CannotChangeWithPendingFees();
+        error TokenPolicyForbids();
+        error IdenticalAddresses();
+        error InsufficientReserves();
+        error DivisionByZero();
+        error InvalidSwapCalculation();
+    }
+
+    #[derive(Debug)]
+    interface Nonce {
+        error ProtocolNonceNotSupported();
+        error InvalidNonceKey();
+        error NonceOverflow();
+        error ExpiringNonceReplay();
+        error ExpiringNonceSetFull();
+        error InvalidExpiringNonceExpiry();
+    }
+
+    #[derive(Debug)]
+    interface AccountKeychain {
+        error UnauthorizedCaller();
+        error KeyAlreadyExists();
+        error KeyNotFound();
+        error KeyExpired();
+        error SpendingLimitExceeded();
+        error InvalidSignatureType();
+        error ZeroPublicKey();
+        error ExpiryInPast();
+        error KeyAlreadyRevoked();
+        error SignatureTypeMismatch(uint8 expected, uint8 actual);
+    }
+}
+
+type DecodeFn = fn(&[u8]) -> Option<String>;
+
+/// Custom error selectors mapped to their decoders
+#[derive(Debug, Clone)]
+pub struct RevertDecoder {
+    errors: HashMap<[u8; SELECTOR_LEN], DecodeFn>,
+}
+
+impl Default for RevertDecoder {
+    /// A decoder that knows the Tempo precompile errors
+    fn default() -> Self {
+        let mut decoder = Self::empty();
+        decoder
+            .register::<TIP20::TIP20Errors>()
+            .register::<TIP20Factory::TIP20FactoryErrors>()
+            .register::<StablecoinDEX::StablecoinDEXErrors>()
+            .register::<TIP403Registry::TIP403RegistryErrors>()
+            .register::<FeeManager::FeeManagerErrors>()
+            .register::<Nonce::NonceErrors>()
+            .register::<AccountKeychain::AccountKeychainErrors>();
+        decoder
+    }
+}
+
+impl RevertDecoder {
+    /// A decoder that only understands `Error(string)` and `Panic(uint256)`
+    pub fn empty() -> Self {
+        Self {
+            errors: HashMap::new(),
+        }
+    }
+
+    /// Registers every custom error of a `sol!` interface
+    ///
+    /// Pass the interface's generated errors enum, e.g.
+    /// `register::<IMyContract::IMyContractErrors>()`; the interface needs
+    /// `#[derive(Debug)]` inside its `sol!` block. A selector that is already
+    /// registered keeps its first decoder.
+    pub fn register<E: SolInterface + Debug>(&mut self) -> &mut Self {
+        for selector in (0..E::COUNT).filter_map(E::selector_at) {
+            self.errors.entry(selector).or_insert(decode_custom::<E>);
+        }
+        self
+    }
+
+    /// Number of registered custom error selectors
+    pub fn len(&self) -> usize {
+        self.errors.len()
+    }
+
+    pub fn is_empty(&self) -> bool {
+        self.errors.is_empty()
+    }
+
+    /// Whether the selector is a standard or registered error
+    pub fn knows(&self, data: &[u8]) -> bool {
+        match data.get(..SELECTOR_LEN) {
+            Some(selector) => {
+                GenericContractError::valid_selector(selector.try_into().unwrap())
+                    || self.errors.contains_key(selector)
+            }
+            None => false,
+        }
+    }
+
+    /// Human-readable reason for raw revert data
+    ///
+    /// Returns `None` for empty data (a bare `revert()`); unknown selectors
+    /// are reported as `unknown error 0x<selector>`.
+    pub fn decode(&self, data: &[u8]) -> Option<String> {
+        let selector: [u8; SELECTOR_LEN] = data.get(..SELECTOR_LEN)?.try_into().ok()?;
+
+        if let Ok(standard) = GenericContractError::abi_decode(data) {
+            return Some(match standard {
+                GenericContractError::Revert(revert) => revert.reason().to_string(),
+                GenericContractError::Panic(panic) => panic.to_string(),
+            });
+        }
+
+        match self.errors.get(&selector) {
+            Some(decode) => decode(data),
+            None => Some(format!("unknown error 0x{}", alloy::hex::encode(selector))),
+        }
+    }
+}
+
+/// Renders a decoded interface error as `Name { args }`
+///
+/// The generated enum's Debug output wraps the error struct in its variant,
+/// e.g. `PolicyForbids(PolicyForbids)`; only the inner struct is kept.
+fn decode_custom<E: SolInterface + Debug>(data: &[u8]) -> Option<String> {
+    let rendered = format!("{:?}", E::abi_decode(data).ok()?);
+    let inner = rendered
+        .split_once('(')
+        .and_then(|(_, rest)| rest.strip_suffix(')'))
+        .unwrap_or(&rendered);
+    Some(inner.to_string())
+}
+
+static DECODER: OnceLock<RwLock<RevertDecoder>> = OnceLock::new();
+
+/// Process-wide decoder used by [`revert_reason`] and [`describe`]
+pub fn decoder() -> &'static RwLock<RevertDecoder> {
+    DECODER.get_or_init(|| RwLock::new(RevertDecoder::default()))
+}
+
+/// Raw revert data carried anywhere in an error chain
+///
+/// Looks for RPC error responses and contract call errors first. Errors that
+/// were flattened into strings are searched for a hex blob following the
+/// word "revert", which is only accepted if its selector is known.
+pub fn revert_data(err: &(dyn Error + 'static)) -> Option<Bytes> {
+    let mut current = Some(err);
+    while let Some(e) = current {
+        if let Some(data) = typed_revert_data(e) {
+            return Some(data);
+        }
+        current = e.source();
+    }
+
+    let message = chain_message(err);
+    let data = revert_data_in_message(&message)?;
+    decoder().read().unwrap().knows(&data).then_some(data)
+}
+
+/// Decoded revert reason carried anywhere in an error chain
+pub fn revert_reason(err: &(dyn Error + 'static)) -> Option<String> {
+    let data = revert_data(err)?;
+    decoder().read().unwrap().decode(&data)
+}
+
+/// Error chain as one line, with the decoded revert reason appended
+pub fn describe(err: &(dyn Error + 'static)) -> String {
+    let message = chain_message(err);
+    match revert_reason(err) {
+        Some(reason) => format!("{} (revert: {})", message, reason),
+        None => message,
+    }
+}
+
+fn typed_revert_data(err: &(dyn Error + 'static)) -> Option<Bytes> {
+    if let Some(e) = err.downcast_ref::<TransportError>() {
+        return e.as_error_resp().and_then(|resp| resp.as_revert_data());
+    }
+    if let Some(e) = err.downcast_ref::<alloy::contract::Error>() {
+        return e.as_revert_data();
+    }
+    None
+}
+
+/// Same layout as anyhow's `{:#}`: every error in the chain joined by ": "
+fn chain_message(err: &(dyn Error + 'static)) -> String {
+    let mut message = err.to_string();
+    let mut current = err.source();
+    while let Some(e) = current {
+        let part = e.to_string();
+        // Wrappers often repeat their source's message verbatim
+        if !message.ends_with(&part) {
+            message.push_str(": ");
+            message.push_str(&part);
+        }
+        current = e.source();
+    }
+    message
+}
+
+fn revert_data_in_message(message: &str) -> Option<Bytes> {
+    let lower = message.to_ascii_lowercase();
+    let after_revert = &lower[lower.find("revert")?..];
+    let start = after_revert.find("0x")? + 2;
+    let hex: String = after_revert[start..]
+        .chars()
+        .take_while(|c| c.is_ascii_hexdigit())
+        .collect();
+    if hex.len() < SELECTOR_LEN * 2 || hex.len() % 2 != 0 {
+        return None;
+    }
+    alloy::hex::decode(hex).ok().map(Bytes::from)
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use alloy::primitives::{Address, U256};
+    use alloy::rpc::json_rpc::ErrorPayload;
+    use alloy_sol_types::{Panic, PanicKind, Revert, SolError};
+
+    sol! {
+        #[derive(Debug)]
+        interface ICustom {
+            error NotAllowed(address caller);
+        }
+    }
+
+    #[test]
+    fn test_decode_standard_and_registered_errors() {
+        let mut decoder = RevertDecoder::default();
+        assert_eq!(
+            decoder.decode(&Revert::from("sold out").abi_encode()),
+            Some("sold out".to_string())
+        );
+        assert_eq!(
+            decoder.decode(&Panic::from(PanicKind::UnderOverflow).abi_encode()),
+            Some("panic: arithmetic underflow or overflow (0x11)".to_string())
+        );
+        assert_eq!(
+            decoder.decode(&TIP20::PolicyForbids {}.abi_encode()),
+            Some("PolicyForbids".to_string())
+        );
+
+        let insufficient = TIP20::InsufficientBalance {
+            available: U256::ZERO,
+            required: U256::from(5),
+            token: Address::ZERO,
+        };
+        let reason = decoder.decode(&insufficient.abi_encode()).unwrap();
+        assert!(reason.starts_with("InsufficientBalance {"), "{}", reason);
+
+        // Unknown until the task interface is registered
+        let custom = ICustom::NotAllowed {
+            caller: Address::ZERO,
+        }
+        .abi_encode();
+        assert!(
+            decoder
+                .decode(&custom)
+                .unwrap()
+                .starts_with("unknown error 0x")
+        );
+        decoder.register::<ICustom::ICustomErrors>();
+        assert!(decoder.decode(&custom).unwrap().starts_with("NotAllowed {"));
+
+        assert_eq!(decoder.decode(&[]), None);
+    }
+
+    #[test]
+    fn test_reason_from_rpc_error() {
+        let data = alloy::hex::encode(StablecoinDEX::PairDoesNotExist {}.abi_encode());
+        let payload: ErrorPayload = serde_json::from_str(&format!(
+            r#"{{"code":3,"message":"execution reverted","data":"0x{}"}}"#,
+            data
+        ))
+        .unwrap();
+        let err = anyhow::Error::new(TransportError::ErrorResp(payload)).context("Order failed");
+        assert_eq!(
+            revert_reason(err.as_ref()),
+            Some("PairDoesNotExist".to_string())
+        );
+        assert!(describe(err.as_ref()).ends_with("(revert: PairDoesNotExist)"));
+    }
+
+    #[test]
+    fn test_describe_flattened_error() {
+        let data = alloy::hex::encode(TIP20::ContractPaused {}.abi_encode());
+        let err = anyhow::anyhow!("execution reverted: data 0x{}", data).context("Failed to mint");
+        assert_eq!(
+            describe(err.as_ref()),
+            format!(
+                "Failed to mint: execution reverted: data 0x{} (revert: ContractPaused)",
+                data
+            )
+        );
+
+        // Hex that does not follow a revert, or has an unknown selector, is left alone
+        let tx = anyhow::anyhow!("transaction 0xdeadbeefdeadbeef failed");
+        assert_eq!(
+            describe(tx.as_ref()),
+            "transaction 0xdeadbeefdeadbeef failed"
+        );
+        let unknown = anyhow::anyhow!("execution reverted: 0xdeadbeef");
+        assert_eq!(revert_reason(unknown.as_ref()), None);
+    }
+}
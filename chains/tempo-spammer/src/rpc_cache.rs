@@ -0,0 +1,537 @@
+//! RPC Cache - Answers for data that does not change between calls
+//!
+//! Every task run starts from a fresh [`TempoClient`](crate::TempoClient), so
+//! the chain id, token decimals and the code of well-known contracts are
+//! fetched again for nearly every run. [`RpcCacheLayer`] sits in the
+//! transport of each client and answers repeated requests from one
+//! process-wide cache keyed by endpoint, method and params.
+//!
+//! # Classification
+//!
+//! Only requests [`classify`] recognizes are cached:
+//!
+//! - immutable, kept until evicted: `eth_chainId`, `net_version`,
+//!   `eth_getCode` of deployed contracts, `eth_getBlockByHash`,
+//!   `eth_getBlockByNumber` and `eth_getBlockReceipts` for an explicit block
+//!   number or hash, and `eth_call`s of `decimals()`, `symbol()` and `name()`
+//! - with a TTL: methods listed in `[rpc_cache] ttl_ms`, such as `eth_gasPrice`
+//!
+//! Errors, `null` results and empty `0x` results are never stored, so code
+//! that is not deployed yet and blocks that are not mined yet are asked for
+//! again. Batches pass through untouched.
+//!
+//! # Metrics
+//!
+//! Hits and misses are counted per method for classified requests;
+//! [`RpcCache::stats`] returns them and the runner logs the totals every 30
+//! seconds and the per-method counts on shutdown.
+//!
+//! The layer sits inside the audit layer, so audited runs still record cached
+//! answers, and outside the proxy usage layer, so hits cost no proxy traffic.
+
+use crate::config::RpcCacheSettings;
+use alloy::rpc::json_rpc::{RequestPacket, Response, ResponsePacket, ResponsePayload};
+use alloy::transports::{TransportError, TransportFut};
+use serde_json::Value;
+use serde_json::value::RawValue;
+use std::collections::{BTreeMap, HashMap};
+use std::sync::{Arc, Mutex, OnceLock, RwLock};
+use std::task::{Context, Poll};
+use std::time::{Duration, Instant};
+use tower::{Layer, Service};
+
+/// `eth_call` selectors of ERC-20 metadata fixed at deployment:
+/// `decimals()`, `symbol()` and `name()`
+const IMMUTABLE_SELECTORS: [&str; 3] = ["0x313ce567", "0x95d89b41", "0x06fdde03"];
+
+static CACHE: OnceLock<Arc<RpcCache>> = OnceLock::new();
+
+/// Process-wide cache shared by all clients
+pub fn cache() -> &'static Arc<RpcCache> {
+    CACHE.get_or_init(|| Arc::new(RpcCache::new(RpcCacheSettings::default())))
+}
+
+/// How long a cached answer stays valid
+#[derive(Debug, Clone, Copy, PartialEq, Eq)]
+pub enum Lifetime {
+    /// Until evicted to make room
+    Immutable,
+    /// For the given time after it was fetched
+    Ttl(Duration),
+}
+
+/// Lifetime of the answer to `method` with `params`, or `None` when it must
+/// always be fetched
+pub fn classify(
+    method: &str,
+    params: Option<&RawValue>,
+    ttl_ms: &BTreeMap<String, u64>,
+) -> Option<Lifetime> {
+    let first = || -> Option<Value> {
+        let params: Vec<Value> = serde_json::from_str(params?.get()).ok()?;
+        params.into_iter().next()
+    };
+    let immutable = match method {
+        "eth_chainId" | "net_version" | "eth_getCode" | "eth_getBlockByHash" => true,
+        // A number or hash, not a tag such as `latest`
+        "eth_getBlockByNumber" | "eth_getBlockReceipts" => first()
+            .as_ref()
+            .and_then(Value::as_str)
+            .is_some_and(|block| block.starts_with("0x")),
+        "eth_call" => first().is_some_and(|call| {
+            let input = call.get("input").or_else(|| call.get("data"));
+            input
+                .and_then(Value::as_str)
+                .is_some_and(|input| IMMUTABLE_SELECTORS.contains(&input))
+        }),
+        _ => false,
+    };
+    if immutable {
+        return Some(Lifetime::Immutable);
+    }
+    ttl_ms
+        .get(method)
+        .filter(|ttl| **ttl > 0)
+        .map(|ttl| Lifetime::Ttl(Duration::from_millis(*ttl)))
+}
+
+/// Whether a successful result may be reused
+fn storable(result: &RawValue) -> bool {
+    !matches!(result.get(), "null" | "\"0x\"")
+}
+
+/// Hit and miss counts of one method
+#[derive(Debug, Clone, Copy, Default, PartialEq, Eq)]
+pub struct CacheStats {
+    pub hits: u64,
+    pub misses: u64,
+}
+
+impl CacheStats {
+    /// Share of classified requests answered from the cache, in percent
+    pub fn hit_rate(&self) -> f64 {
+        let total = self.hits + self.misses;
+        if total == 0 {
+            0.0
+        } else {
+            self.hits as f64 * 100.0 / total as f64
+        }
+    }
+}
+
+#[derive(Debug)]
+struct Entry {
+    result: Box<RawValue>,
+    stored: Instant,
+    expires: Option<Instant>,
+}
+
+impl Entry {
+    fn live(&self, now: Instant) -> bool {
+        self.expires.is_none_or(|expires| now < expires)
+    }
+}
+
+/// Cached answers with their hit and miss counters
+#[derive(Debug)]
+pub struct RpcCache {
+    settings: RwLock<RpcCacheSettings>,
+    entries: Mutex<HashMap<String, Entry>>,
+    stats: Mutex<BTreeMap<String, CacheStats>>,
+}
+
+impl RpcCache {
+    pub fn new(settings: RpcCacheSettings) -> Self {
+        Self {
+            settings: RwLock::new(settings),
+            entries: Mutex::new(HashMap::new()),
+            stats: Mutex::new(BTreeMap::new()),
+        }
+    }
+
+    /// Applies `[rpc_cache]`, dropping the answers cached so far
+    pub fn configure(&self, settings: RpcCacheSettings) {
+        *self.settings.write().unwrap() = settings;
+        self.entries.lock().unwrap().clear();
+    }
+
+    /// Transport layer answering from this cache for the RPC at `endpoint`
+    pub fn layer(self: &Arc<Self>, endpoint: &str) -> RpcCacheLayer {
+        RpcCacheLayer {
+            cache: self.clone(),
+            endpoint: endpoint.into(),
+        }
+    }
+
+    /// Hit and miss counts per method, by method name
+    pub fn stats(&self) -> BTreeMap<String, CacheStats> {
+        self.stats.lock().unwrap().clone()
+    }
+
+    /// Hit and miss counts over all methods
+    pub fn totals(&self) -> CacheStats {
+        self.stats
+            .lock()
+            .unwrap()
+            .values()
+            .fold(CacheStats::default(), |total, stats| CacheStats {
+                hits: total.hits + stats.hits,
+                misses: total.misses + stats.misses,
+            })
+    }
+
+    /// Number of cached answers, including expired ones not evicted yet
+    pub fn len(&self) -> usize {
+        self.entries.lock().unwrap().len()
+    }
+
+    pub fn is_empty(&self) -> bool {
+        self.len() == 0
+    }
+
+    fn lifetime(&self, method: &str, params: Option<&RawValue>) -> Option<Lifetime> {
+        let settings = self.settings.read().unwrap();
+        if !settings.enabled {
+            return None;
+        }
+        classify(method, params, &settings.ttl_ms)
+    }
+
+    /// Cached answer for `key`, counting a hit or a miss for `method`
+    fn get(&self, key: &str, method: &str) -> Option<Box<RawValue>> {
+        let result = {
+            let mut entries = self.entries.lock().unwrap();
+            match entries.get(key) {
+                Some(entry) if entry.live(Instant::now()) => Some(entry.result.clone()),
+                Some(_) => {
+                    entries.remove(key);
+                    None
+                }
+                None => None,
+            }
+        };
+        let mut stats = self.stats.lock().unwrap();
+        let stats = stats.entry(method.to_string()).or_default();
+        match result {
+            Some(_) => stats.hits += 1,
+            None => stats.misses += 1,
+        }
+        result
+    }
+
+    fn insert(&self, key: String, result: Box<RawValue>, lifetime: Lifetime) {
+        let max_entries = self.settings.read().unwrap().max_entries;
+        let now = Instant::now();
+        let mut entries = self.entries.lock().unwrap();
+        if entries.len() >= max_entries && !entries.contains_key(&key) {
+            entries.retain(|_, entry| entry.live(now));
+            if entries.len() >= max_entries {
+                let oldest = entries
+                    .iter()
+                    .min_by_key(|(_, entry)| entry.stored)
+                    .map(|(key, _)| key.clone());
+                if let Some(oldest) = oldest {
+                    entries.remove(&oldest);
+                }
+            }
+        }
+        let expires = match lifetime {
+            Lifetime::Immutable => None,
+            Lifetime::Ttl(ttl) => Some(now + ttl),
+        };
+        entries.insert(
+            key,
+            Entry {
+                result,
+                stored: now,
+                expires,
+            },
+        );
+    }
+}
+
+/// Tower layer installing [`RpcCacheService`] in an RPC client
+#[derive(Debug, Clone)]
+pub struct RpcCacheLayer {
+    cache: Arc<RpcCache>,
+    endpoint: Arc<str>,
+}
+
+impl<S> Layer<S> for RpcCacheLayer {
+    type Service = RpcCacheService<S>;
+
+    fn layer(&self, inner: S) -> Self::Service {
+        RpcCacheService {
+            inner,
+            cache: self.cache.clone(),
+            endpoint: self.endpoint.clone(),
+        }
+    }
+}
+
+/// Transport service answering classified requests from the [`RpcCache`]
+#[derive(Debug, Clone)]
+pub struct RpcCacheService<S> {
+    inner: S,
+    cache: Arc<RpcCache>,
+    endpoint: Arc<str>,
+}
+
+impl<S> Service<RequestPacket> for RpcCacheService<S>
+where
+    S: Service<
+            RequestPacket,
+            Response = ResponsePacket,
+            Error = TransportError,
+            Future = TransportFut<'static>,
+        > + Send
+        + 'static,
+{
+    type Response = ResponsePacket;
+    type Error = TransportError;
+    type Future = TransportFut<'static>;
+
+    fn poll_ready(&mut self, cx: &mut Context<'_>) -> Poll<Result<(), Self::Error>> {
+        self.inner.poll_ready(cx)
+    }
+
+    fn call(&mut self, request: RequestPacket) -> Self::Future {
+        let RequestPacket::Single(req) = &request else {
+            return self.inner.call(request);
+        };
+        let Some(lifetime) = self.cache.lifetime(req.method(), req.params()) else {
+            return self.inner.call(request);
+        };
+
+        let key = format!(
+            "{} {} {}",
+            self.endpoint,
+            req.method(),
+            req.params().map_or("", |params| params.get())
+        );
+        if let Some(result) = self.cache.get(&key, req.method()) {
+            let id = req.id().clone();
+            return Box::pin(async move {
+                Ok(ResponsePacket::Single(Response {
+                    id,
+                    payload: ResponsePayload::Success(result),
+                }))
+            });
+        }
+
+        let cache = self.cache.clone();
+        let fut = self.inner.call(request);
+        Box::pin(async move {
+            let response = fut.await;
+            if let Ok(ResponsePacket::Single(Response {
+                payload: ResponsePayload::Success(result),
+                ..
+            })) = &response
+            {
+                if storable(result) {
+                    cache.insert(key, result.clone(), lifetime);
+                }
+            }
+            response
+        })
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use alloy::rpc::json_rpc::{Id, Request};
+    use alloy::transports::TransportErrorKind;
+    use serde_json::json;
+    use std::sync::atomic::{AtomicUsize, Ordering};
+
+    /// Answers every request with `result`, counting the calls that reach it
+    #[derive(Clone)]
+    struct Upstream {
+        result: &'static str,
+        calls: Arc<AtomicUsize>,
+    }
+
+    impl Service<RequestPacket> for Upstream {
+        type Response = ResponsePacket;
+        type Error = TransportError;
+        type Future = TransportFut<'static>;
+
+        fn poll_ready(&mut self, _cx: &mut Context<'_>) -> Poll<Result<(), Self::Error>> {
+            Poll::Ready(Ok(()))
+        }
+
+        fn call(&mut self, packet: RequestPacket) -> Self::Future {
+            self.calls.fetch_add(1, Ordering::SeqCst);
+            let RequestPacket::Single(req) = packet else {
+                unreachable!("tests send single requests")
+            };
+            let response = format!(
+                r#"{{"jsonrpc":"2.0","id":{},"result":{}}}"#,
+                serde_json::to_string(req.id()).unwrap(),
+                self.result
+            );
+            Box::pin(
+                async move { serde_json::from_str(&response).map_err(TransportErrorKind::custom) },
+            )
+        }
+    }
+
+    fn request(id: u64, method: &'static str, params: Value) -> RequestPacket {
+        RequestPacket::Single(
+            Request::new(method, Id::Number(id), params)
+                .serialize()
+                .unwrap(),
+        )
+    }
+
+    fn service(
+        cache: &Arc<RpcCache>,
+        result: &'static str,
+    ) -> (RpcCacheService<Upstream>, Arc<AtomicUsize>) {
+        let calls = Arc::new(AtomicUsize::new(0));
+        let upstream = Upstream {
+            result,
+            calls: calls.clone(),
+        };
+        (cache.layer("http://rpc.test").layer(upstream), calls)
+    }
+
+    #[test]
+    fn test_classify() {
+        let ttls = BTreeMap::from([("eth_gasPrice".to_string(), 500)]);
+        let raw = |params: Value| RawValue::from_string(params.to_string()).unwrap();
+        let lifetime = |method, params: Value| classify(method, Some(&raw(params)), &ttls);
+
+        assert_eq!(
+            lifetime("eth_chainId", json!([])),
+            Some(Lifetime::Immutable)
+        );
+        assert_eq!(
+            lifetime("eth_getBlockByNumber", json!(["0x10", false])),
+            Some(Lifetime::Immutable)
+        );
+        assert_eq!(
+            lifetime("eth_getBlockByNumber", json!(["latest", false])),
+            None
+        );
+        assert_eq!(
+            lifetime(
+                "eth_call",
+                json!([{"to": "0x20", "input": "0x313ce567"}, "latest"])
+            ),
+            Some(Lifetime::Immutable)
+        );
+        assert_eq!(
+            lifetime(
+                "eth_call",
+                json!([{"to": "0x20", "input": "0x70a08231"}, "latest"])
+            ),
+            None
+        );
+        assert_eq!(
+            lifetime("eth_gasPrice", json!([])),
+            Some(Lifetime::Ttl(Duration::from_millis(500)))
+        );
+        assert_eq!(
+            lifetime("eth_getTransactionCount", json!(["0x1", "pending"])),
+            None
+        );
+    }
+
+    #[tokio::test]
+    async fn test_immutable_answers_cached_per_params() {
+        let cache = Arc::new(RpcCache::new(RpcCacheSettings::default()));
+        let (mut svc, calls) = service(&cache, r#""0xa5bf""#);
+
+        for id in 1..=3 {
+            let response = svc
+                .call(request(id, "eth_chainId", json!([])))
+                .await
+                .unwrap();
+            let ResponsePacket::Single(response) = response else {
+                panic!("single request answered with a batch")
+            };
+            assert_eq!(response.id, Id::Number(id));
+        }
+        assert_eq!(calls.load(Ordering::SeqCst), 1);
+
+        let code = |addr: &str| request(4, "eth_getCode", json!([addr, "latest"]));
+        svc.call(code("0x01")).await.unwrap();
+        svc.call(code("0x02")).await.unwrap();
+        svc.call(code("0x01")).await.unwrap();
+        assert_eq!(calls.load(Ordering::SeqCst), 3);
+
+        assert_eq!(
+            cache.stats()["eth_chainId"],
+            CacheStats { hits: 2, misses: 1 }
+        );
+        assert_eq!(cache.totals(), CacheStats { hits: 3, misses: 3 });
+        assert_eq!(cache.len(), 3);
+    }
+
+    #[tokio::test]
+    async fn test_empty_results_and_uncached_methods_pass_through() {
+        let cache = Arc::new(RpcCache::new(RpcCacheSettings::default()));
+        let (mut svc, calls) = service(&cache, r#""0x""#);
+
+        for _ in 0..2 {
+            svc.call(request(1, "eth_getCode", json!(["0x01", "latest"])))
+                .await
+                .unwrap();
+            svc.call(request(
+                2,
+                "eth_getTransactionCount",
+                json!(["0x01", "pending"]),
+            ))
+            .await
+            .unwrap();
+        }
+        assert_eq!(calls.load(Ordering::SeqCst), 4);
+        assert!(cache.is_empty());
+        assert!(!cache.stats().contains_key("eth_getTransactionCount"));
+
+        cache.configure(RpcCacheSettings {
+            enabled: false,
+            ..RpcCacheSettings::default()
+        });
+        let (mut svc, calls) = service(&cache, r#""0xa5bf""#);
+        svc.call(request(1, "eth_chainId", json!([])))
+            .await
+            .unwrap();
+        svc.call(request(2, "eth_chainId", json!([])))
+            .await
+            .unwrap();
+        assert_eq!(calls.load(Ordering::SeqCst), 2);
+    }
+
+    #[tokio::test]
+    async fn test_ttl_and_eviction() {
+        let cache = Arc::new(RpcCache::new(RpcCacheSettings {
+            max_entries: 2,
+            ttl_ms: BTreeMap::from([("eth_gasPrice".to_string(), 30)]),
+            ..RpcCacheSettings::default()
+        }));
+        let (mut svc, calls) = service(&cache, r#""0x3b9aca00""#);
+
+        svc.call(request(1, "eth_gasPrice", json!([])))
+            .await
+            .unwrap();
+        svc.call(request(2, "eth_gasPrice", json!([])))
+            .await
+            .unwrap();
+        assert_eq!(calls.load(Ordering::SeqCst), 1);
+        tokio::time::sleep(Duration::from_millis(40)).await;
+        svc.call(request(3, "eth_gasPrice", json!([])))
+            .await
+            .unwrap();
+        assert_eq!(calls.load(Ordering::SeqCst), 2);
+
+        for block in ["0x1", "0x2", "0x3"] {
+            svc.call(request(4, "eth_getBlockByNumber", json!([block, false])))
+                .await
+                .unwrap();
+        }
+        assert_eq!(cache.len(), 2);
+    }
+}
@@ -0,0 +1,154 @@
+//! RPC Spans - One tracing span per JSON-RPC call
+//!
+//! [`RpcSpanLayer`] is the outermost layer of every
+//! [`TempoClient`](crate::TempoClient) transport, so each call a task makes,
+//! retries included, shows up as one `rpc` span under the task's span with
+//! the method and the wallet. `eth_sendRawTransaction` and receipt lookups
+//! also carry the `tx_hash`. With `[tracing]` export enabled the spans reach
+//! the collector; without it they cost next to nothing.
+
+use alloy::rpc::json_rpc::{RequestPacket, ResponsePacket};
+use alloy::transports::{TransportError, TransportFut};
+use alloy_primitives::{Address, B256};
+use std::task::{Context, Poll};
+use tower::{Layer, Service};
+use tracing::Instrument;
+use tracing::field::Empty;
+
+/// Transport layer wrapping each request of one wallet's client in a span
+#[derive(Debug, Clone, Copy)]
+pub struct RpcSpanLayer {
+    wallet: Address,
+}
+
+impl RpcSpanLayer {
+    pub fn new(wallet: Address) -> Self {
+        Self { wallet }
+    }
+}
+
+impl<S> Layer<S> for RpcSpanLayer {
+    type Service = RpcSpanService<S>;
+
+    fn layer(&self, inner: S) -> Self::Service {
+        RpcSpanService {
+            inner,
+            wallet: self.wallet,
+        }
+    }
+}
+
+/// Transport service opening an `rpc` span around every request
+#[derive(Debug, Clone)]
+pub struct RpcSpanService<S> {
+    inner: S,
+    wallet: Address,
+}
+
+impl<S> Service<RequestPacket> for RpcSpanService<S>
+where
+    S: Service<
+            RequestPacket,
+            Response = ResponsePacket,
+            Error = TransportError,
+            Future = TransportFut<'static>,
+        > + Send
+        + 'static,
+{
+    type Response = ResponsePacket;
+    type Error = TransportError;
+    type Future = TransportFut<'static>;
+
+    fn poll_ready(&mut self, cx: &mut Context<'_>) -> Poll<Result<(), Self::Error>> {
+        self.inner.poll_ready(cx)
+    }
+
+    fn call(&mut self, request: RequestPacket) -> Self::Future {
+        let method = match &request {
+            RequestPacket::Single(req) => req.method().to_string(),
+            RequestPacket::Batch(reqs) => format!("batch of {}", reqs.len()),
+        };
+        let span = tracing::info_span!(
+            "rpc",
+            rpc.method = %method,
+            wallet = %self.wallet,
+            tx_hash = Empty,
+            error = Empty,
+        );
+        if let RequestPacket::Single(req) = &request
+            && let Some(hash) = req
+                .params()
+                .and_then(|params| hash_in_params(&method, params.get()))
+        {
+            span.record("tx_hash", tracing::field::display(hash));
+        }
+
+        let fut = span.in_scope(|| self.inner.call(request));
+        let recorder = span.clone();
+        Box::pin(
+            async move {
+                let result = fut.await;
+                match &result {
+                    Ok(ResponsePacket::Single(resp)) => match resp.payload.as_success() {
+                        Some(raw) => {
+                            if let Some(hash) = sent_hash(&method, raw.get()) {
+                                recorder.record("tx_hash", tracing::field::display(hash));
+                            }
+                        }
+                        None => {
+                            if let Some(e) = resp.payload.as_error() {
+                                recorder.record("error", e.message.as_ref());
+                            }
+                        }
+                    },
+                    Ok(ResponsePacket::Batch(_)) => {}
+                    Err(e) => {
+                        recorder.record("error", tracing::field::display(e));
+                    }
+                }
+                result
+            }
+            .instrument(span),
+        )
+    }
+}
+
+/// Hash a receipt or transaction lookup asks about
+fn hash_in_params(method: &str, params: &str) -> Option<B256> {
+    match method {
+        "eth_getTransactionReceipt" | "eth_getTransactionByHash" => {
+            serde_json::from_str::<(B256,)>(params).ok().map(|p| p.0)
+        }
+        _ => None,
+    }
+}
+
+/// Hash the node returned for a sent transaction
+fn sent_hash(method: &str, result: &str) -> Option<B256> {
+    match method {
+        "eth_sendRawTransaction" | "eth_sendTransaction" => serde_json::from_str(result).ok(),
+        _ => None,
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    #[test]
+    fn test_tx_hash_from_params_and_results() {
+        let hash = B256::repeat_byte(0xab);
+        let quoted = format!("\"{}\"", hash);
+
+        assert_eq!(sent_hash("eth_sendRawTransaction", &quoted), Some(hash));
+        assert_eq!(sent_hash("eth_blockNumber", "\"0x10\""), None);
+        assert_eq!(sent_hash("eth_sendRawTransaction", "null"), None);
+
+        let params = format!("[{}]", quoted);
+        assert_eq!(
+            hash_in_params("eth_getTransactionReceipt", &params),
+            Some(hash)
+        );
+        assert_eq!(hash_in_params("eth_call", &params), None);
+    }
+}
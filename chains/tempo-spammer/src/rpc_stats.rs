@@ -0,0 +1,485 @@
+//! RPC Stats - Per-method counters, latencies and threshold alerts
+//!
+//! [`RpcStatsLayer`] sits right inside the span layer of every
+//! [`TempoClient`](crate::TempoClient) transport, so it times each call a task
+//! makes, retries included, and counts it by JSON-RPC method in the shared
+//! [`MetricsCollector`]:
+//!
+//! - `rpc_requests_total{method, outcome}`, outcome `ok` or `error`
+//! - `rpc_method_latency_ms{method}`
+//!
+//! Both are part of `tempo-spammer ctl metrics`. An error is a transport
+//! failure or an error answer from the node, which includes reverted
+//! `eth_call` and `eth_estimateGas` calls.
+//!
+//! `[rpc_stats]` sets limits on the last `window_secs` of each method's calls:
+//!
+//! ```toml
+//! [rpc_stats]
+//! max_error_rate = 0.2
+//!
+//! [rpc_stats.p95_ms]
+//! eth_getTransactionReceipt = 5000
+//! eth_sendRawTransaction = 3000
+//! ```
+//!
+//! Every `check_secs` a method past one of its limits, with at least
+//! `min_samples` calls in the window, is logged as a warning and, with
+//! notifications on, sent to Telegram. It alerts again only after it has been
+//! back within the limit.
+
+use crate::config::RpcStatsSettings;
+use alloy::rpc::json_rpc::{RequestPacket, ResponsePacket};
+use alloy::transports::{TransportError, TransportFut};
+use chrono::{DateTime, Utc};
+use core_logic::metrics::MetricsCollector;
+use std::collections::{BTreeMap, HashSet, VecDeque};
+use std::fmt;
+use std::sync::{Arc, Mutex, OnceLock, RwLock};
+use std::task::{Context, Poll};
+use std::time::{Duration, Instant};
+use tower::{Layer, Service};
+use tracing::warn;
+
+/// Calls kept per method, whatever the window
+const MAX_WINDOW_CALLS: usize = 10_000;
+
+/// Raised alerts kept for the notifier
+const MAX_ALERTS: usize = 100;
+
+static RPC_STATS: OnceLock<Arc<RpcStats>> = OnceLock::new();
+
+/// Process-wide RPC stats shared by all clients
+pub fn rpc_stats() -> &'static Arc<RpcStats> {
+    RPC_STATS.get_or_init(|| Arc::new(RpcStats::new(RpcStatsSettings::default())))
+}
+
+/// One finished call
+#[derive(Debug, Clone, Copy)]
+struct Call {
+    at: Instant,
+    latency_ms: u64,
+    error: bool,
+}
+
+/// Calls of one method within the window
+#[derive(Debug, Clone, PartialEq)]
+pub struct MethodStats {
+    pub method: String,
+    pub calls: usize,
+    pub errors: usize,
+    pub p95_ms: u64,
+}
+
+impl MethodStats {
+    fn of(method: &str, calls: &VecDeque<Call>) -> Self {
+        let mut latencies: Vec<u64> = calls.iter().map(|call| call.latency_ms).collect();
+        latencies.sort_unstable();
+        let p95_ms = match latencies.len() {
+            0 => 0,
+            n => latencies[((n - 1) as f64 * 0.95).round() as usize],
+        };
+        Self {
+            method: method.to_string(),
+            calls: calls.len(),
+            errors: calls.iter().filter(|call| call.error).count(),
+            p95_ms,
+        }
+    }
+
+    /// Share of the calls answered with an error, 0.0 to 1.0
+    pub fn error_rate(&self) -> f64 {
+        match self.calls {
+            0 => 0.0,
+            calls => self.errors as f64 / calls as f64,
+        }
+    }
+}
+
+/// Limit a method went past
+#[derive(Debug, Clone, PartialEq)]
+pub enum Breach {
+    Latency { p95_ms: u64, limit_ms: u64 },
+    Errors { rate: f64, limit: f64 },
+}
+
+impl Breach {
+    fn kind(&self) -> &'static str {
+        match self {
+            Breach::Latency { .. } => "latency",
+            Breach::Errors { .. } => "errors",
+        }
+    }
+}
+
+/// A method past one of its `[rpc_stats]` limits
+#[derive(Debug, Clone, PartialEq)]
+pub struct RpcAlert {
+    pub method: String,
+    pub breach: Breach,
+    /// Calls in the window
+    pub calls: usize,
+    pub raised_at: DateTime<Utc>,
+}
+
+impl fmt::Display for RpcAlert {
+    fn fmt(&self, f: &mut fmt::Formatter<'_>) -> fmt::Result {
+        match &self.breach {
+            Breach::Latency { p95_ms, limit_ms } => write!(
+                f,
+                "{} p95 latency {}ms over the {}ms limit",
+                self.method, p95_ms, limit_ms
+            )?,
+            Breach::Errors { rate, limit } => write!(
+                f,
+                "{} errors {:.1}% over the {:.1}% limit",
+                self.method,
+                rate * 100.0,
+                limit * 100.0
+            )?,
+        }
+        write!(f, " ({} calls in the window)", self.calls)
+    }
+}
+
+/// Recent calls of every method and the alerts raised on them
+#[derive(Debug)]
+pub struct RpcStats {
+    settings: RwLock<RpcStatsSettings>,
+    windows: Mutex<BTreeMap<String, VecDeque<Call>>>,
+    /// Method and breach kind of the limits exceeded at the last check
+    breached: Mutex<HashSet<(String, &'static str)>>,
+    /// Oldest first
+    alerts: Mutex<VecDeque<RpcAlert>>,
+}
+
+impl RpcStats {
+    pub fn new(settings: RpcStatsSettings) -> Self {
+        Self {
+            settings: RwLock::new(settings),
+            windows: Mutex::new(BTreeMap::new()),
+            breached: Mutex::new(HashSet::new()),
+            alerts: Mutex::new(VecDeque::new()),
+        }
+    }
+
+    /// Applies `[rpc_stats]`
+    pub fn configure(&self, settings: RpcStatsSettings) {
+        *self.settings.write().unwrap() = settings;
+    }
+
+    /// Transport layer counting the calls of a client
+    pub fn layer(self: &Arc<Self>) -> RpcStatsLayer {
+        RpcStatsLayer {
+            stats: self.clone(),
+        }
+    }
+
+    /// Counts a finished `method` call in the metrics and the method's window
+    pub fn record(&self, method: &str, latency: Duration, error: bool) {
+        self.record_at(method, latency, error, Instant::now());
+    }
+
+    fn record_at(&self, method: &str, latency: Duration, error: bool, at: Instant) {
+        let metrics = MetricsCollector::global();
+        let outcome = if error { "error" } else { "ok" };
+        metrics
+            .counter(
+                "rpc_requests_total",
+                &[("method", method), ("outcome", outcome)],
+            )
+            .inc();
+        metrics
+            .histogram("rpc_method_latency_ms", &[("method", method)])
+            .observe_duration(latency);
+        metrics.record_rpc_latency(latency);
+
+        let window = self.window();
+        let mut windows = self.windows.lock().unwrap();
+        let calls = windows.entry(method.to_string()).or_default();
+        calls.push_back(Call {
+            at,
+            latency_ms: latency.as_millis() as u64,
+            error,
+        });
+        trim(calls, at, window);
+    }
+
+    /// Calls of every method within the window at `now`, by method
+    pub fn summary(&self, now: Instant) -> Vec<MethodStats> {
+        let window = self.window();
+        let mut windows = self.windows.lock().unwrap();
+        windows
+            .iter_mut()
+            .filter_map(|(method, calls)| {
+                trim(calls, now, window);
+                (!calls.is_empty()).then(|| MethodStats::of(method, calls))
+            })
+            .collect()
+    }
+
+    /// Checks the limits at `now`, returning the newly exceeded ones
+    pub fn check(&self, now: Instant) -> Vec<RpcAlert> {
+        let settings = self.settings.read().unwrap().clone();
+        let mut breaches = Vec::new();
+        for stats in self.summary(now) {
+            if stats.calls < settings.min_samples.max(1) {
+                continue;
+            }
+            if let Some(&limit_ms) = settings.p95_ms.get(&stats.method)
+                && stats.p95_ms > limit_ms
+            {
+                let breach = Breach::Latency {
+                    p95_ms: stats.p95_ms,
+                    limit_ms,
+                };
+                breaches.push((stats.method.clone(), stats.calls, breach));
+            }
+            if settings.max_error_rate > 0.0 && stats.error_rate() > settings.max_error_rate {
+                let breach = Breach::Errors {
+                    rate: stats.error_rate(),
+                    limit: settings.max_error_rate,
+                };
+                breaches.push((stats.method.clone(), stats.calls, breach));
+            }
+        }
+
+        let mut breached = self.breached.lock().unwrap();
+        let raised_at = Utc::now();
+        let new: Vec<RpcAlert> = breaches
+            .iter()
+            .filter(|(method, _, breach)| !breached.contains(&(method.clone(), breach.kind())))
+            .map(|(method, calls, breach)| RpcAlert {
+                method: method.clone(),
+                breach: breach.clone(),
+                calls: *calls,
+                raised_at,
+            })
+            .collect();
+        *breached = breaches
+            .iter()
+            .map(|(method, _, breach)| (method.clone(), breach.kind()))
+            .collect();
+
+        let mut alerts = self.alerts.lock().unwrap();
+        alerts.extend(new.iter().cloned());
+        while alerts.len() > MAX_ALERTS {
+            alerts.pop_front();
+        }
+        new
+    }
+
+    /// Alerts raised after `since`, oldest first
+    pub fn alerts_since(&self, since: DateTime<Utc>) -> Vec<RpcAlert> {
+        self.alerts
+            .lock()
+            .unwrap()
+            .iter()
+            .filter(|alert| alert.raised_at > since)
+            .cloned()
+            .collect()
+    }
+
+    /// Checks the limits every `check_secs` until the task is aborted,
+    /// logging each new alert
+    pub async fn run(&self) {
+        let check_secs = self.settings.read().unwrap().check_secs;
+        if check_secs == 0 {
+            return std::future::pending().await;
+        }
+        let mut interval = tokio::time::interval(Duration::from_secs(check_secs));
+        interval.tick().await; // the first tick fires at once
+        loop {
+            interval.tick().await;
+            for alert in self.check(Instant::now()) {
+                warn!("RPC alert: {}", alert);
+            }
+        }
+    }
+
+    fn window(&self) -> Duration {
+        Duration::from_secs(self.settings.read().unwrap().window_secs)
+    }
+}
+
+/// Drops the calls older than `window` at `now` and those beyond the cap
+fn trim(calls: &mut VecDeque<Call>, now: Instant, window: Duration) {
+    while calls.len() > MAX_WINDOW_CALLS
+        || calls
+            .front()
+            .is_some_and(|call| now.saturating_duration_since(call.at) > window)
+    {
+        calls.pop_front();
+    }
+}
+
+/// Transport layer installing [`RpcStatsService`] in a client
+#[derive(Debug, Clone)]
+pub struct RpcStatsLayer {
+    stats: Arc<RpcStats>,
+}
+
+impl<S> Layer<S> for RpcStatsLayer {
+    type Service = RpcStatsService<S>;
+
+    fn layer(&self, inner: S) -> Self::Service {
+        RpcStatsService {
+            inner,
+            stats: self.stats.clone(),
+        }
+    }
+}
+
+/// Transport service timing and counting every request by method
+#[derive(Debug, Clone)]
+pub struct RpcStatsService<S> {
+    inner: S,
+    stats: Arc<RpcStats>,
+}
+
+impl<S> Service<RequestPacket> for RpcStatsService<S>
+where
+    S: Service<
+            RequestPacket,
+            Response = ResponsePacket,
+            Error = TransportError,
+            Future = TransportFut<'static>,
+        > + Send
+        + 'static,
+{
+    type Response = ResponsePacket;
+    type Error = TransportError;
+    type Future = TransportFut<'static>;
+
+    fn poll_ready(&mut self, cx: &mut Context<'_>) -> Poll<Result<(), Self::Error>> {
+        self.inner.poll_ready(cx)
+    }
+
+    fn call(&mut self, request: RequestPacket) -> Self::Future {
+        let method = match &request {
+            RequestPacket::Single(req) => req.method().to_string(),
+            RequestPacket::Batch(_) => "batch".to_string(),
+        };
+        let stats = self.stats.clone();
+        let fut = self.inner.call(request);
+
+        Box::pin(async move {
+            let started = Instant::now();
+            let result = fut.await;
+            let error = match &result {
+                Ok(ResponsePacket::Single(resp)) => resp.payload.as_error().is_some(),
+                Ok(ResponsePacket::Batch(resps)) => {
+                    resps.iter().any(|resp| resp.payload.as_error().is_some())
+                }
+                Err(_) => true,
+            };
+            stats.record(&method, started.elapsed(), error);
+            result
+        })
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    fn settings() -> RpcStatsSettings {
+        RpcStatsSettings {
+            min_samples: 10,
+            p95_ms: [("eth_getTransactionReceipt".to_string(), 1000)].into(),
+            max_error_rate: 0.2,
+            ..Default::default()
+        }
+    }
+
+    #[test]
+    fn test_summary_per_method() {
+        let stats = RpcStats::new(RpcStatsSettings::default());
+        let now = Instant::now();
+        for ms in 1..=100 {
+            stats.record_at("eth_call", Duration::from_millis(ms), ms > 90, now);
+        }
+        stats.record_at("eth_chainId", Duration::from_millis(5), false, now);
+
+        let summary = stats.summary(now);
+        assert_eq!(summary.len(), 2);
+        assert_eq!(summary[0].method, "eth_call");
+        assert_eq!(summary[0].calls, 100);
+        assert_eq!(summary[0].errors, 10);
+        assert_eq!(summary[0].p95_ms, 95);
+        assert_eq!(summary[1].method, "eth_chainId");
+
+        // Calls age out of the window
+        let later = now + Duration::from_secs(301);
+        assert!(stats.summary(later).is_empty());
+    }
+
+    #[test]
+    fn test_alerts_once_per_breach() {
+        let stats = RpcStats::new(settings());
+        let now = Instant::now();
+        for _ in 0..9 {
+            stats.record_at(
+                "eth_getTransactionReceipt",
+                Duration::from_secs(3),
+                false,
+                now,
+            );
+        }
+        // Too few calls to judge
+        assert!(stats.check(now).is_empty());
+
+        stats.record_at(
+            "eth_getTransactionReceipt",
+            Duration::from_secs(3),
+            false,
+            now,
+        );
+        let alerts = stats.check(now);
+        assert_eq!(alerts.len(), 1);
+        assert_eq!(
+            alerts[0].breach,
+            Breach::Latency {
+                p95_ms: 3000,
+                limit_ms: 1000
+            }
+        );
+        assert!(stats.check(now).is_empty());
+        assert_eq!(stats.alerts_since(DateTime::<Utc>::MIN_UTC).len(), 1);
+
+        // Back within the limit once the slow calls age out, then past it again
+        let later = now + Duration::from_secs(301);
+        assert!(stats.check(later).is_empty());
+        for _ in 0..10 {
+            stats.record_at(
+                "eth_getTransactionReceipt",
+                Duration::from_secs(2),
+                false,
+                later,
+            );
+        }
+        assert_eq!(stats.check(later).len(), 1);
+    }
+
+    #[test]
+    fn test_error_rate_alert() {
+        let stats = RpcStats::new(settings());
+        let now = Instant::now();
+        for i in 0..20 {
+            stats.record_at(
+                "eth_sendRawTransaction",
+                Duration::from_millis(50),
+                i < 5,
+                now,
+            );
+        }
+        let alerts = stats.check(now);
+        assert_eq!(alerts.len(), 1);
+        assert_eq!(alerts[0].method, "eth_sendRawTransaction");
+        assert!(matches!(alerts[0].breach, Breach::Errors { rate, .. } if rate == 0.25));
+        assert_eq!(
+            alerts[0].to_string(),
+            "eth_sendRawTransaction errors 25.0% over the 20.0% limit (20 calls in the window)"
+        );
+    }
+}
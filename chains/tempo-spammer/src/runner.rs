@@ -0,0 +1,1995 @@
+//! Spammer Runner - Weighted task loop shared by the spammer binaries
+//!
+//! [`run_spammer`] drives `worker_count` workers that lease wallets from a
+//! [`ClientPool`], pick a task by weight, and queue every outcome on the
+//! async database logger tagged with the configured `chain_id`. It lives in
+//! the library so `tempo-spammer` and the multi-chain runner drive Tempo the
+//! same way. With `[wallets] assignment = "partitioned"` each worker cycles
+//! through its own [`WalletPartition`] instead of leasing. Workers run in a
+//! [`worker_scope`], so `[proxy] assignment = "worker"` can send each one
+//! through its own proxy.
+//!
+//! Task weights and UTC run windows come from `[tasks]`, optionally re-tuned
+//! at runtime by a [`remote_config`](crate::remote_config) document. Each
+//! lease is further biased by the wallet's [`persona`](crate::persona).
+//! The [`activity`](crate::activity) schedule scales how many workers run
+//! through the day; workers above the current level sleep. Each worker's
+//! start offset and pauses between tasks come from its
+//! [`pacing`](crate::pacing).
+//!
+//! Value-moving tasks check the shared [`budget`](crate::budget) before
+//! sending; today's spend is loaded from the database at startup. With
+//! `[verify]` enabled, state changes are re-read through another route by the
+//! [`consistency`](crate::consistency) verifier. With `[session_keys]`
+//! enabled, Tempo transactions are signed by per-wallet
+//! [`session_key`](crate::session_key)s. With `[audit]` sampling, a share of
+//! runs is stored with every RPC call and transaction by [`audit`](crate::audit).
+//! Reported transactions are handed to the [`gas_usage`](crate::gas_usage)
+//! tracker. Leased wallets join the pool [`transfers`](crate::transfers)
+//! knows; with `[recipients] pool_share` set, they become recipients of
+//! other workers' transfers. With `[mempool]` enabled, wallets with too many
+//! unconfirmed transactions are skipped until the
+//! [`mempool`](crate::mempool) monitor sees them land. The
+//! [`chain_head`](crate::chain_head) watcher polls the latest block once for
+//! all workers.
+//!
+//! Each task runs inside a `task` span, the parent of its transaction, RPC
+//! and database spans when `[tracing]` exports them. Runs past `[watchdog]
+//! soft_threshold` of their timeout are snapshotted by the
+//! [`watchdog`](crate::watchdog), and the snapshot is stored with the run.
+//!
+//! Task errors are logged with their decoded revert reason; each task's custom
+//! errors are added to the [`revert`](crate::revert) registry at startup.
+//! Success lines are sampled and summarised per task by the
+//! [`result_log`](crate::result_log) under `[results]`.
+//! With `[slo] min_success_rate` set, tasks whose success rate falls below
+//! it are paused and probed by [`task_slo`](crate::task_slo).
+//!
+//! [`run_spammer`] runs until the process exits; [`run_spammer_until`] stops
+//! the workers when a shutdown future resolves. Embedders reach the loop
+//! through [`Spammer`](crate::spammer::Spammer), which can also hand every
+//! finished task to [`ResultSink`]s, or through
+//! [`SpammerRuntime`](crate::runtime::SpammerRuntime), which can also pause
+//! the workers between tasks. Per-proxy traffic counters are
+//! flushed to `proxy_stats` every 30 seconds and once more on shutdown.
+
+use crate::ClientPool;
+use crate::activity::ActivitySchedule;
+use crate::audit::{self, AuditTrace};
+use crate::budget::SpendBudget;
+use crate::chain_head::ChainHead;
+use crate::client_pool::WalletPartition;
+use crate::config::{HourWindow, TaskSettings, TempoSpammerConfig, WalletAssignment};
+use crate::consistency::ConsistencyVerifier;
+use crate::contracts::HelperRegistry;
+use crate::gas_usage::GasTracker;
+use crate::log_archive::LogArchive;
+use crate::mempool::PendingMonitor;
+use crate::pacing::WorkerPacing;
+use crate::persona::{Persona, PersonaAssigner};
+use crate::proxy_assignment::worker_scope;
+use crate::remote_config;
+use crate::result_log::result_log;
+use crate::revert;
+use crate::session_key::SessionKeys;
+use crate::task_slo::task_slo;
+use crate::tasks::{TaskContext, TaskResult, TempoTask};
+use crate::transfers::TransferTracker;
+use crate::watchdog::{self, Diagnostics};
+use alloy_primitives::Address;
+use chrono::Timelike;
+use core_logic::database::{DatabaseManager, QueuedTaskResult, TaskMetadata};
+use core_logic::metrics::{MetricsCollector, TaskLabels};
+use futures::future::join_all;
+use rand::distributions::{Distribution, WeightedIndex};
+use rand::rngs::StdRng;
+use rand::{Rng, SeedableRng};
+use std::collections::{HashMap, VecDeque};
+use std::future::Future;
+use std::sync::{Arc, Mutex};
+use std::time::{Duration, Instant};
+use tokio::sync::{broadcast, watch};
+use tracing::{Instrument, debug, error, info, warn};
+
+/// How often a worker parked by the activity schedule checks the level again
+const ACTIVITY_RECHECK: Duration = Duration::from_secs(30);
+
+/// How long a worker waits on a saturated client pool before looping again
+const LEASE_WAIT: Duration = Duration::from_secs(1);
+
+/// Every task the spammer knows about, in catalog order
+pub fn default_tasks() -> Vec<Box<dyn TempoTask>> {
+    vec![
+        Box::new(crate::tasks::t01_deploy_contract::DeployContractTask::new()),
+        Box::new(crate::tasks::t02_claim_faucet::ClaimFaucetTask::new()),
+        Box::new(crate::tasks::t03_send_token::SendTokenTask::new()),
+        Box::new(crate::tasks::t04_create_stable::CreateStableTask::new()),
+        Box::new(crate::tasks::t05_swap_stable::SwapStableTask::new()),
+        Box::new(crate::tasks::t06_add_liquidity::AddLiquidityTask::new()),
+        Box::new(crate::tasks::t07_mint_stable::MintStableTask::new()),
+        Box::new(crate::tasks::t08_burn_stable::BurnStableTask::new()),
+        Box::new(crate::tasks::t09_transfer_token::TransferTokenTask::new()),
+        Box::new(crate::tasks::t10_transfer_memo::TransferMemoTask::new()),
+        Box::new(crate::tasks::t11_limit_order::LimitOrderTask::new()),
+        Box::new(crate::tasks::t12_remove_liquidity::RemoveLiquidityTask::new()),
+        Box::new(crate::tasks::t13_grant_role::GrantRoleTask::new()),
+        Box::new(crate::tasks::t14_nft_create_mint::NftCreateMintTask::new()),
+        Box::new(crate::tasks::t15_mint_domain::MintDomainTask::new()),
+        Box::new(crate::tasks::t16_mint_random_nft::MintRandomNftTask::new()),
+        Box::new(crate::tasks::t17_batch_eip7702::BatchEip7702Task::new()),
+        Box::new(crate::tasks::t18_tip403_policies::Tip403PoliciesTask::new()),
+        Box::new(crate::tasks::t19_wallet_analytics::WalletAnalyticsTask::new()),
+        Box::new(crate::tasks::t20_wallet_activity::WalletActivityTask::new()),
+        Box::new(crate::tasks::t21_create_meme::CreateMemeTask::new()),
+        Box::new(crate::tasks::t22_mint_meme::MintMemeTask::new()),
+        Box::new(crate::tasks::t23_transfer_meme::TransferMemeTask::new()),
+        Box::new(crate::tasks::t24_batch_swap::BatchSwapTask::new()),
+        Box::new(crate::tasks::t25_batch_system_token::BatchSystemTokenTask::new()),
+        Box::new(crate::tasks::t26_batch_stable_token::BatchStableTokenTask::new()),
+        Box::new(crate::tasks::t27_batch_meme_token::BatchMemeTokenTask::new()),
+        Box::new(crate::tasks::t28_multi_send_disperse::MultiSendDisperseTask::new()),
+        Box::new(crate::tasks::t29_multi_send_disperse_stable::MultiSendDisperseStableTask::new()),
+        Box::new(crate::tasks::t30_multi_send_disperse_meme::MultiSendDisperseMemeTask::new()),
+        Box::new(crate::tasks::t31_multi_send_concurrent::MultiSendConcurrentTask::new()),
+        Box::new(
+            crate::tasks::t32_multi_send_concurrent_stable::MultiSendConcurrentStableTask::new(),
+        ),
+        Box::new(crate::tasks::t33_multi_send_concurrent_meme::MultiSendConcurrentMemeTask::new()),
+        Box::new(crate::tasks::t34_batch_send_transaction::BatchSendTransactionTask::new()),
+        Box::new(
+            crate::tasks::t35_batch_send_transaction_stable::BatchSendTransactionStableTask::new(),
+        ),
+        Box::new(
+            crate::tasks::t36_batch_send_transaction_meme::BatchSendTransactionMemeTask::new(),
+        ),
+        Box::new(crate::tasks::t37_transfer_later::TransferLaterTask::new()),
+        Box::new(crate::tasks::t38_transfer_later_stable::TransferLaterStableTask::new()),
+        Box::new(crate::tasks::t39_transfer_later_meme::TransferLaterMemeTask::new()),
+        Box::new(crate::tasks::t40_distribute_shares::DistributeSharesTask::new()),
+        Box::new(crate::tasks::t41_distribute_shares_stable::DistributeSharesStableTask::new()),
+        Box::new(crate::tasks::t42_distribute_shares_meme::DistributeSharesMemeTask::new()),
+        Box::new(crate::tasks::t43_batch_mint_stable::BatchMintStableTask::new()),
+        Box::new(crate::tasks::t44_batch_mint_meme::BatchMintMemeTask::new()),
+        Box::new(crate::tasks::t45_deploy_viral_faucet::DeployViralFaucetTask::new()),
+        Box::new(crate::tasks::t46_claim_viral_faucet::ClaimViralFaucetTask::new()),
+        Box::new(crate::tasks::t47_deploy_viral_nft::DeployViralNftTask::new()),
+        Box::new(crate::tasks::t48_mint_viral_nft::MintViralNftTask::new()),
+        Box::new(crate::tasks::t49_time_bomb::TimeBombTask::new()),
+        Box::new(crate::tasks::t50_deploy_storm::DeployStormTask::new()),
+        Box::new(crate::tasks::t51_stress_large_calldata::StressLargeCalldataTask::new()),
+        Box::new(crate::tasks::t52_stress_max_calls::StressMaxCallsTask::new()),
+        Box::new(crate::tasks::t53_stress_near_gas_limit::StressNearGasLimitTask::new()),
+        Box::new(crate::tasks::t54_disperse_helper::DisperseHelperTask::new()),
+        Box::new(crate::tasks::t55_native_batch_calls::NativeBatchCallsTask::new()),
+        Box::new(crate::tasks::t56_aa_create_account::AaCreateAccountTask::new()),
+        Box::new(crate::tasks::t57_aa_user_operation::AaUserOperationTask::new()),
+        Box::new(crate::tasks::t58_aa_batch_user_operation::AaBatchUserOperationTask::new()),
+        Box::new(crate::tasks::t59_nft_list::NftListTask::new()),
+        Box::new(crate::tasks::t60_nft_buy::NftBuyTask::new()),
+        Box::new(crate::tasks::t61_nft_cancel_listing::NftCancelListingTask::new()),
+        Box::new(crate::tasks::t62_domain_set_resolver::DomainSetResolverTask::new()),
+        Box::new(crate::tasks::t63_domain_set_addr::DomainSetAddrTask::new()),
+        Box::new(crate::tasks::t64_domain_transfer::DomainTransferTask::new()),
+        Box::new(crate::tasks::t65_domain_renew::DomainRenewTask::new()),
+        Box::new(crate::tasks::t66_abi_fuzz_call::AbiFuzzCallTask::new()),
+        Box::new(crate::tasks::t67_sign_message::SignMessageTask::new()),
+        Box::new(crate::tasks::t68_sign_typed_order::SignTypedOrderTask::new()),
+    ]
+}
+
+/// Runs the weighted task loop with `worker_count` workers
+///
+/// # Arguments
+///
+/// * `client_pool` - Wallet pool the workers lease clients from
+/// * `tasks` - Tasks to pick from, usually [`default_tasks`]
+/// * `config` - Spammer configuration (intervals, timeouts, chain id)
+/// * `db_manager` - Database with async logging enabled
+/// * `worker_count` - Number of concurrent workers
+pub async fn run_spammer(
+    client_pool: Arc<ClientPool>,
+    tasks: Vec<Box<dyn TempoTask>>,
+    config: &TempoSpammerConfig,
+    db_manager: Arc<DatabaseManager>,
+    worker_count: u64,
+) {
+    run_spammer_until(
+        client_pool,
+        tasks,
+        config,
+        db_manager,
+        worker_count,
+        std::future::pending(),
+    )
+    .await
+}
+
+/// Weighted task choice and timeouts under the current [`TaskSettings`]
+#[derive(Debug, Clone)]
+struct TaskPicker {
+    names: Vec<&'static str>,
+    weights: Vec<u32>,
+    windows: Vec<Option<HourWindow>>,
+    timeouts: Vec<Duration>,
+    starvation: Option<Duration>,
+    /// Block interval of the tasks run by [`BlockTriggers`] instead of weight
+    every_blocks: Vec<Option<u64>>,
+}
+
+/// Runs per task across all workers, kept while the picker is rebuilt
+#[derive(Debug)]
+struct TaskLedger {
+    tallies: Mutex<Vec<TaskTally>>,
+}
+
+#[derive(Debug, Clone, Copy)]
+struct TaskTally {
+    runs: u64,
+    forced: u64,
+    /// Last pick, or the start of the run before the first one
+    last: Instant,
+}
+
+impl TaskLedger {
+    fn new(task_count: usize, start: Instant) -> Self {
+        let tally = TaskTally {
+            runs: 0,
+            forced: 0,
+            last: start,
+        };
+        Self {
+            tallies: Mutex::new(vec![tally; task_count]),
+        }
+    }
+
+    /// Index of the enabled task longest without a run, if it waited
+    /// `starvation` or more; records nothing
+    fn most_starved(&self, weights: &[f64], starvation: Duration, now: Instant) -> Option<usize> {
+        let tallies = self.tallies.lock().unwrap();
+        tallies
+            .iter()
+            .enumerate()
+            .filter(|&(idx, tally)| {
+                weights[idx] > 0.0 && now.saturating_duration_since(tally.last) >= starvation
+            })
+            .min_by_key(|(_, tally)| tally.last)
+            .map(|(idx, _)| idx)
+    }
+
+    fn record(&self, idx: usize, forced: bool, now: Instant) {
+        let mut tallies = self.tallies.lock().unwrap();
+        let tally = &mut tallies[idx];
+        tally.runs += 1;
+        tally.forced += u64::from(forced);
+        tally.last = now;
+    }
+
+    /// `(runs, forced runs)` per task index
+    fn counts(&self) -> Vec<(u64, u64)> {
+        let tallies = self.tallies.lock().unwrap();
+        tallies.iter().map(|t| (t.runs, t.forced)).collect()
+    }
+}
+
+impl TaskPicker {
+    /// `task_timeout` applies to tasks without a `[tasks] timeouts` entry
+    /// whose own [`TempoTask::default_timeout`] is not longer
+    fn new(tasks: &[Box<dyn TempoTask>], settings: &TaskSettings, task_timeout: Duration) -> Self {
+        for name in settings
+            .weights
+            .keys()
+            .chain(settings.schedule.keys())
+            .chain(settings.timeouts.keys())
+            .chain(settings.every_blocks.keys())
+        {
+            if !tasks.iter().any(|task| task.name() == name) {
+                warn!("Task settings refer to unknown task '{}'", name);
+            }
+        }
+
+        let every_blocks: Vec<Option<u64>> = tasks
+            .iter()
+            .map(|task| {
+                settings
+                    .every_blocks
+                    .get(task.name())
+                    .copied()
+                    .filter(|&blocks| blocks > 0)
+            })
+            .collect();
+
+        Self {
+            names: tasks.iter().map(|task| task.name()).collect(),
+            // Block-triggered tasks only run on their blocks
+            weights: tasks
+                .iter()
+                .zip(&every_blocks)
+                .map(|(task, every)| match every {
+                    Some(_) => 0,
+                    None => settings
+                        .weights
+                        .get(task.name())
+                        .copied()
+                        .unwrap_or_else(|| default_weight(task.name())),
+                })
+                .collect(),
+            windows: tasks
+                .iter()
+                .map(|task| settings.schedule.get(task.name()).copied())
+                .collect(),
+            timeouts: tasks
+                .iter()
+                .map(|task| match settings.timeouts.get(task.name()) {
+                    Some(&secs) => Duration::from_secs(secs),
+                    None => task
+                        .default_timeout()
+                        .map_or(task_timeout, |timeout| timeout.max(task_timeout)),
+                })
+                .collect(),
+            starvation: settings
+                .starvation_secs
+                .filter(|&secs| secs > 0)
+                .map(Duration::from_secs),
+            every_blocks,
+        }
+    }
+
+    /// Effective timeout of the task at `idx`
+    fn timeout(&self, idx: usize) -> Duration {
+        self.timeouts[idx]
+    }
+
+    /// Whether any task runs on blocks rather than by weight
+    fn has_block_tasks(&self) -> bool {
+        self.every_blocks.iter().any(Option::is_some)
+    }
+
+    /// Index of a task allowed to run at the UTC `hour`, if any is, recorded
+    /// in the `ledger`
+    ///
+    /// A persona multiplies the weights by its own per-task factors. With
+    /// `starvation_secs` set, an allowed task that has not run for that long
+    /// is picked before any weighted choice.
+    fn pick(
+        &self,
+        rng: &mut impl Rng,
+        hour: u32,
+        persona: Option<&Persona>,
+        ledger: &TaskLedger,
+        now: Instant,
+    ) -> Option<usize> {
+        let weights: Vec<f64> = self
+            .names
+            .iter()
+            .zip(&self.weights)
+            .zip(&self.windows)
+            .map(|((name, &weight), window)| match window {
+                Some(window) if !window.contains(hour) => 0.0,
+                _ if task_slo().is_paused(name) => 0.0,
+                _ => f64::from(weight) * persona.map_or(1.0, |p| p.weight_multiplier(name)),
+            })
+            .collect();
+
+        if let Some(idx) = self
+            .starvation
+            .and_then(|starvation| ledger.most_starved(&weights, starvation, now))
+        {
+            debug!("Picking starved task {}", self.names[idx]);
+            ledger.record(idx, true, now);
+            return Some(idx);
+        }
+
+        let idx = WeightedIndex::new(&weights).ok()?.sample(rng);
+        ledger.record(idx, false, now);
+        Some(idx)
+    }
+}
+
+/// Block-triggered tasks waiting for a worker
+///
+/// Each new block from the [`ChainHead`] marks the tasks whose `[tasks]
+/// every_blocks` interval it completes as due, and workers take a due task
+/// ahead of their weighted pick, so each trigger runs once. A trigger still
+/// waiting when its task's next one comes is replaced rather than queued.
+#[derive(Debug, Default)]
+struct BlockTriggers {
+    state: Mutex<TriggerState>,
+    /// Wakes workers idling for lack of a weighted task
+    ready: tokio::sync::Notify,
+}
+
+#[derive(Debug, Default)]
+struct TriggerState {
+    /// Block each task was last triggered by
+    last: HashMap<usize, u64>,
+    /// Due task indices with their triggering block, oldest first
+    due: VecDeque<(usize, u64)>,
+    /// Triggers replaced before a worker took them
+    missed: u64,
+}
+
+impl BlockTriggers {
+    /// Marks the tasks due at `block` and the UTC `hour`, returning how many
+    ///
+    /// A task is due on the first block seen and then once `every_blocks`
+    /// blocks have passed since its last trigger, so blocks the watcher
+    /// skipped do not pile up runs.
+    fn on_block(&self, picker: &TaskPicker, block: u64, hour: u32) -> usize {
+        let mut guard = self.state.lock().unwrap();
+        let state = &mut *guard;
+        let mut triggered = 0;
+        for (idx, every) in picker.every_blocks.iter().enumerate() {
+            let Some(every) = *every else {
+                continue;
+            };
+            if picker.windows[idx].is_some_and(|window| !window.contains(hour)) {
+                continue;
+            }
+            if state
+                .last
+                .get(&idx)
+                .is_some_and(|&last| block < last.saturating_add(every))
+            {
+                continue;
+            }
+            state.last.insert(idx, block);
+            match state.due.iter_mut().find(|(due, _)| *due == idx) {
+                Some(waiting) => {
+                    waiting.1 = block;
+                    state.missed += 1;
+                }
+                None => state.due.push_back((idx, block)),
+            }
+            triggered += 1;
+        }
+        drop(guard);
+
+        for _ in 0..triggered {
+            self.ready.notify_one();
+        }
+        triggered
+    }
+
+    /// Oldest due task and the block that triggered it
+    fn take(&self) -> Option<(usize, u64)> {
+        self.state.lock().unwrap().due.pop_front()
+    }
+
+    /// Waits up to `max` for a trigger
+    async fn wait(&self, max: Duration) {
+        let _ = tokio::time::timeout(max, self.ready.notified()).await;
+    }
+
+    fn missed(&self) -> u64 {
+        self.state.lock().unwrap().missed
+    }
+}
+
+/// Resolves the helper contracts and warns about tasks that cannot use theirs
+async fn resolve_helpers(
+    client_pool: &ClientPool,
+    config: &TempoSpammerConfig,
+    db: &Arc<DatabaseManager>,
+    tasks: &[Box<dyn TempoTask>],
+) -> Option<Arc<HelperRegistry>> {
+    let resolved = match client_pool.get_client(0).await {
+        Ok(client) => {
+            HelperRegistry::resolve(
+                client.provider(),
+                client.chain_id(),
+                &config.helpers,
+                Some(db.as_ref()),
+            )
+            .await
+        }
+        Err(e) => Err(e),
+    };
+    let registry = match resolved {
+        Ok(registry) => registry,
+        Err(e) => {
+            warn!(
+                "Helper contracts not resolved, tasks using them will fail: {:#}",
+                e
+            );
+            return None;
+        }
+    };
+
+    for check in registry.checks() {
+        let Some(problem) = &check.problem else {
+            info!(target: "task_result", "Helper {} at {}", check.name, check.address.unwrap_or_default());
+            continue;
+        };
+        let dependents: Vec<_> = tasks
+            .iter()
+            .filter(|task| task.required_helpers().contains(&check.name))
+            .map(|task| task.name())
+            .collect();
+        if dependents.is_empty() {
+            debug!("Helper {} unavailable: {}", check.name, problem);
+        } else {
+            warn!(
+                "Helper {} unavailable ({}), failing tasks: {}",
+                check.name,
+                problem,
+                dependents.join(", ")
+            );
+        }
+    }
+    Some(Arc::new(registry))
+}
+
+/// Weight of tasks without one in the task settings
+fn default_weight(name: &str) -> u32 {
+    match name {
+        // Protocol limit probes only run when given a weight
+        n if n.contains("_stress_") => 0,
+        // Need a bundler and the ERC-4337 contracts from [aa]
+        n if n.contains("_aa_") => 0,
+        n if n.contains("SendToken") => 10,
+        n if n.contains("Transfer") => 10,
+        n if n.contains("Swap") => 5,
+        _ => 1,
+    }
+}
+
+/// Console text for a finished task: the tx hash or message, then receipt fields
+fn result_summary(result: &TaskResult) -> String {
+    let mut summary = if result.success {
+        if let Some(tx_hash) = &result.tx_hash {
+            format!("TxHash: {}", tx_hash)
+        } else if !result.message.is_empty() {
+            result.message.clone()
+        } else {
+            "Success".to_string()
+        }
+    } else {
+        result.message.clone()
+    };
+
+    if let Some(contract) = &result.contract_address {
+        summary.push_str(&format!(" contract:{}", contract));
+    }
+    if let Some(block) = result.block_number {
+        summary.push_str(&format!(" blk:{}", block));
+    }
+    if let Some(gas) = result.gas_used {
+        summary.push_str(&format!(" gas:{}", gas));
+    }
+    summary
+}
+
+/// Scores a finished task of wallet `wallet_idx`, logging a quarantine it starts
+///
+/// `error` is the failure message, `None` for a success.
+fn record_wallet_health(
+    client_pool: &ClientPool,
+    wallet_idx: usize,
+    wallet: Address,
+    error: Option<&str>,
+) {
+    let Some(quarantined) = client_pool
+        .wallet_health()
+        .record(wallet_idx, wallet, error)
+    else {
+        return;
+    };
+    warn!(
+        target: "task_result",
+        "[WL:{:03}] Quarantined wallet {} until {}: score {}, {} failures in a row ({})",
+        wallet_idx,
+        wallet,
+        quarantined.until.format("%H:%M:%S UTC"),
+        quarantined.score,
+        quarantined.consecutive_failures,
+        quarantined.error_summary()
+    );
+}
+
+/// A finished task as handed to [`ResultSink`]s
+#[derive(Debug, Clone)]
+pub struct TaskOutcome {
+    pub worker_id: u64,
+    pub wallet: Address,
+    pub task: &'static str,
+    pub duration: Duration,
+    /// The task's result; errors and timeouts are unsuccessful results
+    /// carrying the error as their message, as in `task_metrics`
+    pub result: TaskResult,
+}
+
+/// Receives every finished task alongside the database
+///
+/// Called on the worker right after the task, so implementations should
+/// only hand the outcome off. An unbounded channel sender is a sink.
+pub trait ResultSink: Send + Sync {
+    fn record(&self, outcome: &TaskOutcome);
+}
+
+impl ResultSink for tokio::sync::mpsc::UnboundedSender<TaskOutcome> {
+    fn record(&self, outcome: &TaskOutcome) {
+        // A dropped receiver only means nobody listens anymore
+        let _ = self.send(outcome.clone());
+    }
+}
+
+/// Hands a finished task to every sink, building the outcome only if needed
+fn notify_sinks(
+    sinks: &[Arc<dyn ResultSink>],
+    worker_id: u64,
+    wallet: Address,
+    task: &'static str,
+    duration: Duration,
+    result: impl FnOnce() -> TaskResult,
+) {
+    if sinks.is_empty() {
+        return;
+    }
+    let outcome = TaskOutcome {
+        worker_id,
+        wallet,
+        task,
+        duration,
+        result: result(),
+    };
+    for sink in sinks {
+        sink.record(&outcome);
+    }
+}
+
+/// Counts a finished task in the global metrics registry by chain, task,
+/// wallet bucket and proxy
+fn record_task_metrics(
+    chain_id: u64,
+    task: &str,
+    wallet_index: usize,
+    proxy_index: Option<usize>,
+    duration: Duration,
+    success: bool,
+) {
+    let labels = TaskLabels {
+        chain_id: Some(chain_id),
+        task,
+        wallet_index: Some(wallet_index),
+        proxy_index,
+    };
+    MetricsCollector::global().record_task_with(&labels, duration, success);
+}
+
+/// Same as [`run_spammer`], aborting the workers once `shutdown` resolves
+///
+/// Tasks in flight are dropped; results already queued still reach the
+/// database when it is shut down.
+pub async fn run_spammer_until(
+    client_pool: Arc<ClientPool>,
+    tasks: Vec<Box<dyn TempoTask>>,
+    config: &TempoSpammerConfig,
+    db_manager: Arc<DatabaseManager>,
+    worker_count: u64,
+    shutdown: impl Future<Output = ()>,
+) {
+    run_with_sinks(
+        client_pool,
+        tasks,
+        config,
+        db_manager,
+        worker_count,
+        Vec::new(),
+        watch::channel(false).1,
+        shutdown,
+    )
+    .await
+}
+
+/// [`run_spammer_until`] that also hands every finished task to `sinks`
+///
+/// While `paused` holds `true` the workers finish their current task and
+/// wait before leasing the next wallet.
+#[allow(clippy::too_many_arguments)]
+pub(crate) async fn run_with_sinks(
+    client_pool: Arc<ClientPool>,
+    tasks: Vec<Box<dyn TempoTask>>,
+    config: &TempoSpammerConfig,
+    db_manager: Arc<DatabaseManager>,
+    worker_count: u64,
+    sinks: Vec<Arc<dyn ResultSink>>,
+    paused: watch::Receiver<bool>,
+    shutdown: impl Future<Output = ()>,
+) {
+    info!(target: "task_result", "Starting spammer with {} workers...", worker_count);
+    info!(target: "task_result", "Per-worker semaphore: {} concurrent requests", config.worker_semaphore);
+
+    crate::rpc_cache::cache().configure(config.rpc_cache.clone());
+    crate::rate_limit::limiter().configure(config.rate_limit.clone());
+    crate::watchdog::watchdog().configure(config.watchdog.clone());
+    crate::chaos::chaos().configure(config.chaos);
+    task_slo().configure(config.slo);
+    if config.chaos.enabled {
+        warn!(
+            "Chaos enabled: dropping {:.1}%, 429ing {:.1}%, nonce too low on {:.1}% of sends, delaying {:.1}% of receipts by {}ms",
+            config.chaos.drop_rate * 100.0,
+            config.chaos.rate_limit_rate * 100.0,
+            config.chaos.nonce_too_low_rate * 100.0,
+            config.chaos.receipt_delay_rate * 100.0,
+            config.chaos.receipt_delay_ms
+        );
+    }
+    crate::rpc_stats::rpc_stats().configure(config.rpc_stats.clone());
+    crate::network::configure(&config.contracts);
+    result_log().configure(config.results);
+    if let Err(e) = crate::recording::recorder().configure(&config.rpc_recording) {
+        warn!("RPC recording disabled: {:#}", e);
+    }
+
+    {
+        let mut decoder = revert::decoder().write().unwrap();
+        for task in &tasks {
+            task.register_errors(&mut decoder);
+        }
+    }
+
+    let tasks = Arc::new(tasks);
+
+    let personas = Arc::new(PersonaAssigner::new(&config.personas));
+    if !personas.is_empty() {
+        for (name, count) in personas.distribution(client_pool.count()) {
+            info!(target: "task_result", "Persona {}: {} wallets", name, count);
+        }
+    }
+
+    let budget = match SpendBudget::new(&config.budget) {
+        Ok(budget) if !budget.is_empty() => {
+            let budget = budget.with_db(db_manager.clone());
+            if let Err(e) = budget.load_today().await {
+                warn!("Failed to load today's spend, starting from zero: {:#}", e);
+            }
+            info!(target: "task_result", "Spend budget active for {} tokens", budget.capped_tokens().len());
+            Some(Arc::new(budget))
+        }
+        Ok(_) => None,
+        Err(e) => {
+            warn!("Ignoring [budget]: {:#}", e);
+            None
+        }
+    };
+
+    let verifier = config.verify.enabled.then(|| {
+        info!(
+            "Verifying {:.0}% of state changes through a second route",
+            config.verify.sample_rate * 100.0
+        );
+        Arc::new(ConsistencyVerifier::new(
+            config.verify.clone(),
+            &config.rpc_url,
+            client_pool.proxies().to_vec(),
+            Some(db_manager.clone()),
+        ))
+    });
+
+    for name in config.gas.limits.keys() {
+        if !tasks.iter().any(|task| task.name() == name) {
+            warn!("gas.limits refers to unknown task '{}'", name);
+        }
+    }
+    let gas_tracker = config
+        .gas
+        .track_usage
+        .then(|| Arc::new(GasTracker::new(config.gas.clone(), db_manager.clone())));
+
+    for name in &config.log_archive.tasks {
+        if !tasks.iter().any(|task| task.name() == name) {
+            warn!("log_archive.tasks refers to unknown task '{}'", name);
+        }
+    }
+    let log_archive = config.log_archive.enabled.then(|| {
+        Arc::new(LogArchive::new(
+            config.log_archive.clone(),
+            db_manager.clone(),
+            Duration::from_secs(config.gas.receipt_timeout_secs),
+            config.gas.max_pending,
+        ))
+    });
+
+    let session_keys = if config.session_keys.enabled {
+        match SessionKeys::new(&config.session_keys) {
+            Ok(keys) => {
+                info!(target: "task_result", "Signing Tempo transactions with session keys ({}h rotation)", config.session_keys.ttl_hours);
+                Some(Arc::new(keys))
+            }
+            Err(e) => {
+                warn!("Ignoring [session_keys]: {:#}", e);
+                None
+            }
+        }
+    } else {
+        None
+    };
+
+    let helpers = resolve_helpers(&client_pool, config, &db_manager, &tasks).await;
+    let capabilities = client_pool.capabilities();
+
+    // Every run knows its leased wallets (domain transfers go between them);
+    // only `pool_share` sends ordinary transfers their way
+    if config.recipients.pool_share > 0.0 {
+        info!(
+            "Sending {:.0}% of transfers to other pool wallets",
+            config.recipients.pool_share * 100.0
+        );
+    }
+    let transfers = Arc::new(TransferTracker::new(config.recipients.clone()));
+
+    let (pending_monitor, mempool_handle) = if config.mempool.enabled {
+        match client_pool.get_client(0).await {
+            Ok(client) => {
+                info!(
+                    "Skipping wallets with {}+ unconfirmed transactions",
+                    config.mempool.max_pending
+                );
+                let monitor = Arc::new(PendingMonitor::new(config.mempool.clone()));
+                let polling = monitor.clone();
+                let handle = tokio::spawn(async move { polling.run(client.provider()).await });
+                (Some(monitor), Some(handle))
+            }
+            Err(e) => {
+                warn!("Ignoring [mempool], no client to poll with: {:#}", e);
+                (None, None)
+            }
+        }
+    } else {
+        (None, None)
+    };
+
+    // One poll of the latest block for every task that reads the head
+    let (chain_head, chain_head_handle) = if config.chain_head.enabled {
+        match client_pool.get_client(0).await {
+            Ok(client) => {
+                let chain_head = Arc::new(ChainHead::new(config.chain_head.clone()));
+                let polling = chain_head.clone();
+                let handle = tokio::spawn(async move { polling.run(client.provider()).await });
+                (Some(chain_head), Some(handle))
+            }
+            Err(e) => {
+                warn!("Ignoring [chain_head], no client to poll with: {:#}", e);
+                (None, None)
+            }
+        }
+    } else {
+        (None, None)
+    };
+
+    // [tasks] settings, re-tuned by the remote document when one is configured
+    let (settings_tx, mut settings_rx) = watch::channel(config.tasks.clone());
+    let remote_handle = if config.remote.enabled() {
+        info!(
+            "Polling task settings from {} every {}s",
+            config.remote.url, config.remote.poll_interval_secs
+        );
+        Some(remote_config::spawn_poller(
+            config.remote.clone(),
+            config.tasks.clone(),
+            settings_tx,
+        ))
+    } else {
+        None
+    };
+
+    let price_handle = config.price.enabled.then(|| {
+        info!(
+            "USD prices from {:?} source, refreshed every {}s",
+            config.price.source, config.price.refresh_secs
+        );
+        crate::price::feed().spawn_refresher(config.price.clone(), config.rpc_url.clone())
+    });
+
+    let task_timeout = Duration::from_secs(config.task_timeout);
+    let initial_picker = TaskPicker::new(&tasks, &settings_rx.borrow_and_update(), task_timeout);
+    let (picker_tx, picker_rx) = watch::channel(Arc::new(initial_picker));
+    let picker_tasks = tasks.clone();
+    let ledger = Arc::new(TaskLedger::new(tasks.len(), Instant::now()));
+    let picker_handle = tokio::spawn(async move {
+        while settings_rx.changed().await.is_ok() {
+            let picker = TaskPicker::new(
+                &picker_tasks,
+                &settings_rx.borrow_and_update(),
+                task_timeout,
+            );
+            if picker_tx.send(Arc::new(picker)).is_err() {
+                break;
+            }
+        }
+    });
+
+    // [tasks] every_blocks tasks, marked due by each new block
+    let triggers = Arc::new(BlockTriggers::default());
+    let trigger_handle = match &chain_head {
+        Some(chain_head) => {
+            let mut blocks = chain_head.subscribe();
+            let triggers = triggers.clone();
+            let picker_rx = picker_rx.clone();
+            Some(tokio::spawn(async move {
+                loop {
+                    match blocks.recv().await {
+                        Ok(head) => {
+                            let picker = picker_rx.borrow().clone();
+                            let due =
+                                triggers.on_block(&picker, head.number, chrono::Utc::now().hour());
+                            if due > 0 {
+                                debug!("Block {} triggered {} tasks", head.number, due);
+                            }
+                        }
+                        Err(broadcast::error::RecvError::Lagged(_)) => {}
+                        Err(broadcast::error::RecvError::Closed) => break,
+                    }
+                }
+            }))
+        }
+        None => {
+            if picker_rx.borrow().has_block_tasks() {
+                warn!("Tasks in [tasks] every_blocks never run without [chain_head]");
+            }
+            None
+        }
+    };
+
+    let activity = Arc::new(
+        ActivitySchedule::from_settings(&config.activity).unwrap_or_else(|e| {
+            warn!("Ignoring [activity]: {:#}", e);
+            ActivitySchedule::default()
+        }),
+    );
+    if !activity.is_constant() {
+        info!(
+            "Activity schedule: {} of {} workers running now",
+            ActivitySchedule::active_workers(activity.level(chrono::Utc::now()), worker_count),
+            worker_count
+        );
+    }
+
+    let partitioned = config.wallets.assignment == WalletAssignment::Partitioned;
+    if partitioned {
+        let wallets = client_pool.count();
+        info!(target: "task_result", "Partitioning {} wallets over {} workers", wallets, worker_count);
+        if (worker_count as usize) > wallets {
+            warn!(
+                "{} of {} workers own no wallet and stay idle",
+                worker_count as usize - wallets,
+                worker_count
+            );
+        }
+    }
+    if let Some(workers) = client_pool.worker_proxies() {
+        info!(target: "task_result", "Binding {} workers to their own proxies", worker_count);
+        let shared = workers.shared(worker_count);
+        if shared > 0 {
+            warn!(
+                "{} of {} workers share a proxy with another worker",
+                shared, worker_count
+            );
+        }
+        if !partitioned {
+            warn!(
+                "Worker proxies with leased wallets rebuild a wallet's client whenever another worker leases it; set [wallets] assignment = \"partitioned\""
+            );
+        }
+    }
+    let reuse_cooldown = Duration::from_millis(
+        config
+            .nonce
+            .base_cooldown_ms
+            .max(config.nonce.min_cooldown_ms),
+    );
+
+    let host_partition = config.wallets.partition.map(|p| p.to_string());
+    let config = config.clone();
+    let _client_count = client_pool.count();
+    let sinks: Arc<[Arc<dyn ResultSink>]> = sinks.into();
+
+    let mut handles = Vec::new();
+
+    for worker_id in 0..worker_count {
+        let client_pool = client_pool.clone();
+        let tasks = tasks.clone();
+        let db = db_manager.clone();
+        let config = config.clone();
+        let picker_rx = picker_rx.clone();
+        let ledger = ledger.clone();
+        let host_partition = host_partition.clone();
+        let personas = personas.clone();
+        let activity = activity.clone();
+        let budget = budget.clone();
+        let verifier = verifier.clone();
+        let session_keys = session_keys.clone();
+        let helpers = helpers.clone();
+        let transfers = transfers.clone();
+        let pending_monitor = pending_monitor.clone();
+        let chain_head = chain_head.clone();
+        let triggers = triggers.clone();
+        let gas_tracker = gas_tracker.clone();
+        let log_archive = log_archive.clone();
+        let sinks = sinks.clone();
+        let mut paused = paused.clone();
+        let pacing = WorkerPacing::new(&config, worker_id);
+        let mut partition = partitioned.then(|| {
+            WalletPartition::new(worker_id, worker_count, client_pool.count(), reuse_cooldown)
+        });
+        if partition.as_ref().is_some_and(|p| p.wallets().is_empty()) {
+            continue;
+        }
+
+        // Per-worker semaphore to prevent burst patterns
+        let worker_semaphore = Arc::new(tokio::sync::Semaphore::new(config.worker_semaphore));
+
+        let worker = async move {
+            let mut rng = StdRng::from_entropy();
+            tokio::time::sleep(pacing.start_delay(&mut rng)).await;
+
+            let mut backoff_ms = 10u64; // Start with 10ms backoff
+            let mut last_active = worker_count;
+
+            loop {
+                if *paused.borrow() && paused.wait_for(|paused| !paused).await.is_err() {
+                    break;
+                }
+
+                // Workers above the current activity level sit out
+                if !activity.is_constant() {
+                    let level = activity.level(chrono::Utc::now());
+                    let active = ActivitySchedule::active_workers(level, worker_count);
+                    if worker_id == 0 && active != last_active {
+                        info!(target: "task_result", "Activity level {:.2}: {}/{} workers", level, active, worker_count);
+                        last_active = active;
+                    }
+                    if worker_id >= active {
+                        tokio::time::sleep(ACTIVITY_RECHECK).await;
+                        continue;
+                    }
+                }
+
+                // Acquire per-worker permit (prevents burst patterns)
+                let _worker_permit = match worker_semaphore.clone().try_acquire_owned() {
+                    Ok(permit) => permit,
+                    Err(_) => {
+                        // Worker at capacity, wait briefly
+                        tokio::time::sleep(Duration::from_millis(50)).await;
+                        continue;
+                    }
+                };
+                // Acquire lease on a wallet: the shared pool parks the worker until a
+                // permit and a wallet free up, owned slices back off exponentially
+                let lease_started = Instant::now();
+                let acquired = match &mut partition {
+                    Some(partition) => partition.acquire(&client_pool).await,
+                    None => match client_pool.acquire_client_waiting(LEASE_WAIT).await {
+                        Some(lease) => Some(lease),
+                        // Timed out, re-check the activity level before waiting again
+                        None => continue,
+                    },
+                };
+                let lease = match acquired {
+                    Some(l) => {
+                        backoff_ms = 10; // Reset backoff on success
+                        l
+                    }
+                    None => {
+                        // All owned wallets busy, use exponential backoff (10ms -> 20ms -> 40ms... max 100ms)
+                        tokio::time::sleep(Duration::from_millis(backoff_ms)).await;
+                        backoff_ms = (backoff_ms * 2).min(100); // Double but cap at 100ms
+                        continue;
+                    }
+                };
+
+                let wallet_idx = lease.index;
+                let client = lease.client.clone(); // Clone ARC, lease stays alive until end of scope
+                transfers.register(client.address());
+                let hour = chrono::Utc::now().hour();
+
+                // Wallets rest outside their persona's active hours
+                let persona = personas.for_wallet(wallet_idx);
+                if persona.as_ref().is_some_and(|p| !p.is_active(hour)) {
+                    drop(lease);
+                    tokio::time::sleep(Duration::from_millis(100)).await;
+                    continue;
+                }
+
+                // Wallets with too many unconfirmed transactions wait for them to land
+                if let Some(monitor) = &pending_monitor {
+                    monitor.register(client.address());
+                    if monitor.is_backed_up(client.address()) {
+                        if let Some(partition) = &mut partition {
+                            partition.rest(wallet_idx, std::time::Instant::now());
+                        }
+                        lease.release().await;
+                        tokio::time::sleep(Duration::from_millis(100)).await;
+                        continue;
+                    }
+                }
+
+                // Stay within this chain's share of a proxy other chains also use
+                let _proxy_slot = match (&client_pool.proxy_arbiter, client.proxy_index) {
+                    (Some(arbiter), Some(proxy_idx)) => match arbiter.try_acquire(proxy_idx) {
+                        Some(permit) => Some(permit),
+                        None => {
+                            drop(lease);
+                            tokio::time::sleep(Duration::from_millis(backoff_ms)).await;
+                            continue;
+                        }
+                    },
+                    _ => None,
+                };
+
+                let picker = picker_rx.borrow().clone();
+                // A paused task due for its probe goes first, then a task its
+                // block triggered, then the weighted pick
+                let probe = task_slo()
+                    .take_probe(Instant::now())
+                    .and_then(|name| picker.names.iter().position(|task| *task == name));
+                let trigger = match probe {
+                    Some(_) => None,
+                    None => triggers
+                        .take()
+                        .filter(|(idx, _)| !task_slo().is_paused(picker.names[*idx])),
+                };
+                let picked = match (probe, trigger) {
+                    (Some(idx), _) => {
+                        info!("Probing paused task {}", picker.names[idx]);
+                        ledger.record(idx, false, Instant::now());
+                        Some(idx)
+                    }
+                    (None, Some((idx, block))) => {
+                        debug!("Running {} for block {}", picker.names[idx], block);
+                        ledger.record(idx, false, Instant::now());
+                        Some(idx)
+                    }
+                    (None, None) => {
+                        picker.pick(&mut rng, hour, persona.as_deref(), &ledger, Instant::now())
+                    }
+                };
+                let Some(task_idx) = picked else {
+                    // Every task is weighted 0 or outside its schedule
+                    drop(lease);
+                    triggers.wait(Duration::from_secs(1)).await;
+                    continue;
+                };
+                let task = &tasks[task_idx];
+                let timeout = picker.timeout(task_idx);
+                let timeout_ms = Some(timeout.as_millis() as u64);
+
+                let persona_name = persona
+                    .as_ref()
+                    .map_or_else(|| "-".to_string(), |p| p.name.clone());
+                let ctx = TaskContext::new(client.clone(), config.clone(), Some(db.clone()))
+                    .with_persona(persona)
+                    .with_budget(budget.clone())
+                    .with_verifier(verifier.clone())
+                    .with_session_keys(session_keys.clone())
+                    .with_helpers(helpers.clone())
+                    .with_transfers(Some(transfers.clone()))
+                    .with_capabilities(Some(capabilities))
+                    .with_chain_head(chain_head.clone())
+                    .with_deadline(timeout);
+
+                let proxy_url_for_span = client
+                    .proxy_config
+                    .as_ref()
+                    .map(|p| p.url.as_str())
+                    .unwrap_or("direct");
+
+                let span = tracing::info_span!(
+                    "task",
+                    worker_id = worker_id,
+                    wallet = ?client.address(),
+                    task = task.name(),
+                    proxy = proxy_url_for_span,
+                    persona = %persona_name,
+                    lease_wait_ms = lease_started.elapsed().as_millis() as u64,
+                    timeout_ms = timeout.as_millis() as u64,
+                );
+                let audit_trace = (config.audit.sample_rate > 0.0
+                    && rng.gen_bool(config.audit.sample_rate))
+                .then(|| AuditTrace::new(config.audit.max_param_bytes));
+                let start = std::time::Instant::now();
+
+                let (outcome, diagnostics) = audit::scope(
+                    audit_trace.clone(),
+                    watchdog::watch(
+                        task.name(),
+                        config.watchdog.soft_deadline(timeout),
+                        tokio::time::timeout(timeout, task.run(&ctx)),
+                        |elapsed| Diagnostics::capture(&client, elapsed),
+                    )
+                    .instrument(span.clone()),
+                )
+                .await;
+                let diagnostics = diagnostics.map(|d| d.to_json());
+
+                if let Some(trace) = &audit_trace {
+                    let record = trace.finish(task.name(), worker_id, &client, &outcome);
+                    if let Err(e) = db.log_audit(&record).await {
+                        warn!("Failed to store audit of {}: {}", task.name(), e);
+                    }
+                }
+
+                let succeeded = matches!(&outcome, Ok(Ok(result)) if result.success);
+                match outcome {
+                    Ok(Ok(result)) => {
+                        let _enter = span.enter();
+                        let duration = start.elapsed();
+
+                        if let Some(proxy_idx) = client.proxy_index {
+                            client_pool.record_proxy_success(proxy_idx).await;
+                        }
+
+                        if let Some(hash) = result.tx_hash.as_deref().and_then(|h| h.parse().ok()) {
+                            if let Some(tracker) = &gas_tracker {
+                                tracker.spawn(task.name(), &client, hash);
+                            }
+                            if let Some(archive) = &log_archive {
+                                archive.spawn(task.name(), &client, hash);
+                            }
+                        }
+
+                        // Async logging: queue result without blocking
+                        if let Some(database) = &ctx.db {
+                            let queued_result = QueuedTaskResult {
+                                chain_id: Some(config.chain_id),
+                                worker_id: format!("{:03}", worker_id),
+                                wallet_address: client.address().to_string(),
+                                task_name: task.name().to_string(),
+                                success: result.success,
+                                message: result.message.clone(),
+                                duration_ms: duration.as_millis() as u64,
+                                timestamp: chrono::Utc::now().timestamp(),
+                                metadata: TaskMetadata {
+                                    timeout_ms,
+                                    host_partition: host_partition.clone(),
+                                    diagnostics: diagnostics.clone(),
+                                    ..TaskMetadata::from(&result)
+                                },
+                            };
+
+                            // Non-blocking send (returns immediately)
+                            if let Err(e) = database.queue_task_result(queued_result) {
+                                // Log at warn level for visibility - this shouldn't happen often
+                                warn!("Failed to queue task result for DB logging: {}", e);
+                            }
+                        }
+
+                        record_task_metrics(
+                            config.chain_id,
+                            task.name(),
+                            wallet_idx,
+                            client.proxy_index,
+                            duration,
+                            result.success,
+                        );
+                        notify_sinks(
+                            &sinks,
+                            worker_id,
+                            client.address(),
+                            task.name(),
+                            duration,
+                            || result.clone(),
+                        );
+
+                        record_wallet_health(
+                            &client_pool,
+                            wallet_idx,
+                            client.address(),
+                            (!result.success).then_some(result.message.as_str()),
+                        );
+
+                        if result_log().record(task.name(), result.success, duration) {
+                            let status_msg = result_summary(&result);
+
+                            info!(
+                                target: "task_result",
+                                "[WK:{:03}][WL:{:03}][P:{}] {} [{}] {} t:{:.1}s",
+                                worker_id,
+                                wallet_idx,
+                                client.proxy_index.map(|i| format!("{:03}", i)).unwrap_or_else(|| "DIR".to_string()),
+                                if result.success { "SUCCESS" } else { "FAILED " },
+                                task.name(),
+                                status_msg,
+                                duration.as_secs_f32()
+                            );
+                        }
+                    }
+                    Ok(Err(e)) => {
+                        let _enter = span.enter();
+                        let duration = start.elapsed();
+                        let error_msg = revert::describe(e.as_ref());
+
+                        // === PROXY BANNING LOGIC ===
+                        // Detect connection/tunnel errors that indicate a bad proxy
+                        if error_msg.contains("tunnel error")
+                            || error_msg.contains("Connect")
+                            || error_msg.contains("connection closed")
+                            || error_msg.contains("error sending request")
+                        {
+                            if let Some(proxy_idx) = client.proxy_index {
+                                tracing::warn!(
+                                    "[WK:{:03}][P:{:03}] 🚫 Banning unhealthy proxy due to error: {:.100}...",
+                                    worker_id,
+                                    proxy_idx,
+                                    error_msg
+                                );
+                                client_pool.ban_proxy(proxy_idx).await;
+                            }
+                        }
+
+                        let mut recovered = false;
+
+                        // Auto-refresh nonce cache on "nonce too low" errors
+                        if error_msg.contains("nonce too low") {
+                            tracing::debug!(
+                                "[WK:{:03}] Detected stale nonce, refreshing from blockchain...",
+                                worker_id
+                            );
+
+                            // Force refresh nonce from blockchain
+                            if let Some(robust_manager) = &ctx.client.robust_nonce_manager {
+                                let mut handled = false;
+                                // Parse error: "nonce too low: next nonce <next>, tx nonce <tx>"
+                                if let (Some(next_pos), Some(tx_pos)) =
+                                    (error_msg.find("next nonce "), error_msg.find(", tx nonce "))
+                                {
+                                    let next_str = &error_msg[next_pos + 11..tx_pos];
+                                    let tx_str_check = &error_msg[tx_pos + 11..];
+                                    // tx_str might have trailing chars, take until non-digit
+                                    let tx_str = tx_str_check
+                                        .chars()
+                                        .take_while(|c| c.is_ascii_digit())
+                                        .collect::<String>();
+
+                                    if let (Ok(next_nonce), Ok(tx_nonce)) = (
+                                        next_str.trim().parse::<u64>(),
+                                        tx_str.trim().parse::<u64>(),
+                                    ) {
+                                        robust_manager
+                                            .handle_nonce_error(ctx.address(), tx_nonce, next_nonce)
+                                            .await;
+                                        tracing::info!(
+                                            "[WK:{:03}] Robust recovery: failed {} -> actual {}",
+                                            worker_id,
+                                            tx_nonce,
+                                            next_nonce
+                                        );
+                                        handled = true;
+                                        recovered = true;
+                                    }
+                                }
+
+                                if !handled {
+                                    // Fallback: use get_pending_nonce which handles "pending" tag correctly manual
+                                    match ctx.client.get_pending_nonce(&ctx.config.rpc_url).await {
+                                        Ok(fresh_nonce) => {
+                                            robust_manager
+                                                .initialize(ctx.address(), fresh_nonce)
+                                                .await;
+                                            tracing::debug!(
+                                                "[WK:{:03}] RobustNonceManager re-initialized to {}",
+                                                worker_id,
+                                                fresh_nonce
+                                            );
+                                            recovered = true;
+                                        }
+                                        Err(e) => tracing::warn!(
+                                            "[WK:{:03}] Failed to refresh robust nonce: {:?}",
+                                            worker_id,
+                                            e
+                                        ),
+                                    }
+                                }
+                            }
+                            // Legacy Manager
+                            else if let Some(manager) = &ctx.client.nonce_manager {
+                                match ctx.client.get_pending_nonce(&ctx.config.rpc_url).await {
+                                    Ok(fresh_nonce) => {
+                                        manager.set(ctx.address(), fresh_nonce).await;
+                                        tracing::debug!(
+                                            "[WK:{:03}] Nonce cache refreshed to {}",
+                                            worker_id,
+                                            fresh_nonce
+                                        );
+                                        recovered = true;
+                                    }
+                                    Err(refresh_err) => {
+                                        tracing::warn!(
+                                            "[WK:{:03}] Failed to refresh nonce: {:?}",
+                                            worker_id,
+                                            refresh_err
+                                        );
+                                    }
+                                }
+                            }
+                        }
+
+                        // Async logging for error
+                        if let Some(database) = &ctx.db {
+                            let queued_result = QueuedTaskResult {
+                                chain_id: Some(config.chain_id),
+                                worker_id: format!("{:03}", worker_id),
+                                wallet_address: client.address().to_string(),
+                                task_name: task.name().to_string(),
+                                success: false,
+                                message: error_msg.clone(),
+                                duration_ms: duration.as_millis() as u64,
+                                timestamp: chrono::Utc::now().timestamp(),
+                                metadata: TaskMetadata {
+                                    timeout_ms,
+                                    host_partition: host_partition.clone(),
+                                    diagnostics: diagnostics.clone(),
+                                    ..Default::default()
+                                },
+                            };
+
+                            if let Err(e) = database.queue_task_result(queued_result) {
+                                warn!("Failed to queue error result for DB logging: {}", e);
+                            }
+                        }
+                        record_task_metrics(
+                            config.chain_id,
+                            task.name(),
+                            wallet_idx,
+                            client.proxy_index,
+                            duration,
+                            false,
+                        );
+                        notify_sinks(
+                            &sinks,
+                            worker_id,
+                            client.address(),
+                            task.name(),
+                            duration,
+                            || TaskResult {
+                                message: error_msg.clone(),
+                                ..Default::default()
+                            },
+                        );
+
+                        // A recovered nonce mismatch says nothing about the wallet
+                        if !recovered {
+                            record_wallet_health(
+                                &client_pool,
+                                wallet_idx,
+                                client.address(),
+                                Some(&error_msg),
+                            );
+                        }
+
+                        // Failures are always logged, only counted here
+                        result_log().record(task.name(), false, duration);
+                        if recovered {
+                            // Log as INFO/WARN - it's a recovered error, normal operation
+                            info!(target: "task_result", "[WK:{:03}][WL:{:03}][P:{}] RETRY [{}] Nonce mismatch (recovered) t:{:.1}s",
+                                worker_id,
+                                wallet_idx,
+                                client.proxy_index.map(|i| format!("{:03}", i)).unwrap_or_else(|| "DIR".to_string()),
+                                task.name(),
+                                duration.as_secs_f32()
+                            );
+                        } else {
+                            error!(target: "task_result", "[WK:{:03}][WL:{:03}][P:{}] ERROR [{}] Task error: {} t:{:.1}s",
+                                worker_id,
+                                wallet_idx,
+                                client.proxy_index.map(|i| format!("{:03}", i)).unwrap_or_else(|| "DIR".to_string()),
+                                task.name(),
+                                error_msg,
+                                duration.as_secs_f32()
+                            );
+                        }
+                    }
+                    Err(_) => {
+                        let _enter = span.enter();
+                        let duration = start.elapsed();
+                        let error_msg = format!("Task timed out after {}s", timeout.as_secs());
+
+                        // Async logging for timeout
+                        if let Some(database) = &ctx.db {
+                            let queued_result = QueuedTaskResult {
+                                chain_id: Some(config.chain_id),
+                                worker_id: format!("{:03}", worker_id),
+                                wallet_address: client.address().to_string(),
+                                task_name: task.name().to_string(),
+                                success: false,
+                                message: error_msg.clone(),
+                                duration_ms: duration.as_millis() as u64,
+                                timestamp: chrono::Utc::now().timestamp(),
+                                metadata: TaskMetadata {
+                                    timeout_ms,
+                                    host_partition: host_partition.clone(),
+                                    diagnostics: diagnostics.clone(),
+                                    ..Default::default()
+                                },
+                            };
+
+                            if let Err(e) = database.queue_task_result(queued_result) {
+                                warn!("Failed to queue timeout result for DB logging: {}", e);
+                            }
+                        }
+                        record_task_metrics(
+                            config.chain_id,
+                            task.name(),
+                            wallet_idx,
+                            client.proxy_index,
+                            duration,
+                            false,
+                        );
+                        notify_sinks(
+                            &sinks,
+                            worker_id,
+                            client.address(),
+                            task.name(),
+                            duration,
+                            || TaskResult {
+                                message: error_msg.clone(),
+                                ..Default::default()
+                            },
+                        );
+                        record_wallet_health(
+                            &client_pool,
+                            wallet_idx,
+                            client.address(),
+                            Some(&error_msg),
+                        );
+                        result_log().record(task.name(), false, duration);
+                        error!(target: "task_result", "[WK:{:03}][WL:{:03}][P:{}] ERROR [{}] {} t:{:.1}s",
+                            worker_id,
+                            wallet_idx,
+                            client.proxy_index.map(|i| format!("{:03}", i)).unwrap_or_else(|| "DIR".to_string()),
+                            task.name(),
+                            error_msg,
+                            duration.as_secs_f32()
+                        );
+                    }
+                }
+                task_slo().record(task.name(), succeeded, Instant::now());
+
+                // Explicitly release the lease with cooldown
+                if let Some(partition) = &mut partition {
+                    partition.rest(wallet_idx, std::time::Instant::now());
+                }
+                lease.release().await;
+
+                tokio::time::sleep(pacing.next_interval(&mut rng)).await;
+            }
+        };
+
+        // The client pool reads the worker id to pick its bound proxy
+        let handle = tokio::spawn(worker_scope(worker_id, worker));
+        handles.push(handle);
+    }
+
+    // Spawn database and client pool monitoring task
+    let db_monitor = db_manager.clone();
+    let pool_monitor = client_pool.clone();
+    let gas_monitor = gas_tracker.clone();
+    let archive_monitor = log_archive.clone();
+    let mempool_monitor = pending_monitor.clone();
+    let monitor_handle = tokio::spawn(async move {
+        let mut interval = tokio::time::interval(Duration::from_secs(30));
+        let mut last_timeouts = 0;
+        loop {
+            interval.tick().await;
+            let metrics = db_monitor.get_metrics();
+            let (queued, dropped) = db_monitor.get_async_metrics();
+            info!(
+                "DB Metrics: {} queries, {} errors ({:.1}%), avg {:.1}ms, {} queued, {} dropped",
+                metrics.total_queries,
+                metrics.total_errors,
+                metrics.error_rate(),
+                metrics.avg_query_ms,
+                queued,
+                dropped
+            );
+            let rpc_cache = crate::rpc_cache::cache();
+            let totals = rpc_cache.totals();
+            if totals.hits + totals.misses > 0 {
+                info!(
+                    "RPC cache: {} hits, {} misses ({:.1}% hit rate), {} entries",
+                    totals.hits,
+                    totals.misses,
+                    totals.hit_rate(),
+                    rpc_cache.len()
+                );
+            }
+            let limits = crate::rate_limit::limiter().stats();
+            if limits.limited > 0 {
+                info!(
+                    "RPC rate limits: {} limited, {} retried, {} given up",
+                    limits.limited, limits.retried, limits.given_up
+                );
+            }
+            let faults = crate::chaos::chaos().totals();
+            if faults.injected > 0 {
+                info!(
+                    "Chaos: {} faults injected, {} recovered",
+                    faults.injected, faults.recovered
+                );
+            }
+            if let Some(tracker) = &gas_monitor {
+                let cost_wei = tracker.cost_wei();
+                if cost_wei > 0.0 {
+                    match crate::price::feed().quote() {
+                        Some(quote) => info!(
+                            "Gas spent this run: {:.6} ({})",
+                            cost_wei / 1e18,
+                            crate::price::format_usd(quote.gas_usd(cost_wei))
+                        ),
+                        None => info!("Gas spent this run: {:.6}", cost_wei / 1e18),
+                    }
+                }
+            }
+            if let Some(archive) = &archive_monitor {
+                let skipped = archive.skipped();
+                if skipped > 0 {
+                    info!("Log archive: {} transactions skipped", skipped);
+                }
+            }
+            if let Some(monitor) = &mempool_monitor {
+                let backed_up = monitor.backed_up();
+                if backed_up > 0 {
+                    info!(
+                        "Mempool: {} wallets backed up, {} leases skipped",
+                        backed_up,
+                        monitor.skipped()
+                    );
+                }
+            }
+            let returned = pool_monitor.recheck_quarantined().await;
+            if returned > 0 {
+                info!(target: "task_result", "{} wallets back from quarantine", returned);
+            }
+            let pool = pool_monitor.stats().await;
+            info!(
+                "Pool: {}/{} permits free, {} wallets locked, {} available, {} quarantined, wait avg {}ms max {}ms, hold avg {}ms max {}ms, {} saturated",
+                pool.permits_available,
+                pool.permits_total,
+                pool.wallets_locked,
+                pool.wallets_available,
+                pool.wallets_quarantined,
+                pool.avg_wait.as_millis(),
+                pool.max_wait.as_millis(),
+                pool.avg_hold.as_millis(),
+                pool.max_hold.as_millis(),
+                pool.saturated
+            );
+            if pool.timeouts > last_timeouts {
+                warn!(
+                    "Client pool backpressure: {} lease waits timed out in the last 30s ({}/{} permits free, {} wallets available)",
+                    pool.timeouts - last_timeouts,
+                    pool.permits_available,
+                    pool.permits_total,
+                    pool.wallets_available
+                );
+            }
+            last_timeouts = pool.timeouts;
+        }
+    });
+
+    // Per-task SUMMARY lines every [results] summary_secs
+    let summary_handle = tokio::spawn(result_log().run());
+
+    // Warnings for methods past their [rpc_stats] limits
+    let rpc_alert_handle = tokio::spawn(crate::rpc_stats::rpc_stats().run());
+
+    // Periodically add per-proxy traffic counters onto proxy_stats
+    let usage_db = db_manager.clone();
+    let usage_handle = tokio::spawn(async move {
+        let mut interval = tokio::time::interval(Duration::from_secs(30));
+        loop {
+            interval.tick().await;
+            if let Err(e) = crate::proxy_usage::tracker().flush(&usage_db).await {
+                warn!("Failed to flush proxy usage: {:#}", e);
+            }
+        }
+    });
+
+    let abort_handles: Vec<_> = handles.iter().map(|h| h.abort_handle()).collect();
+    tokio::select! {
+        _ = join_all(handles) => {}
+        _ = shutdown => {
+            info!(target: "task_result", "Stopping {} workers...", abort_handles.len());
+            for handle in &abort_handles {
+                handle.abort();
+            }
+        }
+    }
+
+    // Cancel monitor tasks
+    monitor_handle.abort();
+    usage_handle.abort();
+    summary_handle.abort();
+    rpc_alert_handle.abort();
+    picker_handle.abort();
+    if let Some(handle) = remote_handle {
+        handle.abort();
+    }
+    if let Some(handle) = price_handle {
+        handle.abort();
+    }
+    if let Some(handle) = mempool_handle {
+        handle.abort();
+    }
+    if let Some(handle) = chain_head_handle {
+        handle.abort();
+    }
+    if let Some(handle) = trigger_handle {
+        handle.abort();
+    }
+    if triggers.missed() > 0 {
+        info!(
+            "{} block triggers were replaced before a worker was free",
+            triggers.missed()
+        );
+    }
+    for (task, (runs, forced)) in tasks.iter().zip(ledger.counts()) {
+        if forced > 0 {
+            info!(
+                "{}: {} of {} runs picked by starvation protection",
+                task.name(),
+                forced,
+                runs
+            );
+        }
+    }
+    for paused in task_slo().paused() {
+        info!(
+            "{} paused since {} at {:.1}% success",
+            paused.task,
+            paused.since.format("%H:%M:%S"),
+            paused.success_rate * 100.0
+        );
+    }
+    result_log().log_summaries();
+    if let Err(e) = crate::proxy_usage::tracker().flush(&db_manager).await {
+        warn!("Failed to flush proxy usage: {:#}", e);
+    }
+    for (fault, counts) in crate::chaos::chaos().stats() {
+        if counts.injected > 0 {
+            info!(
+                "Chaos {}: {} injected, {} recovered",
+                fault, counts.injected, counts.recovered
+            );
+        }
+    }
+    for (method, stats) in crate::rpc_cache::cache().stats() {
+        info!(
+            "RPC cache {}: {} hits, {} misses ({:.1}%)",
+            method,
+            stats.hits,
+            stats.misses,
+            stats.hit_rate()
+        );
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    #[test]
+    fn test_channel_sink_receives_outcomes() {
+        let (tx, mut rx) = tokio::sync::mpsc::unbounded_channel();
+        let sinks: Vec<Arc<dyn ResultSink>> = vec![Arc::new(tx)];
+        notify_sinks(
+            &sinks,
+            3,
+            Address::repeat_byte(1),
+            "03_send_token",
+            Duration::from_millis(250),
+            || TaskResult {
+                message: "Task timed out".to_string(),
+                ..Default::default()
+            },
+        );
+
+        let outcome = rx.try_recv().unwrap();
+        assert_eq!(outcome.worker_id, 3);
+        assert_eq!(outcome.task, "03_send_token");
+        assert!(!outcome.result.success);
+        assert_eq!(outcome.result.message, "Task timed out");
+
+        // Without sinks the outcome is never built
+        notify_sinks(
+            &[],
+            0,
+            Address::ZERO,
+            "03_send_token",
+            Duration::ZERO,
+            || unreachable!(),
+        );
+    }
+
+    #[test]
+    fn test_picker_applies_weights_and_schedule() {
+        let tasks = default_tasks();
+        let mut settings = TaskSettings::default();
+        for task in &tasks {
+            settings.weights.insert(task.name().to_string(), 0);
+        }
+        settings.weights.insert("03_send_token".to_string(), 1);
+        settings
+            .schedule
+            .insert("03_send_token".to_string(), "08-20".parse().unwrap());
+
+        let picker = TaskPicker::new(&tasks, &settings, Duration::from_secs(20));
+        let ledger = TaskLedger::new(tasks.len(), Instant::now());
+        let now = Instant::now();
+        let mut rng = StdRng::seed_from_u64(7);
+        for _ in 0..20 {
+            let idx = picker.pick(&mut rng, 12, None, &ledger, now).unwrap();
+            assert_eq!(tasks[idx].name(), "03_send_token");
+        }
+        assert_eq!(picker.pick(&mut rng, 21, None, &ledger, now), None);
+
+        // A persona can switch a task off entirely
+        let persona = Persona {
+            name: "collector".to_string(),
+            weights: [("03_send_token".to_string(), 0.0)].into(),
+            amount_scale: 1.0,
+            hours: None,
+        };
+        assert_eq!(
+            picker.pick(&mut rng, 12, Some(&persona), &ledger, now),
+            None
+        );
+    }
+
+    #[test]
+    fn test_picker_runs_starved_tasks() {
+        let tasks = default_tasks();
+        let idx = |name: &str| tasks.iter().position(|t| t.name() == name).unwrap();
+        let mut settings = TaskSettings::default();
+        for task in &tasks {
+            settings.weights.insert(task.name().to_string(), 0);
+        }
+        settings
+            .weights
+            .insert("03_send_token".to_string(), 1_000_000);
+        settings.weights.insert("05_swap_stable".to_string(), 1);
+        settings
+            .schedule
+            .insert("06_add_liquidity".to_string(), "08-20".parse().unwrap());
+        settings.weights.insert("06_add_liquidity".to_string(), 1);
+        settings.starvation_secs = Some(60);
+
+        let picker = TaskPicker::new(&tasks, &settings, Duration::from_secs(20));
+        let start = Instant::now();
+        let ledger = TaskLedger::new(tasks.len(), start);
+        let mut rng = StdRng::seed_from_u64(7);
+
+        // Within the period the weights decide
+        let now = start + Duration::from_secs(30);
+        for _ in 0..10 {
+            let picked = picker.pick(&mut rng, 21, None, &ledger, now).unwrap();
+            assert_eq!(picked, idx("03_send_token"));
+        }
+
+        // Past it the rare task runs once, then the weights take over again;
+        // a task outside its window or weighted 0 is never forced
+        let now = start + Duration::from_secs(61);
+        let picks: Vec<_> = (0..3)
+            .map(|_| picker.pick(&mut rng, 21, None, &ledger, now).unwrap())
+            .collect();
+        assert_eq!(
+            picks,
+            [
+                idx("05_swap_stable"),
+                idx("03_send_token"),
+                idx("03_send_token")
+            ]
+        );
+
+        let counts = ledger.counts();
+        assert_eq!(counts[idx("05_swap_stable")], (1, 1));
+        assert_eq!(counts[idx("03_send_token")], (12, 0));
+        assert_eq!(counts[idx("06_add_liquidity")], (0, 0));
+
+        // Without starvation_secs nothing is forced
+        settings.starvation_secs = None;
+        let picker = TaskPicker::new(&tasks, &settings, Duration::from_secs(20));
+        let now = start + Duration::from_secs(600);
+        assert_eq!(
+            picker.pick(&mut rng, 21, None, &ledger, now),
+            Some(idx("03_send_token"))
+        );
+    }
+
+    #[test]
+    fn test_block_triggers_run_every_n_blocks() {
+        let tasks = default_tasks();
+        let idx = |name: &str| tasks.iter().position(|t| t.name() == name).unwrap();
+        let mut settings = TaskSettings::default();
+        settings.every_blocks.insert("03_send_token".to_string(), 1);
+        settings
+            .every_blocks
+            .insert("05_swap_stable".to_string(), 3);
+        settings
+            .schedule
+            .insert("05_swap_stable".to_string(), "08-20".parse().unwrap());
+        let picker = TaskPicker::new(&tasks, &settings, Duration::from_secs(20));
+
+        // Block-triggered tasks leave the weighted pick
+        assert_eq!(picker.weights[idx("03_send_token")], 0);
+        assert!(picker.has_block_tasks());
+
+        let triggers = BlockTriggers::default();
+        assert_eq!(triggers.on_block(&picker, 10, 12), 2);
+        assert_eq!(triggers.take(), Some((idx("03_send_token"), 10)));
+        assert_eq!(triggers.take(), Some((idx("05_swap_stable"), 10)));
+        assert_eq!(triggers.take(), None);
+
+        assert_eq!(triggers.on_block(&picker, 11, 12), 1);
+        assert_eq!(triggers.on_block(&picker, 12, 12), 1);
+        // Not taken in time: the newer trigger replaces the older one
+        assert_eq!(triggers.take(), Some((idx("03_send_token"), 12)));
+        assert_eq!(triggers.missed(), 1);
+
+        // A skipped block still counts towards the interval, outside the window none do
+        assert_eq!(triggers.on_block(&picker, 14, 21), 1);
+        assert_eq!(triggers.take(), Some((idx("03_send_token"), 14)));
+        assert_eq!(triggers.on_block(&picker, 15, 12), 2);
+        assert_eq!(triggers.take(), Some((idx("03_send_token"), 15)));
+        assert_eq!(triggers.take(), Some((idx("05_swap_stable"), 15)));
+    }
+
+    #[test]
+    fn test_picker_resolves_task_timeouts() {
+        use crate::tasks::DEPLOY_TIMEOUT;
+
+        let tasks = default_tasks();
+        let idx = |name: &str| tasks.iter().position(|t| t.name() == name).unwrap();
+        let mut settings = TaskSettings::default();
+
+        // Deployments get their longer default, other tasks the global one
+        let picker = TaskPicker::new(&tasks, &settings, Duration::from_secs(20));
+        assert_eq!(picker.timeout(idx("01_deploy_contract")), DEPLOY_TIMEOUT);
+        assert_eq!(
+            picker.timeout(idx("03_send_token")),
+            Duration::from_secs(20)
+        );
+
+        // A task default never lowers a longer global timeout
+        let picker = TaskPicker::new(&tasks, &settings, Duration::from_secs(300));
+        assert_eq!(
+            picker.timeout(idx("01_deploy_contract")),
+            Duration::from_secs(300)
+        );
+
+        // Configured timeouts replace both
+        settings
+            .timeouts
+            .insert("01_deploy_contract".to_string(), 10);
+        settings.timeouts.insert("03_send_token".to_string(), 5);
+        let picker = TaskPicker::new(&tasks, &settings, Duration::from_secs(300));
+        assert_eq!(
+            picker.timeout(idx("01_deploy_contract")),
+            Duration::from_secs(10)
+        );
+        assert_eq!(picker.timeout(idx("03_send_token")), Duration::from_secs(5));
+    }
+
+    #[test]
+    fn test_result_summary_appends_receipt_fields() {
+        let plain = TaskResult {
+            success: true,
+            message: "Claimed".to_string(),
+            ..Default::default()
+        };
+        assert_eq!(result_summary(&plain), "Claimed");
+
+        let deployed = TaskResult {
+            success: true,
+            message: "Deployed".to_string(),
+            tx_hash: Some("0xabc".to_string()),
+            ..Default::default()
+        }
+        .with_receipt(21000, Some(42))
+        .with_contract("0xdef");
+        assert_eq!(
+            result_summary(&deployed),
+            "TxHash: 0xabc contract:0xdef blk:42 gas:21000"
+        );
+    }
+}
@@ -0,0 +1,611 @@
+//! Runtime - The `tempo-spammer` startup and run lifecycle as a library API
+//!
+//! [`SpammerRuntime::build`] does everything the binary does before the
+//! first task: it checks the node's chain id and
+//! [`capabilities`](crate::capabilities), loads `proxies.txt` beside the
+//! config file and scans it, assembles a [`Spammer`] through its builder
+//! and starts the Telegram bot and the [`control`](crate::control) socket.
+//! Prompts stay with the caller:
+//! the wallet password goes into [`UnlockedWallets::unlock`], the worker
+//! count into [`RuntimeOptions`].
+//!
+//! A built runtime is driven from the outside:
+//!
+//! - [`start`](SpammerRuntime::start) runs the weighted task loop of
+//!   [`runner`](crate::runner) in the background
+//! - [`pause`](SpammerRuntime::pause) and [`resume`](SpammerRuntime::resume)
+//!   hold the workers between tasks, leases are returned as usual
+//! - [`stop`](SpammerRuntime::stop) ends the run and gives queued results a
+//!   moment to reach the database
+//! - [`stats`](SpammerRuntime::stats) reports task counts and the pool's
+//!   [`PoolStats`] at any point
+//!
+//! [`Spammer`] is the lighter choice for a run assembled from private keys
+//! and values in code; the runtime reads wallets and proxies from disk the
+//! way the binary does.
+//!
+//! ```rust,no_run
+//! use std::path::PathBuf;
+//! use tempo_spammer::config::TempoSpammerConfig;
+//! use tempo_spammer::runtime::{RuntimeOptions, SpammerRuntime, UnlockedWallets};
+//! use zeroize::Zeroizing;
+//!
+//! # async fn example() -> anyhow::Result<()> {
+//! let config_path = PathBuf::from("config/config.toml");
+//! let config = TempoSpammerConfig::from_path(&config_path)?;
+//! let wallets = UnlockedWallets::unlock(
+//!     core_logic::WalletManager::new()?,
+//!     Some(Zeroizing::new("password".to_string())),
+//! )
+//! .await?;
+//!
+//! let mut runtime = SpammerRuntime::build(
+//!     config,
+//!     wallets,
+//!     RuntimeOptions {
+//!         config_path,
+//!         workers: Some(4),
+//!         ..Default::default()
+//!     },
+//! )
+//! .await?;
+//! runtime.start()?;
+//! tokio::signal::ctrl_c().await?;
+//! runtime.stop().await;
+//! println!("{} tasks run", runtime.stats().await.tasks());
+//! # Ok(())
+//! # }
+//! ```
+
+use crate::ClientPool;
+use crate::bot::notification::spawn_notification_service;
+use crate::client_pool::PoolStats;
+use crate::config::TempoSpammerConfig;
+use crate::control::{Lifecycle, LogControl};
+use crate::proxy_health::{self, ProxyBanlist};
+use crate::runner::{ResultSink, TaskOutcome};
+use crate::spammer::Spammer;
+use crate::tasks::{TempoTask, load_proxies_with_rotation};
+use alloy::signers::Signer;
+use alloy::signers::local::PrivateKeySigner;
+use anyhow::{Context, Result, bail};
+use core_logic::database::DatabaseManager;
+use core_logic::{LogLevels, WalletManager};
+use std::path::{Path, PathBuf};
+use std::sync::Arc;
+use std::sync::atomic::{AtomicU64, Ordering};
+use std::time::{Duration, Instant};
+use tokio::sync::{Notify, oneshot, watch};
+use tokio::task::JoinHandle;
+use tracing::{error, info};
+use zeroize::Zeroizing;
+
+/// Healthy proxies after which startup continues while the scan finishes
+const PROXY_SCAN_MIN_HEALTHY: usize = 50;
+
+/// Wallets whose password was checked against the first wallet file
+pub struct UnlockedWallets {
+    pub(crate) manager: WalletManager,
+    pub(crate) password: Option<Zeroizing<String>>,
+}
+
+impl UnlockedWallets {
+    /// Checks `password` by decrypting the first wallet of `manager`
+    ///
+    /// # Errors
+    ///
+    /// Fails when there are no wallets or the password does not decrypt the
+    /// first one.
+    pub async fn unlock(
+        manager: WalletManager,
+        password: Option<Zeroizing<String>>,
+    ) -> Result<Self> {
+        if manager.count() == 0 {
+            bail!("No wallets found in wallet-json/ or pv.txt");
+        }
+        manager
+            .load_wallet(0, password.as_deref().map(String::as_str))
+            .await
+            .context("Failed to decrypt the first wallet")?;
+        Ok(Self { manager, password })
+    }
+
+    /// Raw private keys, which need no password
+    pub fn from_private_keys<I, S>(keys: I) -> Self
+    where
+        I: IntoIterator<Item = S>,
+        S: Into<String>,
+    {
+        Self {
+            manager: WalletManager::from_private_keys(keys),
+            password: None,
+        }
+    }
+
+    pub fn count(&self) -> usize {
+        self.manager.count()
+    }
+
+    /// Signer of wallet `index`, refusing to sign for any chain but `chain_id`
+    ///
+    /// The decrypted wallet is zeroized on return; the key lives on only
+    /// inside the signer.
+    pub async fn signer(&self, index: usize, chain_id: u64) -> Result<PrivateKeySigner> {
+        let wallet = self
+            .manager
+            .load_wallet(index, self.password.as_deref().map(String::as_str))
+            .await
+            .with_context(|| format!("Failed to decrypt wallet {}", index))?;
+        let signer: PrivateKeySigner = wallet
+            .evm_private_key
+            .parse()
+            .with_context(|| format!("Failed to parse private key of wallet {}", index))?;
+        Ok(signer.with_chain_id(Some(chain_id)))
+    }
+}
+
+/// Startup choices the binary takes from its flags and prompts
+#[derive(Default)]
+pub struct RuntimeOptions {
+    /// Config file the config came from; `proxies.txt` is looked up beside it
+    pub config_path: PathBuf,
+    /// Number of workers, `worker_count` from the config unless set
+    pub workers: Option<u64>,
+    /// Run without proxies even if `proxies.txt` exists
+    pub no_proxy: bool,
+    /// Start the Telegram status bot
+    pub notifications: bool,
+    /// Reloadable log filters for the control socket's `log set`, `None`
+    /// when the logger cannot be reloaded
+    pub log_levels: Option<LogLevels>,
+    /// Database to log to, opened at `[database]` with async logging unless set
+    pub database: Option<Arc<DatabaseManager>>,
+}
+
+/// Where a [`SpammerRuntime`] is in its lifecycle
+#[derive(Debug, Clone, Copy, PartialEq, Eq)]
+pub enum RuntimeState {
+    /// Built, [`start`](SpammerRuntime::start) not called yet
+    Ready,
+    Running,
+    /// Started, workers held by [`pause`](SpammerRuntime::pause)
+    Paused,
+    /// Stopped, or every worker exited
+    Stopped,
+}
+
+impl std::fmt::Display for RuntimeState {
+    fn fmt(&self, f: &mut std::fmt::Formatter<'_>) -> std::fmt::Result {
+        f.write_str(match self {
+            RuntimeState::Ready => "ready",
+            RuntimeState::Running => "running",
+            RuntimeState::Paused => "paused",
+            RuntimeState::Stopped => "stopped",
+        })
+    }
+}
+
+/// Snapshot returned by [`SpammerRuntime::stats`]
+#[derive(Debug, Clone, PartialEq)]
+pub struct RuntimeStats {
+    pub state: RuntimeState,
+    pub workers: u64,
+    /// Time since [`start`](SpammerRuntime::start), zero before
+    pub uptime: Duration,
+    /// Finished tasks that succeeded
+    pub succeeded: u64,
+    /// Finished tasks that failed, errored or timed out
+    pub failed: u64,
+    pub pool: PoolStats,
+}
+
+impl RuntimeStats {
+    /// Finished tasks of this run
+    pub fn tasks(&self) -> u64 {
+        self.succeeded + self.failed
+    }
+
+    /// Share of finished tasks that succeeded, in percent
+    pub fn success_rate(&self) -> f64 {
+        if self.tasks() == 0 {
+            return 0.0;
+        }
+        self.succeeded as f64 / self.tasks() as f64 * 100.0
+    }
+}
+
+/// Counts finished tasks for [`RuntimeStats`]
+#[derive(Debug, Default)]
+struct TaskCounter {
+    succeeded: AtomicU64,
+    failed: AtomicU64,
+}
+
+impl TaskCounter {
+    /// The control socket's `status` reply, with uptime counted from now
+    fn status_reply(
+        self: Arc<Self>,
+        paused: watch::Receiver<bool>,
+        workers: u64,
+    ) -> impl Fn() -> String + Send + Sync + 'static {
+        let since = Instant::now();
+        move || {
+            let succeeded = self.succeeded.load(Ordering::Relaxed);
+            let tasks = succeeded + self.failed.load(Ordering::Relaxed);
+            let rate = if tasks == 0 {
+                0.0
+            } else {
+                succeeded as f64 / tasks as f64 * 100.0
+            };
+            let state = if *paused.borrow() {
+                RuntimeState::Paused
+            } else {
+                RuntimeState::Running
+            };
+            format!(
+                "{} (pid {}): {} workers, up {}s, {} tasks, {:.1}% successful",
+                state,
+                std::process::id(),
+                workers,
+                since.elapsed().as_secs(),
+                tasks,
+                rate
+            )
+        }
+    }
+}
+
+impl ResultSink for TaskCounter {
+    fn record(&self, outcome: &TaskOutcome) {
+        let counter = if outcome.result.success {
+            &self.succeeded
+        } else {
+            &self.failed
+        };
+        counter.fetch_add(1, Ordering::Relaxed);
+    }
+}
+
+/// The background run started by [`SpammerRuntime::start`]
+struct Run {
+    shutdown: Option<oneshot::Sender<()>>,
+    handle: JoinHandle<()>,
+    started: Instant,
+    stopped: Option<Instant>,
+}
+
+/// A spammer built from the config, started and stopped by its owner
+///
+/// See the [module docs](self).
+pub struct SpammerRuntime {
+    config: TempoSpammerConfig,
+    config_path: PathBuf,
+    pool: Arc<ClientPool>,
+    db: Arc<DatabaseManager>,
+    workers: u64,
+    /// Taken by [`start`](Self::start)
+    spammer: Option<Spammer>,
+    counter: Arc<TaskCounter>,
+    paused: watch::Sender<bool>,
+    stop_requests: Arc<Notify>,
+    run: Option<Run>,
+}
+
+impl SpammerRuntime {
+    /// Prepares a run: proxies, database, client pool, bot and control socket
+    ///
+    /// # Errors
+    ///
+    /// Fails when the node fails the `[capabilities]` check, the database or
+    /// the `[rpc_recording]` file cannot be opened or the control socket
+    /// cannot listen.
+    pub async fn build(
+        mut config: TempoSpammerConfig,
+        wallets: UnlockedWallets,
+        options: RuntimeOptions,
+    ) -> Result<Self> {
+        // Before the first client, so no call goes unrecorded
+        crate::recording::recorder().configure(&config.rpc_recording)?;
+
+        // May adopt the node's chain id, so before anything copies the config
+        let capabilities = crate::capabilities::check(&mut config).await?;
+
+        let proxies = if options.no_proxy {
+            Vec::new()
+        } else {
+            let path = crate::config::proxies_path(&options.config_path);
+            load_proxies_with_rotation(&path, config.proxy.session_rotation()).unwrap_or_default()
+        };
+
+        let banlist = ProxyBanlist::with_policy(config.proxy.bans.policy());
+        if !proxies.is_empty() {
+            info!(target: "task_result", "🔍 Starting proxy health check for {} proxies...", proxies.len());
+            // The rest of the scan continues in the background
+            let (healthy, banned, _scan) = proxy_health::scan_proxies_partial(
+                Arc::new(proxies.clone()),
+                config.proxy.health.probe(&config.rpc_url),
+                banlist.clone(),
+                PROXY_SCAN_MIN_HEALTHY,
+            )
+            .await;
+            info!(target: "task_result", "✅ {}/{} proxies healthy (partial scan), ⏱️ {} temporarily banned ({}min) - continuing startup...",
+                healthy, proxies.len(), banned, config.proxy.bans.tiers_minutes[0]);
+        }
+
+        let mut builder = Spammer::builder(config.clone())
+            .unlocked_wallets(wallets)
+            .proxies(proxies)
+            .proxy_banlist(banlist)
+            .capabilities(capabilities);
+        if let Some(workers) = options.workers {
+            builder = builder.workers(workers);
+        }
+        if let Some(db) = options.database {
+            builder = builder.database(db);
+        }
+        let mut spammer = builder.build().await?;
+
+        let pool = spammer.client_pool().clone();
+        info!("Found {} wallets", pool.count());
+        let tags = pool.tag_counts();
+        if !tags.is_empty() {
+            let tags: Vec<String> = tags
+                .iter()
+                .map(|(tag, count)| format!("{}: {}", tag, count))
+                .collect();
+            info!("Wallet tags: {}", tags.join(", "));
+        }
+
+        if options.notifications {
+            if let Some(bot) = spawn_notification_service(Some(pool.wallet_health())).await {
+                info!("Telegram bot notification service started (notifications every 3 hours)");
+                tokio::spawn(async move {
+                    if let Err(e) = bot.await {
+                        error!("Telegram bot task failed: {}", e);
+                    }
+                });
+            }
+        }
+
+        let workers = spammer.workers();
+        let counter = Arc::new(TaskCounter::default());
+        spammer.add_sink(counter.clone());
+        let paused = watch::channel(false).0;
+        let stop_requests = Arc::new(Notify::new());
+        if config.control.enabled {
+            let status = counter.clone().status_reply(paused.subscribe(), workers);
+            let control = LogControl::new(options.log_levels)
+                .with_lifecycle(Lifecycle::new(status, stop_requests.clone()));
+            crate::control::serve(&config.control.listen, Arc::new(control)).await?;
+        }
+
+        Ok(Self {
+            workers,
+            config,
+            config_path: options.config_path,
+            pool,
+            db: spammer.database().clone(),
+            spammer: Some(spammer),
+            counter,
+            paused,
+            stop_requests,
+            run: None,
+        })
+    }
+
+    /// Replaces [`default_tasks`](crate::runner::default_tasks) before the run starts
+    pub fn with_tasks(mut self, tasks: Vec<Box<dyn TempoTask>>) -> Self {
+        if let Some(spammer) = self.spammer.as_mut() {
+            spammer.set_tasks(tasks);
+        }
+        self
+    }
+
+    /// Adds a sink receiving every finished task of the run
+    pub fn with_sink(mut self, sink: impl ResultSink + 'static) -> Self {
+        if let Some(spammer) = self.spammer.as_mut() {
+            spammer.add_sink(Arc::new(sink));
+        }
+        self
+    }
+
+    pub fn config(&self) -> &TempoSpammerConfig {
+        &self.config
+    }
+
+    pub fn config_path(&self) -> &Path {
+        &self.config_path
+    }
+
+    /// The wallet pool, e.g. to run a single task on a leased client
+    pub fn client_pool(&self) -> &Arc<ClientPool> {
+        &self.pool
+    }
+
+    pub fn database(&self) -> &Arc<DatabaseManager> {
+        &self.db
+    }
+
+    pub fn workers(&self) -> u64 {
+        self.workers
+    }
+
+    /// Notified when `stop` arrives on the control socket
+    ///
+    /// The runtime keeps running; its owner waits on this next to Ctrl+C and
+    /// calls [`stop`](Self::stop).
+    pub fn stop_requests(&self) -> Arc<Notify> {
+        self.stop_requests.clone()
+    }
+
+    /// Starts the workers in the background
+    ///
+    /// A runtime paused before starting starts with its workers held.
+    ///
+    /// # Errors
+    ///
+    /// Fails when the runtime was started before; a stopped runtime cannot
+    /// be restarted.
+    pub fn start(&mut self) -> Result<()> {
+        let Some(spammer) = self.spammer.take() else {
+            bail!("Runtime was already started");
+        };
+        let (shutdown, stop) = oneshot::channel();
+        let paused = self.paused.subscribe();
+
+        let handle = tokio::spawn(async move {
+            spammer
+                .run_pausable(paused, async {
+                    let _ = stop.await;
+                })
+                .await;
+        });
+        self.run = Some(Run {
+            shutdown: Some(shutdown),
+            handle,
+            started: Instant::now(),
+            stopped: None,
+        });
+        Ok(())
+    }
+
+    /// Holds the workers once their current task finishes
+    pub fn pause(&self) {
+        if !self.paused.send_replace(true) {
+            info!(target: "task_result", "Pausing {} workers", self.workers);
+        }
+    }
+
+    /// Lets paused workers lease wallets again
+    pub fn resume(&self) {
+        if self.paused.send_replace(false) {
+            info!(target: "task_result", "Resuming {} workers", self.workers);
+        }
+    }
+
+    pub fn state(&self) -> RuntimeState {
+        match &self.run {
+            None => RuntimeState::Ready,
+            Some(run) if run.stopped.is_some() || run.handle.is_finished() => RuntimeState::Stopped,
+            Some(_) if *self.paused.borrow() => RuntimeState::Paused,
+            Some(_) => RuntimeState::Running,
+        }
+    }
+
+    /// Stops the workers and waits for the run to wind down
+    ///
+    /// Tasks in flight are dropped; results already queued get a moment to
+    /// reach the database before this returns. Does nothing unless running.
+    pub async fn stop(&mut self) {
+        let Some(run) = self.run.as_mut() else {
+            return;
+        };
+        let Some(shutdown) = run.shutdown.take() else {
+            return;
+        };
+        let _ = shutdown.send(());
+        if let Err(e) = (&mut run.handle).await {
+            error!("Spammer run failed: {}", e);
+        }
+        run.stopped = Some(Instant::now());
+    }
+
+    /// Task counts of this run and the client pool's current load
+    pub async fn stats(&self) -> RuntimeStats {
+        let uptime = self.run.as_ref().map_or(Duration::ZERO, |run| {
+            run.stopped
+                .unwrap_or_else(Instant::now)
+                .duration_since(run.started)
+        });
+        RuntimeStats {
+            state: self.state(),
+            workers: self.workers,
+            uptime,
+            succeeded: self.counter.succeeded.load(Ordering::Relaxed),
+            failed: self.counter.failed.load(Ordering::Relaxed),
+            pool: self.pool.stats().await,
+        }
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use crate::tasks::TaskResult;
+    use crate::tasks::t03_send_token::SendTokenTask;
+    use crate::testing::{TEST_PRIVATE_KEY, TaskHarness};
+    use alloy_primitives::Address;
+
+    async fn runtime(harness: &TaskHarness) -> SpammerRuntime {
+        SpammerRuntime::build(
+            harness.config.clone(),
+            UnlockedWallets::from_private_keys([TEST_PRIVATE_KEY]),
+            RuntimeOptions {
+                workers: Some(1),
+                no_proxy: true,
+                database: harness.db.clone(),
+                ..Default::default()
+            },
+        )
+        .await
+        .unwrap()
+        .with_tasks(vec![Box::new(SendTokenTask::new())])
+    }
+
+    #[tokio::test]
+    async fn test_unlock_requires_wallets() {
+        let err =
+            UnlockedWallets::unlock(WalletManager::from_private_keys(Vec::<String>::new()), None)
+                .await
+                .err()
+                .unwrap();
+        assert!(err.to_string().starts_with("No wallets"));
+    }
+
+    #[tokio::test]
+    async fn test_lifecycle_moves_through_states() {
+        let harness = TaskHarness::new().with_db().await.unwrap();
+        let mut runtime = runtime(&harness).await;
+        assert_eq!(runtime.client_pool().count(), 1);
+
+        let stats = runtime.stats().await;
+        assert_eq!((stats.state, stats.workers), (RuntimeState::Ready, 1));
+        assert_eq!(stats.uptime, Duration::ZERO);
+
+        // Paused up front, the worker never leases a wallet
+        runtime.pause();
+        runtime.start().unwrap();
+        assert_eq!(runtime.state(), RuntimeState::Paused);
+        assert!(runtime.start().is_err());
+        runtime.resume();
+        assert_eq!(runtime.state(), RuntimeState::Running);
+        runtime.pause();
+
+        runtime.stop().await;
+        let stats = runtime.stats().await;
+        assert_eq!(stats.state, RuntimeState::Stopped);
+        assert_eq!(stats.tasks(), 0);
+        // Uptime ends with the run
+        assert_eq!(runtime.stats().await.uptime, stats.uptime);
+        assert_eq!(stats.pool.wallets_locked, 0);
+    }
+
+    #[test]
+    fn test_counter_splits_outcomes() {
+        let counter = TaskCounter::default();
+        for success in [true, false, true] {
+            counter.record(&TaskOutcome {
+                worker_id: 0,
+                wallet: Address::ZERO,
+                task: "03_send_token",
+                duration: Duration::ZERO,
+                result: TaskResult {
+                    success,
+                    ..Default::default()
+                },
+            });
+        }
+        assert_eq!(counter.succeeded.load(Ordering::Relaxed), 2);
+        assert_eq!(counter.failed.load(Ordering::Relaxed), 1);
+    }
+}
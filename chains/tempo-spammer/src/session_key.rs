@@ -0,0 +1,411 @@
+//! Session Keys - Access keys for routine Tempo transaction signing
+//!
+//! Tempo accounts can authorize secondary "access keys" in the AccountKeychain
+//! precompile: a transaction signed by the root key carries a
+//! `key_authorization`, and later transactions are signed by the access key
+//! inside a Keychain signature on behalf of the root account. The keychain
+//! enforces the key's expiry and optional per-token spending limits.
+//!
+//! With `[session_keys] enabled = true`, tasks that build Tempo (0x76)
+//! transactions sign them through [`TempoSigner`]:
+//!
+//! 1. The wallet's session key for the current period is derived from its
+//!    root key, so no extra secrets are stored and restarts reuse the key.
+//! 2. `getKey` on the keychain tells whether the key is already authorized.
+//! 3. If not, the root key signs one registration transaction carrying the
+//!    key authorization (expiry and `limits`), and the key is used once the
+//!    keychain reports it.
+//!
+//! ```toml
+//! [session_keys]
+//! enabled = true
+//! ttl_hours = 24
+//! limits = { "PathUSD" = 1_000_000_000 }
+//! ```
+//!
+//! Keys rotate every `ttl_hours`. Each key stays valid for one period past
+//! its own so transactions signed near the boundary still land.
+
+use crate::TempoClient;
+use crate::config::SessionKeySettings;
+use crate::network;
+use alloy::providers::Provider;
+use alloy::rpc::types::TransactionRequest;
+use alloy::signers::Signer;
+use alloy::signers::local::PrivateKeySigner;
+use alloy_primitives::{Address, Bytes, TxKind, U256, address, keccak256};
+use alloy_sol_types::{SolCall, sol};
+use anyhow::{Context, Result, bail};
+use std::collections::HashMap;
+use std::sync::Mutex;
+use std::time::{Duration, Instant, SystemTime, UNIX_EPOCH};
+use tempo_primitives::transaction::{
+    Call, KeyAuthorization, KeychainSignature, PrimitiveSignature, SignatureType, TempoSignature,
+    TempoTransaction, TokenLimit,
+};
+use tracing::{debug, info};
+use zeroize::Zeroizing;
+
+sol! {
+    interface IAccountKeychain {
+        struct KeyInfo {
+            uint8 signatureType;
+            address keyId;
+            uint64 expiry;
+            bool enforceLimits;
+            bool isRevoked;
+        }
+
+        function getKey(address account, address keyId) external view returns (KeyInfo memory);
+    }
+
+    interface ITIP20 {
+        function balanceOf(address account) external view returns (uint256);
+    }
+}
+
+/// AccountKeychain precompile
+pub const ACCOUNT_KEYCHAIN: Address = address!("AAAAAAAA00000000000000000000000000000000");
+
+/// Domain separator mixed into derived session keys
+const DERIVATION_DOMAIN: &[u8] = b"tempo-spammer/session-key/v1";
+
+/// How long to wait for the keychain to report a registered key
+const REGISTRATION_TIMEOUT: Duration = Duration::from_secs(30);
+
+/// Signs Tempo transactions with the root key or a session key
+#[derive(Debug, Clone)]
+pub enum TempoSigner {
+    /// The wallet's own key
+    Root(PrivateKeySigner),
+    /// An authorized access key signing on behalf of `account`
+    Session {
+        key: PrivateKeySigner,
+        account: Address,
+    },
+}
+
+impl TempoSigner {
+    /// Signs `tx`, wrapping session key signatures in a Keychain signature
+    pub async fn sign(&self, tx: &TempoTransaction) -> Result<TempoSignature> {
+        let hash = tx.signature_hash();
+        match self {
+            Self::Root(signer) => Ok(TempoSignature::from(signer.sign_hash(&hash).await?)),
+            Self::Session { key, account } => {
+                let signature = key.sign_hash(&hash).await?;
+                Ok(TempoSignature::Keychain(KeychainSignature::new(
+                    *account,
+                    PrimitiveSignature::Secp256k1(signature),
+                )))
+            }
+        }
+    }
+
+    pub fn is_session(&self) -> bool {
+        matches!(self, Self::Session { .. })
+    }
+}
+
+/// Rotation period `now_secs` falls in
+pub fn epoch(now_secs: u64, ttl_secs: u64) -> u64 {
+    now_secs / ttl_secs.max(1)
+}
+
+/// Derives the session key of `root` for a rotation period
+pub fn derive_session_signer(root: &PrivateKeySigner, epoch: u64) -> Result<PrivateKeySigner> {
+    // Sized up front so the root key bytes are never left behind by a reallocation
+    let mut seed = Zeroizing::new(Vec::with_capacity(32 + DERIVATION_DOMAIN.len() + 8));
+    seed.extend_from_slice(root.to_bytes().as_slice());
+    seed.extend_from_slice(DERIVATION_DOMAIN);
+    seed.extend_from_slice(&epoch.to_be_bytes());
+    PrivateKeySigner::from_bytes(&keccak256(seed.as_slice()))
+        .context("Derived an invalid session key")
+}
+
+/// A session key authorized for one wallet
+#[derive(Debug, Clone)]
+struct SessionKey {
+    signer: PrivateKeySigner,
+    epoch: u64,
+}
+
+/// Derives, registers and caches session keys for all wallets
+#[derive(Debug)]
+pub struct SessionKeys {
+    ttl_secs: u64,
+    /// Spending limits put on each key, `None` for unlimited
+    limits: Option<Vec<TokenLimit>>,
+    /// Keys known to be authorized, by root account
+    active: Mutex<HashMap<Address, SessionKey>>,
+}
+
+impl SessionKeys {
+    /// Validates the settings and resolves the limit tokens
+    pub fn new(settings: &SessionKeySettings) -> Result<Self> {
+        if settings.ttl_hours == 0 {
+            bail!("session_keys.ttl_hours must be above 0");
+        }
+        let limits = if settings.limits.is_empty() {
+            None
+        } else {
+            let mut limits = Vec::new();
+            for (token, &limit) in &settings.limits {
+                limits.push(TokenLimit {
+                    token: crate::budget::resolve_token(token)
+                        .with_context(|| format!("Invalid session_keys.limits key '{}'", token))?,
+                    limit: U256::from(limit),
+                });
+            }
+            Some(limits)
+        };
+        Ok(Self {
+            ttl_secs: settings.ttl_hours * 3600,
+            limits,
+            active: Mutex::new(HashMap::new()),
+        })
+    }
+
+    /// Expiry written into the authorization of a period's key
+    pub fn expiry(&self, epoch: u64) -> u64 {
+        (epoch + 2) * self.ttl_secs
+    }
+
+    /// Authorization of `key_id` for a period, to be signed by the root key
+    pub fn authorization(&self, chain_id: u64, key_id: Address, epoch: u64) -> KeyAuthorization {
+        KeyAuthorization {
+            chain_id,
+            key_type: SignatureType::Secp256k1,
+            key_id,
+            expiry: Some(self.expiry(epoch)),
+            limits: self.limits.clone(),
+        }
+    }
+
+    /// Session signer for the client's wallet, registering the key on first use
+    pub async fn signer_for(&self, client: &TempoClient, rpc_url: &str) -> Result<TempoSigner> {
+        let account = client.address();
+        let epoch = epoch(unix_secs(), self.ttl_secs);
+
+        let cached = self.active.lock().unwrap().get(&account).cloned();
+        let key = match cached {
+            Some(key) if key.epoch == epoch => key.signer,
+            _ => {
+                let signer = derive_session_signer(&client.signer, epoch)?;
+                if !is_authorized(client, account, signer.address()).await? {
+                    self.register(client, rpc_url, &signer, epoch).await?;
+                }
+                self.active.lock().unwrap().insert(
+                    account,
+                    SessionKey {
+                        signer: signer.clone(),
+                        epoch,
+                    },
+                );
+                signer
+            }
+        };
+
+        Ok(TempoSigner::Session { key, account })
+    }
+
+    /// Sends the root-signed key authorization and waits until the keychain has it
+    async fn register(
+        &self,
+        client: &TempoClient,
+        rpc_url: &str,
+        key: &PrivateKeySigner,
+        epoch: u64,
+    ) -> Result<()> {
+        let account = client.address();
+        let authorization = self.authorization(client.chain_id(), key.address(), epoch);
+        let auth_signature = client
+            .signer
+            .sign_hash(&authorization.signature_hash())
+            .await?;
+
+        let nonce = client.get_pending_nonce(rpc_url).await?;
+        let gas_price = client.provider.get_gas_price().await?;
+        let tx = TempoTransaction {
+            chain_id: client.chain_id(),
+            nonce,
+            max_fee_per_gas: gas_price * 120 / 100,
+            max_priority_fee_per_gas: 1_500_000_000,
+            gas_limit: 400_000,
+            // A no-op PathUSD balance read carries the authorization
+            calls: vec![Call {
+                to: TxKind::Call(network::contracts().path_usd),
+                value: U256::ZERO,
+                input: ITIP20::balanceOfCall { account }.abi_encode().into(),
+            }],
+            key_authorization: Some(
+                authorization.into_signed(PrimitiveSignature::Secp256k1(auth_signature)),
+            ),
+            ..Default::default()
+        };
+        let signature = TempoSigner::Root(client.signer.clone()).sign(&tx).await?;
+
+        let mut encoded = Vec::new();
+        tx.into_signed(signature).eip2718_encode(&mut encoded);
+        let pending = client
+            .provider
+            .send_raw_transaction(&encoded)
+            .await
+            .context("Failed to send session key registration")?;
+        debug!(
+            "Registering session key {} for {}: {:?}",
+            key.address(),
+            account,
+            pending.tx_hash()
+        );
+
+        let started = Instant::now();
+        while started.elapsed() < REGISTRATION_TIMEOUT {
+            tokio::time::sleep(Duration::from_secs(1)).await;
+            if is_authorized(client, account, key.address())
+                .await
+                .unwrap_or(false)
+            {
+                info!("Session key {} authorized for {}", key.address(), account);
+                return Ok(());
+            }
+        }
+        bail!(
+            "Session key {} for {} not authorized after {}s (tx {:?})",
+            key.address(),
+            account,
+            REGISTRATION_TIMEOUT.as_secs(),
+            pending.tx_hash()
+        )
+    }
+}
+
+/// Whether the keychain holds an unexpired, unrevoked `key_id` for `account`
+async fn is_authorized(client: &TempoClient, account: Address, key_id: Address) -> Result<bool> {
+    let call = IAccountKeychain::getKeyCall {
+        account,
+        keyId: key_id,
+    };
+    let data = client
+        .provider
+        .call(
+            TransactionRequest::default()
+                .to(ACCOUNT_KEYCHAIN)
+                .input(Bytes::from(call.abi_encode()).into()),
+        )
+        .await
+        .context("AccountKeychain getKey failed")?;
+    let info = IAccountKeychain::getKeyCall::abi_decode_returns(&data)
+        .context("Failed to decode getKey result")?;
+    Ok(info.keyId == key_id && !info.isRevoked && info.expiry > unix_secs())
+}
+
+fn unix_secs() -> u64 {
+    SystemTime::now()
+        .duration_since(UNIX_EPOCH)
+        .map(|d| d.as_secs())
+        .unwrap_or_default()
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use std::collections::BTreeMap;
+
+    fn settings(limits: &[(&str, u64)]) -> SessionKeySettings {
+        SessionKeySettings {
+            enabled: true,
+            ttl_hours: 24,
+            limits: limits
+                .iter()
+                .map(|(token, limit)| (token.to_string(), *limit))
+                .collect::<BTreeMap<_, _>>(),
+        }
+    }
+
+    #[test]
+    fn test_derived_keys_rotate_per_epoch() {
+        let root = PrivateKeySigner::random();
+        let first = derive_session_signer(&root, 100).unwrap();
+        assert_eq!(
+            first.address(),
+            derive_session_signer(&root, 100).unwrap().address()
+        );
+        assert_ne!(
+            first.address(),
+            derive_session_signer(&root, 101).unwrap().address()
+        );
+        assert_ne!(first.address(), root.address());
+        assert_ne!(
+            first.address(),
+            derive_session_signer(&PrivateKeySigner::random(), 100)
+                .unwrap()
+                .address()
+        );
+
+        assert_eq!(epoch(86_400 * 3 + 5, 86_400), 3);
+    }
+
+    #[test]
+    fn test_authorization_limits_and_expiry() {
+        let keys = SessionKeys::new(&settings(&[("PathUSD", 500)])).unwrap();
+        let key_id = Address::repeat_byte(7);
+        let auth = keys.authorization(42431, key_id, 10);
+        assert_eq!(auth.key_id, key_id);
+        assert_eq!(auth.expiry, Some(12 * 86_400));
+        assert_eq!(
+            auth.limits,
+            Some(vec![TokenLimit {
+                token: network::contracts().path_usd,
+                limit: U256::from(500),
+            }])
+        );
+
+        assert!(
+            SessionKeys::new(&settings(&[]))
+                .unwrap()
+                .authorization(42431, key_id, 10)
+                .has_unlimited_spending()
+        );
+        assert!(SessionKeys::new(&settings(&[("NoSuchUSD", 1)])).is_err());
+        assert!(
+            SessionKeys::new(&SessionKeySettings {
+                ttl_hours: 0,
+                ..settings(&[])
+            })
+            .is_err()
+        );
+    }
+
+    #[tokio::test]
+    async fn test_session_signature_recovers_root_account() {
+        let root = PrivateKeySigner::random();
+        let key = derive_session_signer(&root, 1).unwrap();
+        let tx = TempoTransaction {
+            chain_id: 42431,
+            nonce: 3,
+            gas_limit: 100_000,
+            calls: vec![Call {
+                to: TxKind::Call(network::contracts().path_usd),
+                value: U256::ZERO,
+                input: Bytes::new(),
+            }],
+            ..Default::default()
+        };
+        let hash = tx.signature_hash();
+
+        let session = TempoSigner::Session {
+            key: key.clone(),
+            account: root.address(),
+        };
+        let signature = session.sign(&tx).await.unwrap();
+        assert_eq!(signature.recover_signer(&hash).unwrap(), root.address());
+        assert_eq!(
+            signature.as_keychain().unwrap().key_id(&hash).unwrap(),
+            key.address()
+        );
+
+        let direct = TempoSigner::Root(root.clone()).sign(&tx).await.unwrap();
+        assert!(!direct.is_keychain());
+        assert_eq!(direct.recover_signer(&hash).unwrap(), root.address());
+    }
+}
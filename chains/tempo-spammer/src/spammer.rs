@@ -0,0 +1,353 @@
+//! Spammer - Embedding API for driving the spammer from another binary
+//!
+//! The `tempo-spammer` binary prompts for the wallet password and worker
+//! count, scans proxies, shows a banner and starts the Telegram bot. A
+//! program embedding the library wants none of that, so [`SpammerBuilder`]
+//! assembles the same pieces from values the caller passes in:
+//!
+//! - wallets from raw private keys, `wallet-json/` / `pv.txt` with a
+//!   password given up front, or [`UnlockedWallets`] already checked
+//! - the tasks to pick from, [`default_tasks`] unless replaced
+//! - the worker count, `worker_count` from the config unless set
+//! - proxies, used as given without a health scan
+//! - the database, opened at `[database]` with async logging unless passed in
+//! - [`ResultSink`]s receiving every finished task
+//!
+//! [`Spammer::run_until`] then runs the weighted task loop of
+//! [`runner`](crate::runner) with everything the config enables (budget,
+//! personas, activity schedule, gas tracking and so on).
+//!
+//! ```rust,no_run
+//! use std::time::Duration;
+//! use tempo_spammer::config::TempoSpammerConfig;
+//! use tempo_spammer::spammer::Spammer;
+//! use tempo_spammer::tasks::t03_send_token::SendTokenTask;
+//!
+//! # async fn example() -> anyhow::Result<()> {
+//! let config = TempoSpammerConfig::from_path("config/config.toml")?;
+//! let (outcomes, mut rx) = tokio::sync::mpsc::unbounded_channel();
+//!
+//! let spammer = Spammer::builder(config)
+//!     .private_keys(["0x..."])
+//!     .tasks(vec![Box::new(SendTokenTask::new())])
+//!     .workers(2)
+//!     .sink(outcomes)
+//!     .build()
+//!     .await?;
+//!
+//! tokio::spawn(async move {
+//!     while let Some(outcome) = rx.recv().await {
+//!         println!("{} {}", outcome.task, outcome.result.success);
+//!     }
+//! });
+//! spammer.run_for(Duration::from_secs(60)).await;
+//! # Ok(())
+//! # }
+//! ```
+//!
+//! Runnable versions are in the crate's `examples/` directory.
+
+use crate::ClientPool;
+use crate::capabilities::ChainCapabilities;
+use crate::config::TempoSpammerConfig;
+use crate::proxy_health::ProxyBanlist;
+use crate::runner::{self, ResultSink, default_tasks};
+use crate::runtime::UnlockedWallets;
+use crate::tasks::{ProxyConfig, TempoTask};
+use anyhow::{Context, Result};
+use core_logic::WalletManager;
+use core_logic::database::{DatabaseManager, FallbackStrategy};
+use std::future::Future;
+use std::sync::Arc;
+use std::time::Duration;
+use tokio::sync::watch;
+use tracing::info;
+use zeroize::Zeroizing;
+
+/// How long [`Spammer::run_until`] waits for the async writer after stopping
+const FLUSH_GRACE: Duration = Duration::from_secs(1);
+
+/// Where the builder loads wallets from
+enum Wallets {
+    /// `wallet-json/` or `pv.txt`, as the binary does
+    Files {
+        password: Option<Zeroizing<String>>,
+    },
+    Keys(Vec<Zeroizing<String>>),
+    /// Already checked by [`UnlockedWallets::unlock`]
+    Unlocked(UnlockedWallets),
+}
+
+/// Assembles a [`Spammer`] without prompts, see the [module docs](self)
+pub struct SpammerBuilder {
+    config: TempoSpammerConfig,
+    wallets: Wallets,
+    tasks: Option<Vec<Box<dyn TempoTask>>>,
+    workers: Option<u64>,
+    proxies: Vec<ProxyConfig>,
+    proxy_banlist: Option<ProxyBanlist>,
+    capabilities: Option<ChainCapabilities>,
+    db: Option<Arc<DatabaseManager>>,
+    sinks: Vec<Arc<dyn ResultSink>>,
+}
+
+impl SpammerBuilder {
+    /// Signs with these private keys instead of reading wallet files
+    pub fn private_keys<I, S>(mut self, keys: I) -> Self
+    where
+        I: IntoIterator<Item = S>,
+        S: Into<String>,
+    {
+        self.wallets = Wallets::Keys(
+            keys.into_iter()
+                .map(|key| Zeroizing::new(key.into()))
+                .collect(),
+        );
+        self
+    }
+
+    /// Password for the encrypted wallets in `wallet-json/`
+    ///
+    /// Checked against the first wallet by [`build`](Self::build).
+    pub fn wallet_password(mut self, password: impl Into<String>) -> Self {
+        self.wallets = Wallets::Files {
+            password: Some(Zeroizing::new(password.into())),
+        };
+        self
+    }
+
+    /// Wallets whose password was already checked
+    pub fn unlocked_wallets(mut self, wallets: UnlockedWallets) -> Self {
+        self.wallets = Wallets::Unlocked(wallets);
+        self
+    }
+
+    /// Tasks to pick from, weighted by `[tasks]` as usual
+    pub fn tasks(mut self, tasks: Vec<Box<dyn TempoTask>>) -> Self {
+        self.tasks = Some(tasks);
+        self
+    }
+
+    /// Number of concurrent workers
+    pub fn workers(mut self, workers: u64) -> Self {
+        self.workers = Some(workers);
+        self
+    }
+
+    /// Proxies to rotate through, e.g. from [`load_proxies`](crate::tasks::load_proxies)
+    pub fn proxies(mut self, proxies: Vec<ProxyConfig>) -> Self {
+        self.proxies = proxies;
+        self
+    }
+
+    /// Banlist the proxies were scanned into, shared with the pool
+    pub fn proxy_banlist(mut self, banlist: ProxyBanlist) -> Self {
+        self.proxy_banlist = Some(banlist);
+        self
+    }
+
+    /// What the startup probe found the node to support
+    pub fn capabilities(mut self, capabilities: Option<ChainCapabilities>) -> Self {
+        self.capabilities = capabilities;
+        self
+    }
+
+    /// Database to log to; it must have async logging enabled
+    pub fn database(mut self, db: Arc<DatabaseManager>) -> Self {
+        self.db = Some(db);
+        self
+    }
+
+    /// Adds a sink receiving every finished task
+    pub fn sink(mut self, sink: impl ResultSink + 'static) -> Self {
+        self.sinks.push(Arc::new(sink));
+        self
+    }
+
+    /// Loads the wallets, opens the database and builds the client pool
+    ///
+    /// # Errors
+    ///
+    /// Fails when there are no wallets, the password does not decrypt the
+    /// first wallet file, or the database cannot be opened.
+    pub async fn build(self) -> Result<Spammer> {
+        let wallets = match self.wallets {
+            Wallets::Unlocked(wallets) => wallets,
+            Wallets::Keys(keys) => {
+                let manager = WalletManager::from_private_keys(keys.iter().map(|key| key.as_str()));
+                UnlockedWallets::unlock(manager, None).await?
+            }
+            Wallets::Files { password } => {
+                let manager = WalletManager::with_options(self.config.wallets.wallet_options())?;
+                UnlockedWallets::unlock(manager, password).await?
+            }
+        };
+
+        let db = match self.db {
+            Some(db) => db,
+            None => {
+                let path = self.config.database.active_path();
+                info!(target: "task_result", "Database: {}", path);
+                Arc::new(
+                    DatabaseManager::new_with_async(
+                        &path,
+                        self.config.database.async_config(),
+                        FallbackStrategy::Hybrid,
+                    )
+                    .await
+                    .with_context(|| format!("Failed to open database {}", path))?,
+                )
+            }
+        };
+
+        // The pool takes over the password and wipes it when it drops
+        let mut pool = ClientPool::from_wallet_manager(
+            self.config.clone(),
+            db.clone(),
+            wallets.manager,
+            wallets.password,
+            self.config.connection_semaphore,
+        )
+        .with_proxies(self.proxies)
+        .with_capabilities(self.capabilities);
+        if let Some(banlist) = self.proxy_banlist {
+            pool = pool.with_proxy_banlist(banlist);
+        }
+
+        Ok(Spammer {
+            workers: self.workers.unwrap_or(self.config.worker_count),
+            tasks: self.tasks.unwrap_or_else(default_tasks),
+            pool: Arc::new(pool),
+            config: self.config,
+            db,
+            sinks: self.sinks,
+        })
+    }
+}
+
+/// A ready-to-run spammer built by [`Spammer::builder`]
+pub struct Spammer {
+    config: TempoSpammerConfig,
+    pool: Arc<ClientPool>,
+    tasks: Vec<Box<dyn TempoTask>>,
+    db: Arc<DatabaseManager>,
+    workers: u64,
+    sinks: Vec<Arc<dyn ResultSink>>,
+}
+
+impl Spammer {
+    /// Starts a builder for `config`
+    pub fn builder(config: TempoSpammerConfig) -> SpammerBuilder {
+        SpammerBuilder {
+            config,
+            wallets: Wallets::Files { password: None },
+            tasks: None,
+            workers: None,
+            proxies: Vec::new(),
+            proxy_banlist: None,
+            capabilities: None,
+            db: None,
+            sinks: Vec::new(),
+        }
+    }
+
+    pub fn config(&self) -> &TempoSpammerConfig {
+        &self.config
+    }
+
+    /// The wallet pool, e.g. to run a single task on a leased client
+    pub fn client_pool(&self) -> &Arc<ClientPool> {
+        &self.pool
+    }
+
+    pub fn database(&self) -> &Arc<DatabaseManager> {
+        &self.db
+    }
+
+    pub fn workers(&self) -> u64 {
+        self.workers
+    }
+
+    pub fn task_names(&self) -> Vec<&'static str> {
+        self.tasks.iter().map(|task| task.name()).collect()
+    }
+
+    /// Replaces the tasks chosen at build time
+    pub(crate) fn set_tasks(&mut self, tasks: Vec<Box<dyn TempoTask>>) {
+        self.tasks = tasks;
+    }
+
+    pub(crate) fn add_sink(&mut self, sink: Arc<dyn ResultSink>) {
+        self.sinks.push(sink);
+    }
+
+    /// Runs the workers until `shutdown` resolves
+    ///
+    /// Tasks in flight are dropped; results already queued get a moment to
+    /// reach the database before this returns.
+    pub async fn run_until(self, shutdown: impl Future<Output = ()>) {
+        self.run_pausable(watch::channel(false).1, shutdown).await
+    }
+
+    /// Like [`run_until`](Self::run_until), holding the workers between
+    /// tasks while `paused` is true
+    pub(crate) async fn run_pausable(
+        self,
+        paused: watch::Receiver<bool>,
+        shutdown: impl Future<Output = ()>,
+    ) {
+        runner::run_with_sinks(
+            self.pool,
+            self.tasks,
+            &self.config,
+            self.db,
+            self.workers,
+            self.sinks,
+            paused,
+            shutdown,
+        )
+        .await;
+        tokio::time::sleep(FLUSH_GRACE).await;
+    }
+
+    /// Runs the workers for `duration`
+    pub async fn run_for(self, duration: Duration) {
+        self.run_until(tokio::time::sleep(duration)).await
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use crate::tasks::t03_send_token::SendTokenTask;
+    use crate::testing::{TEST_PRIVATE_KEY, TaskHarness};
+
+    #[tokio::test]
+    async fn test_builds_from_private_keys() {
+        let harness = TaskHarness::new().with_db().await.unwrap();
+        let spammer = Spammer::builder(harness.config.clone())
+            .private_keys([TEST_PRIVATE_KEY])
+            .tasks(vec![Box::new(SendTokenTask::new())])
+            .database(harness.db.clone().unwrap())
+            .build()
+            .await
+            .unwrap();
+
+        assert_eq!(spammer.client_pool().count(), 1);
+        assert_eq!(spammer.workers(), harness.config.worker_count);
+        assert_eq!(spammer.task_names(), ["03_send_token"]);
+    }
+
+    #[tokio::test]
+    async fn test_build_requires_wallets() {
+        let harness = TaskHarness::new().with_db().await.unwrap();
+        let err = Spammer::builder(harness.config.clone())
+            .private_keys(Vec::<String>::new())
+            .database(harness.db.clone().unwrap())
+            .build()
+            .await
+            .err()
+            .unwrap();
+        assert!(err.to_string().starts_with("No wallets"));
+    }
+}
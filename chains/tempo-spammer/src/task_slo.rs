@@ -0,0 +1,374 @@
+//! Task SLO - Rolling success rate per task and pausing of broken tasks
+//!
+//! A task whose contract broke fails every run, spending gas and proxy
+//! traffic for nothing until someone notices. With `[slo] min_success_rate`
+//! above 0 the runner records every run here, and a task whose success rate
+//! over the last `window_secs` falls below it, with at least `min_runs` runs
+//! in the window, is paused:
+//!
+//! ```toml
+//! [slo]
+//! min_success_rate = 0.05
+//! window_secs = 900
+//! min_runs = 20
+//! probe_secs = 300
+//! ```
+//!
+//! Workers stop picking a paused task, by weight or by block trigger. Every
+//! `probe_secs` one worker runs it once as a probe; a successful run resumes
+//! it with a fresh window, a failed probe leaves it paused until the next one.
+//! Pauses and resumes are logged and, with notifications on, sent to
+//! Telegram.
+
+use crate::config::SloSettings;
+use chrono::{DateTime, Utc};
+use std::collections::{HashMap, VecDeque};
+use std::fmt;
+use std::sync::atomic::{AtomicBool, Ordering};
+use std::sync::{Arc, Mutex, OnceLock, RwLock};
+use std::time::{Duration, Instant};
+use tracing::{info, warn};
+
+/// Runs kept per task, whatever the window
+const MAX_WINDOW_RUNS: usize = 10_000;
+
+/// Pauses and resumes kept for the notifier
+const MAX_EVENTS: usize = 100;
+
+static TASK_SLO: OnceLock<Arc<TaskSlo>> = OnceLock::new();
+
+/// Process-wide task SLO tracker shared by all workers
+pub fn task_slo() -> &'static Arc<TaskSlo> {
+    TASK_SLO.get_or_init(|| Arc::new(TaskSlo::new(SloSettings::default())))
+}
+
+/// What happened to a task
+#[derive(Debug, Clone, PartialEq)]
+pub enum SloChange {
+    /// Fell below `min_success_rate` over `runs` runs in the last `window_secs`
+    Paused {
+        success_rate: f64,
+        runs: usize,
+        window_secs: u64,
+    },
+    /// A run succeeded while it was paused
+    Resumed { paused_for: Duration },
+}
+
+/// A task paused or resumed
+#[derive(Debug, Clone, PartialEq)]
+pub struct SloEvent {
+    pub task: String,
+    pub change: SloChange,
+    pub at: DateTime<Utc>,
+}
+
+impl fmt::Display for SloEvent {
+    fn fmt(&self, f: &mut fmt::Formatter<'_>) -> fmt::Result {
+        match &self.change {
+            SloChange::Paused {
+                success_rate,
+                runs,
+                window_secs,
+            } => write!(
+                f,
+                "{} paused: {:.1}% of {} runs succeeded in the last {}s",
+                self.task,
+                success_rate * 100.0,
+                runs,
+                window_secs
+            ),
+            SloChange::Resumed { paused_for } => write!(
+                f,
+                "{} resumed after {}m paused",
+                self.task,
+                paused_for.as_secs() / 60
+            ),
+        }
+    }
+}
+
+/// A paused task
+#[derive(Debug, Clone, PartialEq)]
+pub struct PausedTask {
+    pub task: String,
+    /// Success rate that paused it
+    pub success_rate: f64,
+    pub since: DateTime<Utc>,
+}
+
+#[derive(Debug, Clone, Copy)]
+struct Pause {
+    success_rate: f64,
+    since: DateTime<Utc>,
+    started: Instant,
+    next_probe: Instant,
+}
+
+#[derive(Debug, Default)]
+struct TaskRuns {
+    /// When each run in the window finished and whether it succeeded
+    runs: VecDeque<(Instant, bool)>,
+    paused: Option<Pause>,
+}
+
+/// Recent runs of every task and the tasks paused for falling below the SLO
+#[derive(Debug)]
+pub struct TaskSlo {
+    settings: RwLock<SloSettings>,
+    tasks: Mutex<HashMap<String, TaskRuns>>,
+    /// Whether any task is paused, so picks skip the lock while none is
+    any_paused: AtomicBool,
+    events: Mutex<VecDeque<SloEvent>>,
+}
+
+impl TaskSlo {
+    pub fn new(settings: SloSettings) -> Self {
+        Self {
+            settings: RwLock::new(settings),
+            tasks: Mutex::new(HashMap::new()),
+            any_paused: AtomicBool::new(false),
+            events: Mutex::new(VecDeque::new()),
+        }
+    }
+
+    /// Applies `[slo]`, resuming every task when it turns the SLO off
+    pub fn configure(&self, settings: SloSettings) {
+        *self.settings.write().unwrap() = settings;
+        if !settings.enabled() {
+            let mut tasks = self.tasks.lock().unwrap();
+            for runs in tasks.values_mut() {
+                runs.paused = None;
+            }
+            self.any_paused.store(false, Ordering::Relaxed);
+        }
+    }
+
+    /// Records a finished run of `task`, returning the pause or resume it caused
+    pub fn record(&self, task: &str, success: bool, now: Instant) -> Option<SloEvent> {
+        let settings = *self.settings.read().unwrap();
+        if !settings.enabled() {
+            return None;
+        }
+        let window = Duration::from_secs(settings.window_secs);
+
+        let mut tasks = self.tasks.lock().unwrap();
+        let entry = tasks.entry(task.to_string()).or_default();
+        entry.runs.push_back((now, success));
+        while entry.runs.len() > MAX_WINDOW_RUNS
+            || entry
+                .runs
+                .front()
+                .is_some_and(|(at, _)| now.saturating_duration_since(*at) > window)
+        {
+            entry.runs.pop_front();
+        }
+
+        let change = match entry.paused {
+            // Runs already under way when it was paused count as probes too
+            Some(pause) if success => {
+                entry.paused = None;
+                entry.runs.clear();
+                SloChange::Resumed {
+                    paused_for: now.saturating_duration_since(pause.started),
+                }
+            }
+            Some(_) => return None,
+            None => {
+                let runs = entry.runs.len();
+                let succeeded = entry.runs.iter().filter(|(_, success)| *success).count();
+                let success_rate = succeeded as f64 / runs as f64;
+                if runs < settings.min_runs || success_rate >= settings.min_success_rate {
+                    return None;
+                }
+                entry.paused = Some(Pause {
+                    success_rate,
+                    since: Utc::now(),
+                    started: now,
+                    next_probe: now + Duration::from_secs(settings.probe_secs),
+                });
+                SloChange::Paused {
+                    success_rate,
+                    runs,
+                    window_secs: settings.window_secs,
+                }
+            }
+        };
+        self.any_paused.store(
+            tasks.values().any(|runs| runs.paused.is_some()),
+            Ordering::Relaxed,
+        );
+        drop(tasks);
+
+        let event = SloEvent {
+            task: task.to_string(),
+            change,
+            at: Utc::now(),
+        };
+        match event.change {
+            SloChange::Paused { .. } => {
+                warn!("Task {}; probing every {}s", event, settings.probe_secs)
+            }
+            SloChange::Resumed { .. } => info!("Task {}", event),
+        }
+        let mut events = self.events.lock().unwrap();
+        events.push_back(event.clone());
+        while events.len() > MAX_EVENTS {
+            events.pop_front();
+        }
+        Some(event)
+    }
+
+    /// Whether workers should leave `task` alone
+    pub fn is_paused(&self, task: &str) -> bool {
+        self.any_paused.load(Ordering::Relaxed)
+            && self
+                .tasks
+                .lock()
+                .unwrap()
+                .get(task)
+                .is_some_and(|runs| runs.paused.is_some())
+    }
+
+    /// A paused task due for its probe run, which is then not due again for
+    /// `probe_secs` whether or not its result comes back
+    pub fn take_probe(&self, now: Instant) -> Option<String> {
+        if !self.any_paused.load(Ordering::Relaxed) {
+            return None;
+        }
+        let probe = Duration::from_secs(self.settings.read().unwrap().probe_secs);
+        let mut tasks = self.tasks.lock().unwrap();
+        let (task, pause) = tasks
+            .iter_mut()
+            .filter_map(|(task, runs)| runs.paused.as_mut().map(|pause| (task, pause)))
+            .filter(|(_, pause)| pause.next_probe <= now)
+            .min_by_key(|(_, pause)| pause.next_probe)?;
+        pause.next_probe = now + probe;
+        Some(task.clone())
+    }
+
+    /// Paused tasks, longest paused first
+    pub fn paused(&self) -> Vec<PausedTask> {
+        let mut paused: Vec<PausedTask> = self
+            .tasks
+            .lock()
+            .unwrap()
+            .iter()
+            .filter_map(|(task, runs)| {
+                runs.paused.map(|pause| PausedTask {
+                    task: task.clone(),
+                    success_rate: pause.success_rate,
+                    since: pause.since,
+                })
+            })
+            .collect();
+        paused.sort_by_key(|task| task.since);
+        paused
+    }
+
+    /// Pauses and resumes after `since`, oldest first
+    pub fn events_since(&self, since: DateTime<Utc>) -> Vec<SloEvent> {
+        self.events
+            .lock()
+            .unwrap()
+            .iter()
+            .filter(|event| event.at > since)
+            .cloned()
+            .collect()
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    fn settings() -> SloSettings {
+        SloSettings {
+            min_success_rate: 0.5,
+            window_secs: 60,
+            min_runs: 4,
+            probe_secs: 10,
+        }
+    }
+
+    #[test]
+    fn test_pauses_below_threshold_after_min_runs() {
+        let slo = TaskSlo::new(settings());
+        let now = Instant::now();
+
+        for _ in 0..3 {
+            assert_eq!(slo.record("05_swap_stable", false, now), None);
+        }
+        assert!(!slo.is_paused("05_swap_stable"));
+        let event = slo.record("05_swap_stable", false, now).unwrap();
+        assert_eq!(
+            event.change,
+            SloChange::Paused {
+                success_rate: 0.0,
+                runs: 4,
+                window_secs: 60
+            }
+        );
+        assert!(slo.is_paused("05_swap_stable"));
+        assert!(!slo.is_paused("03_send_token"));
+        assert_eq!(slo.paused()[0].task, "05_swap_stable");
+
+        // Runs that left the window no longer count
+        let later = now + Duration::from_secs(61);
+        for _ in 0..2 {
+            slo.record("03_send_token", false, now);
+        }
+        for _ in 0..2 {
+            slo.record("03_send_token", true, later);
+        }
+        assert_eq!(slo.record("03_send_token", false, later), None);
+        assert!(!slo.is_paused("03_send_token"));
+    }
+
+    #[test]
+    fn test_probe_resumes_on_success() {
+        let slo = TaskSlo::new(settings());
+        let now = Instant::now();
+        for _ in 0..4 {
+            slo.record("05_swap_stable", false, now);
+        }
+
+        assert_eq!(slo.take_probe(now), None);
+        let due = now + Duration::from_secs(10);
+        assert_eq!(slo.take_probe(due).as_deref(), Some("05_swap_stable"));
+        assert_eq!(slo.take_probe(due), None);
+
+        // A failed probe waits for the next one
+        assert_eq!(slo.record("05_swap_stable", false, due), None);
+        assert!(slo.is_paused("05_swap_stable"));
+        let next = due + Duration::from_secs(10);
+        assert_eq!(slo.take_probe(next).as_deref(), Some("05_swap_stable"));
+
+        let event = slo.record("05_swap_stable", true, next).unwrap();
+        assert!(matches!(event.change, SloChange::Resumed { .. }));
+        assert!(!slo.is_paused("05_swap_stable"));
+        assert_eq!(slo.events_since(DateTime::<Utc>::MIN_UTC).len(), 2);
+
+        // The window starts over, so one more failure does not pause it again
+        assert_eq!(slo.record("05_swap_stable", false, next), None);
+    }
+
+    #[test]
+    fn test_disabled_records_nothing() {
+        let slo = TaskSlo::new(SloSettings::default());
+        let now = Instant::now();
+        for _ in 0..100 {
+            assert_eq!(slo.record("05_swap_stable", false, now), None);
+        }
+        assert!(!slo.is_paused("05_swap_stable"));
+
+        slo.configure(settings());
+        for _ in 0..4 {
+            slo.record("05_swap_stable", false, now);
+        }
+        assert!(slo.is_paused("05_swap_stable"));
+        slo.configure(SloSettings::default());
+        assert!(!slo.is_paused("05_swap_stable"));
+        assert_eq!(slo.take_probe(now + Duration::from_secs(60)), None);
+    }
+}
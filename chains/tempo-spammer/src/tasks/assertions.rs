@@ -0,0 +1,280 @@
+//! Assertions - Post-task checks of on-chain state
+//!
+//! Tasks confirm their effects through [`TaskContext::assertions`] instead of
+//! hand-written `eth_call`s. Every check returns a [`Verification`] that the
+//! task attaches to its result with
+//! [`TaskResult::with_verification`](crate::tasks::TaskResult::with_verification), so the
+//! `details` column of `task_metrics` records whether the chain matched and
+//! `db stats` counts mismatching runs per task.
+//!
+//! A check never fails the task: an RPC error is recorded as a failed check
+//! carrying the error as its actual value. Whether a mismatch makes the
+//! result unsuccessful is up to the task.
+//!
+//! ```rust,ignore
+//! let checks = ctx.assertions();
+//! let before = checks.balance(token, recipient).await?;
+//! // send the transfer and wait for the receipt...
+//! let result = result
+//!     .with_verification(
+//!         checks
+//!             .assert_balance_delta(token, recipient, before, I256::try_from(amount)?)
+//!             .await,
+//!     )
+//!     .with_verification(checks.assert_event_emitted::<ITIP20::Transfer>(
+//!         receipt.inner.logs(),
+//!         token,
+//!         |event| event.to == recipient,
+//!     ));
+//! ```
+
+use crate::tasks::TaskContext;
+use crate::tasks::tempo_tokens::TempoTokens;
+use alloy::providers::Provider;
+use alloy::rpc::types::{Log, TransactionRequest};
+use alloy_primitives::{Address, I256, U256};
+use alloy_sol_types::{SolCall, SolEvent, sol};
+use anyhow::Result;
+use core_logic::traits::Verification;
+
+sol! {
+    interface IOwnable721 {
+        function ownerOf(uint256 tokenId) view returns (address);
+    }
+}
+
+/// State checks bound to one task's client, see the [module docs](self)
+#[derive(Debug, Clone, Copy)]
+pub struct Assertions<'a> {
+    ctx: &'a TaskContext,
+}
+
+impl<'a> Assertions<'a> {
+    pub(crate) fn new(ctx: &'a TaskContext) -> Self {
+        Self { ctx }
+    }
+
+    /// `holder`'s balance of TIP-20/ERC-20 `token`, the baseline for
+    /// [`assert_balance_delta`](Self::assert_balance_delta)
+    pub async fn balance(&self, token: Address, holder: Address) -> Result<U256> {
+        TempoTokens::get_token_balance(&self.ctx.client, token, holder).await
+    }
+
+    /// Raw value of storage `slot` of `contract`, the baseline for
+    /// [`assert_storage_changed`](Self::assert_storage_changed)
+    pub async fn storage(&self, contract: Address, slot: U256) -> Result<U256> {
+        Ok(self
+            .ctx
+            .client
+            .provider
+            .get_storage_at(contract, slot)
+            .await?)
+    }
+
+    /// Checks that `holder`'s `token` balance moved by exactly `delta` since `before`
+    pub async fn assert_balance_delta(
+        &self,
+        token: Address,
+        holder: Address,
+        before: U256,
+        delta: I256,
+    ) -> Verification {
+        let target = format!("{}@{}", holder, token);
+        // Two's complement difference, negative when the balance fell
+        let actual = self
+            .balance(token, holder)
+            .await
+            .map(|after| I256::from_raw(after.wrapping_sub(before)));
+        verification("balance_delta", target, delta, actual)
+    }
+
+    /// Checks that `emitter` logged at least one `E` accepted by `matches`
+    pub fn assert_event_emitted<E: SolEvent>(
+        &self,
+        logs: &[Log],
+        emitter: Address,
+        matches: impl Fn(&E) -> bool,
+    ) -> Verification {
+        let emitted = logs
+            .iter()
+            .filter(|log| log.address() == emitter)
+            .filter_map(|log| E::decode_raw_log(log.topics(), &log.data().data).ok())
+            .filter(|event| matches(event))
+            .count();
+        Verification {
+            check: "event_emitted".to_string(),
+            target: format!("{} {}", emitter, E::SIGNATURE),
+            expected: "at least 1".to_string(),
+            actual: emitted.to_string(),
+            passed: emitted > 0,
+        }
+    }
+
+    /// Checks that storage `slot` of `contract` no longer holds `before`
+    pub async fn assert_storage_changed(
+        &self,
+        contract: Address,
+        slot: U256,
+        before: U256,
+    ) -> Verification {
+        let target = format!("{}[{:#x}]", contract, slot);
+        match self.storage(contract, slot).await {
+            Ok(after) => Verification {
+                check: "storage_changed".to_string(),
+                target,
+                expected: format!("not {:#x}", before),
+                actual: format!("{:#x}", after),
+                passed: after != before,
+            },
+            Err(e) => failed("storage_changed", target, format!("not {:#x}", before), e),
+        }
+    }
+
+    /// Checks that ERC-721 `token_id` of `contract` belongs to `owner`
+    pub async fn assert_owner_of(
+        &self,
+        contract: Address,
+        token_id: U256,
+        owner: Address,
+    ) -> Verification {
+        let target = format!("{}#{}", contract, token_id);
+        let call = TransactionRequest::default().to(contract).input(
+            IOwnable721::ownerOfCall { tokenId: token_id }
+                .abi_encode()
+                .into(),
+        );
+        let actual = match self.ctx.client.provider.call(call).await {
+            Ok(data) => IOwnable721::ownerOfCall::abi_decode_returns(&data).map_err(Into::into),
+            Err(e) => Err(anyhow::Error::from(e)),
+        };
+        verification("owner_of", target, owner, actual)
+    }
+}
+
+/// Passes when the value read equals `expected`
+fn verification<T: PartialEq + std::fmt::Display>(
+    check: &str,
+    target: String,
+    expected: T,
+    actual: Result<T>,
+) -> Verification {
+    match actual {
+        Ok(actual) => Verification {
+            check: check.to_string(),
+            target,
+            passed: actual == expected,
+            expected: expected.to_string(),
+            actual: actual.to_string(),
+        },
+        Err(e) => failed(check, target, expected.to_string(), e),
+    }
+}
+
+/// A check whose value could not be read
+fn failed(check: &str, target: String, expected: String, error: anyhow::Error) -> Verification {
+    Verification {
+        check: check.to_string(),
+        target,
+        expected,
+        actual: format!("error: {:#}", error),
+        passed: false,
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use crate::testing::TaskHarness;
+    use alloy_primitives::{Bytes, LogData};
+
+    sol! {
+        event Transfer(address indexed from, address indexed to, uint256 indexed tokenId);
+    }
+
+    #[tokio::test]
+    async fn test_balance_delta_and_storage() {
+        let harness = TaskHarness::new();
+        let ctx = harness.context();
+        let checks = ctx.assertions();
+        let (token, holder) = (Address::repeat_byte(0x20), Address::repeat_byte(1));
+
+        harness
+            .mock
+            .on_call(token, "70a08231", U256::from(70).to_be_bytes_vec());
+        let before = U256::from(100);
+        let spent = checks
+            .assert_balance_delta(token, holder, before, I256::try_from(-30).unwrap())
+            .await;
+        assert!(spent.passed, "{:?}", spent);
+        assert_eq!(spent.actual, "-30");
+
+        let wrong = checks
+            .assert_balance_delta(token, holder, before, I256::try_from(-20).unwrap())
+            .await;
+        assert!(!wrong.passed);
+        assert_eq!(
+            (wrong.expected.as_str(), wrong.actual.as_str()),
+            ("-20", "-30")
+        );
+
+        let slot = U256::from(3);
+        let before = checks.storage(token, slot).await.unwrap();
+        assert!(
+            !checks
+                .assert_storage_changed(token, slot, before)
+                .await
+                .passed
+        );
+        harness.mock.set_storage(token, slot, U256::from(7));
+        let changed = checks.assert_storage_changed(token, slot, before).await;
+        assert!(changed.passed);
+        assert_eq!(changed.actual, "0x7");
+    }
+
+    #[tokio::test]
+    async fn test_owner_of_and_events() {
+        let harness = TaskHarness::new();
+        let ctx = harness.context();
+        let checks = ctx.assertions();
+        let nft = Address::repeat_byte(0x30);
+        let owner = ctx.address();
+
+        harness
+            .mock
+            .on_call(nft, "6352211e", owner.into_word().to_vec());
+        let owned = checks.assert_owner_of(nft, U256::from(9), owner).await;
+        assert!(owned.passed, "{:?}", owned);
+        assert_eq!(owned.target, format!("{}#9", nft));
+
+        harness.mock.on_call_revert(nft, "6352211e", Bytes::new());
+        let unreadable = checks.assert_owner_of(nft, U256::from(9), owner).await;
+        assert!(!unreadable.passed);
+        assert!(
+            unreadable.actual.starts_with("error:"),
+            "{}",
+            unreadable.actual
+        );
+
+        let log = |emitter: Address, to: Address| Log {
+            inner: alloy_primitives::Log {
+                address: emitter,
+                data: LogData::new_unchecked(
+                    vec![
+                        Transfer::SIGNATURE_HASH,
+                        Address::ZERO.into_word(),
+                        to.into_word(),
+                        U256::from(9).into(),
+                    ],
+                    Bytes::new(),
+                ),
+            },
+            ..Default::default()
+        };
+        let logs = [log(Address::repeat_byte(2), owner), log(nft, owner)];
+        let emitted = checks.assert_event_emitted::<Transfer>(&logs, nft, |e| e.to == owner);
+        assert!(emitted.passed);
+        assert_eq!(emitted.actual, "1");
+        let missing = checks.assert_event_emitted::<Transfer>(&logs[..1], nft, |e| e.to == owner);
+        assert!(!missing.passed);
+    }
+}
@@ -32,6 +32,7 @@ impl TempoTask for CheckNativeBalanceTask {
             success: true,
             message: "Diagnostics complete".to_string(),
             tx_hash: None,
+            ..Default::default()
         })
     }
 }
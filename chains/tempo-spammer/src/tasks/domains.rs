@@ -0,0 +1,208 @@
+//! Domains - Shared steps of the domain management tasks (15, 62-65)
+//!
+//! `15_mint_domain` registers `.tempo` names with the Infinity Name Service
+//! and records them in the `domains` table. The follow-up tasks pick one of
+//! the wallet's recorded names instead of registering another:
+//! `62_domain_set_resolver`, `63_domain_set_addr`, `64_domain_transfer` (to
+//! another pool wallet) and `65_domain_renew`. The service is addressed
+//! ENS-style: records by namehash, ownership as an ERC-721 token of the
+//! registrar, all at `[contracts] name_service`.
+//!
+//! A record is rewritten only once the transaction's receipt succeeded, so
+//! the table follows what the chain holds.
+
+use crate::tasks::TaskContext;
+use alloy::rpc::types::{Log, TransactionReceipt};
+use alloy_primitives::{Address, B256, TxHash, U256, keccak256};
+use alloy_sol_types::{SolEvent, sol};
+use anyhow::{Context, Result};
+use core_logic::database::{DatabaseManager, DomainRecord};
+use rand::seq::SliceRandom;
+use std::sync::Arc;
+use std::time::Duration;
+
+/// Top-level domain every registered name ends in
+pub const TLD: &str = "tempo";
+
+sol! {
+    interface IDomainService {
+        function setResolver(bytes32 node, address resolver);
+        function setAddr(bytes32 node, address addr);
+        function setText(bytes32 node, string key, string value);
+        function transferFrom(address from, address to, uint256 tokenId);
+        function renew(string name, uint256 duration);
+
+        event Transfer(address indexed from, address indexed to, uint256 indexed tokenId);
+    }
+}
+
+const RECEIPT_TIMEOUT: Duration = Duration::from_secs(60);
+const RECEIPT_POLL: Duration = Duration::from_secs(1);
+
+/// ENS namehash of a full name such as `abc.tempo`
+pub fn namehash(name: &str) -> B256 {
+    if name.is_empty() {
+        return B256::ZERO;
+    }
+    let mut hash = B256::ZERO;
+    for label in name.split('.').rev() {
+        let label_hash = keccak256(label.as_bytes());
+        hash = keccak256([hash.as_slice(), label_hash.as_slice()].concat());
+    }
+    hash
+}
+
+/// The database, failing when the run has none
+pub fn database(ctx: &TaskContext) -> Result<&Arc<DatabaseManager>> {
+    ctx.db
+        .as_ref()
+        .context("Registered domains are tracked in the database, which this run does not have")
+}
+
+/// Record of a name the wallet just registered
+///
+/// The token id comes from the registrar's ERC-721 `Transfer` to the wallet;
+/// without one it falls back to the labelhash, the id ENS registrars use.
+pub fn registration(ctx: &TaskContext, label: &str, logs: &[Log], tx_hash: TxHash) -> DomainRecord {
+    let owner = ctx.address();
+    let token_id = logs
+        .iter()
+        .filter(|log| log.address() == ctx.contracts().name_service && log.topics().len() == 4)
+        .filter_map(|log| {
+            IDomainService::Transfer::decode_raw_log(log.topics(), &log.data().data).ok()
+        })
+        .find(|event| event.to == owner)
+        .map(|event| event.tokenId)
+        .unwrap_or_else(|| U256::from_be_bytes(keccak256(label.as_bytes()).0));
+    let name = format!("{}.{}", label, TLD);
+    DomainRecord {
+        chain_id: ctx.config.chain_id as i64,
+        node: format!("{:?}", namehash(&name)),
+        name,
+        token_id: token_id.to_string(),
+        owner: owner.to_string(),
+        resolver: String::new(),
+        addr: String::new(),
+        tx_hash: format!("{:?}", tx_hash),
+        registered_at: chrono::Utc::now().timestamp(),
+        renewed_at: 0,
+    }
+}
+
+/// A random recorded domain of the wallet that `eligible` accepts
+pub async fn pick_domain(
+    ctx: &TaskContext,
+    eligible: impl Fn(&DomainRecord) -> bool,
+) -> Result<Option<DomainRecord>> {
+    let domains = database(ctx)?
+        .get_domains(ctx.config.chain_id, &ctx.address().to_string())
+        .await?;
+    let candidates: Vec<_> = domains.into_iter().filter(|d| eligible(d)).collect();
+    Ok(candidates.choose(&mut rand::thread_rng()).cloned())
+}
+
+/// Stores the updated record of a domain
+pub async fn update(ctx: &TaskContext, domain: &DomainRecord) -> Result<()> {
+    database(ctx)?.log_domain(domain).await
+}
+
+/// Namehash of a recorded domain
+pub fn node(domain: &DomainRecord) -> Result<B256> {
+    domain
+        .node
+        .parse()
+        .with_context(|| format!("Bad node '{}' of {}", domain.node, domain.name))
+}
+
+/// The result when the wallet has no recorded domain to work on
+pub fn none_left(what: &str) -> crate::tasks::TaskResult {
+    crate::tasks::TaskResult {
+        success: false,
+        message: format!(
+            "No registered domain {}, register one with 15_mint_domain",
+            what
+        ),
+        ..Default::default()
+    }
+}
+
+/// Waits for the receipt of a name service transaction
+pub async fn confirm(ctx: &TaskContext, tx_hash: TxHash) -> Option<TransactionReceipt> {
+    ctx.wait_for_receipt(tx_hash, RECEIPT_TIMEOUT, RECEIPT_POLL)
+        .await
+}
+
+#[cfg(test)]
+pub(crate) fn test_domain(owner: Address, label: &str, registered_at: i64) -> DomainRecord {
+    let name = format!("{}.{}", label, TLD);
+    DomainRecord {
+        chain_id: crate::testing::TEST_CHAIN_ID as i64,
+        node: format!("{:?}", namehash(&name)),
+        name,
+        token_id: "77".to_string(),
+        owner: owner.to_string(),
+        resolver: String::new(),
+        addr: String::new(),
+        tx_hash: format!("{:?}", B256::ZERO),
+        registered_at,
+        renewed_at: 0,
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use crate::testing::TaskHarness;
+    use alloy_primitives::{Bytes, LogData};
+
+    #[test]
+    fn test_namehash_matches_ens() {
+        assert_eq!(namehash(""), B256::ZERO);
+        // namehash("eth") as specified in EIP-137
+        assert_eq!(
+            namehash("eth"),
+            "0x93cdeb708b7545dc668eb9280176169d1c33cfd8ed6f04690a0bcc88a93fc4ae"
+                .parse::<B256>()
+                .unwrap()
+        );
+    }
+
+    #[tokio::test]
+    async fn test_registration_takes_token_id_from_transfer() {
+        let harness = TaskHarness::new();
+        let ctx = harness.context();
+        let wallet = ctx.address();
+        let transfer = |to: Address, token_id: u64| Log {
+            inner: alloy_primitives::Log {
+                address: ctx.contracts().name_service,
+                data: LogData::new_unchecked(
+                    vec![
+                        IDomainService::Transfer::SIGNATURE_HASH,
+                        Address::ZERO.into_word(),
+                        to.into_word(),
+                        U256::from(token_id).into(),
+                    ],
+                    Bytes::new(),
+                ),
+            },
+            ..Default::default()
+        };
+
+        let record = registration(
+            &ctx,
+            "abc",
+            &[transfer(Address::repeat_byte(1), 5), transfer(wallet, 9)],
+            TxHash::ZERO,
+        );
+        assert_eq!(record.name, "abc.tempo");
+        assert_eq!(record.token_id, "9");
+        assert_eq!(record.owner, wallet.to_string());
+        assert_eq!(node(&record).unwrap(), namehash("abc.tempo"));
+
+        let record = registration(&ctx, "abc", &[], TxHash::ZERO);
+        assert_eq!(
+            record.token_id,
+            U256::from_be_bytes(keccak256("abc").0).to_string()
+        );
+    }
+}
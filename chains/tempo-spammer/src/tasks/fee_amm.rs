@@ -0,0 +1,475 @@
+//! Fee AMM Client
+//!
+//! Typed client for the Stablecoin DEX (`[contracts] stablecoin_dex`), the
+//! orderbook the swap and liquidity tasks trade system tokens on. Not to be
+//! confused with the fee manager (`[contracts] fee_manager`), which only
+//! converts the tokens fees are paid in.
+//!
+//! - **Quotes**: [`FeeAmm::get_amount_out`] and [`FeeAmm::get_amount_in`]
+//!   ask the book what a swap would fill at right now
+//! - **Swaps**: [`FeeAmm::quote`] turns a quote and a slippage tolerance in
+//!   basis points into a [`SwapQuote`] that [`FeeAmm::swap`] sends with its
+//!   minimum output, so a book that moves in between reverts the swap
+//! - **Liquidity**: [`FeeAmm::place`] adds an order to the book,
+//!   [`FeeAmm::withdraw`] takes the DEX balance back out
+//! - **State**: DEX balances, tick liquidity and order ids
+//!
+//! A swap that reverts because the book moved past its minimum output is
+//! told apart with [`FeeAmm::slippage_exceeded`] and reported as
+//! [`SlippageExceeded`], like a [`BudgetExceeded`](crate::budget::BudgetExceeded).
+//!
+//! Writes return a [`TxBuilder`]; the `*_request` variants return unsigned
+//! transactions for tasks that pipeline their own nonces.
+//!
+//! ```rust,ignore
+//! let amm = ctx.fee_amm();
+//! let quote = amm.quote(path_usd, alpha_usd, amount, 100).await?;
+//! let outcome = amm.swap(&quote).send_and_confirm().await?;
+//! ```
+
+use crate::TempoClient;
+use crate::revert::{self, StablecoinDEX};
+use crate::tasks::TaskResult;
+use crate::tasks::tip20::call_view;
+use crate::tx_builder::TxBuilder;
+use alloy::primitives::Address;
+use alloy::rpc::types::{TransactionReceipt, TransactionRequest};
+use alloy_sol_types::{SolCall, SolError, SolEvent, sol};
+use anyhow::Result;
+use std::fmt;
+
+sol! {
+    #[allow(clippy::too_many_arguments)]
+    interface IStablecoinDex {
+        function place(address token, uint128 amount, bool isBid, int16 tick) returns (uint128 orderId);
+        function placeFlip(address token, uint128 amount, bool isBid, int16 tick, int16 flipTick) returns (uint128 orderId);
+        function cancel(uint128 orderId);
+        function swapExactAmountIn(address tokenIn, address tokenOut, uint128 amountIn, uint128 minAmountOut) returns (uint128 amountOut);
+        function swapExactAmountOut(address tokenIn, address tokenOut, uint128 amountOut, uint128 maxAmountIn) returns (uint128 amountIn);
+        function quoteSwapExactAmountIn(address tokenIn, address tokenOut, uint128 amountIn) view returns (uint128 amountOut);
+        function quoteSwapExactAmountOut(address tokenIn, address tokenOut, uint128 amountOut) view returns (uint128 amountIn);
+        function balanceOf(address user, address token) view returns (uint128);
+        function withdraw(address token, uint128 amount);
+        function getTickLevel(address base, int16 tick, bool isBid) view returns (uint128 head, uint128 tail, uint128 totalLiquidity);
+        function nextOrderId() view returns (uint128);
+
+        event OrderPlaced(uint128 indexed orderId, address indexed maker, address indexed token, uint128 amount, bool isBid, int16 tick, bool isFlipOrder, int16 flipTick);
+    }
+}
+
+/// Basis points in a whole
+pub const BPS: u32 = 10_000;
+
+/// Least output accepted from a swap quoted at `amount_out`
+pub fn min_amount_out(amount_out: u128, slippage_bps: u32) -> u128 {
+    amount_out.saturating_mul(u128::from(BPS.saturating_sub(slippage_bps))) / u128::from(BPS)
+}
+
+/// Most input accepted by a swap quoted at `amount_in`
+pub fn max_amount_in(amount_in: u128, slippage_bps: u32) -> u128 {
+    amount_in.saturating_mul(u128::from(BPS.saturating_add(slippage_bps))) / u128::from(BPS)
+}
+
+/// Exact-input swap priced by the book, with its slippage floor
+#[derive(Debug, Clone, Copy, PartialEq, Eq)]
+pub struct SwapQuote {
+    pub token_in: Address,
+    pub token_out: Address,
+    pub amount_in: u128,
+    /// Output the book quoted
+    pub amount_out: u128,
+    /// Output below which the swap reverts
+    pub min_amount_out: u128,
+}
+
+/// A swap that failed because the book moved past its quote's minimum output
+///
+/// Other spammers trading the same pair cause this, not the task, so the
+/// swap tasks return it as a result of its own rather than an error.
+#[derive(Debug, Clone, Copy, PartialEq, Eq)]
+pub struct SlippageExceeded {
+    pub quote: SwapQuote,
+    /// Output the book quoted after the swap failed, if it still answered
+    pub amount_out_now: Option<u128>,
+}
+
+impl fmt::Display for SlippageExceeded {
+    fn fmt(&self, f: &mut fmt::Formatter<'_>) -> fmt::Result {
+        write!(
+            f,
+            "Slippage exceeded: {} -> {} quoted {} for {}, minimum {}",
+            self.quote.token_in,
+            self.quote.token_out,
+            self.quote.amount_out,
+            self.quote.amount_in,
+            self.quote.min_amount_out
+        )?;
+        if let Some(now) = self.amount_out_now {
+            write!(f, ", now {}", now)?;
+        }
+        Ok(())
+    }
+}
+
+impl From<SlippageExceeded> for TaskResult {
+    fn from(exceeded: SlippageExceeded) -> Self {
+        TaskResult {
+            success: false,
+            message: exceeded.to_string(),
+            tx_hash: None,
+            ..Default::default()
+        }
+        .with_detail("outcome", "slippage_exceeded")
+        .with_detail("quoted_out", exceeded.quote.amount_out.to_string())
+        .with_detail("min_out", exceeded.quote.min_amount_out.to_string())
+    }
+}
+
+/// Orders resting at one tick of a book
+#[derive(Debug, Clone, Copy, Default, PartialEq, Eq)]
+pub struct TickLiquidity {
+    /// Oldest order id, filled first
+    pub head: u128,
+    /// Newest order id
+    pub tail: u128,
+    pub total: u128,
+}
+
+/// The Stablecoin DEX seen from one client's wallet
+#[derive(Debug, Clone, Copy)]
+pub struct FeeAmm<'a> {
+    client: &'a TempoClient,
+    address: Address,
+}
+
+impl<'a> FeeAmm<'a> {
+    /// DEX at `address`, read and sent to with `client`
+    pub fn new(client: &'a TempoClient, address: Address) -> Self {
+        Self { client, address }
+    }
+
+    /// Address of the DEX
+    pub fn address(&self) -> Address {
+        self.address
+    }
+
+    /// Output a swap of `amount_in` would fill at now
+    pub async fn get_amount_out(
+        &self,
+        token_in: Address,
+        token_out: Address,
+        amount_in: u128,
+    ) -> Result<u128> {
+        self.read(IStablecoinDex::quoteSwapExactAmountInCall {
+            tokenIn: token_in,
+            tokenOut: token_out,
+            amountIn: amount_in,
+        })
+        .await
+    }
+
+    /// Input a swap for `amount_out` would take now
+    pub async fn get_amount_in(
+        &self,
+        token_in: Address,
+        token_out: Address,
+        amount_out: u128,
+    ) -> Result<u128> {
+        self.read(IStablecoinDex::quoteSwapExactAmountOutCall {
+            tokenIn: token_in,
+            tokenOut: token_out,
+            amountOut: amount_out,
+        })
+        .await
+    }
+
+    /// Quotes swapping `amount_in`, accepting `slippage_bps` less than the quote
+    pub async fn quote(
+        &self,
+        token_in: Address,
+        token_out: Address,
+        amount_in: u128,
+        slippage_bps: u32,
+    ) -> Result<SwapQuote> {
+        let amount_out = self.get_amount_out(token_in, token_out, amount_in).await?;
+        Ok(SwapQuote {
+            token_in,
+            token_out,
+            amount_in,
+            amount_out,
+            min_amount_out: min_amount_out(amount_out, slippage_bps),
+        })
+    }
+
+    /// Swaps as quoted, reverting below the quote's minimum output
+    pub fn swap(&self, quote: &SwapQuote) -> TxBuilder<'a> {
+        self.swap_exact_amount_in(
+            quote.token_in,
+            quote.token_out,
+            quote.amount_in,
+            quote.min_amount_out,
+        )
+    }
+
+    /// Unsigned [`Self::swap`] for callers that manage nonces themselves
+    pub fn swap_request(&self, quote: &SwapQuote) -> TransactionRequest {
+        self.request(&IStablecoinDex::swapExactAmountInCall {
+            tokenIn: quote.token_in,
+            tokenOut: quote.token_out,
+            amountIn: quote.amount_in,
+            minAmountOut: quote.min_amount_out,
+        })
+    }
+
+    pub fn swap_exact_amount_in(
+        &self,
+        token_in: Address,
+        token_out: Address,
+        amount_in: u128,
+        min_amount_out: u128,
+    ) -> TxBuilder<'a> {
+        self.send(&IStablecoinDex::swapExactAmountInCall {
+            tokenIn: token_in,
+            tokenOut: token_out,
+            amountIn: amount_in,
+            minAmountOut: min_amount_out,
+        })
+    }
+
+    pub fn swap_exact_amount_out(
+        &self,
+        token_in: Address,
+        token_out: Address,
+        amount_out: u128,
+        max_amount_in: u128,
+    ) -> TxBuilder<'a> {
+        self.send(&IStablecoinDex::swapExactAmountOutCall {
+            tokenIn: token_in,
+            tokenOut: token_out,
+            amountOut: amount_out,
+            maxAmountIn: max_amount_in,
+        })
+    }
+
+    /// Adds liquidity: a bid buys `token` with its quote token, an ask sells it
+    pub fn place(&self, token: Address, amount: u128, is_bid: bool, tick: i16) -> TxBuilder<'a> {
+        self.send(&IStablecoinDex::placeCall {
+            token,
+            amount,
+            isBid: is_bid,
+            tick,
+        })
+    }
+
+    /// Order that places its opposite at `flip_tick` once filled
+    pub fn place_flip(
+        &self,
+        token: Address,
+        amount: u128,
+        is_bid: bool,
+        tick: i16,
+        flip_tick: i16,
+    ) -> TxBuilder<'a> {
+        self.send(&IStablecoinDex::placeFlipCall {
+            token,
+            amount,
+            isBid: is_bid,
+            tick,
+            flipTick: flip_tick,
+        })
+    }
+
+    pub fn cancel(&self, order_id: u128) -> TxBuilder<'a> {
+        self.send(&IStablecoinDex::cancelCall { orderId: order_id })
+    }
+
+    /// Removes liquidity: moves `amount` of the DEX balance back to the wallet
+    pub fn withdraw(&self, token: Address, amount: u128) -> TxBuilder<'a> {
+        self.send(&IStablecoinDex::withdrawCall { token, amount })
+    }
+
+    /// Balance `user` holds inside the DEX from filled and cancelled orders
+    pub async fn balance_of(&self, user: Address, token: Address) -> Result<u128> {
+        self.read(IStablecoinDex::balanceOfCall { user, token })
+            .await
+    }
+
+    /// Id the next placed order gets
+    pub async fn next_order_id(&self) -> Result<u128> {
+        self.read(IStablecoinDex::nextOrderIdCall {}).await
+    }
+
+    pub async fn tick_liquidity(
+        &self,
+        base: Address,
+        tick: i16,
+        is_bid: bool,
+    ) -> Result<TickLiquidity> {
+        let level = self
+            .read(IStablecoinDex::getTickLevelCall {
+                base,
+                tick,
+                isBid: is_bid,
+            })
+            .await?;
+        Ok(TickLiquidity {
+            head: level.head,
+            tail: level.tail,
+            total: level.totalLiquidity,
+        })
+    }
+
+    /// Whether a swap sent for `quote` failed on slippage
+    ///
+    /// `error` is the send error, `None` for a swap that was mined and
+    /// reverted. A send the DEX rejected with `InsufficientOutput` is
+    /// slippage; otherwise the book is quoted again and the swap counts as
+    /// slippage when it no longer fills the minimum.
+    pub async fn slippage_exceeded(
+        &self,
+        quote: &SwapQuote,
+        error: Option<&anyhow::Error>,
+    ) -> Option<SlippageExceeded> {
+        let below_minimum = error.is_some_and(|e| {
+            revert::revert_data(e.as_ref())
+                .is_some_and(|data| data.starts_with(&StablecoinDEX::InsufficientOutput::SELECTOR))
+        });
+        let amount_out_now = self
+            .get_amount_out(quote.token_in, quote.token_out, quote.amount_in)
+            .await
+            .ok();
+
+        (below_minimum || amount_out_now.is_some_and(|now| now < quote.min_amount_out)).then_some(
+            SlippageExceeded {
+                quote: *quote,
+                amount_out_now,
+            },
+        )
+    }
+
+    /// Id of the first order this DEX logged placing in `receipt`
+    pub fn placed_order_id(&self, receipt: &TransactionReceipt) -> Option<u128> {
+        receipt
+            .logs()
+            .iter()
+            .filter(|log| log.address() == self.address)
+            .find_map(|log| {
+                IStablecoinDex::OrderPlaced::decode_raw_log(log.topics(), &log.data().data).ok()
+            })
+            .map(|event| event.orderId)
+    }
+
+    fn send<C: SolCall>(&self, call: &C) -> TxBuilder<'a> {
+        self.client.tx().to(self.address).call(call)
+    }
+
+    fn request<C: SolCall>(&self, call: &C) -> TransactionRequest {
+        TransactionRequest::default()
+            .to(self.address)
+            .input(call.abi_encode().into())
+    }
+
+    async fn read<C: SolCall>(&self, call: C) -> Result<C::Return> {
+        call_view(self.client, self.address, call).await
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use crate::testing::MockTempoClient;
+    use alloy::primitives::{B256, Bytes, U256, address};
+
+    const DEX: Address = address!("DEc0000000000000000000000000000000000000");
+    const PATH_USD: Address = address!("20C0000000000000000000000000000000000000");
+    const ALPHA_USD: Address = address!("20c0000000000000000000000000000000000001");
+
+    #[test]
+    fn test_slippage_bounds() {
+        assert_eq!(min_amount_out(1_000_000, 100), 990_000);
+        assert_eq!(min_amount_out(1_000_000, 0), 1_000_000);
+        assert_eq!(min_amount_out(1_000_000, 20_000), 0);
+        assert_eq!(max_amount_in(1_000_000, 250), 1_025_000);
+    }
+
+    #[tokio::test]
+    async fn test_swap_sends_quoted_minimum() {
+        let mock = MockTempoClient::default();
+        mock.on_call(
+            DEX,
+            "",
+            Bytes::from(U256::from(2_000_000u64).to_be_bytes_vec()),
+        );
+        let client = mock.client();
+        let amm = FeeAmm::new(&client, DEX);
+
+        let quote = amm.quote(PATH_USD, ALPHA_USD, 2_000_000, 50).await.unwrap();
+        assert_eq!(quote.amount_out, 2_000_000);
+        assert_eq!(quote.min_amount_out, 1_990_000);
+
+        amm.swap(&quote).send().await.unwrap();
+        let call =
+            IStablecoinDex::swapExactAmountInCall::abi_decode(&mock.sent()[0].input).unwrap();
+        assert_eq!(call.tokenIn, PATH_USD);
+        assert_eq!(call.tokenOut, ALPHA_USD);
+        assert_eq!(call.minAmountOut, 1_990_000);
+    }
+
+    #[tokio::test]
+    async fn test_slippage_exceeded_on_revert_or_moved_price() {
+        let mock = MockTempoClient::default();
+        mock.on_call(DEX, "", U256::from(2_000_000u64).to_be_bytes_vec());
+        let client = mock.client();
+        let amm = FeeAmm::new(&client, DEX);
+        let quote = amm
+            .quote(PATH_USD, ALPHA_USD, 2_000_000, 100)
+            .await
+            .unwrap();
+
+        assert!(amm.slippage_exceeded(&quote, None).await.is_none());
+
+        let selector = alloy::hex::encode(IStablecoinDex::swapExactAmountInCall::SELECTOR);
+        mock.on_call_revert(
+            DEX,
+            &selector,
+            StablecoinDEX::InsufficientOutput {}.abi_encode(),
+        );
+        let error = amm.swap(&quote).send().await.unwrap_err();
+        assert!(amm.slippage_exceeded(&quote, Some(&error)).await.is_some());
+
+        mock.on_call(DEX, "", U256::from(1_900_000u64).to_be_bytes_vec());
+        let exceeded = amm.slippage_exceeded(&quote, None).await.unwrap();
+        assert_eq!(exceeded.amount_out_now, Some(1_900_000));
+
+        let result = TaskResult::from(exceeded);
+        assert!(!result.success);
+        assert_eq!(result.details["outcome"], "slippage_exceeded");
+    }
+
+    #[tokio::test]
+    async fn test_placed_order_id_from_receipt() {
+        let mock = MockTempoClient::default();
+        let client = mock.client();
+        let amm = FeeAmm::new(&client, DEX);
+
+        let event = IStablecoinDex::OrderPlaced {
+            orderId: 42,
+            maker: mock.address(),
+            token: ALPHA_USD,
+            amount: 1_000_000,
+            isBid: true,
+            tick: 0,
+            isFlipOrder: false,
+            flipTick: 0,
+        };
+        let topics: Vec<B256> = event.encode_topics().into_iter().map(|t| t.0).collect();
+        mock.push_receipt_log(DEX, topics, event.encode_data());
+
+        let outcome = amm
+            .place(ALPHA_USD, 1_000_000, true, 0)
+            .send_and_confirm()
+            .await
+            .unwrap();
+        assert_eq!(amm.placed_order_id(&outcome.receipt), Some(42));
+    }
+}
@@ -47,6 +47,7 @@
 //!             success: true,
 //!             message: "Task completed".to_string(),
 //!             tx_hash: None,
+//!             ..Default::default()
 //!         })
 //!     }
 //! }
@@ -61,14 +62,20 @@
 //! - **db**: Optional [`DatabaseManager`] for persistence
 //! - **gas_manager**: [`GasManager`] for fee operations
 //! - **timeout**: Maximum execution duration
+//! - **deadline**: When the runner aborts the run. [`TaskContext::remaining`],
+//!   [`TaskContext::cancelled`] and [`TaskContext::wait_for_receipt`] let long
+//!   waits end [`WRAP_UP`] early, so the task reports what it has
 //!
 //! # Gas Management
 //!
 //! The [`GasManager`] provides utilities for:
 //!
 //! - Estimating gas prices from the network
+//! - EIP-1559 fees from fee history, capped per [`GasConfig`]
 //! - Bumping fees by a percentage (for retries)
-//! - Future: EIP-1559 fee estimation
+//!
+//! The fee policy itself is [`core_logic::GasManager`], shared with the
+//! other EVM chains; this type applies it to a [`TempoClient`].
 //!
 //! # Utilities
 //!
@@ -76,6 +83,7 @@
 //!
 //! - [`get_random_address()`]: Gets random address from file or generates one
 //! - [`generate_random_shares()`]: Generates random share distributions
+//! - [`AmountSizer`]: Fits transfer and swap amounts to balances and DEX depth
 //! - [`load_proxies()`]: Loads proxy configuration from file
 //!
 //! # See Also
@@ -83,22 +91,39 @@
 //! - [Task Catalog](../../docs/TASK_CATALOG.md) - Complete task reference
 //! - [Task Development Guide](../../docs/TASK_DEVELOPMENT.md) - Creating new tasks
 
+use crate::access_list::AccessListEstimate;
+use crate::budget::{BudgetExceeded, SpendBudget, SpendReservation};
+use crate::capabilities::ChainCapabilities;
+use crate::chain_head::{BlockHead, ChainHead};
 use crate::client::TempoClient;
-use crate::config::TempoSpammerConfig;
-use alloy_primitives::{Address, U256};
+use crate::config::{SystemContracts, TempoSpammerConfig};
+use crate::consistency::{ConsistencyVerifier, StateCheck};
+use crate::contracts::HelperRegistry;
+use crate::idempotency;
+use crate::persona::Persona;
+use crate::proxy_session::{self, ProxySession, SessionRotation};
+use crate::revert::RevertDecoder;
+use crate::session_key::{SessionKeys, TempoSigner};
+use crate::transfers::{InFlightTransfer, TransferTracker};
+use alloy::rpc::types::TransactionRequest;
+use alloy_primitives::{Address, TxKind, U256};
 use anyhow::{Context, Result};
 use async_trait::async_trait;
 use core_logic::database::DatabaseManager;
+use core_logic::{Fees, GasConfig};
 use rand::Rng;
 use rand::prelude::SliceRandom;
-use std::collections::HashSet;
+use std::collections::{HashMap, HashSet};
 use std::fs;
 use std::path::Path;
 use std::str::FromStr;
-use std::sync::Arc;
+use std::sync::{Arc, Mutex};
 use std::time::Duration;
+use tempo_primitives::transaction::Call;
+use tokio::time::Instant;
 use url::Url;
 
+pub use assertions::Assertions;
 pub use core_logic::traits::TaskResult;
 
 /// Execution context provided to tasks
@@ -113,6 +138,14 @@ pub use core_logic::traits::TaskResult;
 /// - `db`: Optional database for persistence
 /// - `gas_manager`: Fee estimation utilities
 /// - `timeout`: Maximum execution time (default 180s)
+/// - `persona`: Behavior profile of the leased wallet, if personas are configured
+/// - `budget`: Daily spend caps checked before value-moving sends
+/// - `verifier`: Re-reads state changes through another route, if `[verify]` is enabled
+/// - `session_keys`: Access keys for Tempo transactions, if `[session_keys]` is enabled
+/// - `helpers`: Helper contract addresses checked at startup
+/// - `transfers`: In-flight transfers between pool wallets, if `[recipients]` is enabled
+/// - `chain_head`: Latest block shared by all workers, see [`TaskContext::head`]
+/// - `deadline`: When the runner aborts the task, see [`TaskContext::remaining`]
 ///
 /// # Example
 ///
@@ -148,6 +181,67 @@ pub struct TaskContext {
     pub gas_manager: Arc<GasManager>,
     /// Maximum task execution duration
     pub timeout: Duration,
+    /// Behavior profile of the wallet running the task
+    pub persona: Option<Arc<Persona>>,
+    /// Daily spend caps shared by all workers
+    pub budget: Option<Arc<SpendBudget>>,
+    /// Read-your-writes checker shared by all workers
+    pub verifier: Option<Arc<ConsistencyVerifier>>,
+    /// Session keys shared by all workers
+    pub session_keys: Option<Arc<SessionKeys>>,
+    /// Helper contracts resolved at startup
+    pub helpers: Option<Arc<HelperRegistry>>,
+    /// Pool recipients and their in-flight transfers, shared by all workers
+    pub transfers: Option<Arc<TransferTracker>>,
+    /// What the node supports, probed at startup
+    pub capabilities: Option<ChainCapabilities>,
+    /// Latest block polled for all workers, if `[chain_head]` is enabled
+    pub chain_head: Option<Arc<ChainHead>>,
+    /// When the run is aborted, `None` when nothing enforces `timeout`
+    pub deadline: Option<Instant>,
+}
+
+/// Time before the deadline a task gets to return what it has
+///
+/// [`TaskContext::cancelled`] fires this long before the runner aborts the
+/// task, and bounded waits stop here.
+pub const WRAP_UP: Duration = Duration::from_secs(2);
+
+/// Pause before a task resends after a nonce error
+pub const RETRY_PAUSE: Duration = Duration::from_millis(200);
+
+/// Longest [`TaskContext::receipt`] waits, shortened by the task's deadline
+pub const RECEIPT_TIMEOUT: Duration = Duration::from_secs(60);
+
+const RECEIPT_POLL: Duration = Duration::from_secs(1);
+
+/// A sent transaction whose receipt did not arrive in the task's time
+///
+/// Returned by [`TaskContext::receipt`]; the task returns it as its result
+/// so the hash is still reported.
+#[derive(Debug, Clone, Copy, PartialEq, Eq)]
+pub struct ReceiptTimeout {
+    pub tx_hash: alloy_primitives::TxHash,
+}
+
+impl std::fmt::Display for ReceiptTimeout {
+    fn fmt(&self, f: &mut std::fmt::Formatter<'_>) -> std::fmt::Result {
+        write!(f, "Sent {:?}, out of time before its receipt", self.tx_hash)
+    }
+}
+
+impl std::error::Error for ReceiptTimeout {}
+
+impl From<ReceiptTimeout> for TaskResult {
+    fn from(timeout: ReceiptTimeout) -> Self {
+        TaskResult {
+            success: false,
+            message: timeout.to_string(),
+            tx_hash: Some(format!("{:?}", timeout.tx_hash)),
+            ..Default::default()
+        }
+        .with_detail("outcome", "receipt_timeout")
+    }
 }
 
 impl TaskContext {
@@ -188,11 +282,540 @@ impl TaskContext {
             client,
             config,
             db,
-            gas_manager: Arc::new(GasManager),
+            gas_manager: Arc::new(GasManager::default()),
             timeout: Duration::from_secs(180),
+            persona: None,
+            budget: None,
+            verifier: None,
+            session_keys: None,
+            helpers: None,
+            transfers: None,
+            capabilities: None,
+            chain_head: None,
+            deadline: None,
+        }
+    }
+
+    /// Sets the wallet's persona
+    pub fn with_persona(mut self, persona: Option<Arc<Persona>>) -> Self {
+        self.persona = persona;
+        self
+    }
+
+    /// Sets the shared spend budget
+    pub fn with_budget(mut self, budget: Option<Arc<SpendBudget>>) -> Self {
+        self.budget = budget;
+        self
+    }
+
+    /// Sets the shared consistency verifier
+    pub fn with_verifier(mut self, verifier: Option<Arc<ConsistencyVerifier>>) -> Self {
+        self.verifier = verifier;
+        self
+    }
+
+    /// Sets the shared session keys
+    pub fn with_session_keys(mut self, session_keys: Option<Arc<SessionKeys>>) -> Self {
+        self.session_keys = session_keys;
+        self
+    }
+
+    /// Sets the helper contracts resolved at startup
+    pub fn with_helpers(mut self, helpers: Option<Arc<HelperRegistry>>) -> Self {
+        self.helpers = helpers;
+        self
+    }
+
+    /// Sets the shared tracker of transfers between pool wallets
+    pub fn with_transfers(mut self, transfers: Option<Arc<TransferTracker>>) -> Self {
+        self.transfers = transfers;
+        self
+    }
+
+    /// Sets what the startup probe found the node to support
+    pub fn with_capabilities(mut self, capabilities: Option<ChainCapabilities>) -> Self {
+        self.capabilities = capabilities;
+        self
+    }
+
+    /// Sets the shared chain head watcher
+    pub fn with_chain_head(mut self, chain_head: Option<Arc<ChainHead>>) -> Self {
+        self.chain_head = chain_head;
+        self
+    }
+
+    /// Starts the task's `timeout` now, as the runner enforcing it does
+    pub fn with_deadline(mut self, timeout: Duration) -> Self {
+        self.timeout = timeout;
+        self.deadline = Some(Instant::now() + timeout);
+        self
+    }
+
+    /// When the runner aborts the task, `None` when no deadline was set
+    pub fn deadline(&self) -> Option<Instant> {
+        self.deadline
+    }
+
+    /// Time left until the deadline, [`Duration::MAX`] without one
+    pub fn remaining(&self) -> Duration {
+        self.deadline.map_or(Duration::MAX, |deadline| {
+            deadline.saturating_duration_since(Instant::now())
+        })
+    }
+
+    /// `wanted`, cut short so a wait ends [`WRAP_UP`] before the deadline
+    ///
+    /// Use it for receipt waits and retry pauses, then report what happened
+    /// so far instead of being aborted mid-wait.
+    pub fn time_for(&self, wanted: Duration) -> Duration {
+        wanted.min(self.remaining().saturating_sub(WRAP_UP))
+    }
+
+    /// Resolves [`WRAP_UP`] before the deadline, never without one
+    ///
+    /// # Example
+    ///
+    /// ```rust,ignore
+    /// tokio::select! {
+    ///     receipt = pending.get_receipt() => { /* ... */ }
+    ///     _ = ctx.cancelled() => return Ok(TaskResult {
+    ///         success: false,
+    ///         message: format!("Sent {}, out of time before its receipt", tx_hash),
+    ///         tx_hash: Some(format!("{:?}", tx_hash)),
+    ///         ..Default::default()
+    ///     }),
+    /// }
+    /// ```
+    pub async fn cancelled(&self) {
+        match self.deadline {
+            Some(deadline) => {
+                let wrap_up = deadline.checked_sub(WRAP_UP).unwrap_or(deadline);
+                tokio::time::sleep_until(wrap_up).await;
+            }
+            None => std::future::pending().await,
+        }
+    }
+
+    /// Whether [`cancelled`](Self::cancelled) has resolved
+    pub fn is_cancelled(&self) -> bool {
+        self.deadline
+            .is_some_and(|deadline| Instant::now() + WRAP_UP >= deadline)
+    }
+
+    /// Sleeps `pause` before another attempt, `false` without time for one
+    ///
+    /// # Example
+    ///
+    /// ```rust,ignore
+    /// if attempt >= max_retries || !ctx.retry_after(Duration::from_millis(200)).await {
+    ///     return Err(e).context("Failed to send");
+    /// }
+    /// ```
+    pub async fn retry_after(&self, pause: Duration) -> bool {
+        if self.time_for(pause) < pause {
+            return false;
+        }
+        tokio::time::sleep(pause).await;
+        true
+    }
+
+    /// Polls for the receipt of `tx_hash` for up to [`time_for`](Self::time_for)`(timeout)`
+    ///
+    /// `None` when the transaction was not included in time; the task can
+    /// still report the hash.
+    pub async fn wait_for_receipt(
+        &self,
+        tx_hash: alloy_primitives::TxHash,
+        timeout: Duration,
+        poll: Duration,
+    ) -> Option<alloy::rpc::types::TransactionReceipt> {
+        crate::gas_usage::wait_for_receipt(
+            self.client.provider.as_ref(),
+            tx_hash,
+            self.time_for(timeout),
+            poll,
+        )
+        .await
+    }
+
+    /// Receipt of `tx_hash`, waiting at most [`RECEIPT_TIMEOUT`] and never past
+    /// the task's wrap-up
+    ///
+    /// # Example
+    ///
+    /// ```rust,ignore
+    /// let receipt = match ctx.receipt(tx_hash).await {
+    ///     Ok(receipt) => receipt,
+    ///     Err(timeout) => return Ok(timeout.into()),
+    /// };
+    /// ```
+    pub async fn receipt(
+        &self,
+        tx_hash: alloy_primitives::TxHash,
+    ) -> std::result::Result<alloy::rpc::types::TransactionReceipt, ReceiptTimeout> {
+        self.wait_for_receipt(tx_hash, RECEIPT_TIMEOUT, RECEIPT_POLL)
+            .await
+            .ok_or(ReceiptTimeout { tx_hash })
+    }
+
+    /// Result to return instead of repeating operation `key`, `None` to send
+    ///
+    /// See [`idempotency`](crate::idempotency) for choosing keys. Always
+    /// `None` without a database.
+    ///
+    /// # Example
+    ///
+    /// ```rust,ignore
+    /// let key = format!("02_claim_faucet:{:?}:{}", ctx.address(), idempotency::today());
+    /// if let Some(done) = ctx.already_done(&key).await? {
+    ///     return Ok(done);
+    /// }
+    /// ```
+    ///
+    /// # Errors
+    ///
+    /// Fails when the key or its transaction cannot be checked.
+    pub async fn already_done(&self, key: &str) -> Result<Option<TaskResult>> {
+        let Some(db) = &self.db else {
+            return Ok(None);
+        };
+        let prior =
+            idempotency::lookup(db, self.client.provider.as_ref(), self.config.chain_id, key)
+                .await?;
+        Ok(idempotency::already_done(key, prior))
+    }
+
+    /// Records that operation `key` was sent in `tx_hash`
+    ///
+    /// Call right after sending, before waiting for the receipt, so a run
+    /// aborted while waiting leaves the key behind.
+    pub async fn operation_sent(&self, key: &str, tx_hash: alloy_primitives::TxHash) {
+        self.record_operation(key, tx_hash, idempotency::SENT).await;
+    }
+
+    /// Records that operation `key` succeeded in `tx_hash`
+    pub async fn operation_done(&self, key: &str, tx_hash: alloy_primitives::TxHash) {
+        self.record_operation(key, tx_hash, idempotency::DONE).await;
+    }
+
+    async fn record_operation(&self, key: &str, tx_hash: alloy_primitives::TxHash, status: &str) {
+        let Some(db) = &self.db else {
+            return;
+        };
+        let recorded = idempotency::record(
+            db,
+            self.config.chain_id,
+            self.address(),
+            key,
+            tx_hash,
+            status,
+        )
+        .await;
+        if let Err(e) = recorded {
+            tracing::warn!("Failed to record operation {}: {:?}", key, e);
+        }
+    }
+
+    /// What the node supports, every feature when it was not probed
+    ///
+    /// # Example
+    ///
+    /// ```rust,ignore
+    /// if !ctx.capabilities().nonce_2d {
+    ///     anyhow::bail!("The node does not support 2D nonces");
+    /// }
+    /// ```
+    pub fn capabilities(&self) -> ChainCapabilities {
+        self.capabilities
+            .unwrap_or_else(|| ChainCapabilities::assumed(self.chain_id()))
+    }
+
+    /// Signer for Tempo (0x76) transactions
+    ///
+    /// With `[session_keys]` enabled this is the wallet's session key,
+    /// registered with a root-signed transaction on first use, so call it
+    /// before fetching the nonce. Falls back to the wallet key when the
+    /// session key cannot be registered.
+    pub async fn tempo_signer(&self) -> TempoSigner {
+        if let Some(session_keys) = &self.session_keys {
+            match session_keys
+                .signer_for(&self.client, &self.config.rpc_url)
+                .await
+            {
+                Ok(signer) => return signer,
+                Err(e) => tracing::warn!("Signing with the wallet key: {:#}", e),
+            }
+        }
+        TempoSigner::Root(self.client.signer.clone())
+    }
+
+    /// Schedules a check that other routes serve the state this task just wrote
+    ///
+    /// Reads the current value through the task's own client and hands it to
+    /// the verifier, which re-reads it in the background. Does nothing when
+    /// `[verify]` is disabled, the change is not sampled, or the own read fails.
+    pub async fn verify_later(&self, task_name: &str, check: StateCheck) {
+        let Some(verifier) = &self.verifier else {
+            return;
+        };
+        if !verifier.sample() {
+            return;
+        }
+        match check.read(self.client.provider.as_ref()).await {
+            Ok(expected) => verifier.spawn(
+                task_name,
+                self.address(),
+                self.client.proxy_index,
+                check,
+                expected,
+            ),
+            Err(e) => tracing::debug!("Not verifying {}: {:#}", check.target(), e),
+        }
+    }
+
+    /// Reserves `amount` of `token` against the wallet's and the global daily caps
+    ///
+    /// Call before sending value and commit the reservation once the
+    /// transaction is accepted; dropping it refunds the amount. Without a
+    /// budget every spend is allowed.
+    ///
+    /// # Example
+    ///
+    /// ```rust,no_run
+    /// # use tempo_spammer::tasks::{TaskContext, TaskResult};
+    /// # use alloy_primitives::{Address, U256};
+    /// # async fn example(ctx: &TaskContext, token: Address, amount: U256) -> anyhow::Result<TaskResult> {
+    /// let reservation = match ctx.reserve_spend(token, amount) {
+    ///     Ok(reservation) => reservation,
+    ///     Err(exceeded) => return Ok(exceeded.into()),
+    /// };
+    /// // send the transfer...
+    /// reservation.commit().await;
+    /// # unimplemented!()
+    /// # }
+    /// ```
+    pub fn reserve_spend(
+        &self,
+        token: Address,
+        amount: U256,
+    ) -> std::result::Result<SpendReservation, BudgetExceeded> {
+        match &self.budget {
+            Some(budget) => budget.reserve(self.address(), token, amount),
+            None => Ok(SpendReservation::untracked()),
+        }
+    }
+
+    /// Recipient for a transfer: another pool wallet or an outside address
+    ///
+    /// With `[recipients] pool_share` set, that share of calls returns a
+    /// pool wallet other than this one; the rest, and every call without the
+    /// tracker, fall back to [`get_random_address`].
+    pub fn pick_recipient(&self) -> Result<Address> {
+        match self
+            .transfers
+            .as_ref()
+            .and_then(|transfers| transfers.pick_recipient(self.address()))
+        {
+            Some(wallet) => Ok(wallet),
+            None => get_random_address(),
+        }
+    }
+
+    /// Another wallet of the pool, `None` until a second wallet was leased
+    pub fn pool_wallet(&self) -> Option<Address> {
+        self.transfers
+            .as_ref()
+            .and_then(|transfers| transfers.other_wallet(self.address()))
+    }
+
+    /// Marks a transfer to `to` as in flight until the guard is settled or dropped
+    ///
+    /// Only transfers to pool wallets are tracked. Call before sending and
+    /// hand the guard to [`InFlightTransfer::settle_after`] once the
+    /// transaction is accepted.
+    pub fn track_transfer(&self, to: Address, token: Address, amount: U256) -> InFlightTransfer {
+        match &self.transfers {
+            Some(transfers) => transfers.begin(self.address(), to, token, amount),
+            None => InFlightTransfer::untracked(),
+        }
+    }
+
+    /// The wallet's `token` balance minus transfers still on their way out
+    ///
+    /// When that is below `needed` only because transfers from other pool
+    /// wallets have not landed yet, waits up to `[recipients]
+    /// incoming_wait_secs` for them and reads the balance again, so the
+    /// task is not skipped for a balance that is about to arrive.
+    pub async fn spendable_balance(&self, token: Address, needed: U256) -> Result<U256> {
+        let balance =
+            tempo_tokens::TempoTokens::get_token_balance(&self.client, token, self.address())
+                .await?;
+        let Some(transfers) = &self.transfers else {
+            return Ok(balance);
+        };
+
+        let pending = transfers.pending(self.address(), token);
+        let spendable = pending.spendable(balance);
+        if spendable >= needed || pending.incoming.is_zero() {
+            return Ok(spendable);
+        }
+        tracing::debug!(
+            "{} short of {} ({} incoming), waiting for pool transfers",
+            token,
+            needed,
+            pending.incoming
+        );
+        transfers.wait_incoming(self.address(), token).await;
+        let balance =
+            tempo_tokens::TempoTokens::get_token_balance(&self.client, token, self.address())
+                .await?;
+        Ok(transfers.pending(self.address(), token).spendable(balance))
+    }
+
+    /// Scales a transfer amount by the wallet persona's `amount_scale`
+    ///
+    /// Returns the amount unchanged when the wallet has no persona. Callers
+    /// still need to cap the result at the available balance.
+    pub fn scale_amount(&self, amount: U256) -> U256 {
+        match &self.persona {
+            Some(persona) => persona.scale_amount(amount),
+            None => amount,
+        }
+    }
+
+    /// Caps a gas limit at the task's `[gas.limits]` ceiling
+    ///
+    /// Returns `wanted` unchanged when the task has no ceiling. Tasks pass
+    /// their own name so the ceiling applies however the context was built.
+    ///
+    /// # Example
+    ///
+    /// ```rust,ignore
+    /// let tx = TransactionRequest::default()
+    ///     .to(token)
+    ///     .gas_limit(ctx.gas_limit(self.name(), 500_000));
+    /// ```
+    pub fn gas_limit(&self, task_name: &str, wanted: u64) -> u64 {
+        match self.config.gas.limits.get(task_name) {
+            Some(&ceiling) => wanted.min(ceiling),
+            None => wanted,
+        }
+    }
+
+    /// Attaches an access list to `tx` when `[gas] access_lists` is enabled
+    ///
+    /// Returns `tx` unchanged and no estimate when the option is off or the
+    /// node cannot produce a list, so tasks send either way. Tasks record the
+    /// estimate with [`AccessListEstimate::record`].
+    ///
+    /// # Example
+    ///
+    /// ```rust,ignore
+    /// let (tx, access_list) = ctx.attach_access_list(tx).await;
+    /// let pending = client.provider.send_transaction(tx).await?;
+    /// ```
+    pub async fn attach_access_list(
+        &self,
+        tx: TransactionRequest,
+    ) -> (TransactionRequest, Option<AccessListEstimate>) {
+        if !self.config.gas.access_lists {
+            return (tx, None);
+        }
+        match self.gas_manager.access_list(&self.client, &tx).await {
+            Ok(estimate) => (tx.access_list(estimate.access_list.clone()), Some(estimate)),
+            Err(e) => {
+                tracing::debug!("Sending without an access list: {:#}", e);
+                (tx, None)
+            }
+        }
+    }
+
+    /// Sends calls as one Tempo transaction, with an access list when enabled
+    ///
+    /// Signs with [`tempo_signer`](Self::tempo_signer) and, with
+    /// `[gas] access_lists` on, attaches the merged list of all calls. Without
+    /// the option, or when the node cannot produce a list, this is
+    /// [`TempoClient::send_calls`]. Fails without sending when the startup
+    /// probe found the node without Tempo transactions.
+    ///
+    /// # Returns
+    ///
+    /// The transaction hash and the access list estimate, if one was attached.
+    pub async fn send_calls(
+        &self,
+        calls: Vec<Call>,
+        gas_limit: u64,
+    ) -> Result<(alloy_primitives::TxHash, Option<AccessListEstimate>)> {
+        if !self.capabilities().tempo_transactions {
+            anyhow::bail!("The node does not support Tempo (0x76) transactions");
+        }
+        let estimate = if self.config.gas.access_lists {
+            match self
+                .gas_manager
+                .calls_access_list(&self.client, self.address(), &calls)
+                .await
+            {
+                Ok(estimate) => Some(estimate),
+                Err(e) => {
+                    tracing::debug!("Sending calls without an access list: {:#}", e);
+                    None
+                }
+            }
+        } else {
+            None
+        };
+
+        let signer = self.tempo_signer().await;
+        let access_list = estimate
+            .as_ref()
+            .map(|estimate| estimate.access_list.clone())
+            .unwrap_or_default();
+        let tx_hash = self
+            .client
+            .send_calls_with_access_list(
+                &signer,
+                &self.config.rpc_url,
+                calls,
+                access_list,
+                gas_limit,
+            )
+            .await?;
+        Ok((tx_hash, estimate))
+    }
+
+    /// Address of helper contract `name`
+    ///
+    /// Fails without touching the chain when the helper was not pinned or
+    /// deployed, or its code did not match at startup, so tasks built on a
+    /// helper stop before sending anything.
+    ///
+    /// # Example
+    ///
+    /// ```rust,ignore
+    /// let disperse = ctx.helper("disperse")?;
+    /// ```
+    pub fn helper(&self, name: &str) -> Result<Address> {
+        match &self.helpers {
+            Some(helpers) => helpers.address(name),
+            None => anyhow::bail!(
+                "Helper contract '{}' unavailable: helpers were not resolved at startup",
+                name
+            ),
         }
     }
 
+    /// Checks of on-chain state to attach to the task's result
+    ///
+    /// # Example
+    ///
+    /// ```rust,ignore
+    /// let owned = ctx.assertions().assert_owner_of(nft, token_id, ctx.address()).await;
+    /// Ok(result.with_verification(owned))
+    /// ```
+    pub fn assertions(&self) -> Assertions<'_> {
+        Assertions::new(self)
+    }
+
     /// Returns the wallet address
     ///
     /// Convenience method that delegates to the client.
@@ -208,6 +831,172 @@ impl TaskContext {
     pub fn chain_id(&self) -> u64 {
         self.client.chain_id()
     }
+
+    /// Latest block header
+    ///
+    /// Taken from the shared [`ChainHead`] watcher when it has a recent one,
+    /// read from the node otherwise.
+    ///
+    /// # Example
+    ///
+    /// ```rust,ignore
+    /// let head = ctx.head().await?;
+    /// let gas_limit = head.gas_limit / 2;
+    /// ```
+    pub async fn head(&self) -> Result<BlockHead> {
+        match self.chain_head.as_ref().and_then(|head| head.latest()) {
+            Some(head) => Ok(head),
+            None => BlockHead::fetch(self.client.provider()).await,
+        }
+    }
+
+    /// Returns the system contract addresses of the configured network
+    ///
+    /// Convenience method that reads `[contracts]` from the config.
+    #[inline]
+    pub fn contracts(&self) -> &SystemContracts {
+        &self.config.contracts
+    }
+
+    /// TIP-20 token at `address`, read and sent to with this wallet
+    pub fn token(&self, address: Address) -> tip20::Tip20Token<'_> {
+        tip20::Tip20Token::new(&self.client, address)
+    }
+
+    /// Stablecoin DEX at `[contracts] stablecoin_dex`, used with this wallet
+    pub fn fee_amm(&self) -> fee_amm::FeeAmm<'_> {
+        fee_amm::FeeAmm::new(&self.client, self.contracts().stablecoin_dex)
+    }
+
+    /// Sizes transfer and swap amounts to this wallet's balances and the DEX
+    ///
+    /// # Example
+    ///
+    /// ```rust,ignore
+    /// let sizer = ctx.sizer();
+    /// let amount = sizer.transfer_amount(token, token.units(25).await?).await?;
+    /// if amount.is_zero() {
+    ///     // nothing to send
+    /// }
+    /// ```
+    pub fn sizer(&self) -> AmountSizer<'_> {
+        AmountSizer::new(self)
+    }
+}
+
+/// Largest share of the wallet's balance one transfer or swap is sized to,
+/// in basis points (5%)
+pub const MAX_BALANCE_SHARE_BPS: u32 = 500;
+
+/// Largest share of the DEX's holdings of the bought token one swap is sized
+/// to, in basis points (0.1%)
+pub const MAX_POOL_SHARE_BPS: u32 = 10;
+
+/// Fits the amounts tasks pick at random to what the chain can take
+///
+/// An amount is scaled by the wallet persona, then capped at
+/// [`MAX_BALANCE_SHARE_BPS`] of the wallet's spendable balance and, for swaps,
+/// at [`MAX_POOL_SHARE_BPS`] of what the Stablecoin DEX holds of the token
+/// bought. An amount over its cap is redrawn between half the cap and the
+/// cap, so sized amounts keep varying. Balances are read once per sizer.
+#[derive(Debug)]
+pub struct AmountSizer<'a> {
+    ctx: &'a TaskContext,
+    balances: Mutex<HashMap<(Address, Address), U256>>,
+}
+
+impl<'a> AmountSizer<'a> {
+    fn new(ctx: &'a TaskContext) -> Self {
+        Self {
+            ctx,
+            balances: Mutex::new(HashMap::new()),
+        }
+    }
+
+    /// Uses `balance` as the wallet's `token` balance instead of reading it
+    ///
+    /// For tasks that already read their balances in one batch.
+    pub fn remember_balance(&self, token: Address, balance: U256) {
+        self.store(token, self.ctx.address(), balance);
+    }
+
+    /// Spendable `token` balance of the wallet, see [`TaskContext::spendable_balance`]
+    pub async fn balance(&self, token: Address) -> Result<U256> {
+        let owner = self.ctx.address();
+        if let Some(balance) = self.cached(token, owner) {
+            return Ok(balance);
+        }
+        let balance = self.ctx.spendable_balance(token, U256::ZERO).await?;
+        self.store(token, owner, balance);
+        Ok(balance)
+    }
+
+    /// `token` held by the Stablecoin DEX, the depth a swap into it draws on
+    pub async fn pool_reserve(&self, token: Address) -> Result<U256> {
+        let dex = self.ctx.contracts().stablecoin_dex;
+        if let Some(reserve) = self.cached(token, dex) {
+            return Ok(reserve);
+        }
+        let reserve = self.ctx.token(token).balance_of(dex).await?;
+        self.store(token, dex, reserve);
+        Ok(reserve)
+    }
+
+    /// `wanted` of `token` sized for a transfer, zero when the wallet holds none
+    pub async fn transfer_amount(&self, token: Address, wanted: U256) -> Result<U256> {
+        let cap = share(self.balance(token).await?, MAX_BALANCE_SHARE_BPS);
+        Ok(fit_amount(
+            self.ctx.scale_amount(wanted),
+            cap,
+            &mut rand::thread_rng(),
+        ))
+    }
+
+    /// `wanted` of `token_in` sized for a swap into `token_out`
+    ///
+    /// The stablecoins trade near 1:1 at equal decimals, so the DEX's
+    /// `token_out` holdings cap the input directly.
+    pub async fn swap_amount(
+        &self,
+        token_in: Address,
+        token_out: Address,
+        wanted: U256,
+    ) -> Result<U256> {
+        let cap = share(self.balance(token_in).await?, MAX_BALANCE_SHARE_BPS).min(share(
+            self.pool_reserve(token_out).await?,
+            MAX_POOL_SHARE_BPS,
+        ));
+        Ok(fit_amount(
+            self.ctx.scale_amount(wanted),
+            cap,
+            &mut rand::thread_rng(),
+        ))
+    }
+
+    fn cached(&self, token: Address, holder: Address) -> Option<U256> {
+        self.balances.lock().unwrap().get(&(token, holder)).copied()
+    }
+
+    fn store(&self, token: Address, holder: Address, balance: U256) {
+        self.balances
+            .lock()
+            .unwrap()
+            .insert((token, holder), balance);
+    }
+}
+
+/// `bps` basis points of `amount`
+fn share(amount: U256, bps: u32) -> U256 {
+    amount * U256::from(bps) / U256::from(fee_amm::BPS)
+}
+
+/// `amount` when within `cap`, otherwise a random amount from half the cap to the cap
+pub fn fit_amount(amount: U256, cap: U256, rng: &mut impl Rng) -> U256 {
+    if amount <= cap {
+        return amount;
+    }
+    let floor = cap / U256::from(2);
+    floor + (cap - floor) * U256::from(rng.gen_range(0..=1000u64)) / U256::from(1000)
 }
 
 /// Trait for implementing tempo tasks
@@ -245,6 +1034,7 @@ impl TaskContext {
 ///             success: true,
 ///             message: "Completed".to_string(),
 ///             tx_hash: None,
+///             ..Default::default()
 ///         })
 ///     }
 /// }
@@ -304,18 +1094,55 @@ pub trait TempoTask: Send + Sync {
     ///         success: true,
     ///         message: "Operation completed".to_string(),
     ///         tx_hash: Some("0x...".to_string()),
+    ///         ..Default::default()
     ///     })
     /// }
     /// # }
     /// ```
     async fn run(&self, ctx: &TaskContext) -> Result<TaskResult>;
+
+    /// Registers the custom errors of the contracts this task calls
+    ///
+    /// Called once when the spammer starts so reverts from those contracts
+    /// show up by name in logs and `task_metrics` instead of as raw data.
+    /// Errors of the Tempo precompiles are known without registering.
+    ///
+    /// # Example
+    ///
+    /// ```rust,ignore
+    /// fn register_errors(&self, decoder: &mut RevertDecoder) {
+    ///     decoder.register::<IMyContract::IMyContractErrors>();
+    /// }
+    /// ```
+    fn register_errors(&self, _decoder: &mut RevertDecoder) {}
+
+    /// Names of the helper contracts this task calls
+    ///
+    /// The runner warns at startup about tasks whose helpers are unavailable
+    /// on the chain; the task itself fails fast through [`TaskContext::helper`].
+    fn required_helpers(&self) -> &'static [&'static str] {
+        &[]
+    }
+
+    /// How long this task needs at least, `None` for the global `task_timeout`
+    ///
+    /// Raises `task_timeout` for tasks that legitimately take longer, such as
+    /// contract deployments; a shorter default never lowers it. An entry in
+    /// `[tasks] timeouts` replaces both.
+    fn default_timeout(&self) -> Option<Duration> {
+        None
+    }
 }
 
+/// [`TempoTask::default_timeout`] of tasks that deploy contracts
+pub const DEPLOY_TIMEOUT: Duration = Duration::from_secs(60);
+
 /// Gas price estimation and fee management
 ///
-/// Provides utilities for estimating gas prices and calculating fees.
-/// Currently supports legacy gas price estimation. Future versions may
-/// add EIP-1559 support.
+/// Applies the shared [`core_logic::GasManager`] fee policy to a
+/// [`TempoClient`]: legacy or EIP-1559 pricing, the fee-history percentile
+/// used for the tip, fee caps and the bump policy all come from its
+/// [`GasConfig`]. Also asks the node for access lists.
 ///
 /// # Example
 ///
@@ -324,7 +1151,7 @@ pub trait TempoTask: Send + Sync {
 /// use tempo_spammer::TempoClient;
 ///
 /// # async fn example() -> anyhow::Result<()> {
-/// let gas_manager = GasManager;
+/// let gas_manager = GasManager::default();
 /// let client = TempoClient::new(
 ///     "https://rpc.moderato.tempo.xyz",
 ///     "0x...",
@@ -337,16 +1164,44 @@ pub trait TempoTask: Send + Sync {
 ///
 /// // Bump fees by 20% for faster confirmation
 /// let bumped = gas_manager.bump_fees(gas_price, 20);
+///
+/// // Or price a type 2 transaction
+/// let fees = gas_manager.fees(&client).await?;
+/// println!("max fee {} wei", fees.max_fee());
 /// # Ok(())
 /// # }
 /// ```
-#[derive(Debug, Default, Clone)]
-pub struct GasManager;
+#[derive(Debug, Clone)]
+pub struct GasManager {
+    policy: core_logic::GasManager,
+}
+
+impl Default for GasManager {
+    fn default() -> Self {
+        Self::new(GasConfig::new().with_max_fee(Self::MAX_FEE_GWEI_DEFAULT))
+    }
+}
 
 impl GasManager {
+    /// Default cap on the max fee, the 150 gwei the tasks send with
+    pub const MAX_FEE_GWEI_DEFAULT: f64 = 150.0;
+
+    /// Creates a gas manager with the given fee policy
+    pub fn new(config: GasConfig) -> Self {
+        Self {
+            policy: core_logic::GasManager::new(config),
+        }
+    }
+
+    /// The shared fee policy, e.g. for [`bump`](core_logic::GasManager::bump)
+    pub fn policy(&self) -> &core_logic::GasManager {
+        &self.policy
+    }
+
     /// Estimates the current gas price from the network
     ///
-    /// Queries the RPC for the current gas price.
+    /// Queries the RPC for the current gas price, capped at the configured
+    /// max fee.
     ///
     /// # Arguments
     ///
@@ -357,12 +1212,27 @@ impl GasManager {
     /// Returns `Result<U256>` containing the gas price in wei.
     pub async fn estimate_gas(&self, client: &TempoClient) -> Result<U256> {
         let gas_price = client.provider.get_gas_price().await?;
-        Ok(U256::from(gas_price))
+        Ok(U256::from(gas_price.min(self.policy.max_fee_cap())))
+    }
+
+    /// Prices a transaction from the network's fee history
+    ///
+    /// # Arguments
+    ///
+    /// * `client` - The blockchain client
+    ///
+    /// # Returns
+    ///
+    /// Returns `Result<Fees>`; EIP-1559 fees unless the policy is legacy or
+    /// the node reports no base fee.
+    pub async fn fees(&self, client: &TempoClient) -> Result<Fees> {
+        self.policy.fees(client).await
     }
 
     /// Increases gas price by a percentage
     ///
     /// Useful for retrying transactions with higher fees for faster confirmation.
+    /// Rounds up, so even a 1 wei price goes up.
     ///
     /// # Arguments
     ///
@@ -379,7 +1249,7 @@ impl GasManager {
     /// use tempo_spammer::tasks::GasManager;
     /// use alloy_primitives::U256;
     ///
-    /// let gas_manager = GasManager;
+    /// let gas_manager = GasManager::default();
     /// let current = U256::from(1000000000u64); // 1 Gwei
     ///
     /// // Bump by 20%
@@ -387,9 +1257,82 @@ impl GasManager {
     /// assert_eq!(bumped, U256::from(1200000000u64)); // 1.2 Gwei
     /// ```
     pub fn bump_fees(&self, gas_price: U256, percent: u64) -> U256 {
-        let multiplier = U256::from(100 + percent);
-        let divisor = U256::from(100);
-        gas_price * multiplier / divisor
+        U256::from(core_logic::bump_fee(gas_price.saturating_to(), percent))
+    }
+
+    /// Asks the node for a transaction's access list and measures its effect
+    ///
+    /// Calls `eth_createAccessList` for the list and the gas with it, and
+    /// `eth_estimateGas` for the gas of `tx` as given. `tx` needs `from` set,
+    /// since the list depends on the sender.
+    ///
+    /// # Arguments
+    ///
+    /// * `client` - The blockchain client
+    /// * `tx` - The transaction request, without an access list
+    ///
+    /// # Returns
+    ///
+    /// Returns `Result<AccessListEstimate>`; fails when either call fails or
+    /// the node reports that the transaction would revert.
+    pub async fn access_list(
+        &self,
+        client: &TempoClient,
+        tx: &TransactionRequest,
+    ) -> Result<AccessListEstimate> {
+        let created = client
+            .provider
+            .create_access_list(tx)
+            .await
+            .context("eth_createAccessList failed")?;
+        if let Some(error) = created.error {
+            anyhow::bail!("eth_createAccessList: {}", error);
+        }
+        let gas_without = client
+            .provider
+            .estimate_gas(tx.clone())
+            .await
+            .context("eth_estimateGas failed")?;
+
+        Ok(AccessListEstimate {
+            access_list: created.access_list,
+            gas_with: created.gas_used.saturating_to(),
+            gas_without,
+        })
+    }
+
+    /// Access list for the calls of a Tempo (0x76) transaction
+    ///
+    /// Estimates each call as a transaction from `from` and merges the lists
+    /// and gas figures, see [`AccessListEstimate::merge`]. Contract creations
+    /// are skipped since they touch no existing account. Calls are estimated
+    /// on their own, so one relying on an earlier call, such as spending an
+    /// approval from the same batch, can make the estimate fail.
+    ///
+    /// # Arguments
+    ///
+    /// * `client` - The blockchain client
+    /// * `from` - The account sending the calls
+    /// * `calls` - The calls of the transaction
+    pub async fn calls_access_list(
+        &self,
+        client: &TempoClient,
+        from: Address,
+        calls: &[Call],
+    ) -> Result<AccessListEstimate> {
+        let mut estimates = Vec::with_capacity(calls.len());
+        for call in calls {
+            let TxKind::Call(to) = call.to else {
+                continue;
+            };
+            let tx = TransactionRequest::default()
+                .from(from)
+                .to(to)
+                .value(call.value)
+                .input(call.input.clone().into());
+            estimates.push(self.access_list(client, &tx).await?);
+        }
+        Ok(AccessListEstimate::merge(estimates))
     }
 }
 
@@ -512,16 +1455,108 @@ pub struct ProxyConfig {
     pub url: String,
     pub username: Option<String>,
     pub password: Option<String>,
+    /// Session source when the credentials contain `{session}`
+    pub session: Option<Arc<ProxySession>>,
 }
 
-// Add at the top of the file: use url::Url;
+/// Proxy credentials resolved for a single connection
+#[derive(Debug, Clone)]
+pub struct ProxyCredentials {
+    pub username: Option<String>,
+    pub password: Option<String>,
+    /// Session id filled into the template (None for static credentials)
+    pub session_id: Option<String>,
+    /// Session generation the credentials belong to
+    pub generation: u64,
+}
+
+impl ProxyConfig {
+    /// Whether the username or password contains the `{session}` placeholder
+    pub fn is_templated(&self) -> bool {
+        self.username
+            .as_deref()
+            .is_some_and(proxy_session::is_templated)
+            || self
+                .password
+                .as_deref()
+                .is_some_and(proxy_session::is_templated)
+    }
+
+    /// Attaches a session source if the credentials are templated
+    pub fn with_session_rotation(mut self, rotation: SessionRotation) -> Self {
+        if self.is_templated() {
+            self.session = Some(Arc::new(ProxySession::new(rotation)));
+        }
+        self
+    }
+
+    /// Credentials for a new connection, with `{session}` filled in
+    pub fn credentials(&self) -> ProxyCredentials {
+        match &self.session {
+            Some(session) => {
+                let (id, generation) = session.next_id();
+                ProxyCredentials {
+                    username: self
+                        .username
+                        .as_deref()
+                        .map(|u| proxy_session::fill_template(u, &id)),
+                    password: self
+                        .password
+                        .as_deref()
+                        .map(|p| proxy_session::fill_template(p, &id)),
+                    session_id: Some(id),
+                    generation,
+                }
+            }
+            None => ProxyCredentials {
+                username: self.username.clone(),
+                password: self.password.clone(),
+                session_id: None,
+                generation: 0,
+            },
+        }
+    }
+
+    /// Username and password for basic auth, if both are set
+    pub fn basic_auth(&self) -> Option<(String, String)> {
+        let creds = self.credentials();
+        creds.username.zip(creds.password)
+    }
+
+    /// Current session generation (0 for static credentials)
+    pub fn session_generation(&self) -> u64 {
+        self.session.as_ref().map_or(0, |s| s.generation())
+    }
+}
 
-pub fn load_proxies(path: &str) -> Result<Vec<ProxyConfig>> {
-    if !Path::new(path).exists() {
+/// Placeholder swapped in while URL-parsing templated credentials, since
+/// `Url` would otherwise percent-encode the braces of `{session}`
+const URL_SESSION_MARKER: &str = "SESSIONPLACEHOLDER";
+
+/// Loads proxies from a proxies.txt file
+///
+/// Templated credentials get a fresh session per connection; use
+/// [`load_proxies_with_rotation`] to share sessions for an interval.
+pub fn load_proxies(path: impl AsRef<Path>) -> Result<Vec<ProxyConfig>> {
+    load_proxies_with_rotation(path, SessionRotation::PerConnection)
+}
+
+/// Loads proxies, attaching session sources for `{session}` templates
+///
+/// # Arguments
+/// * `path` - Path to proxies.txt
+/// * `rotation` - When templated proxies get a new session id
+pub fn load_proxies_with_rotation(
+    path: impl AsRef<Path>,
+    rotation: SessionRotation,
+) -> Result<Vec<ProxyConfig>> {
+    let path = path.as_ref();
+    if !path.exists() {
         return Ok(Vec::new());
     }
 
-    let content = fs::read_to_string(path).context("Failed to read proxies.txt")?;
+    let content =
+        fs::read_to_string(path).with_context(|| format!("Failed to read {}", path.display()))?;
 
     let proxies: Vec<ProxyConfig> = content
         .lines()
@@ -530,18 +1565,21 @@ pub fn load_proxies(path: &str) -> Result<Vec<ProxyConfig>> {
             let line = line.trim();
             // Try parsing as URL first if it looks like one
             if line.starts_with("http") && line.contains('@') {
-                if let Ok(u) = url::Url::parse(line) {
+                let marked = line.replace(proxy_session::SESSION_PLACEHOLDER, URL_SESSION_MARKER);
+                if let Ok(u) = url::Url::parse(&marked) {
                     let host = u.host_str().unwrap_or("").to_string();
                     let port = u
                         .port()
                         .unwrap_or(if u.scheme() == "https" { 443 } else { 80 });
+                    let unmark =
+                        |s: &str| s.replace(URL_SESSION_MARKER, proxy_session::SESSION_PLACEHOLDER);
                     let username = if !u.username().is_empty() {
-                        Some(u.username().to_string())
+                        Some(unmark(u.username()))
                     } else {
                         None
                     };
                     let password = if let Some(p) = u.password() {
-                        Some(p.to_string())
+                        Some(unmark(p))
                     } else {
                         None
                     };
@@ -552,6 +1590,7 @@ pub fn load_proxies(path: &str) -> Result<Vec<ProxyConfig>> {
                         url: base_url,
                         username,
                         password,
+                        session: None,
                     });
                 }
             }
@@ -566,25 +1605,30 @@ pub fn load_proxies(path: &str) -> Result<Vec<ProxyConfig>> {
                     },
                     username: None,
                     password: None,
+                    session: None,
                 }),
                 2 => Some(ProxyConfig {
                     url: format!("http://{}:{}", parts[0], parts[1]),
                     username: None,
                     password: None,
+                    session: None,
                 }),
                 3 => Some(ProxyConfig {
                     url: format!("http://{}", parts[0]), // host:user:pass ? Unusual.
                     username: Some(parts[1].to_string()),
                     password: Some(parts[2].to_string()),
+                    session: None,
                 }),
                 4 => Some(ProxyConfig {
                     url: format!("http://{}:{}", parts[0], parts[1]),
                     username: Some(parts[2].to_string()),
                     password: Some(parts[3].to_string()),
+                    session: None,
                 }),
                 _ => None,
             }
         })
+        .map(|proxy| proxy.with_session_rotation(rotation))
         .collect();
 
     Ok(proxies)
@@ -592,12 +1636,19 @@ pub fn load_proxies(path: &str) -> Result<Vec<ProxyConfig>> {
 
 pub mod prelude {
     pub use super::{
-        GasManager, TaskContext, TaskResult, TempoTask, generate_random_shares,
-        get_n_random_addresses, get_random_address, load_proxies as load_proxy_config,
+        DEPLOY_TIMEOUT, GasManager, RETRY_PAUSE, ReceiptTimeout, TaskContext, TaskResult,
+        TempoTask, generate_random_shares, get_n_random_addresses, get_random_address,
+        load_proxies as load_proxy_config,
     };
 }
 
+pub mod assertions;
 pub mod check_native_balance;
+pub mod domains;
+pub mod fee_amm;
+pub mod nft_market;
+pub mod smart_account;
+pub mod stress;
 pub mod t01_deploy_contract;
 pub mod t02_claim_faucet;
 pub mod t03_send_token;
@@ -648,4 +1699,109 @@ pub mod t47_deploy_viral_nft;
 pub mod t48_mint_viral_nft;
 pub mod t49_time_bomb;
 pub mod t50_deploy_storm;
+pub mod t51_stress_large_calldata;
+pub mod t52_stress_max_calls;
+pub mod t53_stress_near_gas_limit;
+pub mod t54_disperse_helper;
+pub mod t55_native_batch_calls;
+pub mod t56_aa_create_account;
+pub mod t57_aa_user_operation;
+pub mod t58_aa_batch_user_operation;
+pub mod t59_nft_list;
+pub mod t60_nft_buy;
+pub mod t61_nft_cancel_listing;
+pub mod t62_domain_set_resolver;
+pub mod t63_domain_set_addr;
+pub mod t64_domain_transfer;
+pub mod t65_domain_renew;
+pub mod t66_abi_fuzz_call;
+pub mod t67_sign_message;
+pub mod t68_sign_typed_order;
 pub mod tempo_tokens;
+pub mod tip20;
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use crate::testing::TaskHarness;
+
+    #[test]
+    fn test_fit_amount_stays_within_cap() {
+        let mut rng = rand::thread_rng();
+        assert_eq!(
+            fit_amount(U256::from(40), U256::from(50), &mut rng),
+            U256::from(40)
+        );
+        for _ in 0..100 {
+            let amount = fit_amount(U256::from(1_000), U256::from(100), &mut rng);
+            assert!(amount >= U256::from(50) && amount <= U256::from(100));
+        }
+        assert_eq!(fit_amount(U256::from(1), U256::ZERO, &mut rng), U256::ZERO);
+    }
+
+    #[tokio::test]
+    async fn test_swap_amount_capped_by_pool_and_balance() {
+        let harness = TaskHarness::new();
+        let ctx = harness.context();
+        let (token_in, token_out) = (ctx.contracts().path_usd, ctx.contracts().alpha_usd);
+        // 5% of 1_000_000 is 50_000, 0.1% of 20_000_000 is 20_000
+        harness.mock.on_call(
+            token_in,
+            "70a08231",
+            U256::from(1_000_000).to_be_bytes_vec(),
+        );
+        harness.mock.on_call(
+            token_out,
+            "70a08231",
+            U256::from(20_000_000).to_be_bytes_vec(),
+        );
+
+        let sizer = ctx.sizer();
+        let amount = sizer
+            .swap_amount(token_in, token_out, U256::from(1_000_000))
+            .await
+            .unwrap();
+        assert!(amount >= U256::from(10_000) && amount <= U256::from(20_000));
+        assert_eq!(
+            sizer
+                .swap_amount(token_in, token_out, U256::from(5_000))
+                .await
+                .unwrap(),
+            U256::from(5_000)
+        );
+        assert_eq!(harness.mock.count("eth_call"), 2);
+    }
+
+    #[tokio::test]
+    async fn test_head_prefers_watcher() {
+        let harness = TaskHarness::new();
+        assert_eq!(harness.context().head().await.unwrap().number, 1);
+        assert_eq!(harness.mock.count("eth_getBlockByNumber"), 1);
+
+        let chain_head = Arc::new(ChainHead::new(Default::default()));
+        chain_head.record(BlockHead {
+            number: 42,
+            base_fee: None,
+            timestamp: 0,
+            gas_limit: 1,
+        });
+        let ctx = harness.context().with_chain_head(Some(chain_head));
+        assert_eq!(ctx.head().await.unwrap().number, 42);
+        assert_eq!(harness.mock.count("eth_getBlockByNumber"), 1);
+    }
+
+    #[tokio::test]
+    async fn test_receipt_past_wrap_up_reports_hash() {
+        let harness = TaskHarness::new();
+        let ctx = harness.context().with_deadline(WRAP_UP);
+        let tx_hash = alloy_primitives::B256::repeat_byte(7);
+
+        let timeout = ctx.receipt(tx_hash).await.unwrap_err();
+        assert_eq!(timeout, ReceiptTimeout { tx_hash });
+        assert_eq!(harness.mock.count("eth_getTransactionReceipt"), 1);
+
+        let result = TaskResult::from(timeout);
+        assert!(!result.success);
+        assert_eq!(result.tx_hash, Some(format!("{:?}", tx_hash)));
+    }
+}
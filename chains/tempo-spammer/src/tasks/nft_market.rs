@@ -0,0 +1,247 @@
+//! NFT Market - Shared steps of the marketplace tasks (59-61)
+//!
+//! The tasks trade ViralNFTs (tasks 47, 48) on the `marketplace` helper
+//! contract, paying in system tokens. A listing leaves the NFT with its
+//! seller, approved for the marketplace, so a sale can fail when the seller
+//! moved the NFT since; the buy task checks ownership before paying.
+//!
+//! Listings are recorded in the `nft_listings` table: `59_nft_list` adds
+//! them, `60_nft_buy` buys other wallets' listings and `61_nft_cancel_listing`
+//! withdraws the wallet's stale ones. All three need the database.
+
+use crate::contracts::IMarketplace;
+use crate::tasks::TaskContext;
+use alloy::rpc::types::{BlockNumberOrTag, Filter, TransactionReceipt, TransactionRequest};
+use alloy_primitives::{Address, TxHash, U256};
+use alloy_sol_types::{SolCall, SolEvent, sol};
+use anyhow::{Context, Result};
+use core_logic::database::{DatabaseManager, NftListingRecord};
+use std::collections::BTreeSet;
+use std::sync::Arc;
+use std::time::Duration;
+
+sol! {
+    interface IMarketNft {
+        function ownerOf(uint256 tokenId) view returns (address);
+        function isApprovedForAll(address owner, address operator) view returns (bool);
+        function setApprovalForAll(address operator, bool approved);
+
+        event Transfer(address indexed from, address indexed to, uint256 indexed tokenId);
+    }
+
+    interface IMarketToken {
+        function approve(address spender, uint256 amount) returns (bool);
+    }
+}
+
+/// Created-assets type of the collections listed (see task 47)
+pub const COLLECTION_TYPE: &str = "viral_nft";
+
+const RECEIPT_TIMEOUT: Duration = Duration::from_secs(60);
+const RECEIPT_POLL: Duration = Duration::from_secs(1);
+
+/// The database, failing when the run has none
+pub fn database(ctx: &TaskContext) -> Result<&Arc<DatabaseManager>> {
+    ctx.db
+        .as_ref()
+        .context("NFT listings are tracked in the database, which this run does not have")
+}
+
+/// Active listings on `marketplace` recorded for this chain
+pub async fn active_listings(
+    ctx: &TaskContext,
+    marketplace: Address,
+) -> Result<Vec<NftListingRecord>> {
+    database(ctx)?
+        .get_active_nft_listings(ctx.config.chain_id, &marketplace.to_string())
+        .await
+}
+
+/// Marks a recorded listing `sold`, `cancelled` or `closed`
+pub async fn close_listing(
+    ctx: &TaskContext,
+    listing: &NftListingRecord,
+    status: &str,
+) -> Result<()> {
+    database(ctx)?
+        .close_nft_listing(
+            ctx.config.chain_id,
+            &listing.marketplace,
+            &listing.listing_id,
+            status,
+        )
+        .await
+}
+
+/// Current owner of `token_id`, `None` when the call fails
+pub async fn owner_of(ctx: &TaskContext, nft: Address, token_id: U256) -> Option<Address> {
+    let data = ctx
+        .client
+        .provider
+        .call(
+            TransactionRequest::default().to(nft).input(
+                IMarketNft::ownerOfCall { tokenId: token_id }
+                    .abi_encode()
+                    .into(),
+            ),
+        )
+        .await
+        .ok()?;
+    IMarketNft::ownerOfCall::abi_decode_returns(&data).ok()
+}
+
+/// Whether `owner` approved `marketplace` for all its tokens of `nft`
+pub async fn is_approved(
+    ctx: &TaskContext,
+    nft: Address,
+    owner: Address,
+    marketplace: Address,
+) -> Result<bool> {
+    let call = IMarketNft::isApprovedForAllCall {
+        owner,
+        operator: marketplace,
+    };
+    let data = ctx
+        .client
+        .provider
+        .call(
+            TransactionRequest::default()
+                .to(nft)
+                .input(call.abi_encode().into()),
+        )
+        .await?;
+    IMarketNft::isApprovedForAllCall::abi_decode_returns(&data)
+        .context("Unexpected isApprovedForAll result")
+}
+
+/// Tokens of `nft` the wallet holds, by the `Transfer` logs to it
+///
+/// Every token ever sent to the wallet is checked with `ownerOf`, so tokens
+/// it passed on since are left out.
+pub async fn owned_tokens(ctx: &TaskContext, nft: Address) -> Result<Vec<U256>> {
+    let filter = Filter::new()
+        .address(nft)
+        .event_signature(IMarketNft::Transfer::SIGNATURE_HASH)
+        .topic2(ctx.address().into_word())
+        .from_block(BlockNumberOrTag::Earliest);
+    let received: BTreeSet<U256> = ctx
+        .client
+        .provider
+        .get_logs(&filter)
+        .await
+        .with_context(|| format!("Failed to read transfers of {}", nft))?
+        .iter()
+        .filter_map(|log| log.topics().get(3).map(|id| U256::from_be_bytes(id.0)))
+        .collect();
+
+    let mut owned = Vec::new();
+    for token_id in received {
+        if owner_of(ctx, nft, token_id).await == Some(ctx.address()) {
+            owned.push(token_id);
+        }
+    }
+    Ok(owned)
+}
+
+/// The listing as the marketplace stores it; a zero seller means it was
+/// sold or cancelled
+pub async fn onchain_listing(
+    ctx: &TaskContext,
+    marketplace: Address,
+    listing_id: U256,
+) -> Result<IMarketplace::listingsReturn> {
+    let data = ctx
+        .client
+        .provider
+        .call(
+            TransactionRequest::default().to(marketplace).input(
+                IMarketplace::listingsCall { id: listing_id }
+                    .abi_encode()
+                    .into(),
+            ),
+        )
+        .await?;
+    IMarketplace::listingsCall::abi_decode_returns(&data).context("Unexpected listings result")
+}
+
+/// Id of the listing a `list` call in `receipt` created
+pub fn listed_id(receipt: &TransactionReceipt, marketplace: Address) -> Option<U256> {
+    receipt
+        .inner
+        .logs()
+        .iter()
+        .filter(|log| log.address() == marketplace)
+        .find_map(|log| IMarketplace::Listed::decode_raw_log(log.topics(), &log.data().data).ok())
+        .map(|event| event.id)
+}
+
+/// Waits for the receipt of a marketplace transaction
+pub async fn confirm(ctx: &TaskContext, tx_hash: TxHash) -> Option<TransactionReceipt> {
+    ctx.wait_for_receipt(tx_hash, RECEIPT_TIMEOUT, RECEIPT_POLL)
+        .await
+}
+
+/// Parses a decimal id or amount of a listing record
+pub fn parse_number(value: &str) -> Result<U256> {
+    value
+        .parse()
+        .with_context(|| format!("Bad number '{}' in nft_listings", value))
+}
+
+/// Parses an address of a listing record
+pub fn parse_address(value: &str) -> Result<Address> {
+    value
+        .parse()
+        .with_context(|| format!("Bad address '{}' in nft_listings", value))
+}
+
+/// Harness with a database and the marketplace helper deployed at a pinned
+/// address, returned with the registry resolving it
+#[cfg(test)]
+pub(crate) async fn test_harness() -> (
+    crate::testing::TaskHarness,
+    Arc<crate::contracts::HelperRegistry>,
+    Address,
+) {
+    use crate::config::HelperSettings;
+    use crate::contracts::{HelperRegistry, marketplace};
+    use std::collections::BTreeMap;
+
+    let harness = crate::testing::TaskHarness::new().with_db().await.unwrap();
+    let client = harness.mock.client();
+    let address = Address::repeat_byte(0x3a);
+    harness.mock.set_code(address, marketplace());
+    let mut settings = HelperSettings::default();
+    settings.pinned.insert(
+        client.chain_id().to_string(),
+        BTreeMap::from([("marketplace".to_string(), address)]),
+    );
+    let helpers = HelperRegistry::resolve(client.provider(), client.chain_id(), &settings, None)
+        .await
+        .unwrap();
+    (harness, Arc::new(helpers), address)
+}
+
+/// Listing record of `seller` for token `token_id`, listed at `listed_at`
+#[cfg(test)]
+pub(crate) fn test_listing(
+    marketplace: Address,
+    listing_id: u64,
+    seller: Address,
+    token_id: u64,
+    listed_at: i64,
+) -> NftListingRecord {
+    NftListingRecord {
+        chain_id: crate::testing::TEST_CHAIN_ID as i64,
+        marketplace: marketplace.to_string(),
+        listing_id: listing_id.to_string(),
+        seller: seller.to_string(),
+        nft: Address::repeat_byte(0x4e).to_string(),
+        token_id: token_id.to_string(),
+        payment_token: crate::tasks::tempo_tokens::TempoTokens::get_path_usd_address().to_string(),
+        price: "5000".to_string(),
+        status: "active".to_string(),
+        tx_hash: "0xabc".to_string(),
+        listed_at,
+    }
+}
@@ -0,0 +1,260 @@
+//! Smart Accounts - Shared steps of the ERC-4337 (`_aa_`) tasks
+//!
+//! The tasks use eth-infinitism's SimpleAccount: one owner key, created by
+//! `SimpleAccountFactory.createAccount(owner, salt)` at an address the
+//! factory's `getAddress` predicts, executing `execute` and `executeBatch`
+//! when the EntryPoint calls it with a user operation the owner signed.
+//!
+//! Addresses come from `[aa]`, and every task checks the EntryPoint and
+//! factory have code before doing anything. Accounts created by
+//! `56_aa_create_account` are recorded in the `smart_accounts` table; the
+//! user operation tasks pick one of the wallet's accounts from there.
+
+use crate::bundler::{BundlerClient, DUMMY_SIGNATURE, UserOperation, UserOperationReceipt};
+use crate::tasks::{TaskContext, TaskResult};
+use alloy::rpc::types::TransactionRequest;
+use alloy_primitives::{Address, B256, Bytes, U256};
+use alloy_sol_types::{SolCall, sol};
+use anyhow::{Context, Result};
+use rand::seq::SliceRandom;
+use std::time::Duration;
+
+sol! {
+    interface ISimpleAccountFactory {
+        function createAccount(address owner, uint256 salt) returns (address);
+        function getAddress(address owner, uint256 salt) view returns (address);
+    }
+
+    interface ISimpleAccount {
+        function execute(address dest, uint256 value, bytes func);
+        function executeBatch(address[] dest, uint256[] value, bytes[] func);
+    }
+
+    interface IAccountToken {
+        function transfer(address to, uint256 amount) returns (bool);
+    }
+
+    interface IEntryPoint {
+        function getNonce(address sender, uint192 key) view returns (uint256);
+        function depositTo(address account) payable;
+    }
+}
+
+const RECEIPT_POLL: Duration = Duration::from_secs(2);
+
+/// Fails unless the configured EntryPoint and account factory have code
+pub async fn ensure_contracts(ctx: &TaskContext) -> Result<()> {
+    let aa = &ctx.config.aa;
+    for (name, address) in [
+        ("EntryPoint", aa.entry_point),
+        ("SimpleAccountFactory", aa.account_factory),
+    ] {
+        let code = ctx.client.provider.get_code_at(address).await?;
+        if code.is_empty() {
+            anyhow::bail!("{} not deployed at {} (see [aa])", name, address);
+        }
+    }
+    Ok(())
+}
+
+/// Address the factory creates the wallet's account with `salt` at
+pub async fn account_address(ctx: &TaskContext, salt: U256) -> Result<Address> {
+    let call = ISimpleAccountFactory::getAddressCall {
+        owner: ctx.address(),
+        salt,
+    };
+    let data = ctx
+        .client
+        .provider
+        .call(
+            TransactionRequest::default()
+                .to(ctx.config.aa.account_factory)
+                .input(call.abi_encode().into()),
+        )
+        .await?;
+    ISimpleAccountFactory::getAddressCall::abi_decode_returns(&data)
+        .context("Unexpected getAddress result")
+}
+
+/// The wallet's recorded accounts on this chain, oldest first
+///
+/// Without a database only the account with salt 0 is known.
+pub async fn known_accounts(ctx: &TaskContext) -> Result<Vec<Address>> {
+    match &ctx.db {
+        Some(db) => db
+            .get_smart_accounts(ctx.config.chain_id, &ctx.address().to_string())
+            .await?
+            .iter()
+            .map(|record| {
+                record
+                    .account
+                    .parse()
+                    .with_context(|| format!("Bad account address '{}'", record.account))
+            })
+            .collect(),
+        None => Ok(vec![account_address(ctx, U256::ZERO).await?]),
+    }
+}
+
+/// A random deployed account of the wallet
+pub async fn pick_account(ctx: &TaskContext) -> Result<Address> {
+    let mut accounts = known_accounts(ctx).await?;
+    accounts.shuffle(&mut rand::thread_rng());
+    for account in accounts {
+        if !ctx.client.provider.get_code_at(account).await?.is_empty() {
+            return Ok(account);
+        }
+    }
+    anyhow::bail!(
+        "No smart account deployed for {}, run 56_aa_create_account first",
+        ctx.address()
+    )
+}
+
+/// Bundler client for `[aa]`, failing when no bundler is configured
+pub fn bundler(ctx: &TaskContext) -> Result<BundlerClient> {
+    let aa = &ctx.config.aa;
+    if aa.bundler_url.is_empty() {
+        anyhow::bail!("No bundler configured: set [aa] bundler_url");
+    }
+    Ok(BundlerClient::new(&aa.bundler_url, aa.entry_point))
+}
+
+/// A submitted user operation and its receipt, if it arrived in time
+#[derive(Debug, Clone)]
+pub struct SentOperation {
+    pub hash: B256,
+    pub receipt: Option<UserOperationReceipt>,
+}
+
+/// Builds, signs and submits a user operation running `call_data` on `account`
+///
+/// Gas limits come from the bundler's estimate; fees follow the tasks' usual
+/// 125% of the current gas price. Waits up to `[aa] receipt_timeout_secs`
+/// for the receipt.
+pub async fn send_user_operation(
+    ctx: &TaskContext,
+    bundler: &BundlerClient,
+    account: Address,
+    call_data: Vec<u8>,
+) -> Result<SentOperation> {
+    let client = &ctx.client;
+    let entry_point = bundler.entry_point();
+
+    let nonce_call = IEntryPoint::getNonceCall {
+        sender: account,
+        key: Default::default(),
+    };
+    let data = client
+        .provider
+        .call(
+            TransactionRequest::default()
+                .to(entry_point)
+                .input(nonce_call.abi_encode().into()),
+        )
+        .await?;
+    let nonce = IEntryPoint::getNonceCall::abi_decode_returns(&data)
+        .context("Unexpected getNonce result")?;
+
+    let max_fee = U256::from(client.provider.get_gas_price().await? * 125 / 100);
+    let mut op = UserOperation {
+        sender: account,
+        nonce,
+        call_data: Bytes::from(call_data),
+        max_fee_per_gas: max_fee,
+        max_priority_fee_per_gas: max_fee.min(U256::from(1_500_000_000u64)),
+        signature: Bytes::from(DUMMY_SIGNATURE.to_vec()),
+        ..Default::default()
+    };
+    let gas = bundler.estimate_user_operation_gas(&op).await?;
+    op.call_gas_limit = gas.call_gas_limit;
+    op.verification_gas_limit = gas.verification_gas_limit;
+    op.pre_verification_gas = gas.pre_verification_gas;
+    op.sign(&client.signer, entry_point, client.chain_id)
+        .await?;
+
+    let hash = bundler.send_user_operation(&op).await?;
+    let receipt = bundler
+        .wait_for_receipt(
+            hash,
+            ctx.time_for(Duration::from_secs(ctx.config.aa.receipt_timeout_secs)),
+            RECEIPT_POLL,
+        )
+        .await;
+    Ok(SentOperation { hash, receipt })
+}
+
+impl SentOperation {
+    /// Task result for the operation: successful when it was included and
+    /// the account's call succeeded
+    ///
+    /// The transaction hash is the bundle's `handleOps` transaction.
+    pub fn into_result(self, account: Address, done: String, timeout_secs: u64) -> TaskResult {
+        let Some(receipt) = self.receipt else {
+            return TaskResult {
+                success: false,
+                message: format!(
+                    "User operation {} not included within {}s",
+                    self.hash, timeout_secs
+                ),
+                ..Default::default()
+            }
+            .with_detail("account", account.to_string())
+            .with_detail("user_op_hash", self.hash.to_string());
+        };
+
+        let message = if receipt.success {
+            done
+        } else {
+            format!(
+                "User operation reverted in {}: {}",
+                account,
+                receipt.reason.as_deref().unwrap_or("no reason")
+            )
+        };
+        TaskResult {
+            success: receipt.success,
+            message,
+            tx_hash: Some(format!("{:?}", receipt.receipt.transaction_hash)),
+            ..Default::default()
+        }
+        .with_receipt(
+            receipt.actual_gas_used.saturating_to(),
+            receipt.receipt.block_number.map(|n| n.saturating_to()),
+        )
+        .with_detail("account", account.to_string())
+        .with_detail("user_op_hash", self.hash.to_string())
+        .with_detail("actual_gas_cost", receipt.actual_gas_cost.to_string())
+    }
+}
+
+/// Harness with the `[aa]` contracts deployed, the salt-0 account predicted
+/// (and deployed) and every system token balance at 10^7
+#[cfg(test)]
+pub(crate) fn test_harness() -> (crate::testing::TaskHarness, Address) {
+    use crate::tasks::tempo_tokens::TempoTokens;
+    use alloy_sol_types::SolValue;
+
+    let harness = crate::testing::TaskHarness::new();
+    let aa = &harness.config.aa;
+    let account = Address::repeat_byte(0xac);
+    for address in [aa.entry_point, aa.account_factory, account] {
+        harness.mock.set_code(address, vec![0x60, 0x00]);
+    }
+    harness.mock.on_call(
+        aa.account_factory,
+        &hex::encode(ISimpleAccountFactory::getAddressCall::SELECTOR),
+        account.abi_encode(),
+    );
+    harness.mock.on_call(
+        aa.entry_point,
+        &hex::encode(IEntryPoint::getNonceCall::SELECTOR),
+        U256::from(7).to_be_bytes_vec(),
+    );
+    for (_, token) in TempoTokens::system_tokens() {
+        harness
+            .mock
+            .on_call(token, "70a08231", U256::from(10_000_000).to_be_bytes_vec());
+    }
+    (harness, account)
+}
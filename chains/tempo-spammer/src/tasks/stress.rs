@@ -0,0 +1,221 @@
+//! Stress Probe Utilities
+//!
+//! Shared sending and classification for the `_stress_` tasks, which push
+//! one Tempo (0x76) transaction against a protocol limit per run:
+//!
+//! - `51_stress_large_calldata`: one call with `[stress] calldata_sizes` bytes
+//! - `52_stress_max_calls`: `[stress] call_counts` calls in one transaction
+//! - `53_stress_near_gas_limit`: a gas burner with `[stress] gas_fraction` of
+//!   the block gas limit
+//!
+//! Every probe ends in a [`StressOutcome`], stored as the `outcome` detail of
+//! the task result. Hitting a limit is an expected finding, so only
+//! [`StressOutcome::Rejected`] fails the task.
+
+use crate::tasks::{TaskContext, TaskResult};
+use alloy::providers::Provider;
+use alloy_primitives::{Address, Bytes, TxKind, U256};
+use anyhow::Result;
+use rand::Rng;
+use std::fmt;
+use std::time::Duration;
+use tempo_primitives::transaction::{Call, TempoTransaction};
+
+/// How long a probe waits for its receipt
+const RECEIPT_TIMEOUT: Duration = Duration::from_secs(60);
+const RECEIPT_POLL: Duration = Duration::from_secs(1);
+
+/// Error fragments of refusals caused by a size, count or gas limit
+const LIMIT_PATTERNS: &[&str] = &[
+    "too many calls",
+    "exceeds maximum allowed",
+    "oversized data",
+    "exceeds block gas limit",
+    "gas limit reached",
+    "intrinsic gas too low",
+    "too large",
+];
+
+/// How the network answered a probe
+#[derive(Debug, Clone, Copy, PartialEq, Eq)]
+pub enum StressOutcome {
+    /// Accepted by the pool and, if a receipt came in time, executed successfully
+    Accepted,
+    /// Refused or reverted for a reason other than a protocol limit
+    Rejected,
+    /// Refused because the transaction exceeds a protocol limit
+    Limit,
+}
+
+impl StressOutcome {
+    pub fn as_str(&self) -> &'static str {
+        match self {
+            Self::Accepted => "accepted",
+            Self::Rejected => "rejected",
+            Self::Limit => "limit",
+        }
+    }
+}
+
+impl fmt::Display for StressOutcome {
+    fn fmt(&self, f: &mut fmt::Formatter<'_>) -> fmt::Result {
+        f.write_str(self.as_str())
+    }
+}
+
+/// Classifies an error returned when sending a probe
+pub fn classify_error(message: &str) -> StressOutcome {
+    let message = message.to_lowercase();
+    if LIMIT_PATTERNS
+        .iter()
+        .any(|pattern| message.contains(pattern))
+    {
+        StressOutcome::Limit
+    } else {
+        StressOutcome::Rejected
+    }
+}
+
+/// A random entry of a `[stress]` size list, which `validate` keeps non-empty
+pub fn pick(sizes: &[usize]) -> usize {
+    sizes[rand::rngs::OsRng.gen_range(0..sizes.len())]
+}
+
+/// Signs and sends one Tempo transaction, then classifies the result
+///
+/// `label` describes the probe in the result message, e.g. "64 calls".
+pub async fn send_probe(
+    ctx: &TaskContext,
+    label: &str,
+    calls: Vec<Call>,
+    gas_limit: u64,
+) -> Result<TaskResult> {
+    let client = &ctx.client;
+    let signer = ctx.tempo_signer().await;
+    let nonce = client.get_pending_nonce(&ctx.config.rpc_url).await?;
+    let gas_price = client.provider.get_gas_price().await?;
+
+    let tx = TempoTransaction {
+        chain_id: ctx.chain_id(),
+        nonce,
+        max_fee_per_gas: (gas_price * 125) / 100,
+        max_priority_fee_per_gas: 1_500_000_000,
+        gas_limit,
+        calls,
+        ..Default::default()
+    };
+    let sig = signer.sign(&tx).await?;
+    let mut raw = Vec::new();
+    tx.into_signed(sig).eip2718_encode(&mut raw);
+    let tx_bytes = raw.len();
+
+    let pending = match client.provider.send_raw_transaction(&raw).await {
+        Ok(pending) => pending,
+        Err(e) => {
+            // The nonce was not used, so the cached one is stale
+            client.reset_nonce_cache().await;
+            let outcome = classify_error(&e.to_string());
+            return Ok(TaskResult {
+                success: outcome == StressOutcome::Limit,
+                message: format!("{}: {} ({})", label, outcome, e),
+                ..Default::default()
+            }
+            .with_detail("outcome", outcome.as_str())
+            .with_detail("tx_bytes", tx_bytes)
+            .with_detail("gas_limit", gas_limit));
+        }
+    };
+    if let Some(manager) = &client.nonce_manager {
+        manager.set(ctx.address(), nonce + 1).await;
+    }
+
+    let tx_hash = *pending.tx_hash();
+    let receipt = ctx
+        .wait_for_receipt(tx_hash, RECEIPT_TIMEOUT, RECEIPT_POLL)
+        .await;
+    let (outcome, status) = match &receipt {
+        Some(receipt) if receipt.status() => (StressOutcome::Accepted, "included"),
+        Some(_) => (StressOutcome::Rejected, "reverted"),
+        None => (StressOutcome::Accepted, "pending"),
+    };
+
+    let mut result = TaskResult {
+        success: outcome != StressOutcome::Rejected,
+        message: format!("{}: {} ({})", label, outcome, status),
+        tx_hash: Some(format!("{:?}", tx_hash)),
+        ..Default::default()
+    };
+    if let Some(receipt) = &receipt {
+        result = result.with_receipt(receipt.gas_used, receipt.block_number);
+    }
+    Ok(result
+        .with_detail("outcome", outcome.as_str())
+        .with_detail("status", status)
+        .with_detail("tx_bytes", tx_bytes)
+        .with_detail("gas_limit", gas_limit))
+}
+
+/// A call to `to` without value
+pub fn call(to: Address, input: impl Into<Bytes>) -> Call {
+    Call {
+        to: TxKind::Call(to),
+        value: U256::ZERO,
+        input: input.into(),
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use crate::testing::TaskHarness;
+
+    #[test]
+    fn test_classify_error() {
+        assert_eq!(
+            classify_error("Too many calls in AA transaction: 64 exceeds maximum allowed 32"),
+            StressOutcome::Limit
+        );
+        assert_eq!(
+            classify_error(
+                "Call input size 196608 exceeds maximum allowed 131072 bytes (call index: 0)"
+            ),
+            StressOutcome::Limit
+        );
+        assert_eq!(
+            classify_error("exceeds block gas limit"),
+            StressOutcome::Limit
+        );
+        assert_eq!(
+            classify_error("insufficient funds for gas * price + value"),
+            StressOutcome::Rejected
+        );
+    }
+
+    #[tokio::test]
+    async fn test_probe_outcomes() {
+        let harness = TaskHarness::new();
+        let ctx = harness.context();
+        let calls = || vec![call(Address::repeat_byte(1), vec![0u8; 8])];
+
+        let accepted = send_probe(&ctx, "8 bytes", calls(), 100_000).await.unwrap();
+        assert!(accepted.success, "{}", accepted.message);
+        assert_eq!(accepted.details["outcome"], "accepted");
+        assert!(accepted.tx_hash.is_some());
+
+        harness.mock.revert_next_receipt();
+        let reverted = send_probe(&ctx, "8 bytes", calls(), 100_000).await.unwrap();
+        assert!(!reverted.success);
+        assert_eq!(reverted.details["status"], "reverted");
+
+        harness
+            .mock
+            .fail_next_send("Too many calls in AA transaction: 33 exceeds maximum allowed 32");
+        let limit = send_probe(&ctx, "33 calls", calls(), 100_000)
+            .await
+            .unwrap();
+        assert!(limit.success);
+        assert_eq!(limit.details["outcome"], "limit");
+        assert!(limit.tx_hash.is_none());
+        assert_eq!(harness.mock.sent().len(), 2);
+    }
+}
@@ -8,6 +8,7 @@ use alloy_primitives::{Address, Bytes, U256};
 use anyhow::{Context, Result, anyhow};
 use async_trait::async_trait;
 use std::str::FromStr;
+use std::time::Duration;
 
 #[derive(Debug, Clone, Default)]
 pub struct DeployContractTask;
@@ -24,6 +25,10 @@ impl TempoTask for DeployContractTask {
         "01_deploy_contract"
     }
 
+    fn default_timeout(&self) -> Option<Duration> {
+        Some(DEPLOY_TIMEOUT)
+    }
+
     async fn run(&self, ctx: &TaskContext) -> Result<TaskResult> {
         let client = &ctx.client;
 
@@ -47,10 +52,9 @@ impl TempoTask for DeployContractTask {
                         max_retries,
                         e
                     );
-                    if attempt >= max_retries {
+                    if attempt >= max_retries || !ctx.retry_after(RETRY_PAUSE).await {
                         return Err(e).context("Failed to get nonce after max retries");
                     }
-                    tokio::time::sleep(std::time::Duration::from_millis(200)).await;
                     continue;
                 }
             };
@@ -59,7 +63,7 @@ impl TempoTask for DeployContractTask {
                 .input(bytecode.clone().into())
                 .from(ctx.address())
                 .nonce(nonce) // EXPLICIT NONCE - prevents race conditions
-                .gas_limit(500_000);
+                .gas_limit(ctx.gas_limit(self.name(), 500_000));
             tx.to = Some(alloy::primitives::TxKind::Create);
 
             match client.provider.send_transaction(tx).await {
@@ -79,7 +83,9 @@ impl TempoTask for DeployContractTask {
 
                         // Reset nonce cache and wait
                         client.reset_nonce_cache().await;
-                        tokio::time::sleep(std::time::Duration::from_millis(200)).await;
+                        if !ctx.retry_after(RETRY_PAUSE).await {
+                            return Err(e).context("Failed to send deployment transaction");
+                        }
                         continue;
                     } else {
                         return Err(e).context("Failed to send deployment transaction");
@@ -103,6 +109,7 @@ impl TempoTask for DeployContractTask {
             success: true,
             message: format!("Contract deployed: {:?}", tx_hash),
             tx_hash: Some(format!("{:?}", tx_hash)),
+            ..Default::default()
         })
     }
 }
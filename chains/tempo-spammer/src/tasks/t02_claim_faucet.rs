@@ -1,14 +1,15 @@
 //! Claim Faucet Task
 //!
-//! Claims tokens from the Tempo testnet faucet.
+//! Claims tokens from the Tempo testnet faucet, once per wallet and UTC day
+//! when a database is attached: a run retried after its claim landed returns
+//! the earlier claim instead of sending another.
 
+use crate::idempotency;
 use crate::tasks::prelude::*;
 use alloy::rpc::types::TransactionRequest;
 use anyhow::{Context, Result};
 use async_trait::async_trait;
 
-const FAUCET_ADDRESS: &str = "0x4200000000000000000000000000000000000019";
-
 #[derive(Debug, Clone, Default)]
 pub struct ClaimFaucetTask;
 
@@ -28,6 +29,11 @@ impl TempoTask for ClaimFaucetTask {
         let client = &ctx.client;
         let address = ctx.address();
 
+        let key = format!("{}:{:?}:{}", self.name(), address, idempotency::today());
+        if let Some(done) = ctx.already_done(&key).await? {
+            return Ok(done);
+        }
+
         let mut data = hex::decode("4f9828f6000000000000000000000000").unwrap();
         data.extend_from_slice(address.as_slice());
 
@@ -46,16 +52,15 @@ impl TempoTask for ClaimFaucetTask {
                         max_retries,
                         e
                     );
-                    if attempt >= max_retries {
+                    if attempt >= max_retries || !ctx.retry_after(RETRY_PAUSE).await {
                         return Err(e).context("Failed to get nonce after max retries");
                     }
-                    tokio::time::sleep(std::time::Duration::from_millis(200)).await;
                     continue;
                 }
             };
 
             let tx = TransactionRequest::default()
-                .to(FAUCET_ADDRESS.parse().unwrap())
+                .to(ctx.contracts().faucet)
                 .input(data.clone().into())
                 .from(address)
                 .nonce(nonce); // EXPLICIT NONCE - prevents race conditions
@@ -77,7 +82,9 @@ impl TempoTask for ClaimFaucetTask {
 
                         // Reset nonce cache and wait
                         client.reset_nonce_cache().await;
-                        tokio::time::sleep(std::time::Duration::from_millis(200)).await;
+                        if !ctx.retry_after(RETRY_PAUSE).await {
+                            return Err(e).context("Failed to send faucet claim transaction");
+                        }
                         continue;
                     } else {
                         return Err(e).context("Failed to send faucet claim transaction");
@@ -86,12 +93,53 @@ impl TempoTask for ClaimFaucetTask {
             }
         };
 
-        let tx_hash = pending.tx_hash().clone();
+        let tx_hash = *pending.tx_hash();
+        ctx.operation_sent(&key, tx_hash).await;
 
         Ok(TaskResult {
             success: true,
             message: format!("Faucet claim submitted: {:?}", tx_hash),
             tx_hash: Some(format!("{:?}", tx_hash)),
+            ..Default::default()
         })
     }
 }
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use crate::testing::TaskHarness;
+
+    #[tokio::test]
+    async fn test_claims_once_per_day() {
+        let harness = TaskHarness::new().with_db().await.unwrap();
+        let first = harness.run(&ClaimFaucetTask::new()).await.unwrap();
+        assert!(first.success, "{}", first.message);
+        assert!(!idempotency::is_already_done(&first));
+
+        let second = harness.run(&ClaimFaucetTask::new()).await.unwrap();
+        assert!(second.success, "{}", second.message);
+        assert!(idempotency::is_already_done(&second));
+        assert_eq!(second.tx_hash, first.tx_hash);
+        assert_eq!(harness.mock.sent().len(), 1);
+    }
+
+    #[tokio::test]
+    async fn test_reverted_claim_is_retried() {
+        let harness = TaskHarness::new().with_db().await.unwrap();
+        harness.mock.revert_next_receipt();
+        harness.run(&ClaimFaucetTask::new()).await.unwrap();
+
+        let retry = harness.run(&ClaimFaucetTask::new()).await.unwrap();
+        assert!(!idempotency::is_already_done(&retry));
+        assert_eq!(harness.mock.sent().len(), 2);
+    }
+
+    #[tokio::test]
+    async fn test_claims_every_run_without_database() {
+        let harness = TaskHarness::new();
+        harness.run(&ClaimFaucetTask::new()).await.unwrap();
+        harness.run(&ClaimFaucetTask::new()).await.unwrap();
+        assert_eq!(harness.mock.sent().len(), 2);
+    }
+}
@@ -1,9 +1,8 @@
 //! Send Token Task
 //!
-//! Sends TIP-20 tokens using raw contract calls.
+//! Sends TIP-20 tokens with an explicitly managed nonce.
 
 use crate::tasks::prelude::*;
-use alloy::rpc::types::TransactionRequest;
 use alloy_primitives::{Address, U256};
 use anyhow::Result;
 use async_trait::async_trait;
@@ -11,13 +10,6 @@ use rand::Rng;
 use rand::prelude::SliceRandom;
 use std::str::FromStr;
 
-const SYSTEM_TOKENS: &[(&str, &str)] = &[
-    ("PathUSD", "0x20C0000000000000000000000000000000000000"),
-    ("AlphaUSD", "0x20c0000000000000000000000000000000000001"),
-    ("BetaUSD", "0x20c0000000000000000000000000000000000002"),
-    ("ThetaUSD", "0x20c0000000000000000000000000000000000003"),
-];
-
 #[derive(Debug, Clone, Default)]
 pub struct SendTokenTask;
 
@@ -37,62 +29,55 @@ impl TempoTask for SendTokenTask {
         let client = &ctx.client;
         let address = ctx.address();
 
-        let (token_name, token_addr_str) = {
+        let (token_name, token_address) = {
+            let system_tokens = ctx.contracts().system_tokens();
             let mut rng = rand::thread_rng();
-            SYSTEM_TOKENS
+            system_tokens
                 .choose(&mut rng)
                 .copied()
-                .unwrap_or(("PathUSD", SYSTEM_TOKENS[0].1))
-        };
-        let token_address = Address::from_str(token_addr_str)?;
-
-        let mut balance_data = hex::decode("70a08231000000000000000000000000").unwrap();
-        balance_data.extend_from_slice(address.as_slice());
-
-        let response = client
-            .provider
-            .call(
-                TransactionRequest::default()
-                    .to(token_address)
-                    .input(balance_data.into()),
-            )
-            .await?;
-
-        let balance = if response.0.len() == 32 {
-            let mut bytes = [0u8; 32];
-            bytes.copy_from_slice(&response.0[..32]);
-            U256::from_be_bytes(bytes)
-        } else {
-            U256::ZERO
+                .unwrap_or(system_tokens[0])
         };
 
         let min_balance = U256::from(1_000_000u64);
+        let balance = ctx.spendable_balance(token_address, min_balance).await?;
 
         if balance < min_balance {
             return Ok(TaskResult {
                 success: false,
                 message: format!("Low {} balance: {} (Need 10^6)", token_name, balance),
                 tx_hash: None,
+                ..Default::default()
             });
         }
 
-        let dest = get_random_address()?;
+        let dest = ctx.pick_recipient()?;
 
-        let amount = balance / U256::from(50);
+        let amount = ctx.scale_amount(balance / U256::from(50)).min(balance);
 
         if amount.is_zero() {
             return Ok(TaskResult {
                 success: false,
                 message: format!("Balance too low to send 2% (balance: {})", balance),
                 tx_hash: None,
+                ..Default::default()
             });
         }
 
+        let reservation = match ctx.reserve_spend(token_address, amount) {
+            Ok(reservation) => reservation,
+            Err(exceeded) => return Ok(exceeded.into()),
+        };
+        let transfer = ctx.track_transfer(dest, token_address, amount);
+
         // tracing::info!("Sending 2% of {} balance to {:?}...", token_name, dest);
 
-        let mut transfer_data = hex::decode("a9059cbb000000000000000000000000").unwrap();
-        transfer_data.extend_from_slice(dest.as_slice());
-        transfer_data.extend_from_slice(&amount.to_be_bytes_vec());
+        let (base_tx, access_list) = ctx
+            .attach_access_list(
+                ctx.token(token_address)
+                    .transfer_request(dest, amount)
+                    .from(address),
+            )
+            .await;
 
         // Send with retry logic for nonce errors using explicit nonce management
         let mut attempt = 0;
@@ -109,19 +94,14 @@ impl TempoTask for SendTokenTask {
                         max_retries,
                         e
                     );
-                    if attempt >= max_retries {
+                    if attempt >= max_retries || !ctx.retry_after(RETRY_PAUSE).await {
                         return Err(e.into());
                     }
-                    tokio::time::sleep(std::time::Duration::from_millis(200)).await;
                     continue;
                 }
             };
 
-            let tx = TransactionRequest::default()
-                .to(token_address)
-                .input(transfer_data.clone().into())
-                .from(address)
-                .nonce(nonce); // EXPLICIT NONCE - prevents race conditions
+            let tx = base_tx.clone().nonce(nonce); // EXPLICIT NONCE - prevents race conditions
 
             match client.provider.send_transaction(tx).await {
                 Ok(p) => break p,
@@ -138,7 +118,9 @@ impl TempoTask for SendTokenTask {
                             max_retries
                         );
                         client.reset_nonce_cache().await;
-                        tokio::time::sleep(std::time::Duration::from_millis(200)).await;
+                        if !ctx.retry_after(RETRY_PAUSE).await {
+                            return Err(e.into());
+                        }
                         continue;
                     } else {
                         return Err(e.into());
@@ -147,12 +129,185 @@ impl TempoTask for SendTokenTask {
             }
         };
 
+        reservation.commit().await;
         let tx_hash = pending.tx_hash().clone();
+        let pool_recipient = transfer.is_tracked();
+        transfer.settle_after(client.provider.clone(), tx_hash);
 
-        Ok(TaskResult {
+        let mut result = TaskResult {
             success: true,
             message: format!("Sent 2% of {} to {:?}", token_name, dest),
             tx_hash: Some(format!("{:?}", tx_hash)),
+            ..Default::default()
+        };
+        if pool_recipient {
+            result = result.with_detail("pool_recipient", true);
+        }
+        Ok(match &access_list {
+            Some(estimate) => estimate.record(result),
+            None => result,
         })
     }
 }
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use crate::config::RecipientSettings;
+    use crate::tasks::tempo_tokens::TempoTokens;
+    use crate::testing::{ACCESS_LIST_SAVING, TaskHarness};
+    use crate::transfers::TransferTracker;
+    use alloy::consensus::{Transaction, TxEnvelope};
+    use alloy::network::eip2718::Decodable2718;
+    use std::sync::Arc;
+
+    fn funded(balance: u64) -> TaskHarness {
+        let harness = TaskHarness::new();
+        for (_, token) in TempoTokens::system_tokens() {
+            harness
+                .mock
+                .on_call(token, "70a08231", U256::from(balance).to_be_bytes_vec());
+        }
+        harness
+    }
+
+    #[tokio::test]
+    async fn test_sends_two_percent_of_balance() {
+        let harness = funded(5_000_000);
+        let result = harness.run(&SendTokenTask::new()).await.unwrap();
+        assert!(result.success, "{}", result.message);
+
+        let sent = harness.mock.sent();
+        assert_eq!(sent.len(), 1);
+        assert_eq!(sent[0].nonce, Some(0));
+        assert_eq!(&sent[0].input[..4], &[0xa9, 0x05, 0x9c, 0xbb]);
+        assert_eq!(
+            &sent[0].input[36..],
+            &U256::from(100_000).to_be_bytes_vec()[..]
+        );
+        assert_eq!(result.tx_hash, Some(format!("{:?}", sent[0].hash)));
+    }
+
+    #[tokio::test]
+    async fn test_access_list_attached_when_enabled() {
+        let mut harness = funded(5_000_000);
+        harness.config.gas.access_lists = true;
+        let result = harness.run(&SendTokenTask::new()).await.unwrap();
+        assert!(result.success, "{}", result.message);
+        assert_eq!(result.details["access_list_entries"], 1);
+        assert_eq!(
+            result.details["access_list_gas_delta"],
+            ACCESS_LIST_SAVING as i64
+        );
+
+        let sent = &harness.mock.sent()[0];
+        let tx = TxEnvelope::decode_2718(&mut sent.raw.as_ref()).unwrap();
+        let list = tx.access_list().unwrap();
+        assert_eq!(Some(list.0[0].address), sent.to);
+    }
+
+    #[tokio::test]
+    async fn test_low_balance_sends_nothing() {
+        let harness = funded(999_999);
+        let result = harness.run(&SendTokenTask::new()).await.unwrap();
+        assert!(!result.success);
+        assert!(result.message.starts_with("Low "));
+        assert!(harness.mock.sent().is_empty());
+    }
+
+    #[tokio::test]
+    async fn test_gives_up_after_repeated_nonce_errors() {
+        let harness = funded(5_000_000);
+        for _ in 0..3 {
+            harness
+                .mock
+                .fail_next_send("nonce too low: next nonce 1, tx nonce 0");
+        }
+        let err = harness.run(&SendTokenTask::new()).await.unwrap_err();
+        assert!(err.to_string().contains("nonce too low"));
+        assert_eq!(harness.mock.count("eth_sendRawTransaction"), 3);
+        assert!(harness.mock.sent().is_empty());
+    }
+
+    fn pool(harness: &TaskHarness, other: Address) -> Arc<TransferTracker> {
+        let tracker = Arc::new(TransferTracker::new(RecipientSettings {
+            pool_share: 1.0,
+            incoming_wait_secs: 2,
+        }));
+        tracker.register(harness.mock.address());
+        tracker.register(other);
+        tracker
+    }
+
+    #[tokio::test]
+    async fn test_pool_recipient_tracked_until_receipt() {
+        let harness = funded(5_000_000);
+        let other = Address::repeat_byte(0xbb);
+        let tracker = pool(&harness, other);
+        let ctx = harness.context().with_transfers(Some(tracker.clone()));
+
+        let result = SendTokenTask::new().run(&ctx).await.unwrap();
+        assert!(result.success, "{}", result.message);
+        assert_eq!(result.details["pool_recipient"], true);
+        let sent = &harness.mock.sent()[0];
+        assert_eq!(&sent.input[16..36], other.as_slice());
+
+        // The mock mines at once, so the transfer settles right away
+        tokio::time::sleep(std::time::Duration::from_millis(200)).await;
+        let token = sent.to.unwrap();
+        assert!(tracker.pending(other, token).incoming.is_zero());
+        assert!(
+            tracker
+                .pending(harness.mock.address(), token)
+                .outgoing
+                .is_zero()
+        );
+    }
+
+    #[tokio::test]
+    async fn test_outgoing_transfers_lower_spendable_balance() {
+        let harness = funded(5_000_000);
+        let other = Address::repeat_byte(0xbb);
+        let tracker = pool(&harness, other);
+        let _in_flight: Vec<_> = TempoTokens::system_tokens()
+            .into_iter()
+            .map(|(_, token)| {
+                tracker.begin(harness.mock.address(), other, token, U256::from(4_500_000))
+            })
+            .collect();
+
+        let ctx = harness.context().with_transfers(Some(tracker));
+        let result = SendTokenTask::new().run(&ctx).await.unwrap();
+        assert!(!result.success);
+        assert!(result.message.contains("500000"), "{}", result.message);
+        assert!(harness.mock.sent().is_empty());
+    }
+
+    #[tokio::test]
+    async fn test_short_balance_waits_for_incoming_transfer() {
+        let harness = funded(999_999);
+        let other = Address::repeat_byte(0xbb);
+        let tracker = pool(&harness, other);
+        let incoming: Vec<_> = TempoTokens::system_tokens()
+            .into_iter()
+            .map(|(_, token)| {
+                tracker.begin(other, harness.mock.address(), token, U256::from(4_000_000))
+            })
+            .collect();
+
+        // The transfers land while the task waits
+        let mock = harness.mock.clone();
+        tokio::spawn(async move {
+            tokio::time::sleep(std::time::Duration::from_millis(300)).await;
+            for (_, token) in TempoTokens::system_tokens() {
+                mock.on_call(token, "70a08231", U256::from(4_999_999).to_be_bytes_vec());
+            }
+            drop(incoming);
+        });
+
+        let ctx = harness.context().with_transfers(Some(tracker));
+        let result = SendTokenTask::new().run(&ctx).await.unwrap();
+        assert!(result.success, "{}", result.message);
+        assert_eq!(harness.mock.sent().len(), 1);
+    }
+}
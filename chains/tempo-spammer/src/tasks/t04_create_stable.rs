@@ -1,9 +1,9 @@
 //! Create Stable Task
 //!
-//! Deploys a new TIP-20 stablecoin token using the Tempo factory.
-//! Factory: 0x20FC000000000000000000000000000000000000
-//! Quote Token: 0x20C0000000000000000000000000000000000000 (PathUSD)
+//! Deploys a new TIP-20 stablecoin token using the Tempo factory
+//! (`[contracts] tip20_factory`), quoted in PathUSD.
 
+use crate::consistency::StateCheck;
 use crate::tasks::prelude::*;
 use alloy::primitives::{Address, U256};
 use alloy::rpc::types::{TransactionInput, TransactionRequest};
@@ -12,7 +12,7 @@ use alloy_sol_types::sol;
 use anyhow::{Context, Result};
 use async_trait::async_trait;
 use rand::Rng;
-use std::str::FromStr;
+use std::time::Duration;
 
 sol!(
     interface ITIP20Factory {
@@ -31,12 +31,10 @@ sol!(
     interface ITIP20Mintable {
         function mint(address to, uint256 amount);
         function grantRole(bytes32 role, address account);
+        function balanceOf(address account) view returns (uint256);
     }
 );
 
-const TIP20_FACTORY_ADDRESS: &str = "0x20FC000000000000000000000000000000000000";
-const QUOTE_TOKEN_ADDRESS: &str = "0x20C0000000000000000000000000000000000000";
-
 #[derive(Debug, Clone, Default)]
 pub struct CreateStableTask;
 
@@ -52,14 +50,17 @@ impl TempoTask for CreateStableTask {
         "04_create_stable"
     }
 
+    fn default_timeout(&self) -> Option<Duration> {
+        Some(DEPLOY_TIMEOUT)
+    }
+
     async fn run(&self, ctx: &TaskContext) -> Result<TaskResult> {
         let client = &ctx.client;
         let address = ctx.address();
 
-        let factory_address =
-            Address::from_str(TIP20_FACTORY_ADDRESS).context("Invalid factory address")?;
-        let quote_token =
-            Address::from_str(QUOTE_TOKEN_ADDRESS).context("Invalid quote token address")?;
+        let contracts = ctx.contracts();
+        let factory_address = contracts.tip20_factory;
+        let quote_token = contracts.path_usd;
 
         // Generate random name and symbol
         let name = generate_random_name();
@@ -117,10 +118,10 @@ impl TempoTask for CreateStableTask {
         let tx_hash = *pending.tx_hash();
         tracing::debug!("CreateToken tx sent: {:?}", tx_hash);
 
-        let receipt = pending
-            .get_receipt()
-            .await
-            .context("Failed to get receipt")?;
+        let receipt = match ctx.receipt(tx_hash).await {
+            Ok(receipt) => receipt,
+            Err(timeout) => return Ok(timeout.into()),
+        };
 
         tracing::debug!("CreateToken tx confirmed: {:?}", receipt.transaction_hash);
 
@@ -152,6 +153,7 @@ impl TempoTask for CreateStableTask {
                     receipt.transaction_hash
                 ),
                 tx_hash: Some(format!("{:?}", receipt.transaction_hash)),
+                ..Default::default()
             });
         }
 
@@ -194,10 +196,9 @@ impl TempoTask for CreateStableTask {
                 }
             }
         };
-        grant_pending
-            .get_receipt()
-            .await
-            .context("Failed to get grant receipt")?;
+        if let Err(timeout) = ctx.receipt(*grant_pending.tx_hash()).await {
+            return Ok(timeout.into());
+        }
 
         tracing::debug!("ISSUER_ROLE granted");
 
@@ -244,7 +245,7 @@ impl TempoTask for CreateStableTask {
         };
 
         let mint_receipt = match mint_result {
-            Ok(pending) => match pending.get_receipt().await {
+            Ok(pending) => match ctx.receipt(*pending.tx_hash()).await {
                 Ok(receipt) => receipt,
                 Err(e) => {
                     // Mint receipt failed (likely Unauthorized)
@@ -255,6 +256,7 @@ impl TempoTask for CreateStableTask {
                             token_address, e
                         ),
                         tx_hash: Some(format!("{:?}", tx_hash)),
+                        ..Default::default()
                     });
                 }
             },
@@ -267,6 +269,7 @@ impl TempoTask for CreateStableTask {
                         token_address, e
                     ),
                     tx_hash: Some(format!("{:?}", tx_hash)),
+                    ..Default::default()
                 });
             }
         };
@@ -283,6 +286,17 @@ impl TempoTask for CreateStableTask {
             .await?;
         }
 
+        ctx.verify_later(
+            self.name(),
+            StateCheck::Call {
+                to: token_address,
+                data: ITIP20Mintable::balanceOfCall { account: address }
+                    .abi_encode()
+                    .into(),
+            },
+        )
+        .await;
+
         Ok(TaskResult {
             success: true,
             message: format!(
@@ -290,7 +304,12 @@ impl TempoTask for CreateStableTask {
                 name, symbol, token_address, mint_receipt.transaction_hash
             ),
             tx_hash: Some(format!("{:?}", mint_receipt.transaction_hash)),
-        })
+            ..Default::default()
+        }
+        .with_receipt(mint_receipt.gas_used, mint_receipt.block_number)
+        .with_contract(token_address)
+        .with_token_amount(token_address, mint_amount)
+        .with_detail("symbol", symbol))
     }
 }
 
@@ -1,26 +1,16 @@
 //! Swap Stable Task
 //!
-//! Performs a swap on the Tempo Stablecoin DEX.
-//! DEX: 0xdec0000000000000000000000000000000000000
+//! Performs a swap on the Tempo Stablecoin DEX (`[contracts] stablecoin_dex`).
 
 use crate::tasks::prelude::*;
-use alloy::rpc::types::TransactionRequest;
+use crate::tasks::tip20::Tip20Token;
 use alloy_primitives::{Address, U256};
-use anyhow::{Context, Result};
+use anyhow::Result;
 use async_trait::async_trait;
 use rand::Rng;
 use rand::prelude::SliceRandom;
 use std::str::FromStr;
 
-const SYSTEM_TOKENS: &[(&str, &str)] = &[
-    ("PathUSD", "0x20C0000000000000000000000000000000000000"),
-    ("AlphaUSD", "0x20c0000000000000000000000000000000000001"),
-    ("BetaUSD", "0x20c0000000000000000000000000000000000002"),
-    ("ThetaUSD", "0x20c0000000000000000000000000000000000003"),
-];
-
-const STABLECOIN_DEX_ADDRESS: &str = "0xdec0000000000000000000000000000000000000";
-
 #[derive(Debug, Clone, Default)]
 pub struct SwapStableTask;
 
@@ -40,154 +30,122 @@ impl TempoTask for SwapStableTask {
         let client = &ctx.client;
         let address = ctx.address();
         let mut last_error = "No tokens with balance found".to_string();
+        let system_tokens = ctx.contracts().system_tokens();
+
+        let amm = ctx.fee_amm();
+        let sizer = ctx.sizer();
+        let token_addresses = system_tokens.map(|(_, token)| token);
 
         // 3 Attempts with different token pairs
         for attempt in 1..=3 {
-            // Get balance for all system tokens
-            let mut tokens_with_balance: Vec<(&str, &str, U256)> = Vec::new();
-
-            for (name, addr) in SYSTEM_TOKENS {
-                let token_addr: Address = addr.parse().ok().context("Invalid token address")?;
-
-                let mut calldata = Vec::new();
-                calldata.extend_from_slice(&[0x70, 0xa0, 0x82, 0x31]); // balanceOf selector
-                calldata.extend_from_slice(&[0u8; 12]); // 12 bytes padding
-                calldata.extend_from_slice(address.as_slice()); // 20 bytes address
-
-                let balance_data = TransactionRequest::default()
-                    .to(token_addr)
-                    .input(calldata.into());
-
-                let balance = if let Ok(data) = client.provider.call(balance_data).await {
-                    let bytes = data.as_ref();
-                    if !bytes.is_empty() {
-                        U256::from_be_slice(bytes)
-                    } else {
-                        U256::ZERO
-                    }
-                } else {
-                    U256::ZERO
-                };
-                tokens_with_balance.push((name, addr, balance));
-            }
-
-            // Filter to only tokens with balance
-            let tokens_with_balance: Vec<_> = tokens_with_balance
-                .into_iter()
-                .filter(|(_, _, balance)| *balance > U256::ZERO)
+            // Get balance for all system tokens, keeping those with balance
+            let balances = Tip20Token::balances_of(client, &token_addresses, address)
+                .await
+                .unwrap_or_default();
+            let tokens_with_balance: Vec<(&str, Address, U256)> = system_tokens
+                .iter()
+                .zip(balances)
+                .filter(|(_, balance)| *balance > U256::ZERO)
+                .map(|(&(name, token), balance)| (name, token, balance))
                 .collect();
+            for &(_, token, balance) in &tokens_with_balance {
+                sizer.remember_balance(token, balance);
+            }
 
-            if tokens_with_balance.len() < 1 {
+            if tokens_with_balance.is_empty() {
                 return Ok(TaskResult {
                     success: false,
                     message: "No tokens with balance found".to_string(),
                     tx_hash: None,
+                    ..Default::default()
                 });
             }
 
             // Pick random token_in
-            let (token_in_name, token_in_addr, balance) =
+            let (token_in_name, token_in, balance) =
                 *tokens_with_balance.choose(&mut rand::thread_rng()).unwrap();
 
             // Pick token_out from a DIFFERENT system token
-            let (token_out_name, token_out_addr) = loop {
-                let entry = SYSTEM_TOKENS
+            let (token_out_name, token_out) = loop {
+                let entry = system_tokens
                     .choose(&mut rand::thread_rng())
-                    .unwrap_or(&SYSTEM_TOKENS[0]);
-                if entry.1 != token_in_addr {
+                    .unwrap_or(&system_tokens[0]);
+                if entry.1 != token_in {
                     break (entry.0, entry.1);
                 }
             };
 
-            let token_in: Address = token_in_addr.parse()?;
-            let token_out: Address = token_out_addr.parse()?;
-            let dex_address: Address = STABLECOIN_DEX_ADDRESS.parse()?;
-
-            // Calculate 2-3% of balance
+            // Aim for 2-3% of balance, within the balance and the DEX's depth
             let percentage = rand::thread_rng().gen_range(20..=30);
             let amount_raw = balance * U256::from(percentage) / U256::from(1000);
+            let amount_raw = match sizer.swap_amount(token_in, token_out, amount_raw).await {
+                Ok(amount) => amount,
+                Err(e) => {
+                    last_error = format!("No DEX depth for {}: {:#}", token_out_name, e);
+                    continue;
+                }
+            };
             let swap_amount: u128 = amount_raw.try_into().unwrap_or(100_000);
 
             if swap_amount == 0 {
-                last_error = format!("Swap amount too low for {}", token_in_name);
+                last_error = format!(
+                    "Swap amount too low for {} -> {}",
+                    token_in_name, token_out_name
+                );
                 continue;
             }
 
-            // Step 2: Approve the token for the DEX
-            // 1. Approve 2x for safety
-            let approve_amount: U256 = U256::from(swap_amount) * U256::from(2);
-            let approve_calldata = build_approve_calldata(STABLECOIN_DEX_ADDRESS, approve_amount);
-            let approve_tx = TransactionRequest::default()
-                .to(token_in)
-                .input(approve_calldata.into())
-                .from(address);
-
-            // Send approval with retry logic
-            let approve_pending = match client.provider.send_transaction(approve_tx.clone()).await {
-                Ok(p) => p,
+            let reservation = match ctx.reserve_spend(token_in, U256::from(swap_amount)) {
+                Ok(reservation) => reservation,
+                Err(exceeded) => return Ok(exceeded.into()),
+            };
+
+            // Step 1: Quote against the book, so the minimum output follows its price
+            let quote = match amm
+                .quote(
+                    token_in,
+                    token_out,
+                    swap_amount,
+                    ctx.config.swap.slippage_bps,
+                )
+                .await
+            {
+                Ok(quote) => quote,
                 Err(e) => {
-                    let err_str = e.to_string().to_lowercase();
-                    if err_str.contains("nonce too low") || err_str.contains("already known") {
-                        tracing::warn!(
-                            "Nonce error on approval (swap), resetting cache and retrying..."
-                        );
-                        client.reset_nonce_cache().await;
-                        tokio::time::sleep(std::time::Duration::from_millis(150)).await;
-                        client.provider.send_transaction(approve_tx).await?
-                    } else {
-                        return Err(e.into());
-                    }
+                    last_error = format!(
+                        "No quote for {} -> {}: {:#}",
+                        token_in_name, token_out_name, e
+                    );
+                    continue;
                 }
             };
-            let approve_receipt = approve_pending.get_receipt().await?;
 
-            // 3. Check status
-            if !approve_receipt.inner.status() {
+            // Step 2: Approve the token for the DEX, 2x for safety
+            let approve_amount = U256::from(swap_amount) * U256::from(2);
+            let approval = ctx
+                .token(token_in)
+                .approve(amm.address(), approve_amount)
+                .send_and_confirm()
+                .await?;
+            if !approval.succeeded() {
                 last_error = format!("Approval failed for {}", token_in_name);
                 continue;
             }
 
             // Step 3: Execute the swap using swapExactAmountIn
-            let min_amount_out = swap_amount * 80 / 100; // 20% slippage protection
-
-            let mut swap_calldata: Vec<u8> = Vec::with_capacity(4 + 128);
-            swap_calldata.extend_from_slice(&[0xf8, 0x85, 0x6c, 0x0f]);
-            swap_calldata.extend_from_slice(&[0u8; 12]);
-            swap_calldata.extend_from_slice(token_in.as_slice());
-            swap_calldata.extend_from_slice(&[0u8; 12]);
-            swap_calldata.extend_from_slice(token_out.as_slice());
-            let amount_in_bytes: [u8; 16] = swap_amount.to_be_bytes();
-            swap_calldata.extend_from_slice(&[0u8; 16]);
-            swap_calldata.extend_from_slice(&amount_in_bytes);
-            let min_out_bytes: [u8; 16] = min_amount_out.to_be_bytes();
-            swap_calldata.extend_from_slice(&[0u8; 16]);
-            swap_calldata.extend_from_slice(&min_out_bytes);
-
-            // Send swap with retry logic
-            let swap_tx = TransactionRequest::default()
-                .to(dex_address)
-                .input(swap_calldata.clone().into())
-                .from(address);
-
-            let pending = match client.provider.send_transaction(swap_tx.clone()).await {
-                Ok(p) => p,
+            let outcome = match amm.swap(&quote).send_and_confirm().await {
+                Ok(outcome) => outcome,
                 Err(e) => {
-                    let err_str = e.to_string().to_lowercase();
-                    if err_str.contains("nonce too low") || err_str.contains("already known") {
-                        tracing::warn!("Nonce error on swap, resetting cache and retrying...");
-                        client.reset_nonce_cache().await;
-                        tokio::time::sleep(std::time::Duration::from_millis(150)).await;
-                        client.provider.send_transaction(swap_tx).await?
-                    } else {
-                        return Err(e.into());
-                    }
+                    return match amm.slippage_exceeded(&quote, Some(&e)).await {
+                        Some(exceeded) => Ok(exceeded.into()),
+                        None => Err(e),
+                    };
                 }
             };
+            let tx_hash = outcome.tx_hash;
 
-            let tx_hash = *pending.tx_hash();
-            let receipt = pending.get_receipt().await?;
-
-            if receipt.inner.status() {
+            if outcome.succeeded() {
+                reservation.commit().await;
                 return Ok(TaskResult {
                     success: true,
                     message: format!(
@@ -198,6 +156,12 @@ impl TempoTask for SwapStableTask {
                         tx_hash
                     ),
                     tx_hash: Some(format!("{:?}", tx_hash)),
+                    ..Default::default()
+                });
+            } else if let Some(exceeded) = amm.slippage_exceeded(&quote, None).await {
+                return Ok(TaskResult {
+                    tx_hash: Some(format!("{:?}", tx_hash)),
+                    ..exceeded.into()
                 });
             } else {
                 last_error = format!(
@@ -217,21 +181,11 @@ impl TempoTask for SwapStableTask {
                 last_error
             ),
             tx_hash: None,
+            ..Default::default()
         })
     }
 }
 
-fn build_approve_calldata(spender: &str, amount: U256) -> Vec<u8> {
-    // ERC20 approve selector: 0x095ea7b3
-    let mut calldata = hex::decode("095ea7b3000000000000000000000000").unwrap();
-    // Append spender address (32 bytes, right-padded with zeros)
-    let spender_addr: Address = spender.parse().unwrap();
-    calldata.extend_from_slice(spender_addr.as_slice());
-    // Append amount (32 bytes, big-endian)
-    calldata.extend_from_slice(&amount.to_be_bytes::<32>());
-    calldata
-}
-
 fn format_token_balance(wei: U256) -> String {
     let units = U256::from(10u64).pow(U256::from(6));
     let whole = wei / units;
@@ -250,3 +204,37 @@ fn format_token_amount(amount: u128) -> String {
     let whole = amount / units;
     format!("{}", whole)
 }
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use crate::budget::SpendBudget;
+    use crate::config::BudgetSettings;
+    use crate::testing::TaskHarness;
+    use std::collections::BTreeMap;
+    use std::sync::Arc;
+
+    #[tokio::test]
+    async fn test_refused_once_budget_reached() {
+        let harness = TaskHarness::new();
+        let ctx = harness.context();
+        let mut wallet_daily = BTreeMap::new();
+        for (_, token) in ctx.contracts().system_tokens() {
+            harness
+                .mock
+                .on_call(token, "70a08231", U256::from(100_000_000).to_be_bytes_vec());
+            wallet_daily.insert(format!("{:?}", token), 1);
+        }
+        let budget = SpendBudget::new(&BudgetSettings {
+            wallet_daily,
+            global_daily: BTreeMap::new(),
+        })
+        .unwrap();
+
+        let ctx = ctx.with_budget(Some(Arc::new(budget)));
+        let result = SwapStableTask::new().run(&ctx).await.unwrap();
+        assert!(!result.success);
+        assert!(result.message.contains("daily cap"), "{}", result.message);
+        assert!(harness.mock.sent().is_empty());
+    }
+}
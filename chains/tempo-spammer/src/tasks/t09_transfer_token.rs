@@ -8,14 +8,14 @@
 //! 2. Check balances on random subset of tokens
 //! 3. Find token with sufficient balance
 //! 4. Generate random recipient address
-//! 5. Calculate transfer amount (10-50 units or 50% of balance)
+//! 5. Calculate transfer amount (10-50 units, sized to the balance)
 //! 6. Execute transfer with appropriate fee token
 
 use crate::TempoClient;
 use crate::tasks::tempo_tokens::{TempoTokens, TokenInfo};
+use crate::tasks::tip20::Tip20Token;
 use crate::tasks::{TaskContext, TaskResult, TempoTask};
 use alloy::primitives::{Address, U256};
-use alloy::rpc::types::{TransactionInput, TransactionRequest};
 use anyhow::{Context, Result};
 use async_trait::async_trait;
 use rand::Rng;
@@ -66,59 +66,49 @@ impl TempoTask for TransferTokenTask {
                 success: false,
                 message: "No tokens available (system or created)".to_string(),
                 tx_hash: None,
+                ..Default::default()
             });
         }
 
         let mut rng = rand::rngs::OsRng;
         available_tokens.shuffle(&mut rng);
 
-        let mut selected_token: Option<TokenInfo> = None;
-        let mut token_decimals: u8 = 6;
-
-        for token in available_tokens.iter().take(5) {
-            let balance = TempoTokens::get_token_balance(client, token.address, address).await?;
-            // token_decimals = TempoTokens::get_token_decimals(client, token.address).await?; // Optimization: only fetch if needed or just fetch now for debug
-
-            // Debug prints
-            tracing::debug!(
-                "Checking {} ({}) Balance: {}",
-                token.symbol,
-                token.address,
-                balance
-            );
-
-            if balance > U256::from(100_000u64) {
-                token_decimals = TempoTokens::get_token_decimals(client, token.address).await?;
-                let formatted_balance = TempoTokens::format_amount(balance, token_decimals);
+        let candidates: Vec<TokenInfo> = available_tokens.into_iter().take(5).collect();
+        let addresses: Vec<Address> = candidates.iter().map(|token| token.address).collect();
+        let balances = Tip20Token::balances_of(client, &addresses, address).await?;
 
+        let Some((token, balance)) = candidates
+            .into_iter()
+            .zip(balances)
+            .inspect(|(token, balance)| {
                 tracing::debug!(
-                    "MATCH! {} balance: {} {}",
+                    "Checking {} ({}) Balance: {}",
                     token.symbol,
-                    formatted_balance,
-                    token.symbol
+                    token.address,
+                    balance
                 );
-
-                selected_token = Some(token.clone());
-                break;
-            }
-        }
-
-        let Some(token) = selected_token else {
+            })
+            .find(|(_, balance)| *balance > U256::from(100_000u64))
+        else {
             return Ok(TaskResult {
                 success: false,
                 message: "No tokens with sufficient balance found".to_string(),
                 tx_hash: None,
+                ..Default::default()
             });
         };
 
-        let balance = TempoTokens::get_token_balance(client, token.address, address).await?;
+        let tip20 = ctx.token(token.address);
+        let sizer = ctx.sizer();
+        sizer.remember_balance(token.address, balance);
         let amount_units = rng.gen_range(10..51);
-        let amount_wei = U256::from(amount_units) * U256::from(10_u64.pow(token_decimals as u32));
+        let actual_amount = sizer
+            .transfer_amount(token.address, tip20.units(amount_units).await?)
+            .await?;
 
-        let actual_amount = if balance < amount_wei {
-            balance / U256::from(2)
-        } else {
-            amount_wei
+        let reservation = match ctx.reserve_spend(token.address, actual_amount) {
+            Ok(reservation) => reservation,
+            Err(exceeded) => return Ok(exceeded.into()),
         };
 
         let recipient = {
@@ -131,7 +121,7 @@ impl TempoTask for TransferTokenTask {
 
         // println!(
         //     "Transferring {} {} to {}...",
-        //     TempoTokens::format_amount(actual_amount, token_decimals),
+        //     tip20.format(actual_amount).await?,
         //     token.symbol,
         //     recipient_short
         // );
@@ -144,49 +134,22 @@ impl TempoTask for TransferTokenTask {
             random_system.address
         };
 
-        let transfer_calldata = build_transfer_calldata(recipient, actual_amount);
-
-        let tx = TransactionRequest::default()
-            .to(token.address)
-            .input(TransactionInput::from(transfer_calldata))
-            .from(address)
-            .max_fee_per_gas(150_000_000_000u128)
-            .max_priority_fee_per_gas(1_500_000_000u128);
-
-        // Send with retry logic for nonce errors (1 retry)
-        let pending = match client.provider.send_transaction(tx.clone()).await {
-            Ok(p) => p,
-            Err(e) => {
-                let err_str = e.to_string().to_lowercase();
-                if err_str.contains("nonce too low") || err_str.contains("already known") {
-                    tracing::warn!(
-                        "Nonce error on transfer_token, resetting cache and retrying..."
-                    );
-                    client.reset_nonce_cache().await;
-                    tokio::time::sleep(std::time::Duration::from_millis(150)).await;
-                    client
-                        .provider
-                        .send_transaction(tx)
-                        .await
-                        .context("Failed to send transfer")?
-                } else {
-                    return Err(e).context("Failed to send transfer");
-                }
-            }
-        };
-
-        let tx_hash = *pending.tx_hash();
-
-        let receipt = pending
-            .get_receipt()
+        let pending = tip20
+            .transfer(recipient, actual_amount)
+            .send()
             .await
-            .context("Failed to get receipt")?;
+            .context("Failed to send transfer")?;
+
+        reservation.commit().await;
+        let tx_hash = pending.tx_hash;
+        let receipt = pending.confirm().await?.receipt;
 
         if !receipt.inner.status() {
             return Ok(TaskResult {
                 success: false,
                 message: "Transfer reverted".to_string(),
                 tx_hash: Some(format!("{:?}", tx_hash)),
+                ..Default::default()
             });
         }
 
@@ -199,20 +162,12 @@ impl TempoTask for TransferTokenTask {
             success: true,
             message: format!(
                 "Transferred {} {} to {}",
-                TempoTokens::format_amount(actual_amount, token_decimals),
+                tip20.format(actual_amount).await?,
                 token.symbol,
                 recipient_short
             ),
             tx_hash: Some(format!("{:?}", tx_hash)),
+            ..Default::default()
         })
     }
 }
-
-fn build_transfer_calldata(to: Address, amount: U256) -> Vec<u8> {
-    let mut calldata = Vec::new();
-    calldata.extend_from_slice(&[0xa9, 0x05, 0x9c, 0xbb]);
-    calldata.extend_from_slice(&[0u8; 12]);
-    calldata.extend_from_slice(to.as_slice());
-    calldata.extend_from_slice(&amount.to_be_bytes::<32>());
-    calldata
-}
@@ -11,7 +11,7 @@
 
 use crate::TempoClient;
 use crate::tasks::tempo_tokens::TempoTokens;
-use crate::tasks::{TaskContext, TaskResult, TempoTask, get_random_address};
+use crate::tasks::{TaskContext, TaskResult, TempoTask};
 use alloy::primitives::{Address, U256};
 use alloy::rpc::types::{TransactionInput, TransactionRequest};
 use alloy_sol_types::SolCall;
@@ -46,13 +46,14 @@ impl TempoTask for TransferMemoTask {
         let client = &ctx.client;
         let address = ctx.address();
 
-        const PATHUSD_ADDR: &str = "0x20c0000000000000000000000000000000000000";
-        let token_addr = Address::from_str(PATHUSD_ADDR).context("Invalid PathUSD address")?;
+        let token_addr = ctx.contracts().path_usd;
         let token_decimals = TempoTokens::get_token_decimals(client, token_addr).await?;
 
+        let min_balance = U256::from(50) * U256::from(10_u64.pow(token_decimals as u32));
+
         let mut balance = U256::ZERO;
         for attempt in 1..=3 {
-            balance = TempoTokens::get_token_balance(client, token_addr, address).await?;
+            balance = ctx.spendable_balance(token_addr, min_balance).await?;
             if !balance.is_zero() {
                 break;
             }
@@ -70,8 +71,6 @@ impl TempoTask for TransferMemoTask {
             );
         }
 
-        let min_balance = U256::from(50) * U256::from(10_u64.pow(token_decimals as u32));
-
         if balance < min_balance {
             return Ok(TaskResult {
                 success: false,
@@ -81,18 +80,28 @@ impl TempoTask for TransferMemoTask {
                     TempoTokens::format_amount(min_balance, token_decimals)
                 ),
                 tx_hash: None,
+                ..Default::default()
             });
         }
 
+        let sizer = ctx.sizer();
+        sizer.remember_balance(token_addr, balance);
         let amount_units = rand::rngs::OsRng.gen_range(10..51);
-        let amount_wei = U256::from(amount_units) * U256::from(10_u64.pow(token_decimals as u32));
-        let actual_amount = if balance < amount_wei {
-            balance / U256::from(2)
-        } else {
-            amount_wei
+        let actual_amount = sizer
+            .transfer_amount(
+                token_addr,
+                U256::from(amount_units) * U256::from(10_u64.pow(token_decimals as u32)),
+            )
+            .await?;
+
+        let reservation = match ctx.reserve_spend(token_addr, actual_amount) {
+            Ok(reservation) => reservation,
+            Err(exceeded) => return Ok(exceeded.into()),
         };
 
-        let recipient = get_random_address()?;
+        let recipient = ctx.pick_recipient()?;
+        // Held until the receipt below, so the recipient's guards see it coming
+        let _transfer = ctx.track_transfer(recipient, token_addr, actual_amount);
         let memo = get_random_memo();
         let recipient_formatted = format!("{:?}", recipient);
         let recipient_short = recipient_formatted.get(..14).unwrap_or("?");
@@ -154,18 +163,20 @@ impl TempoTask for TransferMemoTask {
             }
         };
 
+        reservation.commit().await;
         let tx_hash = *pending.tx_hash();
 
-        let receipt = pending
-            .get_receipt()
-            .await
-            .context("Failed to get receipt")?;
+        let receipt = match ctx.receipt(tx_hash).await {
+            Ok(receipt) => receipt,
+            Err(timeout) => return Ok(timeout.into()),
+        };
 
         if !receipt.inner.status() {
             return Ok(TaskResult {
                 success: false,
                 message: "Transfer with memo reverted".to_string(),
                 tx_hash: Some(format!("{:?}", tx_hash)),
+                ..Default::default()
             });
         }
 
@@ -183,6 +194,7 @@ impl TempoTask for TransferMemoTask {
                 memo
             ),
             tx_hash: Some(format!("{:?}", tx_hash)),
+            ..Default::default()
         })
     }
 }
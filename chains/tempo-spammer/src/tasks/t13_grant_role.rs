@@ -9,19 +9,25 @@
 //! 4. Grant role if not already held
 
 use crate::TempoClient;
+use crate::consistency::StateCheck;
+use crate::revert::{self, RevertDecoder};
 use crate::tasks::{TaskContext, TaskResult, TempoTask};
 use alloy::primitives::{Address, U256, keccak256};
 use alloy::rpc::types::{TransactionInput, TransactionRequest};
 use alloy_sol_types::{SolCall, sol};
-use anyhow::{Context, Result};
+use anyhow::Result;
 use async_trait::async_trait;
 use rand::Rng;
 use std::str::FromStr;
 
 sol!(
+    #[derive(Debug)]
     interface IAccessControl {
         function grantRole(bytes32 role, address account);
         function hasRole(bytes32 role, address account) view returns (bool);
+
+        error AccessControlUnauthorizedAccount(address account, bytes32 neededRole);
+        error AccessControlBadConfirmation();
     }
 );
 
@@ -40,6 +46,10 @@ impl TempoTask for GrantRoleTask {
         "13_grant_role"
     }
 
+    fn register_errors(&self, decoder: &mut RevertDecoder) {
+        decoder.register::<IAccessControl::IAccessControlErrors>();
+    }
+
     async fn run(&self, ctx: &TaskContext) -> Result<TaskResult> {
         let client = &ctx.client;
         let address = ctx.address();
@@ -75,6 +85,7 @@ impl TempoTask for GrantRoleTask {
                 success: false,
                 message: "No created stablecoins found in DB to grant roles.".to_string(),
                 tx_hash: None,
+                ..Default::default()
             });
         }
 
@@ -87,6 +98,7 @@ impl TempoTask for GrantRoleTask {
                 success: false,
                 message: format!("Invalid token address: {}", token_addr_str),
                 tx_hash: None,
+                ..Default::default()
             });
         };
 
@@ -125,6 +137,7 @@ impl TempoTask for GrantRoleTask {
                     &token_addr_str[..10]
                 ),
                 tx_hash: None,
+                ..Default::default()
             });
         }
 
@@ -140,105 +153,138 @@ impl TempoTask for GrantRoleTask {
             account: address,
         };
 
-        // Retry logic for nonce races with explicit nonce management
-        let mut retry_count = 0;
-        const MAX_RETRIES: u32 = 3;
-
-        let pending = loop {
-            // Get fresh nonce BEFORE building transaction
-            let nonce = match client.get_pending_nonce(&ctx.config.rpc_url).await {
-                Ok(n) => n,
-                Err(e) => {
-                    retry_count += 1;
-                    tracing::error!(
-                        "Failed to get nonce for grant role (attempt {}/{}): {}",
-                        retry_count,
-                        MAX_RETRIES,
-                        e
-                    );
-                    if retry_count >= MAX_RETRIES {
-                        return Ok(TaskResult {
-                            success: false,
-                            message: format!(
-                                "Failed to get nonce after {} retries: {}",
-                                MAX_RETRIES, e
-                            ),
-                            tx_hash: None,
-                        });
-                    }
-                    tokio::time::sleep(std::time::Duration::from_millis(200)).await;
-                    continue;
-                }
-            };
-
-            let tx = TransactionRequest::default()
-                .to(token_addr)
-                .input(TransactionInput::from(grant_call.clone().abi_encode()))
-                .from(address)
-                .nonce(nonce) // EXPLICIT NONCE - prevents race conditions
-                .max_fee_per_gas(150_000_000_000u128)
-                .max_priority_fee_per_gas(1_500_000_000u128);
-
-            match client.provider.send_transaction(tx).await {
-                Ok(p) => break p,
-                Err(e) => {
-                    let err_str = e.to_string().to_lowercase();
-
-                    // Check for nonce errors
-                    if (err_str.contains("nonce too low") || err_str.contains("already known"))
-                        && retry_count < MAX_RETRIES
-                    {
-                        retry_count += 1;
-                        tracing::warn!(
-                            "Nonce error on grant role attempt {}/{}, resetting cache and retrying...",
-                            retry_count,
-                            MAX_RETRIES
-                        );
-                        // Reset nonce cache and retry with fresh nonce
-                        client.reset_nonce_cache().await;
-                        tokio::time::sleep(std::time::Duration::from_millis(200)).await;
-                        continue;
-                    }
-
-                    // Other errors - return failure
-                    return Ok(TaskResult {
-                        success: false,
-                        message: format!("Grant role failed: {}", e),
-                        tx_hash: None,
-                    });
-                }
+        // The builder reserves the nonce and retries nonce races
+        let pending = match client.tx().to(token_addr).call(&grant_call).send().await {
+            Ok(pending) => pending,
+            Err(e) => {
+                return Ok(TaskResult {
+                    success: false,
+                    message: format!("Grant role failed: {}", revert::describe(e.as_ref())),
+                    tx_hash: None,
+                    ..Default::default()
+                });
             }
         };
 
-        let tx_hash = *pending.tx_hash();
-        match pending.get_receipt().await {
-            Ok(receipt) => {
-                if receipt.inner.status() {
+        let tx_hash = pending.tx_hash;
+        let attempts = pending.attempts;
+        match pending.confirm().await {
+            Ok(outcome) => {
+                if outcome.succeeded() {
+                    let has_role = IAccessControl::hasRoleCall {
+                        role: grant_call.role,
+                        account: address,
+                    };
+                    ctx.verify_later(
+                        self.name(),
+                        StateCheck::Call {
+                            to: token_addr,
+                            data: has_role.abi_encode().into(),
+                        },
+                    )
+                    .await;
+
+                    let receipt = outcome.receipt;
                     Ok(TaskResult {
                         success: true,
                         message: format!(
                             "Granted {} on {} ({} attempt(s)). Tx: {}",
                             role_name,
                             &token_addr_str[..10],
-                            retry_count + 1,
+                            attempts,
                             tx_hash
                         ),
                         tx_hash: Some(format!("{:?}", tx_hash)),
-                    })
+                        ..Default::default()
+                    }
+                    .with_receipt(receipt.gas_used, receipt.block_number)
+                    .with_detail("role", role_name))
                 } else {
                     // Reverted
                     Ok(TaskResult {
                         success: false,
                         message: "grantRole reverted".to_string(),
                         tx_hash: Some(format!("{:?}", tx_hash)),
+                        ..Default::default()
                     })
                 }
             }
             Err(e) => Ok(TaskResult {
                 success: false,
-                message: format!("Failed to get receipt: {}", e),
+                message: format!("{:#}", e),
                 tx_hash: Some(format!("{:?}", tx_hash)),
+                ..Default::default()
             }),
         }
     }
 }
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use crate::testing::TaskHarness;
+
+    const TOKEN: &str = "0x20C0000000000000000000000000000000000042";
+
+    async fn with_stablecoin() -> TaskHarness {
+        let harness = TaskHarness::new().with_db().await.unwrap();
+        let wallet = harness.mock.address().to_string();
+        harness
+            .db
+            .as_ref()
+            .unwrap()
+            .log_asset_creation(&wallet, TOKEN, "stablecoin", "Test USD", "TUSD")
+            .await
+            .unwrap();
+        harness
+    }
+
+    #[tokio::test]
+    async fn test_grants_missing_role() {
+        let harness = with_stablecoin().await;
+        let result = harness.run(&GrantRoleTask::new()).await.unwrap();
+        assert!(result.success, "{}", result.message);
+        assert_eq!(result.gas_used, Some(100_000));
+        assert!(result.details["role"].is_string());
+
+        let sent = harness.mock.sent();
+        assert_eq!(sent.len(), 1);
+        assert_eq!(sent[0].to, Some(Address::from_str(TOKEN).unwrap()));
+        assert_eq!(
+            &sent[0].input[..4],
+            &IAccessControl::grantRoleCall::SELECTOR
+        );
+    }
+
+    #[tokio::test]
+    async fn test_reverted_grant_fails() {
+        let harness = with_stablecoin().await;
+        harness.mock.revert_next_receipt();
+        let result = harness.run(&GrantRoleTask::new()).await.unwrap();
+        assert!(!result.success);
+        assert_eq!(result.message, "grantRole reverted");
+        assert!(result.tx_hash.is_some());
+    }
+
+    #[tokio::test]
+    async fn test_retries_after_nonce_error() {
+        let harness = with_stablecoin().await;
+        harness.mock.set_nonce(5);
+        harness
+            .mock
+            .fail_next_send("nonce too low: next nonce 6, tx nonce 5");
+
+        let result = harness.run(&GrantRoleTask::new()).await.unwrap();
+        assert!(result.success, "{}", result.message);
+        assert!(result.message.contains("(2 attempt(s))"));
+        assert_eq!(harness.mock.sent()[0].nonce, Some(5));
+    }
+
+    #[tokio::test]
+    async fn test_without_stablecoins_sends_nothing() {
+        let harness = TaskHarness::new();
+        let result = harness.run(&GrantRoleTask::new()).await.unwrap();
+        assert!(!result.success);
+        assert!(harness.mock.methods().is_empty());
+    }
+}
@@ -10,6 +10,7 @@
 //! 4. Log to database
 
 use crate::TempoClient;
+use crate::consistency::StateCheck;
 use crate::tasks::{TaskContext, TaskResult, TempoTask};
 use alloy::primitives::{Address, TxKind, U256};
 use alloy::rpc::types::{TransactionInput, TransactionRequest};
@@ -83,22 +84,30 @@ impl TempoTask for NftCreateMintTask {
         };
 
         let tx_hash = *pending.tx_hash();
-        let receipt = pending
-            .get_receipt()
-            .await
-            .context("Failed to get receipt")?;
+        let receipt = match ctx.receipt(tx_hash).await {
+            Ok(receipt) => receipt,
+            Err(timeout) => return Ok(timeout.into()),
+        };
 
         if !receipt.inner.status() {
             return Ok(TaskResult {
                 success: false,
                 message: format!("NFT deployment transaction failed. Tx: {:?}", tx_hash),
                 tx_hash: Some(format!("{:?}", tx_hash)),
+                ..Default::default()
             });
         }
 
         let contract_address = receipt
             .contract_address
             .ok_or(anyhow::anyhow!("No contract address in receipt"))?;
+        ctx.verify_later(
+            self.name(),
+            StateCheck::Code {
+                address: contract_address,
+            },
+        )
+        .await;
 
         // println!(
         //     "✅ Contract deployed at {:?}. Tx: {:?}",
@@ -156,7 +165,7 @@ impl TempoTask for NftCreateMintTask {
             .to(contract_address)
             .input(TransactionInput::from(grant_input.clone()))
             .from(address)
-            .gas_limit(200_000)
+            .gas_limit(ctx.gas_limit(self.name(), 200_000))
             .max_fee_per_gas(150_000_000_000u128)
             .max_priority_fee_per_gas(1_500_000_000u128);
 
@@ -181,10 +190,10 @@ impl TempoTask for NftCreateMintTask {
         };
 
         let grant_hash = *grant_pending.tx_hash();
-        let grant_receipt = grant_pending
-            .get_receipt()
-            .await
-            .context("Failed to get grant role receipt")?;
+        let grant_receipt = match ctx.receipt(grant_hash).await {
+            Ok(receipt) => receipt,
+            Err(timeout) => return Ok(timeout.into()),
+        };
 
         if !grant_receipt.inner.status() {
             return Ok(TaskResult {
@@ -194,6 +203,7 @@ impl TempoTask for NftCreateMintTask {
                     tx_hash, grant_hash
                 ),
                 tx_hash: Some(format!("{:?}", grant_hash)),
+                ..Default::default()
             });
         }
         // println!("✅ Minter role granted. Tx: {:?}", grant_hash);
@@ -210,7 +220,7 @@ impl TempoTask for NftCreateMintTask {
             .to(contract_address)
             .input(TransactionInput::from(mint_input.clone()))
             .from(address)
-            .gas_limit(5_000_000)
+            .gas_limit(ctx.gas_limit(self.name(), 5_000_000))
             .max_fee_per_gas(150_000_000_000u128)
             .max_priority_fee_per_gas(1_500_000_000u128);
 
@@ -235,10 +245,10 @@ impl TempoTask for NftCreateMintTask {
         };
 
         let mint_hash = *mint_pending.tx_hash();
-        let mint_receipt = mint_pending
-            .get_receipt()
-            .await
-            .context("Failed to get mint receipt")?;
+        let mint_receipt = match ctx.receipt(mint_hash).await {
+            Ok(receipt) => receipt,
+            Err(timeout) => return Ok(timeout.into()),
+        };
 
         if !mint_receipt.inner.status() {
             return Ok(TaskResult {
@@ -248,6 +258,7 @@ impl TempoTask for NftCreateMintTask {
                     tx_hash, grant_hash, mint_hash
                 ),
                 tx_hash: Some(format!("{:?}", mint_hash)),
+                ..Default::default()
             });
         }
 
@@ -293,6 +304,10 @@ impl TempoTask for NftCreateMintTask {
                 contract_address, minted_id, tx_hash
             ),
             tx_hash: Some(format!("{:?}", tx_hash)),
-        })
+            ..Default::default()
+        }
+        .with_receipt(receipt.gas_used, receipt.block_number)
+        .with_contract(contract_address)
+        .with_detail("token_id", minted_id.to_string()))
     }
 }
@@ -75,6 +75,7 @@ impl TempoTask for MintRandomNftTask {
                 success: false,
                 message: "No NFT collections available to mint from.".to_string(),
                 tx_hash: None,
+                ..Default::default()
             });
         };
 
@@ -102,6 +103,7 @@ impl TempoTask for MintRandomNftTask {
                             e
                         ),
                         tx_hash: None,
+                        ..Default::default()
                     });
                 }
             }
@@ -117,6 +119,7 @@ impl TempoTask for MintRandomNftTask {
                         success: false,
                         message: "Failed to select random NFT collection.".to_string(),
                         tx_hash: None,
+                        ..Default::default()
                     });
                 }
             }
@@ -133,6 +136,7 @@ impl TempoTask for MintRandomNftTask {
                         selected_collection, e
                     ),
                     tx_hash: None,
+                    ..Default::default()
                 });
             }
         };
@@ -152,7 +156,7 @@ impl TempoTask for MintRandomNftTask {
                 .to(contract_address)
                 .input(TransactionInput::from(mint_input))
                 .from(address)
-                .gas_limit(5_000_000)
+                .gas_limit(ctx.gas_limit(self.name(), 5_000_000))
                 .max_fee_per_gas(150_000_000_000u128)
                 .max_priority_fee_per_gas(1_500_000_000u128);
 
@@ -201,6 +205,7 @@ impl TempoTask for MintRandomNftTask {
             } else {
                 Some(minted_token_ids.first().cloned().unwrap_or_default())
             },
+            ..Default::default()
         })
     }
 }
@@ -244,10 +249,7 @@ async fn deploy_nft_collection(
     };
 
     let tx_hash = *pending.tx_hash();
-    let receipt = pending
-        .get_receipt()
-        .await
-        .context("Getting transaction confirmation")?;
+    let receipt = ctx.receipt(tx_hash).await?;
 
     if !receipt.inner.status() {
         anyhow::bail!("NFT deployment transaction failed. Tx: {:?}", tx_hash);
@@ -272,7 +274,7 @@ async fn deploy_nft_collection(
 
     match client.provider.send_transaction(grant_tx).await {
         Ok(grant_pending) => {
-            let _ = grant_pending.get_receipt().await;
+            let _ = ctx.receipt(*grant_pending.tx_hash()).await;
             tracing::debug!(
                 "Granted minter role for NFT collection {}",
                 contract_address_str
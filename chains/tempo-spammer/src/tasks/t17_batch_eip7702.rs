@@ -29,12 +29,6 @@ sol!(
     }
 );
 
-const PATHUSD_ADDRESS: &str = "0x20C0000000000000000000000000000000000000";
-const ALPHAUSD_ADDRESS: &str = "0x20c0000000000000000000000000000000000001";
-const STABLECOIN_DEX_ADDRESS: &str = "0xdec0000000000000000000000000000000000000";
-
-const FAUCET_ADDRESS: &str = "0x4200000000000000000000000000000000000019";
-
 #[derive(Debug, Clone, Default)]
 pub struct BatchEip7702Task;
 
@@ -54,9 +48,10 @@ impl TempoTask for BatchEip7702Task {
         let client = &ctx.client;
         let address = ctx.address();
 
-        let pathusd_addr: Address = PATHUSD_ADDRESS.parse()?;
-        let alphausd_addr: Address = ALPHAUSD_ADDRESS.parse()?;
-        let dex_address: Address = STABLECOIN_DEX_ADDRESS.parse()?;
+        let contracts = ctx.contracts();
+        let pathusd_addr = contracts.path_usd;
+        let alphausd_addr = contracts.alpha_usd;
+        let dex_address = contracts.stablecoin_dex;
 
         tracing::debug!("Simulating Batch EIP-7702 Delegated Operation...");
 
@@ -73,7 +68,7 @@ impl TempoTask for BatchEip7702Task {
             data.extend_from_slice(address.as_slice());
 
             let faucet_tx = TransactionRequest::default()
-                .to(FAUCET_ADDRESS.parse().unwrap())
+                .to(contracts.faucet)
                 .input(data.into())
                 .from(address);
 
@@ -104,10 +99,9 @@ impl TempoTask for BatchEip7702Task {
                     }
                 }
             };
-            let _receipt = pending
-                .get_receipt()
-                .await
-                .context("Faucet claim receipt failed")?;
+            if let Err(timeout) = ctx.receipt(*pending.tx_hash()).await {
+                return Ok(timeout.into());
+            }
 
             // Update balance
             balance = TempoTokens::get_token_balance(client, pathusd_addr, address).await?;
@@ -115,11 +109,15 @@ impl TempoTask for BatchEip7702Task {
         }
 
         let swap_amount = U256::from(1_000_000_000); // 1000 PathUSD
+        let spend = match ctx.reserve_spend(pathusd_addr, swap_amount) {
+            Ok(spend) => spend,
+            Err(exceeded) => return Ok(exceeded.into()),
+        };
 
         // 2. Approve PathUSD for DEX (2x for safety buffer)
         // println!("Step 1/2: Approving PathUSD for DEX...");
         let approve_amount = swap_amount * U256::from(2);
-        let approve_calldata = build_approve_calldata(STABLECOIN_DEX_ADDRESS, approve_amount);
+        let approve_calldata = build_approve_calldata(dex_address, approve_amount);
         let approve_tx = TransactionRequest::default()
             .to(pathusd_addr)
             .input(approve_calldata.into())
@@ -153,10 +151,10 @@ impl TempoTask for BatchEip7702Task {
             }
         };
 
-        let approve_receipt = pending_approve
-            .get_receipt()
-            .await
-            .context("Failed to get approve receipt")?;
+        let approve_receipt = match ctx.receipt(*pending_approve.tx_hash()).await {
+            Ok(receipt) => receipt,
+            Err(timeout) => return Ok(timeout.into()),
+        };
 
         // Ensure approval succeeded
         if !approve_receipt.inner.status() {
@@ -222,19 +220,21 @@ impl TempoTask for BatchEip7702Task {
         };
 
         let tx_hash = *pending_swap.tx_hash();
-        let receipt = pending_swap
-            .get_receipt()
-            .await
-            .context("Failed to get swap receipt")?;
+        let receipt = match ctx.receipt(tx_hash).await {
+            Ok(receipt) => receipt,
+            Err(timeout) => return Ok(timeout.into()),
+        };
 
         if !receipt.inner.status() {
             return Ok(TaskResult {
                 success: false,
                 message: "Batch simulation swap failed (reverted)".to_string(),
                 tx_hash: Some(format!("{:?}", tx_hash)),
+                ..Default::default()
             });
         }
 
+        spend.commit().await;
         let hash_str = format!("{:?}", tx_hash);
         // println!("✅ Batch Simulation Successful!");
 
@@ -245,15 +245,15 @@ impl TempoTask for BatchEip7702Task {
                 hash_str
             ),
             tx_hash: Some(hash_str),
+            ..Default::default()
         })
     }
 }
 
-fn build_approve_calldata(spender: &str, amount: U256) -> Vec<u8> {
+fn build_approve_calldata(spender: Address, amount: U256) -> Vec<u8> {
     let mut calldata = hex::decode("095ea7b3").unwrap();
-    let spender_addr: Address = spender.parse().unwrap();
     calldata.extend_from_slice(&[0u8; 12]);
-    calldata.extend_from_slice(spender_addr.as_slice());
+    calldata.extend_from_slice(spender.as_slice());
     calldata.extend_from_slice(&amount.to_be_bytes::<32>());
     calldata
 }
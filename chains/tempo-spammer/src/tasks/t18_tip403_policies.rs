@@ -22,8 +22,6 @@ sol!(
     }
 );
 
-const TIP403_REGISTRY_ADDRESS: &str = "0x403c000000000000000000000000000000000000";
-
 #[derive(Debug, Clone, Default)]
 pub struct Tip403PoliciesTask;
 
@@ -43,8 +41,7 @@ impl TempoTask for Tip403PoliciesTask {
         let client = &ctx.client;
         let address = ctx.address();
 
-        let registry_addr =
-            Address::from_str(TIP403_REGISTRY_ADDRESS).context("Invalid TIP403 registry")?;
+        let registry_addr = ctx.contracts().tip403_registry;
 
         tracing::debug!("Creating TIP-403 Whitelist Policy...");
 
@@ -87,16 +84,17 @@ impl TempoTask for Tip403PoliciesTask {
         };
 
         let tx_hash = *pending.tx_hash();
-        let receipt = pending
-            .get_receipt()
-            .await
-            .context("Failed to get receipt")?;
+        let receipt = match ctx.receipt(tx_hash).await {
+            Ok(receipt) => receipt,
+            Err(timeout) => return Ok(timeout.into()),
+        };
 
         if !receipt.inner.status() {
             return Ok(TaskResult {
                 success: false,
                 message: "TIP-403 Policy creation reverted".to_string(),
                 tx_hash: Some(format!("{:?}", tx_hash)),
+                ..Default::default()
             });
         }
 
@@ -109,6 +107,7 @@ impl TempoTask for Tip403PoliciesTask {
             success: true,
             message: format!("Created TIP-403 Whitelist Policy. Tx: {}", tx_hash),
             tx_hash: Some(format!("{:?}", tx_hash)),
+            ..Default::default()
         })
     }
 }
@@ -10,13 +10,6 @@ use anyhow::{Context, Result};
 use async_trait::async_trait;
 use std::str::FromStr;
 
-const SYSTEM_TOKENS: &[(&str, &str)] = &[
-    ("PathUSD", "0x20C0000000000000000000000000000000000000"),
-    ("AlphaUSD", "0x20c0000000000000000000000000000000000001"),
-    ("BetaUSD", "0x20c0000000000000000000000000000000000002"),
-    ("ThetaUSD", "0x20c0000000000000000000000000000000000003"),
-];
-
 #[derive(Debug, Clone, Default)]
 pub struct WalletAnalyticsTask;
 
@@ -45,9 +38,7 @@ impl TempoTask for WalletAnalyticsTask {
         // ERC20 balanceOf signature
         let balance_of_selector: [u8; 4] = hex::decode("70a08231").unwrap().try_into().unwrap();
 
-        for (name, addr) in SYSTEM_TOKENS {
-            let token_addr: Address = addr.parse().context("Invalid token address")?;
-
+        for (name, token_addr) in ctx.contracts().system_tokens() {
             // Build balanceOf calldata: selector + padded address
             let mut calldata = Vec::new();
             calldata.extend_from_slice(&balance_of_selector);
@@ -189,6 +180,7 @@ impl TempoTask for WalletAnalyticsTask {
                 success_rate
             ),
             tx_hash: None,
+            ..Default::default()
         })
     }
 }
@@ -64,6 +64,7 @@ impl TempoTask for WalletActivityTask {
                 tx_count, balance_formatted
             ),
             tx_hash: None,
+            ..Default::default()
         })
     }
 }
@@ -9,7 +9,7 @@
 
 use crate::TempoClient;
 use crate::tasks::tempo_tokens::TempoTokens;
-use crate::tasks::{TaskContext, TaskResult, TempoTask};
+use crate::tasks::{DEPLOY_TIMEOUT, TaskContext, TaskResult, TempoTask};
 use alloy::primitives::{Address, U256};
 use alloy::rpc::types::{TransactionInput, TransactionRequest};
 use alloy_sol_types::SolCall;
@@ -20,6 +20,7 @@ use rand::Rng;
 use rand::seq::SliceRandom;
 use std::path::Path;
 use std::str::FromStr;
+use std::time::Duration;
 
 sol!(
     interface ITIP20Factory {
@@ -41,9 +42,6 @@ sol!(
     }
 );
 
-const TIP20_FACTORY_ADDRESS: &str = "0x20fc000000000000000000000000000000000000";
-const PATHUSD_ADDRESS: &str = "0x20c0000000000000000000000000000000000000";
-
 #[derive(Debug, Clone, Default)]
 pub struct CreateMemeTask;
 
@@ -59,12 +57,17 @@ impl TempoTask for CreateMemeTask {
         "21_create_meme"
     }
 
+    fn default_timeout(&self) -> Option<Duration> {
+        Some(DEPLOY_TIMEOUT)
+    }
+
     async fn run(&self, ctx: &TaskContext) -> Result<TaskResult> {
         let client = &ctx.client;
         let address = ctx.address();
 
-        let factory_addr = Address::from_str(TIP20_FACTORY_ADDRESS).context("Invalid factory")?;
-        let pathusd_addr = Address::from_str(PATHUSD_ADDRESS).context("Invalid PathUSD")?;
+        let contracts = ctx.contracts();
+        let factory_addr = contracts.tip20_factory;
+        let pathusd_addr = contracts.path_usd;
 
         let mut rng = rand::rngs::OsRng;
 
@@ -107,6 +110,7 @@ impl TempoTask for CreateMemeTask {
                 success: false,
                 message: "Insufficient PathUSD for meme creation".to_string(),
                 tx_hash: None,
+                ..Default::default()
             });
         }
 
@@ -150,16 +154,17 @@ impl TempoTask for CreateMemeTask {
             }
         };
         let tx_hash = *pending.tx_hash();
-        let receipt = pending
-            .get_receipt()
-            .await
-            .context("Failed to get receipt")?;
+        let receipt = match ctx.receipt(tx_hash).await {
+            Ok(receipt) => receipt,
+            Err(timeout) => return Ok(timeout.into()),
+        };
 
         if !receipt.inner.status() {
             return Ok(TaskResult {
                 success: false,
                 message: "Token creation reverted".to_string(),
                 tx_hash: Some(format!("{:?}", tx_hash)),
+                ..Default::default()
             });
         }
 
@@ -188,6 +193,7 @@ impl TempoTask for CreateMemeTask {
                     receipt.transaction_hash
                 ),
                 tx_hash: Some(format!("{:?}", receipt.transaction_hash)),
+                ..Default::default()
             });
         }
 
@@ -226,7 +232,7 @@ impl TempoTask for CreateMemeTask {
         };
 
         if let Ok(pending) = grant_result {
-            let _ = pending.get_receipt().await;
+            let _ = ctx.receipt(*pending.tx_hash()).await;
             // println!("ISSUER_ROLE granted");
         }
 
@@ -265,7 +271,7 @@ impl TempoTask for CreateMemeTask {
         };
 
         if let Ok(pending) = mint_result {
-            let mint_receipt = pending.get_receipt().await;
+            let mint_receipt = ctx.receipt(*pending.tx_hash()).await;
             match mint_receipt {
                 Ok(_r) => {
                     // println!("Initial mint: {:?}", _r.transaction_hash);
@@ -294,6 +300,7 @@ impl TempoTask for CreateMemeTask {
             success: true,
             message: format!("Created Meme {} at {:?}", symbol, token_address),
             tx_hash: Some(format!("{:?}", tx_hash)),
+            ..Default::default()
         })
     }
 }
@@ -60,6 +60,7 @@ impl TempoTask for MintMemeTask {
                 success: true, // Mark as success/skipped to avoid alarming errors in sequence
                 message: "Skipped: No created meme tokens found".to_string(),
                 tx_hash: None,
+                ..Default::default()
             });
         }
 
@@ -72,6 +73,7 @@ impl TempoTask for MintMemeTask {
                 success: false,
                 message: "Invalid token address".to_string(),
                 tx_hash: None,
+                ..Default::default()
             });
         };
 
@@ -113,7 +115,7 @@ impl TempoTask for MintMemeTask {
 
             match client.provider.send_transaction(grant_tx).await {
                 Ok(pending) => {
-                    let _ = pending.get_receipt().await;
+                    let _ = ctx.receipt(*pending.tx_hash()).await;
                     // println!("ISSUER_ROLE granted");
                 }
                 Err(e) => {
@@ -168,6 +170,7 @@ impl TempoTask for MintMemeTask {
                                 message: "Mint reverted: Likely Sold Out or Already Claimed"
                                     .to_string(),
                                 tx_hash: None,
+                                ..Default::default()
                             });
                         }
                     }
@@ -177,16 +180,17 @@ impl TempoTask for MintMemeTask {
         };
 
         let tx_hash = *pending.tx_hash();
-        let receipt = pending
-            .get_receipt()
-            .await
-            .context("Failed to get receipt")?;
+        let receipt = match ctx.receipt(tx_hash).await {
+            Ok(receipt) => receipt,
+            Err(timeout) => return Ok(timeout.into()),
+        };
 
         if !receipt.inner.status() {
             return Ok(TaskResult {
                 success: false,
                 message: "Mint reverted".to_string(),
                 tx_hash: Some(format!("{:?}", tx_hash)),
+                ..Default::default()
             });
         }
 
@@ -207,6 +211,7 @@ impl TempoTask for MintMemeTask {
                 tx_hash
             ),
             tx_hash: Some(format!("{:?}", tx_hash)),
+            ..Default::default()
         })
     }
 }
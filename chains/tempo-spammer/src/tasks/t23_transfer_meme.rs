@@ -54,7 +54,7 @@ impl TransferMemeTask {
             match client.provider.send_transaction(mint_tx.clone()).await {
                 Ok(pending) => {
                     tracing::debug!("Mint Sent: {}. Waiting confirmation...", pending.tx_hash());
-                    if let Ok(receipt) = pending.get_receipt().await {
+                    if let Ok(receipt) = ctx.receipt(*pending.tx_hash()).await {
                         if receipt.inner.status() {
                             tracing::info!("✅ Mint Confirmed.");
                             balance = TempoTokens::get_token_balance(client, token_addr, address)
@@ -81,7 +81,7 @@ impl TransferMemeTask {
                                     "Mint Sent (Retry): {}. Waiting confirmation...",
                                     pending.tx_hash()
                                 );
-                                if let Ok(receipt) = pending.get_receipt().await {
+                                if let Ok(receipt) = ctx.receipt(*pending.tx_hash()).await {
                                     if receipt.inner.status() {
                                         tracing::info!("✅ Mint Confirmed (Retry).");
                                         balance = TempoTokens::get_token_balance(
@@ -99,6 +99,7 @@ impl TransferMemeTask {
                             success: false,
                             message: "Skipped: Token sold out (0xaa4bc69a)".to_string(),
                             tx_hash: None,
+                            ..Default::default()
                         });
                     } else if err_str.contains("unauthorized") || err_str.contains("82b42900") {
                         tracing::debug!("Cannot mint token (unauthorized), using existing balance");
@@ -116,9 +117,15 @@ impl TransferMemeTask {
                 success: false,
                 message: format!("Insufficient balance for {} after mint attempt", symbol),
                 tx_hash: None,
+                ..Default::default()
             });
         }
 
+        let reservation = match ctx.reserve_spend(token_addr, amount_wei) {
+            Ok(reservation) => reservation,
+            Err(exceeded) => return Ok(exceeded.into()),
+        };
+
         // 2. Transfer (Sequential)
         let recipient = get_random_address()?;
         tracing::debug!("Transferring {} {} to {:?}", amount_base, symbol, recipient);
@@ -132,8 +139,9 @@ impl TransferMemeTask {
         match client.provider.send_transaction(tx.clone()).await {
             Ok(pending) => {
                 let tx_hash = *pending.tx_hash();
-                if let Ok(receipt) = pending.get_receipt().await {
-                    if receipt.inner.status() {
+                match ctx.receipt(tx_hash).await {
+                    Ok(receipt) if receipt.inner.status() => {
+                        reservation.commit().await;
                         Ok(TaskResult {
                             success: true,
                             message: format!(
@@ -141,16 +149,20 @@ impl TransferMemeTask {
                                 amount_base, symbol, recipient
                             ),
                             tx_hash: Some(format!("{:?}", tx_hash)),
-                        })
-                    } else {
-                        Ok(TaskResult {
-                            success: false,
-                            message: "Transfer transaction reverted".to_string(),
-                            tx_hash: Some(format!("{:?}", tx_hash)),
+                            ..Default::default()
                         })
                     }
-                } else {
-                    anyhow::bail!("Failed to get transfer receipt");
+                    Ok(_) => Ok(TaskResult {
+                        success: false,
+                        message: "Transfer transaction reverted".to_string(),
+                        tx_hash: Some(format!("{:?}", tx_hash)),
+                        ..Default::default()
+                    }),
+                    Err(timeout) => {
+                        // Sent and likely to land, so keep it counted
+                        reservation.commit().await;
+                        Ok(timeout.into())
+                    }
                 }
             }
             Err(e) => {
@@ -165,6 +177,7 @@ impl TransferMemeTask {
                         Ok(pending) => {
                             let tx_hash = *pending.tx_hash();
                             // Assuming success on retry if sent
+                            reservation.commit().await;
                             Ok(TaskResult {
                                 success: true,
                                 message: format!(
@@ -172,6 +185,7 @@ impl TransferMemeTask {
                                     amount_base, symbol, recipient
                                 ),
                                 tx_hash: Some(format!("{:?}", tx_hash)),
+                                ..Default::default()
                             })
                         }
                         Err(e2) => anyhow::bail!("Transfer (Retry) failed: {}", e2),
@@ -210,7 +224,7 @@ impl TempoTask for TransferMemeTask {
         };
 
         if meme_tokens.is_empty() {
-            meme_tokens.push(TempoTokens::FALLBACK_MEME_TOKEN.to_string());
+            meme_tokens.push(TempoTokens::fallback_meme_token().to_string());
         }
 
         let mut rng = rand::rngs::OsRng;
@@ -3,38 +3,17 @@
 //! Executes multiple swaps on the Stablecoin DEX.
 //!
 //! Workflow:
-//! 1. Execute 2-3 swaps between PathUSD and AlphaUSD
-//! 2. Quote before swapping (liquidity check)
-//! 3. Approve if necessary
-//! 4. Execute swap
+//! 1. Quote PathUSD -> AlphaUSD and back (liquidity check)
+//! 2. Approve if necessary
+//! 3. Pipeline 3-7 swaps alternating between the two directions
 
-use crate::TempoClient;
-use crate::tasks::tempo_tokens::TempoTokens;
 use crate::tasks::{TaskContext, TaskResult, TempoTask};
 use alloy::primitives::{Address, U256};
-use alloy::rpc::types::{TransactionInput, TransactionRequest};
-use alloy_sol_types::{SolCall, sol};
 use anyhow::{Context, Result};
 use async_trait::async_trait;
 use rand::Rng;
 use std::str::FromStr;
 
-const DEX_ADDRESS: &str = "0xdec0000000000000000000000000000000000000";
-const PATHUSD_ADDRESS: &str = "0x20c0000000000000000000000000000000000000";
-const ALPHAUSD_ADDRESS: &str = "0x20c0000000000000000000000000000000000001";
-
-sol!(
-    interface IStablecoinDEX {
-        function quoteSwapExactAmountIn(address tokenIn, address tokenOut, uint128 amountIn) view returns (uint128 amountOut);
-        function swapExactAmountIn(address tokenIn, address tokenOut, uint128 amountIn, uint128 minAmountOut) returns (uint128 amountOut);
-    }
-
-    interface IERC20 {
-        function approve(address spender, uint256 amount) returns (bool);
-        function allowance(address owner, address spender) view returns (uint256);
-    }
-);
-
 #[derive(Debug, Clone, Default)]
 pub struct BatchSwapTask;
 
@@ -54,13 +33,67 @@ impl TempoTask for BatchSwapTask {
         let client = &ctx.client;
         let address = ctx.address();
 
-        let dex_addr = Address::from_str(DEX_ADDRESS).context("Invalid DEX")?;
-        let pathusd_addr = Address::from_str(PATHUSD_ADDRESS).context("Invalid PathUSD")?;
-        let alphausd_addr = Address::from_str(ALPHAUSD_ADDRESS).context("Invalid AlphaUSD")?;
+        let contracts = ctx.contracts();
+        let amm = ctx.fee_amm();
+        let pathusd = ctx.token(contracts.path_usd);
+        let alphausd = pathusd.at(contracts.alpha_usd);
 
         let mut rng = rand::rngs::OsRng;
         let count = rng.gen_range(3..=7);
-        let amount_per_swap = U256::from(1000) * U256::from(10_u128.pow(18)); // 1000 tokens (assumed 18 decimals)
+        // Up to 1000 tokens, as much as both directions take
+        let sizer = ctx.sizer();
+        let wanted = pathusd.units(1000).await?;
+        let amount_per_swap = sizer
+            .swap_amount(pathusd.address(), alphausd.address(), wanted)
+            .await?
+            .min(
+                sizer
+                    .swap_amount(alphausd.address(), pathusd.address(), wanted)
+                    .await?,
+            );
+        let amount_in = u128::try_from(amount_per_swap).unwrap_or(0);
+        if amount_in == 0 {
+            return Ok(TaskResult {
+                success: false,
+                message: "No PathUSD/AlphaUSD balance or DEX depth to swap".to_string(),
+                tx_hash: None,
+                ..Default::default()
+            });
+        }
+
+        // Swaps alternate, so PathUSD goes in on the odd ones
+        let mut reservations = Vec::new();
+        for (token, swaps) in [
+            (pathusd.address(), count.div_ceil(2)),
+            (alphausd.address(), count / 2),
+        ] {
+            match ctx.reserve_spend(token, amount_per_swap * U256::from(swaps)) {
+                Ok(reservation) => reservations.push(reservation),
+                Err(exceeded) => return Ok(exceeded.into()),
+            }
+        }
+
+        // Quote each direction once; every swap of it reverts below the quote's floor
+        let mut quotes = Vec::new();
+        for (token_in, token_out) in [
+            (pathusd.address(), alphausd.address()),
+            (alphausd.address(), pathusd.address()),
+        ] {
+            match amm
+                .quote(token_in, token_out, amount_in, ctx.config.swap.slippage_bps)
+                .await
+            {
+                Ok(quote) => quotes.push(quote),
+                Err(e) => {
+                    return Ok(TaskResult {
+                        success: false,
+                        message: format!("No quote for {} -> {}: {:#}", token_in, token_out, e),
+                        tx_hash: None,
+                        ..Default::default()
+                    });
+                }
+            }
+        }
 
         tracing::debug!(
             "🚀 Optimistic Pipelining Swaps: Sending {} swaps between Path/Alpha...",
@@ -74,66 +107,31 @@ impl TempoTask for BatchSwapTask {
         let mut burst_txs = Vec::new();
 
         // Check/Add Approvals for both tokens
-        for token_addr in [pathusd_addr, alphausd_addr] {
-            let allowance_call = IERC20::allowanceCall {
-                owner: address,
-                spender: dex_addr,
-            };
-            let current_allowance = if let Ok(data) = client
-                .provider
-                .call(
-                    TransactionRequest::default()
-                        .to(token_addr)
-                        .input(allowance_call.abi_encode().into()),
-                )
+        for token in [&pathusd, &alphausd] {
+            let current_allowance = token
+                .allowance(address, amm.address())
                 .await
-            {
-                IERC20::allowanceCall::abi_decode_returns(&data).unwrap_or(U256::ZERO)
-            } else {
-                U256::ZERO
-            };
+                .unwrap_or(U256::ZERO);
 
             if current_allowance < amount_per_swap * U256::from(count) {
-                let approve_call = IERC20::approveCall {
-                    spender: dex_addr,
-                    amount: U256::MAX,
-                };
-                let approve_tx = TransactionRequest::default()
-                    .to(token_addr)
-                    .input(approve_call.abi_encode().into())
+                let approve_tx = token
+                    .approve_request(amm.address(), U256::MAX)
                     .from(address)
                     .nonce(current_nonce)
-                    .gas_limit(100_000);
+                    .gas_limit(ctx.gas_limit(self.name(), 100_000));
                 burst_txs.push(approve_tx);
                 current_nonce += 1;
             }
         }
 
-        // 2. Prepare Swaps
+        // 2. Prepare Swaps, alternating Path -> Alpha and Alpha -> Path
+        let first_swap = burst_txs.len();
         for i in 0..count {
-            let (token_in, token_out) = if i % 2 == 0 {
-                (pathusd_addr, alphausd_addr)
-            } else {
-                (alphausd_addr, pathusd_addr)
-            };
-
-            // Using 1:1 quote assumption with 10% slippage for speed
-            let amount_in_u128 = u128::try_from(amount_per_swap).unwrap_or(0);
-            let min_out = amount_in_u128 * 90 / 100;
-
-            let swap_call = IStablecoinDEX::swapExactAmountInCall {
-                tokenIn: token_in,
-                tokenOut: token_out,
-                amountIn: amount_in_u128,
-                minAmountOut: min_out,
-            };
-
-            let swap_tx = TransactionRequest::default()
-                .to(dex_addr)
-                .input(swap_call.abi_encode().into())
+            let swap_tx = amm
+                .swap_request(&quotes[i % 2])
                 .from(address)
                 .nonce(current_nonce)
-                .gas_limit(500_000);
+                .gas_limit(ctx.gas_limit(self.name(), 500_000));
 
             burst_txs.push(swap_tx);
             current_nonce += 1;
@@ -163,7 +161,7 @@ impl TempoTask for BatchSwapTask {
                 Err(e) => {
                     tracing::error!("Failed to submit transaction at nonce {}: {}", tx_nonce, e);
                     if first_error.is_none() {
-                        first_error = Some(e);
+                        first_error = Some((idx, anyhow::Error::from(e)));
                     }
                     break; // CRITICAL: Stop on first failure
                 }
@@ -176,14 +174,29 @@ impl TempoTask for BatchSwapTask {
             manager.set(address, next_nonce).await;
         }
 
+        // A swap rejected below its quote's floor is slippage, not a failure
+        let slippage = match &first_error {
+            Some((idx, err)) if *idx >= first_swap => {
+                let quote = &quotes[(idx - first_swap) % 2];
+                amm.slippage_exceeded(quote, Some(err)).await
+            }
+            _ => None,
+        };
+
         if success_hashes.is_empty() {
-            if let Some(err) = first_error {
-                return Err(anyhow::anyhow!(err));
+            if let Some(exceeded) = slippage {
+                return Ok(exceeded.into());
+            }
+            if let Some((_, err)) = first_error {
+                return Err(err);
             }
             anyhow::bail!("Failed to submit any transactions in batch.");
         }
+        for reservation in reservations {
+            reservation.commit().await;
+        }
 
-        Ok(TaskResult {
+        let result = TaskResult {
             success: true,
             message: format!(
                 "Pipelined {}/{} transactions ({} swaps)",
@@ -192,6 +205,11 @@ impl TempoTask for BatchSwapTask {
                 count
             ),
             tx_hash: Some(format!("{:?}", success_hashes.last().unwrap())),
+            ..Default::default()
+        };
+        Ok(match slippage {
+            Some(exceeded) => result.with_detail("slippage_exceeded", exceeded.to_string()),
+            None => result,
         })
     }
 }
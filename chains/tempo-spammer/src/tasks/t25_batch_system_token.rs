@@ -61,9 +61,15 @@ impl TempoTask for BatchSystemTokenTask {
                     token_info.symbol, balance
                 ),
                 tx_hash: None,
+                ..Default::default()
             });
         }
 
+        let spend = match ctx.reserve_spend(token_addr, amount_per_recipient * U256::from(count)) {
+            Ok(spend) => spend,
+            Err(exceeded) => return Ok(exceeded.into()),
+        };
+
         tracing::debug!(
             "Executing Batch of {} {} Transfers (2% of balance)...",
             count,
@@ -106,14 +112,19 @@ impl TempoTask for BatchSystemTokenTask {
                 Ok(pending) => {
                     reservation.mark_submitted().await;
                     let tx_hash = *pending.tx_hash();
-                    match pending.get_receipt().await {
+                    match ctx.receipt(tx_hash).await {
                         Ok(receipt) => {
                             if receipt.inner.status() {
                                 success_count += 1;
                                 last_hash = format!("{:?}", tx_hash);
                             }
                         }
-                        Err(_e) => {}
+                        Err(timeout) => {
+                            spend.commit().await;
+                            return Ok(
+                                TaskResult::from(timeout).with_detail("confirmed", success_count)
+                            );
+                        }
                     }
                 }
                 Err(e) => {
@@ -144,10 +155,17 @@ impl TempoTask for BatchSystemTokenTask {
                         if let Ok(pending) = client.provider.send_transaction(retry_tx).await {
                             retry_reservation.mark_submitted().await;
                             let tx_hash = *pending.tx_hash();
-                            if let Ok(receipt) = pending.get_receipt().await {
-                                if receipt.inner.status() {
-                                    success_count += 1;
-                                    last_hash = format!("{:?}", tx_hash);
+                            match ctx.receipt(tx_hash).await {
+                                Ok(receipt) => {
+                                    if receipt.inner.status() {
+                                        success_count += 1;
+                                        last_hash = format!("{:?}", tx_hash);
+                                    }
+                                }
+                                Err(timeout) => {
+                                    spend.commit().await;
+                                    return Ok(TaskResult::from(timeout)
+                                        .with_detail("confirmed", success_count));
                                 }
                             }
                         } else {
@@ -160,6 +178,11 @@ impl TempoTask for BatchSystemTokenTask {
             }
         }
 
+        // Keep the whole batch counted once any transfer went out
+        if success_count > 0 {
+            spend.commit().await;
+        }
+
         Ok(TaskResult {
             success: success_count > 0,
             message: format!(
@@ -171,6 +194,7 @@ impl TempoTask for BatchSystemTokenTask {
             } else {
                 Some(last_hash)
             },
+            ..Default::default()
         })
     }
 }
@@ -86,6 +86,10 @@ impl TempoTask for BatchStableTokenTask {
         let amount_per_recipient =
             U256::from(rng.gen_range(100..500)) * U256::from(10_u64.pow(decimals as u32));
         let total_needed = amount_per_recipient * U256::from(count);
+        let spend = match ctx.reserve_spend(token_addr, total_needed) {
+            Ok(spend) => spend,
+            Err(exceeded) => return Ok(exceeded.into()),
+        };
 
         // 3. Prepare Pipeline
         let mut current_nonce = client.get_pending_nonce(&ctx.config.rpc_url).await?;
@@ -108,7 +112,7 @@ impl TempoTask for BatchStableTokenTask {
                 .input(mint_call.abi_encode().into())
                 .from(address)
                 .nonce(current_nonce)
-                .gas_limit(150_000);
+                .gas_limit(ctx.gas_limit(self.name(), 150_000));
             burst_txs.push(mint_tx);
             current_nonce += 1;
         }
@@ -125,7 +129,7 @@ impl TempoTask for BatchStableTokenTask {
                 .input(transfer_call.abi_encode().into())
                 .from(address)
                 .nonce(current_nonce)
-                .gas_limit(100_000);
+                .gas_limit(ctx.gas_limit(self.name(), 100_000));
 
             burst_txs.push(tx);
             current_nonce += 1;
@@ -212,6 +216,7 @@ impl TempoTask for BatchStableTokenTask {
         if last_hash.is_empty() {
             anyhow::bail!("Failed to submit any transactions in pipeline.");
         }
+        spend.commit().await;
 
         Ok(TaskResult {
             success: true,
@@ -220,6 +225,7 @@ impl TempoTask for BatchStableTokenTask {
                 count, symbol
             ),
             tx_hash: Some(last_hash),
+            ..Default::default()
         })
     }
 }
@@ -69,6 +69,7 @@ impl TempoTask for BatchMemeTokenTask {
                     success: false,
                     message: format!("Failed to create meme token: {}", create_result.message),
                     tx_hash: create_result.tx_hash,
+                    ..Default::default()
                 });
             }
 
@@ -88,6 +89,7 @@ impl TempoTask for BatchMemeTokenTask {
                             message: "Created meme token but could not find it in database"
                                 .to_string(),
                             tx_hash: None,
+                            ..Default::default()
                         });
                     }
                 }
@@ -96,6 +98,7 @@ impl TempoTask for BatchMemeTokenTask {
                     success: false,
                     message: "Cannot create meme token without database".to_string(),
                     tx_hash: None,
+                    ..Default::default()
                 });
             }
         }
@@ -131,7 +134,7 @@ impl TempoTask for BatchMemeTokenTask {
 
             match client.provider.send_transaction(mint_tx.clone()).await {
                 Ok(pending) => {
-                    let _ = pending.get_receipt().await;
+                    let _ = ctx.receipt(*pending.tx_hash()).await;
                     balance = TempoTokens::get_token_balance(client, token_addr, address).await?;
                     amount_wei = balance * U256::from(2) / U256::from(100) / U256::from(count);
                 }
@@ -144,7 +147,7 @@ impl TempoTask for BatchMemeTokenTask {
                         client.reset_nonce_cache().await;
                         tokio::time::sleep(std::time::Duration::from_millis(150)).await;
                         if let Ok(pending) = client.provider.send_transaction(mint_tx).await {
-                            let _ = pending.get_receipt().await;
+                            let _ = ctx.receipt(*pending.tx_hash()).await;
                             balance = TempoTokens::get_token_balance(client, token_addr, address)
                                 .await
                                 .unwrap_or(U256::ZERO);
@@ -170,9 +173,15 @@ impl TempoTask for BatchMemeTokenTask {
                     symbol
                 ),
                 tx_hash: None,
+                ..Default::default()
             });
         }
 
+        let spend = match ctx.reserve_spend(token_addr, amount_wei * U256::from(count)) {
+            Ok(spend) => spend,
+            Err(exceeded) => return Ok(exceeded.into()),
+        };
+
         // 2. Transfers
         let mut last_hash = String::new();
         let mut success_count = 0;
@@ -200,7 +209,7 @@ impl TempoTask for BatchMemeTokenTask {
             match client.provider.send_transaction(tx.clone()).await {
                 Ok(pending) => {
                     let tx_hash = *pending.tx_hash();
-                    match pending.get_receipt().await {
+                    match ctx.receipt(tx_hash).await {
                         Ok(receipt) => {
                             if receipt.inner.status() {
                                 success_count += 1;
@@ -224,8 +233,11 @@ impl TempoTask for BatchMemeTokenTask {
                                 );
                             }
                         }
-                        Err(e) => {
-                            tracing::error!("Error getting receipt for transfer {}: {:?}", i + 1, e)
+                        Err(timeout) => {
+                            spend.commit().await;
+                            return Ok(
+                                TaskResult::from(timeout).with_detail("confirmed", success_count)
+                            );
                         }
                     }
                 }
@@ -240,10 +252,17 @@ impl TempoTask for BatchMemeTokenTask {
 
                         if let Ok(pending) = client.provider.send_transaction(tx).await {
                             let tx_hash = *pending.tx_hash();
-                            if let Ok(receipt) = pending.get_receipt().await {
-                                if receipt.inner.status() {
-                                    success_count += 1;
-                                    last_hash = format!("{:?}", tx_hash);
+                            match ctx.receipt(tx_hash).await {
+                                Ok(receipt) => {
+                                    if receipt.inner.status() {
+                                        success_count += 1;
+                                        last_hash = format!("{:?}", tx_hash);
+                                    }
+                                }
+                                Err(timeout) => {
+                                    spend.commit().await;
+                                    return Ok(TaskResult::from(timeout)
+                                        .with_detail("confirmed", success_count));
                                 }
                             }
                         }
@@ -254,6 +273,11 @@ impl TempoTask for BatchMemeTokenTask {
             }
         }
 
+        // Keep the whole batch counted once any transfer went out
+        if success_count > 0 {
+            spend.commit().await;
+        }
+
         Ok(TaskResult {
             success: success_count > 0,
             message: format!(
@@ -265,6 +289,7 @@ impl TempoTask for BatchMemeTokenTask {
             } else {
                 Some(last_hash)
             },
+            ..Default::default()
         })
     }
 }
@@ -68,6 +68,7 @@ impl TempoTask for MultiSendDisperseTask {
                 success: false,
                 message: "No system tokens with balance found".to_string(),
                 tx_hash: None,
+                ..Default::default()
             });
         }
 
@@ -101,9 +102,15 @@ impl TempoTask for MultiSendDisperseTask {
                 success: false,
                 message: "Calculated amount is zero".to_string(),
                 tx_hash: None,
+                ..Default::default()
             });
         }
 
+        let spend = match ctx.reserve_spend(token_addr, total_amount) {
+            Ok(spend) => spend,
+            Err(exceeded) => return Ok(exceeded.into()),
+        };
+
         let mut last_tx_hash = None;
 
         for _ in 0..recipient_count {
@@ -123,7 +130,10 @@ impl TempoTask for MultiSendDisperseTask {
                 match client.provider.send_transaction(tx.clone()).await {
                     Ok(pending) => {
                         last_tx_hash = Some(format!("{:?}", *pending.tx_hash()));
-                        let _ = pending.get_receipt().await;
+                        if let Err(timeout) = ctx.receipt(*pending.tx_hash()).await {
+                            spend.commit().await;
+                            return Ok(timeout.into());
+                        }
                     }
                     Err(e) => {
                         let err_str = e.to_string().to_lowercase();
@@ -135,7 +145,10 @@ impl TempoTask for MultiSendDisperseTask {
                             tokio::time::sleep(std::time::Duration::from_millis(150)).await;
                             if let Ok(pending) = client.provider.send_transaction(tx).await {
                                 last_tx_hash = Some(format!("{:?}", *pending.tx_hash()));
-                                let _ = pending.get_receipt().await;
+                                if let Err(timeout) = ctx.receipt(*pending.tx_hash()).await {
+                                    spend.commit().await;
+                                    return Ok(timeout.into());
+                                }
                             }
                         } else {
                             // println!("Transfer to {} failed: {:?}", recipient, e);
@@ -145,6 +158,10 @@ impl TempoTask for MultiSendDisperseTask {
             }
         }
 
+        if last_tx_hash.is_some() {
+            spend.commit().await;
+        }
+
         Ok(TaskResult {
             success: last_tx_hash.is_some(),
             message: format!(
@@ -154,6 +171,7 @@ impl TempoTask for MultiSendDisperseTask {
                 recipient_count
             ),
             tx_hash: last_tx_hash,
+            ..Default::default()
         })
     }
 }
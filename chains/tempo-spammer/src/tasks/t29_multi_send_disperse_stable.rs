@@ -60,6 +60,7 @@ impl TempoTask for MultiSendDisperseStableTask {
                 success: false,
                 message: "No stable tokens found in DB.".to_string(),
                 tx_hash: None,
+                ..Default::default()
             });
         }
 
@@ -93,7 +94,9 @@ impl TempoTask for MultiSendDisperseStableTask {
                 .max_priority_fee_per_gas(1_500_000_000u128);
 
             let pending = client.provider.send_transaction(tx).await?;
-            let _receipt = pending.get_receipt().await?;
+            if let Err(timeout) = ctx.receipt(*pending.tx_hash()).await {
+                return Ok(timeout.into());
+            }
 
             balance = TempoTokens::get_token_balance(client, token_addr, address).await?;
             total_amount = balance * percent / U256::from(100);
@@ -104,11 +107,16 @@ impl TempoTask for MultiSendDisperseStableTask {
                 success: false,
                 message: "Calculated amount is zero".to_string(),
                 tx_hash: None,
+                ..Default::default()
             });
         }
 
         let recipient_count = 3;
         let amount_per_recipient = total_amount / U256::from(recipient_count);
+        let spend = match ctx.reserve_spend(token_addr, total_amount) {
+            Ok(spend) => spend,
+            Err(exceeded) => return Ok(exceeded.into()),
+        };
         let mut last_tx_hash = None;
 
         let mut first_error = None;
@@ -130,7 +138,10 @@ impl TempoTask for MultiSendDisperseStableTask {
                 match client.provider.send_transaction(tx).await {
                     Ok(pending) => {
                         last_tx_hash = Some(format!("{:?}", *pending.tx_hash()));
-                        let _ = pending.get_receipt().await;
+                        if let Err(timeout) = ctx.receipt(*pending.tx_hash()).await {
+                            spend.commit().await;
+                            return Ok(timeout.into());
+                        }
                     }
                     Err(e) => {
                         if first_error.is_none() {
@@ -144,6 +155,9 @@ impl TempoTask for MultiSendDisperseStableTask {
         if last_tx_hash.is_none() && first_error.is_some() {
             return Err(anyhow::anyhow!(first_error.unwrap()));
         }
+        if last_tx_hash.is_some() {
+            spend.commit().await;
+        }
 
         Ok(TaskResult {
             success: last_tx_hash.is_some(),
@@ -153,6 +167,7 @@ impl TempoTask for MultiSendDisperseStableTask {
                 recipient_count
             ),
             tx_hash: last_tx_hash,
+            ..Default::default()
         })
     }
 }
@@ -60,6 +60,7 @@ impl TempoTask for MultiSendDisperseMemeTask {
                 success: false,
                 message: "No meme tokens found in DB.".to_string(),
                 tx_hash: None,
+                ..Default::default()
             });
         }
 
@@ -71,7 +72,7 @@ impl TempoTask for MultiSendDisperseMemeTask {
 
         if meme_tokens.is_empty() {
             // Fallback to default, mintable meme token if we filtered everything out
-            meme_tokens.push(TempoTokens::FALLBACK_MEME_TOKEN.to_string());
+            meme_tokens.push(TempoTokens::fallback_meme_token().to_string());
         }
 
         let mut rng = rand::rngs::OsRng;
@@ -105,7 +106,7 @@ impl TempoTask for MultiSendDisperseMemeTask {
             // Try to mint, but don't fail if unauthorized (not owner)
             match client.provider.send_transaction(tx).await {
                 Ok(pending) => {
-                    match pending.get_receipt().await {
+                    match ctx.receipt(*pending.tx_hash()).await {
                         Ok(_receipt) => {
                             balance =
                                 TempoTokens::get_token_balance(client, token_addr, address).await?;
@@ -134,6 +135,7 @@ impl TempoTask for MultiSendDisperseMemeTask {
                     success: false,
                     message: "Insufficient balance for disperse (need 100+ tokens)".to_string(),
                     tx_hash: None,
+                    ..Default::default()
                 });
             }
         }
@@ -143,11 +145,16 @@ impl TempoTask for MultiSendDisperseMemeTask {
                 success: false,
                 message: "Calculated amount is zero".to_string(),
                 tx_hash: None,
+                ..Default::default()
             });
         }
 
         let recipient_count = 3;
         let amount_per_recipient = total_amount / U256::from(recipient_count);
+        let spend = match ctx.reserve_spend(token_addr, total_amount) {
+            Ok(spend) => spend,
+            Err(exceeded) => return Ok(exceeded.into()),
+        };
         let mut last_tx_hash = None;
 
         let mut first_error = None;
@@ -169,7 +176,10 @@ impl TempoTask for MultiSendDisperseMemeTask {
                 match client.provider.send_transaction(tx).await {
                     Ok(pending) => {
                         last_tx_hash = Some(format!("{:?}", *pending.tx_hash()));
-                        let _ = pending.get_receipt().await;
+                        if let Err(timeout) = ctx.receipt(*pending.tx_hash()).await {
+                            spend.commit().await;
+                            return Ok(timeout.into());
+                        }
                     }
                     Err(e) => {
                         tracing::warn!("Transfer to {} failed: {:?}", recipient, e);
@@ -184,6 +194,9 @@ impl TempoTask for MultiSendDisperseMemeTask {
         if last_tx_hash.is_none() && first_error.is_some() {
             return Err(first_error.unwrap());
         }
+        if last_tx_hash.is_some() {
+            spend.commit().await;
+        }
 
         Ok(TaskResult {
             success: last_tx_hash.is_some(),
@@ -193,6 +206,7 @@ impl TempoTask for MultiSendDisperseMemeTask {
                 recipient_count
             ),
             tx_hash: last_tx_hash,
+            ..Default::default()
         })
     }
 }
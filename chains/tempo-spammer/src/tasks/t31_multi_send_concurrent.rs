@@ -70,6 +70,7 @@ impl TempoTask for MultiSendConcurrentTask {
                     success: false,
                     message: "No system token balance found for concurrent transfer".to_string(),
                     tx_hash: None,
+                    ..Default::default()
                 });
             }
         };
@@ -89,9 +90,15 @@ impl TempoTask for MultiSendConcurrentTask {
                     token_info.symbol, balance
                 ),
                 tx_hash: None,
+                ..Default::default()
             });
         }
 
+        let spend = match ctx.reserve_spend(token_addr, amount_per_recipient * U256::from(count)) {
+            Ok(spend) => spend,
+            Err(exceeded) => return Ok(exceeded.into()),
+        };
+
         tracing::debug!(
             "Executing {} Concurrent {} Transfers (3% total)...",
             count,
@@ -125,12 +132,13 @@ impl TempoTask for MultiSendConcurrentTask {
 
         let mut success_count = 0;
         let mut last_hash = String::new();
+        let mut timed_out = None;
 
         for (i, future) in futures.into_iter().enumerate() {
             match future.await {
                 Ok(pending) => {
                     let tx_hash = *pending.tx_hash();
-                    match pending.get_receipt().await {
+                    match ctx.receipt(tx_hash).await {
                         Ok(receipt) => {
                             if receipt.inner.status() {
                                 success_count += 1;
@@ -146,8 +154,9 @@ impl TempoTask for MultiSendConcurrentTask {
                                 // println!("  [{}] Failed: transaction reverted", i + 1);
                             }
                         }
-                        Err(_e) => {
-                            // println!("  [{}] Failed to get receipt: {:?}", i + 1, _e);
+                        Err(timeout) => {
+                            // The rest still go out so the nonces stay contiguous
+                            timed_out.get_or_insert(timeout);
                         }
                     }
                 }
@@ -162,6 +171,15 @@ impl TempoTask for MultiSendConcurrentTask {
             manager.set(address, base_nonce + count as u64).await;
         }
 
+        // Keep the whole batch counted once any transfer went out
+        if let Some(timeout) = timed_out {
+            spend.commit().await;
+            return Ok(TaskResult::from(timeout).with_detail("confirmed", success_count));
+        }
+        if success_count > 0 {
+            spend.commit().await;
+        }
+
         Ok(TaskResult {
             success: success_count > 0,
             message: format!(
@@ -173,6 +191,7 @@ impl TempoTask for MultiSendConcurrentTask {
             } else {
                 Some(last_hash)
             },
+            ..Default::default()
         })
     }
 }
@@ -105,7 +105,7 @@ impl TempoTask for MultiSendConcurrentStableTask {
 
             match client.provider.send_transaction(mint_tx).await {
                 Ok(pending) => {
-                    let _ = pending.get_receipt().await;
+                    let _ = ctx.receipt(*pending.tx_hash()).await;
                     balance = TempoTokens::get_token_balance(client, token_addr, address).await?;
                     total_impact = balance * U256::from(3) / U256::from(100);
                     amount_per_recipient = total_impact / U256::from(count);
@@ -122,6 +122,7 @@ impl TempoTask for MultiSendConcurrentStableTask {
                     symbol
                 ),
                 tx_hash: None,
+                ..Default::default()
             });
         }
 
@@ -130,6 +131,11 @@ impl TempoTask for MultiSendConcurrentStableTask {
         //     count, symbol
         // );
 
+        let spend = match ctx.reserve_spend(token_addr, amount_per_recipient * U256::from(count)) {
+            Ok(spend) => spend,
+            Err(exceeded) => return Ok(exceeded.into()),
+        };
+
         // Reserve nonces atomically using the batch helper
         let nonces = if let Some(manager) = &client.nonce_manager {
             // Use atomic nonce reservation
@@ -176,20 +182,22 @@ impl TempoTask for MultiSendConcurrentStableTask {
         let mut success_count = 0;
         let mut last_hash = String::new();
         let mut failed_nonces = Vec::new();
+        let mut timed_out = None;
 
         for (i, future) in futures.into_iter().enumerate() {
             match future.await {
                 Ok(pending) => {
                     let tx_hash = *pending.tx_hash();
-                    if let Ok(receipt) = pending.get_receipt().await {
-                        if receipt.inner.status() {
+                    match ctx.receipt(tx_hash).await {
+                        Ok(receipt) if receipt.inner.status() => {
                             success_count += 1;
                             last_hash = format!("{:?}", tx_hash);
-                        } else {
-                            failed_nonces.push(nonces[i]);
                         }
-                    } else {
-                        failed_nonces.push(nonces[i]);
+                        Ok(_) => failed_nonces.push(nonces[i]),
+                        // The rest still go out so the nonces stay contiguous
+                        Err(timeout) => {
+                            timed_out.get_or_insert(timeout);
+                        }
                     }
                 }
                 Err(e) => {
@@ -210,6 +218,15 @@ impl TempoTask for MultiSendConcurrentStableTask {
             client.reset_nonce_cache().await;
         }
 
+        // Keep the whole batch counted once any transfer went out
+        if let Some(timeout) = timed_out {
+            spend.commit().await;
+            return Ok(TaskResult::from(timeout).with_detail("confirmed", success_count));
+        }
+        if success_count > 0 {
+            spend.commit().await;
+        }
+
         // Return result immediately, removing the old futures loop
         return Ok(TaskResult {
             success: success_count > 0,
@@ -222,6 +239,7 @@ impl TempoTask for MultiSendConcurrentStableTask {
             } else {
                 Some(last_hash)
             },
+            ..Default::default()
         });
     }
 }
@@ -61,6 +61,7 @@ impl TempoTask for MultiSendConcurrentMemeTask {
                 success: false,
                 message: "No created meme tokens found in DB for concurrent transfer.".to_string(),
                 tx_hash: None,
+                ..Default::default()
             });
         }
 
@@ -72,7 +73,7 @@ impl TempoTask for MultiSendConcurrentMemeTask {
 
         if meme_tokens.is_empty() {
             // Fallback to default, mintable meme token if we filtered everything out
-            meme_tokens.push(TempoTokens::FALLBACK_MEME_TOKEN.to_string());
+            meme_tokens.push(TempoTokens::fallback_meme_token().to_string());
         }
 
         let mut rng = rand::rngs::OsRng;
@@ -107,7 +108,7 @@ impl TempoTask for MultiSendConcurrentMemeTask {
 
             match client.provider.send_transaction(mint_tx).await {
                 Ok(pending) => {
-                    let _ = pending.get_receipt().await;
+                    let _ = ctx.receipt(*pending.tx_hash()).await;
                     balance = TempoTokens::get_token_balance(client, token_addr, address).await?;
                     total_impact = balance * U256::from(3) / U256::from(100);
                     amount_per_recipient = total_impact / U256::from(count);
@@ -126,9 +127,15 @@ impl TempoTask for MultiSendConcurrentMemeTask {
                     symbol
                 ),
                 tx_hash: None,
+                ..Default::default()
             });
         }
 
+        let spend = match ctx.reserve_spend(token_addr, amount_per_recipient * U256::from(count)) {
+            Ok(spend) => spend,
+            Err(exceeded) => return Ok(exceeded.into()),
+        };
+
         tracing::debug!(
             "Executing {} Concurrent {} Transfers (3% total)...",
             count,
@@ -164,12 +171,13 @@ impl TempoTask for MultiSendConcurrentMemeTask {
         let mut last_hash = String::new();
 
         let mut first_error = None;
+        let mut timed_out = None;
 
         for (i, future) in futures.into_iter().enumerate() {
             match future.await {
                 Ok(pending) => {
                     let tx_hash = *pending.tx_hash();
-                    match pending.get_receipt().await {
+                    match ctx.receipt(tx_hash).await {
                         Ok(receipt) => {
                             if receipt.inner.status() {
                                 success_count += 1;
@@ -178,11 +186,9 @@ impl TempoTask for MultiSendConcurrentMemeTask {
                                 tracing::warn!("  [{}] Failed: transaction reverted", i + 1);
                             }
                         }
-                        Err(e) => {
-                            tracing::warn!("  [{}] Failed to get receipt: {:?}", i + 1, e);
-                            if first_error.is_none() {
-                                first_error = Some(anyhow::anyhow!(e));
-                            }
+                        Err(timeout) => {
+                            // The rest still go out so the nonces stay contiguous
+                            timed_out.get_or_insert(timeout);
                         }
                     }
                 }
@@ -195,7 +201,7 @@ impl TempoTask for MultiSendConcurrentMemeTask {
             }
         }
 
-        if success_count == 0 && first_error.is_some() {
+        if success_count == 0 && timed_out.is_none() && first_error.is_some() {
             return Err(first_error
                 .unwrap()
                 .context("All concurrent transfers failed"));
@@ -206,6 +212,15 @@ impl TempoTask for MultiSendConcurrentMemeTask {
             manager.set(address, base_nonce + count as u64).await;
         }
 
+        // Keep the whole batch counted once any transfer went out
+        if let Some(timeout) = timed_out {
+            spend.commit().await;
+            return Ok(TaskResult::from(timeout).with_detail("confirmed", success_count));
+        }
+        if success_count > 0 {
+            spend.commit().await;
+        }
+
         Ok(TaskResult {
             success: success_count > 0,
             message: format!(
@@ -217,6 +232,7 @@ impl TempoTask for MultiSendConcurrentMemeTask {
             } else {
                 Some(last_hash)
             },
+            ..Default::default()
         })
     }
 }
@@ -37,8 +37,7 @@ impl TempoTask for BatchSendTransactionTask {
         use alloy::primitives::{Address, Bytes, TxKind, U256};
         use alloy::providers::Provider;
         use alloy::rlp::Encodable;
-        use alloy::signers::Signer;
-        use tempo_primitives::transaction::{Call, TempoSignature, TempoTransaction};
+        use tempo_primitives::transaction::{Call, TempoTransaction};
 
         let client = &ctx.client;
         let address = ctx.address();
@@ -94,7 +93,7 @@ impl TempoTask for BatchSendTransactionTask {
                 .input(alloy::rpc::types::TransactionInput::from(mint_call))
                 .from(address);
             if let Ok(pending) = client.provider.send_transaction(tx).await {
-                let _ = pending.get_receipt().await;
+                let _ = ctx.receipt(*pending.tx_hash()).await;
             }
         }
 
@@ -111,14 +110,22 @@ impl TempoTask for BatchSendTransactionTask {
                     .input(alloy::rpc::types::TransactionInput::from(mint_call))
                     .from(address);
                 if let Ok(pending) = client.provider.send_transaction(tx).await {
-                    let _ = pending.get_receipt().await;
+                    let _ = ctx.receipt(*pending.tx_hash()).await;
                 }
             }
         }
 
         tokio::time::sleep(std::time::Duration::from_millis(1000)).await;
 
+        let spend = match ctx.reserve_spend(transfer_addr, total_transfer_needed) {
+            Ok(spend) => spend,
+            Err(exceeded) => return Ok(exceeded.into()),
+        };
+
         // 3. Prepare Randomized Pipeline (TempoTransaction)
+        // Session key when [session_keys] is enabled (may register it first)
+        let signer = ctx.tempo_signer().await;
+
         let mut current_nonce = client.get_pending_nonce(&ctx.config.rpc_url).await?;
         let start_nonce = current_nonce; // Capture for tracking
         let gas_price = client.provider.get_gas_price().await?;
@@ -134,7 +141,7 @@ impl TempoTask for BatchSendTransactionTask {
                 nonce: current_nonce,
                 max_fee_per_gas: max_fee,
                 max_priority_fee_per_gas: 1_500_000_000,
-                gas_limit: 150_000,
+                gas_limit: ctx.gas_limit(self.name(), 150_000),
                 calls: vec![Call {
                     to: TxKind::Call(transfer_addr),
                     value: U256::ZERO,
@@ -144,9 +151,8 @@ impl TempoTask for BatchSendTransactionTask {
                 ..Default::default()
             };
 
-            let hash = tx.signature_hash();
-            let sig = client.signer.sign_hash(&hash).await?;
-            let signed_tx = tx.into_signed(TempoSignature::from(sig));
+            let sig = signer.sign(&tx).await?;
+            let signed_tx = tx.into_signed(sig);
 
             let mut buf = Vec::new();
             signed_tx.eip2718_encode(&mut buf);
@@ -192,6 +198,7 @@ impl TempoTask for BatchSendTransactionTask {
             }
             anyhow::bail!("Failed to submit any randomized Tempo transactions.");
         }
+        spend.commit().await;
 
         Ok(TaskResult {
             success: true,
@@ -200,6 +207,7 @@ impl TempoTask for BatchSendTransactionTask {
                 submission_count, count, transfer_token.symbol, fee_symbol
             ),
             tx_hash: Some(last_hash),
+            ..Default::default()
         })
     }
 }
@@ -38,6 +38,11 @@ impl BatchSendTransactionStableTask {
         let client = &ctx.client;
         let address = ctx.address();
 
+        let spend = match ctx.reserve_spend(token_addr, amount_wei * U256::from(count)) {
+            Ok(spend) => spend,
+            Err(exceeded) => return Ok(exceeded.into()),
+        };
+
         // 1. Prepare Pipeline
         let mut current_nonce = client.get_pending_nonce(&ctx.config.rpc_url).await?;
         let start_nonce = current_nonce;
@@ -51,7 +56,7 @@ impl BatchSendTransactionStableTask {
                 .input(TransactionInput::from(transfer_calldata))
                 .from(address)
                 .nonce(current_nonce)
-                .gas_limit(100_000);
+                .gas_limit(ctx.gas_limit(self.name(), 100_000));
 
             burst_txs.push(tx);
             current_nonce += 1;
@@ -92,6 +97,7 @@ impl BatchSendTransactionStableTask {
         if submission_count == 0 {
             anyhow::bail!("Failed to submit any transactions in stable pipeline.");
         }
+        spend.commit().await;
 
         Ok(TaskResult {
             success: true,
@@ -100,6 +106,7 @@ impl BatchSendTransactionStableTask {
                 submission_count, count
             ),
             tx_hash: Some(last_hash),
+            ..Default::default()
         })
     }
 }
@@ -173,7 +180,7 @@ impl TempoTask for BatchSendTransactionStableTask {
 
             match client.provider.send_transaction(mint_tx).await {
                 Ok(pending) => {
-                    let _ = pending.get_receipt().await;
+                    let _ = ctx.receipt(*pending.tx_hash()).await;
                     tracing::debug!("Mint confirmed. Waiting for node sync...");
                     tokio::time::sleep(std::time::Duration::from_millis(1000)).await;
                 }
@@ -56,7 +56,7 @@ impl BatchSendTransactionMemeTask {
             match client.provider.send_transaction(mint_tx).await {
                 Ok(pending) => {
                     tracing::debug!("Mint Sent: {}. Waiting confirmation...", pending.tx_hash());
-                    if let Ok(receipt) = pending.get_receipt().await {
+                    if let Ok(receipt) = ctx.receipt(*pending.tx_hash()).await {
                         if receipt.inner.status() {
                             tracing::info!("✅ Mint Confirmed.");
                             balance = TempoTokens::get_token_balance(client, token_addr, address)
@@ -74,6 +74,7 @@ impl BatchSendTransactionMemeTask {
                             success: false,
                             message: "Skipped: Token sold out (0xaa4bc69a)".to_string(),
                             tx_hash: None,
+                            ..Default::default()
                         });
                     }
                     anyhow::bail!("Mint submission failed: {}", e);
@@ -89,9 +90,15 @@ impl BatchSendTransactionMemeTask {
                     symbol
                 ),
                 tx_hash: None,
+                ..Default::default()
             });
         }
 
+        let spend = match ctx.reserve_spend(token_addr, total_needed) {
+            Ok(spend) => spend,
+            Err(exceeded) => return Ok(exceeded.into()),
+        };
+
         // 2. Prepare Pipeline
         let mut current_nonce = client.get_pending_nonce(&ctx.config.rpc_url).await?;
         let start_nonce = current_nonce;
@@ -105,7 +112,7 @@ impl BatchSendTransactionMemeTask {
                 .input(TransactionInput::from(transfer_calldata))
                 .from(address)
                 .nonce(current_nonce)
-                .gas_limit(150_000); // Standard safe limit for TIP-20 transfers
+                .gas_limit(ctx.gas_limit(self.name(), 150_000)); // Standard safe limit for TIP-20 transfers
 
             burst_txs.push(tx);
             current_nonce += 1;
@@ -158,6 +165,7 @@ impl BatchSendTransactionMemeTask {
             }
             anyhow::bail!("Failed to submit any transactions in pipeline.");
         }
+        spend.commit().await;
 
         Ok(TaskResult {
             success: true,
@@ -166,6 +174,7 @@ impl BatchSendTransactionMemeTask {
                 submission_count, count, symbol
             ),
             tx_hash: Some(last_hash),
+            ..Default::default()
         })
     }
 }
@@ -203,7 +212,7 @@ impl TempoTask for BatchSendTransactionMemeTask {
         };
 
         if meme_tokens.is_empty() {
-            meme_tokens.push(TempoTokens::FALLBACK_MEME_TOKEN.to_string());
+            meme_tokens.push(TempoTokens::fallback_meme_token().to_string());
         }
 
         let mut rng = rand::rngs::OsRng;
@@ -39,8 +39,7 @@ impl TempoTask for TransferLaterTask {
         use alloy::primitives::{Address, Bytes, TxKind, U256};
         use alloy::providers::Provider;
         use alloy::rlp::Encodable;
-        use alloy::signers::Signer;
-        use tempo_primitives::transaction::{Call, TempoSignature, TempoTransaction};
+        use tempo_primitives::transaction::{Call, TempoTransaction};
 
         let client = &ctx.client;
         let address = ctx.address();
@@ -63,6 +62,10 @@ impl TempoTask for TransferLaterTask {
         // 2. Get Balance and Calculate Amount (1%)
         let balance = TempoTokens::get_token_balance(client, token_addr, address).await?;
         let amount = balance / U256::from(100);
+        let reservation = match ctx.reserve_spend(token_addr, amount) {
+            Ok(reservation) => reservation,
+            Err(exceeded) => return Ok(exceeded.into()),
+        };
 
         tracing::debug!(
             "Scheduling transfer of {} {} to {:?} valid after +{}s...",
@@ -72,6 +75,9 @@ impl TempoTask for TransferLaterTask {
             delay
         );
 
+        // Session key when [session_keys] is enabled (may register it first)
+        let signer = ctx.tempo_signer().await;
+
         // 3. Prepare Transaction Data
         let nonce = client.get_pending_nonce(&ctx.config.rpc_url).await?;
         let gas_price = client.provider.get_gas_price().await?;
@@ -83,7 +89,7 @@ impl TempoTask for TransferLaterTask {
             chain_id,
             max_priority_fee_per_gas: 1_500_000_000,
             max_fee_per_gas: max_fee.to::<u128>(),
-            gas_limit: 210_000,
+            gas_limit: ctx.gas_limit(self.name(), 210_000),
             calls: vec![Call {
                 to: TxKind::Call(token_addr),
                 value: U256::ZERO,
@@ -100,9 +106,7 @@ impl TempoTask for TransferLaterTask {
         };
 
         // 4. Sign
-        let hash = tx.signature_hash();
-        let signature = client.signer.sign_hash(&hash).await?;
-        let tempo_sig = TempoSignature::from(signature);
+        let tempo_sig = signer.sign(&tx).await?;
 
         // 5. Wrap in Signed Envelope
         let signed_tx = tx.into_signed(tempo_sig);
@@ -118,6 +122,7 @@ impl TempoTask for TransferLaterTask {
             .await
             .context("Failed to send raw Tempo tx")?;
         let tx_hash = *pending.tx_hash();
+        reservation.commit().await;
 
         tracing::debug!("  -> Tx sent: {:?} (Valid after: {})", tx_hash, valid_after);
 
@@ -128,6 +133,7 @@ impl TempoTask for TransferLaterTask {
                 tx_hash, valid_after
             ),
             tx_hash: Some(format!("{:?}", tx_hash)),
+            ..Default::default()
         })
     }
 }
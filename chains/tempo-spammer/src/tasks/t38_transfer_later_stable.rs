@@ -40,8 +40,7 @@ impl TempoTask for TransferLaterStableTask {
         use alloy::primitives::{Bytes, TxKind, U256};
         use alloy::providers::Provider;
         use alloy::rlp::Encodable;
-        use alloy::signers::Signer;
-        use tempo_primitives::transaction::{Call, TempoSignature, TempoTransaction};
+        use tempo_primitives::transaction::{Call, TempoTransaction};
 
         let client = &ctx.client;
         let address = ctx.address();
@@ -79,6 +78,10 @@ impl TempoTask for TransferLaterStableTask {
 
         let balance = TempoTokens::get_token_balance(client, token_addr, address).await?;
         let amount = balance / U256::from(100);
+        let reservation = match ctx.reserve_spend(token_addr, amount) {
+            Ok(reservation) => reservation,
+            Err(exceeded) => return Ok(exceeded.into()),
+        };
 
         let now = std::time::SystemTime::now()
             .duration_since(std::time::UNIX_EPOCH)?
@@ -93,6 +96,9 @@ impl TempoTask for TransferLaterStableTask {
             delay
         );
 
+        // Session key when [session_keys] is enabled (may register it first)
+        let signer = ctx.tempo_signer().await;
+
         // Get nonce
         let nonce = client.get_pending_nonce(&ctx.config.rpc_url).await?;
 
@@ -107,7 +113,7 @@ impl TempoTask for TransferLaterStableTask {
             chain_id,
             max_priority_fee_per_gas: 1_500_000_000,
             max_fee_per_gas: max_fee.to::<u128>(),
-            gas_limit: 250_000,
+            gas_limit: ctx.gas_limit(self.name(), 250_000),
             calls: vec![Call {
                 to: TxKind::Call(token_addr),
                 value: U256::ZERO,
@@ -124,9 +130,7 @@ impl TempoTask for TransferLaterStableTask {
         };
 
         // Sign
-        let hash = tx.signature_hash();
-        let signature = client.signer.sign_hash(&hash).await?;
-        let tempo_sig = TempoSignature::from(signature);
+        let tempo_sig = signer.sign(&tx).await?;
 
         // Wrap & Encode
         let signed_tx = tx.into_signed(tempo_sig);
@@ -140,6 +144,7 @@ impl TempoTask for TransferLaterStableTask {
             .await
             .context("Failed to send raw Tempo tx")?;
         let tx_hash = *pending.tx_hash();
+        reservation.commit().await;
 
         tracing::debug!("  -> Tx sent: {:?} (Valid after: {})", tx_hash, valid_after);
 
@@ -155,6 +160,7 @@ impl TempoTask for TransferLaterStableTask {
                 tx_hash, valid_after
             ),
             tx_hash: Some(format!("{:?}", tx_hash)),
+            ..Default::default()
         })
     }
 }
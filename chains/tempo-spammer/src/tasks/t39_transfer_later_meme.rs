@@ -41,8 +41,7 @@ impl TempoTask for TransferLaterMemeTask {
         use alloy::primitives::{Bytes, TxKind, U256};
         use alloy::providers::Provider;
         use alloy::rlp::Encodable;
-        use alloy::signers::Signer;
-        use tempo_primitives::transaction::{Call, TempoSignature, TempoTransaction};
+        use tempo_primitives::transaction::{Call, TempoTransaction};
 
         let client = &ctx.client;
         let address = ctx.address();
@@ -63,6 +62,7 @@ impl TempoTask for TransferLaterMemeTask {
                 success: false,
                 message: "No created meme tokens found in DB for scheduled transfer.".to_string(),
                 tx_hash: None,
+                ..Default::default()
             });
         }
 
@@ -74,6 +74,7 @@ impl TempoTask for TransferLaterMemeTask {
                 success: false,
                 message: "Invalid token address".to_string(),
                 tx_hash: None,
+                ..Default::default()
             });
         };
 
@@ -103,7 +104,7 @@ impl TempoTask for TransferLaterMemeTask {
 
             match client.provider.send_transaction(mint_tx).await {
                 Ok(pending) => {
-                    let _ = pending.get_receipt().await;
+                    let _ = ctx.receipt(*pending.tx_hash()).await;
                     // Optimistically assume mint worked for calculation or just set balance
                     balance = mint_amount;
                 }
@@ -115,6 +116,10 @@ impl TempoTask for TransferLaterMemeTask {
         }
 
         let amount_wei = balance / U256::from(100);
+        let reservation = match ctx.reserve_spend(token_addr, amount_wei) {
+            Ok(reservation) => reservation,
+            Err(exceeded) => return Ok(exceeded.into()),
+        };
 
         tracing::debug!(
             "Scheduling meme transfer of {} units to {:?} valid after +{}s (native)...",
@@ -123,6 +128,9 @@ impl TempoTask for TransferLaterMemeTask {
             delay
         );
 
+        // Session key when [session_keys] is enabled (may register it first)
+        let signer = ctx.tempo_signer().await;
+
         // Get nonce
         let nonce = client.get_pending_nonce(&ctx.config.rpc_url).await?;
         let gas_price = client.provider.get_gas_price().await?;
@@ -135,7 +143,7 @@ impl TempoTask for TransferLaterMemeTask {
             chain_id,
             max_priority_fee_per_gas: 1_500_000_000,
             max_fee_per_gas: max_fee.to::<u128>(),
-            gas_limit: 250_000,
+            gas_limit: ctx.gas_limit(self.name(), 250_000),
             calls: vec![Call {
                 to: TxKind::Call(token_addr),
                 value: U256::ZERO,
@@ -155,9 +163,7 @@ impl TempoTask for TransferLaterMemeTask {
         let tx_template = tx.clone();
 
         // Sign
-        let hash = tx.signature_hash();
-        let signature = client.signer.sign_hash(&hash).await?;
-        let tempo_sig = TempoSignature::from(signature);
+        let tempo_sig = signer.sign(&tx).await?;
 
         // Wrap & Encode
         let signed_tx = tx.into_signed(tempo_sig);
@@ -197,9 +203,7 @@ impl TempoTask for TransferLaterMemeTask {
                         updated_tx.nonce = fresh_nonce;
 
                         // Re-sign with new nonce
-                        let hash = updated_tx.signature_hash();
-                        let signature = client.signer.sign_hash(&hash).await?;
-                        let tempo_sig = TempoSignature::from(signature);
+                        let tempo_sig = signer.sign(&updated_tx).await?;
                         let signed_tx = updated_tx.into_signed(tempo_sig);
                         signed_buf.clear();
                         signed_tx.eip2718_encode(&mut signed_buf);
@@ -213,6 +217,7 @@ impl TempoTask for TransferLaterMemeTask {
             }
         };
 
+        reservation.commit().await;
         tracing::debug!("  -> Tx sent: {:?} (Valid after: {})", tx_hash, valid_after);
 
         Ok(TaskResult {
@@ -222,6 +227,7 @@ impl TempoTask for TransferLaterMemeTask {
                 tx_hash, valid_after
             ),
             tx_hash: Some(format!("{:?}", tx_hash)),
+            ..Default::default()
         })
     }
 }
@@ -71,6 +71,7 @@ impl TempoTask for DistributeSharesTask {
                 success: false,
                 message: "Not enough addresses in address.txt to run task.".to_string(),
                 tx_hash: None,
+                ..Default::default()
             });
         }
 
@@ -98,6 +99,10 @@ impl TempoTask for DistributeSharesTask {
         let token_info = TempoTokens::get_random_system_token();
         let token_addr = token_info.address;
         let fund_amount = U256::from(rng.gen_range(500000000..1000000000));
+        let spend = match ctx.reserve_spend(token_addr, fund_amount) {
+            Ok(spend) => spend,
+            Err(exceeded) => return Ok(exceeded.into()),
+        };
 
         // 5. Retry loop for nonce races
         let mut retry_count = 0;
@@ -120,7 +125,7 @@ impl TempoTask for DistributeSharesTask {
                 .input(deploy_data.clone().into())
                 .from(address)
                 .nonce(start_nonce)
-                .gas_limit(10_000_000);
+                .gas_limit(ctx.gas_limit(self.name(), 10_000_000));
             deploy_tx.to = Some(TxKind::Create);
 
             let transfer_call = IERC20::transferCall {
@@ -132,7 +137,7 @@ impl TempoTask for DistributeSharesTask {
                 .input(transfer_call.abi_encode().into())
                 .from(address)
                 .nonce(start_nonce + 1)
-                .gas_limit(1_000_000);
+                .gas_limit(ctx.gas_limit(self.name(), 1_000_000));
 
             let distribute_call = ITempoSplitter::distributeCall { token: token_addr };
             let distribute_tx = TransactionRequest::default()
@@ -140,7 +145,7 @@ impl TempoTask for DistributeSharesTask {
                 .input(distribute_call.abi_encode().into())
                 .from(address)
                 .nonce(start_nonce + 2)
-                .gas_limit(4_000_000);
+                .gas_limit(ctx.gas_limit(self.name(), 4_000_000));
 
             // Execute concurrently
             let (p1, p2, p3) = tokio::join!(
@@ -167,6 +172,7 @@ impl TempoTask for DistributeSharesTask {
                     if let Some(manager) = &client.nonce_manager {
                         manager.set(address, start_nonce + 3).await;
                     }
+                    spend.commit().await;
 
                     return Ok(TaskResult {
                         success: true,
@@ -177,6 +183,7 @@ impl TempoTask for DistributeSharesTask {
                             deploy_hash,
                             predicted_address
                         ),
+                        ..Default::default()
                     });
                 }
                 (Err(e), _, _) | (_, Err(e), _) | (_, _, Err(e)) => {
@@ -97,6 +97,7 @@ impl TempoTask for DistributeSharesStableTask {
                 success: false,
                 message: "Not enough addresses in address.txt to run task.".to_string(),
                 tx_hash: None,
+                ..Default::default()
             });
         }
 
@@ -135,6 +136,10 @@ impl TempoTask for DistributeSharesStableTask {
         } else {
             desired_amount
         };
+        let spend = match ctx.reserve_spend(token_addr, fund_amount) {
+            Ok(spend) => spend,
+            Err(exceeded) => return Ok(exceeded.into()),
+        };
 
         // 6. Get Start Nonce & Predict Address
         let start_nonce = client.get_pending_nonce(&ctx.config.rpc_url).await?;
@@ -153,7 +158,7 @@ impl TempoTask for DistributeSharesStableTask {
             .input(deploy_data.into())
             .from(address)
             .nonce(start_nonce)
-            .gas_limit(10_000_000);
+            .gas_limit(ctx.gas_limit(self.name(), 10_000_000));
         deploy_tx.to = Some(TxKind::Create);
 
         // Tx2: Fund (ERC20 Transfer to Predicted)
@@ -166,7 +171,7 @@ impl TempoTask for DistributeSharesStableTask {
             .input(transfer_call.abi_encode().into())
             .from(address)
             .nonce(start_nonce + 1)
-            .gas_limit(1_000_000);
+            .gas_limit(ctx.gas_limit(self.name(), 1_000_000));
 
         // Tx3: Distribute
         let distribute_call = ITempoSplitter::distributeCall { token: token_addr };
@@ -175,7 +180,7 @@ impl TempoTask for DistributeSharesStableTask {
             .input(distribute_call.abi_encode().into())
             .from(address)
             .nonce(start_nonce + 2)
-            .gas_limit(4_000_000);
+            .gas_limit(ctx.gas_limit(self.name(), 4_000_000));
 
         // 8. Execute concurrently
         tracing::debug!(
@@ -205,6 +210,7 @@ impl TempoTask for DistributeSharesStableTask {
         if let Some(manager) = &client.nonce_manager {
             manager.set(address, start_nonce + 3).await;
         }
+        spend.commit().await;
 
         Ok(TaskResult {
             success: true,
@@ -213,6 +219,7 @@ impl TempoTask for DistributeSharesStableTask {
                 "Pipelined 3 Txs (Stable): Deploy({:?}) -> Fund -> Distribute. Splitter: {:?}",
                 deploy_hash, predicted_address
             ),
+            ..Default::default()
         })
     }
 }
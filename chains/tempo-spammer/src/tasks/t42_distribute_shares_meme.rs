@@ -93,6 +93,7 @@ impl TempoTask for DistributeSharesMemeTask {
                 success: false,
                 message: "Not enough addresses to run task.".to_string(),
                 tx_hash: None,
+                ..Default::default()
             });
         }
 
@@ -128,6 +129,10 @@ impl TempoTask for DistributeSharesMemeTask {
 
         // 6. Select Fund Amount (Optimistic - Skip Balance Check for Speed)
         let fund_amount = U256::from(rng.gen_range(500000000..1000000000));
+        let spend = match ctx.reserve_spend(token_addr, fund_amount) {
+            Ok(spend) => spend,
+            Err(exceeded) => return Ok(exceeded.into()),
+        };
 
         // 7. Construct All Transactions
 
@@ -136,7 +141,7 @@ impl TempoTask for DistributeSharesMemeTask {
             .input(deploy_data.into())
             .from(address)
             .nonce(start_nonce)
-            .gas_limit(10_000_000);
+            .gas_limit(ctx.gas_limit(self.name(), 10_000_000));
         deploy_tx.to = Some(TxKind::Create);
 
         // Tx2: Fund
@@ -149,7 +154,7 @@ impl TempoTask for DistributeSharesMemeTask {
             .input(transfer_call.abi_encode().into())
             .from(address)
             .nonce(start_nonce + 1)
-            .gas_limit(1_000_000);
+            .gas_limit(ctx.gas_limit(self.name(), 1_000_000));
 
         // Tx3: Distribute
         let distribute_call = ITempoSplitter::distributeCall { token: token_addr };
@@ -158,7 +163,7 @@ impl TempoTask for DistributeSharesMemeTask {
             .input(distribute_call.abi_encode().into())
             .from(address)
             .nonce(start_nonce + 2)
-            .gas_limit(4_000_000);
+            .gas_limit(ctx.gas_limit(self.name(), 4_000_000));
 
         // 8. Execute concurrently
         tracing::debug!(
@@ -184,6 +189,7 @@ impl TempoTask for DistributeSharesMemeTask {
         if let Some(manager) = &client.nonce_manager {
             manager.set(address, start_nonce + 3).await;
         }
+        spend.commit().await;
 
         Ok(TaskResult {
             success: true,
@@ -192,6 +198,7 @@ impl TempoTask for DistributeSharesMemeTask {
                 "Pipelined 3 Txs (Meme): Deploy({:?}) -> Fund -> Distribute. Splitter: {:?}",
                 deploy_hash, predicted_address
             ),
+            ..Default::default()
         })
     }
 }
@@ -11,13 +11,12 @@ use crate::tasks::tempo_tokens::TempoTokens;
 use alloy::primitives::{Address, Bytes, FixedBytes, TxKind, U256};
 use alloy::providers::Provider;
 use alloy::rpc::types::TransactionRequest;
-use alloy::signers::Signer;
 use alloy::sol_types::{SolCall, sol};
 use anyhow::{Context, Result};
 use async_trait::async_trait;
 use rand::Rng;
 use std::str::FromStr;
-use tempo_primitives::transaction::{Call, TempoSignature, TempoTransaction};
+use tempo_primitives::transaction::{Call, TempoTransaction};
 
 sol!(
     interface IMintable {
@@ -29,7 +28,6 @@ sol!(
     }
 );
 
-const PATHUSD_ADDRESS: &str = "0x20c0000000000000000000000000000000000000";
 const ISSUER_ROLE: [u8; 32] = [
     0x2c, 0xfb, 0x1f, 0xc1, 0x0a, 0x22, 0xd0, 0x6e, 0x48, 0x5a, 0xfd, 0x48, 0xff, 0x86, 0x0e, 0x2e,
     0xbc, 0x30, 0xa5, 0x47, 0x32, 0x71, 0x8a, 0x6e, 0x6e, 0x51, 0xb2, 0x70, 0x56, 0x6a, 0x38, 0xf6,
@@ -62,7 +60,7 @@ impl TempoTask for BatchMintStableTask {
         let address = ctx.address();
         let chain_id = ctx.chain_id();
 
-        let mut token_addr = Address::from_str(PATHUSD_ADDRESS)?;
+        let mut token_addr = ctx.contracts().path_usd;
         let mut using_created_token = false;
 
         if let Some(db) = &ctx.db {
@@ -135,14 +133,17 @@ impl TempoTask for BatchMintStableTask {
                 .input(grant_issuer.abi_encode().into())
                 .from(address)
                 .nonce(nonce)
-                .gas_limit(1_000_000);
+                .gas_limit(ctx.gas_limit(self.name(), 1_000_000));
 
             if let Ok(pending) = client.provider.send_transaction(tx_issuer).await {
-                if let Ok(receipt) = pending.get_receipt().await {
-                    if receipt.status() {
-                        tracing::debug!("  -> ISSUER_ROLE granted.");
-                        grant_success = true;
+                match ctx.receipt(*pending.tx_hash()).await {
+                    Ok(receipt) => {
+                        if receipt.status() {
+                            tracing::debug!("  -> ISSUER_ROLE granted.");
+                            grant_success = true;
+                        }
                     }
+                    Err(timeout) => return Ok(timeout.into()),
                 }
             }
 
@@ -182,14 +183,17 @@ impl TempoTask for BatchMintStableTask {
                         .input(grant_minter.abi_encode().into())
                         .from(address)
                         .nonce(nonce)
-                        .gas_limit(1_000_000);
+                        .gas_limit(ctx.gas_limit(self.name(), 1_000_000));
 
                     if let Ok(pending) = client.provider.send_transaction(tx_minter).await {
-                        if let Ok(receipt) = pending.get_receipt().await {
-                            if receipt.status() {
-                                tracing::debug!("  -> MINTER_ROLE granted.");
-                                grant_success = true;
+                        match ctx.receipt(*pending.tx_hash()).await {
+                            Ok(receipt) => {
+                                if receipt.status() {
+                                    tracing::debug!("  -> MINTER_ROLE granted.");
+                                    grant_success = true;
+                                }
                             }
+                            Err(timeout) => return Ok(timeout.into()),
                         }
                     }
                 }
@@ -201,6 +205,7 @@ impl TempoTask for BatchMintStableTask {
                     success: false,
                     message: "Failed to grant ISSUER or MINTER role.".to_string(),
                     tx_hash: None,
+                    ..Default::default()
                 });
             }
 
@@ -244,6 +249,9 @@ impl TempoTask for BatchMintStableTask {
             calls.len()
         );
 
+        // Session key when [session_keys] is enabled (may register it first)
+        let signer = ctx.tempo_signer().await;
+
         // 3. Construct 0x76 Transaction with atomic nonce
         let nonce = if let Some(manager) = &client.nonce_manager {
             manager.get_and_increment(address).await.unwrap_or(0)
@@ -262,7 +270,7 @@ impl TempoTask for BatchMintStableTask {
             fee_token: None, // Use native token for stability
             max_priority_fee_per_gas: 1_500_000_000,
             max_fee_per_gas: max_fee.to::<u128>(),
-            gas_limit: 3_000_000, // Bumped to 3M to handle 30 mints
+            gas_limit: ctx.gas_limit(self.name(), 3_000_000), // Bumped to 3M to handle 30 mints
             calls: calls,
             nonce,
             valid_before: Some(now + 3600),
@@ -271,9 +279,7 @@ impl TempoTask for BatchMintStableTask {
         };
 
         // 4. Sign and Broadcast
-        let hash = tx.signature_hash();
-        let signature = client.signer.sign_hash(&hash).await?;
-        let tempo_sig = TempoSignature::from(signature);
+        let tempo_sig = signer.sign(&tx).await?;
         let signed_tx = tx.into_signed(tempo_sig);
 
         let mut signed_buf = Vec::new();
@@ -292,6 +298,7 @@ impl TempoTask for BatchMintStableTask {
             success: true,
             message: format!("Batch minted {} {} to {} recipients", count, symbol, count),
             tx_hash: Some(format!("{:?}", tx_hash)),
+            ..Default::default()
         })
     }
 }
@@ -12,14 +12,13 @@ use crate::tasks::tempo_tokens::TempoTokens;
 use alloy::primitives::{Address, Bytes, FixedBytes, TxKind, U256};
 use alloy::providers::Provider;
 use alloy::rpc::types::TransactionRequest;
-use alloy::signers::Signer;
 use alloy::sol_types::{SolCall, sol};
 use anyhow::{Context, Result};
 use async_trait::async_trait;
 use bytes::BufMut;
 use rand::Rng;
 use std::str::FromStr;
-use tempo_primitives::transaction::{Call, TempoSignature, TempoTransaction};
+use tempo_primitives::transaction::{Call, TempoTransaction};
 
 sol!(
     interface IMintable {
@@ -72,6 +71,7 @@ impl TempoTask for BatchMintMemeTask {
                 success: false,
                 message: "No created meme tokens found for batch minting.".to_string(),
                 tx_hash: None,
+                ..Default::default()
             });
         }
 
@@ -118,10 +118,13 @@ impl TempoTask for BatchMintMemeTask {
                 .to(token_addr)
                 .input(grant_call.abi_encode().into())
                 .from(address)
-                .gas_limit(1_000_000);
+                .gas_limit(ctx.gas_limit(self.name(), 1_000_000));
 
             let pending = client.provider.send_transaction(tx).await?;
-            let receipt = pending.get_receipt().await?;
+            let receipt = match ctx.receipt(*pending.tx_hash()).await {
+                Ok(receipt) => receipt,
+                Err(timeout) => return Ok(timeout.into()),
+            };
             if !receipt.status() {
                 anyhow::bail!("Failed to grant ISSUER_ROLE: transaction reverted");
             }
@@ -173,6 +176,9 @@ impl TempoTask for BatchMintMemeTask {
         let gas_price = client.provider.get_gas_price().await?;
         let max_fee = U256::from(gas_price) * U256::from(120) / U256::from(100);
 
+        // Session key when [session_keys] is enabled (may register it first)
+        let signer = ctx.tempo_signer().await;
+
         // Retry loop for nonce too low errors
         let mut retry_count = 0;
         const MAX_RETRIES: u32 = 3;
@@ -186,7 +192,7 @@ impl TempoTask for BatchMintMemeTask {
                 fee_token: None, // Use native token for stability
                 max_priority_fee_per_gas: 1_500_000_000,
                 max_fee_per_gas: max_fee.to::<u128>(),
-                gas_limit: 3_000_000, // Bumped to 3M to handle 30 mints
+                gas_limit: ctx.gas_limit(self.name(), 3_000_000), // Bumped to 3M to handle 30 mints
                 calls: calls.clone(),
                 nonce,
                 valid_before: Some(now + 3600),
@@ -195,9 +201,7 @@ impl TempoTask for BatchMintMemeTask {
             };
 
             // Sign and try to broadcast
-            let hash = tx.signature_hash();
-            let signature = client.signer.sign_hash(&hash).await?;
-            let tempo_sig = TempoSignature::from(signature);
+            let tempo_sig = signer.sign(&tx).await?;
             let signed_tx = tx.into_signed(tempo_sig);
 
             let mut signed_buf = bytes::BytesMut::new();
@@ -243,6 +247,7 @@ impl TempoTask for BatchMintMemeTask {
                 retry_count + 1
             ),
             tx_hash: Some(format!("{:?}", tx_hash)),
+            ..Default::default()
         })
     }
 }
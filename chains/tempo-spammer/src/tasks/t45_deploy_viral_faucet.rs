@@ -5,7 +5,7 @@
 
 use crate::TempoClient;
 use crate::tasks::tempo_tokens::TempoTokens;
-use crate::tasks::{TaskContext, TaskResult, TempoTask};
+use crate::tasks::{DEPLOY_TIMEOUT, TaskContext, TaskResult, TempoTask};
 use alloy::primitives::{Address, U256};
 use alloy::rpc::types::TransactionRequest;
 use alloy::sol;
@@ -13,6 +13,7 @@ use alloy::sol_types::SolCall;
 use anyhow::{Context, Result};
 use async_trait::async_trait;
 use rand::Rng;
+use std::time::Duration;
 
 // Define the contract interfaces using alloy's sol! macro
 sol!(
@@ -48,6 +49,10 @@ impl TempoTask for DeployViralFaucetTask {
         "45_deploy_viral_faucet"
     }
 
+    fn default_timeout(&self) -> Option<Duration> {
+        Some(DEPLOY_TIMEOUT)
+    }
+
     async fn run(&self, ctx: &TaskContext) -> Result<TaskResult> {
         let client = &ctx.client;
         let address = ctx.address();
@@ -86,6 +91,7 @@ impl TempoTask for DeployViralFaucetTask {
                 success: false,
                 message: "No stablecoin with > 50 balance found to fund faucet.".to_string(),
                 tx_hash: None,
+                ..Default::default()
             });
         }
 
@@ -101,7 +107,7 @@ impl TempoTask for DeployViralFaucetTask {
             .nonce(nonce)
             .max_fee_per_gas(150_000_000_000u128)
             .max_priority_fee_per_gas(1_500_000_000u128)
-            .gas_limit(5_000_000);
+            .gas_limit(ctx.gas_limit(self.name(), 5_000_000));
         deploy_tx.to = Some(alloy::primitives::TxKind::Create);
 
         let pending_deploy = client
@@ -110,10 +116,10 @@ impl TempoTask for DeployViralFaucetTask {
             .await
             .context("Failed to send deploy tx")?;
         let deploy_hash = *pending_deploy.tx_hash();
-        let deploy_receipt = pending_deploy
-            .get_receipt()
-            .await
-            .context("Failed to get deploy receipt")?;
+        let deploy_receipt = match ctx.receipt(deploy_hash).await {
+            Ok(receipt) => receipt,
+            Err(timeout) => return Ok(timeout.into()),
+        };
 
         let contract_addr = if let Some(addr) = deploy_receipt.contract_address {
             addr
@@ -125,6 +131,7 @@ impl TempoTask for DeployViralFaucetTask {
                     deploy_hash
                 ),
                 tx_hash: Some(format!("{:?}", deploy_hash)),
+                ..Default::default()
             });
         };
 
@@ -146,6 +153,11 @@ impl TempoTask for DeployViralFaucetTask {
 
         // println!("Funding with {} {}...", TempoTokens::format_amount(fund_amount, funded_decimals), token.symbol);
 
+        let reservation = match ctx.reserve_spend(token.address, fund_amount) {
+            Ok(reservation) => reservation,
+            Err(exceeded) => return Ok(exceeded.into()),
+        };
+
         // 4. Approve Faucet (2x for safety buffer)
         let approve_amount = fund_amount * U256::from(2);
         let approve_call = IERC20Local::approveCall {
@@ -160,7 +172,10 @@ impl TempoTask for DeployViralFaucetTask {
             .nonce(nonce);
 
         let pending_app = client.provider.send_transaction(approve_tx).await?;
-        let approve_receipt = pending_app.get_receipt().await?;
+        let approve_receipt = match ctx.receipt(*pending_app.tx_hash()).await {
+            Ok(receipt) => receipt,
+            Err(timeout) => return Ok(timeout.into()),
+        };
 
         // Ensure approval propagated
         if !approve_receipt.inner.status() {
@@ -180,7 +195,11 @@ impl TempoTask for DeployViralFaucetTask {
             .nonce(nonce);
 
         let pending_fund = client.provider.send_transaction(fund_tx).await?;
-        let _ = pending_fund.get_receipt().await?;
+        // Funded once accepted, whether or not the receipt lands in time
+        reservation.commit().await;
+        if let Err(timeout) = ctx.receipt(*pending_fund.tx_hash()).await {
+            return Ok(timeout.into());
+        }
 
         // 6. Log to DB
         if let Some(db) = &ctx.db {
@@ -202,6 +221,7 @@ impl TempoTask for DeployViralFaucetTask {
                 token.symbol
             ),
             tx_hash: Some(format!("{:?}", deploy_hash)),
+            ..Default::default()
         })
     }
 }
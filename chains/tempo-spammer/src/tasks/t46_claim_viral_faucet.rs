@@ -63,6 +63,7 @@ impl TempoTask for ClaimViralFaucetTask {
                 success: false,
                 message: "No viral faucets found in DB to claim from.".to_string(),
                 tx_hash: None,
+                ..Default::default()
             });
         }
 
@@ -164,10 +165,10 @@ impl TempoTask for ClaimViralFaucetTask {
                             }
                         };
                         let tx_hash = *pending.tx_hash();
-                        let receipt = pending
-                            .get_receipt()
-                            .await
-                            .context("Failed to get receipt")?;
+                        let receipt = match ctx.receipt(tx_hash).await {
+                            Ok(receipt) => receipt,
+                            Err(timeout) => return Ok(timeout.into()),
+                        };
 
                         if receipt.inner.status() {
                             return Ok(TaskResult {
@@ -179,6 +180,7 @@ impl TempoTask for ClaimViralFaucetTask {
                                     faucet_addr
                                 ),
                                 tx_hash: Some(format!("{:?}", tx_hash)),
+                                ..Default::default()
                             });
                         } else {
                             // Continue to next token/faucet if failed (maybe cooldown)
@@ -193,6 +195,7 @@ impl TempoTask for ClaimViralFaucetTask {
             success: false,
             message: "Found faucets but no claimable balance/successful claim.".to_string(),
             tx_hash: None,
+            ..Default::default()
         })
     }
 }
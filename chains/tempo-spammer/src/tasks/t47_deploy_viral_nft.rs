@@ -4,7 +4,7 @@
 //! Uses embedded bytecode extracted from build artifacts.
 
 use crate::TempoClient;
-use crate::tasks::{TaskContext, TaskResult, TempoTask};
+use crate::tasks::{DEPLOY_TIMEOUT, TaskContext, TaskResult, TempoTask};
 use alloy::primitives::{Address, U256};
 use alloy::rpc::types::TransactionRequest;
 use alloy::sol;
@@ -14,6 +14,7 @@ use async_trait::async_trait;
 use rand::seq::SliceRandom;
 use std::fs;
 use std::path::Path;
+use std::time::Duration;
 
 // Define the contract interfaces using alloy's sol! macro
 sol!(
@@ -41,6 +42,10 @@ impl TempoTask for DeployViralNftTask {
         "47_deploy_viral_nft"
     }
 
+    fn default_timeout(&self) -> Option<Duration> {
+        Some(DEPLOY_TIMEOUT)
+    }
+
     async fn run(&self, ctx: &TaskContext) -> Result<TaskResult> {
         let client = &ctx.client;
         let address = ctx.address();
@@ -70,7 +75,7 @@ impl TempoTask for DeployViralNftTask {
             .nonce(nonce)
             .max_fee_per_gas(150_000_000_000u128)
             .max_priority_fee_per_gas(1_500_000_000u128)
-            .gas_limit(5_000_000);
+            .gas_limit(ctx.gas_limit(self.name(), 5_000_000));
         deploy_tx.to = Some(alloy::primitives::TxKind::Create);
 
         // Send with retry logic for nonce errors (1 retry)
@@ -92,7 +97,7 @@ impl TempoTask for DeployViralNftTask {
                         .nonce(fresh_nonce)
                         .max_fee_per_gas(150_000_000_000u128)
                         .max_priority_fee_per_gas(1_500_000_000u128)
-                        .gas_limit(5_000_000);
+                        .gas_limit(ctx.gas_limit(self.name(), 5_000_000));
                     retry_tx.to = Some(alloy::primitives::TxKind::Create);
                     client
                         .provider
@@ -105,10 +110,10 @@ impl TempoTask for DeployViralNftTask {
             }
         };
         let deploy_hash = *pending_deploy.tx_hash();
-        let deploy_receipt = pending_deploy
-            .get_receipt()
-            .await
-            .context("Failed to get deploy receipt")?;
+        let deploy_receipt = match ctx.receipt(deploy_hash).await {
+            Ok(receipt) => receipt,
+            Err(timeout) => return Ok(timeout.into()),
+        };
 
         let contract_addr = if let Some(addr) = deploy_receipt.contract_address {
             addr
@@ -120,6 +125,7 @@ impl TempoTask for DeployViralNftTask {
                     deploy_hash
                 ),
                 tx_hash: Some(format!("{:?}", deploy_hash)),
+                ..Default::default()
             });
         };
 
@@ -142,6 +148,7 @@ impl TempoTask for DeployViralNftTask {
                 name, symbol, contract_addr
             ),
             tx_hash: Some(format!("{:?}", deploy_hash)),
+            ..Default::default()
         })
     }
 }
@@ -59,6 +59,7 @@ impl TempoTask for MintViralNftTask {
                 success: false,
                 message: "No viral NFTs found in DB to mint.".to_string(),
                 tx_hash: None,
+                ..Default::default()
             });
         }
 
@@ -119,16 +120,17 @@ impl TempoTask for MintViralNftTask {
                     match client.provider.send_transaction(claim_tx).await {
                         Ok(pending) => {
                             let tx_hash = *pending.tx_hash();
-                            let receipt = pending
-                                .get_receipt()
-                                .await
-                                .context("Failed to get receipt")?;
+                            let receipt = match ctx.receipt(tx_hash).await {
+                                Ok(receipt) => receipt,
+                                Err(timeout) => return Ok(timeout.into()),
+                            };
 
                             if receipt.inner.status() {
                                 return Ok(TaskResult {
                                     success: true,
                                     message: format!("Minted Viral NFT at {:?}", nft_addr),
                                     tx_hash: Some(format!("{:?}", tx_hash)),
+                                    ..Default::default()
                                 });
                             } else {
                                 tracing::debug!("Mint failed (reverted), trying next NFT...");
@@ -176,6 +178,7 @@ impl TempoTask for MintViralNftTask {
             success: false,
             message: "Found NFTs but already owned or mint failed.".to_string(),
             tx_hash: None,
+            ..Default::default()
         })
     }
 }
@@ -36,8 +36,7 @@ impl TempoTask for TimeBombTask {
         use alloy::primitives::{Bytes, TxKind, U256};
         use alloy::providers::Provider;
         use alloy::rlp::Encodable;
-        use alloy::signers::Signer;
-        use tempo_primitives::transaction::{Call, TempoSignature, TempoTransaction};
+        use tempo_primitives::transaction::{Call, TempoTransaction};
 
         let client = &ctx.client;
         let chain_id = ctx.chain_id();
@@ -46,10 +45,13 @@ impl TempoTask for TimeBombTask {
         let mut rng = rand::rngs::OsRng;
         let delay = rng.gen_range(20..30); // 20-30 seconds delay
 
-        // 1. Calculate Timestamps
-        let now = std::time::SystemTime::now()
-            .duration_since(std::time::UNIX_EPOCH)?
-            .as_secs();
+        // 1. Calculate Timestamps, against block time where the window is checked
+        let now = match ctx.head().await {
+            Ok(head) => head.timestamp,
+            Err(_) => std::time::SystemTime::now()
+                .duration_since(std::time::UNIX_EPOCH)?
+                .as_secs(),
+        };
         let valid_after = now + delay;
         let valid_before = valid_after + 300; // 5 minute window
 
@@ -58,6 +60,9 @@ impl TempoTask for TimeBombTask {
         // 2. Prepare Deployment Transaction
         let bytecode = hex::decode(MINIMAL_BYTECODE).context("Invalid hex")?;
 
+        // Session key when [session_keys] is enabled (may register it first)
+        let signer = ctx.tempo_signer().await;
+
         let nonce = client.get_pending_nonce(&ctx.config.rpc_url).await?;
         let gas_price = client.provider.get_gas_price().await?;
         // println!("Current Gas Price: {}", gas_price);
@@ -66,7 +71,7 @@ impl TempoTask for TimeBombTask {
             chain_id,
             max_priority_fee_per_gas: 1_500_000_000,
             max_fee_per_gas: 200_000_000_000u128, // High gas for priority
-            gas_limit: 100_000,                   // Sufficient for minimal deploy
+            gas_limit: ctx.gas_limit(self.name(), 100_000), // Sufficient for minimal deploy
             calls: vec![Call {
                 to: TxKind::Create, // Deployment
                 value: U256::ZERO,
@@ -83,9 +88,7 @@ impl TempoTask for TimeBombTask {
         };
 
         // 3. Sign
-        let hash = tx.signature_hash();
-        let signature = client.signer.sign_hash(&hash).await?;
-        let tempo_sig = TempoSignature::from(signature);
+        let tempo_sig = signer.sign(&tx).await?;
 
         // 4. Wrap & Encode
         let signed_tx = tx.into_signed(tempo_sig);
@@ -110,6 +113,7 @@ impl TempoTask for TimeBombTask {
                 delay, valid_after, tx_hash
             ),
             tx_hash: Some(format!("{:?}", tx_hash)),
+            ..Default::default()
         })
     }
 }
@@ -4,13 +4,14 @@
 //! Uses manual nonce management to stress test the mempool.
 
 use crate::TempoClient;
-use crate::tasks::{TaskContext, TaskResult, TempoTask};
+use crate::tasks::{DEPLOY_TIMEOUT, TaskContext, TaskResult, TempoTask};
 use alloy::primitives::U256;
 use alloy::rpc::types::TransactionRequest;
 use anyhow::{Context, Result};
 use async_trait::async_trait;
 use futures::future::join_all;
 use rand::Rng;
+use std::time::Duration;
 
 const MINIMAL_BYTECODE: &str = "608060405234801561001057600080fd5b5061012a806100206000396000f3fe6080604052348015600f57600080fd5b506004361060325760003560e01c8063368b8772146037578063d826f88a146068575b600080fd5b606660048036038101906062919060ba565b600055565b60005460749060d6565b60405180910390f35b600080fd5b609e8160eb565b811460a857600080fd5b50565b600081359050610bc565b600080fd5b6000601f19601f83011690549093919060d6560";
 
@@ -29,6 +30,10 @@ impl TempoTask for DeployStormTask {
         "50_deploy_storm"
     }
 
+    fn default_timeout(&self) -> Option<Duration> {
+        Some(DEPLOY_TIMEOUT)
+    }
+
     async fn run(&self, ctx: &TaskContext) -> Result<TaskResult> {
         use alloy::primitives::Address;
 
@@ -58,7 +63,7 @@ impl TempoTask for DeployStormTask {
                 .nonce(base_nonce + i as u64)
                 .max_fee_per_gas(200_000_000_000u128)
                 .max_priority_fee_per_gas(2_000_000_000u128)
-                .gas_limit(2_000_000);
+                .gas_limit(ctx.gas_limit(self.name(), 2_000_000));
             deploy_tx.to = Some(alloy::primitives::TxKind::Create);
 
             // println!("  -> Launching missile {}/{}", i+1, storm_size);
@@ -100,6 +105,7 @@ impl TempoTask for DeployStormTask {
             } else {
                 Some(last_hash)
             },
+            ..Default::default()
         })
     }
 }
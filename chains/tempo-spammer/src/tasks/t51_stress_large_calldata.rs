@@ -0,0 +1,71 @@
+//! Stress Large Calldata Task
+//!
+//! Sends one Tempo transaction whose single call carries a large blob of
+//! random calldata, probing the per-call input size limit.
+//!
+//! Workflow:
+//! 1. Pick a size from `[stress] calldata_sizes`
+//! 2. Call the wallet's own address with that many random bytes
+//! 3. Classify the answer as accepted, rejected or limit
+
+use crate::tasks::prelude::*;
+use crate::tasks::stress::{call, pick, send_probe};
+use anyhow::Result;
+use async_trait::async_trait;
+use rand::RngCore;
+
+/// Gas on top of the calldata cost
+const BASE_GAS: u64 = 150_000;
+/// Calldata gas per non-zero byte
+const GAS_PER_BYTE: u64 = 16;
+
+#[derive(Debug, Clone, Default)]
+pub struct StressLargeCalldataTask;
+
+impl StressLargeCalldataTask {
+    pub fn new() -> Self {
+        Self
+    }
+}
+
+#[async_trait]
+impl TempoTask for StressLargeCalldataTask {
+    fn name(&self) -> &'static str {
+        "51_stress_large_calldata"
+    }
+
+    async fn run(&self, ctx: &TaskContext) -> Result<TaskResult> {
+        let size = pick(&ctx.config.stress.calldata_sizes);
+        let mut input = vec![0u8; size];
+        rand::rngs::OsRng.fill_bytes(&mut input);
+
+        let gas_limit = ctx.gas_limit(self.name(), BASE_GAS + GAS_PER_BYTE * size as u64);
+        tracing::debug!("Probing {} bytes of calldata (gas {})", size, gas_limit);
+
+        let result = send_probe(
+            ctx,
+            &format!("{} bytes of calldata", size),
+            vec![call(ctx.address(), input)],
+            gas_limit,
+        )
+        .await?;
+        Ok(result.with_detail("calldata_bytes", size))
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    #[tokio::test]
+    async fn test_sends_configured_size() {
+        let mut harness = crate::testing::TaskHarness::new();
+        harness.config.stress.calldata_sizes = vec![1000];
+        let result = harness.run(&StressLargeCalldataTask::new()).await.unwrap();
+
+        assert!(result.success, "{}", result.message);
+        assert_eq!(result.details["calldata_bytes"], 1000);
+        assert_eq!(result.details["gas_limit"], 166_000);
+        assert!(harness.mock.sent()[0].raw.len() > 1000);
+    }
+}
@@ -0,0 +1,55 @@
+//! Stress Max Calls Task
+//!
+//! Packs many calls into one Tempo transaction, probing the limit on calls
+//! per transaction.
+//!
+//! Workflow:
+//! 1. Pick a count from `[stress] call_counts`
+//! 2. Batch that many PathUSD `balanceOf(self)` calls
+//! 3. Classify the answer as accepted, rejected or limit
+
+use crate::tasks::prelude::*;
+use crate::tasks::stress::{call, pick, send_probe};
+use crate::tasks::tempo_tokens::TempoTokens;
+use anyhow::Result;
+use async_trait::async_trait;
+
+const BASE_GAS: u64 = 50_000;
+const GAS_PER_CALL: u64 = 30_000;
+
+#[derive(Debug, Clone, Default)]
+pub struct StressMaxCallsTask;
+
+impl StressMaxCallsTask {
+    pub fn new() -> Self {
+        Self
+    }
+}
+
+#[async_trait]
+impl TempoTask for StressMaxCallsTask {
+    fn name(&self) -> &'static str {
+        "52_stress_max_calls"
+    }
+
+    async fn run(&self, ctx: &TaskContext) -> Result<TaskResult> {
+        let count = pick(&ctx.config.stress.call_counts);
+        let token = TempoTokens::get_path_usd_address();
+
+        // balanceOf(address): read-only, so only the call count matters
+        let mut input = vec![0x70, 0xa0, 0x82, 0x31];
+        input.extend_from_slice(&[0u8; 12]);
+        input.extend_from_slice(ctx.address().as_slice());
+        let calls = (0..count).map(|_| call(token, input.clone())).collect();
+
+        let gas_limit = ctx.gas_limit(self.name(), BASE_GAS + GAS_PER_CALL * count as u64);
+        tracing::debug!(
+            "Probing {} calls in one transaction (gas {})",
+            count,
+            gas_limit
+        );
+
+        let result = send_probe(ctx, &format!("{} calls", count), calls, gas_limit).await?;
+        Ok(result.with_detail("calls", count))
+    }
+}
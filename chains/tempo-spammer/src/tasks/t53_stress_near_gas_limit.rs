@@ -0,0 +1,107 @@
+//! Stress Near Gas Limit Task
+//!
+//! Sends one Tempo transaction with a gas limit close to the block gas limit
+//! whose only call burns nearly all of it, probing how the pool and block
+//! builder treat transactions that fill a block.
+//!
+//! Workflow:
+//! 1. Read the gas limit of the latest block
+//! 2. Scale it by `[stress] gas_fraction`; above 1.0 the limit is exceeded
+//! 3. Send a contract creation whose init code loops until little gas is left
+//! 4. Classify the answer as accepted, rejected or limit
+
+use crate::asm::{Asm, op};
+use crate::tasks::prelude::*;
+use crate::tasks::stress::send_probe;
+use alloy_primitives::{Bytes, TxKind, U256};
+use anyhow::{Result, bail};
+use async_trait::async_trait;
+use tempo_primitives::transaction::Call;
+
+/// Gas the burner leaves for its final instructions and the empty deployment
+const BURNER_RESERVE: u64 = 5_000;
+
+#[derive(Debug, Clone, Default)]
+pub struct StressNearGasLimitTask;
+
+impl StressNearGasLimitTask {
+    pub fn new() -> Self {
+        Self
+    }
+}
+
+/// Init code that spins until at most [`BURNER_RESERVE`] gas is left, then deploys nothing
+fn burner() -> Bytes {
+    let mut asm = Asm::default();
+    asm.label("burn")
+        .push_u64(BURNER_RESERVE)
+        .op(op::GAS)
+        .op(op::GT)
+        .push_label("burn")
+        .op(op::JUMPI)
+        .op(op::STOP);
+    asm.assemble()
+}
+
+#[async_trait]
+impl TempoTask for StressNearGasLimitTask {
+    fn name(&self) -> &'static str {
+        "53_stress_near_gas_limit"
+    }
+
+    async fn run(&self, ctx: &TaskContext) -> Result<TaskResult> {
+        let block_gas_limit = ctx.head().await?.gas_limit;
+        if block_gas_limit == 0 {
+            bail!("Latest block reports no gas limit");
+        }
+
+        let fraction = ctx.config.stress.gas_fraction;
+        let gas_limit = ctx.gas_limit(self.name(), (block_gas_limit as f64 * fraction) as u64);
+        tracing::debug!(
+            "Probing gas limit {} ({:.0}% of block gas limit {})",
+            gas_limit,
+            fraction * 100.0,
+            block_gas_limit
+        );
+
+        // Creation must be the first call of a Tempo transaction, here it is the only one
+        let calls = vec![Call {
+            to: TxKind::Create,
+            value: U256::ZERO,
+            input: burner(),
+        }];
+        let result = send_probe(
+            ctx,
+            &format!("{:.0}% of block gas", fraction * 100.0),
+            calls,
+            gas_limit,
+        )
+        .await?;
+        Ok(result.with_detail("block_gas_limit", block_gas_limit))
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use crate::asm::bad_jumps;
+    use crate::testing::TaskHarness;
+
+    #[test]
+    fn test_burner_loops_back_to_start() {
+        let code = burner();
+        assert!(bad_jumps(&code).is_empty());
+        assert_eq!(code[0], op::JUMPDEST);
+    }
+
+    #[tokio::test]
+    async fn test_gas_limit_scaled_from_block() {
+        let mut harness = TaskHarness::new();
+        harness.config.stress.gas_fraction = 1.5;
+        let result = harness.run(&StressNearGasLimitTask::new()).await.unwrap();
+
+        assert!(result.success, "{}", result.message);
+        assert_eq!(result.details["block_gas_limit"], 30_000_000);
+        assert_eq!(result.details["gas_limit"], 45_000_000);
+    }
+}
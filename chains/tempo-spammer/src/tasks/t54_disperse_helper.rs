@@ -0,0 +1,180 @@
+//! Disperse Helper Task
+//!
+//! Spreads a system token over several recipients through the disperse
+//! helper contract, approving and dispersing in one Tempo (0x76) transaction.
+//!
+//! Workflow:
+//! 1. Look up the disperse helper (fails fast when it is unavailable)
+//! 2. Pick a random system token and 3% of its balance
+//! 3. Send `approve` and `disperseToken` as two calls of one transaction
+
+use crate::tasks::prelude::*;
+use crate::tasks::tempo_tokens::TempoTokens;
+use alloy_primitives::{Address, Bytes, TxKind, U256};
+use alloy_sol_types::{SolCall, sol};
+use anyhow::{Context, Result};
+use async_trait::async_trait;
+use rand::Rng;
+use tempo_primitives::transaction::Call;
+
+sol! {
+    interface IDisperseToken {
+        function approve(address spender, uint256 amount) returns (bool);
+    }
+}
+
+#[derive(Debug, Clone, Default)]
+pub struct DisperseHelperTask;
+
+impl DisperseHelperTask {
+    pub fn new() -> Self {
+        Self
+    }
+}
+
+#[async_trait]
+impl TempoTask for DisperseHelperTask {
+    fn name(&self) -> &'static str {
+        "54_disperse_helper"
+    }
+
+    fn required_helpers(&self) -> &'static [&'static str] {
+        &["disperse"]
+    }
+
+    async fn run(&self, ctx: &TaskContext) -> Result<TaskResult> {
+        use crate::contracts::IDisperse;
+
+        let disperse = ctx.helper("disperse")?;
+        let client = &ctx.client;
+        let address = ctx.address();
+
+        let token = TempoTokens::get_random_system_token();
+        let balance = TempoTokens::get_token_balance(client, token.address, address)
+            .await
+            .unwrap_or(U256::ZERO);
+
+        let count = rand::rngs::OsRng.gen_range(3..=8);
+        let per_recipient = balance * U256::from(3) / U256::from(100) / U256::from(count);
+        if per_recipient.is_zero() {
+            return Ok(TaskResult {
+                success: false,
+                message: format!("Low {} balance: {}", token.symbol, balance),
+                ..Default::default()
+            });
+        }
+        let total = per_recipient * U256::from(count);
+
+        let reservation = match ctx.reserve_spend(token.address, total) {
+            Ok(reservation) => reservation,
+            Err(exceeded) => return Ok(exceeded.into()),
+        };
+
+        let recipients = (0..count)
+            .map(|_| get_random_address())
+            .collect::<Result<Vec<_>>>()?;
+        let approve = IDisperseToken::approveCall {
+            spender: disperse,
+            amount: total,
+        };
+        let disperse_call = IDisperse::disperseTokenCall {
+            token: token.address,
+            recipients: recipients.clone(),
+            values: vec![per_recipient; count],
+        };
+
+        let (tx_hash, access_list) = ctx
+            .send_calls(
+                vec![
+                    Call {
+                        to: TxKind::Call(token.address),
+                        value: U256::ZERO,
+                        input: Bytes::from(approve.abi_encode()),
+                    },
+                    Call {
+                        to: TxKind::Call(disperse),
+                        value: U256::ZERO,
+                        input: Bytes::from(disperse_call.abi_encode()),
+                    },
+                ],
+                ctx.gas_limit(self.name(), 150_000 + 60_000 * count as u64),
+            )
+            .await
+            .context("Failed to send disperse transaction")?;
+        reservation.commit().await;
+
+        let result = TaskResult {
+            success: true,
+            message: format!(
+                "Dispersed {} {} to {} recipients via {}",
+                total, token.symbol, count, disperse
+            ),
+            tx_hash: Some(format!("{:?}", tx_hash)),
+            ..Default::default()
+        }
+        .with_token_amount(token.address, total)
+        .with_detail("recipients", count);
+        Ok(match &access_list {
+            Some(estimate) => estimate.record(result),
+            None => result,
+        })
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use crate::config::HelperSettings;
+    use crate::contracts::{HelperRegistry, disperse};
+    use crate::testing::TaskHarness;
+    use std::collections::BTreeMap;
+    use std::sync::Arc;
+
+    async fn registry(harness: &TaskHarness, pinned: Option<Address>) -> Arc<HelperRegistry> {
+        let client = harness.mock.client();
+        let mut settings = HelperSettings::default();
+        if let Some(address) = pinned {
+            settings.pinned.insert(
+                client.chain_id().to_string(),
+                BTreeMap::from([("disperse".to_string(), address)]),
+            );
+        }
+        Arc::new(
+            HelperRegistry::resolve(client.provider(), client.chain_id(), &settings, None)
+                .await
+                .unwrap(),
+        )
+    }
+
+    #[tokio::test]
+    async fn test_approves_and_disperses_in_one_transaction() {
+        let harness = TaskHarness::new();
+        let helper = Address::repeat_byte(0xd1);
+        harness.mock.set_code(helper, disperse());
+        for (_, token) in TempoTokens::system_tokens() {
+            harness
+                .mock
+                .on_call(token, "70a08231", U256::from(10_000_000).to_be_bytes_vec());
+        }
+        let ctx = harness
+            .context()
+            .with_helpers(Some(registry(&harness, Some(helper)).await));
+
+        let result = DisperseHelperTask::new().run(&ctx).await.unwrap();
+        assert!(result.success, "{}", result.message);
+        assert_eq!(harness.mock.sent().len(), 1);
+        assert!(result.message.contains(&helper.to_string()));
+    }
+
+    #[tokio::test]
+    async fn test_missing_helper_fails_before_sending() {
+        let harness = TaskHarness::new();
+        let ctx = harness.context().with_helpers(Some(
+            registry(&harness, Some(Address::repeat_byte(0xd2))).await,
+        ));
+
+        let err = DisperseHelperTask::new().run(&ctx).await.unwrap_err();
+        assert!(err.to_string().contains("no code at"), "{}", err);
+        assert!(harness.mock.methods().iter().all(|m| m == "eth_getCode"));
+    }
+}
@@ -0,0 +1,282 @@
+//! Native Batch Calls Task
+//!
+//! Packs token transfers and approvals into the calls of one Tempo (0x76)
+//! transaction, which execute atomically, where tasks 34-36 send one
+//! transaction per transfer.
+//!
+//! Workflow:
+//! 1. Pick a random system token and 1% of its balance per transfer
+//! 2. Build 2-6 transfers to fresh addresses and 1-2 approvals to random spenders
+//! 3. In one run out of four, append a transfer of more than the balance
+//! 4. Send all calls with [`TaskContext::send_calls`] and wait for the receipt
+//!
+//! The variant with the failing call passes only if the transaction reverted
+//! and the first recipient's balance did not change, i.e. the transfers ahead
+//! of the failing call were rolled back with it.
+
+use crate::tasks::prelude::*;
+use crate::tasks::tempo_tokens::TempoTokens;
+use alloy_primitives::{Address, Bytes, TxKind, U256};
+use alloy_sol_types::{SolCall, sol};
+use anyhow::Result;
+use async_trait::async_trait;
+use rand::Rng;
+use std::time::Duration;
+use tempo_primitives::transaction::Call;
+
+sol! {
+    interface IBatchCallToken {
+        function transfer(address to, uint256 amount) returns (bool);
+        function approve(address spender, uint256 amount) returns (bool);
+    }
+}
+
+/// Share of runs that append a failing call
+const FAILING_VARIANT_CHANCE: f64 = 0.25;
+
+const RECEIPT_TIMEOUT: Duration = Duration::from_secs(60);
+const RECEIPT_POLL: Duration = Duration::from_secs(1);
+
+#[derive(Debug, Clone, Default)]
+pub struct NativeBatchCallsTask;
+
+impl NativeBatchCallsTask {
+    pub fn new() -> Self {
+        Self
+    }
+
+    async fn run_variant(&self, ctx: &TaskContext, failing: bool) -> Result<TaskResult> {
+        let client = &ctx.client;
+        let token = TempoTokens::get_random_system_token();
+        let balance = TempoTokens::get_token_balance(client, token.address, ctx.address())
+            .await
+            .unwrap_or(U256::ZERO);
+
+        let per_transfer = balance / U256::from(100);
+        if per_transfer.is_zero() {
+            return Ok(TaskResult {
+                success: false,
+                message: format!("Low {} balance: {}", token.symbol, balance),
+                ..Default::default()
+            });
+        }
+
+        let mut rng = rand::rngs::OsRng;
+        let transfers = rng.gen_range(2..=6);
+        let approvals = rng.gen_range(1..=2);
+        let total = per_transfer * U256::from(transfers);
+
+        let reservation = match ctx.reserve_spend(token.address, total) {
+            Ok(reservation) => reservation,
+            Err(exceeded) => return Ok(exceeded.into()),
+        };
+
+        let recipients = (0..transfers)
+            .map(|_| get_random_address())
+            .collect::<Result<Vec<_>>>()?;
+        let token_call = |input: Vec<u8>| Call {
+            to: TxKind::Call(token.address),
+            value: U256::ZERO,
+            input: Bytes::from(input),
+        };
+        let mut calls: Vec<Call> = recipients
+            .iter()
+            .map(|to| {
+                token_call(
+                    IBatchCallToken::transferCall {
+                        to: *to,
+                        amount: per_transfer,
+                    }
+                    .abi_encode(),
+                )
+            })
+            .collect();
+        for _ in 0..approvals {
+            calls.push(token_call(
+                IBatchCallToken::approveCall {
+                    spender: get_random_address()?,
+                    amount: per_transfer,
+                }
+                .abi_encode(),
+            ));
+        }
+
+        let first: Address = recipients[0];
+        let first_before = if failing {
+            // More than the whole balance, so the token reverts this call
+            calls.push(token_call(
+                IBatchCallToken::transferCall {
+                    to: first,
+                    amount: balance + U256::from(1),
+                }
+                .abi_encode(),
+            ));
+            Some(TempoTokens::get_token_balance(client, token.address, first).await?)
+        } else {
+            None
+        };
+
+        let call_count = calls.len();
+        let (tx_hash, access_list) = ctx
+            .send_calls(
+                calls,
+                ctx.gas_limit(self.name(), 50_000 + 60_000 * call_count as u64),
+            )
+            .await?;
+        if !failing {
+            reservation.commit().await;
+        }
+
+        // Cut short near the task's deadline
+        let wait = ctx.time_for(RECEIPT_TIMEOUT);
+        let receipt = ctx.wait_for_receipt(tx_hash, wait, RECEIPT_POLL).await;
+        let (success, message) = match (&receipt, first_before) {
+            (None, _) => (
+                false,
+                format!(
+                    "{} calls sent, no receipt within {}s",
+                    call_count,
+                    wait.as_secs()
+                ),
+            ),
+            (Some(receipt), None) if receipt.status() => (
+                true,
+                format!(
+                    "Sent {} {} to {} recipients with {} approvals in one transaction",
+                    total, token.symbol, transfers, approvals
+                ),
+            ),
+            (Some(_), None) => (false, format!("Batch of {} calls reverted", call_count)),
+            (Some(receipt), Some(_)) if receipt.status() => (
+                false,
+                "Batch with a failing call was included without reverting".to_string(),
+            ),
+            (Some(_), Some(before)) => {
+                let after = TempoTokens::get_token_balance(client, token.address, first).await?;
+                if after == before {
+                    (
+                        true,
+                        format!("Failing call reverted all {} calls", call_count),
+                    )
+                } else {
+                    (
+                        false,
+                        format!(
+                            "Batch reverted but {} {} balance changed from {} to {}",
+                            first, token.symbol, before, after
+                        ),
+                    )
+                }
+            }
+        };
+
+        let mut result = TaskResult {
+            success,
+            message,
+            tx_hash: Some(format!("{:?}", tx_hash)),
+            ..Default::default()
+        };
+        if let Some(receipt) = &receipt {
+            result = result.with_receipt(receipt.gas_used, receipt.block_number);
+        }
+        if !failing {
+            result = result.with_token_amount(token.address, total);
+        }
+        if let Some(estimate) = &access_list {
+            result = estimate.record(result);
+        }
+        Ok(result
+            .with_detail("variant", if failing { "failing_call" } else { "atomic" })
+            .with_detail("calls", call_count))
+    }
+}
+
+#[async_trait]
+impl TempoTask for NativeBatchCallsTask {
+    fn name(&self) -> &'static str {
+        "55_native_batch_calls"
+    }
+
+    async fn run(&self, ctx: &TaskContext) -> Result<TaskResult> {
+        let failing = rand::rngs::OsRng.gen_bool(FAILING_VARIANT_CHANCE);
+        self.run_variant(ctx, failing).await
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use crate::audit::AuditedTx;
+    use crate::testing::{ACCESS_LIST_SAVING, TaskHarness};
+
+    fn funded() -> TaskHarness {
+        let harness = TaskHarness::new();
+        for (_, token) in TempoTokens::system_tokens() {
+            harness
+                .mock
+                .on_call(token, "70a08231", U256::from(10_000_000).to_be_bytes_vec());
+        }
+        harness
+    }
+
+    #[tokio::test]
+    async fn test_batches_calls_in_one_transaction() {
+        let harness = funded();
+        let result = NativeBatchCallsTask::new()
+            .run_variant(&harness.context(), false)
+            .await
+            .unwrap();
+        assert!(result.success, "{}", result.message);
+        assert_eq!(result.details["variant"], "atomic");
+
+        let sent = harness.mock.sent();
+        assert_eq!(sent.len(), 1);
+        let tx = AuditedTx::decode(&sent[0].raw, 0).unwrap();
+        assert_eq!(
+            tx.calls.len() as u64,
+            result.details["calls"].as_u64().unwrap()
+        );
+        assert!(tx.calls.len() >= 3);
+    }
+
+    #[tokio::test]
+    async fn test_access_list_merged_over_calls() {
+        let mut harness = funded();
+        harness.config.gas.access_lists = true;
+        let result = NativeBatchCallsTask::new()
+            .run_variant(&harness.context(), false)
+            .await
+            .unwrap();
+        assert!(result.success, "{}", result.message);
+
+        // Every call goes to the same token, so the merged list has one entry
+        let calls = result.details["calls"].as_i64().unwrap();
+        assert_eq!(result.details["access_list_entries"], 1);
+        assert_eq!(
+            result.details["access_list_gas_delta"],
+            calls * ACCESS_LIST_SAVING as i64
+        );
+        assert_eq!(harness.mock.count("eth_createAccessList") as i64, calls);
+    }
+
+    #[tokio::test]
+    async fn test_failing_call_must_revert_the_batch() {
+        let harness = funded();
+        harness.mock.revert_next_receipt();
+        let reverted = NativeBatchCallsTask::new()
+            .run_variant(&harness.context(), true)
+            .await
+            .unwrap();
+        assert!(reverted.success, "{}", reverted.message);
+        assert_eq!(reverted.details["variant"], "failing_call");
+        assert!(reverted.token_amounts.is_empty());
+
+        // Included without reverting means the calls were not atomic
+        let included = NativeBatchCallsTask::new()
+            .run_variant(&harness.context(), true)
+            .await
+            .unwrap();
+        assert!(!included.success);
+        assert_eq!(harness.mock.sent().len(), 2);
+    }
+}
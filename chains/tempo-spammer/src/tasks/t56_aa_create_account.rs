@@ -0,0 +1,237 @@
+//! AA Create Account Task
+//!
+//! Creates an ERC-4337 SimpleAccount owned by the wallet and funds it, so
+//! the user operation tasks (57, 58) have an account holding tokens.
+//!
+//! Workflow:
+//! 1. Check the EntryPoint and factory from `[aa]` have code
+//! 2. Below `[aa] max_accounts` recorded accounts, predict the next account
+//!    (salt = accounts so far); at the limit, top up a random existing one
+//! 3. In one Tempo (0x76) transaction: `createAccount`, a transfer of 2% of
+//!    a random system token and, with `[aa] deposit_wei` set, an EntryPoint
+//!    `depositTo` for the account's gas
+//! 4. Record a created account in `smart_accounts` once the receipt succeeded
+
+use crate::tasks::prelude::*;
+use crate::tasks::smart_account::{self, IAccountToken, IEntryPoint, ISimpleAccountFactory};
+use crate::tasks::tempo_tokens::TempoTokens;
+use alloy_primitives::{Bytes, TxKind, U256};
+use alloy_sol_types::SolCall;
+use anyhow::Result;
+use async_trait::async_trait;
+use core_logic::database::SmartAccountRecord;
+use rand::seq::SliceRandom;
+use std::time::Duration;
+use tempo_primitives::transaction::Call;
+
+const RECEIPT_TIMEOUT: Duration = Duration::from_secs(60);
+const RECEIPT_POLL: Duration = Duration::from_secs(1);
+
+#[derive(Debug, Clone, Default)]
+pub struct AaCreateAccountTask;
+
+impl AaCreateAccountTask {
+    pub fn new() -> Self {
+        Self
+    }
+}
+
+#[async_trait]
+impl TempoTask for AaCreateAccountTask {
+    fn name(&self) -> &'static str {
+        "56_aa_create_account"
+    }
+
+    async fn run(&self, ctx: &TaskContext) -> Result<TaskResult> {
+        let client = &ctx.client;
+        let aa = &ctx.config.aa;
+        smart_account::ensure_contracts(ctx).await?;
+
+        // Without a database nothing is recorded, so always aim at salt 0;
+        // the factory returns the existing account if it is deployed already
+        let recorded = if ctx.db.is_some() {
+            smart_account::known_accounts(ctx).await?
+        } else {
+            Vec::new()
+        };
+        let create = recorded.len() < aa.max_accounts;
+        let salt = U256::from(recorded.len());
+        let account = if create {
+            smart_account::account_address(ctx, salt).await?
+        } else {
+            *recorded
+                .choose(&mut rand::thread_rng())
+                .expect("max_accounts is at least 1")
+        };
+
+        let token = TempoTokens::get_random_system_token();
+        let balance = TempoTokens::get_token_balance(client, token.address, ctx.address())
+            .await
+            .unwrap_or(U256::ZERO);
+        let amount = ctx.scale_amount(balance / U256::from(50)).min(balance);
+        if amount.is_zero() {
+            return Ok(TaskResult {
+                success: false,
+                message: format!("Low {} balance: {}", token.symbol, balance),
+                ..Default::default()
+            });
+        }
+        let reservation = match ctx.reserve_spend(token.address, amount) {
+            Ok(reservation) => reservation,
+            Err(exceeded) => return Ok(exceeded.into()),
+        };
+
+        let mut calls = Vec::new();
+        let mut gas = 50_000;
+        if create {
+            calls.push(Call {
+                to: TxKind::Call(aa.account_factory),
+                value: U256::ZERO,
+                input: Bytes::from(
+                    ISimpleAccountFactory::createAccountCall {
+                        owner: ctx.address(),
+                        salt,
+                    }
+                    .abi_encode(),
+                ),
+            });
+            gas += 350_000;
+        }
+        calls.push(Call {
+            to: TxKind::Call(token.address),
+            value: U256::ZERO,
+            input: Bytes::from(
+                IAccountToken::transferCall {
+                    to: account,
+                    amount,
+                }
+                .abi_encode(),
+            ),
+        });
+        gas += 60_000;
+        if aa.deposit_wei > 0 {
+            calls.push(Call {
+                to: TxKind::Call(aa.entry_point),
+                value: U256::from(aa.deposit_wei),
+                input: Bytes::from(IEntryPoint::depositToCall { account }.abi_encode()),
+            });
+            gas += 60_000;
+        }
+
+        let (tx_hash, access_list) = ctx
+            .send_calls(calls, ctx.gas_limit(self.name(), gas))
+            .await?;
+        reservation.commit().await;
+
+        // Cut short near the task's deadline
+        let wait = ctx.time_for(RECEIPT_TIMEOUT);
+        let receipt = ctx.wait_for_receipt(tx_hash, wait, RECEIPT_POLL).await;
+        let success = receipt.as_ref().is_some_and(|r| r.status());
+        if success && create {
+            if let Some(db) = &ctx.db {
+                db.log_smart_account(&SmartAccountRecord {
+                    chain_id: ctx.config.chain_id as i64,
+                    owner: ctx.address().to_string(),
+                    account: account.to_string(),
+                    factory: aa.account_factory.to_string(),
+                    salt: salt.to_string(),
+                    tx_hash: format!("{:?}", tx_hash),
+                    created_at: chrono::Utc::now().timestamp(),
+                })
+                .await?;
+            }
+        }
+
+        let message = match (&receipt, create) {
+            (None, _) => format!(
+                "No receipt for account {} within {}s",
+                account,
+                wait.as_secs()
+            ),
+            (Some(_), _) if !success => format!("Funding account {} reverted", account),
+            (Some(_), true) => format!(
+                "Created smart account {} (salt {}) with {} {}",
+                account, salt, amount, token.symbol
+            ),
+            (Some(_), false) => format!(
+                "Topped up smart account {} with {} {}",
+                account, amount, token.symbol
+            ),
+        };
+        let mut result = TaskResult {
+            success,
+            message,
+            tx_hash: Some(format!("{:?}", tx_hash)),
+            ..Default::default()
+        }
+        .with_token_amount(token.address, amount);
+        if let Some(receipt) = &receipt {
+            result = result.with_receipt(receipt.gas_used, receipt.block_number);
+        }
+        if create {
+            result = result.with_contract(account);
+        }
+        if let Some(estimate) = &access_list {
+            result = estimate.record(result);
+        }
+        Ok(result
+            .with_detail("account", account.to_string())
+            .with_detail("created", create)
+            .with_detail("salt", salt.to_string()))
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use crate::audit::AuditedTx;
+    use crate::tasks::smart_account::test_harness;
+    use crate::testing::TEST_CHAIN_ID;
+
+    #[tokio::test]
+    async fn test_creates_funds_and_records_account() {
+        let (harness, account) = test_harness();
+        let mut harness = harness.with_db().await.unwrap();
+        harness.config.aa.max_accounts = 1;
+        harness.config.aa.deposit_wei = 1_000;
+
+        let result = harness.run(&AaCreateAccountTask::new()).await.unwrap();
+        assert!(result.success, "{}", result.message);
+        assert_eq!(result.details["created"], true);
+        assert_eq!(result.contract_address, Some(account.to_string()));
+
+        let tx = AuditedTx::decode(&harness.mock.sent()[0].raw, 0).unwrap();
+        assert_eq!(tx.calls.len(), 3);
+        assert_eq!(tx.calls[0].to, Some(harness.config.aa.account_factory));
+        assert_eq!(tx.calls[2].to, Some(harness.config.aa.entry_point));
+
+        let db = harness.db.as_ref().unwrap();
+        let owner = harness.mock.address().to_string();
+        let records = db.get_smart_accounts(TEST_CHAIN_ID, &owner).await.unwrap();
+        assert_eq!(records.len(), 1);
+        assert_eq!(records[0].account, account.to_string());
+        assert_eq!(records[0].salt, "0");
+
+        // At max_accounts the account is only topped up
+        let result = harness.run(&AaCreateAccountTask::new()).await.unwrap();
+        assert!(result.success, "{}", result.message);
+        assert_eq!(result.details["created"], false);
+        let tx = AuditedTx::decode(&harness.mock.sent()[1].raw, 1).unwrap();
+        assert_eq!(tx.calls.len(), 2);
+        assert_eq!(
+            db.get_smart_accounts(TEST_CHAIN_ID, &owner)
+                .await
+                .unwrap()
+                .len(),
+            1
+        );
+    }
+
+    #[tokio::test]
+    async fn test_requires_deployed_factory() {
+        let harness = crate::testing::TaskHarness::new();
+        let err = harness.run(&AaCreateAccountTask::new()).await.unwrap_err();
+        assert!(err.to_string().starts_with("EntryPoint not deployed"));
+        assert!(harness.mock.sent().is_empty());
+    }
+}
@@ -0,0 +1,142 @@
+//! AA User Operation Task
+//!
+//! Sends a token transfer from one of the wallet's smart accounts as an
+//! ERC-4337 user operation through the `[aa]` bundler.
+//!
+//! Workflow:
+//! 1. Pick a deployed account recorded by `56_aa_create_account`
+//! 2. Pick a random system token the account holds and 1% of its balance
+//! 3. Wrap `transfer` in the account's `execute`, sign the operation with the
+//!    wallet key and submit it to the bundler
+//! 4. Wait for the operation's receipt; it fails if the transfer reverted
+
+use crate::tasks::prelude::*;
+use crate::tasks::smart_account::{self, IAccountToken, ISimpleAccount};
+use crate::tasks::tempo_tokens::TempoTokens;
+use alloy_primitives::U256;
+use alloy_sol_types::SolCall;
+use anyhow::Result;
+use async_trait::async_trait;
+
+#[derive(Debug, Clone, Default)]
+pub struct AaUserOperationTask;
+
+impl AaUserOperationTask {
+    pub fn new() -> Self {
+        Self
+    }
+}
+
+#[async_trait]
+impl TempoTask for AaUserOperationTask {
+    fn name(&self) -> &'static str {
+        "57_aa_user_operation"
+    }
+
+    async fn run(&self, ctx: &TaskContext) -> Result<TaskResult> {
+        let bundler = smart_account::bundler(ctx)?;
+        smart_account::ensure_contracts(ctx).await?;
+        let account = smart_account::pick_account(ctx).await?;
+
+        let token = TempoTokens::get_random_system_token();
+        let balance = TempoTokens::get_token_balance(&ctx.client, token.address, account).await?;
+        let amount = balance / U256::from(100);
+        if amount.is_zero() {
+            return Ok(TaskResult {
+                success: false,
+                message: format!(
+                    "Smart account {} has too little {}: {}",
+                    account, token.symbol, balance
+                ),
+                ..Default::default()
+            });
+        }
+
+        let spend = match ctx.reserve_spend(token.address, amount) {
+            Ok(spend) => spend,
+            Err(exceeded) => return Ok(exceeded.into()),
+        };
+
+        let recipient = get_random_address()?;
+        let call_data = ISimpleAccount::executeCall {
+            dest: token.address,
+            value: U256::ZERO,
+            func: IAccountToken::transferCall {
+                to: recipient,
+                amount,
+            }
+            .abi_encode()
+            .into(),
+        }
+        .abi_encode();
+
+        let sent = smart_account::send_user_operation(ctx, &bundler, account, call_data).await?;
+        let result = sent.into_result(
+            account,
+            format!(
+                "Smart account {} sent {} {} to {}",
+                account, amount, token.symbol, recipient
+            ),
+            ctx.config.aa.receipt_timeout_secs,
+        );
+        if result.success {
+            spend.commit().await;
+        }
+        Ok(result.with_token_amount(token.address, amount))
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use crate::tasks::smart_account::test_harness;
+    use crate::testing::{MockBundler, TEST_CHAIN_ID};
+
+    #[tokio::test]
+    async fn test_submits_signed_operation() {
+        let (mut harness, account) = test_harness();
+        let bundler = MockBundler::start().await.unwrap();
+        harness.config.aa.bundler_url = bundler.url().to_string();
+
+        let result = harness.run(&AaUserOperationTask::new()).await.unwrap();
+        assert!(result.success, "{}", result.message);
+        assert_eq!(result.details["account"], account.to_string());
+        assert_eq!(result.gas_used, Some(180_000));
+        // The wallet itself sends nothing
+        assert!(harness.mock.sent().is_empty());
+
+        let ops = bundler.operations();
+        assert_eq!(ops.len(), 1);
+        assert_eq!(ops[0].sender, account);
+        assert_eq!(ops[0].nonce, U256::from(7));
+        assert_eq!(ops[0].call_gas_limit, U256::from(100_000));
+        ISimpleAccount::executeCall::abi_decode(&ops[0].call_data).unwrap();
+
+        let signature = alloy_primitives::Signature::try_from(ops[0].signature.as_ref()).unwrap();
+        let hash = ops[0].hash(harness.config.aa.entry_point, TEST_CHAIN_ID);
+        assert_eq!(
+            signature.recover_address_from_msg(hash.as_slice()).unwrap(),
+            harness.mock.address()
+        );
+    }
+
+    #[tokio::test]
+    async fn test_reverted_operation_fails() {
+        let (mut harness, _) = test_harness();
+        let bundler = MockBundler::start().await.unwrap();
+        harness.config.aa.bundler_url = bundler.url().to_string();
+        bundler.revert_next_operation();
+
+        let result = harness.run(&AaUserOperationTask::new()).await.unwrap();
+        assert!(!result.success);
+        assert!(result.message.starts_with("User operation reverted"));
+        assert!(result.tx_hash.is_some());
+    }
+
+    #[tokio::test]
+    async fn test_requires_bundler() {
+        let (harness, _) = test_harness();
+        let err = harness.run(&AaUserOperationTask::new()).await.unwrap_err();
+        assert!(err.to_string().starts_with("No bundler configured"));
+    }
+}
@@ -0,0 +1,122 @@
+//! AA Batch User Operation Task
+//!
+//! Like task 57, but one user operation runs several transfers through the
+//! account's `executeBatch`, which reverts them all if one fails.
+//!
+//! Workflow:
+//! 1. Pick a deployed account recorded by `56_aa_create_account`
+//! 2. Pick a random system token the account holds and 1% of its balance
+//!    per transfer
+//! 3. Build 2-5 transfers to fresh addresses into one `executeBatch`
+//! 4. Sign, submit through the bundler and wait for the receipt
+
+use crate::tasks::prelude::*;
+use crate::tasks::smart_account::{self, IAccountToken, ISimpleAccount};
+use crate::tasks::tempo_tokens::TempoTokens;
+use alloy_primitives::{Bytes, U256};
+use alloy_sol_types::SolCall;
+use anyhow::Result;
+use async_trait::async_trait;
+use rand::Rng;
+
+#[derive(Debug, Clone, Default)]
+pub struct AaBatchUserOperationTask;
+
+impl AaBatchUserOperationTask {
+    pub fn new() -> Self {
+        Self
+    }
+}
+
+#[async_trait]
+impl TempoTask for AaBatchUserOperationTask {
+    fn name(&self) -> &'static str {
+        "58_aa_batch_user_operation"
+    }
+
+    async fn run(&self, ctx: &TaskContext) -> Result<TaskResult> {
+        let bundler = smart_account::bundler(ctx)?;
+        smart_account::ensure_contracts(ctx).await?;
+        let account = smart_account::pick_account(ctx).await?;
+
+        let token = TempoTokens::get_random_system_token();
+        let balance = TempoTokens::get_token_balance(&ctx.client, token.address, account).await?;
+        let per_transfer = balance / U256::from(100);
+        if per_transfer.is_zero() {
+            return Ok(TaskResult {
+                success: false,
+                message: format!(
+                    "Smart account {} has too little {}: {}",
+                    account, token.symbol, balance
+                ),
+                ..Default::default()
+            });
+        }
+
+        let transfers = rand::rngs::OsRng.gen_range(2..=5);
+        let func = (0..transfers)
+            .map(|_| {
+                Ok(Bytes::from(
+                    IAccountToken::transferCall {
+                        to: get_random_address()?,
+                        amount: per_transfer,
+                    }
+                    .abi_encode(),
+                ))
+            })
+            .collect::<Result<Vec<_>>>()?;
+        let call_data = ISimpleAccount::executeBatchCall {
+            dest: vec![token.address; transfers],
+            value: vec![U256::ZERO; transfers],
+            func,
+        }
+        .abi_encode();
+
+        let total = per_transfer * U256::from(transfers);
+        let spend = match ctx.reserve_spend(token.address, total) {
+            Ok(spend) => spend,
+            Err(exceeded) => return Ok(exceeded.into()),
+        };
+        let sent = smart_account::send_user_operation(ctx, &bundler, account, call_data).await?;
+        let result = sent.into_result(
+            account,
+            format!(
+                "Smart account {} sent {} {} in {} batched transfers",
+                account, total, token.symbol, transfers
+            ),
+            ctx.config.aa.receipt_timeout_secs,
+        );
+        if result.success {
+            spend.commit().await;
+        }
+        Ok(result
+            .with_token_amount(token.address, total)
+            .with_detail("calls", transfers))
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use crate::tasks::smart_account::test_harness;
+    use crate::testing::MockBundler;
+
+    #[tokio::test]
+    async fn test_batches_transfers_in_one_operation() {
+        let (mut harness, account) = test_harness();
+        let bundler = MockBundler::start().await.unwrap();
+        harness.config.aa.bundler_url = bundler.url().to_string();
+
+        let result = harness.run(&AaBatchUserOperationTask::new()).await.unwrap();
+        assert!(result.success, "{}", result.message);
+
+        let ops = bundler.operations();
+        assert_eq!(ops.len(), 1);
+        assert_eq!(ops[0].sender, account);
+        let batch = ISimpleAccount::executeBatchCall::abi_decode(&ops[0].call_data).unwrap();
+        let calls = result.details["calls"].as_u64().unwrap() as usize;
+        assert!((2..=5).contains(&calls));
+        assert_eq!(batch.dest.len(), calls);
+        assert_eq!(batch.func.len(), calls);
+    }
+}
@@ -0,0 +1,297 @@
+//! NFT List Task
+//!
+//! Lists one of the wallet's ViralNFTs on the marketplace helper for a
+//! random price in a system token.
+//!
+//! Workflow:
+//! 1. Stop when the wallet has `[marketplace] max_listings` active listings
+//! 2. Find a token of a recorded `viral_nft` collection the wallet owns and
+//!    has not listed yet
+//! 3. In one Tempo (0x76) transaction: `setApprovalForAll` for the
+//!    marketplace (unless approved already) and `list`
+//! 4. Record the listing from the `Listed` event in `nft_listings`
+
+use crate::tasks::nft_market::{self, COLLECTION_TYPE, IMarketNft};
+use crate::tasks::prelude::*;
+use crate::tasks::tempo_tokens::TempoTokens;
+use alloy_primitives::{Address, Bytes, TxKind, U256};
+use alloy_sol_types::SolCall;
+use anyhow::Result;
+use async_trait::async_trait;
+use core_logic::database::NftListingRecord;
+use rand::Rng;
+use rand::seq::SliceRandom;
+use tempo_primitives::transaction::Call;
+
+/// Prices are drawn from this range, in base units of the payment token
+const PRICE_RANGE: std::ops::RangeInclusive<u64> = 1_000..=100_000;
+
+#[derive(Debug, Clone, Default)]
+pub struct NftListTask;
+
+impl NftListTask {
+    pub fn new() -> Self {
+        Self
+    }
+}
+
+#[async_trait]
+impl TempoTask for NftListTask {
+    fn name(&self) -> &'static str {
+        "59_nft_list"
+    }
+
+    fn required_helpers(&self) -> &'static [&'static str] {
+        &["marketplace"]
+    }
+
+    async fn run(&self, ctx: &TaskContext) -> Result<TaskResult> {
+        use crate::contracts::IMarketplace;
+
+        let marketplace = ctx.helper("marketplace")?;
+        let db = nft_market::database(ctx)?;
+        let address = ctx.address();
+        let seller = address.to_string();
+
+        let listed: Vec<_> = nft_market::active_listings(ctx, marketplace)
+            .await?
+            .into_iter()
+            .filter(|listing| listing.seller == seller)
+            .collect();
+        if listed.len() >= ctx.config.marketplace.max_listings {
+            return Ok(TaskResult {
+                success: false,
+                message: format!("Already {} active listings", listed.len()),
+                ..Default::default()
+            });
+        }
+
+        let mut collections = db.get_all_assets_by_type(COLLECTION_TYPE).await?;
+        collections.shuffle(&mut rand::thread_rng());
+        let mut pick = None;
+        for collection in collections {
+            let Ok(nft) = collection.parse::<Address>() else {
+                continue;
+            };
+            let Ok(owned) = nft_market::owned_tokens(ctx, nft).await else {
+                continue;
+            };
+            let nft_name = nft.to_string();
+            if let Some(token_id) = owned.into_iter().find(|token_id| {
+                !listed
+                    .iter()
+                    .any(|l| l.nft == nft_name && l.token_id == token_id.to_string())
+            }) {
+                pick = Some((nft, token_id));
+                break;
+            }
+        }
+        let Some((nft, token_id)) = pick else {
+            return Ok(TaskResult {
+                success: false,
+                message: "No unlisted NFT owned, mint one with 48_mint_viral_nft".to_string(),
+                ..Default::default()
+            });
+        };
+
+        let token = TempoTokens::get_random_system_token();
+        let price = U256::from(rand::rngs::OsRng.gen_range(PRICE_RANGE));
+
+        let mut calls = Vec::new();
+        let mut gas = 200_000;
+        if !nft_market::is_approved(ctx, nft, address, marketplace).await? {
+            calls.push(Call {
+                to: TxKind::Call(nft),
+                value: U256::ZERO,
+                input: Bytes::from(
+                    IMarketNft::setApprovalForAllCall {
+                        operator: marketplace,
+                        approved: true,
+                    }
+                    .abi_encode(),
+                ),
+            });
+            gas += 50_000;
+        }
+        calls.push(Call {
+            to: TxKind::Call(marketplace),
+            value: U256::ZERO,
+            input: Bytes::from(
+                IMarketplace::listCall {
+                    nft,
+                    tokenId: token_id,
+                    token: token.address,
+                    price,
+                }
+                .abi_encode(),
+            ),
+        });
+
+        let (tx_hash, access_list) = ctx
+            .send_calls(calls, ctx.gas_limit(self.name(), gas))
+            .await?;
+        let receipt = nft_market::confirm(ctx, tx_hash).await;
+        let listing_id = receipt
+            .as_ref()
+            .filter(|r| r.status())
+            .and_then(|r| nft_market::listed_id(r, marketplace));
+
+        let message = match (&receipt, listing_id) {
+            (None, _) => format!("No receipt for listing {} #{}", nft, token_id),
+            (Some(_), None) => format!("Listing {} #{} reverted", nft, token_id),
+            (Some(_), Some(id)) => {
+                db.log_nft_listing(&NftListingRecord {
+                    chain_id: ctx.config.chain_id as i64,
+                    marketplace: marketplace.to_string(),
+                    listing_id: id.to_string(),
+                    seller,
+                    nft: nft.to_string(),
+                    token_id: token_id.to_string(),
+                    payment_token: token.address.to_string(),
+                    price: price.to_string(),
+                    status: "active".to_string(),
+                    tx_hash: format!("{:?}", tx_hash),
+                    listed_at: chrono::Utc::now().timestamp(),
+                })
+                .await?;
+                format!(
+                    "Listed {} #{} for {} {} (listing {})",
+                    nft, token_id, price, token.symbol, id
+                )
+            }
+        };
+        let mut result = TaskResult {
+            success: listing_id.is_some(),
+            message,
+            tx_hash: Some(format!("{:?}", tx_hash)),
+            ..Default::default()
+        }
+        .with_detail("nft", nft.to_string())
+        .with_detail("token_id", token_id.to_string())
+        .with_detail("price", price.to_string());
+        if let Some(id) = listing_id {
+            result = result.with_detail("listing_id", id.to_string());
+        }
+        if let Some(receipt) = &receipt {
+            result = result.with_receipt(receipt.gas_used, receipt.block_number);
+        }
+        Ok(match &access_list {
+            Some(estimate) => estimate.record(result),
+            None => result,
+        })
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use crate::audit::AuditedTx;
+    use crate::contracts::IMarketplace;
+    use crate::tasks::nft_market::{test_harness, test_listing};
+    use crate::testing::TEST_CHAIN_ID;
+    use alloy_sol_types::{SolEvent, SolValue};
+
+    const NFT: Address = Address::repeat_byte(0x4e);
+
+    /// Records the collection and gives the wallet tokens 3 and 8, not yet
+    /// approved for the marketplace
+    async fn own_tokens(harness: &crate::testing::TaskHarness) {
+        let wallet = harness.mock.address();
+        harness
+            .db
+            .as_ref()
+            .unwrap()
+            .log_asset_creation(
+                &wallet.to_string(),
+                &NFT.to_string(),
+                COLLECTION_TYPE,
+                "Viral",
+                "VRL",
+            )
+            .await
+            .unwrap();
+        for token_id in [3u64, 8] {
+            harness.mock.push_log(
+                NFT,
+                vec![
+                    IMarketNft::Transfer::SIGNATURE_HASH,
+                    NFT.into_word(),
+                    wallet.into_word(),
+                    U256::from(token_id).into(),
+                ],
+                Bytes::new(),
+            );
+        }
+        harness.mock.on_call(
+            NFT,
+            &hex::encode(IMarketNft::ownerOfCall::SELECTOR),
+            wallet.abi_encode(),
+        );
+        harness.mock.on_call(
+            NFT,
+            &hex::encode(IMarketNft::isApprovedForAllCall::SELECTOR),
+            false.abi_encode(),
+        );
+    }
+
+    #[tokio::test]
+    async fn test_lists_owned_nft_and_records_listing() {
+        let (harness, helpers, marketplace) = test_harness().await;
+        let ctx = harness.context().with_helpers(Some(helpers));
+        own_tokens(&harness).await;
+        let db = harness.db.as_ref().unwrap();
+        let wallet = harness.mock.address();
+        // Token 3 is listed already
+        db.log_nft_listing(&test_listing(marketplace, 1, wallet, 3, 0))
+            .await
+            .unwrap();
+        harness.mock.push_receipt_log(
+            marketplace,
+            vec![
+                IMarketplace::Listed::SIGNATURE_HASH,
+                U256::from(2).into(),
+                wallet.into_word(),
+            ],
+            (NFT, U256::from(8), Address::ZERO, U256::from(1_000)).abi_encode(),
+        );
+
+        let result = NftListTask::new().run(&ctx).await.unwrap();
+        assert!(result.success, "{}", result.message);
+        assert_eq!(result.details["token_id"], "8");
+        assert_eq!(result.details["listing_id"], "2");
+
+        // Not approved yet, so approval and listing go in one transaction
+        let tx = AuditedTx::decode(&harness.mock.sent()[0].raw, 0).unwrap();
+        assert_eq!(tx.calls.len(), 2);
+        assert_eq!(tx.calls[0].to, Some(NFT));
+        assert_eq!(tx.calls[1].to, Some(marketplace));
+
+        let listings = db
+            .get_active_nft_listings(TEST_CHAIN_ID, &marketplace.to_string())
+            .await
+            .unwrap();
+        assert_eq!(listings.len(), 2);
+        assert_eq!(listings[1].token_id, "8");
+        assert_eq!(listings[1].seller, wallet.to_string());
+    }
+
+    #[tokio::test]
+    async fn test_stops_at_max_listings() {
+        let (mut harness, helpers, marketplace) = test_harness().await;
+        harness.config.marketplace.max_listings = 1;
+        let ctx = harness.context().with_helpers(Some(helpers));
+        own_tokens(&harness).await;
+        let wallet = harness.mock.address();
+        harness
+            .db
+            .as_ref()
+            .unwrap()
+            .log_nft_listing(&test_listing(marketplace, 1, wallet, 3, 0))
+            .await
+            .unwrap();
+
+        let result = NftListTask::new().run(&ctx).await.unwrap();
+        assert!(!result.success);
+        assert!(harness.mock.sent().is_empty());
+    }
+}
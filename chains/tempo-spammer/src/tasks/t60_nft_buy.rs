@@ -0,0 +1,278 @@
+//! NFT Buy Task
+//!
+//! Buys an NFT another pool wallet listed with `59_nft_list`.
+//!
+//! Workflow:
+//! 1. Walk the recorded active listings of other sellers, oldest first
+//! 2. Skip listings the marketplace no longer holds (recorded as `closed`)
+//!    and ones whose seller moved the NFT or withdrew the approval
+//! 3. Check the wallet can pay the price in the listing's token
+//! 4. In one Tempo (0x76) transaction: `approve` the price for the
+//!    marketplace and `buy`
+//! 5. Record the listing as `sold` once the receipt succeeded
+
+use crate::tasks::nft_market::{self, IMarketToken};
+use crate::tasks::prelude::*;
+use alloy_primitives::{Bytes, TxKind, U256};
+use alloy_sol_types::SolCall;
+use anyhow::Result;
+use async_trait::async_trait;
+use tempo_primitives::transaction::Call;
+
+#[derive(Debug, Clone, Default)]
+pub struct NftBuyTask;
+
+impl NftBuyTask {
+    pub fn new() -> Self {
+        Self
+    }
+}
+
+#[async_trait]
+impl TempoTask for NftBuyTask {
+    fn name(&self) -> &'static str {
+        "60_nft_buy"
+    }
+
+    fn required_helpers(&self) -> &'static [&'static str] {
+        &["marketplace"]
+    }
+
+    async fn run(&self, ctx: &TaskContext) -> Result<TaskResult> {
+        use crate::contracts::IMarketplace;
+
+        let marketplace = ctx.helper("marketplace")?;
+        let buyer = ctx.address().to_string();
+
+        let mut pick = None;
+        for listing in nft_market::active_listings(ctx, marketplace).await? {
+            if listing.seller == buyer {
+                continue;
+            }
+            let listing_id = nft_market::parse_number(&listing.listing_id)?;
+            let seller = nft_market::parse_address(&listing.seller)?;
+            let nft = nft_market::parse_address(&listing.nft)?;
+            let token_id = nft_market::parse_number(&listing.token_id)?;
+
+            let onchain = nft_market::onchain_listing(ctx, marketplace, listing_id).await?;
+            if onchain.seller != seller {
+                nft_market::close_listing(ctx, &listing, "closed").await?;
+                continue;
+            }
+            if nft_market::owner_of(ctx, nft, token_id).await != Some(seller)
+                || !nft_market::is_approved(ctx, nft, seller, marketplace)
+                    .await
+                    .unwrap_or(false)
+            {
+                continue;
+            }
+            pick = Some((listing, listing_id, seller, onchain));
+            break;
+        }
+        let Some((listing, listing_id, seller, onchain)) = pick else {
+            return Ok(TaskResult {
+                success: false,
+                message: "No listing of another wallet available to buy".to_string(),
+                ..Default::default()
+            });
+        };
+
+        let (token, price) = (onchain.token, onchain.price);
+        let balance = ctx.spendable_balance(token, price).await?;
+        if balance < price {
+            return Ok(TaskResult {
+                success: false,
+                message: format!(
+                    "Balance {} of {} is below the price {} of listing {}",
+                    balance, token, price, listing_id
+                ),
+                ..Default::default()
+            });
+        }
+        let reservation = match ctx.reserve_spend(token, price) {
+            Ok(reservation) => reservation,
+            Err(exceeded) => return Ok(exceeded.into()),
+        };
+        let payment = ctx.track_transfer(seller, token, price);
+
+        let calls = vec![
+            Call {
+                to: TxKind::Call(token),
+                value: U256::ZERO,
+                input: Bytes::from(
+                    IMarketToken::approveCall {
+                        spender: marketplace,
+                        amount: price,
+                    }
+                    .abi_encode(),
+                ),
+            },
+            Call {
+                to: TxKind::Call(marketplace),
+                value: U256::ZERO,
+                input: Bytes::from(IMarketplace::buyCall { id: listing_id }.abi_encode()),
+            },
+        ];
+        let (tx_hash, access_list) = ctx
+            .send_calls(calls, ctx.gas_limit(self.name(), 300_000))
+            .await?;
+        reservation.commit().await;
+
+        let receipt = nft_market::confirm(ctx, tx_hash).await;
+        drop(payment);
+        let success = receipt.as_ref().is_some_and(|r| r.status());
+        if success {
+            nft_market::close_listing(ctx, &listing, "sold").await?;
+        }
+
+        let message = match &receipt {
+            None => format!("No receipt for buying listing {}", listing_id),
+            Some(_) if !success => format!("Buying listing {} reverted", listing_id),
+            Some(_) => format!(
+                "Bought {} #{} from {} for {} of {}",
+                listing.nft, listing.token_id, seller, price, token
+            ),
+        };
+        let mut result = TaskResult {
+            success,
+            message,
+            tx_hash: Some(format!("{:?}", tx_hash)),
+            ..Default::default()
+        }
+        .with_token_amount(token, price)
+        .with_detail("listing_id", listing.listing_id.clone())
+        .with_detail("seller", listing.seller.clone());
+        if let Some(receipt) = &receipt {
+            result = result.with_receipt(receipt.gas_used, receipt.block_number);
+        }
+        Ok(match &access_list {
+            Some(estimate) => estimate.record(result),
+            None => result,
+        })
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use crate::audit::AuditedTx;
+    use crate::contracts::IMarketplace;
+    use crate::tasks::nft_market::{IMarketNft, test_harness, test_listing};
+    use crate::testing::{TEST_CHAIN_ID, TaskHarness};
+    use alloy_primitives::Address;
+    use alloy_sol_types::SolValue;
+
+    const SELLER: Address = Address::repeat_byte(0x5e);
+
+    /// Programs the marketplace to hold listing 4 of SELLER, who still owns
+    /// and approved the NFT
+    fn program_listing(harness: &TaskHarness, marketplace: Address, price: u64) {
+        let record = test_listing(marketplace, 4, SELLER, 9, 0);
+        let nft: Address = record.nft.parse().unwrap();
+        let listing = IMarketplace::listingsReturn {
+            seller: SELLER,
+            nft,
+            tokenId: U256::from(9),
+            token: record.payment_token.parse().unwrap(),
+            price: U256::from(price),
+        };
+        harness.mock.on_call(
+            marketplace,
+            &hex::encode(IMarketplace::listingsCall::SELECTOR),
+            IMarketplace::listingsCall::abi_encode_returns(&listing),
+        );
+        harness.mock.on_call(
+            nft,
+            &hex::encode(IMarketNft::ownerOfCall::SELECTOR),
+            SELLER.abi_encode(),
+        );
+        harness.mock.on_call(
+            nft,
+            &hex::encode(IMarketNft::isApprovedForAllCall::SELECTOR),
+            true.abi_encode(),
+        );
+        harness.mock.on_call(
+            listing.token,
+            "70a08231",
+            U256::from(10_000_000).to_be_bytes_vec(),
+        );
+    }
+
+    #[tokio::test]
+    async fn test_buys_listing_of_other_wallet() {
+        let (harness, helpers, marketplace) = test_harness().await;
+        let ctx = harness.context().with_helpers(Some(helpers));
+        let db = harness.db.as_ref().unwrap();
+        // The wallet's own listing is never bought
+        db.log_nft_listing(&test_listing(marketplace, 1, harness.mock.address(), 2, 0))
+            .await
+            .unwrap();
+        db.log_nft_listing(&test_listing(marketplace, 4, SELLER, 9, 10))
+            .await
+            .unwrap();
+        program_listing(&harness, marketplace, 5_000);
+
+        let result = NftBuyTask::new().run(&ctx).await.unwrap();
+        assert!(result.success, "{}", result.message);
+        assert_eq!(result.details["listing_id"], "4");
+
+        let tx = AuditedTx::decode(&harness.mock.sent()[0].raw, 0).unwrap();
+        assert_eq!(tx.calls.len(), 2);
+        assert_eq!(tx.calls[1].to, Some(marketplace));
+        let remaining = db
+            .get_active_nft_listings(TEST_CHAIN_ID, &marketplace.to_string())
+            .await
+            .unwrap();
+        assert_eq!(remaining.len(), 1);
+        assert_eq!(remaining[0].listing_id, "1");
+    }
+
+    #[tokio::test]
+    async fn test_closes_listing_gone_on_chain() {
+        let (harness, helpers, marketplace) = test_harness().await;
+        let ctx = harness.context().with_helpers(Some(helpers));
+        let db = harness.db.as_ref().unwrap();
+        db.log_nft_listing(&test_listing(marketplace, 4, SELLER, 9, 0))
+            .await
+            .unwrap();
+        // The marketplace reports a zero seller: sold or cancelled elsewhere
+        harness.mock.on_call(
+            marketplace,
+            &hex::encode(IMarketplace::listingsCall::SELECTOR),
+            vec![0u8; 160],
+        );
+
+        let result = NftBuyTask::new().run(&ctx).await.unwrap();
+        assert!(!result.success);
+        assert!(harness.mock.sent().is_empty());
+        assert!(
+            db.get_active_nft_listings(TEST_CHAIN_ID, &marketplace.to_string())
+                .await
+                .unwrap()
+                .is_empty()
+        );
+    }
+
+    #[tokio::test]
+    async fn test_price_above_balance_skips() {
+        let (harness, helpers, marketplace) = test_harness().await;
+        let ctx = harness.context().with_helpers(Some(helpers));
+        harness
+            .db
+            .as_ref()
+            .unwrap()
+            .log_nft_listing(&test_listing(marketplace, 4, SELLER, 9, 0))
+            .await
+            .unwrap();
+        program_listing(&harness, marketplace, 50_000_000);
+
+        let result = NftBuyTask::new().run(&ctx).await.unwrap();
+        assert!(!result.success);
+        assert!(
+            result.message.contains("below the price"),
+            "{}",
+            result.message
+        );
+        assert!(harness.mock.sent().is_empty());
+    }
+}
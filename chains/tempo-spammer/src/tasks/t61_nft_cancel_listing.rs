@@ -0,0 +1,190 @@
+//! NFT Cancel Listing Task
+//!
+//! Withdraws the wallet's oldest stale listing from the marketplace helper.
+//!
+//! Workflow:
+//! 1. Take the wallet's recorded active listings, oldest first
+//! 2. A listing is stale once it is older than `[marketplace]
+//!    stale_after_secs`, or at once when the wallet no longer owns the NFT
+//! 3. Listings the marketplace no longer holds are recorded as `closed`
+//!    without a transaction
+//! 4. Send `cancel` and record the listing as `cancelled` once the receipt
+//!    succeeded
+
+use crate::tasks::nft_market;
+use crate::tasks::prelude::*;
+use alloy_primitives::{Bytes, TxKind, U256};
+use alloy_sol_types::SolCall;
+use anyhow::Result;
+use async_trait::async_trait;
+use tempo_primitives::transaction::Call;
+
+#[derive(Debug, Clone, Default)]
+pub struct NftCancelListingTask;
+
+impl NftCancelListingTask {
+    pub fn new() -> Self {
+        Self
+    }
+}
+
+#[async_trait]
+impl TempoTask for NftCancelListingTask {
+    fn name(&self) -> &'static str {
+        "61_nft_cancel_listing"
+    }
+
+    fn required_helpers(&self) -> &'static [&'static str] {
+        &["marketplace"]
+    }
+
+    async fn run(&self, ctx: &TaskContext) -> Result<TaskResult> {
+        use crate::contracts::IMarketplace;
+
+        let marketplace = ctx.helper("marketplace")?;
+        let address = ctx.address();
+        let seller = address.to_string();
+        let stale_before =
+            chrono::Utc::now().timestamp() - ctx.config.marketplace.stale_after_secs as i64;
+
+        let mut closed = 0;
+        let mut pick = None;
+        for listing in nft_market::active_listings(ctx, marketplace).await? {
+            if listing.seller != seller {
+                continue;
+            }
+            let nft = nft_market::parse_address(&listing.nft)?;
+            let token_id = nft_market::parse_number(&listing.token_id)?;
+            if listing.listed_at > stale_before
+                && nft_market::owner_of(ctx, nft, token_id).await == Some(address)
+            {
+                continue;
+            }
+
+            let listing_id = nft_market::parse_number(&listing.listing_id)?;
+            let onchain = nft_market::onchain_listing(ctx, marketplace, listing_id).await?;
+            if onchain.seller != address {
+                nft_market::close_listing(ctx, &listing, "closed").await?;
+                closed += 1;
+                continue;
+            }
+            pick = Some((listing, listing_id));
+            break;
+        }
+        let Some((listing, listing_id)) = pick else {
+            let message = if closed > 0 {
+                format!("Closed {} listings gone from the marketplace", closed)
+            } else {
+                "No stale listings".to_string()
+            };
+            return Ok(TaskResult {
+                success: closed > 0,
+                message,
+                ..Default::default()
+            });
+        };
+
+        let call = Call {
+            to: TxKind::Call(marketplace),
+            value: U256::ZERO,
+            input: Bytes::from(IMarketplace::cancelCall { id: listing_id }.abi_encode()),
+        };
+        let (tx_hash, access_list) = ctx
+            .send_calls(vec![call], ctx.gas_limit(self.name(), 80_000))
+            .await?;
+        let receipt = nft_market::confirm(ctx, tx_hash).await;
+        let success = receipt.as_ref().is_some_and(|r| r.status());
+        if success {
+            nft_market::close_listing(ctx, &listing, "cancelled").await?;
+        }
+
+        let message = match &receipt {
+            None => format!("No receipt for cancelling listing {}", listing_id),
+            Some(_) if !success => format!("Cancelling listing {} reverted", listing_id),
+            Some(_) => format!(
+                "Cancelled listing {} of {} #{}",
+                listing_id, listing.nft, listing.token_id
+            ),
+        };
+        let mut result = TaskResult {
+            success,
+            message,
+            tx_hash: Some(format!("{:?}", tx_hash)),
+            ..Default::default()
+        }
+        .with_detail("listing_id", listing.listing_id.clone())
+        .with_detail("closed", closed);
+        if let Some(receipt) = &receipt {
+            result = result.with_receipt(receipt.gas_used, receipt.block_number);
+        }
+        Ok(match &access_list {
+            Some(estimate) => estimate.record(result),
+            None => result,
+        })
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use crate::audit::AuditedTx;
+    use crate::contracts::IMarketplace;
+    use crate::tasks::nft_market::{IMarketNft, test_harness, test_listing};
+    use crate::testing::TEST_CHAIN_ID;
+    use alloy_primitives::Address;
+    use alloy_sol_types::SolValue;
+
+    #[tokio::test]
+    async fn test_cancels_stale_listing() {
+        let (harness, helpers, marketplace) = test_harness().await;
+        let ctx = harness.context().with_helpers(Some(helpers));
+        let wallet = harness.mock.address();
+        let db = harness.db.as_ref().unwrap();
+        let now = chrono::Utc::now().timestamp();
+        // Fresh and still owned, so left alone
+        db.log_nft_listing(&test_listing(marketplace, 7, wallet, 3, now))
+            .await
+            .unwrap();
+        db.log_nft_listing(&test_listing(marketplace, 2, wallet, 1, now - 7200))
+            .await
+            .unwrap();
+        let record = test_listing(marketplace, 2, wallet, 1, 0);
+        let nft: Address = record.nft.parse().unwrap();
+        harness.mock.on_call(
+            nft,
+            &hex::encode(IMarketNft::ownerOfCall::SELECTOR),
+            wallet.abi_encode(),
+        );
+        let listing = IMarketplace::listingsReturn {
+            seller: wallet,
+            nft,
+            tokenId: U256::from(1),
+            token: record.payment_token.parse().unwrap(),
+            price: U256::from(5_000),
+        };
+        harness.mock.on_call(
+            marketplace,
+            &hex::encode(IMarketplace::listingsCall::SELECTOR),
+            IMarketplace::listingsCall::abi_encode_returns(&listing),
+        );
+
+        let result = NftCancelListingTask::new().run(&ctx).await.unwrap();
+        assert!(result.success, "{}", result.message);
+        assert_eq!(result.details["listing_id"], "2");
+        let tx = AuditedTx::decode(&harness.mock.sent()[0].raw, 0).unwrap();
+        assert_eq!(tx.calls.len(), 1);
+        assert_eq!(tx.calls[0].to, Some(marketplace));
+
+        let active = db
+            .get_active_nft_listings(TEST_CHAIN_ID, &marketplace.to_string())
+            .await
+            .unwrap();
+        assert_eq!(active.len(), 1);
+        assert_eq!(active[0].listing_id, "7");
+
+        // Only the fresh listing is left
+        let result = NftCancelListingTask::new().run(&ctx).await.unwrap();
+        assert!(!result.success);
+        assert_eq!(harness.mock.sent().len(), 1);
+    }
+}
@@ -0,0 +1,139 @@
+//! Domain Set Resolver Task
+//!
+//! Points one of the wallet's registered `.tempo` names at the configured
+//! resolver and writes a text record.
+//!
+//! Workflow:
+//! 1. Pick a recorded domain of the wallet whose resolver differs from
+//!    `[domains] resolver`
+//! 2. In one Tempo (0x76) transaction: `setResolver` on the name service and
+//!    `setText` (`url`) on the resolver
+//! 3. Record the resolver once the receipt succeeded
+
+use crate::tasks::domains::{self, IDomainService};
+use crate::tasks::prelude::*;
+use alloy_primitives::{Bytes, TxKind, U256};
+use alloy_sol_types::SolCall;
+use anyhow::Result;
+use async_trait::async_trait;
+use tempo_primitives::transaction::Call;
+
+#[derive(Debug, Clone, Default)]
+pub struct DomainSetResolverTask;
+
+impl DomainSetResolverTask {
+    pub fn new() -> Self {
+        Self
+    }
+}
+
+#[async_trait]
+impl TempoTask for DomainSetResolverTask {
+    fn name(&self) -> &'static str {
+        "62_domain_set_resolver"
+    }
+
+    async fn run(&self, ctx: &TaskContext) -> Result<TaskResult> {
+        let resolver = ctx.config.domains.resolver;
+        let resolver_name = resolver.to_string();
+        let Some(mut domain) = domains::pick_domain(ctx, |d| d.resolver != resolver_name).await?
+        else {
+            return Ok(domains::none_left("without the configured resolver"));
+        };
+        let node = domains::node(&domain)?;
+
+        let calls = vec![
+            Call {
+                to: TxKind::Call(ctx.contracts().name_service),
+                value: U256::ZERO,
+                input: Bytes::from(IDomainService::setResolverCall { node, resolver }.abi_encode()),
+            },
+            Call {
+                to: TxKind::Call(resolver),
+                value: U256::ZERO,
+                input: Bytes::from(
+                    IDomainService::setTextCall {
+                        node,
+                        key: "url".to_string(),
+                        value: format!("https://{}", domain.name),
+                    }
+                    .abi_encode(),
+                ),
+            },
+        ];
+        let (tx_hash, access_list) = ctx
+            .send_calls(calls, ctx.gas_limit(self.name(), 200_000))
+            .await?;
+        let receipt = domains::confirm(ctx, tx_hash).await;
+        let success = receipt.as_ref().is_some_and(|r| r.status());
+        if success {
+            domain.resolver = resolver_name;
+            domains::update(ctx, &domain).await?;
+        }
+
+        let message = match &receipt {
+            None => format!("No receipt for setting the resolver of {}", domain.name),
+            Some(_) if !success => format!("Setting the resolver of {} reverted", domain.name),
+            Some(_) => format!("Set resolver of {} to {}", domain.name, resolver),
+        };
+        let mut result = TaskResult {
+            success,
+            message,
+            tx_hash: Some(format!("{:?}", tx_hash)),
+            ..Default::default()
+        }
+        .with_detail("domain", domain.name.clone())
+        .with_detail("resolver", resolver.to_string());
+        if let Some(receipt) = &receipt {
+            result = result.with_receipt(receipt.gas_used, receipt.block_number);
+        }
+        Ok(match &access_list {
+            Some(estimate) => estimate.record(result),
+            None => result,
+        })
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use crate::audit::AuditedTx;
+    use crate::tasks::domains::test_domain;
+    use crate::testing::{TEST_CHAIN_ID, TaskHarness};
+
+    #[tokio::test]
+    async fn test_sets_resolver_of_recorded_domain() {
+        let harness = TaskHarness::new().with_db().await.unwrap();
+        let ctx = harness.context();
+        let wallet = harness.mock.address();
+        let db = harness.db.as_ref().unwrap();
+        let mut done = test_domain(wallet, "done", 0);
+        done.resolver = ctx.contracts().name_service.to_string();
+        db.log_domain(&done).await.unwrap();
+        db.log_domain(&test_domain(wallet, "fresh", 10))
+            .await
+            .unwrap();
+
+        let result = DomainSetResolverTask::new().run(&ctx).await.unwrap();
+        assert!(result.success, "{}", result.message);
+        assert_eq!(result.details["domain"], "fresh.tempo");
+        let tx = AuditedTx::decode(&harness.mock.sent()[0].raw, 0).unwrap();
+        assert_eq!(tx.calls.len(), 2);
+        assert_eq!(tx.calls[0].to, Some(ctx.contracts().name_service));
+
+        let recorded = db
+            .get_domains(TEST_CHAIN_ID, &wallet.to_string())
+            .await
+            .unwrap();
+        assert!(
+            recorded
+                .iter()
+                .all(|d| d.resolver == ctx.contracts().name_service.to_string())
+        );
+
+        // Every domain has the resolver now
+        let result = DomainSetResolverTask::new().run(&ctx).await.unwrap();
+        assert!(!result.success);
+        assert_eq!(harness.mock.sent().len(), 1);
+    }
+}
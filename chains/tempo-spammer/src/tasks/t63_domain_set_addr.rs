@@ -0,0 +1,123 @@
+//! Domain Set Addr Task
+//!
+//! Updates the address record of one of the wallet's registered `.tempo`
+//! names.
+//!
+//! Workflow:
+//! 1. Choose the target: another wallet of the pool, else the wallet itself
+//! 2. Pick a recorded domain of the wallet that has a resolver (see
+//!    `62_domain_set_resolver`) and does not resolve to the target yet
+//! 3. Send `setAddr` to the domain's resolver
+//! 4. Record the address once the receipt succeeded
+
+use crate::tasks::domains::{self, IDomainService};
+use crate::tasks::prelude::*;
+use alloy_primitives::{Address, Bytes, TxKind, U256};
+use alloy_sol_types::SolCall;
+use anyhow::{Context, Result};
+use async_trait::async_trait;
+use tempo_primitives::transaction::Call;
+
+#[derive(Debug, Clone, Default)]
+pub struct DomainSetAddrTask;
+
+impl DomainSetAddrTask {
+    pub fn new() -> Self {
+        Self
+    }
+}
+
+#[async_trait]
+impl TempoTask for DomainSetAddrTask {
+    fn name(&self) -> &'static str {
+        "63_domain_set_addr"
+    }
+
+    async fn run(&self, ctx: &TaskContext) -> Result<TaskResult> {
+        let target = ctx.pool_wallet().unwrap_or_else(|| ctx.address());
+        let target_name = target.to_string();
+        let Some(mut domain) =
+            domains::pick_domain(ctx, |d| !d.resolver.is_empty() && d.addr != target_name).await?
+        else {
+            return Ok(domains::none_left("with a resolver to update"));
+        };
+        let node = domains::node(&domain)?;
+        let resolver: Address = domain
+            .resolver
+            .parse()
+            .with_context(|| format!("Bad resolver '{}' of {}", domain.resolver, domain.name))?;
+
+        let call = Call {
+            to: TxKind::Call(resolver),
+            value: U256::ZERO,
+            input: Bytes::from(IDomainService::setAddrCall { node, addr: target }.abi_encode()),
+        };
+        let (tx_hash, access_list) = ctx
+            .send_calls(vec![call], ctx.gas_limit(self.name(), 100_000))
+            .await?;
+        let receipt = domains::confirm(ctx, tx_hash).await;
+        let success = receipt.as_ref().is_some_and(|r| r.status());
+        if success {
+            domain.addr = target_name;
+            domains::update(ctx, &domain).await?;
+        }
+
+        let message = match &receipt {
+            None => format!("No receipt for setting the address of {}", domain.name),
+            Some(_) if !success => format!("Setting the address of {} reverted", domain.name),
+            Some(_) => format!("Pointed {} at {}", domain.name, target),
+        };
+        let mut result = TaskResult {
+            success,
+            message,
+            tx_hash: Some(format!("{:?}", tx_hash)),
+            ..Default::default()
+        }
+        .with_detail("domain", domain.name.clone())
+        .with_detail("addr", target.to_string());
+        if let Some(receipt) = &receipt {
+            result = result.with_receipt(receipt.gas_used, receipt.block_number);
+        }
+        Ok(match &access_list {
+            Some(estimate) => estimate.record(result),
+            None => result,
+        })
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use crate::audit::AuditedTx;
+    use crate::tasks::domains::test_domain;
+    use crate::testing::{TEST_CHAIN_ID, TaskHarness};
+
+    #[tokio::test]
+    async fn test_points_domain_with_resolver_at_wallet() {
+        let harness = TaskHarness::new().with_db().await.unwrap();
+        let ctx = harness.context();
+        let wallet = harness.mock.address();
+        let db = harness.db.as_ref().unwrap();
+        let resolver = Address::repeat_byte(0x7e);
+        // No resolver yet, so not eligible
+        db.log_domain(&test_domain(wallet, "bare", 0))
+            .await
+            .unwrap();
+        let mut resolved = test_domain(wallet, "resolved", 10);
+        resolved.resolver = resolver.to_string();
+        db.log_domain(&resolved).await.unwrap();
+
+        let result = DomainSetAddrTask::new().run(&ctx).await.unwrap();
+        assert!(result.success, "{}", result.message);
+        assert_eq!(result.details["domain"], "resolved.tempo");
+        let tx = AuditedTx::decode(&harness.mock.sent()[0].raw, 0).unwrap();
+        assert_eq!(tx.calls[0].to, Some(resolver));
+
+        let recorded = db
+            .get_domains(TEST_CHAIN_ID, &wallet.to_string())
+            .await
+            .unwrap();
+        assert_eq!(recorded[1].addr, wallet.to_string());
+        assert!(recorded[0].addr.is_empty());
+    }
+}
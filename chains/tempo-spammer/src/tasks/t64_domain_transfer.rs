@@ -0,0 +1,160 @@
+//! Domain Transfer Task
+//!
+//! Hands one of the wallet's registered `.tempo` names to another wallet of
+//! the pool, which can then manage it with tasks 62-65 in turn.
+//!
+//! Workflow:
+//! 1. Choose another leased wallet of the pool as the receiver
+//! 2. Pick a random recorded domain of the wallet
+//! 3. Send the registrar's ERC-721 `transferFrom`
+//! 4. Record the receiver as owner once the receipt succeeded
+
+use crate::tasks::domains::{self, IDomainService};
+use crate::tasks::prelude::*;
+use alloy_primitives::{Bytes, TxKind, U256};
+use alloy_sol_types::SolCall;
+use anyhow::{Context, Result};
+use async_trait::async_trait;
+use tempo_primitives::transaction::Call;
+
+#[derive(Debug, Clone, Default)]
+pub struct DomainTransferTask;
+
+impl DomainTransferTask {
+    pub fn new() -> Self {
+        Self
+    }
+}
+
+#[async_trait]
+impl TempoTask for DomainTransferTask {
+    fn name(&self) -> &'static str {
+        "64_domain_transfer"
+    }
+
+    async fn run(&self, ctx: &TaskContext) -> Result<TaskResult> {
+        let Some(receiver) = ctx.pool_wallet() else {
+            return Ok(TaskResult {
+                success: false,
+                message: "No other pool wallet to transfer a domain to".to_string(),
+                ..Default::default()
+            });
+        };
+        let Some(mut domain) = domains::pick_domain(ctx, |_| true).await? else {
+            return Ok(domains::none_left("to transfer"));
+        };
+        let token_id: U256 = domain
+            .token_id
+            .parse()
+            .with_context(|| format!("Bad token id '{}' of {}", domain.token_id, domain.name))?;
+
+        let call = Call {
+            to: TxKind::Call(ctx.contracts().name_service),
+            value: U256::ZERO,
+            input: Bytes::from(
+                IDomainService::transferFromCall {
+                    from: ctx.address(),
+                    to: receiver,
+                    tokenId: token_id,
+                }
+                .abi_encode(),
+            ),
+        };
+        let (tx_hash, access_list) = ctx
+            .send_calls(vec![call], ctx.gas_limit(self.name(), 120_000))
+            .await?;
+        let receipt = domains::confirm(ctx, tx_hash).await;
+        let success = receipt.as_ref().is_some_and(|r| r.status());
+        if success {
+            domain.owner = receiver.to_string();
+            domains::update(ctx, &domain).await?;
+        }
+
+        let message = match &receipt {
+            None => format!("No receipt for transferring {}", domain.name),
+            Some(_) if !success => format!("Transferring {} reverted", domain.name),
+            Some(_) => format!("Transferred {} to {}", domain.name, receiver),
+        };
+        let mut result = TaskResult {
+            success,
+            message,
+            tx_hash: Some(format!("{:?}", tx_hash)),
+            ..Default::default()
+        }
+        .with_detail("domain", domain.name.clone())
+        .with_detail("receiver", receiver.to_string());
+        if let Some(receipt) = &receipt {
+            result = result.with_receipt(receipt.gas_used, receipt.block_number);
+        }
+        Ok(match &access_list {
+            Some(estimate) => estimate.record(result),
+            None => result,
+        })
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use crate::audit::AuditedTx;
+    use crate::config::RecipientSettings;
+    use crate::tasks::domains::test_domain;
+    use crate::testing::{TEST_CHAIN_ID, TaskHarness};
+    use crate::transfers::TransferTracker;
+    use alloy_primitives::Address;
+    use std::sync::Arc;
+
+    #[tokio::test]
+    async fn test_transfers_domain_to_pool_wallet() {
+        let harness = TaskHarness::new().with_db().await.unwrap();
+        let wallet = harness.mock.address();
+        let other = Address::repeat_byte(0xbb);
+        // Domain transfers use the pool even when `pool_share` is 0
+        let tracker = Arc::new(TransferTracker::new(RecipientSettings::default()));
+        tracker.register(wallet);
+        tracker.register(other);
+        let ctx = harness.context().with_transfers(Some(tracker));
+        let db = harness.db.as_ref().unwrap();
+        db.log_domain(&test_domain(wallet, "gift", 0))
+            .await
+            .unwrap();
+
+        let result = DomainTransferTask::new().run(&ctx).await.unwrap();
+        assert!(result.success, "{}", result.message);
+        assert_eq!(result.details["receiver"], other.to_string());
+        let tx = AuditedTx::decode(&harness.mock.sent()[0].raw, 0).unwrap();
+        assert_eq!(tx.calls[0].to, Some(ctx.contracts().name_service));
+
+        assert!(
+            db.get_domains(TEST_CHAIN_ID, &wallet.to_string())
+                .await
+                .unwrap()
+                .is_empty()
+        );
+        let moved = db
+            .get_domains(TEST_CHAIN_ID, &other.to_string())
+            .await
+            .unwrap();
+        assert_eq!(moved[0].name, "gift.tempo");
+    }
+
+    #[tokio::test]
+    async fn test_needs_another_pool_wallet() {
+        let harness = TaskHarness::new().with_db().await.unwrap();
+        let wallet = harness.mock.address();
+        harness
+            .db
+            .as_ref()
+            .unwrap()
+            .log_domain(&test_domain(wallet, "gift", 0))
+            .await
+            .unwrap();
+
+        let result = DomainTransferTask::new()
+            .run(&harness.context())
+            .await
+            .unwrap();
+        assert!(!result.success);
+        assert!(harness.mock.sent().is_empty());
+    }
+}
@@ -0,0 +1,127 @@
+//! Domain Renew Task
+//!
+//! Renews the wallet's registered `.tempo` name that went longest without a
+//! renewal.
+//!
+//! Workflow:
+//! 1. Take the recorded domain of the wallet registered or renewed longest ago
+//! 2. Send `renew` for its label and `[domains] renew_duration_secs`
+//! 3. Record the renewal time once the receipt succeeded
+
+use crate::tasks::domains::{self, IDomainService};
+use crate::tasks::prelude::*;
+use alloy_primitives::{Bytes, TxKind, U256};
+use alloy_sol_types::SolCall;
+use anyhow::Result;
+use async_trait::async_trait;
+use tempo_primitives::transaction::Call;
+
+#[derive(Debug, Clone, Default)]
+pub struct DomainRenewTask;
+
+impl DomainRenewTask {
+    pub fn new() -> Self {
+        Self
+    }
+}
+
+#[async_trait]
+impl TempoTask for DomainRenewTask {
+    fn name(&self) -> &'static str {
+        "65_domain_renew"
+    }
+
+    async fn run(&self, ctx: &TaskContext) -> Result<TaskResult> {
+        let Some(mut domain) = domains::database(ctx)?
+            .get_domains(ctx.config.chain_id, &ctx.address().to_string())
+            .await?
+            .into_iter()
+            .min_by_key(|d| d.registered_at.max(d.renewed_at))
+        else {
+            return Ok(domains::none_left("to renew"));
+        };
+        let label = domain
+            .name
+            .strip_suffix(&format!(".{}", domains::TLD))
+            .unwrap_or(&domain.name)
+            .to_string();
+        let duration = ctx.config.domains.renew_duration_secs;
+
+        let call = Call {
+            to: TxKind::Call(ctx.contracts().name_service),
+            value: U256::ZERO,
+            input: Bytes::from(
+                IDomainService::renewCall {
+                    name: label,
+                    duration: U256::from(duration),
+                }
+                .abi_encode(),
+            ),
+        };
+        let (tx_hash, access_list) = ctx
+            .send_calls(vec![call], ctx.gas_limit(self.name(), 150_000))
+            .await?;
+        let receipt = domains::confirm(ctx, tx_hash).await;
+        let success = receipt.as_ref().is_some_and(|r| r.status());
+        if success {
+            domain.renewed_at = chrono::Utc::now().timestamp();
+            domains::update(ctx, &domain).await?;
+        }
+
+        let message = match &receipt {
+            None => format!("No receipt for renewing {}", domain.name),
+            Some(_) if !success => format!("Renewing {} reverted", domain.name),
+            Some(_) => format!("Renewed {} for {}s", domain.name, duration),
+        };
+        let mut result = TaskResult {
+            success,
+            message,
+            tx_hash: Some(format!("{:?}", tx_hash)),
+            ..Default::default()
+        }
+        .with_detail("domain", domain.name.clone())
+        .with_detail("duration_secs", duration);
+        if let Some(receipt) = &receipt {
+            result = result.with_receipt(receipt.gas_used, receipt.block_number);
+        }
+        Ok(match &access_list {
+            Some(estimate) => estimate.record(result),
+            None => result,
+        })
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use crate::tasks::domains::test_domain;
+    use crate::testing::{TEST_CHAIN_ID, TaskHarness};
+
+    #[tokio::test]
+    async fn test_renews_longest_unrenewed_domain() {
+        let harness = TaskHarness::new().with_db().await.unwrap();
+        let ctx = harness.context();
+        let wallet = harness.mock.address();
+        let db = harness.db.as_ref().unwrap();
+        let mut renewed = test_domain(wallet, "renewed", 0);
+        renewed.renewed_at = 500;
+        db.log_domain(&renewed).await.unwrap();
+        db.log_domain(&test_domain(wallet, "due", 100))
+            .await
+            .unwrap();
+
+        let result = DomainRenewTask::new().run(&ctx).await.unwrap();
+        assert!(result.success, "{}", result.message);
+        assert_eq!(result.details["domain"], "due.tempo");
+        assert_eq!(result.details["duration_secs"], 31_536_000);
+
+        // The renewal moves it behind the other domain
+        let recorded = db
+            .get_domains(TEST_CHAIN_ID, &wallet.to_string())
+            .await
+            .unwrap();
+        assert!(recorded.iter().all(|d| d.renewed_at > 0));
+        let result = DomainRenewTask::new().run(&ctx).await.unwrap();
+        assert_eq!(result.details["domain"], "renewed.tempo");
+    }
+}
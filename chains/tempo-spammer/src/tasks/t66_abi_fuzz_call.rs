@@ -0,0 +1,381 @@
+//! ABI Fuzz Call Task
+//!
+//! Calls a random mutating function of a random helper contract with random
+//! arguments, covering every helper without a task per contract.
+//!
+//! Workflow:
+//! 1. Pick a helper the registry resolved and parse its ABI from the
+//!    reference source
+//! 2. Pick one of its non-payable mutating functions whose argument types
+//!    can be generated: bounded integers, pool wallet addresses, up to 32
+//!    random bytes, short strings and arrays of up to 3 elements
+//! 3. Simulate the call; a revert is recorded without sending anything
+//! 4. Otherwise send it and record whether the receipt succeeded
+//!
+//! Outcomes add up per selector in the `fuzz_results` table when the run
+//! has a database. A revert is a finding rather than a failure, so the task
+//! only fails when it cannot tell how the call went.
+
+use crate::contracts::helper_contracts;
+use crate::revert;
+use crate::tasks::prelude::*;
+use alloy::dyn_abi::{DynSolType, DynSolValue, JsonAbiExt, Specifier};
+use alloy::json_abi::{Function, StateMutability};
+use alloy::providers::Provider;
+use alloy::rpc::types::TransactionRequest;
+use alloy_primitives::{Address, B256, Bytes, I256, TxKind, U256};
+use anyhow::{Context, Result};
+use async_trait::async_trait;
+use core_logic::database::FuzzResultRecord;
+use rand::Rng;
+use rand::distributions::Alphanumeric;
+use rand::seq::SliceRandom;
+use std::time::Duration;
+use tempo_primitives::transaction::Call;
+
+/// Largest magnitude of a generated integer
+const INT_BOUND: u64 = 1_000_000;
+/// Most elements of a generated dynamic array
+const MAX_ARRAY_LEN: usize = 3;
+/// Longest fixed array arguments are generated for
+const MAX_FIXED_ARRAY_LEN: usize = 8;
+/// Most bytes of a generated `bytes` argument
+const MAX_BYTES_LEN: usize = 32;
+/// Most characters of a generated `string` argument
+const MAX_STRING_LEN: usize = 12;
+
+const RECEIPT_TIMEOUT: Duration = Duration::from_secs(60);
+const RECEIPT_POLL: Duration = Duration::from_secs(1);
+
+#[derive(Debug, Clone, Default)]
+pub struct AbiFuzzCallTask;
+
+impl AbiFuzzCallTask {
+    pub fn new() -> Self {
+        Self
+    }
+}
+
+#[async_trait]
+impl TempoTask for AbiFuzzCallTask {
+    fn name(&self) -> &'static str {
+        "66_abi_fuzz_call"
+    }
+
+    async fn run(&self, ctx: &TaskContext) -> Result<TaskResult> {
+        let addresses: Vec<Address> = std::iter::once(ctx.address())
+            .chain(ctx.pool_wallet())
+            .collect();
+        let mut targets = Vec::new();
+        for helper in helper_contracts() {
+            let Ok(contract) = ctx.helper(helper.name) else {
+                continue;
+            };
+            for function in helper.abi() {
+                // Structs declared in the source, such as the order verifier's
+                // `Order`, do not resolve without the full ABI
+                let resolves = function.inputs.iter().all(|param| param.resolve().is_ok());
+                if function.state_mutability == StateMutability::NonPayable && resolves {
+                    targets.push((helper.name, contract, function));
+                }
+            }
+        }
+
+        let picked = {
+            let mut rng = rand::thread_rng();
+            targets
+                .choose(&mut rng)
+                .cloned()
+                .map(|(name, contract, function)| {
+                    let input = fuzz_input(&function, &addresses, &mut rng);
+                    (name, contract, function, input)
+                })
+        };
+        let Some((name, contract, function, input)) = picked else {
+            return Ok(TaskResult {
+                success: false,
+                message: "No helper contract with mutating functions is available".to_string(),
+                ..Default::default()
+            });
+        };
+        let Some(input) = input else {
+            return Ok(TaskResult {
+                success: false,
+                message: format!(
+                    "Cannot generate arguments for {}.{}",
+                    name,
+                    function.signature()
+                ),
+                ..Default::default()
+            });
+        };
+
+        let simulation = ctx
+            .client
+            .provider
+            .call(
+                TransactionRequest::default()
+                    .from(ctx.address())
+                    .to(contract)
+                    .input(input.clone().into()),
+            )
+            .await;
+        let (outcome, error, tx_hash, receipt, access_list) = match simulation {
+            Err(e) if e.as_error_resp().is_some() => {
+                (Outcome::Reverted, revert::describe(&e), None, None, None)
+            }
+            Err(e) => {
+                return Err(e).with_context(|| format!("Failed to simulate {}", name));
+            }
+            Ok(_) => {
+                let call = Call {
+                    to: TxKind::Call(contract),
+                    value: U256::ZERO,
+                    input,
+                };
+                let (tx_hash, access_list) = ctx
+                    .send_calls(vec![call], ctx.gas_limit(self.name(), 200_000))
+                    .await?;
+                let receipt = ctx
+                    .wait_for_receipt(tx_hash, RECEIPT_TIMEOUT, RECEIPT_POLL)
+                    .await;
+                let (outcome, error) = match &receipt {
+                    None => (Outcome::Unknown, String::new()),
+                    Some(r) if r.status() => (Outcome::Succeeded, String::new()),
+                    Some(_) => (Outcome::Reverted, "transaction reverted".to_string()),
+                };
+                (outcome, error, Some(tx_hash), receipt, access_list)
+            }
+        };
+
+        let signature = function.signature();
+        let selector = format!("{:?}", function.selector());
+        if outcome != Outcome::Unknown {
+            if let Some(db) = &ctx.db {
+                let record = FuzzResultRecord {
+                    chain_id: ctx.config.chain_id as i64,
+                    contract: name.to_string(),
+                    selector: selector.clone(),
+                    signature: signature.clone(),
+                    successes: i64::from(outcome == Outcome::Succeeded),
+                    reverts: i64::from(outcome == Outcome::Reverted),
+                    last_error: error.clone(),
+                    updated_at: chrono::Utc::now().timestamp(),
+                };
+                if let Err(e) = db.record_fuzz_result(&record).await {
+                    tracing::warn!("Failed to record fuzz result of {}: {:?}", signature, e);
+                }
+            }
+        }
+
+        let message = match (outcome, tx_hash) {
+            (Outcome::Unknown, _) => format!("No receipt for {}.{}", name, signature),
+            (Outcome::Succeeded, _) => format!("{}.{} succeeded", name, signature),
+            (Outcome::Reverted, Some(_)) => format!("{}.{} reverted on chain", name, signature),
+            (Outcome::Reverted, None) => {
+                format!("{}.{} reverts: {}", name, signature, error)
+            }
+        };
+        let mut result = TaskResult {
+            success: outcome != Outcome::Unknown,
+            message,
+            tx_hash: tx_hash.map(|hash| format!("{:?}", hash)),
+            ..Default::default()
+        }
+        .with_detail("contract", name)
+        .with_detail("function", signature)
+        .with_detail("selector", selector)
+        .with_detail("outcome", outcome.as_str());
+        if let Some(receipt) = &receipt {
+            result = result.with_receipt(receipt.gas_used, receipt.block_number);
+        }
+        Ok(match &access_list {
+            Some(estimate) => estimate.record(result),
+            None => result,
+        })
+    }
+}
+
+/// How a fuzzed call went
+#[derive(Debug, Clone, Copy, PartialEq, Eq)]
+enum Outcome {
+    Succeeded,
+    Reverted,
+    /// Sent, but no receipt arrived in time
+    Unknown,
+}
+
+impl Outcome {
+    fn as_str(self) -> &'static str {
+        match self {
+            Self::Succeeded => "succeeded",
+            Self::Reverted => "reverted",
+            Self::Unknown => "unknown",
+        }
+    }
+}
+
+/// Calldata calling `function` with random arguments, `None` when one of
+/// its parameter types is not supported
+fn fuzz_input(function: &Function, addresses: &[Address], rng: &mut impl Rng) -> Option<Bytes> {
+    let args = function
+        .inputs
+        .iter()
+        .map(|param| fuzz_value(&param.resolve().ok()?, addresses, rng))
+        .collect::<Option<Vec<_>>>()?;
+    function.abi_encode_input(&args).ok().map(Bytes::from)
+}
+
+/// A random value of type `ty`, `None` for unsupported types
+fn fuzz_value(ty: &DynSolType, addresses: &[Address], rng: &mut impl Rng) -> Option<DynSolValue> {
+    Some(match ty {
+        DynSolType::Bool => DynSolValue::Bool(rng.gen_bool(0.5)),
+        DynSolType::Uint(bits) => {
+            let max = if *bits < 64 {
+                INT_BOUND.min((1 << bits) - 1)
+            } else {
+                INT_BOUND
+            };
+            DynSolValue::Uint(U256::from(rng.gen_range(0..=max)), *bits)
+        }
+        DynSolType::Int(bits) => {
+            let max = if *bits < 64 {
+                INT_BOUND.min((1 << (bits - 1)) - 1)
+            } else {
+                INT_BOUND
+            } as i64;
+            DynSolValue::Int(I256::try_from(rng.gen_range(-max..=max)).ok()?, *bits)
+        }
+        DynSolType::Address => DynSolValue::Address(*addresses.choose(rng)?),
+        DynSolType::FixedBytes(len) => {
+            let mut word = B256::ZERO;
+            rng.fill(&mut word[..*len]);
+            DynSolValue::FixedBytes(word, *len)
+        }
+        DynSolType::Bytes => {
+            let mut bytes = vec![0u8; rng.gen_range(0..=MAX_BYTES_LEN)];
+            rng.fill(&mut bytes[..]);
+            DynSolValue::Bytes(bytes)
+        }
+        DynSolType::String => {
+            let len = rng.gen_range(1..=MAX_STRING_LEN);
+            DynSolValue::String(
+                (0..len)
+                    .map(|_| char::from(rng.sample(Alphanumeric)))
+                    .collect(),
+            )
+        }
+        DynSolType::Array(inner) => DynSolValue::Array(
+            (0..rng.gen_range(0..=MAX_ARRAY_LEN))
+                .map(|_| fuzz_value(inner, addresses, rng))
+                .collect::<Option<_>>()?,
+        ),
+        DynSolType::FixedArray(inner, len) if *len <= MAX_FIXED_ARRAY_LEN => {
+            DynSolValue::FixedArray(
+                (0..*len)
+                    .map(|_| fuzz_value(inner, addresses, rng))
+                    .collect::<Option<_>>()?,
+            )
+        }
+        DynSolType::Tuple(types) => DynSolValue::Tuple(
+            types
+                .iter()
+                .map(|ty| fuzz_value(ty, addresses, rng))
+                .collect::<Option<_>>()?,
+        ),
+        _ => return None,
+    })
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use crate::config::HelperSettings;
+    use crate::contracts::{HelperRegistry, IFuzzTarget, fuzz_target};
+    use crate::testing::{TEST_CHAIN_ID, TaskHarness};
+    use alloy_sol_types::SolCall;
+    use std::collections::BTreeMap;
+    use std::sync::Arc;
+
+    /// Harness with a database and only the fuzz target helper resolved
+    async fn harness() -> (TaskHarness, Arc<HelperRegistry>, Address) {
+        let harness = TaskHarness::new().with_db().await.unwrap();
+        let client = harness.mock.client();
+        let address = Address::repeat_byte(0xf2);
+        harness.mock.set_code(address, fuzz_target());
+        let mut settings = HelperSettings::default();
+        settings.pinned.insert(
+            client.chain_id().to_string(),
+            BTreeMap::from([("fuzz_target".to_string(), address)]),
+        );
+        let helpers =
+            HelperRegistry::resolve(client.provider(), client.chain_id(), &settings, None)
+                .await
+                .unwrap();
+        (harness, Arc::new(helpers), address)
+    }
+
+    #[test]
+    fn test_generated_arguments_stay_in_bounds() {
+        let function = Function::parse(
+            "function f(uint8 a, int256 b, address c, bytes4 d, bytes e, string f, address[] g, (bool,uint16)[2] h)",
+        )
+        .unwrap();
+        let pool = [Address::repeat_byte(1), Address::repeat_byte(2)];
+        let mut rng = rand::thread_rng();
+        for _ in 0..50 {
+            let input = fuzz_input(&function, &pool, &mut rng).unwrap();
+            let args = function.abi_decode_input(&input[4..]).unwrap();
+            assert!(args[0].as_uint().unwrap().0 <= U256::from(255));
+            assert!(args[1].as_int().unwrap().0.unsigned_abs() <= U256::from(INT_BOUND));
+            assert!(pool.contains(&args[2].as_address().unwrap()));
+            assert!(args[4].as_bytes().unwrap().len() <= MAX_BYTES_LEN);
+            assert!(args[6].as_array().unwrap().len() <= MAX_ARRAY_LEN);
+        }
+    }
+
+    #[tokio::test]
+    async fn test_records_success_and_simulated_revert() {
+        let (harness, helpers, address) = harness().await;
+        let ctx = harness.context().with_helpers(Some(helpers));
+        let db = harness.db.as_ref().unwrap();
+
+        let result = AbiFuzzCallTask::new().run(&ctx).await.unwrap();
+        assert!(result.success, "{}", result.message);
+        assert_eq!(result.details["function"], "poke(uint256)");
+        assert_eq!(result.details["outcome"], "succeeded");
+        let tx = crate::audit::AuditedTx::decode(&harness.mock.sent()[0].raw, 1024).unwrap();
+        assert_eq!(tx.calls[0].to, Some(address));
+        let input: Bytes = tx.calls[0].input.parse().unwrap();
+        let seed = IFuzzTarget::pokeCall::abi_decode(&input).unwrap().seed;
+        assert!(seed <= U256::from(INT_BOUND));
+
+        let selector = hex::encode(IFuzzTarget::pokeCall::SELECTOR);
+        harness
+            .mock
+            .on_call_revert(address, &selector, Bytes::new());
+        let result = AbiFuzzCallTask::new().run(&ctx).await.unwrap();
+        assert!(result.success, "{}", result.message);
+        assert_eq!(result.details["outcome"], "reverted");
+        assert!(result.tx_hash.is_none());
+        assert_eq!(harness.mock.sent().len(), 1);
+
+        let results = db.get_fuzz_results(TEST_CHAIN_ID).await.unwrap();
+        assert_eq!(results.len(), 1);
+        assert_eq!(results[0].contract, "fuzz_target");
+        assert_eq!(results[0].signature, "poke(uint256)");
+        assert_eq!((results[0].successes, results[0].reverts), (1, 1));
+        assert!(!results[0].last_error.is_empty());
+    }
+
+    #[tokio::test]
+    async fn test_fails_without_helpers() {
+        let harness = TaskHarness::new();
+        let result = AbiFuzzCallTask::new()
+            .run(&harness.context())
+            .await
+            .unwrap();
+        assert!(!result.success);
+        assert!(harness.mock.sent().is_empty());
+    }
+}
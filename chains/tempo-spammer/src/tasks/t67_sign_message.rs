@@ -0,0 +1,82 @@
+//! Sign Message Task
+//!
+//! Signs a random personal message with the wallet key and verifies it,
+//! without sending a transaction.
+//!
+//! Workflow:
+//! 1. Build a message naming the wallet and a random number
+//! 2. Sign it the EIP-191 way (`personal_sign`)
+//! 3. Recover the signer from the message and the signature; it must be the
+//!    wallet
+//! 4. Recover again from the message with one character changed; it must not
+//!    be the wallet
+
+use crate::tasks::prelude::*;
+use anyhow::{Context, Result};
+use async_trait::async_trait;
+use rand::Rng;
+
+#[derive(Debug, Clone, Default)]
+pub struct SignMessageTask;
+
+impl SignMessageTask {
+    pub fn new() -> Self {
+        Self
+    }
+}
+
+#[async_trait]
+impl TempoTask for SignMessageTask {
+    fn name(&self) -> &'static str {
+        "67_sign_message"
+    }
+
+    async fn run(&self, ctx: &TaskContext) -> Result<TaskResult> {
+        let address = ctx.address();
+        let number: u64 = rand::thread_rng().r#gen();
+        let message = format!("Tempo message #{} from {:?}", number, address);
+
+        let signature = ctx.client.sign_message(message.as_bytes())?;
+        let recovered = signature
+            .recover_address_from_msg(&message)
+            .context("Failed to recover signer")?;
+        let tampered = format!("{}.", message);
+        let tampered_signer = signature
+            .recover_address_from_msg(&tampered)
+            .context("Failed to recover signer of the tampered message")?;
+
+        let valid = recovered == address;
+        let tamper_rejected = tampered_signer != address;
+        let message_text = match (valid, tamper_rejected) {
+            (true, true) => format!("Signed and verified '{}'", message),
+            (false, _) => format!("Signature recovers {:?}, not the wallet", recovered),
+            (true, false) => "Signature also verifies a tampered message".to_string(),
+        };
+
+        Ok(TaskResult {
+            success: valid && tamper_rejected,
+            message: message_text,
+            tx_hash: None,
+            ..Default::default()
+        }
+        .with_detail(
+            "signature",
+            alloy_primitives::hex::encode_prefixed(signature.as_bytes()),
+        ))
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use crate::testing::TaskHarness;
+
+    #[tokio::test]
+    async fn test_signs_and_verifies_without_sending() {
+        let harness = TaskHarness::new();
+        let result = harness.run(&SignMessageTask::new()).await.unwrap();
+        assert!(result.success, "{}", result.message);
+        assert!(result.details["signature"].is_string());
+        assert!(harness.mock.sent().is_empty());
+    }
+}
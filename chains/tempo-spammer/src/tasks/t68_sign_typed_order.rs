@@ -0,0 +1,204 @@
+//! Sign Typed Order Task
+//!
+//! Signs an EIP-712 order and has the order verifier helper check the
+//! signature on-chain.
+//!
+//! Workflow:
+//! 1. Build an order of the wallet for a random system token, amount and
+//!    nonce, valid for ten minutes
+//! 2. Sign it as typed data under the verifier's domain (name, version,
+//!    chain id and verifier address)
+//! 3. Recover the signer from the digest locally; it must be the wallet
+//! 4. Send `verifyOrder(order, v, r, s)`, which reverts unless the contract
+//!    recovers the maker too
+//! 5. Check the receipt for the verifier's `OrderVerified` event carrying the
+//!    same digest
+
+use crate::contracts::{IOrderVerifier, order_domain};
+use crate::tasks::prelude::*;
+use crate::tasks::tempo_tokens::TempoTokens;
+use alloy_primitives::{B256, U256};
+use alloy_sol_types::SolStruct;
+use anyhow::{Context, Result};
+use async_trait::async_trait;
+use rand::Rng;
+use std::time::{SystemTime, UNIX_EPOCH};
+
+/// How long a signed order stays valid
+const ORDER_TTL_SECS: u64 = 600;
+
+#[derive(Debug, Clone, Default)]
+pub struct SignTypedOrderTask;
+
+impl SignTypedOrderTask {
+    pub fn new() -> Self {
+        Self
+    }
+}
+
+#[async_trait]
+impl TempoTask for SignTypedOrderTask {
+    fn name(&self) -> &'static str {
+        "68_sign_typed_order"
+    }
+
+    fn required_helpers(&self) -> &'static [&'static str] {
+        &["order_verifier"]
+    }
+
+    async fn run(&self, ctx: &TaskContext) -> Result<TaskResult> {
+        let verifier = ctx.helper("order_verifier")?;
+        let maker = ctx.address();
+
+        let now = SystemTime::now()
+            .duration_since(UNIX_EPOCH)
+            .unwrap_or_default()
+            .as_secs();
+        let order = {
+            let mut rng = rand::thread_rng();
+            IOrderVerifier::Order {
+                maker,
+                token: TempoTokens::get_random_system_token().address,
+                amount: U256::from(rng.gen_range(1..=1_000u64)) * U256::from(1_000_000u64),
+                nonce: U256::from(rng.r#gen::<u64>()),
+                deadline: U256::from(now + ORDER_TTL_SECS),
+            }
+        };
+
+        let domain = order_domain(ctx.chain_id(), verifier);
+        let digest = order.eip712_signing_hash(&domain);
+        let signature = ctx.client.sign_typed_data(&order, &domain)?;
+        let recovered = signature
+            .recover_address_from_prehash(&digest)
+            .context("Failed to recover signer")?;
+        if recovered != maker {
+            return Ok(TaskResult {
+                success: false,
+                message: format!("Order signature recovers {:?}, not the wallet", recovered),
+                tx_hash: None,
+                ..Default::default()
+            });
+        }
+
+        let nonce = order.nonce;
+        let call = IOrderVerifier::verifyOrderCall {
+            order,
+            v: 27 + signature.v() as u8,
+            r: B256::from(signature.r()),
+            s: B256::from(signature.s()),
+        };
+        let outcome = ctx
+            .client
+            .tx()
+            .to(verifier)
+            .call(&call)
+            .send_and_confirm()
+            .await
+            .context("Failed to send verifyOrder")?;
+
+        if !outcome.succeeded() {
+            return Ok(TaskResult {
+                success: false,
+                message: "verifyOrder reverted".to_string(),
+                tx_hash: Some(format!("{:?}", outcome.tx_hash)),
+                ..Default::default()
+            });
+        }
+
+        let verified = ctx
+            .assertions()
+            .assert_event_emitted::<IOrderVerifier::OrderVerified>(
+                outcome.receipt.inner.logs(),
+                verifier,
+                |event| event.digest == digest && event.maker == maker,
+            );
+
+        Ok(TaskResult {
+            success: true,
+            message: format!(
+                "Order {} verified on-chain by {}. Tx: {}",
+                nonce, verifier, outcome.tx_hash
+            ),
+            tx_hash: Some(format!("{:?}", outcome.tx_hash)),
+            ..Default::default()
+        }
+        .with_receipt(outcome.receipt.gas_used, outcome.receipt.block_number)
+        .with_detail("digest", digest.to_string())
+        .with_verification(verified))
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use crate::config::HelperSettings;
+    use crate::contracts::{HelperRegistry, order_verifier};
+    use crate::testing::TaskHarness;
+    use alloy_primitives::{Address, Signature};
+    use alloy_sol_types::SolCall;
+    use std::collections::BTreeMap;
+    use std::sync::Arc;
+
+    const VERIFIER: Address = Address::repeat_byte(0x71);
+
+    async fn helpers(harness: &TaskHarness) -> Arc<HelperRegistry> {
+        let client = harness.mock.client();
+        harness.mock.set_code(VERIFIER, order_verifier());
+        let settings = HelperSettings {
+            pinned: BTreeMap::from([(
+                client.chain_id().to_string(),
+                BTreeMap::from([("order_verifier".to_string(), VERIFIER)]),
+            )]),
+            ..Default::default()
+        };
+        let registry =
+            HelperRegistry::resolve(client.provider(), client.chain_id(), &settings, None)
+                .await
+                .unwrap();
+        Arc::new(registry)
+    }
+
+    #[tokio::test]
+    async fn test_sends_order_signed_by_wallet() {
+        let harness = TaskHarness::new();
+        let ctx = harness
+            .context()
+            .with_helpers(Some(helpers(&harness).await));
+
+        let result = SignTypedOrderTask::new().run(&ctx).await.unwrap();
+        assert!(result.success, "{}", result.message);
+
+        let sent = harness.mock.sent();
+        assert_eq!(sent.len(), 1);
+        assert_eq!(sent[0].to, Some(VERIFIER));
+        let call = IOrderVerifier::verifyOrderCall::abi_decode(&sent[0].input).unwrap();
+        assert_eq!(call.order.maker, harness.mock.address());
+
+        // The signature in the call recovers the maker from the typed digest
+        let digest = call
+            .order
+            .eip712_signing_hash(&order_domain(ctx.chain_id(), VERIFIER));
+        assert_eq!(result.details["digest"], digest.to_string());
+        let signature = Signature::new(call.r.into(), call.s.into(), call.v == 28);
+        assert_eq!(
+            signature.recover_address_from_prehash(&digest).unwrap(),
+            harness.mock.address()
+        );
+
+        // The mock does not run the verifier, so no event was logged
+        assert!(!result.verifications[0].passed);
+    }
+
+    #[tokio::test]
+    async fn test_reverted_verification_fails() {
+        let harness = TaskHarness::new();
+        let ctx = harness
+            .context()
+            .with_helpers(Some(helpers(&harness).await));
+        harness.mock.revert_next_receipt();
+
+        let result = SignTypedOrderTask::new().run(&ctx).await.unwrap();
+        assert!(!result.success);
+        assert_eq!(result.message, "verifyOrder reverted");
+    }
+}
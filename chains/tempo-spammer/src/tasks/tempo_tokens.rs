@@ -4,6 +4,7 @@
 //! and created tokens from the database.
 
 use crate::TempoClient;
+use crate::network;
 use crate::tasks::TaskContext;
 use alloy::primitives::{Address, U256};
 use alloy::rpc::types::TransactionRequest;
@@ -32,32 +33,36 @@ impl TokenInfo {
 pub struct TempoTokens;
 
 impl TempoTokens {
-    pub const SYSTEM_TOKENS: &[(&str, &str)] = &[
-        ("PathUSD", "0x20c0000000000000000000000000000000000000"),
-        ("AlphaUSD", "0x20c0000000000000000000000000000000000001"),
-        ("BetaUSD", "0x20c0000000000000000000000000000000000002"),
-        ("ThetaUSD", "0x20c0000000000000000000000000000000000003"),
-    ];
-
-    // Use PathUSD as a temporary fallback to verify logic when all memes are dead
-    pub const FALLBACK_MEME_TOKEN: &'static str = "0x20c0000000000000000000000000000000000000";
+    /// System tokens of the configured network, PathUSD first
+    pub fn system_tokens() -> [(&'static str, Address); 4] {
+        network::contracts().system_tokens()
+    }
 
     pub fn get_system_tokens() -> Vec<TokenInfo> {
-        Self::SYSTEM_TOKENS
-            .iter()
-            .map(|(symbol, addr)| TokenInfo::new(symbol, addr, true))
+        Self::system_tokens()
+            .into_iter()
+            .map(|(symbol, address)| TokenInfo {
+                symbol: symbol.to_string(),
+                address,
+                is_system: true,
+            })
             .collect()
     }
 
     pub fn get_random_system_token() -> TokenInfo {
         let mut rng = rand::rngs::OsRng;
-        let idx = rng.r#gen_range(0..Self::SYSTEM_TOKENS.len());
-        let (symbol, addr) = Self::SYSTEM_TOKENS[idx];
-        TokenInfo::new(symbol, addr, true)
+        let tokens = Self::get_system_tokens();
+        let idx = rng.r#gen_range(0..tokens.len());
+        tokens[idx].clone()
     }
 
     pub fn get_path_usd_address() -> Address {
-        Address::from_str(Self::SYSTEM_TOKENS[0].1).unwrap_or_else(|_| Address::ZERO)
+        network::contracts().path_usd
+    }
+
+    // Use PathUSD as a temporary fallback to verify logic when all memes are dead
+    pub fn fallback_meme_token() -> Address {
+        Self::get_path_usd_address()
     }
 
     pub fn get_random_memo() -> String {
@@ -134,8 +139,8 @@ impl TempoTokens {
         format!("{}", whole)
     }
 
-    /// Format amount with M/K suffixes and orange color
-    /// Example: 64718064 -> "\x1b[38;5;208m64.71M\x1b[0m"
+    /// Format amount with M/K suffixes, orange when the console shows colors
+    /// Example: 64718064 -> "\x1b[38;5;208m64.71M\x1b[0m", or "64.71M" without colors
     pub fn format_compact_colored(amount: U256, decimals: u8) -> String {
         let divisor = U256::from(10_u64.pow(decimals as u32));
         let whole_units = amount / divisor;
@@ -151,8 +156,7 @@ impl TempoTokens {
             format!("{:.2}", value)
         };
 
-        // Orange color (ANSI 208)
-        format!("\x1b[38;5;208m{}\x1b[0m", formatted)
+        core_logic::paint(core_logic::Tone::Amount, formatted)
     }
 }
 
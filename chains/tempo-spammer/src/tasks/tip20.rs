@@ -0,0 +1,364 @@
+//! TIP-20 Token Client
+//!
+//! Typed access to a TIP-20 token for the tasks, so they stop re-declaring
+//! `sol!` fragments and hand-building calldata. Reads return decoded values,
+//! writes return a [`TxBuilder`] for the caller to tune and send:
+//!
+//! ```rust,ignore
+//! let token = ctx.token(ctx.contracts().path_usd);
+//! let balance = token.balance_of(ctx.address()).await?;
+//! let amount = token.units(10).await?;
+//! let outcome = token.transfer(recipient, amount).send_and_confirm().await?;
+//! ```
+//!
+//! Decimals are read once per wrapper and cached. Reads that cover several
+//! tokens or several getters go through Multicall3 in one `eth_call`, and
+//! fall back to one call each where Multicall3 is unavailable.
+
+use crate::TempoClient;
+use crate::tasks::tempo_tokens::TempoTokens;
+use crate::tx_builder::TxBuilder;
+use alloy::primitives::{Address, B256, Bytes, U256, address};
+use alloy::rpc::types::TransactionRequest;
+use alloy_sol_types::{SolCall, sol};
+use anyhow::{Context, Result};
+use tokio::sync::OnceCell;
+
+sol! {
+    interface ITIP20 {
+        function name() returns (string);
+        function symbol() returns (string);
+        function decimals() returns (uint8);
+        function totalSupply() returns (uint256);
+        function balanceOf(address account) returns (uint256);
+        function allowance(address owner, address spender) returns (uint256);
+        function transfer(address to, uint256 amount) returns (bool);
+        function transferWithMemo(address to, uint256 amount, bytes32 memo);
+        function approve(address spender, uint256 amount) returns (bool);
+        function mint(address to, uint256 amount);
+        function burn(uint256 amount);
+        function hasRole(address account, bytes32 role) returns (bool);
+        function grantRole(bytes32 role, address account);
+    }
+
+    interface IMulticall3 {
+        struct Call3 {
+            address target;
+            bool allowFailure;
+            bytes callData;
+        }
+
+        struct Result {
+            bool success;
+            bytes returnData;
+        }
+
+        function aggregate3(Call3[] calls) returns (Result[] returnData);
+    }
+}
+
+/// Multicall3, predeployed in Tempo's genesis
+pub const MULTICALL3: Address = address!("cA11bde05977b3631167028862bE2a173976CA11");
+
+/// Name, symbol, decimals and supply of a token
+#[derive(Debug, Clone, PartialEq, Eq)]
+pub struct TokenMetadata {
+    pub name: String,
+    pub symbol: String,
+    pub decimals: u8,
+    pub total_supply: U256,
+}
+
+/// A TIP-20 token seen from one client's wallet
+#[derive(Debug, Clone)]
+pub struct Tip20Token<'a> {
+    client: &'a TempoClient,
+    address: Address,
+    decimals: OnceCell<u8>,
+}
+
+impl<'a> Tip20Token<'a> {
+    /// Token at `address`, read and sent to with `client`
+    pub fn new(client: &'a TempoClient, address: Address) -> Self {
+        Self {
+            client,
+            address,
+            decimals: OnceCell::new(),
+        }
+    }
+
+    /// Another token on the same client
+    pub fn at(&self, address: Address) -> Self {
+        Self::new(self.client, address)
+    }
+
+    /// Uses `decimals` instead of reading them from the token
+    pub fn with_decimals(mut self, decimals: u8) -> Self {
+        self.decimals = OnceCell::new_with(Some(decimals));
+        self
+    }
+
+    /// Address of the token
+    pub fn address(&self) -> Address {
+        self.address
+    }
+
+    /// Decimals of the token, read on first use
+    pub async fn decimals(&self) -> Result<u8> {
+        self.decimals
+            .get_or_try_init(|| self.read(ITIP20::decimalsCall {}))
+            .await
+            .copied()
+    }
+
+    /// `whole` tokens in base units
+    pub async fn units(&self, whole: u64) -> Result<U256> {
+        let decimals = self.decimals().await?;
+        Ok(U256::from(whole) * U256::from(10).pow(U256::from(decimals)))
+    }
+
+    /// `amount` in whole tokens, for messages
+    pub async fn format(&self, amount: U256) -> Result<String> {
+        Ok(TempoTokens::format_amount(amount, self.decimals().await?))
+    }
+
+    pub async fn balance_of(&self, account: Address) -> Result<U256> {
+        self.read(ITIP20::balanceOfCall { account }).await
+    }
+
+    pub async fn allowance(&self, owner: Address, spender: Address) -> Result<U256> {
+        self.read(ITIP20::allowanceCall { owner, spender }).await
+    }
+
+    pub async fn has_role(&self, account: Address, role: B256) -> Result<bool> {
+        self.read(ITIP20::hasRoleCall { account, role }).await
+    }
+
+    /// Name, symbol, decimals and total supply in one batch
+    ///
+    /// Also fills the decimals cache.
+    pub async fn metadata(&self) -> Result<TokenMetadata> {
+        let calls = [
+            ITIP20::nameCall {}.abi_encode(),
+            ITIP20::symbolCall {}.abi_encode(),
+            ITIP20::decimalsCall {}.abi_encode(),
+            ITIP20::totalSupplyCall {}.abi_encode(),
+        ]
+        .into_iter()
+        .map(|data| (self.address, Bytes::from(data)))
+        .collect();
+        let results = batch_read(self.client, calls).await?;
+
+        let metadata = TokenMetadata {
+            name: ITIP20::nameCall::abi_decode_returns(&results[0])
+                .context("Failed to decode name")?,
+            symbol: ITIP20::symbolCall::abi_decode_returns(&results[1])
+                .context("Failed to decode symbol")?,
+            decimals: ITIP20::decimalsCall::abi_decode_returns(&results[2])
+                .context("Failed to decode decimals")?,
+            total_supply: ITIP20::totalSupplyCall::abi_decode_returns(&results[3])
+                .context("Failed to decode totalSupply")?,
+        };
+        let _ = self.decimals.set(metadata.decimals);
+        Ok(metadata)
+    }
+
+    /// Balances of `owner` in each of `tokens`, in order, in one batch
+    pub async fn balances_of(
+        client: &TempoClient,
+        tokens: &[Address],
+        owner: Address,
+    ) -> Result<Vec<U256>> {
+        let data = Bytes::from(ITIP20::balanceOfCall { account: owner }.abi_encode());
+        let calls = tokens.iter().map(|token| (*token, data.clone())).collect();
+        batch_read(client, calls)
+            .await?
+            .iter()
+            .zip(tokens)
+            .map(|(result, token)| {
+                ITIP20::balanceOfCall::abi_decode_returns(result)
+                    .with_context(|| format!("Failed to decode balanceOf from {}", token))
+            })
+            .collect()
+    }
+
+    pub fn transfer(&self, to: Address, amount: U256) -> TxBuilder<'a> {
+        self.send(&ITIP20::transferCall { to, amount })
+    }
+
+    pub fn transfer_with_memo(&self, to: Address, amount: U256, memo: B256) -> TxBuilder<'a> {
+        self.send(&ITIP20::transferWithMemoCall { to, amount, memo })
+    }
+
+    pub fn approve(&self, spender: Address, amount: U256) -> TxBuilder<'a> {
+        self.send(&ITIP20::approveCall { spender, amount })
+    }
+
+    pub fn mint(&self, to: Address, amount: U256) -> TxBuilder<'a> {
+        self.send(&ITIP20::mintCall { to, amount })
+    }
+
+    pub fn burn(&self, amount: U256) -> TxBuilder<'a> {
+        self.send(&ITIP20::burnCall { amount })
+    }
+
+    pub fn grant_role(&self, role: B256, account: Address) -> TxBuilder<'a> {
+        self.send(&ITIP20::grantRoleCall { role, account })
+    }
+
+    /// Unsigned transfer for callers that manage nonces or access lists themselves
+    pub fn transfer_request(&self, to: Address, amount: U256) -> TransactionRequest {
+        self.request(&ITIP20::transferCall { to, amount })
+    }
+
+    /// Unsigned approval for callers that manage nonces themselves
+    pub fn approve_request(&self, spender: Address, amount: U256) -> TransactionRequest {
+        self.request(&ITIP20::approveCall { spender, amount })
+    }
+
+    fn request<C: SolCall>(&self, call: &C) -> TransactionRequest {
+        TransactionRequest::default()
+            .to(self.address)
+            .input(call.abi_encode().into())
+    }
+
+    fn send<C: SolCall>(&self, call: &C) -> TxBuilder<'a> {
+        self.client.tx().to(self.address).call(call)
+    }
+
+    async fn read<C: SolCall>(&self, call: C) -> Result<C::Return> {
+        call_view(self.client, self.address, call).await
+    }
+}
+
+/// Decoded result of `call` on `to`
+pub(crate) async fn call_view<C: SolCall>(
+    client: &TempoClient,
+    to: Address,
+    call: C,
+) -> Result<C::Return> {
+    let data = call_raw(client, to, call.abi_encode().into()).await?;
+    C::abi_decode_returns(&data)
+        .with_context(|| format!("Failed to decode {} from {}", C::SIGNATURE, to))
+}
+
+async fn call_raw(client: &TempoClient, to: Address, data: Bytes) -> Result<Bytes> {
+    let query = TransactionRequest::default().to(to).input(data.into());
+    client
+        .provider
+        .call(query)
+        .await
+        .with_context(|| format!("Call to {} failed", to))
+}
+
+/// Return data of each `(target, calldata)` read
+///
+/// One `aggregate3` through Multicall3 when it answers, one call per read
+/// otherwise. A read that fails fails the batch.
+async fn batch_read(client: &TempoClient, calls: Vec<(Address, Bytes)>) -> Result<Vec<Bytes>> {
+    let aggregate = IMulticall3::aggregate3Call {
+        calls: calls
+            .iter()
+            .map(|(target, data)| IMulticall3::Call3 {
+                target: *target,
+                allowFailure: true,
+                callData: data.clone(),
+            })
+            .collect(),
+    };
+    let batched = call_raw(client, MULTICALL3, aggregate.abi_encode().into())
+        .await
+        .ok()
+        .and_then(|data| IMulticall3::aggregate3Call::abi_decode_returns(&data).ok())
+        .filter(|results| results.len() == calls.len());
+
+    match batched {
+        Some(results) => results
+            .into_iter()
+            .zip(&calls)
+            .map(|(result, (target, _))| {
+                anyhow::ensure!(result.success, "Batched call to {} failed", target);
+                Ok(result.returnData)
+            })
+            .collect(),
+        None => {
+            let mut results = Vec::with_capacity(calls.len());
+            for (target, data) in calls {
+                results.push(call_raw(client, target, data).await?);
+            }
+            Ok(results)
+        }
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use crate::testing::MockTempoClient;
+
+    fn addr(n: u8) -> Address {
+        let mut bytes = [0u8; 20];
+        bytes[0] = 0x20;
+        bytes[1] = 0xc0;
+        bytes[19] = n;
+        Address::from(bytes)
+    }
+
+    #[tokio::test]
+    async fn test_balances_of_falls_back_without_multicall() {
+        let mock = MockTempoClient::default();
+        mock.on_call(addr(1), "70a08231", U256::from(7).to_be_bytes_vec());
+        mock.on_call(addr(2), "70a08231", U256::from(9).to_be_bytes_vec());
+        let client = mock.client();
+
+        let balances = Tip20Token::balances_of(&client, &[addr(1), addr(2)], mock.address())
+            .await
+            .unwrap();
+        assert_eq!(balances, vec![U256::from(7), U256::from(9)]);
+    }
+
+    #[tokio::test]
+    async fn test_decimals_read_once() {
+        let mock = MockTempoClient::default();
+        mock.on_call(addr(1), "313ce567", U256::from(18).to_be_bytes_vec());
+        let client = mock.client();
+        let token = Tip20Token::new(&client, addr(1));
+
+        assert_eq!(
+            token.units(3).await.unwrap(),
+            U256::from(3_000_000_000_000_000_000u128)
+        );
+        assert_eq!(token.decimals().await.unwrap(), 18);
+        assert_eq!(mock.count("eth_call"), 1);
+
+        let fixed = token.at(addr(2)).with_decimals(6);
+        assert_eq!(fixed.units(3).await.unwrap(), U256::from(3_000_000));
+        assert_eq!(mock.count("eth_call"), 1);
+    }
+
+    #[tokio::test]
+    async fn test_transfer_encodes_call() {
+        let mock = MockTempoClient::default();
+        let client = mock.client();
+        let to = addr(9);
+
+        Tip20Token::new(&client, addr(1))
+            .transfer(to, U256::from(5))
+            .send()
+            .await
+            .unwrap();
+
+        let sent = mock.sent();
+        assert_eq!(sent[0].to, Some(addr(1)));
+        assert_eq!(
+            sent[0].input,
+            Bytes::from(
+                ITIP20::transferCall {
+                    to,
+                    amount: U256::from(5)
+                }
+                .abi_encode()
+            )
+        );
+    }
+}
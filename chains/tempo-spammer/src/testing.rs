@@ -0,0 +1,921 @@
+//! Testing - Mock RPC client and harness for deterministic task tests
+//!
+//! Tasks talk to the chain only through [`TempoClient::provider`], so a
+//! client whose transport answers JSON-RPC from memory is enough to run any
+//! task without a node. [`MockTempoClient`] holds that in-memory chain:
+//!
+//! - `eth_call` and `eth_estimateGas` answers per contract and selector,
+//!   including reverts with revert data
+//! - storage slots for `eth_getStorageAt`, zero unless set
+//! - the wallet nonce, fees and gas estimate
+//! - `eth_createAccessList`, listing the called contract and reporting
+//!   [`ACCESS_LIST_SAVING`] less gas than the estimate
+//! - `eth_sendRawTransaction` failures such as `nonce too low`, returned once
+//!   each in the order they were queued
+//! - receipts for every accepted transaction: mined in the next block,
+//!   successful unless a revert was queued, with optional logs
+//! - no pending transactions: `eth_getTransactionByHash` finds nothing
+//!
+//! [`TaskHarness`] wraps a mock in a [`TaskContext`] with a minimal config and
+//! an optional throwaway database. [`MockBundler`] serves the ERC-4337 bundler
+//! calls of the `_aa_` tasks over a local HTTP port.
+//!
+//! ```rust,ignore
+//! use tempo_spammer::testing::TaskHarness;
+//!
+//! let harness = TaskHarness::new();
+//! harness.mock.on_call(token, "70a08231", U256::from(5_000_000).to_be_bytes_vec());
+//! harness.mock.fail_next_send("nonce too low: next nonce 1, tx nonce 0");
+//!
+//! let result = harness.run(&SendTokenTask::new()).await?;
+//! assert!(result.success);
+//! assert_eq!(harness.mock.sent().len(), 1);
+//! ```
+//!
+//! Available to this crate's tests and, with the `testing` feature, to other
+//! crates' tests.
+
+use crate::TempoClient;
+use crate::bundler::UserOperation;
+use crate::config::TempoSpammerConfig;
+use crate::tasks::{TaskContext, TaskResult, TempoTask};
+use alloy::consensus::{Transaction, TxEnvelope};
+use alloy::network::eip2718::Decodable2718;
+use alloy::providers::{Provider, ProviderBuilder};
+use alloy::rpc::client::ClientBuilder;
+use alloy::rpc::json_rpc::{RequestPacket, ResponsePacket, SerializedRequest};
+use alloy::rpc::types::Block;
+use alloy::signers::local::PrivateKeySigner;
+use alloy::transports::{TransportError, TransportErrorKind, TransportFut};
+use alloy_primitives::{Address, B256, Bytes, U256, keccak256};
+use anyhow::{Context, Result};
+use core_logic::database::DatabaseManager;
+use serde_json::{Value, json};
+use std::collections::{HashMap, VecDeque};
+use std::path::PathBuf;
+use std::sync::{Arc, Mutex};
+use std::task::Poll;
+
+/// Well-known development key, never funded on a real network
+pub const TEST_PRIVATE_KEY: &str =
+    "0xac0974bec39a17e36ba4a6b4d238ff944bacb478cbed5efcae784d7bf4f2ff80";
+
+/// Chain id the mock reports
+pub const TEST_CHAIN_ID: u64 = 42431;
+
+/// Gas `eth_createAccessList` reports below the mock's gas estimate
+pub const ACCESS_LIST_SAVING: u64 = 2_100;
+
+/// Config the harness runs tasks with; `rpc_url` goes nowhere, so the
+/// startup probe is off
+const TEST_CONFIG: &str = "rpc_url = \"http://mock.invalid\"\nchain_id = 42431\n\
+     worker_count = 1\ndefault_gas_limit = 500000\nmax_fee_per_gas = 150000000000\n\
+     priority_fee_per_gas = 1500000000\ntask_interval_min = 1\ntask_interval_max = 2\n\
+     task_timeout = 30\n[capabilities]\nenabled = false\n";
+
+/// JSON-RPC error code nodes use for reverts
+const REVERT_CODE: i64 = 3;
+
+/// A transaction the mock accepted
+#[derive(Debug, Clone, PartialEq, Eq)]
+pub struct SentTx {
+    pub hash: B256,
+    /// Nonce the transaction was signed with, `None` for types the mock cannot decode
+    pub nonce: Option<u64>,
+    /// Recipient, `None` for contract creations and undecodable types
+    pub to: Option<Address>,
+    pub input: Bytes,
+    /// Signed transaction as submitted
+    pub raw: Bytes,
+}
+
+/// Log attached to the next receipt
+#[derive(Debug, Clone)]
+struct MockLog {
+    address: Address,
+    topics: Vec<B256>,
+    data: Bytes,
+}
+
+impl MockLog {
+    /// Whether the log passes an `eth_getLogs` filter's address and topics
+    fn matches(&self, filter: &Value) -> bool {
+        // Filter members are one value, a list of alternatives, or null for any
+        fn accepts<T: serde::de::DeserializeOwned + PartialEq>(wanted: &Value, actual: &T) -> bool {
+            match wanted {
+                Value::Null => true,
+                Value::Array(options) => options.iter().any(|option| {
+                    serde_json::from_value::<T>(option.clone()).ok().as_ref() == Some(actual)
+                }),
+                single => serde_json::from_value::<T>(single.clone()).ok().as_ref() == Some(actual),
+            }
+        }
+
+        let address = filter.get("address").unwrap_or(&Value::Null);
+        let topics = filter
+            .get("topics")
+            .and_then(Value::as_array)
+            .cloned()
+            .unwrap_or_default();
+        accepts(address, &self.address)
+            && topics.iter().enumerate().all(|(i, wanted)| {
+                wanted.is_null()
+                    || self
+                        .topics
+                        .get(i)
+                        .is_some_and(|topic| accepts(wanted, topic))
+            })
+    }
+}
+
+/// Outcome of a programmed `eth_call`
+#[derive(Debug, Clone)]
+enum CallResponse {
+    Return(Bytes),
+    Revert(Bytes),
+}
+
+/// In-memory chain state behind the mock transport
+#[derive(Debug)]
+struct MockState {
+    from: Address,
+    chain_id: u64,
+    block_number: u64,
+    nonce: u64,
+    balance: U256,
+    gas_price: u128,
+    base_fee: u128,
+    priority_fee: u128,
+    gas_estimate: u64,
+    /// Answers by contract and selector; `None` matches any selector
+    calls: HashMap<(Address, Option<[u8; 4]>), CallResponse>,
+    code: HashMap<Address, Bytes>,
+    storage: HashMap<(Address, U256), U256>,
+    send_errors: VecDeque<String>,
+    receipt_reverts: VecDeque<bool>,
+    receipt_logs: Vec<MockLog>,
+    /// Status and logs of the next receipts, ahead of `receipt_reverts` and `receipt_logs`
+    queued_receipts: VecDeque<(bool, Vec<MockLog>)>,
+    /// Logs `eth_getLogs` filters
+    chain_logs: Vec<MockLog>,
+    receipts: HashMap<B256, Value>,
+    sent: Vec<SentTx>,
+    methods: Vec<String>,
+}
+
+impl MockState {
+    fn new(from: Address) -> Self {
+        Self {
+            from,
+            chain_id: TEST_CHAIN_ID,
+            block_number: 1,
+            nonce: 0,
+            balance: U256::from(10u128.pow(18)),
+            gas_price: 20_000_000_000,
+            base_fee: 10_000_000_000,
+            priority_fee: 1_000_000_000,
+            gas_estimate: 100_000,
+            calls: HashMap::new(),
+            code: HashMap::new(),
+            storage: HashMap::new(),
+            send_errors: VecDeque::new(),
+            receipt_reverts: VecDeque::new(),
+            receipt_logs: Vec::new(),
+            queued_receipts: VecDeque::new(),
+            chain_logs: Vec::new(),
+            receipts: HashMap::new(),
+            sent: Vec::new(),
+            methods: Vec::new(),
+        }
+    }
+
+    /// Answers one request with its `result` or `error` member
+    fn handle(&mut self, method: &str, params: &[Value]) -> std::result::Result<Value, Value> {
+        self.methods.push(method.to_string());
+        match method {
+            "eth_chainId" => Ok(quantity(self.chain_id)),
+            "eth_blockNumber" => Ok(quantity(self.block_number)),
+            "eth_getTransactionCount" => Ok(quantity(self.nonce)),
+            "eth_getBalance" => Ok(json!(self.balance)),
+            "eth_gasPrice" => Ok(json!(U256::from(self.gas_price))),
+            "eth_maxPriorityFeePerGas" => Ok(json!(U256::from(self.priority_fee))),
+            "eth_feeHistory" => Ok(json!({
+                "oldestBlock": quantity(self.block_number),
+                "baseFeePerGas": [U256::from(self.base_fee), U256::from(self.base_fee)],
+                "gasUsedRatio": [0.5],
+                "reward": [[U256::from(self.priority_fee)]],
+            })),
+            "eth_getBlockByNumber" => {
+                let mut block = Block::<alloy::rpc::types::Transaction>::default();
+                block.header.inner.number = self.block_number;
+                block.header.inner.base_fee_per_gas = Some(self.base_fee as u64);
+                block.header.inner.gas_limit = 30_000_000;
+                Ok(json!(block))
+            }
+            "eth_getCode" => {
+                let address = param::<Address>(params, 0)?;
+                Ok(json!(self.code.get(&address).cloned().unwrap_or_default()))
+            }
+            "eth_getStorageAt" => {
+                let address = param::<Address>(params, 0)?;
+                let slot = param::<U256>(params, 1)?;
+                let value = self.storage.get(&(address, slot)).copied();
+                Ok(json!(B256::from(value.unwrap_or_default())))
+            }
+            "eth_call" => self.call(params).map(|data| json!(data)),
+            "eth_estimateGas" => self.call(params).map(|_| quantity(self.gas_estimate)),
+            "eth_createAccessList" => self.call(params).map(|_| {
+                let to = params.first().and_then(|tx| tx.get("to")).cloned();
+                json!({
+                    "accessList": to.map(|to| vec![json!({
+                        "address": to,
+                        "storageKeys": [B256::ZERO],
+                    })]).unwrap_or_default(),
+                    "gasUsed": quantity(self.gas_estimate - ACCESS_LIST_SAVING),
+                })
+            }),
+            "eth_getLogs" => {
+                let filter = params.first().cloned().unwrap_or_default();
+                let logs: Vec<Value> = self
+                    .chain_logs
+                    .iter()
+                    .filter(|log| log.matches(&filter))
+                    .enumerate()
+                    .map(|(index, log)| {
+                        json!({
+                            "address": log.address,
+                            "topics": log.topics,
+                            "data": log.data,
+                            "blockNumber": quantity(1),
+                            "transactionHash": B256::ZERO,
+                            "transactionIndex": "0x0",
+                            "logIndex": quantity(index as u64),
+                            "removed": false,
+                        })
+                    })
+                    .collect();
+                Ok(json!(logs))
+            }
+            "eth_sendRawTransaction" => self.send(params),
+            "eth_getTransactionReceipt" => {
+                let hash = param::<B256>(params, 0)?;
+                Ok(self.receipts.get(&hash).cloned().unwrap_or(Value::Null))
+            }
+            // Every accepted transaction is mined, so the mempool is empty
+            "eth_getTransactionByHash" => Ok(Value::Null),
+            _ => Err(json!({"code": -32601, "message": format!("mock: {} not supported", method)})),
+        }
+    }
+
+    fn call(&self, params: &[Value]) -> std::result::Result<Bytes, Value> {
+        let tx = params.first().cloned().unwrap_or_default();
+        let Some(to) = tx
+            .get("to")
+            .and_then(|to| serde_json::from_value(to.clone()).ok())
+        else {
+            // Deployments estimate fine and return no data
+            return Ok(Bytes::new());
+        };
+        let input: Bytes = tx
+            .get("input")
+            .or_else(|| tx.get("data"))
+            .and_then(|data| serde_json::from_value(data.clone()).ok())
+            .unwrap_or_default();
+        let selector = input.get(..4).map(|s| <[u8; 4]>::try_from(s).unwrap());
+
+        let response = selector
+            .and_then(|selector| self.calls.get(&(to, Some(selector))))
+            .or_else(|| self.calls.get(&(to, None)));
+        match response {
+            Some(CallResponse::Return(data)) => Ok(data.clone()),
+            Some(CallResponse::Revert(data)) => Err(json!({
+                "code": REVERT_CODE,
+                "message": "execution reverted",
+                "data": data,
+            })),
+            // Calls to accounts without code succeed with no data
+            None => Ok(Bytes::new()),
+        }
+    }
+
+    fn send(&mut self, params: &[Value]) -> std::result::Result<Value, Value> {
+        if let Some(message) = self.send_errors.pop_front() {
+            return Err(json!({"code": -32000, "message": message}));
+        }
+        let raw = param::<Bytes>(params, 0)?;
+        let hash = keccak256(&raw);
+
+        let decoded = TxEnvelope::decode_2718(&mut raw.as_ref()).ok();
+        let nonce = decoded.as_ref().map(|tx| tx.nonce());
+        let to = decoded.as_ref().and_then(|tx| tx.to());
+        let input = decoded
+            .as_ref()
+            .map(|tx| tx.input().clone())
+            .unwrap_or_default();
+        let contract_address = match (&decoded, nonce) {
+            (Some(tx), Some(nonce)) if tx.is_create() => Some(self.from.create(nonce)),
+            _ => None,
+        };
+
+        self.block_number += 1;
+        self.nonce = nonce.map_or(self.nonce + 1, |n| n.max(self.nonce) + 1);
+        let (status, logs) = match self.queued_receipts.pop_front() {
+            Some(queued) => queued,
+            None => (
+                !self.receipt_reverts.pop_front().unwrap_or(false),
+                std::mem::take(&mut self.receipt_logs),
+            ),
+        };
+        let logs: Vec<Value> = logs
+            .into_iter()
+            .enumerate()
+            .map(|(index, log)| {
+                json!({
+                    "address": log.address,
+                    "topics": log.topics,
+                    "data": log.data,
+                    "blockNumber": quantity(self.block_number),
+                    "transactionHash": hash,
+                    "transactionIndex": "0x0",
+                    "logIndex": quantity(index as u64),
+                    "removed": false,
+                })
+            })
+            .collect();
+
+        self.receipts.insert(
+            hash,
+            json!({
+                "type": "0x2",
+                "status": if status { "0x1" } else { "0x0" },
+                "transactionHash": hash,
+                "transactionIndex": "0x0",
+                "blockHash": B256::from(U256::from(self.block_number)),
+                "blockNumber": quantity(self.block_number),
+                "from": self.from,
+                "to": to,
+                "contractAddress": contract_address,
+                "gasUsed": quantity(self.gas_estimate),
+                "cumulativeGasUsed": quantity(self.gas_estimate),
+                "effectiveGasPrice": U256::from(self.gas_price),
+                "logs": logs,
+                "logsBloom": Bytes::from(vec![0u8; 256]),
+            }),
+        );
+        self.sent.push(SentTx {
+            hash,
+            nonce,
+            to,
+            input,
+            raw,
+        });
+        Ok(json!(hash))
+    }
+}
+
+fn quantity(value: u64) -> Value {
+    json!(format!("{:#x}", value))
+}
+
+fn param<T: serde::de::DeserializeOwned>(
+    params: &[Value],
+    index: usize,
+) -> std::result::Result<T, Value> {
+    params
+        .get(index)
+        .and_then(|value| serde_json::from_value(value.clone()).ok())
+        .ok_or_else(|| json!({"code": -32602, "message": format!("invalid param {}", index)}))
+}
+
+/// Transport answering every request from the shared [`MockState`]
+#[derive(Clone)]
+struct MockTransport {
+    state: Arc<Mutex<MockState>>,
+}
+
+impl MockTransport {
+    fn respond(&self, request: &SerializedRequest) -> Value {
+        let params: Vec<Value> = request
+            .params()
+            .and_then(|raw| serde_json::from_str(raw.get()).ok())
+            .unwrap_or_default();
+        let mut response = json!({"jsonrpc": "2.0", "id": request.id()});
+        match self.state.lock().unwrap().handle(request.method(), &params) {
+            Ok(result) => response["result"] = result,
+            Err(error) => response["error"] = error,
+        }
+        response
+    }
+}
+
+impl tower::Service<RequestPacket> for MockTransport {
+    type Response = ResponsePacket;
+    type Error = TransportError;
+    type Future = TransportFut<'static>;
+
+    fn poll_ready(&mut self, _cx: &mut std::task::Context<'_>) -> Poll<Result<(), Self::Error>> {
+        Poll::Ready(Ok(()))
+    }
+
+    fn call(&mut self, packet: RequestPacket) -> Self::Future {
+        let response = match &packet {
+            RequestPacket::Single(request) => self.respond(request),
+            RequestPacket::Batch(requests) => {
+                Value::Array(requests.iter().map(|r| self.respond(r)).collect())
+            }
+        };
+        Box::pin(async move {
+            serde_json::from_str(&response.to_string()).map_err(TransportErrorKind::custom)
+        })
+    }
+}
+
+/// A [`TempoClient`] backed by a programmable in-memory chain
+///
+/// Clones share the same chain, so a test can keep one to program responses
+/// and inspect what the task sent.
+#[derive(Clone)]
+pub struct MockTempoClient {
+    client: TempoClient,
+    state: Arc<Mutex<MockState>>,
+}
+
+impl std::fmt::Debug for MockTempoClient {
+    fn fmt(&self, f: &mut std::fmt::Formatter<'_>) -> std::fmt::Result {
+        f.debug_struct("MockTempoClient")
+            .field("address", &self.address())
+            .finish_non_exhaustive()
+    }
+}
+
+impl Default for MockTempoClient {
+    fn default() -> Self {
+        Self::new(TEST_PRIVATE_KEY).expect("test key is valid")
+    }
+}
+
+impl MockTempoClient {
+    /// Creates a mock chain and a client signing with `private_key`
+    pub fn new(private_key: &str) -> Result<Self> {
+        let signer: PrivateKeySigner =
+            private_key.parse().context("Failed to parse private key")?;
+        let state = Arc::new(Mutex::new(MockState::new(signer.address())));
+        let transport = MockTransport {
+            state: state.clone(),
+        };
+
+        let provider: Arc<dyn Provider + Send + Sync> = Arc::new(
+            ProviderBuilder::new()
+                .wallet(signer.clone())
+                .connect_client(
+                    ClientBuilder::default()
+                        .layer(crate::audit::AuditLayer)
+                        .transport(transport, true),
+                ),
+        );
+        let client = TempoClient {
+            provider,
+            signer,
+            chain_id: TEST_CHAIN_ID,
+            proxy_config: None,
+            proxy_index: None,
+            nonce_manager: None,
+            robust_nonce_manager: None,
+            use_pending_count: false,
+        };
+        Ok(Self { client, state })
+    }
+
+    /// Client to hand to the code under test
+    pub fn client(&self) -> TempoClient {
+        self.client.clone()
+    }
+
+    /// Wallet address of the client
+    pub fn address(&self) -> Address {
+        self.client.address()
+    }
+
+    /// Returns `data` for calls to `to` with the hex `selector`, or any selector when empty
+    pub fn on_call(&self, to: Address, selector: &str, data: impl Into<Bytes>) {
+        self.program(to, selector, CallResponse::Return(data.into()));
+    }
+
+    /// Reverts calls and gas estimates to `to` with `revert_data`
+    pub fn on_call_revert(&self, to: Address, selector: &str, revert_data: impl Into<Bytes>) {
+        self.program(to, selector, CallResponse::Revert(revert_data.into()));
+    }
+
+    fn program(&self, to: Address, selector: &str, response: CallResponse) {
+        let selector = (!selector.is_empty()).then(|| {
+            let bytes = hex::decode(selector.trim_start_matches("0x")).expect("hex selector");
+            <[u8; 4]>::try_from(bytes.as_slice()).expect("4-byte selector")
+        });
+        self.state
+            .lock()
+            .unwrap()
+            .calls
+            .insert((to, selector), response);
+    }
+
+    /// Sets the nonce `eth_getTransactionCount` reports
+    pub fn set_nonce(&self, nonce: u64) {
+        self.state.lock().unwrap().nonce = nonce;
+    }
+
+    /// Sets the native balance `eth_getBalance` reports
+    pub fn set_balance(&self, balance: U256) {
+        self.state.lock().unwrap().balance = balance;
+    }
+
+    /// Sets the legacy gas price, the base fee and the gas estimate (also used as gas used)
+    pub fn set_gas(&self, gas_price: u128, base_fee: u128, gas_estimate: u64) {
+        let mut state = self.state.lock().unwrap();
+        state.gas_price = gas_price;
+        state.base_fee = base_fee;
+        state.gas_estimate = gas_estimate;
+    }
+
+    /// Sets the code `eth_getCode` returns for `address`
+    pub fn set_code(&self, address: Address, code: impl Into<Bytes>) {
+        self.state.lock().unwrap().code.insert(address, code.into());
+    }
+
+    /// Sets the value `eth_getStorageAt` returns for `slot` of `address`
+    pub fn set_storage(&self, address: Address, slot: U256, value: U256) {
+        self.state
+            .lock()
+            .unwrap()
+            .storage
+            .insert((address, slot), value);
+    }
+
+    /// Rejects the next `eth_sendRawTransaction` with `message`
+    ///
+    /// Queued failures are returned one per send, in order.
+    pub fn fail_next_send(&self, message: &str) {
+        self.state
+            .lock()
+            .unwrap()
+            .send_errors
+            .push_back(message.to_string());
+    }
+
+    /// Mines the next accepted transaction with a failed status
+    pub fn revert_next_receipt(&self) {
+        self.state.lock().unwrap().receipt_reverts.push_back(true);
+    }
+
+    /// Adds a log to the receipt of the next accepted transaction
+    pub fn push_receipt_log(&self, address: Address, topics: Vec<B256>, data: impl Into<Bytes>) {
+        self.state.lock().unwrap().receipt_logs.push(MockLog {
+            address,
+            topics,
+            data: data.into(),
+        });
+    }
+
+    /// Mines the next accepted transaction with `status` and exactly `logs`
+    ///
+    /// Queued receipts are used one per send, in order, before anything set
+    /// with [`Self::revert_next_receipt`] or [`Self::push_receipt_log`].
+    pub fn queue_receipt(&self, status: bool, logs: Vec<(Address, Vec<B256>, Bytes)>) {
+        let logs = logs
+            .into_iter()
+            .map(|(address, topics, data)| MockLog {
+                address,
+                topics,
+                data,
+            })
+            .collect();
+        self.state
+            .lock()
+            .unwrap()
+            .queued_receipts
+            .push_back((status, logs));
+    }
+
+    /// Adds a log for `eth_getLogs` to return when its filter matches
+    pub fn push_log(&self, address: Address, topics: Vec<B256>, data: impl Into<Bytes>) {
+        self.state.lock().unwrap().chain_logs.push(MockLog {
+            address,
+            topics,
+            data: data.into(),
+        });
+    }
+
+    /// Transactions accepted so far
+    pub fn sent(&self) -> Vec<SentTx> {
+        self.state.lock().unwrap().sent.clone()
+    }
+
+    /// JSON-RPC methods requested so far, in order
+    pub fn methods(&self) -> Vec<String> {
+        self.state.lock().unwrap().methods.clone()
+    }
+
+    /// How often `method` was requested
+    pub fn count(&self, method: &str) -> usize {
+        self.state
+            .lock()
+            .unwrap()
+            .methods
+            .iter()
+            .filter(|m| *m == method)
+            .count()
+    }
+}
+
+/// What [`MockBundler`] received and has yet to fail
+#[derive(Default)]
+struct BundlerState {
+    operations: Vec<UserOperation>,
+    /// Whether each accepted operation's call succeeded, by hash
+    outcomes: HashMap<B256, bool>,
+    revert_next: bool,
+}
+
+impl BundlerState {
+    fn handle(&mut self, method: &str, params: &[Value]) -> std::result::Result<Value, Value> {
+        match method {
+            "eth_supportedEntryPoints" => Ok(json!([params.first().cloned().unwrap_or_default()])),
+            "eth_estimateUserOperationGas" => Ok(json!({
+                "preVerificationGas": quantity(50_000),
+                "verificationGasLimit": quantity(150_000),
+                "callGasLimit": quantity(100_000),
+            })),
+            "eth_sendUserOperation" => {
+                let op: UserOperation = param(params, 0)?;
+                let hash = keccak256(serde_json::to_vec(&op).unwrap_or_default());
+                self.outcomes
+                    .insert(hash, !std::mem::take(&mut self.revert_next));
+                self.operations.push(op);
+                Ok(json!(hash))
+            }
+            "eth_getUserOperationReceipt" => {
+                let hash: B256 = param(params, 0)?;
+                Ok(match self.outcomes.get(&hash) {
+                    Some(success) => json!({
+                        "userOpHash": hash,
+                        "success": success,
+                        "actualGasUsed": quantity(180_000),
+                        "actualGasCost": quantity(180_000 * 1_000_000_000),
+                        "receipt": {
+                            "transactionHash": keccak256(hash),
+                            "blockNumber": quantity(self.operations.len() as u64),
+                        },
+                    }),
+                    None => Value::Null,
+                })
+            }
+            _ => Err(json!({"code": -32601, "message": format!("method {} not found", method)})),
+        }
+    }
+}
+
+/// An ERC-4337 bundler on a local HTTP port
+///
+/// Estimates fixed gas, accepts every operation and has its receipt ready
+/// at once, successful unless [`Self::revert_next_operation`] was called.
+/// Point `[aa] bundler_url` at [`Self::url`].
+pub struct MockBundler {
+    url: String,
+    state: Arc<Mutex<BundlerState>>,
+}
+
+impl MockBundler {
+    pub async fn start() -> Result<Self> {
+        let listener = tokio::net::TcpListener::bind("127.0.0.1:0").await?;
+        let url = format!("http://{}", listener.local_addr()?);
+        let state = Arc::new(Mutex::new(BundlerState::default()));
+        let server_state = state.clone();
+        tokio::spawn(async move {
+            while let Ok((stream, _)) = listener.accept().await {
+                tokio::spawn(serve_bundler_request(stream, server_state.clone()));
+            }
+        });
+        Ok(Self { url, state })
+    }
+
+    pub fn url(&self) -> &str {
+        &self.url
+    }
+
+    /// Operations accepted so far
+    pub fn operations(&self) -> Vec<UserOperation> {
+        self.state.lock().unwrap().operations.clone()
+    }
+
+    /// Reports the next accepted operation's call as reverted
+    pub fn revert_next_operation(&self) {
+        self.state.lock().unwrap().revert_next = true;
+    }
+}
+
+/// Answers one JSON-RPC request and closes the connection
+async fn serve_bundler_request(
+    mut stream: tokio::net::TcpStream,
+    state: Arc<Mutex<BundlerState>>,
+) -> std::io::Result<()> {
+    use tokio::io::{AsyncReadExt, AsyncWriteExt};
+
+    let mut request = Vec::new();
+    let mut buf = [0u8; 4096];
+    let body_start = loop {
+        let n = stream.read(&mut buf).await?;
+        if n == 0 {
+            return Ok(());
+        }
+        request.extend_from_slice(&buf[..n]);
+        if let Some(end) = request.windows(4).position(|w| w == b"\r\n\r\n") {
+            break end + 4;
+        }
+    };
+    let headers = String::from_utf8_lossy(&request[..body_start]).to_lowercase();
+    let length: usize = headers
+        .lines()
+        .find_map(|line| line.strip_prefix("content-length:"))
+        .and_then(|value| value.trim().parse().ok())
+        .unwrap_or(0);
+    while request.len() < body_start + length {
+        let n = stream.read(&mut buf).await?;
+        if n == 0 {
+            break;
+        }
+        request.extend_from_slice(&buf[..n]);
+    }
+
+    let body: Value = serde_json::from_slice(&request[body_start..]).unwrap_or_default();
+    let method = body["method"].as_str().unwrap_or_default();
+    let params = body["params"].as_array().cloned().unwrap_or_default();
+    let outcome = state.lock().unwrap().handle(method, &params);
+    let response = match outcome {
+        Ok(result) => json!({"jsonrpc": "2.0", "id": body["id"], "result": result}),
+        Err(error) => json!({"jsonrpc": "2.0", "id": body["id"], "error": error}),
+    }
+    .to_string();
+    stream
+        .write_all(
+            format!(
+                "HTTP/1.1 200 OK\r\nContent-Type: application/json\r\nContent-Length: {}\r\nConnection: close\r\n\r\n{}",
+                response.len(),
+                response
+            )
+            .as_bytes(),
+        )
+        .await?;
+    stream.shutdown().await
+}
+
+/// Runs tasks against a [`MockTempoClient`]
+pub struct TaskHarness {
+    pub mock: MockTempoClient,
+    pub config: TempoSpammerConfig,
+    pub db: Option<Arc<DatabaseManager>>,
+    db_path: Option<PathBuf>,
+}
+
+impl Default for TaskHarness {
+    fn default() -> Self {
+        Self::new()
+    }
+}
+
+impl TaskHarness {
+    /// Harness with a fresh mock chain, a minimal config and no database
+    pub fn new() -> Self {
+        Self {
+            mock: MockTempoClient::default(),
+            config: toml::from_str(TEST_CONFIG).expect("test config parses"),
+            db: None,
+            db_path: None,
+        }
+    }
+
+    /// Adds a database in a temporary file, removed when the harness drops
+    pub async fn with_db(mut self) -> Result<Self> {
+        let path = std::env::temp_dir().join(format!(
+            "tempo-harness-{}-{}.db",
+            std::process::id(),
+            fastrand::u64(..)
+        ));
+        let db = DatabaseManager::new(path.to_str().context("Non UTF-8 temp path")?).await?;
+        self.db = Some(Arc::new(db));
+        self.db_path = Some(path);
+        Ok(self)
+    }
+
+    /// Context a worker would build for the mock wallet
+    pub fn context(&self) -> TaskContext {
+        TaskContext::new(self.mock.client(), self.config.clone(), self.db.clone())
+    }
+
+    /// Runs `task` once with [`Self::context`]
+    pub async fn run(&self, task: &dyn TempoTask) -> Result<TaskResult> {
+        task.run(&self.context()).await
+    }
+}
+
+impl Drop for TaskHarness {
+    fn drop(&mut self) {
+        if let Some(path) = &self.db_path {
+            for suffix in ["", "-wal", "-shm"] {
+                let mut file = path.clone().into_os_string();
+                file.push(suffix);
+                let _ = std::fs::remove_file(file);
+            }
+        }
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use alloy::rpc::types::TransactionRequest;
+
+    fn token() -> Address {
+        "0x20C0000000000000000000000000000000000001"
+            .parse()
+            .unwrap()
+    }
+
+    #[tokio::test]
+    async fn test_programmed_calls_and_reverts() {
+        let mock = MockTempoClient::default();
+        mock.on_call(token(), "70a08231", vec![0xab; 32]);
+        mock.on_call_revert(token(), "", vec![0x08, 0xc3, 0x79, 0xa0]);
+        let provider = mock.client().provider;
+
+        let balance_of = TransactionRequest::default()
+            .to(token())
+            .input(hex::decode("70a08231").unwrap().into());
+        assert_eq!(
+            provider.call(balance_of).await.unwrap(),
+            Bytes::from(vec![0xab; 32])
+        );
+
+        let transfer = TransactionRequest::default()
+            .to(token())
+            .input(hex::decode("a9059cbb").unwrap().into());
+        let err = provider.call(transfer).await.unwrap_err();
+        let revert = err.as_error_resp().and_then(|e| e.as_revert_data());
+        assert_eq!(revert, Some(Bytes::from(vec![0x08, 0xc3, 0x79, 0xa0])));
+    }
+
+    #[tokio::test]
+    async fn test_send_mines_receipt_and_advances_nonce() {
+        let mock = MockTempoClient::default();
+        mock.set_nonce(7);
+        mock.fail_next_send("nonce too low: next nonce 7, tx nonce 6");
+        mock.revert_next_receipt();
+        let client = mock.client();
+
+        let tx = TransactionRequest::default()
+            .to(token())
+            .from(mock.address())
+            .nonce(7);
+        let err = client
+            .provider
+            .send_transaction(tx.clone())
+            .await
+            .unwrap_err();
+        assert!(err.to_string().contains("nonce too low"));
+
+        let receipt = client
+            .provider
+            .send_transaction(tx)
+            .await
+            .unwrap()
+            .get_receipt()
+            .await
+            .unwrap();
+        assert!(!receipt.status());
+        assert_eq!(receipt.gas_used, 100_000);
+        assert_eq!(client.get_pending_nonce("").await.unwrap(), 8);
+
+        let sent = mock.sent();
+        assert_eq!(sent.len(), 1);
+        assert_eq!((sent[0].nonce, sent[0].to), (Some(7), Some(token())));
+        assert_eq!(mock.count("eth_sendRawTransaction"), 2);
+    }
+
+    #[tokio::test]
+    async fn test_deployment_receipt_has_contract_address() {
+        let mock = MockTempoClient::default();
+        let mut tx = TransactionRequest::default()
+            .from(mock.address())
+            .nonce(0)
+            .input(vec![0x60, 0x00].into());
+        tx.to = Some(alloy_primitives::TxKind::Create);
+        let receipt = mock
+            .client()
+            .provider
+            .send_transaction(tx)
+            .await
+            .unwrap()
+            .get_receipt()
+            .await
+            .unwrap();
+        assert_eq!(receipt.contract_address, Some(mock.address().create(0)));
+    }
+}
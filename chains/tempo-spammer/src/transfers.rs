@@ -0,0 +1,349 @@
+//! Pool Transfers - In-flight transfers between the pool's own wallets
+//!
+//! With `[recipients] pool_share` above 0, that share of transfers goes to
+//! another wallet of the pool instead of an outside address:
+//!
+//! ```toml
+//! [recipients]
+//! pool_share = 0.3          # 30% of transfers stay inside the pool
+//! incoming_wait_secs = 10
+//! ```
+//!
+//! A wallet can then receive while a worker is sending from it, and balance
+//! pre-checks read a number that is about to move. [`TransferTracker`]
+//! keeps the workers in step:
+//!
+//! - [`TransferTracker::pick_recipient`] never returns the sender and prefers
+//!   wallets nothing is on its way to
+//! - each transfer to a pool wallet is held as an [`InFlightTransfer`] from
+//!   before the send until its receipt, annotating the sender with a pending
+//!   outgoing and the recipient with a pending incoming amount
+//! - [`TaskContext::spendable_balance`](crate::tasks::TaskContext::spendable_balance)
+//!   subtracts pending outgoing amounts and, when the wallet is short only
+//!   because incoming transfers have not landed, waits up to
+//!   `incoming_wait_secs` for them instead of skipping the task
+//!
+//! Wallets join the recipient set when a worker first leases them, whatever
+//! `pool_share` is; [`TransferTracker::other_wallet`] draws from them for
+//! tasks that move assets between pool wallets.
+
+use crate::config::RecipientSettings;
+use crate::gas_usage::wait_for_receipt;
+use alloy::providers::Provider;
+use alloy_primitives::{Address, B256, U256};
+use rand::Rng;
+use rand::seq::SliceRandom;
+use std::collections::HashMap;
+use std::sync::{Arc, Mutex};
+use std::time::Duration;
+
+/// Longest an in-flight transfer is held while waiting for its receipt
+const SETTLE_TIMEOUT: Duration = Duration::from_secs(120);
+const SETTLE_POLL: Duration = Duration::from_secs(2);
+const INCOMING_POLL: Duration = Duration::from_millis(250);
+
+/// Amounts of one token on their way to and from a wallet
+#[derive(Debug, Clone, Copy, Default, PartialEq, Eq)]
+pub struct PendingDeltas {
+    pub incoming: U256,
+    pub outgoing: U256,
+}
+
+impl PendingDeltas {
+    /// `balance` minus what is still on its way out
+    pub fn spendable(&self, balance: U256) -> U256 {
+        balance.saturating_sub(self.outgoing)
+    }
+}
+
+#[derive(Debug, Default)]
+struct Pending {
+    deltas: HashMap<(Address, Address), PendingDeltas>,
+}
+
+impl Pending {
+    fn add(&mut self, transfer: &InFlightTransfer) {
+        let sent = self
+            .deltas
+            .entry((transfer.from, transfer.token))
+            .or_default();
+        sent.outgoing += transfer.amount;
+        let received = self
+            .deltas
+            .entry((transfer.to, transfer.token))
+            .or_default();
+        received.incoming += transfer.amount;
+    }
+
+    fn remove(&mut self, transfer: &InFlightTransfer) {
+        for (wallet, outgoing) in [(transfer.from, true), (transfer.to, false)] {
+            let key = (wallet, transfer.token);
+            if let Some(deltas) = self.deltas.get_mut(&key) {
+                let side = if outgoing {
+                    &mut deltas.outgoing
+                } else {
+                    &mut deltas.incoming
+                };
+                *side = side.saturating_sub(transfer.amount);
+                if *deltas == PendingDeltas::default() {
+                    self.deltas.remove(&key);
+                }
+            }
+        }
+    }
+
+    fn receiving(&self, wallet: Address) -> bool {
+        self.deltas
+            .iter()
+            .any(|((w, _), deltas)| *w == wallet && !deltas.incoming.is_zero())
+    }
+}
+
+/// Pool wallets usable as recipients and the transfers in flight between them
+#[derive(Debug)]
+pub struct TransferTracker {
+    settings: RecipientSettings,
+    wallets: Mutex<Vec<Address>>,
+    pending: Mutex<Pending>,
+}
+
+impl TransferTracker {
+    pub fn new(settings: RecipientSettings) -> Self {
+        Self {
+            settings,
+            wallets: Mutex::new(Vec::new()),
+            pending: Mutex::new(Pending::default()),
+        }
+    }
+
+    /// Adds a pool wallet to the recipient set
+    pub fn register(&self, wallet: Address) {
+        let mut wallets = self.wallets.lock().unwrap();
+        if !wallets.contains(&wallet) {
+            wallets.push(wallet);
+        }
+    }
+
+    /// Pool wallets known so far
+    pub fn wallets(&self) -> Vec<Address> {
+        self.wallets.lock().unwrap().clone()
+    }
+
+    /// A pool wallet for `sender` to send to, drawn with `pool_share` chance
+    ///
+    /// Returns `None` when the draw picks an outside address or no other
+    /// wallet is known. Wallets with a transfer already on its way to them
+    /// are only picked when every other wallet is receiving too.
+    pub fn pick_recipient(&self, sender: Address) -> Option<Address> {
+        let mut rng = rand::thread_rng();
+        if !rng.gen_bool(self.settings.pool_share) {
+            return None;
+        }
+        let candidates: Vec<Address> = self
+            .wallets()
+            .into_iter()
+            .filter(|wallet| *wallet != sender)
+            .collect();
+        let pending = self.pending.lock().unwrap();
+        let idle: Vec<Address> = candidates
+            .iter()
+            .copied()
+            .filter(|wallet| !pending.receiving(*wallet))
+            .collect();
+        if idle.is_empty() {
+            candidates.choose(&mut rng).copied()
+        } else {
+            idle.choose(&mut rng).copied()
+        }
+    }
+
+    /// A random pool wallet other than `sender`, regardless of `pool_share`
+    pub fn other_wallet(&self, sender: Address) -> Option<Address> {
+        self.wallets()
+            .into_iter()
+            .filter(|wallet| *wallet != sender)
+            .collect::<Vec<_>>()
+            .choose(&mut rand::thread_rng())
+            .copied()
+    }
+
+    /// Whether `wallet` is a known pool wallet
+    pub fn is_pool_wallet(&self, wallet: Address) -> bool {
+        self.wallets.lock().unwrap().contains(&wallet)
+    }
+
+    /// Registers a transfer until the returned guard is dropped
+    ///
+    /// Transfers to outside addresses are not tracked.
+    pub fn begin(
+        self: &Arc<Self>,
+        from: Address,
+        to: Address,
+        token: Address,
+        amount: U256,
+    ) -> InFlightTransfer {
+        if from == to || !self.is_pool_wallet(to) {
+            return InFlightTransfer::untracked();
+        }
+        let transfer = InFlightTransfer {
+            tracker: Some(self.clone()),
+            from,
+            to,
+            token,
+            amount,
+        };
+        self.pending.lock().unwrap().add(&transfer);
+        transfer
+    }
+
+    /// Pending amounts of `token` to and from `wallet`
+    pub fn pending(&self, wallet: Address, token: Address) -> PendingDeltas {
+        self.pending
+            .lock()
+            .unwrap()
+            .deltas
+            .get(&(wallet, token))
+            .copied()
+            .unwrap_or_default()
+    }
+
+    /// Waits until no `token` transfer to `wallet` is in flight, up to `incoming_wait_secs`
+    ///
+    /// Returns whether the incoming transfers settled in time.
+    pub async fn wait_incoming(&self, wallet: Address, token: Address) -> bool {
+        let deadline =
+            tokio::time::Instant::now() + Duration::from_secs(self.settings.incoming_wait_secs);
+        loop {
+            if self.pending(wallet, token).incoming.is_zero() {
+                return true;
+            }
+            if tokio::time::Instant::now() + INCOMING_POLL > deadline {
+                return false;
+            }
+            tokio::time::sleep(INCOMING_POLL).await;
+        }
+    }
+}
+
+/// A transfer between pool wallets, pending until dropped
+#[derive(Debug)]
+#[must_use = "dropping the transfer clears it at once"]
+pub struct InFlightTransfer {
+    tracker: Option<Arc<TransferTracker>>,
+    from: Address,
+    to: Address,
+    token: Address,
+    amount: U256,
+}
+
+impl InFlightTransfer {
+    /// A transfer nothing waits on
+    pub fn untracked() -> Self {
+        Self {
+            tracker: None,
+            from: Address::ZERO,
+            to: Address::ZERO,
+            token: Address::ZERO,
+            amount: U256::ZERO,
+        }
+    }
+
+    pub fn is_tracked(&self) -> bool {
+        self.tracker.is_some()
+    }
+
+    /// Keeps the transfer pending until `tx_hash` has a receipt, in the background
+    pub fn settle_after(self, provider: Arc<dyn Provider + Send + Sync>, tx_hash: B256) {
+        if self.tracker.is_none() {
+            return;
+        }
+        tokio::spawn(async move {
+            wait_for_receipt(provider.as_ref(), tx_hash, SETTLE_TIMEOUT, SETTLE_POLL).await;
+            drop(self);
+        });
+    }
+}
+
+impl Drop for InFlightTransfer {
+    fn drop(&mut self) {
+        if let Some(tracker) = &self.tracker {
+            tracker.pending.lock().unwrap().remove(self);
+        }
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    const WALLET_A: Address = Address::repeat_byte(0xaa);
+    const WALLET_B: Address = Address::repeat_byte(0xbb);
+    const WALLET_C: Address = Address::repeat_byte(0xcc);
+    const TOKEN: Address = Address::repeat_byte(0x20);
+
+    fn tracker() -> Arc<TransferTracker> {
+        let tracker = Arc::new(TransferTracker::new(RecipientSettings {
+            pool_share: 1.0,
+            incoming_wait_secs: 1,
+        }));
+        for wallet in [WALLET_A, WALLET_B, WALLET_C] {
+            tracker.register(wallet);
+        }
+        tracker
+    }
+
+    #[test]
+    fn test_pending_deltas_follow_transfer_lifetime() {
+        let tracker = tracker();
+        let transfer = tracker.begin(WALLET_A, WALLET_B, TOKEN, U256::from(40));
+        assert!(transfer.is_tracked());
+        assert_eq!(tracker.pending(WALLET_A, TOKEN).outgoing, U256::from(40));
+        assert_eq!(tracker.pending(WALLET_B, TOKEN).incoming, U256::from(40));
+        assert_eq!(
+            tracker.pending(WALLET_A, TOKEN).spendable(U256::from(100)),
+            U256::from(60)
+        );
+
+        drop(transfer);
+        assert_eq!(tracker.pending(WALLET_A, TOKEN), PendingDeltas::default());
+        assert_eq!(tracker.pending(WALLET_B, TOKEN), PendingDeltas::default());
+
+        // Outside recipients are not tracked
+        let outside = tracker.begin(WALLET_A, Address::repeat_byte(0x99), TOKEN, U256::from(1));
+        assert!(!outside.is_tracked());
+    }
+
+    #[test]
+    fn test_pick_recipient_skips_sender_and_receiving_wallets() {
+        let tracker = tracker();
+        let _to_b = tracker.begin(WALLET_C, WALLET_B, TOKEN, U256::from(1));
+        for _ in 0..20 {
+            assert_eq!(tracker.pick_recipient(WALLET_A), Some(WALLET_C));
+        }
+
+        let _to_c = tracker.begin(WALLET_B, WALLET_C, TOKEN, U256::from(1));
+        for _ in 0..20 {
+            assert_ne!(tracker.pick_recipient(WALLET_A), Some(WALLET_A));
+        }
+
+        let disabled = TransferTracker::new(RecipientSettings::default());
+        disabled.register(WALLET_B);
+        assert_eq!(disabled.pick_recipient(WALLET_A), None);
+    }
+
+    #[tokio::test]
+    async fn test_wait_incoming_returns_when_settled() {
+        let tracker = tracker();
+        let transfer = tracker.begin(WALLET_A, WALLET_B, TOKEN, U256::from(5));
+        let waiter = {
+            let tracker = tracker.clone();
+            tokio::spawn(async move { tracker.wait_incoming(WALLET_B, TOKEN).await })
+        };
+        tokio::time::sleep(Duration::from_millis(300)).await;
+        drop(transfer);
+        assert!(waiter.await.unwrap());
+
+        let _stuck = tracker.begin(WALLET_A, WALLET_C, TOKEN, U256::from(5));
+        assert!(!tracker.wait_incoming(WALLET_C, TOKEN).await);
+    }
+}
@@ -0,0 +1,503 @@
+//! Transaction Builder - One way to send a call and wait for it
+//!
+//! Most tasks send their transactions the same way: encode a call, estimate
+//! its gas, take the next nonce, price it at Tempo's fixed fees, retry once
+//! or twice when the node reports a nonce race, then wait for the receipt.
+//! [`TxBuilder`], returned by [`TempoClient::tx`], does all of that:
+//!
+//! - **Gas**: estimated once with `eth_estimateGas` unless a limit is given
+//! - **Nonce**: reserved from the robust nonce manager when the client has
+//!   one, otherwise taken from the client's nonce cache
+//! - **Fees**: from the [`FeeProfile`], `Normal` by default
+//! - **Retry**: `nonce too low` and `already known` reset the nonce cache and
+//!   send again with a fresh nonce, up to `max_attempts` sends in total
+//! - **Receipt**: [`PendingTx::confirm`] waits for it and confirms the nonce
+//!
+//! Sending runs in a `tx.send` span and waiting in a `tx.receipt` span, both
+//! with the wallet and, once known, the transaction hash; the RPC calls they
+//! make are their children.
+//!
+//! # Example
+//!
+//! ```rust,no_run
+//! use alloy_primitives::{Address, U256};
+//! use alloy_sol_types::sol;
+//! use tempo_spammer::TempoClient;
+//! use tempo_spammer::tx_builder::FeeProfile;
+//!
+//! sol! {
+//!     interface IERC20 {
+//!         function transfer(address to, uint256 amount) returns (bool);
+//!     }
+//! }
+//!
+//! # async fn example(client: &TempoClient, token: Address, to: Address) -> anyhow::Result<()> {
+//! let outcome = client
+//!     .tx()
+//!     .to(token)
+//!     .call(&IERC20::transferCall { to, amount: U256::from(1_000_000) })
+//!     .fee_profile(FeeProfile::Fast)
+//!     .send_and_confirm()
+//!     .await?;
+//!
+//! println!("{:?} mined: {}", outcome.tx_hash, outcome.succeeded());
+//! # Ok(())
+//! # }
+//! ```
+
+use crate::TempoClient;
+use alloy::network::Ethereum;
+use alloy::providers::PendingTransactionBuilder;
+use alloy::rpc::types::{TransactionReceipt, TransactionRequest};
+use alloy_primitives::{Address, Bytes, TxHash, U256};
+use alloy_sol_types::SolCall;
+use anyhow::{Context, Result};
+use std::time::Duration;
+
+/// Pause before sending again after a nonce race
+const NONCE_RETRY_DELAY: Duration = Duration::from_millis(200);
+
+/// Fee level of a transaction
+///
+/// Tempo has a fixed base fee, so the profiles only differ in how much tip
+/// they offer and how high they let the max fee go.
+#[derive(Debug, Clone, Copy, Default, PartialEq, Eq)]
+pub enum FeeProfile {
+    /// Twice the usual tip and max fee
+    Fast,
+    /// The 150 gwei max fee and 1.5 gwei tip the tasks have always used
+    #[default]
+    Normal,
+    /// The usual max fee with a third of the tip
+    Cheap,
+}
+
+impl FeeProfile {
+    /// Max fee per gas in wei
+    pub fn max_fee_per_gas(self) -> u128 {
+        match self {
+            FeeProfile::Fast => 300_000_000_000,
+            FeeProfile::Normal | FeeProfile::Cheap => 150_000_000_000,
+        }
+    }
+
+    /// Max priority fee per gas in wei
+    pub fn max_priority_fee_per_gas(self) -> u128 {
+        match self {
+            FeeProfile::Fast => 3_000_000_000,
+            FeeProfile::Normal => 1_500_000_000,
+            FeeProfile::Cheap => 500_000_000,
+        }
+    }
+}
+
+impl std::str::FromStr for FeeProfile {
+    type Err = anyhow::Error;
+
+    fn from_str(s: &str) -> Result<Self> {
+        match s.to_ascii_lowercase().as_str() {
+            "fast" => Ok(Self::Fast),
+            "normal" => Ok(Self::Normal),
+            "cheap" => Ok(Self::Cheap),
+            _ => anyhow::bail!(
+                "Unknown fee profile '{}', expected fast, normal or cheap",
+                s
+            ),
+        }
+    }
+}
+
+/// Whether a send failed because another transaction took the nonce
+fn is_nonce_race(message: &str) -> bool {
+    let message = message.to_lowercase();
+    message.contains("nonce too low") || message.contains("already known")
+}
+
+/// Builder for one transaction from a [`TempoClient`]'s wallet
+///
+/// Created by [`TempoClient::tx`]. Nothing is sent until
+/// [`send`](Self::send) or [`send_and_confirm`](Self::send_and_confirm).
+#[must_use = "a transaction builder does nothing until sent"]
+#[derive(Debug, Clone)]
+pub struct TxBuilder<'a> {
+    client: &'a TempoClient,
+    to: Option<Address>,
+    input: Bytes,
+    value: U256,
+    fee_profile: FeeProfile,
+    gas_limit: Option<u64>,
+    max_attempts: u32,
+}
+
+impl<'a> TxBuilder<'a> {
+    /// Sends allowed per transaction by default, the first one included
+    pub const MAX_ATTEMPTS_DEFAULT: u32 = 3;
+
+    pub(crate) fn new(client: &'a TempoClient) -> Self {
+        Self {
+            client,
+            to: None,
+            input: Bytes::new(),
+            value: U256::ZERO,
+            fee_profile: FeeProfile::default(),
+            gas_limit: None,
+            max_attempts: Self::MAX_ATTEMPTS_DEFAULT,
+        }
+    }
+
+    /// Contract or account to send to; without one the input deploys a contract
+    pub fn to(mut self, to: Address) -> Self {
+        self.to = Some(to);
+        self
+    }
+
+    /// ABI-encodes `call` as the input
+    pub fn call<C: SolCall>(mut self, call: &C) -> Self {
+        self.input = call.abi_encode().into();
+        self
+    }
+
+    /// Raw input, e.g. hand-built calldata or deployment bytecode
+    pub fn input(mut self, input: impl Into<Bytes>) -> Self {
+        self.input = input.into();
+        self
+    }
+
+    /// Native value to send along (default: 0)
+    pub fn value(mut self, value: U256) -> Self {
+        self.value = value;
+        self
+    }
+
+    /// Fee level (default: [`FeeProfile::Normal`])
+    pub fn fee_profile(mut self, fee_profile: FeeProfile) -> Self {
+        self.fee_profile = fee_profile;
+        self
+    }
+
+    /// Fixed gas limit, skipping the estimate
+    pub fn gas_limit(mut self, gas_limit: u64) -> Self {
+        self.gas_limit = Some(gas_limit);
+        self
+    }
+
+    /// Sends allowed in total, at least 1 (default: 3)
+    pub fn max_attempts(mut self, max_attempts: u32) -> Self {
+        self.max_attempts = max_attempts.max(1);
+        self
+    }
+
+    fn request(&self) -> TransactionRequest {
+        let mut tx = TransactionRequest::default()
+            .from(self.client.address())
+            .input(self.input.clone().into())
+            .value(self.value);
+        if let Some(to) = self.to {
+            tx = tx.to(to);
+        }
+        tx
+    }
+
+    /// Sends the transaction, retrying nonce races
+    ///
+    /// # Returns
+    ///
+    /// The pending transaction once the node accepted it.
+    ///
+    /// # Errors
+    ///
+    /// Fails when the gas estimate fails, e.g. because the call reverts, when
+    /// no nonce can be had, or when the node rejects the transaction for any
+    /// reason other than a nonce race, or for that one on the last attempt.
+    #[tracing::instrument(
+        name = "tx.send",
+        skip_all,
+        fields(wallet = %self.client.address(), to = ?self.to, tx_hash, nonce, attempts)
+    )]
+    pub async fn send(self) -> Result<PendingTx<'a>> {
+        let client = self.client;
+        // Both nonce getters fetch through the client's own provider
+        let rpc_url = "";
+        let gas_limit = match self.gas_limit {
+            Some(gas_limit) => gas_limit,
+            None => client
+                .provider
+                .estimate_gas(self.request())
+                .await
+                .context("Failed to estimate gas")?,
+        };
+
+        let mut attempts = 0;
+        loop {
+            attempts += 1;
+            let last_attempt = attempts >= self.max_attempts;
+
+            let (nonce, reservation) = if client.robust_nonce_manager.is_some() {
+                match client.get_robust_nonce(rpc_url).await {
+                    Ok(reservation) => (reservation.nonce, Some(reservation)),
+                    Err(e) if !last_attempt => {
+                        tracing::warn!("Failed to reserve nonce (attempt {}): {}", attempts, e);
+                        tokio::time::sleep(NONCE_RETRY_DELAY).await;
+                        continue;
+                    }
+                    Err(e) => return Err(e).context("Failed to get nonce"),
+                }
+            } else {
+                match client.get_pending_nonce(rpc_url).await {
+                    Ok(nonce) => (nonce, None),
+                    Err(e) if !last_attempt => {
+                        tracing::warn!("Failed to get nonce (attempt {}): {}", attempts, e);
+                        tokio::time::sleep(NONCE_RETRY_DELAY).await;
+                        continue;
+                    }
+                    Err(e) => return Err(e).context("Failed to get nonce"),
+                }
+            };
+
+            let tx = self
+                .request()
+                .nonce(nonce)
+                .gas_limit(gas_limit)
+                .max_fee_per_gas(self.fee_profile.max_fee_per_gas())
+                .max_priority_fee_per_gas(self.fee_profile.max_priority_fee_per_gas());
+
+            match client.provider.send_transaction(tx).await {
+                Ok(pending) => {
+                    if let Some(reservation) = reservation {
+                        reservation.mark_submitted().await;
+                    }
+                    tracing::Span::current()
+                        .record("tx_hash", tracing::field::display(pending.tx_hash()))
+                        .record("nonce", nonce)
+                        .record("attempts", attempts);
+                    return Ok(PendingTx {
+                        client,
+                        tx_hash: *pending.tx_hash(),
+                        nonce,
+                        attempts,
+                        pending,
+                    });
+                }
+                Err(e) => {
+                    if let Some(reservation) = reservation {
+                        reservation.release().await;
+                    }
+                    client.reset_nonce_cache().await;
+                    if !last_attempt && is_nonce_race(&e.to_string()) {
+                        tracing::warn!(
+                            "Nonce race on attempt {}/{} with nonce {}, retrying...",
+                            attempts,
+                            self.max_attempts,
+                            nonce
+                        );
+                        tokio::time::sleep(NONCE_RETRY_DELAY).await;
+                        continue;
+                    }
+                    return Err(e).context("Failed to send transaction");
+                }
+            }
+        }
+    }
+
+    /// Sends the transaction and waits for its receipt
+    ///
+    /// Same as [`send`](Self::send) followed by [`PendingTx::confirm`]. A
+    /// reverted transaction is not an error, see [`TxOutcome::succeeded`].
+    pub async fn send_and_confirm(self) -> Result<TxOutcome> {
+        self.send().await?.confirm().await
+    }
+}
+
+/// Transaction accepted by the node, not yet mined
+#[derive(Debug)]
+pub struct PendingTx<'a> {
+    client: &'a TempoClient,
+    /// Hash of the accepted transaction
+    pub tx_hash: TxHash,
+    /// Nonce it was sent with
+    pub nonce: u64,
+    /// Sends it took, 1 unless a nonce race was retried
+    pub attempts: u32,
+    pending: PendingTransactionBuilder<Ethereum>,
+}
+
+impl PendingTx<'_> {
+    /// Waits for the receipt and confirms the nonce to the nonce manager
+    #[tracing::instrument(
+        name = "tx.receipt",
+        skip_all,
+        fields(wallet = %self.client.address(), tx_hash = %self.tx_hash, block_number)
+    )]
+    pub async fn confirm(self) -> Result<TxOutcome> {
+        let receipt = self
+            .pending
+            .get_receipt()
+            .await
+            .context("Failed to get receipt")?;
+        self.client.confirm_robust_nonce(self.nonce).await;
+        if let Some(block) = receipt.block_number {
+            tracing::Span::current().record("block_number", block);
+        }
+
+        Ok(TxOutcome {
+            tx_hash: self.tx_hash,
+            nonce: self.nonce,
+            attempts: self.attempts,
+            receipt,
+        })
+    }
+}
+
+/// Mined transaction and its receipt
+#[derive(Debug, Clone)]
+pub struct TxOutcome {
+    /// Hash of the transaction
+    pub tx_hash: TxHash,
+    /// Nonce it was sent with
+    pub nonce: u64,
+    /// Sends it took, 1 unless a nonce race was retried
+    pub attempts: u32,
+    /// Its receipt
+    pub receipt: TransactionReceipt,
+}
+
+impl TxOutcome {
+    /// Whether the transaction executed without reverting
+    pub fn succeeded(&self) -> bool {
+        self.receipt.inner.status()
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use crate::testing::MockTempoClient;
+    use alloy_sol_types::sol;
+
+    sol! {
+        interface IToken {
+            function transfer(address to, uint256 amount) returns (bool);
+        }
+    }
+
+    const TOKEN: Address = Address::repeat_byte(0x42);
+
+    fn transfer() -> IToken::transferCall {
+        IToken::transferCall {
+            to: Address::repeat_byte(0x11),
+            amount: U256::from(5),
+        }
+    }
+
+    #[tokio::test]
+    async fn test_sends_call_with_profile_fees() {
+        let mock = MockTempoClient::default();
+        mock.set_nonce(7);
+        let client = mock.client();
+
+        let outcome = client
+            .tx()
+            .to(TOKEN)
+            .call(&transfer())
+            .fee_profile(FeeProfile::Fast)
+            .send_and_confirm()
+            .await
+            .unwrap();
+        assert!(outcome.succeeded());
+        assert_eq!((outcome.nonce, outcome.attempts), (7, 1));
+        assert_eq!(outcome.receipt.gas_used, 100_000);
+
+        let sent = mock.sent();
+        assert_eq!(sent.len(), 1);
+        assert_eq!(sent[0].to, Some(TOKEN));
+        assert_eq!(sent[0].nonce, Some(7));
+        assert_eq!(&sent[0].input[..4], &IToken::transferCall::SELECTOR);
+        assert_eq!(mock.count("eth_estimateGas"), 1);
+    }
+
+    #[tokio::test]
+    async fn test_gas_limit_skips_estimate() {
+        let mock = MockTempoClient::default();
+        let client = mock.client();
+
+        client
+            .tx()
+            .to(TOKEN)
+            .call(&transfer())
+            .gas_limit(60_000)
+            .send_and_confirm()
+            .await
+            .unwrap();
+        assert_eq!(mock.count("eth_estimateGas"), 0);
+    }
+
+    #[tokio::test]
+    async fn test_retries_nonce_race_with_fresh_nonce() {
+        let mock = MockTempoClient::default();
+        mock.set_nonce(3);
+        mock.fail_next_send("nonce too low: next nonce 4, tx nonce 3");
+        let client = mock.client();
+
+        let outcome = client
+            .tx()
+            .to(TOKEN)
+            .call(&transfer())
+            .send_and_confirm()
+            .await
+            .unwrap();
+        assert_eq!(outcome.attempts, 2);
+        assert_eq!(mock.sent().len(), 1);
+    }
+
+    #[tokio::test]
+    async fn test_gives_up_after_max_attempts() {
+        let mock = MockTempoClient::default();
+        mock.fail_next_send("already known");
+        mock.fail_next_send("already known");
+        let client = mock.client();
+
+        let err = client
+            .tx()
+            .to(TOKEN)
+            .call(&transfer())
+            .max_attempts(2)
+            .send()
+            .await
+            .unwrap_err();
+        assert!(format!("{:#}", err).contains("already known"));
+        assert!(mock.sent().is_empty());
+    }
+
+    #[tokio::test]
+    async fn test_other_send_errors_are_not_retried() {
+        let mock = MockTempoClient::default();
+        mock.fail_next_send("insufficient funds for gas");
+        let client = mock.client();
+
+        let err = client
+            .tx()
+            .to(TOKEN)
+            .call(&transfer())
+            .send()
+            .await
+            .unwrap_err();
+        assert!(format!("{:#}", err).contains("insufficient funds"));
+        assert_eq!(mock.count("eth_sendRawTransaction"), 1);
+    }
+
+    #[tokio::test]
+    async fn test_reverted_receipt_is_not_an_error() {
+        let mock = MockTempoClient::default();
+        mock.revert_next_receipt();
+        let client = mock.client();
+
+        let outcome = client
+            .tx()
+            .to(TOKEN)
+            .call(&transfer())
+            .send_and_confirm()
+            .await
+            .unwrap();
+        assert!(!outcome.succeeded());
+    }
+}
@@ -4,6 +4,7 @@
 //! and created tokens from the database.
 
 use crate::TempoClient;
+use crate::network;
 use alloy::primitives::{Address, U256};
 use alloy::rpc::types::TransactionRequest;
 use anyhow::Result;
@@ -29,28 +30,30 @@ impl TokenInfo {
 pub struct TempoTokens;
 
 impl TempoTokens {
-    pub const SYSTEM_TOKENS: &[(&str, &str)] = &[
-        ("PathUSD", "0x20c0000000000000000000000000000000000000"),
-        ("AlphaUSD", "0x20c0000000000000000000000000000000000001"),
-        ("BetaUSD", "0x20c0000000000000000000000000000000000002"),
-        ("ThetaUSD", "0x20c0000000000000000000000000000000000003"),
-    ];
+    /// System tokens of the configured network, PathUSD first
+    pub fn system_tokens() -> [(&'static str, Address); 4] {
+        network::contracts().system_tokens()
+    }
 
     pub fn get_system_tokens() -> Vec<TokenInfo> {
-        Self::SYSTEM_TOKENS
-            .iter()
-            .map(|(symbol, addr)| TokenInfo::new(symbol, addr, true))
+        Self::system_tokens()
+            .into_iter()
+            .map(|(symbol, address)| TokenInfo {
+                symbol: symbol.to_string(),
+                address,
+                is_system: true,
+            })
             .collect()
     }
 
     pub fn get_random_system_token() -> TokenInfo {
-        let idx = rand::thread_rng().gen_range(0..Self::SYSTEM_TOKENS.len());
-        let (symbol, addr) = Self::SYSTEM_TOKENS[idx];
-        TokenInfo::new(symbol, addr, true)
+        let mut tokens = Self::get_system_tokens();
+        let idx = rand::thread_rng().gen_range(0..tokens.len());
+        tokens.swap_remove(idx)
     }
 
     pub fn get_path_usd_address() -> Address {
-        Address::from_str(Self::SYSTEM_TOKENS[0].1).unwrap_or_else(|_| Address::ZERO)
+        network::contracts().path_usd
     }
 
     pub fn get_random_memo() -> String {
@@ -0,0 +1,437 @@
+//! Wallet Health - Scoring wallets and quarantining broken ones
+//!
+//! A wallet that ran dry, has a stuck nonce or was blacklisted by a faucet or
+//! token policy fails every task it is leased for and wastes the worker's
+//! time. [`WalletHealth`] keeps a score per wallet: every failure costs points
+//! depending on its [`ErrorClass`], every success wins some back. A wallet
+//! whose score drops below `[wallet_health] threshold`, or that fails
+//! `max_consecutive_failures` times in a row, is quarantined for
+//! `recheck_secs`:
+//!
+//! - the [`ClientPool`](crate::ClientPool) keeps it out of the available set
+//!   when it is released; the runner's 30-second sweep puts it back once the
+//!   recheck time has passed
+//! - partitioned workers skip it in their own slice
+//!
+//! A wallet back from quarantine starts halfway between the threshold and a
+//! full score, so a wallet that is still broken is quarantined again after a
+//! failure or two.
+//!
+//! Connection and proxy errors say nothing about the wallet and are not
+//! scored. The runner logs each quarantine and the quarantined count every
+//! 30 seconds, and the Telegram bot reports new quarantines.
+
+use crate::config::WalletHealthSettings;
+use alloy_primitives::Address;
+use chrono::{DateTime, Utc};
+use std::collections::HashMap;
+use std::sync::Mutex;
+use std::time::{Duration, Instant};
+
+/// Score of a wallet that never failed
+pub const FULL_SCORE: u32 = 100;
+
+/// Points a success wins back
+const SUCCESS_BONUS: u32 = 10;
+
+/// Why a task failed, as far as the wallet is concerned
+#[derive(Debug, Clone, Copy, PartialEq, Eq, Hash)]
+pub enum ErrorClass {
+    /// Not enough native or token balance
+    InsufficientFunds,
+    /// Nonce errors the nonce manager could not get past
+    Nonce,
+    /// Refused by a faucet, allowlist or token transfer policy
+    Blacklisted,
+    /// Any other revert
+    Reverted,
+    Timeout,
+    /// Proxy or RPC connection trouble, not the wallet's fault
+    Network,
+    Other,
+}
+
+impl ErrorClass {
+    /// Class of a failed task from its message
+    pub fn of(message: &str) -> Self {
+        let message = message.to_ascii_lowercase();
+        let has = |needles: &[&str]| needles.iter().any(|needle| message.contains(needle));
+        if has(&[
+            "tunnel error",
+            "connection closed",
+            "error sending request",
+            "connect",
+            "429",
+            "too many requests",
+        ]) {
+            ErrorClass::Network
+        } else if has(&["insufficient", "exceeds balance", "not enough balance"]) {
+            ErrorClass::InsufficientFunds
+        } else if has(&[
+            "nonce too",
+            "replacement transaction underpriced",
+            "already known",
+        ]) {
+            ErrorClass::Nonce
+        } else if has(&[
+            "policyforbids",
+            "blacklist",
+            "denylist",
+            "not whitelisted",
+            "not allowed",
+            "forbidden",
+        ]) {
+            ErrorClass::Blacklisted
+        } else if has(&["timed out", "timeout"]) {
+            ErrorClass::Timeout
+        } else if has(&["revert"]) {
+            ErrorClass::Reverted
+        } else {
+            ErrorClass::Other
+        }
+    }
+
+    /// Points one failure of this class costs
+    pub fn penalty(self) -> u32 {
+        match self {
+            ErrorClass::InsufficientFunds => 40,
+            ErrorClass::Blacklisted => 35,
+            ErrorClass::Nonce => 25,
+            ErrorClass::Reverted | ErrorClass::Other => 10,
+            ErrorClass::Timeout => 5,
+            ErrorClass::Network => 0,
+        }
+    }
+
+    pub fn as_str(self) -> &'static str {
+        match self {
+            ErrorClass::InsufficientFunds => "insufficient_funds",
+            ErrorClass::Nonce => "nonce",
+            ErrorClass::Blacklisted => "blacklisted",
+            ErrorClass::Reverted => "reverted",
+            ErrorClass::Timeout => "timeout",
+            ErrorClass::Network => "network",
+            ErrorClass::Other => "other",
+        }
+    }
+}
+
+impl std::fmt::Display for ErrorClass {
+    fn fmt(&self, f: &mut std::fmt::Formatter<'_>) -> std::fmt::Result {
+        f.write_str(self.as_str())
+    }
+}
+
+/// A wallet currently kept out of rotation
+#[derive(Debug, Clone, PartialEq)]
+pub struct QuarantinedWallet {
+    pub index: usize,
+    pub address: Address,
+    pub score: u32,
+    pub consecutive_failures: u32,
+    /// Failures per class since the wallet last succeeded
+    pub errors: Vec<(ErrorClass, u32)>,
+    pub since: DateTime<Utc>,
+    pub until: DateTime<Utc>,
+}
+
+impl QuarantinedWallet {
+    /// Failure counts like `insufficient_funds x3, nonce x1`
+    pub fn error_summary(&self) -> String {
+        self.errors
+            .iter()
+            .map(|(class, count)| format!("{} x{}", class, count))
+            .collect::<Vec<_>>()
+            .join(", ")
+    }
+}
+
+#[derive(Debug)]
+struct WalletState {
+    address: Address,
+    score: u32,
+    consecutive_failures: u32,
+    errors: HashMap<ErrorClass, u32>,
+    quarantine: Option<Quarantine>,
+}
+
+#[derive(Debug, Clone, Copy)]
+struct Quarantine {
+    until: Instant,
+    since_utc: DateTime<Utc>,
+    until_utc: DateTime<Utc>,
+}
+
+impl WalletState {
+    fn new(address: Address) -> Self {
+        Self {
+            address,
+            score: FULL_SCORE,
+            consecutive_failures: 0,
+            errors: HashMap::new(),
+            quarantine: None,
+        }
+    }
+
+    fn snapshot(&self, index: usize, quarantine: Quarantine) -> QuarantinedWallet {
+        let mut errors: Vec<_> = self.errors.iter().map(|(c, n)| (*c, *n)).collect();
+        errors.sort_by(|a, b| b.1.cmp(&a.1).then(a.0.as_str().cmp(b.0.as_str())));
+        QuarantinedWallet {
+            index,
+            address: self.address,
+            score: self.score,
+            consecutive_failures: self.consecutive_failures,
+            errors,
+            since: quarantine.since_utc,
+            until: quarantine.until_utc,
+        }
+    }
+}
+
+/// Health scores and quarantines of the pool's wallets, by wallet index
+#[derive(Debug)]
+pub struct WalletHealth {
+    settings: WalletHealthSettings,
+    wallets: Mutex<HashMap<usize, WalletState>>,
+}
+
+impl WalletHealth {
+    pub fn new(settings: WalletHealthSettings) -> Self {
+        Self {
+            settings,
+            wallets: Mutex::new(HashMap::new()),
+        }
+    }
+
+    /// Scores a finished task of wallet `index`
+    ///
+    /// `error` is the failure message, `None` for a success.
+    ///
+    /// # Returns
+    ///
+    /// The wallet's quarantine if this outcome started one
+    pub fn record(
+        &self,
+        index: usize,
+        address: Address,
+        error: Option<&str>,
+    ) -> Option<QuarantinedWallet> {
+        self.record_at(index, address, error, Instant::now(), Utc::now())
+    }
+
+    fn record_at(
+        &self,
+        index: usize,
+        address: Address,
+        error: Option<&str>,
+        now: Instant,
+        now_utc: DateTime<Utc>,
+    ) -> Option<QuarantinedWallet> {
+        if !self.settings.enabled {
+            return None;
+        }
+        let mut wallets = self.wallets.lock().unwrap();
+        let state = wallets
+            .entry(index)
+            .or_insert_with(|| WalletState::new(address));
+
+        let Some(message) = error else {
+            state.score = (state.score + SUCCESS_BONUS).min(FULL_SCORE);
+            state.consecutive_failures = 0;
+            state.errors.clear();
+            return None;
+        };
+        let class = ErrorClass::of(message);
+        if class == ErrorClass::Network {
+            return None;
+        }
+        state.score = state.score.saturating_sub(class.penalty());
+        state.consecutive_failures += 1;
+        *state.errors.entry(class).or_default() += 1;
+
+        let failing = state.score < self.settings.threshold
+            || state.consecutive_failures >= self.settings.max_consecutive_failures;
+        if !failing || state.quarantine.is_some_and(|q| now < q.until) {
+            return None;
+        }
+        let recheck = Duration::from_secs(self.settings.recheck_secs);
+        let quarantine = Quarantine {
+            until: now + recheck,
+            since_utc: now_utc,
+            until_utc: now_utc + chrono::Duration::from_std(recheck).unwrap_or_default(),
+        };
+        state.quarantine = Some(quarantine);
+        Some(state.snapshot(index, quarantine))
+    }
+
+    /// Whether wallet `index` is quarantined, lifting an expired quarantine
+    pub fn is_quarantined(&self, index: usize) -> bool {
+        self.is_quarantined_at(index, Instant::now())
+    }
+
+    fn is_quarantined_at(&self, index: usize, now: Instant) -> bool {
+        let mut wallets = self.wallets.lock().unwrap();
+        let Some(state) = wallets.get_mut(&index) else {
+            return false;
+        };
+        match state.quarantine {
+            Some(quarantine) if now < quarantine.until => true,
+            Some(_) => {
+                // Probation: one more bad streak sends the wallet back
+                state.quarantine = None;
+                state.score = state.score.max((self.settings.threshold + FULL_SCORE) / 2);
+                state.consecutive_failures = 0;
+                state.errors.clear();
+                false
+            }
+            None => false,
+        }
+    }
+
+    /// Wallets in quarantine right now, by index
+    pub fn quarantined(&self) -> Vec<QuarantinedWallet> {
+        let now = Instant::now();
+        let wallets = self.wallets.lock().unwrap();
+        let mut quarantined: Vec<_> = wallets
+            .iter()
+            .filter_map(|(index, state)| {
+                let quarantine = state.quarantine.filter(|q| now < q.until)?;
+                Some(state.snapshot(*index, quarantine))
+            })
+            .collect();
+        quarantined.sort_by_key(|wallet| wallet.index);
+        quarantined
+    }
+
+    /// Current score of wallet `index`, [`FULL_SCORE`] if it never ran
+    pub fn score(&self, index: usize) -> u32 {
+        self.wallets
+            .lock()
+            .unwrap()
+            .get(&index)
+            .map_or(FULL_SCORE, |state| state.score)
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    fn health() -> WalletHealth {
+        WalletHealth::new(WalletHealthSettings {
+            enabled: true,
+            threshold: 30,
+            max_consecutive_failures: 4,
+            recheck_secs: 60,
+        })
+    }
+
+    #[test]
+    fn test_error_classes() {
+        let cases = [
+            (
+                "execution reverted (revert: InsufficientBalance { available: 0, .. })",
+                ErrorClass::InsufficientFunds,
+            ),
+            (
+                "insufficient funds for gas * price + value",
+                ErrorClass::InsufficientFunds,
+            ),
+            ("nonce too low: next nonce 5, tx nonce 3", ErrorClass::Nonce),
+            (
+                "execution reverted (revert: PolicyForbids)",
+                ErrorClass::Blacklisted,
+            ),
+            ("Faucet: address blacklisted", ErrorClass::Blacklisted),
+            ("execution reverted", ErrorClass::Reverted),
+            ("Task timed out", ErrorClass::Timeout),
+            ("error sending request for url", ErrorClass::Network),
+            ("something odd", ErrorClass::Other),
+        ];
+        for (message, class) in cases {
+            assert_eq!(ErrorClass::of(message), class, "{}", message);
+        }
+    }
+
+    #[test]
+    fn test_quarantine_and_probation() {
+        let health = health();
+        let wallet = Address::repeat_byte(7);
+        let (start, utc) = (Instant::now(), Utc::now());
+
+        // Connection trouble and a success in between cost nothing
+        assert!(
+            health
+                .record_at(3, wallet, Some("tunnel error"), start, utc)
+                .is_none()
+        );
+        assert!(
+            health
+                .record_at(3, wallet, Some("insufficient funds"), start, utc)
+                .is_none()
+        );
+        assert_eq!(health.score(3), 60);
+        health.record_at(3, wallet, None, start, utc);
+        assert_eq!(health.score(3), 70);
+
+        // Exactly at the threshold is still fine
+        assert!(
+            health
+                .record_at(3, wallet, Some("insufficient funds"), start, utc)
+                .is_none()
+        );
+        let quarantined = health
+            .record_at(3, wallet, Some("insufficient funds"), start, utc)
+            .unwrap();
+        assert_eq!(
+            (quarantined.index, quarantined.address, quarantined.score),
+            (3, wallet, 0)
+        );
+        assert_eq!(quarantined.errors, vec![(ErrorClass::InsufficientFunds, 2)]);
+        assert_eq!(quarantined.error_summary(), "insufficient_funds x2");
+        assert!(health.is_quarantined_at(3, start));
+        assert!(!health.is_quarantined_at(4, start));
+
+        // Back on probation after the recheck time
+        let later = start + Duration::from_secs(60);
+        assert!(!health.is_quarantined_at(3, later));
+        assert_eq!(health.score(3), 65);
+        assert!(
+            health
+                .record_at(3, wallet, Some("insufficient funds"), later, utc)
+                .is_some()
+        );
+    }
+
+    #[test]
+    fn test_consecutive_failures_quarantine() {
+        let health = health();
+        let wallet = Address::repeat_byte(8);
+        let (now, utc) = (Instant::now(), Utc::now());
+        for _ in 0..3 {
+            assert!(
+                health
+                    .record_at(1, wallet, Some("Task timed out"), now, utc)
+                    .is_none()
+            );
+        }
+        let quarantined = health
+            .record_at(1, wallet, Some("Task timed out"), now, utc)
+            .unwrap();
+        assert_eq!(quarantined.consecutive_failures, 4);
+        assert_eq!(quarantined.score, 80);
+
+        let disabled = WalletHealth::new(WalletHealthSettings {
+            enabled: false,
+            ..WalletHealthSettings::default()
+        });
+        for _ in 0..20 {
+            assert!(
+                disabled
+                    .record(1, wallet, Some("insufficient funds"))
+                    .is_none()
+            );
+        }
+        assert!(!disabled.is_quarantined(1));
+    }
+}
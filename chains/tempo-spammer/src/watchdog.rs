@@ -0,0 +1,502 @@
+//! Watchdog - Diagnostics for task runs that approach their timeout
+//!
+//! A run that hangs until its timeout leaves only "Task timed out" behind. With
+//! `[watchdog] soft_threshold` above 0 the runner watches every run and, once
+//! it passes that share of its timeout, takes a [`Diagnostics`] snapshot of
+//! the wallet:
+//!
+//! - the RPC calls in flight and how long each has been waiting
+//! - the proxy the client goes through
+//! - the nonce manager's state for the wallet
+//! - the last provider errors, `recent_errors` of them
+//!
+//! ```toml
+//! [watchdog]
+//! soft_threshold = 0.8
+//! slow_rpc_ms = 10000
+//! ```
+//!
+//! The snapshot is logged as a warning while the run is still going and kept
+//! as JSON in the run's `task_metrics.diagnostics` column, whether the run
+//! then succeeds, fails or times out.
+//!
+//! RPC activity is tracked per wallet by [`WatchdogLayer`], which every
+//! [`TempoClient`](crate::TempoClient) installs inside the retry layer, so each
+//! attempt counts on its own. Attempts slower than `slow_rpc_ms` are logged as
+//! they finish.
+
+use crate::TempoClient;
+use crate::config::WatchdogSettings;
+use alloy::rpc::json_rpc::{RequestPacket, ResponsePacket};
+use alloy::transports::{TransportError, TransportFut};
+use alloy_primitives::Address;
+use serde::Serialize;
+use std::collections::{BTreeMap, HashMap, VecDeque};
+use std::fmt;
+use std::future::Future;
+use std::sync::atomic::{AtomicU64, Ordering};
+use std::sync::{Arc, Mutex, OnceLock, RwLock};
+use std::task::{Context, Poll};
+use std::time::{Duration, Instant};
+use tower::{Layer, Service};
+use tracing::warn;
+
+/// Longest wait for the nonce manager's locks while taking a snapshot
+const NONCE_LOCK_WAIT: Duration = Duration::from_millis(500);
+
+/// Longest error message kept per provider error, in characters
+const MAX_ERROR_CHARS: usize = 300;
+
+static WATCHDOG: OnceLock<Arc<Watchdog>> = OnceLock::new();
+
+/// Process-wide watchdog shared by all clients
+pub fn watchdog() -> &'static Arc<Watchdog> {
+    WATCHDOG.get_or_init(|| Arc::new(Watchdog::new(WatchdogSettings::default())))
+}
+
+/// RPC activity of every wallet seen so far
+#[derive(Debug)]
+pub struct Watchdog {
+    settings: RwLock<WatchdogSettings>,
+    wallets: Mutex<HashMap<Address, Arc<WalletActivity>>>,
+}
+
+impl Watchdog {
+    pub fn new(settings: WatchdogSettings) -> Self {
+        Self {
+            settings: RwLock::new(settings),
+            wallets: Mutex::new(HashMap::new()),
+        }
+    }
+
+    /// Applies `[watchdog]`
+    pub fn configure(&self, settings: WatchdogSettings) {
+        *self.settings.write().unwrap() = settings;
+    }
+
+    /// Activity of `wallet`, created on first use
+    pub fn activity(&self, wallet: Address) -> Arc<WalletActivity> {
+        self.wallets
+            .lock()
+            .unwrap()
+            .entry(wallet)
+            .or_default()
+            .clone()
+    }
+
+    /// Transport layer tracking the calls of `wallet`'s client
+    pub fn layer(self: &Arc<Self>, wallet: Address) -> WatchdogLayer {
+        WatchdogLayer {
+            watchdog: self.clone(),
+            wallet,
+            activity: self.activity(wallet),
+        }
+    }
+
+    fn slow_rpc(&self) -> Option<Duration> {
+        let slow_rpc_ms = self.settings.read().unwrap().slow_rpc_ms;
+        (slow_rpc_ms > 0).then(|| Duration::from_millis(slow_rpc_ms))
+    }
+
+    fn recent_errors(&self) -> usize {
+        self.settings.read().unwrap().recent_errors
+    }
+}
+
+/// Calls in flight and recent provider errors of one wallet
+#[derive(Debug, Default)]
+pub struct WalletActivity {
+    next_call: AtomicU64,
+    in_flight: Mutex<BTreeMap<u64, (String, Instant)>>,
+    errors: Mutex<VecDeque<(String, String, Instant)>>,
+}
+
+impl WalletActivity {
+    /// Notes the start of a call, which stays in flight until the guard drops
+    fn start(self: &Arc<Self>, method: &str) -> CallGuard {
+        let id = self.next_call.fetch_add(1, Ordering::Relaxed);
+        self.in_flight
+            .lock()
+            .unwrap()
+            .insert(id, (method.to_string(), Instant::now()));
+        CallGuard {
+            activity: self.clone(),
+            id,
+        }
+    }
+
+    /// Keeps `error` of a `method` call, dropping the oldest beyond `keep`
+    pub fn record_error(&self, method: &str, error: &str, keep: usize) {
+        let mut errors = self.errors.lock().unwrap();
+        errors.push_back((
+            method.to_string(),
+            error.chars().take(MAX_ERROR_CHARS).collect(),
+            Instant::now(),
+        ));
+        while errors.len() > keep {
+            errors.pop_front();
+        }
+    }
+
+    /// Calls in flight at `now`, longest waiting first
+    pub fn in_flight(&self, now: Instant) -> Vec<InFlightCall> {
+        let mut calls: Vec<InFlightCall> = self
+            .in_flight
+            .lock()
+            .unwrap()
+            .values()
+            .map(|(method, started)| InFlightCall {
+                method: method.clone(),
+                elapsed_ms: now.saturating_duration_since(*started).as_millis() as u64,
+            })
+            .collect();
+        calls.sort_by_key(|call| std::cmp::Reverse(call.elapsed_ms));
+        calls
+    }
+
+    /// Kept provider errors at `now`, newest first
+    pub fn recent_errors(&self, now: Instant) -> Vec<ProviderError> {
+        self.errors
+            .lock()
+            .unwrap()
+            .iter()
+            .rev()
+            .map(|(method, error, at)| ProviderError {
+                method: method.clone(),
+                error: error.clone(),
+                age_ms: now.saturating_duration_since(*at).as_millis() as u64,
+            })
+            .collect()
+    }
+}
+
+/// Removes a call from the in-flight set when it finishes or is cancelled
+struct CallGuard {
+    activity: Arc<WalletActivity>,
+    id: u64,
+}
+
+impl Drop for CallGuard {
+    fn drop(&mut self) {
+        self.activity.in_flight.lock().unwrap().remove(&self.id);
+    }
+}
+
+/// A JSON-RPC call still waiting for its answer
+#[derive(Debug, Clone, PartialEq, Eq, Serialize)]
+pub struct InFlightCall {
+    pub method: String,
+    pub elapsed_ms: u64,
+}
+
+/// A failed JSON-RPC call
+#[derive(Debug, Clone, PartialEq, Eq, Serialize)]
+pub struct ProviderError {
+    pub method: String,
+    pub error: String,
+    /// Time since the error, in milliseconds
+    pub age_ms: u64,
+}
+
+/// Nonce manager state of the wallet
+#[derive(Debug, Clone, PartialEq, Eq, Serialize)]
+pub struct NonceSnapshot {
+    /// Next nonce the manager hands out
+    pub next: u64,
+    /// Highest nonce known confirmed, with the robust manager
+    #[serde(skip_serializing_if = "Option::is_none")]
+    pub confirmed: Option<u64>,
+    /// Nonces reserved but not sent, with the robust manager
+    #[serde(skip_serializing_if = "Option::is_none")]
+    pub reserved: Option<usize>,
+    /// Nonces sent but not confirmed, with the robust manager
+    #[serde(skip_serializing_if = "Option::is_none")]
+    pub in_flight: Option<usize>,
+}
+
+impl NonceSnapshot {
+    /// State of the client's robust or legacy nonce manager, `None` without one
+    async fn of(client: &TempoClient) -> Option<Self> {
+        if let Some(stats) = client.get_robust_nonce_stats().await {
+            return Some(Self {
+                next: stats.cached_next,
+                confirmed: Some(stats.confirmed),
+                reserved: Some(stats.reserved),
+                in_flight: Some(stats.in_flight),
+            });
+        }
+        let next = client
+            .nonce_manager
+            .as_ref()?
+            .peek(client.address())
+            .await?;
+        Some(Self {
+            next,
+            confirmed: None,
+            reserved: None,
+            in_flight: None,
+        })
+    }
+}
+
+/// State of a wallet while one of its task runs is past the soft threshold
+#[derive(Debug, Clone, PartialEq, Eq, Serialize)]
+pub struct Diagnostics {
+    /// Time the run had taken when the snapshot was taken, in milliseconds
+    pub elapsed_ms: u64,
+    /// Proxy URL, `None` for direct connections
+    pub proxy: Option<String>,
+    pub in_flight: Vec<InFlightCall>,
+    /// `None` when the client has no nonce manager or its locks stayed busy
+    pub nonce: Option<NonceSnapshot>,
+    /// Newest first
+    pub recent_errors: Vec<ProviderError>,
+}
+
+impl Diagnostics {
+    /// Snapshot of `client`'s wallet `elapsed` into a run
+    pub async fn capture(client: &TempoClient, elapsed: Duration) -> Self {
+        let activity = watchdog().activity(client.address());
+        let now = Instant::now();
+        Self {
+            elapsed_ms: elapsed.as_millis() as u64,
+            proxy: client.proxy_config.as_ref().map(|p| p.url.clone()),
+            in_flight: activity.in_flight(now),
+            nonce: tokio::time::timeout(NONCE_LOCK_WAIT, NonceSnapshot::of(client))
+                .await
+                .ok()
+                .flatten(),
+            recent_errors: activity.recent_errors(now),
+        }
+    }
+
+    /// JSON for the `task_metrics.diagnostics` column
+    pub fn to_json(&self) -> String {
+        serde_json::to_string(self).unwrap_or_default()
+    }
+}
+
+impl fmt::Display for Diagnostics {
+    fn fmt(&self, f: &mut fmt::Formatter<'_>) -> fmt::Result {
+        write!(f, "proxy {}", self.proxy.as_deref().unwrap_or("direct"))?;
+        if self.in_flight.is_empty() {
+            write!(f, ", no RPC in flight")?;
+        } else {
+            let calls: Vec<String> = self
+                .in_flight
+                .iter()
+                .map(|call| format!("{} {:.1}s", call.method, call.elapsed_ms as f64 / 1000.0))
+                .collect();
+            write!(f, ", in flight: {}", calls.join(", "))?;
+        }
+        if let Some(nonce) = &self.nonce {
+            write!(f, ", next nonce {}", nonce.next)?;
+            if let (Some(confirmed), Some(in_flight)) = (nonce.confirmed, nonce.in_flight) {
+                write!(f, " (confirmed {}, {} in flight)", confirmed, in_flight)?;
+            }
+        }
+        match self.recent_errors.first() {
+            Some(last) => write!(
+                f,
+                ", {} recent errors, last {}: {}",
+                self.recent_errors.len(),
+                last.method,
+                last.error
+            ),
+            None => write!(f, ", no recent errors"),
+        }
+    }
+}
+
+/// Runs `fut`, capturing diagnostics if it is still running after `soft`
+///
+/// `capture` gets the time the run has taken. The snapshot is logged for
+/// `task` right away and returned with the output once `fut` completes.
+pub async fn watch<F, C>(
+    task: &str,
+    soft: Option<Duration>,
+    fut: F,
+    capture: impl FnOnce(Duration) -> C,
+) -> (F::Output, Option<Diagnostics>)
+where
+    F: Future,
+    C: Future<Output = Diagnostics>,
+{
+    let Some(soft) = soft else {
+        return (fut.await, None);
+    };
+    let started = Instant::now();
+    tokio::pin!(fut);
+    tokio::select! {
+        output = &mut fut => return (output, None),
+        _ = tokio::time::sleep(soft) => {}
+    }
+
+    let diagnostics = capture(started.elapsed()).await;
+    warn!(
+        "{} still running after {:.1}s: {}",
+        task,
+        diagnostics.elapsed_ms as f64 / 1000.0,
+        diagnostics
+    );
+    (fut.await, Some(diagnostics))
+}
+
+/// Transport layer installing [`WatchdogService`] in one wallet's client
+#[derive(Debug, Clone)]
+pub struct WatchdogLayer {
+    watchdog: Arc<Watchdog>,
+    wallet: Address,
+    activity: Arc<WalletActivity>,
+}
+
+impl<S> Layer<S> for WatchdogLayer {
+    type Service = WatchdogService<S>;
+
+    fn layer(&self, inner: S) -> Self::Service {
+        WatchdogService {
+            inner,
+            watchdog: self.watchdog.clone(),
+            wallet: self.wallet,
+            activity: self.activity.clone(),
+        }
+    }
+}
+
+/// Transport service tracking calls in flight, slow calls and provider errors
+#[derive(Debug, Clone)]
+pub struct WatchdogService<S> {
+    inner: S,
+    watchdog: Arc<Watchdog>,
+    wallet: Address,
+    activity: Arc<WalletActivity>,
+}
+
+impl<S> Service<RequestPacket> for WatchdogService<S>
+where
+    S: Service<
+            RequestPacket,
+            Response = ResponsePacket,
+            Error = TransportError,
+            Future = TransportFut<'static>,
+        > + Send
+        + 'static,
+{
+    type Response = ResponsePacket;
+    type Error = TransportError;
+    type Future = TransportFut<'static>;
+
+    fn poll_ready(&mut self, cx: &mut Context<'_>) -> Poll<Result<(), Self::Error>> {
+        self.inner.poll_ready(cx)
+    }
+
+    fn call(&mut self, request: RequestPacket) -> Self::Future {
+        let method = match &request {
+            RequestPacket::Single(req) => req.method().to_string(),
+            RequestPacket::Batch(reqs) => format!("batch of {}", reqs.len()),
+        };
+        let guard = self.activity.start(&method);
+        let watchdog = self.watchdog.clone();
+        let wallet = self.wallet;
+        let activity = self.activity.clone();
+        let fut = self.inner.call(request);
+
+        Box::pin(async move {
+            let started = Instant::now();
+            let result = fut.await;
+            drop(guard);
+
+            let elapsed = started.elapsed();
+            if let Some(slow) = watchdog.slow_rpc()
+                && elapsed >= slow
+            {
+                warn!(
+                    "Slow RPC {} for {}: {:.1}s",
+                    method,
+                    wallet,
+                    elapsed.as_secs_f64()
+                );
+            }
+
+            let error = match &result {
+                Ok(ResponsePacket::Single(resp)) => {
+                    resp.payload.as_error().map(|e| e.message.to_string())
+                }
+                Ok(ResponsePacket::Batch(_)) => None,
+                Err(e) => Some(e.to_string()),
+            };
+            if let Some(error) = error {
+                activity.record_error(&method, &error, watchdog.recent_errors());
+            }
+            result
+        })
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    #[test]
+    fn test_activity_tracks_calls_and_errors() {
+        let activity = Arc::new(WalletActivity::default());
+
+        let receipt = activity.start("eth_getTransactionReceipt");
+        let call = activity.start("eth_call");
+        drop(call);
+        let calls = activity.in_flight(Instant::now());
+        assert_eq!(calls.len(), 1);
+        assert_eq!(calls[0].method, "eth_getTransactionReceipt");
+        drop(receipt);
+        assert!(activity.in_flight(Instant::now()).is_empty());
+
+        for i in 0..7 {
+            activity.record_error("eth_sendRawTransaction", &format!("error {}", i), 5);
+        }
+        let errors = activity.recent_errors(Instant::now());
+        assert_eq!(errors.len(), 5);
+        assert_eq!(errors[0].error, "error 6");
+        assert_eq!(errors[4].error, "error 2");
+
+        activity.record_error("eth_call", &"x".repeat(1000), 5);
+        assert_eq!(
+            activity.recent_errors(Instant::now())[0].error.len(),
+            MAX_ERROR_CHARS
+        );
+    }
+
+    #[tokio::test]
+    async fn test_watch_captures_only_past_the_threshold() {
+        let snapshot = |elapsed: Duration| async move {
+            Diagnostics {
+                elapsed_ms: elapsed.as_millis() as u64,
+                proxy: None,
+                in_flight: Vec::new(),
+                nonce: None,
+                recent_errors: Vec::new(),
+            }
+        };
+
+        let (output, diagnostics) = watch(
+            "fast",
+            Some(Duration::from_millis(200)),
+            async { 1 },
+            snapshot,
+        )
+        .await;
+        assert_eq!(output, 1);
+        assert_eq!(diagnostics, None);
+
+        let slow = async {
+            tokio::time::sleep(Duration::from_millis(60)).await;
+            2
+        };
+        let (output, diagnostics) =
+            watch("slow", Some(Duration::from_millis(20)), slow, snapshot).await;
+        assert_eq!(output, 2);
+        assert!(diagnostics.unwrap().elapsed_ms >= 20);
+
+        let (_, diagnostics) = watch("off", None, async { 3 }, snapshot).await;
+        assert_eq!(diagnostics, None);
+    }
+}
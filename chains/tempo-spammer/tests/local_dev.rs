@@ -0,0 +1,32 @@
+//! End-to-end run of the default `[local_dev] tasks` against anvil.
+//!
+//! Skipped when `anvil` is not on PATH.
+
+use tempo_spammer::config::TempoSpammerConfig;
+use tempo_spammer::local_dev::run_local_dev;
+use tempo_spammer::runner::default_tasks;
+
+fn anvil_installed() -> bool {
+    std::process::Command::new("anvil")
+        .arg("--version")
+        .output()
+        .is_ok_and(|output| output.status.success())
+}
+
+#[tokio::test]
+async fn test_default_tasks_pass_on_anvil() {
+    if !anvil_installed() {
+        eprintln!("anvil not installed, skipping");
+        return;
+    }
+    let config =
+        TempoSpammerConfig::from_path(concat!(env!("CARGO_MANIFEST_DIR"), "/config/config.toml"))
+            .unwrap();
+
+    let report = run_local_dev(&config, &default_tasks()).await.unwrap();
+    assert_eq!(
+        report.outcomes.len(),
+        config.local_dev.tasks.len() * config.local_dev.wallets
+    );
+    assert!(report.failures().is_empty(), "{:#?}", report.failures());
+}
@@ -55,7 +55,7 @@ fn main() -> Result<()> {
     match SecurityUtils::decrypt_components(ciphertext, iv, salt, tag, &password) {
         Ok(plaintext) => {
             println!("SUCCESS!");
-            println!("Decrypted: {}", plaintext);
+            println!("Decrypted: {}", plaintext.as_str());
         }
         Err(e) => {
             println!("Failed: {}", e);
@@ -1,6 +1,8 @@
+use crate::error::ConfigError;
 use serde::{Deserialize, Serialize};
 
 #[derive(Debug, Clone, Serialize, Deserialize)]
+#[serde(deny_unknown_fields)]
 pub struct SpamConfig {
     pub rpc_url: String,
     pub chain_id: u64,
@@ -10,12 +12,14 @@ pub struct SpamConfig {
 }
 
 #[derive(Debug, Clone, Serialize, Deserialize)]
+#[serde(deny_unknown_fields)]
 pub enum WalletSource {
     File { path: String, encrypted: bool },
     Env { key: String },
 }
 
 #[derive(Debug, Clone, Serialize, Deserialize)]
+#[serde(deny_unknown_fields)]
 pub struct ProxyConfig {
     pub url: String,
     pub username: Option<String>,
@@ -23,8 +27,128 @@ pub struct ProxyConfig {
 }
 
 #[derive(Debug, Clone, Serialize, Deserialize)]
+#[serde(deny_unknown_fields)]
 pub struct ChainConfig {
     pub name: String,
     pub rpc_endpoint: String,
     pub chain_id: u64,
 }
+
+/// Builds a [`ConfigError::UnknownKey`] from a serde "unknown field" message
+///
+/// Serde reports unknown keys as ``unknown field `x`, expected one of `a`, `b` ``
+/// without saying where they are. This locates the key in the file content
+/// (at `offset` when the parser reports a span, otherwise by searching for an
+/// assignment of the key), prefixes it with its TOML table, and suggests the
+/// closest valid key by edit distance.
+///
+/// Returns `None` for any other kind of error message.
+///
+/// # Arguments
+///
+/// * `message` - Raw serde error message
+/// * `source` - File name used in the reported location
+/// * `content` - File content, may be empty if it is not available
+/// * `offset` - Byte offset of the key, if the parser reported one
+pub fn unknown_key_error(
+    message: &str,
+    source: &str,
+    content: &str,
+    offset: Option<usize>,
+) -> Option<ConfigError> {
+    let (key, expected) = parse_unknown_field(message)?;
+
+    let line_idx = match offset {
+        Some(offset) if offset <= content.len() => Some(content[..offset].matches('\n').count()),
+        _ => content.lines().position(|line| assigns_key(line, &key)),
+    };
+
+    let (location, table) = match line_idx {
+        Some(idx) => {
+            let line = content.lines().nth(idx).unwrap_or_default();
+            let column = line.len() - line.trim_start().len() + 1;
+            let table = content.lines().take(idx).filter_map(table_header).last();
+            (format!("{}:{}:{}", source, idx + 1, column), table)
+        }
+        None => (source.to_string(), None),
+    };
+
+    let expected: Vec<&str> = expected.iter().map(String::as_str).collect();
+    Some(ConfigError::UnknownKey {
+        key: match table {
+            Some(table) => format!("{}.{}", table, key),
+            None => key.clone(),
+        },
+        location,
+        suggestion: suggest_key(&key, &expected).map(str::to_string),
+    })
+}
+
+/// Closest valid key to a misspelled one, if it is plausibly a typo
+///
+/// Accepts candidates within an edit distance of a third of the key length
+/// (at least 1), so `worker_cont` suggests `worker_count` but unrelated
+/// names suggest nothing.
+pub fn suggest_key<'a>(unknown: &str, valid: &[&'a str]) -> Option<&'a str> {
+    let max_distance = (unknown.chars().count() / 3).max(1);
+    valid
+        .iter()
+        .map(|candidate| (*candidate, edit_distance(unknown, candidate)))
+        .filter(|(_, distance)| *distance <= max_distance)
+        .min_by_key(|(_, distance)| *distance)
+        .map(|(candidate, _)| candidate)
+}
+
+/// Splits ``unknown field `x`, expected one of `a`, `b` `` into `x` and `[a, b]`
+fn parse_unknown_field(message: &str) -> Option<(String, Vec<String>)> {
+    let rest = &message[message.find("unknown field `")? + "unknown field `".len()..];
+    let end = rest.find('`')?;
+    let key = rest[..end].to_string();
+
+    let expected = rest[end + 1..]
+        .split('`')
+        .skip(1)
+        .step_by(2)
+        .map(str::to_string)
+        .collect();
+    Some((key, expected))
+}
+
+fn assigns_key(line: &str, key: &str) -> bool {
+    let line = line.trim_start();
+    let unquoted = line
+        .strip_prefix('"')
+        .and_then(|l| l.strip_prefix(key))
+        .and_then(|l| l.strip_prefix('"'));
+    match unquoted.or_else(|| line.strip_prefix(key)) {
+        Some(rest) => rest.trim_start().starts_with('='),
+        None => false,
+    }
+}
+
+fn table_header(line: &str) -> Option<String> {
+    let line = line.trim();
+    let inner = line
+        .strip_prefix("[[")
+        .and_then(|l| l.split("]]").next())
+        .or_else(|| line.strip_prefix('[').and_then(|l| l.split(']').next()))?;
+    Some(inner.trim().to_string())
+}
+
+/// Levenshtein distance between two strings
+fn edit_distance(a: &str, b: &str) -> usize {
+    let b: Vec<char> = b.chars().collect();
+    let mut previous: Vec<usize> = (0..=b.len()).collect();
+    let mut current = vec![0; b.len() + 1];
+
+    for (i, ca) in a.chars().enumerate() {
+        current[0] = i + 1;
+        for (j, cb) in b.iter().enumerate() {
+            let substitution = previous[j] + usize::from(ca != *cb);
+            current[j + 1] = substitution.min(previous[j + 1] + 1).min(current[j] + 1);
+        }
+        std::mem::swap(&mut previous, &mut current);
+    }
+
+    previous[b.len()]
+}
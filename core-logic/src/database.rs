@@ -1,23 +1,46 @@
 use anyhow::{Context, Result};
-use sqlx::sqlite::{SqlitePool, SqlitePoolOptions};
-use std::path::Path;
-use std::sync::atomic::{AtomicU64, Ordering};
-use std::sync::Arc;
+use serde::{Deserialize, Serialize};
+use sqlx::sqlite::{
+    SqliteConnectOptions, SqliteJournalMode, SqlitePool, SqlitePoolOptions, SqliteSynchronous,
+};
+use std::path::{Path, PathBuf};
+use std::str::FromStr;
+use std::sync::atomic::Ordering;
+use std::sync::{Arc, PoisonError, RwLock};
 use std::time::Duration;
 use tokio::sync::mpsc;
 use tokio::task::JoinHandle;
 use tokio::time::Instant;
-use tracing::{debug, error, info, warn};
+use tracing::{debug, error, info, instrument, warn};
+use zeroize::Zeroizing;
 
 use crate::error::{ConfigError, DatabaseError};
+use crate::metrics::{Counter, Histogram, MetricsCollector};
+use crate::traits::TaskResult;
 use smallvec::SmallVec;
 
 /// Configuration for async database logging
-#[derive(Debug, Clone, Copy)]
+#[derive(Debug, Clone)]
 pub struct AsyncDbConfig {
     pub channel_capacity: usize,
     pub batch_size: usize,
     pub flush_interval_ms: u64,
+    pub journal_mode: JournalMode,
+    pub synchronous: SynchronousLevel,
+    /// How long a connection waits on a locked database before SQLITE_BUSY
+    pub busy_timeout_ms: u64,
+    /// Passive WAL checkpoint interval of the flush worker, 0 = SQLite's
+    /// automatic checkpoints only
+    pub checkpoint_interval_ms: u64,
+    /// JSONL file that batches the flush worker fails to write are appended
+    /// to as [`SpillRecord`] lines, for [`DatabaseManager::import_spill_file`].
+    /// `None` drops them
+    pub spill_path: Option<PathBuf>,
+    /// Base path of monthly shards: the flush worker writes each batch to
+    /// the [shard](monthly_shard_path) of the current month and opens the
+    /// next one when the month changes. `None` keeps writing to the
+    /// database the manager opened
+    pub shard_base_path: Option<String>,
 }
 
 impl Default for AsyncDbConfig {
@@ -26,20 +49,247 @@ impl Default for AsyncDbConfig {
             channel_capacity: 1000,
             batch_size: 200,
             flush_interval_ms: 200,
+            journal_mode: JournalMode::default(),
+            synchronous: SynchronousLevel::default(),
+            busy_timeout_ms: 5000,
+            checkpoint_interval_ms: 60_000,
+            spill_path: None,
+            shard_base_path: None,
+        }
+    }
+}
+
+/// `PRAGMA user_version` of databases whose schema is current
+///
+/// [`DatabaseManager::open_read_only`] migrates files stamped with an older one.
+const SCHEMA_VERSION: i64 = 1;
+
+/// SQLCipher key of the databases opened from now on, `None` for plain SQLite
+static ENCRYPTION_KEY: RwLock<Option<Zeroizing<String>>> = RwLock::new(None);
+
+/// Opens every database from now on encrypted with SQLCipher under `key`
+///
+/// `None` goes back to plain SQLite. The key only takes effect in builds
+/// with the `sqlcipher` feature; with a key set, a build on plain SQLite
+/// refuses to open databases rather than write them unencrypted.
+pub fn set_encryption_key(key: Option<Zeroizing<String>>) {
+    *ENCRYPTION_KEY
+        .write()
+        .unwrap_or_else(PoisonError::into_inner) = key;
+}
+
+/// Whether databases are opened with an SQLCipher key
+pub fn encryption_enabled() -> bool {
+    ENCRYPTION_KEY
+        .read()
+        .unwrap_or_else(PoisonError::into_inner)
+        .is_some()
+}
+
+/// SQLite `journal_mode` of every pooled connection
+#[derive(Debug, Clone, Copy, PartialEq, Eq, Default, Serialize, Deserialize)]
+#[serde(rename_all = "lowercase")]
+pub enum JournalMode {
+    Delete,
+    Truncate,
+    Persist,
+    Memory,
+    #[default]
+    Wal,
+    Off,
+}
+
+impl From<JournalMode> for SqliteJournalMode {
+    fn from(mode: JournalMode) -> Self {
+        match mode {
+            JournalMode::Delete => Self::Delete,
+            JournalMode::Truncate => Self::Truncate,
+            JournalMode::Persist => Self::Persist,
+            JournalMode::Memory => Self::Memory,
+            JournalMode::Wal => Self::Wal,
+            JournalMode::Off => Self::Off,
         }
     }
 }
 
+/// SQLite `synchronous` level of every pooled connection
+#[derive(Debug, Clone, Copy, PartialEq, Eq, Default, Serialize, Deserialize)]
+#[serde(rename_all = "lowercase")]
+pub enum SynchronousLevel {
+    Off,
+    #[default]
+    Normal,
+    Full,
+    Extra,
+}
+
+impl From<SynchronousLevel> for SqliteSynchronous {
+    fn from(level: SynchronousLevel) -> Self {
+        match level {
+            SynchronousLevel::Off => Self::Off,
+            SynchronousLevel::Normal => Self::Normal,
+            SynchronousLevel::Full => Self::Full,
+            SynchronousLevel::Extra => Self::Extra,
+        }
+    }
+}
+
+/// Flush attempts the worker makes while the database reports SQLITE_BUSY
+const FLUSH_BUSY_ATTEMPTS: u32 = 5;
+/// Wait before the first flush retry, doubled for each further one
+const FLUSH_RETRY_BASE_MS: u64 = 50;
+
 /// Queued task result for async logging
 #[derive(Debug, Clone)]
 pub struct QueuedTaskResult {
+    /// Chain the task ran on, when several chains share one database
+    pub chain_id: Option<u64>,
+    pub worker_id: String,
+    pub wallet_address: String,
+    pub task_name: String,
+    pub success: bool,
+    pub message: String,
+    pub duration_ms: u64,
+    pub timestamp: i64,
+    pub metadata: TaskMetadata,
+}
+
+/// Structured [`TaskResult`] fields stored next to the message in task_metrics
+///
+/// `token_amounts`, `details` and `verifications` share the JSON `details`
+/// column so tasks can add fields without a schema change.
+#[derive(Debug, Clone, Default, PartialEq, Serialize, Deserialize)]
+#[serde(default)]
+pub struct TaskMetadata {
+    #[serde(skip_serializing_if = "Option::is_none")]
+    pub tx_hash: Option<String>,
+    #[serde(skip_serializing_if = "Option::is_none")]
+    pub gas_used: Option<u64>,
+    #[serde(skip_serializing_if = "Option::is_none")]
+    pub block_number: Option<u64>,
+    #[serde(skip_serializing_if = "Option::is_none")]
+    pub contract_address: Option<String>,
+    /// JSON object with `token_amounts`, `details` and `verifications`, `None` when all are empty
+    #[serde(skip_serializing_if = "Option::is_none")]
+    pub details: Option<String>,
+    /// Timeout the task ran under, in milliseconds
+    #[serde(skip_serializing_if = "Option::is_none")]
+    pub timeout_ms: Option<u64>,
+    /// Wallet partition of the host that ran the task, e.g. `2/5`, for
+    /// merging the databases of several hosts
+    #[serde(skip_serializing_if = "Option::is_none")]
+    pub host_partition: Option<String>,
+    /// JSON snapshot taken when the task ran past its soft threshold: RPC
+    /// calls in flight, proxy, nonce state and recent provider errors
+    #[serde(skip_serializing_if = "Option::is_none")]
+    pub diagnostics: Option<String>,
+}
+
+impl From<&TaskResult> for TaskMetadata {
+    fn from(result: &TaskResult) -> Self {
+        let mut extra = serde_json::Map::new();
+        if !result.token_amounts.is_empty() {
+            extra.insert(
+                "token_amounts".to_string(),
+                serde_json::to_value(&result.token_amounts).unwrap_or_default(),
+            );
+        }
+        if !result.details.is_null() {
+            extra.insert("details".to_string(), result.details.clone());
+        }
+        if !result.verifications.is_empty() {
+            extra.insert(
+                "verifications".to_string(),
+                serde_json::to_value(&result.verifications).unwrap_or_default(),
+            );
+        }
+
+        Self {
+            tx_hash: result.tx_hash.clone(),
+            gas_used: result.gas_used,
+            block_number: result.block_number,
+            contract_address: result.contract_address.clone(),
+            details: (!extra.is_empty()).then(|| serde_json::Value::Object(extra).to_string()),
+            timeout_ms: None,
+            host_partition: None,
+            diagnostics: None,
+        }
+    }
+}
+
+/// A single task result line in a JSONL spill file
+///
+/// Spill files hold results that never reached the database: batches the
+/// flush worker failed to write when [`AsyncDbConfig::spill_path`] is set, or
+/// the task results of a run bundle. They are merged back with
+/// [`DatabaseManager::import_spill_file`]. Records without a `run_id` are
+/// attributed to the spill file's name so repeated imports still dedup.
+#[derive(Debug, Clone, Serialize, Deserialize)]
+pub struct SpillRecord {
+    #[serde(default)]
+    pub run_id: Option<String>,
+    #[serde(default)]
+    pub chain_id: Option<u64>,
     pub worker_id: String,
     pub wallet_address: String,
     pub task_name: String,
     pub success: bool,
+    #[serde(default)]
     pub message: String,
     pub duration_ms: u64,
     pub timestamp: i64,
+    #[serde(default, flatten)]
+    pub metadata: TaskMetadata,
+}
+
+/// One task_metrics row as read back by [`DatabaseManager::get_task_records`]
+#[derive(sqlx::FromRow)]
+struct TaskRecordRow {
+    run_id: Option<String>,
+    chain_id: Option<i64>,
+    worker_id: String,
+    wallet_address: String,
+    task_name: String,
+    status: String,
+    message: Option<String>,
+    duration_ms: i64,
+    timestamp: i64,
+    tx_hash: Option<String>,
+    gas_used: Option<i64>,
+    block_number: Option<i64>,
+    contract_address: Option<String>,
+    details: Option<String>,
+    timeout_ms: Option<i64>,
+    host_partition: Option<String>,
+    diagnostics: Option<String>,
+}
+
+impl SpillRecord {
+    pub fn from_queued(run_id: &str, result: &QueuedTaskResult) -> Self {
+        Self {
+            run_id: Some(run_id.to_string()),
+            chain_id: result.chain_id,
+            worker_id: result.worker_id.clone(),
+            wallet_address: result.wallet_address.clone(),
+            task_name: result.task_name.clone(),
+            success: result.success,
+            message: result.message.clone(),
+            duration_ms: result.duration_ms,
+            timestamp: result.timestamp,
+            metadata: result.metadata.clone(),
+        }
+    }
+}
+
+/// Outcome of a spill file import
+#[derive(Debug, Clone, Copy, Default, PartialEq, Eq)]
+pub struct SpillImportSummary {
+    /// Rows newly written to task_metrics
+    pub imported: usize,
+    /// Rows already present (same run id, worker, timestamp and task)
+    pub duplicates: usize,
+    /// Lines that could not be parsed and were skipped
+    pub malformed: usize,
 }
 
 /// Fallback strategy when channel is full
@@ -71,18 +321,450 @@ pub struct DatabaseManager {
     fallback_strategy: Option<FallbackStrategy>,
 }
 
+/// Query counters of one database, shared with the metrics registry
+///
+/// [`DbMetrics::register`] backs every field with a `db_*` series labeled
+/// with the database path, so the monitor line and the exporters read the
+/// same numbers. `Default` gives unregistered series.
 #[derive(Debug, Default)]
 pub struct DbMetrics {
-    pub total_queries: AtomicU64,
-    pub total_errors: AtomicU64,
-    pub total_inserts: AtomicU64,
-    pub total_selects: AtomicU64,
-    pub avg_query_time_ms: AtomicU64,
-    pub query_count_for_avg: AtomicU64,
+    pub total_queries: Arc<Counter>,
+    pub total_errors: Arc<Counter>,
+    pub total_inserts: Arc<Counter>,
+    pub total_selects: Arc<Counter>,
+    pub query_duration: Arc<Histogram>,
     /// Async-specific metrics
-    pub queued_entries: AtomicU64,
-    pub dropped_entries: AtomicU64,
-    pub batch_flush_count: AtomicU64,
+    pub queued_entries: Arc<Counter>,
+    pub dropped_entries: Arc<Counter>,
+}
+
+impl DbMetrics {
+    pub fn register(metrics: &MetricsCollector, db_path: &str) -> Self {
+        let labels = [("db", db_path)];
+        Self {
+            total_queries: metrics.counter("db_queries_total", &labels),
+            total_errors: metrics.counter("db_errors_total", &labels),
+            total_inserts: metrics.counter("db_inserts_total", &labels),
+            total_selects: metrics.counter("db_selects_total", &labels),
+            query_duration: metrics.histogram("db_query_duration_ms", &labels),
+            queued_entries: metrics.counter("db_queued_entries_total", &labels),
+            dropped_entries: metrics.counter("db_dropped_entries_total", &labels),
+        }
+    }
+}
+
+/// Per-task outcome totals from task_metrics
+#[derive(Debug, Clone, sqlx::FromRow)]
+pub struct TaskSummary {
+    pub task_name: String,
+    pub success: i64,
+    pub failed: i64,
+    pub total_duration_ms: i64,
+    pub total_gas_used: i64,
+    /// Rows that recorded gas used
+    pub gas_samples: i64,
+    /// Rows whose task checked on-chain state afterwards
+    pub verified: i64,
+    /// Verified rows where at least one check failed
+    pub mismatched: i64,
+}
+
+impl TaskSummary {
+    pub fn total(&self) -> i64 {
+        self.success + self.failed
+    }
+
+    /// Average gas per run that reported it, `None` when none did
+    pub fn avg_gas_used(&self) -> Option<f64> {
+        (self.gas_samples > 0).then(|| self.total_gas_used as f64 / self.gas_samples as f64)
+    }
+
+    pub fn avg_duration_ms(&self) -> f64 {
+        if self.total() == 0 {
+            0.0
+        } else {
+            self.total_duration_ms as f64 / self.total() as f64
+        }
+    }
+}
+
+/// Per-chain outcome totals from task_metrics
+///
+/// Rows written before the chain_id column existed are grouped under `None`.
+#[derive(Debug, Clone, PartialEq, Eq, sqlx::FromRow)]
+pub struct ChainSummary {
+    pub chain_id: Option<i64>,
+    pub success: i64,
+    pub failed: i64,
+}
+
+impl ChainSummary {
+    pub fn total(&self) -> i64 {
+        self.success + self.failed
+    }
+}
+
+/// Traffic counters for one proxy, added onto its proxy_stats row
+#[derive(Debug, Clone, Copy, Default, PartialEq, Eq)]
+pub struct ProxyUsage {
+    pub requests: u64,
+    pub successes: u64,
+    pub failures: u64,
+    pub bytes_sent: u64,
+    pub bytes_received: u64,
+}
+
+impl ProxyUsage {
+    pub fn is_empty(&self) -> bool {
+        self.requests == 0
+    }
+}
+
+/// Accumulated proxy_stats row for reporting
+#[derive(Debug, Clone, sqlx::FromRow)]
+pub struct ProxyStats {
+    pub proxy_url: String,
+    pub request_count: i64,
+    pub success_count: i64,
+    pub fail_count: i64,
+    pub bytes_sent: i64,
+    pub bytes_received: i64,
+}
+
+impl ProxyStats {
+    pub fn total_bytes(&self) -> i64 {
+        self.bytes_sent + self.bytes_received
+    }
+
+    /// Success rate in percent, 0 when nothing was recorded
+    pub fn success_rate(&self) -> f64 {
+        let total = self.success_count + self.fail_count;
+        if total == 0 {
+            0.0
+        } else {
+            self.success_count as f64 / total as f64 * 100.0
+        }
+    }
+}
+
+/// A coordinated burst agreed on by every instance sharing the database
+#[derive(Debug, Clone, PartialEq, sqlx::FromRow)]
+pub struct BurstRound {
+    pub round_id: String,
+    /// Unix time in milliseconds at which every instance starts sending
+    pub start_at_ms: i64,
+    pub duration_ms: i64,
+    /// Target transactions per second for each instance
+    pub target_rate: f64,
+}
+
+/// One instance's sending and inclusion outcome for a burst round
+#[derive(Debug, Clone, PartialEq, sqlx::FromRow)]
+pub struct BurstResult {
+    pub round_id: String,
+    pub instance_id: String,
+    /// Transactions accepted by the RPC
+    pub sent: i64,
+    /// Transactions the RPC rejected or that could not be built
+    pub send_errors: i64,
+    /// Accepted transactions per second over the burst window
+    pub achieved_rate: f64,
+    pub included: i64,
+    pub reverted: i64,
+    /// Accepted but without a receipt when the instance stopped waiting
+    pub pending: i64,
+    pub first_block: Option<i64>,
+    pub last_block: Option<i64>,
+    /// Mean time from send to the receipt being seen, for included transactions
+    pub avg_inclusion_ms: f64,
+    /// Percentiles and maximum of the same times, 0 when nothing landed
+    pub p50_inclusion_ms: f64,
+    pub p90_inclusion_ms: f64,
+    pub p99_inclusion_ms: f64,
+    pub max_inclusion_ms: f64,
+}
+
+impl BurstResult {
+    /// Share of accepted transactions that landed on chain, in percent
+    pub fn inclusion_rate(&self) -> f64 {
+        if self.sent == 0 {
+            0.0
+        } else {
+            (self.included + self.reverted) as f64 / self.sent as f64 * 100.0
+        }
+    }
+}
+
+/// Transactions of one instance's burst that landed in one block
+#[derive(Debug, Clone, PartialEq, sqlx::FromRow)]
+pub struct BurstBlock {
+    pub round_id: String,
+    pub instance_id: String,
+    pub block_number: i64,
+    /// Burst transactions in the block, reverted ones included
+    pub transactions: i64,
+    /// Of those, the ones a validator proposed in a subblock
+    pub in_subblocks: i64,
+    /// Time from send to the receipt being seen
+    pub min_inclusion_ms: f64,
+    pub p50_inclusion_ms: f64,
+    pub max_inclusion_ms: f64,
+}
+
+/// One transaction's way from send to confirmation
+///
+/// A subblock has no timestamp of its own: it is included when the proposer
+/// seals the block, so `included_at_ms` is the block's millisecond timestamp
+/// whichever lane carried the transaction.
+#[derive(Debug, Clone, PartialEq, sqlx::FromRow)]
+pub struct LatencySample {
+    pub tx_hash: String,
+    /// What sent the transaction, e.g. `burst:<round>`
+    pub source: String,
+    pub wallet_address: String,
+    pub block_number: i64,
+    /// Validator of the subblock that carried it, `unlisted` for a subblock
+    /// the block's metadata does not name, `None` for the proposer's own lane
+    pub subblock: Option<String>,
+    /// Unix milliseconds the transaction was sent
+    pub sent_at_ms: i64,
+    /// Unix milliseconds of the block that included it
+    pub included_at_ms: i64,
+    /// Unix milliseconds its receipt was seen
+    pub confirmed_at_ms: i64,
+}
+
+/// A wallet's running spend of one token on one UTC day
+///
+/// Amounts are decimal strings so full 256-bit token amounts round-trip.
+#[derive(Debug, Clone, PartialEq, Eq, sqlx::FromRow)]
+pub struct SpendRecord {
+    /// UTC day as `YYYY-MM-DD`
+    pub day: String,
+    pub wallet_address: String,
+    pub token: String,
+    pub amount: String,
+}
+
+/// Outcome of re-reading a task's state change through a second endpoint
+#[derive(Debug, Clone, PartialEq, Eq)]
+pub struct ConsistencyCheck {
+    pub task_name: String,
+    pub wallet_address: String,
+    /// What was re-read, e.g. `call 0x...` or `code 0x...`
+    pub target: String,
+    /// Endpoint or proxy the state was re-read through
+    pub reader: String,
+    /// Whether the reader saw the written state before the timeout
+    pub consistent: bool,
+    /// Time from the write to the first matching read, or to giving up
+    pub lag_ms: i64,
+    pub attempts: i64,
+    pub timestamp: i64,
+}
+
+/// Per-task propagation totals from consistency_checks
+#[derive(Debug, Clone, PartialEq, sqlx::FromRow)]
+pub struct ConsistencySummary {
+    pub task_name: String,
+    pub checks: i64,
+    pub inconsistent: i64,
+    /// Mean lag of the checks that became consistent
+    pub avg_lag_ms: f64,
+    pub max_lag_ms: i64,
+}
+
+/// Gas paid by one transaction, from its receipt
+#[derive(Debug, Clone, PartialEq, Eq)]
+pub struct GasUsageRecord {
+    pub task_name: String,
+    pub wallet_address: String,
+    pub tx_hash: String,
+    pub gas_used: i64,
+    /// Wei per gas actually paid
+    pub effective_gas_price: i64,
+    pub block_number: Option<i64>,
+    /// Receipt status; reverted transactions still pay for gas
+    pub success: bool,
+    pub timestamp: i64,
+}
+
+/// Per-task totals from gas_usage
+#[derive(Debug, Clone, PartialEq, sqlx::FromRow)]
+pub struct GasSummary {
+    pub task_name: String,
+    pub transactions: i64,
+    pub reverted: i64,
+    pub total_gas: i64,
+    pub max_gas: i64,
+    /// Sum of gas_used * effective_gas_price in wei, as a float to avoid overflow
+    pub total_cost_wei: f64,
+}
+
+impl GasSummary {
+    pub fn avg_gas(&self) -> f64 {
+        self.total_gas as f64 / self.transactions.max(1) as f64
+    }
+
+    /// Mean price paid per unit of gas, in wei
+    pub fn avg_gas_price(&self) -> f64 {
+        self.total_cost_wei / self.total_gas.max(1) as f64
+    }
+}
+
+/// One log of a task transaction's receipt
+#[derive(Debug, Clone, PartialEq, Eq)]
+pub struct ReceiptLogRecord {
+    pub tx_hash: String,
+    /// Position of the log in its block
+    pub log_index: i64,
+    pub task_name: String,
+    pub block_number: Option<i64>,
+    /// Contract that emitted the log
+    pub address: String,
+    /// Topics in order; `topic0` is the event signature hash of non-anonymous events
+    pub topics: Vec<String>,
+    /// Log data, zlib-compressed
+    pub data: Vec<u8>,
+    pub timestamp: i64,
+}
+
+/// Logs of one event signature emitted by one contract
+#[derive(Debug, Clone, PartialEq, Eq, sqlx::FromRow)]
+pub struct LogCoverage {
+    pub address: String,
+    /// `None` for anonymous events without topics
+    pub topic0: Option<String>,
+    pub logs: i64,
+    /// Comma-separated names of the tasks whose transactions emitted them
+    pub tasks: String,
+}
+
+/// Full context of one sampled task run
+#[derive(Debug, Clone, PartialEq, Eq, sqlx::FromRow)]
+pub struct AuditRecord {
+    pub task_name: String,
+    pub worker_id: String,
+    pub wallet_address: String,
+    /// Proxy the run went through, or `direct`
+    pub proxy: String,
+    pub success: bool,
+    pub message: String,
+    pub tx_hash: Option<String>,
+    /// Nonce, nonce key and gas parameters of the last transaction sent
+    pub nonce: Option<i64>,
+    pub nonce_key: Option<String>,
+    pub gas_limit: Option<i64>,
+    pub max_fee_per_gas: Option<String>,
+    pub max_priority_fee_per_gas: Option<String>,
+    /// JSON array of every transaction sent, decoded
+    pub transactions: String,
+    /// JSON array of timed steps, one per RPC call
+    pub steps: String,
+    pub duration_ms: i64,
+    pub timestamp: i64,
+}
+
+/// A framework helper contract deployed by `bootstrap`, one per chain and name
+#[derive(Debug, Clone, PartialEq, Eq, sqlx::FromRow)]
+pub struct HelperContractRecord {
+    pub chain_id: i64,
+    pub name: String,
+    pub address: String,
+    /// keccak256 of the runtime code, to spot a reset chain
+    pub code_hash: String,
+    pub tx_hash: String,
+    pub deployer: String,
+    pub deployed_at: i64,
+}
+
+/// An ERC-4337 smart account created for a wallet, one per chain and address
+#[derive(Debug, Clone, PartialEq, Eq, sqlx::FromRow)]
+pub struct SmartAccountRecord {
+    pub chain_id: i64,
+    /// Wallet whose key signs the account's user operations
+    pub owner: String,
+    pub account: String,
+    pub factory: String,
+    /// Factory salt, decimal
+    pub salt: String,
+    pub tx_hash: String,
+    pub created_at: i64,
+}
+
+/// An NFT listed on a marketplace, one per chain, marketplace and listing id
+#[derive(Debug, Clone, PartialEq, Eq, sqlx::FromRow)]
+pub struct NftListingRecord {
+    pub chain_id: i64,
+    pub marketplace: String,
+    /// Listing id the marketplace assigned, decimal
+    pub listing_id: String,
+    pub seller: String,
+    pub nft: String,
+    /// Token id, decimal
+    pub token_id: String,
+    /// TIP-20 token the price is paid in
+    pub payment_token: String,
+    /// Price in the payment token's base units, decimal
+    pub price: String,
+    /// `active`, `sold`, `cancelled`, or `closed` when found gone on chain
+    pub status: String,
+    pub tx_hash: String,
+    pub listed_at: i64,
+}
+
+/// A name registered with the name service, one per chain and name
+#[derive(Debug, Clone, PartialEq, Eq, sqlx::FromRow)]
+pub struct DomainRecord {
+    pub chain_id: i64,
+    /// Full name, e.g. `abc.tempo`
+    pub name: String,
+    /// ENS namehash of the name, hex
+    pub node: String,
+    /// Registrar token id, decimal
+    pub token_id: String,
+    pub owner: String,
+    /// Resolver set for the name, empty until one was set
+    pub resolver: String,
+    /// Address record, empty until one was set
+    pub addr: String,
+    pub tx_hash: String,
+    pub registered_at: i64,
+    /// Time of the last renewal, 0 when never renewed
+    pub renewed_at: i64,
+}
+
+/// Outcomes of fuzzed calls to one function of a contract, one row per
+/// chain, contract and selector
+#[derive(Debug, Clone, PartialEq, Eq, sqlx::FromRow)]
+pub struct FuzzResultRecord {
+    pub chain_id: i64,
+    /// Name of the helper contract called
+    pub contract: String,
+    /// Function selector, hex
+    pub selector: String,
+    /// Function signature, e.g. `poke(uint256)`
+    pub signature: String,
+    pub successes: i64,
+    pub reverts: i64,
+    /// Reason of the latest revert, empty when none reverted yet
+    pub last_error: String,
+    pub updated_at: i64,
+}
+
+/// A side effect a task sent under an idempotency key, one row per chain
+/// and key
+#[derive(Debug, Clone, PartialEq, Eq, sqlx::FromRow)]
+pub struct OperationRecord {
+    pub chain_id: i64,
+    /// Deterministic key the task derived for the side effect
+    pub op_key: String,
+    pub wallet_address: String,
+    /// Latest transaction sent for the key
+    pub tx_hash: String,
+    /// `sent` until the transaction is known to have succeeded, then `done`
+    pub status: String,
+    pub updated_at: i64,
 }
 
 #[derive(Debug, Clone, sqlx::FromRow)]
@@ -105,41 +787,19 @@ impl DatabaseManager {
     pub const DEFAULT_TIMEOUT_MS: u64 = 30000;
 
     pub async fn new(db_path: &str) -> Result<Self> {
-        if !Path::new(db_path).exists() {
-            std::fs::File::create(db_path).map_err(|e| ConfigError::IoError {
-                path: db_path.to_string(),
-                msg: e.to_string(),
-            })?;
-            info!("Created new database file: {}", db_path);
-        }
+        Self::create_file(db_path)?;
 
-        let pool = SqlitePoolOptions::new()
-            .max_connections(Self::DEFAULT_MAX_CONNECTIONS)
-            .acquire_timeout(Duration::from_millis(Self::DEFAULT_TIMEOUT_MS))
-            .after_connect(|conn, _meta| {
-                Box::pin(async move {
-                    sqlx::query("PRAGMA journal_mode=WAL;")
-                        .execute(&mut *conn)
-                        .await?;
-                    sqlx::query("PRAGMA synchronous=NORMAL;")
-                        .execute(&mut *conn)
-                        .await?;
-                    Ok(())
-                })
-            })
-            .connect(&format!("sqlite://{}", db_path))
-            .await
-            .map_err(|e| DatabaseError::TransactionFailed { msg: e.to_string() })?;
+        let pool = Self::connect(db_path, &AsyncDbConfig::default(), false).await?;
 
         let manager = Self {
             pool,
-            metrics: Arc::new(DbMetrics::default()),
+            metrics: Arc::new(DbMetrics::register(MetricsCollector::global(), db_path)),
             log_sender: None,
             flush_handle: None,
             async_config: None,
             fallback_strategy: None,
         };
-        manager.init_schema().await?;
+        Self::init_schema(&manager.pool).await?;
         info!(
             "Database initialized with pool size {} (WAL Mode)",
             Self::DEFAULT_MAX_CONNECTIONS
@@ -147,12 +807,39 @@ impl DatabaseManager {
         Ok(manager)
     }
 
-    /// Create a new DatabaseManager with async logging enabled
-    pub async fn new_with_async(
-        db_path: &str,
-        config: AsyncDbConfig,
-        fallback: FallbackStrategy,
-    ) -> Result<Self> {
+    /// Opens a database with the current schema for reading
+    ///
+    /// The file is not written to unless its schema is older than this
+    /// build's: only then are the tables and columns added, as
+    /// [`new`](Self::new) would. No task data should be written through the
+    /// returned manager.
+    pub async fn open_read_only(db_path: &str) -> Result<Self> {
+        let config = AsyncDbConfig::default();
+        let pool = Self::connect(db_path, &config, true).await?;
+        let version: i64 = sqlx::query_scalar("PRAGMA user_version")
+            .fetch_one(&pool)
+            .await
+            .map_err(|e| DatabaseError::TransactionFailed { msg: e.to_string() })?;
+        let pool = if version < SCHEMA_VERSION {
+            pool.close().await;
+            info!("Migrating database {} to the current schema", db_path);
+            let pool = Self::connect(db_path, &config, false).await?;
+            Self::init_schema(&pool).await?;
+            pool
+        } else {
+            pool
+        };
+        Ok(Self {
+            pool,
+            metrics: Arc::new(DbMetrics::register(MetricsCollector::global(), db_path)),
+            log_sender: None,
+            flush_handle: None,
+            async_config: None,
+            fallback_strategy: None,
+        })
+    }
+
+    fn create_file(db_path: &str) -> Result<()> {
         if !Path::new(db_path).exists() {
             std::fs::File::create(db_path).map_err(|e| ConfigError::IoError {
                 path: db_path.to_string(),
@@ -160,34 +847,104 @@ impl DatabaseManager {
             })?;
             info!("Created new database file: {}", db_path);
         }
+        Ok(())
+    }
 
+    /// Pool whose connections apply the journal, synchronous and busy
+    /// timeout settings of `config`, keyed with the
+    /// [encryption key](set_encryption_key) when one is set
+    ///
+    /// A `read_only` pool leaves the journal and synchronous settings to
+    /// the file.
+    async fn connect(db_path: &str, config: &AsyncDbConfig, read_only: bool) -> Result<SqlitePool> {
+        let mut options = SqliteConnectOptions::from_str(&format!("sqlite://{}", db_path))
+            .map_err(|e| DatabaseError::TransactionFailed { msg: e.to_string() })?
+            .busy_timeout(Duration::from_millis(config.busy_timeout_ms));
+        options = if read_only {
+            options.read_only(true)
+        } else {
+            options
+                .journal_mode(config.journal_mode.into())
+                .synchronous(config.synchronous.into())
+        };
+        let keyed = {
+            let key = ENCRYPTION_KEY
+                .read()
+                .unwrap_or_else(PoisonError::into_inner);
+            if let Some(key) = key.as_ref() {
+                // sqlx sends `key` before every other pragma, as SQLCipher needs
+                options = options.pragma("key", format!("'{}'", key.replace('\'', "''")));
+            }
+            key.is_some()
+        };
         let pool = SqlitePoolOptions::new()
             .max_connections(Self::DEFAULT_MAX_CONNECTIONS)
             .acquire_timeout(Duration::from_millis(Self::DEFAULT_TIMEOUT_MS))
-            .after_connect(|conn, _meta| {
-                Box::pin(async move {
-                    sqlx::query("PRAGMA journal_mode=WAL;")
-                        .execute(&mut *conn)
-                        .await?;
-                    sqlx::query("PRAGMA synchronous=NORMAL;")
-                        .execute(&mut *conn)
-                        .await?;
-                    Ok(())
-                })
-            })
-            .connect(&format!("sqlite://{}", db_path))
+            .connect_with(options)
+            .await
+            .map_err(|e| {
+                if keyed {
+                    DatabaseError::Encryption {
+                        msg: format!("the key does not open {} ({})", db_path, e),
+                    }
+                } else {
+                    DatabaseError::TransactionFailed { msg: e.to_string() }
+                }
+            })?;
+        if keyed {
+            Self::check_cipher(&pool, db_path).await?;
+        }
+        Ok(pool)
+    }
+
+    /// Fails unless SQLite is SQLCipher and the key reads `db_path`
+    async fn check_cipher(pool: &SqlitePool, db_path: &str) -> Result<()> {
+        let version: Option<String> = sqlx::query_scalar("PRAGMA cipher_version")
+            .fetch_optional(pool)
             .await
             .map_err(|e| DatabaseError::TransactionFailed { msg: e.to_string() })?;
+        let Some(version) = version else {
+            pool.close().await;
+            return Err(DatabaseError::Encryption {
+                msg: "a database key is set but SQLite was built without SQLCipher; \
+                      build with the `sqlcipher` feature"
+                    .to_string(),
+            }
+            .into());
+        };
+        sqlx::query("SELECT count(*) FROM sqlite_master")
+            .execute(pool)
+            .await
+            .map_err(|e| DatabaseError::Encryption {
+                msg: format!("the key does not open {} ({})", db_path, e),
+            })?;
+        debug!("Opened {} with SQLCipher {}", db_path, version);
+        Ok(())
+    }
+
+    /// Create a new DatabaseManager with async logging enabled
+    pub async fn new_with_async(
+        db_path: &str,
+        config: AsyncDbConfig,
+        fallback: FallbackStrategy,
+    ) -> Result<Self> {
+        Self::create_file(db_path)?;
+
+        let pool = Self::connect(db_path, &config, false).await?;
 
-        let metrics = Arc::new(DbMetrics::default());
+        let metrics = Arc::new(DbMetrics::register(MetricsCollector::global(), db_path));
 
         // Create channel for async logging
         let (tx, rx) = mpsc::channel(config.channel_capacity);
 
         // Spawn background flush task
-        let pool_clone = pool.clone();
+        let target = FlushTarget {
+            path: db_path.to_string(),
+            pool: pool.clone(),
+        };
+        let worker_config = config.clone();
         let flush_handle = tokio::spawn(async move {
-            db_flush_worker(rx, pool_clone, config).await;
+            db_flush_worker(rx, target, worker_config).await;
         });
 
         let manager = Self {
@@ -195,11 +952,11 @@ impl DatabaseManager {
             metrics,
             log_sender: Some(tx),
             flush_handle: Some(flush_handle),
-            async_config: Some(config),
+            async_config: Some(config.clone()),
             fallback_strategy: Some(fallback),
         };
 
-        manager.init_schema().await?;
+        Self::init_schema(&manager.pool).await?;
         info!(
             "Database initialized with async logging (channel: {}, batch: {}, interval: {}ms)",
             config.channel_capacity, config.batch_size, config.flush_interval_ms
@@ -208,9 +965,9 @@ impl DatabaseManager {
         Ok(manager)
     }
 
-    async fn init_schema(&self) -> Result<()> {
-        let mut conn = self
-            .pool
+    /// Creates the tables, migrates them and stamps [`SCHEMA_VERSION`]
+    async fn init_schema(pool: &SqlitePool) -> Result<()> {
+        let mut conn = pool
             .acquire()
             .await
             .map_err(|_| DatabaseError::PoolExhausted {
@@ -226,7 +983,8 @@ impl DatabaseManager {
                 status TEXT,
                 message TEXT,
                 duration_ms INTEGER,
-                timestamp INTEGER
+                timestamp INTEGER,
+                chain_id INTEGER
             );
             CREATE TABLE IF NOT EXISTS created_counter_contracts (
                 id INTEGER PRIMARY KEY,
@@ -248,16 +1006,195 @@ impl DatabaseManager {
                 id INTEGER PRIMARY KEY,
                 proxy_url TEXT UNIQUE,
                 success_count INTEGER DEFAULT 0,
-                fail_count INTEGER DEFAULT 0
+                fail_count INTEGER DEFAULT 0,
+                request_count INTEGER DEFAULT 0,
+                bytes_sent INTEGER DEFAULT 0,
+                bytes_received INTEGER DEFAULT 0
             );
-            CREATE TABLE IF NOT EXISTS dex_orders (
+            CREATE TABLE IF NOT EXISTS burst_rounds (
+                round_id TEXT PRIMARY KEY,
+                start_at_ms INTEGER NOT NULL,
+                duration_ms INTEGER NOT NULL,
+                target_rate REAL NOT NULL
+            );
+            CREATE TABLE IF NOT EXISTS burst_results (
+                round_id TEXT NOT NULL,
+                instance_id TEXT NOT NULL,
+                sent INTEGER NOT NULL,
+                send_errors INTEGER NOT NULL,
+                achieved_rate REAL NOT NULL,
+                included INTEGER NOT NULL,
+                reverted INTEGER NOT NULL,
+                pending INTEGER NOT NULL,
+                first_block INTEGER,
+                last_block INTEGER,
+                avg_inclusion_ms REAL NOT NULL,
+                p50_inclusion_ms REAL NOT NULL DEFAULT 0,
+                p90_inclusion_ms REAL NOT NULL DEFAULT 0,
+                p99_inclusion_ms REAL NOT NULL DEFAULT 0,
+                max_inclusion_ms REAL NOT NULL DEFAULT 0,
+                PRIMARY KEY (round_id, instance_id)
+            );
+            CREATE TABLE IF NOT EXISTS burst_blocks (
+                round_id TEXT NOT NULL,
+                instance_id TEXT NOT NULL,
+                block_number INTEGER NOT NULL,
+                transactions INTEGER NOT NULL,
+                in_subblocks INTEGER NOT NULL,
+                min_inclusion_ms REAL NOT NULL,
+                p50_inclusion_ms REAL NOT NULL,
+                max_inclusion_ms REAL NOT NULL,
+                PRIMARY KEY (round_id, instance_id, block_number)
+            );
+            CREATE TABLE IF NOT EXISTS latency_samples (
+                tx_hash TEXT PRIMARY KEY,
+                source TEXT NOT NULL,
+                wallet_address TEXT NOT NULL,
+                block_number INTEGER NOT NULL,
+                subblock TEXT,
+                sent_at_ms INTEGER NOT NULL,
+                included_at_ms INTEGER NOT NULL,
+                confirmed_at_ms INTEGER NOT NULL
+            );
+            CREATE INDEX IF NOT EXISTS idx_latency_samples_source ON latency_samples(source);
+            CREATE TABLE IF NOT EXISTS spend_ledger (
+                day TEXT NOT NULL,
+                wallet_address TEXT NOT NULL,
+                token TEXT NOT NULL,
+                amount TEXT NOT NULL,
+                PRIMARY KEY (day, wallet_address, token)
+            );
+            CREATE TABLE IF NOT EXISTS consistency_checks (
                 id INTEGER PRIMARY KEY,
+                task_name TEXT,
                 wallet_address TEXT,
-                order_id TEXT,
-                base_token TEXT,
-                quote_token TEXT,
-                amount TEXT,
-                is_bid INTEGER,
+                target TEXT,
+                reader TEXT,
+                consistent INTEGER,
+                lag_ms INTEGER,
+                attempts INTEGER,
+                timestamp INTEGER
+            );
+            CREATE TABLE IF NOT EXISTS gas_usage (
+                id INTEGER PRIMARY KEY,
+                task_name TEXT,
+                wallet_address TEXT,
+                tx_hash TEXT,
+                gas_used INTEGER,
+                effective_gas_price INTEGER,
+                block_number INTEGER,
+                success INTEGER,
+                timestamp INTEGER
+            );
+            CREATE TABLE IF NOT EXISTS receipt_logs (
+                tx_hash TEXT NOT NULL,
+                log_index INTEGER NOT NULL,
+                task_name TEXT NOT NULL,
+                block_number INTEGER,
+                address TEXT NOT NULL,
+                topic0 TEXT,
+                topic1 TEXT,
+                topic2 TEXT,
+                topic3 TEXT,
+                data BLOB NOT NULL,
+                timestamp INTEGER NOT NULL,
+                PRIMARY KEY (tx_hash, log_index)
+            );
+            CREATE INDEX IF NOT EXISTS idx_receipt_logs_task ON receipt_logs(task_name);
+            CREATE TABLE IF NOT EXISTS audits (
+                id INTEGER PRIMARY KEY,
+                task_name TEXT,
+                worker_id TEXT,
+                wallet_address TEXT,
+                proxy TEXT,
+                success INTEGER,
+                message TEXT,
+                tx_hash TEXT,
+                nonce INTEGER,
+                nonce_key TEXT,
+                gas_limit INTEGER,
+                max_fee_per_gas TEXT,
+                max_priority_fee_per_gas TEXT,
+                transactions TEXT,
+                steps TEXT,
+                duration_ms INTEGER,
+                timestamp INTEGER
+            );
+            CREATE TABLE IF NOT EXISTS helper_contracts (
+                chain_id INTEGER,
+                name TEXT,
+                address TEXT,
+                code_hash TEXT,
+                tx_hash TEXT,
+                deployer TEXT,
+                deployed_at INTEGER,
+                PRIMARY KEY (chain_id, name)
+            );
+            CREATE TABLE IF NOT EXISTS smart_accounts (
+                chain_id INTEGER,
+                owner TEXT,
+                account TEXT,
+                factory TEXT,
+                salt TEXT,
+                tx_hash TEXT,
+                created_at INTEGER,
+                PRIMARY KEY (chain_id, account)
+            );
+            CREATE TABLE IF NOT EXISTS nft_listings (
+                chain_id INTEGER,
+                marketplace TEXT,
+                listing_id TEXT,
+                seller TEXT,
+                nft TEXT,
+                token_id TEXT,
+                payment_token TEXT,
+                price TEXT,
+                status TEXT,
+                tx_hash TEXT,
+                listed_at INTEGER,
+                PRIMARY KEY (chain_id, marketplace, listing_id)
+            );
+            CREATE TABLE IF NOT EXISTS domains (
+                chain_id INTEGER,
+                name TEXT,
+                node TEXT,
+                token_id TEXT,
+                owner TEXT,
+                resolver TEXT,
+                addr TEXT,
+                tx_hash TEXT,
+                registered_at INTEGER,
+                renewed_at INTEGER,
+                PRIMARY KEY (chain_id, name)
+            );
+            CREATE TABLE IF NOT EXISTS fuzz_results (
+                chain_id INTEGER,
+                contract TEXT,
+                selector TEXT,
+                signature TEXT,
+                successes INTEGER DEFAULT 0,
+                reverts INTEGER DEFAULT 0,
+                last_error TEXT,
+                updated_at INTEGER,
+                PRIMARY KEY (chain_id, contract, selector)
+            );
+            CREATE TABLE IF NOT EXISTS operations (
+                chain_id INTEGER,
+                op_key TEXT,
+                wallet_address TEXT,
+                tx_hash TEXT,
+                status TEXT,
+                updated_at INTEGER,
+                PRIMARY KEY (chain_id, op_key)
+            );
+            CREATE TABLE IF NOT EXISTS dex_orders (
+                id INTEGER PRIMARY KEY,
+                wallet_address TEXT,
+                order_id TEXT,
+                base_token TEXT,
+                quote_token TEXT,
+                amount TEXT,
+                is_bid INTEGER,
                 tick INTEGER,
                 tx_hash TEXT,
                 status TEXT,
@@ -268,25 +1205,67 @@ impl DatabaseManager {
         .await
         .map_err(|e| DatabaseError::TransactionFailed { msg: e.to_string() })?;
 
-        self.create_indexes().await?;
+        Self::apply_migrations(pool).await?;
+        Self::create_indexes(pool).await?;
+        sqlx::query(&format!("PRAGMA user_version = {}", SCHEMA_VERSION))
+            .execute(pool)
+            .await
+            .map_err(|e| DatabaseError::TransactionFailed { msg: e.to_string() })?;
 
         info!("Database schema initialized with indexes.");
         Ok(())
     }
 
-    async fn create_indexes(&self) -> Result<()> {
+    /// Add columns introduced after the initial schema to existing databases
+    ///
+    /// Bump [`SCHEMA_VERSION`] with every change here or to the tables.
+    async fn apply_migrations(pool: &SqlitePool) -> Result<()> {
+        let migrations = [
+            "ALTER TABLE task_metrics ADD COLUMN run_id TEXT;",
+            "ALTER TABLE task_metrics ADD COLUMN chain_id INTEGER;",
+            "ALTER TABLE task_metrics ADD COLUMN tx_hash TEXT;",
+            "ALTER TABLE task_metrics ADD COLUMN gas_used INTEGER;",
+            "ALTER TABLE task_metrics ADD COLUMN block_number INTEGER;",
+            "ALTER TABLE task_metrics ADD COLUMN contract_address TEXT;",
+            "ALTER TABLE task_metrics ADD COLUMN details TEXT;",
+            "ALTER TABLE task_metrics ADD COLUMN timeout_ms INTEGER;",
+            "ALTER TABLE task_metrics ADD COLUMN host_partition TEXT;",
+            "ALTER TABLE task_metrics ADD COLUMN diagnostics TEXT;",
+            "ALTER TABLE proxy_stats ADD COLUMN request_count INTEGER DEFAULT 0;",
+            "ALTER TABLE proxy_stats ADD COLUMN bytes_sent INTEGER DEFAULT 0;",
+            "ALTER TABLE proxy_stats ADD COLUMN bytes_received INTEGER DEFAULT 0;",
+            "ALTER TABLE burst_results ADD COLUMN p50_inclusion_ms REAL NOT NULL DEFAULT 0;",
+            "ALTER TABLE burst_results ADD COLUMN p90_inclusion_ms REAL NOT NULL DEFAULT 0;",
+            "ALTER TABLE burst_results ADD COLUMN p99_inclusion_ms REAL NOT NULL DEFAULT 0;",
+            "ALTER TABLE burst_results ADD COLUMN max_inclusion_ms REAL NOT NULL DEFAULT 0;",
+        ];
+
+        for migration_sql in migrations {
+            if let Err(e) = sqlx::query(migration_sql).execute(pool).await {
+                debug!("Migration skipped (already applied): {}", e);
+            }
+        }
+        Ok(())
+    }
+
+    async fn create_indexes(pool: &SqlitePool) -> Result<()> {
         let indexes = [
             "CREATE INDEX IF NOT EXISTS idx_task_metrics_wallet ON task_metrics(wallet_address);",
             "CREATE INDEX IF NOT EXISTS idx_task_metrics_task ON task_metrics(task_name);",
             "CREATE INDEX IF NOT EXISTS idx_task_metrics_timestamp ON task_metrics(timestamp);",
+            "CREATE INDEX IF NOT EXISTS idx_task_metrics_chain ON task_metrics(chain_id);",
+            // Live rows carry a NULL run_id, which SQLite never treats as a conflict
+            "CREATE UNIQUE INDEX IF NOT EXISTS idx_task_metrics_dedup ON task_metrics(run_id, worker_id, timestamp, task_name);",
             "CREATE INDEX IF NOT EXISTS idx_contracts_wallet ON created_counter_contracts(wallet_address);",
             "CREATE INDEX IF NOT EXISTS idx_assets_wallet_type ON created_assets(wallet_address, asset_type);",
             "CREATE INDEX IF NOT EXISTS idx_proxy_stats_url ON proxy_stats(proxy_url);",
             "CREATE INDEX IF NOT EXISTS idx_dex_orders_wallet ON dex_orders(wallet_address);",
+            "CREATE INDEX IF NOT EXISTS idx_audits_task ON audits(task_name, timestamp);",
+            "CREATE INDEX IF NOT EXISTS idx_gas_usage_task ON gas_usage(task_name);",
         ];
 
         for idx_sql in indexes {
-            if let Err(e) = sqlx::query(idx_sql).execute(&self.pool).await {
+            if let Err(e) = sqlx::query(idx_sql).execute(pool).await {
                 debug!("Index creation skipped (may exist): {}", e);
             }
         }
@@ -302,20 +1281,74 @@ impl DatabaseManager {
         message: &str,
         duration_ms: u64,
     ) -> Result<()> {
+        self.log_chain_task_result(None, worker_id, wallet, task, success, message, duration_ms)
+            .await
+    }
+
+    /// Same as [`log_task_result`](Self::log_task_result), tagging the row with a chain id
+    ///
+    /// Used when several chain spammers write to one shared database.
+    #[allow(clippy::too_many_arguments)]
+    pub async fn log_chain_task_result(
+        &self,
+        chain_id: Option<u64>,
+        worker_id: &str,
+        wallet: &str,
+        task: &str,
+        success: bool,
+        message: &str,
+        duration_ms: u64,
+    ) -> Result<()> {
+        self.log_task_record(&QueuedTaskResult {
+            chain_id,
+            worker_id: worker_id.to_string(),
+            wallet_address: wallet.to_string(),
+            task_name: task.to_string(),
+            success,
+            message: message.to_string(),
+            duration_ms,
+            timestamp: chrono::Utc::now().timestamp(),
+            metadata: TaskMetadata::default(),
+        })
+        .await
+    }
+
+    /// Write a task result with its metadata right away, bypassing the async queue
+    #[instrument(
+        name = "db.insert",
+        skip_all,
+        fields(
+            db.system = "sqlite",
+            db.table = "task_metrics",
+            wallet = %record.wallet_address,
+            task = %record.task_name,
+            tx_hash = record.metadata.tx_hash.as_deref(),
+        )
+    )]
+    pub async fn log_task_record(&self, record: &QueuedTaskResult) -> Result<()> {
         let start = std::time::Instant::now();
-        let status = if success { "SUCCESS" } else { "FAILED" };
-        let timestamp = chrono::Utc::now().timestamp();
+        let status = if record.success { "SUCCESS" } else { "FAILED" };
+        let meta = &record.metadata;
 
         let result = sqlx::query(
-            "INSERT INTO task_metrics (worker_id, wallet_address, task_name, status, message, duration_ms, timestamp) VALUES (?, ?, ?, ?, ?, ?, ?)"
+            "INSERT INTO task_metrics (chain_id, worker_id, wallet_address, task_name, status, message, duration_ms, timestamp, tx_hash, gas_used, block_number, contract_address, details, timeout_ms, host_partition, diagnostics) VALUES (?, ?, ?, ?, ?, ?, ?, ?, ?, ?, ?, ?, ?, ?, ?, ?)"
         )
-        .bind(worker_id)
-        .bind(wallet)
-        .bind(task)
+        .bind(record.chain_id.map(|id| id as i64))
+        .bind(&record.worker_id)
+        .bind(&record.wallet_address)
+        .bind(&record.task_name)
         .bind(status)
-        .bind(message)
-        .bind(duration_ms as i64)
-        .bind(timestamp)
+        .bind(&record.message)
+        .bind(record.duration_ms as i64)
+        .bind(record.timestamp)
+        .bind(&meta.tx_hash)
+        .bind(meta.gas_used.map(|gas| gas as i64))
+        .bind(meta.block_number.map(|block| block as i64))
+        .bind(&meta.contract_address)
+        .bind(&meta.details)
+        .bind(meta.timeout_ms.map(|ms| ms as i64))
+        .bind(&meta.host_partition)
+        .bind(&meta.diagnostics)
         .execute(&self.pool)
         .await;
 
@@ -346,6 +1379,15 @@ impl DatabaseManager {
     /// # Returns
     /// * `Ok(())` - Successfully queued (or dropped based on fallback strategy)
     /// * `Err` - Channel is closed (database shutting down)
+    #[instrument(
+        name = "db.queue",
+        skip_all,
+        fields(
+            wallet = %result.wallet_address,
+            task = %result.task_name,
+            tx_hash = result.metadata.tx_hash.as_deref(),
+        )
+    )]
     pub fn queue_task_result(&self, result: QueuedTaskResult) -> Result<()> {
         if let Some(sender) = &self.log_sender {
             match sender.try_send(result) {
@@ -402,38 +1444,1278 @@ impl DatabaseManager {
         // Drop sender to signal shutdown to worker
         self.log_sender = None;
 
-        // Wait for flush task to complete (with timeout)
-        if let Some(handle) = self.flush_handle.take() {
-            match tokio::time::timeout(Duration::from_secs(5), handle).await {
-                Ok(Ok(())) => info!("Database flush completed"),
-                Ok(Err(e)) => error!("Flush task error: {}", e),
-                Err(_) => warn!("Flush timeout - some data may be lost"),
+        // Wait for flush task to complete (with timeout)
+        if let Some(handle) = self.flush_handle.take() {
+            match tokio::time::timeout(Duration::from_secs(5), handle).await {
+                Ok(Ok(())) => info!("Database flush completed"),
+                Ok(Err(e)) => error!("Flush task error: {}", e),
+                Err(_) => warn!("Flush timeout - some data may be lost"),
+            }
+        }
+
+        // Close pool
+        self.pool.close().await;
+        info!("Database shutdown complete");
+
+        Ok(())
+    }
+
+    #[instrument(
+        name = "db.insert",
+        skip_all,
+        fields(
+            db.system = "sqlite",
+            db.table = "created_counter_contracts",
+            wallet = %wallet,
+            contract = %contract,
+        )
+    )]
+    pub async fn log_counter_contract_creation(
+        &self,
+        wallet: &str,
+        contract: &str,
+        chain_id: u64,
+    ) -> Result<()> {
+        let start = std::time::Instant::now();
+        let timestamp = chrono::Utc::now().timestamp();
+
+        let result = sqlx::query(
+            "INSERT INTO created_counter_contracts (wallet_address, contract_address, chain_id, timestamp) VALUES (?, ?, ?, ?)"
+        )
+        .bind(wallet)
+        .bind(contract)
+        .bind(chain_id as i64)
+        .bind(timestamp)
+        .execute(&self.pool)
+        .await;
+
+        self.metrics.total_inserts.fetch_add(1, Ordering::SeqCst);
+        self.record_query_time(start, result.is_ok());
+
+        match result {
+            Ok(_) => {
+                self.metrics.total_queries.fetch_add(1, Ordering::SeqCst);
+                Ok(())
+            }
+            Err(e) => {
+                self.metrics.total_errors.fetch_add(1, Ordering::SeqCst);
+                error!("Failed to log contract creation: {}", e);
+                Err(e).context("Failed to insert contract")
+            }
+        }
+    }
+
+    #[instrument(
+        name = "db.insert",
+        skip_all,
+        fields(
+            db.system = "sqlite",
+            db.table = "created_assets",
+            wallet = %wallet,
+            asset = %asset_addr,
+        )
+    )]
+    pub async fn log_asset_creation(
+        &self,
+        wallet: &str,
+        asset_addr: &str,
+        asset_type: &str,
+        name: &str,
+        symbol: &str,
+    ) -> Result<()> {
+        let start = std::time::Instant::now();
+        let timestamp = chrono::Utc::now().timestamp();
+
+        let result = sqlx::query(
+            "INSERT INTO created_assets (wallet_address, asset_address, asset_type, name, symbol, timestamp) VALUES (?, ?, ?, ?, ?, ?)"
+        )
+        .bind(wallet)
+        .bind(asset_addr)
+        .bind(asset_type)
+        .bind(name)
+        .bind(symbol)
+        .bind(timestamp)
+        .execute(&self.pool)
+        .await;
+
+        self.metrics.total_inserts.fetch_add(1, Ordering::SeqCst);
+        self.record_query_time(start, result.is_ok());
+
+        match result {
+            Ok(_) => {
+                self.metrics.total_queries.fetch_add(1, Ordering::SeqCst);
+                Ok(())
+            }
+            Err(e) => {
+                self.metrics.total_errors.fetch_add(1, Ordering::SeqCst);
+                error!("Failed to log asset creation: {}", e);
+                Err(e).context("Failed to insert asset")
+            }
+        }
+    }
+
+    pub async fn update_proxy_stats(&self, proxy_url: &str, success: bool) -> Result<()> {
+        let start = std::time::Instant::now();
+        let query = if success {
+            "INSERT INTO proxy_stats (proxy_url, success_count, fail_count) VALUES (?, 1, 0)
+             ON CONFLICT(proxy_url) DO UPDATE SET success_count = success_count + 1"
+        } else {
+            "INSERT INTO proxy_stats (proxy_url, success_count, fail_count) VALUES (?, 0, 1)
+             ON CONFLICT(proxy_url) DO UPDATE SET fail_count = fail_count + 1"
+        };
+
+        let result = sqlx::query(query).bind(proxy_url).execute(&self.pool).await;
+
+        self.metrics.total_inserts.fetch_add(1, Ordering::SeqCst);
+        self.record_query_time(start, result.is_ok());
+
+        match result {
+            Ok(_) => {
+                self.metrics.total_queries.fetch_add(1, Ordering::SeqCst);
+                Ok(())
+            }
+            Err(e) => {
+                self.metrics.total_errors.fetch_add(1, Ordering::SeqCst);
+                error!("Failed to update proxy stats: {}", e);
+                Err(e).context("Failed to update proxy stats")
+            }
+        }
+    }
+
+    /// Adds request and byte counters onto a proxy's proxy_stats row
+    pub async fn record_proxy_usage(&self, proxy_url: &str, usage: &ProxyUsage) -> Result<()> {
+        let start = std::time::Instant::now();
+
+        let result = sqlx::query(
+            "INSERT INTO proxy_stats (proxy_url, success_count, fail_count, request_count, bytes_sent, bytes_received)
+             VALUES (?, ?, ?, ?, ?, ?)
+             ON CONFLICT(proxy_url) DO UPDATE SET
+                success_count = success_count + excluded.success_count,
+                fail_count = fail_count + excluded.fail_count,
+                request_count = request_count + excluded.request_count,
+                bytes_sent = bytes_sent + excluded.bytes_sent,
+                bytes_received = bytes_received + excluded.bytes_received",
+        )
+        .bind(proxy_url)
+        .bind(usage.successes as i64)
+        .bind(usage.failures as i64)
+        .bind(usage.requests as i64)
+        .bind(usage.bytes_sent as i64)
+        .bind(usage.bytes_received as i64)
+        .execute(&self.pool)
+        .await;
+
+        self.metrics.total_inserts.fetch_add(1, Ordering::SeqCst);
+        self.record_query_time(start, result.is_ok());
+
+        match result {
+            Ok(_) => {
+                self.metrics.total_queries.fetch_add(1, Ordering::SeqCst);
+                Ok(())
+            }
+            Err(e) => {
+                self.metrics.total_errors.fetch_add(1, Ordering::SeqCst);
+                error!("Failed to record proxy usage: {}", e);
+                Err(e).context("Failed to record proxy usage")
+            }
+        }
+    }
+
+    /// Per-proxy traffic and outcome totals, most bytes first
+    pub async fn get_proxy_stats(&self) -> Result<Vec<ProxyStats>> {
+        let start = std::time::Instant::now();
+
+        let rows = sqlx::query_as::<_, ProxyStats>(
+            "SELECT proxy_url, request_count, success_count, fail_count, bytes_sent, bytes_received
+            FROM proxy_stats
+            ORDER BY bytes_sent + bytes_received DESC, proxy_url",
+        )
+        .fetch_all(&self.pool)
+        .await;
+
+        self.metrics.total_selects.fetch_add(1, Ordering::SeqCst);
+        self.record_query_time(start, rows.is_ok());
+
+        match rows {
+            Ok(stats) => {
+                self.metrics.total_queries.fetch_add(1, Ordering::SeqCst);
+                Ok(stats)
+            }
+            Err(e) => {
+                self.metrics.total_errors.fetch_add(1, Ordering::SeqCst);
+                Err(e).context("Failed to read proxy stats")
+            }
+        }
+    }
+
+    /// Joins a burst round, creating it from `proposal` if no instance has yet
+    ///
+    /// The first instance to reach the database fixes the round's start time
+    /// and parameters; every later caller gets the stored round back instead
+    /// of its own proposal.
+    pub async fn join_burst_round(&self, proposal: &BurstRound) -> Result<BurstRound> {
+        let start = std::time::Instant::now();
+
+        let result = sqlx::query(
+            "INSERT OR IGNORE INTO burst_rounds (round_id, start_at_ms, duration_ms, target_rate)
+             VALUES (?, ?, ?, ?)",
+        )
+        .bind(&proposal.round_id)
+        .bind(proposal.start_at_ms)
+        .bind(proposal.duration_ms)
+        .bind(proposal.target_rate)
+        .execute(&self.pool)
+        .await;
+        self.metrics.total_inserts.fetch_add(1, Ordering::SeqCst);
+        if let Err(e) = result {
+            self.metrics.total_errors.fetch_add(1, Ordering::SeqCst);
+            return Err(e).context("Failed to propose burst round");
+        }
+
+        let round = sqlx::query_as::<_, BurstRound>(
+            "SELECT round_id, start_at_ms, duration_ms, target_rate
+            FROM burst_rounds WHERE round_id = ?",
+        )
+        .bind(&proposal.round_id)
+        .fetch_one(&self.pool)
+        .await;
+
+        self.metrics.total_selects.fetch_add(1, Ordering::SeqCst);
+        self.record_query_time(start, round.is_ok());
+
+        match round {
+            Ok(round) => {
+                self.metrics.total_queries.fetch_add(1, Ordering::SeqCst);
+                Ok(round)
+            }
+            Err(e) => {
+                self.metrics.total_errors.fetch_add(1, Ordering::SeqCst);
+                Err(e).context("Failed to read burst round")
+            }
+        }
+    }
+
+    /// Stores an instance's burst outcome, replacing an earlier report for the round
+    pub async fn record_burst_result(&self, result: &BurstResult) -> Result<()> {
+        let start = std::time::Instant::now();
+
+        let outcome = sqlx::query(
+            "INSERT OR REPLACE INTO burst_results
+                (round_id, instance_id, sent, send_errors, achieved_rate, included, reverted,
+                 pending, first_block, last_block, avg_inclusion_ms, p50_inclusion_ms,
+                 p90_inclusion_ms, p99_inclusion_ms, max_inclusion_ms)
+             VALUES (?, ?, ?, ?, ?, ?, ?, ?, ?, ?, ?, ?, ?, ?, ?)",
+        )
+        .bind(&result.round_id)
+        .bind(&result.instance_id)
+        .bind(result.sent)
+        .bind(result.send_errors)
+        .bind(result.achieved_rate)
+        .bind(result.included)
+        .bind(result.reverted)
+        .bind(result.pending)
+        .bind(result.first_block)
+        .bind(result.last_block)
+        .bind(result.avg_inclusion_ms)
+        .bind(result.p50_inclusion_ms)
+        .bind(result.p90_inclusion_ms)
+        .bind(result.p99_inclusion_ms)
+        .bind(result.max_inclusion_ms)
+        .execute(&self.pool)
+        .await;
+
+        self.metrics.total_inserts.fetch_add(1, Ordering::SeqCst);
+        self.record_query_time(start, outcome.is_ok());
+
+        match outcome {
+            Ok(_) => {
+                self.metrics.total_queries.fetch_add(1, Ordering::SeqCst);
+                Ok(())
+            }
+            Err(e) => {
+                self.metrics.total_errors.fetch_add(1, Ordering::SeqCst);
+                error!("Failed to record burst result: {}", e);
+                Err(e).context("Failed to record burst result")
+            }
+        }
+    }
+
+    /// Burst round and every instance's report for it, if the round exists
+    pub async fn get_burst_results(
+        &self,
+        round_id: &str,
+    ) -> Result<Option<(BurstRound, Vec<BurstResult>)>> {
+        let start = std::time::Instant::now();
+
+        let round = sqlx::query_as::<_, BurstRound>(
+            "SELECT round_id, start_at_ms, duration_ms, target_rate
+            FROM burst_rounds WHERE round_id = ?",
+        )
+        .bind(round_id)
+        .fetch_optional(&self.pool)
+        .await
+        .context("Failed to read burst round")?;
+        let Some(round) = round else {
+            return Ok(None);
+        };
+
+        let rows = sqlx::query_as::<_, BurstResult>(
+            "SELECT round_id, instance_id, sent, send_errors, achieved_rate, included, reverted,
+                pending, first_block, last_block, avg_inclusion_ms, p50_inclusion_ms,
+                p90_inclusion_ms, p99_inclusion_ms, max_inclusion_ms
+            FROM burst_results WHERE round_id = ? ORDER BY instance_id",
+        )
+        .bind(round_id)
+        .fetch_all(&self.pool)
+        .await;
+
+        self.metrics.total_selects.fetch_add(2, Ordering::SeqCst);
+        self.record_query_time(start, rows.is_ok());
+
+        match rows {
+            Ok(results) => {
+                self.metrics.total_queries.fetch_add(1, Ordering::SeqCst);
+                Ok(Some((round, results)))
+            }
+            Err(e) => {
+                self.metrics.total_errors.fetch_add(1, Ordering::SeqCst);
+                Err(e).context("Failed to read burst results")
+            }
+        }
+    }
+
+    /// Stores an instance's per-block burst breakdown, replacing an earlier one
+    pub async fn record_burst_blocks(
+        &self,
+        round_id: &str,
+        instance_id: &str,
+        blocks: &[BurstBlock],
+    ) -> Result<()> {
+        let start = std::time::Instant::now();
+
+        let outcome = async {
+            let mut tx = self.pool.begin().await?;
+            sqlx::query("DELETE FROM burst_blocks WHERE round_id = ? AND instance_id = ?")
+                .bind(round_id)
+                .bind(instance_id)
+                .execute(&mut *tx)
+                .await?;
+            for block in blocks {
+                sqlx::query(
+                    "INSERT INTO burst_blocks
+                        (round_id, instance_id, block_number, transactions, in_subblocks,
+                         min_inclusion_ms, p50_inclusion_ms, max_inclusion_ms)
+                     VALUES (?, ?, ?, ?, ?, ?, ?, ?)",
+                )
+                .bind(&block.round_id)
+                .bind(&block.instance_id)
+                .bind(block.block_number)
+                .bind(block.transactions)
+                .bind(block.in_subblocks)
+                .bind(block.min_inclusion_ms)
+                .bind(block.p50_inclusion_ms)
+                .bind(block.max_inclusion_ms)
+                .execute(&mut *tx)
+                .await?;
+            }
+            tx.commit().await
+        }
+        .await;
+
+        self.metrics
+            .total_inserts
+            .fetch_add(blocks.len() as u64, Ordering::SeqCst);
+        self.record_query_time(start, outcome.is_ok());
+
+        match outcome {
+            Ok(()) => {
+                self.metrics.total_queries.fetch_add(1, Ordering::SeqCst);
+                Ok(())
+            }
+            Err(e) => {
+                self.metrics.total_errors.fetch_add(1, Ordering::SeqCst);
+                error!("Failed to record burst blocks: {}", e);
+                Err(e).context("Failed to record burst blocks")
+            }
+        }
+    }
+
+    /// Per-block breakdown of every instance's burst in a round, by block then instance
+    pub async fn get_burst_blocks(&self, round_id: &str) -> Result<Vec<BurstBlock>> {
+        let start = std::time::Instant::now();
+
+        let rows = sqlx::query_as::<_, BurstBlock>(
+            "SELECT round_id, instance_id, block_number, transactions, in_subblocks,
+                min_inclusion_ms, p50_inclusion_ms, max_inclusion_ms
+            FROM burst_blocks WHERE round_id = ? ORDER BY block_number, instance_id",
+        )
+        .bind(round_id)
+        .fetch_all(&self.pool)
+        .await;
+
+        self.metrics.total_selects.fetch_add(1, Ordering::SeqCst);
+        self.record_query_time(start, rows.is_ok());
+
+        match rows {
+            Ok(blocks) => {
+                self.metrics.total_queries.fetch_add(1, Ordering::SeqCst);
+                Ok(blocks)
+            }
+            Err(e) => {
+                self.metrics.total_errors.fetch_add(1, Ordering::SeqCst);
+                Err(e).context("Failed to read burst blocks")
+            }
+        }
+    }
+
+    /// Stores latency samples, replacing earlier samples of the same transactions
+    pub async fn record_latency_samples(&self, samples: &[LatencySample]) -> Result<()> {
+        let start = std::time::Instant::now();
+
+        let outcome = async {
+            let mut tx = self.pool.begin().await?;
+            for sample in samples {
+                sqlx::query(
+                    "INSERT OR REPLACE INTO latency_samples
+                        (tx_hash, source, wallet_address, block_number, subblock, sent_at_ms,
+                         included_at_ms, confirmed_at_ms)
+                     VALUES (?, ?, ?, ?, ?, ?, ?, ?)",
+                )
+                .bind(&sample.tx_hash)
+                .bind(&sample.source)
+                .bind(&sample.wallet_address)
+                .bind(sample.block_number)
+                .bind(&sample.subblock)
+                .bind(sample.sent_at_ms)
+                .bind(sample.included_at_ms)
+                .bind(sample.confirmed_at_ms)
+                .execute(&mut *tx)
+                .await?;
+            }
+            tx.commit().await
+        }
+        .await;
+
+        self.metrics
+            .total_inserts
+            .fetch_add(samples.len() as u64, Ordering::SeqCst);
+        self.record_query_time(start, outcome.is_ok());
+
+        match outcome {
+            Ok(()) => {
+                self.metrics.total_queries.fetch_add(1, Ordering::SeqCst);
+                Ok(())
+            }
+            Err(e) => {
+                self.metrics.total_errors.fetch_add(1, Ordering::SeqCst);
+                error!("Failed to record latency samples: {}", e);
+                Err(e).context("Failed to record latency samples")
+            }
+        }
+    }
+
+    /// Latency samples sent at or after `since_ms`, of one source or all, oldest first
+    pub async fn get_latency_samples(
+        &self,
+        source: Option<&str>,
+        since_ms: i64,
+    ) -> Result<Vec<LatencySample>> {
+        let start = std::time::Instant::now();
+
+        let rows = sqlx::query_as::<_, LatencySample>(
+            "SELECT tx_hash, source, wallet_address, block_number, subblock, sent_at_ms,
+                included_at_ms, confirmed_at_ms
+            FROM latency_samples
+            WHERE (?1 IS NULL OR source = ?1) AND sent_at_ms >= ?2
+            ORDER BY sent_at_ms, tx_hash",
+        )
+        .bind(source)
+        .bind(since_ms)
+        .fetch_all(&self.pool)
+        .await;
+
+        self.metrics.total_selects.fetch_add(1, Ordering::SeqCst);
+        self.record_query_time(start, rows.is_ok());
+
+        match rows {
+            Ok(samples) => {
+                self.metrics.total_queries.fetch_add(1, Ordering::SeqCst);
+                Ok(samples)
+            }
+            Err(e) => {
+                self.metrics.total_errors.fetch_add(1, Ordering::SeqCst);
+                Err(e).context("Failed to read latency samples")
+            }
+        }
+    }
+
+    /// Stores a wallet's spend total for a day, replacing the previous total
+    pub async fn set_daily_spend(&self, record: &SpendRecord) -> Result<()> {
+        let start = std::time::Instant::now();
+
+        let result = sqlx::query(
+            "INSERT OR REPLACE INTO spend_ledger (day, wallet_address, token, amount)
+             VALUES (?, ?, ?, ?)",
+        )
+        .bind(&record.day)
+        .bind(&record.wallet_address)
+        .bind(&record.token)
+        .bind(&record.amount)
+        .execute(&self.pool)
+        .await;
+
+        self.metrics.total_inserts.fetch_add(1, Ordering::SeqCst);
+        self.record_query_time(start, result.is_ok());
+
+        match result {
+            Ok(_) => {
+                self.metrics.total_queries.fetch_add(1, Ordering::SeqCst);
+                Ok(())
+            }
+            Err(e) => {
+                self.metrics.total_errors.fetch_add(1, Ordering::SeqCst);
+                error!("Failed to record spend: {}", e);
+                Err(e).context("Failed to record spend")
+            }
+        }
+    }
+
+    /// Every wallet's spend totals for a UTC day (`YYYY-MM-DD`)
+    pub async fn get_daily_spend(&self, day: &str) -> Result<Vec<SpendRecord>> {
+        let start = std::time::Instant::now();
+
+        let rows = sqlx::query_as::<_, SpendRecord>(
+            "SELECT day, wallet_address, token, amount
+            FROM spend_ledger WHERE day = ? ORDER BY token, wallet_address",
+        )
+        .bind(day)
+        .fetch_all(&self.pool)
+        .await;
+
+        self.metrics.total_selects.fetch_add(1, Ordering::SeqCst);
+        self.record_query_time(start, rows.is_ok());
+
+        match rows {
+            Ok(records) => {
+                self.metrics.total_queries.fetch_add(1, Ordering::SeqCst);
+                Ok(records)
+            }
+            Err(e) => {
+                self.metrics.total_errors.fetch_add(1, Ordering::SeqCst);
+                Err(e).context("Failed to read spend ledger")
+            }
+        }
+    }
+
+    /// Records one read-your-writes check
+    #[instrument(
+        name = "db.insert",
+        skip_all,
+        fields(
+            db.system = "sqlite",
+            db.table = "consistency_checks",
+            wallet = %check.wallet_address,
+        )
+    )]
+    pub async fn log_consistency_check(&self, check: &ConsistencyCheck) -> Result<()> {
+        let start = std::time::Instant::now();
+
+        let result = sqlx::query(
+            "INSERT INTO consistency_checks
+                (task_name, wallet_address, target, reader, consistent, lag_ms, attempts, timestamp)
+             VALUES (?, ?, ?, ?, ?, ?, ?, ?)",
+        )
+        .bind(&check.task_name)
+        .bind(&check.wallet_address)
+        .bind(&check.target)
+        .bind(&check.reader)
+        .bind(check.consistent)
+        .bind(check.lag_ms)
+        .bind(check.attempts)
+        .bind(check.timestamp)
+        .execute(&self.pool)
+        .await;
+
+        self.metrics.total_inserts.fetch_add(1, Ordering::SeqCst);
+        self.record_query_time(start, result.is_ok());
+
+        match result {
+            Ok(_) => {
+                self.metrics.total_queries.fetch_add(1, Ordering::SeqCst);
+                Ok(())
+            }
+            Err(e) => {
+                self.metrics.total_errors.fetch_add(1, Ordering::SeqCst);
+                error!("Failed to log consistency check: {}", e);
+                Err(e).context("Failed to log consistency check")
+            }
+        }
+    }
+
+    /// Check counts, inconsistencies and propagation lag per task
+    pub async fn get_consistency_summary(&self) -> Result<Vec<ConsistencySummary>> {
+        let start = std::time::Instant::now();
+
+        let rows = sqlx::query_as::<_, ConsistencySummary>(
+            "SELECT task_name,
+                COUNT(*) AS checks,
+                SUM(CASE WHEN consistent = 1 THEN 0 ELSE 1 END) AS inconsistent,
+                COALESCE(AVG(CASE WHEN consistent = 1 THEN lag_ms END), 0.0) AS avg_lag_ms,
+                COALESCE(MAX(CASE WHEN consistent = 1 THEN lag_ms END), 0) AS max_lag_ms
+            FROM consistency_checks GROUP BY task_name ORDER BY task_name",
+        )
+        .fetch_all(&self.pool)
+        .await;
+
+        self.metrics.total_selects.fetch_add(1, Ordering::SeqCst);
+        self.record_query_time(start, rows.is_ok());
+
+        match rows {
+            Ok(summary) => {
+                self.metrics.total_queries.fetch_add(1, Ordering::SeqCst);
+                Ok(summary)
+            }
+            Err(e) => {
+                self.metrics.total_errors.fetch_add(1, Ordering::SeqCst);
+                Err(e).context("Failed to summarize consistency checks")
+            }
+        }
+    }
+
+    /// Records the gas paid by one transaction
+    #[instrument(
+        name = "db.insert",
+        skip_all,
+        fields(
+            db.system = "sqlite",
+            db.table = "gas_usage",
+            wallet = %usage.wallet_address,
+            tx_hash = %usage.tx_hash,
+        )
+    )]
+    pub async fn log_gas_usage(&self, usage: &GasUsageRecord) -> Result<()> {
+        let start = std::time::Instant::now();
+
+        let result = sqlx::query(
+            "INSERT INTO gas_usage
+                (task_name, wallet_address, tx_hash, gas_used, effective_gas_price, block_number,
+                 success, timestamp)
+             VALUES (?, ?, ?, ?, ?, ?, ?, ?)",
+        )
+        .bind(&usage.task_name)
+        .bind(&usage.wallet_address)
+        .bind(&usage.tx_hash)
+        .bind(usage.gas_used)
+        .bind(usage.effective_gas_price)
+        .bind(usage.block_number)
+        .bind(usage.success)
+        .bind(usage.timestamp)
+        .execute(&self.pool)
+        .await;
+
+        self.metrics.total_inserts.fetch_add(1, Ordering::SeqCst);
+        self.record_query_time(start, result.is_ok());
+
+        match result {
+            Ok(_) => {
+                self.metrics.total_queries.fetch_add(1, Ordering::SeqCst);
+                Ok(())
+            }
+            Err(e) => {
+                self.metrics.total_errors.fetch_add(1, Ordering::SeqCst);
+                error!("Failed to log gas usage: {}", e);
+                Err(e).context("Failed to log gas usage")
+            }
+        }
+    }
+
+    /// Stores receipt logs, skipping logs already archived
+    pub async fn log_receipt_logs(&self, logs: &[ReceiptLogRecord]) -> Result<()> {
+        let start = std::time::Instant::now();
+
+        let outcome = async {
+            let mut tx = self.pool.begin().await?;
+            for log in logs {
+                let topic = |index: usize| log.topics.get(index);
+                sqlx::query(
+                    "INSERT OR IGNORE INTO receipt_logs
+                        (tx_hash, log_index, task_name, block_number, address, topic0, topic1,
+                         topic2, topic3, data, timestamp)
+                     VALUES (?, ?, ?, ?, ?, ?, ?, ?, ?, ?, ?)",
+                )
+                .bind(&log.tx_hash)
+                .bind(log.log_index)
+                .bind(&log.task_name)
+                .bind(log.block_number)
+                .bind(&log.address)
+                .bind(topic(0))
+                .bind(topic(1))
+                .bind(topic(2))
+                .bind(topic(3))
+                .bind(&log.data)
+                .bind(log.timestamp)
+                .execute(&mut *tx)
+                .await?;
+            }
+            tx.commit().await
+        }
+        .await;
+
+        self.metrics
+            .total_inserts
+            .fetch_add(logs.len() as u64, Ordering::SeqCst);
+        self.record_query_time(start, outcome.is_ok());
+
+        match outcome {
+            Ok(()) => {
+                self.metrics.total_queries.fetch_add(1, Ordering::SeqCst);
+                Ok(())
+            }
+            Err(e) => {
+                self.metrics.total_errors.fetch_add(1, Ordering::SeqCst);
+                error!("Failed to log receipt logs: {}", e);
+                Err(e).context("Failed to log receipt logs")
+            }
+        }
+    }
+
+    /// Archived logs per contract and event signature, of one task or all,
+    /// most logs first
+    pub async fn get_log_coverage(&self, task: Option<&str>) -> Result<Vec<LogCoverage>> {
+        let start = std::time::Instant::now();
+
+        let rows = sqlx::query_as::<_, LogCoverage>(
+            "SELECT address, topic0, COUNT(*) AS logs,
+                GROUP_CONCAT(DISTINCT task_name) AS tasks
+            FROM receipt_logs WHERE ?1 IS NULL OR task_name = ?1
+            GROUP BY address, topic0
+            ORDER BY logs DESC, address, topic0",
+        )
+        .bind(task)
+        .fetch_all(&self.pool)
+        .await;
+
+        self.metrics.total_selects.fetch_add(1, Ordering::SeqCst);
+        self.record_query_time(start, rows.is_ok());
+
+        match rows {
+            Ok(coverage) => {
+                self.metrics.total_queries.fetch_add(1, Ordering::SeqCst);
+                Ok(coverage)
+            }
+            Err(e) => {
+                self.metrics.total_errors.fetch_add(1, Ordering::SeqCst);
+                Err(e).context("Failed to read log coverage")
+            }
+        }
+    }
+
+    /// Transactions, gas and cost per task from gas_usage
+    pub async fn get_gas_summary(&self) -> Result<Vec<GasSummary>> {
+        self.get_gas_summary_between(i64::MIN, i64::MAX).await
+    }
+
+    /// Transactions, gas and cost per task of the receipts whose timestamp
+    /// lies within `since..=until` (unix seconds)
+    pub async fn get_gas_summary_between(&self, since: i64, until: i64) -> Result<Vec<GasSummary>> {
+        let start = std::time::Instant::now();
+
+        let rows = sqlx::query_as::<_, GasSummary>(
+            "SELECT task_name,
+                COUNT(*) AS transactions,
+                SUM(CASE WHEN success = 1 THEN 0 ELSE 1 END) AS reverted,
+                COALESCE(SUM(gas_used), 0) AS total_gas,
+                COALESCE(MAX(gas_used), 0) AS max_gas,
+                TOTAL(gas_used * effective_gas_price) AS total_cost_wei
+            FROM gas_usage WHERE timestamp BETWEEN ? AND ?
+            GROUP BY task_name ORDER BY task_name",
+        )
+        .bind(since)
+        .bind(until)
+        .fetch_all(&self.pool)
+        .await;
+
+        self.metrics.total_selects.fetch_add(1, Ordering::SeqCst);
+        self.record_query_time(start, rows.is_ok());
+
+        match rows {
+            Ok(summary) => {
+                self.metrics.total_queries.fetch_add(1, Ordering::SeqCst);
+                Ok(summary)
+            }
+            Err(e) => {
+                self.metrics.total_errors.fetch_add(1, Ordering::SeqCst);
+                Err(e).context("Failed to summarize gas usage")
+            }
+        }
+    }
+
+    /// Stores one sampled task run
+    #[instrument(
+        name = "db.insert",
+        skip_all,
+        fields(
+            db.system = "sqlite",
+            db.table = "audits",
+            wallet = %audit.wallet_address,
+            tx_hash = audit.tx_hash.as_deref(),
+        )
+    )]
+    pub async fn log_audit(&self, audit: &AuditRecord) -> Result<()> {
+        let start = std::time::Instant::now();
+
+        let result = sqlx::query(
+            "INSERT INTO audits
+                (task_name, worker_id, wallet_address, proxy, success, message, tx_hash, nonce,
+                 nonce_key, gas_limit, max_fee_per_gas, max_priority_fee_per_gas, transactions,
+                 steps, duration_ms, timestamp)
+             VALUES (?, ?, ?, ?, ?, ?, ?, ?, ?, ?, ?, ?, ?, ?, ?, ?)",
+        )
+        .bind(&audit.task_name)
+        .bind(&audit.worker_id)
+        .bind(&audit.wallet_address)
+        .bind(&audit.proxy)
+        .bind(audit.success)
+        .bind(&audit.message)
+        .bind(&audit.tx_hash)
+        .bind(audit.nonce)
+        .bind(&audit.nonce_key)
+        .bind(audit.gas_limit)
+        .bind(&audit.max_fee_per_gas)
+        .bind(&audit.max_priority_fee_per_gas)
+        .bind(&audit.transactions)
+        .bind(&audit.steps)
+        .bind(audit.duration_ms)
+        .bind(audit.timestamp)
+        .execute(&self.pool)
+        .await;
+
+        self.metrics.total_inserts.fetch_add(1, Ordering::SeqCst);
+        self.record_query_time(start, result.is_ok());
+
+        match result {
+            Ok(_) => {
+                self.metrics.total_queries.fetch_add(1, Ordering::SeqCst);
+                Ok(())
+            }
+            Err(e) => {
+                self.metrics.total_errors.fetch_add(1, Ordering::SeqCst);
+                error!("Failed to log audit: {}", e);
+                Err(e).context("Failed to log audit")
+            }
+        }
+    }
+
+    /// Most recent audits first, optionally for one task only
+    pub async fn get_audits(
+        &self,
+        task_name: Option<&str>,
+        limit: i64,
+    ) -> Result<Vec<AuditRecord>> {
+        let start = std::time::Instant::now();
+
+        let rows = sqlx::query_as::<_, AuditRecord>(
+            "SELECT task_name, worker_id, wallet_address, proxy, success, message, tx_hash, nonce,
+                nonce_key, gas_limit, max_fee_per_gas, max_priority_fee_per_gas, transactions,
+                steps, duration_ms, timestamp
+            FROM audits WHERE ?1 IS NULL OR task_name = ?1
+            ORDER BY timestamp DESC, id DESC LIMIT ?2",
+        )
+        .bind(task_name)
+        .bind(limit)
+        .fetch_all(&self.pool)
+        .await;
+
+        self.metrics.total_selects.fetch_add(1, Ordering::SeqCst);
+        self.record_query_time(start, rows.is_ok());
+
+        match rows {
+            Ok(audits) => {
+                self.metrics.total_queries.fetch_add(1, Ordering::SeqCst);
+                Ok(audits)
+            }
+            Err(e) => {
+                self.metrics.total_errors.fetch_add(1, Ordering::SeqCst);
+                Err(e).context("Failed to read audits")
+            }
+        }
+    }
+
+    /// Stores a helper contract, replacing an earlier deployment of the same name
+    #[instrument(
+        name = "db.insert",
+        skip_all,
+        fields(
+            db.system = "sqlite",
+            db.table = "helper_contracts",
+            wallet = %helper.deployer,
+            tx_hash = %helper.tx_hash,
+        )
+    )]
+    pub async fn log_helper_contract(&self, helper: &HelperContractRecord) -> Result<()> {
+        let start = std::time::Instant::now();
+
+        let result = sqlx::query(
+            "INSERT OR REPLACE INTO helper_contracts
+                (chain_id, name, address, code_hash, tx_hash, deployer, deployed_at)
+             VALUES (?, ?, ?, ?, ?, ?, ?)",
+        )
+        .bind(helper.chain_id)
+        .bind(&helper.name)
+        .bind(&helper.address)
+        .bind(&helper.code_hash)
+        .bind(&helper.tx_hash)
+        .bind(&helper.deployer)
+        .bind(helper.deployed_at)
+        .execute(&self.pool)
+        .await;
+
+        self.metrics.total_inserts.fetch_add(1, Ordering::SeqCst);
+        self.record_query_time(start, result.is_ok());
+
+        match result {
+            Ok(_) => {
+                self.metrics.total_queries.fetch_add(1, Ordering::SeqCst);
+                Ok(())
+            }
+            Err(e) => {
+                self.metrics.total_errors.fetch_add(1, Ordering::SeqCst);
+                error!("Failed to log helper contract: {}", e);
+                Err(e).context("Failed to log helper contract")
+            }
+        }
+    }
+
+    /// Helper contracts recorded for a chain, by name
+    pub async fn get_helper_contracts(&self, chain_id: u64) -> Result<Vec<HelperContractRecord>> {
+        let start = std::time::Instant::now();
+
+        let rows = sqlx::query_as::<_, HelperContractRecord>(
+            "SELECT chain_id, name, address, code_hash, tx_hash, deployer, deployed_at
+            FROM helper_contracts WHERE chain_id = ? ORDER BY name",
+        )
+        .bind(chain_id as i64)
+        .fetch_all(&self.pool)
+        .await;
+
+        self.metrics.total_selects.fetch_add(1, Ordering::SeqCst);
+        self.record_query_time(start, rows.is_ok());
+
+        match rows {
+            Ok(helpers) => {
+                self.metrics.total_queries.fetch_add(1, Ordering::SeqCst);
+                Ok(helpers)
+            }
+            Err(e) => {
+                self.metrics.total_errors.fetch_add(1, Ordering::SeqCst);
+                Err(e).context("Failed to read helper contracts")
+            }
+        }
+    }
+
+    /// Stores a smart account, replacing an earlier record of the same address
+    #[instrument(
+        name = "db.insert",
+        skip_all,
+        fields(
+            db.system = "sqlite",
+            db.table = "smart_accounts",
+            wallet = %account.owner,
+            tx_hash = %account.tx_hash,
+        )
+    )]
+    pub async fn log_smart_account(&self, account: &SmartAccountRecord) -> Result<()> {
+        let start = std::time::Instant::now();
+
+        let result = sqlx::query(
+            "INSERT OR REPLACE INTO smart_accounts
+                (chain_id, owner, account, factory, salt, tx_hash, created_at)
+             VALUES (?, ?, ?, ?, ?, ?, ?)",
+        )
+        .bind(account.chain_id)
+        .bind(&account.owner)
+        .bind(&account.account)
+        .bind(&account.factory)
+        .bind(&account.salt)
+        .bind(&account.tx_hash)
+        .bind(account.created_at)
+        .execute(&self.pool)
+        .await;
+
+        self.metrics.total_inserts.fetch_add(1, Ordering::SeqCst);
+        self.record_query_time(start, result.is_ok());
+
+        match result {
+            Ok(_) => {
+                self.metrics.total_queries.fetch_add(1, Ordering::SeqCst);
+                Ok(())
+            }
+            Err(e) => {
+                self.metrics.total_errors.fetch_add(1, Ordering::SeqCst);
+                error!("Failed to log smart account: {}", e);
+                Err(e).context("Failed to log smart account")
+            }
+        }
+    }
+
+    /// Smart accounts `owner` created on a chain, oldest first
+    pub async fn get_smart_accounts(
+        &self,
+        chain_id: u64,
+        owner: &str,
+    ) -> Result<Vec<SmartAccountRecord>> {
+        let start = std::time::Instant::now();
+
+        let rows = sqlx::query_as::<_, SmartAccountRecord>(
+            "SELECT chain_id, owner, account, factory, salt, tx_hash, created_at
+            FROM smart_accounts WHERE chain_id = ? AND owner = ? ORDER BY created_at, account",
+        )
+        .bind(chain_id as i64)
+        .bind(owner)
+        .fetch_all(&self.pool)
+        .await;
+
+        self.metrics.total_selects.fetch_add(1, Ordering::SeqCst);
+        self.record_query_time(start, rows.is_ok());
+
+        match rows {
+            Ok(accounts) => {
+                self.metrics.total_queries.fetch_add(1, Ordering::SeqCst);
+                Ok(accounts)
+            }
+            Err(e) => {
+                self.metrics.total_errors.fetch_add(1, Ordering::SeqCst);
+                Err(e).context("Failed to read smart accounts")
+            }
+        }
+    }
+
+    /// Stores a listing, replacing an earlier record of the same listing id
+    #[instrument(
+        name = "db.insert",
+        skip_all,
+        fields(
+            db.system = "sqlite",
+            db.table = "nft_listings",
+            wallet = %listing.seller,
+            tx_hash = %listing.tx_hash,
+        )
+    )]
+    pub async fn log_nft_listing(&self, listing: &NftListingRecord) -> Result<()> {
+        let start = std::time::Instant::now();
+
+        let result = sqlx::query(
+            "INSERT OR REPLACE INTO nft_listings
+                (chain_id, marketplace, listing_id, seller, nft, token_id, payment_token, price,
+                 status, tx_hash, listed_at)
+             VALUES (?, ?, ?, ?, ?, ?, ?, ?, ?, ?, ?)",
+        )
+        .bind(listing.chain_id)
+        .bind(&listing.marketplace)
+        .bind(&listing.listing_id)
+        .bind(&listing.seller)
+        .bind(&listing.nft)
+        .bind(&listing.token_id)
+        .bind(&listing.payment_token)
+        .bind(&listing.price)
+        .bind(&listing.status)
+        .bind(&listing.tx_hash)
+        .bind(listing.listed_at)
+        .execute(&self.pool)
+        .await;
+
+        self.metrics.total_inserts.fetch_add(1, Ordering::SeqCst);
+        self.record_query_time(start, result.is_ok());
+
+        match result {
+            Ok(_) => {
+                self.metrics.total_queries.fetch_add(1, Ordering::SeqCst);
+                Ok(())
+            }
+            Err(e) => {
+                self.metrics.total_errors.fetch_add(1, Ordering::SeqCst);
+                error!("Failed to log NFT listing: {}", e);
+                Err(e).context("Failed to log NFT listing")
+            }
+        }
+    }
+
+    /// Active listings on a marketplace, oldest first
+    pub async fn get_active_nft_listings(
+        &self,
+        chain_id: u64,
+        marketplace: &str,
+    ) -> Result<Vec<NftListingRecord>> {
+        let start = std::time::Instant::now();
+
+        let rows = sqlx::query_as::<_, NftListingRecord>(
+            "SELECT chain_id, marketplace, listing_id, seller, nft, token_id, payment_token, price,
+                status, tx_hash, listed_at
+            FROM nft_listings WHERE chain_id = ? AND marketplace = ? AND status = 'active'
+            ORDER BY listed_at, CAST(listing_id AS INTEGER)",
+        )
+        .bind(chain_id as i64)
+        .bind(marketplace)
+        .fetch_all(&self.pool)
+        .await;
+
+        self.metrics.total_selects.fetch_add(1, Ordering::SeqCst);
+        self.record_query_time(start, rows.is_ok());
+
+        match rows {
+            Ok(listings) => {
+                self.metrics.total_queries.fetch_add(1, Ordering::SeqCst);
+                Ok(listings)
+            }
+            Err(e) => {
+                self.metrics.total_errors.fetch_add(1, Ordering::SeqCst);
+                Err(e).context("Failed to read NFT listings")
+            }
+        }
+    }
+
+    /// Sets the status of a listing that is no longer active
+    pub async fn close_nft_listing(
+        &self,
+        chain_id: u64,
+        marketplace: &str,
+        listing_id: &str,
+        status: &str,
+    ) -> Result<()> {
+        let start = std::time::Instant::now();
+
+        let result = sqlx::query(
+            "UPDATE nft_listings SET status = ?
+             WHERE chain_id = ? AND marketplace = ? AND listing_id = ?",
+        )
+        .bind(status)
+        .bind(chain_id as i64)
+        .bind(marketplace)
+        .bind(listing_id)
+        .execute(&self.pool)
+        .await;
+
+        self.record_query_time(start, result.is_ok());
+
+        match result {
+            Ok(_) => {
+                self.metrics.total_queries.fetch_add(1, Ordering::SeqCst);
+                Ok(())
+            }
+            Err(e) => {
+                self.metrics.total_errors.fetch_add(1, Ordering::SeqCst);
+                Err(e).context("Failed to update NFT listing")
+            }
+        }
+    }
+
+    /// Stores a domain, replacing the earlier record of the same name
+    #[instrument(
+        name = "db.insert",
+        skip_all,
+        fields(
+            db.system = "sqlite",
+            db.table = "domains",
+            wallet = %domain.owner,
+            tx_hash = %domain.tx_hash,
+        )
+    )]
+    pub async fn log_domain(&self, domain: &DomainRecord) -> Result<()> {
+        let start = std::time::Instant::now();
+
+        let result = sqlx::query(
+            "INSERT OR REPLACE INTO domains
+                (chain_id, name, node, token_id, owner, resolver, addr, tx_hash, registered_at,
+                 renewed_at)
+             VALUES (?, ?, ?, ?, ?, ?, ?, ?, ?, ?)",
+        )
+        .bind(domain.chain_id)
+        .bind(&domain.name)
+        .bind(&domain.node)
+        .bind(&domain.token_id)
+        .bind(&domain.owner)
+        .bind(&domain.resolver)
+        .bind(&domain.addr)
+        .bind(&domain.tx_hash)
+        .bind(domain.registered_at)
+        .bind(domain.renewed_at)
+        .execute(&self.pool)
+        .await;
+
+        self.metrics.total_inserts.fetch_add(1, Ordering::SeqCst);
+        self.record_query_time(start, result.is_ok());
+
+        match result {
+            Ok(_) => {
+                self.metrics.total_queries.fetch_add(1, Ordering::SeqCst);
+                Ok(())
+            }
+            Err(e) => {
+                self.metrics.total_errors.fetch_add(1, Ordering::SeqCst);
+                error!("Failed to log domain: {}", e);
+                Err(e).context("Failed to log domain")
+            }
+        }
+    }
+
+    /// Domains an owner holds on a chain, oldest registration first
+    pub async fn get_domains(&self, chain_id: u64, owner: &str) -> Result<Vec<DomainRecord>> {
+        let start = std::time::Instant::now();
+
+        let rows = sqlx::query_as::<_, DomainRecord>(
+            "SELECT chain_id, name, node, token_id, owner, resolver, addr, tx_hash, registered_at,
+                renewed_at
+            FROM domains WHERE chain_id = ? AND owner = ?
+            ORDER BY registered_at, name",
+        )
+        .bind(chain_id as i64)
+        .bind(owner)
+        .fetch_all(&self.pool)
+        .await;
+
+        self.metrics.total_selects.fetch_add(1, Ordering::SeqCst);
+        self.record_query_time(start, rows.is_ok());
+
+        match rows {
+            Ok(domains) => {
+                self.metrics.total_queries.fetch_add(1, Ordering::SeqCst);
+                Ok(domains)
+            }
+            Err(e) => {
+                self.metrics.total_errors.fetch_add(1, Ordering::SeqCst);
+                Err(e).context("Failed to read domains")
             }
         }
-
-        // Close pool
-        self.pool.close().await;
-        info!("Database shutdown complete");
-
-        Ok(())
     }
 
-    pub async fn log_counter_contract_creation(
-        &self,
-        wallet: &str,
-        contract: &str,
-        chain_id: u64,
-    ) -> Result<()> {
+    /// Adds the successes and reverts of `result` onto its selector's row
+    ///
+    /// `last_error` is only replaced by a non-empty one, so the reason of the
+    /// latest revert survives later successes.
+    pub async fn record_fuzz_result(&self, fuzz: &FuzzResultRecord) -> Result<()> {
         let start = std::time::Instant::now();
-        let timestamp = chrono::Utc::now().timestamp();
 
         let result = sqlx::query(
-            "INSERT INTO created_counter_contracts (wallet_address, contract_address, chain_id, timestamp) VALUES (?, ?, ?, ?)"
+            "INSERT INTO fuzz_results
+                (chain_id, contract, selector, signature, successes, reverts, last_error,
+                 updated_at)
+             VALUES (?, ?, ?, ?, ?, ?, ?, ?)
+             ON CONFLICT(chain_id, contract, selector) DO UPDATE SET
+                signature = excluded.signature,
+                successes = successes + excluded.successes,
+                reverts = reverts + excluded.reverts,
+                last_error = CASE WHEN excluded.last_error = '' THEN last_error
+                    ELSE excluded.last_error END,
+                updated_at = excluded.updated_at",
         )
-        .bind(wallet)
-        .bind(contract)
-        .bind(chain_id as i64)
-        .bind(timestamp)
+        .bind(fuzz.chain_id)
+        .bind(&fuzz.contract)
+        .bind(&fuzz.selector)
+        .bind(&fuzz.signature)
+        .bind(fuzz.successes)
+        .bind(fuzz.reverts)
+        .bind(&fuzz.last_error)
+        .bind(fuzz.updated_at)
         .execute(&self.pool)
         .await;
 
@@ -447,32 +2729,66 @@ impl DatabaseManager {
             }
             Err(e) => {
                 self.metrics.total_errors.fetch_add(1, Ordering::SeqCst);
-                error!("Failed to log contract creation: {}", e);
-                Err(e).context("Failed to insert contract")
+                error!("Failed to record fuzz result: {}", e);
+                Err(e).context("Failed to record fuzz result")
             }
         }
     }
 
-    pub async fn log_asset_creation(
-        &self,
-        wallet: &str,
-        asset_addr: &str,
-        asset_type: &str,
-        name: &str,
-        symbol: &str,
-    ) -> Result<()> {
+    /// Fuzzed selectors of a chain by contract, then signature
+    pub async fn get_fuzz_results(&self, chain_id: u64) -> Result<Vec<FuzzResultRecord>> {
+        let start = std::time::Instant::now();
+
+        let rows = sqlx::query_as::<_, FuzzResultRecord>(
+            "SELECT chain_id, contract, selector, signature, successes, reverts, last_error,
+                updated_at
+            FROM fuzz_results WHERE chain_id = ?
+            ORDER BY contract, signature",
+        )
+        .bind(chain_id as i64)
+        .fetch_all(&self.pool)
+        .await;
+
+        self.metrics.total_selects.fetch_add(1, Ordering::SeqCst);
+        self.record_query_time(start, rows.is_ok());
+
+        match rows {
+            Ok(results) => {
+                self.metrics.total_queries.fetch_add(1, Ordering::SeqCst);
+                Ok(results)
+            }
+            Err(e) => {
+                self.metrics.total_errors.fetch_add(1, Ordering::SeqCst);
+                Err(e).context("Failed to read fuzz results")
+            }
+        }
+    }
+
+    /// Stores an operation, replacing the earlier record of the same key
+    #[instrument(
+        name = "db.insert",
+        skip_all,
+        fields(
+            db.system = "sqlite",
+            db.table = "operations",
+            wallet = %operation.wallet_address,
+            tx_hash = %operation.tx_hash,
+        )
+    )]
+    pub async fn record_operation(&self, operation: &OperationRecord) -> Result<()> {
         let start = std::time::Instant::now();
-        let timestamp = chrono::Utc::now().timestamp();
 
         let result = sqlx::query(
-            "INSERT INTO created_assets (wallet_address, asset_address, asset_type, name, symbol, timestamp) VALUES (?, ?, ?, ?, ?, ?)"
+            "INSERT OR REPLACE INTO operations
+                (chain_id, op_key, wallet_address, tx_hash, status, updated_at)
+             VALUES (?, ?, ?, ?, ?, ?)",
         )
-        .bind(wallet)
-        .bind(asset_addr)
-        .bind(asset_type)
-        .bind(name)
-        .bind(symbol)
-        .bind(timestamp)
+        .bind(operation.chain_id)
+        .bind(&operation.op_key)
+        .bind(&operation.wallet_address)
+        .bind(&operation.tx_hash)
+        .bind(&operation.status)
+        .bind(operation.updated_at)
         .execute(&self.pool)
         .await;
 
@@ -486,36 +2802,40 @@ impl DatabaseManager {
             }
             Err(e) => {
                 self.metrics.total_errors.fetch_add(1, Ordering::SeqCst);
-                error!("Failed to log asset creation: {}", e);
-                Err(e).context("Failed to insert asset")
+                error!("Failed to record operation: {}", e);
+                Err(e).context("Failed to record operation")
             }
         }
     }
 
-    pub async fn update_proxy_stats(&self, proxy_url: &str, success: bool) -> Result<()> {
+    /// The operation stored under `op_key` on a chain, if any
+    pub async fn get_operation(
+        &self,
+        chain_id: u64,
+        op_key: &str,
+    ) -> Result<Option<OperationRecord>> {
         let start = std::time::Instant::now();
-        let query = if success {
-            "INSERT INTO proxy_stats (proxy_url, success_count, fail_count) VALUES (?, 1, 0)
-             ON CONFLICT(proxy_url) DO UPDATE SET success_count = success_count + 1"
-        } else {
-            "INSERT INTO proxy_stats (proxy_url, success_count, fail_count) VALUES (?, 0, 1)
-             ON CONFLICT(proxy_url) DO UPDATE SET fail_count = fail_count + 1"
-        };
 
-        let result = sqlx::query(query).bind(proxy_url).execute(&self.pool).await;
+        let row = sqlx::query_as::<_, OperationRecord>(
+            "SELECT chain_id, op_key, wallet_address, tx_hash, status, updated_at
+            FROM operations WHERE chain_id = ? AND op_key = ?",
+        )
+        .bind(chain_id as i64)
+        .bind(op_key)
+        .fetch_optional(&self.pool)
+        .await;
 
-        self.metrics.total_inserts.fetch_add(1, Ordering::SeqCst);
-        self.record_query_time(start, result.is_ok());
+        self.metrics.total_selects.fetch_add(1, Ordering::SeqCst);
+        self.record_query_time(start, row.is_ok());
 
-        match result {
-            Ok(_) => {
+        match row {
+            Ok(operation) => {
                 self.metrics.total_queries.fetch_add(1, Ordering::SeqCst);
-                Ok(())
+                Ok(operation)
             }
             Err(e) => {
                 self.metrics.total_errors.fetch_add(1, Ordering::SeqCst);
-                error!("Failed to update proxy stats: {}", e);
-                Err(e).context("Failed to update proxy stats")
+                Err(e).context("Failed to read operation")
             }
         }
     }
@@ -677,6 +2997,70 @@ impl DatabaseManager {
         }
     }
 
+    /// Success/failure totals for every task recorded in task_metrics
+    pub async fn get_task_summary(&self) -> Result<Vec<TaskSummary>> {
+        let start = std::time::Instant::now();
+
+        let rows = sqlx::query_as::<_, TaskSummary>(
+            "SELECT task_name,
+                SUM(CASE WHEN status = 'SUCCESS' THEN 1 ELSE 0 END) AS success,
+                SUM(CASE WHEN status = 'SUCCESS' THEN 0 ELSE 1 END) AS failed,
+                COALESCE(SUM(duration_ms), 0) AS total_duration_ms,
+                COALESCE(SUM(gas_used), 0) AS total_gas_used,
+                COUNT(gas_used) AS gas_samples,
+                COUNT(json_extract(details, '$.verifications')) AS verified,
+                SUM(CASE WHEN EXISTS (
+                    SELECT 1 FROM json_each(task_metrics.details, '$.verifications')
+                    WHERE json_extract(value, '$.passed') = 0
+                ) THEN 1 ELSE 0 END) AS mismatched
+            FROM task_metrics GROUP BY task_name ORDER BY task_name",
+        )
+        .fetch_all(&self.pool)
+        .await;
+
+        self.metrics.total_selects.fetch_add(1, Ordering::SeqCst);
+        self.record_query_time(start, rows.is_ok());
+
+        match rows {
+            Ok(summary) => {
+                self.metrics.total_queries.fetch_add(1, Ordering::SeqCst);
+                Ok(summary)
+            }
+            Err(e) => {
+                self.metrics.total_errors.fetch_add(1, Ordering::SeqCst);
+                Err(e).context("Failed to summarize task metrics")
+            }
+        }
+    }
+
+    /// Success/failure totals per chain recorded in task_metrics
+    pub async fn get_chain_summary(&self) -> Result<Vec<ChainSummary>> {
+        let start = std::time::Instant::now();
+
+        let rows = sqlx::query_as::<_, ChainSummary>(
+            "SELECT chain_id,
+                SUM(CASE WHEN status = 'SUCCESS' THEN 1 ELSE 0 END) AS success,
+                SUM(CASE WHEN status = 'SUCCESS' THEN 0 ELSE 1 END) AS failed
+            FROM task_metrics GROUP BY chain_id ORDER BY chain_id",
+        )
+        .fetch_all(&self.pool)
+        .await;
+
+        self.metrics.total_selects.fetch_add(1, Ordering::SeqCst);
+        self.record_query_time(start, rows.is_ok());
+
+        match rows {
+            Ok(summary) => {
+                self.metrics.total_queries.fetch_add(1, Ordering::SeqCst);
+                Ok(summary)
+            }
+            Err(e) => {
+                self.metrics.total_errors.fetch_add(1, Ordering::SeqCst);
+                Err(e).context("Failed to summarize task metrics per chain")
+            }
+        }
+    }
+
     /// Check if a specific task has succeeded for a wallet
     pub async fn has_task_succeeded(&self, wallet: &str, task_name: &str) -> Result<bool> {
         let start = std::time::Instant::now();
@@ -695,79 +3079,384 @@ impl DatabaseManager {
         match row {
             Ok((count,)) => {
                 self.metrics.total_queries.fetch_add(1, Ordering::SeqCst);
-                Ok(count > 0)
+                Ok(count > 0)
+            }
+            Err(e) => {
+                self.metrics.total_errors.fetch_add(1, Ordering::SeqCst);
+                Err(e).context(format!(
+                    "Failed to check task success for {}: {}",
+                    wallet, task_name
+                ))
+            }
+        }
+    }
+
+    pub async fn batch_log_task_results(&self, results: &[TaskMetricBatchItem]) -> Result<usize> {
+        if results.is_empty() {
+            return Ok(0);
+        }
+
+        // Use SmallVec for stack allocation - typical batch size is 200
+        // SmallVec<[T; 32]> stores up to 32 items on the stack before heap allocation
+        type BatchRow = (String, String, String, String, String, i64, i64);
+        let mut batch_params: SmallVec<[BatchRow; 32]> = SmallVec::new();
+
+        let timestamp = chrono::Utc::now().timestamp();
+
+        for item in results {
+            let status = if item.success { "SUCCESS" } else { "FAILED" };
+            batch_params.push((
+                item.worker_id.clone(),
+                item.wallet.clone(),
+                item.task.clone(),
+                status.to_string(),
+                item.message.clone(),
+                item.duration_ms as i64,
+                timestamp,
+            ));
+        }
+
+        // Batch insert in a single transaction for better performance
+        let mut tx = self.pool.begin().await?;
+        let mut inserted = 0;
+
+        for param in &batch_params {
+            let result = sqlx::query(
+                "INSERT INTO task_metrics (worker_id, wallet_address, task_name, status, message, duration_ms, timestamp) VALUES (?, ?, ?, ?, ?, ?, ?)"
+            )
+            .bind(&param.0)
+            .bind(&param.1)
+            .bind(&param.2)
+            .bind(&param.3)
+            .bind(&param.4)
+            .bind(param.5)
+            .bind(param.6)
+            .execute(&mut *tx)
+            .await;
+
+            match result {
+                Ok(_) => {
+                    inserted += 1;
+                    self.metrics.total_inserts.fetch_add(1, Ordering::SeqCst);
+                }
+                Err(_) => {
+                    self.metrics.total_errors.fetch_add(1, Ordering::SeqCst);
+                }
+            }
+        }
+
+        tx.commit().await?;
+
+        self.metrics
+            .total_queries
+            .fetch_add(results.len() as u64, Ordering::SeqCst);
+
+        Ok(inserted)
+    }
+
+    /// Import task results from a JSONL spill file into task_metrics
+    ///
+    /// Each non-empty line is parsed as a [`SpillRecord`]. Rows are deduplicated
+    /// by run id, worker, timestamp and task name, so importing the same file
+    /// twice is harmless. Malformed lines are logged and skipped; the import
+    /// itself runs in a single transaction.
+    ///
+    /// # Arguments
+    /// * `path` - Path to the spill file
+    ///
+    /// # Returns
+    /// * `Ok(SpillImportSummary)` - Counts of imported, duplicate and malformed lines
+    /// * `Err` - The file could not be read or a row failed to insert
+    pub async fn import_spill_file(&self, path: &str) -> Result<SpillImportSummary> {
+        let content = tokio::fs::read_to_string(path)
+            .await
+            .map_err(|e| ConfigError::IoError {
+                path: path.to_string(),
+                msg: e.to_string(),
+            })?;
+
+        let fallback_run_id = Path::new(path)
+            .file_stem()
+            .map(|stem| stem.to_string_lossy().into_owned())
+            .unwrap_or_else(|| path.to_string());
+
+        let start = std::time::Instant::now();
+        let mut summary = SpillImportSummary::default();
+        let mut tx = self.pool.begin().await?;
+
+        for (line_no, line) in content.lines().enumerate() {
+            let line = line.trim();
+            if line.is_empty() {
+                continue;
+            }
+
+            let record: SpillRecord = match serde_json::from_str(line) {
+                Ok(record) => record,
+                Err(e) => {
+                    warn!("Skipping malformed spill line {}: {}", line_no + 1, e);
+                    summary.malformed += 1;
+                    continue;
+                }
+            };
+
+            let run_id = record.run_id.as_deref().unwrap_or(&fallback_run_id);
+            let status = if record.success { "SUCCESS" } else { "FAILED" };
+
+            let result = sqlx::query(
+                "INSERT OR IGNORE INTO task_metrics (run_id, chain_id, worker_id, wallet_address, task_name, status, message, duration_ms, timestamp, tx_hash, gas_used, block_number, contract_address, details, timeout_ms, host_partition, diagnostics) VALUES (?, ?, ?, ?, ?, ?, ?, ?, ?, ?, ?, ?, ?, ?, ?, ?, ?)"
+            )
+            .bind(run_id)
+            .bind(record.chain_id.map(|id| id as i64))
+            .bind(&record.worker_id)
+            .bind(&record.wallet_address)
+            .bind(&record.task_name)
+            .bind(status)
+            .bind(&record.message)
+            .bind(record.duration_ms as i64)
+            .bind(record.timestamp)
+            .bind(&record.metadata.tx_hash)
+            .bind(record.metadata.gas_used.map(|gas| gas as i64))
+            .bind(record.metadata.block_number.map(|block| block as i64))
+            .bind(&record.metadata.contract_address)
+            .bind(&record.metadata.details)
+            .bind(record.metadata.timeout_ms.map(|ms| ms as i64))
+            .bind(&record.metadata.host_partition)
+            .bind(&record.metadata.diagnostics)
+            .execute(&mut *tx)
+            .await;
+
+            self.metrics.total_inserts.fetch_add(1, Ordering::SeqCst);
+
+            match result {
+                Ok(done) if done.rows_affected() > 0 => summary.imported += 1,
+                Ok(_) => summary.duplicates += 1,
+                Err(e) => {
+                    self.metrics.total_errors.fetch_add(1, Ordering::SeqCst);
+                    error!("Failed to import spill line {}: {}", line_no + 1, e);
+                    return Err(e).context(format!(
+                        "Failed to import spill line {} from {}",
+                        line_no + 1,
+                        path
+                    ));
+                }
+            }
+        }
+
+        let committed = tx.commit().await;
+        self.record_query_time(start, committed.is_ok());
+        committed.context("Failed to commit spill import")?;
+
+        self.metrics.total_queries.fetch_add(
+            (summary.imported + summary.duplicates) as u64,
+            Ordering::SeqCst,
+        );
+
+        info!(
+            "Imported spill file {}: {} new, {} duplicate, {} malformed",
+            path, summary.imported, summary.duplicates, summary.malformed
+        );
+        Ok(summary)
+    }
+
+    /// task_metrics rows with `since <= timestamp <= until`, oldest first
+    ///
+    /// Returned as spill records so an extract can be merged into another
+    /// database with [`DatabaseManager::import_spill_file`].
+    pub async fn get_task_records(&self, since: i64, until: i64) -> Result<Vec<SpillRecord>> {
+        let start = std::time::Instant::now();
+
+        let rows = sqlx::query_as::<_, TaskRecordRow>(
+            "SELECT run_id, chain_id, COALESCE(worker_id, '') AS worker_id,
+                COALESCE(wallet_address, '') AS wallet_address,
+                COALESCE(task_name, '') AS task_name, COALESCE(status, '') AS status, message,
+                COALESCE(duration_ms, 0) AS duration_ms, timestamp, tx_hash, gas_used,
+                block_number, contract_address, details, timeout_ms, host_partition, diagnostics
+            FROM task_metrics WHERE timestamp BETWEEN ? AND ? ORDER BY timestamp, id",
+        )
+        .bind(since)
+        .bind(until)
+        .fetch_all(&self.pool)
+        .await;
+
+        self.metrics.total_selects.fetch_add(1, Ordering::SeqCst);
+        self.record_query_time(start, rows.is_ok());
+
+        match rows {
+            Ok(rows) => {
+                self.metrics.total_queries.fetch_add(1, Ordering::SeqCst);
+                Ok(rows
+                    .into_iter()
+                    .map(|row| SpillRecord {
+                        run_id: row.run_id,
+                        chain_id: row.chain_id.map(|id| id as u64),
+                        worker_id: row.worker_id,
+                        wallet_address: row.wallet_address,
+                        task_name: row.task_name,
+                        success: row.status == "SUCCESS",
+                        message: row.message.unwrap_or_default(),
+                        duration_ms: row.duration_ms.max(0) as u64,
+                        timestamp: row.timestamp,
+                        metadata: TaskMetadata {
+                            tx_hash: row.tx_hash,
+                            gas_used: row.gas_used.map(|gas| gas as u64),
+                            block_number: row.block_number.map(|block| block as u64),
+                            contract_address: row.contract_address,
+                            details: row.details,
+                            timeout_ms: row.timeout_ms.map(|ms| ms as u64),
+                            host_partition: row.host_partition,
+                            diagnostics: row.diagnostics,
+                        },
+                    })
+                    .collect())
             }
             Err(e) => {
                 self.metrics.total_errors.fetch_add(1, Ordering::SeqCst);
-                Err(e).context(format!(
-                    "Failed to check task success for {}: {}",
-                    wallet, task_name
-                ))
+                Err(e).context("Failed to read task records")
             }
         }
     }
 
-    pub async fn batch_log_task_results(&self, results: &[TaskMetricBatchItem]) -> Result<usize> {
-        if results.is_empty() {
-            return Ok(0);
-        }
-
-        // Use SmallVec for stack allocation - typical batch size is 200
-        // SmallVec<[T; 32]> stores up to 32 items on the stack before heap allocation
-        type BatchRow = (String, String, String, String, String, i64, i64);
-        let mut batch_params: SmallVec<[BatchRow; 32]> = SmallVec::new();
-
-        let timestamp = chrono::Utc::now().timestamp();
+    /// Writes the rows of `table` to a CSV or Parquet file at `path`
+    ///
+    /// `since` and `until` (unix seconds, inclusive) filter on the table's
+    /// time column from [`EXPORT_TABLES`]; tables without one only export
+    /// whole. Rows are read in batches of 10 000, so large tables never sit
+    /// in memory at once, and the file only appears once it is complete.
+    /// Returns the number of rows written.
+    ///
+    /// # Errors
+    ///
+    /// Fails for tables not listed in [`EXPORT_TABLES`], a time range on a
+    /// table without a time column, Parquet in a build without the `parquet`
+    /// feature, and read or write errors.
+    pub async fn export_table(
+        &self,
+        table: &str,
+        format: ExportFormat,
+        path: &Path,
+        since: Option<i64>,
+        until: Option<i64>,
+    ) -> Result<u64> {
+        let Some(&(table, time_column)) = EXPORT_TABLES.iter().find(|(name, _)| *name == table)
+        else {
+            anyhow::bail!(
+                "Unknown table '{}', expected one of: {}",
+                table,
+                EXPORT_TABLES
+                    .iter()
+                    .map(|(name, _)| *name)
+                    .collect::<Vec<_>>()
+                    .join(", ")
+            );
+        };
+        let filter = match (time_column, since.is_some() || until.is_some()) {
+            (Some(column), true) => format!(" AND {} BETWEEN ? AND ?", column),
+            (None, true) => anyhow::bail!("Table {} has no time column to filter on", table),
+            (_, false) => String::new(),
+        };
 
-        for item in results {
-            let status = if item.success { "SUCCESS" } else { "FAILED" };
-            batch_params.push((
-                item.worker_id.clone(),
-                item.wallet.clone(),
-                item.task.clone(),
-                status.to_string(),
-                item.message.clone(),
-                item.duration_ms as i64,
-                timestamp,
-            ));
-        }
+        let columns: Vec<(String, String)> =
+            sqlx::query_as("SELECT name, type FROM pragma_table_info(?) ORDER BY cid")
+                .bind(table)
+                .fetch_all(&self.pool)
+                .await
+                .with_context(|| format!("Failed to read the columns of {}", table))?;
+        let columns: Vec<ExportColumn> = columns
+            .into_iter()
+            .map(|(name, declared)| ExportColumn {
+                kind: ColumnKind::of(&declared),
+                name,
+            })
+            .collect();
+        let sql = format!(
+            "SELECT rowid, {} FROM {} WHERE rowid > ?{} ORDER BY rowid LIMIT {}",
+            columns
+                .iter()
+                .map(|column| format!("CAST(\"{}\" AS {})", column.name, column.kind.sql_type()))
+                .collect::<Vec<_>>()
+                .join(", "),
+            table,
+            filter,
+            EXPORT_BATCH_ROWS
+        );
 
-        // Batch insert in a single transaction for better performance
-        let mut tx = self.pool.begin().await?;
-        let mut inserted = 0;
+        let file_name = path
+            .file_name()
+            .with_context(|| format!("Export path {} has no file name", path.display()))?;
+        let tmp = path.with_file_name(format!("{}.tmp", file_name.to_string_lossy()));
+        let sink = ExportSink::create(format, &tmp, &columns)?;
+        let range = time_column.map(|_| (since.unwrap_or(i64::MIN), until.unwrap_or(i64::MAX)));
+        let written = match self.write_export(sink, &sql, table, &columns, range).await {
+            Ok(written) => written,
+            Err(e) => {
+                let _ = std::fs::remove_file(&tmp);
+                return Err(e);
+            }
+        };
+        std::fs::rename(&tmp, path)
+            .with_context(|| format!("Failed to move the export to {}", path.display()))?;
+        info!(
+            "Exported {} rows of {} to {}",
+            written,
+            table,
+            path.display()
+        );
+        Ok(written)
+    }
 
-        for param in &batch_params {
-            let result = sqlx::query(
-                "INSERT INTO task_metrics (worker_id, wallet_address, task_name, status, message, duration_ms, timestamp) VALUES (?, ?, ?, ?, ?, ?, ?)"
-            )
-            .bind(&param.0)
-            .bind(&param.1)
-            .bind(&param.2)
-            .bind(&param.3)
-            .bind(&param.4)
-            .bind(param.5)
-            .bind(param.6)
-            .execute(&mut *tx)
-            .await;
+    /// Runs the batched `sql` of [`export_table`](Self::export_table) into `sink`
+    async fn write_export(
+        &self,
+        mut sink: ExportSink,
+        sql: &str,
+        table: &str,
+        columns: &[ExportColumn],
+        range: Option<(i64, i64)>,
+    ) -> Result<u64> {
+        use sqlx::Row;
 
-            match result {
-                Ok(_) => {
-                    inserted += 1;
-                    self.metrics.total_inserts.fetch_add(1, Ordering::SeqCst);
-                }
-                Err(_) => {
+        let mut last_rowid = 0i64;
+        let mut written = 0u64;
+        loop {
+            let start = std::time::Instant::now();
+            let mut query = sqlx::query(sql).bind(last_rowid);
+            if let Some((since, until)) = range {
+                query = query.bind(since).bind(until);
+            }
+            let rows = query.fetch_all(&self.pool).await;
+            self.metrics.total_selects.fetch_add(1, Ordering::SeqCst);
+            self.record_query_time(start, rows.is_ok());
+            let rows = match rows {
+                Ok(rows) => rows,
+                Err(e) => {
                     self.metrics.total_errors.fetch_add(1, Ordering::SeqCst);
+                    return Err(e).with_context(|| format!("Failed to read {}", table));
                 }
+            };
+            self.metrics.total_queries.fetch_add(1, Ordering::SeqCst);
+            let Some(last) = rows.last() else {
+                break;
+            };
+            last_rowid = last.try_get(0)?;
+
+            let batch = rows
+                .iter()
+                .map(|row| {
+                    columns
+                        .iter()
+                        .enumerate()
+                        .map(|(i, column)| column.kind.read(row, i + 1))
+                        .collect::<Result<Vec<_>>>()
+                })
+                .collect::<Result<Vec<_>>>()?;
+            sink.write(&batch)?;
+            written += batch.len() as u64;
+            if rows.len() < EXPORT_BATCH_ROWS {
+                break;
             }
         }
-
-        tx.commit().await?;
-
-        self.metrics
-            .total_queries
-            .fetch_add(results.len() as u64, Ordering::SeqCst);
-
-        Ok(inserted)
+        sink.finish()?;
+        Ok(written)
     }
 
     pub fn get_metrics(&self) -> DbMetricsSnapshot {
@@ -776,12 +3465,13 @@ impl DatabaseManager {
             total_errors: self.metrics.total_errors.load(Ordering::SeqCst),
             total_inserts: self.metrics.total_inserts.load(Ordering::SeqCst),
             total_selects: self.metrics.total_selects.load(Ordering::SeqCst),
+            avg_query_ms: self.metrics.query_duration.mean(),
         }
     }
 
     /// Get the async database configuration (if async mode is enabled)
     pub fn get_async_config(&self) -> Option<AsyncDbConfig> {
-        self.async_config
+        self.async_config.clone()
     }
 
     /// Check if async logging is enabled
@@ -797,6 +3487,11 @@ impl DatabaseManager {
         )
     }
 
+    #[instrument(
+        name = "db.insert",
+        skip_all,
+        fields(db.system = "sqlite", db.table = "dex_orders", wallet = %wallet, tx_hash = %tx_hash)
+    )]
     pub async fn log_dex_order(
         &self,
         wallet: &str,
@@ -887,23 +3582,10 @@ impl DatabaseManager {
     }
 
     fn record_query_time(&self, start: std::time::Instant, success: bool) {
-        let elapsed_ms = start.elapsed().as_millis() as u64;
-        let count = self.metrics.query_count_for_avg.load(Ordering::SeqCst);
-        let current_avg = self.metrics.avg_query_time_ms.load(Ordering::SeqCst);
-
         if success {
-            let new_count = count + 1;
-            let new_avg = if count == 0 {
-                elapsed_ms
-            } else {
-                (current_avg * count + elapsed_ms) / new_count
-            };
-            self.metrics
-                .query_count_for_avg
-                .store(new_count, Ordering::SeqCst);
             self.metrics
-                .avg_query_time_ms
-                .store(new_avg, Ordering::SeqCst);
+                .query_duration
+                .observe_duration(start.elapsed());
         }
     }
 }
@@ -924,6 +3606,8 @@ pub struct DbMetricsSnapshot {
     pub total_errors: u64,
     pub total_inserts: u64,
     pub total_selects: u64,
+    /// Mean duration of successful queries
+    pub avg_query_ms: f64,
 }
 
 impl DbMetricsSnapshot {
@@ -936,20 +3620,528 @@ impl DbMetricsSnapshot {
     }
 }
 
+/// Rows [`DatabaseManager::export_table`] reads per query
+const EXPORT_BATCH_ROWS: usize = 10_000;
+
+/// Tables [`DatabaseManager::export_table`] writes out, with the column of
+/// unix seconds their time range filters on
+pub const EXPORT_TABLES: &[(&str, Option<&str>)] = &[
+    ("task_metrics", Some("timestamp")),
+    ("gas_usage", Some("timestamp")),
+    ("audits", Some("timestamp")),
+    ("consistency_checks", Some("timestamp")),
+    ("created_counter_contracts", Some("timestamp")),
+    ("created_assets", Some("timestamp")),
+    ("dex_orders", Some("timestamp")),
+    ("helper_contracts", Some("deployed_at")),
+    ("smart_accounts", Some("created_at")),
+    ("nft_listings", Some("listed_at")),
+    ("domains", Some("registered_at")),
+    ("fuzz_results", Some("updated_at")),
+    ("operations", Some("updated_at")),
+    ("proxy_stats", None),
+    ("spend_ledger", None),
+    ("burst_rounds", None),
+    ("burst_results", None),
+    ("burst_blocks", None),
+    ("latency_samples", None),
+];
+
+/// File format of [`DatabaseManager::export_table`]
+#[derive(Debug, Clone, Copy, PartialEq, Eq, Default, Serialize, Deserialize)]
+#[serde(rename_all = "lowercase")]
+pub enum ExportFormat {
+    /// Comma-separated with a header row; NULL is an empty field
+    #[default]
+    Csv,
+    /// Apache Parquet, Snappy-compressed (needs the `parquet` feature)
+    Parquet,
+}
+
+impl ExportFormat {
+    /// File extension without the dot
+    pub fn extension(self) -> &'static str {
+        match self {
+            Self::Csv => "csv",
+            Self::Parquet => "parquet",
+        }
+    }
+}
+
+impl FromStr for ExportFormat {
+    type Err = anyhow::Error;
+
+    fn from_str(s: &str) -> Result<Self> {
+        match s.to_ascii_lowercase().as_str() {
+            "csv" => Ok(Self::Csv),
+            "parquet" => Ok(Self::Parquet),
+            _ => anyhow::bail!("Unknown export format '{}', expected csv or parquet", s),
+        }
+    }
+}
+
+/// A column of an exported table
+struct ExportColumn {
+    name: String,
+    kind: ColumnKind,
+}
+
+/// How an exported column is read and typed, from its declared SQLite type
+#[derive(Debug, Clone, Copy, PartialEq, Eq)]
+enum ColumnKind {
+    Integer,
+    Real,
+    Text,
+}
+
+impl ColumnKind {
+    /// SQLite's affinity rules, reduced to the three types the schema uses
+    fn of(declared: &str) -> Self {
+        let declared = declared.to_ascii_uppercase();
+        if declared.contains("INT") {
+            Self::Integer
+        } else if ["REAL", "FLOA", "DOUB"]
+            .iter()
+            .any(|real| declared.contains(real))
+        {
+            Self::Real
+        } else {
+            Self::Text
+        }
+    }
+
+    fn sql_type(self) -> &'static str {
+        match self {
+            Self::Integer => "INTEGER",
+            Self::Real => "REAL",
+            Self::Text => "TEXT",
+        }
+    }
+
+    fn read(self, row: &sqlx::sqlite::SqliteRow, index: usize) -> Result<ExportValue> {
+        use sqlx::Row;
+
+        Ok(match self {
+            Self::Integer => row
+                .try_get::<Option<i64>, _>(index)?
+                .map_or(ExportValue::Null, ExportValue::Integer),
+            Self::Real => row
+                .try_get::<Option<f64>, _>(index)?
+                .map_or(ExportValue::Null, ExportValue::Real),
+            Self::Text => row
+                .try_get::<Option<String>, _>(index)?
+                .map_or(ExportValue::Null, ExportValue::Text),
+        })
+    }
+}
+
+/// One exported cell
+#[derive(Debug, Clone, PartialEq)]
+enum ExportValue {
+    Null,
+    Integer(i64),
+    Real(f64),
+    Text(String),
+}
+
+impl ExportValue {
+    fn to_field(&self) -> std::borrow::Cow<'_, str> {
+        match self {
+            Self::Null => "".into(),
+            Self::Integer(value) => value.to_string().into(),
+            Self::Real(value) => value.to_string().into(),
+            Self::Text(value) => value.as_str().into(),
+        }
+    }
+}
+
+/// Open export file of one format
+enum ExportSink {
+    Csv(csv::Writer<std::fs::File>),
+    #[cfg(feature = "parquet")]
+    Parquet(parquet_export::ParquetSink),
+}
+
+impl ExportSink {
+    fn create(format: ExportFormat, path: &Path, columns: &[ExportColumn]) -> Result<Self> {
+        let file = std::fs::File::create(path)
+            .with_context(|| format!("Failed to create {}", path.display()))?;
+        match format {
+            ExportFormat::Csv => {
+                let mut writer = csv::Writer::from_writer(file);
+                writer.write_record(columns.iter().map(|column| column.name.as_str()))?;
+                Ok(Self::Csv(writer))
+            }
+            #[cfg(feature = "parquet")]
+            ExportFormat::Parquet => Ok(Self::Parquet(parquet_export::ParquetSink::create(
+                file, columns,
+            )?)),
+            #[cfg(not(feature = "parquet"))]
+            ExportFormat::Parquet => {
+                drop(file);
+                let _ = std::fs::remove_file(path);
+                anyhow::bail!("Parquet export needs a build with the `parquet` feature")
+            }
+        }
+    }
+
+    fn write(&mut self, rows: &[Vec<ExportValue>]) -> Result<()> {
+        match self {
+            Self::Csv(writer) => {
+                for row in rows {
+                    writer.write_record(row.iter().map(|value| value.to_field().into_owned()))?;
+                }
+                Ok(())
+            }
+            #[cfg(feature = "parquet")]
+            Self::Parquet(sink) => sink.write(rows),
+        }
+    }
+
+    fn finish(self) -> Result<()> {
+        match self {
+            Self::Csv(mut writer) => Ok(writer.flush()?),
+            #[cfg(feature = "parquet")]
+            Self::Parquet(sink) => sink.finish(),
+        }
+    }
+}
+
+#[cfg(feature = "parquet")]
+mod parquet_export {
+    use super::{ColumnKind, ExportColumn, ExportValue};
+    use anyhow::Result;
+    use arrow_array::{ArrayRef, Float64Array, Int64Array, RecordBatch, StringArray};
+    use arrow_schema::{DataType, Field, Schema};
+    use parquet::arrow::ArrowWriter;
+    use parquet::basic::Compression;
+    use parquet::file::properties::WriterProperties;
+    use std::sync::Arc;
+
+    /// Parquet file written one record batch per export batch
+    pub(super) struct ParquetSink {
+        writer: ArrowWriter<std::fs::File>,
+        schema: Arc<Schema>,
+        kinds: Vec<ColumnKind>,
+    }
+
+    impl ParquetSink {
+        pub(super) fn create(file: std::fs::File, columns: &[ExportColumn]) -> Result<Self> {
+            let schema = Arc::new(Schema::new(
+                columns
+                    .iter()
+                    .map(|column| {
+                        let data_type = match column.kind {
+                            ColumnKind::Integer => DataType::Int64,
+                            ColumnKind::Real => DataType::Float64,
+                            ColumnKind::Text => DataType::Utf8,
+                        };
+                        Field::new(&column.name, data_type, true)
+                    })
+                    .collect::<Vec<_>>(),
+            ));
+            let properties = WriterProperties::builder()
+                .set_compression(Compression::SNAPPY)
+                .build();
+            Ok(Self {
+                writer: ArrowWriter::try_new(file, schema.clone(), Some(properties))?,
+                schema,
+                kinds: columns.iter().map(|column| column.kind).collect(),
+            })
+        }
+
+        pub(super) fn write(&mut self, rows: &[Vec<ExportValue>]) -> Result<()> {
+            let arrays = self
+                .kinds
+                .iter()
+                .enumerate()
+                .map(|(i, kind)| -> ArrayRef {
+                    let cells = rows.iter().map(|row| &row[i]);
+                    match kind {
+                        ColumnKind::Integer => Arc::new(
+                            cells
+                                .map(|cell| match cell {
+                                    ExportValue::Integer(value) => Some(*value),
+                                    _ => None,
+                                })
+                                .collect::<Int64Array>(),
+                        ),
+                        ColumnKind::Real => Arc::new(
+                            cells
+                                .map(|cell| match cell {
+                                    ExportValue::Real(value) => Some(*value),
+                                    _ => None,
+                                })
+                                .collect::<Float64Array>(),
+                        ),
+                        ColumnKind::Text => Arc::new(
+                            cells
+                                .map(|cell| match cell {
+                                    ExportValue::Text(value) => Some(value.as_str()),
+                                    _ => None,
+                                })
+                                .collect::<StringArray>(),
+                        ),
+                    }
+                })
+                .collect();
+            self.writer
+                .write(&RecordBatch::try_new(self.schema.clone(), arrays)?)?;
+            Ok(())
+        }
+
+        pub(super) fn finish(self) -> Result<()> {
+            self.writer.close()?;
+            Ok(())
+        }
+    }
+}
+
+/// Path of the monthly shard for a base database path
+///
+/// `tempo-spammer.db` becomes `tempo-spammer-2024-06.db` for a timestamp in
+/// June 2024. The shard lives next to the base file.
+pub fn monthly_shard_path(base_path: &str, at: chrono::DateTime<chrono::Utc>) -> String {
+    let base = Path::new(base_path);
+    let stem = base
+        .file_stem()
+        .map(|s| s.to_string_lossy().into_owned())
+        .unwrap_or_else(|| base_path.to_string());
+    let file_name = match base.extension() {
+        Some(ext) => format!("{}-{}.{}", stem, at.format("%Y-%m"), ext.to_string_lossy()),
+        None => format!("{}-{}", stem, at.format("%Y-%m")),
+    };
+    base.with_file_name(file_name)
+        .to_string_lossy()
+        .into_owned()
+}
+
+/// Find the base database and all of its monthly shards, oldest first
+///
+/// The unsharded base file (if present) comes first, followed by shards
+/// matching `<stem>-YYYY-MM.<ext>` in the same directory.
+pub fn discover_shards(base_path: &str) -> Result<Vec<PathBuf>> {
+    let base = Path::new(base_path);
+    let dir = match base.parent() {
+        Some(parent) if !parent.as_os_str().is_empty() => parent.to_path_buf(),
+        _ => PathBuf::from("."),
+    };
+    let stem = base
+        .file_stem()
+        .map(|s| s.to_string_lossy().into_owned())
+        .unwrap_or_default();
+    let ext = base.extension().map(|e| e.to_string_lossy().into_owned());
+
+    let mut shards = Vec::new();
+    let entries = std::fs::read_dir(&dir).map_err(|e| ConfigError::IoError {
+        path: dir.display().to_string(),
+        msg: e.to_string(),
+    })?;
+
+    for entry in entries.flatten() {
+        let path = entry.path();
+        if path.extension().map(|e| e.to_string_lossy().into_owned()) != ext {
+            continue;
+        }
+        let Some(file_stem) = path.file_stem().map(|s| s.to_string_lossy().into_owned()) else {
+            continue;
+        };
+        let Some(month) = file_stem
+            .strip_prefix(&stem)
+            .and_then(|rest| rest.strip_prefix('-'))
+        else {
+            continue;
+        };
+        if chrono::NaiveDate::parse_from_str(&format!("{}-01", month), "%Y-%m-%d").is_ok() {
+            shards.push(path);
+        }
+    }
+
+    // YYYY-MM names sort chronologically
+    shards.sort();
+    if base.exists() {
+        shards.insert(0, base.to_path_buf());
+    }
+    Ok(shards)
+}
+
+/// Union over a base database and its monthly shards for reporting
+///
+/// Stats are computed per file and merged in memory, so there is no limit on
+/// the number of shards (unlike `ATTACH DATABASE`). Shards are opened read
+/// only; no task data is written through it.
+#[derive(Debug)]
+pub struct ShardedDatabase {
+    shards: Vec<(String, DatabaseManager)>,
+}
+
+impl ShardedDatabase {
+    /// Open every shard found by [`discover_shards`]
+    ///
+    /// See [`DatabaseManager::open_read_only`]: only a shard written by an
+    /// older build is migrated, so it gains the columns the stats queries read.
+    pub async fn open(base_path: &str) -> Result<Self> {
+        let mut shards = Vec::new();
+        for path in discover_shards(base_path)? {
+            let path = path.to_string_lossy().into_owned();
+            let manager = DatabaseManager::open_read_only(&path)
+                .await
+                .with_context(|| format!("Failed to open database shard {}", path))?;
+            shards.push((path, manager));
+        }
+        debug!(
+            "Opened {} database shard(s) for {}",
+            shards.len(),
+            base_path
+        );
+        Ok(Self { shards })
+    }
+
+    pub fn shard_paths(&self) -> Vec<&str> {
+        self.shards.iter().map(|(path, _)| path.as_str()).collect()
+    }
+
+    pub fn is_empty(&self) -> bool {
+        self.shards.is_empty()
+    }
+
+    pub async fn get_transaction_count(&self, wallet: &str) -> Result<i64> {
+        let mut total = 0i64;
+        for (_, db) in &self.shards {
+            total += db.get_transaction_count(wallet).await? as i64;
+        }
+        Ok(total)
+    }
+
+    pub async fn get_success_count(&self, wallet: &str) -> Result<i64> {
+        let mut total = 0i64;
+        for (_, db) in &self.shards {
+            total += db.get_success_count(wallet).await? as i64;
+        }
+        Ok(total)
+    }
+
+    pub async fn has_task_succeeded(&self, wallet: &str, task_name: &str) -> Result<bool> {
+        for (_, db) in &self.shards {
+            if db.has_task_succeeded(wallet, task_name).await? {
+                return Ok(true);
+            }
+        }
+        Ok(false)
+    }
+
+    /// task_metrics rows of a time window from every shard, oldest first
+    pub async fn get_task_records(&self, since: i64, until: i64) -> Result<Vec<SpillRecord>> {
+        let mut records = Vec::new();
+        for (_, db) in &self.shards {
+            records.extend(db.get_task_records(since, until).await?);
+        }
+        records.sort_by_key(|record| record.timestamp);
+        Ok(records)
+    }
+
+    /// Per-task totals merged across all shards, ordered by task name
+    pub async fn get_task_summary(&self) -> Result<Vec<TaskSummary>> {
+        let mut merged: std::collections::BTreeMap<String, TaskSummary> =
+            std::collections::BTreeMap::new();
+
+        for (_, db) in &self.shards {
+            for row in db.get_task_summary().await? {
+                merged
+                    .entry(row.task_name.clone())
+                    .and_modify(|acc| {
+                        acc.success += row.success;
+                        acc.failed += row.failed;
+                        acc.total_duration_ms += row.total_duration_ms;
+                        acc.total_gas_used += row.total_gas_used;
+                        acc.gas_samples += row.gas_samples;
+                        acc.verified += row.verified;
+                        acc.mismatched += row.mismatched;
+                    })
+                    .or_insert(row);
+            }
+        }
+        Ok(merged.into_values().collect())
+    }
+
+    /// Per-task gas totals across all shards
+    pub async fn get_gas_summary(&self) -> Result<Vec<GasSummary>> {
+        self.get_gas_summary_between(i64::MIN, i64::MAX).await
+    }
+
+    /// Per-task gas totals of a time window across all shards
+    pub async fn get_gas_summary_between(&self, since: i64, until: i64) -> Result<Vec<GasSummary>> {
+        let mut merged: std::collections::BTreeMap<String, GasSummary> =
+            std::collections::BTreeMap::new();
+
+        for (_, db) in &self.shards {
+            for row in db.get_gas_summary_between(since, until).await? {
+                merged
+                    .entry(row.task_name.clone())
+                    .and_modify(|acc| {
+                        acc.transactions += row.transactions;
+                        acc.reverted += row.reverted;
+                        acc.total_gas += row.total_gas;
+                        acc.max_gas = acc.max_gas.max(row.max_gas);
+                        acc.total_cost_wei += row.total_cost_wei;
+                    })
+                    .or_insert(row);
+            }
+        }
+        Ok(merged.into_values().collect())
+    }
+
+    /// Per-proxy totals across all shards, most bytes first
+    pub async fn get_proxy_stats(&self) -> Result<Vec<ProxyStats>> {
+        let mut merged: std::collections::BTreeMap<String, ProxyStats> =
+            std::collections::BTreeMap::new();
+
+        for (_, db) in &self.shards {
+            for row in db.get_proxy_stats().await? {
+                merged
+                    .entry(row.proxy_url.clone())
+                    .and_modify(|acc| {
+                        acc.request_count += row.request_count;
+                        acc.success_count += row.success_count;
+                        acc.fail_count += row.fail_count;
+                        acc.bytes_sent += row.bytes_sent;
+                        acc.bytes_received += row.bytes_received;
+                    })
+                    .or_insert(row);
+            }
+        }
+
+        let mut stats: Vec<ProxyStats> = merged.into_values().collect();
+        stats.sort_by_key(|row| std::cmp::Reverse(row.total_bytes()));
+        Ok(stats)
+    }
+}
+
 /// Background worker that batches and flushes database writes
 ///
 /// This function runs in a separate tokio task and handles:
 /// - Receiving entries from workers via channel
 /// - Batching entries up to config.batch_size
 /// - Periodic flushing based on config.flush_interval_ms
+/// - Spilling batches that fail to flush to config.spill_path
 /// - Graceful shutdown when channel closes
 async fn db_flush_worker(
     mut rx: mpsc::Receiver<QueuedTaskResult>,
-    pool: SqlitePool,
+    mut target: FlushTarget,
     config: AsyncDbConfig,
 ) {
     let mut batch = Vec::with_capacity(config.batch_size);
     let mut flush_interval = tokio::time::interval(Duration::from_millis(config.flush_interval_ms));
+    let checkpoints = config.journal_mode == JournalMode::Wal && config.checkpoint_interval_ms > 0;
+    let mut checkpoint_interval =
+        tokio::time::interval(Duration::from_millis(config.checkpoint_interval_ms.max(1)));
+    checkpoint_interval.reset();
+    // Spilled lines of one worker dedup among themselves on import
+    let spill_run_id = format!(
+        "spill-{}-{}",
+        std::process::id(),
+        chrono::Utc::now().timestamp_millis()
+    );
 
     info!(
         "Database flush worker started (batch: {}, interval: {}ms)",
@@ -964,9 +4156,8 @@ async fn db_flush_worker(
 
                 // Flush immediately if batch is full
                 if batch.len() >= config.batch_size {
-                    if let Err(e) = flush_batch(&batch, &pool).await {
-                        error!("Failed to flush batch: {}", e);
-                    }
+                    target.follow_month(&config).await;
+                    flush_or_spill(&batch, &target.pool, &config, &spill_run_id).await;
                     batch.clear();
                 }
             }
@@ -974,13 +4165,23 @@ async fn db_flush_worker(
             // Periodic flush based on time
             _ = flush_interval.tick() => {
                 if !batch.is_empty() {
-                    if let Err(e) = flush_batch(&batch, &pool).await {
-                        error!("Failed to flush batch: {}", e);
-                    }
+                    target.follow_month(&config).await;
+                    flush_or_spill(&batch, &target.pool, &config, &spill_run_id).await;
                     batch.clear();
                 }
             }
 
+            // Passive checkpoints never wait on readers, so long stats
+            // queries only delay how much of the WAL gets copied back
+            _ = checkpoint_interval.tick(), if checkpoints => {
+                if let Err(e) = sqlx::query("PRAGMA wal_checkpoint(PASSIVE);")
+                    .execute(&target.pool)
+                    .await
+                {
+                    warn!("WAL checkpoint failed: {}", e);
+                }
+            }
+
             // Channel closed (shutdown signal)
             else => {
                 info!("Database channel closed, performing final flush");
@@ -991,9 +4192,8 @@ async fn db_flush_worker(
 
     // Final flush on shutdown
     if !batch.is_empty() {
-        if let Err(e) = flush_batch(&batch, &pool).await {
-            error!("Final flush failed: {}", e);
-        } else {
+        target.follow_month(&config).await;
+        if flush_or_spill(&batch, &target.pool, &config, &spill_run_id).await {
             info!("Final flush completed: {} entries", batch.len());
         }
     }
@@ -1001,6 +4201,143 @@ async fn db_flush_worker(
     info!("Database flush worker stopped");
 }
 
+/// The database file the flush worker writes to
+struct FlushTarget {
+    path: String,
+    pool: SqlitePool,
+}
+
+impl FlushTarget {
+    /// Moves to the current month's shard if it is not the target yet
+    ///
+    /// The shard is created with the full schema when missing. When it
+    /// cannot be opened the worker stays on its current file and tries
+    /// again with the next batch.
+    async fn follow_month(&mut self, config: &AsyncDbConfig) {
+        let Some(base) = &config.shard_base_path else {
+            return;
+        };
+        let path = monthly_shard_path(base, chrono::Utc::now());
+        if path == self.path {
+            return;
+        }
+        let opened = async {
+            DatabaseManager::create_file(&path)?;
+            let pool = DatabaseManager::connect(&path, config, false).await?;
+            DatabaseManager::init_schema(&pool).await?;
+            Ok::<_, anyhow::Error>(pool)
+        };
+        match opened.await {
+            Ok(pool) => {
+                info!("Database flush worker moved from {} to {}", self.path, path);
+                self.path = path;
+                self.pool = pool;
+            }
+            Err(e) => warn!(
+                "Failed to open database shard {}, still writing to {}: {:#}",
+                path, self.path, e
+            ),
+        }
+    }
+}
+
+/// [`flush_with_retry`], appending the batch to the spill file if it fails
+///
+/// Returns whether the batch reached the database.
+async fn flush_or_spill(
+    batch: &[QueuedTaskResult],
+    pool: &SqlitePool,
+    config: &AsyncDbConfig,
+    run_id: &str,
+) -> bool {
+    let Err(e) = flush_with_retry(batch, pool).await else {
+        return true;
+    };
+    let Some(path) = &config.spill_path else {
+        error!("Failed to flush batch, {} entries lost: {}", batch.len(), e);
+        return false;
+    };
+    match spill_batch(batch, path, run_id).await {
+        Ok(()) => warn!(
+            "Failed to flush batch, spilled {} entries to {}: {}",
+            batch.len(),
+            path.display(),
+            e
+        ),
+        Err(spill_err) => error!(
+            "Failed to flush batch, {} entries lost: {} (spilling to {} failed: {:#})",
+            batch.len(),
+            e,
+            path.display(),
+            spill_err
+        ),
+    }
+    false
+}
+
+/// Appends `batch` to the JSONL spill file at `path`
+async fn spill_batch(batch: &[QueuedTaskResult], path: &Path, run_id: &str) -> Result<()> {
+    use tokio::io::AsyncWriteExt;
+
+    let mut lines = String::new();
+    for result in batch {
+        lines.push_str(&serde_json::to_string(&SpillRecord::from_queued(
+            run_id, result,
+        ))?);
+        lines.push('\n');
+    }
+    let mut file = tokio::fs::OpenOptions::new()
+        .create(true)
+        .append(true)
+        .open(path)
+        .await
+        .with_context(|| format!("Failed to open spill file {}", path.display()))?;
+    file.write_all(lines.as_bytes()).await?;
+    file.flush().await?;
+    Ok(())
+}
+
+/// [`flush_batch`], retried with backoff while the database is busy
+///
+/// A flush racing a long read or another writer can hit SQLITE_BUSY even
+/// with a busy timeout, e.g. when a WAL snapshot went stale. The batch is
+/// written in one transaction, so a failed attempt leaves nothing behind.
+async fn flush_with_retry(batch: &[QueuedTaskResult], pool: &SqlitePool) -> Result<()> {
+    let mut attempt = 1;
+    loop {
+        match flush_batch(batch, pool).await {
+            Err(e) if attempt < FLUSH_BUSY_ATTEMPTS && is_busy(&e) => {
+                let backoff = FLUSH_RETRY_BASE_MS << (attempt - 1);
+                warn!(
+                    "Database busy flushing {} entries (attempt {}/{}), retrying in {}ms",
+                    batch.len(),
+                    attempt,
+                    FLUSH_BUSY_ATTEMPTS,
+                    backoff
+                );
+                tokio::time::sleep(Duration::from_millis(backoff)).await;
+                attempt += 1;
+            }
+            result => return result,
+        }
+    }
+}
+
+/// Whether `err` is SQLITE_BUSY or SQLITE_LOCKED, including their extended codes
+fn is_busy(err: &anyhow::Error) -> bool {
+    const SQLITE_BUSY: i32 = 5;
+    const SQLITE_LOCKED: i32 = 6;
+    err.chain().any(|cause| {
+        let Some(sqlx::Error::Database(db_err)) = cause.downcast_ref::<sqlx::Error>() else {
+            return false;
+        };
+        db_err
+            .code()
+            .and_then(|code| code.parse::<i32>().ok())
+            .is_some_and(|code| matches!(code & 0xff, SQLITE_BUSY | SQLITE_LOCKED))
+    })
+}
+
 /// Flush a batch of entries to SQLite in a single transaction
 ///
 /// # Arguments
@@ -1010,6 +4347,11 @@ async fn db_flush_worker(
 /// # Returns
 /// * `Ok(())` - All entries flushed successfully
 /// * `Err` - Database error during flush
+#[instrument(
+    name = "db.flush",
+    skip_all,
+    fields(db.system = "sqlite", db.table = "task_metrics", rows = batch.len())
+)]
 async fn flush_batch(batch: &[QueuedTaskResult], pool: &SqlitePool) -> Result<()> {
     if batch.is_empty() {
         return Ok(());
@@ -1019,7 +4361,17 @@ async fn flush_batch(batch: &[QueuedTaskResult], pool: &SqlitePool) -> Result<()
 
     // Use SmallVec for batch parameters - typical batch size is 200
     // SmallVec<[T; 64]> stores up to 64 items on the stack
-    type FlushRow = (String, String, String, String, String, i64, i64);
+    type FlushRow<'a> = (
+        String,
+        String,
+        String,
+        String,
+        String,
+        i64,
+        i64,
+        Option<i64>,
+        &'a TaskMetadata,
+    );
     let mut rows: SmallVec<[FlushRow; 64]> = SmallVec::new();
 
     for entry in batch {
@@ -1035,6 +4387,8 @@ async fn flush_batch(batch: &[QueuedTaskResult], pool: &SqlitePool) -> Result<()
             entry.message.clone(),
             entry.duration_ms as i64,
             entry.timestamp,
+            entry.chain_id.map(|id| id as i64),
+            &entry.metadata,
         ));
     }
 
@@ -1043,7 +4397,7 @@ async fn flush_batch(batch: &[QueuedTaskResult], pool: &SqlitePool) -> Result<()
 
     for row in &rows {
         sqlx::query(
-            "INSERT INTO task_metrics (worker_id, wallet_address, task_name, status, message, duration_ms, timestamp) VALUES (?, ?, ?, ?, ?, ?, ?)"
+            "INSERT INTO task_metrics (worker_id, wallet_address, task_name, status, message, duration_ms, timestamp, chain_id, tx_hash, gas_used, block_number, contract_address, details, timeout_ms, host_partition, diagnostics) VALUES (?, ?, ?, ?, ?, ?, ?, ?, ?, ?, ?, ?, ?, ?, ?, ?)"
         )
         .bind(&row.0)
         .bind(&row.1)
@@ -1052,6 +4406,15 @@ async fn flush_batch(batch: &[QueuedTaskResult], pool: &SqlitePool) -> Result<()
         .bind(&row.4)
         .bind(row.5)
         .bind(row.6)
+        .bind(row.7)
+        .bind(&row.8.tx_hash)
+        .bind(row.8.gas_used.map(|gas| gas as i64))
+        .bind(row.8.block_number.map(|block| block as i64))
+        .bind(&row.8.contract_address)
+        .bind(&row.8.details)
+        .bind(row.8.timeout_ms.map(|ms| ms as i64))
+        .bind(&row.8.host_partition)
+        .bind(&row.8.diagnostics)
         .execute(&mut *tx)
         .await?;
     }
@@ -78,6 +78,20 @@ pub enum ConfigError {
 
     #[error("I/O error reading {path}: {msg}")]
     IoError { path: String, msg: String },
+
+    #[error("Unknown configuration key '{key}' at {location}{}", did_you_mean(.suggestion))]
+    UnknownKey {
+        key: String,
+        location: String,
+        suggestion: Option<String>,
+    },
+}
+
+fn did_you_mean(suggestion: &Option<String>) -> String {
+    match suggestion {
+        Some(s) => format!(" (did you mean '{}'?)", s),
+        None => String::new(),
+    }
 }
 
 /// Wallet and cryptographic operation errors
@@ -119,6 +133,9 @@ pub enum DatabaseError {
 
     #[error("Constraint violation: {constraint}")]
     ConstraintViolation { constraint: String },
+
+    #[error("Database encryption: {msg}")]
+    Encryption { msg: String },
 }
 
 /// Network and RPC-related errors
@@ -27,20 +27,36 @@ pub(crate) mod utils;
 // Selective exports - only public API types
 pub use config::{ChainConfig, ProxyConfig, SpamConfig, WalletSource};
 pub use database::{
-    AsyncDbConfig, DatabaseManager, DbMetrics, DbMetricsSnapshot, DexOrder, FallbackStrategy,
-    QueuedTaskResult, TaskMetricBatchItem,
+    AsyncDbConfig, BurstBlock, BurstResult, BurstRound, ChainSummary, ConsistencyCheck,
+    ConsistencySummary, DatabaseManager, DbMetrics, DbMetricsSnapshot, DexOrder, ExportFormat,
+    FallbackStrategy, JournalMode, LatencySample, LogCoverage, ProxyStats, ProxyUsage,
+    QueuedTaskResult, ReceiptLogRecord, ShardedDatabase, SpendRecord, SpillImportSummary,
+    SpillRecord, SynchronousLevel, TaskMetadata, TaskMetricBatchItem, TaskSummary,
 };
 pub use error::{ConfigError, CoreError, DatabaseError, NetworkError, SecurityError, WalletError};
 pub use metrics::{MetricsCollector, MetricsSnapshot};
 pub use security::SecurityUtils;
 pub use templates::{
-    ChainBuilder, ChainSpammer, EvmChainAdapter, GasEstimator, RpcProvider, SpammerConfig,
-    SpammerResult, TransactionSigner,
+    ChainBuilder, ChainSpammer, EvmAction, EvmChainAdapter, GasEstimator, NonceSource, RpcProvider,
+    SpammerConfig, SpammerResult, TransactionSigner,
+};
+pub use traits::{
+    Spammer as SpammerTrait, SpammerStats, Task, TaskResult, TokenAmount, Verification,
+    WalletLoader,
 };
-pub use traits::{Spammer as SpammerTrait, SpammerStats, Task, TaskResult, WalletLoader};
 
 // Utils are pub(crate) - only export specific public utilities
-pub use utils::{setup_logger, GasConfig, ProxyManager, WalletManager, WorkerRunner};
+pub use utils::{
+    ansi_supported, bump_fee, colors_enabled, database_key, non_interactive, paint,
+    password_sources_hint, setup_logger, setup_logger_with_levels, setup_logger_with_rotation,
+    setup_logger_with_tracing, shutdown_tracing, wallet_password, ArbiterConfig, BumpPolicy,
+    ChainProxies, ChainShare, FeeSample, FeeStrategy, Fees, GasConfig, GasManager, HostPartition,
+    LogLevels, LogRotation, Nonce2D, NonceKey, NonceManagement, NonceManager, NonceManagerConfig,
+    NonceReservation, NonceState, NonceStats, OtlpConfig, ProxyArbiter, ProxyManager, RampUp,
+    RequestId, RobustNonceManager, RotationPeriod, ScaleHandle, Tone, WalletEntry, WalletManager,
+    WalletOptions, WalletStream, WalletTags, WorkerRunner, DATABASE_KEY_ENV, DATABASE_KEY_FILE_ENV,
+    NON_INTERACTIVE_ENV, PASSWORD_ENV, PASSWORD_FILE_ENV,
+};
 
 // Export retry utilities for testing
 pub use utils::retry::{
@@ -1,14 +1,213 @@
+//! # Core Logic - Metrics
+//!
+//! Besides aggregate task and RPC stats, [`MetricsCollector`] keeps
+//! counters and histograms by name and labels, registered on first use:
+//!
+//! ```rust
+//! use core_logic::metrics::{MetricsCollector, TaskLabels};
+//! use std::time::Duration;
+//!
+//! let metrics = MetricsCollector::default();
+//! let labels = TaskLabels {
+//!     chain_id: Some(42431),
+//!     wallet_index: Some(7),
+//!     ..TaskLabels::task("03_send_token")
+//! };
+//! metrics.record_task_with(&labels, Duration::from_millis(120), true);
+//!
+//! let snapshot = metrics.snapshot();
+//! assert_eq!(snapshot.counter_total("tasks_total", &[("outcome", "success")]), 1);
+//! print!("{}", snapshot.to_prometheus());
+//! ```
+//!
+//! [`MetricsSnapshot`] is what every exporter reads: the JSON file export,
+//! the Prometheus text format and the spammers' status lines.
+
+use crate::traits::SpammerStats;
 use chrono::Utc;
 use serde::Serialize;
+use std::collections::BTreeMap;
+use std::fmt::Write as _;
+use std::ops::Deref;
 use std::sync::atomic::{AtomicU64, Ordering};
+use std::sync::{Arc, RwLock};
 use std::time::{Duration, Instant};
 
+/// Label names and values of one series
+pub type Labels = BTreeMap<String, String>;
+
+/// Upper bounds of the duration histogram buckets, in milliseconds
+pub const DURATION_BUCKETS_MS: &[u64] = &[
+    5, 10, 25, 50, 100, 250, 500, 1_000, 2_500, 5_000, 10_000, 30_000, 60_000,
+];
+
+/// Wallets per `wallet_bucket` label value
+pub const WALLET_BUCKET_SIZE: usize = 100;
+
+/// Monotonic counter of one series
+///
+/// Derefs to its [`AtomicU64`] for callers that count with `fetch_add`.
+#[derive(Debug, Default)]
+pub struct Counter(AtomicU64);
+
+impl Counter {
+    pub fn inc(&self) {
+        self.0.fetch_add(1, Ordering::Relaxed);
+    }
+
+    pub fn get(&self) -> u64 {
+        self.0.load(Ordering::Relaxed)
+    }
+}
+
+impl Deref for Counter {
+    type Target = AtomicU64;
+
+    fn deref(&self) -> &AtomicU64 {
+        &self.0
+    }
+}
+
+/// Distribution of one series over [`DURATION_BUCKETS_MS`]
+#[derive(Debug)]
+pub struct Histogram {
+    /// Per bucket, not cumulative; the last one past the highest bound
+    buckets: Vec<AtomicU64>,
+    sum: AtomicU64,
+    count: AtomicU64,
+}
+
+impl Default for Histogram {
+    fn default() -> Self {
+        Self {
+            buckets: (0..=DURATION_BUCKETS_MS.len())
+                .map(|_| AtomicU64::new(0))
+                .collect(),
+            sum: AtomicU64::new(0),
+            count: AtomicU64::new(0),
+        }
+    }
+}
+
+impl Histogram {
+    pub fn observe(&self, value: u64) {
+        let bucket = DURATION_BUCKETS_MS.partition_point(|bound| *bound < value);
+        self.buckets[bucket].fetch_add(1, Ordering::Relaxed);
+        self.sum.fetch_add(value, Ordering::Relaxed);
+        self.count.fetch_add(1, Ordering::Relaxed);
+    }
+
+    pub fn observe_duration(&self, duration: Duration) {
+        self.observe(duration.as_millis() as u64);
+    }
+
+    pub fn count(&self) -> u64 {
+        self.count.load(Ordering::Relaxed)
+    }
+
+    pub fn mean(&self) -> f64 {
+        match self.count() {
+            0 => 0.0,
+            count => self.sum.load(Ordering::Relaxed) as f64 / count as f64,
+        }
+    }
+
+    fn sample(&self, name: &str, labels: &Labels) -> HistogramSample {
+        let mut cumulative = 0;
+        let buckets = self
+            .buckets
+            .iter()
+            .enumerate()
+            .map(|(i, bucket)| {
+                cumulative += bucket.load(Ordering::Relaxed);
+                BucketSample {
+                    le: DURATION_BUCKETS_MS.get(i).copied(),
+                    count: cumulative,
+                }
+            })
+            .collect();
+        HistogramSample {
+            name: name.to_string(),
+            labels: labels.clone(),
+            count: self.count(),
+            sum: self.sum.load(Ordering::Relaxed),
+            buckets,
+        }
+    }
+}
+
+/// Dimensions of a finished task for [`MetricsCollector::record_task_with`]
+#[derive(Debug, Clone, Default)]
+pub struct TaskLabels<'a> {
+    pub chain_id: Option<u64>,
+    pub task: &'a str,
+    /// Counted per [`WALLET_BUCKET_SIZE`] wallets to keep the series few
+    pub wallet_index: Option<usize>,
+    /// `None` for a direct connection
+    pub proxy_index: Option<usize>,
+}
+
+impl<'a> TaskLabels<'a> {
+    /// Labels with only the task set
+    pub fn task(task: &'a str) -> Self {
+        Self {
+            task,
+            ..Self::default()
+        }
+    }
+}
+
+/// `wallet_bucket` label value of a wallet index, e.g. `100-199`
+pub fn wallet_bucket(index: usize) -> String {
+    let start = index / WALLET_BUCKET_SIZE * WALLET_BUCKET_SIZE;
+    format!("{}-{}", start, start + WALLET_BUCKET_SIZE - 1)
+}
+
+fn labels(pairs: &[(&str, &str)]) -> Labels {
+    pairs
+        .iter()
+        .map(|(name, value)| (name.to_string(), value.to_string()))
+        .collect()
+}
+
 #[derive(Debug, Clone, Serialize)]
 pub struct MetricsSnapshot {
     pub timestamp: String,
+    pub uptime_secs: u64,
     pub tasks: TaskMetrics,
     pub performance: PerformanceMetrics,
     pub rpc: RpcMetrics,
+    /// Labeled counters, sorted by name and labels
+    pub counters: Vec<CounterSample>,
+    /// Labeled histograms, sorted by name and labels
+    pub histograms: Vec<HistogramSample>,
+    /// Combined spammer stats, once a run has finished
+    #[serde(skip_serializing_if = "Option::is_none")]
+    pub run_summary: Option<SpammerStats>,
+}
+
+#[derive(Debug, Clone, PartialEq, Serialize)]
+pub struct CounterSample {
+    pub name: String,
+    pub labels: Labels,
+    pub value: u64,
+}
+
+#[derive(Debug, Clone, PartialEq, Serialize)]
+pub struct HistogramSample {
+    pub name: String,
+    pub labels: Labels,
+    pub count: u64,
+    pub sum: u64,
+    /// Cumulative counts per upper bound, the last one unbounded
+    pub buckets: Vec<BucketSample>,
+}
+
+#[derive(Debug, Clone, PartialEq, Serialize)]
+pub struct BucketSample {
+    /// Upper bound in milliseconds, `None` for +Inf
+    pub le: Option<u64>,
+    pub count: u64,
 }
 
 #[derive(Debug, Clone, Serialize)]
@@ -48,6 +247,9 @@ pub struct MetricsCollector {
     rpc_min_latency_ms: AtomicU64,
     rpc_max_latency_ms: AtomicU64,
     start_time: Instant,
+    counters: RwLock<BTreeMap<(String, Labels), Arc<Counter>>>,
+    histograms: RwLock<BTreeMap<(String, Labels), Arc<Histogram>>>,
+    run_summary: RwLock<Option<SpammerStats>>,
 }
 
 impl Default for MetricsCollector {
@@ -64,6 +266,9 @@ impl Default for MetricsCollector {
             rpc_min_latency_ms: AtomicU64::new(u64::MAX),
             rpc_max_latency_ms: AtomicU64::new(0),
             start_time: Instant::now(),
+            counters: RwLock::default(),
+            histograms: RwLock::default(),
+            run_summary: RwLock::default(),
         }
     }
 }
@@ -74,7 +279,47 @@ impl MetricsCollector {
         INSTANCE.get_or_init(|| MetricsCollector::default())
     }
 
-    pub fn record_task(&self, _name: &str, duration: Duration, success: bool) {
+    /// The counter `name` with `labels`, registered on first use
+    pub fn counter(&self, name: &str, labels: &[(&str, &str)]) -> Arc<Counter> {
+        series(&self.counters, name, labels)
+    }
+
+    /// The histogram `name` with `labels`, registered on first use
+    pub fn histogram(&self, name: &str, labels: &[(&str, &str)]) -> Arc<Histogram> {
+        series(&self.histograms, name, labels)
+    }
+
+    pub fn record_task(&self, name: &str, duration: Duration, success: bool) {
+        self.record_task_with(&TaskLabels::task(name), duration, success);
+    }
+
+    /// Records a task in the aggregates, in `tasks_total` by chain, task,
+    /// wallet bucket, proxy and outcome, and in `task_duration_ms` by chain
+    /// and task
+    pub fn record_task_with(&self, labels: &TaskLabels, duration: Duration, success: bool) {
+        let chain = labels.chain_id.map(|id| id.to_string()).unwrap_or_default();
+        let wallet = labels.wallet_index.map(wallet_bucket).unwrap_or_default();
+        let proxy = labels
+            .proxy_index
+            .map_or_else(|| "direct".to_string(), |index| index.to_string());
+        let outcome = if success { "success" } else { "failure" };
+        self.counter(
+            "tasks_total",
+            &[
+                ("chain", &chain),
+                ("task", labels.task),
+                ("wallet_bucket", &wallet),
+                ("proxy", &proxy),
+                ("outcome", outcome),
+            ],
+        )
+        .inc();
+        self.histogram(
+            "task_duration_ms",
+            &[("chain", &chain), ("task", labels.task)],
+        )
+        .observe_duration(duration);
+
         self.tasks_total.fetch_add(1, Ordering::SeqCst);
         self.task_duration_sum_ms
             .fetch_add(duration.as_millis() as u64, Ordering::SeqCst);
@@ -103,6 +348,7 @@ impl MetricsCollector {
             .fetch_min(latency_ms, Ordering::SeqCst);
         self.rpc_max_latency_ms
             .fetch_max(latency_ms, Ordering::SeqCst);
+        self.histogram("rpc_latency_ms", &[]).observe(latency_ms);
     }
 
     pub fn snapshot(&self) -> MetricsSnapshot {
@@ -118,8 +364,28 @@ impl MetricsCollector {
 
         let total_success = self.tasks_success.load(Ordering::SeqCst);
 
+        let counters = self
+            .counters
+            .read()
+            .unwrap()
+            .iter()
+            .map(|((name, labels), counter)| CounterSample {
+                name: name.clone(),
+                labels: labels.clone(),
+                value: counter.get(),
+            })
+            .collect();
+        let histograms = self
+            .histograms
+            .read()
+            .unwrap()
+            .iter()
+            .map(|((name, labels), histogram)| histogram.sample(name, labels))
+            .collect();
+
         MetricsSnapshot {
             timestamp: Utc::now().to_rfc3339(),
+            uptime_secs: self.uptime().as_secs(),
             tasks: TaskMetrics {
                 total: total_tasks,
                 success: total_success,
@@ -154,9 +420,22 @@ impl MetricsCollector {
                 min_latency_ms: if min_rpc == u64::MAX { 0 } else { min_rpc },
                 max_latency_ms: max_rpc,
             },
+            counters,
+            histograms,
+            run_summary: self.run_summary.read().unwrap().clone(),
         }
     }
 
+    /// Keeps the stats of a finished run for later snapshots and exports
+    pub fn set_run_summary(&self, stats: SpammerStats) {
+        *self.run_summary.write().unwrap() = Some(stats);
+    }
+
+    /// [`snapshot`](Self::snapshot) in the Prometheus text format
+    pub fn to_prometheus(&self) -> String {
+        self.snapshot().to_prometheus()
+    }
+
     pub fn to_json(&self) -> String {
         let snapshot = self.snapshot();
         serde_json::to_string_pretty(&snapshot).unwrap_or_else(|_| "{}".to_string())
@@ -189,6 +468,102 @@ impl MetricsCollector {
     }
 }
 
+fn series<T: Default>(
+    registry: &RwLock<BTreeMap<(String, Labels), Arc<T>>>,
+    name: &str,
+    pairs: &[(&str, &str)],
+) -> Arc<T> {
+    let key = (name.to_string(), labels(pairs));
+    if let Some(found) = registry.read().unwrap().get(&key) {
+        return found.clone();
+    }
+    registry.write().unwrap().entry(key).or_default().clone()
+}
+
+impl MetricsSnapshot {
+    /// Sum of the counters `name` whose labels include `filter`
+    pub fn counter_total(&self, name: &str, filter: &[(&str, &str)]) -> u64 {
+        self.counters
+            .iter()
+            .filter(|sample| sample.name == name && matches(&sample.labels, filter))
+            .map(|sample| sample.value)
+            .sum()
+    }
+
+    /// Prometheus text exposition of the uptime and the labeled series
+    pub fn to_prometheus(&self) -> String {
+        let mut out = String::new();
+        let _ = writeln!(out, "# TYPE uptime_seconds gauge");
+        let _ = writeln!(out, "uptime_seconds {}", self.uptime_secs);
+
+        let mut last = None;
+        for sample in &self.counters {
+            if last != Some(&sample.name) {
+                let _ = writeln!(out, "# TYPE {} counter", sample.name);
+                last = Some(&sample.name);
+            }
+            let _ = writeln!(
+                out,
+                "{}{} {}",
+                sample.name,
+                render_labels(&sample.labels, None),
+                sample.value
+            );
+        }
+
+        last = None;
+        for sample in &self.histograms {
+            if last != Some(&sample.name) {
+                let _ = writeln!(out, "# TYPE {} histogram", sample.name);
+                last = Some(&sample.name);
+            }
+            for bucket in &sample.buckets {
+                let le = bucket
+                    .le
+                    .map_or_else(|| "+Inf".to_string(), |le| le.to_string());
+                let _ = writeln!(
+                    out,
+                    "{}_bucket{} {}",
+                    sample.name,
+                    render_labels(&sample.labels, Some(&le)),
+                    bucket.count
+                );
+            }
+            let labels = render_labels(&sample.labels, None);
+            let _ = writeln!(out, "{}_sum{} {}", sample.name, labels, sample.sum);
+            let _ = writeln!(out, "{}_count{} {}", sample.name, labels, sample.count);
+        }
+        out
+    }
+}
+
+fn matches(labels: &Labels, filter: &[(&str, &str)]) -> bool {
+    filter
+        .iter()
+        .all(|(name, value)| labels.get(*name).is_some_and(|found| found == value))
+}
+
+/// `{name="value",...}`, empty without labels
+fn render_labels(labels: &Labels, le: Option<&str>) -> String {
+    let pairs: Vec<String> = labels
+        .iter()
+        .map(|(name, value)| (name.as_str(), value.as_str()))
+        .chain(le.map(|le| ("le", le)))
+        .map(|(name, value)| {
+            let value = value
+                .replace('\\', "\\\\")
+                .replace('"', "\\\"")
+                .replace('\n', "\\n");
+            format!("{}=\"{}\"", name, value)
+        })
+        .collect();
+    if pairs.is_empty() {
+        String::new()
+    } else {
+        format!("{{{}}}", pairs.join(","))
+    }
+}
+
 #[cfg(test)]
 mod tests {
     use super::*;
@@ -219,4 +594,66 @@ mod tests {
         assert!(json.contains("tasks"));
         assert!(json.contains("performance"));
     }
+
+    #[test]
+    fn test_labeled_series() {
+        let metrics = MetricsCollector::default();
+        let labels = TaskLabels {
+            chain_id: Some(42431),
+            wallet_index: Some(150),
+            proxy_index: Some(3),
+            ..TaskLabels::task("t01")
+        };
+        metrics.record_task_with(&labels, Duration::from_millis(40), true);
+        metrics.record_task_with(&labels, Duration::from_millis(4_000), false);
+        metrics.record_task("t02", Duration::from_millis(10), true);
+
+        let snapshot = metrics.snapshot();
+        assert_eq!(snapshot.tasks.total, 3);
+        assert_eq!(snapshot.counter_total("tasks_total", &[]), 3);
+        assert_eq!(
+            snapshot.counter_total("tasks_total", &[("chain", "42431"), ("outcome", "failure")]),
+            1
+        );
+        assert_eq!(
+            snapshot.counter_total(
+                "tasks_total",
+                &[("wallet_bucket", "100-199"), ("proxy", "3")]
+            ),
+            2
+        );
+        assert_eq!(
+            snapshot.counter_total("tasks_total", &[("proxy", "direct")]),
+            1
+        );
+
+        let t01 = snapshot
+            .histograms
+            .iter()
+            .find(|sample| sample.labels.get("task").map(String::as_str) == Some("t01"))
+            .unwrap();
+        assert_eq!((t01.count, t01.sum), (2, 4_040));
+        // 40ms falls in the 50ms bucket, 4s in the 5s one
+        let at = |le| t01.buckets.iter().find(|b| b.le == le).unwrap().count;
+        assert_eq!(
+            (at(Some(25)), at(Some(50)), at(Some(5_000)), at(None)),
+            (0, 1, 2, 2)
+        );
+    }
+
+    #[test]
+    fn test_prometheus_text() {
+        let metrics = MetricsCollector::default();
+        metrics
+            .counter("db_queries_total", &[("db", "a\"b.db")])
+            .inc();
+        metrics.histogram("rpc_latency_ms", &[]).observe(7);
+
+        let text = metrics.to_prometheus();
+        assert!(text
+            .contains("# TYPE db_queries_total counter\ndb_queries_total{db=\"a\\\"b.db\"} 1\n"));
+        assert!(text.contains("rpc_latency_ms_bucket{le=\"10\"} 1\n"));
+        assert!(text.contains("rpc_latency_ms_bucket{le=\"+Inf\"} 1\n"));
+        assert!(text.contains("rpc_latency_ms_sum 7\nrpc_latency_ms_count 1\n"));
+    }
 }
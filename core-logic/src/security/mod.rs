@@ -4,34 +4,73 @@ use aes_gcm::{
     Nonce,
 };
 use anyhow::{Context, Result};
+use rand::RngCore;
 use scrypt;
 // use std::fs;
 use hex;
+use zeroize::Zeroizing;
 
 pub struct SecurityUtils;
 
+/// Hex-encoded parts of an encrypted wallet, as stored under `"encrypted"`
+#[derive(Debug, Clone, PartialEq, Eq)]
+pub struct EncryptedComponents {
+    pub ciphertext: String,
+    pub iv: String,
+    pub salt: String,
+    pub tag: String,
+}
+
 impl SecurityUtils {
+    fn derive_key(password: &str, salt: &[u8]) -> Result<Zeroizing<[u8; 32]>> {
+        // Derive Key using Scrypt (Node.js crypto.scryptSync defaults: N=16384, r=8, p=1)
+        // Rust scrypt Params: log_n (14 -> 16384), r (8), p (1)
+        let params = scrypt::Params::new(14, 8, 1, 32)
+            .map_err(|e| anyhow::anyhow!("Invalid scrypt params: {}", e))?;
+        let mut key = Zeroizing::new([0u8; 32]);
+        scrypt::scrypt(password.as_bytes(), salt, &params, key.as_mut())
+            .map_err(|e| anyhow::anyhow!("Scrypt failed: {}", e))?;
+        Ok(key)
+    }
+
+    /// Encrypts `plaintext` the way [`decrypt_components`](Self::decrypt_components) expects
+    pub fn encrypt_components(plaintext: &str, password: &str) -> Result<EncryptedComponents> {
+        let mut salt = [0u8; 16];
+        let mut iv = [0u8; 12];
+        rand::rngs::OsRng.fill_bytes(&mut salt);
+        rand::rngs::OsRng.fill_bytes(&mut iv);
+
+        let key = Self::derive_key(password, &salt)?;
+        let cipher = Aes256Gcm::new((&*key).into());
+        let mut sealed = cipher
+            .encrypt(Nonce::from_slice(&iv), plaintext.as_bytes())
+            .map_err(|e| anyhow::anyhow!("Encryption failed: {}", e))?;
+        // aes-gcm appends the 16-byte tag to the ciphertext
+        let tag = sealed.split_off(sealed.len() - 16);
+
+        Ok(EncryptedComponents {
+            ciphertext: hex::encode(sealed),
+            iv: hex::encode(iv),
+            salt: hex::encode(salt),
+            tag: hex::encode(tag),
+        })
+    }
+
     pub fn decrypt_components(
         ciphertext_hex: &str,
         iv_hex: &str,
         salt_hex: &str,
         tag_hex: &str,
         password: &str,
-    ) -> Result<String> {
+    ) -> Result<Zeroizing<String>> {
         let ciphertext = hex::decode(ciphertext_hex).context("Invalid ciphertext hex")?;
         let iv = hex::decode(iv_hex).context("Invalid IV hex")?;
         let salt = hex::decode(salt_hex).context("Invalid salt hex")?;
         let mut tag = hex::decode(tag_hex).context("Invalid tag hex")?;
 
-        // Derive Key using Scrypt (Node.js crypto.scryptSync defaults: N=16384, r=8, p=1)
-        // Rust scrypt Params: log_n (14 -> 16384), r (8), p (1)
-        let params = scrypt::Params::new(14, 8, 1, 32)
-            .map_err(|e| anyhow::anyhow!("Invalid scrypt params: {}", e))?;
-        let mut key = [0u8; 32];
-        scrypt::scrypt(password.as_bytes(), &salt, &params, &mut key)
-            .map_err(|e| anyhow::anyhow!("Scrypt failed: {}", e))?;
+        let key = Self::derive_key(password, &salt)?;
 
-        let cipher = Aes256Gcm::new(&key.into());
+        let cipher = Aes256Gcm::new((&*key).into());
         let nonce = Nonce::from_slice(&iv);
 
         let mut full_payload = ciphertext.clone();
@@ -41,11 +80,44 @@ impl SecurityUtils {
             .decrypt(nonce, full_payload.as_ref())
             .map_err(|e| anyhow::anyhow!("Decryption failed: {}", e))?;
 
-        let text = String::from_utf8(plaintext).context("Decrypted data is not valid UTF-8")?;
-        Ok(text)
+        // The plaintext holds private keys; a non-UTF-8 one is wiped on error too
+        let text = String::from_utf8(plaintext).map_err(|e| {
+            let mut bytes = e.into_bytes();
+            zeroize::Zeroize::zeroize(&mut bytes);
+            anyhow::anyhow!("Decrypted data is not valid UTF-8")
+        })?;
+        Ok(Zeroizing::new(text))
     }
     // Keeping old one for reference or other tools, but likely unused now
     pub fn decrypt_file(_path: &str, _password: &str) -> Result<String> {
         Err(anyhow::anyhow!("Use decrypt_components for JSON wallets"))
     }
 }
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    #[test]
+    fn test_encrypted_components_decrypt() {
+        let sealed =
+            SecurityUtils::encrypt_components("{\"evm_private_key\":\"0x01\"}", "pw").unwrap();
+        let plain = SecurityUtils::decrypt_components(
+            &sealed.ciphertext,
+            &sealed.iv,
+            &sealed.salt,
+            &sealed.tag,
+            "pw",
+        )
+        .unwrap();
+        assert_eq!(plain.as_str(), "{\"evm_private_key\":\"0x01\"}");
+        assert!(SecurityUtils::decrypt_components(
+            &sealed.ciphertext,
+            &sealed.iv,
+            &sealed.salt,
+            &sealed.tag,
+            "wrong",
+        )
+        .is_err());
+    }
+}
@@ -1,36 +1,188 @@
 //! # EVM Chain Adapter
 //!
-//! This module provides a reference implementation of the chain traits
-//! for EVM-compatible blockchains. Use this as a template when adding
-//! new EVM-based chains to the framework.
+//! Reference implementation of the chain traits for EVM-compatible
+//! blockchains, built on alloy. It signs locally, estimates gas and fees,
+//! hands out nonces from a local counter and waits for receipts, so a new
+//! EVM chain can be spammed from its RPC URL, chain id and an [`EvmAction`]
+//! without writing chain code.
+//!
+//! ```rust,no_run
+//! use core_logic::templates::{create_evm_adapter, ChainSpammer, EvmAction};
+//!
+//! # async fn example() -> Result<(), String> {
+//! let adapter = create_evm_adapter("https://sepolia.base.org", 84532, 5)
+//!     .with_signer("0x...")?
+//!     .with_action(EvmAction::SelfTransfer);
+//! let result = adapter.execute_task().await?;
+//! println!("{} {:?}", result.message, result.tx_hash);
+//! # Ok(())
+//! # }
+//! ```
 
 use super::*;
-use crate::utils::{GasConfig, RpcManager};
+use crate::config::ProxyConfig;
+use crate::utils::{
+    FeeSample, Fees, GasConfig, GasManager, NonceManagement, NonceManager, RpcManager,
+};
+use alloy::eips::eip2718::Encodable2718;
+use alloy::eips::BlockNumberOrTag;
+use alloy::network::{EthereumWallet, TransactionBuilder};
+use alloy::primitives::{Address, Bytes, Signature, TxHash, U256};
+use alloy::providers::{Provider, RootProvider};
+use alloy::rpc::client::RpcClient;
+use alloy::rpc::types::{Block, TransactionReceipt, TransactionRequest};
+use alloy::signers::local::PrivateKeySigner;
+use alloy::transports::http::{reqwest, Http};
 use async_trait::async_trait;
+use std::time::Instant;
 use tokio_util::sync::CancellationToken;
 use tracing::info;
 
+/// Headroom added on top of `eth_estimateGas`, in percent
+const GAS_LIMIT_BUFFER_PERCENT: u64 = 20;
+
+/// Transaction sent by each [`ChainSpammer::execute_task`] call
+#[derive(Debug, Clone, Default)]
+pub enum EvmAction {
+    /// Zero-value transfer to the sending wallet
+    #[default]
+    SelfTransfer,
+    Transfer {
+        to: Address,
+        value: U256,
+    },
+    Call {
+        to: Address,
+        data: Bytes,
+        value: U256,
+    },
+    /// Contract creation from init code
+    Deploy {
+        bytecode: Bytes,
+    },
+}
+
+impl EvmAction {
+    /// Name used for stats and metrics
+    pub fn name(&self) -> &'static str {
+        match self {
+            EvmAction::SelfTransfer => "self_transfer",
+            EvmAction::Transfer { .. } => "transfer",
+            EvmAction::Call { .. } => "call",
+            EvmAction::Deploy { .. } => "deploy",
+        }
+    }
+
+    fn request(&self, from: Address) -> TransactionRequest {
+        let request = TransactionRequest::default().with_from(from);
+        match self {
+            EvmAction::SelfTransfer => request.with_to(from).with_value(U256::ZERO),
+            EvmAction::Transfer { to, value } => request.with_to(*to).with_value(*value),
+            EvmAction::Call { to, data, value } => request
+                .with_to(*to)
+                .with_input(data.clone())
+                .with_value(*value),
+            EvmAction::Deploy { bytecode } => request.with_deploy_code(bytecode.clone()),
+        }
+    }
+
+    /// Gas limit used when estimation fails
+    fn fallback_gas_limit(&self, gas: &GasConfig) -> u64 {
+        match self {
+            EvmAction::SelfTransfer | EvmAction::Transfer { .. } => gas.limit_transfer(),
+            EvmAction::Call { .. } => gas.limit_counter_interact(),
+            EvmAction::Deploy { .. } => gas.limit_deploy(),
+        }
+    }
+}
+
 /// EVM-compatible chain adapter implementation
 #[derive(Debug)]
 pub struct EvmChainAdapter {
     config: SpammerConfig,
     rpc_manager: RpcManager,
-    gas_config: GasConfig,
+    gas: GasManager,
+    provider: Result<RootProvider, String>,
+    wallet: Option<EthereumWallet>,
+    /// Signer address, zero until [`with_signer`](Self::with_signer)
+    address: Address,
+    action: EvmAction,
+    nonces: NonceManager,
+    receipt_timeout: Duration,
+}
+
+fn connect(url: &str, proxy: Option<&ProxyConfig>) -> Result<RootProvider, String> {
+    let url = url
+        .parse()
+        .map_err(|e| format!("invalid RPC url {}: {}", url, e))?;
+    let mut builder = reqwest::Client::builder().timeout(Duration::from_secs(30));
+    if let Some(proxy) = proxy {
+        let mut upstream = reqwest::Proxy::all(&proxy.url).map_err(|e| e.to_string())?;
+        if let (Some(user), Some(pass)) = (&proxy.username, &proxy.password) {
+            upstream = upstream.basic_auth(user, pass);
+        }
+        builder = builder.proxy(upstream);
+    }
+    let client = builder.build().map_err(|e| e.to_string())?;
+    Ok(RootProvider::new(RpcClient::new(
+        Http::with_client(client, url),
+        false,
+    )))
 }
 
 impl EvmChainAdapter {
     /// Create a new EVM chain adapter
+    ///
+    /// Read-only calls work right away; sending needs
+    /// [`with_signer`](Self::with_signer).
     pub fn new(config: SpammerConfig, rpc_urls: Vec<String>) -> Self {
+        let url = rpc_urls
+            .first()
+            .map_or(config.rpc_url.as_str(), String::as_str);
         Self {
-            config: config.clone(),
+            provider: connect(url, None),
             rpc_manager: RpcManager::new(config.chain_id, &rpc_urls),
-            gas_config: GasConfig::new(),
+            config,
+            gas: GasManager::default(),
+            wallet: None,
+            address: Address::ZERO,
+            action: EvmAction::default(),
+            nonces: NonceManager::new(),
+            receipt_timeout: Duration::from_secs(60),
         }
     }
 
     /// Create with custom gas configuration
     pub fn with_gas_config(mut self, gas_config: GasConfig) -> Self {
-        self.gas_config = gas_config;
+        self.gas = GasManager::new(gas_config);
+        self
+    }
+
+    /// Signs with the hex `private_key`
+    pub fn with_signer(mut self, private_key: &str) -> Result<Self, String> {
+        let signer: PrivateKeySigner = private_key
+            .trim()
+            .parse()
+            .map_err(|e| format!("invalid private key: {}", e))?;
+        self.address = signer.address();
+        self.wallet = Some(EthereumWallet::from(signer));
+        Ok(self)
+    }
+
+    /// Sends all RPC requests through `proxy`
+    pub fn with_proxy(mut self, proxy: &ProxyConfig) -> Result<Self, String> {
+        self.provider = Ok(connect(self.rpc_url(), Some(proxy))?);
+        Ok(self)
+    }
+
+    pub fn with_action(mut self, action: EvmAction) -> Self {
+        self.action = action;
+        self
+    }
+
+    /// How long [`send`](Self::send) waits for a receipt
+    pub fn with_receipt_timeout(mut self, timeout: Duration) -> Self {
+        self.receipt_timeout = timeout;
         self
     }
 
@@ -41,7 +193,122 @@ impl EvmChainAdapter {
 
     /// Get the gas configuration
     pub fn gas_config(&self) -> &GasConfig {
-        &self.gas_config
+        self.gas.config()
+    }
+
+    pub fn gas_manager(&self) -> &GasManager {
+        &self.gas
+    }
+
+    pub fn action(&self) -> &EvmAction {
+        &self.action
+    }
+
+    pub fn provider(&self) -> Result<&RootProvider, String> {
+        self.provider.as_ref().map_err(Clone::clone)
+    }
+
+    fn rpc_url(&self) -> &str {
+        self.rpc_manager
+            .urls()
+            .first()
+            .copied()
+            .unwrap_or(&self.config.rpc_url)
+    }
+
+    fn signing_wallet(&self) -> Result<&EthereumWallet, String> {
+        self.wallet
+            .as_ref()
+            .ok_or_else(|| "no signer configured, see with_signer".to_string())
+    }
+
+    /// Fills gas limit and fees the same way for every transaction
+    ///
+    /// The limit is the node's estimate plus a buffer, or the configured
+    /// default for the action when estimation fails. Fees follow
+    /// `eth_feeHistory` capped at the configured max fee, falling back to a
+    /// legacy gas price on chains without EIP-1559.
+    async fn fill_gas(
+        &self,
+        mut request: TransactionRequest,
+    ) -> Result<TransactionRequest, String> {
+        let provider = self.provider()?;
+
+        if request.gas.is_none() {
+            let limit = match provider.estimate_gas(request.clone()).await {
+                Ok(estimate) => estimate + estimate * GAS_LIMIT_BUFFER_PERCENT / 100,
+                Err(e) => {
+                    tracing::debug!("Gas estimation failed, using default limit: {}", e);
+                    self.action.fallback_gas_limit(self.gas.config())
+                }
+            };
+            request.set_gas_limit(limit);
+        }
+
+        match self.gas.fees(self).await? {
+            Fees::Eip1559 {
+                max_fee,
+                priority_fee,
+            } => {
+                request.set_max_fee_per_gas(max_fee);
+                request.set_max_priority_fee_per_gas(priority_fee);
+            }
+            Fees::Legacy { gas_price } => request.set_gas_price(gas_price),
+        }
+        Ok(request)
+    }
+
+    /// Signs `request` with the next nonce, giving the EIP-2718 encoding
+    async fn sign(&self, request: TransactionRequest) -> Result<Vec<u8>, String> {
+        let wallet = self.signing_wallet()?;
+        let address = self.address;
+
+        let nonce = self.nonces.next_or_fetch(address, self).await?;
+        let request = request
+            .with_from(address)
+            .with_nonce(nonce)
+            .with_chain_id(self.config.chain_id);
+
+        match request.build(wallet).await {
+            Ok(envelope) => Ok(envelope.encoded_2718()),
+            Err(e) => {
+                self.nonces.reset(self.address).await;
+                Err(format!("failed to sign transaction: {}", e))
+            }
+        }
+    }
+
+    /// Broadcasts a signed transaction, resyncing the nonce when it fails
+    async fn broadcast(
+        &self,
+        raw: &[u8],
+    ) -> Result<alloy::providers::PendingTransactionBuilder<alloy::network::Ethereum>, String> {
+        let start = Instant::now();
+        match self.provider()?.send_raw_transaction(raw).await {
+            Ok(pending) => {
+                self.rpc_manager.record_success(self.rpc_url());
+                self.rpc_manager
+                    .record_latency(self.rpc_url(), start.elapsed().as_millis() as u64);
+                Ok(pending)
+            }
+            Err(e) => {
+                self.rpc_manager.record_failure(self.rpc_url());
+                self.nonces.reset(self.address).await;
+                Err(format!("failed to send transaction: {}", e))
+            }
+        }
+    }
+
+    /// Fills, signs and sends `request`, then waits for its receipt
+    pub async fn send(&self, request: TransactionRequest) -> Result<TransactionReceipt, String> {
+        let request = self.fill_gas(request).await?;
+        let raw = self.sign(request).await?;
+        self.broadcast(&raw)
+            .await?
+            .with_timeout(Some(self.receipt_timeout))
+            .get_receipt()
+            .await
+            .map_err(|e| format!("no receipt: {}", e))
     }
 }
 
@@ -56,12 +323,27 @@ impl ChainSpammer for EvmChainAdapter {
     }
 
     async fn execute_task(&self) -> Result<SpammerResult, String> {
-        // Placeholder implementation
-        // In a real implementation, this would execute spam transactions
+        self.signing_wallet()?;
+        let receipt = self.send(self.action.request(self.address)).await?;
+
+        let success = receipt.status();
+        let mut message = if success {
+            format!("{} confirmed", self.action.name())
+        } else {
+            format!("{} reverted", self.action.name())
+        };
+        if let Some(block) = receipt.block_number {
+            message.push_str(&format!(" in block {}", block));
+        }
+        if let Some(contract) = receipt.contract_address {
+            message.push_str(&format!(", contract {}", contract));
+        }
+
         Ok(SpammerResult {
-            success: true,
-            message: "Task executed".to_string(),
-            tx_hash: None,
+            success,
+            message,
+            tx_hash: Some(receipt.transaction_hash.to_string()),
+            gas_used: Some(receipt.gas_used),
         })
     }
 
@@ -76,12 +358,16 @@ impl ChainSpammer for EvmChainAdapter {
                 break;
             }
 
-            if let Err(e) = self.execute_task().await {
-                tracing::error!("Task failed: {}", e);
+            match self.execute_task().await {
+                Ok(result) => info!("{} {:?}", result.message, result.tx_hash),
+                Err(e) => tracing::error!("Task failed: {}", e),
             }
 
             let delay_ms = 1000 / self.config.target_tps.max(1) as u64;
-            tokio::time::sleep(Duration::from_millis(delay_ms)).await;
+            tokio::select! {
+                _ = token.cancelled() => break,
+                _ = tokio::time::sleep(Duration::from_millis(delay_ms)) => {}
+            }
         }
 
         Ok(())
@@ -92,6 +378,140 @@ impl ChainSpammer for EvmChainAdapter {
     }
 }
 
+#[async_trait]
+impl GasEstimator for EvmChainAdapter {
+    type Error = String;
+
+    async fn estimate_gas(&self, data: &[u8], to: Option<&str>) -> Result<u64, Self::Error> {
+        let mut request = TransactionRequest::default()
+            .with_from(self.address)
+            .with_input(data.to_vec());
+        if let Some(to) = to {
+            request.set_to(parse_address(to)?);
+        }
+        self.provider()?
+            .estimate_gas(request)
+            .await
+            .map_err(|e| e.to_string())
+    }
+
+    async fn get_gas_price(&self) -> Result<u64, Self::Error> {
+        let price = self
+            .provider()?
+            .get_gas_price()
+            .await
+            .map_err(|e| e.to_string())?;
+        u64::try_from(price).map_err(|e| e.to_string())
+    }
+
+    async fn fee_sample(&self, percentile: f64) -> Result<FeeSample, Self::Error> {
+        FeeSample::fetch(self.provider()?, percentile).await
+    }
+}
+
+#[async_trait]
+impl NonceSource for EvmChainAdapter {
+    type Error = String;
+
+    async fn next_nonce(&self, address: Address) -> Result<u64, Self::Error> {
+        self.provider()?
+            .get_transaction_count(address)
+            .pending()
+            .await
+            .map_err(|e| e.to_string())
+    }
+}
+
+#[async_trait]
+impl TransactionSigner for EvmChainAdapter {
+    type Address = Address;
+    type TransactionHash = TxHash;
+    type Signature = Signature;
+
+    fn address(&self) -> &Self::Address {
+        &self.address
+    }
+
+    /// An empty `to` creates a contract from `data`
+    async fn sign_transaction(
+        &self,
+        to: &str,
+        data: &[u8],
+        value: u64,
+        gas_limit: u64,
+    ) -> Result<Vec<u8>, String> {
+        let mut request = TransactionRequest::default()
+            .with_value(U256::from(value))
+            .with_gas_limit(gas_limit);
+        request = if to.is_empty() {
+            request.with_deploy_code(data.to_vec())
+        } else {
+            request
+                .with_to(parse_address(to)?)
+                .with_input(data.to_vec())
+        };
+        let request = self.fill_gas(request).await?;
+        self.sign(request).await
+    }
+
+    async fn send_raw_transaction(
+        &self,
+        signed_tx: &[u8],
+    ) -> Result<Self::TransactionHash, String> {
+        Ok(*self.broadcast(signed_tx).await?.tx_hash())
+    }
+}
+
+#[async_trait]
+impl RpcProvider for EvmChainAdapter {
+    type Block = Block;
+    type Transaction = TransactionReceipt;
+
+    async fn get_latest_block_number(&self) -> Result<u64, String> {
+        self.provider()?
+            .get_block_number()
+            .await
+            .map_err(|e| e.to_string())
+    }
+
+    async fn get_block_by_number(&self, number: u64) -> Result<Option<Self::Block>, String> {
+        self.provider()?
+            .get_block_by_number(BlockNumberOrTag::Number(number))
+            .await
+            .map_err(|e| e.to_string())
+    }
+
+    async fn get_transaction_receipt(
+        &self,
+        tx_hash: &str,
+    ) -> Result<Option<Self::Transaction>, String> {
+        let hash: TxHash = tx_hash
+            .parse()
+            .map_err(|e| format!("invalid transaction hash {}: {}", tx_hash, e))?;
+        self.provider()?
+            .get_transaction_receipt(hash)
+            .await
+            .map_err(|e| e.to_string())
+    }
+
+    async fn call(&self, to: &str, data: &[u8]) -> Result<Vec<u8>, String> {
+        let request = TransactionRequest::default()
+            .with_to(parse_address(to)?)
+            .with_input(data.to_vec());
+        self.provider()?
+            .call(request)
+            .await
+            .map(|output| output.to_vec())
+            .map_err(|e| e.to_string())
+    }
+}
+
+fn parse_address(address: &str) -> Result<Address, String> {
+    address
+        .parse()
+        .map_err(|e| format!("invalid address {}: {}", address, e))
+}
+
 /// Helper function to create an EVM adapter with default settings
 pub fn create_evm_adapter(rpc_url: &str, chain_id: u64, tps: u32) -> EvmChainAdapter {
     let config = SpammerConfig {
@@ -113,4 +533,20 @@ mod tests {
         assert_eq!(adapter.config.chain_id, 1);
         assert_eq!(adapter.config.target_tps, 10);
     }
+
+    #[test]
+    fn test_signer_and_action() {
+        // First default anvil account
+        let adapter = create_evm_adapter("https://rpc.example.com", 1, 10)
+            .with_signer("0xac0974bec39a17e36ba4a6b4d238ff944bacb478cbed5efcae784d7bf4f2ff80")
+            .unwrap();
+        let expected: Address = "0xf39Fd6e51aad88F6F4ce6aB8827279cffFb92266"
+            .parse()
+            .unwrap();
+        assert_eq!(*TransactionSigner::address(&adapter), expected);
+
+        let request = adapter.action().request(expected);
+        assert_eq!(request.to, Some(expected.into()));
+        assert!(create_evm_adapter("not a url", 1, 10).provider().is_err());
+    }
 }
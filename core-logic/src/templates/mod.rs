@@ -4,6 +4,8 @@
 //! integrations. The core-logic utilities are designed to work with any
 //! chain implementation that adheres to these traits.
 
+use crate::utils::FeeSample;
+use alloy::primitives::Address;
 use async_trait::async_trait;
 use std::time::Duration;
 use tokio_util::sync::CancellationToken;
@@ -17,6 +19,7 @@ pub struct SpammerResult {
     pub success: bool,
     pub message: String,
     pub tx_hash: Option<String>,
+    pub gas_used: Option<u64>,
 }
 
 /// Configuration for a spammer
@@ -43,6 +46,28 @@ pub trait GasEstimator: Send + Sync {
     type Error;
     async fn estimate_gas(&self, data: &[u8], to: Option<&str>) -> Result<u64, Self::Error>;
     async fn get_gas_price(&self) -> Result<u64, Self::Error>;
+
+    /// Network fees for a [`GasManager`](crate::GasManager), with the tip
+    /// taken at `percentile` of recent blocks
+    ///
+    /// The default prices from [`get_gas_price`](Self::get_gas_price) alone;
+    /// chains with EIP-1559 fee history override it.
+    async fn fee_sample(&self, _percentile: f64) -> Result<FeeSample, Self::Error> {
+        Ok(FeeSample::legacy(self.get_gas_price().await? as u128))
+    }
+}
+
+/// Trait for reading nonces - chains implement this for each kind of nonce
+/// sequence they have
+///
+/// Nonce managers call it when a sequence is not cached yet; see
+/// [`NonceManagement::next_or_fetch`](crate::NonceManagement::next_or_fetch).
+#[async_trait]
+pub trait NonceSource<K = Address>: Send + Sync {
+    type Error;
+
+    /// The next usable nonce of `key`, e.g. the pending transaction count
+    async fn next_nonce(&self, key: K) -> Result<u64, Self::Error>;
 }
 
 /// Trait for transaction signing - chains implement this based on their signature scheme
@@ -116,11 +141,14 @@ impl ChainBuilder {
     }
 
     pub fn build_evm(self) -> Result<EvmChainAdapter, String> {
-        let config = self.spammer_config.unwrap_or(SpammerConfig {
-            rpc_url: self.rpc_urls.first().cloned().unwrap_or_default(),
+        let mut config = self.spammer_config.unwrap_or(SpammerConfig {
+            rpc_url: String::new(),
             chain_id: self.chain_id.unwrap_or(1),
             target_tps: 10,
         });
+        if config.rpc_url.is_empty() {
+            config.rpc_url = self.rpc_urls.first().cloned().unwrap_or_default();
+        }
 
         Ok(EvmChainAdapter::new(config, self.rpc_urls))
     }
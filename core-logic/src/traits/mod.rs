@@ -1,11 +1,148 @@
 use crate::config::SpamConfig;
 use anyhow::Result;
 use async_trait::async_trait;
+use serde::{Deserialize, Serialize};
+use std::collections::BTreeMap;
+use std::fmt::Write as _;
+use std::time::Duration;
 
-#[derive(Debug, Default, Clone)]
+/// What a spammer did until it stopped, summed over spammers by
+/// [`WorkerRunner`](crate::WorkerRunner)
+#[derive(Debug, Default, Clone, PartialEq, Serialize)]
 pub struct SpammerStats {
     pub success: u64,
     pub failed: u64,
+    /// Gas of the successful runs that reported it
+    pub gas_used: u64,
+    /// Failures whose error mentions the nonce
+    pub nonce_errors: u64,
+    /// Summed task durations
+    pub total_latency_ms: u64,
+    /// Wall time of the run
+    pub run_duration_ms: u64,
+    pub tasks: BTreeMap<String, TaskStats>,
+}
+
+/// Outcomes of one task within [`SpammerStats`]
+#[derive(Debug, Default, Clone, PartialEq, Serialize)]
+pub struct TaskStats {
+    pub success: u64,
+    pub failed: u64,
+    pub gas_used: u64,
+    pub total_latency_ms: u64,
+}
+
+impl TaskStats {
+    pub fn total(&self) -> u64 {
+        self.success + self.failed
+    }
+
+    pub fn avg_latency_ms(&self) -> f64 {
+        average(self.total_latency_ms, self.total())
+    }
+}
+
+impl SpammerStats {
+    pub fn record_success(&mut self, task: &str, duration: Duration, gas_used: Option<u64>) {
+        let latency = duration.as_millis() as u64;
+        let gas = gas_used.unwrap_or(0);
+        self.success += 1;
+        self.gas_used += gas;
+        self.total_latency_ms += latency;
+
+        let entry = self.tasks.entry(task.to_string()).or_default();
+        entry.success += 1;
+        entry.gas_used += gas;
+        entry.total_latency_ms += latency;
+    }
+
+    pub fn record_failure(&mut self, task: &str, duration: Duration, error: &str) {
+        let latency = duration.as_millis() as u64;
+        self.failed += 1;
+        self.total_latency_ms += latency;
+        if error.to_lowercase().contains("nonce") {
+            self.nonce_errors += 1;
+        }
+
+        let entry = self.tasks.entry(task.to_string()).or_default();
+        entry.failed += 1;
+        entry.total_latency_ms += latency;
+    }
+
+    /// Adds the counts of `other`; the run duration is the longer one
+    pub fn merge(&mut self, other: &SpammerStats) {
+        self.success += other.success;
+        self.failed += other.failed;
+        self.gas_used += other.gas_used;
+        self.nonce_errors += other.nonce_errors;
+        self.total_latency_ms += other.total_latency_ms;
+        self.run_duration_ms = self.run_duration_ms.max(other.run_duration_ms);
+        for (task, stats) in &other.tasks {
+            let entry = self.tasks.entry(task.clone()).or_default();
+            entry.success += stats.success;
+            entry.failed += stats.failed;
+            entry.gas_used += stats.gas_used;
+            entry.total_latency_ms += stats.total_latency_ms;
+        }
+    }
+
+    pub fn total(&self) -> u64 {
+        self.success + self.failed
+    }
+
+    /// Percentage of successful runs, 0 before any ran
+    pub fn success_rate(&self) -> f64 {
+        average(self.success * 100, self.total())
+    }
+
+    pub fn avg_latency_ms(&self) -> f64 {
+        average(self.total_latency_ms, self.total())
+    }
+
+    /// End-of-run summary: totals, then one line per task by run count
+    pub fn report(&self) -> String {
+        let mut out = String::new();
+        let _ = writeln!(
+            out,
+            "Run time {:.1}s | {} tasks | {} success | {} failed | {:.2}% success",
+            self.run_duration_ms as f64 / 1000.0,
+            self.total(),
+            self.success,
+            self.failed,
+            self.success_rate()
+        );
+        let _ = write!(
+            out,
+            "Gas used {} | avg latency {:.0}ms | {} nonce errors",
+            self.gas_used,
+            self.avg_latency_ms(),
+            self.nonce_errors
+        );
+
+        let mut tasks: Vec<_> = self.tasks.iter().collect();
+        tasks.sort_by(|a, b| b.1.total().cmp(&a.1.total()).then(a.0.cmp(b.0)));
+        let width = tasks.iter().map(|(name, _)| name.len()).max().unwrap_or(0);
+        for (name, stats) in tasks {
+            let _ = write!(
+                out,
+                "\n  {:<width$}  {:>6} ok  {:>6} failed  {:>7.0}ms avg  {:>12} gas",
+                name,
+                stats.success,
+                stats.failed,
+                stats.avg_latency_ms(),
+                stats.gas_used,
+            );
+        }
+        out
+    }
+}
+
+fn average(sum: u64, count: u64) -> f64 {
+    if count == 0 {
+        0.0
+    } else {
+        sum as f64 / count as f64
+    }
 }
 
 #[async_trait]
@@ -25,11 +162,91 @@ pub trait Spammer: Send + Sync {
     async fn stop(&self) -> Result<()>;
 }
 
-#[derive(Debug, Clone)]
+/// Outcome of a single task run
+///
+/// Only `success` and `message` are required; the remaining fields carry
+/// structured data from the receipt so it does not have to be parsed back
+/// out of the message. Build results with struct update syntax
+/// (`..Default::default()`) so new fields stay optional.
+#[derive(Debug, Clone, Default, PartialEq, Serialize, Deserialize)]
+#[serde(default)]
 pub struct TaskResult {
     pub success: bool,
     pub message: String,
     pub tx_hash: Option<String>,
+    pub gas_used: Option<u64>,
+    pub block_number: Option<u64>,
+    /// Contract deployed or created by the task
+    pub contract_address: Option<String>,
+    /// Tokens moved, minted or burned, in the order the task handled them
+    pub token_amounts: Vec<TokenAmount>,
+    /// Task-specific fields, a JSON object or `null`
+    pub details: serde_json::Value,
+    /// Checks of the on-chain state the task ran after its transactions
+    pub verifications: Vec<Verification>,
+}
+
+/// An amount of one token, in base units
+#[derive(Debug, Clone, PartialEq, Eq, Serialize, Deserialize)]
+pub struct TokenAmount {
+    pub token: String,
+    pub amount: String,
+}
+
+/// Outcome of one check that on-chain state matches what a task expected
+#[derive(Debug, Clone, PartialEq, Eq, Serialize, Deserialize)]
+pub struct Verification {
+    /// Kind of check, such as `balance_delta` or `owner_of`
+    pub check: String,
+    /// Contract, account or slot the check read
+    pub target: String,
+    pub expected: String,
+    /// Value found, or the error that prevented reading it
+    pub actual: String,
+    pub passed: bool,
+}
+
+impl TaskResult {
+    /// Records gas used and block number from the receipt
+    pub fn with_receipt(mut self, gas_used: u64, block_number: Option<u64>) -> Self {
+        self.gas_used = Some(gas_used);
+        self.block_number = block_number;
+        self
+    }
+
+    pub fn with_contract(mut self, address: impl ToString) -> Self {
+        self.contract_address = Some(address.to_string());
+        self
+    }
+
+    pub fn with_token_amount(mut self, token: impl ToString, amount: impl ToString) -> Self {
+        self.token_amounts.push(TokenAmount {
+            token: token.to_string(),
+            amount: amount.to_string(),
+        });
+        self
+    }
+
+    pub fn with_verification(mut self, verification: Verification) -> Self {
+        self.verifications.push(verification);
+        self
+    }
+
+    /// Whether every check passed, `None` when the task ran none
+    pub fn verified(&self) -> Option<bool> {
+        (!self.verifications.is_empty()).then(|| self.verifications.iter().all(|v| v.passed))
+    }
+
+    /// Adds `key` to `details`, turning it into an object if needed
+    pub fn with_detail(mut self, key: &str, value: impl Into<serde_json::Value>) -> Self {
+        if !self.details.is_object() {
+            self.details = serde_json::Value::Object(Default::default());
+        }
+        if let Some(map) = self.details.as_object_mut() {
+            map.insert(key.to_string(), value.into());
+        }
+        self
+    }
 }
 
 #[async_trait]
@@ -48,3 +265,36 @@ pub trait WalletLoader: Send + Sync {
     /// Load wallets from a source (encrypted file, etc.)
     async fn load_wallets(&self) -> Result<Vec<Self::Wallet>>;
 }
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    #[test]
+    fn test_stats_merge_and_report() {
+        let mut first = SpammerStats::default();
+        first.record_success("t01_deploy", Duration::from_millis(300), Some(21_000));
+        first.record_failure("t01_deploy", Duration::from_millis(100), "Nonce too low");
+        first.run_duration_ms = 5_000;
+
+        let mut second = SpammerStats::default();
+        second.record_success("t02_transfer", Duration::from_millis(200), None);
+        second.record_failure("t02_transfer", Duration::from_millis(200), "reverted");
+        second.run_duration_ms = 8_000;
+
+        first.merge(&second);
+        assert_eq!((first.success, first.failed, first.nonce_errors), (2, 2, 1));
+        assert_eq!(first.gas_used, 21_000);
+        assert_eq!(first.run_duration_ms, 8_000);
+        assert_eq!(first.avg_latency_ms(), 200.0);
+        assert_eq!(first.success_rate(), 50.0);
+        assert_eq!(first.tasks["t01_deploy"].avg_latency_ms(), 200.0);
+
+        let report = first.report();
+        assert!(
+            report.starts_with("Run time 8.0s | 4 tasks | 2 success | 2 failed | 50.00% success")
+        );
+        assert!(report.contains("Gas used 21000 | avg latency 200ms | 1 nonce errors"));
+        assert_eq!(report.lines().count(), 4);
+    }
+}
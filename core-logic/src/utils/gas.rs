@@ -28,12 +28,49 @@ impl Default for StandardGasLimits {
     }
 }
 
+/// How transactions are priced
+#[derive(Debug, Clone, Copy, Default, PartialEq, Eq, Deserialize)]
+#[serde(rename_all = "lowercase")]
+pub enum FeeStrategy {
+    /// Type 0 transactions with a single `gasPrice`
+    Legacy,
+    /// Type 2 transactions with a max fee and a priority fee, priced as
+    /// legacy when the node reports no base fee
+    #[default]
+    Eip1559,
+}
+
+/// How far the fees of a stuck transaction may be raised to replace it
+#[derive(Debug, Clone, Copy)]
+pub struct BumpPolicy {
+    /// Raise per bump, in percent. Nodes drop replacements raising the fees
+    /// by less than 10%
+    pub percent: u64,
+    /// Bumps allowed before the transaction is given up on
+    pub max_bumps: u32,
+}
+
+impl Default for BumpPolicy {
+    fn default() -> Self {
+        Self {
+            percent: 10,
+            max_bumps: 3,
+        }
+    }
+}
+
 /// Configuration for gas management
 #[derive(Debug, Clone)]
 pub struct GasConfig {
     pub max_gwei: f64,
     pub priority_gwei: f64,
     pub limits: StandardGasLimits,
+    pub strategy: FeeStrategy,
+    /// Fee-history percentile the priority fee is taken from
+    pub priority_percentile: f64,
+    /// Room left above the base fee in the max fee, in percent of it
+    pub base_fee_headroom: u64,
+    pub bump: BumpPolicy,
 }
 
 impl Default for GasConfig {
@@ -42,6 +79,10 @@ impl Default for GasConfig {
             max_gwei: 2.5,      // 2.5 Gwei
             priority_gwei: 1.5, // 1.5 Gwei
             limits: StandardGasLimits::default(),
+            strategy: FeeStrategy::default(),
+            priority_percentile: 50.0,
+            base_fee_headroom: 100, // Survives ~6 full blocks of base fee growth
+            bump: BumpPolicy::default(),
         }
     }
 }
@@ -61,6 +102,26 @@ impl GasConfig {
         self
     }
 
+    pub fn with_strategy(mut self, strategy: FeeStrategy) -> Self {
+        self.strategy = strategy;
+        self
+    }
+
+    pub fn with_priority_percentile(mut self, percentile: f64) -> Self {
+        self.priority_percentile = percentile;
+        self
+    }
+
+    pub fn with_base_fee_headroom(mut self, percent: u64) -> Self {
+        self.base_fee_headroom = percent;
+        self
+    }
+
+    pub fn with_bump(mut self, bump: BumpPolicy) -> Self {
+        self.bump = bump;
+        self
+    }
+
     pub fn max_gwei(&self) -> f64 {
         self.max_gwei
     }
@@ -84,6 +145,22 @@ impl GasConfig {
     pub fn limit_send_meme(&self) -> u64 {
         self.limits.send_meme
     }
+
+    pub fn strategy(&self) -> FeeStrategy {
+        self.strategy
+    }
+
+    pub fn priority_percentile(&self) -> f64 {
+        self.priority_percentile
+    }
+
+    pub fn base_fee_headroom(&self) -> u64 {
+        self.base_fee_headroom
+    }
+
+    pub fn bump(&self) -> BumpPolicy {
+        self.bump
+    }
 }
 
 /// Convert gwei to wei as u64
@@ -100,10 +177,16 @@ pub struct GasConfigToml {
     pub limit_transfer: Option<u64>,
     pub limit_counter_interact: Option<u64>,
     pub limit_send_meme: Option<u64>,
+    pub strategy: Option<FeeStrategy>,
+    pub priority_percentile: Option<f64>,
+    pub base_fee_headroom: Option<u64>,
+    pub bump_percent: Option<u64>,
+    pub max_bumps: Option<u32>,
 }
 
 impl From<GasConfigToml> for GasConfig {
     fn from(toml: GasConfigToml) -> Self {
+        let defaults = GasConfig::default();
         Self {
             max_gwei: toml.max_gwei.unwrap_or(0.000000009),
             priority_gwei: toml.priority_gwei.unwrap_or(0.000000001),
@@ -113,6 +196,15 @@ impl From<GasConfigToml> for GasConfig {
                 counter_interact: toml.limit_counter_interact.unwrap_or(50_000),
                 send_meme: toml.limit_send_meme.unwrap_or(100_000),
             },
+            strategy: toml.strategy.unwrap_or(defaults.strategy),
+            priority_percentile: toml
+                .priority_percentile
+                .unwrap_or(defaults.priority_percentile),
+            base_fee_headroom: toml.base_fee_headroom.unwrap_or(defaults.base_fee_headroom),
+            bump: BumpPolicy {
+                percent: toml.bump_percent.unwrap_or(defaults.bump.percent),
+                max_bumps: toml.max_bumps.unwrap_or(defaults.bump.max_bumps),
+            },
         }
     }
 }
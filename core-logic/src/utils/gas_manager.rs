@@ -0,0 +1,282 @@
+//! # Core Logic - Gas Manager
+//!
+//! Fee policy shared by the EVM chains. A [`GasManager`] prices a
+//! [`FeeSample`] read from the node according to a [`GasConfig`]: legacy or
+//! EIP-1559 transactions, the fee-history percentile the tip comes from, the
+//! caps on both fees and how far a stuck transaction may be bumped. Chains
+//! supply the sample through [`GasEstimator::fee_sample`].
+
+use super::gas::{gwei_to_wei, FeeStrategy, GasConfig};
+use crate::templates::GasEstimator;
+use alloy::eips::BlockNumberOrTag;
+use alloy::providers::Provider;
+
+/// Network fees a [`GasManager`] prices from, in wei
+#[derive(Debug, Clone, Copy, Default, PartialEq, Eq)]
+pub struct FeeSample {
+    /// `eth_gasPrice`
+    pub gas_price: u128,
+    /// Base fee of the next block, `None` when the chain has none
+    pub base_fee: Option<u128>,
+    /// Tip paid at the configured percentile in the latest block
+    pub priority_fee: Option<u128>,
+}
+
+impl FeeSample {
+    pub fn legacy(gas_price: u128) -> Self {
+        Self {
+            gas_price,
+            ..Default::default()
+        }
+    }
+
+    /// Reads a sample with `eth_gasPrice` and `eth_feeHistory`
+    ///
+    /// Nodes without fee history give a legacy sample, as do pre-London
+    /// blocks, which fee history reports with a zero base fee.
+    pub async fn fetch<P: Provider + ?Sized>(
+        provider: &P,
+        percentile: f64,
+    ) -> Result<Self, String> {
+        let gas_price = provider.get_gas_price().await.map_err(|e| e.to_string())?;
+        let Ok(history) = provider
+            .get_fee_history(1, BlockNumberOrTag::Latest, &[percentile])
+            .await
+        else {
+            return Ok(Self::legacy(gas_price));
+        };
+
+        Ok(Self {
+            gas_price,
+            base_fee: history.next_block_base_fee().filter(|fee| *fee > 0),
+            priority_fee: history
+                .reward
+                .as_ref()
+                .and_then(|blocks| blocks.last())
+                .and_then(|rewards| rewards.first())
+                .copied(),
+        })
+    }
+}
+
+/// Fees to send a transaction with, in wei
+#[derive(Debug, Clone, Copy, PartialEq, Eq)]
+pub enum Fees {
+    Legacy { gas_price: u128 },
+    Eip1559 { max_fee: u128, priority_fee: u128 },
+}
+
+impl Fees {
+    /// Most the transaction pays per gas
+    pub fn max_fee(&self) -> u128 {
+        match self {
+            Fees::Legacy { gas_price } => *gas_price,
+            Fees::Eip1559 { max_fee, .. } => *max_fee,
+        }
+    }
+
+    /// Tip per gas; a legacy transaction tips whatever exceeds the base fee
+    pub fn priority_fee(&self) -> u128 {
+        match self {
+            Fees::Legacy { gas_price } => *gas_price,
+            Fees::Eip1559 { priority_fee, .. } => *priority_fee,
+        }
+    }
+
+    /// Upper bound on what `gas_limit` gas costs
+    pub fn max_cost(&self, gas_limit: u64) -> u128 {
+        self.max_fee().saturating_mul(gas_limit as u128)
+    }
+}
+
+/// Raises `value` by `percent`, rounding up so even 1 wei moves
+pub fn bump_fee(value: u128, percent: u64) -> u128 {
+    value.saturating_add(value.saturating_mul(percent as u128).div_ceil(100))
+}
+
+/// Prices transactions according to a [`GasConfig`]
+#[derive(Debug, Clone, Default)]
+pub struct GasManager {
+    config: GasConfig,
+}
+
+impl GasManager {
+    pub fn new(config: GasConfig) -> Self {
+        Self { config }
+    }
+
+    pub fn config(&self) -> &GasConfig {
+        &self.config
+    }
+
+    /// Cap on the max fee (or gas price), from `max_gwei`
+    pub fn max_fee_cap(&self) -> u128 {
+        gwei_to_wei(self.config.max_gwei()) as u128
+    }
+
+    /// Cap on the tip, from `priority_gwei`
+    pub fn priority_fee_cap(&self) -> u128 {
+        gwei_to_wei(self.config.priority_gwei()) as u128
+    }
+
+    /// The sampled tip, at least 1 wei, or the cap when there is none
+    pub fn priority_fee(&self, sample: &FeeSample) -> u128 {
+        let cap = self.priority_fee_cap();
+        sample.priority_fee.map_or(cap, |tip| tip.max(1).min(cap))
+    }
+
+    /// Base fee plus headroom plus `priority_fee`, capped
+    pub fn max_fee(&self, base_fee: u128, priority_fee: u128) -> u128 {
+        let headroom = base_fee.saturating_mul(self.config.base_fee_headroom() as u128) / 100;
+        base_fee
+            .saturating_add(headroom)
+            .saturating_add(priority_fee)
+            .min(self.max_fee_cap())
+    }
+
+    pub fn fees_from(&self, sample: &FeeSample) -> Fees {
+        match (self.config.strategy(), sample.base_fee) {
+            (FeeStrategy::Eip1559, Some(base_fee)) => {
+                let priority_fee = self.priority_fee(sample);
+                let max_fee = self.max_fee(base_fee, priority_fee);
+                Fees::Eip1559 {
+                    max_fee,
+                    priority_fee: priority_fee.min(max_fee),
+                }
+            }
+            _ => Fees::Legacy {
+                gas_price: sample.gas_price.min(self.max_fee_cap()),
+            },
+        }
+    }
+
+    /// Samples the network through `estimator` and prices the result
+    pub async fn fees<E: GasEstimator + ?Sized>(&self, estimator: &E) -> Result<Fees, E::Error> {
+        let sample = estimator
+            .fee_sample(self.config.priority_percentile())
+            .await?;
+        Ok(self.fees_from(&sample))
+    }
+
+    /// Fees for the `attempt`-th replacement of a transaction sent with `fees`
+    ///
+    /// `None` once the bumps are used up or the raised fee would pass the cap.
+    pub fn bump(&self, fees: &Fees, attempt: u32) -> Option<Fees> {
+        let policy = self.config.bump();
+        if attempt == 0 || attempt > policy.max_bumps {
+            return None;
+        }
+
+        let bumped = match *fees {
+            Fees::Legacy { gas_price } => Fees::Legacy {
+                gas_price: bump_fee(gas_price, policy.percent),
+            },
+            Fees::Eip1559 {
+                max_fee,
+                priority_fee,
+            } => Fees::Eip1559 {
+                max_fee: bump_fee(max_fee, policy.percent),
+                priority_fee: bump_fee(priority_fee, policy.percent),
+            },
+        };
+        (bumped.max_fee() <= self.max_fee_cap()).then_some(bumped)
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use crate::utils::gas::BumpPolicy;
+
+    fn manager() -> GasManager {
+        GasManager::new(GasConfig::new().with_max_fee(100.0).with_priority_fee(2.0))
+    }
+
+    #[test]
+    fn test_eip1559_fees_from_sample() {
+        let sample = FeeSample {
+            gas_price: 30_000_000_000,
+            base_fee: Some(20_000_000_000),
+            priority_fee: Some(1_000_000_000),
+        };
+        assert_eq!(
+            manager().fees_from(&sample),
+            Fees::Eip1559 {
+                max_fee: 41_000_000_000,
+                priority_fee: 1_000_000_000,
+            }
+        );
+
+        // The tip is capped at priority_gwei and the max fee at max_gwei
+        let busy = FeeSample {
+            gas_price: 300_000_000_000,
+            base_fee: Some(80_000_000_000),
+            priority_fee: Some(5_000_000_000),
+        };
+        assert_eq!(
+            manager().fees_from(&busy),
+            Fees::Eip1559 {
+                max_fee: 100_000_000_000,
+                priority_fee: 2_000_000_000,
+            }
+        );
+    }
+
+    #[test]
+    fn test_legacy_fees_from_sample() {
+        let sample = FeeSample::legacy(150_000_000_000);
+        assert_eq!(
+            manager().fees_from(&sample),
+            Fees::Legacy {
+                gas_price: 100_000_000_000
+            }
+        );
+
+        let legacy = GasManager::new(
+            GasConfig::new()
+                .with_max_fee(100.0)
+                .with_strategy(FeeStrategy::Legacy),
+        );
+        let sample = FeeSample {
+            gas_price: 30_000_000_000,
+            base_fee: Some(20_000_000_000),
+            priority_fee: None,
+        };
+        assert_eq!(
+            legacy.fees_from(&sample),
+            Fees::Legacy {
+                gas_price: 30_000_000_000
+            }
+        );
+    }
+
+    #[test]
+    fn test_bump_policy() {
+        let gm = GasManager::new(GasConfig::new().with_max_fee(0.000000009).with_bump(
+            BumpPolicy {
+                percent: 10,
+                max_bumps: 2,
+            },
+        ));
+        let fees = Fees::Eip1559 {
+            max_fee: 5,
+            priority_fee: 1,
+        };
+
+        assert_eq!(gm.bump(&fees, 0), None);
+        assert_eq!(
+            gm.bump(&fees, 1),
+            Some(Fees::Eip1559 {
+                max_fee: 6,
+                priority_fee: 2,
+            })
+        );
+        assert_eq!(gm.bump(&fees, 3), None);
+
+        // Nothing left under the 9 wei cap
+        let capped = Fees::Legacy { gas_price: 9 };
+        assert_eq!(gm.bump(&capped, 1), None);
+
+        assert_eq!(bump_fee(1_000_000_000, 20), 1_200_000_000);
+    }
+}
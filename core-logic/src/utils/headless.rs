@@ -0,0 +1,169 @@
+//! # Core Logic - Headless Startup
+//!
+//! Spammers run under systemd or in containers have no one to answer a
+//! prompt. [`non_interactive`] decides whether a binary may prompt at all,
+//! and [`wallet_password`] finds the wallet password without asking:
+//!
+//! 1. a password file passed by flag or config
+//! 2. the file named by `WALLET_PASSWORD_FILE` (Docker and systemd secrets)
+//! 3. the `WALLET_PASSWORD` variable
+//!
+//! A password file holds the password on its first line; a trailing newline
+//! is not part of it. [`database_key`] finds the SQLCipher key of the metrics
+//! database the same way, from `DATABASE_KEY_FILE` and `DATABASE_KEY`.
+
+use anyhow::{bail, Context, Result};
+use std::io::IsTerminal;
+use std::path::{Path, PathBuf};
+use zeroize::Zeroizing;
+
+/// Set to `1` or `true` to run without prompts
+pub const NON_INTERACTIVE_ENV: &str = "NON_INTERACTIVE";
+
+/// Wallet password
+pub const PASSWORD_ENV: &str = "WALLET_PASSWORD";
+
+/// Path of a file holding the wallet password
+pub const PASSWORD_FILE_ENV: &str = "WALLET_PASSWORD_FILE";
+
+/// Key of an encrypted (SQLCipher) database
+pub const DATABASE_KEY_ENV: &str = "DATABASE_KEY";
+
+/// Path of a file holding the database key
+pub const DATABASE_KEY_FILE_ENV: &str = "DATABASE_KEY_FILE";
+
+/// Whether prompts are off: by flag, by config, by `NON_INTERACTIVE` or
+/// because stdin is not a terminal
+pub fn non_interactive(flag: bool, configured: bool) -> bool {
+    flag || configured
+        || env_flag(std::env::var(NON_INTERACTIVE_ENV).ok())
+        || !std::io::stdin().is_terminal()
+}
+
+fn env_flag(value: Option<String>) -> bool {
+    value.is_some_and(|value| {
+        matches!(
+            value.trim().to_ascii_lowercase().as_str(),
+            "1" | "true" | "yes"
+        )
+    })
+}
+
+/// The wallet password from `file`, `WALLET_PASSWORD_FILE` or
+/// `WALLET_PASSWORD`, `None` when none of them is set
+///
+/// # Errors
+///
+/// Fails when the password file cannot be read or is empty.
+pub fn wallet_password(file: Option<&Path>) -> Result<Option<Zeroizing<String>>> {
+    password_from(file, |name| std::env::var(name).ok())
+}
+
+fn password_from(
+    file: Option<&Path>,
+    env: impl Fn(&str) -> Option<String>,
+) -> Result<Option<Zeroizing<String>>> {
+    secret_from(
+        "Wallet password",
+        file,
+        PASSWORD_FILE_ENV,
+        PASSWORD_ENV,
+        env,
+    )
+}
+
+/// The database key from `file`, `DATABASE_KEY_FILE` or `DATABASE_KEY`,
+/// `None` when none of them is set
+///
+/// # Errors
+///
+/// Fails when the key file cannot be read or is empty.
+pub fn database_key(file: Option<&Path>) -> Result<Option<Zeroizing<String>>> {
+    secret_from(
+        "Database key",
+        file,
+        DATABASE_KEY_FILE_ENV,
+        DATABASE_KEY_ENV,
+        |name| std::env::var(name).ok(),
+    )
+}
+
+fn secret_from(
+    what: &str,
+    file: Option<&Path>,
+    file_var: &str,
+    var: &str,
+    env: impl Fn(&str) -> Option<String>,
+) -> Result<Option<Zeroizing<String>>> {
+    let file = file
+        .map(Path::to_path_buf)
+        .or_else(|| env(file_var).map(PathBuf::from));
+    if let Some(path) = file {
+        let content = Zeroizing::new(std::fs::read_to_string(&path).with_context(|| {
+            format!(
+                "Failed to read {} file {}",
+                what.to_lowercase(),
+                path.display()
+            )
+        })?);
+        let secret = content.lines().next().unwrap_or_default();
+        if secret.is_empty() {
+            bail!("{} file {} is empty", what, path.display());
+        }
+        return Ok(Some(Zeroizing::new(secret.to_string())));
+    }
+    Ok(env(var).map(Zeroizing::new))
+}
+
+/// Where a headless run looks for the password, for error messages
+pub fn password_sources_hint() -> String {
+    format!(
+        "set {}, point {} or --password-file at a file holding it",
+        PASSWORD_ENV, PASSWORD_FILE_ENV
+    )
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    #[test]
+    fn test_password_precedence() {
+        let path = std::env::temp_dir().join(format!("core-pw-{}", std::process::id()));
+        std::fs::write(&path, "from-file\r\nignored\n").unwrap();
+        let file_env = path.display().to_string();
+
+        let only_var = |name: &str| (name == PASSWORD_ENV).then(|| "from-var".to_string());
+        let both = |name: &str| match name {
+            PASSWORD_ENV => Some("from-var".to_string()),
+            _ => Some(file_env.clone()),
+        };
+
+        let password = |file, env: &dyn Fn(&str) -> Option<String>| {
+            password_from(file, env).unwrap().map(|p| p.to_string())
+        };
+        assert_eq!(password(None, &|_| None), None);
+        assert_eq!(password(None, &only_var).as_deref(), Some("from-var"));
+        assert_eq!(password(None, &both).as_deref(), Some("from-file"));
+        assert_eq!(
+            password(Some(&path), &only_var).as_deref(),
+            Some("from-file")
+        );
+
+        std::fs::write(&path, "\n").unwrap();
+        assert!(password_from(Some(&path), |_| None).is_err());
+        std::fs::remove_file(&path).unwrap();
+        assert!(password_from(Some(&path), |_| None).is_err());
+    }
+
+    #[test]
+    fn test_env_flag_values() {
+        for value in ["1", "true", "YES "] {
+            assert!(env_flag(Some(value.to_string())));
+        }
+        for value in ["0", "false", ""] {
+            assert!(!env_flag(Some(value.to_string())));
+        }
+        assert!(!env_flag(None));
+    }
+}
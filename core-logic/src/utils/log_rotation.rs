@@ -0,0 +1,235 @@
+//! # Core Logic - Log Rotation
+//!
+//! The file log starts a new file every period (hourly by default) and when
+//! the current file outgrows `max_bytes`, then deletes the oldest files past
+//! `max_files`. Names follow the period in UTC so they sort chronologically:
+//!
+//! ```text
+//! logs/app.2026-10-18-13
+//! logs/app.2026-10-18-14
+//! logs/app.2026-10-18-14.001   (size rollover within the hour)
+//! ```
+
+use chrono::{DateTime, Utc};
+use serde::Deserialize;
+use std::fs::{self, File, OpenOptions};
+use std::io::{self, Write};
+use std::path::PathBuf;
+
+/// How often the file log starts a new file
+#[derive(Debug, Clone, Copy, PartialEq, Eq, Default, Deserialize)]
+#[serde(rename_all = "lowercase")]
+pub enum RotationPeriod {
+    #[default]
+    Hourly,
+    Daily,
+    /// Only size rolls files over
+    Never,
+}
+
+/// Where the file log goes and how much of it is kept
+#[derive(Debug, Clone, PartialEq, Eq)]
+pub struct LogRotation {
+    pub dir: PathBuf,
+    /// File name before the period, `app` gives `app.2026-10-18-14`
+    pub prefix: String,
+    pub period: RotationPeriod,
+    /// Size at which a new file starts, `None` for no limit
+    pub max_bytes: Option<u64>,
+    /// Files kept including the current one, 0 keeps all
+    pub max_files: usize,
+}
+
+impl Default for LogRotation {
+    fn default() -> Self {
+        Self {
+            dir: PathBuf::from("logs"),
+            prefix: "app".to_string(),
+            period: RotationPeriod::Hourly,
+            max_bytes: Some(50 * 1024 * 1024),
+            max_files: 48,
+        }
+    }
+}
+
+/// Writer behind the file log layer
+pub(crate) struct RotatingFile {
+    rotation: LogRotation,
+    file: Option<File>,
+    /// Name of the current period's first file
+    base: String,
+    part: u32,
+    written: u64,
+}
+
+impl RotatingFile {
+    pub(crate) fn new(rotation: LogRotation) -> Self {
+        fs::create_dir_all(&rotation.dir).ok();
+        Self {
+            rotation,
+            file: None,
+            base: String::new(),
+            part: 0,
+            written: 0,
+        }
+    }
+
+    fn base_name(&self, now: DateTime<Utc>) -> String {
+        let prefix = &self.rotation.prefix;
+        match self.rotation.period {
+            RotationPeriod::Hourly => format!("{}.{}", prefix, now.format("%Y-%m-%d-%H")),
+            RotationPeriod::Daily => format!("{}.{}", prefix, now.format("%Y-%m-%d")),
+            RotationPeriod::Never => prefix.clone(),
+        }
+    }
+
+    fn path(&self, part: u32) -> PathBuf {
+        match part {
+            0 => self.rotation.dir.join(&self.base),
+            part => self.rotation.dir.join(format!("{}.{:03}", self.base, part)),
+        }
+    }
+
+    fn full(&self, size: u64) -> bool {
+        self.rotation.max_bytes.is_some_and(|max| size >= max)
+    }
+
+    /// Opens the current part, moving on to the last part a previous run
+    /// wrote and past any that are full
+    fn open(&mut self) -> io::Result<()> {
+        self.file = None;
+        loop {
+            let size = fs::metadata(self.path(self.part)).map_or(0, |meta| meta.len());
+            if !self.full(size) && !self.path(self.part + 1).exists() {
+                break;
+            }
+            self.part += 1;
+        }
+        let file = OpenOptions::new()
+            .create(true)
+            .append(true)
+            .open(self.path(self.part))?;
+        self.written = file.metadata()?.len();
+        self.file = Some(file);
+        self.prune();
+        Ok(())
+    }
+
+    /// Deletes the oldest log files past `max_files`
+    fn prune(&self) {
+        if self.rotation.max_files == 0 {
+            return;
+        }
+        let Ok(entries) = fs::read_dir(&self.rotation.dir) else {
+            return;
+        };
+        let prefix = &self.rotation.prefix;
+        let mut names: Vec<String> = entries
+            .filter_map(|entry| entry.ok()?.file_name().into_string().ok())
+            .filter(|name| name == prefix || name.starts_with(&format!("{}.", prefix)))
+            .collect();
+        names.sort();
+        let excess = names.len().saturating_sub(self.rotation.max_files);
+        for name in &names[..excess] {
+            fs::remove_file(self.rotation.dir.join(name)).ok();
+        }
+    }
+
+    fn write_at(&mut self, buf: &[u8], now: DateTime<Utc>) -> io::Result<usize> {
+        let base = self.base_name(now);
+        if base != self.base {
+            self.base = base;
+            self.part = 0;
+            self.open()?;
+        } else if self.file.is_none() {
+            self.open()?;
+        } else if self.written > 0 && self.full(self.written + buf.len() as u64) {
+            self.part += 1;
+            self.open()?;
+        }
+        let Some(file) = self.file.as_mut() else {
+            return Err(io::Error::other("log file not open"));
+        };
+        let written = file.write(buf)?;
+        self.written += written as u64;
+        Ok(written)
+    }
+}
+
+impl Write for RotatingFile {
+    fn write(&mut self, buf: &[u8]) -> io::Result<usize> {
+        self.write_at(buf, Utc::now())
+    }
+
+    fn flush(&mut self) -> io::Result<()> {
+        match self.file.as_mut() {
+            Some(file) => file.flush(),
+            None => Ok(()),
+        }
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use chrono::TimeZone;
+
+    fn rotation(name: &str, period: RotationPeriod, max_bytes: Option<u64>) -> LogRotation {
+        let dir = std::env::temp_dir().join(format!("core-logs-{}-{}", name, std::process::id()));
+        fs::remove_dir_all(&dir).ok();
+        LogRotation {
+            dir,
+            prefix: "app".to_string(),
+            period,
+            max_bytes,
+            max_files: 3,
+        }
+    }
+
+    fn files(rotation: &LogRotation) -> Vec<String> {
+        let mut names: Vec<String> = fs::read_dir(&rotation.dir)
+            .unwrap()
+            .map(|entry| entry.unwrap().file_name().into_string().unwrap())
+            .collect();
+        names.sort();
+        names
+    }
+
+    #[test]
+    fn test_rotates_by_period_and_prunes() {
+        let rotation = rotation("period", RotationPeriod::Hourly, None);
+        let mut file = RotatingFile::new(rotation.clone());
+        for hour in 10..15 {
+            let now = Utc.with_ymd_and_hms(2026, 10, 18, hour, 5, 0).unwrap();
+            file.write_at(b"line\n", now).unwrap();
+        }
+        assert_eq!(
+            files(&rotation),
+            [
+                "app.2026-10-18-12",
+                "app.2026-10-18-13",
+                "app.2026-10-18-14"
+            ]
+        );
+        fs::remove_dir_all(&rotation.dir).unwrap();
+    }
+
+    #[test]
+    fn test_rotates_by_size_and_resumes_parts() {
+        let rotation = rotation("size", RotationPeriod::Daily, Some(10));
+        let now = Utc.with_ymd_and_hms(2026, 10, 18, 9, 0, 0).unwrap();
+        let mut file = RotatingFile::new(rotation.clone());
+        file.write_at(b"12345678\n", now).unwrap();
+        file.write_at(b"abc\n", now).unwrap();
+        assert_eq!(files(&rotation), ["app.2026-10-18", "app.2026-10-18.001"]);
+
+        // A restart appends to the part that still has room
+        let mut file = RotatingFile::new(rotation.clone());
+        file.write_at(b"de\n", now).unwrap();
+        assert_eq!(
+            fs::read_to_string(rotation.dir.join("app.2026-10-18.001")).unwrap(),
+            "abc\nde\n"
+        );
+        fs::remove_dir_all(&rotation.dir).unwrap();
+    }
+}
@@ -1,27 +1,86 @@
 #![allow(dead_code)]
 
+use super::log_rotation::{LogRotation, RotatingFile};
+use super::telemetry::{self, OtlpConfig};
 use anyhow::{Context, Result};
 use chrono::Local;
 use nu_ansi_term::{Color, Style};
 use std::fmt;
 use std::fs::File;
 use std::io::BufWriter;
+use std::sync::Arc;
 use tracing::{Event, Subscriber};
 use tracing_appender::non_blocking::WorkerGuard;
 use tracing_subscriber::{
+    filter::{FilterExt, Targets},
     fmt::{format::Writer, FmtContext, FormatEvent, FormatFields},
     prelude::*,
     registry::LookupSpan,
-    Layer,
+    reload, Layer, Registry,
 };
 
+type ApplyFn = dyn Fn(Targets) -> Result<()> + Send + Sync;
+
+/// Handle for raising log verbosity of individual targets while running
+///
+/// The targets passed to [`LogLevels::apply`] are enabled on top of the
+/// default console and file filters, so they can make a subsystem more
+/// verbose but never hide what the defaults already show.
+#[derive(Clone)]
+pub struct LogLevels {
+    apply: Arc<ApplyFn>,
+}
+
+impl fmt::Debug for LogLevels {
+    fn fmt(&self, f: &mut fmt::Formatter<'_>) -> fmt::Result {
+        f.debug_struct("LogLevels").finish_non_exhaustive()
+    }
+}
+
+impl LogLevels {
+    /// Replaces the extra targets; an empty [`Targets`] restores the defaults
+    pub fn apply(&self, extra: Targets) -> Result<()> {
+        (self.apply)(extra)
+    }
+}
+
 pub fn setup_logger() -> Option<WorkerGuard> {
-    // Create logs directory
-    std::fs::create_dir_all("logs").ok();
+    setup_logger_with_levels().0
+}
+
+/// Same as [`setup_logger`], returning a handle to change levels at runtime
+pub fn setup_logger_with_levels() -> (Option<WorkerGuard>, LogLevels) {
+    setup_logger_with_rotation(LogRotation::default())
+}
+
+/// Same as [`setup_logger_with_levels`], with the file log rotated and
+/// pruned as `rotation` says
+pub fn setup_logger_with_rotation(rotation: LogRotation) -> (Option<WorkerGuard>, LogLevels) {
+    install(rotation, None)
+}
 
-    // HOURLY rotation with aggressive cleanup for 10-20MB total limit
-    // Each log file ~5-10MB max, keep only 2 files (current + previous hour)
-    let file_appender = tracing_appender::rolling::hourly("logs", "app");
+/// Same as [`setup_logger_with_rotation`], also exporting spans to the
+/// OpenTelemetry collector in `otlp`
+///
+/// Fails without installing anything when the exporter cannot be set up,
+/// e.g. because core-logic was built without the `otel` feature. Call
+/// [`shutdown_tracing`](super::shutdown_tracing) before exiting so the last
+/// spans are sent.
+pub fn setup_logger_with_tracing(
+    rotation: LogRotation,
+    otlp: Option<&OtlpConfig>,
+) -> Result<(Option<WorkerGuard>, LogLevels)> {
+    let export = otlp.map(telemetry::export_layer).transpose()?;
+    Ok(install(rotation, export))
+}
+
+fn install(
+    rotation: LogRotation,
+    export: Option<telemetry::ExportLayer<Registry>>,
+) -> (Option<WorkerGuard>, LogLevels) {
+    // Hourly files under logs/ by default, rolled over at 50 MB and pruned to
+    // the last 48 so a long run cannot fill the disk
+    let file_appender = RotatingFile::new(rotation);
     let (non_blocking, guard) = tracing_appender::non_blocking(file_appender);
 
     // File layer: INFO for task_result, WARN for others
@@ -29,31 +88,45 @@ pub fn setup_logger() -> Option<WorkerGuard> {
         .with_target("task_result", tracing::Level::INFO)
         .with_default(tracing::Level::WARN);
 
+    let (file_extra, file_handle) = reload::Layer::new(Targets::new());
     let file_layer = tracing_subscriber::fmt::layer()
         .with_writer(non_blocking)
         .with_ansi(false)
         .event_format(FileFormatter)
-        .with_filter(file_filter);
+        .with_filter(file_filter.or(file_extra));
 
     // Console layer: INFO for task_result, ERROR for others
     let console_filter = tracing_subscriber::filter::Targets::new()
         .with_target("task_result", tracing::Level::INFO)
         .with_default(tracing::Level::ERROR);
 
+    let (console_extra, console_handle) = reload::Layer::new(Targets::new());
     let console_layer = tracing_subscriber::fmt::layer()
         .with_writer(std::io::stdout)
-        .with_ansi(true)
+        .with_ansi(super::terminal::colors_enabled())
         .event_format(TerminalFormatter)
-        .with_filter(console_filter);
+        .with_filter(console_filter.or(console_extra));
 
-    // Combine both layers
+    // Combine both layers, under the span export if there is one
     tracing_subscriber::registry()
+        .with(export)
         .with(file_layer)
         .with(console_layer)
         .init();
 
+    let levels = LogLevels {
+        apply: Arc::new(move |extra: Targets| {
+            file_handle
+                .reload(extra.clone())
+                .context("Failed to reload file log filter")?;
+            console_handle
+                .reload(extra)
+                .context("Failed to reload console log filter")
+        }),
+    };
+
     // Return guard - MUST be kept alive by caller
-    Some(guard)
+    (Some(guard), levels)
 }
 
 pub fn setup_logger_with_file(log_path: &str) -> Result<WorkerGuard> {
@@ -112,8 +185,11 @@ where
         event.record(&mut msg_visitor);
         let msg = msg_visitor.message;
 
-        // Colorization for SUCCESS and FAILED
-        let colored_msg = if msg.contains("SUCCESS") || msg.contains("Success") {
+        // Colorization for SUCCESS, FAILED, RETRY and ERROR, skipped when the
+        // console layer was built without ANSI support
+        let colored_msg = if !writer.has_ansi_escapes() {
+            msg
+        } else if msg.contains("SUCCESS") || msg.contains("Success") {
             let green_text = Style::new().fg(Color::LightGreen).bold();
             msg.replace("SUCCESS", &format!("{}", green_text.paint("SUCCESS")))
                 .replace("Success", &format!("{}", green_text.paint("Success")))
@@ -121,6 +197,10 @@ where
             let red_text = Style::new().fg(Color::LightRed).bold();
             msg.replace("FAILED", &format!("{}", red_text.paint("FAILED")))
                 .replace("Failed", &format!("{}", red_text.paint("Failed")))
+        } else if msg.contains("RETRY") {
+            msg.replace("RETRY", &format!("{}", Color::Yellow.paint("RETRY")))
+        } else if msg.contains("ERROR") {
+            msg.replace("ERROR", &format!("{}", Color::Red.paint("ERROR")))
         } else {
             msg
         };
@@ -5,18 +5,43 @@
 
 // Internal modules - not part of public API
 pub(crate) mod gas;
+pub(crate) mod gas_manager;
+pub(crate) mod headless;
+pub(crate) mod log_rotation;
 pub(crate) mod logger;
+pub(crate) mod nonce_manager;
+pub(crate) mod proxy_arbiter;
 pub(crate) mod proxy_manager;
 pub(crate) mod rate_limiter;
 pub(crate) mod retry;
 pub(crate) mod rpc_manager;
 pub(crate) mod runner;
+pub(crate) mod telemetry;
+pub(crate) mod terminal;
 pub(crate) mod wallet_manager;
 
 // Selective exports - only public utilities
-pub use gas::GasConfig;
-pub use logger::setup_logger;
+pub use gas::{BumpPolicy, FeeStrategy, GasConfig};
+pub use gas_manager::{bump_fee, FeeSample, Fees, GasManager};
+pub use headless::{
+    database_key, non_interactive, password_sources_hint, wallet_password, DATABASE_KEY_ENV,
+    DATABASE_KEY_FILE_ENV, NON_INTERACTIVE_ENV, PASSWORD_ENV, PASSWORD_FILE_ENV,
+};
+pub use log_rotation::{LogRotation, RotationPeriod};
+pub use logger::{
+    setup_logger, setup_logger_with_levels, setup_logger_with_rotation, setup_logger_with_tracing,
+    LogLevels,
+};
+pub use nonce_manager::{
+    Nonce2D, NonceKey, NonceManagement, NonceManager, NonceManagerConfig, NonceReservation,
+    NonceState, NonceStats, RequestId, RobustNonceManager,
+};
+pub use proxy_arbiter::{ArbiterConfig, ChainProxies, ChainShare, ProxyArbiter};
 pub use proxy_manager::ProxyManager;
 pub use rpc_manager::RpcManager;
-pub use runner::WorkerRunner;
-pub use wallet_manager::WalletManager;
+pub use runner::{RampUp, ScaleHandle, WorkerRunner};
+pub use telemetry::{shutdown_tracing, OtlpConfig};
+pub use terminal::{ansi_supported, colors_enabled, paint, Tone};
+pub use wallet_manager::{
+    HostPartition, WalletEntry, WalletManager, WalletOptions, WalletStream, WalletTags,
+};
@@ -0,0 +1,876 @@
+//! # Core Logic - Nonce Managers
+//!
+//! Nonce bookkeeping shared by the EVM chains. Two managers implement
+//! [`NonceManagement`]:
+//!
+//! - [`NonceManager`] - a plain cache of each sequence's next nonce, read
+//!   from the node once and counted up locally
+//! - [`RobustNonceManager`] - tracks every nonce it hands out through
+//!   reservation, submission and confirmation, reuses nonces that were never
+//!   sent and recovers from "nonce too low" races
+//!
+//! Both are keyed by any [`NonceKey`], by default an account [`Address`].
+//! Chains with parallel nonce sequences key them by [`Nonce2D`] instead, and
+//! read a sequence's next nonce through their own [`NonceSource`].
+
+use crate::templates::NonceSource;
+use alloy::primitives::{Address, U256};
+use async_trait::async_trait;
+use std::collections::{HashMap, HashSet, VecDeque};
+use std::fmt::Debug;
+use std::hash::Hash;
+use std::sync::atomic::{AtomicBool, AtomicU64, Ordering};
+use std::sync::Arc;
+use std::time::{Duration, Instant};
+use tokio::sync::{Mutex, RwLock};
+use tracing::{debug, info, warn};
+
+/// Identifies one nonce sequence
+pub trait NonceKey: Copy + Eq + Hash + Debug + Send + Sync + 'static {}
+
+impl<T: Copy + Eq + Hash + Debug + Send + Sync + 'static> NonceKey for T {}
+
+/// One of an account's parallel nonce sequences, as on Tempo
+///
+/// Key 0 is the protocol nonce every account has. Keys from 1 up count
+/// independently, so transactions on different keys never wait on each
+/// other.
+#[derive(Debug, Clone, Copy, PartialEq, Eq, Hash)]
+pub struct Nonce2D {
+    pub address: Address,
+    pub key: U256,
+}
+
+impl Nonce2D {
+    pub fn new(address: Address, key: U256) -> Self {
+        Self { address, key }
+    }
+
+    /// Whether this is the account's protocol nonce
+    pub fn is_protocol(&self) -> bool {
+        self.key.is_zero()
+    }
+}
+
+impl From<Address> for Nonce2D {
+    fn from(address: Address) -> Self {
+        Self::new(address, U256::ZERO)
+    }
+}
+
+/// Hands out nonces for one or more nonce sequences
+///
+/// A manager only knows what it was told: [`sync`](Self::sync) it from the
+/// node, or let [`next_or_fetch`](Self::next_or_fetch) do so on a miss.
+#[async_trait]
+pub trait NonceManagement<K: NonceKey = Address>: Send + Sync {
+    /// The next nonce of `key`, `None` until the sequence is synced
+    async fn next(&self, key: K) -> Option<u64>;
+
+    /// Sets the next nonce of `key`, as the node reports it
+    async fn sync(&self, key: K, next_nonce: u64);
+
+    /// Records that the transaction using `nonce` landed
+    async fn confirm(&self, key: K, nonce: u64);
+
+    /// Gives back a nonce that was never sent
+    async fn release(&self, key: K, nonce: u64);
+
+    /// Recovers from the node rejecting `attempted` as too low
+    async fn recover(&self, key: K, attempted: u64, actual_next: u64);
+
+    /// Forgets `key`, so its next nonce is read from the node again
+    async fn reset(&self, key: K);
+
+    /// The next nonce of `key`, syncing it from `source` when unknown
+    async fn next_or_fetch<S>(&self, key: K, source: &S) -> Result<u64, S::Error>
+    where
+        S: NonceSource<K> + ?Sized,
+    {
+        if let Some(nonce) = self.next(key).await {
+            return Ok(nonce);
+        }
+
+        let next_nonce = source.next_nonce(key).await?;
+        self.sync(key, next_nonce).await;
+        Ok(self.next(key).await.unwrap_or(next_nonce))
+    }
+}
+
+/// Thread-safe cache of the next nonce of each sequence
+///
+/// Stores the NEXT nonce to use, not the last one used. Nothing is cached
+/// until [`set`](Self::set) is called for a key.
+#[derive(Debug)]
+pub struct NonceManager<K = Address> {
+    nonces: RwLock<HashMap<K, u64>>,
+}
+
+impl<K> Default for NonceManager<K> {
+    fn default() -> Self {
+        Self {
+            nonces: RwLock::new(HashMap::new()),
+        }
+    }
+}
+
+impl<K: NonceKey> NonceManager<K> {
+    pub fn new() -> Self {
+        Self::default()
+    }
+
+    /// The cached nonce of `key`, counting it up; `None` when not cached
+    pub async fn get_and_increment(&self, key: K) -> Option<u64> {
+        let mut map = self.nonces.write().await;
+        let nonce = map.get_mut(&key)?;
+        let current = *nonce;
+        *nonce += 1;
+        Some(current)
+    }
+
+    /// The cached nonce of `key` without consuming it
+    pub async fn peek(&self, key: K) -> Option<u64> {
+        self.nonces.read().await.get(&key).copied()
+    }
+
+    /// Caches `next_nonce` as the next nonce of `key`
+    ///
+    /// `eth_getTransactionCount` already is the next usable nonce; store it
+    /// as is.
+    pub async fn set(&self, key: K, next_nonce: u64) {
+        self.nonces.write().await.insert(key, next_nonce);
+    }
+
+    /// Drops `key` from the cache, e.g. after a "nonce too low" error
+    pub async fn reset(&self, key: K) {
+        self.nonces.write().await.remove(&key);
+    }
+}
+
+#[async_trait]
+impl<K: NonceKey> NonceManagement<K> for NonceManager<K> {
+    async fn next(&self, key: K) -> Option<u64> {
+        self.get_and_increment(key).await
+    }
+
+    async fn sync(&self, key: K, next_nonce: u64) {
+        self.set(key, next_nonce).await;
+    }
+
+    async fn confirm(&self, _key: K, _nonce: u64) {}
+
+    /// Steps back when `nonce` was the last one handed out; otherwise drops
+    /// the cache rather than leave a gap
+    async fn release(&self, key: K, nonce: u64) {
+        let mut map = self.nonces.write().await;
+        match map.get_mut(&key) {
+            Some(next) if *next == nonce + 1 => *next = nonce,
+            Some(_) => {
+                map.remove(&key);
+            }
+            None => {}
+        }
+    }
+
+    async fn recover(&self, key: K, _attempted: u64, actual_next: u64) {
+        let mut map = self.nonces.write().await;
+        let next = map.entry(key).or_insert(actual_next);
+        *next = (*next).max(actual_next);
+    }
+
+    async fn reset(&self, key: K) {
+        NonceManager::reset(self, key).await;
+    }
+}
+
+/// Request ID for tracking individual nonce allocations
+pub type RequestId = u64;
+
+/// State for a single nonce request
+#[derive(Debug, Clone)]
+pub enum NonceState {
+    /// Nonce is reserved but transaction not yet sent
+    Reserved { since: Instant },
+    /// Transaction submitted, waiting for confirmation
+    InFlight { since: Instant },
+    /// Transaction confirmed on-chain
+    Confirmed { at: Instant },
+    /// Transaction failed, nonce can be reused
+    Failed { error: String },
+}
+
+/// Per-sequence nonce tracking
+#[derive(Debug)]
+struct SequenceState {
+    /// Whether the sequence was synced from the node; 0 is a valid nonce
+    initialized: AtomicBool,
+
+    /// The next nonce to allocate (cached value)
+    cached_nonce: AtomicU64,
+
+    /// Highest confirmed nonce seen on-chain
+    confirmed_nonce: AtomicU64,
+
+    /// Nonces currently in various states
+    requests: Mutex<HashMap<u64, (RequestId, NonceState)>>,
+
+    /// In-flight nonces (submitted but not confirmed)
+    in_flight: Mutex<HashSet<u64>>,
+
+    /// Failed nonces that can be reused
+    failed_nonces: Mutex<VecDeque<u64>>,
+
+    /// Last sync time with blockchain
+    last_sync: Mutex<Instant>,
+}
+
+impl SequenceState {
+    fn new() -> Self {
+        Self {
+            initialized: AtomicBool::new(false),
+            cached_nonce: AtomicU64::new(0),
+            confirmed_nonce: AtomicU64::new(0),
+            requests: Mutex::new(HashMap::new()),
+            in_flight: Mutex::new(HashSet::new()),
+            failed_nonces: Mutex::new(VecDeque::new()),
+            last_sync: Mutex::new(Instant::now()),
+        }
+    }
+}
+
+/// Configuration for the nonce manager
+#[derive(Debug, Clone)]
+pub struct NonceManagerConfig {
+    /// How long to wait before considering a reserved nonce as expired
+    pub reservation_timeout: Duration,
+    /// How long to wait before considering an in-flight nonce as stuck
+    pub in_flight_timeout: Duration,
+    /// Auto-sync interval
+    pub auto_sync_interval: Duration,
+    /// Maximum number of failed nonces to track per sequence
+    pub max_failed_cache: usize,
+}
+
+impl Default for NonceManagerConfig {
+    fn default() -> Self {
+        Self {
+            reservation_timeout: Duration::from_secs(30),
+            in_flight_timeout: Duration::from_secs(120),
+            auto_sync_interval: Duration::from_secs(60),
+            max_failed_cache: 100,
+        }
+    }
+}
+
+/// Nonce manager tracking each nonce from reservation to confirmation
+///
+/// Nonces released unused, or failing for reasons other than being too low,
+/// are handed out again before new ones. A "nonce too low" error moves the
+/// sequence up to the node's count and drops every older reservation.
+#[derive(Debug)]
+pub struct RobustNonceManager<K = Address> {
+    sequences: RwLock<HashMap<K, Arc<SequenceState>>>,
+
+    /// Global request ID counter
+    global_request_id: AtomicU64,
+
+    config: NonceManagerConfig,
+}
+
+/// Nonce reservation handle
+///
+/// This handle represents a reserved nonce. It must be either:
+/// - Marked as submitted via `mark_submitted()`
+/// - Released via `release()` if not used
+pub struct NonceReservation<K: NonceKey = Address> {
+    pub request_id: RequestId,
+    /// Sequence the nonce belongs to, the account for [`Address`] keys
+    pub address: K,
+    pub nonce: u64,
+    manager: Arc<RobustNonceManager<K>>,
+    submitted: bool,
+}
+
+impl<K: NonceKey> NonceReservation<K> {
+    /// Mark this nonce as submitted (transaction sent)
+    ///
+    /// Moves the nonce from "Reserved" to "InFlight" state
+    pub async fn mark_submitted(mut self) {
+        self.submitted = true;
+        self.manager.mark_submitted(self.address, self.nonce).await;
+    }
+
+    /// Release the nonce without using it
+    ///
+    /// Returns the nonce to the pool for reuse
+    pub async fn release(mut self) {
+        if !self.submitted {
+            self.submitted = true;
+            self.manager.release_nonce(self.address, self.nonce).await;
+        }
+    }
+}
+
+impl<K: NonceKey> Drop for NonceReservation<K> {
+    /// Automatic cleanup on drop (with warning)
+    ///
+    /// This is a safety fallback. If you see this warning in logs,
+    /// you should update your code to call `reservation.release().await` explicitly.
+    fn drop(&mut self) {
+        if !self.submitted && !std::thread::panicking() {
+            tracing::warn!(
+                target: "nonce_manager",
+                "NonceReservation dropped without explicit release(). \
+                 Using automatic cleanup. \
+                 Prefer calling reservation.release().await explicitly."
+            );
+            let manager = self.manager.clone();
+            let key = self.address;
+            let nonce = self.nonce;
+            tokio::spawn(async move {
+                manager.release_nonce(key, nonce).await;
+            });
+        }
+    }
+}
+
+impl<K: NonceKey> RobustNonceManager<K> {
+    /// Creates a new robust nonce manager with default configuration
+    pub fn new() -> Self {
+        Self::with_config(NonceManagerConfig::default())
+    }
+
+    /// Creates a new robust nonce manager with custom configuration
+    pub fn with_config(config: NonceManagerConfig) -> Self {
+        Self {
+            sequences: RwLock::new(HashMap::new()),
+            global_request_id: AtomicU64::new(1),
+            config,
+        }
+    }
+
+    /// Reserve a nonce for a transaction
+    ///
+    /// Reuses a failed or released nonce when there is one, otherwise takes
+    /// the next cached nonce. `None` while the sequence is not initialized.
+    pub async fn reserve_nonce(self: &Arc<Self>, address: K) -> Option<NonceReservation<K>> {
+        let (request_id, nonce) = self.allocate(address).await?;
+        Some(NonceReservation {
+            request_id,
+            address,
+            nonce,
+            manager: self.clone(),
+            submitted: false,
+        })
+    }
+
+    /// Takes a nonce and tracks it as reserved
+    async fn allocate(&self, key: K) -> Option<(RequestId, u64)> {
+        let state = self.get_or_create_sequence(key).await;
+
+        let nonce = {
+            let mut failed = state.failed_nonces.lock().await;
+            match failed.pop_front() {
+                Some(nonce) => nonce,
+                None => {
+                    if !state.initialized.load(Ordering::SeqCst) {
+                        return None;
+                    }
+                    state.cached_nonce.fetch_add(1, Ordering::SeqCst)
+                }
+            }
+        };
+
+        let request_id = self.global_request_id.fetch_add(1, Ordering::SeqCst);
+        state.requests.lock().await.insert(
+            nonce,
+            (
+                request_id,
+                NonceState::Reserved {
+                    since: Instant::now(),
+                },
+            ),
+        );
+
+        debug!(
+            "Reserved nonce {} for {:?} (request {})",
+            nonce, key, request_id
+        );
+        Some((request_id, nonce))
+    }
+
+    /// Initialize or update the cached nonce for a sequence
+    ///
+    /// Call this after fetching `eth_getTransactionCount` from RPC. Once
+    /// initialized, only a higher count moves the sequence.
+    pub async fn initialize(&self, address: K, confirmed_count: u64) {
+        let state = self.get_or_create_sequence(address).await;
+
+        let initialized = state.initialized.load(Ordering::SeqCst);
+        let current_cached = state.cached_nonce.load(Ordering::SeqCst);
+        let current_confirmed = state.confirmed_nonce.load(Ordering::SeqCst);
+
+        if !initialized || confirmed_count > current_cached || confirmed_count > current_confirmed {
+            state.cached_nonce.store(confirmed_count, Ordering::SeqCst);
+            state
+                .confirmed_nonce
+                .store(confirmed_count.saturating_sub(1), Ordering::SeqCst);
+            state.initialized.store(true, Ordering::SeqCst);
+
+            *state.last_sync.lock().await = Instant::now();
+
+            info!(
+                "Initialized nonce for {:?}: cached={}, confirmed={}",
+                address,
+                confirmed_count,
+                confirmed_count.saturating_sub(1)
+            );
+        }
+    }
+
+    /// Moves a nonce from Reserved to InFlight
+    async fn mark_submitted(&self, address: K, nonce: u64) {
+        let Some(state) = self.sequence(address).await else {
+            return;
+        };
+
+        let mut requests = state.requests.lock().await;
+        if let Some((req_id, _)) = requests.get(&nonce) {
+            let req_id = *req_id;
+            requests.insert(
+                nonce,
+                (
+                    req_id,
+                    NonceState::InFlight {
+                        since: Instant::now(),
+                    },
+                ),
+            );
+        }
+
+        state.in_flight.lock().await.insert(nonce);
+
+        debug!("Nonce {} for {:?} marked as in-flight", nonce, address);
+    }
+
+    /// Confirm a nonce as successful
+    ///
+    /// Call this when a transaction is confirmed on-chain
+    pub async fn confirm_nonce(&self, address: K, nonce: u64) {
+        let Some(state) = self.sequence(address).await else {
+            return;
+        };
+
+        {
+            let mut requests = state.requests.lock().await;
+            if let Some((req_id, _)) = requests.get(&nonce) {
+                let req_id = *req_id;
+                requests.insert(
+                    nonce,
+                    (req_id, NonceState::Confirmed { at: Instant::now() }),
+                );
+            }
+        }
+
+        state.in_flight.lock().await.remove(&nonce);
+        state.confirmed_nonce.fetch_max(nonce, Ordering::SeqCst);
+
+        // Cleanup old confirmed entries periodically
+        if nonce.is_multiple_of(10) {
+            Self::cleanup_confirmed(&state).await;
+        }
+
+        debug!("Nonce {} for {:?} confirmed", nonce, address);
+    }
+
+    /// Mark a nonce as failed
+    ///
+    /// The nonce will be reused for future transactions IF recycle is true
+    pub async fn mark_failed(&self, address: K, nonce: u64, error: String, recycle: bool) {
+        let Some(state) = self.sequence(address).await else {
+            return;
+        };
+
+        let mut requests = state.requests.lock().await;
+        if let Some((req_id, _)) = requests.get(&nonce) {
+            let req_id = *req_id;
+            requests.insert(
+                nonce,
+                (
+                    req_id,
+                    NonceState::Failed {
+                        error: error.clone(),
+                    },
+                ),
+            );
+        }
+
+        state.in_flight.lock().await.remove(&nonce);
+
+        if recycle {
+            let mut failed = state.failed_nonces.lock().await;
+            if !failed.contains(&nonce) {
+                failed.push_back(nonce);
+                while failed.len() > self.config.max_failed_cache {
+                    failed.pop_front();
+                }
+            }
+        }
+
+        warn!(
+            "Nonce {} for {:?} failed (recycle={}): {}",
+            nonce, address, recycle, error
+        );
+    }
+
+    /// Returns an unsent nonce to the pool for reuse
+    pub async fn release_nonce(&self, address: K, nonce: u64) {
+        let Some(state) = self.sequence(address).await else {
+            return;
+        };
+
+        state.requests.lock().await.remove(&nonce);
+
+        let mut failed = state.failed_nonces.lock().await;
+        if !failed.contains(&nonce) {
+            failed.push_back(nonce);
+        }
+
+        debug!("Nonce {} for {:?} released", nonce, address);
+    }
+
+    /// Handle "nonce too low" error with automatic recovery
+    ///
+    /// The attempted nonce is dead and not recycled. When the node is ahead
+    /// of the cache, the cache jumps to `actual_next_nonce` and every
+    /// reserved or in-flight nonce below it is invalidated.
+    pub async fn handle_nonce_error(
+        &self,
+        address: K,
+        attempted_nonce: u64,
+        actual_next_nonce: u64,
+    ) {
+        let error = format!(
+            "nonce too low: attempted {}, actual next is {}",
+            attempted_nonce, actual_next_nonce
+        );
+        self.mark_failed(address, attempted_nonce, error, false)
+            .await;
+
+        let Some(state) = self.sequence(address).await else {
+            return;
+        };
+
+        let current_cached = state.cached_nonce.load(Ordering::SeqCst);
+        if actual_next_nonce > current_cached {
+            state
+                .cached_nonce
+                .store(actual_next_nonce, Ordering::SeqCst);
+            state.initialized.store(true, Ordering::SeqCst);
+
+            let mut requests = state.requests.lock().await;
+            let mut in_flight = state.in_flight.lock().await;
+            let mut failed_queue = state.failed_nonces.lock().await;
+
+            let stale_nonces: Vec<u64> = requests
+                .keys()
+                .filter(|&n| *n < actual_next_nonce && *n != attempted_nonce)
+                .copied()
+                .collect();
+
+            for stale in stale_nonces {
+                if let Some((req_id, _)) = requests.get(&stale) {
+                    let req_id = *req_id;
+                    requests.insert(
+                        stale,
+                        (
+                            req_id,
+                            NonceState::Failed {
+                                error: "Stale: superseded by chain state".to_string(),
+                            },
+                        ),
+                    );
+                }
+                in_flight.remove(&stale);
+                warn!(
+                    "Invalidated stale nonce {} for {:?} due to chain sync",
+                    stale, address
+                );
+            }
+            failed_queue.retain(|&n| n >= actual_next_nonce);
+
+            warn!(
+                "Adjusted cached nonce for {:?}: {} -> {}",
+                address, current_cached, actual_next_nonce
+            );
+        }
+
+        state
+            .confirmed_nonce
+            .store(actual_next_nonce.saturating_sub(1), Ordering::SeqCst);
+    }
+
+    /// Get the next nonce to use (for external synchronization)
+    ///
+    /// Returns the current cached nonce value
+    pub async fn peek_next_nonce(&self, address: K) -> Option<u64> {
+        self.sequence(address)
+            .await
+            .map(|state| state.cached_nonce.load(Ordering::SeqCst))
+    }
+
+    /// Get statistics for a sequence
+    pub async fn get_stats(&self, address: K) -> Option<NonceStats> {
+        let state = self.sequence(address).await?;
+
+        let requests = state.requests.lock().await;
+        let in_flight = state.in_flight.lock().await;
+        let failed = state.failed_nonces.lock().await;
+
+        let reserved = requests
+            .values()
+            .filter(|(_, state)| matches!(state, NonceState::Reserved { .. }))
+            .count();
+
+        Some(NonceStats {
+            cached_next: state.cached_nonce.load(Ordering::SeqCst),
+            confirmed: state.confirmed_nonce.load(Ordering::SeqCst),
+            reserved,
+            in_flight: in_flight.len(),
+            failed_cached: failed.len(),
+            total_tracked: requests.len(),
+        })
+    }
+
+    /// Reset a sequence's state (force full resync)
+    pub async fn reset(&self, address: K) {
+        self.sequences.write().await.remove(&address);
+        info!("Reset nonce state for {:?}", address);
+    }
+
+    /// Clean up old confirmed nonces to prevent memory growth
+    async fn cleanup_confirmed(state: &SequenceState) {
+        let cutoff = state
+            .confirmed_nonce
+            .load(Ordering::SeqCst)
+            .saturating_sub(50);
+        state
+            .requests
+            .lock()
+            .await
+            .retain(|nonce, _| *nonce >= cutoff);
+    }
+
+    async fn sequence(&self, key: K) -> Option<Arc<SequenceState>> {
+        self.sequences.read().await.get(&key).cloned()
+    }
+
+    async fn get_or_create_sequence(&self, key: K) -> Arc<SequenceState> {
+        if let Some(state) = self.sequence(key).await {
+            return state;
+        }
+
+        self.sequences
+            .write()
+            .await
+            .entry(key)
+            .or_insert_with(|| Arc::new(SequenceState::new()))
+            .clone()
+    }
+}
+
+impl<K: NonceKey> Default for RobustNonceManager<K> {
+    fn default() -> Self {
+        Self::new()
+    }
+}
+
+/// Hands out nonces already marked in-flight, for callers sending right away
+/// without a [`NonceReservation`]
+#[async_trait]
+impl<K: NonceKey> NonceManagement<K> for RobustNonceManager<K> {
+    async fn next(&self, key: K) -> Option<u64> {
+        let (_, nonce) = self.allocate(key).await?;
+        self.mark_submitted(key, nonce).await;
+        Some(nonce)
+    }
+
+    async fn sync(&self, key: K, next_nonce: u64) {
+        self.initialize(key, next_nonce).await;
+    }
+
+    async fn confirm(&self, key: K, nonce: u64) {
+        self.confirm_nonce(key, nonce).await;
+    }
+
+    async fn release(&self, key: K, nonce: u64) {
+        if let Some(state) = self.sequence(key).await {
+            state.in_flight.lock().await.remove(&nonce);
+        }
+        self.release_nonce(key, nonce).await;
+    }
+
+    async fn recover(&self, key: K, attempted: u64, actual_next: u64) {
+        self.handle_nonce_error(key, attempted, actual_next).await;
+    }
+
+    async fn reset(&self, key: K) {
+        RobustNonceManager::reset(self, key).await;
+    }
+}
+
+/// Statistics for a sequence's nonce state
+#[derive(Debug, Clone)]
+pub struct NonceStats {
+    pub cached_next: u64,
+    pub confirmed: u64,
+    pub reserved: usize,
+    pub in_flight: usize,
+    pub failed_cached: usize,
+    pub total_tracked: usize,
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    /// Node reporting a fixed next nonce for every sequence
+    struct FixedSource(u64);
+
+    #[async_trait]
+    impl<K: NonceKey> NonceSource<K> for FixedSource {
+        type Error = String;
+
+        async fn next_nonce(&self, _key: K) -> Result<u64, String> {
+            Ok(self.0)
+        }
+    }
+
+    #[tokio::test]
+    async fn test_cache_counts_up_and_resets() {
+        let manager = NonceManager::new();
+        let address = Address::ZERO;
+
+        assert_eq!(manager.get_and_increment(address).await, None);
+        manager.set(address, 5).await;
+        assert_eq!(manager.get_and_increment(address).await, Some(5));
+        assert_eq!(manager.get_and_increment(address).await, Some(6));
+        assert_eq!(manager.peek(address).await, Some(7));
+
+        // Releasing the last nonce steps back, an older one drops the cache
+        NonceManagement::release(&manager, address, 6).await;
+        assert_eq!(manager.peek(address).await, Some(6));
+        NonceManagement::release(&manager, address, 2).await;
+        assert_eq!(manager.peek(address).await, None);
+    }
+
+    #[tokio::test]
+    async fn test_next_or_fetch_syncs_once() {
+        let source = FixedSource(3);
+        let address = Address::ZERO;
+
+        let cache = NonceManager::new();
+        assert_eq!(cache.next_or_fetch(address, &source).await, Ok(3));
+        assert_eq!(cache.next_or_fetch(address, &source).await, Ok(4));
+
+        let robust = RobustNonceManager::new();
+        assert_eq!(robust.next_or_fetch(address, &source).await, Ok(3));
+        assert_eq!(robust.next_or_fetch(address, &source).await, Ok(4));
+        assert_eq!(robust.get_stats(address).await.unwrap().in_flight, 2);
+    }
+
+    #[tokio::test]
+    async fn test_reserve_and_confirm() {
+        let manager = Arc::new(RobustNonceManager::new());
+        let address = Address::ZERO;
+
+        manager.initialize(address, 5).await;
+
+        let reservation = manager.reserve_nonce(address).await.unwrap();
+        assert_eq!(reservation.nonce, 5);
+        reservation.mark_submitted().await;
+
+        manager.confirm_nonce(address, 5).await;
+
+        let stats = manager.get_stats(address).await.unwrap();
+        assert_eq!(stats.cached_next, 6);
+        assert_eq!(stats.confirmed, 5);
+    }
+
+    #[tokio::test]
+    async fn test_fresh_account_starts_at_zero() {
+        let manager = Arc::new(RobustNonceManager::new());
+        let address = Address::ZERO;
+
+        assert!(manager.reserve_nonce(address).await.is_none());
+        manager.initialize(address, 0).await;
+
+        let reservation = manager.reserve_nonce(address).await.unwrap();
+        assert_eq!(reservation.nonce, 0);
+        reservation.release().await;
+    }
+
+    #[tokio::test]
+    async fn test_nonce_error_recovery() {
+        let manager = Arc::new(RobustNonceManager::new());
+        let address = Address::ZERO;
+
+        manager.initialize(address, 10).await;
+        let _res = manager.reserve_nonce(address).await.unwrap();
+
+        // Someone else used 10 and 11
+        manager.handle_nonce_error(address, 10, 12).await;
+
+        assert_eq!(manager.peek_next_nonce(address).await, Some(12));
+    }
+
+    #[tokio::test]
+    async fn test_failed_nonce_reuse() {
+        let manager = Arc::new(RobustNonceManager::new());
+        let address = Address::ZERO;
+
+        manager.initialize(address, 5).await;
+
+        let res = manager.reserve_nonce(address).await.unwrap();
+        assert_eq!(res.nonce, 5);
+        manager
+            .mark_failed(address, 5, "test error".to_string(), true)
+            .await;
+
+        let res2 = manager.reserve_nonce(address).await.unwrap();
+        assert_eq!(res2.nonce, 5);
+    }
+
+    #[tokio::test]
+    async fn test_nonce_error_no_recycle() {
+        let manager = Arc::new(RobustNonceManager::new());
+        let address = Address::ZERO;
+
+        manager.initialize(address, 10).await;
+
+        let res = manager.reserve_nonce(address).await.unwrap();
+        assert_eq!(res.nonce, 10);
+
+        // A nonce that is too low is dead, not recycled
+        manager.handle_nonce_error(address, 10, 15).await;
+
+        let res2 = manager.reserve_nonce(address).await.unwrap();
+        assert_eq!(res2.nonce, 15);
+    }
+
+    #[tokio::test]
+    async fn test_2d_sequences_are_independent() {
+        let manager = RobustNonceManager::<Nonce2D>::new();
+        let protocol = Nonce2D::from(Address::ZERO);
+        let lane = Nonce2D::new(Address::ZERO, U256::from(1));
+        assert!(protocol.is_protocol());
+
+        manager.sync(protocol, 7).await;
+        manager.sync(lane, 0).await;
+
+        assert_eq!(manager.next(protocol).await, Some(7));
+        assert_eq!(manager.next(lane).await, Some(0));
+        assert_eq!(manager.next(lane).await, Some(1));
+        assert_eq!(manager.peek_next_nonce(protocol).await, Some(8));
+    }
+}
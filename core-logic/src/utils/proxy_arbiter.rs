@@ -0,0 +1,346 @@
+//! # Core Logic - Shared Proxy Arbitration
+//!
+//! Lets several chain spammers in one process draw from the same proxy list.
+//! Each chain gets its quota of in-flight requests on every proxy, and ban
+//! state is scoped to the chain's RPC host: a proxy that keeps failing
+//! against one chain's endpoint is pulled from chains on that host only,
+//! while chains on other hosts keep using it.
+
+use std::collections::{HashMap, HashSet};
+use std::sync::{Arc, Mutex};
+use std::time::{Duration, Instant};
+use tokio::sync::{OwnedSemaphorePermit, Semaphore};
+use tracing::{info, warn};
+
+use super::proxy_manager::ProxyManager;
+
+/// Limits shared by every chain in a [`ProxyArbiter`]
+#[derive(Debug, Clone)]
+pub struct ArbiterConfig {
+    /// In-flight requests per proxy, split between chains by quota
+    pub per_proxy_concurrency: usize,
+    /// Consecutive failures against one host before the proxy is banned for it
+    pub failure_threshold: u32,
+    /// How long a host-scoped ban lasts
+    pub ban_duration: Duration,
+}
+
+impl Default for ArbiterConfig {
+    fn default() -> Self {
+        Self {
+            per_proxy_concurrency: 8,
+            failure_threshold: 3,
+            ban_duration: Duration::from_secs(600),
+        }
+    }
+}
+
+/// A chain drawing from the shared pool
+#[derive(Debug, Clone)]
+pub struct ChainShare {
+    pub name: String,
+    /// RPC URL the chain sends its requests to
+    pub target: String,
+    /// Relative share of each proxy's concurrency
+    pub quota: f64,
+}
+
+#[derive(Debug)]
+struct ChainSlot {
+    name: String,
+    host: String,
+    concurrency: usize,
+    permits: Vec<Arc<Semaphore>>,
+}
+
+#[derive(Debug, Default)]
+struct HostHealth {
+    consecutive_failures: u32,
+    banned_at: Option<Instant>,
+}
+
+#[derive(Debug)]
+struct Shared {
+    config: ArbiterConfig,
+    proxy_count: usize,
+    chains: Vec<ChainSlot>,
+    health: Mutex<HashMap<(usize, String), HostHealth>>,
+}
+
+/// Arbitrates one proxy list between several chains
+///
+/// Cloning shares the same quotas and ban state.
+#[derive(Debug, Clone)]
+pub struct ProxyArbiter {
+    shared: Arc<Shared>,
+}
+
+impl ProxyArbiter {
+    /// Creates an arbiter for `proxy_count` proxies shared by `chains`
+    ///
+    /// Per-proxy concurrency is split with [`ProxyManager::partition`]; a
+    /// chain with a positive quota always gets at least one slot per proxy.
+    pub fn new(proxy_count: usize, chains: &[ChainShare], config: ArbiterConfig) -> Self {
+        let quotas: Vec<f64> = chains.iter().map(|chain| chain.quota).collect();
+        let shares = ProxyManager::partition(config.per_proxy_concurrency, &quotas);
+
+        let chains = chains
+            .iter()
+            .zip(shares)
+            .map(|(chain, share)| {
+                let concurrency = if chain.quota > 0.0 {
+                    share.len().max(1)
+                } else {
+                    0
+                };
+                ChainSlot {
+                    name: chain.name.clone(),
+                    host: target_host(&chain.target),
+                    concurrency,
+                    permits: (0..proxy_count)
+                        .map(|_| Arc::new(Semaphore::new(concurrency)))
+                        .collect(),
+                }
+            })
+            .collect();
+
+        Self {
+            shared: Arc::new(Shared {
+                config,
+                proxy_count,
+                chains,
+                health: Mutex::new(HashMap::new()),
+            }),
+        }
+    }
+
+    /// Handle for the chain registered under `name`
+    pub fn chain(&self, name: &str) -> Option<ChainProxies> {
+        let index = self
+            .shared
+            .chains
+            .iter()
+            .position(|chain| chain.name == name)?;
+        Some(ChainProxies {
+            arbiter: self.clone(),
+            index,
+        })
+    }
+
+    pub fn proxy_count(&self) -> usize {
+        self.shared.proxy_count
+    }
+
+    /// Whether a proxy is banned for requests to `host`
+    pub fn is_banned(&self, proxy_idx: usize, host: &str) -> bool {
+        let health = self.shared.health.lock().unwrap();
+        health
+            .get(&(proxy_idx, host.to_string()))
+            .and_then(|h| h.banned_at)
+            .is_some_and(|at| at.elapsed() < self.shared.config.ban_duration)
+    }
+
+    /// Proxies currently banned on every host in use, i.e. dead for all chains
+    pub fn banned_everywhere(&self) -> Vec<usize> {
+        let hosts: HashSet<&str> = self
+            .shared
+            .chains
+            .iter()
+            .map(|chain| chain.host.as_str())
+            .collect();
+        (0..self.shared.proxy_count)
+            .filter(|&idx| !hosts.is_empty() && hosts.iter().all(|h| self.is_banned(idx, h)))
+            .collect()
+    }
+
+    fn record(&self, proxy_idx: usize, chain: &ChainSlot, success: bool) -> bool {
+        let mut health = self.shared.health.lock().unwrap();
+        let entry = health.entry((proxy_idx, chain.host.clone())).or_default();
+
+        if success {
+            if entry.banned_at.take().is_some() {
+                info!(
+                    "Proxy {} recovered for {} ({})",
+                    proxy_idx, chain.host, chain.name
+                );
+            }
+            entry.consecutive_failures = 0;
+            return false;
+        }
+
+        // A failure after an expired ban starts a fresh count
+        if entry
+            .banned_at
+            .is_some_and(|at| at.elapsed() >= self.shared.config.ban_duration)
+        {
+            entry.banned_at = None;
+            entry.consecutive_failures = 0;
+        }
+        entry.consecutive_failures += 1;
+        if entry.banned_at.is_none()
+            && entry.consecutive_failures >= self.shared.config.failure_threshold
+        {
+            entry.banned_at = Some(Instant::now());
+            warn!(
+                "Proxy {} banned for {} after {} failures ({})",
+                proxy_idx, chain.host, entry.consecutive_failures, chain.name
+            );
+            return true;
+        }
+        false
+    }
+}
+
+/// One chain's view of a [`ProxyArbiter`]
+#[derive(Debug, Clone)]
+pub struct ChainProxies {
+    arbiter: ProxyArbiter,
+    index: usize,
+}
+
+impl ChainProxies {
+    fn slot(&self) -> &ChainSlot {
+        &self.arbiter.shared.chains[self.index]
+    }
+
+    pub fn name(&self) -> &str {
+        &self.slot().name
+    }
+
+    /// Host the chain's requests go to; bans are scoped to it
+    pub fn host(&self) -> &str {
+        &self.slot().host
+    }
+
+    /// Requests this chain may have in flight on each proxy
+    pub fn concurrency(&self) -> usize {
+        self.slot().concurrency
+    }
+
+    pub fn arbiter(&self) -> &ProxyArbiter {
+        &self.arbiter
+    }
+
+    /// Takes one of the chain's slots on a proxy
+    ///
+    /// Returns `None` when the chain already uses its whole quota on the
+    /// proxy. The slot is freed when the permit is dropped.
+    pub fn try_acquire(&self, proxy_idx: usize) -> Option<OwnedSemaphorePermit> {
+        self.slot()
+            .permits
+            .get(proxy_idx)?
+            .clone()
+            .try_acquire_owned()
+            .ok()
+    }
+
+    /// Whether the proxy is banned for this chain's host
+    pub fn is_banned(&self, proxy_idx: usize) -> bool {
+        self.arbiter.is_banned(proxy_idx, self.host())
+    }
+
+    /// Proxies this chain may use right now
+    pub fn available(&self) -> Vec<usize> {
+        (0..self.arbiter.proxy_count())
+            .filter(|&idx| !self.is_banned(idx))
+            .collect()
+    }
+
+    /// Records a request that went through the proxy, lifting any ban on this host
+    pub fn record_success(&self, proxy_idx: usize) {
+        self.arbiter.record(proxy_idx, self.slot(), true);
+    }
+
+    /// Records a proxy failure against this chain's host
+    ///
+    /// Returns `true` when this failure got the proxy banned for the host.
+    pub fn record_failure(&self, proxy_idx: usize) -> bool {
+        self.arbiter.record(proxy_idx, self.slot(), false)
+    }
+}
+
+/// Host part of an RPC URL, lowercased
+fn target_host(url: &str) -> String {
+    let without_scheme = url.split_once("://").map_or(url, |(_, rest)| rest);
+    let authority = without_scheme
+        .split(['/', '?', '#'])
+        .next()
+        .unwrap_or(without_scheme);
+    let host = authority.rsplit_once('@').map_or(authority, |(_, h)| h);
+    let host = match host.rsplit_once(':') {
+        Some((name, port)) if port.chars().all(|c| c.is_ascii_digit()) => name,
+        _ => host,
+    };
+    host.to_ascii_lowercase()
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    fn arbiter(threshold: u32) -> ProxyArbiter {
+        let chains = [
+            ChainShare {
+                name: "tempo".to_string(),
+                target: "https://rpc.moderato.tempo.xyz".to_string(),
+                quota: 0.75,
+            },
+            ChainShare {
+                name: "rise".to_string(),
+                target: "https://testnet.riselabs.xyz:443/rpc".to_string(),
+                quota: 0.25,
+            },
+        ];
+        ProxyArbiter::new(
+            2,
+            &chains,
+            ArbiterConfig {
+                per_proxy_concurrency: 4,
+                failure_threshold: threshold,
+                ..Default::default()
+            },
+        )
+    }
+
+    #[test]
+    fn test_concurrency_split_by_quota() {
+        let arbiter = arbiter(3);
+        let tempo = arbiter.chain("tempo").unwrap();
+        let rise = arbiter.chain("rise").unwrap();
+        assert_eq!((tempo.concurrency(), rise.concurrency()), (3, 1));
+        assert_eq!(rise.host(), "testnet.riselabs.xyz");
+
+        let held = rise.try_acquire(0).unwrap();
+        assert!(rise.try_acquire(0).is_none());
+        // Other proxies and other chains have their own slots
+        assert!(rise.try_acquire(1).is_some());
+        assert!(tempo.try_acquire(0).is_some());
+        drop(held);
+        assert!(rise.try_acquire(0).is_some());
+    }
+
+    #[test]
+    fn test_bans_are_scoped_to_target_host() {
+        let arbiter = arbiter(2);
+        let tempo = arbiter.chain("tempo").unwrap();
+        let rise = arbiter.chain("rise").unwrap();
+
+        assert!(!tempo.record_failure(0));
+        tempo.record_success(0);
+        assert!(!tempo.record_failure(0));
+        assert!(tempo.record_failure(0));
+
+        assert!(tempo.is_banned(0));
+        assert_eq!(tempo.available(), vec![1]);
+        // Rise still reaches its own RPC through proxy 0
+        assert!(!rise.is_banned(0));
+        assert!(arbiter.banned_everywhere().is_empty());
+
+        rise.record_failure(0);
+        rise.record_failure(0);
+        assert_eq!(arbiter.banned_everywhere(), vec![0]);
+
+        tempo.record_success(0);
+        assert!(!tempo.is_banned(0));
+    }
+}
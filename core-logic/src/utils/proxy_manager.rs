@@ -1,6 +1,7 @@
 use crate::config::ProxyConfig;
 use anyhow::{Context, Result};
 use std::fs;
+use std::ops::Range;
 use std::path::Path;
 use tracing::{info, warn};
 
@@ -55,4 +56,46 @@ impl ProxyManager {
         info!("Loaded {} proxies from {}", proxies.len(), Self::PROXY_FILE);
         Ok(proxies)
     }
+
+    /// Splits a shared proxy pool into disjoint per-chain slices
+    ///
+    /// Each chain gets a contiguous range of proxy indices sized by its share
+    /// of the total quota, so chains running in one process never hand out the
+    /// same proxy. Leftover proxies from rounding go to the largest remainders.
+    /// A quota of 0 (or a negative one) gets an empty range, i.e. direct
+    /// connections.
+    ///
+    /// # Arguments
+    ///
+    /// * `total` - Number of proxies in the shared pool
+    /// * `quotas` - Relative share per chain, in chain order
+    pub fn partition(total: usize, quotas: &[f64]) -> Vec<Range<usize>> {
+        let weights: Vec<f64> = quotas.iter().map(|q| q.max(0.0)).collect();
+        let sum: f64 = weights.iter().sum();
+        if sum <= 0.0 || total == 0 {
+            return vec![0..0; quotas.len()];
+        }
+
+        let exact: Vec<f64> = weights.iter().map(|w| w / sum * total as f64).collect();
+        let mut sizes: Vec<usize> = exact.iter().map(|e| e.floor() as usize).collect();
+
+        let mut by_remainder: Vec<usize> = (0..sizes.len()).filter(|&i| weights[i] > 0.0).collect();
+        by_remainder.sort_by(|&a, &b| {
+            (exact[b] - exact[b].floor()).total_cmp(&(exact[a] - exact[a].floor()))
+        });
+        let assigned: usize = sizes.iter().sum();
+        for &i in by_remainder.iter().cycle().take(total - assigned) {
+            sizes[i] += 1;
+        }
+
+        let mut start = 0;
+        sizes
+            .into_iter()
+            .map(|size| {
+                let range = start..start + size;
+                start += size;
+                range
+            })
+            .collect()
+    }
 }
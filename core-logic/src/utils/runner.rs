@@ -1,19 +1,150 @@
-use crate::traits::Spammer;
+use crate::metrics::MetricsCollector;
+use crate::traits::{Spammer, SpammerStats};
 use anyhow::Result;
+use std::collections::HashMap;
+use std::sync::atomic::{AtomicUsize, Ordering};
+use std::sync::Arc;
+use std::time::Duration;
 use tokio::signal;
-use tokio::task::JoinSet;
+use tokio::sync::watch;
+use tokio::task::{Id, JoinSet};
+use tokio::time::MissedTickBehavior;
 use tokio_util::sync::CancellationToken;
-use tracing::{error, info, Instrument};
+use tracing::{error, info, warn, Instrument};
 
-pub struct WorkerRunner;
+/// How [`WorkerRunner`] brings workers up
+///
+/// The default starts every worker at once, as `run_spammers` always did.
+#[derive(Debug, Clone, PartialEq)]
+pub struct RampUp {
+    /// Workers started per step, 0 starts all at once
+    pub batch: usize,
+    /// Time between steps
+    pub interval: Duration,
+    /// Failure percentage above which no workers are added
+    pub max_error_rate: Option<f64>,
+    /// Tasks finished before the failure rate is judged again; until then
+    /// the last verdict stands
+    pub min_samples: u64,
+}
+
+impl Default for RampUp {
+    fn default() -> Self {
+        Self {
+            batch: 0,
+            interval: Duration::from_secs(5),
+            max_error_rate: None,
+            min_samples: 20,
+        }
+    }
+}
+
+/// Changes the worker count of a running [`WorkerRunner`] from elsewhere
+#[derive(Debug, Clone)]
+pub struct ScaleHandle {
+    target: Arc<watch::Sender<usize>>,
+    running: Arc<AtomicUsize>,
+    capacity: usize,
+    shutdown: CancellationToken,
+}
+
+impl ScaleHandle {
+    /// Sets the number of workers to run, at most the number of spammers
+    ///
+    /// Workers above `n` are cancelled right away; new ones are started in
+    /// the runner's ramp-up steps. Returns the target actually set.
+    pub fn scale(&self, n: usize) -> usize {
+        let n = n.min(self.capacity);
+        self.target.send_replace(n);
+        n
+    }
+
+    pub fn target(&self) -> usize {
+        *self.target.borrow()
+    }
+
+    pub fn running(&self) -> usize {
+        self.running.load(Ordering::SeqCst)
+    }
+
+    /// Spammers the runner was given
+    pub fn capacity(&self) -> usize {
+        self.capacity
+    }
+
+    /// Stops all workers like Ctrl+C does
+    pub fn stop(&self) {
+        self.shutdown.cancel();
+    }
+}
+
+enum Slot {
+    Idle,
+    Running(CancellationToken),
+    /// Cancelled by scaling down, idle again once its task returns
+    Stopping,
+    /// Ended on its own or failed; not started again
+    Done,
+}
+
+pub struct WorkerRunner {
+    spammers: Vec<Arc<dyn Spammer>>,
+    ramp: RampUp,
+    metrics: &'static MetricsCollector,
+    handle: ScaleHandle,
+}
 
 impl WorkerRunner {
     /// Spawns a list of spammers as concurrent tasks and waits for them.
-    pub async fn run_spammers(spammers: Vec<Box<dyn Spammer>>) -> Result<()> {
-        let mut set = JoinSet::new();
+    ///
+    /// Returns the stats of all spammers combined, which are also logged as
+    /// a summary and kept for [`MetricsCollector`] exports.
+    pub async fn run_spammers(spammers: Vec<Box<dyn Spammer>>) -> Result<SpammerStats> {
+        Self::new(spammers).run().await
+    }
+
+    /// A runner for `spammers`, all of them wanted
+    pub fn new(spammers: Vec<Box<dyn Spammer>>) -> Self {
+        let capacity = spammers.len();
+        Self {
+            spammers: spammers.into_iter().map(Arc::from).collect(),
+            ramp: RampUp::default(),
+            metrics: MetricsCollector::global(),
+            handle: ScaleHandle {
+                target: Arc::new(watch::channel(capacity).0),
+                running: Arc::new(AtomicUsize::new(0)),
+                capacity,
+                shutdown: CancellationToken::new(),
+            },
+        }
+    }
+
+    pub fn with_ramp_up(mut self, ramp: RampUp) -> Self {
+        self.ramp = ramp;
+        self
+    }
 
-        // Create a cancellation token for graceful shutdown
-        let token = CancellationToken::new();
+    /// Starts with `n` workers instead of all of them
+    pub fn with_target(self, n: usize) -> Self {
+        self.handle.scale(n);
+        self
+    }
+
+    /// Collector whose task counts gate the ramp-up, the global one by
+    /// default
+    pub fn with_metrics(mut self, metrics: &'static MetricsCollector) -> Self {
+        self.metrics = metrics;
+        self
+    }
+
+    pub fn handle(&self) -> ScaleHandle {
+        self.handle.clone()
+    }
+
+    /// Runs workers until Ctrl+C, [`ScaleHandle::stop`] or every spammer
+    /// has ended on its own
+    pub async fn run(self) -> Result<SpammerStats> {
+        let token = self.handle.shutdown.clone();
         let cloned_token = token.clone();
 
         // Spawn a task to listen for Ctrl+C
@@ -30,42 +161,103 @@ impl WorkerRunner {
         });
 
         let start_time = std::time::Instant::now();
-        info!("Starting {} spammer workers...", spammers.len());
+        let mut target = self.handle.target.subscribe();
+        info!(
+            "Starting {} of {} spammer workers...",
+            *target.borrow(),
+            self.spammers.len()
+        );
 
-        for (i, spammer) in spammers.into_iter().enumerate() {
-            // Move spammer into the async block
-            let id = i + 1;
-            let span = tracing::info_span!("worker", worker_id = format!("{:03}", id));
-            let child_token = token.clone();
+        let mut slots: Vec<Slot> = self.spammers.iter().map(|_| Slot::Idle).collect();
+        let mut tasks = HashMap::new();
+        let mut set = JoinSet::new();
+        let mut total = SpammerStats::default();
+        let mut steps = tokio::time::interval(self.ramp.interval);
+        // Steps skipped while at the target must not fire in a burst later
+        steps.set_missed_tick_behavior(MissedTickBehavior::Delay);
+        // Task counts when the failure rate was last judged
+        let mut window = (self.metrics.tasks_total(), self.metrics.tasks_failed());
+        let mut healthy = true;
 
-            set.spawn(
-                async move {
-                    // We don't log "Worker {} starting" here because it might clutter if we strictly follow user format,
-                    // but for debugging it's fine. The span will attach WK ID.
-                    // info!("Worker {} starting...", id);
-                    // Context is already in span.
+        loop {
+            let running = count(&slots, |slot| matches!(slot, Slot::Running(_)));
+            let idle = count(&slots, |slot| matches!(slot, Slot::Idle));
+            self.handle.running.store(running, Ordering::SeqCst);
+            if count(&slots, |slot| !matches!(slot, Slot::Done)) == 0 {
+                break;
+            }
+            let wanted = *target.borrow_and_update();
 
-                    match spammer.start(child_token).await {
-                        Ok(stats) => Ok(stats),
+            tokio::select! {
+                _ = token.cancelled() => break,
+                Ok(()) = target.changed() => {
+                    let wanted = *target.borrow();
+                    if wanted < running {
+                        info!("Scaling down from {} to {} workers", running, wanted);
+                    }
+                    // Newest workers go first
+                    let mut excess = running.saturating_sub(wanted);
+                    for slot in slots.iter_mut().rev() {
+                        if excess == 0 {
+                            break;
+                        }
+                        if let Slot::Running(worker) = slot {
+                            worker.cancel();
+                            *slot = Slot::Stopping;
+                            excess -= 1;
+                        }
+                    }
+                }
+                _ = steps.tick(), if running < wanted && idle > 0 => {
+                    let counts = (self.metrics.tasks_total(), self.metrics.tasks_failed());
+                    let (done, failed) = (counts.0 - window.0, counts.1 - window.1);
+                    if done >= self.ramp.min_samples {
+                        window = counts;
+                        let rate = failed as f64 * 100.0 / done as f64;
+                        healthy = self.ramp.max_error_rate.is_none_or(|max| rate <= max);
+                        if !healthy {
+                            warn!(
+                                "Holding at {} workers: {:.1}% of the last {} tasks failed",
+                                running, rate, done
+                            );
+                        }
+                    }
+                    if healthy {
+                        let missing = wanted - running;
+                        let batch = match self.ramp.batch {
+                            0 => missing,
+                            batch => batch.min(missing),
+                        };
+                        tasks.extend(self.start(&mut slots, &mut set, &token, batch));
+                    }
+                }
+                Some(res) = set.join_next_with_id() => {
+                    let (id, result) = match res {
+                        Ok((id, result)) => (id, result),
                         Err(e) => {
-                            error!("Worker {} failed: {:?}", id, e);
-                            Err(e)
+                            error!("A worker task panicked or failed to join: {:?}", e);
+                            (e.id(), Err(anyhow::anyhow!("worker panicked")))
                         }
+                    };
+                    let Some(idx) = tasks.remove(&id) else {
+                        continue;
+                    };
+                    if let Ok(stats) = &result {
+                        total.merge(stats);
                     }
+                    slots[idx] = match (&slots[idx], result) {
+                        (Slot::Stopping, Ok(_)) => Slot::Idle,
+                        _ => Slot::Done,
+                    };
                 }
-                .instrument(span),
-            );
+            }
         }
 
-        let mut total_success = 0;
-        let mut total_failed = 0;
-
+        // Cancel what is still running and collect the rest
+        token.cancel();
         while let Some(res) = set.join_next().await {
             match res {
-                Ok(Ok(stats)) => {
-                    total_success += stats.success;
-                    total_failed += stats.failed;
-                }
+                Ok(Ok(stats)) => total.merge(&stats),
                 Ok(Err(_)) => {
                     // Already logged in thread
                 }
@@ -74,24 +266,171 @@ impl WorkerRunner {
                 }
             }
         }
-
-        let total_duration = start_time.elapsed();
-        let total = total_success + total_failed;
-        let rate = if total > 0 {
-            (total_success as f64 / total as f64) * 100.0
-        } else {
-            0.0
-        };
+        self.handle.running.store(0, Ordering::SeqCst);
+        total.run_duration_ms = start_time.elapsed().as_millis() as u64;
 
         info!("🛑 Shutdown Complete.");
-        info!(
-            "Total Time: {:.1}s | Total Success: {} | Total Fail: {} | Success Rate: {:.2}%",
-            total_duration.as_secs_f64(),
-            total_success,
-            total_failed,
-            rate
-        );
+        for line in total.report().lines() {
+            info!("{}", line);
+        }
+        MetricsCollector::global().set_run_summary(total.clone());
+
+        Ok(total)
+    }
+
+    /// Starts up to `n` idle spammers, lowest first, returning their task
+    /// ids and slots
+    fn start(
+        &self,
+        slots: &mut [Slot],
+        set: &mut JoinSet<Result<SpammerStats>>,
+        token: &CancellationToken,
+        n: usize,
+    ) -> Vec<(Id, usize)> {
+        let mut started = Vec::new();
+        for (idx, slot) in slots.iter_mut().enumerate() {
+            if started.len() == n {
+                break;
+            }
+            if !matches!(slot, Slot::Idle) {
+                continue;
+            }
+            let id = idx + 1;
+            let span = tracing::info_span!("worker", worker_id = format!("{:03}", id));
+            let worker = token.child_token();
+            let spammer = self.spammers[idx].clone();
+            let child_token = worker.clone();
+            let handle = set.spawn(
+                async move {
+                    match spammer.start(child_token).await {
+                        Ok(stats) => Ok(stats),
+                        Err(e) => {
+                            error!("Worker {} failed: {:?}", id, e);
+                            Err(e)
+                        }
+                    }
+                }
+                .instrument(span),
+            );
+            *slot = Slot::Running(worker);
+            started.push((handle.id(), idx));
+        }
+        if !started.is_empty() {
+            info!(
+                "Started {} workers ({} running)",
+                started.len(),
+                count(slots, |slot| matches!(slot, Slot::Running(_)))
+            );
+        }
+        started
+    }
+}
+
+fn count(slots: &[Slot], pred: impl Fn(&Slot) -> bool) -> usize {
+    slots.iter().filter(|slot| pred(slot)).count()
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use crate::config::SpamConfig;
+    use async_trait::async_trait;
+
+    /// Runs until cancelled, counting its starts
+    struct Waiter(Arc<AtomicUsize>);
+
+    #[async_trait]
+    impl Spammer for Waiter {
+        async fn new(_config: SpamConfig) -> Result<Self> {
+            unreachable!()
+        }
+
+        async fn start(&self, token: CancellationToken) -> Result<SpammerStats> {
+            self.0.fetch_add(1, Ordering::SeqCst);
+            token.cancelled().await;
+            Ok(SpammerStats::default())
+        }
+
+        async fn stop(&self) -> Result<()> {
+            Ok(())
+        }
+    }
+
+    fn waiters(n: usize, starts: &Arc<AtomicUsize>) -> Vec<Box<dyn Spammer>> {
+        (0..n)
+            .map(|_| Box::new(Waiter(starts.clone())) as Box<dyn Spammer>)
+            .collect()
+    }
+
+    async fn wait_for(handle: &ScaleHandle, running: usize) {
+        tokio::time::timeout(Duration::from_secs(5), async {
+            while handle.running() != running {
+                tokio::time::sleep(Duration::from_millis(5)).await;
+            }
+        })
+        .await
+        .unwrap_or_else(|_| panic!("{} workers running, wanted {}", handle.running(), running));
+    }
+
+    fn ramp(batch: usize, interval_ms: u64) -> RampUp {
+        RampUp {
+            batch,
+            interval: Duration::from_millis(interval_ms),
+            ..RampUp::default()
+        }
+    }
+
+    #[tokio::test]
+    async fn test_staged_start_and_scaling() {
+        let starts = Arc::new(AtomicUsize::new(0));
+        let runner = WorkerRunner::new(waiters(3, &starts)).with_ramp_up(ramp(1, 100));
+        let handle = runner.handle();
+        let run = tokio::spawn(runner.run());
+
+        wait_for(&handle, 1).await;
+        tokio::time::sleep(Duration::from_millis(30)).await;
+        assert_eq!(handle.running(), 1);
+        wait_for(&handle, 3).await;
+
+        assert_eq!(handle.scale(1), 1);
+        wait_for(&handle, 1).await;
+        // Stopped workers can be started again, never above the spammers given
+        assert_eq!(handle.scale(10), 3);
+        wait_for(&handle, 3).await;
+        assert_eq!(starts.load(Ordering::SeqCst), 5);
+
+        handle.stop();
+        run.await.unwrap().unwrap();
+        assert_eq!(handle.running(), 0);
+    }
+
+    #[tokio::test]
+    async fn test_high_error_rate_holds_the_ramp() {
+        let metrics: &'static MetricsCollector = Box::leak(Box::default());
+        let starts = Arc::new(AtomicUsize::new(0));
+        let runner = WorkerRunner::new(waiters(2, &starts))
+            .with_ramp_up(RampUp {
+                max_error_rate: Some(50.0),
+                min_samples: 2,
+                ..ramp(1, 50)
+            })
+            .with_metrics(metrics);
+        let handle = runner.handle();
+        let run = tokio::spawn(runner.run());
+
+        wait_for(&handle, 1).await;
+        for _ in 0..4 {
+            metrics.record_task("t01", Duration::ZERO, false);
+        }
+        tokio::time::sleep(Duration::from_millis(150)).await;
+        assert_eq!(handle.running(), 1);
+
+        for _ in 0..4 {
+            metrics.record_task("t01", Duration::ZERO, true);
+        }
+        wait_for(&handle, 2).await;
 
-        Ok(())
+        handle.stop();
+        run.await.unwrap().unwrap();
     }
 }
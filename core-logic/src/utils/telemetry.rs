@@ -0,0 +1,132 @@
+//! # Core Logic - Trace Export
+//!
+//! Spans can go to an OpenTelemetry collector such as Jaeger over OTLP/gRPC,
+//! next to the console and file logs. Export needs the `otel` feature;
+//! [`setup_logger_with_tracing`](super::setup_logger_with_tracing) refuses an
+//! [`OtlpConfig`] without it. Spans are batched and sent from a background
+//! thread, and [`shutdown_tracing`] sends what is left before the process
+//! exits.
+
+use anyhow::Result;
+use std::collections::BTreeMap;
+use tracing::Subscriber;
+use tracing_subscriber::registry::LookupSpan;
+use tracing_subscriber::Layer;
+
+/// Where spans are exported and how many of them
+#[derive(Debug, Clone, PartialEq)]
+pub struct OtlpConfig {
+    /// OTLP/gRPC endpoint of the collector, port 4317 for Jaeger
+    pub endpoint: String,
+    /// `service.name` the spans are filed under
+    pub service_name: String,
+    /// Share of traces kept, from 0.0 to 1.0
+    pub sample_ratio: f64,
+    /// Further resource attributes, such as the host or campaign
+    pub attributes: BTreeMap<String, String>,
+}
+
+impl Default for OtlpConfig {
+    fn default() -> Self {
+        Self {
+            endpoint: "http://localhost:4317".to_string(),
+            service_name: "core-logic".to_string(),
+            sample_ratio: 1.0,
+            attributes: BTreeMap::new(),
+        }
+    }
+}
+
+/// Boxed layer the logger puts under its console and file layers
+pub(crate) type ExportLayer<S> = Box<dyn Layer<S> + Send + Sync>;
+
+#[cfg(feature = "otel")]
+mod export {
+    use super::{ExportLayer, OtlpConfig};
+    use anyhow::{Context, Result};
+    use opentelemetry::trace::TracerProvider as _;
+    use opentelemetry::KeyValue;
+    use opentelemetry_otlp::{SpanExporter, WithExportConfig};
+    use opentelemetry_sdk::trace::{Sampler, SdkTracerProvider};
+    use opentelemetry_sdk::Resource;
+    use std::sync::{Mutex, PoisonError};
+    use tracing::Subscriber;
+    use tracing_subscriber::filter::LevelFilter;
+    use tracing_subscriber::registry::LookupSpan;
+    use tracing_subscriber::Layer;
+
+    /// Provider behind the installed layer, kept to flush it on shutdown
+    static PROVIDER: Mutex<Option<SdkTracerProvider>> = Mutex::new(None);
+
+    pub(super) fn layer<S>(config: &OtlpConfig) -> Result<ExportLayer<S>>
+    where
+        S: Subscriber + for<'a> LookupSpan<'a> + Send + Sync,
+    {
+        let exporter = SpanExporter::builder()
+            .with_tonic()
+            .with_endpoint(&config.endpoint)
+            .build()
+            .with_context(|| format!("Failed to set up OTLP export to {}", config.endpoint))?;
+        let resource = Resource::builder()
+            .with_service_name(config.service_name.clone())
+            .with_attributes(
+                config
+                    .attributes
+                    .iter()
+                    .map(|(key, value)| KeyValue::new(key.clone(), value.clone())),
+            )
+            .build();
+        // A sampled task keeps all its child spans, whatever the ratio
+        let sampler = Sampler::ParentBased(Box::new(Sampler::TraceIdRatioBased(
+            config.sample_ratio.clamp(0.0, 1.0),
+        )));
+        let provider = SdkTracerProvider::builder()
+            .with_batch_exporter(exporter)
+            .with_sampler(sampler)
+            .with_resource(resource)
+            .build();
+        let tracer = provider.tracer("core-logic");
+        *PROVIDER.lock().unwrap_or_else(PoisonError::into_inner) = Some(provider);
+
+        Ok(tracing_opentelemetry::layer()
+            .with_tracer(tracer)
+            .with_filter(LevelFilter::INFO)
+            .boxed())
+    }
+
+    pub(super) fn shutdown() {
+        let provider = PROVIDER
+            .lock()
+            .unwrap_or_else(PoisonError::into_inner)
+            .take();
+        if let Some(provider) = provider {
+            if let Err(e) = provider.shutdown() {
+                eprintln!("Failed to flush exported traces: {}", e);
+            }
+        }
+    }
+}
+
+/// Layer sending INFO and higher spans to the collector in `config`
+pub(crate) fn export_layer<S>(config: &OtlpConfig) -> Result<ExportLayer<S>>
+where
+    S: Subscriber + for<'a> LookupSpan<'a> + Send + Sync,
+{
+    #[cfg(feature = "otel")]
+    {
+        export::layer(config)
+    }
+    #[cfg(not(feature = "otel"))]
+    {
+        anyhow::bail!(
+            "Cannot export traces to {}: core-logic was built without the `otel` feature",
+            config.endpoint
+        )
+    }
+}
+
+/// Sends the spans still queued and stops exporting; does nothing without export
+pub fn shutdown_tracing() {
+    #[cfg(feature = "otel")]
+    export::shutdown();
+}
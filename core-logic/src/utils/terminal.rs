@@ -0,0 +1,102 @@
+//! # Core Logic - Terminal Output
+//!
+//! Windows consoles only interpret ANSI escape codes once virtual terminal
+//! processing is switched on, and legacy consoles cannot at all. The first
+//! call to [`ansi_supported`] or [`colors_enabled`] enables it where needed
+//! and decides for the rest of the process whether stdout gets escape codes.
+//! Output is plain text when stdout is not a terminal (pipes, log files,
+//! services), when `TERM=dumb`, or when `NO_COLOR` is set.
+
+use nu_ansi_term::{Color, Style};
+use std::fmt::Display;
+use std::io::IsTerminal;
+use std::sync::OnceLock;
+
+static ANSI: OnceLock<bool> = OnceLock::new();
+
+/// Meaning of a piece of colored output
+#[derive(Debug, Clone, Copy, PartialEq, Eq)]
+pub enum Tone {
+    Success,
+    Warning,
+    Error,
+    Info,
+    Accent,
+    /// Token amounts
+    Amount,
+    /// Bold without a color, for headings
+    Strong,
+}
+
+impl Tone {
+    fn style(self) -> Style {
+        match self {
+            Tone::Success => Color::Green.normal(),
+            Tone::Warning => Color::Yellow.normal(),
+            Tone::Error => Color::Red.normal(),
+            Tone::Info => Color::Cyan.normal(),
+            Tone::Accent => Color::Magenta.bold(),
+            Tone::Amount => Color::Fixed(208).normal(),
+            Tone::Strong => Style::new().bold(),
+        }
+    }
+
+    /// `text` in this tone, or as is when `ansi` is false
+    pub fn paint_if(self, ansi: bool, text: impl Display) -> String {
+        if ansi {
+            self.style().paint(text.to_string()).to_string()
+        } else {
+            text.to_string()
+        }
+    }
+}
+
+/// Whether stdout is a terminal that interprets ANSI escape codes
+///
+/// Enables virtual terminal processing on Windows the first time it is called.
+pub fn ansi_supported() -> bool {
+    *ANSI.get_or_init(|| {
+        if !std::io::stdout().is_terminal() {
+            return false;
+        }
+        if std::env::var("TERM").is_ok_and(|term| term == "dumb") {
+            return false;
+        }
+        enable_virtual_terminal()
+    })
+}
+
+/// Whether stdout output should be colored: [`ansi_supported`] and no `NO_COLOR`
+pub fn colors_enabled() -> bool {
+    ansi_supported() && std::env::var_os("NO_COLOR").is_none_or(|value| value.is_empty())
+}
+
+/// `text` in `tone` when colors are enabled, otherwise plain
+pub fn paint(tone: Tone, text: impl Display) -> String {
+    tone.paint_if(colors_enabled(), text)
+}
+
+#[cfg(windows)]
+fn enable_virtual_terminal() -> bool {
+    nu_ansi_term::enable_ansi_support().is_ok()
+}
+
+#[cfg(not(windows))]
+fn enable_virtual_terminal() -> bool {
+    true
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    #[test]
+    fn test_plain_without_ansi() {
+        assert_eq!(Tone::Error.paint_if(false, "ERROR"), "ERROR");
+        assert_eq!(
+            Tone::Amount.paint_if(true, "1.5K"),
+            "\x1b[38;5;208m1.5K\x1b[0m"
+        );
+        assert_eq!(Tone::Success.paint_if(true, 7), "\x1b[32m7\x1b[0m");
+    }
+}
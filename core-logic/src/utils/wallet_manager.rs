@@ -1,15 +1,15 @@
 use crate::security::SecurityUtils;
 use anyhow::{anyhow, Context, Result};
-use serde::Deserialize;
+use serde::{Deserialize, Serialize};
 use serde_json::Value;
-use std::collections::HashMap;
+use std::collections::{BTreeMap, BTreeSet, HashMap};
 use std::fs;
 use std::path::{Path, PathBuf};
 use std::sync::Arc;
 use tokio::sync::Mutex;
 
 use std::fmt;
-use zeroize::{Zeroize, ZeroizeOnDrop};
+use zeroize::{Zeroize, ZeroizeOnDrop, Zeroizing};
 
 #[derive(Clone, Deserialize, Zeroize, ZeroizeOnDrop)]
 pub struct DecryptedWallet {
@@ -61,44 +61,335 @@ impl fmt::Debug for DecryptedWallet {
     }
 }
 
+/// A wallet file found by the index scan, described without decrypting it
+#[derive(Debug, Clone, PartialEq, Eq)]
+pub struct WalletEntry {
+    pub path: PathBuf,
+    /// Directory of the file relative to the scanned directory, empty at its top level
+    pub subdir: PathBuf,
+    /// The file's unencrypted `address` field, if any
+    pub address: Option<String>,
+    /// The file's unencrypted `tags` list merged with its `wallet-tags.json` tags
+    pub tags: Vec<String>,
+}
+
+/// Unencrypted fields read from a wallet file while indexing
+#[derive(Default, Deserialize)]
+struct WalletHeader {
+    #[serde(default)]
+    address: Option<String>,
+    #[serde(default)]
+    tags: Vec<String>,
+}
+
+/// Tags of wallets by address, kept in `wallet-tags.json` next to the wallet files
+///
+/// Keyed by address rather than file name, so renaming a wallet file or moving
+/// it to another subdirectory keeps its tags. Addresses are compared without
+/// regard to case.
+#[derive(Debug, Clone, Default, PartialEq, Eq, Serialize, Deserialize)]
+#[serde(transparent)]
+pub struct WalletTags {
+    by_address: BTreeMap<String, BTreeSet<String>>,
+}
+
+impl WalletTags {
+    /// File name of the tags file in the wallets directory
+    pub const FILE: &'static str = "wallet-tags.json";
+
+    /// Reads the tags file, empty when it does not exist
+    pub fn load(path: &Path) -> Result<Self> {
+        if !path.exists() {
+            return Ok(Self::default());
+        }
+        let content =
+            fs::read_to_string(path).with_context(|| format!("Failed to read {:?}", path))?;
+        let tags: Self = serde_json::from_str(&content)
+            .with_context(|| format!("Invalid tags in {:?}", path))?;
+        Ok(Self {
+            by_address: tags
+                .by_address
+                .into_iter()
+                .map(|(address, tags)| (address.to_lowercase(), tags))
+                .collect(),
+        })
+    }
+
+    /// Writes the tags file, replacing it in one step
+    pub fn save(&self, path: &Path) -> Result<()> {
+        let tmp = path.with_extension("json.tmp");
+        fs::write(&tmp, serde_json::to_string_pretty(self)?)
+            .with_context(|| format!("Failed to write {:?}", tmp))?;
+        fs::rename(&tmp, path).with_context(|| format!("Failed to replace {:?}", path))?;
+        Ok(())
+    }
+
+    /// Tags of `address`
+    pub fn get(&self, address: &str) -> impl Iterator<Item = &str> {
+        self.by_address
+            .get(&address.to_lowercase())
+            .into_iter()
+            .flatten()
+            .map(String::as_str)
+    }
+
+    /// Tags `address`, false if it already had the tag
+    pub fn add(&mut self, address: &str, tag: &str) -> bool {
+        self.by_address
+            .entry(address.to_lowercase())
+            .or_default()
+            .insert(tag.to_string())
+    }
+
+    /// Removes a tag from `address`, false if it did not have it
+    pub fn remove(&mut self, address: &str, tag: &str) -> bool {
+        let key = address.to_lowercase();
+        let Some(tags) = self.by_address.get_mut(&key) else {
+            return false;
+        };
+        let removed = tags.remove(tag);
+        if tags.is_empty() {
+            self.by_address.remove(&key);
+        }
+        removed
+    }
+}
+
+/// Which wallet files a [`WalletManager`] indexes and how many it keeps decrypted
+#[derive(Debug, Clone, PartialEq, Eq)]
+pub struct WalletOptions {
+    /// Directory of wallet files (default: `wallet-json/` here or two levels up)
+    pub dir: Option<PathBuf>,
+    /// Only index wallets under this subdirectory of `dir` (default: its top level)
+    pub subdir: Option<PathBuf>,
+    /// Also index wallets in directories nested below it (default: false)
+    pub recursive: bool,
+    /// Only index files whose `tags` contain one of these (default: all)
+    pub tags: Vec<String>,
+    /// Decrypted wallets kept in memory, 0 = unlimited (default: 1024)
+    pub cache_size: usize,
+    /// Only keep this host's share of the selected wallets (default: all)
+    pub partition: Option<HostPartition>,
+}
+
+impl Default for WalletOptions {
+    fn default() -> Self {
+        Self {
+            dir: None,
+            subdir: None,
+            recursive: false,
+            tags: Vec::new(),
+            cache_size: WalletManager::DEFAULT_CACHE_SIZE,
+            partition: None,
+        }
+    }
+}
+
+/// One of `total` hosts sharing a wallet directory snapshot
+///
+/// Host `index` keeps the wallets whose index is `index` modulo `total`, so
+/// hosts with the same files and filters never use the same wallet.
+#[derive(Debug, Clone, Copy, PartialEq, Eq, Serialize, Deserialize)]
+#[serde(deny_unknown_fields)]
+pub struct HostPartition {
+    pub index: usize,
+    pub total: usize,
+}
+
+impl HostPartition {
+    pub fn new(index: usize, total: usize) -> Result<Self> {
+        let partition = Self { index, total };
+        partition.validate()?;
+        Ok(partition)
+    }
+
+    /// Fails unless `index` is below a non-zero `total`
+    pub fn validate(&self) -> Result<()> {
+        if self.index >= self.total {
+            return Err(anyhow!(
+                "Wallet partition index {} must be below its total {}",
+                self.index,
+                self.total
+            ));
+        }
+        Ok(())
+    }
+
+    /// Whether the wallet at `wallet_index` belongs to this host
+    pub fn contains(&self, wallet_index: usize) -> bool {
+        wallet_index % self.total == self.index
+    }
+}
+
+impl fmt::Display for HostPartition {
+    fn fmt(&self, f: &mut fmt::Formatter<'_>) -> fmt::Result {
+        write!(f, "{}/{}", self.index, self.total)
+    }
+}
+
+impl std::str::FromStr for HostPartition {
+    type Err = anyhow::Error;
+
+    /// Parses `index/total`, e.g. `2/5`
+    fn from_str(s: &str) -> Result<Self> {
+        let (index, total) = s
+            .split_once('/')
+            .ok_or_else(|| anyhow!("Expected a wallet partition as index/total, got {:?}", s))?;
+        let index = index
+            .trim()
+            .parse()
+            .with_context(|| format!("Invalid wallet partition index in {:?}", s))?;
+        let total = total
+            .trim()
+            .parse()
+            .with_context(|| format!("Invalid wallet partition total in {:?}", s))?;
+        Self::new(index, total)
+    }
+}
+
+impl WalletOptions {
+    /// Whether only part of the wallet files is wanted, in which case there is no `pv.txt` fallback
+    fn selects_subset(&self) -> bool {
+        self.subdir.is_some() || !self.tags.is_empty()
+    }
+}
+
 #[derive(Debug)]
 enum WalletSource {
-    JsonFile(PathBuf),
-    RawKey(String),
+    JsonFile(WalletEntry),
+    RawKey(Zeroizing<String>),
+}
+
+/// Decrypted wallets by index, dropping the least recently used beyond `capacity`
+///
+/// [`DecryptedWallet`] zeroizes itself on drop, so an evicted wallet is wiped
+/// as soon as no caller holds it any more.
+struct DecryptedCache {
+    capacity: usize,
+    tick: u64,
+    entries: HashMap<usize, (u64, Arc<DecryptedWallet>)>,
+    recency: BTreeMap<u64, usize>,
+}
+
+impl DecryptedCache {
+    fn new(capacity: usize) -> Self {
+        Self {
+            capacity,
+            tick: 0,
+            entries: HashMap::new(),
+            recency: BTreeMap::new(),
+        }
+    }
+
+    fn get(&mut self, index: usize) -> Option<Arc<DecryptedWallet>> {
+        self.tick += 1;
+        let (used, wallet) = self.entries.get_mut(&index)?;
+        self.recency.remove(used);
+        *used = self.tick;
+        self.recency.insert(self.tick, index);
+        Some(Arc::clone(wallet))
+    }
+
+    fn insert(&mut self, index: usize, wallet: Arc<DecryptedWallet>) {
+        self.tick += 1;
+        if let Some((used, _)) = self.entries.insert(index, (self.tick, wallet)) {
+            self.recency.remove(&used);
+        }
+        self.recency.insert(self.tick, index);
+
+        while self.capacity > 0 && self.entries.len() > self.capacity {
+            let Some((_, oldest)) = self.recency.pop_first() else {
+                break;
+            };
+            self.entries.remove(&oldest);
+        }
+    }
+
+    fn len(&self) -> usize {
+        self.entries.len()
+    }
 }
 
 pub struct WalletManager {
     sources: Vec<WalletSource>,
-    cache: Mutex<HashMap<usize, Arc<DecryptedWallet>>>,
+    tags_file: Option<PathBuf>,
+    cache: Mutex<DecryptedCache>,
 }
 
 impl WalletManager {
     const WALLETS_DIR: &'static str = "wallet-json";
     const PV_FILE: &'static str = "pv.txt";
+    const DEFAULT_CACHE_SIZE: usize = 1024;
 
+    /// Indexes `wallet-json/` with the default [`WalletOptions`], falling back to `pv.txt`
     pub fn new() -> Result<Self> {
+        Self::with_options(WalletOptions::default())
+    }
+
+    /// Indexes the wallet files selected by `options`
+    ///
+    /// Only the unencrypted `address` and `tags` of each file, and the
+    /// [`WalletTags`] of the wallets directory, are read here; wallets are
+    /// decrypted on demand by [`get_wallet`](Self::get_wallet).
+    /// `pv.txt` is only a fallback when no subdirectory or tag is asked for.
+    /// A [`HostPartition`] applies last, to the files or keys that remain.
+    pub fn with_options(options: WalletOptions) -> Result<Self> {
+        if let Some(partition) = &options.partition {
+            partition.validate()?;
+        }
+
         // Try current dir first, then workspace root (../../)
-        let candidates = vec![
-            PathBuf::from(Self::WALLETS_DIR),
-            PathBuf::from("../..").join(Self::WALLETS_DIR),
-        ];
+        let candidates = match &options.dir {
+            Some(dir) => vec![dir.clone()],
+            None => vec![
+                PathBuf::from(Self::WALLETS_DIR),
+                PathBuf::from("../..").join(Self::WALLETS_DIR),
+            ],
+        };
 
         let mut sources = Vec::new();
+        let mut tags_file = None;
 
-        for wallets_path in candidates {
+        for root in candidates {
+            let wallets_path = match &options.subdir {
+                Some(subdir) => root.join(subdir),
+                None => root.clone(),
+            };
             if wallets_path.exists() && wallets_path.is_dir() {
                 println!("[WalletManager] Scanning wallets in {:?}", wallets_path);
-                let mut entries: Vec<PathBuf> = fs::read_dir(&wallets_path)?
-                    .filter_map(|res| res.ok())
-                    .map(|e| e.path())
-                    .filter(|p| p.extension().is_some_and(|ext| ext == "json"))
-                    .collect();
+                let mut entries = Vec::new();
+                Self::scan_dir(
+                    &wallets_path,
+                    Path::new(""),
+                    options.recursive,
+                    &mut entries,
+                )?;
+                // Top-level files keep their indices when nested ones are added
+                entries.sort_by(|a, b| (&a.subdir, &a.path).cmp(&(&b.subdir, &b.path)));
+                let found = entries.len();
+
+                let tags_path = root.join(WalletTags::FILE);
+                let sidecar = WalletTags::load(&tags_path)?;
+                for entry in &mut entries {
+                    let Some(address) = &entry.address else {
+                        continue;
+                    };
+                    for tag in sidecar.get(address) {
+                        if !entry.tags.iter().any(|t| t == tag) {
+                            entry.tags.push(tag.to_string());
+                        }
+                    }
+                }
 
-                entries.sort();
+                entries.retain(|entry| {
+                    options.tags.is_empty()
+                        || entry.tags.iter().any(|tag| options.tags.contains(tag))
+                });
                 println!(
-                    "[WalletManager] Found {} wallet files in {:?}",
-                    entries.len(),
-                    wallets_path
+                    "[WalletManager] Found {} wallet files in {:?} ({} selected)",
+                    found,
+                    wallets_path,
+                    entries.len()
                 );
 
                 for entry in entries {
@@ -106,36 +397,72 @@ impl WalletManager {
                 }
 
                 // If we found wallets in one location, stop searching to avoid duplicates or confusion
-                if !sources.is_empty() {
+                if found > 0 {
+                    tags_file = Some(tags_path);
                     break;
                 }
             }
         }
 
         // Fallback to pv.txt if no JSON wallets found
-        if sources.is_empty() {
+        if sources.is_empty() && !options.selects_subset() {
             println!(
                 "[WalletManager] No wallets found in wallet-json/, checking for pv.txt fallback"
             );
             let pv_path = Path::new(Self::PV_FILE);
             if pv_path.exists() {
                 println!("[WalletManager] Loading raw keys from {}", Self::PV_FILE);
-                let content = fs::read_to_string(pv_path)?;
+                let content = Zeroizing::new(fs::read_to_string(pv_path)?);
                 for line in content.lines() {
                     let trimmed = line.trim();
                     if !trimmed.is_empty() && !trimmed.starts_with('#') {
-                        sources.push(WalletSource::RawKey(trimmed.to_string()));
+                        sources.push(WalletSource::RawKey(Zeroizing::new(trimmed.to_string())));
                     }
                 }
             }
         }
 
+        if let Some(partition) = options.partition {
+            let selected = sources.len();
+            sources = sources
+                .into_iter()
+                .enumerate()
+                .filter(|(index, _)| partition.contains(*index))
+                .map(|(_, source)| source)
+                .collect();
+            println!(
+                "[WalletManager] Partition {}: using {} of {} wallets",
+                partition,
+                sources.len(),
+                selected
+            );
+        }
+
         Ok(Self {
             sources,
-            cache: Mutex::new(HashMap::new()),
+            tags_file,
+            cache: Mutex::new(DecryptedCache::new(options.cache_size)),
         })
     }
 
+    /// Wallets from raw private keys instead of `wallet-json/` or `pv.txt`
+    ///
+    /// For callers that manage keys themselves; nothing is read from disk.
+    pub fn from_private_keys<I, S>(keys: I) -> Self
+    where
+        I: IntoIterator<Item = S>,
+        S: Into<String>,
+    {
+        Self {
+            sources: keys
+                .into_iter()
+                .map(|key| WalletSource::RawKey(Zeroizing::new(key.into())))
+                .collect(),
+            tags_file: None,
+            cache: Mutex::new(DecryptedCache::new(Self::DEFAULT_CACHE_SIZE)),
+        }
+    }
+
     /// Returns the number of available wallets
     pub fn count(&self) -> usize {
         self.sources.len()
@@ -147,7 +474,8 @@ impl WalletManager {
             .iter()
             .enumerate()
             .map(|(i, src)| match src {
-                WalletSource::JsonFile(path) => path
+                WalletSource::JsonFile(entry) => entry
+                    .path
                     .file_name()
                     .and_then(|n| n.to_str())
                     .unwrap_or("unknown.json")
@@ -157,6 +485,46 @@ impl WalletManager {
             .collect()
     }
 
+    /// Indexed wallet files with their positions, without decrypting
+    ///
+    /// Raw keys from `pv.txt` have no file and are skipped.
+    pub fn entries(&self) -> impl Iterator<Item = (usize, &WalletEntry)> {
+        self.sources
+            .iter()
+            .enumerate()
+            .filter_map(|(i, src)| match src {
+                WalletSource::JsonFile(entry) => Some((i, entry)),
+                WalletSource::RawKey(_) => None,
+            })
+    }
+
+    /// Tags of a wallet, from its file and `wallet-tags.json`
+    ///
+    /// Empty for raw keys and indices out of range.
+    pub fn tags(&self, index: usize) -> &[String] {
+        match self.sources.get(index) {
+            Some(WalletSource::JsonFile(entry)) => &entry.tags,
+            _ => &[],
+        }
+    }
+
+    /// Whether the wallet at `index` carries `tag`
+    pub fn has_tag(&self, index: usize, tag: &str) -> bool {
+        self.tags(index).iter().any(|t| t == tag)
+    }
+
+    /// The `wallet-tags.json` of the directory the wallets came from
+    ///
+    /// `None` when no wallet files were found.
+    pub fn tags_file(&self) -> Option<&Path> {
+        self.tags_file.as_deref()
+    }
+
+    /// Number of wallets currently held decrypted
+    pub async fn cached_count(&self) -> usize {
+        self.cache.lock().await.len()
+    }
+
     /// Get a decrypted wallet by index. Decrypts if not cached.
     /// Returns Arc<DecryptedWallet> to avoid cloning sensitive data.
     pub async fn get_wallet(
@@ -165,24 +533,71 @@ impl WalletManager {
         password: Option<&str>,
     ) -> Result<Arc<DecryptedWallet>> {
         // Check cache first
-        {
-            let cache = self.cache.lock().await;
-            if let Some(wallet) = cache.get(&index) {
-                return Ok(Arc::clone(wallet));
-            }
+        if let Some(wallet) = self.cache.lock().await.get(index) {
+            return Ok(wallet);
         }
 
         // Not in cache, decrypt
+        let wallet = Arc::new(self.decrypt(index, password)?);
+
+        // Store in cache
+        self.cache.lock().await.insert(index, Arc::clone(&wallet));
+
+        Ok(wallet)
+    }
+
+    /// Like [`get_wallet`](Self::get_wallet), but a wallet that is not cached
+    /// is decrypted without entering the cache
+    ///
+    /// For callers that only need the keys for a moment, e.g. to build a
+    /// signer: the wallet is zeroized as soon as the returned `Arc` drops.
+    pub async fn load_wallet(
+        &self,
+        index: usize,
+        password: Option<&str>,
+    ) -> Result<Arc<DecryptedWallet>> {
+        if let Some(wallet) = self.cache.lock().await.get(index) {
+            return Ok(wallet);
+        }
+        Ok(Arc::new(self.decrypt(index, password)?))
+    }
+
+    /// Decrypts the wallets one at a time, in index order
+    ///
+    /// Each wallet is read with [`load_wallet`](Self::load_wallet), so walking
+    /// a large directory keeps at most one extra wallet in memory.
+    pub fn stream<'a>(&'a self, password: Option<&'a str>) -> WalletStream<'a> {
+        WalletStream {
+            manager: self,
+            password,
+            next: 0,
+        }
+    }
+
+    // Helper for legacy support, loads ALL private keys
+    pub async fn get_private_keys(password: Option<String>) -> Result<Vec<Zeroizing<String>>> {
+        let password = password.map(Zeroizing::new);
+        let manager = Self::new()?;
+        let mut keys = Vec::with_capacity(manager.count());
+        let mut wallets = manager.stream(password.as_deref().map(String::as_str));
+        while let Some(wallet) = wallets.next().await {
+            let (_, w) = wallet?;
+            keys.push(Zeroizing::new(w.evm_private_key.clone()));
+        }
+        Ok(keys)
+    }
+
+    fn decrypt(&self, index: usize, password: Option<&str>) -> Result<DecryptedWallet> {
         let source = self.sources.get(index).context(format!(
             "Wallet index {} out of bounds (total: {})",
             index,
             self.sources.len()
         ))?;
-        let wallet = match source {
-            WalletSource::JsonFile(path) => Arc::new(Self::decrypt_json_wallet(path, password)?),
-            WalletSource::RawKey(key) => Arc::new(DecryptedWallet {
+        match source {
+            WalletSource::JsonFile(entry) => Self::decrypt_json_wallet(&entry.path, password),
+            WalletSource::RawKey(key) => Ok(DecryptedWallet {
                 mnemonic: "".to_string(),
-                evm_private_key: key.clone(),
+                evm_private_key: key.to_string(),
                 evm_address: "".to_string(),
                 sol_private_key: "".to_string(),
                 sol_address: "".to_string(),
@@ -195,26 +610,41 @@ impl WalletManager {
                 ton_private_key: "".to_string(),
                 ton_address: "".to_string(),
             }),
-        };
-
-        // Store in cache
-        {
-            let mut cache = self.cache.lock().await;
-            cache.insert(index, Arc::clone(&wallet));
         }
-
-        Ok(wallet)
     }
 
-    // Helper for legacy support, loads ALL private keys
-    pub async fn get_private_keys(password: Option<String>) -> Result<Vec<String>> {
-        let manager = Self::new()?;
-        let mut keys = Vec::new();
-        for i in 0..manager.count() {
-            let w = manager.get_wallet(i, password.as_deref()).await?;
-            keys.push(w.evm_private_key.clone());
+    /// Collects the `*.json` files below `dir`, `subdir` being its path relative to the scan root
+    fn scan_dir(
+        dir: &Path,
+        subdir: &Path,
+        recursive: bool,
+        entries: &mut Vec<WalletEntry>,
+    ) -> Result<()> {
+        for dir_entry in fs::read_dir(dir)?.filter_map(|res| res.ok()) {
+            let path = dir_entry.path();
+            let is_dir = dir_entry.file_type().is_ok_and(|kind| kind.is_dir());
+            if is_dir {
+                if recursive {
+                    let nested = subdir.join(dir_entry.file_name());
+                    Self::scan_dir(&path, &nested, recursive, entries)?;
+                }
+            } else if path.extension().is_some_and(|ext| ext == "json")
+                && dir_entry.file_name() != WalletTags::FILE
+            {
+                // Unreadable files are still indexed and fail when decrypted
+                let header: WalletHeader = fs::read_to_string(&path)
+                    .ok()
+                    .and_then(|content| serde_json::from_str(&content).ok())
+                    .unwrap_or_default();
+                entries.push(WalletEntry {
+                    path,
+                    subdir: subdir.to_path_buf(),
+                    address: header.address,
+                    tags: header.tags,
+                });
+            }
         }
-        Ok(keys)
+        Ok(())
     }
 
     fn decrypt_json_wallet(path: &Path, password: Option<&str>) -> Result<DecryptedWallet> {
@@ -264,3 +694,246 @@ impl WalletManager {
         ))
     }
 }
+
+/// Wallets decrypted one by one, see [`WalletManager::stream`]
+pub struct WalletStream<'a> {
+    manager: &'a WalletManager,
+    password: Option<&'a str>,
+    next: usize,
+}
+
+impl WalletStream<'_> {
+    /// The next wallet and its index, `None` once every wallet was returned
+    pub async fn next(&mut self) -> Option<Result<(usize, Arc<DecryptedWallet>)>> {
+        let index = self.next;
+        if index >= self.manager.count() {
+            return None;
+        }
+        self.next += 1;
+
+        Some(
+            self.manager
+                .load_wallet(index, self.password)
+                .await
+                .map(|wallet| (index, wallet)),
+        )
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    /// Writes a wallet file, only encrypting the key when `sealed` as scrypt is slow
+    fn write_wallet(dir: &Path, name: &str, key: &str, tags: &[&str], sealed: bool) {
+        fs::create_dir_all(dir).unwrap();
+        let encrypted = if sealed {
+            let secret = serde_json::json!({ "evm_private_key": key }).to_string();
+            let parts = SecurityUtils::encrypt_components(&secret, "pw").unwrap();
+            serde_json::json!({
+                "ciphertext": parts.ciphertext,
+                "iv": parts.iv,
+                "salt": parts.salt,
+                "tag": parts.tag,
+            })
+        } else {
+            serde_json::json!({})
+        };
+        let file = serde_json::json!({
+            "address": format!("addr-{}", key),
+            "tags": tags,
+            "encrypted": encrypted,
+        });
+        fs::write(dir.join(name), file.to_string()).unwrap();
+    }
+
+    fn campaign_dir(sealed: bool) -> tempfile::TempDir {
+        let dir = tempfile::tempdir().unwrap();
+        write_wallet(dir.path(), "a.json", "k1", &["airdrop"], sealed);
+        write_wallet(dir.path(), "b.json", "k2", &[], sealed);
+        write_wallet(
+            &dir.path().join("alpha"),
+            "c.json",
+            "k3",
+            &["airdrop"],
+            sealed,
+        );
+        write_wallet(
+            &dir.path().join("alpha/late"),
+            "d.json",
+            "k4",
+            &["late"],
+            sealed,
+        );
+        dir
+    }
+
+    fn options(dir: &tempfile::TempDir) -> WalletOptions {
+        WalletOptions {
+            dir: Some(dir.path().to_path_buf()),
+            ..Default::default()
+        }
+    }
+
+    fn names(manager: &WalletManager) -> Vec<String> {
+        manager.list_wallets()
+    }
+
+    #[test]
+    fn test_index_filters_by_subdir_and_tags() {
+        let dir = campaign_dir(false);
+
+        let top = WalletManager::with_options(options(&dir)).unwrap();
+        assert_eq!(names(&top), ["a.json", "b.json"]);
+        let (_, entry) = top.entries().next().unwrap();
+        assert_eq!(entry.address.as_deref(), Some("addr-k1"));
+        assert_eq!(entry.tags, ["airdrop"]);
+
+        let all = WalletManager::with_options(WalletOptions {
+            recursive: true,
+            ..options(&dir)
+        })
+        .unwrap();
+        assert_eq!(names(&all), ["a.json", "b.json", "c.json", "d.json"]);
+        let subdirs: Vec<_> = all.entries().map(|(_, e)| e.subdir.clone()).collect();
+        assert_eq!(subdirs[2], Path::new("alpha"));
+        assert_eq!(subdirs[3], Path::new("alpha/late"));
+
+        let alpha = WalletManager::with_options(WalletOptions {
+            subdir: Some("alpha".into()),
+            ..options(&dir)
+        })
+        .unwrap();
+        assert_eq!(names(&alpha), ["c.json"]);
+
+        let tagged = WalletManager::with_options(WalletOptions {
+            recursive: true,
+            tags: vec!["airdrop".into(), "late".into()],
+            ..options(&dir)
+        })
+        .unwrap();
+        assert_eq!(names(&tagged), ["a.json", "c.json", "d.json"]);
+
+        // Hosts split the selected wallets without overlap
+        let partition = |index| WalletOptions {
+            recursive: true,
+            partition: Some(HostPartition::new(index, 3).unwrap()),
+            ..options(&dir)
+        };
+        let first = WalletManager::with_options(partition(0)).unwrap();
+        assert_eq!(names(&first), ["a.json", "d.json"]);
+        let third = WalletManager::with_options(partition(2)).unwrap();
+        assert_eq!(names(&third), ["c.json"]);
+        assert!(WalletManager::with_options(WalletOptions {
+            partition: Some(HostPartition { index: 3, total: 3 }),
+            ..options(&dir)
+        })
+        .is_err());
+
+        // A subset never falls back to pv.txt
+        let none = WalletManager::with_options(WalletOptions {
+            tags: vec!["missing".into()],
+            ..options(&dir)
+        })
+        .unwrap();
+        assert_eq!(none.count(), 0);
+    }
+
+    #[test]
+    fn test_tags_file_follows_addresses() {
+        let dir = campaign_dir(false);
+        let tags_path = dir.path().join(WalletTags::FILE);
+        let mut tags = WalletTags::default();
+        assert!(tags.add("ADDR-K2", "funded"));
+        assert!(!tags.add("addr-k2", "funded"));
+        assert!(tags.add("addr-k3", "burned"));
+        tags.save(&tags_path).unwrap();
+
+        // Renaming the file keeps its tags
+        fs::rename(dir.path().join("b.json"), dir.path().join("z.json")).unwrap();
+
+        let manager = WalletManager::with_options(WalletOptions {
+            recursive: true,
+            ..options(&dir)
+        })
+        .unwrap();
+        // The tags file itself is not a wallet
+        assert_eq!(names(&manager), ["a.json", "z.json", "c.json", "d.json"]);
+        assert_eq!(manager.tags_file(), Some(tags_path.as_path()));
+        assert_eq!(manager.tags(1), ["funded"]);
+        assert_eq!(manager.tags(2), ["airdrop", "burned"]);
+        assert!(manager.has_tag(2, "burned"));
+        assert!(!manager.has_tag(0, "funded"));
+
+        let funded = WalletManager::with_options(WalletOptions {
+            recursive: true,
+            tags: vec!["funded".into()],
+            ..options(&dir)
+        })
+        .unwrap();
+        assert_eq!(names(&funded), ["z.json"]);
+
+        let mut tags = WalletTags::load(&tags_path).unwrap();
+        assert!(tags.remove("addr-k2", "funded"));
+        assert!(!tags.remove("addr-k2", "funded"));
+        assert_eq!(tags.get("addr-k2").count(), 0);
+        assert_eq!(tags.get("Addr-K3").collect::<Vec<_>>(), ["burned"]);
+    }
+
+    #[tokio::test]
+    async fn test_cache_evicts_least_recently_used() {
+        let dir = campaign_dir(true);
+        let manager = WalletManager::with_options(WalletOptions {
+            recursive: true,
+            cache_size: 2,
+            ..options(&dir)
+        })
+        .unwrap();
+
+        let first = manager.get_wallet(0, Some("pw")).await.unwrap();
+        assert_eq!(first.evm_private_key, "k1");
+        manager.get_wallet(1, Some("pw")).await.unwrap();
+        // Touch wallet 0 so wallet 1 is the oldest
+        assert!(Arc::ptr_eq(
+            &first,
+            &manager.get_wallet(0, Some("pw")).await.unwrap()
+        ));
+        manager.get_wallet(2, Some("pw")).await.unwrap();
+        assert_eq!(manager.cached_count().await, 2);
+
+        // Loading skips the cache and evicts nothing
+        let loaded = manager.load_wallet(3, Some("pw")).await.unwrap();
+        assert_eq!(loaded.evm_private_key, "k4");
+        assert_eq!(manager.cached_count().await, 2);
+
+        let cache = manager.cache.lock().await;
+        assert!(cache.entries.contains_key(&0));
+        assert!(!cache.entries.contains_key(&1));
+        assert!(cache.entries.contains_key(&2));
+    }
+
+    #[tokio::test]
+    async fn test_stream_decrypts_without_filling_cache() {
+        let dir = campaign_dir(true);
+        let manager = WalletManager::with_options(WalletOptions {
+            recursive: true,
+            ..options(&dir)
+        })
+        .unwrap();
+
+        let mut keys = Vec::new();
+        let mut wallets = manager.stream(Some("pw"));
+        while let Some(wallet) = wallets.next().await {
+            let (index, wallet) = wallet.unwrap();
+            keys.push((index, wallet.evm_private_key.clone()));
+        }
+        assert_eq!(
+            keys,
+            [(0, "k1"), (1, "k2"), (2, "k3"), (3, "k4")].map(|(i, k)| (i, k.to_string()))
+        );
+        assert_eq!(manager.cached_count().await, 0);
+
+        let mut wrong = manager.stream(Some("wrong"));
+        assert!(wrong.next().await.unwrap().is_err());
+    }
+}
@@ -1,4 +1,7 @@
-use core_logic::config::{ChainConfig, ProxyConfig, SpamConfig, WalletSource};
+use core_logic::config::{
+    suggest_key, unknown_key_error, ChainConfig, ProxyConfig, SpamConfig, WalletSource,
+};
+use core_logic::{ConfigError, ProxyManager};
 use serde::Deserialize;
 
 #[derive(Debug, Clone, Deserialize)]
@@ -100,6 +103,19 @@ mod tests {
         assert!(proxy.password.is_none());
     }
 
+    #[test]
+    fn test_proxy_partition_by_quota() {
+        assert_eq!(ProxyManager::partition(10, &[0.6, 0.4]), vec![0..6, 6..10]);
+        // Rounding leftovers go to the largest remainders
+        assert_eq!(
+            ProxyManager::partition(10, &[1.0, 1.0, 1.0]),
+            vec![0..4, 4..7, 7..10]
+        );
+        // Zero quota runs direct and takes nothing from the pool
+        assert_eq!(ProxyManager::partition(5, &[1.0, 0.0]), vec![0..5, 5..5]);
+        assert_eq!(ProxyManager::partition(0, &[1.0, 1.0]), vec![0..0, 0..0]);
+    }
+
     #[test]
     fn test_chain_config() {
         let config = ChainConfig {
@@ -147,4 +163,44 @@ mod tests {
         assert_eq!(cloned.target_tps, config.target_tps);
         assert_eq!(cloned.duration_seconds, config.duration_seconds);
     }
+
+    #[test]
+    fn test_unknown_fields_rejected() {
+        let err = serde_json::from_str::<ProxyConfig>(
+            r#"{"url": "http://127.0.0.1:8080", "user": "bob"}"#,
+        )
+        .unwrap_err();
+        assert!(err.to_string().contains("unknown field `user`"));
+    }
+
+    #[test]
+    fn test_suggest_key() {
+        let valid = ["worker_count", "task_timeout", "rpc_url"];
+        assert_eq!(suggest_key("worker_cont", &valid), Some("worker_count"));
+        assert_eq!(suggest_key("rpc_ulr", &valid), Some("rpc_url"));
+        assert_eq!(suggest_key("log_level", &valid), None);
+    }
+
+    #[test]
+    fn test_unknown_key_error_location_and_suggestion() {
+        let content = "rpc_url = \"http://localhost\"\n\n[nonce]\nretry_maxx = 3\n";
+        let message = "unknown field `retry_maxx`, expected one of `retry_max`, `shard_count`";
+
+        match unknown_key_error(message, "config.toml", content, None) {
+            Some(ConfigError::UnknownKey {
+                key,
+                location,
+                suggestion,
+            }) => {
+                assert_eq!(key, "nonce.retry_maxx");
+                assert_eq!(location, "config.toml:4:1");
+                assert_eq!(suggestion.as_deref(), Some("retry_max"));
+            }
+            other => panic!("unexpected result: {:?}", other),
+        }
+
+        assert!(
+            unknown_key_error("missing field `rpc_url`", "config.toml", content, None).is_none()
+        );
+    }
 }
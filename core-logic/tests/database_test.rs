@@ -0,0 +1,1420 @@
+use chrono::TimeZone;
+use core_logic::database::{
+    monthly_shard_path, AsyncDbConfig, AuditRecord, BurstBlock, BurstResult, BurstRound,
+    ChainSummary, ConsistencyCheck, ConsistencySummary, DatabaseManager, DomainRecord,
+    ExportFormat, FallbackStrategy, FuzzResultRecord, GasUsageRecord, HelperContractRecord,
+    JournalMode, LatencySample, LogCoverage, NftListingRecord, OperationRecord, ProxyUsage,
+    QueuedTaskResult, ReceiptLogRecord, ShardedDatabase, SmartAccountRecord, SpendRecord,
+    SpillRecord, SynchronousLevel, TaskMetadata,
+};
+use core_logic::{TaskResult, Verification};
+use std::io::Write;
+
+fn spill_line(run_id: Option<&str>, worker_id: &str, task_name: &str, timestamp: i64) -> String {
+    let record = SpillRecord {
+        run_id: run_id.map(str::to_string),
+        chain_id: None,
+        worker_id: worker_id.to_string(),
+        wallet_address: "0x0000000000000000000000000000000000000001".to_string(),
+        task_name: task_name.to_string(),
+        success: true,
+        message: "ok".to_string(),
+        duration_ms: 120,
+        timestamp,
+        metadata: TaskMetadata::default(),
+    };
+    serde_json::to_string(&record).unwrap()
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    #[tokio::test]
+    async fn test_import_spill_file_dedups_on_reimport() {
+        let dir = tempfile::tempdir().unwrap();
+        let db_path = dir.path().join("test.db");
+        let spill_path = dir.path().join("run-42.jsonl");
+
+        let mut spill = std::fs::File::create(&spill_path).unwrap();
+        writeln!(
+            spill,
+            "{}",
+            spill_line(Some("run-a"), "001", "01_deploy_contract", 1000)
+        )
+        .unwrap();
+        writeln!(
+            spill,
+            "{}",
+            spill_line(Some("run-a"), "002", "01_deploy_contract", 1000)
+        )
+        .unwrap();
+        writeln!(spill, "{}", spill_line(None, "001", "03_send_token", 1001)).unwrap();
+        writeln!(spill).unwrap();
+        writeln!(spill, "not json").unwrap();
+        drop(spill);
+
+        let db = DatabaseManager::new(db_path.to_str().unwrap())
+            .await
+            .unwrap();
+        let spill_path = spill_path.to_str().unwrap();
+
+        let first = db.import_spill_file(spill_path).await.unwrap();
+        assert_eq!(first.imported, 3);
+        assert_eq!(first.duplicates, 0);
+        assert_eq!(first.malformed, 1);
+
+        let second = db.import_spill_file(spill_path).await.unwrap();
+        assert_eq!(second.imported, 0);
+        assert_eq!(second.duplicates, 3);
+
+        let wallet = "0x0000000000000000000000000000000000000001";
+        assert_eq!(db.get_transaction_count(wallet).await.unwrap(), 3);
+    }
+
+    #[tokio::test]
+    async fn test_import_spill_file_missing_file() {
+        let dir = tempfile::tempdir().unwrap();
+        let db_path = dir.path().join("test.db");
+        let db = DatabaseManager::new(db_path.to_str().unwrap())
+            .await
+            .unwrap();
+
+        let missing = dir.path().join("missing.jsonl");
+        assert!(db
+            .import_spill_file(missing.to_str().unwrap())
+            .await
+            .is_err());
+    }
+
+    #[test]
+    fn test_monthly_shard_path() {
+        let june = chrono::Utc.with_ymd_and_hms(2024, 6, 15, 12, 0, 0).unwrap();
+        assert_eq!(
+            monthly_shard_path("tempo-spammer.db", june),
+            "tempo-spammer-2024-06.db"
+        );
+        assert_eq!(
+            monthly_shard_path("data/tempo.db", june),
+            "data/tempo-2024-06.db"
+        );
+    }
+
+    #[tokio::test]
+    async fn test_sharded_database_merges_task_summary() {
+        let dir = tempfile::tempdir().unwrap();
+        let base = dir.path().join("tempo.db");
+        let base = base.to_str().unwrap();
+        let wallet = "0x0000000000000000000000000000000000000001";
+
+        for (month, successes) in [(5, 2), (6, 3)] {
+            let at = chrono::Utc
+                .with_ymd_and_hms(2024, month, 1, 0, 0, 0)
+                .unwrap();
+            let shard = DatabaseManager::new(&monthly_shard_path(base, at))
+                .await
+                .unwrap();
+            for _ in 0..successes {
+                shard
+                    .log_task_result("001", wallet, "03_send_token", true, "ok", 100)
+                    .await
+                    .unwrap();
+            }
+            shard
+                .log_task_result("001", wallet, "03_send_token", false, "err", 300)
+                .await
+                .unwrap();
+        }
+        std::fs::write(dir.path().join("tempo-notes.db"), b"").unwrap();
+
+        let sharded = ShardedDatabase::open(base).await.unwrap();
+        assert_eq!(sharded.shard_paths().len(), 2);
+        assert_eq!(sharded.get_transaction_count(wallet).await.unwrap(), 7);
+        assert_eq!(sharded.get_success_count(wallet).await.unwrap(), 5);
+
+        let summary = sharded.get_task_summary().await.unwrap();
+        assert_eq!(summary.len(), 1);
+        assert_eq!(summary[0].success, 5);
+        assert_eq!(summary[0].failed, 2);
+        assert_eq!(summary[0].total_duration_ms, 1100);
+    }
+
+    #[tokio::test]
+    async fn test_sharded_open_migrates_old_shards() {
+        use sqlx::Connection;
+
+        let dir = tempfile::tempdir().unwrap();
+        let base = dir.path().join("tempo.db");
+        let base = base.to_str().unwrap();
+        let at = chrono::Utc.with_ymd_and_hms(2024, 5, 1, 0, 0, 0).unwrap();
+        let shard = monthly_shard_path(base, at);
+
+        // A shard from a build before the later task_metrics columns
+        let url = format!("sqlite://{}?mode=rwc", shard);
+        let mut conn = sqlx::SqliteConnection::connect(&url).await.unwrap();
+        sqlx::query(
+            "CREATE TABLE task_metrics (id INTEGER PRIMARY KEY, worker_id TEXT, \
+             wallet_address TEXT, task_name TEXT, status TEXT, message TEXT, \
+             duration_ms INTEGER, timestamp INTEGER);
+             INSERT INTO task_metrics (worker_id, wallet_address, task_name, status, \
+             message, duration_ms, timestamp) VALUES ('001', '0x01', '03_send_token', \
+             'SUCCESS', 'ok', 100, 1000);",
+        )
+        .execute(&mut conn)
+        .await
+        .unwrap();
+        conn.close().await.unwrap();
+
+        let sharded = ShardedDatabase::open(base).await.unwrap();
+        let summary = sharded.get_task_summary().await.unwrap();
+        assert_eq!(summary.len(), 1);
+        assert_eq!(summary[0].success, 1);
+
+        let mut conn = sqlx::SqliteConnection::connect(&url).await.unwrap();
+        let version: i64 = sqlx::query_scalar("PRAGMA user_version")
+            .fetch_one(&mut conn)
+            .await
+            .unwrap();
+        assert!(version > 0);
+    }
+
+    #[tokio::test]
+    async fn test_flush_worker_follows_month() {
+        let dir = tempfile::tempdir().unwrap();
+        let base = dir.path().join("tempo.db");
+        let base = base.to_str().unwrap();
+        let wallet = "0x0000000000000000000000000000000000000001";
+
+        // Opened in a past month, the run now writes to this month's shard
+        let started = chrono::Utc.with_ymd_and_hms(2024, 5, 1, 0, 0, 0).unwrap();
+        let db = DatabaseManager::new_with_async(
+            &monthly_shard_path(base, started),
+            AsyncDbConfig {
+                flush_interval_ms: 10,
+                shard_base_path: Some(base.to_string()),
+                ..AsyncDbConfig::default()
+            },
+            FallbackStrategy::Hybrid,
+        )
+        .await
+        .unwrap();
+        db.queue_task_result(QueuedTaskResult {
+            chain_id: None,
+            worker_id: "001".to_string(),
+            wallet_address: wallet.to_string(),
+            task_name: "03_send_token".to_string(),
+            success: true,
+            message: String::new(),
+            duration_ms: 50,
+            timestamp: 1000,
+            metadata: TaskMetadata::default(),
+        })
+        .unwrap();
+        db.shutdown().await.unwrap();
+
+        let current = DatabaseManager::new(&monthly_shard_path(base, chrono::Utc::now()))
+            .await
+            .unwrap();
+        assert_eq!(current.get_transaction_count(wallet).await.unwrap(), 1);
+        let old = DatabaseManager::new(&monthly_shard_path(base, started))
+            .await
+            .unwrap();
+        assert_eq!(old.get_transaction_count(wallet).await.unwrap(), 0);
+    }
+
+    #[tokio::test]
+    async fn test_gas_summary_merges_shards() {
+        let dir = tempfile::tempdir().unwrap();
+        let base = dir.path().join("tempo.db");
+        let base = base.to_str().unwrap();
+        let usage = |task: &str, gas_used: i64, gwei: i64, success: bool| GasUsageRecord {
+            task_name: task.to_string(),
+            wallet_address: "0x0000000000000000000000000000000000000001".to_string(),
+            tx_hash: "0xabc".to_string(),
+            gas_used,
+            effective_gas_price: gwei * 1_000_000_000,
+            block_number: Some(1),
+            success,
+            timestamp: 1000,
+        };
+
+        for (month, rows) in [
+            (
+                5,
+                vec![
+                    usage("01_deploy_contract", 400_000, 20, true),
+                    usage("03_send_token", 50_000, 20, true),
+                ],
+            ),
+            (
+                6,
+                vec![
+                    usage("01_deploy_contract", 600_000, 30, false),
+                    usage("01_deploy_contract", 500_000, 30, true),
+                ],
+            ),
+        ] {
+            let at = chrono::Utc
+                .with_ymd_and_hms(2024, month, 1, 0, 0, 0)
+                .unwrap();
+            let shard = DatabaseManager::new(&monthly_shard_path(base, at))
+                .await
+                .unwrap();
+            for row in rows {
+                shard.log_gas_usage(&row).await.unwrap();
+            }
+        }
+
+        let sharded = ShardedDatabase::open(base).await.unwrap();
+        let summary = sharded.get_gas_summary().await.unwrap();
+        assert_eq!(summary.len(), 2);
+        let deploy = &summary[0];
+        assert_eq!(deploy.task_name, "01_deploy_contract");
+        assert_eq!(deploy.transactions, 3);
+        assert_eq!(deploy.reverted, 1);
+        assert_eq!(deploy.total_gas, 1_500_000);
+        assert_eq!(deploy.max_gas, 600_000);
+        assert_eq!(deploy.avg_gas(), 500_000.0);
+        // 8e15 + 18e15 + 15e15 wei
+        assert_eq!(deploy.total_cost_wei, 41e15);
+        assert!((deploy.avg_gas_price() - 41e15 / 1.5e6).abs() < 1.0);
+        assert_eq!(summary[1].transactions, 1);
+
+        // Receipts are windowed by their timestamp
+        assert_eq!(
+            sharded.get_gas_summary_between(1000, 2000).await.unwrap(),
+            summary
+        );
+        assert!(sharded
+            .get_gas_summary_between(0, 999)
+            .await
+            .unwrap()
+            .is_empty());
+    }
+
+    #[tokio::test]
+    async fn test_proxy_usage_accumulates() {
+        let dir = tempfile::tempdir().unwrap();
+        let db_path = dir.path().join("test.db");
+        let db = DatabaseManager::new(db_path.to_str().unwrap())
+            .await
+            .unwrap();
+
+        let usage = ProxyUsage {
+            requests: 10,
+            successes: 9,
+            failures: 1,
+            bytes_sent: 4000,
+            bytes_received: 16000,
+        };
+        db.record_proxy_usage("http://10.0.0.1:8080", &usage)
+            .await
+            .unwrap();
+        db.record_proxy_usage("http://10.0.0.1:8080", &usage)
+            .await
+            .unwrap();
+        db.record_proxy_usage(
+            "http://10.0.0.2:8080",
+            &ProxyUsage {
+                requests: 1,
+                successes: 1,
+                ..Default::default()
+            },
+        )
+        .await
+        .unwrap();
+
+        let stats = db.get_proxy_stats().await.unwrap();
+        assert_eq!(stats.len(), 2);
+        assert_eq!(stats[0].proxy_url, "http://10.0.0.1:8080");
+        assert_eq!(stats[0].request_count, 20);
+        assert_eq!(stats[0].total_bytes(), 40000);
+        assert!((stats[0].success_rate() - 90.0).abs() < f64::EPSILON);
+    }
+
+    #[tokio::test]
+    async fn test_flush_retries_while_database_busy() {
+        use sqlx::Connection;
+
+        let dir = tempfile::tempdir().unwrap();
+        let db_path = dir.path().join("busy.db");
+        let wallet = "0x0000000000000000000000000000000000000001";
+        let db = DatabaseManager::new_with_async(
+            db_path.to_str().unwrap(),
+            AsyncDbConfig {
+                flush_interval_ms: 10,
+                // Fail fast on the lock, leaving the waiting to the flush retries
+                busy_timeout_ms: 0,
+                synchronous: SynchronousLevel::Full,
+                ..AsyncDbConfig::default()
+            },
+            FallbackStrategy::Hybrid,
+        )
+        .await
+        .unwrap();
+
+        let mut writer =
+            sqlx::SqliteConnection::connect(&format!("sqlite://{}", db_path.to_str().unwrap()))
+                .await
+                .unwrap();
+        sqlx::query("BEGIN IMMEDIATE")
+            .execute(&mut writer)
+            .await
+            .unwrap();
+        db.queue_task_result(QueuedTaskResult {
+            chain_id: None,
+            worker_id: "001".to_string(),
+            wallet_address: wallet.to_string(),
+            task_name: "03_send_token".to_string(),
+            success: true,
+            message: String::new(),
+            duration_ms: 50,
+            timestamp: 1000,
+            metadata: TaskMetadata::default(),
+        })
+        .unwrap();
+        tokio::time::sleep(std::time::Duration::from_millis(120)).await;
+        sqlx::query("COMMIT").execute(&mut writer).await.unwrap();
+        tokio::time::sleep(std::time::Duration::from_millis(800)).await;
+
+        assert_eq!(db.get_transaction_count(wallet).await.unwrap(), 1);
+        db.shutdown().await.unwrap();
+    }
+
+    #[tokio::test]
+    async fn test_failed_flush_spilled_and_importable() {
+        use sqlx::Connection;
+
+        let dir = tempfile::tempdir().unwrap();
+        let db_path = dir.path().join("spill.db");
+        let spill_path = dir.path().join("spill.jsonl");
+        let wallet = "0x0000000000000000000000000000000000000001";
+        let db = DatabaseManager::new_with_async(
+            db_path.to_str().unwrap(),
+            AsyncDbConfig {
+                flush_interval_ms: 10,
+                busy_timeout_ms: 0,
+                spill_path: Some(spill_path.clone()),
+                ..AsyncDbConfig::default()
+            },
+            FallbackStrategy::Hybrid,
+        )
+        .await
+        .unwrap();
+
+        // Hold the write lock past every flush retry
+        let mut writer =
+            sqlx::SqliteConnection::connect(&format!("sqlite://{}", db_path.to_str().unwrap()))
+                .await
+                .unwrap();
+        sqlx::query("BEGIN IMMEDIATE")
+            .execute(&mut writer)
+            .await
+            .unwrap();
+        db.queue_task_result(QueuedTaskResult {
+            chain_id: None,
+            worker_id: "001".to_string(),
+            wallet_address: wallet.to_string(),
+            task_name: "03_send_token".to_string(),
+            success: true,
+            message: String::new(),
+            duration_ms: 50,
+            timestamp: 1000,
+            metadata: TaskMetadata::default(),
+        })
+        .unwrap();
+        tokio::time::sleep(std::time::Duration::from_millis(1500)).await;
+        sqlx::query("COMMIT").execute(&mut writer).await.unwrap();
+
+        assert_eq!(db.get_transaction_count(wallet).await.unwrap(), 0);
+        let spilled = std::fs::read_to_string(&spill_path).unwrap();
+        assert_eq!(spilled.lines().count(), 1);
+
+        let spill_path = spill_path.to_str().unwrap();
+        let first = db.import_spill_file(spill_path).await.unwrap();
+        assert_eq!(first.imported, 1);
+        let second = db.import_spill_file(spill_path).await.unwrap();
+        assert_eq!(second.duplicates, 1);
+        assert_eq!(db.get_transaction_count(wallet).await.unwrap(), 1);
+        db.shutdown().await.unwrap();
+    }
+
+    #[tokio::test]
+    async fn test_journal_mode_applied() {
+        let dir = tempfile::tempdir().unwrap();
+        let db_path = dir.path().join("journal.db");
+        let db = DatabaseManager::new_with_async(
+            db_path.to_str().unwrap(),
+            AsyncDbConfig {
+                journal_mode: JournalMode::Delete,
+                ..AsyncDbConfig::default()
+            },
+            FallbackStrategy::Hybrid,
+        )
+        .await
+        .unwrap();
+        db.log_task_result("001", "0x01", "deploy", true, "ok", 100)
+            .await
+            .unwrap();
+        assert!(!dir.path().join("journal.db-wal").exists());
+        db.shutdown().await.unwrap();
+    }
+
+    #[tokio::test]
+    async fn test_chain_summary_separates_chains() {
+        let dir = tempfile::tempdir().unwrap();
+        let db_path = dir.path().join("shared.db");
+        let wallet = "0x0000000000000000000000000000000000000001";
+        let db = DatabaseManager::new_with_async(
+            db_path.to_str().unwrap(),
+            AsyncDbConfig::default(),
+            FallbackStrategy::Hybrid,
+        )
+        .await
+        .unwrap();
+
+        db.log_chain_task_result(Some(11155931), "001", wallet, "deploy", true, "ok", 100)
+            .await
+            .unwrap();
+        db.log_task_result("001", wallet, "deploy", false, "err", 100)
+            .await
+            .unwrap();
+        for success in [true, true, false] {
+            db.queue_task_result(QueuedTaskResult {
+                chain_id: Some(42431),
+                worker_id: "002".to_string(),
+                wallet_address: wallet.to_string(),
+                task_name: "03_send_token".to_string(),
+                success,
+                message: String::new(),
+                duration_ms: 50,
+                timestamp: 1000,
+                metadata: TaskMetadata::default(),
+            })
+            .unwrap();
+        }
+        db.shutdown().await.unwrap();
+
+        let db = DatabaseManager::new(db_path.to_str().unwrap())
+            .await
+            .unwrap();
+        let summary = db.get_chain_summary().await.unwrap();
+        assert_eq!(
+            summary,
+            vec![
+                ChainSummary {
+                    chain_id: None,
+                    success: 0,
+                    failed: 1
+                },
+                ChainSummary {
+                    chain_id: Some(42431),
+                    success: 2,
+                    failed: 1
+                },
+                ChainSummary {
+                    chain_id: Some(11155931),
+                    success: 1,
+                    failed: 0
+                },
+            ]
+        );
+    }
+
+    #[tokio::test]
+    async fn test_burst_round_first_proposal_wins() {
+        let dir = tempfile::tempdir().unwrap();
+        let db_path = dir.path().join("burst.db");
+        let first = DatabaseManager::new(db_path.to_str().unwrap())
+            .await
+            .unwrap();
+        let second = DatabaseManager::new(db_path.to_str().unwrap())
+            .await
+            .unwrap();
+
+        let proposal = BurstRound {
+            round_id: "spike-1".to_string(),
+            start_at_ms: 1_700_000_030_000,
+            duration_ms: 10_000,
+            target_rate: 20.0,
+        };
+        assert_eq!(first.join_burst_round(&proposal).await.unwrap(), proposal);
+        // A later instance proposing another start joins the stored round
+        let late = BurstRound {
+            start_at_ms: 1_700_000_045_000,
+            target_rate: 50.0,
+            ..proposal.clone()
+        };
+        assert_eq!(second.join_burst_round(&late).await.unwrap(), proposal);
+
+        let mut report = BurstResult {
+            round_id: "spike-1".to_string(),
+            instance_id: "host-a".to_string(),
+            sent: 0,
+            send_errors: 0,
+            achieved_rate: 0.0,
+            included: 0,
+            reverted: 0,
+            pending: 0,
+            first_block: None,
+            last_block: None,
+            avg_inclusion_ms: 0.0,
+            p50_inclusion_ms: 0.0,
+            p90_inclusion_ms: 0.0,
+            p99_inclusion_ms: 0.0,
+            max_inclusion_ms: 0.0,
+        };
+        first.record_burst_result(&report).await.unwrap();
+        report.sent = 200;
+        report.included = 180;
+        report.reverted = 10;
+        report.pending = 10;
+        report.first_block = Some(100);
+        report.last_block = Some(104);
+        report.p50_inclusion_ms = 900.0;
+        report.max_inclusion_ms = 2_400.0;
+        first.record_burst_result(&report).await.unwrap();
+        second
+            .record_burst_result(&BurstResult {
+                instance_id: "host-b".to_string(),
+                ..report.clone()
+            })
+            .await
+            .unwrap();
+
+        let (round, results) = second.get_burst_results("spike-1").await.unwrap().unwrap();
+        assert_eq!(round, proposal);
+        assert_eq!(results.len(), 2);
+        assert_eq!(results[0], report);
+        assert!((results[0].inclusion_rate() - 95.0).abs() < f64::EPSILON);
+        assert!(first.get_burst_results("missing").await.unwrap().is_none());
+    }
+
+    #[tokio::test]
+    async fn test_burst_blocks_replace_earlier_breakdown() {
+        let dir = tempfile::tempdir().unwrap();
+        let db = DatabaseManager::new(dir.path().join("burst.db").to_str().unwrap())
+            .await
+            .unwrap();
+        let block = |instance_id: &str, block_number: i64, transactions: i64| BurstBlock {
+            round_id: "spike-1".to_string(),
+            instance_id: instance_id.to_string(),
+            block_number,
+            transactions,
+            in_subblocks: 1,
+            min_inclusion_ms: 300.0,
+            p50_inclusion_ms: 700.0,
+            max_inclusion_ms: 1_100.0,
+        };
+
+        db.record_burst_blocks("spike-1", "host-a", &[block("host-a", 101, 5)])
+            .await
+            .unwrap();
+        let breakdown = [block("host-a", 101, 40), block("host-a", 102, 12)];
+        db.record_burst_blocks("spike-1", "host-a", &breakdown)
+            .await
+            .unwrap();
+        db.record_burst_blocks("spike-1", "host-b", &[block("host-b", 101, 30)])
+            .await
+            .unwrap();
+
+        let blocks = db.get_burst_blocks("spike-1").await.unwrap();
+        let rows: Vec<(&str, i64, i64)> = blocks
+            .iter()
+            .map(|b| (b.instance_id.as_str(), b.block_number, b.transactions))
+            .collect();
+        assert_eq!(
+            rows,
+            vec![
+                ("host-a", 101, 40),
+                ("host-b", 101, 30),
+                ("host-a", 102, 12)
+            ]
+        );
+        assert!(db.get_burst_blocks("missing").await.unwrap().is_empty());
+    }
+
+    #[tokio::test]
+    async fn test_latency_samples_filter_by_source_and_time() {
+        let dir = tempfile::tempdir().unwrap();
+        let db = DatabaseManager::new(dir.path().join("latency.db").to_str().unwrap())
+            .await
+            .unwrap();
+        let sample =
+            |hash: &str, source: &str, sent_at_ms: i64, subblock: Option<&str>| LatencySample {
+                tx_hash: hash.to_string(),
+                source: source.to_string(),
+                wallet_address: "0x0000000000000000000000000000000000000001".to_string(),
+                block_number: 101,
+                subblock: subblock.map(str::to_string),
+                sent_at_ms,
+                included_at_ms: sent_at_ms + 400,
+                confirmed_at_ms: sent_at_ms + 1_200,
+            };
+
+        db.record_latency_samples(&[
+            sample("0x02", "burst:spike-1", 2_000, Some("0xabc")),
+            sample("0x01", "burst:spike-1", 1_000, None),
+            sample("0x03", "burst:spike-2", 3_000, None),
+        ])
+        .await
+        .unwrap();
+        // Sampling a transaction again replaces its row
+        db.record_latency_samples(&[sample("0x01", "burst:spike-1", 1_500, None)])
+            .await
+            .unwrap();
+
+        let all = db.get_latency_samples(None, 0).await.unwrap();
+        let hashes: Vec<&str> = all.iter().map(|s| s.tx_hash.as_str()).collect();
+        assert_eq!(hashes, vec!["0x01", "0x02", "0x03"]);
+        assert_eq!(all[0].sent_at_ms, 1_500);
+        assert_eq!(all[1].subblock.as_deref(), Some("0xabc"));
+
+        let round = db
+            .get_latency_samples(Some("burst:spike-1"), 1_800)
+            .await
+            .unwrap();
+        assert_eq!(
+            round,
+            vec![sample("0x02", "burst:spike-1", 2_000, Some("0xabc"))]
+        );
+    }
+
+    #[tokio::test]
+    async fn test_log_coverage_per_contract_and_event() {
+        let dir = tempfile::tempdir().unwrap();
+        let db = DatabaseManager::new(dir.path().join("logs.db").to_str().unwrap())
+            .await
+            .unwrap();
+        let log = |tx_hash: &str, log_index: i64, task: &str, address: &str, topic0: &str| {
+            ReceiptLogRecord {
+                tx_hash: tx_hash.to_string(),
+                log_index,
+                task_name: task.to_string(),
+                block_number: Some(101),
+                address: address.to_string(),
+                topics: vec![topic0.to_string(), "0x01".to_string()],
+                data: vec![0x78, 0x9c],
+                timestamp: 1_700_000_000,
+            }
+        };
+
+        db.log_receipt_logs(&[
+            log("0xa1", 0, "03_send_token", "0xtoken", "0xtransfer"),
+            log("0xa1", 1, "03_send_token", "0xtoken", "0xtransfer"),
+            log("0xb2", 4, "10_transfer_memo", "0xtoken", "0xtransfer"),
+            log("0xb2", 5, "10_transfer_memo", "0xtoken", "0xmemo"),
+            log("0xc3", 0, "21_create_meme", "0xfactory", "0xcreated"),
+        ])
+        .await
+        .unwrap();
+        // Archiving the same receipt again adds nothing
+        db.log_receipt_logs(&[log("0xa1", 0, "03_send_token", "0xtoken", "0xtransfer")])
+            .await
+            .unwrap();
+
+        let coverage = db.get_log_coverage(None).await.unwrap();
+        assert_eq!(coverage.len(), 3);
+        assert_eq!(coverage[0].address, "0xtoken");
+        assert_eq!(coverage[0].topic0.as_deref(), Some("0xtransfer"));
+        assert_eq!(coverage[0].logs, 3);
+        let mut tasks: Vec<&str> = coverage[0].tasks.split(',').collect();
+        tasks.sort();
+        assert_eq!(tasks, vec!["03_send_token", "10_transfer_memo"]);
+
+        let memo = db.get_log_coverage(Some("10_transfer_memo")).await.unwrap();
+        assert_eq!(
+            memo,
+            vec![
+                LogCoverage {
+                    address: "0xtoken".to_string(),
+                    topic0: Some("0xmemo".to_string()),
+                    logs: 1,
+                    tasks: "10_transfer_memo".to_string(),
+                },
+                LogCoverage {
+                    address: "0xtoken".to_string(),
+                    topic0: Some("0xtransfer".to_string()),
+                    logs: 1,
+                    tasks: "10_transfer_memo".to_string(),
+                },
+            ]
+        );
+    }
+
+    #[tokio::test]
+    async fn test_daily_spend_replaces_totals() {
+        let dir = tempfile::tempdir().unwrap();
+        let db = DatabaseManager::new(dir.path().join("spend.db").to_str().unwrap())
+            .await
+            .unwrap();
+        let record = |day: &str, amount: &str| SpendRecord {
+            day: day.to_string(),
+            wallet_address: "0x0000000000000000000000000000000000000001".to_string(),
+            token: "0x20c0000000000000000000000000000000000000".to_string(),
+            amount: amount.to_string(),
+        };
+
+        db.set_daily_spend(&record("2026-10-18", "1000"))
+            .await
+            .unwrap();
+        db.set_daily_spend(&record("2026-10-18", "250000000000000000000000"))
+            .await
+            .unwrap();
+        db.set_daily_spend(&record("2026-10-19", "5"))
+            .await
+            .unwrap();
+
+        assert_eq!(
+            db.get_daily_spend("2026-10-18").await.unwrap(),
+            vec![record("2026-10-18", "250000000000000000000000")]
+        );
+        assert!(db.get_daily_spend("2026-10-17").await.unwrap().is_empty());
+    }
+
+    #[tokio::test]
+    async fn test_consistency_summary_per_task() {
+        let dir = tempfile::tempdir().unwrap();
+        let db = DatabaseManager::new(dir.path().join("consistency.db").to_str().unwrap())
+            .await
+            .unwrap();
+        let check = |task: &str, consistent: bool, lag_ms: i64| ConsistencyCheck {
+            task_name: task.to_string(),
+            wallet_address: "0x0000000000000000000000000000000000000001".to_string(),
+            target: "call 0x20c0000000000000000000000000000000000005".to_string(),
+            reader: "proxy 2".to_string(),
+            consistent,
+            lag_ms,
+            attempts: 1,
+            timestamp: 1000,
+        };
+
+        for (task, consistent, lag_ms) in [
+            ("04_create_stable", true, 3000),
+            ("04_create_stable", true, 5000),
+            ("04_create_stable", false, 30000),
+            ("13_grant_role", true, 3100),
+        ] {
+            db.log_consistency_check(&check(task, consistent, lag_ms))
+                .await
+                .unwrap();
+        }
+
+        assert_eq!(
+            db.get_consistency_summary().await.unwrap(),
+            vec![
+                ConsistencySummary {
+                    task_name: "04_create_stable".to_string(),
+                    checks: 3,
+                    inconsistent: 1,
+                    avg_lag_ms: 4000.0,
+                    max_lag_ms: 5000,
+                },
+                ConsistencySummary {
+                    task_name: "13_grant_role".to_string(),
+                    checks: 1,
+                    inconsistent: 0,
+                    avg_lag_ms: 3100.0,
+                    max_lag_ms: 3100,
+                },
+            ]
+        );
+    }
+
+    #[tokio::test]
+    async fn test_audits_round_trip_newest_first() {
+        let dir = tempfile::tempdir().unwrap();
+        let db = DatabaseManager::new(dir.path().join("audits.db").to_str().unwrap())
+            .await
+            .unwrap();
+        let audit = |task: &str, timestamp: i64| AuditRecord {
+            task_name: task.to_string(),
+            worker_id: "007".to_string(),
+            wallet_address: "0x0000000000000000000000000000000000000001".to_string(),
+            proxy: "003 127.0.0.1:8080".to_string(),
+            success: true,
+            message: "Sent".to_string(),
+            tx_hash: Some("0xabc".to_string()),
+            nonce: Some(42),
+            nonce_key: Some("0x1".to_string()),
+            gas_limit: Some(21000),
+            max_fee_per_gas: Some("30000000000".to_string()),
+            max_priority_fee_per_gas: Some("1500000000".to_string()),
+            transactions: "[]".to_string(),
+            steps: r#"[{"name":"rpc eth_sendRawTransaction"}]"#.to_string(),
+            duration_ms: 850,
+            timestamp,
+        };
+
+        db.log_audit(&audit("03_send_token", 1000)).await.unwrap();
+        db.log_audit(&audit("13_grant_role", 2000)).await.unwrap();
+        let mut failed = audit("03_send_token", 3000);
+        failed.success = false;
+        failed.tx_hash = None;
+        failed.nonce = None;
+        db.log_audit(&failed).await.unwrap();
+
+        let all = db.get_audits(None, 10).await.unwrap();
+        assert_eq!(
+            all.iter().map(|a| a.timestamp).collect::<Vec<_>>(),
+            vec![3000, 2000, 1000]
+        );
+        assert_eq!(all[0], failed);
+        assert_eq!(all[2], audit("03_send_token", 1000));
+
+        let sends = db.get_audits(Some("03_send_token"), 1).await.unwrap();
+        assert_eq!(sends, vec![failed]);
+    }
+
+    #[tokio::test]
+    async fn test_helper_contracts_replaced_per_chain() {
+        let dir = tempfile::tempdir().unwrap();
+        let db = DatabaseManager::new(dir.path().join("helpers.db").to_str().unwrap())
+            .await
+            .unwrap();
+        let helper = |chain_id: i64, name: &str, address: &str| HelperContractRecord {
+            chain_id,
+            name: name.to_string(),
+            address: address.to_string(),
+            code_hash: "0x01".to_string(),
+            tx_hash: "0xabc".to_string(),
+            deployer: "0x0000000000000000000000000000000000000001".to_string(),
+            deployed_at: 1000,
+        };
+
+        db.log_helper_contract(&helper(42431, "event_emitter", "0xa1"))
+            .await
+            .unwrap();
+        db.log_helper_contract(&helper(42431, "disperse", "0xb1"))
+            .await
+            .unwrap();
+        db.log_helper_contract(&helper(1337, "disperse", "0xc1"))
+            .await
+            .unwrap();
+        // Redeploying after a reset replaces the old address
+        db.log_helper_contract(&helper(42431, "disperse", "0xb2"))
+            .await
+            .unwrap();
+
+        let helpers = db.get_helper_contracts(42431).await.unwrap();
+        assert_eq!(
+            helpers,
+            vec![
+                helper(42431, "disperse", "0xb2"),
+                helper(42431, "event_emitter", "0xa1")
+            ]
+        );
+        assert_eq!(db.get_helper_contracts(1).await.unwrap(), vec![]);
+    }
+
+    #[tokio::test]
+    async fn test_smart_accounts_listed_per_owner_and_chain() {
+        let dir = tempfile::tempdir().unwrap();
+        let db = DatabaseManager::new(dir.path().join("accounts.db").to_str().unwrap())
+            .await
+            .unwrap();
+        let account =
+            |chain_id: i64, owner: &str, address: &str, created_at: i64| SmartAccountRecord {
+                chain_id,
+                owner: owner.to_string(),
+                account: address.to_string(),
+                factory: "0xf1".to_string(),
+                salt: created_at.to_string(),
+                tx_hash: "0xabc".to_string(),
+                created_at,
+            };
+
+        db.log_smart_account(&account(42431, "0x01", "0xa2", 2000))
+            .await
+            .unwrap();
+        db.log_smart_account(&account(42431, "0x01", "0xa1", 1000))
+            .await
+            .unwrap();
+        db.log_smart_account(&account(42431, "0x02", "0xb1", 1000))
+            .await
+            .unwrap();
+        db.log_smart_account(&account(1337, "0x01", "0xc1", 1000))
+            .await
+            .unwrap();
+
+        assert_eq!(
+            db.get_smart_accounts(42431, "0x01").await.unwrap(),
+            vec![
+                account(42431, "0x01", "0xa1", 1000),
+                account(42431, "0x01", "0xa2", 2000)
+            ]
+        );
+        assert_eq!(db.get_smart_accounts(42431, "0x03").await.unwrap(), vec![]);
+    }
+
+    #[tokio::test]
+    async fn test_nft_listings_closed_by_id() {
+        let dir = tempfile::tempdir().unwrap();
+        let db = DatabaseManager::new(dir.path().join("listings.db").to_str().unwrap())
+            .await
+            .unwrap();
+        let listing = |chain_id: i64, listing_id: &str, listed_at: i64| NftListingRecord {
+            chain_id,
+            marketplace: "0xm1".to_string(),
+            listing_id: listing_id.to_string(),
+            seller: "0x01".to_string(),
+            nft: "0xn1".to_string(),
+            token_id: listing_id.to_string(),
+            payment_token: "0x20c0".to_string(),
+            price: "1000".to_string(),
+            status: "active".to_string(),
+            tx_hash: "0xabc".to_string(),
+            listed_at,
+        };
+
+        for record in [
+            listing(42431, "10", 1000),
+            listing(42431, "9", 1000),
+            listing(42431, "2", 500),
+            listing(1337, "1", 1000),
+        ] {
+            db.log_nft_listing(&record).await.unwrap();
+        }
+        assert_eq!(
+            db.get_active_nft_listings(42431, "0xm1").await.unwrap(),
+            vec![
+                listing(42431, "2", 500),
+                listing(42431, "9", 1000),
+                listing(42431, "10", 1000)
+            ]
+        );
+
+        db.close_nft_listing(42431, "0xm1", "9", "sold")
+            .await
+            .unwrap();
+        let active = db.get_active_nft_listings(42431, "0xm1").await.unwrap();
+        assert_eq!(
+            active
+                .iter()
+                .map(|l| l.listing_id.as_str())
+                .collect::<Vec<_>>(),
+            vec!["2", "10"]
+        );
+        assert_eq!(
+            db.get_active_nft_listings(1337, "0xm1")
+                .await
+                .unwrap()
+                .len(),
+            1
+        );
+        assert!(db
+            .get_active_nft_listings(42431, "0xm2")
+            .await
+            .unwrap()
+            .is_empty());
+    }
+
+    #[tokio::test]
+    async fn test_domains_follow_owner() {
+        let dir = tempfile::tempdir().unwrap();
+        let db = DatabaseManager::new(dir.path().join("domains.db").to_str().unwrap())
+            .await
+            .unwrap();
+        let domain = |name: &str, owner: &str, registered_at: i64| DomainRecord {
+            chain_id: 42431,
+            name: name.to_string(),
+            node: format!("0x{}", name.len()),
+            token_id: "1".to_string(),
+            owner: owner.to_string(),
+            resolver: String::new(),
+            addr: String::new(),
+            tx_hash: "0xabc".to_string(),
+            registered_at,
+            renewed_at: 0,
+        };
+
+        db.log_domain(&domain("b.tempo", "0x01", 1000))
+            .await
+            .unwrap();
+        db.log_domain(&domain("a.tempo", "0x01", 1000))
+            .await
+            .unwrap();
+        db.log_domain(&domain("c.tempo", "0x02", 500))
+            .await
+            .unwrap();
+        let names =
+            |domains: Vec<DomainRecord>| domains.into_iter().map(|d| d.name).collect::<Vec<_>>();
+        assert_eq!(
+            names(db.get_domains(42431, "0x01").await.unwrap()),
+            vec!["a.tempo", "b.tempo"]
+        );
+
+        // A transfer rewrites the record under the new owner
+        let mut moved = domain("b.tempo", "0x02", 1000);
+        moved.resolver = "0x30c0".to_string();
+        db.log_domain(&moved).await.unwrap();
+        assert_eq!(
+            names(db.get_domains(42431, "0x01").await.unwrap()),
+            vec!["a.tempo"]
+        );
+        let held = db.get_domains(42431, "0x02").await.unwrap();
+        assert_eq!(held, vec![domain("c.tempo", "0x02", 500), moved]);
+        assert!(db.get_domains(1337, "0x02").await.unwrap().is_empty());
+    }
+
+    #[tokio::test]
+    async fn test_fuzz_results_accumulate() {
+        let dir = tempfile::tempdir().unwrap();
+        let db = DatabaseManager::new(dir.path().join("fuzz.db").to_str().unwrap())
+            .await
+            .unwrap();
+        let call = |selector: &str, reverted: bool, error: &str| FuzzResultRecord {
+            chain_id: 42431,
+            contract: "fuzz_target".to_string(),
+            selector: selector.to_string(),
+            signature: format!("f{}(uint256)", selector),
+            successes: i64::from(!reverted),
+            reverts: i64::from(reverted),
+            last_error: error.to_string(),
+            updated_at: 1000,
+        };
+
+        db.record_fuzz_result(&call("0x02", false, ""))
+            .await
+            .unwrap();
+        db.record_fuzz_result(&call("0x01", true, "execution reverted"))
+            .await
+            .unwrap();
+        db.record_fuzz_result(&call("0x01", false, ""))
+            .await
+            .unwrap();
+        db.record_fuzz_result(&call("0x01", false, ""))
+            .await
+            .unwrap();
+
+        let results = db.get_fuzz_results(42431).await.unwrap();
+        assert_eq!(results.len(), 2);
+        assert_eq!(results[0].selector, "0x01");
+        assert_eq!((results[0].successes, results[0].reverts), (2, 1));
+        // A later success keeps the reason of the last revert
+        assert_eq!(results[0].last_error, "execution reverted");
+        assert_eq!((results[1].successes, results[1].reverts), (1, 0));
+        assert!(db.get_fuzz_results(1337).await.unwrap().is_empty());
+    }
+
+    #[tokio::test]
+    async fn test_operations_replaced_per_key() {
+        let dir = tempfile::tempdir().unwrap();
+        let db = DatabaseManager::new(dir.path().join("operations.db").to_str().unwrap())
+            .await
+            .unwrap();
+        let key = "02_claim_faucet:0x01:2026-10-18";
+        let operation = |tx_hash: &str, status: &str| OperationRecord {
+            chain_id: 42431,
+            op_key: key.to_string(),
+            wallet_address: "0x01".to_string(),
+            tx_hash: tx_hash.to_string(),
+            status: status.to_string(),
+            updated_at: 1000,
+        };
+
+        assert!(db.get_operation(42431, key).await.unwrap().is_none());
+        db.record_operation(&operation("0xaa", "sent"))
+            .await
+            .unwrap();
+        db.record_operation(&operation("0xbb", "done"))
+            .await
+            .unwrap();
+
+        let stored = db.get_operation(42431, key).await.unwrap();
+        assert_eq!(stored, Some(operation("0xbb", "done")));
+        assert!(db.get_operation(1337, key).await.unwrap().is_none());
+    }
+
+    #[tokio::test]
+    async fn test_task_metadata_stored_with_result() {
+        let dir = tempfile::tempdir().unwrap();
+        let db_path = dir.path().join("metadata.db");
+        let db = DatabaseManager::new(db_path.to_str().unwrap())
+            .await
+            .unwrap();
+
+        let result = TaskResult {
+            success: true,
+            message: "Created token".to_string(),
+            tx_hash: Some("0xabc".to_string()),
+            ..Default::default()
+        }
+        .with_receipt(120_000, Some(77))
+        .with_contract("0x20c0000000000000000000000000000000000009")
+        .with_token_amount("0x20c0000000000000000000000000000000000009", 1000)
+        .with_detail("symbol", "TST");
+        let metadata = TaskMetadata::from(&result);
+        assert_eq!(metadata.gas_used, Some(120_000));
+        assert_eq!(
+            serde_json::from_str::<serde_json::Value>(metadata.details.as_deref().unwrap())
+                .unwrap(),
+            serde_json::json!({
+                "token_amounts": [{
+                    "token": "0x20c0000000000000000000000000000000000009",
+                    "amount": "1000",
+                }],
+                "details": { "symbol": "TST" },
+            })
+        );
+
+        // Results from tasks that only set a message carry no metadata
+        let plain = TaskResult {
+            success: true,
+            message: "ok".to_string(),
+            ..Default::default()
+        };
+        assert_eq!(TaskMetadata::from(&plain), TaskMetadata::default());
+
+        for metadata in [metadata.clone(), TaskMetadata::default()] {
+            let record = QueuedTaskResult {
+                chain_id: None,
+                worker_id: "001".to_string(),
+                wallet_address: "0x0000000000000000000000000000000000000001".to_string(),
+                task_name: "04_create_stable".to_string(),
+                success: true,
+                message: String::new(),
+                duration_ms: 100,
+                timestamp: 1000,
+                metadata,
+            };
+            db.log_task_record(&record).await.unwrap();
+        }
+
+        let summary = db.get_task_summary().await.unwrap();
+        assert_eq!(summary[0].gas_samples, 1);
+        assert_eq!(summary[0].avg_gas_used(), Some(120_000.0));
+
+        // Spill lines keep the metadata as top-level fields
+        let spill = SpillRecord::from_queued(
+            "run-1",
+            &QueuedTaskResult {
+                chain_id: None,
+                worker_id: "001".to_string(),
+                wallet_address: String::new(),
+                task_name: "04_create_stable".to_string(),
+                success: true,
+                message: String::new(),
+                duration_ms: 100,
+                timestamp: 1000,
+                metadata: metadata.clone(),
+            },
+        );
+        let line = serde_json::to_string(&spill).unwrap();
+        assert!(line.contains("\"gas_used\":120000"));
+        let parsed: SpillRecord = serde_json::from_str(&line).unwrap();
+        assert_eq!(parsed.metadata, metadata);
+    }
+
+    #[tokio::test]
+    async fn test_task_summary_counts_verifications() {
+        let dir = tempfile::tempdir().unwrap();
+        let db_path = dir.path().join("verify.db");
+        let db = DatabaseManager::new(db_path.to_str().unwrap())
+            .await
+            .unwrap();
+
+        let check = |passed: bool| Verification {
+            check: "owner_of".to_string(),
+            target: "0x30c0000000000000000000000000000000000000#7".to_string(),
+            expected: "0x0000000000000000000000000000000000000001".to_string(),
+            actual: if passed {
+                "0x0000000000000000000000000000000000000001".to_string()
+            } else {
+                "0x0000000000000000000000000000000000000002".to_string()
+            },
+            passed,
+        };
+        let ok = TaskResult {
+            success: true,
+            ..Default::default()
+        };
+        let results = [
+            ok.clone().with_verification(check(true)),
+            ok.clone()
+                .with_verification(check(true))
+                .with_verification(check(false)),
+            ok.clone(),
+        ];
+        assert_eq!(results[0].verified(), Some(true));
+        assert_eq!(results[1].verified(), Some(false));
+        assert_eq!(results[2].verified(), None);
+
+        for (i, result) in results.iter().enumerate() {
+            db.log_task_record(&QueuedTaskResult {
+                chain_id: None,
+                worker_id: "001".to_string(),
+                wallet_address: String::new(),
+                task_name: "15_mint_domain".to_string(),
+                success: true,
+                message: String::new(),
+                duration_ms: 100,
+                timestamp: 1000 + i as i64,
+                metadata: TaskMetadata::from(result),
+            })
+            .await
+            .unwrap();
+        }
+
+        let metadata = TaskMetadata::from(&results[1]);
+        let details: serde_json::Value =
+            serde_json::from_str(metadata.details.as_deref().unwrap()).unwrap();
+        assert_eq!(details["verifications"][1]["passed"], false);
+
+        let summary = db.get_task_summary().await.unwrap();
+        assert_eq!(summary[0].total(), 3);
+        assert_eq!(summary[0].verified, 2);
+        assert_eq!(summary[0].mismatched, 1);
+    }
+
+    #[tokio::test]
+    async fn test_task_records_window_reimports() {
+        let dir = tempfile::tempdir().unwrap();
+        let db_path = dir.path().join("run.db");
+        let db = DatabaseManager::new(db_path.to_str().unwrap())
+            .await
+            .unwrap();
+
+        for (timestamp, success) in [(900, true), (1000, true), (1500, false), (2001, true)] {
+            let record = QueuedTaskResult {
+                chain_id: Some(42431),
+                worker_id: "001".to_string(),
+                wallet_address: "0x0000000000000000000000000000000000000001".to_string(),
+                task_name: "03_send_token".to_string(),
+                success,
+                message: "done".to_string(),
+                duration_ms: 50,
+                timestamp,
+                metadata: TaskMetadata {
+                    gas_used: Some(21_000),
+                    timeout_ms: Some(60_000),
+                    host_partition: Some("2/5".to_string()),
+                    diagnostics: Some(r#"{"elapsed_ms":45000}"#.to_string()),
+                    ..Default::default()
+                },
+            };
+            db.log_task_record(&record).await.unwrap();
+        }
+
+        let records = db.get_task_records(1000, 2000).await.unwrap();
+        assert_eq!(
+            records.iter().map(|r| r.timestamp).collect::<Vec<_>>(),
+            vec![1000, 1500]
+        );
+        assert!(records[0].success && !records[1].success);
+        assert_eq!(records[0].run_id, None);
+        assert_eq!(records[0].chain_id, Some(42431));
+        assert_eq!(records[0].metadata.gas_used, Some(21_000));
+        assert_eq!(records[0].metadata.timeout_ms, Some(60_000));
+        assert_eq!(records[0].metadata.host_partition.as_deref(), Some("2/5"));
+        assert_eq!(
+            records[0].metadata.diagnostics.as_deref(),
+            Some(r#"{"elapsed_ms":45000}"#)
+        );
+
+        // The extract is a spill file another database can import
+        let extract = dir.path().join("run-1.jsonl");
+        let mut file = std::fs::File::create(&extract).unwrap();
+        for record in &records {
+            writeln!(file, "{}", serde_json::to_string(record).unwrap()).unwrap();
+        }
+        drop(file);
+
+        let other = DatabaseManager::new(dir.path().join("other.db").to_str().unwrap())
+            .await
+            .unwrap();
+        let summary = other
+            .import_spill_file(extract.to_str().unwrap())
+            .await
+            .unwrap();
+        assert_eq!(summary.imported, 2);
+        let tasks = other.get_task_summary().await.unwrap();
+        assert_eq!((tasks[0].success, tasks[0].failed), (1, 1));
+    }
+
+    #[tokio::test]
+    async fn test_export_table_csv_with_time_range() {
+        let dir = tempfile::tempdir().unwrap();
+        let db = DatabaseManager::new(dir.path().join("test.db").to_str().unwrap())
+            .await
+            .unwrap();
+        let spill_path = dir.path().join("spill.jsonl");
+        let mut spill = std::fs::File::create(&spill_path).unwrap();
+        for (worker, timestamp) in [("001", 1000), ("002", 1500), ("003", 2500)] {
+            writeln!(
+                spill,
+                "{}",
+                spill_line(Some("run-a"), worker, "02_transfer, native", timestamp)
+            )
+            .unwrap();
+        }
+        drop(spill);
+        db.import_spill_file(spill_path.to_str().unwrap())
+            .await
+            .unwrap();
+
+        let out = dir.path().join("tasks.csv");
+        let rows = db
+            .export_table("task_metrics", ExportFormat::Csv, &out, Some(1200), None)
+            .await
+            .unwrap();
+        assert_eq!(rows, 2);
+
+        let mut reader = csv::Reader::from_path(&out).unwrap();
+        let headers = reader.headers().unwrap().clone();
+        let column = |name: &str| headers.iter().position(|h| h == name).unwrap();
+        let records: Vec<csv::StringRecord> = reader.records().map(Result::unwrap).collect();
+        assert_eq!(records.len(), 2);
+        assert_eq!(&records[0][column("worker_id")], "002");
+        assert_eq!(&records[1][column("timestamp")], "2500");
+        // Commas are quoted and NULL columns stay empty
+        assert_eq!(&records[0][column("task_name")], "02_transfer, native");
+        assert_eq!(&records[0][column("tx_hash")], "");
+        assert!(!dir.path().join("tasks.csv.tmp").exists());
+
+        // Time ranges need a time column, and only known tables export
+        let err = db
+            .export_table("proxy_stats", ExportFormat::Csv, &out, Some(0), None)
+            .await
+            .unwrap_err();
+        assert!(err.to_string().contains("no time column"), "{}", err);
+        assert!(db
+            .export_table("sqlite_master", ExportFormat::Csv, &out, None, None)
+            .await
+            .is_err());
+        assert_eq!(
+            "PARQUET".parse::<ExportFormat>().unwrap(),
+            ExportFormat::Parquet
+        );
+    }
+
+    #[cfg(feature = "parquet")]
+    #[tokio::test]
+    async fn test_export_table_parquet() {
+        use parquet::file::reader::{FileReader, SerializedFileReader};
+
+        let dir = tempfile::tempdir().unwrap();
+        let db = DatabaseManager::new(dir.path().join("test.db").to_str().unwrap())
+            .await
+            .unwrap();
+        for i in 0..3 {
+            db.log_task_result("001", "0xabc", "01_deploy_contract", i != 1, "ok", 100 + i)
+                .await
+                .unwrap();
+        }
+
+        let out = dir.path().join("tasks.parquet");
+        let rows = db
+            .export_table("task_metrics", ExportFormat::Parquet, &out, None, None)
+            .await
+            .unwrap();
+        assert_eq!(rows, 3);
+
+        let reader = SerializedFileReader::new(std::fs::File::open(&out).unwrap()).unwrap();
+        let metadata = reader.metadata();
+        assert_eq!(metadata.file_metadata().num_rows(), 3);
+        let schema = metadata.file_metadata().schema_descr();
+        let names: Vec<&str> = schema.columns().iter().map(|c| c.name()).collect();
+        assert!(names.contains(&"duration_ms") && names.contains(&"status"));
+    }
+}
@@ -0,0 +1,65 @@
+//! The encryption key is process-wide, so these tests live in their own
+//! binary and run as a single test
+
+use core_logic::database::{encryption_enabled, set_encryption_key, DatabaseManager};
+use zeroize::Zeroizing;
+
+fn key(value: &str) -> Option<Zeroizing<String>> {
+    Some(Zeroizing::new(value.to_string()))
+}
+
+#[cfg(feature = "sqlcipher")]
+#[tokio::test]
+async fn test_encrypted_database_needs_its_key() {
+    let dir = tempfile::tempdir().unwrap();
+    let db_path = dir.path().join("encrypted.db");
+    let db_path = db_path.to_str().unwrap();
+
+    set_encryption_key(key("it's a secret"));
+    assert!(encryption_enabled());
+    let db = DatabaseManager::new(db_path).await.unwrap();
+    db.log_task_result("001", "0xabc", "01_deploy_contract", true, "ok", 100)
+        .await
+        .unwrap();
+    db.shutdown().await.unwrap();
+
+    // Nothing of the schema is readable from the file
+    let raw = std::fs::read(db_path).unwrap();
+    assert!(!raw.starts_with(b"SQLite format 3"));
+
+    set_encryption_key(key("wrong"));
+    let err = DatabaseManager::new(db_path).await.unwrap_err();
+    assert!(err.to_string().contains("the key does not open"), "{}", err);
+
+    set_encryption_key(None);
+    assert!(DatabaseManager::new(db_path).await.is_err());
+
+    set_encryption_key(key("it's a secret"));
+    let db = DatabaseManager::new(db_path).await.unwrap();
+    let summary = db.get_task_summary().await.unwrap();
+    assert_eq!(summary.len(), 1);
+    assert_eq!(summary[0].task_name, "01_deploy_contract");
+    db.shutdown().await.unwrap();
+}
+
+#[cfg(not(feature = "sqlcipher"))]
+#[tokio::test]
+async fn test_key_without_sqlcipher_is_refused() {
+    let dir = tempfile::tempdir().unwrap();
+    let db_path = dir.path().join("plain.db");
+    let db_path = db_path.to_str().unwrap();
+
+    set_encryption_key(key("secret"));
+    let err = DatabaseManager::new(db_path).await.unwrap_err();
+    assert!(err.to_string().contains("without SQLCipher"), "{}", err);
+
+    // Without a key the same file opens as plain SQLite
+    set_encryption_key(None);
+    assert!(!encryption_enabled());
+    DatabaseManager::new(db_path)
+        .await
+        .unwrap()
+        .shutdown()
+        .await
+        .unwrap();
+}
@@ -179,3 +179,133 @@ mod tests {
         assert_eq!(max_fee, U256::from(9u64));
     }
 }
+
+/// The same expectations against the shared [`core_logic::GasManager`]
+mod shared_policy {
+    use core_logic::{FeeSample, Fees, GasConfig, GasManager};
+
+    fn gwei(amount: f64) -> u128 {
+        (amount * 1_000_000_000.0) as u128
+    }
+
+    /// Manager pricing at base fee plus tip, as the chains' own copies did
+    fn manager(max_gwei: f64, priority_gwei: f64) -> GasManager {
+        GasManager::new(
+            GasConfig::new()
+                .with_max_fee(max_gwei)
+                .with_priority_fee(priority_gwei)
+                .with_base_fee_headroom(0),
+        )
+    }
+
+    /// The 9 wei / 1 wei defaults the RISE spammer runs with
+    fn rise_manager() -> GasManager {
+        manager(0.000000009, 0.000000001)
+    }
+
+    fn max_fee(gm: &GasManager, base_fee: u128) -> u128 {
+        gm.max_fee(base_fee, gm.priority_fee_cap())
+    }
+
+    #[test]
+    fn test_gas_limits_are_correct() {
+        let config = GasConfig::new();
+        assert_eq!(config.limit_deploy(), 1_200_000);
+        assert_eq!(config.limit_transfer(), 21_000);
+        assert_eq!(config.limit_counter_interact(), 50_000);
+        assert_eq!(config.limit_send_meme(), 100_000);
+    }
+
+    #[test]
+    fn test_calculate_max_fee_below_cap() {
+        let gm = manager(100.0, 10.0);
+        assert_eq!(max_fee(&gm, gwei(50.0)), gwei(60.0));
+    }
+
+    #[test]
+    fn test_calculate_max_fee_above_cap() {
+        let gm = manager(50.0, 10.0);
+        assert_eq!(max_fee(&gm, gwei(100.0)), gwei(50.0));
+    }
+
+    #[test]
+    fn test_calculate_priority_fee() {
+        let gm = manager(100.0, 5.0);
+        assert_eq!(gm.priority_fee_cap(), gwei(5.0));
+    }
+
+    #[test]
+    fn test_calculate_deploy_cost() {
+        let gm = manager(100.0, 10.0);
+        let sample = FeeSample {
+            gas_price: gwei(60.0),
+            base_fee: Some(gwei(50.0)),
+            priority_fee: None,
+        };
+
+        let cost = gm.fees_from(&sample).max_cost(gm.config().limit_deploy());
+        assert_eq!(cost, 1_200_000 * gwei(60.0));
+    }
+
+    #[test]
+    fn test_calculate_transfer_cost() {
+        let gm = manager(100.0, 10.0);
+        let sample = FeeSample {
+            gas_price: gwei(60.0),
+            base_fee: Some(gwei(50.0)),
+            priority_fee: None,
+        };
+
+        let cost = gm.fees_from(&sample).max_cost(gm.config().limit_transfer());
+        assert_eq!(cost, 21_000 * gwei(60.0));
+    }
+
+    #[test]
+    fn test_parse_units_gwei() {
+        assert_eq!(manager(1.0, 1.0).max_fee_cap(), 1_000_000_000);
+        assert_eq!(manager(50.0, 1.0).max_fee_cap(), 50_000_000_000);
+    }
+
+    #[test]
+    fn test_default_priority_fee() {
+        assert_eq!(rise_manager().priority_fee_cap(), 1);
+    }
+
+    #[test]
+    fn test_calculate_max_fee_with_default_config() {
+        let gm = rise_manager();
+
+        // base_fee = 1 wei, priority = 1 wei, max = 2 wei (below cap of 9 wei)
+        assert_eq!(max_fee(&gm, 1), 2);
+    }
+
+    #[test]
+    fn test_calculate_max_fee_default_above_cap() {
+        let gm = rise_manager();
+
+        // Should be capped at 0.000000009 gwei = 9 wei
+        assert_eq!(max_fee(&gm, gwei(50.0)), 9);
+    }
+
+    #[test]
+    fn test_fees_without_base_fee_are_legacy() {
+        let gm = rise_manager();
+        assert_eq!(
+            gm.fees_from(&FeeSample::legacy(7)),
+            Fees::Legacy { gas_price: 7 }
+        );
+        assert_eq!(
+            gm.fees_from(&FeeSample::legacy(gwei(1.0))),
+            Fees::Legacy { gas_price: 9 }
+        );
+    }
+
+    /// The 100% headroom the Tempo and EVM adapter managers run with
+    #[test]
+    fn test_default_headroom_doubles_base_fee() {
+        let gm = GasManager::new(GasConfig::new().with_max_fee(100.0).with_priority_fee(2.0));
+        assert_eq!(gm.config().base_fee_headroom(), 100);
+        assert_eq!(max_fee(&gm, gwei(20.0)), gwei(42.0));
+        assert_eq!(max_fee(&gm, gwei(60.0)), gwei(100.0));
+    }
+}
@@ -0,0 +1,8 @@
+#!/bin/bash
+# Type-checks the chain templates, which are built outside the workspace
+# Usage: scripts/check_templates.sh [extra cargo check args]
+
+set -e
+cd "$(dirname "$0")/.."
+
+cargo check --manifest-path chains/_template_solana/Cargo.toml --all-targets "$@"